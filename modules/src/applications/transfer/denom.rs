@@ -277,6 +277,9 @@ impl fmt::Display for PrefixedDenom {
     }
 }
 
+/// The number of decimals a percentage of an amount can be given with, see [`Amount::percentage`].
+pub const PERCENT_DECIMALS: u32 = 18;
+
 /// A type for representing token transfer amounts.
 #[derive(
     Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Serialize, Deserialize, Display, From, Into,
@@ -291,6 +294,81 @@ impl Amount {
     pub fn checked_sub(self, rhs: Self) -> Option<Self> {
         self.0.checked_sub(rhs.0).map(Self)
     }
+
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        self.0.checked_mul(rhs.0).map(Self)
+    }
+
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        self.0.checked_div(rhs.0).map(Self)
+    }
+
+    /// Parses an amount given in a unit with `decimals` decimals, e.g. `1.5` ATOM
+    /// with 6 decimals, into the amount in the base denomination, e.g. `1500000` uatom.
+    ///
+    /// The amount is never rounded: it is rejected if it has more significant
+    /// fractional digits than `decimals`, or if it does not fit in an `Amount`.
+    pub fn from_decimal_str(s: &str, decimals: u32) -> Result<Self, Error> {
+        let is_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+
+        let (integer, fraction) = match s.split_once('.') {
+            Some((integer, fraction)) if is_digits(integer) && is_digits(fraction) => {
+                (integer, fraction.trim_end_matches('0'))
+            }
+            None if is_digits(s) => (s, ""),
+            _ => return Err(Error::invalid_decimal_amount(s.to_string())),
+        };
+
+        let fraction_digits = fraction.len() as u32;
+        if fraction_digits > decimals {
+            return Err(Error::too_many_decimals(s.to_string(), decimals));
+        }
+
+        let overflow = || Error::amount_overflow(s.to_string(), decimals);
+        let scale = |exponent: u32| U256::from(10u64).checked_pow(U256::from(exponent));
+
+        // Only digits are left, so parsing fails on overflow only
+        let integer = U256::from_dec_str(integer).map_err(|_| overflow())?;
+        let fraction = if fraction.is_empty() {
+            U256::zero()
+        } else {
+            U256::from_dec_str(fraction).map_err(|_| overflow())?
+        };
+
+        scale(decimals)
+            .and_then(|scale| integer.checked_mul(scale))
+            .zip(scale(decimals - fraction_digits))
+            .and_then(|(integer, scale)| {
+                fraction
+                    .checked_mul(scale)
+                    .and_then(|fraction| integer.checked_add(fraction))
+            })
+            .map(Self)
+            .ok_or_else(overflow)
+    }
+
+    /// The given percentage of this amount, e.g. `25` or `12.5` percent, rounded down.
+    ///
+    /// The percentage is rejected if it is over 100, or if it has more significant
+    /// fractional digits than [`PERCENT_DECIMALS`].
+    pub fn percentage(self, percent: &str) -> Result<Self, Error> {
+        let scaled = Self::from_decimal_str(percent, PERCENT_DECIMALS)
+            .map_err(|_| Error::invalid_percentage(percent.to_string(), PERCENT_DECIMALS))?;
+
+        // 100%, with the decimals of the percentage
+        let hundred = U256::from(100u64) * U256::from(10u64).pow(U256::from(PERCENT_DECIMALS));
+
+        if scaled.0 > hundred {
+            return Err(Error::percentage_over_hundred(percent.to_string()));
+        }
+
+        // `self * scaled / hundred` may overflow, unlike its terms
+        // `(self / hundred) * scaled`, at most `self`, and
+        // `(self % hundred) * scaled / hundred`, less than `hundred`
+        let (quotient, remainder) = (self.0 / hundred, self.0 % hundred);
+
+        Ok(Self(quotient * scaled.0 + remainder * scaled.0 / hundred))
+    }
 }
 
 impl FromStr for Amount {
@@ -358,6 +436,7 @@ impl fmt::Display for PrefixedCoin {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::applications::transfer::error::ErrorDetail;
 
     #[test]
     fn test_denom_validation() -> Result<(), Error> {
@@ -398,6 +477,128 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_amount_from_decimal_str() -> Result<(), Error> {
+        assert_eq!(Amount::from_decimal_str("1.5", 6)?, Amount::from(1_500_000));
+        assert_eq!(Amount::from_decimal_str("42", 6)?, Amount::from(42_000_000));
+        assert_eq!(Amount::from_decimal_str("42", 0)?, Amount::from(42));
+        assert_eq!(Amount::from_decimal_str("0.000001", 6)?, Amount::from(1));
+        assert_eq!(Amount::from_decimal_str("0.0", 0)?, Amount::from(0));
+
+        // Trailing zeros are not significant
+        assert_eq!(
+            Amount::from_decimal_str("1.5000000", 6)?,
+            Amount::from(1_500_000)
+        );
+
+        // Amounts below the base denomination are not rounded
+        assert!(matches!(
+            Amount::from_decimal_str("0.0000001", 6)
+                .unwrap_err()
+                .detail(),
+            ErrorDetail::TooManyDecimals(_)
+        ));
+        assert!(matches!(
+            Amount::from_decimal_str("1.5", 0).unwrap_err().detail(),
+            ErrorDetail::TooManyDecimals(_)
+        ));
+
+        for amount in ["", ".", "1.", ".5", "1.2.3", "-1", "+1", "1e6", "1,5", " 1"] {
+            assert!(
+                matches!(
+                    Amount::from_decimal_str(amount, 6).unwrap_err().detail(),
+                    ErrorDetail::InvalidDecimalAmount(_)
+                ),
+                "amount '{}' should be rejected",
+                amount
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_amount_from_decimal_str_overflow() -> Result<(), Error> {
+        let max = U256::MAX.to_string();
+        assert_eq!(Amount::from_decimal_str(&max, 0)?, Amount(U256::MAX));
+
+        // The same amount with its last digit as a fraction
+        let (integer, fraction) = max.split_at(max.len() - 1);
+        let max_with_decimal = format!("{}.{}", integer, fraction);
+        assert_eq!(
+            Amount::from_decimal_str(&max_with_decimal, 1)?,
+            Amount(U256::MAX)
+        );
+
+        let overflows = |amount: &str, decimals: u32| {
+            matches!(
+                Amount::from_decimal_str(amount, decimals)
+                    .unwrap_err()
+                    .detail(),
+                ErrorDetail::AmountOverflow(_)
+            )
+        };
+
+        // One more than the maximum, which ends with a 5, in the integer or fractional part
+        assert!(overflows(&format!("{}6", integer), 0));
+        assert!(overflows(&format!("{}.6", integer), 1));
+        assert!(overflows(&format!("{}0", max), 0));
+        assert!(overflows(&max, 1));
+        assert!(overflows("1", 78));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_amount_percentage() -> Result<(), Error> {
+        let amount = Amount::from(1000);
+
+        assert_eq!(amount.percentage("25")?, Amount::from(250));
+        assert_eq!(amount.percentage("12.5")?, Amount::from(125));
+        assert_eq!(amount.percentage("100")?, amount);
+        assert_eq!(amount.percentage("100.000")?, amount);
+        assert_eq!(amount.percentage("0")?, Amount::from(0));
+
+        // Rounded down
+        assert_eq!(Amount::from(3).percentage("50")?, Amount::from(1));
+        assert_eq!(Amount::from(999).percentage("0.1")?, Amount::from(0));
+        assert_eq!(amount.percentage("0.000000000000000001")?, Amount::from(0));
+
+        // Without overflowing on the largest amounts
+        let max = Amount(U256::MAX);
+        assert_eq!(max.percentage("100")?, max);
+        assert_eq!(max.percentage("50")?, Amount(U256::MAX / 2));
+        assert_eq!(
+            max.percentage("99.999999999999999999")?,
+            Amount(U256::MAX - U256::MAX / U256::from(10u64).pow(U256::from(20)) - 1)
+        );
+
+        let rejected = |percent: &str| amount.percentage(percent).unwrap_err();
+
+        assert!(matches!(
+            rejected("100.000000000000000001").detail(),
+            ErrorDetail::PercentageOverHundred(_)
+        ));
+        assert!(matches!(
+            rejected("101").detail(),
+            ErrorDetail::PercentageOverHundred(_)
+        ));
+        assert!(matches!(
+            rejected("0.0000000000000000001").detail(),
+            ErrorDetail::InvalidPercentage(_)
+        ));
+        assert!(matches!(
+            rejected("-5").detail(),
+            ErrorDetail::InvalidPercentage(_)
+        ));
+        assert!(matches!(
+            rejected("25%").detail(),
+            ErrorDetail::InvalidPercentage(_)
+        ));
+
+        Ok(())
+    }
+
     #[test]
     fn test_denom_trace() -> Result<(), Error> {
         assert_eq!(
@@ -71,6 +71,26 @@ define_error! {
             [ TraceError<FromDecStrErr> ]
             | _ | { "invalid amount" },
 
+        InvalidDecimalAmount
+            { amount: String }
+            | e | { format_args!("invalid decimal amount '{0}', expected digits optionally followed by a fractional part, e.g. `1.5`", e.amount) },
+
+        TooManyDecimals
+            { amount: String, decimals: u32 }
+            | e | { format_args!("amount '{0}' has more than {1} decimals", e.amount, e.decimals) },
+
+        AmountOverflow
+            { amount: String, decimals: u32 }
+            | e | { format_args!("amount '{0}' with {1} decimals is too large", e.amount, e.decimals) },
+
+        InvalidPercentage
+            { percent: String, decimals: u32 }
+            | e | { format_args!("invalid percentage '{0}', expected a number with at most {1} decimals, e.g. `25` or `12.5`", e.percent, e.decimals) },
+
+        PercentageOverHundred
+            { percent: String }
+            | e | { format_args!("percentage '{0}' is over 100", e.percent) },
+
         InvalidToken
             | _ | { "invalid token" },
 
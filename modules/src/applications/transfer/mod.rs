@@ -21,3 +21,7 @@ pub const PORT_ID_STR: &str = "transfer";
 
 /// ICS20 application current version.
 pub const VERSION: &str = "ics20-1";
+
+/// Version of the ICS20 application whose packets carry several tokens
+/// and forwarding information, encoded with protobuf.
+pub const VERSION_V2: &str = "ics20-2";
@@ -41,6 +41,8 @@ pub struct MsgTransfer<C = Coin> {
     /// Timeout timestamp relative to the current block timestamp.
     /// The timeout is disabled when set to 0.
     pub timeout_timestamp: Timestamp,
+    /// An optional memo, left out of the encoded message when empty.
+    pub memo: String,
 }
 
 impl Msg for MsgTransfer {
@@ -81,20 +83,31 @@ impl TryFrom<RawMsgTransfer> for MsgTransfer {
             receiver: raw_msg.receiver.parse().map_err(Error::signer)?,
             timeout_height,
             timeout_timestamp,
+            memo: raw_msg.memo,
         })
     }
 }
 
+/// Unlike in packets, where "no timeout height" is encoded as a zero height,
+/// the timeout height of a `MsgTransfer` is left unset when there is none,
+/// as some versions of ibc-go reject a zero timeout height as invalid.
+/// Both encodings are decoded as [`TimeoutHeight::Never`].
 impl From<MsgTransfer> for RawMsgTransfer {
     fn from(domain_msg: MsgTransfer) -> Self {
+        let timeout_height = match domain_msg.timeout_height {
+            TimeoutHeight::Never => None,
+            timeout_height => timeout_height.into(),
+        };
+
         RawMsgTransfer {
             source_port: domain_msg.source_port.to_string(),
             source_channel: domain_msg.source_channel.to_string(),
             token: Some(domain_msg.token),
             sender: domain_msg.sender.to_string(),
             receiver: domain_msg.receiver.to_string(),
-            timeout_height: domain_msg.timeout_height.into(),
+            timeout_height,
             timeout_timestamp: domain_msg.timeout_timestamp.nanoseconds(),
+            memo: domain_msg.memo,
         }
     }
 }
@@ -131,6 +144,7 @@ pub mod test_util {
     use super::MsgTransfer;
     use crate::bigint::U256;
     use crate::core::ics04_channel::timeout::TimeoutHeight;
+    use crate::prelude::*;
     use crate::signer::Signer;
     use crate::{
         applications::transfer::{BaseCoin, PrefixedCoin},
@@ -159,6 +173,143 @@ pub mod test_util {
             timeout_timestamp: timeout_timestamp
                 .unwrap_or_else(|| Timestamp::now().add(Duration::from_secs(10)).unwrap()),
             timeout_height,
+            memo: String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MsgTransfer;
+
+    use ibc_proto::cosmos::base::v1beta1::Coin;
+    use ibc_proto::ibc::applications::transfer::v1::MsgTransfer as RawMsgTransfer;
+    use subtle_encoding::hex;
+    use tendermint_proto::Protobuf;
+
+    use crate::core::ics02_client::height::Height;
+    use crate::core::ics04_channel::timeout::TimeoutHeight;
+    use crate::core::ics24_host::identifier::{ChannelId, PortId};
+    use crate::prelude::*;
+    use crate::timestamp::Timestamp;
+
+    // `MsgTransfer`s in the wire format of ibc-go v4, which always encodes the
+    // non-nullable timeout height, even when it is zero.
+    const IBC_GO_V4_ZERO_HEIGHT: &str = "0a087472616e7366657212096368616e6e656c2d301a0b0a057561746f6d12023130220d636f736d6f733173656e6465722a0f636f736d6f73317265636569766572320038e807";
+    const IBC_GO_V4_HEIGHT: &str = "0a087472616e7366657212096368616e6e656c2d301a0b0a057561746f6d12023130220d636f736d6f733173656e6465722a0f636f736d6f73317265636569766572320408011064";
+
+    // `MsgTransfer`s in the wire format of ibc-go v7, which adds a `memo` field.
+    const IBC_GO_V7_ZERO_HEIGHT: &str = "0a087472616e7366657212096368616e6e656c2d301a0b0a057561746f6d12023130220d636f736d6f733173656e6465722a0f636f736d6f73317265636569766572320038e80742046d656d6f";
+    const IBC_GO_V7_HEIGHT: &str = "0a087472616e7366657212096368616e6e656c2d301a0b0a057561746f6d12023130220d636f736d6f733173656e6465722a0f636f736d6f7331726563656976657232040801106442046d656d6f";
+
+    // A `MsgTransfer` without timeout height, as encoded by Hermes.
+    const UNSET_HEIGHT: &str = "0a087472616e7366657212096368616e6e656c2d301a0b0a057561746f6d12023130220d636f736d6f733173656e6465722a0f636f736d6f7331726563656976657238e807";
+
+    fn msg_transfer(timeout_height: TimeoutHeight, timeout_timestamp: Timestamp) -> MsgTransfer {
+        MsgTransfer {
+            source_port: PortId::transfer(),
+            source_channel: ChannelId::new(0),
+            token: Coin {
+                denom: "uatom".to_string(),
+                amount: "10".to_string(),
+            },
+            sender: "cosmos1sender".parse().unwrap(),
+            receiver: "cosmos1receiver".parse().unwrap(),
+            timeout_height,
+            timeout_timestamp,
+            memo: String::new(),
+        }
+    }
+
+    fn decode(fixture: &str) -> MsgTransfer {
+        MsgTransfer::decode_vec(&hex::decode(fixture).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_msg_transfer_no_timeout_height_is_unset() {
+        let msg = msg_transfer(
+            TimeoutHeight::Never,
+            Timestamp::from_nanoseconds(1000).unwrap(),
+        );
+
+        assert_eq!(RawMsgTransfer::from(msg.clone()).timeout_height, None);
+        assert_eq!(
+            msg.encode_vec().unwrap(),
+            hex::decode(UNSET_HEIGHT).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_msg_transfer_timeouts_round_trip() {
+        let heights = [
+            TimeoutHeight::Never,
+            TimeoutHeight::At(Height::new(1, 100).unwrap()),
+        ];
+
+        let timestamps = [
+            Timestamp::none(),
+            Timestamp::from_nanoseconds(1000).unwrap(),
+        ];
+
+        for timeout_height in heights {
+            for timeout_timestamp in timestamps {
+                let msg = msg_transfer(timeout_height, timeout_timestamp);
+                let decoded = MsgTransfer::decode_vec(&msg.encode_vec().unwrap()).unwrap();
+
+                assert_eq!(decoded, msg);
+            }
         }
     }
+
+    #[test]
+    fn test_msg_transfer_decode_ibc_go_encodings() {
+        let no_timeout_height = msg_transfer(
+            TimeoutHeight::Never,
+            Timestamp::from_nanoseconds(1000).unwrap(),
+        );
+
+        assert_eq!(decode(IBC_GO_V4_ZERO_HEIGHT), no_timeout_height);
+        assert_eq!(decode(UNSET_HEIGHT), no_timeout_height);
+        assert_eq!(
+            decode(IBC_GO_V7_ZERO_HEIGHT),
+            MsgTransfer {
+                memo: "memo".to_string(),
+                ..no_timeout_height
+            }
+        );
+
+        let timeout_height = msg_transfer(
+            TimeoutHeight::At(Height::new(1, 100).unwrap()),
+            Timestamp::none(),
+        );
+
+        assert_eq!(decode(IBC_GO_V4_HEIGHT), timeout_height);
+        assert_eq!(
+            decode(IBC_GO_V7_HEIGHT),
+            MsgTransfer {
+                memo: "memo".to_string(),
+                ..timeout_height
+            }
+        );
+    }
+
+    #[test]
+    fn test_msg_transfer_memo_encoding() {
+        let msg = MsgTransfer {
+            memo: "memo".to_string(),
+            ..msg_transfer(
+                TimeoutHeight::At(Height::new(1, 100).unwrap()),
+                Timestamp::none(),
+            )
+        };
+
+        assert_eq!(
+            msg.encode_vec().unwrap(),
+            hex::decode(IBC_GO_V7_HEIGHT).unwrap()
+        );
+        assert_eq!(
+            MsgTransfer::decode_vec(&msg.encode_vec().unwrap()).unwrap(),
+            msg
+        );
+    }
 }
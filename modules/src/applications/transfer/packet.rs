@@ -3,10 +3,14 @@ use core::convert::TryFrom;
 use core::str::FromStr;
 
 use ibc_proto::ibc::applications::transfer::v2::FungibleTokenPacketData as RawPacketData;
+use prost::Message;
 use serde::{Deserialize, Serialize};
 
 use super::error::Error;
-use super::{Amount, PrefixedCoin, PrefixedDenom};
+use super::{Amount, PrefixedCoin, PrefixedDenom, VERSION_V2};
+use crate::core::ics04_channel::Version;
+use crate::core::ics24_host::identifier::{ChannelId, PortId};
+use crate::prelude::*;
 use crate::signer::Signer;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -42,3 +46,297 @@ impl From<PacketData> for RawPacketData {
         }
     }
 }
+
+/// The data of the packets of the `ics20-2` version of the application,
+/// which carry several tokens and, optionally, the path they are forwarded through.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PacketDataV2 {
+    pub tokens: Vec<PrefixedCoin>,
+    pub sender: Signer,
+    pub receiver: Signer,
+    pub memo: String,
+    pub forwarding: Forwarding,
+}
+
+/// The path through which the tokens of a packet are forwarded once received.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Forwarding {
+    /// The memo used on the final destination of the tokens.
+    pub destination_memo: String,
+    /// The channel ends the tokens are sent from on the intermediate chains, in order.
+    pub hops: Vec<Hop>,
+}
+
+impl Forwarding {
+    pub fn is_empty(&self) -> bool {
+        self.hops.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Hop {
+    pub port_id: PortId,
+    pub channel_id: ChannelId,
+}
+
+impl PacketDataV2 {
+    /// Decodes the protobuf-encoded data of an `ics20-2` packet.
+    pub fn decode(data: &[u8]) -> Result<Self, Error> {
+        let raw = raw::FungibleTokenPacketDataV2::decode(data)
+            .map_err(|_| Error::packet_data_deserialization())?;
+
+        Self::try_from(raw)
+    }
+}
+
+impl TryFrom<raw::FungibleTokenPacketDataV2> for PacketDataV2 {
+    type Error = Error;
+
+    fn try_from(raw_pkt_data: raw::FungibleTokenPacketDataV2) -> Result<Self, Self::Error> {
+        let tokens = raw_pkt_data
+            .tokens
+            .into_iter()
+            .map(PrefixedCoin::try_from)
+            .collect::<Result<_, _>>()?;
+
+        let forwarding = match raw_pkt_data.forwarding {
+            Some(forwarding) => Forwarding {
+                destination_memo: forwarding.destination_memo,
+                hops: forwarding
+                    .hops
+                    .into_iter()
+                    .map(Hop::try_from)
+                    .collect::<Result<_, _>>()?,
+            },
+            None => Forwarding::default(),
+        };
+
+        Ok(Self {
+            tokens,
+            sender: raw_pkt_data.sender.parse().map_err(Error::signer)?,
+            receiver: raw_pkt_data.receiver.parse().map_err(Error::signer)?,
+            memo: raw_pkt_data.memo,
+            forwarding,
+        })
+    }
+}
+
+impl TryFrom<raw::Token> for PrefixedCoin {
+    type Error = Error;
+
+    fn try_from(raw_token: raw::Token) -> Result<Self, Self::Error> {
+        let raw_denom = raw_token.denom.ok_or_else(Error::invalid_token)?;
+
+        // The most recent hop comes first, as in the trace path of a prefixed denomination
+        let denom = raw_denom
+            .trace
+            .iter()
+            .map(|hop| format!("{}/{}/", hop.port_id, hop.channel_id))
+            .chain(core::iter::once(raw_denom.base))
+            .collect::<String>();
+
+        Ok(Self {
+            denom: PrefixedDenom::from_str(&denom)?,
+            amount: Amount::from_str(&raw_token.amount)?,
+        })
+    }
+}
+
+impl TryFrom<raw::Hop> for Hop {
+    type Error = Error;
+
+    fn try_from(raw_hop: raw::Hop) -> Result<Self, Self::Error> {
+        Ok(Self {
+            port_id: raw_hop
+                .port_id
+                .parse()
+                .map_err(|e| Error::invalid_port_id(raw_hop.port_id.clone(), e))?,
+            channel_id: raw_hop
+                .channel_id
+                .parse()
+                .map_err(|e| Error::invalid_channel_id(raw_hop.channel_id.clone(), e))?,
+        })
+    }
+}
+
+/// The data of an ICS-20 packet, in the format of the version of the channel it is sent on.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum VersionedPacketData {
+    V1(PacketData),
+    V2(PacketDataV2),
+}
+
+impl VersionedPacketData {
+    /// Decodes the data of a packet sent on a channel of the given version.
+    ///
+    /// The version of the application is looked up in the version of the channels
+    /// of middlewares wrapping it, e.g. `{"fee_version":"ics29-1","app_version":"ics20-2"}`.
+    pub fn decode(version: &Version, data: &[u8]) -> Result<Self, Error> {
+        if app_version(version) == VERSION_V2 {
+            PacketDataV2::decode(data).map(Self::V2)
+        } else {
+            serde_json::from_slice(data)
+                .map(Self::V1)
+                .map_err(|_| Error::packet_data_deserialization())
+        }
+    }
+
+    pub fn tokens(&self) -> &[PrefixedCoin] {
+        match self {
+            Self::V1(data) => core::slice::from_ref(&data.token),
+            Self::V2(data) => &data.tokens,
+        }
+    }
+
+    pub fn sender(&self) -> &Signer {
+        match self {
+            Self::V1(data) => &data.sender,
+            Self::V2(data) => &data.sender,
+        }
+    }
+
+    pub fn receiver(&self) -> &Signer {
+        match self {
+            Self::V1(data) => &data.receiver,
+            Self::V2(data) => &data.receiver,
+        }
+    }
+}
+
+fn app_version(version: &Version) -> String {
+    #[derive(Deserialize)]
+    struct MiddlewareVersion {
+        app_version: String,
+    }
+
+    let version = version.to_string();
+
+    match serde_json::from_str::<MiddlewareVersion>(&version) {
+        Ok(wrapped) => wrapped.app_version,
+        Err(_) => version,
+    }
+}
+
+/// The protobuf messages of the `ics20-2` packets, as defined by ibc-go in
+/// `ibc/applications/transfer/v2/packet.proto` and `ibc/applications/transfer/v1/token.proto`.
+mod raw {
+    use crate::prelude::*;
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct FungibleTokenPacketDataV2 {
+        #[prost(message, repeated, tag = "1")]
+        pub tokens: Vec<Token>,
+        #[prost(string, tag = "2")]
+        pub sender: String,
+        #[prost(string, tag = "3")]
+        pub receiver: String,
+        #[prost(string, tag = "4")]
+        pub memo: String,
+        #[prost(message, optional, tag = "5")]
+        pub forwarding: Option<ForwardingPacketData>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ForwardingPacketData {
+        #[prost(string, tag = "1")]
+        pub destination_memo: String,
+        #[prost(message, repeated, tag = "2")]
+        pub hops: Vec<Hop>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Token {
+        #[prost(message, optional, tag = "1")]
+        pub denom: Option<Denom>,
+        #[prost(string, tag = "2")]
+        pub amount: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Denom {
+        #[prost(string, tag = "1")]
+        pub base: String,
+        #[prost(message, repeated, tag = "3")]
+        pub trace: Vec<Hop>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Hop {
+        #[prost(string, tag = "1")]
+        pub port_id: String,
+        #[prost(string, tag = "2")]
+        pub channel_id: String,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACKET_DATA_V1: &str =
+        include_str!("../../../tests/support/transfer/packet_data_v1.json");
+
+    const PACKET_DATA_V2: &[u8] =
+        include_bytes!("../../../tests/support/transfer/packet_data_v2.bin");
+
+    fn coin(denom: &str, amount: u64) -> PrefixedCoin {
+        PrefixedCoin {
+            denom: denom.parse().unwrap(),
+            amount: amount.into(),
+        }
+    }
+
+    #[test]
+    fn decode_v1() {
+        let data =
+            VersionedPacketData::decode(&Version::ics20(), PACKET_DATA_V1.as_bytes()).unwrap();
+
+        assert!(matches!(data, VersionedPacketData::V1(_)));
+        assert_eq!(data.tokens(), &[coin("transfer/channel-7/uatom", 1000)]);
+        assert_eq!(data.sender().as_ref(), "cosmos1sender");
+        assert_eq!(data.receiver().as_ref(), "osmo1receiver");
+    }
+
+    #[test]
+    fn decode_v2() {
+        let version = Version::new(VERSION_V2.to_string());
+        let data = VersionedPacketData::decode(&version, PACKET_DATA_V2).unwrap();
+
+        let data = match data {
+            VersionedPacketData::V2(data) => data,
+            data => panic!("expected ics20-2 packet data, got {:?}", data),
+        };
+
+        assert_eq!(
+            data.tokens,
+            vec![coin("transfer/channel-7/uatom", 1000), coin("stake", 25)]
+        );
+        assert_eq!(data.sender.as_ref(), "cosmos1sender");
+        assert_eq!(data.receiver.as_ref(), "osmo1receiver");
+        assert_eq!(data.memo, "swap");
+        assert_eq!(data.forwarding.destination_memo, "final");
+        assert_eq!(
+            data.forwarding.hops,
+            vec![Hop {
+                port_id: PortId::transfer(),
+                channel_id: ChannelId::new(3),
+            }]
+        );
+    }
+
+    #[test]
+    fn decode_v2_wrapped_in_fee_version() {
+        let version =
+            Version::new(r#"{"fee_version":"ics29-1","app_version":"ics20-2"}"#.to_string());
+        let data = VersionedPacketData::decode(&version, PACKET_DATA_V2).unwrap();
+
+        assert_eq!(data.tokens().len(), 2);
+    }
+
+    #[test]
+    fn decode_v2_as_v1() {
+        let data = VersionedPacketData::decode(&Version::ics20(), PACKET_DATA_V2);
+        assert!(data.is_err());
+    }
+}
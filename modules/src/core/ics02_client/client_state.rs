@@ -125,6 +125,24 @@ impl AnyClientState {
         }
     }
 
+    pub fn unbonding_period(&self) -> Duration {
+        match self {
+            AnyClientState::Tendermint(state) => state.unbonding_period,
+
+            #[cfg(any(test, feature = "mocks"))]
+            AnyClientState::Mock(_) => Duration::new(0, 0),
+        }
+    }
+
+    pub fn trusting_period(&self) -> Duration {
+        match self {
+            AnyClientState::Tendermint(state) => state.trusting_period,
+
+            #[cfg(any(test, feature = "mocks"))]
+            AnyClientState::Mock(_) => Duration::new(0, 0),
+        }
+    }
+
     pub fn client_type(&self) -> ClientType {
         match self {
             Self::Tendermint(state) => state.client_type(),
@@ -10,6 +10,7 @@ use crate::core::ics02_client::height::Height;
 use crate::core::ics24_host::identifier::ClientId;
 use crate::events::{IbcEvent, IbcEventType};
 use crate::prelude::*;
+use crate::timestamp::Timestamp;
 
 /// The content of the `key` field for the attribute containing the height.
 pub const HEIGHT_ATTRIBUTE_KEY: &str = "height";
@@ -31,11 +32,19 @@ pub const HEADER_ATTRIBUTE_KEY: &str = "header";
 #[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
 pub struct NewBlock {
     pub height: Height,
+    /// The time of the block, if known
+    pub time: Timestamp,
 }
 
 impl NewBlock {
     pub fn new(h: Height) -> NewBlock {
-        NewBlock { height: h }
+        NewBlock {
+            height: h,
+            time: Timestamp::none(),
+        }
+    }
+    pub fn with_time(self, time: Timestamp) -> NewBlock {
+        NewBlock { time, ..self }
     }
     pub fn set_height(&mut self, height: Height) {
         self.height = height;
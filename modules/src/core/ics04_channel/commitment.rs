@@ -1,6 +1,10 @@
 use crate::prelude::*;
 
 use serde_derive::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::core::ics04_channel::timeout::TimeoutHeight;
+use crate::timestamp::Timestamp;
 
 /// Packet commitment
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
@@ -24,6 +28,26 @@ impl From<Vec<u8>> for PacketCommitment {
     }
 }
 
+/// Compute the commitment of a packet as stored by ibc-go, i.e. the SHA-256 hash of the
+/// big-endian timeout timestamp, timeout revision number and timeout revision height,
+/// followed by the SHA-256 hash of the packet data.
+///
+/// See [`ChannelReader::packet_commitment`](super::context::ChannelReader::packet_commitment)
+/// for the treatment of the absence of timeout height.
+pub fn compute_packet_commitment(
+    packet_data: &[u8],
+    timeout_height: &TimeoutHeight,
+    timeout_timestamp: &Timestamp,
+) -> PacketCommitment {
+    let mut hash_input = timeout_timestamp.nanoseconds().to_be_bytes().to_vec();
+
+    hash_input.extend_from_slice(&timeout_height.commitment_revision_number().to_be_bytes());
+    hash_input.extend_from_slice(&timeout_height.commitment_revision_height().to_be_bytes());
+    hash_input.extend_from_slice(&Sha256::digest(packet_data));
+
+    Sha256::digest(hash_input).to_vec().into()
+}
+
 /// Acknowledgement commitment to be stored
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub struct AcknowledgementCommitment(Vec<u8>);
@@ -45,3 +69,87 @@ impl From<Vec<u8>> for AcknowledgementCommitment {
         Self(bytes)
     }
 }
+
+/// Compute the commitment of an acknowledgement as stored by ibc-go, i.e. the SHA-256 hash
+/// of the acknowledgement bytes.
+pub fn compute_ack_commitment(ack: &[u8]) -> AcknowledgementCommitment {
+    Sha256::digest(ack).to_vec().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compute_ack_commitment, compute_packet_commitment};
+
+    use crate::core::ics04_channel::timeout::TimeoutHeight;
+    use crate::prelude::*;
+    use crate::timestamp::Timestamp;
+    use crate::Height;
+
+    const DATA: &[u8] = br#"{"amount":"100","denom":"uatom","receiver":"cosmos1receiver","sender":"cosmos1sender"}"#;
+
+    const TIMEOUT_NANOS: u64 = 1_665_000_000_000_000_000;
+
+    fn hex(bytes: Vec<u8>) -> String {
+        subtle_encoding::hex::encode(bytes)
+            .into_iter()
+            .map(char::from)
+            .collect()
+    }
+
+    fn commitment(
+        data: &[u8],
+        timeout_height: TimeoutHeight,
+        timeout_timestamp: Timestamp,
+    ) -> String {
+        hex(compute_packet_commitment(data, &timeout_height, &timeout_timestamp).into_vec())
+    }
+
+    /// Test vectors following the `CommitPacket` function of ibc-go.
+    #[test]
+    fn packet_commitment_vectors() {
+        let timeout_height = TimeoutHeight::At(Height::new(1, 1000).unwrap());
+        let timeout_timestamp = Timestamp::from_nanoseconds(TIMEOUT_NANOS).unwrap();
+
+        assert_eq!(
+            commitment(DATA, timeout_height, timeout_timestamp),
+            "0e76997f6436ae81da36356d1002f2263c9aa288459073ec499ab20d5041612c"
+        );
+
+        assert_eq!(
+            commitment(DATA, TimeoutHeight::Never, timeout_timestamp),
+            "d63c266611973d79b7ed87ca5fe6f0fcbd98b733f9ef41a01794ac81517bf301"
+        );
+
+        assert_eq!(
+            commitment(DATA, timeout_height, Timestamp::none()),
+            "da164c4294f75d673ec2fbe99b2f60189ede0538d3e1dff7a7714ac8efb57fa7"
+        );
+
+        assert_eq!(
+            commitment(&[], TimeoutHeight::Never, Timestamp::none()),
+            "e6414172e184a44066320223590766e7ff9d758405e51f5cdddb546267a848f5"
+        );
+    }
+
+    /// Test vectors following the `CommitAcknowledgement` function of ibc-go.
+    #[test]
+    fn ack_commitment_vectors() {
+        assert_eq!(
+            hex(compute_ack_commitment(br#"{"result":"AQ=="}"#).into_vec()),
+            "08f7557ed51826fe18d84512bf24ec75001edbaf2123a477df72a0a9f3640a7c"
+        );
+
+        assert_eq!(
+            hex(compute_ack_commitment(
+                br#"{"error":"ABCI code: 1: error handling packet: see events for details"}"#
+            )
+            .into_vec()),
+            "439dd0ea54f168850977fce615993ac34f8e2d238c76c0c9328a744e89a2230d"
+        );
+
+        assert_eq!(
+            hex(compute_ack_commitment(&[]).into_vec()),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+}
@@ -77,6 +77,8 @@ pub enum WithBlockDataType {
     UpdateClient,
     SendPacket,
     WriteAck,
+    AckPacket,
+    TimeoutPacket,
 }
 
 impl WithBlockDataType {
@@ -86,6 +88,8 @@ impl WithBlockDataType {
             WithBlockDataType::UpdateClient => "update_client",
             WithBlockDataType::SendPacket => "send_packet",
             WithBlockDataType::WriteAck => "write_acknowledgement",
+            WithBlockDataType::AckPacket => "acknowledge_packet",
+            WithBlockDataType::TimeoutPacket => "timeout_packet",
         }
     }
 }
@@ -244,7 +248,40 @@ pub enum IbcEvent {
 
     AppModule(ModuleEvent),
 
-    ChainError(String), // Special event, signifying an error on CheckTx or DeliverTx
+    ChainError(ChainErrorEvent), // Special event, signifying an error on CheckTx or DeliverTx
+}
+
+/// An error reported by a chain on CheckTx or DeliverTx, along with its ABCI code.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ChainErrorEvent {
+    pub message: String,
+    pub code: u32,
+    /// The codespace of the code, empty if it is not reported
+    pub codespace: String,
+    /// The index of the message which failed among the messages sent together,
+    /// if the error is reported for a single message rather than for the transaction
+    pub msg_index: Option<usize>,
+}
+
+impl ChainErrorEvent {
+    pub fn new(message: String, code: u32, codespace: String) -> Self {
+        Self {
+            message,
+            code,
+            codespace,
+            msg_index: None,
+        }
+    }
+
+    pub fn with_msg_index(self, msg_index: Option<usize>) -> Self {
+        Self { msg_index, ..self }
+    }
+}
+
+impl fmt::Display for ChainErrorEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
 }
 
 /// For use in debug messages
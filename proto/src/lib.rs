@@ -79,6 +79,9 @@ pub mod cosmos {
             pub mod v1beta1 {
                 include_proto!("cosmos.base.reflection.v1beta1.rs");
             }
+            pub mod v2alpha1 {
+                include_proto!("cosmos.base.reflection.v2alpha1.rs");
+            }
         }
         pub mod store {
             pub mod v1beta1 {
@@ -129,6 +132,11 @@ pub mod ibc {
         pub use super::applications::*;
     }
     pub mod applications {
+        pub mod fee {
+            pub mod v1 {
+                include_proto!("ibc.applications.fee.v1.rs");
+            }
+        }
         pub mod transfer {
             pub mod v1 {
                 include_proto!("ibc.applications.transfer.v1.rs");
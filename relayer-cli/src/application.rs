@@ -1,6 +1,6 @@
 //! Definition of the application, based on the Abscissa framework
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use abscissa_core::{
     application::{self, AppCell},
@@ -8,9 +8,11 @@ use abscissa_core::{
     config::{self, CfgCell},
     terminal::component::Terminal,
     terminal::ColorChoice,
-    Application, Configurable, FrameworkError, FrameworkErrorKind, StandardPaths,
+    Application, FrameworkError, FrameworkErrorKind, StandardPaths,
 };
+use ibc_relayer::config::merge::load_merged;
 use ibc_relayer::config::Config;
+use ibc_relayer::home::init_home;
 
 use crate::{
     components::{JsonTracing, PrettyTracing},
@@ -47,8 +49,8 @@ pub struct CliApp {
     /// Toggle json output on/off. Changed with the global config option `-j` / `--json`.
     json_output: bool,
 
-    /// Path to the config file.
-    config_path: Option<PathBuf>,
+    /// Paths to the config files, merged in order.
+    config_paths: Vec<PathBuf>,
 }
 
 /// Initialize a new application instance.
@@ -61,7 +63,7 @@ impl Default for CliApp {
             config: CfgCell::default(),
             state: application::State::default(),
             json_output: false,
-            config_path: None,
+            config_paths: Vec::new(),
         }
     }
 }
@@ -72,9 +74,14 @@ impl CliApp {
         self.json_output
     }
 
-    /// Returns the path to the configuration file
+    /// Returns the path to the first configuration file
     pub fn config_path(&self) -> Option<&PathBuf> {
-        self.config_path.as_ref()
+        self.config_paths.first()
+    }
+
+    /// Returns the paths to the configuration files, in the order they are merged
+    pub fn config_paths(&self) -> &[PathBuf] {
+        &self.config_paths
     }
 }
 
@@ -109,6 +116,18 @@ impl Application for CliApp {
         app_components.register(framework_components)
     }
 
+    /// Load the configuration by merging the configuration files given on the
+    /// command line, the later files overriding the earlier ones.
+    fn load_config(&mut self, path: &Path) -> Result<Self::Cfg, FrameworkError> {
+        let result = if self.config_paths.is_empty() {
+            load_merged(&[path])
+        } else {
+            load_merged(&self.config_paths)
+        };
+
+        result.map_err(|e| FrameworkErrorKind::ConfigError.context(e).into())
+    }
+
     /// Post-configuration lifecycle callback.
     ///
     /// Called regardless of whether config is loaded to indicate this is the
@@ -145,17 +164,32 @@ impl Application for CliApp {
     ) -> Result<Vec<Box<dyn Component<Self>>>, FrameworkError> {
         let terminal = Terminal::new(self.term_colors(command));
 
-        let config_path = command.config_path();
-        self.config_path = config_path.clone();
+        // Resolve the home folder before the default configuration file it holds
+        init_home(command.home.as_deref());
+
+        self.config_paths = command.config_paths();
 
-        let config = config_path
+        let config = self
+            .config_paths
+            .first()
+            .cloned()
             .map(|path| self.load_config(&path))
             .transpose()
             .map_err(|err| {
-                let path = self.config_path.clone().unwrap_or_default();
+                let paths = self
+                    .config_paths
+                    .iter()
+                    .map(|path| path.to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("', '");
                 eprintln!(
-                    "The Hermes configuration file at path '{}' is invalid, reason: {}",
-                    path.to_string_lossy(),
+                    "The Hermes configuration {} at path '{}' is invalid, reason: {}",
+                    if self.config_paths.len() > 1 {
+                        "files"
+                    } else {
+                        "file"
+                    },
+                    paths,
                     err
                 );
                 eprintln!(
@@ -2,22 +2,34 @@
 
 use alloc::sync::Arc;
 
+use abscissa_core::{FrameworkError, FrameworkErrorKind};
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
 use tokio::runtime::Runtime as TokioRuntime;
 
 use ibc::core::ics02_client::client_state::ClientState;
 use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc::timestamp::Timestamp;
+use ibc::Height;
 
 use ibc_relayer::{
     chain::{
         counterparty::{channel_connection_client, ChannelConnectionClient},
         handle::{BaseChainHandle, ChainHandle},
+        requests::{QueryHeight, QueryHostConsensusStateRequest},
     },
-    config::Config,
+    config::{ChainConfig, Config},
+    error::Error as RelayerError,
     spawn,
 };
 
 use crate::error::Error;
 
+pub mod adhoc;
+pub mod dry_run;
+pub mod progress;
+
 #[derive(Clone, Debug)]
 /// Pair of chain handles that are used by most CLIs.
 pub struct ChainHandlePair<Chain: ChainHandle = BaseChainHandle> {
@@ -70,8 +82,133 @@ pub fn spawn_chain_runtime_generic<Handle: ChainHandle>(
     config: &Config,
     chain_id: &ChainId,
 ) -> Result<Handle, Error> {
+    let chain_id = resolve_chain_id(config, chain_id)?;
     let rt = Arc::new(TokioRuntime::new().unwrap());
-    spawn::spawn_chain_runtime(config, chain_id, rt).map_err(Error::spawn)
+    spawn::spawn_chain_runtime(config, &chain_id, rt).map_err(Error::spawn)
+}
+
+/// Finds the configuration of the chain given on the command line,
+/// either by its `nickname` or by its chain identifier.
+///
+/// Nicknames are matched first, so that a chain can be referred to by its nickname
+/// even if another chain had it as chain identifier, which the config validation rejects.
+pub fn find_chain_config<'a>(
+    config: &'a Config,
+    chain: &ChainId,
+) -> Result<&'a ChainConfig, Error> {
+    config
+        .chains
+        .iter()
+        .find(|chain_config| chain_config.nickname.as_deref() == Some(chain.as_str()))
+        .or_else(|| config.find_chain(chain))
+        .ok_or_else(|| Error::unknown_chain(chain.clone(), configured_chains(config)))
+}
+
+/// Resolves the chain given on the command line, either by its `nickname`
+/// or by its chain identifier, to the chain identifier of its configuration.
+pub fn resolve_chain_id(config: &Config, chain: &ChainId) -> Result<ChainId, Error> {
+    find_chain_config(config, chain).map(|chain_config| chain_config.id.clone())
+}
+
+/// Overrides the `key_name` of the chains given on the command line, for each of them given
+/// with a key name, e.g. by the `--key-name` and `--dst-key-name` flags of the `tx` commands.
+pub fn override_key_names(
+    mut config: Config,
+    overrides: &[(&ChainId, Option<&str>)],
+) -> Result<Config, FrameworkError> {
+    for (chain, key_name) in overrides {
+        if let Some(key_name) = key_name {
+            let chain_id = resolve_chain_id(&config, chain)
+                .map_err(|e| FrameworkErrorKind::ComponentError.context(e.to_string()))?;
+
+            if let Some(chain_config) = config.find_chain_mut(&chain_id) {
+                chain_config.key_name = key_name.to_string();
+            }
+        }
+    }
+
+    Ok(config)
+}
+
+/// Resolves a chain given on the command line to filter query results by. As the chain
+/// needs not be configured, identifiers which match no configured chain are kept as is.
+pub fn resolve_chain_filter(config: &Config, chain: &ChainId) -> ChainId {
+    resolve_chain_id(config, chain).unwrap_or_else(|_| chain.clone())
+}
+
+/// Lists the identifiers of the configured chains, along with their nicknames.
+fn configured_chains(config: &Config) -> String {
+    if config.chains.is_empty() {
+        return "none".to_string();
+    }
+
+    config
+        .chains
+        .iter()
+        .map(|chain_config| match &chain_config.nickname {
+            Some(nickname) => format!("'{}' (nickname '{}')", chain_config.id, nickname),
+            None => format!("'{}'", chain_config.id),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The characters allowed in identifiers besides the ASCII letters and digits, as per ICS 24.
+const IDENTIFIER_SPECIAL_CHARS: &str = "._+-#[]<>";
+
+/// Parses a port identifier given on the command line, see [`check_identifier_chars`].
+pub fn parse_port_id(input: &str) -> Result<PortId, Error> {
+    let id = check_identifier_chars("port", input)?;
+
+    id.parse()
+        .map_err(|e| Error::cli_arg(format!("invalid port identifier '{}': {}", id, e)))
+}
+
+/// Parses a channel identifier given on the command line, see [`check_identifier_chars`].
+///
+/// Unlike port identifiers, channel identifiers must be lowercase, as the chains
+/// assign them in the form `channel-<N>`.
+pub fn parse_channel_id(input: &str) -> Result<ChannelId, Error> {
+    let id = check_identifier_chars("channel", input)?;
+
+    if let Some((position, c)) = id.chars().enumerate().find(|(_, c)| c.is_ascii_uppercase()) {
+        return Err(Error::cli_arg(format!(
+            "invalid character {:?} at position {} of channel identifier '{}': \
+            channel identifiers are lowercase, eg. 'channel-0'",
+            c,
+            position + 1,
+            id
+        )));
+    }
+
+    id.parse()
+        .map_err(|e| Error::cli_arg(format!("invalid channel identifier '{}': {}", id, e)))
+}
+
+/// Trims the whitespace surrounding an identifier pasted on the command line, and checks
+/// that it only contains the characters allowed by ICS 24, pointing to the first offending
+/// character otherwise, so that eg. a unicode dash is reported before querying the chain.
+fn check_identifier_chars<'a>(kind: &str, input: &'a str) -> Result<&'a str, Error> {
+    let id = input.trim();
+
+    let invalid = id
+        .chars()
+        .enumerate()
+        .find(|(_, c)| !(c.is_ascii_alphanumeric() || IDENTIFIER_SPECIAL_CHARS.contains(*c)));
+
+    match invalid {
+        Some((position, c)) => Err(Error::cli_arg(format!(
+            "invalid character {:?} (U+{:04X}) at position {} of {} identifier '{}': \
+            only ASCII letters, digits and '{}' are allowed",
+            c,
+            c as u32,
+            position + 1,
+            kind,
+            id,
+            IDENTIFIER_SPECIAL_CHARS
+        ))),
+        None => Ok(id),
+    }
 }
 
 /// Spawns a chain runtime for specified chain identifier, queries the counterparty chain associated
@@ -102,3 +239,258 @@ pub fn spawn_chain_counterparty<Chain: ChainHandle>(
         channel_connection_client,
     ))
 }
+
+/// Queries the time of the block at the given height, as recorded in the
+/// chain's own consensus state at that height.
+pub fn query_block_timestamp<Chain: ChainHandle>(
+    chain: &Chain,
+    height: Height,
+) -> Result<Timestamp, Error> {
+    chain
+        .query_host_consensus_state(QueryHostConsensusStateRequest {
+            height: QueryHeight::Specific(height),
+        })
+        .map(|consensus_state| consensus_state.timestamp())
+        .map_err(Error::relayer)
+}
+
+/// Returns the lowest height, up to `latest_height`, of a block whose time is
+/// at or after the given `timestamp`, found by binary search over the block headers.
+///
+/// Heights for which the node cannot return a block, e.g. because it pruned them,
+/// are treated as older than `timestamp`.
+pub fn query_height_at_time<Chain: ChainHandle>(
+    chain: &Chain,
+    latest_height: Height,
+    timestamp: Timestamp,
+) -> Result<Height, Error> {
+    let revision_number = latest_height.revision_number();
+    let at = |revision_height: u64| {
+        Height::new(revision_number, revision_height)
+            .map_err(|e| Error::relayer(RelayerError::ics02(e)))
+    };
+
+    let mut low = 1;
+    let mut high = latest_height.revision_height();
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+
+        match query_block_timestamp(chain, at(mid)?) {
+            Ok(block_time) if timestamp.after(&block_time) => low = mid + 1,
+            Ok(_) => high = mid,
+            Err(_) => low = mid + 1,
+        }
+    }
+
+    at(low)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        find_chain_config, override_key_names, parse_channel_id, parse_port_id, resolve_chain_id,
+    };
+
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+    use ibc_relayer::config::{load, Config};
+
+    fn config() -> Config {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../relayer/tests/config/fixtures/relayer_conf_example.toml"
+        );
+
+        let mut config = load(path).expect("could not parse config");
+        config.chains[0].nickname = Some("alpha".to_string());
+
+        config
+    }
+
+    #[test]
+    fn resolve_by_chain_id() {
+        let config = config();
+
+        for id in ["chain_A", "chain_B"] {
+            let chain_id = ChainId::from_string(id);
+            assert_eq!(resolve_chain_id(&config, &chain_id).unwrap(), chain_id);
+        }
+    }
+
+    #[test]
+    fn resolve_by_nickname() {
+        let config = config();
+
+        assert_eq!(
+            resolve_chain_id(&config, &ChainId::from_string("alpha")).unwrap(),
+            ChainId::from_string("chain_A")
+        );
+    }
+
+    #[test]
+    fn nickname_takes_precedence() {
+        let mut config = config();
+        config.chains[1].nickname = Some("chain_A".to_string());
+
+        let chain_config = find_chain_config(&config, &ChainId::from_string("chain_A")).unwrap();
+        assert_eq!(chain_config.id, ChainId::from_string("chain_B"));
+    }
+
+    #[test]
+    fn unknown_chain() {
+        let err = resolve_chain_id(&config(), &ChainId::from_string("chain_C")).unwrap_err();
+
+        assert_eq!(
+            err.detail().to_string(),
+            "unknown chain 'chain_C', which is neither the identifier nor the nickname of a chain \
+            in the configuration file; configured chains: 'chain_A' (nickname 'alpha'), 'chain_B'"
+        );
+    }
+
+    fn key_names(config: &Config) -> (String, String) {
+        let key_name = |id| {
+            config
+                .find_chain(&ChainId::from_string(id))
+                .unwrap()
+                .key_name
+                .clone()
+        };
+
+        (key_name("chain_A"), key_name("chain_B"))
+    }
+
+    #[test]
+    fn override_key_names_of_src_and_dst() {
+        let (src, dst) = (
+            ChainId::from_string("alpha"),
+            ChainId::from_string("chain_B"),
+        );
+        let defaults = key_names(&config());
+
+        let config = override_key_names(config(), &[(&src, None), (&dst, None)]).unwrap();
+        assert_eq!(key_names(&config), defaults);
+
+        let config = override_key_names(config, &[(&src, Some("src-key")), (&dst, None)]).unwrap();
+        assert_eq!(key_names(&config), ("src-key".to_string(), defaults.1));
+
+        let config = override_key_names(config, &[(&src, None), (&dst, Some("dst-key"))]).unwrap();
+        assert_eq!(
+            key_names(&config),
+            ("src-key".to_string(), "dst-key".to_string())
+        );
+
+        let config = override_key_names(
+            config,
+            &[(&src, Some("src-key-2")), (&dst, Some("dst-key-2"))],
+        )
+        .unwrap();
+        assert_eq!(
+            key_names(&config),
+            ("src-key-2".to_string(), "dst-key-2".to_string())
+        );
+    }
+
+    #[test]
+    fn override_key_name_of_unknown_chain() {
+        let unknown = ChainId::from_string("chain_C");
+
+        assert!(override_key_names(config(), &[(&unknown, None)]).is_ok());
+        assert!(override_key_names(config(), &[(&unknown, Some("key"))]).is_err());
+    }
+
+    #[test]
+    fn parse_ids_trims_whitespace() {
+        assert_eq!(parse_channel_id(" channel-0\n").unwrap(), ChannelId::new(0));
+        assert_eq!(parse_port_id("transfer\t").unwrap(), PortId::transfer());
+    }
+
+    #[test]
+    fn parse_channel_id_rejects_uppercase() {
+        let err = parse_channel_id("Channel-0").unwrap_err();
+
+        assert_eq!(
+            err.detail().to_string(),
+            "CLI argument error: invalid character 'C' at position 1 of channel identifier \
+            'Channel-0': channel identifiers are lowercase, eg. 'channel-0'"
+        );
+    }
+
+    #[test]
+    fn parse_port_id_accepts_uppercase() {
+        assert_eq!(parse_port_id("defaultPort").unwrap(), PortId::default());
+    }
+
+    #[test]
+    fn parse_ids_reject_unicode_dashes() {
+        let err = parse_channel_id("channel\u{2013}0").unwrap_err();
+
+        assert_eq!(
+            err.detail().to_string(),
+            "CLI argument error: invalid character '\u{2013}' (U+2013) at position 8 of channel \
+            identifier 'channel\u{2013}0': only ASCII letters, digits and '._+-#[]<>' are allowed"
+        );
+
+        assert!(parse_port_id("trans\u{2010}fer").is_err());
+    }
+
+    #[test]
+    fn parse_ids_reject_inner_whitespace() {
+        let err = parse_port_id("trans fer").unwrap_err();
+
+        assert!(err
+            .to_string()
+            .contains("invalid character ' ' (U+0020) at position 6 of port identifier"));
+    }
+
+    #[test]
+    fn parse_ids_check_length() {
+        let err = parse_channel_id("chan-0").unwrap_err();
+
+        // The reason is followed by the trace of the validation error
+        assert!(err.detail().to_string().starts_with(
+            "CLI argument error: invalid channel identifier 'chan-0': identifier chan-0 has \
+            invalid length 6 must be between 8-64 characters"
+        ));
+    }
+}
+
+/// A reply of the REST API, see `JsonResult` in the `ibc-relayer-rest` crate.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "status", content = "result")]
+#[serde(rename_all = "lowercase")]
+pub enum RestReply<R> {
+    Success(R),
+    Error(RestError),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RestError {
+    pub msg: String,
+}
+
+impl<R> RestReply<R> {
+    /// Converts the reply to a request to the given URL into a result.
+    pub fn into_result(self, url: String) -> Result<R, Error> {
+        match self {
+            RestReply::Success(result) => Ok(result),
+            RestReply::Error(e) => Err(Error::rest_api(url, e.msg)),
+        }
+    }
+}
+
+/// Queries the given path of the REST API of the running Hermes instance,
+/// configured in the `[rest]` section.
+pub fn query_rest_api<R: DeserializeOwned>(config: &Config, path: &str) -> Result<R, Error> {
+    if !config.rest.enabled {
+        return Err(Error::rest_api_disabled());
+    }
+
+    let url = format!("http://{}:{}{}", config.rest.host, config.rest.port, path);
+
+    ureq::get(&url)
+        .call()
+        .map_err(|e| Error::rest_api(url.clone(), e.to_string()))?
+        .into_json::<RestReply<R>>()
+        .map_err(|e| Error::rest_api(url.clone(), e.to_string()))?
+        .into_result(url)
+}
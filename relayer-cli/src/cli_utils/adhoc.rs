@@ -0,0 +1,327 @@
+//! Chains absent from the configuration, whose parameters are given on the command line
+//! to run a single command against them, e.g. to test a transfer from a devnet.
+
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use ibc::core::ics24_host::identifier::ChainId;
+use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::config::{default, ChainConfig, GasPrice};
+use ibc_relayer::keyring::{HDPath, KeyEntry, KeyRing, Store};
+use tendermint_rpc::Url;
+
+use crate::error::Error;
+
+/// The name of the key of an ad-hoc chain, when no `--key-name` is given.
+pub const DEFAULT_KEY_NAME: &str = "adhoc";
+
+/// The derivation path of the key of an ad-hoc chain.
+const DEFAULT_HD_PATH: &str = "m/44'/118'/0'/0/0";
+
+/// The parameters of an ad-hoc chain given on the command line, which are all mandatory
+/// as soon as one of them is given.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AdHocChainArgs {
+    pub rpc_addr: Option<Url>,
+    pub grpc_addr: Option<Url>,
+    pub account_prefix: Option<String>,
+    pub gas_price: Option<GasPrice>,
+    pub key_file: Option<PathBuf>,
+}
+
+/// A chain absent from the configuration, with the configuration built from
+/// its parameters, and the file of the key to sign its transactions with.
+#[derive(Clone, Debug)]
+pub struct AdHocChain {
+    pub config: ChainConfig,
+    pub key_file: PathBuf,
+}
+
+impl AdHocChainArgs {
+    /// Whether none of the parameters is given, in which case the chain must be configured.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Builds the ad-hoc chain `chain_id` from the parameters, if any is given.
+    ///
+    /// The flags of the missing parameters, whose names start with `flag_prefix`,
+    /// e.g. `--src-rpc` for the `src` prefix, are listed in the error.
+    pub fn build(
+        &self,
+        chain_id: &ChainId,
+        key_name: Option<&str>,
+        flag_prefix: &str,
+    ) -> Result<Option<AdHocChain>, Error> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+
+        match self {
+            Self {
+                rpc_addr: Some(rpc_addr),
+                grpc_addr: Some(grpc_addr),
+                account_prefix: Some(account_prefix),
+                gas_price: Some(gas_price),
+                key_file: Some(key_file),
+            } => {
+                let config = ChainConfig {
+                    id: chain_id.clone(),
+                    nickname: None,
+                    r#type: default::chain_type(),
+                    rpc_addr: rpc_addr.clone(),
+                    websocket_addr: websocket_addr(rpc_addr)?,
+                    grpc_addr: grpc_addr.clone(),
+                    rest_addr: None,
+                    rpc_timeout: default::rpc_timeout(),
+                    account_prefix: account_prefix.clone(),
+                    key_name: key_name.unwrap_or(DEFAULT_KEY_NAME).to_string(),
+                    // The key is added to the runtime of the chain, see `AdHocChain::add_key`
+                    key_store_type: Store::Memory,
+                    key_store_folder: None,
+                    store_prefix: "ibc".to_string(),
+                    default_gas: None,
+                    max_gas: None,
+                    gas_adjustment: None,
+                    gas_multiplier: None,
+                    fee_granter: None,
+                    max_msg_num: Default::default(),
+                    max_tx_size: Default::default(),
+                    clock_drift: default::clock_drift(),
+                    max_block_time: default::max_block_time(),
+                    // No client of the chain is created or updated by a single transaction
+                    trusting_period: None,
+                    memo_prefix: Default::default(),
+                    proof_specs: Default::default(),
+                    strict_version_check: false,
+                    verify_proofs_locally: false,
+                    halt_detection_window: None,
+                    tx_extension: None,
+                    max_priority_price: 0,
+                    failed_tx_dir: None,
+                    max_transfer_memo_len: default::max_transfer_memo_len(),
+                    resolve_denoms: default::resolve_denoms(),
+                    trust_threshold: Default::default(),
+                    gas_price: gas_price.clone(),
+                    packet_filter: Default::default(),
+                    address_type: Default::default(),
+                    channel_versions: Default::default(),
+                    tx_errors: Default::default(),
+                    tls: Default::default(),
+                };
+
+                Ok(Some(AdHocChain {
+                    config,
+                    key_file: key_file.clone(),
+                }))
+            }
+            _ => {
+                let missing = [
+                    ("rpc", self.rpc_addr.is_none()),
+                    ("grpc", self.grpc_addr.is_none()),
+                    ("account-prefix", self.account_prefix.is_none()),
+                    ("gas-price", self.gas_price.is_none()),
+                    ("key-file", self.key_file.is_none()),
+                ]
+                .iter()
+                .filter(|(_, missing)| *missing)
+                .map(|(flag, _)| format!("--{}-{}", flag_prefix, flag))
+                .collect::<Vec<_>>();
+
+                Err(Error::missing_ad_hoc_chain_flags(
+                    chain_id.clone(),
+                    missing.join(", "),
+                ))
+            }
+        }
+    }
+}
+
+impl AdHocChain {
+    /// Reads the key to sign the transactions with from the key file.
+    pub fn load_key(&self) -> Result<KeyEntry, Error> {
+        let contents = fs::read_to_string(&self.key_file).map_err(|e| {
+            Error::cli_arg(format!(
+                "failed to read the key file '{}': {}",
+                self.key_file.display(),
+                e
+            ))
+        })?;
+
+        let hd_path = HDPath::from_str(DEFAULT_HD_PATH).expect("valid derivation path");
+
+        let keyring = KeyRing::new(
+            Store::Memory,
+            &self.config.account_prefix,
+            &self.config.id,
+            &None,
+        )
+        .map_err(|e| Error::cli_arg(e.to_string()))?;
+
+        keyring
+            .key_from_seed_file(&contents, &hd_path)
+            .map_err(|e| {
+                Error::cli_arg(format!(
+                    "invalid key file '{}': {}",
+                    self.key_file.display(),
+                    e
+                ))
+            })
+    }
+
+    /// Adds the key to the in-memory keyring of the runtime of the chain.
+    pub fn add_key<Chain: ChainHandle>(&self, chain: &Chain, key: KeyEntry) -> Result<(), Error> {
+        chain
+            .add_key(self.config.key_name.clone(), key)
+            .map_err(Error::relayer)
+    }
+}
+
+/// Parses a gas price given as a price followed by a denomination, e.g. `0.01uatom`.
+pub fn parse_gas_price(gas_price: &str) -> Result<GasPrice, String> {
+    let denom_start = gas_price
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(gas_price.len());
+
+    let (price, denom) = gas_price.split_at(denom_start);
+
+    match price.parse::<f64>() {
+        Ok(price) if denom.starts_with(|c: char| c.is_ascii_alphabetic()) => {
+            Ok(GasPrice::new(price, denom.to_string()))
+        }
+        _ => Err(format!(
+            "invalid gas price '{}', expected a price followed by a denomination, e.g. `0.01uatom`",
+            gas_price
+        )),
+    }
+}
+
+/// The address of the WebSocket endpoint of the node with the given RPC address,
+/// e.g. `ws://127.0.0.1:26657/websocket` for `http://127.0.0.1:26657`.
+fn websocket_addr(rpc_addr: &Url) -> Result<Url, Error> {
+    let rpc_addr = rpc_addr.to_string();
+
+    let websocket_addr = match rpc_addr.split_once("://") {
+        Some(("https", rest)) => format!("wss://{}", rest),
+        Some(("http", rest)) => format!("ws://{}", rest),
+        _ => rpc_addr.clone(),
+    };
+
+    format!("{}/websocket", websocket_addr.trim_end_matches('/'))
+        .parse()
+        .map_err(|e| {
+            Error::cli_arg(format!(
+                "invalid WebSocket address derived from the RPC address '{}': {}",
+                rpc_addr, e
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_gas_price, websocket_addr, AdHocChainArgs, DEFAULT_KEY_NAME};
+
+    use std::path::PathBuf;
+
+    use ibc::core::ics24_host::identifier::ChainId;
+    use ibc_relayer::config::GasPrice;
+    use ibc_relayer::keyring::Store;
+
+    fn args() -> AdHocChainArgs {
+        AdHocChainArgs {
+            rpc_addr: Some("http://127.0.0.1:26657".parse().unwrap()),
+            grpc_addr: Some("http://127.0.0.1:9090".parse().unwrap()),
+            account_prefix: Some("cosmos".to_string()),
+            gas_price: Some(GasPrice::new(0.01, "uatom".to_string())),
+            key_file: Some(PathBuf::from("key.json")),
+        }
+    }
+
+    #[test]
+    fn build_ad_hoc_chain() {
+        let chain_id = ChainId::from_string("devnet-1");
+
+        let chain = args().build(&chain_id, None, "src").unwrap().unwrap();
+
+        assert_eq!(chain.config.id, chain_id);
+        assert_eq!(
+            chain.config.websocket_addr.to_string(),
+            "ws://127.0.0.1:26657/websocket"
+        );
+        assert_eq!(chain.config.key_name, DEFAULT_KEY_NAME);
+        assert_eq!(chain.config.key_store_type, Store::Memory);
+        assert_eq!(chain.key_file, PathBuf::from("key.json"));
+
+        let chain = args()
+            .build(&chain_id, Some("devnet"), "src")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(chain.config.key_name, "devnet");
+    }
+
+    #[test]
+    fn no_ad_hoc_chain() {
+        let args = AdHocChainArgs::default();
+
+        assert!(args.is_empty());
+        assert!(args
+            .build(&ChainId::from_string("devnet-1"), None, "src")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn missing_ad_hoc_flags() {
+        let args = AdHocChainArgs {
+            grpc_addr: None,
+            key_file: None,
+            ..args()
+        };
+
+        let error = args
+            .build(&ChainId::from_string("devnet-1"), None, "src")
+            .unwrap_err();
+
+        assert!(error.to_string().contains("--src-grpc, --src-key-file"));
+    }
+
+    #[test]
+    fn websocket_addr_from_rpc_addr() {
+        for (rpc_addr, expected) in [
+            ("http://127.0.0.1:26657", "ws://127.0.0.1:26657/websocket"),
+            (
+                "https://rpc.devnet.example.com/",
+                "wss://rpc.devnet.example.com/websocket",
+            ),
+        ] {
+            assert_eq!(
+                websocket_addr(&rpc_addr.parse().unwrap())
+                    .unwrap()
+                    .to_string(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_gas_price() {
+        assert_eq!(
+            parse_gas_price("0.01uatom"),
+            Ok(GasPrice::new(0.01, "uatom".to_string()))
+        );
+        assert_eq!(
+            parse_gas_price("25stake"),
+            Ok(GasPrice::new(25.0, "stake".to_string()))
+        );
+
+        for gas_price in ["", "0.01", "uatom", "-1uatom", "0.0.1uatom"] {
+            assert!(
+                parse_gas_price(gas_price).is_err(),
+                "gas price '{}' should be rejected",
+                gas_price
+            );
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! Output of the `--dry-run` mode of the `tx` commands, which print the messages
+//! they would send instead of broadcasting them.
+
+use ibc::tx_msg::Msg;
+use serde::Serialize;
+use subtle_encoding::base64;
+
+/// A message built by a `tx` command, as it would be sent to the chain,
+/// e.g. to be signed and broadcast by an external signer.
+#[derive(Clone, Debug, Serialize)]
+pub struct DryRunMessage<Raw> {
+    /// The type URL of the message, as in its `google.protobuf.Any` encoding.
+    pub type_url: String,
+    /// The base64 encoding of the protobuf bytes of the message, as in its
+    /// `google.protobuf.Any` encoding.
+    pub value: String,
+    /// The fields of the message.
+    pub decoded: Raw,
+}
+
+impl<Raw> DryRunMessage<Raw> {
+    pub fn new<M: Msg<Raw = Raw>>(msg: M) -> Self
+    where
+        Raw: From<M>,
+    {
+        let type_url = msg.type_url();
+        let decoded = Raw::from(msg.clone());
+        let value = String::from_utf8(base64::encode(msg.get_sign_bytes())).unwrap();
+
+        Self {
+            type_url,
+            value,
+            decoded,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DryRunMessage;
+
+    use core::str::FromStr;
+
+    use ibc::applications::transfer::msgs::transfer::MsgTransfer;
+    use ibc::core::ics04_channel::timeout::TimeoutHeight;
+    use ibc::signer::Signer;
+    use ibc::timestamp::Timestamp;
+    use ibc::tx_msg::Msg;
+    use ibc_proto::cosmos::base::v1beta1::Coin;
+    use subtle_encoding::base64;
+
+    #[test]
+    fn dry_run_message_of_transfer() {
+        let msg = MsgTransfer {
+            source_port: "transfer".parse().unwrap(),
+            source_channel: "channel-0".parse().unwrap(),
+            token: Coin {
+                denom: "uatom".to_string(),
+                amount: "100".to_string(),
+            },
+            sender: Signer::from_str("cosmos1sender").unwrap(),
+            receiver: Signer::from_str("cosmos1receiver").unwrap(),
+            timeout_height: TimeoutHeight::no_timeout(),
+            timeout_timestamp: Timestamp::none(),
+            memo: String::new(),
+        };
+
+        let dry_run = DryRunMessage::new(msg.clone());
+
+        assert_eq!(
+            dry_run.type_url,
+            "/ibc.applications.transfer.v1.MsgTransfer"
+        );
+        assert_eq!(
+            base64::decode(&dry_run.value).unwrap(),
+            msg.get_sign_bytes()
+        );
+        assert_eq!(dry_run.decoded.receiver, "cosmos1receiver");
+    }
+}
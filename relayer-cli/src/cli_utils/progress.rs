@@ -0,0 +1,50 @@
+//! Streaming of the progress of the opening handshakes on the standard output.
+
+use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::progress::HandshakeProgress;
+
+use crate::conclude::{json, Output};
+use crate::prelude::*;
+
+/// The formats in which the progress of a handshake can be streamed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ArgEnum)]
+pub enum ProgressFormat {
+    /// One line of JSON per completed step.
+    Json,
+}
+
+/// Exits with an error if the progress is to be streamed while the final result of the
+/// command is not output as JSON, i.e. without the global `--json` flag, since the lines
+/// of the progress would then be mixed with the text output.
+pub fn check_progress_format(progress: Option<ProgressFormat>) {
+    if progress.is_some() && !json() {
+        Output::error("option `--progress json` requires the global `--json` flag".to_string())
+            .exit()
+    }
+}
+
+/// Returns the callback writing each completed step of a handshake between `a_chain`
+/// and `b_chain` to the standard output in the given `format`, or a callback doing
+/// nothing if there is none.
+pub fn progress_writer<ChainA: ChainHandle, ChainB: ChainHandle>(
+    format: Option<ProgressFormat>,
+    a_chain: ChainA,
+    b_chain: ChainB,
+) -> impl FnMut(HandshakeProgress) {
+    move |progress: HandshakeProgress| {
+        if let Some(ProgressFormat::Json) = format {
+            let tx_hash = if progress.chain_id == a_chain.id() {
+                progress.query_channel_tx_hash(&a_chain)
+            } else {
+                progress.query_channel_tx_hash(&b_chain)
+            };
+
+            let progress = progress.with_tx_hash(tx_hash);
+
+            match serde_json::to_string(&progress) {
+                Ok(line) => println!("{}", line),
+                Err(e) => warn!("failed to serialize the progress {:?}: {}", progress, e),
+            }
+        }
+    }
+}
@@ -1,9 +1,12 @@
 //! Definition of all the Hermes subcommands
 
+mod bootstrap;
+mod check;
 mod clear;
 mod completions;
 mod config;
 mod create;
+mod fees;
 mod health;
 mod keys;
 mod listen;
@@ -14,12 +17,13 @@ mod tx;
 mod update;
 mod upgrade;
 mod version;
+mod worker;
 
 use self::{
-    clear::ClearCmds, completions::CompletionsCmd, config::ConfigCmd, create::CreateCmds,
-    health::HealthCheckCmd, keys::KeysCmd, listen::ListenCmd, misbehaviour::MisbehaviourCmd,
-    query::QueryCmd, start::StartCmd, tx::TxCmd, update::UpdateCmds, upgrade::UpgradeCmds,
-    version::VersionCmd,
+    bootstrap::BootstrapCmds, check::CheckCmds, clear::ClearCmds, completions::CompletionsCmd,
+    config::ConfigCmd, create::CreateCmds, fees::FeesCmds, health::HealthCheckCmd, keys::KeysCmd,
+    listen::ListenCmd, misbehaviour::MisbehaviourCmd, query::QueryCmd, start::StartCmd, tx::TxCmd,
+    update::UpdateCmds, upgrade::UpgradeCmds, version::VersionCmd, worker::WorkerCmds,
 };
 
 use core::time::Duration;
@@ -29,12 +33,12 @@ use abscissa_core::clap::Parser;
 use abscissa_core::{config::Override, Command, Configurable, FrameworkError, Runnable};
 use tracing::{error, info};
 
-use crate::DEFAULT_CONFIG_PATH;
 use ibc_relayer::config::Config;
+use ibc_relayer::home::{home, HOME_ENV_VAR};
 
-/// Default configuration file path
+/// Default configuration file path, in the home folder
 pub fn default_config_file() -> Option<PathBuf> {
-    dirs_next::home_dir().map(|home| home.join(DEFAULT_CONFIG_PATH))
+    home().map(|home| home.config_file())
 }
 
 /// Cli Subcommands
@@ -56,6 +60,10 @@ pub enum CliCmd {
     #[clap(subcommand)]
     Update(UpdateCmds),
 
+    /// Set up paths between chains, from the clients to a checked transfer channel
+    #[clap(subcommand)]
+    Bootstrap(BootstrapCmds),
+
     /// Upgrade objects (clients) after chain upgrade
     #[clap(subcommand)]
     Upgrade(UpgradeCmds),
@@ -77,6 +85,10 @@ pub enum CliCmd {
     #[clap(subcommand)]
     Tx(TxCmd),
 
+    /// Estimate the fees of IBC messages on a chain
+    #[clap(subcommand)]
+    Fees(FeesCmds),
+
     /// Listen to and display IBC events emitted by a chain
     Listen(ListenCmd),
 
@@ -89,6 +101,14 @@ pub enum CliCmd {
     /// Performs a health check of all chains in the the config
     HealthCheck(HealthCheckCmd),
 
+    /// Check the compatibility of a chain with Hermes
+    #[clap(subcommand)]
+    Check(CheckCmds),
+
+    /// Pause or resume the workers of a running Hermes instance
+    #[clap(subcommand)]
+    Worker(WorkerCmds),
+
     /// Generate auto-complete scripts for different shells.
     #[clap(display_order = 1000)]
     Completions(CompletionsCmd),
@@ -113,11 +133,11 @@ impl Configurable<Config> for CliCmd {
                 None
             }
             None => {
-                // The path to the default config file could not be found
-                error!("could not find default configuration file");
+                // The home folder holding the default config file could not be found
+                error!("could not find default configuration file, the home folder is unknown");
                 error!(
-                    "please create one at '~/{}' or specify it with the '-c'/'--config' flag",
-                    DEFAULT_CONFIG_PATH
+                    "please set the home folder with the '--home' flag or the {} environment variable, or specify the configuration file with the '--config' flag",
+                    HOME_ENV_VAR
                 );
                 error!("for an example, please see https://hermes.informal.systems/config.html#example-configuration-file");
                 None
@@ -0,0 +1,18 @@
+//! `bootstrap` subcommand
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+
+use crate::commands::bootstrap::path::BootstrapPathCmd;
+
+mod path;
+
+/// `bootstrap` subcommands
+#[derive(Command, Debug, Parser, Runnable)]
+pub enum BootstrapCmds {
+    /// Set up a working transfer path between two chains.
+    ///
+    /// Creates the clients, connection and transfer channel between the chains, reusing
+    /// those already set up and resuming the ongoing handshakes, then checks the path
+    /// by relaying a probe transfer.
+    Path(BootstrapPathCmd),
+}
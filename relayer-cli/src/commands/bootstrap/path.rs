@@ -0,0 +1,201 @@
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+
+use ibc::applications::transfer::Amount;
+use ibc::core::ics24_host::identifier::ChainId;
+use ibc_relayer::bootstrap::{bootstrap_channel, probe_channel, ProbeOptions};
+
+use crate::cli_utils::{find_chain_config, ChainHandlePair};
+use crate::conclude::{exit_with_unrecoverable_error, Output};
+use crate::prelude::*;
+
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct BootstrapPathCmd {
+    #[clap(
+        long = "a-chain",
+        required = true,
+        value_name = "A_CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the side `a` chain, which sends the probe transfer"
+    )]
+    chain_a: ChainId,
+
+    #[clap(
+        long = "b-chain",
+        required = true,
+        value_name = "B_CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the side `b` chain, which receives the probe transfer"
+    )]
+    chain_b: ChainId,
+
+    #[clap(
+        long = "probe-amount",
+        default_value = "1",
+        value_name = "AMOUNT",
+        help = "Amount of coins sent by the probe transfer"
+    )]
+    probe_amount: Amount,
+
+    #[clap(
+        long = "probe-denom",
+        value_name = "DENOM",
+        help = "Denomination of the coins sent by the probe transfer (default: the denomination of the gas price of chain `a`)"
+    )]
+    probe_denom: Option<String>,
+
+    #[clap(
+        long = "return-probe",
+        help = "Send the vouchers received on chain `b` by the probe transfer back to chain `a`"
+    )]
+    return_probe: bool,
+
+    #[clap(
+        long = "skip-probe",
+        conflicts_with_all = &["probe-amount", "probe-denom", "return-probe"],
+        help = "Only set up the path, without sending a probe transfer, e.g. to save the fees on mainnets"
+    )]
+    skip_probe: bool,
+}
+
+impl Runnable for BootstrapPathCmd {
+    fn run(&self) {
+        let config = app_config();
+
+        let probe = if self.skip_probe {
+            None
+        } else {
+            let denom = match &self.probe_denom {
+                Some(denom) => denom.clone(),
+                None => find_chain_config(&config, &self.chain_a)
+                    .unwrap_or_else(exit_with_unrecoverable_error)
+                    .gas_price
+                    .denom
+                    .clone(),
+            };
+
+            Some(ProbeOptions {
+                amount: self.probe_amount,
+                denom,
+                send_back: self.return_probe,
+            })
+        };
+
+        let chains = ChainHandlePair::spawn(&config, &self.chain_a, &self.chain_b)
+            .unwrap_or_else(exit_with_unrecoverable_error);
+
+        let (channel, mut report) = bootstrap_channel(&chains.src, &chains.dst)
+            .unwrap_or_else(exit_with_unrecoverable_error);
+
+        info!(
+            "path set up between {}/{} on {} and {}/{} on {}",
+            report.port_id,
+            report.channel.a,
+            report.a_chain_id,
+            report.port_id,
+            report.channel.b,
+            report.b_chain_id
+        );
+
+        if let Some(opts) = &probe {
+            report.probe = Some(probe_channel(&channel, opts));
+        }
+
+        if report.is_success() {
+            Output::success(report).exit()
+        } else {
+            Output::with_error().with_result(report).exit()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BootstrapPathCmd;
+
+    use abscissa_core::clap::Parser;
+    use ibc::applications::transfer::Amount;
+    use ibc::core::ics24_host::identifier::ChainId;
+
+    #[test]
+    fn test_bootstrap_path_required_only() {
+        assert_eq!(
+            BootstrapPathCmd {
+                chain_a: ChainId::from_string("chain_a"),
+                chain_b: ChainId::from_string("chain_b"),
+                probe_amount: Amount::from(1u64),
+                probe_denom: None,
+                return_probe: false,
+                skip_probe: false,
+            },
+            BootstrapPathCmd::parse_from(&["test", "--a-chain", "chain_a", "--b-chain", "chain_b"])
+        )
+    }
+
+    #[test]
+    fn test_bootstrap_path_probe() {
+        assert_eq!(
+            BootstrapPathCmd {
+                chain_a: ChainId::from_string("chain_a"),
+                chain_b: ChainId::from_string("chain_b"),
+                probe_amount: Amount::from(42u64),
+                probe_denom: Some("uatom".to_owned()),
+                return_probe: true,
+                skip_probe: false,
+            },
+            BootstrapPathCmd::parse_from(&[
+                "test",
+                "--a-chain",
+                "chain_a",
+                "--b-chain",
+                "chain_b",
+                "--probe-amount",
+                "42",
+                "--probe-denom",
+                "uatom",
+                "--return-probe"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_bootstrap_path_skip_probe() {
+        assert_eq!(
+            BootstrapPathCmd {
+                chain_a: ChainId::from_string("chain_a"),
+                chain_b: ChainId::from_string("chain_b"),
+                probe_amount: Amount::from(1u64),
+                probe_denom: None,
+                return_probe: false,
+                skip_probe: true,
+            },
+            BootstrapPathCmd::parse_from(&[
+                "test",
+                "--a-chain",
+                "chain_a",
+                "--b-chain",
+                "chain_b",
+                "--skip-probe"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_bootstrap_path_skip_probe_conflicts() {
+        assert!(BootstrapPathCmd::try_parse_from(&[
+            "test",
+            "--a-chain",
+            "chain_a",
+            "--b-chain",
+            "chain_b",
+            "--skip-probe",
+            "--return-probe"
+        ])
+        .is_err())
+    }
+
+    #[test]
+    fn test_bootstrap_path_no_b_chain() {
+        assert!(BootstrapPathCmd::try_parse_from(&["test", "--a-chain", "chain_a"]).is_err())
+    }
+}
@@ -0,0 +1,129 @@
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use serde::Serialize;
+
+use ibc::core::ics24_host::identifier::ChainId;
+use ibc_relayer::chain::cosmos::compatibility::{check_versions, Outcome, VersionCheck};
+use ibc_relayer::chain::handle::ChainHandle;
+
+use crate::cli_utils::{find_chain_config, spawn_chain_runtime};
+use crate::conclude::{json, Output};
+use crate::error::Error;
+use crate::prelude::*;
+
+/// `check` subcommands
+#[derive(Command, Debug, Parser, Runnable)]
+pub enum CheckCmds {
+    /// Check the versions of the software run by the node of a chain
+    /// against the versions supported by Hermes
+    Compatibility(CheckCompatibilityCmd),
+}
+
+/// The result of the `check compatibility` command.
+#[derive(Debug, Serialize)]
+struct Compatibility {
+    chain: ChainId,
+    /// The name and version of the application run by the node.
+    app: String,
+    checks: Vec<VersionCheck>,
+}
+
+/// Check the versions of Tendermint, the SDK and IBC-go run by the node of a chain,
+/// as reported by its `/abci_info` and `/status` RPC endpoints and its `GetNodeInfo`
+/// gRPC query, against the versions supported by Hermes.
+///
+/// `check compatibility --chain <CHAIN_ID>`
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct CheckCompatibilityCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain to check"
+    )]
+    chain_id: ChainId,
+}
+
+impl CheckCompatibilityCmd {
+    fn execute(&self) -> Result<Compatibility, Error> {
+        let mut config = (*app_config()).clone();
+
+        let chain_id = find_chain_config(&config, &self.chain_id)?.id.clone();
+
+        // Report the unsupported versions instead of failing to start the chain runtime
+        for chain_config in config.chains.iter_mut() {
+            chain_config.strict_version_check = false;
+        }
+
+        let chain = spawn_chain_runtime(&config, &chain_id)?;
+        let versions = chain.query_node_versions().map_err(Error::relayer)?;
+
+        Ok(Compatibility {
+            chain: chain_id,
+            checks: check_versions(&versions),
+            app: versions.app,
+        })
+    }
+}
+
+impl Runnable for CheckCompatibilityCmd {
+    fn run(&self) {
+        let result = match self.execute() {
+            Ok(result) => result,
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        };
+
+        let failed = result
+            .checks
+            .iter()
+            .any(|check| check.outcome == Outcome::Fail);
+
+        let output = if failed {
+            Output::with_error()
+        } else {
+            Output::with_success()
+        };
+
+        if json() {
+            output.with_result(result).exit()
+        }
+
+        let mut lines = vec![format!("chain {} runs {}", result.chain, result.app)];
+
+        for check in &result.checks {
+            lines.push(format!(
+                "[{}] {:<10} {:<10} supported: {}",
+                check.outcome,
+                check.component,
+                check.detected.as_deref().unwrap_or("none"),
+                check.supported
+            ));
+        }
+
+        output.with_msg(lines.join("\n")).exit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CheckCompatibilityCmd;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::ChainId;
+
+    #[test]
+    fn test_check_compatibility() {
+        assert_eq!(
+            CheckCompatibilityCmd {
+                chain_id: ChainId::from_string("chain_id")
+            },
+            CheckCompatibilityCmd::parse_from(&["test", "--chain", "chain_id"])
+        )
+    }
+
+    #[test]
+    fn test_check_compatibility_no_chain() {
+        assert!(CheckCompatibilityCmd::try_parse_from(&["test"]).is_err())
+    }
+}
@@ -10,7 +10,9 @@ use ibc_relayer::link::error::LinkError;
 use ibc_relayer::link::{Link, LinkParameters};
 
 use crate::application::app_config;
-use crate::cli_utils::spawn_chain_counterparty;
+use crate::cli_utils::{
+    parse_channel_id, parse_port_id, resolve_chain_id, spawn_chain_counterparty,
+};
 use crate::conclude::Output;
 use crate::error::Error;
 
@@ -38,6 +40,7 @@ pub struct ClearPacketsCmd {
         long = "port",
         required = true,
         value_name = "PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "REQUIRED",
         help = "Identifier of the port"
     )]
@@ -48,6 +51,7 @@ pub struct ClearPacketsCmd {
         alias = "chan",
         required = true,
         value_name = "CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
         help_heading = "REQUIRED",
         help = "Identifier of the channel"
     )]
@@ -68,7 +72,10 @@ pub struct ClearPacketsCmd {
 
 impl Override<Config> for ClearPacketsCmd {
     fn override_config(&self, mut config: Config) -> Result<Config, abscissa_core::FrameworkError> {
-        let chain_config = config.find_chain_mut(&self.chain_id).ok_or_else(|| {
+        let chain_id = resolve_chain_id(&config, &self.chain_id)
+            .map_err(|e| FrameworkErrorKind::ComponentError.context(e.to_string()))?;
+
+        let chain_config = config.find_chain_mut(&chain_id).ok_or_else(|| {
             FrameworkErrorKind::ComponentError.context(format!(
                 "missing configuration for chain '{}'",
                 self.chain_id
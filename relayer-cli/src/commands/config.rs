@@ -3,6 +3,8 @@
 use abscissa_core::clap::Parser;
 use abscissa_core::{Command, Runnable};
 
+mod paths;
+mod show;
 mod validate;
 
 /// `config` subcommand
@@ -10,4 +12,10 @@ mod validate;
 pub enum ConfigCmd {
     /// Validate the relayer configuration
     Validate(validate::ValidateCmd),
+
+    /// Show the configuration files, or the configuration resulting from merging them
+    Show(show::ShowCmd),
+
+    /// Show the paths of the home folder, configuration files, and files of the chains used by Hermes
+    Paths(paths::PathsCmd),
 }
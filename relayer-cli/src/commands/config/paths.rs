@@ -0,0 +1,123 @@
+use std::path::PathBuf;
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use serde::Serialize;
+
+use ibc::core::ics24_host::identifier::ChainId;
+use ibc_relayer::config::ChainConfig;
+use ibc_relayer::home::{self, Home};
+use ibc_relayer::keyring::{disk_store_folder, Store};
+
+use crate::conclude::{json, Output};
+use crate::config;
+use crate::prelude::*;
+
+/// The data structure that represents the arguments when invoking the `config paths` CLI command.
+///
+/// The command has the following format:
+///
+/// `config paths`
+///
+/// It prints the effective paths used by Hermes: the home folder and where it was taken from,
+/// the configuration files, and for each chain the folder its keys are stored in, and the folder
+/// its failed transactions are dumped to, if any.
+#[derive(Command, Debug, Parser, PartialEq)]
+pub struct PathsCmd {}
+
+#[derive(Debug, Serialize)]
+struct Paths {
+    home: Option<Home>,
+    config_files: Vec<PathBuf>,
+    chains: Vec<ChainPaths>,
+}
+
+#[derive(Debug, Serialize)]
+struct ChainPaths {
+    chain_id: ChainId,
+    key_store_type: Store,
+    /// The folder the keys are stored in, if they are stored on disk.
+    key_store_folder: Option<PathBuf>,
+    failed_tx_dir: Option<PathBuf>,
+}
+
+impl ChainPaths {
+    fn new(chain_config: &ChainConfig) -> Self {
+        let key_store_folder = match chain_config.key_store_type {
+            Store::Memory => None,
+            Store::Test => {
+                disk_store_folder(&chain_config.id, chain_config.key_store_folder.as_deref()).ok()
+            }
+        };
+
+        Self {
+            chain_id: chain_config.id.clone(),
+            key_store_type: chain_config.key_store_type,
+            key_store_folder,
+            failed_tx_dir: chain_config
+                .failed_tx_dir
+                .as_deref()
+                .map(home::resolve_path),
+        }
+    }
+}
+
+impl Runnable for PathsCmd {
+    fn run(&self) {
+        let config = app_config();
+
+        let paths = Paths {
+            home: home::home(),
+            config_files: config::config_paths(),
+            chains: config.chains.iter().map(ChainPaths::new).collect(),
+        };
+
+        if json() {
+            Output::success(paths).exit()
+        }
+
+        let mut lines = vec![match &paths.home {
+            Some(home) => format!("home folder: {} ({})", home.path.display(), home.source),
+            None => "home folder: unknown".to_string(),
+        }];
+
+        for path in &paths.config_files {
+            lines.push(format!("configuration file: {}", path.display()));
+        }
+
+        for chain in &paths.chains {
+            lines.push(format!(
+                "chain {}: keys in {}, failed transactions dumped to {}",
+                chain.chain_id,
+                match (chain.key_store_type, &chain.key_store_folder) {
+                    (Store::Memory, _) => "memory".to_string(),
+                    (Store::Test, Some(folder)) => folder.display().to_string(),
+                    (Store::Test, None) => "an unknown folder".to_string(),
+                },
+                chain
+                    .failed_tx_dir
+                    .as_ref()
+                    .map_or("nowhere".to_string(), |dir| dir.display().to_string()),
+            ));
+        }
+
+        Output::success_msg(lines.join("\n")).exit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PathsCmd;
+
+    use abscissa_core::clap::Parser;
+
+    #[test]
+    fn test_paths() {
+        assert_eq!(PathsCmd {}, PathsCmd::parse_from(&["test"]))
+    }
+
+    #[test]
+    fn test_paths_unknown_flag() {
+        assert!(PathsCmd::try_parse_from(&["test", "--merged"]).is_err())
+    }
+}
@@ -0,0 +1,63 @@
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+
+use crate::conclude::{json, Output};
+use crate::config;
+use crate::prelude::*;
+
+/// The data structure that represents the arguments when invoking the `config show` CLI command.
+///
+/// The command has the following format:
+///
+/// `config show [--merged]`
+///
+/// Without `--merged`, the command lists the configuration files in the order they are merged.
+/// With `--merged`, it prints the effective configuration, i.e. the result of merging the files
+/// given with `--config`, as TOML, or as JSON when the JSON output is enabled.
+#[derive(Command, Debug, Parser, PartialEq)]
+pub struct ShowCmd {
+    #[clap(
+        long = "merged",
+        help = "Print the configuration resulting from merging the configuration files"
+    )]
+    merged: bool,
+}
+
+impl Runnable for ShowCmd {
+    fn run(&self) {
+        if !self.merged {
+            Output::success(config::config_paths()).exit()
+        }
+
+        let config = app_config();
+
+        if json() {
+            Output::success(config.as_ref().clone()).exit()
+        }
+
+        match toml::to_string_pretty(&*config) {
+            Ok(config_toml) => println!("{}", config_toml),
+            Err(e) => Output::error(format!("failed to serialize the configuration: {}", e)).exit(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ShowCmd;
+
+    use abscissa_core::clap::Parser;
+
+    #[test]
+    fn test_show() {
+        assert_eq!(ShowCmd { merged: false }, ShowCmd::parse_from(&["test"]))
+    }
+
+    #[test]
+    fn test_show_merged() {
+        assert_eq!(
+            ShowCmd { merged: true },
+            ShowCmd::parse_from(&["test", "--merged"])
+        )
+    }
+}
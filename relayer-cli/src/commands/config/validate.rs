@@ -2,6 +2,12 @@ use std::fs;
 
 use abscissa_core::clap::Parser;
 use abscissa_core::{Command, Runnable};
+use tokio::runtime::Runtime as TokioRuntime;
+
+use ibc_relayer::chain::cosmos::grpc::GrpcEndpoint;
+use ibc_relayer::chain::cosmos::query::account::query_account;
+use ibc_relayer::config::ChainConfig;
+use ibc_relayer::keyring::{check_address, KeyRing, Store};
 
 use crate::conclude::Output;
 use crate::config;
@@ -9,9 +15,19 @@ use crate::prelude::*;
 
 /// In order to validate the configuration file the command will check that the file exists,
 /// that it is readable and not empty. It will then check the validity of the fields inside
-/// the file.
-#[derive(Command, Debug, Parser)]
-pub struct ValidateCmd {}
+/// the file. When several configuration files are given, each of them is checked, and the
+/// fields are checked once the files are merged.
+///
+/// With the `--online` flag, the command will additionally check, for every chain, that the
+/// configured key derives the expected address and that this address has an account on-chain.
+#[derive(Command, Debug, Parser, PartialEq)]
+pub struct ValidateCmd {
+    #[clap(
+        long = "online",
+        help = "Check that the address of each configured key has an account on-chain"
+    )]
+    online: bool,
+}
 
 impl Runnable for ValidateCmd {
     /// Validate the loaded configuration.
@@ -19,31 +35,107 @@ impl Runnable for ValidateCmd {
         let config = app_config();
         trace!("loaded configuration: {:#?}", *config);
 
-        // Verify that the configuration file has been found.
-        match config::config_path() {
-            Some(p) => {
-                // If there is a configuration file, verify that it is readable and not empty.
-                match fs::read_to_string(p.clone()) {
-                    Ok(content) => {
-                        if content.is_empty() {
-                            Output::error("the configuration file is empty").exit();
-                        }
+        // Verify that the configuration files have been found.
+        let paths = config::config_paths();
+        if paths.is_empty() {
+            Output::error("no configuration file found").exit();
+        }
+
+        // Verify that every configuration file is readable and not empty.
+        for p in paths {
+            match fs::read_to_string(&p) {
+                Ok(content) => {
+                    if content.is_empty() {
+                        Output::error(format!("the configuration file {:?} is empty", p)).exit();
                     }
-                    Err(e) => Output::error(format!(
-                        "error reading the configuration file {:?}: {}",
-                        p, e
-                    ))
-                    .exit(),
                 }
+                Err(e) => Output::error(format!(
+                    "error reading the configuration file {:?}: {}",
+                    p, e
+                ))
+                .exit(),
             }
-            None => Output::error("no configuration file found").exit(),
         }
 
         // No need to output the underlying error, this is done already when the application boots.
         // See `application::CliApp::after_config`.
-        match config::validate_config(&config) {
-            Ok(_) => Output::success("configuration is valid").exit(),
-            Err(_) => Output::error("configuration is invalid").exit(),
+        if config::validate_config(&config).is_err() {
+            Output::error("configuration is invalid").exit();
+        }
+
+        if self.online {
+            let rt = TokioRuntime::new().unwrap_or_else(|e| {
+                Output::error(format!("failed to start the Tokio runtime: {}", e)).exit()
+            });
+
+            let errors: Vec<String> = config
+                .chains
+                .iter()
+                .filter_map(|chain_config| {
+                    check_chain_account(&rt, chain_config)
+                        .err()
+                        .map(|e| format!("chain '{}': {}", chain_config.id, e))
+                })
+                .collect();
+
+            if !errors.is_empty() {
+                Output::error(errors.join("\n")).exit();
+            }
         }
+
+        Output::success("configuration is valid").exit()
+    }
+}
+
+/// Check that the key configured for the given chain derives the account stored
+/// in the keyring, and that this account exists on-chain.
+fn check_chain_account(
+    rt: &TokioRuntime,
+    chain_config: &ChainConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let keyring = KeyRing::new(
+        Store::Test,
+        &chain_config.account_prefix,
+        &chain_config.id,
+        &chain_config.key_store_folder,
+    )?;
+    let key = keyring.get_key(&chain_config.key_name)?;
+
+    let account = check_address(
+        &key,
+        &key.account,
+        &chain_config.account_prefix,
+        &chain_config.address_type,
+    )?;
+
+    let grpc_addr = GrpcEndpoint::from_config(chain_config)
+        .map_err(|e| format!("invalid gRPC endpoint '{}': {}", chain_config.grpc_addr, e))?;
+
+    rt.block_on(query_account(&grpc_addr, &account))
+        .map_err(|e| format!("no account found for address '{}': {}", account, e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ValidateCmd;
+
+    use abscissa_core::clap::Parser;
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(
+            ValidateCmd { online: false },
+            ValidateCmd::parse_from(&["test"])
+        )
+    }
+
+    #[test]
+    fn test_validate_online() {
+        assert_eq!(
+            ValidateCmd { online: true },
+            ValidateCmd::parse_from(&["test", "--online"])
+        )
     }
 }
@@ -17,7 +17,8 @@ use ibc_relayer::channel::Channel;
 use ibc_relayer::connection::Connection;
 use ibc_relayer::foreign_client::ForeignClient;
 
-use crate::cli_utils::{spawn_chain_runtime, ChainHandlePair};
+use crate::cli_utils::progress::{check_progress_format, progress_writer, ProgressFormat};
+use crate::cli_utils::{parse_port_id, spawn_chain_runtime, ChainHandlePair};
 use crate::conclude::{exit_with_unrecoverable_error, Output};
 use crate::prelude::*;
 use ibc_relayer::config::default::connection_delay;
@@ -86,6 +87,7 @@ pub struct CreateChannelCommand {
         long = "a-port",
         required = true,
         value_name = "A_PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "FLAGS",
         help = "Identifier of the side `a` port for the new channel"
     )]
@@ -95,6 +97,7 @@ pub struct CreateChannelCommand {
         long = "b-port",
         required = true,
         value_name = "B_PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "FLAGS",
         help = "Identifier of the side `b` port for the new channel"
     )]
@@ -131,10 +134,20 @@ pub struct CreateChannelCommand {
         help = "Skip new_client_connection confirmation"
     )]
     yes: bool,
+
+    #[clap(
+        long = "progress",
+        value_name = "FORMAT",
+        arg_enum,
+        help = "Stream each completed step of the handshakes, as one line of JSON per step for `json`; requires the global `--json` flag"
+    )]
+    progress: Option<ProgressFormat>,
 }
 
 impl Runnable for CreateChannelCommand {
     fn run(&self) {
+        check_progress_format(self.progress);
+
         match &self.connection_a {
             Some(conn) => self.run_reusing_connection(conn),
             None => match &self.chain_b {
@@ -191,20 +204,24 @@ impl CreateChannelCommand {
 
         let client_a = ForeignClient::new(chains.src.clone(), chains.dst.clone())
             .unwrap_or_else(exit_with_unrecoverable_error);
-        let client_b = ForeignClient::new(chains.dst.clone(), chains.src)
+        let client_b = ForeignClient::new(chains.dst.clone(), chains.src.clone())
             .unwrap_or_else(exit_with_unrecoverable_error);
 
+        let mut progress = progress_writer(self.progress, chains.src, chains.dst);
+
         // Create the connection.
-        let con = Connection::new(client_a, client_b, connection_delay())
-            .unwrap_or_else(exit_with_unrecoverable_error);
+        let con =
+            Connection::new_with_progress(client_a, client_b, connection_delay(), &mut progress)
+                .unwrap_or_else(exit_with_unrecoverable_error);
 
         // Finally create the channel.
-        let channel = Channel::new(
+        let channel = Channel::new_with_progress(
             con,
             self.order,
             self.port_a.clone(),
             self.port_b.clone(),
             self.version.clone(),
+            &mut progress,
         )
         .unwrap_or_else(exit_with_unrecoverable_error);
 
@@ -249,20 +266,27 @@ impl CreateChannelCommand {
         // Create the foreign client handles.
         let client_a = ForeignClient::find(chain_b.clone(), chain_a.clone(), conn_end.client_id())
             .unwrap_or_else(exit_with_unrecoverable_error);
-        let client_b = ForeignClient::find(chain_a, chain_b, conn_end.counterparty().client_id())
-            .unwrap_or_else(exit_with_unrecoverable_error);
+        let client_b = ForeignClient::find(
+            chain_a.clone(),
+            chain_b.clone(),
+            conn_end.counterparty().client_id(),
+        )
+        .unwrap_or_else(exit_with_unrecoverable_error);
 
         let identified_end = IdentifiedConnectionEnd::new(connection_a.clone(), conn_end);
 
         let connection = Connection::find(client_a, client_b, &identified_end)
             .unwrap_or_else(exit_with_unrecoverable_error);
 
-        let channel = Channel::new(
+        let mut progress = progress_writer(self.progress, chain_a, chain_b);
+
+        let channel = Channel::new_with_progress(
             connection,
             self.order,
             self.port_a.clone(),
             self.port_b.clone(),
             self.version.clone(),
+            &mut progress,
         )
         .unwrap_or_else(exit_with_unrecoverable_error);
 
@@ -275,6 +299,7 @@ mod tests {
     use std::str::FromStr;
 
     use super::CreateChannelCommand;
+    use crate::cli_utils::progress::ProgressFormat;
     use abscissa_core::clap::Parser;
 
     use ibc::core::ics04_channel::channel::Order;
@@ -293,7 +318,8 @@ mod tests {
                 order: Order::Unordered,
                 version: None,
                 new_client_connection: false,
-                yes: false
+                yes: false,
+                progress: None
             },
             CreateChannelCommand::parse_from(&[
                 "test",
@@ -309,6 +335,37 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_create_channel_progress() {
+        assert_eq!(
+            CreateChannelCommand {
+                chain_a: ChainId::from_string("chain_a"),
+                chain_b: None,
+                connection_a: Some(ConnectionId::from_str("connection_a").unwrap()),
+                port_a: PortId::from_str("port_id_a").unwrap(),
+                port_b: PortId::from_str("port_id_b").unwrap(),
+                order: Order::Unordered,
+                version: None,
+                new_client_connection: false,
+                yes: false,
+                progress: Some(ProgressFormat::Json)
+            },
+            CreateChannelCommand::parse_from(&[
+                "test",
+                "--a-chain",
+                "chain_a",
+                "--a-connection",
+                "connection_a",
+                "--a-port",
+                "port_id_a",
+                "--b-port",
+                "port_id_b",
+                "--progress",
+                "json"
+            ])
+        )
+    }
+
     #[test]
     fn test_create_channel_version() {
         assert_eq!(
@@ -321,7 +378,8 @@ mod tests {
                 order: Order::Unordered,
                 version: Some(Version::new("v1".to_owned())),
                 new_client_connection: false,
-                yes: false
+                yes: false,
+                progress: None
             },
             CreateChannelCommand::parse_from(&[
                 "test",
@@ -351,7 +409,8 @@ mod tests {
                 order: Order::Ordered,
                 version: None,
                 new_client_connection: false,
-                yes: false
+                yes: false,
+                progress: None
             },
             CreateChannelCommand::parse_from(&[
                 "test",
@@ -381,7 +440,8 @@ mod tests {
                 order: Order::Unordered,
                 version: None,
                 new_client_connection: false,
-                yes: false
+                yes: false,
+                progress: None
             },
             CreateChannelCommand::parse_from(&[
                 "test",
@@ -467,7 +527,8 @@ mod tests {
                 order: Order::Unordered,
                 version: None,
                 new_client_connection: true,
-                yes: false
+                yes: false,
+                progress: None
             },
             CreateChannelCommand::parse_from(&[
                 "test",
@@ -496,7 +557,8 @@ mod tests {
                 order: Order::Unordered,
                 version: None,
                 new_client_connection: true,
-                yes: true
+                yes: true,
+                progress: None
             },
             CreateChannelCommand::parse_from(&[
                 "test",
@@ -526,7 +588,8 @@ mod tests {
                 order: Order::Unordered,
                 version: None,
                 new_client_connection: true,
-                yes: false
+                yes: false,
+                progress: None
             },
             CreateChannelCommand::parse_from(&[
                 "test",
@@ -10,6 +10,7 @@ use ibc_relayer::chain::requests::{IncludeProof, QueryClientStateRequest, QueryH
 use ibc_relayer::connection::Connection;
 use ibc_relayer::foreign_client::ForeignClient;
 
+use crate::cli_utils::progress::{check_progress_format, progress_writer, ProgressFormat};
 use crate::cli_utils::{spawn_chain_runtime, ChainHandlePair};
 use crate::conclude::{exit_with_unrecoverable_error, Output};
 use crate::prelude::*;
@@ -65,6 +66,14 @@ pub struct CreateConnectionCommand {
         default_value = "0"
     )]
     delay: u64,
+
+    #[clap(
+        long = "progress",
+        value_name = "FORMAT",
+        arg_enum,
+        help = "Stream each completed step of the handshake, as one line of JSON per step for `json`; requires the global `--json` flag"
+    )]
+    progress: Option<ProgressFormat>,
 }
 
 // cargo run --bin hermes -- create connection --a-chain ibc-0 --b-chain ibc-1
@@ -72,6 +81,8 @@ pub struct CreateConnectionCommand {
 // cargo run --bin hermes -- create connection --a-chain ibc-0 --a-client 07-tendermint-0 --b-client 07-tendermint-0
 impl Runnable for CreateConnectionCommand {
     fn run(&self) {
+        check_progress_format(self.progress);
+
         match &self.chain_b_id {
             Some(side_b) => self.run_using_new_clients(side_b),
             None => self.run_reusing_clients(),
@@ -94,12 +105,13 @@ impl CreateConnectionCommand {
 
         let client_a = ForeignClient::new(chains.src.clone(), chains.dst.clone())
             .unwrap_or_else(exit_with_unrecoverable_error);
-        let client_b = ForeignClient::new(chains.dst.clone(), chains.src)
+        let client_b = ForeignClient::new(chains.dst.clone(), chains.src.clone())
             .unwrap_or_else(exit_with_unrecoverable_error);
 
         // Finally, execute the connection handshake.
         let delay = Duration::from_secs(self.delay);
-        match Connection::new(client_a, client_b, delay) {
+        let mut progress = progress_writer(self.progress, chains.src, chains.dst);
+        match Connection::new_with_progress(client_a, client_b, delay, &mut progress) {
             Ok(conn) => Output::success(conn).exit(),
             Err(e) => Output::error(format!("{}", e)).exit(),
         }
@@ -163,12 +175,13 @@ impl CreateConnectionCommand {
         // Get the two ForeignClient objects.
         let client_a = ForeignClient::find(chain_b.clone(), chain_a.clone(), client_a_id)
             .unwrap_or_else(exit_with_unrecoverable_error);
-        let client_b = ForeignClient::find(chain_a, chain_b, client_b_id)
+        let client_b = ForeignClient::find(chain_a.clone(), chain_b.clone(), client_b_id)
             .unwrap_or_else(exit_with_unrecoverable_error);
 
         // All verification passed. Create the Connection object & do the handshake.
         let delay = Duration::from_secs(self.delay);
-        match Connection::new(client_a, client_b, delay) {
+        let mut progress = progress_writer(self.progress, chain_a, chain_b);
+        match Connection::new_with_progress(client_a, client_b, delay, &mut progress) {
             Ok(conn) => Output::success(conn).exit(),
             Err(e) => Output::error(format!("{}", e)).exit(),
         }
@@ -178,6 +191,7 @@ impl CreateConnectionCommand {
 #[cfg(test)]
 mod tests {
     use super::CreateConnectionCommand;
+    use crate::cli_utils::progress::ProgressFormat;
 
     use abscissa_core::clap::Parser;
     use ibc::core::ics24_host::identifier::{ChainId, ClientId};
@@ -192,7 +206,8 @@ mod tests {
                 chain_b_id: Some(ChainId::from_string("chain_b")),
                 client_a: None,
                 client_b: None,
-                delay: 0
+                delay: 0,
+                progress: None
             },
             CreateConnectionCommand::parse_from(&[
                 "test",
@@ -212,7 +227,8 @@ mod tests {
                 chain_b_id: Some(ChainId::from_string("chain_b")),
                 client_a: None,
                 client_b: None,
-                delay: 42
+                delay: 42,
+                progress: None
             },
             CreateConnectionCommand::parse_from(&[
                 "test",
@@ -226,6 +242,29 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_create_connection_b_chain_with_progress() {
+        assert_eq!(
+            CreateConnectionCommand {
+                chain_a_id: ChainId::from_string("chain_a"),
+                chain_b_id: Some(ChainId::from_string("chain_b")),
+                client_a: None,
+                client_b: None,
+                delay: 0,
+                progress: Some(ProgressFormat::Json)
+            },
+            CreateConnectionCommand::parse_from(&[
+                "test",
+                "--a-chain",
+                "chain_a",
+                "--b-chain",
+                "chain_b",
+                "--progress",
+                "json"
+            ])
+        )
+    }
+
     #[test]
     fn create_connection_a_chain_and_clients() {
         assert_eq!(
@@ -234,7 +273,8 @@ mod tests {
                 chain_b_id: None,
                 client_a: Some(ClientId::from_str("07-client_a").unwrap()),
                 client_b: Some(ClientId::from_str("07-client_b").unwrap()),
-                delay: 0
+                delay: 0,
+                progress: None
             },
             CreateConnectionCommand::parse_from(&[
                 "test",
@@ -256,7 +296,8 @@ mod tests {
                 chain_b_id: None,
                 client_a: Some(ClientId::from_str("07-client_a").unwrap()),
                 client_b: Some(ClientId::from_str("07-client_b").unwrap()),
-                delay: 42
+                delay: 42,
+                progress: None
             },
             CreateConnectionCommand::parse_from(&[
                 "test",
@@ -0,0 +1,15 @@
+//! `fees` subcommand
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+
+use crate::commands::fees::estimate::FeesEstimateCmd;
+
+mod estimate;
+
+/// `fees` subcommands
+#[derive(Command, Debug, Parser, Runnable)]
+pub enum FeesCmds {
+    /// Estimate the gas and fee of representative IBC messages on a chain, by simulating
+    /// them without broadcasting anything.
+    Estimate(FeesEstimateCmd),
+}
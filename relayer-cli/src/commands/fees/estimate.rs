@@ -0,0 +1,326 @@
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use serde::Serialize;
+
+use ibc::core::ics02_client::client_state::ClientState;
+use ibc::core::ics24_host::identifier::{ChainId, ClientId, PortId};
+use ibc::tx_msg::Msg;
+use ibc_proto::cosmos::base::v1beta1::Coin as RawCoin;
+use ibc_proto::google::protobuf::Any;
+use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::chain::requests::{
+    IncludeProof, PageRequest, QueryClientStateRequest, QueryClientStatesRequest, QueryHeight,
+};
+use ibc_relayer::config::Config;
+use ibc_relayer::fee::estimate::{
+    estimate_fee, update_client_messages, FeeEstimate, MessageType, RepresentativeMessages,
+};
+use ibc_relayer::foreign_client::ForeignClient;
+
+use crate::cli_utils::{parse_port_id, spawn_chain_runtime};
+use crate::conclude::{json, Output};
+use crate::error::Error;
+use crate::prelude::*;
+
+/// The estimate of the fee of a type of messages, or why it could not be estimated.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum Row {
+    Estimate(FeeEstimate),
+    Error { message: MessageType, error: String },
+}
+
+/// The result of the `fees estimate` command.
+#[derive(Debug, Serialize)]
+struct FeesEstimate {
+    chain: ChainId,
+    gas_price: String,
+    estimates: Vec<Row>,
+}
+
+fn format_coins(coins: &[RawCoin]) -> String {
+    coins
+        .iter()
+        .map(|coin| format!("{}{}", coin.amount, coin.denom))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The data structure that represents the arguments when invoking the `fees estimate` CLI command.
+///
+/// The command has the following format:
+///
+/// `fees estimate --chain <CHAIN_ID> [--message <MESSAGE>]... [--port <PORT_ID>] [--client <CLIENT_ID>]`
+///
+/// A representative message of each requested type is built and simulated on the chain,
+/// without being broadcast, and its gas and fee at the gas price of the chain are reported.
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct FeesEstimateCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain to estimate the fees on"
+    )]
+    chain_id: ChainId,
+
+    #[clap(
+        long = "message",
+        value_name = "MESSAGE",
+        multiple_occurrences = true,
+        help = "Type of the messages to estimate the fee of, one of 'recv_packet', 'ack', 'update_client' \
+                or 'transfer'. Can be given multiple times (default: all of them)"
+    )]
+    messages: Vec<MessageType>,
+
+    #[clap(
+        long = "port",
+        value_name = "PORT_ID",
+        default_value = "transfer",
+        parse(try_from_str = parse_port_id),
+        help = "Port of the open channel the packet and transfer messages are built with"
+    )]
+    port_id: PortId,
+
+    #[clap(
+        long = "client",
+        value_name = "CLIENT_ID",
+        help = "Client updated by the update_client message (default: the first client of a configured chain)"
+    )]
+    client_id: Option<ClientId>,
+}
+
+impl FeesEstimateCmd {
+    fn message_types(&self) -> Vec<MessageType> {
+        if self.messages.is_empty() {
+            return MessageType::ALL.to_vec();
+        }
+
+        let mut message_types = self.messages.clone();
+        message_types.sort();
+        message_types.dedup();
+        message_types
+    }
+
+    fn execute(&self) -> Result<FeesEstimate, Error> {
+        let config = app_config();
+
+        let chain = spawn_chain_runtime(&config, &self.chain_id)?;
+        let chain_config = chain.config().map_err(Error::relayer)?;
+
+        let representative =
+            RepresentativeMessages::query(&chain, &self.port_id).map_err(Error::fee_estimate)?;
+
+        let estimates = self
+            .message_types()
+            .into_iter()
+            .map(|message| {
+                let estimate = self
+                    .build_messages(&config, &chain, &representative, message)
+                    .and_then(|msgs| {
+                        estimate_fee(&chain, message, msgs).map_err(Error::fee_estimate)
+                    });
+
+                match estimate {
+                    Ok(estimate) => Row::Estimate(estimate),
+                    Err(e) => Row::Error {
+                        message,
+                        error: e.to_string(),
+                    },
+                }
+            })
+            .collect();
+
+        Ok(FeesEstimate {
+            chain: chain.id(),
+            gas_price: chain_config.gas_price.to_string(),
+            estimates,
+        })
+    }
+
+    fn build_messages<Chain: ChainHandle>(
+        &self,
+        config: &Config,
+        chain: &Chain,
+        representative: &RepresentativeMessages,
+        message: MessageType,
+    ) -> Result<Vec<Any>, Error> {
+        let msg = match message {
+            MessageType::RecvPacket => representative.recv_packet().map(Msg::to_any),
+            MessageType::Ack => representative.ack().map(Msg::to_any),
+            MessageType::Transfer => representative.transfer().map(Msg::to_any),
+            MessageType::UpdateClient => return self.update_client_messages(config, chain),
+        };
+
+        msg.map(|msg| vec![msg]).map_err(Error::fee_estimate)
+    }
+
+    /// Builds the update of the client given by `--client`, or else of the first client
+    /// of the chain whose counterparty chain is configured, to the latest header of
+    /// the counterparty chain.
+    fn update_client_messages<Chain: ChainHandle>(
+        &self,
+        config: &Config,
+        chain: &Chain,
+    ) -> Result<Vec<Any>, Error> {
+        let (client_id, counterparty_chain_id) = match &self.client_id {
+            Some(client_id) => {
+                let (client_state, _) = chain
+                    .query_client_state(
+                        QueryClientStateRequest {
+                            client_id: client_id.clone(),
+                            height: QueryHeight::Latest,
+                        },
+                        IncludeProof::No,
+                    )
+                    .map_err(Error::relayer)?;
+
+                (client_id.clone(), client_state.chain_id())
+            }
+            None => chain
+                .query_clients(QueryClientStatesRequest {
+                    pagination: Some(PageRequest::all()),
+                })
+                .map_err(Error::relayer)?
+                .into_iter()
+                .map(|client| (client.client_id, client.client_state.chain_id()))
+                .find(|(_, counterparty_chain_id)| config.has_chain(counterparty_chain_id))
+                .ok_or_else(|| {
+                    Error::cli_arg(format!(
+                        "no client of a configured chain on chain '{}', use `--client` to pick one",
+                        chain.id()
+                    ))
+                })?,
+        };
+
+        let counterparty_chain = spawn_chain_runtime(config, &counterparty_chain_id)?;
+        let client = ForeignClient::restore(client_id, chain.clone(), counterparty_chain);
+
+        update_client_messages(&client).map_err(Error::fee_estimate)
+    }
+}
+
+impl Runnable for FeesEstimateCmd {
+    fn run(&self) {
+        let result = match self.execute() {
+            Ok(result) => result,
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        };
+
+        if json() {
+            Output::success(result).exit()
+        }
+
+        let mut lines = vec![
+            format!(
+                "fees on chain {} at the gas price of {}",
+                result.chain, result.gas_price
+            ),
+            format!(
+                "{:<14} {:>10} {:>10}  {}",
+                "MESSAGE", "GAS", "GAS LIMIT", "FEE"
+            ),
+        ];
+        let mut notes = Vec::new();
+
+        for row in &result.estimates {
+            match row {
+                Row::Estimate(estimate) => {
+                    let gas = match &estimate.simulation_error {
+                        Some(error) => {
+                            notes.push(format!(
+                                "* {} could not be simulated, its gas is the default gas of the chain: {}",
+                                estimate.message, error
+                            ));
+                            format!("{}*", estimate.gas)
+                        }
+                        None => estimate.gas.to_string(),
+                    };
+
+                    lines.push(format!(
+                        "{:<14} {:>10} {:>10}  {}",
+                        estimate.message,
+                        gas,
+                        estimate.gas_limit,
+                        format_coins(&estimate.fee)
+                    ));
+                }
+                Row::Error { message, error } => {
+                    lines.push(format!("{:<14} error: {}", message, error));
+                }
+            }
+        }
+
+        lines.extend(notes);
+
+        Output::success_msg(lines.join("\n")).exit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FeesEstimateCmd;
+
+    use std::str::FromStr;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::{ChainId, ClientId, PortId};
+    use ibc_relayer::fee::estimate::MessageType;
+
+    #[test]
+    fn test_fees_estimate() {
+        assert_eq!(
+            FeesEstimateCmd {
+                chain_id: ChainId::from_string("chain_a"),
+                messages: vec![],
+                port_id: PortId::transfer(),
+                client_id: None,
+            },
+            FeesEstimateCmd::parse_from(&["test", "--chain", "chain_a"])
+        )
+    }
+
+    #[test]
+    fn test_fees_estimate_messages() {
+        let cmd = FeesEstimateCmd::parse_from(&[
+            "test",
+            "--chain",
+            "chain_a",
+            "--message",
+            "update_client",
+            "--message",
+            "recv_packet",
+            "--message",
+            "update_client",
+            "--client",
+            "07-tendermint-2",
+        ]);
+
+        assert_eq!(
+            cmd.client_id,
+            Some(ClientId::from_str("07-tendermint-2").unwrap())
+        );
+        assert_eq!(
+            cmd.message_types(),
+            vec![MessageType::RecvPacket, MessageType::UpdateClient]
+        );
+    }
+
+    #[test]
+    fn test_fees_estimate_unknown_message() {
+        assert!(FeesEstimateCmd::try_parse_from(&[
+            "test",
+            "--chain",
+            "chain_a",
+            "--message",
+            "timeout"
+        ])
+        .is_err())
+    }
+
+    #[test]
+    fn test_fees_estimate_no_chain() {
+        assert!(FeesEstimateCmd::try_parse_from(&["test", "--message", "ack"]).is_err())
+    }
+}
@@ -6,6 +6,8 @@ mod add;
 mod balance;
 mod delete;
 mod list;
+mod rotate;
+mod show;
 
 /// `keys` subcommand
 #[derive(Command, Debug, Parser, Runnable)]
@@ -19,6 +21,12 @@ pub enum KeysCmd {
     /// List keys configured on a chain
     List(list::KeysListCmd),
 
+    /// Show the details of a key, including its public key in several encodings
+    Show(show::KeysShowCmd),
+
     /// Query balance for a key from a configured chain. If no key is given, the key is retrieved from the configuration file.
     Balance(balance::KeyBalanceCmd),
+
+    /// Switch the key signing the transactions of a chain, in a running Hermes instance and in the configuration
+    Rotate(rotate::KeysRotateCmd),
 }
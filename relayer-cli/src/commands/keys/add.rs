@@ -10,11 +10,12 @@ use abscissa_core::{Command, Runnable};
 use ibc::core::ics24_host::identifier::ChainId;
 use ibc_relayer::{
     config::{ChainConfig, Config},
-    keyring::{HDPath, KeyEntry, KeyRing, Store},
+    keyring::{check_address, derive_account, HDPath, KeyEntry, KeyRing, Store},
 };
 use tracing::warn;
 
 use crate::application::app_config;
+use crate::cli_utils::find_chain_config;
 use crate::conclude::Output;
 
 /// The data structure that represents the arguments when invoking the `keys add` CLI command.
@@ -86,13 +87,18 @@ pub struct KeysAddCmd {
         help = "Overwrite the key if there is already one with the same key name"
     )]
     overwrite: bool,
+
+    #[clap(
+        long = "expected-address",
+        value_name = "EXPECTED_ADDRESS",
+        help = "Fail if the address derived from the key does not match the given address"
+    )]
+    expected_address: Option<String>,
 }
 
 impl KeysAddCmd {
     fn options(&self, config: &Config) -> Result<KeysAddOptions, Box<dyn std::error::Error>> {
-        let chain_config = config
-            .find_chain(&self.chain_id)
-            .ok_or_else(|| format!("chain '{}' not found in configuration file", self.chain_id))?;
+        let chain_config = find_chain_config(config, &self.chain_id)?;
 
         let name = self
             .key_name
@@ -135,11 +141,12 @@ impl Runnable for KeysAddCmd {
                     &key_file,
                     &opts.hd_path,
                     self.overwrite,
+                    self.expected_address.as_deref(),
                 );
                 match key {
-                    Ok(key) => Output::success_msg(format!(
+                    Ok(account) => Output::success_msg(format!(
                         "Added key '{}' ({}) on chain {}",
-                        opts.name, key.account, opts.config.id
+                        opts.name, account, opts.config.id
                     ))
                     .exit(),
                     Err(e) => Output::error(format!(
@@ -156,12 +163,13 @@ impl Runnable for KeysAddCmd {
                     &opts.hd_path,
                     &opts.config,
                     self.overwrite,
+                    self.expected_address.as_deref(),
                 );
 
                 match key {
-                    Ok(key) => Output::success_msg(format!(
+                    Ok(account) => Output::success_msg(format!(
                         "Restored key '{}' ({}) on chain {}",
-                        opts.name, key.account, opts.config.id
+                        opts.name, account, opts.config.id
                     ))
                     .exit(),
                     Err(e) => Output::error(format!(
@@ -188,16 +196,23 @@ pub fn add_key(
     file: &Path,
     hd_path: &HDPath,
     overwrite: bool,
-) -> Result<KeyEntry, Box<dyn std::error::Error>> {
-    let mut keyring = KeyRing::new(Store::Test, &config.account_prefix, &config.id)?;
+    expected_address: Option<&str>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut keyring = KeyRing::new(
+        Store::Test,
+        &config.account_prefix,
+        &config.id,
+        &config.key_store_folder,
+    )?;
 
     check_key_exists(&keyring, key_name, overwrite);
 
     let key_contents = fs::read_to_string(file).map_err(|_| "error reading the key file")?;
     let key = keyring.key_from_seed_file(&key_contents, hd_path)?;
+    let account = derived_account(&key, config, expected_address)?;
 
-    keyring.add_key(key_name, key.clone())?;
-    Ok(key)
+    keyring.add_key(key_name, key)?;
+    Ok(account)
 }
 
 pub fn restore_key(
@@ -206,18 +221,43 @@ pub fn restore_key(
     hdpath: &HDPath,
     config: &ChainConfig,
     overwrite: bool,
-) -> Result<KeyEntry, Box<dyn std::error::Error>> {
+    expected_address: Option<&str>,
+) -> Result<String, Box<dyn std::error::Error>> {
     let mnemonic_content =
         fs::read_to_string(mnemonic).map_err(|_| "error reading the mnemonic file")?;
 
-    let mut keyring = KeyRing::new(Store::Test, &config.account_prefix, &config.id)?;
+    let mut keyring = KeyRing::new(
+        Store::Test,
+        &config.account_prefix,
+        &config.id,
+        &config.key_store_folder,
+    )?;
 
     check_key_exists(&keyring, key_name, overwrite);
 
     let key_entry = keyring.key_from_mnemonic(&mnemonic_content, hdpath, &config.address_type)?;
+    let account = derived_account(&key_entry, config, expected_address)?;
+
+    keyring.add_key(key_name, key_entry)?;
+    Ok(account)
+}
+
+/// Derive the account of the given key using the account prefix and address type
+/// configured for the chain. If an expected address is given, fail if the derived
+/// account does not match it.
+fn derived_account(
+    key: &KeyEntry,
+    config: &ChainConfig,
+    expected_address: Option<&str>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let account = match expected_address {
+        Some(expected) => {
+            check_address(key, expected, &config.account_prefix, &config.address_type)?
+        }
+        None => derive_account(key, &config.account_prefix, &config.address_type)?,
+    };
 
-    keyring.add_key(key_name, key_entry.clone())?;
-    Ok(key_entry)
+    Ok(account)
 }
 
 /// Check if the key with the given key name already exists.
@@ -252,6 +292,7 @@ mod tests {
                 key_name: None,
                 hd_path: "m/44'/118'/0'/0/0".to_string(),
                 overwrite: false,
+                expected_address: None,
             },
             KeysAddCmd::parse_from(&["test", "--chain", "chain_id", "--key-file", "key_file"])
         )
@@ -266,7 +307,8 @@ mod tests {
                 mnemonic_file: Some(PathBuf::from("mnemonic_file")),
                 key_name: None,
                 hd_path: "m/44'/118'/0'/0/0".to_string(),
-                overwrite: false
+                overwrite: false,
+                expected_address: None,
             },
             KeysAddCmd::parse_from(&[
                 "test",
@@ -288,6 +330,7 @@ mod tests {
                 key_name: None,
                 hd_path: "m/44'/118'/0'/0/0".to_string(),
                 overwrite: true,
+                expected_address: None,
             },
             KeysAddCmd::parse_from(&[
                 "test",
@@ -310,6 +353,7 @@ mod tests {
                 key_name: None,
                 hd_path: "m/44'/118'/0'/0/0".to_string(),
                 overwrite: true,
+                expected_address: None,
             },
             KeysAddCmd::parse_from(&[
                 "test",
@@ -322,6 +366,30 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_keys_add_mnemonic_file_expected_address() {
+        assert_eq!(
+            KeysAddCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                key_file: None,
+                mnemonic_file: Some(PathBuf::from("mnemonic_file")),
+                key_name: None,
+                hd_path: "m/44'/118'/0'/0/0".to_string(),
+                overwrite: false,
+                expected_address: Some("cosmos1address".to_string()),
+            },
+            KeysAddCmd::parse_from(&[
+                "test",
+                "--chain",
+                "chain_id",
+                "--mnemonic-file",
+                "mnemonic_file",
+                "--expected-address",
+                "cosmos1address"
+            ])
+        )
+    }
+
     #[test]
     fn test_keys_add_no_file_nor_mnemonic() {
         assert!(KeysAddCmd::try_parse_from(&["test", "--chain", "chain_id"]).is_err());
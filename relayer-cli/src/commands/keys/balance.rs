@@ -44,7 +44,7 @@ impl Runnable for KeyBalanceCmd {
             .unwrap_or_else(exit_with_unrecoverable_error);
         let key_name = self.key_name.clone();
 
-        match chain.query_balance(key_name.clone()) {
+        match chain.query_balance(key_name.clone(), None) {
             Ok(balance) if json() => Output::success(balance).exit(),
             Ok(balance) => {
                 // Retrieve the key name string to output.
@@ -8,6 +8,7 @@ use ibc_relayer::{
 };
 
 use crate::application::app_config;
+use crate::cli_utils::find_chain_config;
 use crate::conclude::Output;
 
 #[derive(Clone, Command, Debug, Parser, PartialEq)]
@@ -51,9 +52,7 @@ impl KeysDeleteCmd {
         &self,
         config: &Config,
     ) -> Result<KeysDeleteOptions<'_>, Box<dyn std::error::Error>> {
-        let chain_config = config
-            .find_chain(&self.chain_id)
-            .ok_or_else(|| format!("chain '{}' not found in configuration file", self.chain_id))?;
+        let chain_config = find_chain_config(config, &self.chain_id)?;
 
         let id = match (self.all, &self.key_name) {
             (true, None) => KeysDeleteId::All,
@@ -114,13 +113,23 @@ impl Runnable for KeysDeleteCmd {
 }
 
 pub fn delete_key(config: &ChainConfig, key_name: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let mut keyring = KeyRing::new(Store::Test, &config.account_prefix, &config.id)?;
+    let mut keyring = KeyRing::new(
+        Store::Test,
+        &config.account_prefix,
+        &config.id,
+        &config.key_store_folder,
+    )?;
     keyring.remove_key(key_name)?;
     Ok(())
 }
 
 pub fn delete_all_keys(config: &ChainConfig) -> Result<(), Box<dyn std::error::Error>> {
-    let mut keyring = KeyRing::new(Store::Test, &config.account_prefix, &config.id)?;
+    let mut keyring = KeyRing::new(
+        Store::Test,
+        &config.account_prefix,
+        &config.id,
+        &config.key_store_folder,
+    )?;
     let keys = keyring.keys()?;
     for key in keys {
         keyring.remove_key(&key.0)?;
@@ -10,6 +10,7 @@ use ibc_relayer::{
     keyring::{KeyEntry, KeyRing, Store},
 };
 
+use crate::cli_utils::find_chain_config;
 use crate::conclude::Output;
 use crate::{application::app_config, conclude::json};
 
@@ -27,9 +28,7 @@ pub struct KeysListCmd {
 
 impl KeysListCmd {
     fn options(&self, config: &Config) -> Result<KeysListOptions, String> {
-        let chain_config = config
-            .find_chain(&self.chain_id)
-            .ok_or_else(|| format!("chain '{}' not found in configuration file", self.chain_id))?;
+        let chain_config = find_chain_config(config, &self.chain_id).map_err(|e| e.to_string())?;
 
         Ok(KeysListOptions {
             chain_config: chain_config.clone(),
@@ -71,7 +70,12 @@ pub struct KeysListOptions {
 pub fn list_keys(
     config: ChainConfig,
 ) -> Result<Vec<(String, KeyEntry)>, Box<dyn std::error::Error>> {
-    let keyring = KeyRing::new(Store::Test, &config.account_prefix, &config.id)?;
+    let keyring = KeyRing::new(
+        Store::Test,
+        &config.account_prefix,
+        &config.id,
+        &config.key_store_folder,
+    )?;
     let keys = keyring.keys()?;
     Ok(keys)
 }
@@ -0,0 +1,363 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use serde::Serialize;
+
+use ibc::core::ics24_host::identifier::ChainId;
+use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::config::Config;
+use ibc_relayer::rest::request::{KeyRotation, RotateKey};
+
+use crate::cli_utils::{find_chain_config, spawn_chain_runtime, RestReply};
+use crate::conclude::{json, Output};
+use crate::config::config_paths;
+use crate::error::Error;
+use crate::prelude::*;
+
+/// The data structure that represents the arguments when invoking the `keys rotate` CLI command.
+///
+/// The command has the following format:
+///
+/// `keys rotate --chain <CHAIN_ID> --new-key-name <KEY_NAME>`
+///
+/// The new key must already be in the keyring of the chain, and have a balance to pay the fees.
+/// If a Hermes instance is running with its REST API enabled, its chain runtime completes the
+/// transactions already submitted with the previous key, and signs the next ones with the new key.
+/// The `key_name` of the chain is then updated in the configuration files, each backed up first
+/// with a `.bak` extension, for the key to be used after a restart.
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct KeysRotateCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain"
+    )]
+    chain_id: ChainId,
+
+    #[clap(
+        long = "new-key-name",
+        required = true,
+        value_name = "KEY_NAME",
+        help_heading = "REQUIRED",
+        help = "Name of the key to sign the transactions with, which must be in the keyring"
+    )]
+    new_key_name: String,
+}
+
+/// The outcome of a key rotation.
+#[derive(Debug, Serialize)]
+struct Rotated {
+    #[serde(flatten)]
+    rotation: KeyRotation,
+    /// Whether the key was rotated in a running Hermes instance, or only in the configuration.
+    running_instance: bool,
+    /// The configuration files updated with the new key.
+    config_files: Vec<PathBuf>,
+}
+
+impl KeysRotateCmd {
+    fn execute(&self) -> Result<Rotated, Error> {
+        let config = app_config();
+
+        let chain_config = find_chain_config(&config, &self.chain_id)?;
+
+        if chain_config.key_name == self.new_key_name {
+            return Err(Error::cli_arg(format!(
+                "chain '{}' already uses the key '{}'",
+                chain_config.id, self.new_key_name
+            )));
+        }
+
+        let request = RotateKey {
+            chain_id: chain_config.id.clone(),
+            key_name: self.new_key_name.clone(),
+        };
+
+        let (rotation, running_instance) = match rotate_in_running_instance(&config, &request)? {
+            Some(rotation) => (rotation, true),
+            None => {
+                // Only check that the new key is usable, with a runtime exiting afterwards
+                let chain = spawn_chain_runtime(&config, &request.chain_id)?;
+                chain
+                    .rotate_key(request.key_name.clone())
+                    .map_err(Error::relayer)?;
+
+                let rotation = KeyRotation {
+                    chain_id: request.chain_id,
+                    previous_key_name: chain_config.key_name.clone(),
+                    key_name: request.key_name,
+                };
+
+                (rotation, false)
+            }
+        };
+
+        let config_files = update_config_files(&rotation.chain_id, &rotation.key_name)?;
+
+        Ok(Rotated {
+            rotation,
+            running_instance,
+            config_files,
+        })
+    }
+}
+
+impl Runnable for KeysRotateCmd {
+    fn run(&self) {
+        let rotated = match self.execute() {
+            Ok(rotated) => rotated,
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        };
+
+        if json() {
+            Output::success(rotated).exit()
+        }
+
+        let files = rotated
+            .config_files
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>();
+
+        Output::success_msg(format!(
+            "rotated the key of chain '{}' from '{}' to '{}' {}, updated the configuration files: {}",
+            rotated.rotation.chain_id,
+            rotated.rotation.previous_key_name,
+            rotated.rotation.key_name,
+            if rotated.running_instance {
+                "in the running Hermes instance"
+            } else {
+                "with no running Hermes instance reachable"
+            },
+            files.join(", ")
+        ))
+        .exit()
+    }
+}
+
+/// Rotates the key through the REST API of the running Hermes instance.
+///
+/// Returns `None` if the REST API is disabled, or if no instance is listening on it.
+fn rotate_in_running_instance(
+    config: &Config,
+    request: &RotateKey,
+) -> Result<Option<KeyRotation>, Error> {
+    if !config.rest.enabled {
+        return Ok(None);
+    }
+
+    let url = format!(
+        "http://{}:{}/keys/rotate",
+        config.rest.host, config.rest.port
+    );
+
+    match ureq::post(&url).send_json(request) {
+        Ok(response) => response
+            .into_json::<RestReply<KeyRotation>>()
+            .map_err(|e| Error::rest_api(url.clone(), e.to_string()))?
+            .into_result(url)
+            .map(Some),
+        Err(ureq::Error::Transport(e)) => {
+            debug!("found no running Hermes instance at {}: {}", url, e);
+            Ok(None)
+        }
+        Err(e) => Err(Error::rest_api(url, e.to_string())),
+    }
+}
+
+/// Sets the key of the chain in the configuration files which set it, after backing them up.
+fn update_config_files(chain_id: &ChainId, key_name: &str) -> Result<Vec<PathBuf>, Error> {
+    let mut updated = Vec::new();
+
+    for path in config_paths() {
+        let config_file_error = |e| Error::config_file(path.display().to_string(), e);
+
+        let contents = fs::read_to_string(&path).map_err(config_file_error)?;
+
+        if let Some(contents) = set_key_name(&contents, chain_id, key_name) {
+            fs::copy(&path, backup_path(&path)).map_err(config_file_error)?;
+            fs::write(&path, contents).map_err(config_file_error)?;
+
+            updated.push(path);
+        }
+    }
+
+    if updated.is_empty() {
+        return Err(Error::key_name_not_in_config_file(chain_id.clone()));
+    }
+
+    Ok(updated)
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    PathBuf::from(backup)
+}
+
+/// Sets the `key_name` of the `[[chains]]` table with the given `id` in the given TOML
+/// configuration, leaving the rest of it, including the comments, untouched.
+///
+/// Returns `None` if no such table sets the `key_name`.
+fn set_key_name(contents: &str, chain_id: &ChainId, key_name: &str) -> Option<String> {
+    let mut lines = contents.lines().map(str::to_string).collect::<Vec<_>>();
+
+    // The start and end of the lines of each table, excluding its header
+    let mut tables = Vec::new();
+    let mut start = 0;
+    let mut header = None;
+
+    for (i, line) in lines.iter().enumerate() {
+        if let Some(next_header) = table_header(line) {
+            tables.push((header, start, i));
+            header = Some(next_header);
+            start = i + 1;
+        }
+    }
+    tables.push((header, start, lines.len()));
+
+    let (start, end) = tables.into_iter().find_map(|(header, start, end)| {
+        let table = toml::from_str::<toml::Value>(&lines[start..end].join("\n")).ok()?;
+        let id = table.get("id")?.as_str()?;
+
+        (header == Some("[[chains]]") && id == chain_id.as_str()).then(|| (start, end))
+    })?;
+
+    let line = lines[start..end].iter_mut().find(|line| {
+        line.trim_start()
+            .strip_prefix("key_name")
+            .map_or(false, |rest| rest.trim_start().starts_with('='))
+    })?;
+
+    let indent = &line[..line.len() - line.trim_start().len()];
+    *line = format!("{}key_name = '{}'", indent, key_name);
+
+    let mut contents_with_key = lines.join("\n");
+    if contents.ends_with('\n') {
+        contents_with_key.push('\n');
+    }
+
+    Some(contents_with_key)
+}
+
+/// Returns the header of the table started by the given line, if any, ie. `[name]` or `[[name]]`.
+///
+/// The lines of multi-line arrays starting with `[` contain other characters than
+/// those of the table names, and are not mistaken for headers.
+fn table_header(line: &str) -> Option<&str> {
+    let line = line.split('#').next()?.trim();
+
+    let name = line
+        .strip_prefix("[[")
+        .and_then(|line| line.strip_suffix("]]"))
+        .or_else(|| {
+            line.strip_prefix('[')
+                .and_then(|line| line.strip_suffix(']'))
+        })?;
+
+    let is_name = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.');
+
+    is_name.then(|| line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{set_key_name, KeysRotateCmd};
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::ChainId;
+
+    const CONFIG: &str = r#"[global]
+log_level = 'info'
+
+[[chains]]
+id = 'ibc-0'
+# The key signing the transactions
+key_name = 'testkey'
+packet_filter = { policy = 'allow', list = [
+  ['transfer', 'channel-0'],
+] }
+
+[chains.trust_threshold]
+numerator = '1'
+denominator = '3'
+
+[[chains]]
+key_name = 'testkey'
+id = 'ibc-1'
+"#;
+
+    #[test]
+    fn test_keys_rotate() {
+        assert_eq!(
+            KeysRotateCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                new_key_name: "relayer-2".to_owned(),
+            },
+            KeysRotateCmd::parse_from(&[
+                "test",
+                "--chain",
+                "chain_id",
+                "--new-key-name",
+                "relayer-2"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_keys_rotate_no_new_key_name() {
+        assert!(KeysRotateCmd::try_parse_from(&["test", "--chain", "chain_id"]).is_err())
+    }
+
+    #[test]
+    fn test_keys_rotate_no_chain() {
+        assert!(KeysRotateCmd::try_parse_from(&["test", "--new-key-name", "relayer-2"]).is_err())
+    }
+
+    #[test]
+    fn set_key_name_of_chain() {
+        let updated = set_key_name(CONFIG, &ChainId::from_string("ibc-1"), "relayer-2").unwrap();
+
+        assert_eq!(
+            updated,
+            CONFIG.replace(
+                "key_name = 'testkey'\nid = 'ibc-1'",
+                "key_name = 'relayer-2'\nid = 'ibc-1'"
+            )
+        );
+
+        let updated = set_key_name(CONFIG, &ChainId::from_string("ibc-0"), "relayer-2").unwrap();
+
+        assert_eq!(
+            updated,
+            CONFIG.replacen("key_name = 'testkey'", "key_name = 'relayer-2'", 1)
+        );
+
+        let config = toml::from_str::<toml::Value>(&updated).unwrap();
+        assert_eq!(config["chains"][0]["key_name"].as_str(), Some("relayer-2"));
+        assert_eq!(config["chains"][1]["key_name"].as_str(), Some("testkey"));
+    }
+
+    #[test]
+    fn set_key_name_of_unknown_chain() {
+        assert_eq!(
+            set_key_name(CONFIG, &ChainId::from_string("ibc-2"), "relayer-2"),
+            None
+        );
+        assert_eq!(
+            set_key_name(
+                "[[chains]]\nid = 'ibc-0'\n",
+                &ChainId::from_string("ibc-0"),
+                "relayer-2"
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,283 @@
+use core::fmt;
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use serde::Serialize;
+use subtle_encoding::base64;
+
+use ibc::core::ics24_host::identifier::ChainId;
+use ibc_relayer::{
+    config::{AddressType, ChainConfig, Config},
+    keyring::{derive_account, derive_address, encode_eth_address, KeyEntry, KeyRing, Store},
+};
+
+use crate::application::app_config;
+use crate::cli_utils::find_chain_config;
+use crate::conclude::{json, Output};
+
+/// The type of the public keys of the chains using the Cosmos address type.
+const COSMOS_PUBLIC_KEY_TYPE: &str = "/cosmos.crypto.secp256k1.PubKey";
+
+/// The data structure that represents the arguments when invoking the `keys show` CLI command.
+///
+/// `keys show --chain <CHAIN_ID> [--key-name <KEY_NAME>] [--show-private]`
+///
+/// If no key name is given, it will be taken from the configuration file.
+/// The private key is only displayed with `--show-private`, once confirmed interactively.
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct KeysShowCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain"
+    )]
+    chain_id: ChainId,
+
+    #[clap(
+        long = "key-name",
+        value_name = "KEY_NAME",
+        help = "Name of the key (defaults to the `key_name` defined in the config)"
+    )]
+    key_name: Option<String>,
+
+    #[clap(
+        long = "show-private",
+        help = "Also display the private key, after an interactive confirmation"
+    )]
+    show_private: bool,
+}
+
+impl KeysShowCmd {
+    fn options(&self, config: &Config) -> Result<KeysShowOptions, Box<dyn std::error::Error>> {
+        let chain_config = find_chain_config(config, &self.chain_id)?;
+
+        let name = self
+            .key_name
+            .clone()
+            .unwrap_or_else(|| chain_config.key_name.clone());
+
+        Ok(KeysShowOptions {
+            config: chain_config.clone(),
+            name,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct KeysShowOptions {
+    pub name: String,
+    pub config: ChainConfig,
+}
+
+impl Runnable for KeysShowCmd {
+    fn run(&self) {
+        let config = app_config();
+
+        let opts = match self.options(&config) {
+            Err(err) => Output::error(err).exit(),
+            Ok(result) => result,
+        };
+
+        let key = match get_key(&opts.config, &opts.name) {
+            Err(e) => Output::error(e).exit(),
+            Ok(key) => key,
+        };
+
+        let mut details = match KeyDetails::new(&opts.name, &key, &opts.config) {
+            Err(e) => Output::error(e).exit(),
+            Ok(details) => details,
+        };
+
+        if self.show_private {
+            match confirm_show_private(&opts.name) {
+                Ok(true) => {
+                    details.private_key = Some(hex::encode(key.private_key.to_priv().to_bytes()))
+                }
+                Ok(false) => Output::error("aborted, the private key was not displayed").exit(),
+                Err(e) => Output::error(e).exit(),
+            }
+        }
+
+        if json() {
+            Output::success(details).exit()
+        } else {
+            Output::success_msg(details.to_string()).exit()
+        }
+    }
+}
+
+pub fn get_key(
+    config: &ChainConfig,
+    key_name: &str,
+) -> Result<KeyEntry, Box<dyn std::error::Error>> {
+    let keyring = KeyRing::new(
+        Store::Test,
+        &config.account_prefix,
+        &config.id,
+        &config.key_store_folder,
+    )?;
+
+    Ok(keyring.get_key(key_name)?)
+}
+
+/// Ask the user to confirm that the private key should be displayed.
+/// Fails if the standard input is not a terminal, e.g. when the command runs in a script.
+fn confirm_show_private(key_name: &str) -> Result<bool, String> {
+    if !atty::is(atty::Stream::Stdin) {
+        return Err(
+            "--show-private requires an interactive confirmation, but stdin is not a terminal"
+                .to_string(),
+        );
+    }
+
+    dialoguer::Confirm::new()
+        .with_prompt(format!(
+            "Display the private key of '{}'? Anyone who sees it can spend its funds",
+            key_name
+        ))
+        .default(false)
+        .interact()
+        .map_err(|e| format!("failed to read the confirmation: {}", e))
+}
+
+/// The details of a key, as displayed by `keys show`.
+#[derive(Debug, Serialize)]
+pub struct KeyDetails {
+    name: String,
+    chain_id: ChainId,
+    account: String,
+    /// The address in the Ethereum hexadecimal form, for Ethermint chains only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    eth_address: Option<String>,
+    key_type: String,
+    hd_path: Option<String>,
+    public_key: PublicKeyDetails,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    private_key: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PublicKeyDetails {
+    hex: String,
+    base64: String,
+    /// The public key as the JSON of the protobuf `Any` expected by the Cosmos SDK.
+    proto_any: serde_json::Value,
+}
+
+impl KeyDetails {
+    pub fn new(
+        name: &str,
+        key: &KeyEntry,
+        config: &ChainConfig,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let account = derive_account(key, &config.account_prefix, &config.address_type)?;
+
+        let (key_type, eth_address) = match &config.address_type {
+            AddressType::Ethermint { pk_type } if pk_type.ends_with(".ethsecp256k1.PubKey") => {
+                let address = derive_address(key, &config.address_type);
+                (pk_type.clone(), Some(encode_eth_address(&address)))
+            }
+            AddressType::Ethermint { pk_type } => (pk_type.clone(), None),
+            AddressType::Cosmos => (COSMOS_PUBLIC_KEY_TYPE.to_string(), None),
+        };
+
+        let public_key = key.public_key.to_pub().to_bytes();
+        let public_key_base64 = String::from_utf8(base64::encode(&public_key))?;
+
+        Ok(Self {
+            name: name.to_string(),
+            chain_id: config.id.clone(),
+            account,
+            eth_address,
+            public_key: PublicKeyDetails {
+                hex: hex::encode(&public_key),
+                proto_any: serde_json::json!({
+                    "@type": key_type,
+                    "key": public_key_base64,
+                }),
+                base64: public_key_base64,
+            },
+            key_type,
+            hd_path: key.hd_path.clone(),
+            private_key: None,
+        })
+    }
+}
+
+impl fmt::Display for KeyDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\n- name: {}", self.name)?;
+        write!(f, "\n- chain: {}", self.chain_id)?;
+        write!(f, "\n- account: {}", self.account)?;
+
+        if let Some(eth_address) = &self.eth_address {
+            write!(f, "\n- eth address: {}", eth_address)?;
+        }
+
+        write!(f, "\n- key type: {}", self.key_type)?;
+        write!(
+            f,
+            "\n- derivation path: {}",
+            self.hd_path.as_deref().unwrap_or("unknown")
+        )?;
+        write!(f, "\n- public key (hex): {}", self.public_key.hex)?;
+        write!(f, "\n- public key (base64): {}", self.public_key.base64)?;
+        write!(
+            f,
+            "\n- public key (proto any): {}",
+            self.public_key.proto_any
+        )?;
+
+        if let Some(private_key) = &self.private_key {
+            write!(f, "\n- private key (hex): {}", private_key)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KeysShowCmd;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::ChainId;
+
+    #[test]
+    fn test_keys_show() {
+        assert_eq!(
+            KeysShowCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                key_name: None,
+                show_private: false,
+            },
+            KeysShowCmd::parse_from(&["test", "--chain", "chain_id"])
+        )
+    }
+
+    #[test]
+    fn test_keys_show_key_name_private() {
+        assert_eq!(
+            KeysShowCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                key_name: Some("foo".to_string()),
+                show_private: true,
+            },
+            KeysShowCmd::parse_from(&[
+                "test",
+                "--chain",
+                "chain_id",
+                "--key-name",
+                "foo",
+                "--show-private"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_keys_show_no_chain() {
+        assert!(KeysShowCmd::try_parse_from(&["test"]).is_err())
+    }
+}
@@ -15,6 +15,7 @@ use ibc_relayer::{
     event::monitor::{EventMonitor, EventReceiver},
 };
 
+use crate::cli_utils::find_chain_config;
 use crate::prelude::*;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -74,9 +75,7 @@ impl ListenCmd {
     fn cmd(&self) -> Result<(), Box<dyn std::error::Error>> {
         let config = app_config();
 
-        let chain_config = config
-            .find_chain(&self.chain_id)
-            .ok_or_else(|| format!("chain '{}' not found in configuration", self.chain_id))?;
+        let chain_config = find_chain_config(&config, &self.chain_id)?;
 
         let events = if self.events.is_empty() {
             &[EventFilter::Tx, EventFilter::NewBlock]
@@ -8,21 +8,42 @@ use crate::commands::query::channel_ends::QueryChannelEndsCmd;
 use crate::commands::query::channels::QueryChannelsCmd;
 use crate::commands::query::packet::QueryPacketCmds;
 
+mod chain_params;
+mod chain_status;
+mod chain_sync_status;
 mod channel;
+mod channel_avg_relay_time;
 mod channel_client;
+mod channel_config_template;
+mod channel_create_time;
 mod channel_ends;
+mod channel_ibc_denom;
+mod channel_packet_fee_estimate;
+mod channel_packet_size_histogram;
+mod channel_relay_coverage;
+mod channel_sequence_gap;
 mod channels;
 mod client;
+mod client_compatible_chains;
+mod client_last_update_age;
 mod clients;
 mod connection;
+mod connection_reachable;
 mod connections;
+mod host_consensus_state;
+mod ibc_modules;
 mod packet;
+mod paths;
 mod transfer;
 mod tx;
 
 /// `query` subcommand
 #[derive(Command, Debug, Parser, Runnable)]
 pub enum QueryCmd {
+    /// Query information about chains
+    #[clap(subcommand)]
+    Chain(QueryChainCmds),
+
     /// Query information about clients
     #[clap(subcommand)]
     Client(QueryClientCmds),
@@ -48,6 +69,16 @@ pub enum QueryCmd {
     #[clap(subcommand)]
     Packet(QueryPacketCmds),
 
+    /// Summarize the paths relayed by Hermes and their health, the unhealthy ones first
+    Paths(paths::QueryPathsCmd),
+
+    /// Query the consensus state of a chain itself at a given height
+    HostConsensusState(host_consensus_state::QueryHostConsensusStateCmd),
+
+    /// Query information about IBC application modules
+    #[clap(subcommand)]
+    IbcModule(QueryIbcModuleCmds),
+
     /// Query information about transactions
     #[clap(subcommand)]
     Tx(tx::QueryTxCmd),
@@ -57,6 +88,27 @@ pub enum QueryCmd {
     Transfer(transfer::TransferCmd),
 }
 
+#[derive(Command, Debug, Parser, Runnable)]
+pub enum QueryChainCmds {
+    /// Query the latest height of a chain
+    LatestHeight(chain_status::QueryChainLatestHeightCmd),
+
+    /// Query the status of the node of a chain
+    Status(chain_status::QueryChainStatusCmd),
+
+    /// Query the max expected time per block of a chain, on chain and in the configuration
+    Params(chain_params::QueryChainParamsCmd),
+
+    /// Check whether the node of a chain is fully synced
+    SyncStatus(chain_sync_status::QueryChainSyncStatusCmd),
+}
+
+#[derive(Command, Debug, Parser, Runnable)]
+pub enum QueryIbcModuleCmds {
+    /// List the IBC application modules registered on a chain and the ports they bind to
+    Modules(ibc_modules::QueryIbcModulesCmd),
+}
+
 #[derive(Command, Debug, Parser, Runnable)]
 pub enum QueryClientCmds {
     /// Query the client state
@@ -70,6 +122,12 @@ pub enum QueryClientCmds {
 
     /// Query the client connections
     Connections(client::QueryClientConnectionsCmd),
+
+    /// Show how long ago clients were last updated and how long until they expire
+    LastUpdateAge(client_last_update_age::QueryClientLastUpdateAgeCmd),
+
+    /// Identify the configured chains on which a new client of a chain could be created
+    CompatibleChains(client_compatible_chains::QueryClientCompatibleChainsCmd),
 }
 
 #[derive(Command, Debug, Parser, Runnable)]
@@ -79,6 +137,9 @@ pub enum QueryConnectionCmds {
 
     /// Query connection channels
     Channels(connection::QueryConnectionChannelsCmd),
+
+    /// Check that a connection is open and reachable in both directions
+    Reachable(connection_reachable::QueryConnectionReachableCmd),
 }
 
 #[derive(Command, Debug, Parser, Runnable)]
@@ -91,4 +152,28 @@ pub enum QueryChannelCmds {
 
     /// Query channel ends and underlying connection and client objects
     Ends(QueryChannelEndsCmd),
+
+    /// Measure the end-to-end relay latency of the packets sent on a channel
+    AvgRelayTime(channel_avg_relay_time::QueryChannelAvgRelayTimeCmd),
+
+    /// Compute the IBC denomination of a token received over a channel path
+    IbcDenom(channel_ibc_denom::QueryChannelIbcDenomCmd),
+
+    /// Detect the packets sent on a channel which were neither relayed nor are pending
+    SequenceGap(channel_sequence_gap::QueryChannelSequenceGapCmd),
+
+    /// Estimate whether the ICS-29 fees of a packet cover the cost of relaying it
+    PacketFeeEstimate(channel_packet_fee_estimate::QueryChannelPacketFeeEstimateCmd),
+
+    /// Show the distribution of the sizes of the data of the packets sent on a channel
+    PacketSizeHistogram(channel_packet_size_histogram::QueryChannelPacketSizeHistogramCmd),
+
+    /// Show when channels were created, and how old they are
+    CreateTime(channel_create_time::QueryChannelCreateTimeCmd),
+
+    /// Show which channels of a chain are being relayed, and by which relayers
+    RelayCoverage(channel_relay_coverage::QueryChannelRelayCoverageCmd),
+
+    /// Generate the packet filter configuration to relay on a channel
+    ConfigTemplate(channel_config_template::QueryChannelConfigTemplateCmd),
 }
@@ -0,0 +1,99 @@
+use core::time::Duration;
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use serde::Serialize;
+
+use ibc::core::ics24_host::identifier::ChainId;
+use ibc_relayer::chain::block_time::MaxBlockTimeSource;
+use ibc_relayer::chain::handle::ChainHandle;
+
+use crate::cli_utils::spawn_chain_runtime;
+use crate::conclude::Output;
+use crate::error::Error;
+use crate::prelude::*;
+
+/// The result of the `query chain params` command.
+#[derive(Debug, Serialize)]
+struct ChainParams {
+    /// The `max_expected_time_per_block` parameter of the IBC connection module.
+    max_expected_time_per_block: Option<String>,
+    /// The `max_block_time` of the chain configuration.
+    max_block_time: String,
+    /// The maximum block time used by the relayer to compute the connection block delays.
+    used_max_block_time: String,
+    source: MaxBlockTimeSource,
+}
+
+fn format_duration(duration: Duration) -> String {
+    humantime::format_duration(duration).to_string()
+}
+
+/// The data structure that represents the arguments when invoking the `query chain params` CLI command.
+///
+/// The command has the following format:
+///
+/// `query chain params --chain <CHAIN_ID>`
+///
+/// The `max_expected_time_per_block` is queried from the IBC connection module of the chain,
+/// and is used instead of the configured `max_block_time` when available.
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct QueryChainParamsCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain to query"
+    )]
+    chain_id: ChainId,
+}
+
+impl QueryChainParamsCmd {
+    fn execute(&self) -> Result<ChainParams, Error> {
+        let config = app_config();
+
+        let chain = spawn_chain_runtime(&config, &self.chain_id)?;
+
+        let max_block_time = chain.max_block_time().map_err(Error::relayer)?;
+
+        Ok(ChainParams {
+            max_expected_time_per_block: max_block_time.on_chain.map(format_duration),
+            max_block_time: format_duration(max_block_time.config),
+            used_max_block_time: format_duration(max_block_time.value()),
+            source: max_block_time.source(),
+        })
+    }
+}
+
+impl Runnable for QueryChainParamsCmd {
+    fn run(&self) {
+        match self.execute() {
+            Ok(params) => Output::success(params).exit(),
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::QueryChainParamsCmd;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::ChainId;
+
+    #[test]
+    fn test_query_chain_params() {
+        assert_eq!(
+            QueryChainParamsCmd {
+                chain_id: ChainId::from_string("chain_id"),
+            },
+            QueryChainParamsCmd::parse_from(&["test", "--chain", "chain_id"])
+        )
+    }
+
+    #[test]
+    fn test_query_chain_params_no_chain() {
+        assert!(QueryChainParamsCmd::try_parse_from(&["test"]).is_err())
+    }
+}
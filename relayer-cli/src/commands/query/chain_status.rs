@@ -0,0 +1,319 @@
+use core::time::Duration;
+use std::thread;
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use serde::Serialize;
+
+use ibc::core::ics24_host::identifier::ChainId;
+use ibc::timestamp::Timestamp;
+use ibc::Height;
+use ibc_relayer::chain::endpoint::SyncStatus;
+use ibc_relayer::chain::handle::ChainHandle;
+
+use crate::cli_utils::spawn_chain_runtime;
+use crate::conclude::{json, Output};
+use crate::prelude::*;
+
+/// The result of the `query chain status` command, or one line of its `--watch` mode.
+#[derive(Debug, Serialize)]
+struct ChainStatus {
+    latest_block_height: Height,
+    latest_block_time: Timestamp,
+    catching_up: bool,
+    node_version: String,
+    /// The chain identifier reported by the node.
+    node_chain_id: ChainId,
+    /// Whether the chain identifier reported by the node differs from the configured one.
+    chain_id_mismatch: bool,
+    /// How many blocks were produced since the previous poll, in `--watch` mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    height_delta: Option<u64>,
+}
+
+impl ChainStatus {
+    fn new(chain_id: &ChainId, status: SyncStatus, previous: Option<Height>) -> Self {
+        Self {
+            height_delta: height_delta(previous, status.height),
+            latest_block_height: status.height,
+            latest_block_time: status.timestamp,
+            catching_up: status.catching_up,
+            node_version: status.node_version,
+            chain_id_mismatch: &status.network != chain_id,
+            node_chain_id: status.network,
+        }
+    }
+
+    fn summary(&self) -> String {
+        let mut line = format!(
+            "height {}{} at {}, catching up: {}, node version: {}, node chain id: {}",
+            self.latest_block_height,
+            format_delta(self.height_delta),
+            self.latest_block_time,
+            self.catching_up,
+            self.node_version,
+            self.node_chain_id,
+        );
+
+        if self.chain_id_mismatch {
+            line.push_str(" (does not match the configured chain id)");
+        }
+
+        line
+    }
+}
+
+/// One line of the `--watch` mode of the `query chain latest-height` command.
+#[derive(Debug, Serialize)]
+struct ChainLatestHeight {
+    latest_block_height: Height,
+    height_delta: Option<u64>,
+}
+
+impl ChainLatestHeight {
+    fn new(status: SyncStatus, previous: Option<Height>) -> Self {
+        Self {
+            height_delta: height_delta(previous, status.height),
+            latest_block_height: status.height,
+        }
+    }
+
+    fn summary(&self) -> String {
+        format!(
+            "height {}{}",
+            self.latest_block_height,
+            format_delta(self.height_delta)
+        )
+    }
+}
+
+/// The number of blocks between `previous` and `latest`, if both heights
+/// belong to the same revision of the chain.
+fn height_delta(previous: Option<Height>, latest: Height) -> Option<u64> {
+    previous
+        .filter(|previous| previous.revision_number() == latest.revision_number())
+        .map(|previous| {
+            latest
+                .revision_height()
+                .saturating_sub(previous.revision_height())
+        })
+}
+
+fn format_delta(delta: Option<u64>) -> String {
+    delta.map_or_else(String::new, |delta| format!(" (+{})", delta))
+}
+
+/// Polls the status of the node every `interval`, printing one line per poll,
+/// which is a JSON object when the JSON output is enabled.
+fn watch<T, F, S>(chain: &impl ChainHandle, interval: Duration, to_line: F, summary: S) -> !
+where
+    T: Serialize,
+    F: Fn(SyncStatus, Option<Height>) -> T,
+    S: Fn(&T) -> String,
+{
+    let mut previous = None;
+
+    loop {
+        match chain.query_sync_status() {
+            Ok(status) => {
+                let height = status.height;
+                let line = to_line(status, previous);
+
+                if json() {
+                    match serde_json::to_string(&line) {
+                        Ok(out) => println!("{}", out),
+                        Err(e) => error!("failed to serialize the chain status to JSON: {}", e),
+                    }
+                } else {
+                    println!("{}", summary(&line));
+                }
+
+                previous = Some(height);
+            }
+            Err(e) if json() => {
+                println!("{}", serde_json::json!({ "error": e.to_string() }));
+            }
+            Err(e) => println!("failed to query the status of the node: {}", e),
+        }
+
+        thread::sleep(interval);
+    }
+}
+
+/// The data structure that represents the arguments when invoking the `query chain status` CLI command.
+///
+/// The command has the following format:
+///
+/// `query chain status --chain <CHAIN_ID> [--watch <SECS>]`
+///
+/// The status is queried from the node via the chain runtime. With `--watch`, the status
+/// is polled every `SECS` seconds and one line is printed per poll, together with the number
+/// of blocks produced since the previous poll.
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct QueryChainStatusCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain to query"
+    )]
+    chain_id: ChainId,
+
+    #[clap(
+        long = "watch",
+        value_name = "SECS",
+        help = "Keep polling the status every SECS seconds, printing one line per poll"
+    )]
+    watch: Option<u64>,
+}
+
+impl Runnable for QueryChainStatusCmd {
+    fn run(&self) {
+        let config = app_config();
+
+        let chain = match spawn_chain_runtime(&config, &self.chain_id) {
+            Ok(chain) => chain,
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        };
+
+        if let Some(secs) = self.watch {
+            watch(
+                &chain,
+                Duration::from_secs(secs),
+                |status, previous| ChainStatus::new(&self.chain_id, status, previous),
+                ChainStatus::summary,
+            );
+        }
+
+        match chain.query_sync_status() {
+            Ok(status) => {
+                let status = ChainStatus::new(&self.chain_id, status, None);
+
+                if status.chain_id_mismatch {
+                    warn!(
+                        "the node reports chain id '{}', but it is configured for chain '{}'",
+                        status.node_chain_id, self.chain_id
+                    );
+                }
+
+                Output::success(status).exit()
+            }
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        }
+    }
+}
+
+/// The data structure that represents the arguments when invoking the `query chain latest-height` CLI command.
+///
+/// The command has the following format:
+///
+/// `query chain latest-height --chain <CHAIN_ID> [--watch <SECS>]`
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct QueryChainLatestHeightCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain to query"
+    )]
+    chain_id: ChainId,
+
+    #[clap(
+        long = "watch",
+        value_name = "SECS",
+        help = "Keep polling the latest height every SECS seconds, printing one line per poll"
+    )]
+    watch: Option<u64>,
+}
+
+impl Runnable for QueryChainLatestHeightCmd {
+    fn run(&self) {
+        let config = app_config();
+
+        let chain = match spawn_chain_runtime(&config, &self.chain_id) {
+            Ok(chain) => chain,
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        };
+
+        if let Some(secs) = self.watch {
+            watch(
+                &chain,
+                Duration::from_secs(secs),
+                ChainLatestHeight::new,
+                ChainLatestHeight::summary,
+            );
+        }
+
+        match chain.query_sync_status() {
+            Ok(status) => Output::success(status.height).exit(),
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{height_delta, QueryChainLatestHeightCmd, QueryChainStatusCmd};
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::ChainId;
+    use ibc::Height;
+
+    #[test]
+    fn test_query_chain_status() {
+        assert_eq!(
+            QueryChainStatusCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                watch: None,
+            },
+            QueryChainStatusCmd::parse_from(&["test", "--chain", "chain_id"])
+        )
+    }
+
+    #[test]
+    fn test_query_chain_status_watch() {
+        assert_eq!(
+            QueryChainStatusCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                watch: Some(5),
+            },
+            QueryChainStatusCmd::parse_from(&["test", "--chain", "chain_id", "--watch", "5"])
+        )
+    }
+
+    #[test]
+    fn test_query_chain_status_watch_no_interval() {
+        assert!(
+            QueryChainStatusCmd::try_parse_from(&["test", "--chain", "chain_id", "--watch"])
+                .is_err()
+        )
+    }
+
+    #[test]
+    fn test_query_chain_latest_height() {
+        assert_eq!(
+            QueryChainLatestHeightCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                watch: Some(1),
+            },
+            QueryChainLatestHeightCmd::parse_from(&["test", "--chain", "chain_id", "--watch", "1"])
+        )
+    }
+
+    #[test]
+    fn test_query_chain_latest_height_no_chain() {
+        assert!(QueryChainLatestHeightCmd::try_parse_from(&["test"]).is_err())
+    }
+
+    #[test]
+    fn test_height_delta() {
+        let height = |revision, height| Height::new(revision, height).unwrap();
+
+        assert_eq!(height_delta(None, height(1, 10)), None);
+        assert_eq!(height_delta(Some(height(1, 7)), height(1, 10)), Some(3));
+        assert_eq!(height_delta(Some(height(1, 10)), height(1, 10)), Some(0));
+        assert_eq!(height_delta(Some(height(0, 7)), height(1, 10)), None);
+    }
+}
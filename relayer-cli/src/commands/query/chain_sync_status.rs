@@ -0,0 +1,125 @@
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use serde::Serialize;
+
+use ibc::core::ics24_host::identifier::ChainId;
+use ibc::timestamp::Timestamp;
+use ibc::Height;
+use ibc_relayer::chain::handle::ChainHandle;
+
+use crate::cli_utils::spawn_chain_runtime;
+use crate::conclude::Output;
+use crate::error::Error;
+use crate::prelude::*;
+
+/// The result of the `query chain sync-status` command.
+#[derive(Debug, Serialize)]
+struct ChainSyncStatus {
+    latest_block_height: Height,
+    latest_block_time: Timestamp,
+    catching_up: bool,
+    /// How far the latest block lags behind the local time.
+    lag_secs: u64,
+    synced: bool,
+}
+
+/// The data structure that represents the arguments when invoking the `query chain sync-status` CLI command.
+///
+/// The command has the following format:
+///
+/// `query chain sync-status --chain <CHAIN_ID> [--max-lag <MAX_LAG>]`
+///
+/// The sync info of the node is queried via its `/status` RPC endpoint. The command
+/// exits with an error if the node is still catching up or, when `--max-lag` is given,
+/// if its latest block lags behind the local time by more than `--max-lag`.
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct QueryChainSyncStatusCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain to query"
+    )]
+    chain_id: ChainId,
+
+    #[clap(
+        long = "max-lag",
+        value_name = "MAX_LAG",
+        help = "Maximum lag of the latest block behind the local time, eg. '30s' or '5m'"
+    )]
+    max_lag: Option<humantime::Duration>,
+}
+
+impl QueryChainSyncStatusCmd {
+    fn execute(&self) -> Result<ChainSyncStatus, Error> {
+        let config = app_config();
+
+        let chain = spawn_chain_runtime(&config, &self.chain_id)?;
+
+        let status = chain.query_sync_status().map_err(Error::relayer)?;
+
+        let now = Timestamp::now();
+        let synced = status.is_synced(now, self.max_lag.as_deref().copied());
+
+        Ok(ChainSyncStatus {
+            latest_block_height: status.height,
+            latest_block_time: status.timestamp,
+            catching_up: status.catching_up,
+            lag_secs: status.lag(now).as_secs(),
+            synced,
+        })
+    }
+}
+
+impl Runnable for QueryChainSyncStatusCmd {
+    fn run(&self) {
+        match self.execute() {
+            Ok(status) if status.synced => Output::success(status).exit(),
+            Ok(status) => Output::with_error().with_result(status).exit(),
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::QueryChainSyncStatusCmd;
+
+    use core::time::Duration;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::ChainId;
+
+    #[test]
+    fn test_query_chain_sync_status() {
+        assert_eq!(
+            QueryChainSyncStatusCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                max_lag: None,
+            },
+            QueryChainSyncStatusCmd::parse_from(&["test", "--chain", "chain_id"])
+        )
+    }
+
+    #[test]
+    fn test_query_chain_sync_status_max_lag() {
+        let cmd = QueryChainSyncStatusCmd::parse_from(&[
+            "test",
+            "--chain",
+            "chain_id",
+            "--max-lag",
+            "30s",
+        ]);
+
+        assert_eq!(
+            cmd.max_lag.as_deref().copied(),
+            Some(Duration::from_secs(30))
+        );
+    }
+
+    #[test]
+    fn test_query_chain_sync_status_no_chain() {
+        assert!(QueryChainSyncStatusCmd::try_parse_from(&["test"]).is_err())
+    }
+}
@@ -6,7 +6,7 @@ use ibc::core::ics24_host::identifier::ChainId;
 use ibc::core::ics24_host::identifier::{ChannelId, PortId};
 use ibc_relayer::chain::requests::{IncludeProof, QueryChannelRequest, QueryHeight};
 
-use crate::cli_utils::spawn_chain_runtime;
+use crate::cli_utils::{parse_channel_id, parse_port_id, spawn_chain_runtime};
 use crate::conclude::{exit_with_unrecoverable_error, Output};
 use crate::prelude::*;
 use ibc::core::ics04_channel::channel::State;
@@ -26,6 +26,7 @@ pub struct QueryChannelEndCmd {
         long = "port",
         required = true,
         value_name = "PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "REQUIRED",
         help = "Identifier of the port to query"
     )]
@@ -36,6 +37,7 @@ pub struct QueryChannelEndCmd {
         visible_alias = "chan",
         required = true,
         value_name = "CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
         help_heading = "REQUIRED",
         help = "Identifier of the channel to query"
     )]
@@ -193,4 +195,39 @@ mod tests {
         ])
         .is_err())
     }
+
+    #[test]
+    fn test_query_channel_end_trailing_whitespace() {
+        assert_eq!(
+            QueryChannelEndCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                port_id: PortId::from_str("port_id").unwrap(),
+                channel_id: ChannelId::from_str("channel-07").unwrap(),
+                height: None
+            },
+            QueryChannelEndCmd::parse_from(&[
+                "test",
+                "--chain",
+                "chain_id",
+                "--port",
+                "port_id ",
+                "--channel",
+                "channel-07 "
+            ])
+        )
+    }
+
+    #[test]
+    fn test_query_channel_end_uppercase_chan() {
+        assert!(QueryChannelEndCmd::try_parse_from(&[
+            "test",
+            "--chain",
+            "chain_id",
+            "--port",
+            "port_id",
+            "--channel",
+            "Channel-07"
+        ])
+        .is_err())
+    }
 }
@@ -0,0 +1,360 @@
+use alloc::collections::BTreeMap;
+use core::time::Duration;
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use serde::Serialize;
+
+use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc::events::{IbcEvent, WithBlockDataType};
+use ibc::timestamp::Timestamp;
+use ibc::Height;
+use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::chain::requests::{QueryPacketEventRangeRequest, QueryTxRequest};
+
+use crate::cli_utils::{
+    parse_channel_id, parse_port_id, query_block_timestamp, query_height_at_time,
+    spawn_chain_runtime,
+};
+use crate::conclude::Output;
+use crate::error::Error;
+use crate::prelude::*;
+
+/// Statistics over the relay latencies of the packets sent during the queried period.
+#[derive(Debug, PartialEq, Serialize)]
+struct LatencyStats {
+    mean_ms: u64,
+    median_ms: u64,
+    p95_ms: u64,
+    p99_ms: u64,
+    max_ms: u64,
+}
+
+/// The result of the `query channel avg-relay-time` command.
+#[derive(Debug, Serialize)]
+struct RelayTimeSummary {
+    src_chain: ChainId,
+    dst_chain: ChainId,
+    port_id: PortId,
+    channel_id: ChannelId,
+    start_height: Height,
+    end_height: Height,
+    /// Number of packets sent on the channel during the period.
+    sent: usize,
+    /// Number of those packets which have been acknowledged.
+    acknowledged: usize,
+    /// Number of those packets which have timed out.
+    timed_out: usize,
+    /// Number of those packets which have neither been acknowledged nor timed out yet.
+    pending: usize,
+    /// Fraction of the completed packets which were acknowledged rather than timed out.
+    completeness: Option<f64>,
+    /// Time elapsed between the block in which a packet was sent and
+    /// the block in which its acknowledgement was processed.
+    latency: Option<LatencyStats>,
+}
+
+/// Measure the end-to-end relay latency of the packets sent on a channel during a given period.
+///
+/// The `send_packet` events emitted on the source chain are matched by sequence number with
+/// the `acknowledge_packet` and `timeout_packet` events emitted on the same chain, and the
+/// latency of each packet is the time elapsed between the blocks which include both events.
+///
+/// `query channel avg-relay-time --src-chain <SRC_CHAIN_ID> --src-port <SRC_PORT_ID>
+///     --src-channel <SRC_CHANNEL_ID> --dst-chain <DST_CHAIN_ID> [--period <PERIOD>]`
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct QueryChannelAvgRelayTimeCmd {
+    #[clap(
+        long = "src-chain",
+        required = true,
+        value_name = "SRC_CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain on which the packets are sent"
+    )]
+    src_chain_id: ChainId,
+
+    #[clap(
+        long = "src-port",
+        required = true,
+        value_name = "SRC_PORT_ID",
+        parse(try_from_str = parse_port_id),
+        help_heading = "REQUIRED",
+        help = "Identifier of the port on the source chain"
+    )]
+    src_port_id: PortId,
+
+    #[clap(
+        long = "src-channel",
+        visible_alias = "src-chan",
+        required = true,
+        value_name = "SRC_CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
+        help_heading = "REQUIRED",
+        help = "Identifier of the channel on the source chain"
+    )]
+    src_channel_id: ChannelId,
+
+    #[clap(
+        long = "dst-chain",
+        required = true,
+        value_name = "DST_CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain to which the packets are sent"
+    )]
+    dst_chain_id: ChainId,
+
+    #[clap(
+        long = "period",
+        default_value = "24h",
+        value_name = "PERIOD",
+        help = "Period, ending now, during which the packets were sent"
+    )]
+    period: humantime::Duration,
+}
+
+impl QueryChannelAvgRelayTimeCmd {
+    fn execute(&self) -> Result<RelayTimeSummary, Error> {
+        let config = app_config();
+
+        let chain = spawn_chain_runtime(&config, &self.src_chain_id)?;
+
+        let status = chain.query_application_status().map_err(Error::relayer)?;
+        let start_time = (status.timestamp - *self.period).map_err(|e| {
+            Error::cli_arg(format!("invalid period '{}': {}", self.period, e))
+        })?;
+
+        let end_height = status.height;
+        let start_height = query_height_at_time(&chain, end_height, start_time)?;
+
+        debug!(
+            "querying packet events on {} between heights {} and {}",
+            self.src_chain_id, start_height, end_height
+        );
+
+        let query_events = |event_id| {
+            chain
+                .query_txs(QueryTxRequest::PacketRange(QueryPacketEventRangeRequest {
+                    event_id,
+                    port_id: self.src_port_id.clone(),
+                    channel_id: self.src_channel_id.clone(),
+                    start_height,
+                    end_height,
+                }))
+                .map_err(Error::relayer)
+        };
+
+        let sent = heights_by_sequence(query_events(WithBlockDataType::SendPacket)?);
+        let acked = heights_by_sequence(query_events(WithBlockDataType::AckPacket)?);
+        let timed_out = heights_by_sequence(query_events(WithBlockDataType::TimeoutPacket)?);
+
+        let mut block_times: BTreeMap<Height, Timestamp> = BTreeMap::new();
+        let mut block_time = |height: Height| -> Result<Timestamp, Error> {
+            if let Some(time) = block_times.get(&height) {
+                return Ok(*time);
+            }
+
+            let time = query_block_timestamp(&chain, height)?;
+            block_times.insert(height, time);
+            Ok(time)
+        };
+
+        let mut latencies = Vec::new();
+        let (mut acknowledged, mut timed_out_count) = (0, 0);
+
+        for (sequence, send_height) in &sent {
+            if let Some(ack_height) = acked.get(sequence) {
+                acknowledged += 1;
+
+                let latency = block_time(*ack_height)?
+                    .duration_since(&block_time(*send_height)?)
+                    .unwrap_or_default();
+
+                latencies.push(latency);
+            } else if timed_out.contains_key(sequence) {
+                timed_out_count += 1;
+            }
+        }
+
+        let completed = acknowledged + timed_out_count;
+
+        Ok(RelayTimeSummary {
+            src_chain: self.src_chain_id.clone(),
+            dst_chain: self.dst_chain_id.clone(),
+            port_id: self.src_port_id.clone(),
+            channel_id: self.src_channel_id.clone(),
+            start_height,
+            end_height,
+            sent: sent.len(),
+            acknowledged,
+            timed_out: timed_out_count,
+            pending: sent.len() - completed,
+            completeness: (completed > 0).then(|| acknowledged as f64 / completed as f64),
+            latency: latency_stats(latencies),
+        })
+    }
+}
+
+impl Runnable for QueryChannelAvgRelayTimeCmd {
+    fn run(&self) {
+        match self.execute() {
+            Ok(summary) => Output::success(summary).exit(),
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        }
+    }
+}
+
+/// Indexes the heights of the given packet events by packet sequence number.
+fn heights_by_sequence(events: Vec<IbcEvent>) -> BTreeMap<Sequence, Height> {
+    events
+        .into_iter()
+        .filter_map(|event| {
+            let height = event.height();
+            let sequence = match event {
+                IbcEvent::SendPacket(ev) => ev.packet.sequence,
+                IbcEvent::AcknowledgePacket(ev) => ev.packet.sequence,
+                IbcEvent::TimeoutPacket(ev) => ev.packet.sequence,
+                _ => return None,
+            };
+            Some((sequence, height))
+        })
+        .collect()
+}
+
+/// Computes the mean, median, 95th and 99th percentiles, and maximum of the given latencies.
+/// Percentiles are computed with the nearest-rank method.
+fn latency_stats(mut samples: Vec<Duration>) -> Option<LatencyStats> {
+    if samples.is_empty() {
+        return None;
+    }
+
+    samples.sort();
+
+    let len = samples.len();
+    let millis = |d: Duration| d.as_millis() as u64;
+    let percentile = |p: usize| samples[((p * len + 99) / 100).max(1) - 1];
+
+    let median = if len % 2 == 0 {
+        (samples[len / 2 - 1] + samples[len / 2]) / 2
+    } else {
+        samples[len / 2]
+    };
+
+    Some(LatencyStats {
+        mean_ms: millis(samples.iter().sum::<Duration>() / len as u32),
+        median_ms: millis(median),
+        p95_ms: millis(percentile(95)),
+        p99_ms: millis(percentile(99)),
+        max_ms: millis(samples[len - 1]),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{latency_stats, LatencyStats, QueryChannelAvgRelayTimeCmd};
+
+    use core::time::Duration;
+    use std::str::FromStr;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+
+    #[test]
+    fn test_query_channel_avg_relay_time() {
+        assert_eq!(
+            QueryChannelAvgRelayTimeCmd {
+                src_chain_id: ChainId::from_string("chain_a"),
+                src_port_id: PortId::from_str("transfer").unwrap(),
+                src_channel_id: ChannelId::from_str("channel-0").unwrap(),
+                dst_chain_id: ChainId::from_string("chain_b"),
+                period: "2h".parse().unwrap(),
+            },
+            QueryChannelAvgRelayTimeCmd::parse_from(&[
+                "test",
+                "--src-chain",
+                "chain_a",
+                "--src-port",
+                "transfer",
+                "--src-channel",
+                "channel-0",
+                "--dst-chain",
+                "chain_b",
+                "--period",
+                "2h"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_query_channel_avg_relay_time_default_period() {
+        assert_eq!(
+            QueryChannelAvgRelayTimeCmd {
+                src_chain_id: ChainId::from_string("chain_a"),
+                src_port_id: PortId::from_str("transfer").unwrap(),
+                src_channel_id: ChannelId::from_str("channel-0").unwrap(),
+                dst_chain_id: ChainId::from_string("chain_b"),
+                period: "24h".parse().unwrap(),
+            },
+            QueryChannelAvgRelayTimeCmd::parse_from(&[
+                "test",
+                "--src-chain",
+                "chain_a",
+                "--src-port",
+                "transfer",
+                "--src-chan",
+                "channel-0",
+                "--dst-chain",
+                "chain_b"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_query_channel_avg_relay_time_no_dst_chain() {
+        assert!(QueryChannelAvgRelayTimeCmd::try_parse_from(&[
+            "test",
+            "--src-chain",
+            "chain_a",
+            "--src-port",
+            "transfer",
+            "--src-channel",
+            "channel-0"
+        ])
+        .is_err())
+    }
+
+    #[test]
+    fn test_latency_stats_empty() {
+        assert_eq!(latency_stats(vec![]), None);
+    }
+
+    #[test]
+    fn test_latency_stats() {
+        let samples = (1..=100).rev().map(Duration::from_secs).collect();
+
+        assert_eq!(
+            latency_stats(samples),
+            Some(LatencyStats {
+                mean_ms: 50_500,
+                median_ms: 50_500,
+                p95_ms: 95_000,
+                p99_ms: 99_000,
+                max_ms: 100_000,
+            })
+        );
+    }
+
+    #[test]
+    fn test_latency_stats_single_sample() {
+        assert_eq!(
+            latency_stats(vec![Duration::from_millis(1234)]),
+            Some(LatencyStats {
+                mean_ms: 1234,
+                median_ms: 1234,
+                p95_ms: 1234,
+                p99_ms: 1234,
+                max_ms: 1234,
+            })
+        );
+    }
+}
@@ -6,7 +6,7 @@ use ibc_relayer::chain::handle::ChainHandle;
 use ibc_relayer::chain::requests::QueryChannelClientStateRequest;
 
 use crate::application::app_config;
-use crate::cli_utils::spawn_chain_runtime;
+use crate::cli_utils::{parse_channel_id, parse_port_id, spawn_chain_runtime};
 use crate::conclude::{exit_with_unrecoverable_error, Output};
 
 /// The data structure that represents the arguments when invoking the `query channel client` CLI command.
@@ -29,6 +29,7 @@ pub struct QueryChannelClientCmd {
         long = "port",
         required = true,
         value_name = "PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "REQUIRED",
         help = "Identifier of the port to query"
     )]
@@ -39,6 +40,7 @@ pub struct QueryChannelClientCmd {
         visible_alias = "chan",
         required = true,
         value_name = "CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
         help_heading = "REQUIRED",
         help = "Identifier of the channel to query"
     )]
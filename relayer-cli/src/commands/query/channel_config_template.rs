@@ -0,0 +1,513 @@
+use core::time::Duration;
+use std::path::{Path, PathBuf};
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use serde::{Deserialize, Serialize};
+
+use ibc::core::ics02_client::client_state::ClientState;
+use ibc::core::ics04_channel::Version;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc_relayer::chain::counterparty::channel_connection_client;
+use ibc_relayer::config::filter::{
+    ChannelFilter, ChannelFilters, FilterPattern, PacketFilter, RelayMode,
+};
+use ibc_relayer::config::{self, ChainConfig};
+
+use crate::cli_utils::{
+    find_chain_config, parse_channel_id, parse_port_id, resolve_chain_id, spawn_chain_runtime,
+};
+use crate::conclude::{json, Output};
+use crate::config::{validate_config, Diagnostic};
+use crate::error::Error;
+use crate::prelude::*;
+
+/// Number of blocks on each chain the recommended packet timeout leaves for relaying a packet.
+const TIMEOUT_MARGIN_BLOCKS: u32 = 100;
+
+/// The version of a channel which goes through the ICS-29 fee middleware.
+#[derive(Debug, Serialize, Deserialize)]
+struct FeeVersion {
+    fee_version: String,
+    app_version: String,
+}
+
+impl FeeVersion {
+    fn parse(version: &Version) -> Option<Self> {
+        serde_json::from_str(&version.to_string()).ok()
+    }
+}
+
+/// Recommended timeouts for the packets sent on a channel.
+#[derive(Debug, PartialEq, Serialize)]
+struct RecommendedTimeout {
+    seconds: u64,
+    /// The timeout as a number of blocks of the counterparty chain.
+    height_offset: u64,
+}
+
+impl RecommendedTimeout {
+    /// Leaves time for the connection delay, the clock drift of both chains,
+    /// and [`TIMEOUT_MARGIN_BLOCKS`] blocks on each chain.
+    fn new(delay_period: Duration, chain: &ChainConfig, counterparty: &ChainConfig) -> Self {
+        let timeout = delay_period
+            + chain.clock_drift
+            + counterparty.clock_drift
+            + (chain.max_block_time + counterparty.max_block_time) * TIMEOUT_MARGIN_BLOCKS;
+
+        let block_time = counterparty.max_block_time.as_secs().max(1);
+
+        Self {
+            seconds: timeout.as_secs(),
+            height_offset: (timeout.as_secs() + block_time - 1) / block_time,
+        }
+    }
+}
+
+/// The configuration of a channel for the packet filter of a chain.
+#[derive(Debug, Serialize)]
+struct ChannelConfigTemplate {
+    chain_id: ChainId,
+    port_id: PortId,
+    channel_id: ChannelId,
+    counterparty_chain_id: ChainId,
+    counterparty_port_id: PortId,
+    counterparty_channel_id: Option<ChannelId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fee: Option<FeeVersion>,
+    timeout: RecommendedTimeout,
+    /// The packet filter of the chain once the channel is added to it.
+    packet_filter: PacketFilter,
+    /// Why the channel cannot be added to the packet filter of the chain, if it cannot.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    conflict: Option<String>,
+}
+
+impl ChannelConfigTemplate {
+    /// Renders the template as a TOML snippet for the `[[chains]]` block of the chain.
+    fn snippet(&self) -> String {
+        let counterparty_channel_id = self
+            .counterparty_channel_id
+            .as_ref()
+            .map_or_else(|| "<none>".to_string(), ToString::to_string);
+
+        let mut lines = vec![format!(
+            "# Channel {}/{} on chain '{}', with counterparty {}/{} on chain '{}'",
+            self.port_id,
+            self.channel_id,
+            self.chain_id,
+            self.counterparty_port_id,
+            counterparty_channel_id,
+            self.counterparty_chain_id
+        )];
+
+        lines.push(match &self.fee {
+            Some(fee) => format!(
+                "# ICS-29 fee middleware is enabled on this channel, with fee version '{}' and app version '{}'",
+                fee.fee_version, fee.app_version
+            ),
+            None => "# ICS-29 fee middleware is not enabled on this channel".to_string(),
+        });
+
+        lines.push(format!(
+            "# Recommended packet timeouts: {} seconds, or a height offset of {} blocks on chain '{}'",
+            self.timeout.seconds, self.timeout.height_offset, self.counterparty_chain_id
+        ));
+
+        if let Some(conflict) = &self.conflict {
+            lines.push(format!("# WARNING: {}", conflict));
+        }
+
+        lines.push("[chains.packet_filter]".to_string());
+
+        if let PacketFilter::Allow(filters) = &self.packet_filter {
+            lines.push("policy = 'allow'".to_string());
+            lines.push("list = [".to_string());
+            for filter in filters.iter() {
+                if filter.relay == RelayMode::All {
+                    lines.push(format!("  ['{}', '{}'],", filter.port, filter.channel));
+                } else {
+                    lines.push(format!(
+                        "  {{ port = '{}', channel = '{}', relay = '{}' }},",
+                        filter.port, filter.channel, filter.relay
+                    ));
+                }
+            }
+            lines.push("]".to_string());
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// Adds the channel to the allow list of a packet filter.
+///
+/// Returns the resulting allow list, along with the reason why the channel cannot
+/// be added to the filter, if the filter already relays the channel or denies it.
+fn add_to_filter(
+    filter: &PacketFilter,
+    port_id: &PortId,
+    channel_id: &ChannelId,
+) -> (PacketFilter, Option<String>) {
+    let only_channel = || {
+        PacketFilter::Allow(ChannelFilters::new(vec![(
+            FilterPattern::Exact(port_id.clone()),
+            FilterPattern::Exact(channel_id.clone()),
+        )]))
+    };
+
+    match filter {
+        PacketFilter::Allow(filters) if filters.matches((port_id, channel_id)) => (
+            filter.clone(),
+            Some("the channel is already allowed by the packet filter of the chain".to_string()),
+        ),
+        PacketFilter::Allow(filters) => {
+            let mut filters = filters.iter().cloned().collect::<Vec<_>>();
+            filters.push(ChannelFilter::new(
+                FilterPattern::Exact(port_id.clone()),
+                FilterPattern::Exact(channel_id.clone()),
+            ));
+
+            (PacketFilter::Allow(ChannelFilters::from_filters(filters)), None)
+        }
+        PacketFilter::Deny(filters) if filters.matches((port_id, channel_id)) => (
+            only_channel(),
+            Some("the channel is denied by the packet filter of the chain".to_string()),
+        ),
+        PacketFilter::Deny(_) => (
+            only_channel(),
+            Some(
+                "the channel is already relayed, as the packet filter of the chain uses the deny policy"
+                    .to_string(),
+            ),
+        ),
+        PacketFilter::AllowAll => (
+            only_channel(),
+            Some(
+                "the channel is already relayed, as the chain has no packet filter; \
+                adding this one would stop relaying on all other channels"
+                    .to_string(),
+            ),
+        ),
+    }
+}
+
+/// Generate the configuration of a channel for the `packet_filter` section of the
+/// configuration of a chain, e.g. to start relaying on a newly opened channel.
+///
+/// The snippet is the allow list of the chain with the channel added to it, preceded
+/// by comments describing the channel, whether it goes through the ICS-29 fee middleware,
+/// and the packet timeouts recommended for it. The recommended timeouts leave time for the
+/// connection delay, the `clock_drift` of both chains and 100 blocks of `max_block_time`
+/// on each chain, using the configuration of the chain if the counterparty is not configured.
+///
+/// With `--append-to-config`, the channel is instead added to the packet filter of the chain in
+/// the given configuration file, which is validated before being written back. The file is
+/// left untouched if the channel is already relayed or denied by the packet filter of the chain.
+/// Note that the comments of the file are not preserved.
+///
+/// `query channel config-template --chain-a <CHAIN_ID> --port-a <PORT_ID> --channel-a <CHANNEL_ID> [--append-to-config <PATH>]`
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct QueryChannelConfigTemplateCmd {
+    #[clap(
+        long = "chain-a",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain of the channel"
+    )]
+    chain_a_id: ChainId,
+
+    #[clap(
+        long = "port-a",
+        required = true,
+        value_name = "PORT_ID",
+        parse(try_from_str = parse_port_id),
+        help_heading = "REQUIRED",
+        help = "Identifier of the port of the channel"
+    )]
+    port_a: PortId,
+
+    #[clap(
+        long = "channel-a",
+        alias = "chan-a",
+        required = true,
+        value_name = "CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
+        help_heading = "REQUIRED",
+        help = "Identifier of the channel"
+    )]
+    channel_a: ChannelId,
+
+    #[clap(
+        long = "append-to-config",
+        value_name = "PATH",
+        help = "Add the channel to the packet filter of the chain in the given configuration file"
+    )]
+    append_to_config: Option<PathBuf>,
+}
+
+impl QueryChannelConfigTemplateCmd {
+    fn execute(&self) -> Result<ChannelConfigTemplate, Error> {
+        let config = app_config();
+
+        let chain_config = find_chain_config(&config, &self.chain_a_id)?;
+
+        let chain = spawn_chain_runtime(&config, &chain_config.id)?;
+
+        let channel_connection_client =
+            channel_connection_client(&chain, &self.port_a, &self.channel_a)
+                .map_err(Error::supervisor)?;
+
+        let channel_end = &channel_connection_client.channel.channel_end;
+        let counterparty_chain_id = channel_connection_client.client.client_state.chain_id();
+        let counterparty_config = config
+            .find_chain(&counterparty_chain_id)
+            .unwrap_or(chain_config);
+
+        let timeout = RecommendedTimeout::new(
+            channel_connection_client
+                .connection
+                .connection_end
+                .delay_period(),
+            chain_config,
+            counterparty_config,
+        );
+
+        let (packet_filter, conflict) =
+            add_to_filter(&chain_config.packet_filter, &self.port_a, &self.channel_a);
+
+        Ok(ChannelConfigTemplate {
+            chain_id: chain_config.id.clone(),
+            port_id: self.port_a.clone(),
+            channel_id: self.channel_a.clone(),
+            counterparty_chain_id,
+            counterparty_port_id: channel_end.counterparty().port_id().clone(),
+            counterparty_channel_id: channel_end.counterparty().channel_id().cloned(),
+            fee: FeeVersion::parse(channel_end.version()),
+            timeout,
+            packet_filter,
+            conflict,
+        })
+    }
+
+    /// Adds the channel to the packet filter of the chain in the configuration file at `path`.
+    fn append_to_config(&self, path: &Path) -> Result<(), Error> {
+        let mut file_config = config::load(path).map_err(|e| {
+            Error::cli_arg(format!(
+                "could not load config file '{}': {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        let chain_id = resolve_chain_id(&file_config, &self.chain_a_id)?;
+        let chain_config = file_config
+            .find_chain_mut(&chain_id)
+            .ok_or_else(|| Error::missing_chain_config(chain_id.clone()))?;
+
+        let (packet_filter, conflict) =
+            add_to_filter(&chain_config.packet_filter, &self.port_a, &self.channel_a);
+
+        if let Some(conflict) = conflict {
+            return Err(Error::cli_arg(format!(
+                "cannot add channel {}/{} to config file '{}': {}",
+                self.port_a,
+                self.channel_a,
+                path.display(),
+                conflict
+            )));
+        }
+
+        chain_config.packet_filter = packet_filter;
+
+        if let Err(Diagnostic::Error(e)) = validate_config(&file_config) {
+            return Err(Error::cli_arg(format!(
+                "config file '{}' would be invalid: {}",
+                path.display(),
+                e
+            )));
+        }
+
+        config::store(&file_config, path).map_err(|e| {
+            Error::cli_arg(format!(
+                "could not write config file '{}': {}",
+                path.display(),
+                e
+            ))
+        })
+    }
+}
+
+impl Runnable for QueryChannelConfigTemplateCmd {
+    fn run(&self) {
+        let template = match self.execute() {
+            Ok(template) => template,
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        };
+
+        if let Some(path) = &self.append_to_config {
+            match self.append_to_config(path) {
+                Ok(()) => Output::success_msg(format!(
+                    "added channel {}/{} to the packet filter of chain '{}' in '{}'",
+                    template.port_id,
+                    template.channel_id,
+                    template.chain_id,
+                    path.display()
+                ))
+                .exit(),
+                Err(e) => Output::error(format!("{}", e)).exit(),
+            }
+        }
+
+        if json() {
+            Output::success(template).exit()
+        }
+
+        Output::success_msg(template.snippet()).exit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{add_to_filter, FeeVersion, QueryChannelConfigTemplateCmd, RecommendedTimeout};
+
+    use core::str::FromStr;
+    use core::time::Duration;
+    use std::path::PathBuf;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics04_channel::Version;
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+    use ibc_relayer::config::filter::PacketFilter;
+    use ibc_relayer::config::load;
+
+    fn filter(toml: &str) -> PacketFilter {
+        toml::from_str(toml).unwrap()
+    }
+
+    fn allowed(filter: &PacketFilter) -> Vec<String> {
+        match filter {
+            PacketFilter::Allow(filters) => filters
+                .iter()
+                .map(|filter| format!("{}/{}", filter.port, filter.channel))
+                .collect(),
+            _ => panic!("expected an allow list, got {:?}", filter),
+        }
+    }
+
+    #[test]
+    fn test_query_channel_config_template() {
+        assert_eq!(
+            QueryChannelConfigTemplateCmd {
+                chain_a_id: ChainId::from_string("chain_id"),
+                port_a: PortId::from_str("port_id").unwrap(),
+                channel_a: ChannelId::from_str("channel-07").unwrap(),
+                append_to_config: None,
+            },
+            QueryChannelConfigTemplateCmd::parse_from(&[
+                "test",
+                "--chain-a",
+                "chain_id",
+                "--port-a",
+                "port_id",
+                "--channel-a",
+                "channel-07"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_query_channel_config_template_append() {
+        let cmd = QueryChannelConfigTemplateCmd::parse_from(&[
+            "test",
+            "--chain-a",
+            "chain_id",
+            "--port-a",
+            "port_id",
+            "--channel-a",
+            "channel-07",
+            "--append-to-config",
+            "config.toml",
+        ]);
+
+        assert_eq!(cmd.append_to_config, Some(PathBuf::from("config.toml")));
+    }
+
+    #[test]
+    fn test_query_channel_config_template_no_channel() {
+        assert!(QueryChannelConfigTemplateCmd::try_parse_from(&[
+            "test",
+            "--chain-a",
+            "chain_id",
+            "--port-a",
+            "port_id"
+        ])
+        .is_err())
+    }
+
+    #[test]
+    fn test_fee_version() {
+        let fee = FeeVersion::parse(&Version::new(
+            r#"{"fee_version":"ics29-1","app_version":"ics20-1"}"#.to_string(),
+        ))
+        .unwrap();
+
+        assert_eq!(fee.fee_version, "ics29-1");
+        assert_eq!(fee.app_version, "ics20-1");
+
+        assert!(FeeVersion::parse(&Version::ics20()).is_none());
+    }
+
+    #[test]
+    fn test_recommended_timeout() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../relayer/tests/config/fixtures/relayer_conf_example.toml"
+        );
+        let config = load(path).unwrap();
+
+        let mut chain = config.chains[0].clone();
+        chain.clock_drift = Duration::from_secs(5);
+        chain.max_block_time = Duration::from_secs(10);
+
+        let mut counterparty = config.chains[1].clone();
+        counterparty.clock_drift = Duration::from_secs(5);
+        counterparty.max_block_time = Duration::from_secs(7);
+
+        // 60 + 5 + 5 + (10 + 7) * 100 = 1770 seconds, i.e. 253 blocks of 7 seconds
+        assert_eq!(
+            RecommendedTimeout::new(Duration::from_secs(60), &chain, &counterparty),
+            RecommendedTimeout {
+                seconds: 1770,
+                height_offset: 253,
+            }
+        );
+    }
+
+    #[test]
+    fn test_add_to_filter() {
+        let port_id = PortId::transfer();
+        let channel_id = ChannelId::new(3);
+
+        let allow = filter("policy = 'allow'\nlist = [['ica*', '*'], ['transfer', 'channel-0']]");
+        let (updated, conflict) = add_to_filter(&allow, &port_id, &channel_id);
+        assert!(conflict.is_none());
+        assert_eq!(
+            allowed(&updated),
+            vec!["ica*/*", "transfer/channel-0", "transfer/channel-3"]
+        );
+
+        let (updated, conflict) = add_to_filter(&updated, &port_id, &channel_id);
+        assert!(conflict.unwrap().contains("already allowed"));
+        assert_eq!(allowed(&updated).len(), 3);
+
+        let deny = filter("policy = 'deny'\nlist = [['transfer', 'channel-*']]");
+        let (updated, conflict) = add_to_filter(&deny, &port_id, &channel_id);
+        assert!(conflict.unwrap().contains("denied"));
+        assert_eq!(allowed(&updated), vec!["transfer/channel-3"]);
+
+        let (_, conflict) = add_to_filter(&PacketFilter::AllowAll, &port_id, &channel_id);
+        assert!(conflict.unwrap().contains("already relayed"));
+    }
+}
@@ -0,0 +1,337 @@
+use core::time::Duration;
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use serde::Serialize;
+
+use ibc::core::ics04_channel::channel::State;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc::timestamp::Timestamp;
+use ibc::Height;
+use ibc_relayer::chain::endpoint::ChannelOpenTx;
+use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::chain::requests::{PageRequest, QueryChannelOpenTxRequest, QueryChannelsRequest};
+
+use crate::cli_utils::{
+    parse_channel_id, parse_port_id, query_block_timestamp, spawn_chain_runtime,
+};
+use crate::conclude::{json, Output};
+use crate::error::Error;
+use crate::prelude::*;
+
+/// When a channel end was created, i.e. when its opening handshake completed.
+#[derive(Debug, Serialize)]
+struct ChannelCreateTime {
+    port_id: PortId,
+    channel_id: ChannelId,
+    height: Height,
+    timestamp: Timestamp,
+    /// The event which completed the handshake, either `channel_open_confirm`,
+    /// or `channel_open_ack` on the end which initiated the handshake.
+    event: &'static str,
+    tx_hash: String,
+    /// The account which submitted the transaction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sender: Option<String>,
+    age_secs: u64,
+}
+
+impl ChannelCreateTime {
+    fn new(
+        port_id: PortId,
+        channel_id: ChannelId,
+        tx: ChannelOpenTx,
+        timestamp: Timestamp,
+        now: Timestamp,
+    ) -> Self {
+        Self {
+            port_id,
+            channel_id,
+            height: tx.height,
+            timestamp,
+            event: tx.event_type.as_str(),
+            tx_hash: tx.tx_hash,
+            sender: tx.sender,
+            age_secs: now.duration_since(&timestamp).unwrap_or_default().as_secs(),
+        }
+    }
+
+    fn age(&self) -> Duration {
+        Duration::from_secs(self.age_secs)
+    }
+}
+
+/// Keeps the channels older than `older_than`, if given, and sorts them from the oldest.
+fn oldest_first(
+    mut channels: Vec<ChannelCreateTime>,
+    older_than: Option<Duration>,
+) -> Vec<ChannelCreateTime> {
+    if let Some(older_than) = older_than {
+        channels.retain(|channel| channel.age() > older_than);
+    }
+
+    channels.sort_by(|a, b| b.age_secs.cmp(&a.age_secs));
+    channels
+}
+
+/// Show when channels were created, and how old they are.
+///
+/// The creation of a channel end is the transaction which completed its opening
+/// handshake, found by searching the transactions of the chain for its
+/// `channel_open_confirm` event or, if it initiated the handshake, its
+/// `channel_open_ack` event. Channels whose transaction was pruned by the node
+/// cannot be dated.
+///
+/// `query channel create-time --chain <CHAIN_ID> (--port <PORT_ID> --channel <CHANNEL_ID> | --all-channels)
+///     [--older-than <DURATION>]`
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct QueryChannelCreateTimeCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain to query"
+    )]
+    chain_id: ChainId,
+
+    #[clap(
+        long = "port",
+        required = true,
+        value_name = "PORT_ID",
+        group = "channels",
+        requires = "channel-id",
+        parse(try_from_str = parse_port_id),
+        help_heading = "REQUIRED",
+        help = "Identifier of the port to query"
+    )]
+    port_id: Option<PortId>,
+
+    #[clap(
+        long = "channel",
+        visible_alias = "chan",
+        value_name = "CHANNEL_ID",
+        requires = "port-id",
+        parse(try_from_str = parse_channel_id),
+        help_heading = "REQUIRED",
+        help = "Identifier of the channel to query"
+    )]
+    channel_id: Option<ChannelId>,
+
+    #[clap(
+        long = "all-channels",
+        required = true,
+        group = "channels",
+        help_heading = "REQUIRED",
+        help = "Query all the open channels on the chain"
+    )]
+    all_channels: bool,
+
+    #[clap(
+        long = "older-than",
+        value_name = "DURATION",
+        help = "Only show the channels created longer ago than this, eg. '30days' or '1y'"
+    )]
+    older_than: Option<humantime::Duration>,
+}
+
+impl QueryChannelCreateTimeCmd {
+    fn execute(&self) -> Result<Vec<ChannelCreateTime>, Error> {
+        let config = app_config();
+
+        let chain = spawn_chain_runtime(&config, &self.chain_id)?;
+
+        let channels = match (&self.port_id, &self.channel_id) {
+            (Some(port_id), Some(channel_id)) => vec![(port_id.clone(), channel_id.clone())],
+            _ => chain
+                .query_channels(QueryChannelsRequest {
+                    pagination: Some(PageRequest::all()),
+                })
+                .map_err(Error::relayer)?
+                .into_iter()
+                .filter(|channel| channel.channel_end.state_matches(&State::Open))
+                .map(|channel| (channel.port_id, channel.channel_id))
+                .collect(),
+        };
+
+        let now = Timestamp::now();
+        let mut create_times = Vec::with_capacity(channels.len());
+
+        for (port_id, channel_id) in channels {
+            let open_tx = chain
+                .query_channel_open_tx(QueryChannelOpenTxRequest {
+                    port_id: port_id.clone(),
+                    channel_id: channel_id.clone(),
+                    state: State::Open,
+                })
+                .map_err(Error::relayer)
+                .and_then(|tx| {
+                    tx.ok_or_else(|| {
+                        Error::cli_arg(format!(
+                            "found no transaction completing the opening handshake of channel \
+                             {}/{}, the handshake is either incomplete or was pruned by the node",
+                            port_id, channel_id
+                        ))
+                    })
+                })
+                .and_then(|tx| Ok((query_block_timestamp(&chain, tx.height)?, tx)));
+
+            let (timestamp, tx) = match open_tx {
+                Ok(open_tx) => open_tx,
+                // Only fail when querying a single channel, and skip the others
+                Err(e) if !self.all_channels => return Err(e),
+                Err(e) => {
+                    warn!("skipping channel {}/{}: {}", port_id, channel_id, e);
+                    continue;
+                }
+            };
+
+            create_times.push(ChannelCreateTime::new(
+                port_id, channel_id, tx, timestamp, now,
+            ));
+        }
+
+        Ok(oldest_first(
+            create_times,
+            self.older_than.as_deref().copied(),
+        ))
+    }
+}
+
+impl Runnable for QueryChannelCreateTimeCmd {
+    fn run(&self) {
+        let create_times = match self.execute() {
+            Ok(create_times) => create_times,
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        };
+
+        if json() {
+            Output::success(create_times).exit()
+        }
+
+        let lines = create_times
+            .iter()
+            .map(|channel| {
+                format!(
+                    "{}/{}: created {} ago at height {} ({}) by {} in tx {}",
+                    channel.port_id,
+                    channel.channel_id,
+                    humantime::format_duration(channel.age()),
+                    channel.height,
+                    channel.timestamp,
+                    channel.sender.as_deref().unwrap_or("<unknown>"),
+                    channel.tx_hash,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        Output::success_msg(lines.join("\n")).exit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{oldest_first, ChannelCreateTime, QueryChannelCreateTimeCmd};
+
+    use core::str::FromStr;
+    use core::time::Duration;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+    use ibc::timestamp::Timestamp;
+    use ibc::Height;
+
+    fn create_time(channel: &str, age_secs: u64) -> ChannelCreateTime {
+        ChannelCreateTime {
+            port_id: PortId::transfer(),
+            channel_id: ChannelId::from_str(channel).unwrap(),
+            height: Height::new(0, 10).unwrap(),
+            timestamp: Timestamp::none(),
+            event: "channel_open_confirm",
+            tx_hash: "hash".to_string(),
+            sender: None,
+            age_secs,
+        }
+    }
+
+    #[test]
+    fn test_query_channel_create_time() {
+        assert_eq!(
+            QueryChannelCreateTimeCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                port_id: Some(PortId::from_str("transfer").unwrap()),
+                channel_id: Some(ChannelId::from_str("channel-0").unwrap()),
+                all_channels: false,
+                older_than: None,
+            },
+            QueryChannelCreateTimeCmd::parse_from(&[
+                "test",
+                "--chain",
+                "chain_id",
+                "--port",
+                "transfer",
+                "--chan",
+                "channel-0"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_query_channel_create_time_all_channels() {
+        let cmd = QueryChannelCreateTimeCmd::parse_from(&[
+            "test",
+            "--chain",
+            "chain_id",
+            "--all-channels",
+            "--older-than",
+            "30days",
+        ]);
+
+        assert!(cmd.all_channels);
+        assert_eq!(cmd.port_id, None);
+        assert_eq!(
+            cmd.older_than.as_deref().copied(),
+            Some(Duration::from_secs(30 * 24 * 3600))
+        );
+    }
+
+    #[test]
+    fn test_query_channel_create_time_channel_or_all() {
+        assert!(
+            QueryChannelCreateTimeCmd::try_parse_from(&["test", "--chain", "chain_id"]).is_err()
+        );
+
+        assert!(QueryChannelCreateTimeCmd::try_parse_from(&[
+            "test", "--chain", "chain_id", "--port", "transfer"
+        ])
+        .is_err());
+
+        assert!(QueryChannelCreateTimeCmd::try_parse_from(&[
+            "test",
+            "--chain",
+            "chain_id",
+            "--port",
+            "transfer",
+            "--channel",
+            "channel-0",
+            "--all-channels"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_oldest_first() {
+        let channels = vec![
+            create_time("channel-0", 10),
+            create_time("channel-1", 3000),
+            create_time("channel-2", 200),
+        ];
+
+        let sorted = oldest_first(channels, Some(Duration::from_secs(100)))
+            .into_iter()
+            .map(|channel| channel.channel_id.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(sorted, vec!["channel-1", "channel-2"]);
+    }
+}
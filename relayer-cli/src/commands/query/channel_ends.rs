@@ -14,6 +14,7 @@ use ibc_relayer::chain::requests::{
 };
 use ibc_relayer::registry::Registry;
 
+use crate::cli_utils::{parse_channel_id, parse_port_id, resolve_chain_id};
 use crate::conclude::{exit_with_unrecoverable_error, Output};
 use crate::prelude::*;
 
@@ -32,6 +33,7 @@ pub struct QueryChannelEndsCmd {
         long = "port",
         required = true,
         value_name = "PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "REQUIRED",
         help = "Identifier of the port to query"
     )]
@@ -42,6 +44,7 @@ pub struct QueryChannelEndsCmd {
         visible_alias = "chan",
         required = true,
         value_name = "CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
         help_heading = "REQUIRED",
         help = "Identifier of the channel to query"
     )]
@@ -92,14 +95,15 @@ fn do_run<Chain: ChainHandle>(cmd: &QueryChannelEndsCmd) -> Result<(), Box<dyn s
     let config = app_config();
 
     let QueryChannelEndsCmd {
-        chain_id,
         port_id,
         channel_id,
         ..
     } = cmd;
 
+    let chain_id = resolve_chain_id(&config, &cmd.chain_id)?;
+
     let mut registry = <Registry<Chain>>::new((*config).clone());
-    let chain = registry.get_or_spawn(chain_id)?;
+    let chain = registry.get_or_spawn(&chain_id)?;
 
     let chain_height = match cmd.height {
         Some(height) => {
@@ -221,7 +225,7 @@ fn do_run<Chain: ChainHandle>(cmd: &QueryChannelEndsCmd) -> Result<(), Box<dyn s
         Output::success(res).exit();
     } else {
         let res = ChannelEndsSummary {
-            chain_id: chain_id.clone(),
+            chain_id,
             client_id,
             connection_id,
             channel_id: channel_id.clone(),
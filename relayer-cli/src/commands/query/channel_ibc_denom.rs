@@ -0,0 +1,293 @@
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use serde::Serialize;
+
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::denom::DenomTrace;
+
+use crate::cli_utils::{parse_channel_id, parse_port_id, spawn_chain_runtime};
+use crate::conclude::{json, Output};
+use crate::error::Error;
+use crate::prelude::*;
+
+/// The result of the `query channel ibc-denom` command.
+#[derive(Debug, Serialize)]
+struct IbcDenom {
+    /// The full denomination trace, ie. `{port}/{channel}/.../{base_denom}`.
+    trace: String,
+    /// The denomination of the token on the chain, ie. `ibc/{hash}`.
+    denom: String,
+    /// Whether the chain knows of a denomination trace with that hash,
+    /// if `--verify-on-chain` was given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    verified: Option<bool>,
+}
+
+/// The data structure that represents the arguments when invoking the `query channel ibc-denom` CLI command.
+///
+/// The command has the following format:
+///
+/// `query channel ibc-denom --chain <CHAIN_ID> --port <PORT_ID> --channel <CHANNEL_ID> --base-denom <BASE_DENOM>
+///     [--multi-hop] [--verify-on-chain]`
+///
+/// If successful the denomination trace of the token and its `ibc/{hash}` denomination
+/// on the given chain are displayed.
+///
+/// With `--multi-hop`, the `--port` and `--channel` options can be given several times,
+/// in the order in which the hops appear in the denomination trace, ie. starting with
+/// the channel end on the chain holding the token.
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct QueryChannelIbcDenomCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain holding the token"
+    )]
+    chain_id: ChainId,
+
+    #[clap(
+        long = "port",
+        required = true,
+        multiple_occurrences = true,
+        value_name = "PORT_ID",
+        parse(try_from_str = parse_port_id),
+        help_heading = "REQUIRED",
+        help = "Identifier of the port through which the token was received"
+    )]
+    port_ids: Vec<PortId>,
+
+    #[clap(
+        long = "channel",
+        visible_alias = "chan",
+        required = true,
+        multiple_occurrences = true,
+        value_name = "CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
+        help_heading = "REQUIRED",
+        help = "Identifier of the channel through which the token was received"
+    )]
+    channel_ids: Vec<ChannelId>,
+
+    #[clap(
+        long = "base-denom",
+        required = true,
+        value_name = "BASE_DENOM",
+        help_heading = "REQUIRED",
+        help = "Denomination of the token on its source chain"
+    )]
+    base_denom: String,
+
+    #[clap(
+        long = "multi-hop",
+        help = "Accept multiple --port/--channel pairs, forming a multi-hop path"
+    )]
+    multi_hop: bool,
+
+    #[clap(
+        long = "verify-on-chain",
+        help = "Check that the chain knows of the computed denomination trace"
+    )]
+    verify_on_chain: bool,
+}
+
+impl QueryChannelIbcDenomCmd {
+    fn denom_trace(&self) -> Result<DenomTrace, Error> {
+        if self.port_ids.len() != self.channel_ids.len() {
+            return Err(Error::cli_arg(format!(
+                "the number of ports ({}) and channels ({}) must be equal",
+                self.port_ids.len(),
+                self.channel_ids.len()
+            )));
+        }
+
+        if !self.multi_hop && self.port_ids.len() > 1 {
+            return Err(Error::cli_arg(
+                "multiple --port/--channel pairs require the --multi-hop flag".to_string(),
+            ));
+        }
+
+        let path = self
+            .port_ids
+            .iter()
+            .zip(self.channel_ids.iter())
+            .map(|(port_id, channel_id)| format!("{}/{}", port_id, channel_id))
+            .collect::<Vec<_>>()
+            .join("/");
+
+        Ok(DenomTrace {
+            path,
+            base_denom: self.base_denom.clone(),
+        })
+    }
+
+    fn execute(&self) -> Result<IbcDenom, Error> {
+        let trace = self.denom_trace()?;
+
+        let verified = if self.verify_on_chain {
+            let config = app_config();
+            let chain = spawn_chain_runtime(&config, &self.chain_id)?;
+
+            let on_chain = chain
+                .query_denom_trace(trace.hash())
+                .map_err(Error::relayer)?;
+
+            if on_chain.full_path() != trace.full_path() {
+                return Err(Error::denom_trace_mismatch(
+                    self.chain_id.clone(),
+                    trace.hash(),
+                    trace.full_path(),
+                    on_chain.full_path(),
+                ));
+            }
+
+            Some(true)
+        } else {
+            None
+        };
+
+        Ok(IbcDenom {
+            trace: trace.full_path(),
+            denom: trace.ibc_denom(),
+            verified,
+        })
+    }
+}
+
+impl Runnable for QueryChannelIbcDenomCmd {
+    fn run(&self) {
+        match self.execute() {
+            Ok(ibc_denom) if json() => Output::success(ibc_denom).exit(),
+            Ok(ibc_denom) => Output::success_msg(format!(
+                "trace: {}\ndenom: {}{}",
+                ibc_denom.trace,
+                ibc_denom.denom,
+                if ibc_denom.verified.is_some() {
+                    " (verified on chain)"
+                } else {
+                    ""
+                }
+            ))
+            .exit(),
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::QueryChannelIbcDenomCmd;
+
+    use std::str::FromStr;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+
+    #[test]
+    fn test_query_channel_ibc_denom() {
+        assert_eq!(
+            QueryChannelIbcDenomCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                port_ids: vec![PortId::from_str("transfer").unwrap()],
+                channel_ids: vec![ChannelId::from_str("channel-0").unwrap()],
+                base_denom: "uatom".to_owned(),
+                multi_hop: false,
+                verify_on_chain: true,
+            },
+            QueryChannelIbcDenomCmd::parse_from(&[
+                "test",
+                "--chain",
+                "chain_id",
+                "--port",
+                "transfer",
+                "--chan",
+                "channel-0",
+                "--base-denom",
+                "uatom",
+                "--verify-on-chain"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_query_channel_ibc_denom_multi_hop() {
+        let cmd = QueryChannelIbcDenomCmd::parse_from(&[
+            "test",
+            "--chain",
+            "chain_id",
+            "--multi-hop",
+            "--port",
+            "transfer",
+            "--channel",
+            "channel-1",
+            "--port",
+            "transfer",
+            "--channel",
+            "channel-0",
+            "--base-denom",
+            "uatom",
+        ]);
+
+        assert_eq!(
+            cmd.denom_trace().unwrap().full_path(),
+            "transfer/channel-1/transfer/channel-0/uatom"
+        );
+    }
+
+    #[test]
+    fn test_query_channel_ibc_denom_multiple_pairs_without_multi_hop() {
+        let cmd = QueryChannelIbcDenomCmd::parse_from(&[
+            "test",
+            "--chain",
+            "chain_id",
+            "--port",
+            "transfer",
+            "--channel",
+            "channel-1",
+            "--port",
+            "transfer",
+            "--channel",
+            "channel-0",
+            "--base-denom",
+            "uatom",
+        ]);
+
+        assert!(cmd.denom_trace().is_err());
+    }
+
+    #[test]
+    fn test_query_channel_ibc_denom_unpaired_port() {
+        let cmd = QueryChannelIbcDenomCmd::parse_from(&[
+            "test",
+            "--chain",
+            "chain_id",
+            "--multi-hop",
+            "--port",
+            "transfer",
+            "--port",
+            "transfer",
+            "--channel",
+            "channel-0",
+            "--base-denom",
+            "uatom",
+        ]);
+
+        assert!(cmd.denom_trace().is_err());
+    }
+
+    #[test]
+    fn test_query_channel_ibc_denom_no_base_denom() {
+        assert!(QueryChannelIbcDenomCmd::try_parse_from(&[
+            "test",
+            "--chain",
+            "chain_id",
+            "--port",
+            "transfer",
+            "--channel",
+            "channel-0"
+        ])
+        .is_err())
+    }
+}
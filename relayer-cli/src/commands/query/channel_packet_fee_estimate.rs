@@ -0,0 +1,476 @@
+use std::collections::{BTreeMap, HashMap};
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use serde::Serialize;
+
+use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc_relayer::chain::cosmos::gas::gas_amount_to_fee;
+use ibc_relayer::chain::cosmos::types::gas::GasConfig;
+use ibc_relayer::chain::handle::{BaseChainHandle, ChainHandle};
+use ibc_relayer::chain::requests::{QueryHeight, QueryIncentivizedPacketRequest};
+use ibc_relayer::fee::{Coin, PacketFee};
+
+use crate::cli_utils::{
+    parse_channel_id, parse_port_id, resolve_chain_id, spawn_chain_counterparty,
+};
+use crate::conclude::{json, Output};
+use crate::error::Error;
+use crate::prelude::*;
+
+/// Relative margin around the estimated cost within which relaying a packet breaks even.
+const BREAK_EVEN_MARGIN: f64 = 0.1;
+
+/// Total amount of coins per denomination.
+type Totals = BTreeMap<String, u128>;
+
+/// Whether the fees paid for relaying a packet cover the cost of relaying it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum Verdict {
+    Profitable,
+    BreakEven,
+    Unprofitable,
+}
+
+impl Verdict {
+    fn new(revenue: f64, cost: f64) -> Self {
+        if revenue > cost * (1.0 + BREAK_EVEN_MARGIN) {
+            Self::Profitable
+        } else if revenue < cost * (1.0 - BREAK_EVEN_MARGIN) {
+            Self::Unprofitable
+        } else {
+            Self::BreakEven
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Profitable => "PROFITABLE",
+            Self::BreakEven => "BREAK_EVEN",
+            Self::Unprofitable => "UNPROFITABLE",
+        }
+    }
+}
+
+/// The result of the `query channel packet-fee-estimate` command.
+#[derive(Debug, Serialize)]
+struct PacketFeeEstimate {
+    sequence: Sequence,
+    recv_fee: Totals,
+    ack_fee: Totals,
+    timeout_fee: Totals,
+    /// The estimated gas fees for relaying the packet to the destination chain.
+    recv_cost: Totals,
+    /// The estimated gas fees for relaying the acknowledgement back to the source chain.
+    ack_cost: Totals,
+    /// The value of the recv and ack fees, in USD if prices were given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    revenue_usd: Option<f64>,
+    /// The value of the estimated gas fees, in USD if prices were given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cost_usd: Option<f64>,
+    /// `None` if the fees and the costs cannot be valued in a common unit.
+    verdict: Option<Verdict>,
+}
+
+/// Sums the amounts of the given coins per denomination.
+fn totals<'a>(coins: impl IntoIterator<Item = &'a Coin>) -> Result<Totals, Error> {
+    let mut totals = Totals::new();
+
+    for coin in coins {
+        let amount = coin.amount.parse::<u128>().map_err(|e| {
+            Error::cli_arg(format!(
+                "invalid amount '{}' of '{}': {}",
+                coin.amount, coin.denom, e
+            ))
+        })?;
+
+        *totals.entry(coin.denom.clone()).or_default() += amount;
+    }
+
+    Ok(totals)
+}
+
+fn merge(a: &Totals, b: &Totals) -> Totals {
+    let mut merged = a.clone();
+
+    for (denom, amount) in b {
+        *merged.entry(denom.clone()).or_default() += amount;
+    }
+
+    merged
+}
+
+/// The value in USD of the given totals, if a price is known for all their denominations.
+fn usd_value(totals: &Totals, prices: &HashMap<String, f64>) -> Option<f64> {
+    totals
+        .iter()
+        .map(|(denom, amount)| prices.get(denom).map(|price| *amount as f64 * price))
+        .sum()
+}
+
+/// Compares the `revenue` against the `cost` in USD if both can be valued, or
+/// else directly if they are both made of the same single denomination.
+fn verdict(revenue: &Totals, cost: &Totals, prices: &HashMap<String, f64>) -> Option<Verdict> {
+    if let (Some(revenue), Some(cost)) = (usd_value(revenue, prices), usd_value(cost, prices)) {
+        return Some(Verdict::new(revenue, cost));
+    }
+
+    match (revenue.len(), cost.len()) {
+        (0, _) => Some(Verdict::Unprofitable),
+        (1, 1) => {
+            let (revenue_denom, revenue) = revenue.iter().next()?;
+            let (cost_denom, cost) = cost.iter().next()?;
+
+            (revenue_denom == cost_denom).then(|| Verdict::new(*revenue as f64, *cost as f64))
+        }
+        _ => None,
+    }
+}
+
+fn parse_price(input: &str) -> Result<(String, f64), Error> {
+    let (denom, price) = input.split_once('=').ok_or_else(|| {
+        Error::cli_arg("expected a price in the form '<DENOM>=<USD>', eg. 'uatom=0.00001'".into())
+    })?;
+
+    let price = price
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|price| price.is_finite() && *price >= 0.0)
+        .ok_or_else(|| Error::cli_arg(format!("invalid price '{}' for '{}'", price, denom)))?;
+
+    Ok((denom.trim().to_string(), price))
+}
+
+fn format_totals(totals: &Totals) -> String {
+    if totals.is_empty() {
+        return "none".to_string();
+    }
+
+    totals
+        .iter()
+        .map(|(denom, amount)| format!("{}{}", amount, denom))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The data structure that represents the arguments when invoking the `query channel packet-fee-estimate` CLI command.
+///
+/// The command has the following format:
+///
+/// `query channel packet-fee-estimate --src-chain <SRC_CHAIN_ID> --src-port <SRC_PORT_ID>
+///     --src-channel <SRC_CHANNEL_ID> --dst-chain <DST_CHAIN_ID> --sequence <SEQUENCE>
+///     [--price <DENOM>=<USD>]...`
+///
+/// The ICS-29 fees escrowed on the source chain for the packet are compared against the
+/// gas fees for relaying the packet and its acknowledgement. Since the messages cannot be
+/// simulated without building them first, the gas fees are estimated from the `default_gas`
+/// and `gas_price` configured for each chain, which errs on the side of caution.
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct QueryChannelPacketFeeEstimateCmd {
+    #[clap(
+        long = "src-chain",
+        required = true,
+        value_name = "SRC_CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain on which the packet was sent"
+    )]
+    src_chain_id: ChainId,
+
+    #[clap(
+        long = "src-port",
+        required = true,
+        value_name = "SRC_PORT_ID",
+        parse(try_from_str = parse_port_id),
+        help_heading = "REQUIRED",
+        help = "Identifier of the port on the source chain"
+    )]
+    src_port_id: PortId,
+
+    #[clap(
+        long = "src-channel",
+        visible_alias = "src-chan",
+        required = true,
+        value_name = "SRC_CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
+        help_heading = "REQUIRED",
+        help = "Identifier of the channel on the source chain"
+    )]
+    src_channel_id: ChannelId,
+
+    #[clap(
+        long = "dst-chain",
+        required = true,
+        value_name = "DST_CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain to which the packet is sent"
+    )]
+    dst_chain_id: ChainId,
+
+    #[clap(
+        long = "sequence",
+        required = true,
+        value_name = "SEQUENCE",
+        help_heading = "REQUIRED",
+        help = "Sequence of the packet"
+    )]
+    sequence: Sequence,
+
+    #[clap(
+        long = "price",
+        value_name = "DENOM=USD",
+        multiple_occurrences = true,
+        parse(try_from_str = parse_price),
+        help = "Price in USD of one unit of a denomination, eg. 'uatom=0.00001'. Can be given multiple times"
+    )]
+    prices: Vec<(String, f64)>,
+}
+
+impl QueryChannelPacketFeeEstimateCmd {
+    fn execute(&self) -> Result<PacketFeeEstimate, Error> {
+        let config = app_config();
+
+        let (chains, _) = spawn_chain_counterparty::<BaseChainHandle>(
+            &config,
+            &self.src_chain_id,
+            &self.src_port_id,
+            &self.src_channel_id,
+        )?;
+
+        if chains.dst.id() != resolve_chain_id(&config, &self.dst_chain_id)? {
+            return Err(Error::cli_arg(format!(
+                "channel '{}' on chain '{}' leads to chain '{}', not '{}'",
+                self.src_channel_id,
+                self.src_chain_id,
+                chains.dst.id(),
+                self.dst_chain_id
+            )));
+        }
+
+        let packet_fees = chains
+            .src
+            .query_incentivized_packet(QueryIncentivizedPacketRequest {
+                port_id: self.src_port_id.clone(),
+                channel_id: self.src_channel_id.clone(),
+                sequence: self.sequence,
+                height: QueryHeight::Latest,
+            })
+            .map_err(Error::relayer)?;
+
+        let fees =
+            |select: fn(&PacketFee) -> &Vec<Coin>| totals(packet_fees.iter().flat_map(select));
+
+        let recv_fee = fees(|fee| &fee.recv_fee)?;
+        let ack_fee = fees(|fee| &fee.ack_fee)?;
+        let timeout_fee = fees(|fee| &fee.timeout_fee)?;
+
+        let recv_cost = estimated_cost(&chains.dst)?;
+        let ack_cost = estimated_cost(&chains.src)?;
+
+        let prices = self.prices.iter().cloned().collect::<HashMap<_, _>>();
+
+        // The timeout fee is only paid instead of the recv and ack fees
+        let revenue = merge(&recv_fee, &ack_fee);
+        let cost = merge(&recv_cost, &ack_cost);
+
+        Ok(PacketFeeEstimate {
+            sequence: self.sequence,
+            revenue_usd: usd_value(&revenue, &prices),
+            cost_usd: usd_value(&cost, &prices),
+            verdict: verdict(&revenue, &cost, &prices),
+            recv_fee,
+            ack_fee,
+            timeout_fee,
+            recv_cost,
+            ack_cost,
+        })
+    }
+}
+
+/// The gas fees paid for submitting a single message to the given chain, as per its configuration.
+fn estimated_cost(chain: &impl ChainHandle) -> Result<Totals, Error> {
+    let chain_config = chain.config().map_err(Error::relayer)?;
+    let gas_config = GasConfig::from(&chain_config);
+    let fee = gas_amount_to_fee(&gas_config, gas_config.default_gas);
+
+    totals(&fee.amount.into_iter().map(Coin::from).collect::<Vec<_>>())
+}
+
+impl Runnable for QueryChannelPacketFeeEstimateCmd {
+    fn run(&self) {
+        let estimate = match self.execute() {
+            Ok(estimate) => estimate,
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        };
+
+        if json() {
+            Output::success(estimate).exit()
+        }
+
+        let usd = |value: Option<f64>| {
+            value
+                .map(|value| format!(" (${:.4})", value))
+                .unwrap_or_default()
+        };
+
+        Output::success_msg(format!(
+            "packet {}\n\
+             recv fee: {}\n\
+             ack fee: {}\n\
+             timeout fee: {}\n\
+             estimated cost: {} on {}, {} on {}{}\n\
+             revenue:{}\n\
+             verdict: {}",
+            estimate.sequence,
+            format_totals(&estimate.recv_fee),
+            format_totals(&estimate.ack_fee),
+            format_totals(&estimate.timeout_fee),
+            format_totals(&estimate.recv_cost),
+            self.dst_chain_id,
+            format_totals(&estimate.ack_cost),
+            self.src_chain_id,
+            usd(estimate.cost_usd),
+            usd(estimate.revenue_usd),
+            estimate
+                .verdict
+                .map_or("UNKNOWN", |verdict| verdict.as_str()),
+        ))
+        .exit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_price, totals, verdict, QueryChannelPacketFeeEstimateCmd, Totals, Verdict};
+
+    use std::collections::HashMap;
+    use std::str::FromStr;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics04_channel::packet::Sequence;
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+    use ibc_relayer::fee::Coin;
+
+    fn coin(amount: &str, denom: &str) -> Coin {
+        Coin {
+            amount: amount.to_string(),
+            denom: denom.to_string(),
+        }
+    }
+
+    fn amounts(amounts: &[(&str, u128)]) -> Totals {
+        amounts
+            .iter()
+            .map(|(denom, amount)| (denom.to_string(), *amount))
+            .collect()
+    }
+
+    #[test]
+    fn test_query_channel_packet_fee_estimate() {
+        assert_eq!(
+            QueryChannelPacketFeeEstimateCmd {
+                src_chain_id: ChainId::from_string("chain_a"),
+                src_port_id: PortId::from_str("transfer").unwrap(),
+                src_channel_id: ChannelId::from_str("channel-0").unwrap(),
+                dst_chain_id: ChainId::from_string("chain_b"),
+                sequence: Sequence::from(5),
+                prices: vec![("uatom".to_string(), 0.00001), ("stake".to_string(), 0.5)],
+            },
+            QueryChannelPacketFeeEstimateCmd::parse_from(&[
+                "test",
+                "--src-chain",
+                "chain_a",
+                "--src-port",
+                "transfer",
+                "--src-chan",
+                "channel-0",
+                "--dst-chain",
+                "chain_b",
+                "--sequence",
+                "5",
+                "--price",
+                "uatom=0.00001",
+                "--price",
+                "stake=0.5"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_query_channel_packet_fee_estimate_no_sequence() {
+        assert!(QueryChannelPacketFeeEstimateCmd::try_parse_from(&[
+            "test",
+            "--src-chain",
+            "chain_a",
+            "--src-port",
+            "transfer",
+            "--src-channel",
+            "channel-0",
+            "--dst-chain",
+            "chain_b"
+        ])
+        .is_err())
+    }
+
+    #[test]
+    fn test_parse_price() {
+        assert_eq!(
+            parse_price("uatom=0.25").unwrap(),
+            ("uatom".to_string(), 0.25)
+        );
+        assert!(parse_price("uatom").is_err());
+        assert!(parse_price("uatom=abc").is_err());
+        assert!(parse_price("uatom=-1").is_err());
+    }
+
+    #[test]
+    fn test_totals() {
+        let coins = vec![coin("10", "stake"), coin("5", "uatom"), coin("7", "stake")];
+
+        assert_eq!(
+            totals(&coins).unwrap(),
+            amounts(&[("stake", 17), ("uatom", 5)])
+        );
+
+        assert!(totals(&[coin("ten", "stake")]).is_err());
+    }
+
+    #[test]
+    fn test_verdict_same_denom() {
+        let prices = HashMap::new();
+        let cost = amounts(&[("stake", 100)]);
+
+        let verdict_for = |revenue| verdict(&amounts(&[("stake", revenue)]), &cost, &prices);
+
+        assert_eq!(verdict_for(200), Some(Verdict::Profitable));
+        assert_eq!(verdict_for(105), Some(Verdict::BreakEven));
+        assert_eq!(verdict_for(50), Some(Verdict::Unprofitable));
+
+        // No fee at all is never worth relaying
+        assert_eq!(
+            verdict(&Totals::new(), &cost, &prices),
+            Some(Verdict::Unprofitable)
+        );
+    }
+
+    #[test]
+    fn test_verdict_with_prices() {
+        let revenue = amounts(&[("uatom", 1000)]);
+        let cost = amounts(&[("stake", 100)]);
+
+        // Different denominations cannot be compared without prices
+        assert_eq!(verdict(&revenue, &cost, &HashMap::new()), None);
+
+        let prices = HashMap::from([("uatom".to_string(), 0.01), ("stake".to_string(), 0.05)]);
+        assert_eq!(verdict(&revenue, &cost, &prices), Some(Verdict::Profitable));
+
+        let prices = HashMap::from([("uatom".to_string(), 0.001), ("stake".to_string(), 0.05)]);
+        assert_eq!(
+            verdict(&revenue, &cost, &prices),
+            Some(Verdict::Unprofitable)
+        );
+    }
+}
@@ -0,0 +1,388 @@
+use alloc::collections::BTreeMap;
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use serde::Serialize;
+
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc::events::{IbcEvent, WithBlockDataType};
+use ibc::Height;
+use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::chain::requests::{QueryPacketEventRangeRequest, QueryTxRequest};
+
+use crate::cli_utils::{
+    find_chain_config, parse_channel_id, parse_port_id, query_height_at_time, spawn_chain_runtime,
+};
+use crate::conclude::{json, Output};
+use crate::error::Error;
+use crate::prelude::*;
+
+/// Width of the buckets of the histogram, in bytes.
+const BUCKET_SIZE: usize = 100;
+
+/// The P99 packet data size is flagged when it exceeds this fraction of the `max_tx_size` of the chain.
+const MAX_TX_SIZE_WARNING_FRACTION: f64 = 0.8;
+
+/// Statistics over the sizes of the data of the packets sent during the queried period.
+#[derive(Debug, PartialEq, Serialize)]
+struct SizeStats {
+    mean: usize,
+    p50: usize,
+    p95: usize,
+    p99: usize,
+    max: usize,
+}
+
+/// The number of packets whose data size is at least `from` and less than `from + BUCKET_SIZE` bytes.
+#[derive(Debug, PartialEq, Serialize)]
+struct Bucket {
+    from: usize,
+    count: usize,
+}
+
+/// The result of the `query channel packet-size-histogram` command.
+#[derive(Debug, Serialize)]
+struct PacketSizeHistogram {
+    chain: ChainId,
+    port_id: PortId,
+    channel_id: ChannelId,
+    start_height: Height,
+    end_height: Height,
+    /// Number of packets sent on the channel during the period.
+    sent: usize,
+    /// Packet data sizes, in bytes.
+    sizes: Option<SizeStats>,
+    histogram: Vec<Bucket>,
+    max_tx_size: usize,
+    /// Whether the P99 packet data size is close to the `max_tx_size` of the chain.
+    near_max_tx_size: bool,
+}
+
+/// Show the distribution of the sizes of the data of the packets sent on a channel during a given period.
+///
+/// The sizes of the data of the packets are taken from the `send_packet` events
+/// emitted on the chain, and bucketed by 100-byte intervals. The P99 size is
+/// flagged when it comes close to the `max_tx_size` configured for the chain.
+///
+/// `query channel packet-size-histogram --src-chain <SRC_CHAIN_ID> --src-port <SRC_PORT_ID>
+///     --src-channel <SRC_CHANNEL_ID> [--period <PERIOD>]`
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct QueryChannelPacketSizeHistogramCmd {
+    #[clap(
+        long = "src-chain",
+        required = true,
+        value_name = "SRC_CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain on which the packets are sent"
+    )]
+    src_chain_id: ChainId,
+
+    #[clap(
+        long = "src-port",
+        required = true,
+        value_name = "SRC_PORT_ID",
+        parse(try_from_str = parse_port_id),
+        help_heading = "REQUIRED",
+        help = "Identifier of the port on the source chain"
+    )]
+    src_port_id: PortId,
+
+    #[clap(
+        long = "src-channel",
+        visible_alias = "src-chan",
+        required = true,
+        value_name = "SRC_CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
+        help_heading = "REQUIRED",
+        help = "Identifier of the channel on the source chain"
+    )]
+    src_channel_id: ChannelId,
+
+    #[clap(
+        long = "period",
+        default_value = "24h",
+        value_name = "PERIOD",
+        help = "Period, ending now, during which the packets were sent"
+    )]
+    period: humantime::Duration,
+}
+
+impl QueryChannelPacketSizeHistogramCmd {
+    fn execute(&self) -> Result<PacketSizeHistogram, Error> {
+        let config = app_config();
+
+        let chain_config = find_chain_config(&config, &self.src_chain_id)?;
+        let chain = spawn_chain_runtime(&config, &chain_config.id)?;
+
+        let status = chain.query_application_status().map_err(Error::relayer)?;
+        let start_time = (status.timestamp - *self.period)
+            .map_err(|e| Error::cli_arg(format!("invalid period '{}': {}", self.period, e)))?;
+
+        let end_height = status.height;
+        let start_height = query_height_at_time(&chain, end_height, start_time)?;
+
+        debug!(
+            "querying send_packet events on {} between heights {} and {}",
+            chain_config.id, start_height, end_height
+        );
+
+        let events = chain
+            .query_txs(QueryTxRequest::PacketRange(QueryPacketEventRangeRequest {
+                event_id: WithBlockDataType::SendPacket,
+                port_id: self.src_port_id.clone(),
+                channel_id: self.src_channel_id.clone(),
+                start_height,
+                end_height,
+            }))
+            .map_err(Error::relayer)?;
+
+        let sizes = events
+            .into_iter()
+            .filter_map(|event| match event {
+                IbcEvent::SendPacket(ev) => Some(ev.packet.data.len()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        for size in &sizes {
+            ibc_relayer::telemetry!(
+                packet_data_size,
+                *size as u64,
+                &chain_config.id,
+                &self.src_channel_id,
+                &self.src_port_id,
+            );
+        }
+
+        let max_tx_size = chain_config.max_tx_size.to_usize();
+        let stats = size_stats(sizes.clone());
+        let near_max_tx_size = stats
+            .as_ref()
+            .map_or(false, |stats| near_max_tx_size(stats.p99, max_tx_size));
+
+        Ok(PacketSizeHistogram {
+            chain: chain_config.id.clone(),
+            port_id: self.src_port_id.clone(),
+            channel_id: self.src_channel_id.clone(),
+            start_height,
+            end_height,
+            sent: sizes.len(),
+            sizes: stats,
+            histogram: histogram(&sizes),
+            max_tx_size,
+            near_max_tx_size,
+        })
+    }
+}
+
+impl Runnable for QueryChannelPacketSizeHistogramCmd {
+    fn run(&self) {
+        let result = match self.execute() {
+            Ok(result) => result,
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        };
+
+        if json() {
+            Output::success(result).exit()
+        }
+
+        let mut lines = vec![format!(
+            "{} packets sent on {}/{} of chain {} between heights {} and {}",
+            result.sent,
+            result.port_id,
+            result.channel_id,
+            result.chain,
+            result.start_height,
+            result.end_height
+        )];
+
+        if let Some(sizes) = &result.sizes {
+            lines.push(format!(
+                "packet data size (bytes): mean {}, P50 {}, P95 {}, P99 {}, max {}",
+                sizes.mean, sizes.p50, sizes.p95, sizes.p99, sizes.max
+            ));
+
+            let largest = result.histogram.iter().map(|b| b.count).max().unwrap_or(0);
+            for bucket in &result.histogram {
+                let bar_len = (bucket.count * 50 + largest - 1) / largest;
+                lines.push(format!(
+                    "{:>7}-{:<7} {:>7} {}",
+                    bucket.from,
+                    bucket.from + BUCKET_SIZE - 1,
+                    bucket.count,
+                    "#".repeat(bar_len)
+                ));
+            }
+
+            if result.near_max_tx_size {
+                lines.push(format!(
+                    "WARNING: the P99 packet data size ({} bytes) is close to the `max_tx_size` of the chain ({} bytes)",
+                    sizes.p99, result.max_tx_size
+                ));
+            }
+        }
+
+        Output::success_msg(lines.join("\n")).exit()
+    }
+}
+
+/// Counts the given sizes in buckets of `BUCKET_SIZE` bytes, from the smallest to the
+/// largest non-empty bucket. The empty buckets in between are included.
+fn histogram(sizes: &[usize]) -> Vec<Bucket> {
+    let mut counts = BTreeMap::new();
+    for size in sizes {
+        *counts.entry(size / BUCKET_SIZE).or_insert(0) += 1;
+    }
+
+    let (first, last) = match (counts.keys().next(), counts.keys().next_back()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return vec![],
+    };
+
+    (first..=last)
+        .map(|bucket| Bucket {
+            from: bucket * BUCKET_SIZE,
+            count: counts.get(&bucket).copied().unwrap_or(0),
+        })
+        .collect()
+}
+
+/// Computes the mean, median, 95th and 99th percentiles, and maximum of the given sizes.
+/// Percentiles are computed with the nearest-rank method.
+fn size_stats(mut sizes: Vec<usize>) -> Option<SizeStats> {
+    if sizes.is_empty() {
+        return None;
+    }
+
+    sizes.sort_unstable();
+
+    let len = sizes.len();
+    let percentile = |p: usize| sizes[((p * len + 99) / 100).max(1) - 1];
+
+    Some(SizeStats {
+        mean: sizes.iter().sum::<usize>() / len,
+        p50: percentile(50),
+        p95: percentile(95),
+        p99: percentile(99),
+        max: sizes[len - 1],
+    })
+}
+
+fn near_max_tx_size(size: usize, max_tx_size: usize) -> bool {
+    size as f64 >= max_tx_size as f64 * MAX_TX_SIZE_WARNING_FRACTION
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        histogram, near_max_tx_size, size_stats, Bucket, QueryChannelPacketSizeHistogramCmd,
+        SizeStats,
+    };
+
+    use std::str::FromStr;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+
+    #[test]
+    fn test_query_channel_packet_size_histogram() {
+        assert_eq!(
+            QueryChannelPacketSizeHistogramCmd {
+                src_chain_id: ChainId::from_string("chain_a"),
+                src_port_id: PortId::from_str("transfer").unwrap(),
+                src_channel_id: ChannelId::from_str("channel-0").unwrap(),
+                period: "2h".parse().unwrap(),
+            },
+            QueryChannelPacketSizeHistogramCmd::parse_from(&[
+                "test",
+                "--src-chain",
+                "chain_a",
+                "--src-port",
+                "transfer",
+                "--src-chan",
+                "channel-0",
+                "--period",
+                "2h"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_query_channel_packet_size_histogram_default_period() {
+        let cmd = QueryChannelPacketSizeHistogramCmd::parse_from(&[
+            "test",
+            "--src-chain",
+            "chain_a",
+            "--src-port",
+            "transfer",
+            "--src-channel",
+            "channel-0",
+        ]);
+
+        assert_eq!(cmd.period, "24h".parse().unwrap());
+    }
+
+    #[test]
+    fn test_query_channel_packet_size_histogram_no_channel() {
+        assert!(QueryChannelPacketSizeHistogramCmd::try_parse_from(&[
+            "test",
+            "--src-chain",
+            "chain_a",
+            "--src-port",
+            "transfer"
+        ])
+        .is_err())
+    }
+
+    #[test]
+    fn test_histogram() {
+        assert_eq!(histogram(&[]), vec![]);
+
+        assert_eq!(
+            histogram(&[150, 199, 420, 100, 499]),
+            vec![
+                Bucket {
+                    from: 100,
+                    count: 3
+                },
+                Bucket {
+                    from: 200,
+                    count: 0
+                },
+                Bucket {
+                    from: 300,
+                    count: 0
+                },
+                Bucket {
+                    from: 400,
+                    count: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_size_stats() {
+        assert_eq!(size_stats(vec![]), None);
+
+        let sizes = (1..=100).rev().map(|i| i * 10).collect();
+
+        assert_eq!(
+            size_stats(sizes),
+            Some(SizeStats {
+                mean: 505,
+                p50: 500,
+                p95: 950,
+                p99: 990,
+                max: 1000,
+            })
+        );
+    }
+
+    #[test]
+    fn test_near_max_tx_size() {
+        assert!(!near_max_tx_size(100_000, 2_097_152));
+        assert!(near_max_tx_size(1_800_000, 2_097_152));
+        assert!(near_max_tx_size(3_000_000, 2_097_152));
+    }
+}
@@ -0,0 +1,288 @@
+use alloc::collections::BTreeMap;
+use core::fmt;
+use core::time::Duration;
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use serde::Serialize;
+
+use ibc::core::ics04_channel::channel::State;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc::timestamp::Timestamp;
+use ibc::Height;
+use ibc_relayer::chain::endpoint::PacketRelayTx;
+use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::chain::requests::{PageRequest, QueryChannelsRequest, QueryPacketRelayTxsRequest};
+
+use crate::cli_utils::{query_block_timestamp, spawn_chain_runtime};
+use crate::conclude::{json, Output};
+use crate::error::Error;
+use crate::prelude::*;
+
+/// Default number of blocks searched for relay activity.
+const DEFAULT_BLOCKS: u64 = 10_000;
+
+/// Relay activity more recent than this counts as active relaying.
+const ACTIVE_WITHIN: Duration = Duration::from_secs(3600);
+
+/// Whether a channel is being relayed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Coverage {
+    /// Packets or acknowledgements were relayed to the channel within the last hour.
+    ActivelyRelayed,
+    /// Packets or acknowledgements were relayed to the channel, but more than an hour ago.
+    Stale,
+    /// No packet nor acknowledgement was relayed to the channel in the searched blocks.
+    Unrelayed,
+}
+
+impl Coverage {
+    fn new(last_relayed_at: Option<Timestamp>, now: Timestamp) -> Self {
+        match last_relayed_at {
+            None => Self::Unrelayed,
+            Some(at) if now.duration_since(&at).unwrap_or_default() <= ACTIVE_WITHIN => {
+                Self::ActivelyRelayed
+            }
+            Some(_) => Self::Stale,
+        }
+    }
+}
+
+impl fmt::Display for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ActivelyRelayed => write!(f, "Actively Relayed"),
+            Self::Stale => write!(f, "Stale"),
+            Self::Unrelayed => write!(f, "Unrelayed"),
+        }
+    }
+}
+
+/// The relay activity on a channel end.
+#[derive(Debug, Serialize)]
+struct ChannelRelayCoverage {
+    port_id: PortId,
+    channel_id: ChannelId,
+    coverage: Coverage,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_relayed_height: Option<Height>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_relayed_at: Option<Timestamp>,
+    /// The number of relay transactions submitted by each relayer address.
+    relayers: BTreeMap<String, usize>,
+}
+
+/// Counts the relay transactions per relayer address, leaving out the
+/// transactions whose sender is not reported by the chain.
+fn relayers(txs: &[PacketRelayTx]) -> BTreeMap<String, usize> {
+    let mut relayers = BTreeMap::new();
+
+    for sender in txs.iter().filter_map(|tx| tx.sender.as_ref()) {
+        *relayers.entry(sender.clone()).or_default() += 1;
+    }
+
+    relayers
+}
+
+/// Show which channels of a chain are being relayed, and by which relayers.
+///
+/// The relay activity on a channel end consists of the transactions which
+/// relayed packets to it, or acknowledgements of the packets sent from it,
+/// found by searching the transactions in the last `--blocks` blocks of the chain.
+/// A channel is reported as `Actively Relayed` if the latest of these transactions
+/// is less than an hour old, `Stale` if it is older, and `Unrelayed` if there is none.
+///
+/// `query channel relay-coverage --chain <CHAIN_ID> [--blocks <BLOCKS>]`
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct QueryChannelRelayCoverageCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain to query"
+    )]
+    chain_id: ChainId,
+
+    #[clap(
+        long = "blocks",
+        value_name = "BLOCKS",
+        default_value_t = DEFAULT_BLOCKS,
+        help = "Number of latest blocks to search for relay activity"
+    )]
+    blocks: u64,
+}
+
+impl QueryChannelRelayCoverageCmd {
+    fn execute(&self) -> Result<Vec<ChannelRelayCoverage>, Error> {
+        let config = app_config();
+
+        let chain = spawn_chain_runtime(&config, &self.chain_id)?;
+
+        let end_height = chain.query_latest_height().map_err(Error::relayer)?;
+        let start_height = Height::new(
+            end_height.revision_number(),
+            end_height
+                .revision_height()
+                .saturating_sub(self.blocks.saturating_sub(1))
+                .max(1),
+        )
+        .map_err(|e| Error::cli_arg(e.to_string()))?;
+
+        let channels = chain
+            .query_channels(QueryChannelsRequest {
+                pagination: Some(PageRequest::all()),
+            })
+            .map_err(Error::relayer)?
+            .into_iter()
+            .filter(|channel| channel.channel_end.state_matches(&State::Open));
+
+        let now = Timestamp::now();
+        let mut coverage = Vec::new();
+
+        for channel in channels {
+            let txs = chain
+                .query_packet_relay_txs(QueryPacketRelayTxsRequest {
+                    port_id: channel.port_id.clone(),
+                    channel_id: channel.channel_id.clone(),
+                    start_height,
+                    end_height,
+                })
+                .map_err(Error::relayer)?;
+
+            let last_relayed_height = txs.iter().map(|tx| tx.height).max();
+            let last_relayed_at = last_relayed_height
+                .map(|height| query_block_timestamp(&chain, height))
+                .transpose()?;
+
+            coverage.push(ChannelRelayCoverage {
+                port_id: channel.port_id,
+                channel_id: channel.channel_id,
+                coverage: Coverage::new(last_relayed_at, now),
+                last_relayed_height,
+                last_relayed_at,
+                relayers: relayers(&txs),
+            });
+        }
+
+        Ok(coverage)
+    }
+}
+
+impl Runnable for QueryChannelRelayCoverageCmd {
+    fn run(&self) {
+        let coverage = match self.execute() {
+            Ok(coverage) => coverage,
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        };
+
+        if json() {
+            Output::success(coverage).exit()
+        }
+
+        let lines = coverage
+            .iter()
+            .map(|channel| {
+                let mut line = format!(
+                    "{}/{}: {}",
+                    channel.port_id, channel.channel_id, channel.coverage
+                );
+
+                if let (Some(height), Some(at)) =
+                    (channel.last_relayed_height, channel.last_relayed_at)
+                {
+                    line.push_str(&format!(", last relayed at height {} ({})", height, at));
+                }
+
+                if !channel.relayers.is_empty() {
+                    let relayers = channel
+                        .relayers
+                        .iter()
+                        .map(|(address, count)| format!("{} ({} tx)", address, count))
+                        .collect::<Vec<_>>();
+
+                    line.push_str(&format!(", relayers: {}", relayers.join(", ")));
+                }
+
+                line
+            })
+            .collect::<Vec<_>>();
+
+        Output::success_msg(lines.join("\n")).exit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{relayers, Coverage, QueryChannelRelayCoverageCmd, DEFAULT_BLOCKS};
+
+    use core::time::Duration;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::ChainId;
+    use ibc::events::IbcEventType;
+    use ibc::timestamp::Timestamp;
+    use ibc::Height;
+    use ibc_relayer::chain::endpoint::PacketRelayTx;
+
+    fn relay_tx(height: u64, sender: Option<&str>) -> PacketRelayTx {
+        PacketRelayTx {
+            event_type: IbcEventType::ReceivePacket,
+            height: Height::new(0, height).unwrap(),
+            sender: sender.map(ToString::to_string),
+        }
+    }
+
+    #[test]
+    fn test_query_channel_relay_coverage() {
+        assert_eq!(
+            QueryChannelRelayCoverageCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                blocks: DEFAULT_BLOCKS,
+            },
+            QueryChannelRelayCoverageCmd::parse_from(&["test", "--chain", "chain_id"])
+        )
+    }
+
+    #[test]
+    fn test_query_channel_relay_coverage_blocks() {
+        let cmd = QueryChannelRelayCoverageCmd::parse_from(&[
+            "test", "--chain", "chain_id", "--blocks", "500",
+        ]);
+
+        assert_eq!(cmd.blocks, 500);
+    }
+
+    #[test]
+    fn test_query_channel_relay_coverage_no_chain() {
+        assert!(QueryChannelRelayCoverageCmd::try_parse_from(&["test"]).is_err())
+    }
+
+    #[test]
+    fn test_coverage() {
+        let now = Timestamp::now();
+        let ago = |secs| (now - Duration::from_secs(secs)).unwrap();
+
+        assert_eq!(Coverage::new(None, now), Coverage::Unrelayed);
+        assert_eq!(Coverage::new(Some(ago(60)), now), Coverage::ActivelyRelayed);
+        assert_eq!(Coverage::new(Some(ago(7200)), now), Coverage::Stale);
+    }
+
+    #[test]
+    fn test_relayers() {
+        let txs = vec![
+            relay_tx(10, Some("cosmos1a")),
+            relay_tx(11, Some("cosmos1b")),
+            relay_tx(12, None),
+            relay_tx(13, Some("cosmos1a")),
+        ];
+
+        let relayers = relayers(&txs).into_iter().collect::<Vec<_>>();
+
+        assert_eq!(
+            relayers,
+            vec![("cosmos1a".to_string(), 2), ("cosmos1b".to_string(), 1)]
+        );
+    }
+}
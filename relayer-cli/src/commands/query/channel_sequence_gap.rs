@@ -0,0 +1,380 @@
+use std::collections::HashSet;
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use serde::Serialize;
+
+use ibc::core::ics04_channel::channel::Order;
+use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc_relayer::chain::counterparty::{commitments_on_chain, unreceived_packets_sequences};
+use ibc_relayer::chain::handle::{BaseChainHandle, ChainHandle};
+use ibc_relayer::chain::requests::{
+    IncludeProof, PageRequest, QueryHeight, QueryNextSequenceReceiveRequest,
+    QueryNextSequenceSendRequest, QueryPacketAcknowledgementsRequest,
+};
+
+use crate::cli_utils::{
+    parse_channel_id, parse_port_id, resolve_chain_id, spawn_chain_counterparty,
+};
+use crate::conclude::{json, Output};
+use crate::error::Error;
+use crate::prelude::*;
+
+/// The result of the `query channel sequence-gap` command.
+#[derive(Debug, Serialize)]
+struct SequenceGapReport {
+    ordering: Order,
+    /// The sequence of the next packet to be sent on the source chain.
+    next_sequence_send: Sequence,
+    /// The sequence of the next packet expected on the destination chain,
+    /// only known for ordered channels.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_sequence_recv: Option<Sequence>,
+    /// The sequences of the packets sent on the source chain which have not
+    /// been received by the destination chain yet.
+    pending: Vec<Sequence>,
+    /// The sequences of the packets which were sent, but of which there is
+    /// no trace left on either chain.
+    gaps: Vec<Sequence>,
+    /// Whether the destination chain has received more packets than were sent.
+    over_received: bool,
+    impact: String,
+}
+
+/// Returns the sequences in `range` which are not in any of the `excluded` lists.
+fn missing_sequences(range: impl Iterator<Item = u64>, excluded: &[&[Sequence]]) -> Vec<Sequence> {
+    let excluded = excluded
+        .iter()
+        .flat_map(|sequences| sequences.iter())
+        .collect::<HashSet<_>>();
+
+    range
+        .map(Sequence::from)
+        .filter(|sequence| !excluded.contains(sequence))
+        .collect()
+}
+
+/// Returns the sequences, on an ordered channel, of the packets which are neither
+/// received by the destination chain, as per its `next_sequence_recv`, nor pending
+/// on the source chain.
+fn ordered_gaps(
+    next_sequence_send: Sequence,
+    next_sequence_recv: Sequence,
+    commitments: &[Sequence],
+) -> Vec<Sequence> {
+    missing_sequences(
+        u64::from(next_sequence_recv)..u64::from(next_sequence_send),
+        &[commitments],
+    )
+}
+
+fn impact(ordering: Order, gaps: &[Sequence], pending: usize, over_received: bool) -> String {
+    if over_received {
+        return "the destination chain expects a sequence which was never sent on the source chain: \
+                the channel ends are inconsistent and no further packet can be relayed"
+            .to_string();
+    }
+
+    if gaps.is_empty() {
+        return "none".to_string();
+    }
+
+    match ordering {
+        Order::Ordered => format!(
+            "{} packet(s) can no longer be received on the ordered channel, \
+             which blocks the {} pending packet(s) sent after them",
+            gaps.len(),
+            pending
+        ),
+        _ => format!(
+            "{} packet(s) were neither received nor acknowledged on the destination chain \
+             and are not pending on the source chain: they either timed out or were lost",
+            gaps.len()
+        ),
+    }
+}
+
+/// The data structure that represents the arguments when invoking the `query channel sequence-gap` CLI command.
+///
+/// The command has the following format:
+///
+/// `query channel sequence-gap --src-chain <SRC_CHAIN_ID> --src-port <SRC_PORT_ID>
+///     --src-channel <SRC_CHANNEL_ID> --dst-chain <DST_CHAIN_ID>`
+///
+/// On ordered channels, the `next_sequence_recv` of the destination chain is checked against
+/// the `next_sequence_send` of the source chain and the packet commitments pending on it.
+/// On unordered channels, every sequence sent on the source chain is looked up in the packet
+/// commitments of the source chain, and in the packet receipts and acknowledgements of the
+/// destination chain. The sequences found nowhere are reported as gaps.
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct QueryChannelSequenceGapCmd {
+    #[clap(
+        long = "src-chain",
+        required = true,
+        value_name = "SRC_CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain on which the packets are sent"
+    )]
+    src_chain_id: ChainId,
+
+    #[clap(
+        long = "src-port",
+        required = true,
+        value_name = "SRC_PORT_ID",
+        parse(try_from_str = parse_port_id),
+        help_heading = "REQUIRED",
+        help = "Identifier of the port on the source chain"
+    )]
+    src_port_id: PortId,
+
+    #[clap(
+        long = "src-channel",
+        visible_alias = "src-chan",
+        required = true,
+        value_name = "SRC_CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
+        help_heading = "REQUIRED",
+        help = "Identifier of the channel on the source chain"
+    )]
+    src_channel_id: ChannelId,
+
+    #[clap(
+        long = "dst-chain",
+        required = true,
+        value_name = "DST_CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain to which the packets are sent"
+    )]
+    dst_chain_id: ChainId,
+}
+
+impl QueryChannelSequenceGapCmd {
+    fn execute(&self) -> Result<SequenceGapReport, Error> {
+        let config = app_config();
+
+        let (chains, chan_conn_cli) = spawn_chain_counterparty::<BaseChainHandle>(
+            &config,
+            &self.src_chain_id,
+            &self.src_port_id,
+            &self.src_channel_id,
+        )?;
+
+        if chains.dst.id() != resolve_chain_id(&config, &self.dst_chain_id)? {
+            return Err(Error::cli_arg(format!(
+                "channel '{}' on chain '{}' leads to chain '{}', not '{}'",
+                self.src_channel_id,
+                self.src_chain_id,
+                chains.dst.id(),
+                self.dst_chain_id
+            )));
+        }
+
+        let channel_end = &chan_conn_cli.channel.channel_end;
+        let ordering = *channel_end.ordering();
+        let counterparty = channel_end.counterparty().clone();
+        let dst_port_id = counterparty.port_id().clone();
+        let dst_channel_id = counterparty
+            .channel_id()
+            .cloned()
+            .ok_or_else(|| Error::missing_counterparty_channel_id(chan_conn_cli.channel))?;
+
+        let (commitments, height) =
+            commitments_on_chain(&chains.src, &self.src_port_id, &self.src_channel_id)
+                .map_err(Error::supervisor)?;
+
+        // Query `next_sequence_send` at the height of the commitments, so that
+        // packets sent in the meantime are not mistaken for gaps.
+        let (next_sequence_send, _) = chains
+            .src
+            .query_next_sequence_send(
+                QueryNextSequenceSendRequest {
+                    port_id: self.src_port_id.clone(),
+                    channel_id: self.src_channel_id.clone(),
+                    height: QueryHeight::Specific(height),
+                },
+                IncludeProof::No,
+            )
+            .map_err(Error::relayer)?;
+
+        let pending = unreceived_packets_sequences(
+            &chains.dst,
+            &dst_port_id,
+            &dst_channel_id,
+            commitments.clone(),
+        )
+        .map_err(Error::supervisor)?;
+
+        let (next_sequence_recv, gaps, over_received) = match ordering {
+            Order::Ordered => {
+                let (next_sequence_recv, _) = chains
+                    .dst
+                    .query_next_sequence_receive(
+                        QueryNextSequenceReceiveRequest {
+                            port_id: dst_port_id,
+                            channel_id: dst_channel_id,
+                            height: QueryHeight::Latest,
+                        },
+                        IncludeProof::No,
+                    )
+                    .map_err(Error::relayer)?;
+
+                let gaps = ordered_gaps(next_sequence_send, next_sequence_recv, &commitments);
+
+                (
+                    Some(next_sequence_recv),
+                    gaps,
+                    next_sequence_recv > next_sequence_send,
+                )
+            }
+            _ => {
+                // The packets which are no longer pending on the source chain
+                let candidates =
+                    missing_sequences(1..u64::from(next_sequence_send), &[&commitments]);
+
+                // ... out of which the destination chain has no receipt for
+                let unreceived = unreceived_packets_sequences(
+                    &chains.dst,
+                    &dst_port_id,
+                    &dst_channel_id,
+                    candidates,
+                )
+                .map_err(Error::supervisor)?;
+
+                // ... nor any acknowledgement
+                let (acked, _) = chains
+                    .dst
+                    .query_packet_acknowledgements(QueryPacketAcknowledgementsRequest {
+                        port_id: dst_port_id,
+                        channel_id: dst_channel_id,
+                        pagination: Some(PageRequest::all()),
+                        packet_commitment_sequences: unreceived.clone(),
+                    })
+                    .map_err(Error::relayer)?;
+
+                let gaps = missing_sequences(unreceived.into_iter().map(u64::from), &[&acked]);
+
+                (None, gaps, false)
+            }
+        };
+
+        Ok(SequenceGapReport {
+            impact: impact(ordering, &gaps, pending.len(), over_received),
+            ordering,
+            next_sequence_send,
+            next_sequence_recv,
+            pending,
+            gaps,
+            over_received,
+        })
+    }
+}
+
+impl Runnable for QueryChannelSequenceGapCmd {
+    fn run(&self) {
+        let report = match self.execute() {
+            Ok(report) => report,
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        };
+
+        if json() {
+            Output::success(report).exit()
+        }
+
+        if report.gaps.is_empty() && !report.over_received {
+            Output::success_msg(format!(
+                "no sequence gap found, {} packet(s) pending",
+                report.pending.len()
+            ))
+            .exit()
+        }
+
+        Output::error(format!(
+            "found {} sequence gap(s): {}\nimpact: {}",
+            report.gaps.len(),
+            report
+                .gaps
+                .iter()
+                .map(|sequence| sequence.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            report.impact
+        ))
+        .exit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{missing_sequences, ordered_gaps, QueryChannelSequenceGapCmd};
+
+    use std::str::FromStr;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics04_channel::packet::Sequence;
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+
+    fn sequences(seqs: &[u64]) -> Vec<Sequence> {
+        seqs.iter().copied().map(Sequence::from).collect()
+    }
+
+    #[test]
+    fn test_query_channel_sequence_gap() {
+        assert_eq!(
+            QueryChannelSequenceGapCmd {
+                src_chain_id: ChainId::from_string("chain_a"),
+                src_port_id: PortId::from_str("transfer").unwrap(),
+                src_channel_id: ChannelId::from_str("channel-0").unwrap(),
+                dst_chain_id: ChainId::from_string("chain_b"),
+            },
+            QueryChannelSequenceGapCmd::parse_from(&[
+                "test",
+                "--src-chain",
+                "chain_a",
+                "--src-port",
+                "transfer",
+                "--src-chan",
+                "channel-0",
+                "--dst-chain",
+                "chain_b"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_query_channel_sequence_gap_no_dst_chain() {
+        assert!(QueryChannelSequenceGapCmd::try_parse_from(&[
+            "test",
+            "--src-chain",
+            "chain_a",
+            "--src-port",
+            "transfer",
+            "--src-channel",
+            "channel-0"
+        ])
+        .is_err())
+    }
+
+    #[test]
+    fn test_ordered_gaps() {
+        // Packets 1 to 4 were received, 5 to 7 are pending
+        assert!(ordered_gaps(8.into(), 5.into(), &sequences(&[3, 4, 5, 6, 7])).is_empty());
+
+        // Packet 6 is neither received nor pending
+        assert_eq!(
+            ordered_gaps(8.into(), 5.into(), &sequences(&[5, 7])),
+            sequences(&[6])
+        );
+
+        // More packets were received than sent
+        assert!(ordered_gaps(3.into(), 5.into(), &[]).is_empty());
+    }
+
+    #[test]
+    fn test_missing_sequences() {
+        assert_eq!(
+            missing_sequences(1..8, &[&sequences(&[1, 2]), &sequences(&[4, 7])]),
+            sequences(&[3, 5, 6])
+        );
+    }
+}
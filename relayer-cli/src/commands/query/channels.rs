@@ -7,13 +7,16 @@ use serde::Serialize;
 use ibc::core::ics02_client::client_state::ClientState;
 use ibc::core::ics04_channel::channel::{ChannelEnd, State};
 use ibc::core::ics24_host::identifier::{ChainId, ChannelId, ConnectionId, PortChannelId, PortId};
+use ibc::timestamp::Timestamp;
 use ibc_relayer::chain::handle::{BaseChainHandle, ChainHandle};
 use ibc_relayer::chain::requests::{
     IncludeProof, PageRequest, QueryChannelRequest, QueryChannelsRequest, QueryClientStateRequest,
     QueryConnectionRequest, QueryHeight,
 };
+use ibc_relayer::channel::age::StateAge;
 use ibc_relayer::registry::Registry;
 
+use crate::cli_utils::{resolve_chain_filter, resolve_chain_id};
 use crate::commands::query::channel_ends::ChannelEnds;
 use crate::conclude::Output;
 use crate::prelude::*;
@@ -47,6 +50,36 @@ pub struct QueryChannelsCmd {
         help = "Show the counterparty chain, port, and channel"
     )]
     show_counterparty: bool,
+
+    #[clap(
+        long = "state",
+        value_name = "STATE",
+        parse(try_from_str = parse_channel_state),
+        help = "Only show the channels in this state: init, tryopen, open or closed"
+    )]
+    state: Option<State>,
+
+    #[clap(
+        long = "with-age",
+        conflicts_with_all = &["verbose", "show-counterparty"],
+        help = "Show the state of each channel and since when it is in this state, \
+                estimated from the transaction which brought it to this state, if the node indexed it"
+    )]
+    with_age: bool,
+}
+
+/// Parses a channel state given on the command line, case-insensitively.
+fn parse_channel_state(input: &str) -> Result<State, String> {
+    match input.to_lowercase().replace(&['-', '_'][..], "").as_str() {
+        "init" => Ok(State::Init),
+        "tryopen" => Ok(State::TryOpen),
+        "open" => Ok(State::Open),
+        "closed" => Ok(State::Closed),
+        _ => Err(format!(
+            "invalid channel state '{}', expected one of: init, tryopen, open, closed",
+            input
+        )),
+    }
 }
 
 fn run_query_channels<Chain: ChainHandle>(
@@ -54,22 +87,35 @@ fn run_query_channels<Chain: ChainHandle>(
 ) -> Result<QueryChannelsOutput, Box<dyn std::error::Error>> {
     debug!("Options: {:?}", cmd);
 
-    let mut output = match (cmd.verbose, cmd.show_counterparty) {
-        (true, _) => QueryChannelsOutput::verbose(),
-        (false, true) => QueryChannelsOutput::pretty(),
-        (false, false) => QueryChannelsOutput::summary(),
+    let mut output = match (cmd.with_age, cmd.verbose, cmd.show_counterparty) {
+        (true, _, _) => QueryChannelsOutput::with_age(),
+        (false, true, _) => QueryChannelsOutput::verbose(),
+        (false, false, true) => QueryChannelsOutput::pretty(),
+        (false, false, false) => QueryChannelsOutput::summary(),
     };
 
     let config = app_config();
-    let chain_id = cmd.chain_id.clone();
+    let chain_id = resolve_chain_id(&config, &cmd.chain_id)?;
+    let dst_chain_id = cmd
+        .dst_chain_id
+        .as_ref()
+        .map(|dst_chain_id| resolve_chain_filter(&config, dst_chain_id));
 
     let mut registry = <Registry<Chain>>::new((*config).clone());
-    let chain = registry.get_or_spawn(&cmd.chain_id)?;
+    let chain = registry.get_or_spawn(&chain_id)?;
     let chain_height = chain.query_latest_height()?;
 
-    let identified_channels = chain.query_channels(QueryChannelsRequest {
-        pagination: Some(PageRequest::all()),
-    })?;
+    let identified_channels = chain
+        .query_channels(QueryChannelsRequest {
+            pagination: Some(PageRequest::all()),
+        })?
+        .into_iter()
+        .filter(|channel| {
+            cmd.state
+                .map_or(true, |state| channel.channel_end.state_matches(&state))
+        });
+
+    let now = Timestamp::now();
 
     for identified_channel in identified_channels {
         let port_id = identified_channel.port_id;
@@ -100,7 +146,7 @@ fn run_query_channels<Chain: ChainHandle>(
 
         // If a counterparty chain is specified as a filter, check and skip the
         // channel if required.
-        if cmd.show_counterparty || cmd.dst_chain_id.is_some() {
+        if cmd.show_counterparty || dst_chain_id.is_some() {
             let (connection_end, _) = chain.query_connection(
                 QueryConnectionRequest {
                     connection_id: connection_id.clone(),
@@ -119,7 +165,7 @@ fn run_query_channels<Chain: ChainHandle>(
             )?;
             let cid = client_state.chain_id().clone();
 
-            if let Some(dst_chain_id) = &cmd.dst_chain_id {
+            if let Some(dst_chain_id) = &dst_chain_id {
                 if cid != *dst_chain_id {
                     continue;
                 }
@@ -167,6 +213,25 @@ fn run_query_channels<Chain: ChainHandle>(
                     port_id,
                 });
             }
+            QueryChannelsOutput::WithAge(_) => {
+                let state = channel_end.state;
+
+                let age = StateAge::query(&chain, &port_id, &channel_id, state, now)
+                    .unwrap_or_else(|e| {
+                        warn!(
+                            "failed to estimate since when channel {}/{} is {}: {}",
+                            port_id, channel_id, state, e
+                        );
+                        None
+                    });
+
+                output.push_with_age(ChannelWithAge {
+                    port_id,
+                    channel_id,
+                    state,
+                    age,
+                });
+            }
         }
     }
 
@@ -282,12 +347,22 @@ struct PrettyOutput {
     chain_id_b: Option<ChainId>,
 }
 
+/// A channel with its state and, if it was found, when it reached this state.
+#[derive(Serialize, Debug)]
+struct ChannelWithAge {
+    port_id: PortId,
+    channel_id: ChannelId,
+    state: State,
+    age: Option<StateAge>,
+}
+
 #[derive(Serialize)]
 #[serde(untagged)]
 enum QueryChannelsOutput {
     Verbose(Vec<ChannelEnds>),
     Summary(Vec<PortChannelId>),
     Pretty(Vec<PrettyOutput>),
+    WithAge(Vec<ChannelWithAge>),
 }
 
 impl QueryChannelsOutput {
@@ -303,6 +378,10 @@ impl QueryChannelsOutput {
         Self::Pretty(Vec::new())
     }
 
+    fn with_age() -> Self {
+        Self::WithAge(Vec::new())
+    }
+
     fn push_pretty(&mut self, pe: PrettyOutput) {
         match self {
             Self::Pretty(pes) => pes.push(pe),
@@ -314,6 +393,10 @@ impl QueryChannelsOutput {
                 Output::error("PrettyOutput and QueryChannelsOutput::Summary are incompatible")
                     .exit()
             }
+            Self::WithAge(_) => {
+                Output::error("PrettyOutput and QueryChannelsOutput::WithAge are incompatible")
+                    .exit()
+            }
         }
     }
 
@@ -327,6 +410,10 @@ impl QueryChannelsOutput {
                 Output::error("ChannelEnds and QueryChannelsOutput::Summary are incompatible")
                     .exit()
             }
+            Self::WithAge(_) => {
+                Output::error("ChannelEnds and QueryChannelsOutput::WithAge are incompatible")
+                    .exit()
+            }
         }
     }
 
@@ -341,6 +428,28 @@ impl QueryChannelsOutput {
                     .exit()
             }
             Self::Summary(pcs) => pcs.push(pc),
+            Self::WithAge(_) => {
+                Output::error("PortChannelId and QueryChannelsOutput::WithAge are incompatible")
+                    .exit()
+            }
+        }
+    }
+
+    fn push_with_age(&mut self, channel: ChannelWithAge) {
+        match self {
+            Self::Pretty(_) => {
+                Output::error("ChannelWithAge and QueryChannelsOutput::Pretty are incompatible")
+                    .exit()
+            }
+            Self::Verbose(_) => {
+                Output::error("ChannelWithAge and QueryChannelsOutput::Verbose are incompatible")
+                    .exit()
+            }
+            Self::Summary(_) => {
+                Output::error("ChannelWithAge and QueryChannelsOutput::Summary are incompatible")
+                    .exit()
+            }
+            Self::WithAge(channels) => channels.push(channel),
         }
     }
 }
@@ -350,6 +459,14 @@ impl Debug for QueryChannelsOutput {
         match self {
             QueryChannelsOutput::Verbose(output) => write!(f, "{:#?}", output),
             QueryChannelsOutput::Summary(output) => write!(f, "{:#?}", output),
+            QueryChannelsOutput::WithAge(output) => output.iter().try_for_each(|channel| {
+                write!(f, "\n{}/{}: ", channel.port_id, channel.channel_id)?;
+
+                match &channel.age {
+                    Some(age) => write!(f, "{}", age),
+                    None => write!(f, "{} since an unknown time", channel.state),
+                }
+            }),
             QueryChannelsOutput::Pretty(output) => {
                 output.iter().try_for_each(|pretty_print| {
                     write!(
@@ -379,6 +496,8 @@ impl Debug for QueryChannelsOutput {
 mod tests {
     use super::QueryChannelsCmd;
 
+    use ibc::core::ics04_channel::channel::State;
+
     use abscissa_core::clap::Parser;
     use ibc::core::ics24_host::identifier::ChainId;
 
@@ -390,6 +509,8 @@ mod tests {
                 verbose: false,
                 dst_chain_id: None,
                 show_counterparty: false,
+                state: None,
+                with_age: false,
             },
             QueryChannelsCmd::parse_from(&["test", "--chain", "chain_id"])
         )
@@ -403,6 +524,8 @@ mod tests {
                 verbose: true,
                 dst_chain_id: None,
                 show_counterparty: false,
+                state: None,
+                with_age: false,
             },
             QueryChannelsCmd::parse_from(&["test", "--chain", "chain_id", "--verbose"])
         )
@@ -416,6 +539,8 @@ mod tests {
                 verbose: false,
                 dst_chain_id: Some(ChainId::from_string("counterparty_chain")),
                 show_counterparty: false,
+                state: None,
+                with_age: false,
             },
             QueryChannelsCmd::parse_from(&[
                 "test",
@@ -439,7 +564,9 @@ mod tests {
                 chain_id: ChainId::from_string("chain_id"),
                 verbose: false,
                 dst_chain_id: None,
-                show_counterparty: true
+                show_counterparty: true,
+                state: None,
+                with_age: false,
             },
             QueryChannelsCmd::parse_from(&["test", "--chain", "chain_id", "--show-counterparty",])
         )
@@ -452,7 +579,9 @@ mod tests {
                 chain_id: ChainId::from_string("chain_id"),
                 verbose: false,
                 dst_chain_id: Some(ChainId::from_string("counterparty_chain")),
-                show_counterparty: true
+                show_counterparty: true,
+                state: None,
+                with_age: false,
             },
             QueryChannelsCmd::parse_from(&[
                 "test",
@@ -472,7 +601,9 @@ mod tests {
                 chain_id: ChainId::from_string("chain_id"),
                 verbose: true,
                 dst_chain_id: None,
-                show_counterparty: true
+                show_counterparty: true,
+                state: None,
+                with_age: false,
             },
             QueryChannelsCmd::parse_from(&[
                 "test",
@@ -483,4 +614,65 @@ mod tests {
             ])
         )
     }
+    #[test]
+    fn test_query_channels_state_with_age() {
+        assert_eq!(
+            QueryChannelsCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                verbose: false,
+                dst_chain_id: None,
+                show_counterparty: false,
+                state: Some(State::TryOpen),
+                with_age: true,
+            },
+            QueryChannelsCmd::parse_from(&[
+                "test",
+                "--chain",
+                "chain_id",
+                "--state",
+                "TRYOPEN",
+                "--with-age",
+            ])
+        )
+    }
+
+    #[test]
+    fn test_query_channels_state() {
+        for (input, state) in [
+            ("init", State::Init),
+            ("try-open", State::TryOpen),
+            ("Open", State::Open),
+            ("closed", State::Closed),
+        ] {
+            assert_eq!(
+                QueryChannelsCmd::parse_from(&["test", "--chain", "chain_id", "--state", input])
+                    .state,
+                Some(state)
+            )
+        }
+    }
+
+    #[test]
+    fn test_query_channels_invalid_state() {
+        assert!(QueryChannelsCmd::try_parse_from(&[
+            "test",
+            "--chain",
+            "chain_id",
+            "--state",
+            "uninitialized"
+        ])
+        .is_err())
+    }
+
+    #[test]
+    fn test_query_channels_with_age_verbose() {
+        assert!(QueryChannelsCmd::try_parse_from(&[
+            "test",
+            "--chain",
+            "chain_id",
+            "--with-age",
+            "--verbose"
+        ])
+        .is_err())
+    }
 }
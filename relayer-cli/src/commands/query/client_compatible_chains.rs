@@ -0,0 +1,336 @@
+use core::time::Duration;
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use serde::Serialize;
+
+use ibc::core::ics02_client::client_state::ClientState;
+use ibc::core::ics02_client::client_type::ClientType;
+use ibc::core::ics24_host::identifier::{ChainId, ClientId};
+use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::chain::requests::{PageRequest, QueryClientStatesRequest};
+use ibc_relayer::config::{ChainConfig, Config};
+
+use crate::cli_utils::{find_chain_config, spawn_chain_runtime};
+use crate::conclude::{json, Output};
+use crate::error::Error;
+use crate::prelude::*;
+
+/// Allowed client type which lets any type of client be created on a chain.
+const ALL_CLIENTS: &str = "*";
+
+/// Whether a client of type `client_type` may be created on a chain with the given `allowed_clients`.
+fn is_allowed(allowed_clients: &[String], client_type: ClientType) -> bool {
+    allowed_clients
+        .iter()
+        .any(|allowed| allowed == client_type.as_str() || allowed == ALL_CLIENTS)
+}
+
+/// Whether the clients of the target chain can be trusted for some time before they expire.
+#[derive(Debug, Serialize)]
+struct TrustCheck {
+    unbonding_period: Duration,
+    /// The trusting period of the clients, as set in the configuration
+    /// of the target chain or defaulting to 2/3 of its unbonding period.
+    trusting_period: Duration,
+    compatible: bool,
+    reason: Option<String>,
+}
+
+impl TrustCheck {
+    fn new(unbonding_period: Duration, trusting_period: Option<Duration>) -> Self {
+        let trusting_period = trusting_period.unwrap_or(2 * unbonding_period / 3);
+
+        let reason = if trusting_period.is_zero() {
+            Some("the trusting period is zero".to_string())
+        } else if trusting_period >= unbonding_period {
+            Some(format!(
+                "the trusting period ({}) is not smaller than the unbonding period ({})",
+                humantime::format_duration(trusting_period),
+                humantime::format_duration(unbonding_period)
+            ))
+        } else {
+            None
+        };
+
+        Self {
+            unbonding_period,
+            trusting_period,
+            compatible: reason.is_none(),
+            reason,
+        }
+    }
+}
+
+/// Whether a chain could host a new client of the target chain.
+#[derive(Debug, Serialize)]
+struct HostCompatibility {
+    host_chain: ChainId,
+    /// The clients of the target chain which already exist on the host chain.
+    existing_clients: Vec<ClientId>,
+    client_type_allowed: bool,
+    compatible: bool,
+    /// The reason why the host chain could not be queried.
+    error: Option<String>,
+}
+
+impl HostCompatibility {
+    fn new(
+        host_chain: ChainId,
+        existing_clients: Vec<ClientId>,
+        client_type_allowed: bool,
+        trust: &TrustCheck,
+    ) -> Self {
+        Self {
+            host_chain,
+            existing_clients,
+            client_type_allowed,
+            compatible: client_type_allowed && trust.compatible,
+            error: None,
+        }
+    }
+
+    fn failed(host_chain: ChainId, error: Error) -> Self {
+        Self {
+            host_chain,
+            existing_clients: vec![],
+            client_type_allowed: false,
+            compatible: false,
+            error: Some(error.to_string()),
+        }
+    }
+}
+
+/// The result of the `query client compatible-chains` command.
+#[derive(Debug, Serialize)]
+struct CompatibleChains {
+    target_chain: ChainId,
+    client_type: ClientType,
+    trust: TrustCheck,
+    hosts: Vec<HostCompatibility>,
+}
+
+/// Identify the configured chains on which a new client of the target chain could be created.
+///
+/// For each of the other chains in the configuration, checks whether the chain already
+/// hosts clients of the target chain and whether its `allowed_clients` parameter lets
+/// a client of type `07-tendermint` be created. The trusting period of the new clients
+/// must also be smaller than the unbonding period of the target chain.
+///
+/// `query client compatible-chains --target-chain <TARGET_CHAIN_ID>`
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct QueryClientCompatibleChainsCmd {
+    #[clap(
+        long = "target-chain",
+        required = true,
+        value_name = "TARGET_CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain for which a new client would be created"
+    )]
+    target_chain_id: ChainId,
+}
+
+impl QueryClientCompatibleChainsCmd {
+    fn execute(&self) -> Result<CompatibleChains, Error> {
+        let config = app_config();
+
+        let target_config = find_chain_config(&config, &self.target_chain_id)?;
+        let target_chain = spawn_chain_runtime(&config, &target_config.id)?;
+
+        let unbonding_period = target_chain
+            .query_unbonding_period()
+            .map_err(Error::relayer)?;
+
+        let trust = TrustCheck::new(unbonding_period, target_config.trusting_period);
+        let client_type = ClientType::Tendermint;
+
+        let hosts = config
+            .chains
+            .iter()
+            .filter(|host_config| host_config.id != target_config.id)
+            .map(|host_config| {
+                check_host(&config, host_config, &target_config.id, client_type, &trust)
+                    .unwrap_or_else(|e| {
+                        warn!("failed to query chain {}: {}", host_config.id, e);
+                        HostCompatibility::failed(host_config.id.clone(), e)
+                    })
+            })
+            .collect();
+
+        Ok(CompatibleChains {
+            target_chain: target_config.id.clone(),
+            client_type,
+            trust,
+            hosts,
+        })
+    }
+}
+
+/// Checks whether the chain configured by `host_config` could host a new client of the target chain.
+fn check_host(
+    config: &Config,
+    host_config: &ChainConfig,
+    target_chain_id: &ChainId,
+    client_type: ClientType,
+    trust: &TrustCheck,
+) -> Result<HostCompatibility, Error> {
+    let host_chain = spawn_chain_runtime(config, &host_config.id)?;
+
+    let existing_clients = host_chain
+        .query_clients(QueryClientStatesRequest {
+            pagination: Some(PageRequest::all()),
+        })
+        .map_err(Error::relayer)?
+        .into_iter()
+        .filter(|client| &client.client_state.chain_id() == target_chain_id)
+        .map(|client| client.client_id)
+        .collect();
+
+    let allowed_clients = host_chain.query_allowed_clients().map_err(Error::relayer)?;
+
+    Ok(HostCompatibility::new(
+        host_config.id.clone(),
+        existing_clients,
+        is_allowed(&allowed_clients, client_type),
+        trust,
+    ))
+}
+
+impl Runnable for QueryClientCompatibleChainsCmd {
+    fn run(&self) {
+        let result = match self.execute() {
+            Ok(result) => result,
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        };
+
+        if json() {
+            Output::success(result).exit()
+        }
+
+        let yes_no = |b: bool| if b { "yes" } else { "no" };
+
+        let mut lines = vec![format!(
+            "target chain {} ({}): unbonding period {}, trusting period {}{}",
+            result.target_chain,
+            result.client_type.as_str(),
+            humantime::format_duration(result.trust.unbonding_period),
+            humantime::format_duration(result.trust.trusting_period),
+            match &result.trust.reason {
+                Some(reason) => format!(", incompatible: {}", reason),
+                None => String::new(),
+            }
+        )];
+
+        let rows = result
+            .hosts
+            .iter()
+            .map(|host| {
+                let existing_clients = if host.existing_clients.is_empty() {
+                    "-".to_string()
+                } else {
+                    host.existing_clients
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(",")
+                };
+
+                match &host.error {
+                    Some(error) => [
+                        host.host_chain.to_string(),
+                        "?".to_string(),
+                        "?".to_string(),
+                        format!("no ({})", error),
+                    ],
+                    None => [
+                        host.host_chain.to_string(),
+                        existing_clients,
+                        yes_no(host.client_type_allowed).to_string(),
+                        yes_no(host.compatible).to_string(),
+                    ],
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let header = [
+            "HOST CHAIN".to_string(),
+            "EXISTING CLIENTS".to_string(),
+            "CLIENT TYPE ALLOWED".to_string(),
+            "COMPATIBLE".to_string(),
+        ];
+
+        let mut widths = [0; 4];
+        for row in core::iter::once(&header).chain(&rows) {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+
+        for row in core::iter::once(&header).chain(&rows) {
+            let cells = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect::<Vec<_>>();
+
+            lines.push(cells.join("  ").trim_end().to_string());
+        }
+
+        Output::success_msg(lines.join("\n")).exit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_allowed, QueryClientCompatibleChainsCmd, TrustCheck};
+
+    use core::time::Duration;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics02_client::client_type::ClientType;
+    use ibc::core::ics24_host::identifier::ChainId;
+
+    #[test]
+    fn test_query_client_compatible_chains() {
+        assert_eq!(
+            QueryClientCompatibleChainsCmd {
+                target_chain_id: ChainId::from_string("chain_id")
+            },
+            QueryClientCompatibleChainsCmd::parse_from(&["test", "--target-chain", "chain_id"])
+        )
+    }
+
+    #[test]
+    fn test_query_client_compatible_chains_no_target_chain() {
+        assert!(QueryClientCompatibleChainsCmd::try_parse_from(&["test"]).is_err())
+    }
+
+    #[test]
+    fn test_is_allowed() {
+        let allowed = |clients: &[&str]| {
+            let clients = clients.iter().map(ToString::to_string).collect::<Vec<_>>();
+            is_allowed(&clients, ClientType::Tendermint)
+        };
+
+        assert!(allowed(&["06-solomachine", "07-tendermint"]));
+        assert!(allowed(&["*"]));
+        assert!(!allowed(&["06-solomachine", "09-localhost"]));
+        assert!(!allowed(&[]));
+    }
+
+    #[test]
+    fn test_trust_check() {
+        let unbonding_period = Duration::from_secs(21 * 24 * 3600);
+
+        let trust = TrustCheck::new(unbonding_period, None);
+        assert!(trust.compatible);
+        assert_eq!(trust.trusting_period, Duration::from_secs(14 * 24 * 3600));
+
+        let trust = TrustCheck::new(unbonding_period, Some(Duration::from_secs(3600)));
+        assert!(trust.compatible);
+
+        assert!(!TrustCheck::new(unbonding_period, Some(unbonding_period)).compatible);
+        assert!(!TrustCheck::new(unbonding_period, Some(Duration::ZERO)).compatible);
+        assert!(!TrustCheck::new(Duration::ZERO, None).compatible);
+    }
+}
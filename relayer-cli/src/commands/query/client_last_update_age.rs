@@ -0,0 +1,344 @@
+use core::time::Duration;
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use serde::Serialize;
+
+use ibc::core::ics02_client::client_state::{AnyClientState, ClientState};
+use ibc::core::ics24_host::identifier::{ChainId, ClientId};
+use ibc::timestamp::Timestamp;
+use ibc::Height;
+use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::chain::requests::{
+    IncludeProof, PageRequest, QueryClientStateRequest, QueryClientStatesRequest,
+    QueryConsensusStateRequest, QueryHeight,
+};
+
+use crate::cli_utils::spawn_chain_runtime;
+use crate::conclude::{json, Output};
+use crate::error::Error;
+use crate::prelude::*;
+
+/// How stale a client is, i.e. how long ago it was last updated.
+#[derive(Debug, Serialize)]
+struct ClientAge {
+    client_id: ClientId,
+    /// The chain tracked by the client.
+    chain_id: ChainId,
+    latest_height: Height,
+    last_update: Timestamp,
+    age_secs: u64,
+    /// Fraction of the trusting period of the client elapsed since its last update, in percent.
+    trust_period_consumed: f64,
+    /// Time remaining until the client expires if it is not updated.
+    remaining_secs: u64,
+}
+
+impl ClientAge {
+    fn new(
+        client_id: ClientId,
+        client_state: &AnyClientState,
+        last_update: Timestamp,
+        now: Timestamp,
+    ) -> Self {
+        let age = now.duration_since(&last_update).unwrap_or_default();
+        let trusting_period = client_state.trusting_period();
+
+        Self {
+            client_id,
+            chain_id: client_state.chain_id(),
+            latest_height: client_state.latest_height(),
+            last_update,
+            age_secs: age.as_secs(),
+            trust_period_consumed: trust_period_consumed(age, trusting_period),
+            remaining_secs: trusting_period.saturating_sub(age).as_secs(),
+        }
+    }
+
+    fn age(&self) -> Duration {
+        Duration::from_secs(self.age_secs)
+    }
+
+    fn remaining(&self) -> Duration {
+        Duration::from_secs(self.remaining_secs)
+    }
+}
+
+/// Fraction of the `trusting_period` of a client elapsed after `age`, in percent.
+fn trust_period_consumed(age: Duration, trusting_period: Duration) -> f64 {
+    if trusting_period.is_zero() {
+        return 100.0;
+    }
+
+    age.as_secs_f64() / trusting_period.as_secs_f64() * 100.0
+}
+
+/// Keeps the clients older than `older_than`, if given, and sorts them from the stalest.
+fn stalest_first(mut ages: Vec<ClientAge>, older_than: Option<Duration>) -> Vec<ClientAge> {
+    if let Some(older_than) = older_than {
+        ages.retain(|age| age.age() > older_than);
+    }
+
+    ages.sort_by(|a, b| b.age_secs.cmp(&a.age_secs));
+    ages
+}
+
+/// Show how long ago clients were last updated, and how long until they expire.
+///
+/// The age of a client is the time elapsed since the timestamp of its consensus
+/// state at its latest height, as measured against the local time.
+///
+/// `query client last-update-age --chain <CHAIN_ID> (--client <CLIENT_ID> | --all)
+///     [--older-than <DURATION>]`
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct QueryClientLastUpdateAgeCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain hosting the clients"
+    )]
+    chain_id: ChainId,
+
+    #[clap(
+        long = "client",
+        visible_alias = "client-id",
+        required = true,
+        value_name = "CLIENT_ID",
+        group = "clients",
+        help_heading = "REQUIRED",
+        help = "Identifier of the client to query"
+    )]
+    client_id: Option<ClientId>,
+
+    #[clap(
+        long = "all",
+        required = true,
+        group = "clients",
+        help_heading = "REQUIRED",
+        help = "Query all the clients hosted on the chain"
+    )]
+    all: bool,
+
+    #[clap(
+        long = "older-than",
+        value_name = "DURATION",
+        help = "Only show the clients last updated longer ago than this, eg. '1h' or '2days'"
+    )]
+    older_than: Option<humantime::Duration>,
+}
+
+impl QueryClientLastUpdateAgeCmd {
+    fn execute(&self) -> Result<Vec<ClientAge>, Error> {
+        let config = app_config();
+
+        let chain = spawn_chain_runtime(&config, &self.chain_id)?;
+
+        let clients = match &self.client_id {
+            Some(client_id) => {
+                let (client_state, _) = chain
+                    .query_client_state(
+                        QueryClientStateRequest {
+                            client_id: client_id.clone(),
+                            height: QueryHeight::Latest,
+                        },
+                        IncludeProof::No,
+                    )
+                    .map_err(Error::relayer)?;
+
+                vec![(client_id.clone(), client_state)]
+            }
+            None => chain
+                .query_clients(QueryClientStatesRequest {
+                    pagination: Some(PageRequest::all()),
+                })
+                .map_err(Error::relayer)?
+                .into_iter()
+                .map(|client| (client.client_id, client.client_state))
+                .collect(),
+        };
+
+        let now = Timestamp::now();
+        let mut ages = Vec::with_capacity(clients.len());
+
+        for (client_id, client_state) in clients {
+            let consensus_state = chain.query_consensus_state(
+                QueryConsensusStateRequest {
+                    client_id: client_id.clone(),
+                    consensus_height: client_state.latest_height(),
+                    query_height: QueryHeight::Latest,
+                },
+                IncludeProof::No,
+            );
+
+            let last_update = match consensus_state {
+                Ok((consensus_state, _)) => consensus_state.timestamp(),
+                // Only fail when querying a single client, and skip the others
+                Err(e) if self.client_id.is_some() => return Err(Error::relayer(e)),
+                Err(e) => {
+                    warn!(
+                        "skipping client {}, failed to query its latest consensus state: {}",
+                        client_id, e
+                    );
+                    continue;
+                }
+            };
+
+            let age = ClientAge::new(client_id, &client_state, last_update, now);
+
+            ibc_relayer::telemetry!(
+                client_age,
+                &self.chain_id,
+                &age.client_id,
+                age.age(),
+                age.remaining()
+            );
+
+            ages.push(age);
+        }
+
+        Ok(stalest_first(ages, self.older_than.as_deref().copied()))
+    }
+}
+
+impl Runnable for QueryClientLastUpdateAgeCmd {
+    fn run(&self) {
+        let ages = match self.execute() {
+            Ok(ages) => ages,
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        };
+
+        if json() {
+            Output::success(ages).exit()
+        }
+
+        let lines = ages
+            .iter()
+            .map(|age| {
+                format!(
+                    "{} ({}): last updated {} ago at height {}, {:.1}% of the trusting period consumed, expires in {}",
+                    age.client_id,
+                    age.chain_id,
+                    humantime::format_duration(age.age()),
+                    age.latest_height,
+                    age.trust_period_consumed,
+                    humantime::format_duration(age.remaining()),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        Output::success_msg(lines.join("\n")).exit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{stalest_first, trust_period_consumed, ClientAge, QueryClientLastUpdateAgeCmd};
+
+    use core::str::FromStr;
+    use core::time::Duration;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::{ChainId, ClientId};
+    use ibc::timestamp::Timestamp;
+    use ibc::Height;
+
+    fn client_age(client: &str, age_secs: u64) -> ClientAge {
+        ClientAge {
+            client_id: ClientId::from_str(client).unwrap(),
+            chain_id: ChainId::from_string("chain_id"),
+            latest_height: Height::new(0, 10).unwrap(),
+            last_update: Timestamp::none(),
+            age_secs,
+            trust_period_consumed: 0.0,
+            remaining_secs: 0,
+        }
+    }
+
+    #[test]
+    fn test_query_client_last_update_age() {
+        assert_eq!(
+            QueryClientLastUpdateAgeCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                client_id: Some(ClientId::from_str("07-tendermint-0").unwrap()),
+                all: false,
+                older_than: None,
+            },
+            QueryClientLastUpdateAgeCmd::parse_from(&[
+                "test",
+                "--chain",
+                "chain_id",
+                "--client",
+                "07-tendermint-0"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_query_client_last_update_age_all() {
+        let cmd = QueryClientLastUpdateAgeCmd::parse_from(&[
+            "test",
+            "--chain",
+            "chain_id",
+            "--all",
+            "--older-than",
+            "1h",
+        ]);
+
+        assert!(cmd.all);
+        assert_eq!(cmd.client_id, None);
+        assert_eq!(
+            cmd.older_than.as_deref().copied(),
+            Some(Duration::from_secs(3600))
+        );
+    }
+
+    #[test]
+    fn test_query_client_last_update_age_client_or_all() {
+        assert!(
+            QueryClientLastUpdateAgeCmd::try_parse_from(&["test", "--chain", "chain_id"]).is_err()
+        );
+
+        assert!(QueryClientLastUpdateAgeCmd::try_parse_from(&[
+            "test",
+            "--chain",
+            "chain_id",
+            "--client",
+            "07-tendermint-0",
+            "--all"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_trust_period_consumed() {
+        let trusting_period = Duration::from_secs(64000);
+
+        let consumed = trust_period_consumed(Duration::from_secs(16000), trusting_period);
+        assert!((consumed - 25.0).abs() < f64::EPSILON);
+
+        // An expired client has consumed more than its trusting period
+        assert!(trust_period_consumed(Duration::from_secs(100_000), trusting_period) > 100.0);
+
+        assert!(
+            (trust_period_consumed(Duration::ZERO, Duration::ZERO) - 100.0).abs() < f64::EPSILON
+        );
+    }
+
+    #[test]
+    fn test_stalest_first() {
+        let ages = vec![
+            client_age("07-tendermint-0", 10),
+            client_age("07-tendermint-1", 3000),
+            client_age("07-tendermint-2", 200),
+        ];
+
+        let sorted = stalest_first(ages, Some(Duration::from_secs(100)))
+            .into_iter()
+            .map(|age| age.client_id.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(sorted, vec!["07-tendermint-1", "07-tendermint-2"]);
+    }
+}
@@ -7,7 +7,7 @@ use ibc::core::ics02_client::client_state::ClientState;
 use ibc::core::ics24_host::identifier::{ChainId, ClientId};
 use ibc_relayer::chain::requests::{PageRequest, QueryClientStatesRequest};
 
-use crate::cli_utils::spawn_chain_runtime;
+use crate::cli_utils::{resolve_chain_filter, spawn_chain_runtime};
 use crate::conclude::{exit_with_unrecoverable_error, Output};
 use crate::error::Error;
 use crate::prelude::*;
@@ -63,7 +63,12 @@ impl Runnable for QueryAllClientsCmd {
 
         match res {
             Ok(clients) => {
-                match self.src_chain_id.clone() {
+                let src_chain_id = self
+                    .src_chain_id
+                    .as_ref()
+                    .map(|src_chain_id| resolve_chain_filter(&config, src_chain_id));
+
+                match src_chain_id {
                     None => {
                         match self.omit_chain_ids {
                             true => {
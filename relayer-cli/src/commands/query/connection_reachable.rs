@@ -0,0 +1,391 @@
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use serde::Serialize;
+
+use ibc::core::ics02_client::client_state::ClientState;
+use ibc::core::ics03_connection::connection::ConnectionEnd;
+use ibc::core::ics24_host::identifier::{ChainId, ClientId, ConnectionId};
+use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::chain::requests::{
+    IncludeProof, QueryClientStateRequest, QueryConnectionRequest, QueryHeight,
+};
+
+use crate::cli_utils::spawn_chain_runtime;
+use crate::conclude::{exit_with_unrecoverable_error, json, Output};
+use crate::prelude::*;
+
+/// The outcome of a single check performed by the `query connection reachable` command.
+#[derive(Debug, Serialize)]
+struct Check {
+    name: String,
+    passed: bool,
+    /// Diagnostic information, if the check failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    diagnostic: Option<String>,
+}
+
+impl Check {
+    fn new(name: impl ToString, result: Result<(), String>) -> Self {
+        Self {
+            name: name.to_string(),
+            passed: result.is_ok(),
+            diagnostic: result.err(),
+        }
+    }
+}
+
+/// The data structure that represents the arguments when invoking the `query connection reachable` CLI command.
+///
+/// The command has the following format:
+///
+/// `query connection reachable --a-chain <A_CHAIN_ID> --b-chain <B_CHAIN_ID> --a-connection <A_CONNECTION_ID>`
+///
+/// The command checks that the connection is open on both chains, that the client
+/// identifiers of both connection ends cross-reference each other, that the clients
+/// track the counterparty chain, and that the RPC endpoints of both chains are reachable.
+/// The outcome of each check is displayed, along with diagnostic information for the
+/// checks that failed.
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct QueryConnectionReachableCmd {
+    #[clap(
+        long = "a-chain",
+        alias = "chain-a",
+        required = true,
+        value_name = "A_CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain hosting the connection end to check"
+    )]
+    a_chain_id: ChainId,
+
+    #[clap(
+        long = "b-chain",
+        alias = "chain-b",
+        required = true,
+        value_name = "B_CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the counterparty chain"
+    )]
+    b_chain_id: ChainId,
+
+    #[clap(
+        long = "a-connection",
+        visible_alias = "a-conn",
+        alias = "connection-id-a",
+        required = true,
+        value_name = "A_CONNECTION_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the connection on chain A"
+    )]
+    a_connection_id: ConnectionId,
+}
+
+impl QueryConnectionReachableCmd {
+    fn execute(&self) -> Vec<Check> {
+        let config = app_config();
+
+        let chain_a = spawn_chain_runtime(&config, &self.a_chain_id)
+            .unwrap_or_else(exit_with_unrecoverable_error);
+        let chain_b = spawn_chain_runtime(&config, &self.b_chain_id)
+            .unwrap_or_else(exit_with_unrecoverable_error);
+
+        let mut checks = vec![
+            Check::new(
+                format!("chain '{}' RPC endpoint is reachable", self.a_chain_id),
+                check_reachable(&chain_a),
+            ),
+            Check::new(
+                format!("chain '{}' RPC endpoint is reachable", self.b_chain_id),
+                check_reachable(&chain_b),
+            ),
+        ];
+
+        let connection_a = query_connection(&chain_a, &self.a_connection_id);
+        checks.push(Check::new(
+            format!(
+                "connection '{}' is open on chain '{}'",
+                self.a_connection_id, self.a_chain_id
+            ),
+            check_open(&connection_a),
+        ));
+
+        let connection_a = match connection_a {
+            Ok(connection_a) => connection_a,
+            Err(_) => return checks,
+        };
+
+        let b_connection_id = match connection_a.counterparty().connection_id() {
+            Some(b_connection_id) => b_connection_id.clone(),
+            None => {
+                checks.push(Check::new(
+                    format!(
+                        "counterparty connection is open on chain '{}'",
+                        self.b_chain_id
+                    ),
+                    Err(format!(
+                        "connection '{}' on chain '{}' has no counterparty connection identifier",
+                        self.a_connection_id, self.a_chain_id
+                    )),
+                ));
+                return checks;
+            }
+        };
+
+        let connection_b = query_connection(&chain_b, &b_connection_id);
+        checks.push(Check::new(
+            format!(
+                "connection '{}' is open on chain '{}'",
+                b_connection_id, self.b_chain_id
+            ),
+            check_open(&connection_b),
+        ));
+
+        let connection_b = match connection_b {
+            Ok(connection_b) => connection_b,
+            Err(_) => return checks,
+        };
+
+        checks.push(Check::new(
+            "connection ends cross-reference each other",
+            check_cross_reference(
+                (&self.a_connection_id, &connection_a),
+                (&b_connection_id, &connection_b),
+            ),
+        ));
+
+        checks.push(Check::new(
+            format!(
+                "client '{}' on chain '{}' tracks chain '{}'",
+                connection_a.client_id(),
+                self.a_chain_id,
+                self.b_chain_id
+            ),
+            check_client_tracks(&chain_a, connection_a.client_id(), &chain_b.id()),
+        ));
+
+        checks.push(Check::new(
+            format!(
+                "client '{}' on chain '{}' tracks chain '{}'",
+                connection_b.client_id(),
+                self.b_chain_id,
+                self.a_chain_id
+            ),
+            check_client_tracks(&chain_b, connection_b.client_id(), &chain_a.id()),
+        ));
+
+        checks
+    }
+}
+
+impl Runnable for QueryConnectionReachableCmd {
+    fn run(&self) {
+        debug!("Options: {:?}", self);
+
+        let checks = self.execute();
+        let passed = checks.iter().all(|check| check.passed);
+
+        if json() {
+            if passed {
+                Output::success(checks).exit()
+            } else {
+                Output::with_error().with_result(checks).exit()
+            }
+        }
+
+        let report = checks
+            .iter()
+            .map(|check| match &check.diagnostic {
+                None => format!("PASS: {}", check.name),
+                Some(diagnostic) => format!("FAIL: {}\n    {}", check.name, diagnostic),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if passed {
+            Output::success_msg(format!("connection is reachable\n{}", report)).exit()
+        } else {
+            Output::error(format!("connection is not reachable\n{}", report)).exit()
+        }
+    }
+}
+
+fn query_connection(
+    chain: &impl ChainHandle,
+    connection_id: &ConnectionId,
+) -> Result<ConnectionEnd, String> {
+    chain
+        .query_connection(
+            QueryConnectionRequest {
+                connection_id: connection_id.clone(),
+                height: QueryHeight::Latest,
+            },
+            IncludeProof::No,
+        )
+        .map(|(connection_end, _)| connection_end)
+        .map_err(|e| {
+            format!(
+                "failed to query connection '{}' on chain '{}': {}",
+                connection_id,
+                chain.id(),
+                e
+            )
+        })
+}
+
+fn check_reachable(chain: &impl ChainHandle) -> Result<(), String> {
+    chain.query_application_status().map(|_| ()).map_err(|e| {
+        format!(
+            "failed to query the status of chain '{}': {}",
+            chain.id(),
+            e
+        )
+    })
+}
+
+fn check_open(connection: &Result<ConnectionEnd, String>) -> Result<(), String> {
+    match connection {
+        Ok(connection) if connection.is_open() => Ok(()),
+        Ok(connection) => Err(format!("connection is in state '{}'", connection.state())),
+        Err(e) => Err(e.clone()),
+    }
+}
+
+fn check_cross_reference(
+    (a_connection_id, connection_a): (&ConnectionId, &ConnectionEnd),
+    (b_connection_id, connection_b): (&ConnectionId, &ConnectionEnd),
+) -> Result<(), String> {
+    let mut mismatches = vec![];
+
+    if connection_a.counterparty().client_id() != connection_b.client_id() {
+        mismatches.push(format!(
+            "the counterparty client of connection '{}' is '{}', but connection '{}' uses client '{}'",
+            a_connection_id,
+            connection_a.counterparty().client_id(),
+            b_connection_id,
+            connection_b.client_id()
+        ));
+    }
+
+    if connection_b.counterparty().client_id() != connection_a.client_id() {
+        mismatches.push(format!(
+            "the counterparty client of connection '{}' is '{}', but connection '{}' uses client '{}'",
+            b_connection_id,
+            connection_b.counterparty().client_id(),
+            a_connection_id,
+            connection_a.client_id()
+        ));
+    }
+
+    if connection_b.counterparty().connection_id() != Some(a_connection_id) {
+        mismatches.push(format!(
+            "the counterparty connection of connection '{}' is {:?}, expected '{}'",
+            b_connection_id,
+            connection_b.counterparty().connection_id(),
+            a_connection_id
+        ));
+    }
+
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(mismatches.join("\n    "))
+    }
+}
+
+fn check_client_tracks(
+    chain: &impl ChainHandle,
+    client_id: &ClientId,
+    counterparty_chain_id: &ChainId,
+) -> Result<(), String> {
+    let (client_state, _) = chain
+        .query_client_state(
+            QueryClientStateRequest {
+                client_id: client_id.clone(),
+                height: QueryHeight::Latest,
+            },
+            IncludeProof::No,
+        )
+        .map_err(|e| {
+            format!(
+                "failed to query client '{}' on chain '{}': {}",
+                client_id,
+                chain.id(),
+                e
+            )
+        })?;
+
+    if &client_state.chain_id() != counterparty_chain_id {
+        return Err(format!(
+            "client '{}' tracks chain '{}' instead",
+            client_id,
+            client_state.chain_id()
+        ));
+    }
+
+    if client_state.is_frozen() {
+        return Err(format!("client '{}' is frozen", client_id));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::QueryConnectionReachableCmd;
+
+    use std::str::FromStr;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::{ChainId, ConnectionId};
+
+    #[test]
+    fn test_query_connection_reachable() {
+        assert_eq!(
+            QueryConnectionReachableCmd {
+                a_chain_id: ChainId::from_string("chain_a"),
+                b_chain_id: ChainId::from_string("chain_b"),
+                a_connection_id: ConnectionId::from_str("connection-0").unwrap(),
+            },
+            QueryConnectionReachableCmd::parse_from(&[
+                "test",
+                "--a-chain",
+                "chain_a",
+                "--b-chain",
+                "chain_b",
+                "--a-connection",
+                "connection-0"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_query_connection_reachable_aliases() {
+        assert_eq!(
+            QueryConnectionReachableCmd {
+                a_chain_id: ChainId::from_string("chain_a"),
+                b_chain_id: ChainId::from_string("chain_b"),
+                a_connection_id: ConnectionId::from_str("connection-0").unwrap(),
+            },
+            QueryConnectionReachableCmd::parse_from(&[
+                "test",
+                "--chain-a",
+                "chain_a",
+                "--chain-b",
+                "chain_b",
+                "--connection-id-a",
+                "connection-0"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_query_connection_reachable_no_connection() {
+        assert!(QueryConnectionReachableCmd::try_parse_from(&[
+            "test",
+            "--a-chain",
+            "chain_a",
+            "--b-chain",
+            "chain_b"
+        ])
+        .is_err())
+    }
+}
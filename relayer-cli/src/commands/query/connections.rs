@@ -8,7 +8,7 @@ use ibc_relayer::chain::requests::{
     IncludeProof, PageRequest, QueryClientStateRequest, QueryConnectionsRequest, QueryHeight,
 };
 
-use crate::cli_utils::spawn_chain_runtime;
+use crate::cli_utils::{resolve_chain_filter, spawn_chain_runtime};
 use crate::conclude::{exit_with_unrecoverable_error, Output};
 use crate::prelude::*;
 
@@ -54,7 +54,9 @@ impl Runnable for QueryConnectionsCmd {
         let connections = match res {
             Ok(connections) => {
                 // Check the counterparty chain id only if filtering is required.
-                if let Some(counterparty_filter_id) = self.counterparty_chain_id.clone() {
+                if let Some(counterparty_filter_id) = &self.counterparty_chain_id {
+                    let counterparty_filter_id =
+                        resolve_chain_filter(&config, counterparty_filter_id);
                     let mut output = connections.clone();
 
                     for (id, connection) in connections.into_iter().enumerate() {
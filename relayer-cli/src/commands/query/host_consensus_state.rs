@@ -0,0 +1,100 @@
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+
+use ibc::core::ics24_host::identifier::ChainId;
+use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::chain::requests::{QueryHeight, QueryHostConsensusStateRequest};
+
+use crate::application::app_config;
+use crate::cli_utils::spawn_chain_runtime;
+use crate::conclude::{exit_with_unrecoverable_error, Output};
+
+/// The data structure that represents the arguments when invoking the `query host-consensus-state` CLI command.
+///
+/// The command has the following format:
+///
+/// `query host-consensus-state --chain <CHAIN_ID> [--height <HEIGHT>]`
+///
+/// If successful the consensus state of the chain itself at the given height is displayed,
+/// i.e. the consensus state that the chain checks counterparty proofs against during
+/// the connection handshake.
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct QueryHostConsensusStateCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain to query"
+    )]
+    chain_id: ChainId,
+
+    #[clap(
+        long = "height",
+        value_name = "HEIGHT",
+        help = "Height of the host consensus state to query"
+    )]
+    height: Option<u64>,
+}
+
+impl Runnable for QueryHostConsensusStateCmd {
+    fn run(&self) {
+        let config = app_config();
+
+        let chain = spawn_chain_runtime(&config, &self.chain_id)
+            .unwrap_or_else(exit_with_unrecoverable_error);
+
+        let height = self.height.map_or(QueryHeight::Latest, |revision_height| {
+            QueryHeight::Specific(
+                ibc::Height::new(chain.id().version(), revision_height)
+                    .unwrap_or_else(exit_with_unrecoverable_error),
+            )
+        });
+
+        match chain.query_host_consensus_state(QueryHostConsensusStateRequest { height }) {
+            Ok(consensus_state) => Output::success(consensus_state).exit(),
+            Err(e) => Output::error(format!(
+                "failed to query the host consensus state of chain '{}': {}",
+                self.chain_id, e
+            ))
+            .exit(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::QueryHostConsensusStateCmd;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::ChainId;
+
+    #[test]
+    fn test_query_host_consensus_state() {
+        assert_eq!(
+            QueryHostConsensusStateCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                height: None
+            },
+            QueryHostConsensusStateCmd::parse_from(&["test", "--chain", "chain_id"])
+        )
+    }
+
+    #[test]
+    fn test_query_host_consensus_state_height() {
+        assert_eq!(
+            QueryHostConsensusStateCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                height: Some(42)
+            },
+            QueryHostConsensusStateCmd::parse_from(&[
+                "test", "--chain", "chain_id", "--height", "42"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_query_host_consensus_state_no_chain() {
+        assert!(QueryHostConsensusStateCmd::try_parse_from(&["test", "--height", "42"]).is_err())
+    }
+}
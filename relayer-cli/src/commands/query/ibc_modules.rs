@@ -0,0 +1,343 @@
+use alloc::collections::BTreeSet;
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use serde::Serialize;
+
+use ibc::core::ics04_channel::channel::Order;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortChannelId, PortId};
+use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::chain::requests::{PageRequest, QueryChannelsRequest};
+use ibc_relayer::config::filter::PacketFilter;
+
+use crate::cli_utils::spawn_chain_runtime;
+use crate::conclude::{json, Output};
+use crate::error::Error;
+use crate::prelude::*;
+
+/// The query service of the ICS-29 fee middleware.
+const FEE_SERVICE: &str = "ibc.applications.fee.v1.Query";
+
+/// An IBC application module the relayer knows about.
+struct KnownModule {
+    module: &'static str,
+    /// The query service registered on a chain along with the module
+    service: &'static str,
+    /// The port the module binds to, or the prefix of its ports if it ends with `*`
+    port: &'static str,
+    orderings: &'static [Order],
+    /// The channel versions supported by the module, if they are known in advance
+    versions: &'static [&'static str],
+}
+
+const KNOWN_MODULES: &[KnownModule] = &[
+    KnownModule {
+        module: "transfer",
+        service: "ibc.applications.transfer.v1.Query",
+        port: "transfer",
+        orderings: &[Order::Unordered],
+        versions: &["ics20-1"],
+    },
+    KnownModule {
+        module: "interchainaccounts-host",
+        service: "ibc.applications.interchain_accounts.host.v1.Query",
+        port: "icahost",
+        orderings: &[Order::Ordered],
+        versions: &["ics27-1"],
+    },
+    KnownModule {
+        module: "interchainaccounts-controller",
+        service: "ibc.applications.interchain_accounts.controller.v1.Query",
+        port: "icacontroller-*",
+        orderings: &[Order::Ordered],
+        versions: &["ics27-1"],
+    },
+    KnownModule {
+        module: "wasm",
+        service: "cosmwasm.wasm.v1.Query",
+        port: "wasm.*",
+        orderings: &[Order::Ordered, Order::Unordered],
+        versions: &[],
+    },
+];
+
+/// Whether `port_id` is the port, or one of the ports, denoted by `port`.
+fn binds(port: &str, port_id: &PortId) -> bool {
+    match port.strip_suffix('*') {
+        Some(prefix) => port_id.as_str().starts_with(prefix),
+        None => port_id.as_str() == port,
+    }
+}
+
+/// An IBC application module registered on a chain.
+#[derive(Debug, Serialize)]
+struct IbcModule {
+    /// The port the module binds to, or the pattern of its ports
+    port: String,
+    module: String,
+    orderings: Vec<Order>,
+    versions: Vec<String>,
+}
+
+impl From<&KnownModule> for IbcModule {
+    fn from(known: &KnownModule) -> Self {
+        Self {
+            port: known.port.to_string(),
+            module: known.module.to_string(),
+            orderings: known.orderings.to_vec(),
+            versions: known.versions.iter().map(ToString::to_string).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct IbcModules {
+    modules: Vec<IbcModule>,
+    /// Whether the ICS-29 fee middleware is registered, in which case channels
+    /// may also negotiate fee-enabled versions wrapping the versions of the modules
+    fee_middleware: bool,
+    /// The channels in the packet filter of the chain whose port belongs to
+    /// a module which is not registered on the chain
+    unregistered_channels: Vec<PortChannelId>,
+}
+
+/// Returns the known modules whose query service is registered, followed by
+/// the ports of the channels on the chain which belong to no known module.
+fn ibc_modules(services: &[String], channel_ports: &BTreeSet<PortId>) -> Vec<IbcModule> {
+    let mut modules = KNOWN_MODULES
+        .iter()
+        .filter(|known| services.iter().any(|service| service == known.service))
+        .map(IbcModule::from)
+        .collect::<Vec<_>>();
+
+    let unknown_ports = channel_ports
+        .iter()
+        .filter(|port_id| !KNOWN_MODULES.iter().any(|known| binds(known.port, port_id)));
+
+    modules.extend(unknown_ports.map(|port_id| IbcModule {
+        port: port_id.to_string(),
+        module: "unknown".to_string(),
+        orderings: vec![],
+        versions: vec![],
+    }));
+
+    modules
+}
+
+/// Returns the channels whose port belongs to a known module which is not among
+/// the registered `modules`. The ports of unknown modules cannot be checked.
+fn unregistered_channels<'a>(
+    modules: &[IbcModule],
+    channels: impl Iterator<Item = (&'a PortId, &'a ChannelId)>,
+) -> Vec<PortChannelId> {
+    channels
+        .filter(|(port_id, _)| {
+            KNOWN_MODULES.iter().any(|known| binds(known.port, port_id))
+                && !modules.iter().any(|module| binds(&module.port, port_id))
+        })
+        .map(|(port_id, channel_id)| PortChannelId {
+            port_id: port_id.clone(),
+            channel_id: channel_id.clone(),
+        })
+        .collect()
+}
+
+/// List the IBC application modules registered on a chain, along with the port
+/// they bind to and the channel orderings and versions they support.
+///
+/// The modules are detected from the query services the chain exposes through its
+/// gRPC reflection service, among the modules known to the relayer: ICS-20 transfer,
+/// ICS-27 interchain accounts and CosmWasm. The ports of the channels on the chain
+/// which belong to none of these modules are listed with an `unknown` module.
+///
+/// The channels allowed by the packet filter of the chain whose port belongs to
+/// a module which is not registered, e.g. after a chain upgrade removed it,
+/// are reported as unregistered.
+///
+/// `query ibc-module modules --chain <CHAIN_ID>`
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct QueryIbcModulesCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain to query"
+    )]
+    chain_id: ChainId,
+}
+
+impl QueryIbcModulesCmd {
+    fn execute(&self) -> Result<IbcModules, Error> {
+        let config = app_config();
+
+        let chain = spawn_chain_runtime(&config, &self.chain_id)?;
+
+        let services = chain.query_services().map_err(Error::relayer)?;
+
+        let channel_ports = chain
+            .query_channels(QueryChannelsRequest {
+                pagination: Some(PageRequest::all()),
+            })
+            .map_err(Error::relayer)?
+            .into_iter()
+            .map(|channel| channel.port_id)
+            .collect();
+
+        let modules = ibc_modules(&services, &channel_ports);
+
+        let unregistered_channels = match config
+            .find_chain(&chain.id())
+            .map(|chain_config| &chain_config.packet_filter)
+        {
+            Some(PacketFilter::Allow(filters)) => {
+                unregistered_channels(&modules, filters.iter_exact())
+            }
+            _ => vec![],
+        };
+
+        Ok(IbcModules {
+            modules,
+            fee_middleware: services.iter().any(|service| service == FEE_SERVICE),
+            unregistered_channels,
+        })
+    }
+}
+
+impl Runnable for QueryIbcModulesCmd {
+    fn run(&self) {
+        let modules = match self.execute() {
+            Ok(modules) => modules,
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        };
+
+        if json() {
+            Output::success(modules).exit()
+        }
+
+        let mut lines = modules
+            .modules
+            .iter()
+            .map(|module| {
+                let orderings = module
+                    .orderings
+                    .iter()
+                    .map(Order::as_str)
+                    .collect::<Vec<_>>();
+
+                format!(
+                    "{}: module {}, orderings [{}], versions [{}]",
+                    module.port,
+                    module.module,
+                    orderings.join(", "),
+                    module.versions.join(", ")
+                )
+            })
+            .collect::<Vec<_>>();
+
+        if modules.fee_middleware {
+            lines.push("ICS-29 fee middleware is registered".to_string());
+        }
+
+        for channel in &modules.unregistered_channels {
+            lines.push(format!(
+                "WARNING: configured channel {} is on a port of a module which is not registered",
+                channel
+            ));
+        }
+
+        Output::success_msg(lines.join("\n")).exit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ibc_modules, unregistered_channels, QueryIbcModulesCmd};
+
+    use alloc::collections::BTreeSet;
+    use core::str::FromStr;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+
+    fn services(services: &[&str]) -> Vec<String> {
+        services.iter().map(ToString::to_string).collect()
+    }
+
+    fn ports(ports: &[&str]) -> BTreeSet<PortId> {
+        ports
+            .iter()
+            .map(|port| PortId::from_str(port).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_query_ibc_modules() {
+        assert_eq!(
+            QueryIbcModulesCmd {
+                chain_id: ChainId::from_string("chain_id")
+            },
+            QueryIbcModulesCmd::parse_from(&["test", "--chain", "chain_id"])
+        )
+    }
+
+    #[test]
+    fn test_query_ibc_modules_no_chain() {
+        assert!(QueryIbcModulesCmd::try_parse_from(&["test"]).is_err())
+    }
+
+    #[test]
+    fn test_ibc_modules() {
+        let modules = ibc_modules(
+            &services(&[
+                "cosmos.bank.v1beta1.Query",
+                "ibc.applications.transfer.v1.Query",
+                "ibc.applications.interchain_accounts.controller.v1.Query",
+            ]),
+            &ports(&["transfer", "icacontroller-owner", "custom"]),
+        );
+
+        let modules = modules
+            .iter()
+            .map(|module| (module.port.as_str(), module.module.as_str()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            modules,
+            vec![
+                ("transfer", "transfer"),
+                ("icacontroller-*", "interchainaccounts-controller"),
+                ("custom", "unknown"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unregistered_channels() {
+        let modules = ibc_modules(
+            &services(&["ibc.applications.transfer.v1.Query"]),
+            &ports(&["custom"]),
+        );
+
+        let configured = vec![
+            (PortId::transfer(), ChannelId::new(0)),
+            (PortId::from_str("icahost").unwrap(), ChannelId::new(1)),
+            (PortId::from_str("custom").unwrap(), ChannelId::new(2)),
+        ];
+
+        let unregistered = unregistered_channels(
+            &modules,
+            configured
+                .iter()
+                .map(|(port_id, channel_id)| (port_id, channel_id)),
+        );
+
+        assert_eq!(
+            unregistered
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["icahost/channel-1"]
+        );
+    }
+}
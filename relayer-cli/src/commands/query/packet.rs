@@ -5,9 +5,12 @@ mod ack;
 mod acks;
 mod commitment;
 mod commitments;
+mod event_log;
 mod pending;
 mod pending_acks;
 mod pending_sends;
+mod timeout_countdown;
+mod trace;
 
 #[derive(Command, Debug, Parser, Runnable)]
 pub enum QueryPacketCmds {
@@ -31,4 +34,13 @@ pub enum QueryPacketCmds {
 
     /// Output a summary of pending packets in both directions
     Pending(pending::QueryPendingPacketsCmd),
+
+    /// Display the time remaining until the oldest pending packet on a channel expires
+    TimeoutCountdown(timeout_countdown::QueryPacketTimeoutCountdownCmd),
+
+    /// Display the packet events emitted on a channel within a range of heights
+    EventLog(event_log::QueryPacketEventLogCmd),
+
+    /// Follow a packet through its lifecycle, reporting the stage it reached and its transactions
+    Trace(trace::QueryPacketTraceCmd),
 }
@@ -1,13 +1,18 @@
 use abscissa_core::clap::Parser;
 use abscissa_core::{Command, Runnable};
-use ibc_relayer::chain::requests::{IncludeProof, QueryHeight, QueryPacketAcknowledgementRequest};
+use ibc_relayer::chain::requests::{
+    IncludeProof, QueryHeight, QueryPacketAcknowledgementRequest, QueryWrittenAcksRequest,
+};
+use serde::Serialize;
 use subtle_encoding::{Encoding, Hex};
 
+use ibc::applications::transfer::acknowledgement::Acknowledgement;
 use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics23_commitment::commitment::CommitmentProofBytes;
 use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
 use ibc_relayer::chain::handle::ChainHandle;
 
-use crate::cli_utils::spawn_chain_runtime;
+use crate::cli_utils::{parse_channel_id, parse_port_id, spawn_chain_runtime};
 use crate::conclude::{exit_with_unrecoverable_error, Output};
 use crate::error::Error;
 use crate::prelude::*;
@@ -27,6 +32,7 @@ pub struct QueryPacketAcknowledgmentCmd {
         long = "port",
         required = true,
         value_name = "PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "REQUIRED",
         help = "Identifier of the port to query"
     )]
@@ -37,6 +43,7 @@ pub struct QueryPacketAcknowledgmentCmd {
         visible_alias = "chan",
         required = true,
         value_name = "CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
         help_heading = "REQUIRED",
         help = "Identifier of the channel to query"
     )]
@@ -58,17 +65,54 @@ pub struct QueryPacketAcknowledgmentCmd {
         help = "Height of the state to query. Leave unspecified for latest height."
     )]
     height: Option<u64>,
+
+    #[clap(
+        long = "proof",
+        help = "Include the proof of the stored acknowledgement commitment in the output"
+    )]
+    proof: bool,
+}
+
+/// The acknowledgement of a packet, as committed to on chain and as written
+/// when the packet was received.
+#[derive(Debug, Serialize)]
+struct PacketAck {
+    /// The commitment to the acknowledgement stored on chain, in hex,
+    /// empty if the packet was not acknowledged
+    commitment: String,
+    /// The acknowledgement written when the packet was received, in hex,
+    /// if the transaction which received the packet is found
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ack: Option<String>,
+    /// The acknowledgement decoded as an ICS-20 acknowledgement, if it is one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decoded: Option<Acknowledgement>,
+    /// The proof of the commitment, in hex, with `--proof`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proof: Option<String>,
+}
+
+fn hex(bytes: Vec<u8>) -> String {
+    Hex::upper_case()
+        .encode_to_string(bytes.clone())
+        .unwrap_or_else(|_| format!("{:?}", bytes))
 }
 
 impl QueryPacketAcknowledgmentCmd {
-    fn execute(&self) -> Result<String, Error> {
+    fn execute(&self) -> Result<PacketAck, Error> {
         let config = app_config();
 
         debug!("Options: {:?}", self);
 
         let chain = spawn_chain_runtime(&config, &self.chain_id)?;
 
-        chain
+        let include_proof = if self.proof {
+            IncludeProof::Yes
+        } else {
+            IncludeProof::No
+        };
+
+        let (commitment, proof) = chain
             .query_packet_acknowledgement(
                 QueryPacketAcknowledgementRequest {
                     port_id: self.port_id.clone(),
@@ -81,21 +125,47 @@ impl QueryPacketAcknowledgmentCmd {
                         )
                     }),
                 },
-                IncludeProof::No,
+                include_proof,
             )
-            .map_err(Error::relayer)
-            .map(|(bytes, _)| {
-                Hex::upper_case()
-                    .encode_to_string(bytes.clone())
-                    .unwrap_or_else(|_| format!("{:?}", bytes))
-            })
+            .map_err(Error::relayer)?;
+
+        // Only the commitment to the acknowledgement is stored on chain,
+        // the acknowledgement itself is found in the event written along with it
+        let ack = if commitment.is_empty() {
+            None
+        } else {
+            let written = chain.query_written_acks(QueryWrittenAcksRequest {
+                port_id: self.port_id.clone(),
+                channel_id: self.channel_id.clone(),
+                sequences: vec![self.sequence],
+            });
+
+            match written {
+                Ok(written) => written.into_iter().next().map(|written| written.ack),
+                Err(e) => {
+                    warn!("failed to query the written acknowledgement: {}", e);
+                    None
+                }
+            }
+        };
+
+        Ok(PacketAck {
+            commitment: hex(commitment),
+            decoded: ack
+                .as_ref()
+                .and_then(|ack| serde_json::from_slice(ack).ok()),
+            ack: ack.map(hex),
+            proof: proof
+                .and_then(|proof| CommitmentProofBytes::try_from(proof).ok())
+                .map(|proof| hex(proof.into())),
+        })
     }
 }
 
 impl Runnable for QueryPacketAcknowledgmentCmd {
     fn run(&self) {
         match self.execute() {
-            Ok(hex) => Output::success(hex).exit(),
+            Ok(ack) => Output::success(ack).exit(),
             Err(e) => Output::error(format!("{}", e)).exit(),
         }
     }
@@ -119,7 +189,8 @@ mod tests {
                 port_id: PortId::from_str("port_id").unwrap(),
                 channel_id: ChannelId::from_str("channel-07").unwrap(),
                 sequence: Sequence::from(42),
-                height: None
+                height: None,
+                proof: false
             },
             QueryPacketAcknowledgmentCmd::parse_from(&[
                 "test",
@@ -143,7 +214,8 @@ mod tests {
                 port_id: PortId::from_str("port_id").unwrap(),
                 channel_id: ChannelId::from_str("channel-07").unwrap(),
                 sequence: Sequence::from(42),
-                height: None
+                height: None,
+                proof: false
             },
             QueryPacketAcknowledgmentCmd::parse_from(&[
                 "test",
@@ -167,7 +239,8 @@ mod tests {
                 port_id: PortId::from_str("port_id").unwrap(),
                 channel_id: ChannelId::from_str("channel-07").unwrap(),
                 sequence: Sequence::from(42),
-                height: Some(21)
+                height: Some(21),
+                proof: false
             },
             QueryPacketAcknowledgmentCmd::parse_from(&[
                 "test",
@@ -185,6 +258,32 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_query_packet_ack_proof() {
+        assert_eq!(
+            QueryPacketAcknowledgmentCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                port_id: PortId::from_str("port_id").unwrap(),
+                channel_id: ChannelId::from_str("channel-07").unwrap(),
+                sequence: Sequence::from(42),
+                height: None,
+                proof: true
+            },
+            QueryPacketAcknowledgmentCmd::parse_from(&[
+                "test",
+                "--chain",
+                "chain_id",
+                "--port",
+                "port_id",
+                "--channel",
+                "channel-07",
+                "--sequence",
+                "42",
+                "--proof"
+            ])
+        )
+    }
+
     #[test]
     fn test_query_packet_ack_no_seq() {
         assert!(QueryPacketAcknowledgmentCmd::try_parse_from(&[
@@ -4,19 +4,64 @@ use ibc::core::ics04_channel::packet::Sequence;
 use serde::Serialize;
 
 use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc::events::{IbcEvent, WithBlockDataType};
 use ibc::Height;
-use ibc_relayer::chain::handle::BaseChainHandle;
+use ibc_relayer::chain::handle::{BaseChainHandle, ChainHandle};
+use ibc_relayer::chain::requests::{QueryHeight, QueryPacketEventDataRequest, QueryTxRequest};
 
-use crate::cli_utils::spawn_chain_counterparty;
+use crate::cli_utils::{parse_channel_id, parse_port_id, spawn_chain_counterparty};
 use crate::conclude::Output;
 use crate::error::Error;
 use crate::prelude::*;
-use ibc_relayer::chain::counterparty::acknowledgements_on_chain;
+use ibc_relayer::chain::counterparty::{
+    acknowledgements_on_chain, unreceived_acknowledgements_sequences,
+};
 
 #[derive(Serialize, Debug)]
 struct PacketSeqs {
     height: Height,
     seqs: Vec<Sequence>,
+    /// The outcome of each acknowledgement, in `--verbose` mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    acks: Option<Vec<AckOutcome>>,
+}
+
+/// Whether the packet was successfully processed on the chain which wrote the acknowledgement.
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum AckStatus {
+    Success,
+    Error {
+        error: String,
+    },
+    /// The acknowledgement is not in the standard `{"result": ..}` / `{"error": ..}` format.
+    Unknown,
+}
+
+impl AckStatus {
+    fn classify(ack: &[u8]) -> Self {
+        let ack = match serde_json::from_slice::<serde_json::Value>(ack) {
+            Ok(serde_json::Value::Object(ack)) => ack,
+            _ => return Self::Unknown,
+        };
+
+        match (ack.get("result"), ack.get("error")) {
+            (Some(_), None) => Self::Success,
+            (None, Some(error)) => Self::Error {
+                error: error
+                    .as_str()
+                    .map_or_else(|| error.to_string(), str::to_string),
+            },
+            _ => Self::Unknown,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct AckOutcome {
+    sequence: Sequence,
+    #[serde(flatten)]
+    status: AckStatus,
 }
 
 #[derive(Clone, Command, Debug, Parser, PartialEq)]
@@ -34,6 +79,7 @@ pub struct QueryPacketAcknowledgementsCmd {
         long = "port",
         required = true,
         value_name = "PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "REQUIRED",
         help = "Identifier of the port to query"
     )]
@@ -44,10 +90,23 @@ pub struct QueryPacketAcknowledgementsCmd {
         visible_alias = "chan",
         required = true,
         value_name = "CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
         help_heading = "REQUIRED",
         help = "Identifier of the channel to query"
     )]
     channel_id: ChannelId,
+
+    #[clap(
+        long = "missing-only",
+        help = "Only show the acknowledgements which have not been relayed back to the counterparty chain yet"
+    )]
+    missing_only: bool,
+
+    #[clap(
+        long = "verbose",
+        help = "Show whether each acknowledgement reports a success or an error"
+    )]
+    verbose: bool,
 }
 
 impl QueryPacketAcknowledgementsCmd {
@@ -63,14 +122,77 @@ impl QueryPacketAcknowledgementsCmd {
             &self.channel_id,
         )?;
 
-        let (seqs, height) =
-            acknowledgements_on_chain(&chains.src, &chains.dst, &chan_conn_cli.channel)
-                .map_err(Error::supervisor)?;
+        let channel = &chan_conn_cli.channel;
+        let counterparty = channel.channel_end.counterparty();
+        let counterparty_channel_id = counterparty
+            .channel_id
+            .clone()
+            .ok_or_else(|| Error::missing_counterparty_channel_id(channel.clone()))?;
+
+        let (mut seqs, height) = acknowledgements_on_chain(&chains.src, &chains.dst, channel)
+            .map_err(Error::supervisor)?;
 
-        Ok(PacketSeqs { seqs, height })
+        if self.missing_only {
+            // Keep the acknowledgements that the counterparty chain has not received yet
+            seqs = unreceived_acknowledgements_sequences(
+                &chains.dst,
+                &counterparty.port_id,
+                &counterparty_channel_id,
+                seqs,
+            )
+            .map_err(Error::supervisor)?;
+
+            seqs.sort_unstable();
+        }
+
+        let acks = if self.verbose && !seqs.is_empty() {
+            let events = chains
+                .src
+                .query_txs(QueryTxRequest::Packet(QueryPacketEventDataRequest {
+                    event_id: WithBlockDataType::WriteAck,
+                    source_port_id: counterparty.port_id.clone(),
+                    source_channel_id: counterparty_channel_id,
+                    destination_port_id: self.port_id.clone(),
+                    destination_channel_id: self.channel_id.clone(),
+                    sequences: seqs.clone(),
+                    height: QueryHeight::Specific(height),
+                }))
+                .map_err(Error::relayer)?;
+
+            Some(ack_outcomes(&seqs, &events))
+        } else if self.verbose {
+            Some(vec![])
+        } else {
+            None
+        };
+
+        Ok(PacketSeqs { height, seqs, acks })
     }
 }
 
+/// Classifies the acknowledgement of each of the given sequences, as written
+/// in the given `write_acknowledgement` events.
+fn ack_outcomes(seqs: &[Sequence], events: &[IbcEvent]) -> Vec<AckOutcome> {
+    seqs.iter()
+        .map(|sequence| {
+            let status = events
+                .iter()
+                .find_map(|event| match event {
+                    IbcEvent::WriteAcknowledgement(ev) if ev.packet.sequence == *sequence => {
+                        Some(AckStatus::classify(&ev.ack))
+                    }
+                    _ => None,
+                })
+                .unwrap_or(AckStatus::Unknown);
+
+            AckOutcome {
+                sequence: *sequence,
+                status,
+            }
+        })
+        .collect()
+}
+
 // cargo run --bin hermes -- query packet acknowledgements --chain ibc-0 --port transfer --connection ibconexfer --height 3
 impl Runnable for QueryPacketAcknowledgementsCmd {
     fn run(&self) {
@@ -83,7 +205,7 @@ impl Runnable for QueryPacketAcknowledgementsCmd {
 
 #[cfg(test)]
 mod tests {
-    use super::QueryPacketAcknowledgementsCmd;
+    use super::{AckStatus, QueryPacketAcknowledgementsCmd};
 
     use std::str::FromStr;
 
@@ -96,7 +218,9 @@ mod tests {
             QueryPacketAcknowledgementsCmd {
                 chain_id: ChainId::from_string("chain_id"),
                 port_id: PortId::from_str("port_id").unwrap(),
-                channel_id: ChannelId::from_str("channel-07").unwrap()
+                channel_id: ChannelId::from_str("channel-07").unwrap(),
+                missing_only: false,
+                verbose: false,
             },
             QueryPacketAcknowledgementsCmd::parse_from(&[
                 "test",
@@ -116,7 +240,9 @@ mod tests {
             QueryPacketAcknowledgementsCmd {
                 chain_id: ChainId::from_string("chain_id"),
                 port_id: PortId::from_str("port_id").unwrap(),
-                channel_id: ChannelId::from_str("channel-07").unwrap()
+                channel_id: ChannelId::from_str("channel-07").unwrap(),
+                missing_only: false,
+                verbose: false,
             },
             QueryPacketAcknowledgementsCmd::parse_from(&[
                 "test",
@@ -130,6 +256,46 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_query_packet_acks_missing_only() {
+        assert_eq!(
+            QueryPacketAcknowledgementsCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                port_id: PortId::from_str("port_id").unwrap(),
+                channel_id: ChannelId::from_str("channel-07").unwrap(),
+                missing_only: true,
+                verbose: true,
+            },
+            QueryPacketAcknowledgementsCmd::parse_from(&[
+                "test",
+                "--chain",
+                "chain_id",
+                "--port",
+                "port_id",
+                "--channel",
+                "channel-07",
+                "--missing-only",
+                "--verbose"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_classify_ack() {
+        assert_eq!(
+            AckStatus::classify(br#"{"result":"AQ=="}"#),
+            AckStatus::Success
+        );
+        assert_eq!(
+            AckStatus::classify(br#"{"error":"insufficient funds"}"#),
+            AckStatus::Error {
+                error: "insufficient funds".to_string()
+            }
+        );
+        assert_eq!(AckStatus::classify(b"\x01"), AckStatus::Unknown);
+        assert_eq!(AckStatus::classify(br#"{"other":1}"#), AckStatus::Unknown);
+    }
+
     #[test]
     fn test_query_packet_acks_no_chan() {
         assert!(QueryPacketAcknowledgementsCmd::try_parse_from(&[
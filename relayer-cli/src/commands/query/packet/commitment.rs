@@ -9,7 +9,7 @@ use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
 use ibc::Height;
 use ibc_relayer::chain::handle::ChainHandle;
 
-use crate::cli_utils::spawn_chain_runtime;
+use crate::cli_utils::{parse_channel_id, parse_port_id, spawn_chain_runtime};
 use crate::conclude::{exit_with_unrecoverable_error, Output};
 use crate::error::Error;
 use crate::prelude::*;
@@ -35,6 +35,7 @@ pub struct QueryPacketCommitmentCmd {
         long = "port",
         required = true,
         value_name = "PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "REQUIRED",
         help = "Identifier of the port to query"
     )]
@@ -45,6 +46,7 @@ pub struct QueryPacketCommitmentCmd {
         visible_alias = "chan",
         required = true,
         value_name = "CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
         help_heading = "REQUIRED",
         help = "Identifier of the channel to query"
     )]
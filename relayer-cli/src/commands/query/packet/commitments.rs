@@ -1,13 +1,20 @@
 use abscissa_core::clap::Parser;
 use abscissa_core::{Command, Runnable};
-use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics04_channel::commitment::compute_packet_commitment;
+use ibc::core::ics04_channel::packet::{Packet, Sequence};
 use serde::Serialize;
 
 use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc::events::{IbcEvent, WithBlockDataType};
 use ibc::Height;
 use ibc_relayer::chain::counterparty::commitments_on_chain;
+use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::chain::requests::{
+    IncludeProof, QueryChannelRequest, QueryHeight, QueryPacketCommitmentRequest,
+    QueryPacketEventDataRequest, QueryTxRequest,
+};
 
-use crate::cli_utils::spawn_chain_runtime;
+use crate::cli_utils::{parse_channel_id, parse_port_id, spawn_chain_runtime};
 use crate::conclude::Output;
 use crate::error::Error;
 use crate::prelude::*;
@@ -16,6 +23,30 @@ use crate::prelude::*;
 struct PacketSeqs {
     height: Height,
     seqs: Vec<Sequence>,
+    /// The result of the verification of each commitment, in `--verify` mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    checks: Option<Vec<CommitmentCheck>>,
+}
+
+/// Whether a stored commitment matches the one recomputed from the packet sent.
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum CommitmentStatus {
+    Verified,
+    Mismatch {
+        stored: String,
+        computed: String,
+    },
+    /// The packet could not be reconstructed, e.g. because the
+    /// `send_packet` event was pruned by the node.
+    Unverifiable,
+}
+
+#[derive(Serialize, Debug)]
+struct CommitmentCheck {
+    sequence: Sequence,
+    #[serde(flatten)]
+    status: CommitmentStatus,
 }
 
 #[derive(Clone, Command, Debug, Parser, PartialEq)]
@@ -33,6 +64,7 @@ pub struct QueryPacketCommitmentsCmd {
         long = "port",
         required = true,
         value_name = "PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "REQUIRED",
         help = "Identifier of the port to query"
     )]
@@ -43,10 +75,17 @@ pub struct QueryPacketCommitmentsCmd {
         visible_alias = "chan",
         required = true,
         value_name = "CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
         help_heading = "REQUIRED",
         help = "Identifier of the channel to query"
     )]
     channel_id: ChannelId,
+
+    #[clap(
+        long = "verify",
+        help = "Check each commitment against the hash of the packet reconstructed from its send_packet event"
+    )]
+    verify: bool,
 }
 
 impl QueryPacketCommitmentsCmd {
@@ -57,12 +96,111 @@ impl QueryPacketCommitmentsCmd {
 
         let chain = spawn_chain_runtime(&config, &self.chain_id)?;
 
-        commitments_on_chain(&chain, &self.port_id, &self.channel_id)
-            .map_err(Error::supervisor)
-            .map(|(seqs_vec, height)| PacketSeqs {
-                height,
-                seqs: seqs_vec,
+        let (seqs, height) = commitments_on_chain(&chain, &self.port_id, &self.channel_id)
+            .map_err(Error::supervisor)?;
+
+        let checks = if self.verify {
+            Some(self.verify_commitments(&chain, &seqs, height)?)
+        } else {
+            None
+        };
+
+        Ok(PacketSeqs {
+            height,
+            seqs,
+            checks,
+        })
+    }
+
+    /// Checks the commitments of the given sequences, stored at `height`, against the
+    /// packets sent in the `send_packet` events of the chain.
+    fn verify_commitments<Chain: ChainHandle>(
+        &self,
+        chain: &Chain,
+        seqs: &[Sequence],
+        height: Height,
+    ) -> Result<Vec<CommitmentCheck>, Error> {
+        if seqs.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let (channel_end, _) = chain
+            .query_channel(
+                QueryChannelRequest {
+                    port_id: self.port_id.clone(),
+                    channel_id: self.channel_id.clone(),
+                    height: QueryHeight::Specific(height),
+                },
+                IncludeProof::No,
+            )
+            .map_err(Error::relayer)?;
+
+        let counterparty = channel_end.counterparty();
+
+        let events = match &counterparty.channel_id {
+            Some(counterparty_channel_id) => chain
+                .query_txs(QueryTxRequest::Packet(QueryPacketEventDataRequest {
+                    event_id: WithBlockDataType::SendPacket,
+                    source_port_id: self.port_id.clone(),
+                    source_channel_id: self.channel_id.clone(),
+                    destination_port_id: counterparty.port_id.clone(),
+                    destination_channel_id: counterparty_channel_id.clone(),
+                    sequences: seqs.to_vec(),
+                    height: QueryHeight::Specific(height),
+                }))
+                .map_err(Error::relayer)?,
+            None => vec![],
+        };
+
+        seqs.iter()
+            .map(|sequence| {
+                let (stored, _) = chain
+                    .query_packet_commitment(
+                        QueryPacketCommitmentRequest {
+                            port_id: self.port_id.clone(),
+                            channel_id: self.channel_id.clone(),
+                            sequence: *sequence,
+                            height: QueryHeight::Specific(height),
+                        },
+                        IncludeProof::No,
+                    )
+                    .map_err(Error::relayer)?;
+
+                let packet = events.iter().find_map(|event| match event {
+                    IbcEvent::SendPacket(ev) if ev.packet.sequence == *sequence => Some(&ev.packet),
+                    _ => None,
+                });
+
+                Ok(CommitmentCheck {
+                    sequence: *sequence,
+                    status: check_commitment(&stored, packet),
+                })
             })
+            .collect()
+    }
+}
+
+/// Compares the `stored` commitment of a packet against the one recomputed
+/// from the `packet` reconstructed from its `send_packet` event, if any.
+fn check_commitment(stored: &[u8], packet: Option<&Packet>) -> CommitmentStatus {
+    let packet = match packet {
+        Some(packet) => packet,
+        None => return CommitmentStatus::Unverifiable,
+    };
+
+    let computed = compute_packet_commitment(
+        &packet.data,
+        &packet.timeout_height,
+        &packet.timeout_timestamp,
+    );
+
+    if computed.as_ref() == stored {
+        CommitmentStatus::Verified
+    } else {
+        CommitmentStatus::Mismatch {
+            stored: hex::encode(stored),
+            computed: hex::encode(computed),
+        }
     }
 }
 
@@ -70,7 +208,19 @@ impl QueryPacketCommitmentsCmd {
 impl Runnable for QueryPacketCommitmentsCmd {
     fn run(&self) {
         match self.execute() {
-            Ok(p) => Output::success(p).exit(),
+            Ok(p) => {
+                let mismatch = p
+                    .checks
+                    .iter()
+                    .flatten()
+                    .any(|check| matches!(check.status, CommitmentStatus::Mismatch { .. }));
+
+                if mismatch {
+                    Output::with_error().with_result(p).exit()
+                } else {
+                    Output::success(p).exit()
+                }
+            }
             Err(e) => Output::error(format!("{}", e)).exit(),
         }
     }
@@ -78,12 +228,16 @@ impl Runnable for QueryPacketCommitmentsCmd {
 
 #[cfg(test)]
 mod tests {
-    use super::QueryPacketCommitmentsCmd;
+    use super::{check_commitment, CommitmentStatus, QueryPacketCommitmentsCmd};
 
     use std::str::FromStr;
 
     use abscissa_core::clap::Parser;
+    use ibc::core::ics04_channel::packet::{Packet, Sequence};
+    use ibc::core::ics04_channel::timeout::TimeoutHeight;
     use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+    use ibc::timestamp::Timestamp;
+    use ibc::Height;
 
     #[test]
     fn test_query_packet_commitments() {
@@ -91,7 +245,8 @@ mod tests {
             QueryPacketCommitmentsCmd {
                 chain_id: ChainId::from_string("chain_id"),
                 port_id: PortId::from_str("port_id").unwrap(),
-                channel_id: ChannelId::from_str("channel-07").unwrap()
+                channel_id: ChannelId::from_str("channel-07").unwrap(),
+                verify: false,
             },
             QueryPacketCommitmentsCmd::parse_from(&[
                 "test",
@@ -111,7 +266,8 @@ mod tests {
             QueryPacketCommitmentsCmd {
                 chain_id: ChainId::from_string("chain_id"),
                 port_id: PortId::from_str("port_id").unwrap(),
-                channel_id: ChannelId::from_str("channel-07").unwrap()
+                channel_id: ChannelId::from_str("channel-07").unwrap(),
+                verify: false,
             },
             QueryPacketCommitmentsCmd::parse_from(&[
                 "test",
@@ -156,4 +312,65 @@ mod tests {
         ])
         .is_err())
     }
+
+    #[test]
+    fn test_query_packet_commitments_verify() {
+        assert_eq!(
+            QueryPacketCommitmentsCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                port_id: PortId::from_str("port_id").unwrap(),
+                channel_id: ChannelId::from_str("channel-07").unwrap(),
+                verify: true,
+            },
+            QueryPacketCommitmentsCmd::parse_from(&[
+                "test",
+                "--chain",
+                "chain_id",
+                "--port",
+                "port_id",
+                "--channel",
+                "channel-07",
+                "--verify"
+            ])
+        )
+    }
+
+    fn packet() -> Packet {
+        Packet {
+            sequence: Sequence::from(1),
+            source_port: PortId::transfer(),
+            source_channel: ChannelId::new(0),
+            destination_port: PortId::transfer(),
+            destination_channel: ChannelId::new(7),
+            data: br#"{"amount":"100","denom":"uatom","receiver":"cosmos1receiver","sender":"cosmos1sender"}"#.to_vec(),
+            timeout_height: TimeoutHeight::At(Height::new(1, 1000).unwrap()),
+            timeout_timestamp: Timestamp::from_nanoseconds(1_665_000_000_000_000_000).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_check_commitment() {
+        let stored =
+            hex::decode("0e76997f6436ae81da36356d1002f2263c9aa288459073ec499ab20d5041612c")
+                .unwrap();
+
+        assert_eq!(
+            check_commitment(&stored, Some(&packet())),
+            CommitmentStatus::Verified
+        );
+
+        let mut tampered = packet();
+        tampered.data = br#"{"amount":"1000","denom":"uatom","receiver":"cosmos1receiver","sender":"cosmos1sender"}"#.to_vec();
+
+        assert!(matches!(
+            check_commitment(&stored, Some(&tampered)),
+            CommitmentStatus::Mismatch { stored: ref s, .. }
+                if *s == "0e76997f6436ae81da36356d1002f2263c9aa288459073ec499ab20d5041612c"
+        ));
+
+        assert_eq!(
+            check_commitment(&stored, None),
+            CommitmentStatus::Unverifiable
+        );
+    }
 }
@@ -0,0 +1,546 @@
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use serde::Serialize;
+
+use ibc::applications::transfer::packet::VersionedPacketData;
+use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics04_channel::Version;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc::events::{IbcEvent, WithBlockDataType};
+use ibc::Height;
+use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::chain::requests::{
+    IncludeProof, QueryChannelRequest, QueryHeight, QueryPacketEventRangeRequest, QueryTxRequest,
+};
+
+use crate::cli_utils::{parse_channel_id, parse_port_id, spawn_chain_runtime};
+use crate::conclude::{json, Output};
+use crate::error::Error;
+use crate::prelude::*;
+
+/// The packet events queried on the channel end, in the order in which they
+/// occur in the lifecycle of a packet, so that events emitted at the same
+/// height are listed in that order.
+const PACKET_EVENTS: [WithBlockDataType; 4] = [
+    WithBlockDataType::SendPacket,
+    WithBlockDataType::WriteAck,
+    WithBlockDataType::AckPacket,
+    WithBlockDataType::TimeoutPacket,
+];
+
+/// The data carried by a packet, decoded when it is an ICS-20 token transfer,
+/// in the format of the given version of the channel.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+enum EventData {
+    Transfer(VersionedPacketData),
+    Raw(String),
+}
+
+impl EventData {
+    fn decode(version: &Version, data: &[u8]) -> Self {
+        match VersionedPacketData::decode(version, data) {
+            Ok(data) => Self::Transfer(data),
+            Err(_) => Self::Raw(String::from_utf8_lossy(data).into_owned()),
+        }
+    }
+
+    fn summary(&self) -> String {
+        let data = match self {
+            EventData::Transfer(data) => data,
+            EventData::Raw(data) => return data.clone(),
+        };
+
+        let tokens = data
+            .tokens()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut summary = format!("{} from {} to {}", tokens, data.sender(), data.receiver());
+
+        if let VersionedPacketData::V2(data) = data {
+            if !data.forwarding.is_empty() {
+                let hops = data
+                    .forwarding
+                    .hops
+                    .iter()
+                    .map(|hop| format!("{}/{}", hop.port_id, hop.channel_id))
+                    .collect::<Vec<_>>()
+                    .join(" -> ");
+
+                summary.push_str(&format!(", forwarded through {}", hops));
+            }
+        }
+
+        summary
+    }
+}
+
+/// A packet event emitted on the channel end.
+#[derive(Debug, Serialize)]
+struct PacketEventEntry {
+    height: Height,
+    event_type: &'static str,
+    sequence: Sequence,
+    data: EventData,
+}
+
+impl PacketEventEntry {
+    fn new(event_id: WithBlockDataType, event: &IbcEvent, version: &Version) -> Option<Self> {
+        let packet = event.packet()?;
+
+        Some(Self {
+            height: event.height(),
+            event_type: event_id.as_str(),
+            sequence: packet.sequence,
+            data: EventData::decode(version, &packet.data),
+        })
+    }
+}
+
+/// Returns the range of heights to scan, given the `latest` height of the chain.
+///
+/// The range ends at `to_height`, or at the latest height if unspecified, and
+/// starts either at `from_height` or `last_n_blocks` blocks before its end.
+fn height_range(
+    latest: u64,
+    from_height: Option<u64>,
+    to_height: Option<u64>,
+    last_n_blocks: Option<u64>,
+) -> Result<(u64, u64), Error> {
+    let end = to_height.unwrap_or(latest).min(latest);
+
+    let start = match (from_height, last_n_blocks) {
+        (Some(from_height), _) => from_height,
+        (None, Some(n)) => end.saturating_sub(n.saturating_sub(1)).max(1),
+        (None, None) => {
+            return Err(Error::cli_arg(
+                "either --from-height or --last-n-blocks must be specified".to_string(),
+            ))
+        }
+    };
+
+    if start == 0 || start > end {
+        return Err(Error::cli_arg(format!(
+            "invalid height range {}..={} (latest height is {})",
+            start, end, latest
+        )));
+    }
+
+    Ok((start, end))
+}
+
+/// Lists the given entries, grouped by event type in the order of [`PACKET_EVENTS`],
+/// in chronological order, keeping only those for the given sequence if any.
+fn chronological(
+    entries: Vec<Vec<PacketEventEntry>>,
+    sequence: Option<Sequence>,
+) -> Vec<PacketEventEntry> {
+    let mut entries = entries
+        .into_iter()
+        .flatten()
+        .filter(|entry| sequence.map_or(true, |sequence| entry.sequence == sequence))
+        .collect::<Vec<_>>();
+
+    // The sort is stable, so the events emitted at the same height
+    // remain in the order of the lifecycle of a packet.
+    entries.sort_by_key(|entry| entry.height);
+    entries
+}
+
+/// Display, in chronological order, the packet events emitted on a channel end
+/// within a range of heights.
+///
+/// The `send_packet`, `acknowledge_packet` and `timeout_packet` events of the packets
+/// sent on the channel end, and the `write_acknowledgement` events of the packets
+/// received on it, are fetched by searching the transactions of the chain.
+/// The data of ICS-20 packets is decoded.
+///
+/// `query packet event-log --chain <CHAIN_ID> --port <PORT_ID> --channel <CHANNEL_ID>
+///     (--from-height <FROM_HEIGHT> | --last-n-blocks <N>) [--to-height <TO_HEIGHT>]
+///     [--sequence-filter <SEQ>]`
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct QueryPacketEventLogCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain to query"
+    )]
+    chain_id: ChainId,
+
+    #[clap(
+        long = "port",
+        required = true,
+        value_name = "PORT_ID",
+        parse(try_from_str = parse_port_id),
+        help_heading = "REQUIRED",
+        help = "Identifier of the port to query"
+    )]
+    port_id: PortId,
+
+    #[clap(
+        long = "channel",
+        visible_alias = "chan",
+        required = true,
+        value_name = "CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
+        help_heading = "REQUIRED",
+        help = "Identifier of the channel to query"
+    )]
+    channel_id: ChannelId,
+
+    #[clap(
+        long = "from-height",
+        required = true,
+        value_name = "FROM_HEIGHT",
+        group = "range_start",
+        help_heading = "REQUIRED",
+        help = "Height of the first block to scan"
+    )]
+    from_height: Option<u64>,
+
+    #[clap(
+        long = "last-n-blocks",
+        required = true,
+        value_name = "N",
+        group = "range_start",
+        help_heading = "REQUIRED",
+        help = "Number of blocks to scan, ending at the latest height or at --to-height"
+    )]
+    last_n_blocks: Option<u64>,
+
+    #[clap(
+        long = "to-height",
+        value_name = "TO_HEIGHT",
+        help = "Height of the last block to scan, defaults to the latest height"
+    )]
+    to_height: Option<u64>,
+
+    #[clap(
+        long = "sequence-filter",
+        value_name = "SEQ",
+        help = "Only show the events of the packet with the given sequence number"
+    )]
+    sequence: Option<Sequence>,
+}
+
+impl QueryPacketEventLogCmd {
+    fn execute(&self) -> Result<Vec<PacketEventEntry>, Error> {
+        let config = app_config();
+
+        let chain = spawn_chain_runtime(&config, &self.chain_id)?;
+
+        let latest = chain
+            .query_application_status()
+            .map_err(Error::relayer)?
+            .height;
+
+        let (start, end) = height_range(
+            latest.revision_height(),
+            self.from_height,
+            self.to_height,
+            self.last_n_blocks,
+        )?;
+
+        let at = |revision_height| {
+            Height::new(latest.revision_number(), revision_height)
+                .map_err(|e| Error::cli_arg(format!("invalid height {}: {}", revision_height, e)))
+        };
+        let (start_height, end_height) = (at(start)?, at(end)?);
+
+        // The format of the data of ICS-20 packets depends on the version of the channel
+        let (channel, _) = chain
+            .query_channel(
+                QueryChannelRequest {
+                    port_id: self.port_id.clone(),
+                    channel_id: self.channel_id.clone(),
+                    height: QueryHeight::Latest,
+                },
+                IncludeProof::No,
+            )
+            .map_err(Error::relayer)?;
+
+        debug!(
+            "querying packet events on {}/{} of {} between heights {} and {}",
+            self.port_id, self.channel_id, self.chain_id, start_height, end_height
+        );
+
+        let entries = PACKET_EVENTS
+            .iter()
+            .map(|event_id| {
+                let events = chain
+                    .query_txs(QueryTxRequest::PacketRange(QueryPacketEventRangeRequest {
+                        event_id: event_id.clone(),
+                        port_id: self.port_id.clone(),
+                        channel_id: self.channel_id.clone(),
+                        start_height,
+                        end_height,
+                    }))
+                    .map_err(Error::relayer)?;
+
+                Ok(events
+                    .iter()
+                    .filter_map(|event| {
+                        PacketEventEntry::new(event_id.clone(), event, channel.version())
+                    })
+                    .collect())
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(chronological(entries, self.sequence))
+    }
+}
+
+impl Runnable for QueryPacketEventLogCmd {
+    fn run(&self) {
+        let entries = match self.execute() {
+            Ok(entries) => entries,
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        };
+
+        if json() {
+            Output::success(entries).exit()
+        }
+
+        let lines = entries
+            .iter()
+            .map(|entry| {
+                format!(
+                    "{:>10}  {:<22}  #{:<7}  {}",
+                    entry.height.revision_height(),
+                    entry.event_type,
+                    u64::from(entry.sequence),
+                    entry.data.summary()
+                )
+            })
+            .collect::<Vec<_>>();
+
+        Output::success_msg(format!(
+            "{} packet event(s) found\n{}",
+            entries.len(),
+            lines.join("\n")
+        ))
+        .exit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{chronological, height_range, EventData, PacketEventEntry, QueryPacketEventLogCmd};
+
+    use std::str::FromStr;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics04_channel::packet::Sequence;
+    use ibc::core::ics04_channel::Version;
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+    use ibc::Height;
+
+    fn entry(height: u64, event_type: &'static str, sequence: u64) -> PacketEventEntry {
+        PacketEventEntry {
+            height: Height::new(0, height).unwrap(),
+            event_type,
+            sequence: Sequence::from(sequence),
+            data: EventData::Raw(String::new()),
+        }
+    }
+
+    #[test]
+    fn test_query_packet_event_log() {
+        assert_eq!(
+            QueryPacketEventLogCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                port_id: PortId::from_str("transfer").unwrap(),
+                channel_id: ChannelId::from_str("channel-0").unwrap(),
+                from_height: Some(100),
+                last_n_blocks: None,
+                to_height: Some(200),
+                sequence: Some(Sequence::from(7)),
+            },
+            QueryPacketEventLogCmd::parse_from(&[
+                "test",
+                "--chain",
+                "chain_id",
+                "--port",
+                "transfer",
+                "--chan",
+                "channel-0",
+                "--from-height",
+                "100",
+                "--to-height",
+                "200",
+                "--sequence-filter",
+                "7"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_query_packet_event_log_last_n_blocks() {
+        assert_eq!(
+            QueryPacketEventLogCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                port_id: PortId::from_str("transfer").unwrap(),
+                channel_id: ChannelId::from_str("channel-0").unwrap(),
+                from_height: None,
+                last_n_blocks: Some(50),
+                to_height: None,
+                sequence: None,
+            },
+            QueryPacketEventLogCmd::parse_from(&[
+                "test",
+                "--chain",
+                "chain_id",
+                "--port",
+                "transfer",
+                "--channel",
+                "channel-0",
+                "--last-n-blocks",
+                "50"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_query_packet_event_log_range_start() {
+        // Neither --from-height nor --last-n-blocks
+        assert!(QueryPacketEventLogCmd::try_parse_from(&[
+            "test",
+            "--chain",
+            "chain_id",
+            "--port",
+            "transfer",
+            "--channel",
+            "channel-0"
+        ])
+        .is_err());
+
+        // Both --from-height and --last-n-blocks
+        assert!(QueryPacketEventLogCmd::try_parse_from(&[
+            "test",
+            "--chain",
+            "chain_id",
+            "--port",
+            "transfer",
+            "--channel",
+            "channel-0",
+            "--from-height",
+            "100",
+            "--last-n-blocks",
+            "50"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_height_range() {
+        assert_eq!(
+            height_range(1000, Some(10), None, None).unwrap(),
+            (10, 1000)
+        );
+        assert_eq!(
+            height_range(1000, Some(10), Some(20), None).unwrap(),
+            (10, 20)
+        );
+        assert_eq!(
+            height_range(1000, None, None, Some(50)).unwrap(),
+            (951, 1000)
+        );
+        assert_eq!(
+            height_range(1000, None, Some(500), Some(50)).unwrap(),
+            (451, 500)
+        );
+        assert_eq!(height_range(30, None, None, Some(50)).unwrap(), (1, 30));
+
+        // The range cannot go past the latest height
+        assert_eq!(
+            height_range(1000, Some(10), Some(2000), None).unwrap(),
+            (10, 1000)
+        );
+
+        assert!(height_range(1000, Some(20), Some(10), None).is_err());
+        assert!(height_range(1000, Some(0), None, None).is_err());
+        assert!(height_range(1000, None, None, None).is_err());
+    }
+
+    #[test]
+    fn test_chronological() {
+        let entries = vec![
+            vec![entry(10, "send_packet", 1), entry(12, "send_packet", 2)],
+            vec![entry(11, "write_acknowledgement", 5)],
+            vec![entry(12, "acknowledge_packet", 1)],
+            vec![],
+        ];
+
+        let ordered = chronological(entries, None)
+            .into_iter()
+            .map(|entry| (entry.height.revision_height(), entry.event_type))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            ordered,
+            vec![
+                (10, "send_packet"),
+                (11, "write_acknowledgement"),
+                (12, "send_packet"),
+                (12, "acknowledge_packet"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chronological_sequence_filter() {
+        let entries = vec![
+            vec![entry(10, "send_packet", 1), entry(12, "send_packet", 2)],
+            vec![entry(13, "acknowledge_packet", 1)],
+        ];
+
+        let filtered = chronological(entries, Some(Sequence::from(1)));
+
+        assert_eq!(filtered.len(), 2);
+        assert!(filtered
+            .iter()
+            .all(|entry| entry.sequence == Sequence::from(1)));
+    }
+
+    const PACKET_DATA_V2: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../modules/tests/support/transfer/packet_data_v2.bin"
+    ));
+
+    #[test]
+    fn test_decode_event_data() {
+        let transfer =
+            br#"{"amount":"100","denom":"samoleans","receiver":"cosmos1b","sender":"cosmos1a"}"#;
+
+        match EventData::decode(&Version::ics20(), transfer) {
+            EventData::Transfer(data) => {
+                assert_eq!(data.tokens()[0].amount.to_string(), "100");
+                assert_eq!(data.sender().to_string(), "cosmos1a");
+                assert_eq!(data.receiver().to_string(), "cosmos1b");
+            }
+            data => panic!("unexpected packet data: {:?}", data),
+        }
+
+        assert_eq!(
+            EventData::decode(&Version::ics20(), b"hello"),
+            EventData::Raw("hello".to_string())
+        );
+    }
+
+    #[test]
+    fn test_decode_event_data_v2() {
+        let version = Version::new("ics20-2".to_string());
+        let data = EventData::decode(&version, PACKET_DATA_V2);
+
+        assert_eq!(
+            data.summary(),
+            "1000-transfer/channel-7/uatom, 25-stake from cosmos1sender to osmo1receiver, \
+            forwarded through transfer/channel-3"
+        );
+    }
+}
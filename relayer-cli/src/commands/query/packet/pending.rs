@@ -2,13 +2,14 @@ use abscissa_core::clap::Parser;
 use abscissa_core::{Command, Runnable};
 use serde::Serialize;
 
+use ibc::core::ics04_channel::channel::{IdentifiedChannelEnd, State};
 use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
 use ibc_relayer::chain::counterparty::{
     channel_on_destination, pending_packet_summary, PendingPackets,
 };
-use ibc_relayer::chain::handle::BaseChainHandle;
+use ibc_relayer::chain::handle::{BaseChainHandle, ChainHandle};
 
-use crate::cli_utils::spawn_chain_counterparty;
+use crate::cli_utils::{parse_channel_id, parse_port_id, spawn_chain_counterparty};
 use crate::conclude::Output;
 use crate::error::Error;
 use crate::prelude::*;
@@ -21,11 +22,71 @@ struct Summary {
     src: PendingPackets,
     /// The packets sent on the counterparty chain.
     dst: PendingPackets,
+    /// How many packets and acknowledgements are pending, on each side and overall.
+    totals: Totals,
+}
+
+#[derive(Debug, Serialize)]
+struct Totals {
+    src: PendingCounts,
+    dst: PendingCounts,
+    all: PendingCounts,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+struct PendingCounts {
+    unreceived_packets: usize,
+    unreceived_acks: usize,
+}
+
+impl From<&PendingPackets> for PendingCounts {
+    fn from(pending: &PendingPackets) -> Self {
+        Self {
+            unreceived_packets: pending.unreceived_packets.len(),
+            unreceived_acks: pending.unreceived_acks.len(),
+        }
+    }
+}
+
+impl Summary {
+    fn new(src: PendingPackets, dst: PendingPackets) -> Self {
+        let src_counts = PendingCounts::from(&src);
+        let dst_counts = PendingCounts::from(&dst);
+
+        let totals = Totals {
+            all: PendingCounts {
+                unreceived_packets: src_counts.unreceived_packets + dst_counts.unreceived_packets,
+                unreceived_acks: src_counts.unreceived_acks + dst_counts.unreceived_acks,
+            },
+            src: src_counts,
+            dst: dst_counts,
+        };
+
+        Self { src, dst, totals }
+    }
+}
+
+/// Packets can only be sent once both ends of the channel completed the handshake,
+/// and remain pending once it is closed.
+fn check_handshake_completed(
+    chain_id: &ChainId,
+    channel: &IdentifiedChannelEnd,
+) -> Result<(), Error> {
+    match channel.channel_end.state() {
+        State::Open | State::Closed => Ok(()),
+        state => Err(Error::channel_not_open(
+            chain_id.clone(),
+            channel.port_id.clone(),
+            channel.channel_id.clone(),
+            *state,
+        )),
+    }
 }
 
 /// This command does the following:
 ///
-/// 1. queries the chain to get its counterparty chain, channel and port identifiers (needed in 2)
+/// 1. queries the chain to get its counterparty chain, channel and port identifiers (needed in 2),
+///    and checks that both ends of the channel completed the handshake
 /// 2. queries both chains for all packet commitments/ sequences for the given port and channel
 ///    and its counterparty.
 /// 3. queries both chains for the unreceived sequences and acks out of the lists obtained in 2.
@@ -44,6 +105,7 @@ pub struct QueryPendingPacketsCmd {
         long = "port",
         required = true,
         value_name = "PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "REQUIRED",
         help = "Port identifier on the chain given by <CHAIN_ID>"
     )]
@@ -54,6 +116,7 @@ pub struct QueryPendingPacketsCmd {
         visible_alias = "chan",
         required = true,
         value_name = "CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
         help_heading = "REQUIRED",
         help = "Channel identifier on the chain given by <CHAIN_ID>"
     )]
@@ -77,21 +140,24 @@ impl QueryPendingPacketsCmd {
             chan_conn_cli.channel
         );
 
-        let src_summary = pending_packet_summary(&chains.src, &chains.dst, &chan_conn_cli.channel)
-            .map_err(Error::supervisor)?;
+        check_handshake_completed(&chains.src.id(), &chan_conn_cli.channel)?;
+
         let counterparty_channel = channel_on_destination(
             &chan_conn_cli.channel,
             &chan_conn_cli.connection,
             &chains.dst,
         )
         .map_err(Error::supervisor)?
-        .ok_or_else(|| Error::missing_counterparty_channel_id(chan_conn_cli.channel))?;
+        .ok_or_else(|| Error::missing_counterparty_channel_id(chan_conn_cli.channel.clone()))?;
+
+        check_handshake_completed(&chains.dst.id(), &counterparty_channel)?;
+
+        let src_summary = pending_packet_summary(&chains.src, &chains.dst, &chan_conn_cli.channel)
+            .map_err(Error::supervisor)?;
         let dst_summary = pending_packet_summary(&chains.dst, &chains.src, &counterparty_channel)
             .map_err(Error::supervisor)?;
-        Ok(Summary {
-            src: src_summary,
-            dst: dst_summary,
-        })
+
+        Ok(Summary::new(src_summary, dst_summary))
     }
 }
 
@@ -106,12 +172,76 @@ impl Runnable for QueryPendingPacketsCmd {
 
 #[cfg(test)]
 mod tests {
-    use super::QueryPendingPacketsCmd;
+    use super::{check_handshake_completed, PendingCounts, QueryPendingPacketsCmd, Summary};
 
     use std::str::FromStr;
 
     use abscissa_core::clap::Parser;
+    use ibc::core::ics04_channel::channel::{ChannelEnd, IdentifiedChannelEnd, State};
+    use ibc::core::ics04_channel::packet::Sequence;
     use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+    use ibc_relayer::chain::counterparty::PendingPackets;
+
+    fn sequences(sequences: &[u64]) -> Vec<Sequence> {
+        sequences.iter().copied().map(Sequence::from).collect()
+    }
+
+    #[test]
+    fn test_summary_totals() {
+        let summary = Summary::new(
+            PendingPackets {
+                unreceived_packets: sequences(&[3, 4, 5]),
+                unreceived_acks: sequences(&[1]),
+            },
+            PendingPackets {
+                unreceived_packets: vec![],
+                unreceived_acks: sequences(&[7, 8]),
+            },
+        );
+
+        assert_eq!(
+            summary.totals.src,
+            PendingCounts {
+                unreceived_packets: 3,
+                unreceived_acks: 1
+            }
+        );
+        assert_eq!(
+            summary.totals.dst,
+            PendingCounts {
+                unreceived_packets: 0,
+                unreceived_acks: 2
+            }
+        );
+        assert_eq!(
+            summary.totals.all,
+            PendingCounts {
+                unreceived_packets: 3,
+                unreceived_acks: 3
+            }
+        );
+    }
+
+    #[test]
+    fn test_check_handshake_completed() {
+        let chain_id = ChainId::from_string("chain_id");
+        let channel = |state| IdentifiedChannelEnd {
+            port_id: PortId::transfer(),
+            channel_id: ChannelId::new(7),
+            channel_end: ChannelEnd {
+                state,
+                ..ChannelEnd::default()
+            },
+        };
+
+        assert!(check_handshake_completed(&chain_id, &channel(State::Open)).is_ok());
+        assert!(check_handshake_completed(&chain_id, &channel(State::Closed)).is_ok());
+
+        let error = check_handshake_completed(&chain_id, &channel(State::TryOpen)).unwrap_err();
+        assert!(error.to_string().contains("is in state TRYOPEN"));
+
+        assert!(check_handshake_completed(&chain_id, &channel(State::Init)).is_err());
+    }
 
     #[test]
     fn test_query_packet_pending() {
@@ -6,7 +6,7 @@ use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
 use ibc_relayer::chain::counterparty::unreceived_packets;
 use ibc_relayer::chain::handle::BaseChainHandle;
 
-use crate::cli_utils::spawn_chain_counterparty;
+use crate::cli_utils::{parse_channel_id, parse_port_id, spawn_chain_counterparty};
 use crate::conclude::Output;
 use crate::error::Error;
 use crate::prelude::*;
@@ -30,6 +30,7 @@ pub struct QueryPendingSendsCmd {
         long = "port",
         required = true,
         value_name = "PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "REQUIRED",
         help = "Port identifier"
     )]
@@ -40,6 +41,7 @@ pub struct QueryPendingSendsCmd {
         visible_alias = "chan",
         required = true,
         value_name = "CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
         help_heading = "REQUIRED",
         help = "Channel identifier"
     )]
@@ -0,0 +1,356 @@
+use core::time::Duration;
+use std::thread;
+use std::time::Instant;
+
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use console::{style, Term};
+use serde::Serialize;
+
+use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc::events::{IbcEvent, WithBlockDataType};
+use ibc::timestamp::Timestamp;
+use ibc_relayer::chain::counterparty::{commitments_on_chain, unreceived_packets_sequences};
+use ibc_relayer::chain::handle::{BaseChainHandle, ChainHandle};
+use ibc_relayer::chain::requests::{QueryHeight, QueryPacketEventDataRequest, QueryTxRequest};
+
+use crate::cli_utils::{
+    parse_channel_id, parse_port_id, query_block_timestamp, resolve_chain_id,
+    spawn_chain_counterparty, ChainHandlePair,
+};
+use crate::conclude::{json, Output};
+use crate::error::Error;
+use crate::prelude::*;
+
+/// How often the countdown is refreshed in `--watch` mode.
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often the pending packets are scanned again in `--watch` mode,
+/// to pick up the packets which were relayed or sent in the meantime.
+const RESCAN_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The pending packet with the nearest expiry on a channel.
+#[derive(Debug, Serialize)]
+struct PacketExpiry {
+    sequence: Sequence,
+    timeout_timestamp: Timestamp,
+    /// Time remaining until the packet expires, as measured
+    /// against the latest block time of the destination chain.
+    remaining_secs: u64,
+    /// Time between the block in which the packet was sent and its expiry.
+    total_secs: u64,
+}
+
+impl PacketExpiry {
+    /// The countdown to the expiry of the packet, `elapsed` after it was queried.
+    fn countdown(&self, elapsed: Duration) -> String {
+        let remaining = Duration::from_secs(self.remaining_secs).saturating_sub(elapsed);
+        let total = Duration::from_secs(self.total_secs);
+
+        let line = format!(
+            "packet #{} expires in {}",
+            self.sequence,
+            format_countdown(remaining)
+        );
+
+        if is_urgent(remaining, total) {
+            style(line).red().to_string()
+        } else {
+            line
+        }
+    }
+}
+
+/// Formats the given duration as `HH:MM:SS`.
+fn format_countdown(remaining: Duration) -> String {
+    let secs = remaining.as_secs();
+
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs / 3600,
+        (secs % 3600) / 60,
+        secs % 60
+    )
+}
+
+/// A packet is urgent to relay when less than 10% of its total timeout remains.
+fn is_urgent(remaining: Duration, total: Duration) -> bool {
+    remaining * 10 < total
+}
+
+/// Display the time remaining until the oldest pending packet sent on a channel expires.
+///
+/// The packets sent on the source channel which have not been received on the destination
+/// chain are scanned, and the one whose timeout timestamp is the nearest is selected.
+/// Packets without a timeout timestamp are ignored. The countdown is displayed in red when
+/// less than 10% of the total timeout of the packet remains.
+///
+/// `query packet timeout-countdown --src-chain <SRC_CHAIN_ID> --src-port <SRC_PORT_ID>
+///     --src-channel <SRC_CHANNEL_ID> --dst-chain <DST_CHAIN_ID> [--watch]`
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct QueryPacketTimeoutCountdownCmd {
+    #[clap(
+        long = "src-chain",
+        required = true,
+        value_name = "SRC_CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain on which the packets are sent"
+    )]
+    src_chain_id: ChainId,
+
+    #[clap(
+        long = "src-port",
+        required = true,
+        value_name = "SRC_PORT_ID",
+        parse(try_from_str = parse_port_id),
+        help_heading = "REQUIRED",
+        help = "Identifier of the port on the source chain"
+    )]
+    src_port_id: PortId,
+
+    #[clap(
+        long = "src-channel",
+        visible_alias = "src-chan",
+        required = true,
+        value_name = "SRC_CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
+        help_heading = "REQUIRED",
+        help = "Identifier of the channel on the source chain"
+    )]
+    src_channel_id: ChannelId,
+
+    #[clap(
+        long = "dst-chain",
+        required = true,
+        value_name = "DST_CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain to which the packets are sent"
+    )]
+    dst_chain_id: ChainId,
+
+    #[clap(
+        long = "watch",
+        help = "Continuously refresh the countdown until the command is interrupted"
+    )]
+    watch: bool,
+}
+
+impl QueryPacketTimeoutCountdownCmd {
+    fn spawn_chains(&self) -> Result<(ChainHandlePair, PortId, ChannelId), Error> {
+        let config = app_config();
+
+        let (chains, chan_conn_cli) = spawn_chain_counterparty::<BaseChainHandle>(
+            &config,
+            &self.src_chain_id,
+            &self.src_port_id,
+            &self.src_channel_id,
+        )?;
+
+        if chains.dst.id() != resolve_chain_id(&config, &self.dst_chain_id)? {
+            return Err(Error::cli_arg(format!(
+                "channel '{}' on chain '{}' leads to chain '{}', not '{}'",
+                self.src_channel_id,
+                self.src_chain_id,
+                chains.dst.id(),
+                self.dst_chain_id
+            )));
+        }
+
+        let counterparty = chan_conn_cli.channel.channel_end.counterparty().clone();
+        let dst_channel_id = counterparty
+            .channel_id()
+            .cloned()
+            .ok_or_else(|| Error::missing_counterparty_channel_id(chan_conn_cli.channel))?;
+
+        Ok((chains, counterparty.port_id().clone(), dst_channel_id))
+    }
+
+    fn nearest_expiry(
+        &self,
+        chains: &ChainHandlePair,
+        dst_port_id: &PortId,
+        dst_channel_id: &ChannelId,
+    ) -> Result<Option<PacketExpiry>, Error> {
+        let (commitments, height) =
+            commitments_on_chain(&chains.src, &self.src_port_id, &self.src_channel_id)
+                .map_err(Error::supervisor)?;
+
+        let sequences =
+            unreceived_packets_sequences(&chains.dst, dst_port_id, dst_channel_id, commitments)
+                .map_err(Error::supervisor)?;
+
+        if sequences.is_empty() {
+            return Ok(None);
+        }
+
+        let events = chains
+            .src
+            .query_txs(QueryTxRequest::Packet(QueryPacketEventDataRequest {
+                event_id: WithBlockDataType::SendPacket,
+                source_channel_id: self.src_channel_id.clone(),
+                source_port_id: self.src_port_id.clone(),
+                destination_channel_id: dst_channel_id.clone(),
+                destination_port_id: dst_port_id.clone(),
+                sequences,
+                height: QueryHeight::Specific(height),
+            }))
+            .map_err(Error::relayer)?;
+
+        let nearest = events
+            .into_iter()
+            .filter_map(|event| match event {
+                IbcEvent::SendPacket(send_packet) => Some(send_packet),
+                _ => None,
+            })
+            .filter(|send_packet| send_packet.packet.timeout_timestamp.nanoseconds() != 0)
+            .min_by_key(|send_packet| send_packet.packet.timeout_timestamp.nanoseconds());
+
+        let send_packet = match nearest {
+            Some(send_packet) => send_packet,
+            None => return Ok(None),
+        };
+
+        let dst_time = chains
+            .dst
+            .query_application_status()
+            .map_err(Error::relayer)?
+            .timestamp;
+        let sent_at = query_block_timestamp(&chains.src, send_packet.height)?;
+
+        let timeout_timestamp = send_packet.packet.timeout_timestamp;
+
+        // `duration_since` returns `None` if the packet has already expired
+        let remaining = timeout_timestamp
+            .duration_since(&dst_time)
+            .unwrap_or_default();
+        let total = timeout_timestamp
+            .duration_since(&sent_at)
+            .unwrap_or(remaining);
+
+        Ok(Some(PacketExpiry {
+            sequence: send_packet.packet.sequence,
+            timeout_timestamp,
+            remaining_secs: remaining.as_secs(),
+            total_secs: total.as_secs(),
+        }))
+    }
+
+    fn watch(
+        &self,
+        chains: &ChainHandlePair,
+        dst_port_id: &PortId,
+        dst_channel_id: &ChannelId,
+    ) -> ! {
+        let term = Term::stdout();
+
+        loop {
+            let scanned_at = Instant::now();
+            let expiry = self.nearest_expiry(chains, dst_port_id, dst_channel_id);
+
+            while scanned_at.elapsed() < RESCAN_INTERVAL {
+                let line = match &expiry {
+                    Ok(Some(expiry)) => expiry.countdown(scanned_at.elapsed()),
+                    Ok(None) => "no pending packet with a timeout timestamp".to_string(),
+                    Err(e) => format!("failed to scan the pending packets: {}", e),
+                };
+
+                let _ = term.clear_line().and_then(|_| term.write_str(&line));
+
+                thread::sleep(TICK_INTERVAL);
+            }
+        }
+    }
+}
+
+impl Runnable for QueryPacketTimeoutCountdownCmd {
+    fn run(&self) {
+        let (chains, dst_port_id, dst_channel_id) = match self.spawn_chains() {
+            Ok(result) => result,
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        };
+
+        if self.watch {
+            self.watch(&chains, &dst_port_id, &dst_channel_id);
+        }
+
+        match self.nearest_expiry(&chains, &dst_port_id, &dst_channel_id) {
+            Ok(Some(expiry)) if json() => Output::success(expiry).exit(),
+            Ok(Some(expiry)) => Output::success_msg(expiry.countdown(Duration::ZERO)).exit(),
+            Ok(None) => Output::success_msg("no pending packet with a timeout timestamp").exit(),
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_countdown, is_urgent, QueryPacketTimeoutCountdownCmd};
+
+    use core::time::Duration;
+    use std::str::FromStr;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+
+    #[test]
+    fn test_query_packet_timeout_countdown() {
+        assert_eq!(
+            QueryPacketTimeoutCountdownCmd {
+                src_chain_id: ChainId::from_string("chain_a"),
+                src_port_id: PortId::from_str("transfer").unwrap(),
+                src_channel_id: ChannelId::from_str("channel-0").unwrap(),
+                dst_chain_id: ChainId::from_string("chain_b"),
+                watch: true,
+            },
+            QueryPacketTimeoutCountdownCmd::parse_from(&[
+                "test",
+                "--src-chain",
+                "chain_a",
+                "--src-port",
+                "transfer",
+                "--src-chan",
+                "channel-0",
+                "--dst-chain",
+                "chain_b",
+                "--watch"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_query_packet_timeout_countdown_no_dst_chain() {
+        assert!(QueryPacketTimeoutCountdownCmd::try_parse_from(&[
+            "test",
+            "--src-chain",
+            "chain_a",
+            "--src-port",
+            "transfer",
+            "--src-channel",
+            "channel-0"
+        ])
+        .is_err())
+    }
+
+    #[test]
+    fn test_format_countdown() {
+        assert_eq!(format_countdown(Duration::ZERO), "00:00:00");
+        assert_eq!(
+            format_countdown(Duration::from_secs(3 * 3600 + 25 * 60 + 7)),
+            "03:25:07"
+        );
+        assert_eq!(
+            format_countdown(Duration::from_secs(125 * 3600)),
+            "125:00:00"
+        );
+    }
+
+    #[test]
+    fn test_is_urgent() {
+        let total = Duration::from_secs(1000);
+
+        assert!(!is_urgent(Duration::from_secs(100), total));
+        assert!(is_urgent(Duration::from_secs(99), total));
+        assert!(is_urgent(Duration::ZERO, total));
+    }
+}
@@ -0,0 +1,435 @@
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+use serde::Serialize;
+
+use ibc::core::ics04_channel::channel::Order;
+use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc::events::IbcEventType;
+use ibc::Height;
+use ibc_relayer::chain::handle::{BaseChainHandle, ChainHandle};
+use ibc_relayer::chain::requests::{
+    IncludeProof, QueryHeight, QueryNextSequenceReceiveRequest, QueryNextSequenceSendRequest,
+    QueryPacketCommitmentRequest, QueryPacketReceiptRequest, QueryPacketTxRequest,
+};
+
+use crate::cli_utils::{parse_channel_id, parse_port_id, spawn_chain_counterparty};
+use crate::conclude::{json, Output};
+use crate::error::Error;
+use crate::prelude::*;
+
+/// The stage a packet reached in its lifecycle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum PacketStage {
+    /// No packet was sent with the sequence yet
+    NotSent,
+    /// The packet was sent, but not received on the destination chain
+    Sent,
+    /// The packet was received, but its acknowledgement was not relayed back
+    Received,
+    /// The acknowledgement of the packet was relayed back, which deleted its commitment
+    Acked,
+    /// The packet timed out, which deleted its commitment
+    TimedOut,
+}
+
+impl PacketStage {
+    /// The stage of a packet, given whether its sequence was used on the source channel end,
+    /// whether the commitment to the packet is still stored there, and whether the
+    /// destination chain received it.
+    fn new(sent: bool, committed: bool, received: bool) -> Self {
+        match (sent, committed, received) {
+            (false, _, _) => PacketStage::NotSent,
+            (true, true, false) => PacketStage::Sent,
+            (true, true, true) => PacketStage::Received,
+            (true, false, true) => PacketStage::Acked,
+            // Only a timeout deletes the commitment to a packet which was not received
+            (true, false, false) => PacketStage::TimedOut,
+        }
+    }
+
+    fn describe(&self, ordering: Order) -> &'static str {
+        match self {
+            PacketStage::NotSent => "was not sent yet",
+            PacketStage::Sent => "was sent, but not received yet",
+            PacketStage::Received => {
+                "was received, but its acknowledgement was not relayed back yet"
+            }
+            PacketStage::Acked => "was received and acknowledged",
+            PacketStage::TimedOut if ordering == Order::Ordered => {
+                "timed out, which closed the ordered channel"
+            }
+            PacketStage::TimedOut => "timed out",
+        }
+    }
+}
+
+/// A step of the lifecycle of a packet, and the transaction which performed it.
+#[derive(Debug, Serialize)]
+struct PacketStep {
+    event_type: &'static str,
+    chain_id: ChainId,
+    /// The height of the transaction, unless the node pruned or did not index it
+    height: Option<Height>,
+    /// The hash of the transaction, unless the node pruned or did not index it
+    tx_hash: Option<String>,
+}
+
+impl PacketStep {
+    fn summary(&self) -> String {
+        let tx = match (&self.height, &self.tx_hash) {
+            (Some(height), Some(tx_hash)) => format!("height {}  tx {}", height, tx_hash),
+            _ => "transaction not found, the node pruned or did not index it".to_string(),
+        };
+
+        // The identifiers do not honor the width of the format
+        let chain_id = self.chain_id.to_string();
+
+        format!("{:<24} {:<12} {}", self.event_type, chain_id, tx)
+    }
+}
+
+/// The lifecycle of a packet, from the source channel end to the destination one and back.
+#[derive(Debug, Serialize)]
+struct PacketTrace {
+    chain_id: ChainId,
+    port_id: PortId,
+    channel_id: ChannelId,
+    sequence: Sequence,
+    ordering: Order,
+    stage: PacketStage,
+    /// The steps the packet went through, in order
+    steps: Vec<PacketStep>,
+}
+
+/// Follow a packet through its lifecycle, from the transaction which sent it to the
+/// one which acknowledged it or timed it out.
+///
+/// The stage of the packet is derived from the state of both chains: the next sequence
+/// to send and the commitment to the packet on the source channel end, and on the
+/// destination channel end, the receipt of the packet, or the next sequence to receive
+/// for an ordered channel. The transactions of the steps the packet went through are
+/// then searched for by their events, and reported as not found if the node pruned them.
+///
+/// `query packet trace --chain <CHAIN_ID> --port <PORT_ID> --channel <CHANNEL_ID>
+///     --sequence <SEQUENCE>`
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct QueryPacketTraceCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain which sent the packet"
+    )]
+    chain_id: ChainId,
+
+    #[clap(
+        long = "port",
+        required = true,
+        value_name = "PORT_ID",
+        parse(try_from_str = parse_port_id),
+        help_heading = "REQUIRED",
+        help = "Identifier of the port which sent the packet"
+    )]
+    port_id: PortId,
+
+    #[clap(
+        long = "channel",
+        visible_alias = "chan",
+        required = true,
+        value_name = "CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
+        help_heading = "REQUIRED",
+        help = "Identifier of the channel which sent the packet"
+    )]
+    channel_id: ChannelId,
+
+    #[clap(
+        long = "sequence",
+        visible_alias = "seq",
+        required = true,
+        value_name = "SEQUENCE",
+        help_heading = "REQUIRED",
+        help = "Sequence of the packet to trace"
+    )]
+    sequence: Sequence,
+}
+
+impl QueryPacketTraceCmd {
+    fn execute(&self) -> Result<PacketTrace, Error> {
+        let config = app_config();
+
+        debug!("Options: {:?}", self);
+
+        let (chains, chan_conn_cli) = spawn_chain_counterparty::<BaseChainHandle>(
+            &config,
+            &self.chain_id,
+            &self.port_id,
+            &self.channel_id,
+        )?;
+
+        let ordering = *chan_conn_cli.channel.channel_end.ordering();
+        let counterparty = chan_conn_cli.channel.channel_end.counterparty().clone();
+        let dst_port_id = counterparty.port_id().clone();
+        let dst_channel_id = counterparty
+            .channel_id()
+            .cloned()
+            .ok_or_else(|| Error::missing_counterparty_channel_id(chan_conn_cli.channel))?;
+
+        // The chains are queried in the order of the lifecycle of a packet, so that
+        // a packet which moves on in the meantime is seen at a later stage, not at
+        // an inconsistent one.
+        let (next_sequence_send, _) = chains
+            .src
+            .query_next_sequence_send(
+                QueryNextSequenceSendRequest {
+                    port_id: self.port_id.clone(),
+                    channel_id: self.channel_id.clone(),
+                    height: QueryHeight::Latest,
+                },
+                IncludeProof::No,
+            )
+            .map_err(Error::relayer)?;
+
+        let (commitment, _) = chains
+            .src
+            .query_packet_commitment(
+                QueryPacketCommitmentRequest {
+                    port_id: self.port_id.clone(),
+                    channel_id: self.channel_id.clone(),
+                    sequence: self.sequence,
+                    height: QueryHeight::Latest,
+                },
+                IncludeProof::No,
+            )
+            .map_err(Error::relayer)?;
+
+        // Ordered channels do not store receipts, the packets are received in order
+        let received = if ordering == Order::Ordered {
+            let (next_sequence_receive, _) = chains
+                .dst
+                .query_next_sequence_receive(
+                    QueryNextSequenceReceiveRequest {
+                        port_id: dst_port_id.clone(),
+                        channel_id: dst_channel_id.clone(),
+                        height: QueryHeight::Latest,
+                    },
+                    IncludeProof::No,
+                )
+                .map_err(Error::relayer)?;
+
+            self.sequence < next_sequence_receive
+        } else {
+            let (receipt, _) = chains
+                .dst
+                .query_packet_receipt(
+                    QueryPacketReceiptRequest {
+                        port_id: dst_port_id.clone(),
+                        channel_id: dst_channel_id.clone(),
+                        sequence: self.sequence,
+                        height: QueryHeight::Latest,
+                    },
+                    IncludeProof::No,
+                )
+                .map_err(Error::relayer)?;
+
+            !receipt.is_empty()
+        };
+
+        let stage = PacketStage::new(
+            self.sequence < next_sequence_send,
+            !commitment.is_empty(),
+            received,
+        );
+
+        let src_step =
+            |event_type| self.query_step(&chains.src, event_type, &self.port_id, &self.channel_id);
+        let dst_step =
+            |event_type| self.query_step(&chains.dst, event_type, &dst_port_id, &dst_channel_id);
+
+        let mut steps = Vec::new();
+
+        if stage != PacketStage::NotSent {
+            steps.push(src_step(IbcEventType::SendPacket)?);
+        }
+
+        if matches!(stage, PacketStage::Received | PacketStage::Acked) {
+            steps.push(dst_step(IbcEventType::ReceivePacket)?);
+            steps.push(dst_step(IbcEventType::WriteAck)?);
+        }
+
+        match stage {
+            PacketStage::Acked => steps.push(src_step(IbcEventType::AckPacket)?),
+            PacketStage::TimedOut => {
+                // The packets of a channel closed in the meantime are timed out on close
+                let timeout = src_step(IbcEventType::Timeout)?;
+
+                if timeout.tx_hash.is_some() {
+                    steps.push(timeout);
+                } else {
+                    let timeout_on_close = src_step(IbcEventType::TimeoutOnClose)?;
+
+                    if timeout_on_close.tx_hash.is_some() {
+                        steps.push(timeout_on_close);
+                    } else {
+                        steps.push(timeout);
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Ok(PacketTrace {
+            chain_id: chains.src.id(),
+            port_id: self.port_id.clone(),
+            channel_id: self.channel_id.clone(),
+            sequence: self.sequence,
+            ordering,
+            stage,
+            steps,
+        })
+    }
+
+    fn query_step(
+        &self,
+        chain: &impl ChainHandle,
+        event_type: IbcEventType,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Result<PacketStep, Error> {
+        let tx = chain
+            .query_packet_tx(QueryPacketTxRequest {
+                event_type: event_type.clone(),
+                port_id: port_id.clone(),
+                channel_id: channel_id.clone(),
+                sequence: self.sequence,
+            })
+            .map_err(Error::relayer)?;
+
+        Ok(PacketStep {
+            event_type: event_type.as_str(),
+            chain_id: chain.id(),
+            height: tx.as_ref().map(|tx| tx.height),
+            tx_hash: tx.map(|tx| tx.tx_hash),
+        })
+    }
+}
+
+impl Runnable for QueryPacketTraceCmd {
+    fn run(&self) {
+        let trace = match self.execute() {
+            Ok(trace) => trace,
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        };
+
+        if json() {
+            Output::success(trace).exit()
+        }
+
+        let ordering = match trace.ordering {
+            Order::Ordered => "ordered",
+            _ => "unordered",
+        };
+
+        let mut lines = vec![format!(
+            "packet #{} sent on {}/{} of {} ({} channel) {}",
+            trace.sequence,
+            trace.port_id,
+            trace.channel_id,
+            trace.chain_id,
+            ordering,
+            trace.stage.describe(trace.ordering)
+        )];
+
+        lines.extend(
+            trace
+                .steps
+                .iter()
+                .map(|step| format!("  {}", step.summary())),
+        );
+
+        Output::success_msg(lines.join("\n")).exit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PacketStage, QueryPacketTraceCmd};
+
+    use std::str::FromStr;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics04_channel::packet::Sequence;
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+
+    #[test]
+    fn test_query_packet_trace() {
+        assert_eq!(
+            QueryPacketTraceCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                port_id: PortId::from_str("transfer").unwrap(),
+                channel_id: ChannelId::from_str("channel-0").unwrap(),
+                sequence: Sequence::from(7),
+            },
+            QueryPacketTraceCmd::parse_from(&[
+                "test",
+                "--chain",
+                "chain_id",
+                "--port",
+                "transfer",
+                "--channel",
+                "channel-0",
+                "--sequence",
+                "7"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_query_packet_trace_aliases() {
+        assert_eq!(
+            QueryPacketTraceCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                port_id: PortId::from_str("transfer").unwrap(),
+                channel_id: ChannelId::from_str("channel-0").unwrap(),
+                sequence: Sequence::from(7),
+            },
+            QueryPacketTraceCmd::parse_from(&[
+                "test",
+                "--chain",
+                "chain_id",
+                "--port",
+                "transfer",
+                "--chan",
+                "channel-0",
+                "--seq",
+                "7"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_query_packet_trace_no_seq() {
+        assert!(QueryPacketTraceCmd::try_parse_from(&[
+            "test",
+            "--chain",
+            "chain_id",
+            "--port",
+            "transfer",
+            "--channel",
+            "channel-0"
+        ])
+        .is_err())
+    }
+
+    #[test]
+    fn packet_stages() {
+        assert_eq!(PacketStage::new(false, false, false), PacketStage::NotSent);
+        assert_eq!(PacketStage::new(true, true, false), PacketStage::Sent);
+        assert_eq!(PacketStage::new(true, true, true), PacketStage::Received);
+        assert_eq!(PacketStage::new(true, false, true), PacketStage::Acked);
+        assert_eq!(PacketStage::new(true, false, false), PacketStage::TimedOut);
+    }
+}
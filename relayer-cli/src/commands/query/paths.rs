@@ -0,0 +1,191 @@
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+
+use ibc::timestamp::Timestamp;
+use ibc_relayer::chain::handle::{BaseChainHandle, ChainHandle};
+use ibc_relayer::path::health::{unhealthy_first, PathHealth};
+use ibc_relayer::registry::Registry;
+use ibc_relayer::supervisor::client_state_filter::FilterPolicy;
+use ibc_relayer::supervisor::dump_state::{SupervisorState, WorkerDesc};
+use ibc_relayer::supervisor::scan::{ChainScan, ChainScanner, ScanMode};
+
+use crate::cli_utils::query_rest_api;
+use crate::conclude::{json, Output};
+use crate::error::Error;
+use crate::prelude::*;
+
+/// Summarize the paths relayed by Hermes, and their health.
+///
+/// The paths are the channels found on the configured chains as on start, i.e. the
+/// channels allowed by their packet filters. For each of them, both ends of the channel,
+/// the clients they rely on, and the packets pending in both directions are queried.
+/// The status of the packet workers of the paths is included if the REST API of a
+/// running Hermes instance is reachable.
+///
+/// `query paths [--only-unhealthy]`
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct QueryPathsCmd {
+    #[clap(long = "only-unhealthy", help = "Only show the unhealthy paths")]
+    only_unhealthy: bool,
+}
+
+impl QueryPathsCmd {
+    fn execute(&self) -> Result<Vec<PathHealth>, Error> {
+        let config = app_config();
+
+        let mut registry = <Registry<BaseChainHandle>>::new((*config).clone());
+        let mut client_state_filter = FilterPolicy::default();
+
+        let scan = ChainScanner::new(
+            &config,
+            &mut registry,
+            &mut client_state_filter,
+            ScanMode::Auto,
+        )
+        .scan_chains();
+
+        let workers = match query_rest_api::<SupervisorState>(&config, "/state") {
+            Ok(state) => Some(state.workers.into_values().flatten().collect::<Vec<_>>()),
+            Err(e) => {
+                debug!("omitting the status of the packet workers: {}", e);
+                None
+            }
+        };
+
+        let now = Timestamp::now();
+        let mut paths = Vec::new();
+
+        // The chains which failed to be scanned were already reported by the scanner
+        for chain_scan in scan.chains.iter().flatten() {
+            let chain = registry
+                .get_or_spawn(&chain_scan.chain_id)
+                .map_err(Error::spawn)?;
+
+            query_chain_paths(
+                &mut registry,
+                &chain,
+                chain_scan,
+                workers.as_deref(),
+                now,
+                &mut paths,
+            );
+        }
+
+        if self.only_unhealthy {
+            paths.retain(|path| !path.is_healthy());
+        }
+
+        unhealthy_first(&mut paths);
+
+        Ok(paths)
+    }
+}
+
+/// Queries the health of the paths of the channels found on `chain`, skipping those
+/// already found on their counterparty chain.
+fn query_chain_paths(
+    registry: &mut Registry<BaseChainHandle>,
+    chain: &BaseChainHandle,
+    chain_scan: &ChainScan,
+    workers: Option<&[WorkerDesc]>,
+    now: Timestamp,
+    paths: &mut Vec<PathHealth>,
+) {
+    for client in chain_scan.clients.values() {
+        let counterparty_chain = match registry.get_or_spawn(&client.counterparty_chain_id()) {
+            Ok(counterparty_chain) => counterparty_chain,
+            Err(e) => {
+                warn!(
+                    chain = %chain.id(),
+                    client = %client.id(),
+                    "skipping the channels of the client, failed to spawn its counterparty chain: {}",
+                    e
+                );
+                continue;
+            }
+        };
+
+        let channels = client
+            .connections
+            .values()
+            .flat_map(|connection| connection.channels.values());
+
+        for channel in channels.map(|scan| &scan.channel) {
+            let (port_id, channel_id) = (&channel.port_id, &channel.channel_id);
+
+            if paths
+                .iter()
+                .any(|path| path.has_end(&chain_scan.chain_id, port_id, channel_id))
+            {
+                continue;
+            }
+
+            match PathHealth::query(chain, &counterparty_chain, port_id, channel_id, now) {
+                Ok(path) => paths.push(match workers {
+                    Some(workers) => path.with_workers(workers),
+                    None => path,
+                }),
+                Err(e) => warn!(
+                    chain = %chain.id(),
+                    port = %port_id,
+                    channel = %channel_id,
+                    "skipping channel, failed to query its path: {}",
+                    e
+                ),
+            }
+        }
+    }
+}
+
+impl Runnable for QueryPathsCmd {
+    fn run(&self) {
+        let paths = match self.execute() {
+            Ok(paths) => paths,
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        };
+
+        if json() {
+            Output::success(paths).exit()
+        }
+
+        if paths.is_empty() {
+            Output::success_msg("no paths found").exit()
+        }
+
+        let lines = paths.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        Output::success_msg(lines.join("\n")).exit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::QueryPathsCmd;
+
+    use abscissa_core::clap::Parser;
+
+    #[test]
+    fn test_query_paths() {
+        assert_eq!(
+            QueryPathsCmd {
+                only_unhealthy: false
+            },
+            QueryPathsCmd::parse_from(&["test"])
+        )
+    }
+
+    #[test]
+    fn test_query_paths_only_unhealthy() {
+        assert_eq!(
+            QueryPathsCmd {
+                only_unhealthy: true
+            },
+            QueryPathsCmd::parse_from(&["test", "--only-unhealthy"])
+        )
+    }
+
+    #[test]
+    fn test_query_paths_unknown_flag() {
+        assert!(QueryPathsCmd::try_parse_from(&["test", "--chain", "chain_id"]).is_err())
+    }
+}
@@ -4,10 +4,14 @@ use abscissa_core::clap::Parser;
 use abscissa_core::{Command, Runnable};
 
 mod denom_trace;
+mod denom_traces;
 
 /// `query transfer` subcommand
 #[derive(Command, Debug, Parser, Runnable)]
 pub enum TransferCmd {
     /// Query the denomination trace info from a trace hash
     DenomTrace(denom_trace::DenomTraceCmd),
+
+    /// Query the denomination traces of the IBC vouchers known to a chain
+    DenomTraces(denom_traces::DenomTracesCmd),
 }
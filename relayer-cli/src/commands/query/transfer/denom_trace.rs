@@ -14,6 +14,9 @@ use crate::conclude::{exit_with_unrecoverable_error, json, Output};
 ///
 /// `query transfer denom-trace --chain <CHAIN_ID> --hash <HASH>`
 ///
+/// The hash may also be given as the full denomination of the IBC voucher, ie. `ibc/<HASH>`,
+/// as found in a balance or in the `gas_price` of a chain which accepts vouchers as fees.
+///
 /// If successful the the base denomination and the path will be displayed.
 #[derive(Clone, Command, Debug, Parser, PartialEq)]
 pub struct DenomTraceCmd {
@@ -29,7 +32,7 @@ pub struct DenomTraceCmd {
         long = "hash",
         required = true,
         help_heading = "REQUIRED",
-        help = "Trace hash to query"
+        help = "Trace hash to query, or the full IBC voucher denomination, e.g. 'ibc/<HASH>'"
     )]
     hash: String,
 }
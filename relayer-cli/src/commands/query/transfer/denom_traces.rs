@@ -0,0 +1,157 @@
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+
+use ibc::core::ics24_host::identifier::ChainId;
+use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::chain::requests::{PageRequest, QueryDenomTracesRequest};
+
+use crate::application::app_config;
+use crate::cli_utils::spawn_chain_runtime;
+use crate::conclude::{exit_with_unrecoverable_error, json, Output};
+
+/// The data structure that represents the arguments when invoking the `query transfer denom-traces` CLI command.
+///
+/// The command has the following format:
+///
+/// `query transfer denom-traces --chain <CHAIN_ID> [--limit <LIMIT>] [--offset <OFFSET>] [--reverse]`
+///
+/// If successful, one line per denomination trace is displayed, giving the denomination
+/// of the IBC voucher, ie. `ibc/<HASH>`, followed by its full path.
+/// All the traces are queried unless `--limit` is given.
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct DenomTracesCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain"
+    )]
+    chain_id: ChainId,
+
+    #[clap(
+        long = "limit",
+        value_name = "LIMIT",
+        help = "Maximum number of denomination traces to return"
+    )]
+    limit: Option<u64>,
+
+    #[clap(
+        long = "offset",
+        value_name = "OFFSET",
+        help = "Number of denomination traces to skip before the first one returned"
+    )]
+    offset: Option<u64>,
+
+    #[clap(
+        long = "reverse",
+        help = "Return the denomination traces in descending order"
+    )]
+    reverse: bool,
+}
+
+impl DenomTracesCmd {
+    fn pagination(&self) -> PageRequest {
+        PageRequest {
+            offset: self.offset.unwrap_or_default(),
+            limit: self.limit.unwrap_or(u64::MAX),
+            reverse: self.reverse,
+            ..Default::default()
+        }
+    }
+}
+
+impl Runnable for DenomTracesCmd {
+    fn run(&self) {
+        let config = app_config();
+
+        let chain = spawn_chain_runtime(&config, &self.chain_id)
+            .unwrap_or_else(exit_with_unrecoverable_error);
+
+        let request = QueryDenomTracesRequest {
+            pagination: Some(self.pagination()),
+        };
+
+        match chain.query_denom_traces(request) {
+            Ok(denom_traces) if json() => Output::success(denom_traces).exit(),
+            Ok(denom_traces) if denom_traces.is_empty() => {
+                Output::success_msg("no denomination traces found").exit()
+            }
+            Ok(denom_traces) => {
+                let lines: Vec<String> = denom_traces
+                    .iter()
+                    .map(|trace| format!("{}: {}", trace.ibc_denom(), trace.full_path()))
+                    .collect();
+
+                Output::success_msg(lines.join("\n")).exit()
+            }
+            Err(e) => Output::error(format!(
+                "there was a problem querying the denomination traces: {}",
+                e
+            ))
+            .exit(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DenomTracesCmd;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::ChainId;
+    use ibc_relayer::chain::requests::PageRequest;
+
+    #[test]
+    fn test_transfer_denom_traces() {
+        let cmd = DenomTracesCmd::parse_from(&["test", "--chain", "chain_id"]);
+
+        assert_eq!(
+            DenomTracesCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                limit: None,
+                offset: None,
+                reverse: false,
+            },
+            cmd
+        );
+
+        let pagination = cmd.pagination();
+        assert_eq!(pagination.limit, PageRequest::all().limit);
+        assert_eq!(pagination.offset, 0);
+        assert!(!pagination.reverse);
+    }
+
+    #[test]
+    fn test_transfer_denom_traces_pagination() {
+        let cmd = DenomTracesCmd::parse_from(&[
+            "test",
+            "--chain",
+            "chain_id",
+            "--limit",
+            "10",
+            "--offset",
+            "20",
+            "--reverse",
+        ]);
+
+        assert_eq!(
+            DenomTracesCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                limit: Some(10),
+                offset: Some(20),
+                reverse: true,
+            },
+            cmd
+        );
+
+        let pagination = cmd.pagination();
+        assert_eq!(pagination.limit, 10);
+        assert_eq!(pagination.offset, 20);
+        assert!(pagination.reverse);
+    }
+
+    #[test]
+    fn test_transfer_denom_traces_no_chain() {
+        assert!(DenomTracesCmd::try_parse_from(&["test", "--limit", "10"]).is_err())
+    }
+}
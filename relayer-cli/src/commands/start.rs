@@ -23,13 +23,39 @@ pub struct StartCmd {
         help = "Force a full scan of the chains for clients, connections and channels"
     )]
     full_scan: bool,
+
+    #[clap(
+        long = "dry-run",
+        help = "Run in observation mode: scan and relay as usual, but only record the transactions that would be sent, without ever broadcasting them"
+    )]
+    dry_run: bool,
+
+    #[clap(
+        long = "verbose",
+        help = "Estimate and log how long each channel stuck in the opening handshake found by the scan has been stuck for"
+    )]
+    verbose: bool,
 }
 
 impl Runnable for StartCmd {
     fn run(&self) {
-        let config = (*app_config()).clone();
+        let mut config = (*app_config()).clone();
+
+        if self.dry_run {
+            config.mode.observation = true;
+        }
+
+        if config.mode.observation {
+            warn!("Hermes is running in observation mode, no transaction will be broadcast");
+        }
 
-        let supervisor_handle = make_supervisor::<CachingChainHandle>(config, self.full_scan)
+        let options = SupervisorOptions {
+            health_check: true,
+            force_full_scan: self.full_scan,
+            verbose_scan: self.verbose,
+        };
+
+        let supervisor_handle = make_supervisor::<CachingChainHandle>(config, options)
             .unwrap_or_else(|e| {
                 Output::error(format!("Hermes failed to start, last error: {}", e)).exit()
             });
@@ -136,6 +162,8 @@ fn spawn_telemetry_server(config: &Config) -> Result<(), Box<dyn Error + Send +
     let state = ibc_telemetry::global();
 
     let telemetry = config.telemetry.clone();
+    state.set_label_filter(label_filter(&telemetry.labels));
+
     if telemetry.enabled {
         match ibc_telemetry::spawn((telemetry.host, telemetry.port), state.clone()) {
             Ok((addr, _)) => {
@@ -154,6 +182,39 @@ fn spawn_telemetry_server(config: &Config) -> Result<(), Box<dyn Error + Send +
     Ok(())
 }
 
+/// The filter which selects the labels emitted with the metrics, as configured in `[telemetry.labels]`.
+#[cfg(feature = "telemetry")]
+fn label_filter(
+    labels: &ibc_relayer::config::TelemetryLabels,
+) -> ibc_telemetry::labels::LabelFilter {
+    use ibc_relayer::config::{TelemetryLabel, TelemetryLabelsPreset};
+    use ibc_telemetry::labels::{Label, LabelFilter, LabelPreset};
+
+    let preset = match labels.preset {
+        TelemetryLabelsPreset::Full => LabelPreset::Full,
+        TelemetryLabelsPreset::PerChain => LabelPreset::PerChain,
+        TelemetryLabelsPreset::Minimal => LabelPreset::Minimal,
+    };
+
+    labels
+        .drop
+        .iter()
+        .fold(LabelFilter::from_preset(preset), |filter, label| {
+            filter.without(match label {
+                TelemetryLabel::Chain => Label::Chain,
+                TelemetryLabel::Counterparty => Label::Counterparty,
+                TelemetryLabel::Channel => Label::Channel,
+                TelemetryLabel::Port => Label::Port,
+                TelemetryLabel::Client => Label::Client,
+                TelemetryLabel::Account => Label::Account,
+                TelemetryLabel::Denom => Label::Denom,
+                TelemetryLabel::RelayMode => Label::RelayMode,
+                TelemetryLabel::QueryType => Label::QueryType,
+                TelemetryLabel::Type => Label::Type,
+            })
+        })
+}
+
 #[cfg(not(feature = "telemetry"))]
 fn spawn_telemetry_server(
     config: &Arc<RwLock<Config>>,
@@ -170,22 +231,14 @@ fn spawn_telemetry_server(
 
 fn make_supervisor<Chain: ChainHandle>(
     config: Config,
-    force_full_scan: bool,
+    options: SupervisorOptions,
 ) -> Result<SupervisorHandle, Box<dyn Error + Send + Sync>> {
     let registry = SharedRegistry::<Chain>::new(config.clone());
     spawn_telemetry_server(&config)?;
 
     let rest = spawn_rest_server(&config);
 
-    Ok(spawn_supervisor(
-        config,
-        registry,
-        rest,
-        SupervisorOptions {
-            health_check: true,
-            force_full_scan,
-        },
-    )?)
+    Ok(spawn_supervisor(config, registry, rest, options)?)
 }
 
 #[cfg(test)]
@@ -197,7 +250,11 @@ mod tests {
     #[test]
     fn test_start_required_only() {
         assert_eq!(
-            StartCmd { full_scan: false },
+            StartCmd {
+                full_scan: false,
+                dry_run: false,
+                verbose: false
+            },
             StartCmd::parse_from(&["test"])
         )
     }
@@ -205,8 +262,36 @@ mod tests {
     #[test]
     fn test_start_full_scan() {
         assert_eq!(
-            StartCmd { full_scan: true },
+            StartCmd {
+                full_scan: true,
+                dry_run: false,
+                verbose: false
+            },
             StartCmd::parse_from(&["test", "--full-scan"])
         )
     }
+
+    #[test]
+    fn test_start_dry_run() {
+        assert_eq!(
+            StartCmd {
+                full_scan: false,
+                dry_run: true,
+                verbose: false
+            },
+            StartCmd::parse_from(&["test", "--dry-run"])
+        )
+    }
+
+    #[test]
+    fn test_start_verbose() {
+        assert_eq!(
+            StartCmd {
+                full_scan: false,
+                dry_run: false,
+                verbose: true
+            },
+            StartCmd::parse_from(&["test", "--verbose"])
+        )
+    }
 }
@@ -60,8 +60,20 @@ pub enum TxCmd {
 impl Override<Config> for TxCmd {
     fn override_config(&self, config: Config) -> Result<Config, abscissa_core::FrameworkError> {
         match self {
+            Self::ConnInit(cmd) => cmd.override_config(config),
+            Self::ConnTry(cmd) => cmd.override_config(config),
+            Self::ConnAck(cmd) => cmd.override_config(config),
+            Self::ConnConfirm(cmd) => cmd.override_config(config),
+            Self::ChanOpenInit(cmd) => cmd.override_config(config),
+            Self::ChanOpenTry(cmd) => cmd.override_config(config),
+            Self::ChanOpenAck(cmd) => cmd.override_config(config),
+            Self::ChanOpenConfirm(cmd) => cmd.override_config(config),
+            Self::ChanCloseInit(cmd) => cmd.override_config(config),
+            Self::ChanCloseConfirm(cmd) => cmd.override_config(config),
             Self::FtTransfer(cmd) => cmd.override_config(config),
-            _ => Ok(config),
+            Self::PacketRecv(cmd) => cmd.override_config(config),
+            Self::PacketAck(cmd) => cmd.override_config(config),
+            Self::UpgradeChain(_) => Ok(config),
         }
     }
 }
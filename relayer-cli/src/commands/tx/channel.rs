@@ -1,5 +1,6 @@
 use abscissa_core::clap::Parser;
-use abscissa_core::{Command, Runnable};
+use abscissa_core::config::Override;
+use abscissa_core::{Command, FrameworkError, Runnable};
 
 use ibc::core::ics03_connection::connection::ConnectionEnd;
 use ibc::core::ics04_channel::channel::Order;
@@ -8,8 +9,9 @@ use ibc::events::IbcEvent;
 use ibc_relayer::chain::handle::ChainHandle;
 use ibc_relayer::chain::requests::{IncludeProof, QueryConnectionRequest, QueryHeight};
 use ibc_relayer::channel::{Channel, ChannelSide};
+use ibc_relayer::config::Config;
 
-use crate::cli_utils::ChainHandlePair;
+use crate::cli_utils::{override_key_names, parse_channel_id, parse_port_id, ChainHandlePair};
 use crate::conclude::Output;
 use crate::error::Error;
 use crate::prelude::*;
@@ -83,6 +85,7 @@ pub struct TxChanOpenInitCmd {
         long = "dst-port",
         required = true,
         value_name = "DST_PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "REQUIRED",
         help = "Identifier of the destination port"
     )]
@@ -92,6 +95,7 @@ pub struct TxChanOpenInitCmd {
         long = "src-port",
         required = true,
         value_name = "SRC_PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "REQUIRED",
         help = "Identifier of the source port"
     )]
@@ -104,6 +108,32 @@ pub struct TxChanOpenInitCmd {
         help = "The channel ordering, valid options 'unordered' (default) and 'ordered'"
     )]
     order: Order,
+
+    #[clap(
+        long = "key-name",
+        value_name = "KEY_NAME",
+        help = "Use the given signing key for the source chain (default: `key_name` config)"
+    )]
+    key_name: Option<String>,
+
+    #[clap(
+        long = "dst-key-name",
+        value_name = "DST_KEY_NAME",
+        help = "Use the given signing key for the destination chain (default: `key_name` config)"
+    )]
+    dst_key_name: Option<String>,
+}
+
+impl Override<Config> for TxChanOpenInitCmd {
+    fn override_config(&self, config: Config) -> Result<Config, FrameworkError> {
+        override_key_names(
+            config,
+            &[
+                (&self.src_chain_id, self.key_name.as_deref()),
+                (&self.dst_chain_id, self.dst_key_name.as_deref()),
+            ],
+        )
+    }
 }
 
 impl Runnable for TxChanOpenInitCmd {
@@ -195,6 +225,7 @@ pub struct TxChanOpenTryCmd {
         long = "dst-port",
         required = true,
         value_name = "DST_PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "REQUIRED",
         help = "Identifier of the destination port"
     )]
@@ -204,6 +235,7 @@ pub struct TxChanOpenTryCmd {
         long = "src-port",
         required = true,
         value_name = "SRC_PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "REQUIRED",
         help = "Identifier of the source port"
     )]
@@ -214,6 +246,7 @@ pub struct TxChanOpenTryCmd {
         visible_alias = "src-chan",
         required = true,
         value_name = "SRC_CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
         help_heading = "REQUIRED",
         help = "Identifier of the source channel (required)"
     )]
@@ -223,9 +256,36 @@ pub struct TxChanOpenTryCmd {
         long = "dst-channel",
         visible_alias = "dst-chan",
         value_name = "DST_CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
         help = "Identifier of the destination channel (optional)"
     )]
     dst_chan_id: Option<ChannelId>,
+
+    #[clap(
+        long = "key-name",
+        value_name = "KEY_NAME",
+        help = "Use the given signing key for the source chain (default: `key_name` config)"
+    )]
+    key_name: Option<String>,
+
+    #[clap(
+        long = "dst-key-name",
+        value_name = "DST_KEY_NAME",
+        help = "Use the given signing key for the destination chain (default: `key_name` config)"
+    )]
+    dst_key_name: Option<String>,
+}
+
+impl Override<Config> for TxChanOpenTryCmd {
+    fn override_config(&self, config: Config) -> Result<Config, FrameworkError> {
+        override_key_names(
+            config,
+            &[
+                (&self.src_chain_id, self.key_name.as_deref()),
+                (&self.dst_chain_id, self.dst_key_name.as_deref()),
+            ],
+        )
+    }
 }
 
 impl Runnable for TxChanOpenTryCmd {
@@ -294,6 +354,7 @@ pub struct TxChanOpenAckCmd {
         long = "dst-port",
         required = true,
         value_name = "DST_PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "REQUIRED",
         help = "Identifier of the destination port"
     )]
@@ -303,6 +364,7 @@ pub struct TxChanOpenAckCmd {
         long = "src-port",
         required = true,
         value_name = "SRC_PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "REQUIRED",
         help = "Identifier of the source port"
     )]
@@ -313,6 +375,7 @@ pub struct TxChanOpenAckCmd {
         visible_alias = "dst-chan",
         required = true,
         value_name = "DST_CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
         help_heading = "REQUIRED",
         help = "Identifier of the destination channel (required)"
     )]
@@ -323,10 +386,37 @@ pub struct TxChanOpenAckCmd {
         visible_alias = "src-chan",
         required = true,
         value_name = "SRC_CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
         help_heading = "REQUIRED",
         help = "Identifier of the source channel (required)"
     )]
     src_chan_id: ChannelId,
+
+    #[clap(
+        long = "key-name",
+        value_name = "KEY_NAME",
+        help = "Use the given signing key for the source chain (default: `key_name` config)"
+    )]
+    key_name: Option<String>,
+
+    #[clap(
+        long = "dst-key-name",
+        value_name = "DST_KEY_NAME",
+        help = "Use the given signing key for the destination chain (default: `key_name` config)"
+    )]
+    dst_key_name: Option<String>,
+}
+
+impl Override<Config> for TxChanOpenAckCmd {
+    fn override_config(&self, config: Config) -> Result<Config, FrameworkError> {
+        override_key_names(
+            config,
+            &[
+                (&self.src_chain_id, self.key_name.as_deref()),
+                (&self.dst_chain_id, self.dst_key_name.as_deref()),
+            ],
+        )
+    }
 }
 
 impl Runnable for TxChanOpenAckCmd {
@@ -395,6 +485,7 @@ pub struct TxChanOpenConfirmCmd {
         long = "dst-port",
         required = true,
         value_name = "DST_PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "REQUIRED",
         help = "Identifier of the destination port"
     )]
@@ -404,6 +495,7 @@ pub struct TxChanOpenConfirmCmd {
         long = "src-port",
         required = true,
         value_name = "SRC_PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "REQUIRED",
         help = "Identifier of the source port"
     )]
@@ -414,6 +506,7 @@ pub struct TxChanOpenConfirmCmd {
         visible_alias = "dst-chan",
         required = true,
         value_name = "DST_CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
         help_heading = "REQUIRED",
         help = "Identifier of the destination channel (required)"
     )]
@@ -424,10 +517,37 @@ pub struct TxChanOpenConfirmCmd {
         visible_alias = "src-chan",
         required = true,
         value_name = "SRC_CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
         help_heading = "REQUIRED",
         help = "Identifier of the source channel (required)"
     )]
     src_chan_id: ChannelId,
+
+    #[clap(
+        long = "key-name",
+        value_name = "KEY_NAME",
+        help = "Use the given signing key for the source chain (default: `key_name` config)"
+    )]
+    key_name: Option<String>,
+
+    #[clap(
+        long = "dst-key-name",
+        value_name = "DST_KEY_NAME",
+        help = "Use the given signing key for the destination chain (default: `key_name` config)"
+    )]
+    dst_key_name: Option<String>,
+}
+
+impl Override<Config> for TxChanOpenConfirmCmd {
+    fn override_config(&self, config: Config) -> Result<Config, FrameworkError> {
+        override_key_names(
+            config,
+            &[
+                (&self.src_chain_id, self.key_name.as_deref()),
+                (&self.dst_chain_id, self.dst_key_name.as_deref()),
+            ],
+        )
+    }
 }
 
 impl Runnable for TxChanOpenConfirmCmd {
@@ -496,6 +616,7 @@ pub struct TxChanCloseInitCmd {
         long = "dst-port",
         required = true,
         value_name = "DST_PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "REQUIRED",
         help = "Identifier of the destination port"
     )]
@@ -505,6 +626,7 @@ pub struct TxChanCloseInitCmd {
         long = "src-port",
         required = true,
         value_name = "SRC_PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "REQUIRED",
         help = "Identifier of the source port"
     )]
@@ -515,6 +637,7 @@ pub struct TxChanCloseInitCmd {
         visible_alias = "dst-chan",
         required = true,
         value_name = "DST_CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
         help_heading = "REQUIRED",
         help = "Identifier of the destination channel (required)"
     )]
@@ -525,10 +648,37 @@ pub struct TxChanCloseInitCmd {
         visible_alias = "src-chan",
         required = true,
         value_name = "SRC_CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
         help_heading = "REQUIRED",
         help = "Identifier of the source channel (required)"
     )]
     src_chan_id: ChannelId,
+
+    #[clap(
+        long = "key-name",
+        value_name = "KEY_NAME",
+        help = "Use the given signing key for the source chain (default: `key_name` config)"
+    )]
+    key_name: Option<String>,
+
+    #[clap(
+        long = "dst-key-name",
+        value_name = "DST_KEY_NAME",
+        help = "Use the given signing key for the destination chain (default: `key_name` config)"
+    )]
+    dst_key_name: Option<String>,
+}
+
+impl Override<Config> for TxChanCloseInitCmd {
+    fn override_config(&self, config: Config) -> Result<Config, FrameworkError> {
+        override_key_names(
+            config,
+            &[
+                (&self.src_chain_id, self.key_name.as_deref()),
+                (&self.dst_chain_id, self.dst_key_name.as_deref()),
+            ],
+        )
+    }
 }
 
 impl Runnable for TxChanCloseInitCmd {
@@ -597,6 +747,7 @@ pub struct TxChanCloseConfirmCmd {
         long = "dst-port",
         required = true,
         value_name = "DST_PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "REQUIRED",
         help = "Identifier of the destination port"
     )]
@@ -606,6 +757,7 @@ pub struct TxChanCloseConfirmCmd {
         long = "src-port",
         required = true,
         value_name = "SRC_PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "REQUIRED",
         help = "Identifier of the source port"
     )]
@@ -616,6 +768,7 @@ pub struct TxChanCloseConfirmCmd {
         visible_alias = "dst-chan",
         required = true,
         value_name = "DST_CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
         help_heading = "REQUIRED",
         help = "Identifier of the destination channel (required)"
     )]
@@ -626,10 +779,37 @@ pub struct TxChanCloseConfirmCmd {
         visible_alias = "src-chan",
         required = true,
         value_name = "SRC_CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
         help_heading = "REQUIRED",
         help = "Identifier of the source channel (required)"
     )]
     src_chan_id: ChannelId,
+
+    #[clap(
+        long = "key-name",
+        value_name = "KEY_NAME",
+        help = "Use the given signing key for the source chain (default: `key_name` config)"
+    )]
+    key_name: Option<String>,
+
+    #[clap(
+        long = "dst-key-name",
+        value_name = "DST_KEY_NAME",
+        help = "Use the given signing key for the destination chain (default: `key_name` config)"
+    )]
+    dst_key_name: Option<String>,
+}
+
+impl Override<Config> for TxChanCloseConfirmCmd {
+    fn override_config(&self, config: Config) -> Result<Config, FrameworkError> {
+        override_key_names(
+            config,
+            &[
+                (&self.src_chain_id, self.key_name.as_deref()),
+                (&self.dst_chain_id, self.dst_key_name.as_deref()),
+            ],
+        )
+    }
 }
 
 impl Runnable for TxChanCloseConfirmCmd {
@@ -688,7 +868,9 @@ mod tests {
                 dst_conn_id: ConnectionId::from_str("connection_b").unwrap(),
                 dst_port_id: PortId::from_str("port_b").unwrap(),
                 src_port_id: PortId::from_str("port_a").unwrap(),
-                order: Order::Unordered
+                order: Order::Unordered,
+                key_name: None,
+                dst_key_name: None,
             },
             TxChanOpenInitCmd::parse_from(&[
                 "test",
@@ -715,7 +897,9 @@ mod tests {
                 dst_conn_id: ConnectionId::from_str("connection_b").unwrap(),
                 dst_port_id: PortId::from_str("port_b").unwrap(),
                 src_port_id: PortId::from_str("port_a").unwrap(),
-                order: Order::Ordered
+                order: Order::Ordered,
+                key_name: None,
+                dst_key_name: None,
             },
             TxChanOpenInitCmd::parse_from(&[
                 "test",
@@ -744,7 +928,9 @@ mod tests {
                 dst_conn_id: ConnectionId::from_str("connection_b").unwrap(),
                 dst_port_id: PortId::from_str("port_b").unwrap(),
                 src_port_id: PortId::from_str("port_a").unwrap(),
-                order: Order::Unordered
+                order: Order::Unordered,
+                key_name: None,
+                dst_key_name: None,
             },
             TxChanOpenInitCmd::parse_from(&[
                 "test",
@@ -852,7 +1038,9 @@ mod tests {
                 dst_port_id: PortId::from_str("port_b").unwrap(),
                 src_port_id: PortId::from_str("port_a").unwrap(),
                 src_chan_id: ChannelId::from_str("channel_a").unwrap(),
-                dst_chan_id: None
+                dst_chan_id: None,
+                key_name: None,
+                dst_key_name: None,
             },
             TxChanOpenTryCmd::parse_from(&[
                 "test",
@@ -882,7 +1070,9 @@ mod tests {
                 dst_port_id: PortId::from_str("port_b").unwrap(),
                 src_port_id: PortId::from_str("port_a").unwrap(),
                 src_chan_id: ChannelId::from_str("channel_a").unwrap(),
-                dst_chan_id: Some(ChannelId::from_str("channel_b").unwrap())
+                dst_chan_id: Some(ChannelId::from_str("channel_b").unwrap()),
+                key_name: None,
+                dst_key_name: None,
             },
             TxChanOpenTryCmd::parse_from(&[
                 "test",
@@ -914,7 +1104,9 @@ mod tests {
                 dst_port_id: PortId::from_str("port_b").unwrap(),
                 src_port_id: PortId::from_str("port_a").unwrap(),
                 src_chan_id: ChannelId::from_str("channel_a").unwrap(),
-                dst_chan_id: Some(ChannelId::from_str("channel_b").unwrap())
+                dst_chan_id: Some(ChannelId::from_str("channel_b").unwrap()),
+                key_name: None,
+                dst_key_name: None,
             },
             TxChanOpenTryCmd::parse_from(&[
                 "test",
@@ -1054,7 +1246,9 @@ mod tests {
                 dst_port_id: PortId::from_str("port_b").unwrap(),
                 src_port_id: PortId::from_str("port_a").unwrap(),
                 dst_chan_id: ChannelId::from_str("channel_b").unwrap(),
-                src_chan_id: ChannelId::from_str("channel_a").unwrap()
+                src_chan_id: ChannelId::from_str("channel_a").unwrap(),
+                key_name: None,
+                dst_key_name: None,
             },
             TxChanOpenAckCmd::parse_from(&[
                 "test",
@@ -1086,7 +1280,9 @@ mod tests {
                 dst_port_id: PortId::from_str("port_b").unwrap(),
                 src_port_id: PortId::from_str("port_a").unwrap(),
                 dst_chan_id: ChannelId::from_str("channel_b").unwrap(),
-                src_chan_id: ChannelId::from_str("channel_a").unwrap()
+                src_chan_id: ChannelId::from_str("channel_a").unwrap(),
+                key_name: None,
+                dst_key_name: None,
             },
             TxChanOpenAckCmd::parse_from(&[
                 "test",
@@ -1258,7 +1454,9 @@ mod tests {
                 dst_port_id: PortId::from_str("port_b").unwrap(),
                 src_port_id: PortId::from_str("port_a").unwrap(),
                 dst_chan_id: ChannelId::from_str("channel_b").unwrap(),
-                src_chan_id: ChannelId::from_str("channel_a").unwrap()
+                src_chan_id: ChannelId::from_str("channel_a").unwrap(),
+                key_name: None,
+                dst_key_name: None,
             },
             TxChanOpenConfirmCmd::parse_from(&[
                 "test",
@@ -1290,7 +1488,9 @@ mod tests {
                 dst_port_id: PortId::from_str("port_b").unwrap(),
                 src_port_id: PortId::from_str("port_a").unwrap(),
                 dst_chan_id: ChannelId::from_str("channel_b").unwrap(),
-                src_chan_id: ChannelId::from_str("channel_a").unwrap()
+                src_chan_id: ChannelId::from_str("channel_a").unwrap(),
+                key_name: None,
+                dst_key_name: None,
             },
             TxChanOpenConfirmCmd::parse_from(&[
                 "test",
@@ -1462,7 +1662,9 @@ mod tests {
                 dst_port_id: PortId::from_str("port_b").unwrap(),
                 src_port_id: PortId::from_str("port_a").unwrap(),
                 dst_chan_id: ChannelId::from_str("channel_b").unwrap(),
-                src_chan_id: ChannelId::from_str("channel_a").unwrap()
+                src_chan_id: ChannelId::from_str("channel_a").unwrap(),
+                key_name: None,
+                dst_key_name: None,
             },
             TxChanCloseInitCmd::parse_from(&[
                 "test",
@@ -1494,7 +1696,9 @@ mod tests {
                 dst_port_id: PortId::from_str("port_b").unwrap(),
                 src_port_id: PortId::from_str("port_a").unwrap(),
                 dst_chan_id: ChannelId::from_str("channel_b").unwrap(),
-                src_chan_id: ChannelId::from_str("channel_a").unwrap()
+                src_chan_id: ChannelId::from_str("channel_a").unwrap(),
+                key_name: None,
+                dst_key_name: None,
             },
             TxChanCloseInitCmd::parse_from(&[
                 "test",
@@ -1666,7 +1870,9 @@ mod tests {
                 dst_port_id: PortId::from_str("port_b").unwrap(),
                 src_port_id: PortId::from_str("port_a").unwrap(),
                 dst_chan_id: ChannelId::from_str("channel_b").unwrap(),
-                src_chan_id: ChannelId::from_str("channel_a").unwrap()
+                src_chan_id: ChannelId::from_str("channel_a").unwrap(),
+                key_name: None,
+                dst_key_name: None,
             },
             TxChanCloseConfirmCmd::parse_from(&[
                 "test",
@@ -1698,7 +1904,9 @@ mod tests {
                 dst_port_id: PortId::from_str("port_b").unwrap(),
                 src_port_id: PortId::from_str("port_a").unwrap(),
                 dst_chan_id: ChannelId::from_str("channel_b").unwrap(),
-                src_chan_id: ChannelId::from_str("channel_a").unwrap()
+                src_chan_id: ChannelId::from_str("channel_a").unwrap(),
+                key_name: None,
+                dst_key_name: None,
             },
             TxChanCloseConfirmCmd::parse_from(&[
                 "test",
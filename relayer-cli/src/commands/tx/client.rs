@@ -1,8 +1,11 @@
 use core::{fmt, time::Duration};
+use std::sync::Arc;
 use std::thread;
 
 use abscissa_core::clap::Parser;
 use abscissa_core::{Command, Runnable};
+use crossbeam_channel::unbounded;
+use serde::Serialize;
 
 use ibc::core::ics02_client::client_state::ClientState;
 use ibc::core::ics24_host::identifier::{ChainId, ClientId};
@@ -13,13 +16,15 @@ use ibc_relayer::chain::requests::{
     IncludeProof, PageRequest, QueryClientStateRequest, QueryClientStatesRequest, QueryHeight,
 };
 use ibc_relayer::config::Config;
-use ibc_relayer::foreign_client::{CreateOptions, ForeignClient};
+use ibc_relayer::foreign_client::{CreateOptions, ForeignClient, ForeignClientErrorDetail};
 use tendermint_light_client_verifier::types::TrustThreshold;
 use tracing::debug;
 
 use crate::application::app_config;
-use crate::cli_utils::{spawn_chain_runtime, spawn_chain_runtime_generic, ChainHandlePair};
-use crate::conclude::{exit_with_unrecoverable_error, Output};
+use crate::cli_utils::{
+    resolve_chain_id, spawn_chain_runtime, spawn_chain_runtime_generic, ChainHandlePair,
+};
+use crate::conclude::{exit_with_unrecoverable_error, json, Output};
 use crate::error::Error;
 
 #[derive(Clone, Command, Debug, Parser, PartialEq)]
@@ -75,15 +80,15 @@ impl Runnable for TxCreateClientCmd {
     fn run(&self) {
         let config = app_config();
 
-        if self.src_chain_id == self.dst_chain_id {
-            Output::error("source and destination chains must be different".to_string()).exit()
-        }
-
         let chains = match ChainHandlePair::spawn(&config, &self.src_chain_id, &self.dst_chain_id) {
             Ok(chains) => chains,
             Err(e) => Output::error(format!("{}", e)).exit(),
         };
 
+        if chains.src.id() == chains.dst.id() {
+            Output::error("source and destination chains must be different".to_string()).exit()
+        }
+
         let client = ForeignClient::restore(ClientId::default(), chains.dst, chains.src);
 
         let options = CreateOptions {
@@ -196,6 +201,310 @@ impl Runnable for TxUpdateClientCmd {
     }
 }
 
+/// Default number of clients updated at the same time by `update clients`.
+const DEFAULT_UPDATE_CONCURRENCY: usize = 4;
+
+/// Default number of blocks of the reference chain below which a client is considered fresh.
+const DEFAULT_FRESHNESS_THRESHOLD: u64 = 100;
+
+/// Update all the clients which track a reference chain, on all the configured host chains,
+/// e.g. to refresh them after a long halt of the reference chain before their trusting
+/// period expires.
+///
+/// Sample to run this tx:
+///     `hermes update clients --reference-chain ibc-1`
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct TxUpdateClientsCmd {
+    #[clap(
+        long = "reference-chain",
+        required = true,
+        value_name = "REFERENCE_CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain targeted by the clients; all clients targeting this chain will be updated"
+    )]
+    reference_chain_id: ChainId,
+
+    #[clap(
+        long = "concurrency",
+        value_name = "CONCURRENCY",
+        default_value_t = DEFAULT_UPDATE_CONCURRENCY,
+        help = "Maximum number of clients updated at the same time"
+    )]
+    concurrency: usize,
+
+    #[clap(
+        long = "threshold",
+        value_name = "BLOCKS",
+        default_value_t = DEFAULT_FRESHNESS_THRESHOLD,
+        help = "Skip the clients whose latest height is within this number of blocks of the latest height of the reference chain"
+    )]
+    threshold: u64,
+
+    #[clap(
+        long = "force",
+        help = "Update all the clients, including the ones within the threshold"
+    )]
+    force: bool,
+}
+
+/// The outcome of the update of a client by `update clients`.
+#[derive(Debug, Serialize)]
+struct UpdateClientsOutcome {
+    host_chain: ChainId,
+    /// The client, or `None` if the clients of the host chain could not be queried.
+    client_id: Option<ClientId>,
+    #[serde(flatten)]
+    status: UpdateClientStatus,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum UpdateClientStatus {
+    /// The client was updated to the given height of the reference chain.
+    Updated {
+        height: Option<Height>,
+    },
+    /// The client was already fresh at the given height of the reference chain.
+    Skipped {
+        latest_height: Height,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+impl fmt::Display for UpdateClientsOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let client_id = self
+            .client_id
+            .as_ref()
+            .map_or_else(|| "*".to_string(), ToString::to_string);
+
+        match &self.status {
+            UpdateClientStatus::Updated { height } => match height {
+                Some(height) => write!(
+                    f,
+                    "[updated] {}/{} to height {}",
+                    self.host_chain, client_id, height
+                ),
+                None => write!(f, "[updated] {}/{}", self.host_chain, client_id),
+            },
+            UpdateClientStatus::Skipped { latest_height } => write!(
+                f,
+                "[skipped] {}/{} is fresh at height {}",
+                self.host_chain, client_id, latest_height
+            ),
+            UpdateClientStatus::Failed { error } => {
+                write!(f, "[failed] {}/{}: {}", self.host_chain, client_id, error)
+            }
+        }
+    }
+}
+
+impl TxUpdateClientsCmd {
+    fn execute(&self) -> Result<Vec<UpdateClientsOutcome>, Error> {
+        let config = app_config();
+
+        let reference_chain = spawn_chain_runtime(&config, &self.reference_chain_id)?;
+        let reference_height = reference_chain
+            .query_latest_height()
+            .map_err(Error::relayer)?;
+
+        let mut outcomes = vec![];
+        let mut updates = vec![];
+
+        for host_chain_id in config
+            .chains
+            .iter()
+            .map(|chain| &chain.id)
+            .filter(|chain_id| **chain_id != reference_chain.id())
+        {
+            let host_chain_clients =
+                spawn_chain_runtime(&config, host_chain_id).and_then(|host_chain| {
+                    let clients = host_chain
+                        .query_clients(QueryClientStatesRequest {
+                            pagination: Some(PageRequest::all()),
+                        })
+                        .map_err(Error::relayer)?;
+
+                    Ok((host_chain, clients))
+                });
+
+            let (host_chain, clients) = match host_chain_clients {
+                Ok(host_chain_clients) => host_chain_clients,
+                Err(e) => {
+                    outcomes.push(UpdateClientsOutcome {
+                        host_chain: host_chain_id.clone(),
+                        client_id: None,
+                        status: UpdateClientStatus::Failed {
+                            error: e.to_string(),
+                        },
+                    });
+
+                    continue;
+                }
+            };
+
+            for client in clients
+                .into_iter()
+                .filter(|client| client.client_state.chain_id() == reference_chain.id())
+            {
+                let latest_height = client.client_state.latest_height();
+
+                if !self.force && is_fresh(latest_height, reference_height, self.threshold) {
+                    outcomes.push(UpdateClientsOutcome {
+                        host_chain: host_chain_id.clone(),
+                        client_id: Some(client.client_id),
+                        status: UpdateClientStatus::Skipped { latest_height },
+                    });
+                } else {
+                    updates.push((host_chain.clone(), client.client_id));
+                }
+            }
+        }
+
+        outcomes.extend(run_concurrently(
+            updates,
+            self.concurrency,
+            move |(host_chain, client_id)| {
+                update_client(host_chain, reference_chain.clone(), client_id)
+            },
+        ));
+
+        outcomes.sort_by(|a, b| (&a.host_chain, &a.client_id).cmp(&(&b.host_chain, &b.client_id)));
+
+        Ok(outcomes)
+    }
+}
+
+impl Runnable for TxUpdateClientsCmd {
+    fn run(&self) {
+        let outcomes = match self.execute() {
+            Ok(outcomes) => outcomes,
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        };
+
+        let failed = outcomes
+            .iter()
+            .filter(|outcome| matches!(outcome.status, UpdateClientStatus::Failed { .. }))
+            .count();
+
+        let output = if failed > 0 {
+            Output::with_error()
+        } else {
+            Output::with_success()
+        };
+
+        if json() {
+            output.with_result(outcomes).exit()
+        }
+
+        let updated = outcomes
+            .iter()
+            .filter(|outcome| matches!(outcome.status, UpdateClientStatus::Updated { .. }))
+            .count();
+
+        let mut lines: Vec<_> = outcomes.iter().map(ToString::to_string).collect();
+        lines.push(format!(
+            "{} updated, {} skipped, {} failed",
+            updated,
+            outcomes.len() - updated - failed,
+            failed
+        ));
+
+        output.with_msg(lines.join("\n")).exit()
+    }
+}
+
+/// Whether a client whose latest height is `client_height` is within `threshold` blocks
+/// of the reference chain at `reference_height`. A client of a previous revision of the
+/// reference chain is never fresh.
+fn is_fresh(client_height: Height, reference_height: Height, threshold: u64) -> bool {
+    client_height.revision_number() == reference_height.revision_number()
+        && reference_height
+            .revision_height()
+            .saturating_sub(client_height.revision_height())
+            <= threshold
+}
+
+fn update_client<Chain: ChainHandle>(
+    host_chain: Chain,
+    reference_chain: Chain,
+    client_id: ClientId,
+) -> UpdateClientsOutcome {
+    let host_chain_id = host_chain.id();
+    let client = ForeignClient::restore(client_id.clone(), host_chain, reference_chain);
+
+    let status = match client.build_latest_update_client_and_send() {
+        Ok(events) => UpdateClientStatus::Updated {
+            height: events.iter().find_map(|event| match event {
+                IbcEvent::UpdateClient(update) => Some(update.consensus_height()),
+                _ => None,
+            }),
+        },
+        Err(e) => match e.detail() {
+            ForeignClientErrorDetail::ClientAlreadyUpToDate(up_to_date) => {
+                UpdateClientStatus::Skipped {
+                    latest_height: up_to_date.height,
+                }
+            }
+            _ => UpdateClientStatus::Failed {
+                error: e.to_string(),
+            },
+        },
+    };
+
+    UpdateClientsOutcome {
+        host_chain: host_chain_id,
+        client_id: Some(client_id),
+        status,
+    }
+}
+
+/// Runs `f` on each of the `items`, on at most `concurrency` threads at the same time,
+/// and returns the results in the order of the items.
+fn run_concurrently<T, R, F>(items: Vec<T>, concurrency: usize, f: F) -> Vec<R>
+where
+    T: Send + 'static,
+    R: Send + 'static,
+    F: Fn(T) -> R + Send + Sync + 'static,
+{
+    let len = items.len();
+
+    let (item_sender, item_receiver) = unbounded();
+    for item in items.into_iter().enumerate() {
+        item_sender.send(item).expect("receiver is alive");
+    }
+    drop(item_sender);
+
+    let (result_sender, result_receiver) = unbounded();
+    let f = Arc::new(f);
+
+    let threads: Vec<_> = (0..concurrency.clamp(1, len.max(1)))
+        .map(|_| {
+            let item_receiver = item_receiver.clone();
+            let result_sender = result_sender.clone();
+            let f = f.clone();
+
+            thread::spawn(move || {
+                for (index, item) in item_receiver {
+                    let _ = result_sender.send((index, f(item)));
+                }
+            })
+        })
+        .collect();
+
+    drop(result_sender);
+
+    for thread in threads {
+        thread.join().expect("update thread panicked");
+    }
+
+    let mut results: Vec<_> = result_receiver.into_iter().collect();
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
 #[derive(Clone, Command, Debug, Parser, PartialEq)]
 pub struct TxUpgradeClientCmd {
     #[clap(
@@ -350,6 +659,16 @@ impl Runnable for TxUpgradeClientsCmd {
             Err(e) => Output::error(format!("{}", e)).exit(),
         };
 
+        let host_chain_id = match self
+            .host_chain_id
+            .as_ref()
+            .map(|host_chain_id| resolve_chain_id(&config, host_chain_id))
+            .transpose()
+        {
+            Ok(host_chain_id) => host_chain_id,
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        };
+
         let reference_upgrade_height = Height::new(
             reference_chain.id().version(),
             self.reference_upgrade_height,
@@ -393,9 +712,8 @@ impl Runnable for TxUpgradeClientsCmd {
             .chains
             .iter()
             .filter_map(|chain| {
-                (self.reference_chain_id != chain.id
-                    && (self.host_chain_id.is_none()
-                        || self.host_chain_id == Some(chain.id.clone())))
+                (reference_chain.id() != chain.id
+                    && (host_chain_id.is_none() || host_chain_id == Some(chain.id.clone())))
                 .then(|| {
                     self.upgrade_clients_for_chain(
                         &config,
@@ -424,6 +742,7 @@ impl TxUpgradeClientsCmd {
         reference_upgrade_height: Height,
     ) -> UpgradeClientsForChainResult {
         let host_chain = spawn_chain_runtime_generic::<Chain>(config, host_chain_id)?;
+        let reference_chain_id = reference_chain.id();
 
         let req = QueryClientStatesRequest {
             pagination: Some(PageRequest::all()),
@@ -432,9 +751,7 @@ impl TxUpgradeClientsCmd {
             .query_clients(req)
             .map_err(Error::relayer)?
             .into_iter()
-            .filter_map(|c| {
-                (self.reference_chain_id == c.client_state.chain_id()).then(|| c.client_id)
-            })
+            .filter_map(|c| (reference_chain_id == c.client_state.chain_id()).then(|| c.client_id))
             .map(|id| {
                 TxUpgradeClientsCmd::upgrade_client(
                     id,
@@ -554,15 +871,19 @@ impl OutputBuffer {
 #[cfg(test)]
 mod tests {
     use super::{
-        parse_trust_threshold, TxCreateClientCmd, TxUpdateClientCmd, TxUpgradeClientCmd,
-        TxUpgradeClientsCmd,
+        is_fresh, parse_trust_threshold, run_concurrently, TxCreateClientCmd, TxUpdateClientCmd,
+        TxUpdateClientsCmd, TxUpgradeClientCmd, TxUpgradeClientsCmd,
     };
 
     use std::str::FromStr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
 
     use abscissa_core::clap::Parser;
     use humantime::Duration;
     use ibc::core::ics24_host::identifier::{ChainId, ClientId};
+    use ibc::Height;
     use tendermint_light_client_verifier::types::TrustThreshold;
 
     #[test]
@@ -886,6 +1207,80 @@ mod tests {
         .is_err())
     }
 
+    #[test]
+    fn test_update_clients_required_only() {
+        assert_eq!(
+            TxUpdateClientsCmd {
+                reference_chain_id: ChainId::from_string("reference_chain"),
+                concurrency: 4,
+                threshold: 100,
+                force: false,
+            },
+            TxUpdateClientsCmd::parse_from(&["test", "--reference-chain", "reference_chain"])
+        )
+    }
+
+    #[test]
+    fn test_update_clients_all_options() {
+        assert_eq!(
+            TxUpdateClientsCmd {
+                reference_chain_id: ChainId::from_string("reference_chain"),
+                concurrency: 8,
+                threshold: 10,
+                force: true,
+            },
+            TxUpdateClientsCmd::parse_from(&[
+                "test",
+                "--reference-chain",
+                "reference_chain",
+                "--concurrency",
+                "8",
+                "--threshold",
+                "10",
+                "--force"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_update_clients_no_reference_chain() {
+        assert!(TxUpdateClientsCmd::try_parse_from(&["test", "--force"]).is_err())
+    }
+
+    #[test]
+    fn test_is_fresh() {
+        let height = |revision, height| Height::new(revision, height).unwrap();
+
+        assert!(is_fresh(height(1, 950), height(1, 1000), 100));
+        assert!(is_fresh(height(1, 900), height(1, 1000), 100));
+        assert!(!is_fresh(height(1, 899), height(1, 1000), 100));
+        // The reference chain was upgraded since the last update of the client
+        assert!(!is_fresh(height(0, 1000), height(1, 10), 100));
+    }
+
+    #[test]
+    fn test_run_concurrently() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+
+        let results = {
+            let max_running = max_running.clone();
+
+            run_concurrently((0..20).collect(), 3, move |item: u64| {
+                let now_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running.fetch_max(now_running, Ordering::SeqCst);
+
+                thread::sleep(core::time::Duration::from_millis(20 - item));
+
+                running.fetch_sub(1, Ordering::SeqCst);
+                item * 2
+            })
+        };
+
+        assert_eq!(results, (0..20).map(|item| item * 2).collect::<Vec<_>>());
+        assert!(max_running.load(Ordering::SeqCst) <= 3);
+    }
+
     #[test]
     fn test_upgrade_client_required_only() {
         assert_eq!(
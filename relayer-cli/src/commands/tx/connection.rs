@@ -1,12 +1,14 @@
 use abscissa_core::clap::Parser;
-use abscissa_core::{Command, Runnable};
+use abscissa_core::config::Override;
+use abscissa_core::{Command, FrameworkError, Runnable};
 
 use ibc::core::ics24_host::identifier::{ChainId, ClientId, ConnectionId};
 use ibc::events::IbcEvent;
 use ibc::timestamp::ZERO_DURATION;
+use ibc_relayer::config::Config;
 use ibc_relayer::connection::{Connection, ConnectionSide};
 
-use crate::cli_utils::ChainHandlePair;
+use crate::cli_utils::{override_key_names, ChainHandlePair};
 use crate::conclude::Output;
 use crate::error::Error;
 use crate::prelude::*;
@@ -71,6 +73,32 @@ pub struct TxConnInitCmd {
         help = "Identifier of the source client"
     )]
     src_client_id: ClientId,
+
+    #[clap(
+        long = "key-name",
+        value_name = "KEY_NAME",
+        help = "Use the given signing key for the source chain (default: `key_name` config)"
+    )]
+    key_name: Option<String>,
+
+    #[clap(
+        long = "dst-key-name",
+        value_name = "DST_KEY_NAME",
+        help = "Use the given signing key for the destination chain (default: `key_name` config)"
+    )]
+    dst_key_name: Option<String>,
+}
+
+impl Override<Config> for TxConnInitCmd {
+    fn override_config(&self, config: Config) -> Result<Config, FrameworkError> {
+        override_key_names(
+            config,
+            &[
+                (&self.src_chain_id, self.key_name.as_deref()),
+                (&self.dst_chain_id, self.dst_key_name.as_deref()),
+            ],
+        )
+    }
 }
 
 impl Runnable for TxConnInitCmd {
@@ -145,6 +173,32 @@ pub struct TxConnTryCmd {
         help = "Identifier of the destination connection (optional)"
     )]
     dst_conn_id: Option<ConnectionId>,
+
+    #[clap(
+        long = "key-name",
+        value_name = "KEY_NAME",
+        help = "Use the given signing key for the source chain (default: `key_name` config)"
+    )]
+    key_name: Option<String>,
+
+    #[clap(
+        long = "dst-key-name",
+        value_name = "DST_KEY_NAME",
+        help = "Use the given signing key for the destination chain (default: `key_name` config)"
+    )]
+    dst_key_name: Option<String>,
+}
+
+impl Override<Config> for TxConnTryCmd {
+    fn override_config(&self, config: Config) -> Result<Config, FrameworkError> {
+        override_key_names(
+            config,
+            &[
+                (&self.src_chain_id, self.key_name.as_deref()),
+                (&self.dst_chain_id, self.dst_key_name.as_deref()),
+            ],
+        )
+    }
 }
 
 impl Runnable for TxConnTryCmd {
@@ -229,6 +283,32 @@ pub struct TxConnAckCmd {
         help = "Identifier of the source connection (required)"
     )]
     src_conn_id: ConnectionId,
+
+    #[clap(
+        long = "key-name",
+        value_name = "KEY_NAME",
+        help = "Use the given signing key for the source chain (default: `key_name` config)"
+    )]
+    key_name: Option<String>,
+
+    #[clap(
+        long = "dst-key-name",
+        value_name = "DST_KEY_NAME",
+        help = "Use the given signing key for the destination chain (default: `key_name` config)"
+    )]
+    dst_key_name: Option<String>,
+}
+
+impl Override<Config> for TxConnAckCmd {
+    fn override_config(&self, config: Config) -> Result<Config, FrameworkError> {
+        override_key_names(
+            config,
+            &[
+                (&self.src_chain_id, self.key_name.as_deref()),
+                (&self.dst_chain_id, self.dst_key_name.as_deref()),
+            ],
+        )
+    }
 }
 
 impl Runnable for TxConnAckCmd {
@@ -313,6 +393,32 @@ pub struct TxConnConfirmCmd {
         help = "Identifier of the source connection (required)"
     )]
     src_conn_id: ConnectionId,
+
+    #[clap(
+        long = "key-name",
+        value_name = "KEY_NAME",
+        help = "Use the given signing key for the source chain (default: `key_name` config)"
+    )]
+    key_name: Option<String>,
+
+    #[clap(
+        long = "dst-key-name",
+        value_name = "DST_KEY_NAME",
+        help = "Use the given signing key for the destination chain (default: `key_name` config)"
+    )]
+    dst_key_name: Option<String>,
+}
+
+impl Override<Config> for TxConnConfirmCmd {
+    fn override_config(&self, config: Config) -> Result<Config, FrameworkError> {
+        override_key_names(
+            config,
+            &[
+                (&self.src_chain_id, self.key_name.as_deref()),
+                (&self.dst_chain_id, self.dst_key_name.as_deref()),
+            ],
+        )
+    }
 }
 
 impl Runnable for TxConnConfirmCmd {
@@ -356,7 +462,9 @@ mod tests {
                 dst_chain_id: ChainId::from_string("chain_b"),
                 src_chain_id: ChainId::from_string("chain_a"),
                 dst_client_id: ClientId::from_str("client_b-01").unwrap(),
-                src_client_id: ClientId::from_str("client_a-01").unwrap()
+                src_client_id: ClientId::from_str("client_a-01").unwrap(),
+                key_name: None,
+                dst_key_name: None,
             },
             TxConnInitCmd::parse_from(&[
                 "test",
@@ -437,7 +545,9 @@ mod tests {
                 dst_client_id: ClientId::from_str("client_b-01").unwrap(),
                 src_client_id: ClientId::from_str("client_a-01").unwrap(),
                 src_conn_id: ConnectionId::from_str("connection_a").unwrap(),
-                dst_conn_id: None
+                dst_conn_id: None,
+                key_name: None,
+                dst_key_name: None,
             },
             TxConnTryCmd::parse_from(&[
                 "test",
@@ -464,7 +574,9 @@ mod tests {
                 dst_client_id: ClientId::from_str("client_b-01").unwrap(),
                 src_client_id: ClientId::from_str("client_a-01").unwrap(),
                 src_conn_id: ConnectionId::from_str("connection_a").unwrap(),
-                dst_conn_id: Some(ConnectionId::from_str("connection_b").unwrap())
+                dst_conn_id: Some(ConnectionId::from_str("connection_b").unwrap()),
+                key_name: None,
+                dst_key_name: None,
             },
             TxConnTryCmd::parse_from(&[
                 "test",
@@ -493,7 +605,9 @@ mod tests {
                 dst_client_id: ClientId::from_str("client_b-01").unwrap(),
                 src_client_id: ClientId::from_str("client_a-01").unwrap(),
                 src_conn_id: ConnectionId::from_str("connection_a").unwrap(),
-                dst_conn_id: Some(ConnectionId::from_str("connection_b").unwrap())
+                dst_conn_id: Some(ConnectionId::from_str("connection_b").unwrap()),
+                key_name: None,
+                dst_key_name: None,
             },
             TxConnTryCmd::parse_from(&[
                 "test",
@@ -602,7 +716,9 @@ mod tests {
                 dst_client_id: ClientId::from_str("client_b-01").unwrap(),
                 src_client_id: ClientId::from_str("client_a-01").unwrap(),
                 dst_conn_id: ConnectionId::from_str("connection_b").unwrap(),
-                src_conn_id: ConnectionId::from_str("connection_a").unwrap()
+                src_conn_id: ConnectionId::from_str("connection_a").unwrap(),
+                key_name: None,
+                dst_key_name: None,
             },
             TxConnAckCmd::parse_from(&[
                 "test",
@@ -631,7 +747,9 @@ mod tests {
                 dst_client_id: ClientId::from_str("client_b-01").unwrap(),
                 src_client_id: ClientId::from_str("client_a-01").unwrap(),
                 dst_conn_id: ConnectionId::from_str("connection_b").unwrap(),
-                src_conn_id: ConnectionId::from_str("connection_a").unwrap()
+                src_conn_id: ConnectionId::from_str("connection_a").unwrap(),
+                key_name: None,
+                dst_key_name: None,
             },
             TxConnAckCmd::parse_from(&[
                 "test",
@@ -768,7 +886,9 @@ mod tests {
                 dst_client_id: ClientId::from_str("client_b-01").unwrap(),
                 src_client_id: ClientId::from_str("client_a-01").unwrap(),
                 dst_conn_id: ConnectionId::from_str("connection_b").unwrap(),
-                src_conn_id: ConnectionId::from_str("connection_a").unwrap()
+                src_conn_id: ConnectionId::from_str("connection_a").unwrap(),
+                key_name: None,
+                dst_key_name: None,
             },
             TxConnConfirmCmd::parse_from(&[
                 "test",
@@ -797,7 +917,9 @@ mod tests {
                 dst_client_id: ClientId::from_str("client_b-01").unwrap(),
                 src_client_id: ClientId::from_str("client_a-01").unwrap(),
                 dst_conn_id: ConnectionId::from_str("connection_b").unwrap(),
-                src_conn_id: ConnectionId::from_str("connection_a").unwrap()
+                src_conn_id: ConnectionId::from_str("connection_a").unwrap(),
+                key_name: None,
+                dst_key_name: None,
             },
             TxConnConfirmCmd::parse_from(&[
                 "test",
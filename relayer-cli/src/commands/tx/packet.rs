@@ -1,11 +1,13 @@
 use abscissa_core::clap::Parser;
-use abscissa_core::{Command, Runnable};
+use abscissa_core::config::Override;
+use abscissa_core::{Command, FrameworkError, Runnable};
 
 use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
 use ibc::events::IbcEvent;
+use ibc_relayer::config::Config;
 use ibc_relayer::link::{Link, LinkParameters};
 
-use crate::cli_utils::ChainHandlePair;
+use crate::cli_utils::{override_key_names, parse_channel_id, parse_port_id, ChainHandlePair};
 use crate::conclude::Output;
 use crate::error::Error;
 use crate::prelude::*;
@@ -34,6 +36,7 @@ pub struct TxPacketRecvCmd {
         long = "src-port",
         required = true,
         value_name = "SRC_PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "REQUIRED",
         help = "Identifier of the source port"
     )]
@@ -44,10 +47,37 @@ pub struct TxPacketRecvCmd {
         visible_alias = "src-chan",
         required = true,
         value_name = "SRC_CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
         help_heading = "REQUIRED",
         help = "Identifier of the source channel"
     )]
     src_channel_id: ChannelId,
+
+    #[clap(
+        long = "key-name",
+        value_name = "KEY_NAME",
+        help = "Use the given signing key for the source chain (default: `key_name` config)"
+    )]
+    key_name: Option<String>,
+
+    #[clap(
+        long = "dst-key-name",
+        value_name = "DST_KEY_NAME",
+        help = "Use the given signing key for the destination chain (default: `key_name` config)"
+    )]
+    dst_key_name: Option<String>,
+}
+
+impl Override<Config> for TxPacketRecvCmd {
+    fn override_config(&self, config: Config) -> Result<Config, FrameworkError> {
+        override_key_names(
+            config,
+            &[
+                (&self.src_chain_id, self.key_name.as_deref()),
+                (&self.dst_chain_id, self.dst_key_name.as_deref()),
+            ],
+        )
+    }
 }
 
 impl Runnable for TxPacketRecvCmd {
@@ -103,6 +133,7 @@ pub struct TxPacketAckCmd {
         long = "src-port",
         required = true,
         value_name = "SRC_PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "REQUIRED",
         help = "Identifier of the source port"
     )]
@@ -113,10 +144,37 @@ pub struct TxPacketAckCmd {
         visible_alias = "src-chan",
         required = true,
         value_name = "SRC_CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
         help_heading = "REQUIRED",
         help = "Identifier of the source channel"
     )]
     src_channel_id: ChannelId,
+
+    #[clap(
+        long = "key-name",
+        value_name = "KEY_NAME",
+        help = "Use the given signing key for the source chain (default: `key_name` config)"
+    )]
+    key_name: Option<String>,
+
+    #[clap(
+        long = "dst-key-name",
+        value_name = "DST_KEY_NAME",
+        help = "Use the given signing key for the destination chain (default: `key_name` config)"
+    )]
+    dst_key_name: Option<String>,
+}
+
+impl Override<Config> for TxPacketAckCmd {
+    fn override_config(&self, config: Config) -> Result<Config, FrameworkError> {
+        override_key_names(
+            config,
+            &[
+                (&self.src_chain_id, self.key_name.as_deref()),
+                (&self.dst_chain_id, self.dst_key_name.as_deref()),
+            ],
+        )
+    }
 }
 
 impl Runnable for TxPacketAckCmd {
@@ -163,7 +221,9 @@ mod tests {
                 dst_chain_id: ChainId::from_string("chain_receiver"),
                 src_chain_id: ChainId::from_string("chain_sender"),
                 src_port_id: PortId::from_str("port_sender").unwrap(),
-                src_channel_id: ChannelId::from_str("channel_sender").unwrap()
+                src_channel_id: ChannelId::from_str("channel_sender").unwrap(),
+                key_name: None,
+                dst_key_name: None,
             },
             TxPacketRecvCmd::parse_from(&[
                 "test",
@@ -186,7 +246,9 @@ mod tests {
                 dst_chain_id: ChainId::from_string("chain_receiver"),
                 src_chain_id: ChainId::from_string("chain_sender"),
                 src_port_id: PortId::from_str("port_sender").unwrap(),
-                src_channel_id: ChannelId::from_str("channel_sender").unwrap()
+                src_channel_id: ChannelId::from_str("channel_sender").unwrap(),
+                key_name: None,
+                dst_key_name: None,
             },
             TxPacketRecvCmd::parse_from(&[
                 "test",
@@ -202,6 +264,35 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_packet_recv_key_names() {
+        assert_eq!(
+            TxPacketRecvCmd {
+                dst_chain_id: ChainId::from_string("chain_receiver"),
+                src_chain_id: ChainId::from_string("chain_sender"),
+                src_port_id: PortId::from_str("port_sender").unwrap(),
+                src_channel_id: ChannelId::from_str("channel_sender").unwrap(),
+                key_name: Some("sender_key".to_owned()),
+                dst_key_name: Some("receiver_key".to_owned()),
+            },
+            TxPacketRecvCmd::parse_from(&[
+                "test",
+                "--dst-chain",
+                "chain_receiver",
+                "--src-chain",
+                "chain_sender",
+                "--src-port",
+                "port_sender",
+                "--src-channel",
+                "channel_sender",
+                "--key-name",
+                "sender_key",
+                "--dst-key-name",
+                "receiver_key"
+            ])
+        )
+    }
+
     #[test]
     fn test_packet_recv_no_sender_channel() {
         assert!(TxPacketRecvCmd::try_parse_from(&[
@@ -265,7 +356,9 @@ mod tests {
                 dst_chain_id: ChainId::from_string("chain_receiver"),
                 src_chain_id: ChainId::from_string("chain_sender"),
                 src_port_id: PortId::from_str("port_sender").unwrap(),
-                src_channel_id: ChannelId::from_str("channel_sender").unwrap()
+                src_channel_id: ChannelId::from_str("channel_sender").unwrap(),
+                key_name: None,
+                dst_key_name: None,
             },
             TxPacketAckCmd::parse_from(&[
                 "test",
@@ -288,7 +381,9 @@ mod tests {
                 dst_chain_id: ChainId::from_string("chain_receiver"),
                 src_chain_id: ChainId::from_string("chain_sender"),
                 src_port_id: PortId::from_str("port_sender").unwrap(),
-                src_channel_id: ChannelId::from_str("channel_sender").unwrap()
+                src_channel_id: ChannelId::from_str("channel_sender").unwrap(),
+                key_name: None,
+                dst_key_name: None,
             },
             TxPacketAckCmd::parse_from(&[
                 "test",
@@ -2,91 +2,166 @@ use abscissa_core::clap::Parser;
 use abscissa_core::{config::Override, Command, FrameworkErrorKind, Runnable};
 
 use core::time::Duration;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
 use ibc::{
-    applications::transfer::Amount,
+    applications::transfer::{msgs::transfer::MsgTransfer, Amount, Coin},
     core::{
-        ics02_client::client_state::ClientState,
+        ics04_channel::{packet::Sequence, Version},
         ics24_host::identifier::{ChainId, ChannelId, PortId},
     },
     events::IbcEvent,
+    timestamp::Timestamp,
 };
-use ibc_relayer::chain::handle::ChainHandle;
-use ibc_relayer::chain::requests::{
-    IncludeProof, QueryChannelRequest, QueryClientStateRequest, QueryConnectionRequest, QueryHeight,
-};
+use ibc_proto::cosmos::base::v1beta1::Coin as RawCoin;
+use ibc_proto::ibc::applications::transfer::v1::MsgTransfer as RawMsgTransfer;
 use ibc_relayer::{
-    config::Config,
-    transfer::{build_and_send_transfer_messages, TransferOptions},
+    chain::cosmos::{
+        gas::calculate_fee,
+        types::gas::{default_gas_from_config, max_gas_from_config, ExplicitFee},
+    },
+    chain::endpoint::SendPacketTx,
+    chain::handle::ChainHandle,
+    chain::requests::{IncludeProof, QueryChannelRequest, QueryHeight, QuerySendPacketTxsRequest},
+    chain::tracking::CorrelationId,
+    config::{ChainConfig, Config, GasPrice},
+    denom::resolver::default_resolver,
+    denom::{DenomTrace, IBC_DENOM_PREFIX},
+    keyring::KeyEntry,
+    transfer::{
+        build_multi_channel_transfer_messages, check_timeout_timestamp, check_transfer_path,
+        check_voucher_channel, resolve_voucher_trace, send_transfer_messages,
+        simulate_transfer_messages, wait_for_transfer_acks, PacketStatus, TransferError,
+        TransferOptions, TransferSimulation,
+    },
+    util::retry::retry_transient,
 };
+use tendermint_rpc::Url;
 
-use crate::cli_utils::ChainHandlePair;
-use crate::conclude::{exit_with_unrecoverable_error, Output};
+use crate::cli_utils::adhoc::{parse_gas_price, AdHocChain, AdHocChainArgs};
+use crate::cli_utils::dry_run::DryRunMessage;
+use crate::cli_utils::{
+    find_chain_config, parse_channel_id, parse_port_id, resolve_chain_id, ChainHandlePair,
+};
+use crate::conclude::{exit_with_unrecoverable_error, json, Output};
 use crate::error::Error;
 use crate::prelude::*;
 
+use self::file::TransferFile;
+use self::output::TransferOutput;
+use self::receipt::TransferReceipt;
+
+mod file;
+mod output;
+mod receipt;
+
+/// The denomination of the coins sent with `--amount` when no other is given.
+const DEFAULT_DENOM: &str = "samoleans";
+
+/// The delay before the first retry of a query of the checks, doubled at each retry.
+const QUERY_RETRY_DELAY: Duration = Duration::from_millis(500);
+
 #[derive(Clone, Command, Debug, Parser, PartialEq)]
 pub struct TxIcs20MsgTransferCmd {
     #[clap(
         long = "dst-chain",
-        required = true,
+        required_unless_present = "from-file",
         value_name = "DST_CHAIN_ID",
         help_heading = "REQUIRED",
         help = "Identifier of the destination chain"
     )]
-    dst_chain_id: ChainId,
+    dst_chain_id: Option<ChainId>,
 
     #[clap(
         long = "src-chain",
-        required = true,
+        required_unless_present = "from-file",
         value_name = "SRC_CHAIN_ID",
         help_heading = "REQUIRED",
         help = "Identifier of the source chain"
     )]
-    src_chain_id: ChainId,
+    src_chain_id: Option<ChainId>,
 
     #[clap(
         long = "src-port",
-        required = true,
+        required_unless_present = "from-file",
         value_name = "SRC_PORT_ID",
+        parse(try_from_str = parse_port_id),
         help_heading = "REQUIRED",
         help = "Identifier of the source port"
     )]
-    src_port_id: PortId,
+    src_port_id: Option<PortId>,
 
     #[clap(
         long = "src-channel",
         visible_alias = "src-chan",
-        required = true,
+        required_unless_present_any = &["src-channels", "from-file"],
         value_name = "SRC_CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
         help_heading = "REQUIRED",
-        help = "Identifier of the source channel"
+        help = "Identifier of the source channel, unless `--src-channels` is given"
+    )]
+    src_channel_id: Option<ChannelId>,
+
+    #[clap(
+        long = "src-channels",
+        multiple_occurrences = true,
+        value_delimiter = ',',
+        conflicts_with_all = &["src-channel-id", "receipt-file"],
+        value_name = "SRC_CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
+        help = "Identifiers of several source channels to repeat the transfer over each of them, instead of `--src-channel`. \
+                Repeat the flag or separate the channels with commas"
     )]
-    src_channel_id: ChannelId,
+    src_channels: Vec<ChannelId>,
 
     #[clap(
         long = "amount",
-        required = true,
+        required_unless_present_any = &["coins", "from-file"],
         value_name = "AMOUNT",
         help_heading = "REQUIRED",
-        help = "Amount of coins (samoleans, by default) to send (e.g. `100000`)"
+        parse(try_from_str = parse_amount),
+        help = "Amount of coins (samoleans, by default) to send, optionally followed by their denomination \
+                instead of `--denom` (e.g. `100000` or `100000uatom`), unless `--coin` is given. \
+                The amount can have a fractional part along with `--decimals` (e.g. `1.5`), or be a percentage \
+                of the balance of the sender on the source chain (e.g. `25%` or `25%uatom`)"
+    )]
+    amount: Option<DecimalAmount>,
+
+    #[clap(
+        long = "coin",
+        multiple_occurrences = true,
+        value_delimiter = ',',
+        conflicts_with_all = &["amount", "denom"],
+        value_name = "COIN",
+        parse(try_from_str = parse_coin),
+        help = "Coins to send, as an amount followed by a denomination (e.g. `100stake`), instead of `--amount` and `--denom`. \
+                Repeat the flag or separate the coins with commas to send several coins in the same transaction, each in its own message"
     )]
-    amount: Amount,
+    coins: Vec<Coin<String>>,
 
     #[clap(
         long = "timeout-height-offset",
-        default_value = "0",
         value_name = "TIMEOUT_HEIGHT_OFFSET",
-        help = "Timeout in number of blocks since current"
+        help = "Timeout in number of blocks since current [default: 0]"
     )]
-    timeout_height_offset: u64,
+    timeout_height_offset: Option<u64>,
 
     #[clap(
         long = "timeout-seconds",
-        default_value = "0",
         value_name = "TIMEOUT_SECONDS",
-        help = "Timeout in seconds since current"
+        help = "Timeout in seconds since current [default: 0]"
+    )]
+    timeout_seconds: Option<u64>,
+
+    #[clap(
+        long = "timeout-timestamp",
+        value_name = "TIMEOUT_TIMESTAMP",
+        parse(try_from_str = parse_timeout_timestamp),
+        help = "Absolute timeout timestamp, in RFC 3339 format (e.g. `2024-09-01T12:00:00Z`) or in nanoseconds since the Unix epoch, instead of `--timeout-seconds`"
     )]
-    timeout_seconds: u64,
+    timeout_timestamp: Option<Timestamp>,
 
     #[clap(
         long = "receiver",
@@ -95,13 +170,29 @@ pub struct TxIcs20MsgTransferCmd {
     )]
     receiver: Option<String>,
 
+    #[clap(
+        long = "allow-unchecked-receiver",
+        requires = "receiver",
+        help = "Send the tokens to the receiver even if it is not a bech32 address with the account prefix of the destination chain, e.g. for chains with another address scheme"
+    )]
+    allow_unchecked_receiver: bool,
+
     #[clap(
         long = "denom",
         value_name = "DENOM",
-        help = "Denomination of the coins to send",
-        default_value = "samoleans"
+        help = "Denomination of the coins to send with `--amount` [default: samoleans]"
+    )]
+    denom: Option<String>,
+
+    #[clap(
+        long = "decimals",
+        value_name = "DECIMALS",
+        requires = "amount",
+        conflicts_with = "coins",
+        help = "Number of decimals of the unit in which `--amount` is given, which is converted to the denomination \
+                of the coins sent, e.g. `--amount 1.5 --denom uatom --decimals 6` sends 1500000uatom"
     )]
-    denom: String,
+    decimals: Option<u32>,
 
     #[clap(
         long = "number-msgs",
@@ -116,18 +207,163 @@ pub struct TxIcs20MsgTransferCmd {
         help = "Use the given signing key name (default: `key_name` config)"
     )]
     key_name: Option<String>,
+
+    #[clap(
+        long = "force",
+        help = "Send the transfer even if neither a timeout height offset nor a timeout in seconds is given, in which case the packet never times out, \
+                or if the receiver is not a bech32 address with the account prefix of the destination chain"
+    )]
+    force: bool,
+
+    #[clap(
+        long = "fee",
+        value_name = "FEE",
+        help = "Fee to pay for each transaction instead of the fee estimated by simulating it, e.g. `5000uatom`"
+    )]
+    fee: Option<String>,
+
+    #[clap(
+        long = "gas",
+        value_name = "GAS",
+        requires = "fee",
+        help = "Gas limit of each transaction paying the given fee (default: `default_gas` config)"
+    )]
+    gas: Option<u64>,
+
+    #[clap(
+        long = "fee-granter",
+        value_name = "ADDRESS",
+        help = "Address of the account paying the fees through a fee grant, on the source chain (default: `fee_granter` config)"
+    )]
+    fee_granter: Option<String>,
+
+    #[clap(
+        long = "receipt-file",
+        value_name = "PATH",
+        help = "Write a JSON receipt of the transfer to the given file once the transactions are committed"
+    )]
+    receipt_file: Option<PathBuf>,
+
+    #[clap(
+        long = "memo",
+        value_name = "MEMO",
+        help = "Memo to include in the transfer messages, which the chains running ibc-go v6 or above forward in the packet data"
+    )]
+    memo: Option<String>,
+
+    #[clap(
+        long = "dry-run",
+        conflicts_with = "receipt-file",
+        help = "Run the checks and build the messages to print them along with their estimated fee, without broadcasting them"
+    )]
+    dry_run: bool,
+
+    #[clap(
+        long = "wait-for-ack",
+        value_name = "TIMEOUT_SECS",
+        conflicts_with = "dry-run",
+        help = "Once the transfer is sent, wait at most the given number of seconds for the packets to be acknowledged or to time out on the destination chain, and report their status"
+    )]
+    wait_for_ack: Option<u64>,
+
+    #[clap(
+        long = "verbose",
+        conflicts_with = "dry-run",
+        help = "Include the events of the transactions in the output, along with the packets they sent"
+    )]
+    verbose: bool,
+
+    #[clap(
+        long = "query-retries",
+        default_value = "0",
+        value_name = "QUERY_RETRIES",
+        help = "Number of times the queries of the checks run before the transfer are retried when they fail with a transient error, such as a timeout or a lost connection"
+    )]
+    query_retries: usize,
+
+    #[clap(
+        long = "from-file",
+        value_name = "PATH",
+        help = "Read the parameters of the transfer from the given JSON or TOML file, named after their flags, \
+                e.g. `src_chain` for `--src-chain`. The flags given on the command line take precedence over the file"
+    )]
+    from_file: Option<PathBuf>,
+
+    #[clap(
+        long = "src-rpc",
+        value_name = "RPC_ADDR",
+        help_heading = "AD-HOC SOURCE CHAIN",
+        help = "RPC address of a source chain absent from the configuration, which requires all the other `--src-*` flags of this section"
+    )]
+    src_rpc: Option<Url>,
+
+    #[clap(
+        long = "src-grpc",
+        value_name = "GRPC_ADDR",
+        help_heading = "AD-HOC SOURCE CHAIN",
+        help = "gRPC address of the source chain"
+    )]
+    src_grpc: Option<Url>,
+
+    #[clap(
+        long = "src-account-prefix",
+        value_name = "PREFIX",
+        help_heading = "AD-HOC SOURCE CHAIN",
+        help = "Prefix of the account addresses of the source chain, e.g. `cosmos`"
+    )]
+    src_account_prefix: Option<String>,
+
+    #[clap(
+        long = "src-gas-price",
+        value_name = "GAS_PRICE",
+        parse(try_from_str = parse_gas_price),
+        help_heading = "AD-HOC SOURCE CHAIN",
+        help = "Gas price on the source chain, e.g. `0.01uatom`"
+    )]
+    src_gas_price: Option<GasPrice>,
+
+    #[clap(
+        long = "src-key-file",
+        value_name = "KEY_FILE",
+        help_heading = "AD-HOC SOURCE CHAIN",
+        help = "Key file of the account sending the tokens, in the format of `keys add --key-file`"
+    )]
+    src_key_file: Option<PathBuf>,
 }
 
 impl Override<Config> for TxIcs20MsgTransferCmd {
     fn override_config(&self, mut config: Config) -> Result<Config, abscissa_core::FrameworkError> {
-        let src_chain_config = config.find_chain_mut(&self.src_chain_id).ok_or_else(|| {
+        let cmd = self
+            .merged()
+            .map_err(|e| FrameworkErrorKind::ComponentError.context(e.to_string()))?;
+
+        if let Some(src_chain) = cmd
+            .ad_hoc_src_chain()
+            .map_err(|e| FrameworkErrorKind::ComponentError.context(e.to_string()))?
+        {
+            if config.has_chain(&src_chain.config.id) {
+                let e = Error::ad_hoc_chain_configured(src_chain.config.id);
+                return Err(FrameworkErrorKind::ComponentError
+                    .context(e.to_string())
+                    .into());
+            }
+
+            config.chains.push(src_chain.config);
+        }
+
+        let src_chain_id = cmd
+            .src_chain_id()
+            .and_then(|chain_id| resolve_chain_id(&config, chain_id))
+            .map_err(|e| FrameworkErrorKind::ComponentError.context(e.to_string()))?;
+
+        let src_chain_config = config.find_chain_mut(&src_chain_id).ok_or_else(|| {
             FrameworkErrorKind::ComponentError.context(format!(
                 "missing configuration for source chain '{}'",
-                self.src_chain_id
+                src_chain_id
             ))
         })?;
 
-        if let Some(ref key_name) = self.key_name {
+        if let Some(ref key_name) = cmd.key_name {
             src_chain_config.key_name = key_name.to_string();
         }
 
@@ -136,174 +372,958 @@ impl Override<Config> for TxIcs20MsgTransferCmd {
 }
 
 impl TxIcs20MsgTransferCmd {
+    /// The command with the parameters of the file given with `--from-file`, if any,
+    /// which are not given on the command line, checked to include the required ones.
+    fn merged(&self) -> Result<Self, Error> {
+        let merged = match &self.from_file {
+            Some(path) => TransferFile::read(path)?
+                .merge(self)
+                .map_err(|reason| Error::transfer_file(path.display().to_string(), reason))?,
+            None => self.clone(),
+        };
+
+        merged.dst_chain_id()?;
+        merged.src_chain_id()?;
+        merged.src_port_id()?;
+
+        if merged.src_channel_id.is_none() && merged.src_channels.is_empty() {
+            return Err(missing_parameter("--src-channel", "src_channel"));
+        }
+
+        if merged.amount.is_none() && merged.coins.is_empty() {
+            return Err(missing_parameter("--amount", "amount"));
+        }
+
+        Ok(merged)
+    }
+
+    fn dst_chain_id(&self) -> Result<&ChainId, Error> {
+        self.dst_chain_id
+            .as_ref()
+            .ok_or_else(|| missing_parameter("--dst-chain", "dst_chain"))
+    }
+
+    fn src_chain_id(&self) -> Result<&ChainId, Error> {
+        self.src_chain_id
+            .as_ref()
+            .ok_or_else(|| missing_parameter("--src-chain", "src_chain"))
+    }
+
+    fn src_port_id(&self) -> Result<&PortId, Error> {
+        self.src_port_id
+            .as_ref()
+            .ok_or_else(|| missing_parameter("--src-port", "src_port"))
+    }
+
+    /// The source channels of the transfer, given either by `--src-channel` or by `--src-channels`.
+    fn src_channel_ids(&self) -> Vec<ChannelId> {
+        match &self.src_channel_id {
+            Some(channel_id) => vec![channel_id.clone()],
+            None => {
+                // The same channel given twice is used once
+                let mut channel_ids: Vec<ChannelId> = Vec::with_capacity(self.src_channels.len());
+                for channel_id in &self.src_channels {
+                    if !channel_ids.contains(channel_id) {
+                        channel_ids.push(channel_id.clone());
+                    }
+                }
+                channel_ids
+            }
+        }
+    }
+
+    /// The source chain built from the `--src-*` flags, when it is absent from the configuration.
+    fn ad_hoc_src_chain(&self) -> Result<Option<AdHocChain>, Error> {
+        let args = AdHocChainArgs {
+            rpc_addr: self.src_rpc.clone(),
+            grpc_addr: self.src_grpc.clone(),
+            account_prefix: self.src_account_prefix.clone(),
+            gas_price: self.src_gas_price.clone(),
+            key_file: self.src_key_file.clone(),
+        };
+
+        args.build(self.src_chain_id()?, self.key_name.as_deref(), "src")
+    }
+
+    /// The ad-hoc source chain, if any, along with the key read from its key file.
+    fn ad_hoc_src_key(&self) -> Result<Option<(AdHocChain, KeyEntry)>, Error> {
+        match self.ad_hoc_src_chain()? {
+            Some(chain) => {
+                let key = chain.load_key()?;
+                Ok(Some((chain, key)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// The amount given with `--amount` in the base denomination of the coins,
+    /// converted from the unit with `--decimals` decimals, if any.
+    ///
+    /// A percentage of the balance is only checked, and zero until it is resolved
+    /// from the balance once the source chain is spawned, see [`amount_from_balance`].
+    fn base_amount(&self, amount: &DecimalAmount) -> Result<Amount, String> {
+        if amount.percentage {
+            if self.decimals.is_some() {
+                return Err("a percentage of the balance cannot be given with `--decimals`".into());
+            }
+
+            return Amount::from(0)
+                .percentage(&amount.amount)
+                .map_err(|e| format!("{}", e));
+        }
+
+        match self.decimals {
+            None if amount.amount.contains('.') => Err(format!(
+                "the amount '{}' has a fractional part, give the number of decimals of its unit with `--decimals`",
+                amount.amount
+            )),
+            decimals => Amount::from_decimal_str(&amount.amount, decimals.unwrap_or(0))
+                .map_err(|e| format!("{}", e)),
+        }
+    }
+
+    /// The percentage of the balance given with `--amount`, if any.
+    fn balance_percentage(&self) -> Option<&str> {
+        self.amount
+            .as_ref()
+            .filter(|amount| amount.percentage)
+            .map(|amount| amount.amount.as_str())
+    }
+
     fn validate_options(
         &self,
         config: &Config,
     ) -> Result<TransferOptions, Box<dyn std::error::Error>> {
-        config.find_chain(&self.src_chain_id).ok_or_else(|| {
-            format!(
-                "missing configuration for source chain '{}'",
-                self.src_chain_id
-            )
-        })?;
+        let src_chain_config = find_chain_config(config, self.src_chain_id()?)?;
+        let dst_chain_config = find_chain_config(config, self.dst_chain_id()?)?;
 
-        config.find_chain(&self.dst_chain_id).ok_or_else(|| {
-            format!(
-                "missing configuration for destination chain '{}'",
-                self.dst_chain_id
-            )
-        })?;
+        let timeout_height_offset = self.timeout_height_offset.unwrap_or(0);
+        let timeout_seconds = self.timeout_seconds.unwrap_or(0);
+
+        if self.timeout_timestamp.is_some() && timeout_seconds != 0 {
+            return Err(
+                "an absolute packet timeout timestamp and a packet timeout in seconds cannot both be given"
+                    .into(),
+            );
+        }
+
+        if timeout_height_offset == 0
+            && timeout_seconds == 0
+            && self.timeout_timestamp.is_none()
+            && !self.force
+        {
+            return Err(
+                "packet timeout height offset and packet timeout seconds cannot both be 0, \
+                 pass --force to send a packet which never times out"
+                    .into(),
+            );
+        }
+
+        let coins = match &self.amount {
+            Some(
+                amount @ DecimalAmount {
+                    denom: Some(denom), ..
+                },
+            ) => {
+                if let Some(other_denom) = &self.denom {
+                    return Err(format!(
+                        "the amount is given with the denomination '{}', which conflicts with `--denom {}`, \
+                        only give one of them",
+                        denom, other_denom
+                    )
+                    .into());
+                }
+
+                vec![Coin {
+                    denom: denom.clone(),
+                    amount: self.base_amount(amount)?,
+                }]
+            }
+            Some(amount @ DecimalAmount { denom: None, .. }) => vec![Coin {
+                denom: self
+                    .denom
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_DENOM.to_string()),
+                amount: self.base_amount(amount)?,
+            }],
+            None => {
+                // The same coin given twice is sent once
+                let mut coins: Vec<Coin<String>> = Vec::with_capacity(self.coins.len());
+                for coin in &self.coins {
+                    if !coins.contains(coin) {
+                        coins.push(coin.clone());
+                    }
+                }
+                coins
+            }
+        };
 
-        let denom = self.denom.clone();
+        if coins.len() > 1 && self.receipt_file.is_some() {
+            return Err(
+                "a transfer receipt can only be written for the transfer of a single coin".into(),
+            );
+        }
 
         let number_msgs = self.number_msgs.unwrap_or(1);
         if number_msgs == 0 {
             return Err("number of messages should be greater than zero".into());
         }
 
+        let fee = match &self.fee {
+            Some(fee) => Some(explicit_fee(src_chain_config, fee, self.gas)?),
+            None => None,
+        };
+
+        // The transfer is repeated over the other channels, if any, by `run`
+        let src_channel_id = self
+            .src_channel_ids()
+            .into_iter()
+            .next()
+            .ok_or("at least one source channel must be given")?;
+
         let opts = TransferOptions {
-            packet_src_port_id: self.src_port_id.clone(),
-            packet_src_channel_id: self.src_channel_id.clone(),
-            amount: self.amount,
-            denom,
+            packet_src_port_id: self.src_port_id()?.clone(),
+            packet_src_channel_id: src_channel_id,
+            coins,
             receiver: self.receiver.clone(),
-            timeout_height_offset: self.timeout_height_offset,
-            timeout_duration: Duration::from_secs(self.timeout_seconds),
+            timeout_height_offset,
+            timeout_duration: Duration::from_secs(timeout_seconds),
+            timeout_timestamp: self.timeout_timestamp,
             number_msgs,
+            fee,
+            fee_granter: self.fee_granter.clone(),
+            memo: self.memo.clone(),
         };
 
+        opts.validate_coins()?;
+        opts.validate_fee_granter(src_chain_config)?;
+        opts.validate_memo()?;
+
+        // The relayer wallet, used when no receiver is given, has the prefix of the destination chain
+        if !self.allow_unchecked_receiver && !self.force {
+            opts.validate_receiver(dst_chain_config)?;
+        }
+
+        // The memo is checked by the source chain, and by the middlewares of the destination chain
+        opts.warn_on_long_memo(src_chain_config);
+        opts.warn_on_long_memo(dst_chain_config);
+
         Ok(opts)
     }
 }
 
-impl Runnable for TxIcs20MsgTransferCmd {
-    fn run(&self) {
+/// An amount of coins given with `--amount`, optionally followed by their denomination.
+/// The amount has a fractional part when it is given in a unit with `--decimals` decimals,
+/// and is only converted to the base denomination once the decimals are known.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct DecimalAmount {
+    amount: String,
+    denom: Option<String>,
+    /// Whether the amount is a percentage of the balance of the sender, e.g. `25%`.
+    percentage: bool,
+}
+
+/// Parses an amount of coins, possibly with a fractional part or as a percentage of
+/// the balance, optionally followed by their denomination, e.g. `100000`, `100000uatom`,
+/// `1.5uatom` or `25%uatom`.
+fn parse_amount(amount: &str) -> Result<DecimalAmount, String> {
+    let is_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+
+    // The characters allowed in denominations by the Cosmos SDK
+    let is_denom = |s: &str| {
+        s.starts_with(|c: char| c.is_ascii_alphabetic())
+            && s.chars()
+                .all(|c| c.is_ascii_alphanumeric() || "/:._-".contains(c))
+    };
+
+    let denom_start = amount
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(amount.len());
+
+    let (value, denom) = amount.split_at(denom_start);
+
+    let (percentage, denom) = match denom.strip_prefix('%') {
+        Some(denom) => (true, denom),
+        None => (false, denom),
+    };
+
+    let is_decimal = match value.split_once('.') {
+        Some((integer, fraction)) => is_digits(integer) && is_digits(fraction),
+        None => is_digits(value),
+    };
+
+    if is_decimal && (denom.is_empty() || is_denom(denom)) {
+        Ok(DecimalAmount {
+            amount: value.to_string(),
+            denom: (!denom.is_empty()).then(|| denom.to_string()),
+            percentage,
+        })
+    } else {
+        Err(format!(
+            "invalid amount '{}', expected an amount optionally followed by a denomination, e.g. `100000uatom`, \
+            or a percentage of the balance, e.g. `25%`",
+            amount
+        ))
+    }
+}
+
+/// Parses coins given as an amount followed by a denomination, e.g. `100stake`.
+fn parse_coin(coin: &str) -> Result<Coin<String>, String> {
+    let denom_start = coin
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(coin.len());
+
+    let (amount, denom) = coin.split_at(denom_start);
+
+    match amount.parse::<Amount>() {
+        Ok(amount) if denom_start > 0 && denom.starts_with(|c: char| c.is_ascii_alphabetic()) => {
+            Ok(Coin {
+                denom: denom.to_string(),
+                amount,
+            })
+        }
+        _ => Err(format!(
+            "invalid coin '{}', expected an amount followed by a denomination, e.g. `100stake`",
+            coin
+        )),
+    }
+}
+
+/// Parses an absolute timeout timestamp, given either in RFC 3339 format,
+/// e.g. `2024-09-01T12:00:00Z`, or in nanoseconds since the Unix epoch.
+fn parse_timeout_timestamp(timestamp: &str) -> Result<Timestamp, String> {
+    let parsed = if timestamp.bytes().all(|b| b.is_ascii_digit()) {
+        timestamp.parse::<Timestamp>().ok()
+    } else {
+        tendermint::Time::parse_from_rfc3339(timestamp)
+            .map(Timestamp::from)
+            .ok()
+    };
+
+    match parsed {
+        Some(timestamp) if timestamp != Timestamp::none() => Ok(timestamp),
+        _ => Err(format!(
+            "invalid timeout timestamp '{}', expected a timestamp in RFC 3339 format, e.g. `2024-09-01T12:00:00Z`, \
+            or a number of nanoseconds since the Unix epoch",
+            timestamp
+        )),
+    }
+}
+
+/// Parses a fee given as an amount followed by a denomination, e.g. `5000uatom`.
+fn parse_fee(fee: &str) -> Result<RawCoin, String> {
+    let denom_start = fee.find(|c: char| !c.is_ascii_digit()).unwrap_or(fee.len());
+
+    let (amount, denom) = fee.split_at(denom_start);
+
+    match amount.parse::<u128>() {
+        Ok(_) if denom.starts_with(|c: char| c.is_ascii_alphabetic()) => Ok(RawCoin {
+            denom: denom.to_string(),
+            amount: amount.to_string(),
+        }),
+        _ => Err(format!(
+            "invalid fee '{}', expected an amount followed by a denomination, e.g. `5000uatom`",
+            fee
+        )),
+    }
+}
+
+/// Builds the explicit fee paid for each transaction on the chain configured by `chain_config`,
+/// checking that it is paid in the fee denomination of the chain, and warning when it is
+/// below the fee that the configured gas price would pay for the gas limit.
+fn explicit_fee(
+    chain_config: &ChainConfig,
+    fee: &str,
+    gas_limit: Option<u64>,
+) -> Result<ExplicitFee, String> {
+    let amount = parse_fee(fee)?;
+
+    if amount.denom != chain_config.gas_price.denom {
+        return Err(format!(
+            "the fee denomination '{}' is not the fee denomination of chain '{}', which is '{}'",
+            amount.denom, chain_config.id, chain_config.gas_price.denom
+        ));
+    }
+
+    let gas = gas_limit.unwrap_or_else(|| default_gas_from_config(chain_config));
+    let min_fee = calculate_fee(gas, &chain_config.gas_price);
+
+    if below(&amount, &min_fee) {
+        warn!(
+            "the fee {}{} is below the fee of {}{} paid for {} gas at the gas price configured for chain '{}', \
+            the transactions may be rejected by the nodes",
+            amount.amount, amount.denom, min_fee.amount, min_fee.denom, gas, chain_config.id
+        );
+    }
+
+    Ok(ExplicitFee { amount, gas_limit })
+}
+
+/// Whether the `amount` of coins is lower than `other`, both of the same denomination.
+fn below(amount: &RawCoin, other: &RawCoin) -> bool {
+    match (amount.amount.parse::<u128>(), other.amount.parse::<u128>()) {
+        (Ok(amount), Ok(other)) => amount < other,
+        // The minimum fee does not fit in a `u128`
+        (Ok(_), Err(_)) => true,
+        _ => false,
+    }
+}
+
+impl TxIcs20MsgTransferCmd {
+    /// Sends the transfer described by the command, once merged with the file given
+    /// with `--from-file`, if any.
+    fn transfer(&self) {
         let config = app_config();
 
-        let opts = match self.validate_options(&config) {
+        let mut opts = match self.validate_options(&config) {
             Err(err) => Output::error(err).exit(),
             Ok(result) => result,
         };
 
         debug!("Message: {:?}", opts);
 
-        let chains = ChainHandlePair::spawn(&config, &self.src_chain_id, &self.dst_chain_id)
-            .unwrap_or_else(exit_with_unrecoverable_error);
+        // An ad-hoc source chain was added to the configuration by `override_config`,
+        // but its key is only known to its runtime once added to its in-memory keyring
+        let ad_hoc_src_key = match self.ad_hoc_src_key() {
+            Err(e) => Output::error(format!("{}", e)).exit(),
+            Ok(result) => result,
+        };
 
-        // Double check that channels and chain identifiers match.
-        // To do this, fetch from the source chain the channel end, then the associated connection
-        // end, and then the underlying client state; finally, check that this client is verifying
-        // headers for the destination chain.
-        let (channel_end_src, _) = chains
-            .src
-            .query_channel(
-                QueryChannelRequest {
-                    port_id: opts.packet_src_port_id.clone(),
-                    channel_id: opts.packet_src_channel_id.clone(),
-                    height: QueryHeight::Latest,
-                },
-                IncludeProof::No,
-            )
+        let (src_chain_id, dst_chain_id) = match (self.src_chain_id(), self.dst_chain_id()) {
+            (Ok(src_chain_id), Ok(dst_chain_id)) => (src_chain_id, dst_chain_id),
+            (Err(e), _) | (_, Err(e)) => Output::error(format!("{}", e)).exit(),
+        };
+
+        let chains = ChainHandlePair::spawn(&config, src_chain_id, dst_chain_id)
             .unwrap_or_else(exit_with_unrecoverable_error);
-        if !channel_end_src.is_open() {
-            Output::error(format!(
-                "the requested port/channel ('{}'/'{}') on chain id '{}' is in state '{}'; expected 'open' state",
-                opts.packet_src_port_id,
-                opts.packet_src_channel_id,
-                self.src_chain_id,
-                channel_end_src.state
-            ))
-                .exit();
+
+        if let Some((src_chain, key)) = ad_hoc_src_key {
+            if let Err(e) = src_chain.add_key(&chains.src, key) {
+                Output::error(format!("{}", e)).exit();
+            }
         }
 
-        let conn_id = match channel_end_src.connection_hops.first() {
-            None => {
-                Output::error(format!(
-                    "could not retrieve the connection hop underlying port/channel '{}'/'{}' on chain '{}'",
-                    opts.packet_src_port_id, opts.packet_src_channel_id, self.src_chain_id
-                ))
-                    .exit();
+        // The vouchers sent are resolved to their trace before anything is sent, as the
+        // source chain would reject the coins of an unknown denomination with an opaque error
+        let mut voucher_traces = Vec::new();
+
+        for coin in &opts.coins {
+            let trace = retry_transient(
+                self.query_retries,
+                QUERY_RETRY_DELAY,
+                || resolve_voucher_trace(&chains.src, &coin.denom),
+                TransferError::is_transient,
+            );
+
+            match trace {
+                Ok(Some(trace)) => voucher_traces.push((coin.denom.clone(), trace)),
+                Ok(None) => {}
+                Err(e) => Output::error(format!("{}", e)).exit(),
             }
-            Some(cid) => cid,
-        };
+        }
 
-        let (conn_end, _) = chains
-            .src
-            .query_connection(
-                QueryConnectionRequest {
-                    connection_id: conn_id.clone(),
-                    height: QueryHeight::Latest,
-                },
-                IncludeProof::No,
-            )
-            .unwrap_or_else(exit_with_unrecoverable_error);
+        let channel_ids = self.src_channel_ids();
 
-        debug!("connection hop underlying the channel: {:?}", conn_end);
+        // Each channel is checked on its own, for the error to point at the faulty one
+        for channel_id in &channel_ids {
+            let checked = retry_transient(
+                self.query_retries,
+                QUERY_RETRY_DELAY,
+                || check_src_channel(&chains, &opts, channel_id),
+                TransferError::is_transient,
+            );
 
-        let (src_chain_client_state, _) = chains
-            .src
-            .query_client_state(
-                QueryClientStateRequest {
-                    client_id: conn_end.client_id().clone(),
-                    height: QueryHeight::Latest,
-                },
-                IncludeProof::No,
-            )
-            .unwrap_or_else(exit_with_unrecoverable_error);
+            if let Err(e) = checked {
+                if channel_ids.len() > 1 {
+                    Output::error(format!("source channel '{}': {}", channel_id, e)).exit();
+                } else {
+                    Output::error(format!("{}", e)).exit();
+                }
+            }
 
-        debug!(
-            "client state underlying the channel: {:?}",
-            src_chain_client_state
-        );
+            for (denom, trace) in &voucher_traces {
+                check_voucher_channel(denom, trace, &opts.packet_src_port_id, channel_id);
+            }
+        }
+
+        // Once the checks pass, the percentage of the balance to send, if any, is resolved
+        if let Some(percent) = self.balance_percentage() {
+            match amount_from_balance(&chains.src, &opts, percent, channel_ids.len()) {
+                Ok(amount) => opts.coins[0].amount = amount,
+                Err(e) => Output::error(e).exit(),
+            }
+        }
+
+        let msgs = match build_multi_channel_transfer_messages(
+            &chains.src,
+            &chains.dst,
+            &opts,
+            &channel_ids,
+        ) {
+            Err(e) => Output::error(format!("{}", e)).exit(),
+            Ok(msgs) => msgs,
+        };
 
-        if src_chain_client_state.chain_id() != self.dst_chain_id {
-            Output::error(
-                format!("the requested port/channel ('{}'/'{}') provides a path from chain '{}' to \
-                 chain '{}' (not to the destination chain '{}'). Bailing due to mismatching arguments.",
-                        opts.packet_src_port_id, opts.packet_src_channel_id,
-                        self.src_chain_id,
-                        src_chain_client_state.chain_id(), self.dst_chain_id)).exit();
+        // Checks pass, print the messages without sending them
+        if self.dry_run {
+            Output::success(TransferDryRun::new(&chains, &opts, msgs)).exit()
         }
 
-        // Checks pass, build and send the tx
+        // Checks pass, send the messages of all the channels together
+        let correlation_id = CorrelationId::new();
         let res: Result<Vec<IbcEvent>, Error> =
-            build_and_send_transfer_messages(&chains.src, &chains.dst, &opts)
+            send_transfer_messages(&chains.src, &msgs, &opts, correlation_id)
                 .map_err(Error::transfer);
 
         match res {
-            Ok(ev) => Output::success(ev).exit(),
+            Ok(ev) => {
+                let txs = query_send_packet_txs(&chains.src, &ev);
+
+                if let Some(path) = &self.receipt_file {
+                    // The receipts are only written for the transfers over a single channel
+                    let receipt_txs: Vec<SendPacketTx> =
+                        txs.iter().map(|(_, tx)| tx.clone()).collect();
+
+                    write_receipt(&chains, &opts, &ev, &receipt_txs, path);
+                }
+
+                let output = TransferOutput::new(correlation_id, &ev, &txs, self.verbose);
+
+                match self.wait_for_ack {
+                    Some(timeout_secs) => wait_for_acks(&chains, &ev, output, timeout_secs),
+                    None => exit_with_output(Output::with_success(), output),
+                }
+            }
             Err(e) => Output::error(format!("{}", e)).exit(),
         }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use ibc::{
-        applications::transfer::Amount,
-        core::ics24_host::identifier::{ChainId, ChannelId, PortId},
+impl Runnable for TxIcs20MsgTransferCmd {
+    fn run(&self) {
+        match self.merged() {
+            Ok(cmd) => cmd.transfer(),
+            Err(e) => Output::error(format!("{}", e)).exit(),
+        }
+    }
+}
+
+/// The error of a required parameter given neither on the command line nor in the file.
+fn missing_parameter(flag: &str, key: &str) -> Error {
+    Error::missing_transfer_parameter(flag.to_string(), key.to_string())
+}
+
+/// Checks that the source channel `channel_id` leads to the destination chain and,
+/// if the transfer has an absolute timeout timestamp, that its packets would not
+/// time out right away.
+fn check_src_channel(
+    chains: &ChainHandlePair,
+    opts: &TransferOptions,
+    channel_id: &ChannelId,
+) -> Result<(), TransferError> {
+    // Double check that channels and chain identifiers match.
+    let (client_id, client_state) = check_transfer_path(
+        &chains.src,
+        &chains.dst.id(),
+        &opts.packet_src_port_id,
+        channel_id,
+    )?;
+
+    // The source chain rejects the packets which timed out according to the client
+    if let Some(timeout_timestamp) = opts.timeout_timestamp {
+        check_timeout_timestamp(&chains.src, &client_id, &client_state, timeout_timestamp)?;
+    }
+
+    Ok(())
+}
+
+/// The amount of each message of the transfer described by `opts`, repeated over `channels`
+/// channels, for the messages to send together the given percentage of the balance of the
+/// sender on the source chain. When the coins sent are also used to pay the fees, the fees
+/// of the transactions are set aside first, so that 100% sends all the spendable balance.
+fn amount_from_balance(
+    src_chain: &impl ChainHandle,
+    opts: &TransferOptions,
+    percent: &str,
+    channels: usize,
+) -> Result<Amount, String> {
+    // Rejected by `clap`, a percentage is only given with `--amount`, thus for a single coin
+    let denom = &opts.coins[0].denom;
+
+    let chain_config = src_chain.config().map_err(|e| format!("{}", e))?;
+
+    let balance = src_chain
+        .query_balance(None, Some(denom.clone()))
+        .map_err(|e| format!("failed to query the balance of '{}': {}", denom, e))?;
+
+    let balance = balance
+        .amount
+        .parse::<Amount>()
+        .map_err(|e| format!("invalid balance '{}{}': {}", balance.amount, denom, e))?;
+
+    let number_msgs = opts.number_msgs * channels;
+
+    let fees = if *denom == chain_config.gas_price.denom {
+        max_fees(&chain_config, opts.fee.as_ref(), number_msgs)?
+    } else {
+        Amount::from(0)
     };
 
-    use super::TxIcs20MsgTransferCmd;
+    let amount = split_percentage(balance, fees, percent, number_msgs)
+        .map_err(|e| format!("{}% of the balance of {}{}: {}", percent, balance, denom, e))?;
 
-    use abscissa_core::clap::Parser;
-    use std::str::FromStr;
+    debug!(
+        "sending {}% of the balance of {}{}, with {}{} set aside for the fees: {}{} in each of {} message(s)",
+        percent, balance, denom, fees, denom, amount, denom, number_msgs
+    );
 
-    #[test]
-    fn test_ft_transfer_required_only() {
+    Ok(amount)
+}
+
+/// The fees paid at most for the transactions submitting `number_msgs` messages on the chain
+/// configured by `chain_config`, either the explicit `fee` or the fee paid for its `max_gas`.
+fn max_fees(
+    chain_config: &ChainConfig,
+    fee: Option<&ExplicitFee>,
+    number_msgs: usize,
+) -> Result<Amount, String> {
+    let max_msg_num = chain_config.max_msg_num.to_usize();
+    let number_txs = (number_msgs + max_msg_num - 1) / max_msg_num;
+
+    let fee_per_tx = match fee {
+        Some(fee) => fee.amount.amount.clone(),
+        None => calculate_fee(max_gas_from_config(chain_config), &chain_config.gas_price).amount,
+    };
+
+    fee_per_tx
+        .parse::<Amount>()
+        .ok()
+        .and_then(|fee| fee.checked_mul(Amount::from(number_txs as u64)))
+        .ok_or_else(|| format!("invalid fee '{}' for each transaction", fee_per_tx))
+}
+
+/// The amount of each of `number_msgs` messages sending together the given percentage of
+/// `balance`, once the `fees` are set aside, rounded down.
+fn split_percentage(
+    balance: Amount,
+    fees: Amount,
+    percent: &str,
+    number_msgs: usize,
+) -> Result<Amount, String> {
+    let spendable = balance
+        .checked_sub(fees)
+        .ok_or_else(|| format!("the balance is lower than the fees of at most {}", fees))?;
+
+    let total = spendable
+        .percentage(percent)
+        .map_err(|e| format!("{}", e))?;
+
+    let amount = total
+        .checked_div(Amount::from(number_msgs as u64))
+        .unwrap_or_else(|| Amount::from(0));
+
+    if amount == Amount::from(0) {
+        return Err(format!(
+            "the amount of each of the {} message(s) would be zero, out of a spendable balance of {}",
+            number_msgs, spendable
+        ));
+    }
+
+    Ok(amount)
+}
+
+/// Queries the transactions which sent the packets of the `SendPacket` events among `events`,
+/// on each of their source channels, along with the channel. The transfer succeeded regardless,
+/// so failures are only logged, and the transactions which could not be queried are left out.
+fn query_send_packet_txs(
+    src_chain: &impl ChainHandle,
+    events: &[IbcEvent],
+) -> Vec<(ChannelId, SendPacketTx)> {
+    let mut sequences: Vec<((PortId, ChannelId), Vec<Sequence>)> = Vec::new();
+
+    for event in events {
+        if let IbcEvent::SendPacket(send_packet) = event {
+            let packet = &send_packet.packet;
+            let channel = (packet.source_port.clone(), packet.source_channel.clone());
+
+            match sequences.iter_mut().find(|(c, _)| *c == channel) {
+                Some((_, channel_sequences)) => channel_sequences.push(packet.sequence),
+                None => sequences.push((channel, vec![packet.sequence])),
+            }
+        }
+    }
+
+    sequences
+        .into_iter()
+        .flat_map(|((port_id, channel_id), sequences)| {
+            src_chain
+                .query_send_packet_txs(QuerySendPacketTxsRequest {
+                    port_id,
+                    channel_id: channel_id.clone(),
+                    sequences,
+                })
+                .map(|txs| {
+                    txs.into_iter()
+                        .map(|tx| (channel_id.clone(), tx))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_else(|e| {
+                    warn!(
+                        "failed to query the transactions which sent the packets: {}",
+                        e
+                    );
+                    vec![]
+                })
+        })
+        .collect()
+}
+
+/// Waits at most `timeout_secs` for the packets sent by the transfer, found in its
+/// `events`, to be acknowledged or to time out, and exits with the `output` of the
+/// transfer along with their status.
+/// Exits with an error if some packets are still pending at the end of the wait.
+fn wait_for_acks(
+    chains: &ChainHandlePair,
+    events: &[IbcEvent],
+    mut output: TransferOutput,
+    timeout_secs: u64,
+) -> ! {
+    let packets = wait_for_transfer_acks(&chains.dst, events, Duration::from_secs(timeout_secs));
+
+    let pending = packets
+        .iter()
+        .filter(|packet| packet.status == PacketStatus::Pending)
+        .map(|packet| packet.sequence.to_string())
+        .collect::<Vec<_>>();
+
+    output.set_outcomes(packets);
+
+    if pending.is_empty() {
+        exit_with_output(Output::with_success(), output)
+    } else {
+        error!(
+            "the packets with sequences {} were neither acknowledged nor timed out on chain {} after {}s",
+            pending.join(", "),
+            chains.dst.id(),
+            timeout_secs
+        );
+
+        exit_with_output(Output::with_error(), output)
+    }
+}
+
+/// Exits with the `output` of the transfer as the result of `status`,
+/// in JSON with `--json`, and as lines of text otherwise.
+fn exit_with_output(status: Output, output: TransferOutput) -> ! {
+    if json() {
+        status.with_result(output).exit()
+    } else {
+        status.with_msg(output).exit()
+    }
+}
+
+/// The output of `tx ft-transfer --dry-run`.
+#[derive(Debug, Serialize)]
+struct TransferDryRun {
+    msgs: Vec<DryRunMessage<RawMsgTransfer>>,
+    simulation: Option<TransferSimulation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    simulation_error: Option<String>,
+}
+
+impl TransferDryRun {
+    /// The messages built for the transfer described by `opts`, along with their simulation.
+    /// The messages could be built regardless of the outcome of the simulation, e.g. to be
+    /// submitted later by an external signer, so its failure is only reported.
+    fn new(chains: &ChainHandlePair, opts: &TransferOptions, msgs: Vec<MsgTransfer>) -> Self {
+        let (simulation, simulation_error) =
+            match simulate_transfer_messages(&chains.src, &msgs, opts) {
+                Ok(simulation) => (Some(simulation), None),
+                Err(e) => {
+                    warn!("failed to simulate the transfer messages: {}", e);
+                    (None, Some(e.to_string()))
+                }
+            };
+
+        Self {
+            msgs: msgs.into_iter().map(DryRunMessage::new).collect(),
+            simulation,
+            simulation_error,
+        }
+    }
+}
+
+/// Writes the receipt of the transfer sent with `opts` to the file at `path`.
+/// The transfer succeeded regardless, so failures are only logged.
+fn write_receipt(
+    chains: &ChainHandlePair,
+    opts: &TransferOptions,
+    events: &[IbcEvent],
+    txs: &[SendPacketTx],
+    path: &Path,
+) {
+    // Rejected by `validate_options`, the receipts only describe the transfer of a single coin
+    let denom = match opts.coins.as_slice() {
+        [coin] => &coin.denom,
+        _ => {
+            warn!(
+                "several coins were sent, not writing a transfer receipt to '{}'",
+                path.display()
+            );
+            return;
+        }
+    };
+
+    let src_trace = match denom.strip_prefix(&format!("{}/", IBC_DENOM_PREFIX)) {
+        Some(hash) => chains
+            .src
+            .query_denom_trace(hash.to_string())
+            .map_err(|e| {
+                warn!(
+                    "failed to query the trace of denomination '{}': {}",
+                    denom, e
+                )
+            })
+            .ok(),
+        None => Some(DenomTrace {
+            path: String::new(),
+            base_denom: denom.clone(),
+        }),
+    };
+
+    // The format of the data of the packets depends on the version of the channel
+    let version = chains
+        .src
+        .query_channel(
+            QueryChannelRequest {
+                port_id: opts.packet_src_port_id.clone(),
+                channel_id: opts.packet_src_channel_id.clone(),
+                height: QueryHeight::Latest,
+            },
+            IncludeProof::No,
+        )
+        .map(|(channel, _)| channel.version().clone())
+        .unwrap_or_else(|e| {
+            warn!("failed to query the version of the channel: {}", e);
+            Version::ics20()
+        });
+
+    let receipt = TransferReceipt::new(
+        chains.src.id(),
+        chains.dst.id(),
+        &version,
+        opts,
+        src_trace.as_ref(),
+        events,
+        txs,
+    );
+
+    let result = match receipt {
+        Some(mut receipt) => {
+            receipt.display_amount = display_amount(&chains.src, denom, &receipt.amount);
+            receipt.write(path)
+        }
+        None => {
+            warn!(
+                "no packet was sent, not writing a transfer receipt to '{}'",
+                path.display()
+            );
+            return;
+        }
+    };
+
+    if let Err(e) = result {
+        warn!(
+            "failed to write the transfer receipt to '{}': {}",
+            path.display(),
+            e
+        );
+    }
+}
+
+/// The `amount` of coins of the given denomination on the source chain
+/// in the display unit of the denomination, if known.
+fn display_amount(src_chain: &impl ChainHandle, denom: &str, amount: &str) -> Option<String> {
+    let config = src_chain
+        .config()
+        .map_err(|e| warn!("failed to get the configuration of the source chain: {}", e))
+        .ok()?;
+
+    default_resolver(src_chain, &config)
+        .resolve(denom)?
+        .format(amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use ibc::{
+        applications::transfer::{Amount, Coin},
+        core::ics24_host::identifier::{ChainId, ChannelId, PortId},
+    };
+
+    use super::{
+        explicit_fee, max_fees, parse_amount, parse_coin, parse_fee, parse_timeout_timestamp,
+        split_percentage, DecimalAmount, TxIcs20MsgTransferCmd,
+    };
+
+    use ibc_proto::cosmos::base::v1beta1::Coin as RawCoin;
+    use ibc_relayer::chain::cosmos::types::gas::ExplicitFee;
+    use ibc_relayer::config::types::MaxMsgNum;
+    use ibc_relayer::config::{load, Config, GasPrice};
+
+    fn config() -> Config {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../relayer/tests/config/fixtures/relayer_conf_example.toml"
+        );
+
+        load(path).expect("could not parse config")
+    }
+
+    use abscissa_core::clap::Parser;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_ft_transfer_required_only() {
         assert_eq!(
             TxIcs20MsgTransferCmd {
-                dst_chain_id: ChainId::from_string("chain_receiver"),
-                src_chain_id: ChainId::from_string("chain_sender"),
-                src_port_id: PortId::from_str("port_sender").unwrap(),
-                src_channel_id: ChannelId::from_str("channel_sender").unwrap(),
-                amount: Amount::from(42),
-                timeout_height_offset: 0,
-                timeout_seconds: 0,
+                dst_chain_id: Some(ChainId::from_string("chain_receiver")),
+                src_chain_id: Some(ChainId::from_string("chain_sender")),
+                src_port_id: Some(PortId::from_str("port_sender").unwrap()),
+                src_channel_id: Some(ChannelId::from_str("channel_sender").unwrap()),
+                src_channels: vec![],
+                amount: Some(DecimalAmount {
+                    amount: "42".to_owned(),
+                    denom: None,
+                    percentage: false,
+                }),
+                coins: vec![],
+                timeout_height_offset: None,
+                timeout_seconds: None,
+                timeout_timestamp: None,
                 receiver: None,
-                denom: "samoleans".to_owned(),
+                allow_unchecked_receiver: false,
+                denom: None,
+                decimals: None,
                 number_msgs: None,
-                key_name: None
+                key_name: None,
+                force: false,
+                fee: None,
+                gas: None,
+                fee_granter: None,
+                receipt_file: None,
+                memo: None,
+                dry_run: false,
+                wait_for_ack: None,
+                verbose: false,
+                query_retries: 0,
+                from_file: None,
+                src_rpc: None,
+                src_grpc: None,
+                src_account_prefix: None,
+                src_gas_price: None,
+                src_key_file: None,
             },
             TxIcs20MsgTransferCmd::parse_from(&[
                 "test",
@@ -325,17 +1345,42 @@ mod tests {
     fn test_ft_transfer_aliases() {
         assert_eq!(
             TxIcs20MsgTransferCmd {
-                dst_chain_id: ChainId::from_string("chain_receiver"),
-                src_chain_id: ChainId::from_string("chain_sender"),
-                src_port_id: PortId::from_str("port_sender").unwrap(),
-                src_channel_id: ChannelId::from_str("channel_sender").unwrap(),
-                amount: Amount::from(42),
-                timeout_height_offset: 0,
-                timeout_seconds: 0,
+                dst_chain_id: Some(ChainId::from_string("chain_receiver")),
+                src_chain_id: Some(ChainId::from_string("chain_sender")),
+                src_port_id: Some(PortId::from_str("port_sender").unwrap()),
+                src_channel_id: Some(ChannelId::from_str("channel_sender").unwrap()),
+                src_channels: vec![],
+                amount: Some(DecimalAmount {
+                    amount: "42".to_owned(),
+                    denom: None,
+                    percentage: false,
+                }),
+                coins: vec![],
+                timeout_height_offset: None,
+                timeout_seconds: None,
+                timeout_timestamp: None,
                 receiver: None,
-                denom: "samoleans".to_owned(),
+                allow_unchecked_receiver: false,
+                denom: None,
+                decimals: None,
                 number_msgs: None,
-                key_name: None
+                key_name: None,
+                force: false,
+                fee: None,
+                gas: None,
+                fee_granter: None,
+                receipt_file: None,
+                memo: None,
+                dry_run: false,
+                wait_for_ack: None,
+                verbose: false,
+                query_retries: 0,
+                from_file: None,
+                src_rpc: None,
+                src_grpc: None,
+                src_account_prefix: None,
+                src_gas_price: None,
+                src_key_file: None,
             },
             TxIcs20MsgTransferCmd::parse_from(&[
                 "test",
@@ -357,17 +1402,42 @@ mod tests {
     fn test_ft_transfer_denom() {
         assert_eq!(
             TxIcs20MsgTransferCmd {
-                dst_chain_id: ChainId::from_string("chain_receiver"),
-                src_chain_id: ChainId::from_string("chain_sender"),
-                src_port_id: PortId::from_str("port_sender").unwrap(),
-                src_channel_id: ChannelId::from_str("channel_sender").unwrap(),
-                amount: Amount::from(42),
-                timeout_height_offset: 0,
-                timeout_seconds: 0,
+                dst_chain_id: Some(ChainId::from_string("chain_receiver")),
+                src_chain_id: Some(ChainId::from_string("chain_sender")),
+                src_port_id: Some(PortId::from_str("port_sender").unwrap()),
+                src_channel_id: Some(ChannelId::from_str("channel_sender").unwrap()),
+                src_channels: vec![],
+                amount: Some(DecimalAmount {
+                    amount: "42".to_owned(),
+                    denom: None,
+                    percentage: false,
+                }),
+                coins: vec![],
+                timeout_height_offset: None,
+                timeout_seconds: None,
+                timeout_timestamp: None,
                 receiver: None,
-                denom: "my_denom".to_owned(),
+                allow_unchecked_receiver: false,
+                denom: Some("my_denom".to_owned()),
+                decimals: None,
                 number_msgs: None,
-                key_name: None
+                key_name: None,
+                force: false,
+                fee: None,
+                gas: None,
+                fee_granter: None,
+                receipt_file: None,
+                memo: None,
+                dry_run: false,
+                wait_for_ack: None,
+                verbose: false,
+                query_retries: 0,
+                from_file: None,
+                src_rpc: None,
+                src_grpc: None,
+                src_account_prefix: None,
+                src_gas_price: None,
+                src_key_file: None,
             },
             TxIcs20MsgTransferCmd::parse_from(&[
                 "test",
@@ -391,17 +1461,42 @@ mod tests {
     fn test_ft_transfer_key_name() {
         assert_eq!(
             TxIcs20MsgTransferCmd {
-                dst_chain_id: ChainId::from_string("chain_receiver"),
-                src_chain_id: ChainId::from_string("chain_sender"),
-                src_port_id: PortId::from_str("port_sender").unwrap(),
-                src_channel_id: ChannelId::from_str("channel_sender").unwrap(),
-                amount: Amount::from(42),
-                timeout_height_offset: 0,
-                timeout_seconds: 0,
+                dst_chain_id: Some(ChainId::from_string("chain_receiver")),
+                src_chain_id: Some(ChainId::from_string("chain_sender")),
+                src_port_id: Some(PortId::from_str("port_sender").unwrap()),
+                src_channel_id: Some(ChannelId::from_str("channel_sender").unwrap()),
+                src_channels: vec![],
+                amount: Some(DecimalAmount {
+                    amount: "42".to_owned(),
+                    denom: None,
+                    percentage: false,
+                }),
+                coins: vec![],
+                timeout_height_offset: None,
+                timeout_seconds: None,
+                timeout_timestamp: None,
                 receiver: None,
-                denom: "samoleans".to_owned(),
+                allow_unchecked_receiver: false,
+                denom: None,
+                decimals: None,
                 number_msgs: None,
-                key_name: Some("key_name".to_owned())
+                key_name: Some("key_name".to_owned()),
+                force: false,
+                fee: None,
+                gas: None,
+                fee_granter: None,
+                receipt_file: None,
+                memo: None,
+                dry_run: false,
+                wait_for_ack: None,
+                verbose: false,
+                query_retries: 0,
+                from_file: None,
+                src_rpc: None,
+                src_grpc: None,
+                src_account_prefix: None,
+                src_gas_price: None,
+                src_key_file: None,
             },
             TxIcs20MsgTransferCmd::parse_from(&[
                 "test",
@@ -425,17 +1520,42 @@ mod tests {
     fn test_ft_transfer_number_msgs() {
         assert_eq!(
             TxIcs20MsgTransferCmd {
-                dst_chain_id: ChainId::from_string("chain_receiver"),
-                src_chain_id: ChainId::from_string("chain_sender"),
-                src_port_id: PortId::from_str("port_sender").unwrap(),
-                src_channel_id: ChannelId::from_str("channel_sender").unwrap(),
-                amount: Amount::from(42),
-                timeout_height_offset: 0,
-                timeout_seconds: 0,
+                dst_chain_id: Some(ChainId::from_string("chain_receiver")),
+                src_chain_id: Some(ChainId::from_string("chain_sender")),
+                src_port_id: Some(PortId::from_str("port_sender").unwrap()),
+                src_channel_id: Some(ChannelId::from_str("channel_sender").unwrap()),
+                src_channels: vec![],
+                amount: Some(DecimalAmount {
+                    amount: "42".to_owned(),
+                    denom: None,
+                    percentage: false,
+                }),
+                coins: vec![],
+                timeout_height_offset: None,
+                timeout_seconds: None,
+                timeout_timestamp: None,
                 receiver: None,
-                denom: "samoleans".to_owned(),
+                allow_unchecked_receiver: false,
+                denom: None,
+                decimals: None,
                 number_msgs: Some(21),
-                key_name: None
+                key_name: None,
+                force: false,
+                fee: None,
+                gas: None,
+                fee_granter: None,
+                receipt_file: None,
+                memo: None,
+                dry_run: false,
+                wait_for_ack: None,
+                verbose: false,
+                query_retries: 0,
+                from_file: None,
+                src_rpc: None,
+                src_grpc: None,
+                src_account_prefix: None,
+                src_gas_price: None,
+                src_key_file: None,
             },
             TxIcs20MsgTransferCmd::parse_from(&[
                 "test",
@@ -459,17 +1579,42 @@ mod tests {
     fn test_ft_transfer_receiver() {
         assert_eq!(
             TxIcs20MsgTransferCmd {
-                dst_chain_id: ChainId::from_string("chain_receiver"),
-                src_chain_id: ChainId::from_string("chain_sender"),
-                src_port_id: PortId::from_str("port_sender").unwrap(),
-                src_channel_id: ChannelId::from_str("channel_sender").unwrap(),
-                amount: Amount::from(42),
-                timeout_height_offset: 0,
-                timeout_seconds: 0,
+                dst_chain_id: Some(ChainId::from_string("chain_receiver")),
+                src_chain_id: Some(ChainId::from_string("chain_sender")),
+                src_port_id: Some(PortId::from_str("port_sender").unwrap()),
+                src_channel_id: Some(ChannelId::from_str("channel_sender").unwrap()),
+                src_channels: vec![],
+                amount: Some(DecimalAmount {
+                    amount: "42".to_owned(),
+                    denom: None,
+                    percentage: false,
+                }),
+                coins: vec![],
+                timeout_height_offset: None,
+                timeout_seconds: None,
+                timeout_timestamp: None,
                 receiver: Some("receiver_addr".to_owned()),
-                denom: "samoleans".to_owned(),
+                allow_unchecked_receiver: false,
+                denom: None,
+                decimals: None,
                 number_msgs: None,
-                key_name: None
+                key_name: None,
+                force: false,
+                fee: None,
+                gas: None,
+                fee_granter: None,
+                receipt_file: None,
+                memo: None,
+                dry_run: false,
+                wait_for_ack: None,
+                verbose: false,
+                query_retries: 0,
+                from_file: None,
+                src_rpc: None,
+                src_grpc: None,
+                src_account_prefix: None,
+                src_gas_price: None,
+                src_key_file: None,
             },
             TxIcs20MsgTransferCmd::parse_from(&[
                 "test",
@@ -493,17 +1638,42 @@ mod tests {
     fn test_ft_transfer_timeout_height_offset() {
         assert_eq!(
             TxIcs20MsgTransferCmd {
-                dst_chain_id: ChainId::from_string("chain_receiver"),
-                src_chain_id: ChainId::from_string("chain_sender"),
-                src_port_id: PortId::from_str("port_sender").unwrap(),
-                src_channel_id: ChannelId::from_str("channel_sender").unwrap(),
-                amount: Amount::from(42),
-                timeout_height_offset: 21,
-                timeout_seconds: 0,
+                dst_chain_id: Some(ChainId::from_string("chain_receiver")),
+                src_chain_id: Some(ChainId::from_string("chain_sender")),
+                src_port_id: Some(PortId::from_str("port_sender").unwrap()),
+                src_channel_id: Some(ChannelId::from_str("channel_sender").unwrap()),
+                src_channels: vec![],
+                amount: Some(DecimalAmount {
+                    amount: "42".to_owned(),
+                    denom: None,
+                    percentage: false,
+                }),
+                coins: vec![],
+                timeout_height_offset: Some(21),
+                timeout_seconds: None,
+                timeout_timestamp: None,
                 receiver: None,
-                denom: "samoleans".to_owned(),
+                allow_unchecked_receiver: false,
+                denom: None,
+                decimals: None,
                 number_msgs: None,
-                key_name: None
+                key_name: None,
+                force: false,
+                fee: None,
+                gas: None,
+                fee_granter: None,
+                receipt_file: None,
+                memo: None,
+                dry_run: false,
+                wait_for_ack: None,
+                verbose: false,
+                query_retries: 0,
+                from_file: None,
+                src_rpc: None,
+                src_grpc: None,
+                src_account_prefix: None,
+                src_gas_price: None,
+                src_key_file: None,
             },
             TxIcs20MsgTransferCmd::parse_from(&[
                 "test",
@@ -527,17 +1697,42 @@ mod tests {
     fn test_ft_transfer_timeout_seconds() {
         assert_eq!(
             TxIcs20MsgTransferCmd {
-                dst_chain_id: ChainId::from_string("chain_receiver"),
-                src_chain_id: ChainId::from_string("chain_sender"),
-                src_port_id: PortId::from_str("port_sender").unwrap(),
-                src_channel_id: ChannelId::from_str("channel_sender").unwrap(),
-                amount: Amount::from(42),
-                timeout_height_offset: 0,
-                timeout_seconds: 21,
+                dst_chain_id: Some(ChainId::from_string("chain_receiver")),
+                src_chain_id: Some(ChainId::from_string("chain_sender")),
+                src_port_id: Some(PortId::from_str("port_sender").unwrap()),
+                src_channel_id: Some(ChannelId::from_str("channel_sender").unwrap()),
+                src_channels: vec![],
+                amount: Some(DecimalAmount {
+                    amount: "42".to_owned(),
+                    denom: None,
+                    percentage: false,
+                }),
+                coins: vec![],
+                timeout_height_offset: None,
+                timeout_seconds: Some(21),
+                timeout_timestamp: None,
                 receiver: None,
-                denom: "samoleans".to_owned(),
+                allow_unchecked_receiver: false,
+                denom: None,
+                decimals: None,
                 number_msgs: None,
-                key_name: None
+                key_name: None,
+                force: false,
+                fee: None,
+                gas: None,
+                fee_granter: None,
+                receipt_file: None,
+                memo: None,
+                dry_run: false,
+                wait_for_ack: None,
+                verbose: false,
+                query_retries: 0,
+                from_file: None,
+                src_rpc: None,
+                src_grpc: None,
+                src_account_prefix: None,
+                src_gas_price: None,
+                src_key_file: None,
             },
             TxIcs20MsgTransferCmd::parse_from(&[
                 "test",
@@ -557,6 +1752,866 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_ft_transfer_force() {
+        let cmd = TxIcs20MsgTransferCmd::parse_from(&[
+            "test",
+            "--dst-chain",
+            "chain_receiver",
+            "--src-chain",
+            "chain_sender",
+            "--src-port",
+            "port_sender",
+            "--src-channel",
+            "channel_sender",
+            "--amount",
+            "42",
+            "--force",
+        ]);
+
+        assert!(cmd.force);
+        assert_eq!(cmd.timeout_height_offset, None);
+        assert_eq!(cmd.timeout_seconds, None);
+    }
+
+    #[test]
+    fn test_ft_transfer_fee() {
+        let cmd = TxIcs20MsgTransferCmd::parse_from(&[
+            "test",
+            "--dst-chain",
+            "chain_receiver",
+            "--src-chain",
+            "chain_sender",
+            "--src-port",
+            "port_sender",
+            "--src-channel",
+            "channel_sender",
+            "--amount",
+            "42",
+            "--fee",
+            "5000uatom",
+            "--gas",
+            "250000",
+        ]);
+
+        assert_eq!(cmd.fee, Some("5000uatom".to_owned()));
+        assert_eq!(cmd.gas, Some(250000));
+    }
+
+    #[test]
+    fn test_ft_transfer_receipt_file() {
+        let cmd = TxIcs20MsgTransferCmd::parse_from(&[
+            "test",
+            "--dst-chain",
+            "chain_receiver",
+            "--src-chain",
+            "chain_sender",
+            "--src-port",
+            "port_sender",
+            "--src-channel",
+            "channel_sender",
+            "--amount",
+            "42",
+            "--receipt-file",
+            "receipts/transfer.json",
+        ]);
+
+        assert_eq!(
+            cmd.receipt_file,
+            Some(PathBuf::from("receipts/transfer.json"))
+        );
+    }
+
+    #[test]
+    fn test_ft_transfer_dry_run() {
+        let args = [
+            "test",
+            "--dst-chain",
+            "chain_receiver",
+            "--src-chain",
+            "chain_sender",
+            "--src-port",
+            "port_sender",
+            "--src-channel",
+            "channel_sender",
+            "--amount",
+            "42",
+            "--dry-run",
+        ];
+
+        assert!(TxIcs20MsgTransferCmd::parse_from(&args).dry_run);
+
+        let with_receipt_file = [&args[..], &["--receipt-file", "receipts/transfer.json"]].concat();
+        assert!(TxIcs20MsgTransferCmd::try_parse_from(&with_receipt_file).is_err());
+    }
+
+    #[test]
+    fn test_ft_transfer_wait_for_ack() {
+        let args = [
+            "test",
+            "--dst-chain",
+            "chain_receiver",
+            "--src-chain",
+            "chain_sender",
+            "--src-port",
+            "port_sender",
+            "--src-channel",
+            "channel_sender",
+            "--amount",
+            "42",
+            "--wait-for-ack",
+            "60",
+        ];
+
+        assert_eq!(
+            TxIcs20MsgTransferCmd::parse_from(&args).wait_for_ack,
+            Some(60)
+        );
+
+        let with_dry_run = [&args[..], &["--dry-run"]].concat();
+        assert!(TxIcs20MsgTransferCmd::try_parse_from(&with_dry_run).is_err());
+    }
+
+    #[test]
+    fn test_ft_transfer_verbose() {
+        let args = [
+            "test",
+            "--dst-chain",
+            "chain_receiver",
+            "--src-chain",
+            "chain_sender",
+            "--src-port",
+            "port_sender",
+            "--src-channel",
+            "channel_sender",
+            "--amount",
+            "42",
+            "--verbose",
+        ];
+
+        assert!(TxIcs20MsgTransferCmd::parse_from(&args).verbose);
+
+        let with_dry_run = [&args[..], &["--dry-run"]].concat();
+        assert!(TxIcs20MsgTransferCmd::try_parse_from(&with_dry_run).is_err());
+    }
+
+    #[test]
+    fn test_ft_transfer_query_retries() {
+        let args = [
+            "test",
+            "--dst-chain",
+            "chain_receiver",
+            "--src-chain",
+            "chain_sender",
+            "--src-port",
+            "port_sender",
+            "--src-channel",
+            "channel_sender",
+            "--amount",
+            "42",
+        ];
+
+        assert_eq!(TxIcs20MsgTransferCmd::parse_from(&args).query_retries, 0);
+
+        let with_retries = [&args[..], &["--query-retries", "3"]].concat();
+        assert_eq!(
+            TxIcs20MsgTransferCmd::parse_from(&with_retries).query_retries,
+            3
+        );
+    }
+
+    #[test]
+    fn test_ft_transfer_src_channels() {
+        let args = [
+            "test",
+            "--dst-chain",
+            "chain_receiver",
+            "--src-chain",
+            "chain_sender",
+            "--src-port",
+            "port_sender",
+            "--amount",
+            "42",
+            "--src-channels",
+            "channel-0,channel-3",
+            "--src-channels",
+            "channel-7",
+            "--src-channels",
+            "channel-0",
+        ];
+
+        let cmd = TxIcs20MsgTransferCmd::parse_from(&args);
+
+        assert_eq!(cmd.src_channel_id, None);
+        assert_eq!(
+            cmd.src_channel_ids(),
+            vec![ChannelId::new(0), ChannelId::new(3), ChannelId::new(7)]
+        );
+
+        let with_src_channel = [&args[..], &["--src-channel", "channel-1"]].concat();
+        assert!(TxIcs20MsgTransferCmd::try_parse_from(&with_src_channel).is_err());
+
+        let with_receipt_file = [&args[..], &["--receipt-file", "receipts/transfer.json"]].concat();
+        assert!(TxIcs20MsgTransferCmd::try_parse_from(&with_receipt_file).is_err());
+    }
+
+    #[test]
+    fn test_ft_transfer_memo() {
+        let cmd = TxIcs20MsgTransferCmd::parse_from(&[
+            "test",
+            "--dst-chain",
+            "chain_receiver",
+            "--src-chain",
+            "chain_sender",
+            "--src-port",
+            "port_sender",
+            "--src-channel",
+            "channel_sender",
+            "--amount",
+            "42",
+            "--memo",
+            "{\"wasm\":{}}",
+        ]);
+
+        assert_eq!(cmd.memo, Some("{\"wasm\":{}}".to_owned()));
+    }
+
+    #[test]
+    fn test_ft_transfer_allow_unchecked_receiver() {
+        let cmd = TxIcs20MsgTransferCmd::parse_from(&[
+            "test",
+            "--dst-chain",
+            "chain_receiver",
+            "--src-chain",
+            "chain_sender",
+            "--src-port",
+            "port_sender",
+            "--src-channel",
+            "channel_sender",
+            "--amount",
+            "42",
+            "--receiver",
+            "0x8f1a3b5c",
+            "--allow-unchecked-receiver",
+        ]);
+
+        assert!(cmd.allow_unchecked_receiver);
+
+        // Only meaningful along with a receiver
+        assert!(TxIcs20MsgTransferCmd::try_parse_from(&[
+            "test",
+            "--dst-chain",
+            "chain_receiver",
+            "--src-chain",
+            "chain_sender",
+            "--src-port",
+            "port_sender",
+            "--src-channel",
+            "channel_sender",
+            "--amount",
+            "42",
+            "--allow-unchecked-receiver",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_ft_transfer_force_unchecked_receiver() {
+        let args = [
+            "test",
+            "--dst-chain",
+            "chain_B",
+            "--src-chain",
+            "chain_A",
+            "--src-port",
+            "transfer",
+            "--src-channel",
+            "channel-0",
+            "--timeout-seconds",
+            "60",
+            "--amount",
+            "42",
+            "--receiver",
+            "0x8f1a3b5c",
+        ];
+
+        let err = TxIcs20MsgTransferCmd::parse_from(&args)
+            .validate_options(&config())
+            .unwrap_err();
+        assert!(err.to_string().contains("not a valid bech32 address"));
+
+        for flag in ["--force", "--allow-unchecked-receiver"] {
+            let opts = TxIcs20MsgTransferCmd::parse_from(&[&args[..], &[flag]].concat())
+                .validate_options(&config())
+                .unwrap();
+            assert_eq!(opts.receiver, Some("0x8f1a3b5c".to_owned()));
+        }
+    }
+
+    #[test]
+    fn test_ft_transfer_coins() {
+        let cmd = TxIcs20MsgTransferCmd::parse_from(&[
+            "test",
+            "--dst-chain",
+            "chain_receiver",
+            "--src-chain",
+            "chain_sender",
+            "--src-port",
+            "port_sender",
+            "--src-channel",
+            "channel_sender",
+            "--coin",
+            "100stake",
+            "--coin",
+            "25samoleans,5uatom",
+        ]);
+
+        assert_eq!(cmd.amount, None);
+        assert_eq!(
+            cmd.coins,
+            vec![
+                parse_coin("100stake").unwrap(),
+                parse_coin("25samoleans").unwrap(),
+                parse_coin("5uatom").unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ft_transfer_coins_and_amount() {
+        assert!(TxIcs20MsgTransferCmd::try_parse_from(&[
+            "test",
+            "--dst-chain",
+            "chain_receiver",
+            "--src-chain",
+            "chain_sender",
+            "--src-port",
+            "port_sender",
+            "--src-channel",
+            "channel_sender",
+            "--amount",
+            "42",
+            "--coin",
+            "100stake"
+        ])
+        .is_err())
+    }
+
+    #[test]
+    fn test_ft_transfer_ad_hoc_src_chain() {
+        let cmd = TxIcs20MsgTransferCmd::parse_from(&[
+            "test",
+            "--dst-chain",
+            "chain_receiver",
+            "--src-chain",
+            "devnet-1",
+            "--src-port",
+            "transfer",
+            "--src-channel",
+            "channel-0",
+            "--amount",
+            "42",
+            "--src-rpc",
+            "http://127.0.0.1:26657",
+            "--src-grpc",
+            "http://127.0.0.1:9090",
+            "--src-account-prefix",
+            "cosmos",
+            "--src-gas-price",
+            "0.01uatom",
+            "--src-key-file",
+            "key.json",
+        ]);
+
+        let src_chain = cmd.ad_hoc_src_chain().unwrap().unwrap();
+
+        assert_eq!(src_chain.config.id, ChainId::from_string("devnet-1"));
+        assert_eq!(src_chain.config.account_prefix, "cosmos");
+        assert_eq!(
+            src_chain.config.gas_price,
+            GasPrice::new(0.01, "uatom".to_owned())
+        );
+        assert_eq!(src_chain.key_file, PathBuf::from("key.json"));
+    }
+
+    #[test]
+    fn test_ft_transfer_ad_hoc_src_chain_missing_flags() {
+        let cmd = TxIcs20MsgTransferCmd::parse_from(&[
+            "test",
+            "--dst-chain",
+            "chain_receiver",
+            "--src-chain",
+            "devnet-1",
+            "--src-port",
+            "transfer",
+            "--src-channel",
+            "channel-0",
+            "--amount",
+            "42",
+            "--src-rpc",
+            "http://127.0.0.1:26657",
+        ]);
+
+        assert!(cmd.ad_hoc_src_chain().is_err());
+    }
+
+    #[test]
+    fn test_ft_transfer_invalid_src_gas_price() {
+        assert!(TxIcs20MsgTransferCmd::try_parse_from(&[
+            "test",
+            "--dst-chain",
+            "chain_receiver",
+            "--src-chain",
+            "devnet-1",
+            "--src-port",
+            "transfer",
+            "--src-channel",
+            "channel-0",
+            "--amount",
+            "42",
+            "--src-gas-price",
+            "uatom"
+        ])
+        .is_err())
+    }
+
+    #[test]
+    fn test_ft_transfer_gas_without_fee() {
+        assert!(TxIcs20MsgTransferCmd::try_parse_from(&[
+            "test",
+            "--dst-chain",
+            "chain_receiver",
+            "--src-chain",
+            "chain_sender",
+            "--src-port",
+            "port_sender",
+            "--src-channel",
+            "channel_sender",
+            "--amount",
+            "42",
+            "--gas",
+            "250000"
+        ])
+        .is_err())
+    }
+
+    #[test]
+    fn test_parse_fee() {
+        assert_eq!(
+            parse_fee("5000uatom"),
+            Ok(RawCoin {
+                denom: "uatom".to_owned(),
+                amount: "5000".to_owned()
+            })
+        );
+
+        assert_eq!(
+            parse_fee("12ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2")
+                .map(|coin| coin.denom),
+            Ok("ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2".to_owned())
+        );
+
+        for fee in ["", "5000", "uatom", "-5uatom", "5.5uatom"] {
+            assert!(parse_fee(fee).is_err(), "fee '{}' should be rejected", fee);
+        }
+    }
+
+    #[test]
+    fn test_ft_transfer_amount_with_denom() {
+        let cmd = TxIcs20MsgTransferCmd::parse_from(&[
+            "test",
+            "--dst-chain",
+            "chain_B",
+            "--src-chain",
+            "chain_A",
+            "--src-port",
+            "transfer",
+            "--src-channel",
+            "channel-0",
+            "--timeout-seconds",
+            "60",
+            "--amount",
+            "42my_denom",
+        ]);
+
+        assert_eq!(
+            cmd.amount,
+            Some(DecimalAmount {
+                amount: "42".to_owned(),
+                denom: Some("my_denom".to_owned()),
+                percentage: false,
+            })
+        );
+        assert_eq!(cmd.denom, None);
+
+        let opts = cmd.validate_options(&config()).unwrap();
+
+        assert_eq!(
+            opts.coins,
+            vec![Coin {
+                denom: "my_denom".to_owned(),
+                amount: Amount::from(42),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_ft_transfer_fee_granter() {
+        let args = [
+            "test",
+            "--dst-chain",
+            "chain_B",
+            "--src-chain",
+            "chain_A",
+            "--src-port",
+            "transfer",
+            "--src-channel",
+            "channel-0",
+            "--timeout-seconds",
+            "60",
+            "--amount",
+            "42",
+            "--fee-granter",
+        ];
+
+        let fee_granter = "cosmos1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5lzv7xu";
+
+        let cmd = TxIcs20MsgTransferCmd::parse_from(&[&args[..], &[fee_granter]].concat());
+        assert_eq!(cmd.fee_granter, Some(fee_granter.to_owned()));
+
+        let opts = cmd.validate_options(&config()).unwrap();
+        assert_eq!(opts.fee_granter, Some(fee_granter.to_owned()));
+
+        // A typo breaks the checksum
+        let mistyped = fee_granter.replace("xu", "xv");
+        let err = TxIcs20MsgTransferCmd::parse_from(&[&args[..], &[mistyped.as_str()]].concat())
+            .validate_options(&config())
+            .unwrap_err();
+        assert!(err.to_string().contains("not a valid bech32 address"));
+    }
+
+    #[test]
+    fn test_ft_transfer_amount_with_denom_and_denom() {
+        let cmd = TxIcs20MsgTransferCmd::parse_from(&[
+            "test",
+            "--dst-chain",
+            "chain_B",
+            "--src-chain",
+            "chain_A",
+            "--src-port",
+            "transfer",
+            "--src-channel",
+            "channel-0",
+            "--timeout-seconds",
+            "60",
+            "--amount",
+            "42my_denom",
+            "--denom",
+            "other_denom",
+        ]);
+
+        let err = cmd.validate_options(&config()).unwrap_err();
+
+        assert!(err
+            .to_string()
+            .contains("conflicts with `--denom other_denom`"));
+    }
+
+    #[test]
+    fn test_ft_transfer_decimal_amount() {
+        let args = [
+            "test",
+            "--dst-chain",
+            "chain_B",
+            "--src-chain",
+            "chain_A",
+            "--src-port",
+            "transfer",
+            "--src-channel",
+            "channel-0",
+            "--timeout-seconds",
+            "60",
+            "--denom",
+            "uatom",
+        ];
+
+        let with_decimals = [&args[..], &["--amount", "1.5", "--decimals", "6"]].concat();
+        let opts = TxIcs20MsgTransferCmd::parse_from(&with_decimals)
+            .validate_options(&config())
+            .unwrap();
+
+        assert_eq!(
+            opts.coins,
+            vec![Coin {
+                denom: "uatom".to_owned(),
+                amount: Amount::from(1_500_000),
+            }]
+        );
+
+        let without_decimals = [&args[..], &["--amount", "1.5"]].concat();
+        let err = TxIcs20MsgTransferCmd::parse_from(&without_decimals)
+            .validate_options(&config())
+            .unwrap_err();
+        assert!(err.to_string().contains("`--decimals`"));
+
+        // Not rounded to the base denomination
+        let too_precise = [&args[..], &["--amount", "1.0000005", "--decimals", "6"]].concat();
+        assert!(TxIcs20MsgTransferCmd::parse_from(&too_precise)
+            .validate_options(&config())
+            .is_err());
+
+        let with_coin = [&args[..11], &["--coin", "100stake", "--decimals", "6"]].concat();
+        assert!(TxIcs20MsgTransferCmd::try_parse_from(&with_coin).is_err());
+    }
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(
+            parse_amount("42"),
+            Ok(DecimalAmount {
+                amount: "42".to_owned(),
+                denom: None,
+                percentage: false,
+            })
+        );
+        assert_eq!(
+            parse_amount("1.5uatom"),
+            Ok(DecimalAmount {
+                amount: "1.5".to_owned(),
+                denom: Some("uatom".to_owned()),
+                percentage: false,
+            })
+        );
+        assert_eq!(
+            parse_amount("42ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2")
+                .map(|coin| coin.denom),
+            Ok(Some(
+                "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2".to_owned()
+            ))
+        );
+
+        assert_eq!(
+            parse_amount("12.5%uatom"),
+            Ok(DecimalAmount {
+                amount: "12.5".to_owned(),
+                denom: Some("uatom".to_owned()),
+                percentage: true,
+            })
+        );
+        assert_eq!(
+            parse_amount("25%").map(|amount| amount.percentage),
+            Ok(true)
+        );
+
+        for amount in [
+            "",
+            "uatom",
+            "-42",
+            "4.uatom",
+            ".2uatom",
+            "4.2.1uatom",
+            "4,2uatom",
+            "%",
+            "%uatom",
+            "25%%",
+            "25uatom%",
+        ] {
+            assert!(
+                parse_amount(amount).is_err(),
+                "amount '{}' should be rejected",
+                amount
+            );
+        }
+    }
+
+    #[test]
+    fn test_ft_transfer_percentage_amount() {
+        let args = [
+            "test",
+            "--dst-chain",
+            "chain_B",
+            "--src-chain",
+            "chain_A",
+            "--src-port",
+            "transfer",
+            "--src-channel",
+            "channel-0",
+            "--timeout-seconds",
+            "60",
+            "--denom",
+            "uatom",
+        ];
+
+        // Resolved from the balance once the source chain is spawned
+        let with_percentage = [&args[..], &["--amount", "25%"]].concat();
+        let cmd = TxIcs20MsgTransferCmd::parse_from(&with_percentage);
+        assert_eq!(cmd.balance_percentage(), Some("25"));
+
+        let opts = cmd.validate_options(&config()).unwrap();
+        assert_eq!(opts.coins[0].denom, "uatom");
+
+        let over_hundred = [&args[..], &["--amount", "150%"]].concat();
+        let err = TxIcs20MsgTransferCmd::parse_from(&over_hundred)
+            .validate_options(&config())
+            .unwrap_err();
+        assert!(err.to_string().contains("over 100"));
+
+        let with_decimals = [&args[..], &["--amount", "25%", "--decimals", "6"]].concat();
+        let err = TxIcs20MsgTransferCmd::parse_from(&with_decimals)
+            .validate_options(&config())
+            .unwrap_err();
+        assert!(err.to_string().contains("`--decimals`"));
+    }
+
+    #[test]
+    fn test_split_percentage() {
+        let split = |balance: u64, fees: u64, percent: &str, number_msgs: usize| {
+            split_percentage(
+                Amount::from(balance),
+                Amount::from(fees),
+                percent,
+                number_msgs,
+            )
+        };
+
+        assert_eq!(split(1000, 0, "25", 1), Ok(Amount::from(250)));
+        assert_eq!(split(1000, 0, "0.1", 1), Ok(Amount::from(1)));
+
+        // 100% sends all the balance left once the fees are set aside
+        assert_eq!(split(1000, 100, "100", 1), Ok(Amount::from(900)));
+        assert_eq!(split(1000, 999, "100", 1), Ok(Amount::from(1)));
+
+        // Split evenly over the messages, rounded down
+        assert_eq!(split(1000, 0, "100", 3), Ok(Amount::from(333)));
+        assert_eq!(split(1000, 0, "50", 500), Ok(Amount::from(1)));
+
+        assert!(split(1000, 1000, "100", 1)
+            .unwrap_err()
+            .contains("would be zero"));
+        assert!(split(1000, 0, "50", 501)
+            .unwrap_err()
+            .contains("would be zero"));
+        assert!(split(1000, 0, "0.09", 1)
+            .unwrap_err()
+            .contains("would be zero"));
+        assert!(split(100, 101, "50", 1)
+            .unwrap_err()
+            .contains("lower than the fees"));
+        assert!(split(1000, 0, "100.5", 1).unwrap_err().contains("over 100"));
+    }
+
+    #[test]
+    fn test_max_fees() {
+        let mut chain_config = config().chains[0].clone();
+        chain_config.max_msg_num = MaxMsgNum::new(10).unwrap();
+        chain_config.max_gas = Some(400_000);
+        chain_config.gas_price = GasPrice::new(0.01, "uatom".to_owned());
+
+        let fee = ExplicitFee {
+            amount: RawCoin {
+                denom: "uatom".to_owned(),
+                amount: "5000".to_owned(),
+            },
+            gas_limit: None,
+        };
+
+        // The messages are submitted in as many transactions as `max_msg_num` requires
+        assert_eq!(
+            max_fees(&chain_config, Some(&fee), 10),
+            Ok(Amount::from(5000))
+        );
+        assert_eq!(
+            max_fees(&chain_config, Some(&fee), 11),
+            Ok(Amount::from(10_000))
+        );
+
+        // Each paying the fee of `max_gas` at most, rounded up as with the
+        // gas price of 0.01, which is slightly over it as a float
+        assert_eq!(max_fees(&chain_config, None, 1), Ok(Amount::from(4001)));
+    }
+
+    #[test]
+    fn test_ft_transfer_timeout_timestamp() {
+        let cmd = TxIcs20MsgTransferCmd::parse_from(&[
+            "test",
+            "--dst-chain",
+            "chain_receiver",
+            "--src-chain",
+            "chain_sender",
+            "--src-port",
+            "port_sender",
+            "--src-channel",
+            "channel_sender",
+            "--amount",
+            "42",
+            "--timeout-timestamp",
+            "2024-09-01T12:00:00Z",
+        ]);
+
+        assert_eq!(
+            cmd.timeout_timestamp
+                .map(|timestamp| timestamp.nanoseconds()),
+            Some(1_725_192_000_000_000_000)
+        );
+    }
+
+    #[test]
+    fn test_parse_timeout_timestamp() {
+        assert_eq!(
+            parse_timeout_timestamp("2024-09-01T12:00:00Z"),
+            parse_timeout_timestamp("1725192000000000000")
+        );
+
+        for timestamp in ["", "0", "tomorrow", "2024-09-01", "-1"] {
+            assert!(
+                parse_timeout_timestamp(timestamp).is_err(),
+                "timeout timestamp '{}' should be rejected",
+                timestamp
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_coin() {
+        assert_eq!(
+            parse_coin("100stake"),
+            Ok(Coin {
+                denom: "stake".to_owned(),
+                amount: Amount::from(100)
+            })
+        );
+
+        for coin in ["", "100", "stake", "-5stake", "5.5stake"] {
+            assert!(
+                parse_coin(coin).is_err(),
+                "coin '{}' should be rejected",
+                coin
+            );
+        }
+    }
+
+    #[test]
+    fn test_explicit_fee() {
+        let mut chain_config = config().chains.remove(0);
+        chain_config.gas_price = GasPrice::new(0.01, "uatom".to_owned());
+
+        assert_eq!(
+            explicit_fee(&chain_config, "5000uatom", Some(250000)),
+            Ok(ExplicitFee {
+                amount: RawCoin {
+                    denom: "uatom".to_owned(),
+                    amount: "5000".to_owned()
+                },
+                gas_limit: Some(250000),
+            })
+        );
+
+        // Below the configured gas price, which only warns
+        assert!(explicit_fee(&chain_config, "1uatom", None).is_ok());
+
+        assert!(explicit_fee(&chain_config, "5000stake", None).is_err());
+    }
+
     #[test]
     fn test_ft_transfer_no_amount() {
         assert!(TxIcs20MsgTransferCmd::try_parse_from(&[
@@ -0,0 +1,300 @@
+//! The parameters of the transfers sent by `tx ft-transfer --from-file`.
+
+use core::fmt::Display;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use ibc::core::ics24_host::identifier::ChainId;
+
+use crate::cli_utils::{parse_channel_id, parse_port_id};
+use crate::error::Error;
+
+use super::{parse_amount, parse_timeout_timestamp, TxIcs20MsgTransferCmd};
+
+/// The parameters of a transfer read from a JSON or TOML file, named after the flags
+/// of `tx ft-transfer`, e.g. `src_chain` for `--src-chain`. The flags given on the
+/// command line take precedence over the parameters of the file.
+#[derive(Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct TransferFile {
+    pub dst_chain: Option<String>,
+    pub src_chain: Option<String>,
+    pub src_port: Option<String>,
+    pub src_channel: Option<String>,
+    pub amount: Option<String>,
+    pub denom: Option<String>,
+    pub decimals: Option<u32>,
+    pub receiver: Option<String>,
+    pub allow_unchecked_receiver: Option<bool>,
+    pub timeout_height_offset: Option<u64>,
+    pub timeout_seconds: Option<u64>,
+    pub timeout_timestamp: Option<String>,
+    pub number_msgs: Option<usize>,
+    pub memo: Option<String>,
+}
+
+impl TransferFile {
+    /// Reads the parameters from the file at `path`, in JSON or in TOML depending on its extension.
+    pub fn read(path: &Path) -> Result<Self, Error> {
+        let error = |reason: String| Error::transfer_file(path.display().to_string(), reason);
+
+        let content = fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => serde_json::from_str(&content).map_err(|e| error(e.to_string())),
+            Some("toml") => toml::from_str(&content).map_err(|e| error(e.to_string())),
+            _ => Err(error("expected a `.json` or a `.toml` file".to_string())),
+        }
+    }
+
+    /// The command with the parameters of the file which are not given on the command line.
+    ///
+    /// The parameters given together on the command line replace the matching parameters of
+    /// the file as a whole: `--coin` replaces the amount, denomination and decimals of the file,
+    /// `--src-channels` its source channel, and an amount followed by its denomination the
+    /// denomination of the file. Likewise, `--timeout-seconds` and `--timeout-timestamp`
+    /// replace both of these timeouts of the file.
+    pub fn merge(self, cmd: &TxIcs20MsgTransferCmd) -> Result<TxIcs20MsgTransferCmd, String> {
+        let mut merged = cmd.clone();
+
+        merged.dst_chain_id = merged
+            .dst_chain_id
+            .or_else(|| self.dst_chain.map(|id| ChainId::from_string(&id)));
+
+        merged.src_chain_id = merged
+            .src_chain_id
+            .or_else(|| self.src_chain.map(|id| ChainId::from_string(&id)));
+
+        merged.src_port_id =
+            or_parse(merged.src_port_id, self.src_port, "src_port", parse_port_id)?;
+
+        if merged.src_channels.is_empty() {
+            merged.src_channel_id = or_parse(
+                merged.src_channel_id,
+                self.src_channel,
+                "src_channel",
+                parse_channel_id,
+            )?;
+        }
+
+        if merged.coins.is_empty() {
+            let denom_given = matches!(&merged.amount, Some(amount) if amount.denom.is_some());
+
+            merged.amount = or_parse(merged.amount, self.amount, "amount", parse_amount)?;
+            merged.decimals = merged.decimals.or(self.decimals);
+
+            if !denom_given {
+                merged.denom = merged.denom.or(self.denom);
+            }
+        }
+
+        merged.receiver = merged.receiver.or(self.receiver);
+        merged.allow_unchecked_receiver |= self.allow_unchecked_receiver.unwrap_or(false);
+
+        merged.timeout_height_offset = merged.timeout_height_offset.or(self.timeout_height_offset);
+
+        if merged.timeout_seconds.is_none() && merged.timeout_timestamp.is_none() {
+            merged.timeout_seconds = self.timeout_seconds;
+            merged.timeout_timestamp = or_parse(
+                None,
+                self.timeout_timestamp,
+                "timeout_timestamp",
+                parse_timeout_timestamp,
+            )?;
+        }
+
+        merged.number_msgs = merged.number_msgs.or(self.number_msgs);
+        merged.memo = merged.memo.or(self.memo);
+
+        Ok(merged)
+    }
+}
+
+/// The value given on the command line, if any, or else the value of the parameter `key`
+/// of the file, parsed as its flag would be.
+fn or_parse<T, E: Display>(
+    value: Option<T>,
+    file_value: Option<String>,
+    key: &str,
+    parse: impl FnOnce(&str) -> Result<T, E>,
+) -> Result<Option<T>, String> {
+    match (value, file_value) {
+        (Some(value), _) => Ok(Some(value)),
+        (None, Some(file_value)) => parse(&file_value)
+            .map(Some)
+            .map_err(|e| format!("invalid `{}`: {}", key, e)),
+        (None, None) => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TransferFile;
+
+    use core::time::Duration;
+    use std::path::Path;
+    use std::str::FromStr;
+
+    use abscissa_core::clap::Parser;
+    use ibc::applications::transfer::{Amount, Coin};
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+    use ibc_relayer::config::load;
+
+    use crate::commands::tx::transfer::{DecimalAmount, TxIcs20MsgTransferCmd};
+
+    const FILE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/transfer_params.toml"
+    );
+
+    fn file() -> TransferFile {
+        TransferFile::read(Path::new(FILE)).unwrap()
+    }
+
+    fn parse(args: &[&str]) -> TxIcs20MsgTransferCmd {
+        TxIcs20MsgTransferCmd::parse_from([&["test", "--from-file", FILE][..], args].concat())
+    }
+
+    #[test]
+    fn read_toml_and_json() {
+        let toml = file();
+
+        assert_eq!(toml.src_chain.as_deref(), Some("ibc-0"));
+        assert_eq!(toml.amount.as_deref(), Some("9999"));
+        assert_eq!(toml.timeout_height_offset, Some(1000));
+
+        let json: TransferFile = serde_json::from_str(
+            r#"{ "src_chain": "ibc-0", "amount": "9999", "timeout_height_offset": 1000 }"#,
+        )
+        .unwrap();
+
+        assert_eq!(json.src_chain, toml.src_chain);
+        assert_eq!(json.amount, toml.amount);
+    }
+
+    #[test]
+    fn unknown_parameters_are_rejected() {
+        assert!(toml::from_str::<TransferFile>("src_chan = \"channel-0\"").is_err());
+    }
+
+    #[test]
+    fn merge_file_only() {
+        let cmd = parse(&[]);
+        let merged = file().merge(&cmd).unwrap();
+
+        assert_eq!(merged.src_chain_id, Some(ChainId::from_string("ibc-0")));
+        assert_eq!(merged.dst_chain_id, Some(ChainId::from_string("ibc-1")));
+        assert_eq!(merged.src_port_id, Some(PortId::transfer()));
+        assert_eq!(
+            merged.src_channel_id,
+            Some(ChannelId::from_str("channel-0").unwrap())
+        );
+        assert_eq!(
+            merged.amount,
+            Some(DecimalAmount {
+                amount: "9999".to_owned(),
+                denom: None,
+                percentage: false,
+            })
+        );
+        assert_eq!(merged.denom, Some("uatom".to_owned()));
+        assert_eq!(merged.timeout_height_offset, Some(1000));
+        assert_eq!(merged.timeout_seconds, Some(60));
+    }
+
+    #[test]
+    fn merge_flags_take_precedence() {
+        let cmd = parse(&[
+            "--src-channel",
+            "channel-3",
+            "--amount",
+            "42stake",
+            "--timeout-timestamp",
+            "2024-09-01T12:00:00Z",
+            "--timeout-height-offset",
+            "0",
+        ]);
+        let merged = file().merge(&cmd).unwrap();
+
+        assert_eq!(
+            merged.src_channel_id,
+            Some(ChannelId::from_str("channel-3").unwrap())
+        );
+        assert_eq!(merged.amount.as_ref().unwrap().amount, "42");
+
+        // The denomination given along with the amount replaces the one of the file
+        assert_eq!(merged.denom, None);
+
+        // The timeout timestamp replaces the timeout in seconds of the file
+        assert_eq!(merged.timeout_seconds, None);
+        assert!(merged.timeout_timestamp.is_some());
+
+        assert_eq!(merged.timeout_height_offset, Some(0));
+    }
+
+    #[test]
+    fn merge_coins_replace_amount() {
+        let cmd = parse(&[
+            "--coin",
+            "100stake",
+            "--src-channels",
+            "channel-1,channel-2",
+        ]);
+        let merged = file().merge(&cmd).unwrap();
+
+        assert_eq!(merged.amount, None);
+        assert_eq!(merged.denom, None);
+        assert_eq!(merged.src_channel_id, None);
+        assert_eq!(merged.src_channels.len(), 2);
+    }
+
+    #[test]
+    fn merged_is_validated() {
+        let config = load(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../relayer/tests/config/fixtures/relayer_conf_example.toml"
+        ))
+        .unwrap();
+
+        // The chains of the file are not in the configuration
+        let cmd = parse(&[]).merged().unwrap();
+        assert!(cmd.validate_options(&config).is_err());
+
+        let cmd = parse(&["--src-chain", "chain_A", "--dst-chain", "chain_B"])
+            .merged()
+            .unwrap();
+        let opts = cmd.validate_options(&config).unwrap();
+
+        assert_eq!(
+            opts.coins,
+            vec![Coin {
+                denom: "uatom".to_owned(),
+                amount: Amount::from(9999),
+            }]
+        );
+        assert_eq!(opts.timeout_height_offset, 1000);
+        assert_eq!(opts.timeout_duration, Duration::from_secs(60));
+    }
+
+    #[test]
+    fn merged_missing_parameter() {
+        let mut cmd = parse(&["--src-chain", "ibc-0"]);
+        cmd.from_file = None;
+
+        let err = cmd.merged().unwrap_err();
+        assert!(err.to_string().contains("missing `--dst-chain`"));
+    }
+
+    #[test]
+    fn merge_invalid_parameter() {
+        let file = TransferFile {
+            src_channel: Some("Channel-0".to_owned()),
+            ..TransferFile::default()
+        };
+
+        let err = file.merge(&parse(&[])).unwrap_err();
+        assert!(err.starts_with("invalid `src_channel`"));
+    }
+}
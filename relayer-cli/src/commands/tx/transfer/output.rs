@@ -0,0 +1,375 @@
+//! The output of `tx ft-transfer` once its transactions are committed.
+
+use core::fmt;
+
+use serde::Serialize;
+
+use ibc::core::ics04_channel::events::SendPacket;
+use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics04_channel::timeout::TimeoutHeight;
+use ibc::core::ics24_host::identifier::{ChannelId, PortId};
+use ibc::events::IbcEvent;
+use ibc::Height;
+use ibc_relayer::chain::endpoint::SendPacketTx;
+use ibc_relayer::chain::tracking::CorrelationId;
+use ibc_relayer::transfer::{PacketOutcome, PacketStatus};
+
+/// The transactions of a transfer and the packets they sent, for scripts to extract
+/// them without parsing the events of the transactions.
+///
+/// Printed as JSON with `--json`, and as one line per packet otherwise.
+#[derive(Debug, Serialize)]
+pub struct TransferOutput {
+    /// The correlation id of the messages of the transfer, found in the logs of their transactions.
+    pub correlation_id: String,
+    /// The hashes of the transactions which sent the packets, as far as they could be queried.
+    pub tx_hashes: Vec<String>,
+    pub packets: Vec<SentPacket>,
+    /// The events of the transactions, only given with `--verbose`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub events: Option<Vec<IbcEvent>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SentPacket {
+    pub sequence: Sequence,
+    /// The position of the message which sent the packet among the messages of the transfer,
+    /// over all its source channels, from 0.
+    pub msg_index: usize,
+    pub src: PacketChannelEnd,
+    pub dst: PacketChannelEnd,
+    pub timeout: PacketTimeout,
+    /// The hash of the transaction which sent the packet, or `None` if it could not be queried.
+    pub tx_hash: Option<String>,
+    /// The `SendPacket` event emitted for the packet.
+    pub event: SendPacket,
+    /// What became of the packet on the destination chain, only given with `--wait-for-ack`.
+    #[serde(flatten)]
+    pub status: Option<PacketStatus>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PacketChannelEnd {
+    pub port_id: PortId,
+    pub channel_id: ChannelId,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PacketTimeout {
+    /// The height of the destination chain from which the packet times out, if any.
+    pub height: Option<Height>,
+    /// The time of the destination chain from which the packet times out, if any.
+    pub timestamp: Option<String>,
+}
+
+impl TransferOutput {
+    /// The output of the transfer whose packets were sent by the `SendPacket` events among
+    /// `events`, in the transactions `txs` from each source channel. The events are kept in
+    /// the output if `verbose`.
+    pub fn new(
+        correlation_id: CorrelationId,
+        events: &[IbcEvent],
+        txs: &[(ChannelId, SendPacketTx)],
+        verbose: bool,
+    ) -> Self {
+        let packets: Vec<SentPacket> = events
+            .iter()
+            .filter_map(|event| match event {
+                IbcEvent::SendPacket(send_packet) => Some(send_packet),
+                _ => None,
+            })
+            .enumerate()
+            .map(|(msg_index, send_packet)| SentPacket::new(msg_index, send_packet, txs))
+            .collect();
+
+        let mut tx_hashes: Vec<String> = Vec::new();
+
+        for tx_hash in packets.iter().filter_map(|packet| packet.tx_hash.as_ref()) {
+            if !tx_hashes.contains(tx_hash) {
+                tx_hashes.push(tx_hash.clone());
+            }
+        }
+
+        Self {
+            correlation_id: correlation_id.to_string(),
+            tx_hashes,
+            packets,
+            events: verbose.then(|| events.to_vec()),
+        }
+    }
+
+    /// Sets the status of the packets to their outcome on the destination chain, given in
+    /// the order the packets were sent, as the sequences of several source channels may clash.
+    pub fn set_outcomes(&mut self, outcomes: Vec<PacketOutcome>) {
+        for (packet, outcome) in self.packets.iter_mut().zip(outcomes) {
+            if packet.sequence == outcome.sequence {
+                packet.status = Some(outcome.status);
+            }
+        }
+    }
+}
+
+impl fmt::Display for TransferOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "sent {} packet(s) in {} transaction(s) (correlation id {})",
+            self.packets.len(),
+            self.tx_hashes.len(),
+            self.correlation_id
+        )?;
+
+        for packet in &self.packets {
+            write!(f, "\n{}", packet)?;
+        }
+
+        for event in self.events.iter().flatten() {
+            write!(f, "\n{}", event)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl SentPacket {
+    fn new(msg_index: usize, send_packet: &SendPacket, txs: &[(ChannelId, SendPacketTx)]) -> Self {
+        let packet = &send_packet.packet;
+
+        let tx_hash = txs
+            .iter()
+            .find(|(channel_id, tx)| {
+                *channel_id == packet.source_channel && tx.sequence == packet.sequence
+            })
+            .map(|(_, tx)| tx.tx_hash.clone());
+
+        Self {
+            sequence: packet.sequence,
+            msg_index,
+            src: PacketChannelEnd {
+                port_id: packet.source_port.clone(),
+                channel_id: packet.source_channel.clone(),
+            },
+            dst: PacketChannelEnd {
+                port_id: packet.destination_port.clone(),
+                channel_id: packet.destination_channel.clone(),
+            },
+            timeout: PacketTimeout {
+                height: match packet.timeout_height {
+                    TimeoutHeight::At(height) => Some(height),
+                    TimeoutHeight::Never => None,
+                },
+                timestamp: packet
+                    .timeout_timestamp
+                    .into_tm_time()
+                    .map(|time| time.to_rfc3339()),
+            },
+            tx_hash,
+            event: send_packet.clone(),
+            status: None,
+        }
+    }
+}
+
+impl fmt::Display for SentPacket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[message {}] packet {} from {}/{} to {}/{} in tx {}",
+            self.msg_index,
+            self.sequence,
+            self.src.port_id,
+            self.src.channel_id,
+            self.dst.port_id,
+            self.dst.channel_id,
+            self.tx_hash.as_deref().unwrap_or("unknown"),
+        )?;
+
+        match &self.status {
+            Some(PacketStatus::Success) => write!(f, ": success"),
+            Some(PacketStatus::Error { error }) => write!(f, ": error, {}", error),
+            Some(PacketStatus::TimedOut) => write!(f, ": timed out"),
+            Some(PacketStatus::Pending) => write!(f, ": pending"),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TransferOutput;
+
+    use core::str::FromStr;
+
+    use ibc::core::ics04_channel::events::SendPacket;
+    use ibc::core::ics04_channel::packet::{Packet, Sequence};
+    use ibc::core::ics04_channel::timeout::TimeoutHeight;
+    use ibc::core::ics24_host::identifier::{ChannelId, PortId};
+    use ibc::events::IbcEvent;
+    use ibc::timestamp::Timestamp;
+    use ibc::Height;
+    use ibc_relayer::chain::endpoint::SendPacketTx;
+    use ibc_relayer::chain::tracking::CorrelationId;
+    use ibc_relayer::transfer::{PacketOutcome, PacketStatus};
+
+    const GOLDEN: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/transfer_output.json"
+    ));
+
+    const CORRELATION_ID: &str = "3f2a9c1e";
+
+    const TX_HASH: &str = "5E3F1C0A8E5B0C2A7D9A1F4E6B3C8D2E0F7A9B1C3D5E7F9A2B4C6D8E0F1A3B5C";
+
+    fn send_packet(sequence: u64, src_channel: &str, dst_channel: &str) -> IbcEvent {
+        IbcEvent::SendPacket(SendPacket {
+            height: Height::new(0, 42).unwrap(),
+            packet: Packet {
+                sequence: Sequence::from(sequence),
+                source_port: PortId::transfer(),
+                source_channel: ChannelId::from_str(src_channel).unwrap(),
+                destination_port: PortId::transfer(),
+                destination_channel: ChannelId::from_str(dst_channel).unwrap(),
+                data: vec![],
+                timeout_height: TimeoutHeight::At(Height::new(1, 1100).unwrap()),
+                timeout_timestamp: Timestamp::from_nanoseconds(1_665_000_000_000_000_000).unwrap(),
+            },
+        })
+    }
+
+    fn output(verbose: bool) -> TransferOutput {
+        let events = vec![
+            send_packet(5, "channel-0", "channel-7"),
+            send_packet(9, "channel-1", "channel-8"),
+        ];
+
+        // The transaction of the second packet was pruned
+        let txs = vec![(
+            ChannelId::from_str("channel-0").unwrap(),
+            send_packet_tx(5, TX_HASH),
+        )];
+
+        let output = TransferOutput::new(CorrelationId::new(), &events, &txs, verbose);
+
+        // A fixed correlation id instead of the random one, to compare the output
+        TransferOutput {
+            correlation_id: CORRELATION_ID.to_string(),
+            ..output
+        }
+    }
+
+    fn send_packet_tx(sequence: u64, tx_hash: &str) -> SendPacketTx {
+        SendPacketTx {
+            sequence: Sequence::from(sequence),
+            height: Height::new(0, 42).unwrap(),
+            tx_hash: tx_hash.to_string(),
+        }
+    }
+
+    #[test]
+    fn output_golden() {
+        let mut json = serde_json::to_string_pretty(&output(false)).unwrap();
+        json.push('\n');
+
+        assert_eq!(json, GOLDEN);
+    }
+
+    #[test]
+    fn output_with_events() {
+        let json = serde_json::to_value(&output(true)).unwrap();
+
+        assert_eq!(json["events"].as_array().unwrap().len(), 2);
+        assert_eq!(json["tx_hashes"], serde_json::json!([TX_HASH]));
+    }
+
+    #[test]
+    fn output_with_outcomes() {
+        let mut output = output(false);
+
+        output.set_outcomes(vec![
+            PacketOutcome {
+                sequence: Sequence::from(5),
+                status: PacketStatus::Pending,
+            },
+            PacketOutcome {
+                sequence: Sequence::from(9),
+                status: PacketStatus::Error {
+                    error: "invalid receiver".to_string(),
+                },
+            },
+        ]);
+
+        let json = serde_json::to_value(&output).unwrap();
+
+        assert_eq!(json["packets"][0]["status"], "pending");
+        assert_eq!(json["packets"][1]["status"], "error");
+        assert_eq!(json["packets"][1]["error"], "invalid receiver");
+    }
+
+    #[test]
+    fn output_with_clashing_sequences() {
+        // The same sequence sent over two source channels, in two transactions
+        let events = vec![
+            send_packet(5, "channel-0", "channel-7"),
+            send_packet(5, "channel-1", "channel-8"),
+        ];
+
+        let other_tx_hash = TX_HASH.replace('5', "6");
+        let txs = vec![
+            (
+                ChannelId::from_str("channel-1").unwrap(),
+                send_packet_tx(5, &other_tx_hash),
+            ),
+            (
+                ChannelId::from_str("channel-0").unwrap(),
+                send_packet_tx(5, TX_HASH),
+            ),
+        ];
+
+        let mut output = TransferOutput::new(CorrelationId::new(), &events, &txs, false);
+
+        assert_eq!(output.packets[0].msg_index, 0);
+        assert_eq!(output.packets[0].tx_hash.as_deref(), Some(TX_HASH));
+        assert_eq!(output.packets[1].msg_index, 1);
+        assert_eq!(output.packets[1].tx_hash, Some(other_tx_hash));
+        assert_eq!(output.tx_hashes.len(), 2);
+
+        output.set_outcomes(vec![
+            PacketOutcome {
+                sequence: Sequence::from(5),
+                status: PacketStatus::Success,
+            },
+            PacketOutcome {
+                sequence: Sequence::from(5),
+                status: PacketStatus::TimedOut,
+            },
+        ]);
+
+        assert_eq!(output.packets[0].status, Some(PacketStatus::Success));
+        assert_eq!(output.packets[1].status, Some(PacketStatus::TimedOut));
+    }
+
+    #[test]
+    fn output_as_text() {
+        let mut output = output(false);
+
+        output.set_outcomes(vec![
+            PacketOutcome {
+                sequence: Sequence::from(5),
+                status: PacketStatus::Success,
+            },
+            PacketOutcome {
+                sequence: Sequence::from(9),
+                status: PacketStatus::TimedOut,
+            },
+        ]);
+
+        let expected = format!(
+            "sent 2 packet(s) in 1 transaction(s) (correlation id 3f2a9c1e)\n\
+             [message 0] packet 5 from transfer/channel-0 to transfer/channel-7 in tx {}: success\n\
+             [message 1] packet 9 from transfer/channel-1 to transfer/channel-8 in tx unknown: timed out",
+            TX_HASH
+        );
+
+        assert_eq!(output.to_string(), expected);
+    }
+}
@@ -0,0 +1,401 @@
+//! Machine-readable receipts of the transfers sent by `tx ft-transfer --receipt-file`.
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use ibc::applications::transfer::packet::VersionedPacketData;
+use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics04_channel::timeout::TimeoutHeight;
+use ibc::core::ics04_channel::Version;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc::events::IbcEvent;
+use ibc::Height;
+use ibc_relayer::chain::endpoint::SendPacketTx;
+use ibc_relayer::denom::DenomTrace;
+use ibc_relayer::transfer::TransferOptions;
+
+/// Version of the layout of the receipts, to be bumped on every incompatible change.
+pub const RECEIPT_SCHEMA: u32 = 1;
+
+/// The receipt of a transfer, for reconciliation by the systems which initiated it.
+#[derive(Debug, Serialize)]
+pub struct TransferReceipt {
+    pub schema: u32,
+    pub src: ReceiptChannelEnd,
+    pub dst: ReceiptChannelEnd,
+    pub sender: String,
+    pub receiver: String,
+    pub denom: ReceiptDenom,
+    /// The amount of coins sent by each packet.
+    pub amount: String,
+    /// The amount of coins sent by each packet in the display unit of the source
+    /// denomination, e.g. `1.5 ATOM`, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_amount: Option<String>,
+    pub packets: Vec<ReceiptPacket>,
+    pub timeout: ReceiptTimeout,
+    pub status: ReceiptStatus,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReceiptChannelEnd {
+    pub chain_id: ChainId,
+    pub port_id: PortId,
+    pub channel_id: ChannelId,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReceiptDenom {
+    /// The denomination of the coins on the source chain.
+    pub src: String,
+    /// The denomination under which the receiver gets the coins on the destination chain,
+    /// or `None` if the trace of the source denomination is unknown and the packet does not
+    /// carry it.
+    pub dst: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReceiptPacket {
+    pub sequence: Sequence,
+    /// The hash of the transaction which sent the packet, or `None` if it could not be queried.
+    pub tx_hash: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReceiptTimeout {
+    pub height_offset: u64,
+    pub seconds: u64,
+    /// The height of the destination chain from which the packets time out, if any.
+    pub height: Option<Height>,
+    /// The time of the destination chain from which the packets time out, if any.
+    pub timestamp: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiptStatus {
+    /// The transactions which sent the packets were committed on the source chain.
+    Committed,
+}
+
+impl TransferReceipt {
+    /// Builds the receipt of the transfer from `src_chain` to `dst_chain` sent with `opts`
+    /// on a channel of the given `version`, whose packets were sent by the `SendPacket` events
+    /// among `events`, in the transactions `txs`. The destination denomination is derived from
+    /// `src_trace`, the trace of the denomination of the coins on the source chain if known,
+    /// or else from the trace carried by the packet.
+    ///
+    /// Returns `None` if no ICS-20 packet was sent, or if several coins were.
+    pub fn new(
+        src_chain: ChainId,
+        dst_chain: ChainId,
+        version: &Version,
+        opts: &TransferOptions,
+        src_trace: Option<&DenomTrace>,
+        events: &[IbcEvent],
+        txs: &[SendPacketTx],
+    ) -> Option<Self> {
+        let coin = match opts.coins.as_slice() {
+            [coin] => coin,
+            _ => return None,
+        };
+
+        let packets: Vec<_> = events
+            .iter()
+            .filter_map(|event| match event {
+                IbcEvent::SendPacket(send_packet) => Some(&send_packet.packet),
+                _ => None,
+            })
+            .collect();
+
+        let first = packets.first()?;
+        let data = VersionedPacketData::decode(version, &first.data).ok()?;
+
+        let src_trace = src_trace.cloned().or_else(|| {
+            data.tokens()
+                .first()
+                .map(|token| DenomTrace::from(&token.denom))
+        });
+
+        let dst_denom = src_trace.map(|trace| {
+            trace
+                .transferred(
+                    &first.source_port,
+                    &first.source_channel,
+                    &first.destination_port,
+                    &first.destination_channel,
+                )
+                .ibc_denom()
+        });
+
+        let timeout_height = match first.timeout_height {
+            TimeoutHeight::At(height) => Some(height),
+            TimeoutHeight::Never => None,
+        };
+
+        let tx_hash = |sequence: Sequence| {
+            txs.iter()
+                .find(|tx| tx.sequence == sequence)
+                .map(|tx| tx.tx_hash.clone())
+        };
+
+        Some(TransferReceipt {
+            schema: RECEIPT_SCHEMA,
+            src: ReceiptChannelEnd {
+                chain_id: src_chain,
+                port_id: first.source_port.clone(),
+                channel_id: first.source_channel.clone(),
+            },
+            dst: ReceiptChannelEnd {
+                chain_id: dst_chain,
+                port_id: first.destination_port.clone(),
+                channel_id: first.destination_channel.clone(),
+            },
+            sender: data.sender().to_string(),
+            receiver: data.receiver().to_string(),
+            denom: ReceiptDenom {
+                src: coin.denom.clone(),
+                dst: dst_denom,
+            },
+            amount: coin.amount.to_string(),
+            display_amount: None,
+            packets: packets
+                .iter()
+                .map(|packet| ReceiptPacket {
+                    sequence: packet.sequence,
+                    tx_hash: tx_hash(packet.sequence),
+                })
+                .collect(),
+            timeout: ReceiptTimeout {
+                height_offset: opts.timeout_height_offset,
+                seconds: opts.timeout_duration.as_secs(),
+                height: timeout_height,
+                timestamp: first
+                    .timeout_timestamp
+                    .into_tm_time()
+                    .map(|time| time.to_rfc3339()),
+            },
+            status: ReceiptStatus::Committed,
+        })
+    }
+
+    /// Writes the receipt as JSON to the file at `path`, atomically, by first writing it
+    /// to a temporary file next to it and then renaming the temporary file to `path`.
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        let mut json = serde_json::to_string_pretty(self)?;
+        json.push('\n');
+
+        let tmp_path = tmp_path(path);
+
+        let result = File::create(&tmp_path)
+            .and_then(|mut file| {
+                file.write_all(json.as_bytes())?;
+                file.sync_all()
+            })
+            .and_then(|()| fs::rename(&tmp_path, path));
+
+        if result.is_err() {
+            let _ = fs::remove_file(&tmp_path);
+        }
+
+        result
+    }
+}
+
+/// The temporary file the receipt is written to before being renamed to `path`,
+/// in the same directory for the rename to be atomic.
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(format!(".{}.tmp", std::process::id()));
+
+    path.with_file_name(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TransferReceipt;
+
+    use core::str::FromStr;
+    use core::time::Duration;
+    use std::fs;
+
+    use ibc::applications::transfer::{Amount, Coin};
+    use ibc::core::ics04_channel::events::SendPacket;
+    use ibc::core::ics04_channel::packet::{Packet, Sequence};
+    use ibc::core::ics04_channel::timeout::TimeoutHeight;
+    use ibc::core::ics04_channel::Version;
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+    use ibc::events::IbcEvent;
+    use ibc::timestamp::Timestamp;
+    use ibc::Height;
+    use ibc_proto::ibc::applications::transfer::v2::FungibleTokenPacketData;
+    use ibc_relayer::chain::endpoint::SendPacketTx;
+    use ibc_relayer::denom::DenomTrace;
+    use ibc_relayer::transfer::TransferOptions;
+
+    const GOLDEN: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/transfer_receipt.json"
+    ));
+
+    const TX_HASH: &str = "5E3F1C0A8E5B0C2A7D9A1F4E6B3C8D2E0F7A9B1C3D5E7F9A2B4C6D8E0F1A3B5C";
+
+    fn opts() -> TransferOptions {
+        TransferOptions {
+            packet_src_port_id: PortId::transfer(),
+            packet_src_channel_id: ChannelId::from_str("channel-0").unwrap(),
+            coins: vec![Coin {
+                denom: "uatom".to_string(),
+                amount: Amount::from(1000),
+            }],
+            receiver: None,
+            timeout_height_offset: 100,
+            timeout_duration: Duration::from_secs(600),
+            timeout_timestamp: None,
+            number_msgs: 2,
+            fee: None,
+            fee_granter: None,
+            memo: None,
+        }
+    }
+
+    fn send_packet(sequence: u64) -> IbcEvent {
+        let data = FungibleTokenPacketData {
+            denom: "uatom".to_string(),
+            amount: "1000".to_string(),
+            sender: "cosmos1sender".to_string(),
+            receiver: "osmo1receiver".to_string(),
+        };
+
+        IbcEvent::SendPacket(SendPacket {
+            height: Height::new(0, 42).unwrap(),
+            packet: Packet {
+                sequence: Sequence::from(sequence),
+                source_port: PortId::transfer(),
+                source_channel: ChannelId::from_str("channel-0").unwrap(),
+                destination_port: PortId::transfer(),
+                destination_channel: ChannelId::from_str("channel-7").unwrap(),
+                data: serde_json::to_vec(&data).unwrap(),
+                timeout_height: TimeoutHeight::At(Height::new(1, 1100).unwrap()),
+                timeout_timestamp: Timestamp::from_nanoseconds(1_665_000_000_000_000_000).unwrap(),
+            },
+        })
+    }
+
+    fn receipt() -> TransferReceipt {
+        let events = vec![send_packet(5), send_packet(6)];
+
+        // The transaction of the second packet was pruned
+        let txs = vec![SendPacketTx {
+            sequence: Sequence::from(5),
+            height: Height::new(0, 42).unwrap(),
+            tx_hash: TX_HASH.to_string(),
+        }];
+
+        let src_trace = DenomTrace {
+            path: String::new(),
+            base_denom: "uatom".to_string(),
+        };
+
+        TransferReceipt::new(
+            ChainId::from_string("cosmoshub-4"),
+            ChainId::from_string("osmosis-1"),
+            &Version::ics20(),
+            &opts(),
+            Some(&src_trace),
+            &events,
+            &txs,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn receipt_golden() {
+        let dir = std::env::temp_dir().join(format!("hermes-receipt-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let path = dir.join("receipt.json");
+        receipt().write(&path).unwrap();
+
+        let written = fs::read_to_string(&path).unwrap();
+        let entries = fs::read_dir(&dir).unwrap().count();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(written, GOLDEN);
+        // The temporary file was renamed to the receipt
+        assert_eq!(entries, 1);
+    }
+
+    #[test]
+    fn receipt_with_display_amount() {
+        let mut receipt = receipt();
+        receipt.display_amount = Some("0.001 ATOM".to_string());
+
+        let json = serde_json::to_value(&receipt).unwrap();
+
+        assert_eq!(json["amount"], "1000");
+        assert_eq!(json["display_amount"], "0.001 ATOM");
+    }
+
+    #[test]
+    fn receipt_without_packets() {
+        let receipt = TransferReceipt::new(
+            ChainId::from_string("cosmoshub-4"),
+            ChainId::from_string("osmosis-1"),
+            &Version::ics20(),
+            &opts(),
+            None,
+            &[],
+            &[],
+        );
+
+        assert!(receipt.is_none());
+    }
+
+    const PACKET_DATA_V2: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../modules/tests/support/transfer/packet_data_v2.bin"
+    ));
+
+    #[test]
+    fn receipt_of_v2_packet() {
+        let mut event = send_packet(5);
+
+        if let IbcEvent::SendPacket(send_packet) = &mut event {
+            send_packet.packet.data = PACKET_DATA_V2.to_vec();
+        }
+
+        // Without the trace of the source denomination, the one carried by the packet is used
+        let receipt = TransferReceipt::new(
+            ChainId::from_string("cosmoshub-4"),
+            ChainId::from_string("osmosis-1"),
+            &Version::new("ics20-2".to_string()),
+            &opts(),
+            None,
+            &[event],
+            &[],
+        )
+        .unwrap();
+
+        let dst_trace = DenomTrace {
+            path: "transfer/channel-7/transfer/channel-7".to_string(),
+            base_denom: "uatom".to_string(),
+        };
+
+        assert_eq!(receipt.sender, "cosmos1sender");
+        assert_eq!(receipt.receiver, "osmo1receiver");
+        assert_eq!(receipt.denom.dst, Some(dst_trace.ibc_denom()));
+    }
+
+    #[test]
+    fn receipt_write_failure() {
+        let path = std::env::temp_dir()
+            .join(format!("hermes-missing-{}", std::process::id()))
+            .join("receipt.json");
+
+        assert!(receipt().write(&path).is_err());
+    }
+}
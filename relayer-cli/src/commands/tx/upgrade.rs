@@ -7,7 +7,7 @@ use ibc::core::ics24_host::identifier::{ChainId, ClientId};
 use ibc_relayer::config::Config;
 use ibc_relayer::upgrade_chain::{build_and_send_ibc_upgrade_proposal, UpgradePlanOptions};
 
-use crate::cli_utils::spawn_chain_runtime;
+use crate::cli_utils::{find_chain_config, spawn_chain_runtime};
 use crate::conclude::{exit_with_unrecoverable_error, Output};
 use crate::error::Error;
 use crate::prelude::*;
@@ -90,20 +90,11 @@ pub struct TxIbcUpgradeChainCmd {
 
 impl TxIbcUpgradeChainCmd {
     fn validate_options(&self, config: &Config) -> Result<UpgradePlanOptions, String> {
-        let host_chain_config = config.find_chain(&self.host_chain_id).ok_or_else(|| {
-            format!(
-                "missing configuration for source chain '{}'",
-                self.host_chain_id
-            )
-        })?;
+        let host_chain_config =
+            find_chain_config(config, &self.host_chain_id).map_err(|e| e.to_string())?;
 
         let reference_chain_config =
-            config.find_chain(&self.reference_chain_id).ok_or_else(|| {
-                format!(
-                    "missing configuration for destination chain '{}'",
-                    self.reference_chain_id
-                )
-            })?;
+            find_chain_config(config, &self.reference_chain_id).map_err(|e| e.to_string())?;
 
         let opts = UpgradePlanOptions {
             dst_chain_config: reference_chain_config.clone(),
@@ -115,7 +106,7 @@ impl TxIbcUpgradeChainCmd {
             upgraded_chain_id: self
                 .new_chain_id
                 .clone()
-                .unwrap_or_else(|| self.reference_chain_id.clone()),
+                .unwrap_or_else(|| reference_chain_config.id.clone()),
             upgraded_unbonding_period: self.new_unbonding.map(Duration::from_secs),
             upgrade_plan_name: self
                 .upgrade_name
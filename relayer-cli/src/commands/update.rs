@@ -3,10 +3,13 @@
 use abscissa_core::clap::Parser;
 use abscissa_core::{Command, Runnable};
 
-use crate::commands::tx::client::TxUpdateClientCmd;
+use crate::commands::tx::client::{TxUpdateClientCmd, TxUpdateClientsCmd};
 
 #[derive(Command, Debug, Parser, Runnable)]
 pub enum UpdateCmds {
     /// Update an IBC client
     Client(TxUpdateClientCmd),
+
+    /// Update all IBC clients that target a specific chain
+    Clients(TxUpdateClientsCmd),
 }
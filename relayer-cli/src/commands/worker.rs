@@ -0,0 +1,22 @@
+//! `worker` subcommand
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+
+use crate::commands::worker::pause::{WorkerPauseCmd, WorkerResumeCmd};
+
+mod pause;
+
+/// `worker` subcommands
+#[derive(Command, Debug, Parser, Runnable)]
+pub enum WorkerCmds {
+    /// Pause the relaying of packets on a channel by a running Hermes instance.
+    ///
+    /// The packet workers of the channel, in both directions, keep scheduling the packets
+    /// of the events they receive but submit nothing until resumed.
+    /// Requires the REST API of the running instance to be enabled.
+    Pause(WorkerPauseCmd),
+
+    /// Resume the relaying of packets on a channel paused with `worker pause`,
+    /// starting with a clear of the pending packets.
+    Resume(WorkerResumeCmd),
+}
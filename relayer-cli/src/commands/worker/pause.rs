@@ -0,0 +1,254 @@
+use abscissa_core::clap::Parser;
+use abscissa_core::{Command, Runnable};
+
+use ibc::core::ics02_client::client_state::ClientState;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc_relayer::chain::counterparty::channel_connection_client;
+use ibc_relayer::config::Config;
+use ibc_relayer::object::{Object, Packet};
+use ibc_relayer::supervisor::dump_state::WorkerDesc;
+
+use crate::cli_utils::{parse_channel_id, parse_port_id, spawn_chain_runtime, RestReply};
+use crate::conclude::Output;
+use crate::error::Error;
+use crate::prelude::*;
+
+/// The data structure that represents the arguments when invoking the `worker pause` CLI command.
+///
+/// The command has the following format:
+///
+/// `worker pause --chain <CHAIN_ID> --channel <CHANNEL_ID> [--port <PORT_ID>]`
+///
+/// The packet workers relaying the channel in both directions are paused through
+/// the REST API of the running Hermes instance, configured in the `[rest]` section.
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct WorkerPauseCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain at one end of the channel"
+    )]
+    chain_id: ChainId,
+
+    #[clap(
+        long = "port",
+        default_value = "transfer",
+        value_name = "PORT_ID",
+        parse(try_from_str = parse_port_id),
+        help = "Identifier of the port of the channel on that chain"
+    )]
+    port_id: PortId,
+
+    #[clap(
+        long = "channel",
+        alias = "chan",
+        required = true,
+        value_name = "CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
+        help_heading = "REQUIRED",
+        help = "Identifier of the channel on that chain"
+    )]
+    channel_id: ChannelId,
+}
+
+impl Runnable for WorkerPauseCmd {
+    fn run(&self) {
+        set_paused(&self.chain_id, &self.port_id, &self.channel_id, true)
+    }
+}
+
+/// The data structure that represents the arguments when invoking the `worker resume` CLI command.
+///
+/// The command has the following format:
+///
+/// `worker resume --chain <CHAIN_ID> --channel <CHANNEL_ID> [--port <PORT_ID>]`
+#[derive(Clone, Command, Debug, Parser, PartialEq)]
+pub struct WorkerResumeCmd {
+    #[clap(
+        long = "chain",
+        required = true,
+        value_name = "CHAIN_ID",
+        help_heading = "REQUIRED",
+        help = "Identifier of the chain at one end of the channel"
+    )]
+    chain_id: ChainId,
+
+    #[clap(
+        long = "port",
+        default_value = "transfer",
+        value_name = "PORT_ID",
+        parse(try_from_str = parse_port_id),
+        help = "Identifier of the port of the channel on that chain"
+    )]
+    port_id: PortId,
+
+    #[clap(
+        long = "channel",
+        alias = "chan",
+        required = true,
+        value_name = "CHANNEL_ID",
+        parse(try_from_str = parse_channel_id),
+        help_heading = "REQUIRED",
+        help = "Identifier of the channel on that chain"
+    )]
+    channel_id: ChannelId,
+}
+
+impl Runnable for WorkerResumeCmd {
+    fn run(&self) {
+        set_paused(&self.chain_id, &self.port_id, &self.channel_id, false)
+    }
+}
+
+fn set_paused(chain_id: &ChainId, port_id: &PortId, channel_id: &ChannelId, paused: bool) {
+    let config = app_config();
+
+    if !config.rest.enabled {
+        Output::error(format!("{}", Error::rest_api_disabled())).exit()
+    }
+
+    let result = packet_workers(&config, chain_id, port_id, channel_id).and_then(|objects| {
+        objects
+            .into_iter()
+            .map(|object| set_worker_paused(&config, object, paused))
+            .collect::<Result<Vec<_>, _>>()
+    });
+
+    match result {
+        Ok(workers) => Output::success(workers).exit(),
+        Err(e) => Output::error(format!("{}", e)).exit(),
+    }
+}
+
+/// The objects of the packet workers relaying the channel in both directions.
+fn packet_workers(
+    config: &Config,
+    chain_id: &ChainId,
+    port_id: &PortId,
+    channel_id: &ChannelId,
+) -> Result<[Object; 2], Error> {
+    let chain = spawn_chain_runtime(config, chain_id)?;
+
+    let channel =
+        channel_connection_client(&chain, port_id, channel_id).map_err(Error::supervisor)?;
+
+    let counterparty_chain_id = channel.client.client_state.chain_id();
+    let counterparty = channel.channel.channel_end.counterparty();
+    let counterparty_channel_id = counterparty
+        .channel_id()
+        .cloned()
+        .ok_or_else(|| Error::missing_counterparty_channel_id(channel.channel.clone()))?;
+
+    Ok([
+        Object::Packet(Packet {
+            dst_chain_id: counterparty_chain_id.clone(),
+            src_chain_id: chain_id.clone(),
+            src_channel_id: channel_id.clone(),
+            src_port_id: port_id.clone(),
+        }),
+        Object::Packet(Packet {
+            dst_chain_id: chain_id.clone(),
+            src_chain_id: counterparty_chain_id,
+            src_channel_id: counterparty_channel_id,
+            src_port_id: counterparty.port_id().clone(),
+        }),
+    ])
+}
+
+/// Pauses or resumes the worker for the given object through the REST API.
+fn set_worker_paused(config: &Config, object: Object, paused: bool) -> Result<WorkerDesc, Error> {
+    let url = format!(
+        "http://{}:{}/workers/{}",
+        config.rest.host,
+        config.rest.port,
+        if paused { "pause" } else { "resume" }
+    );
+
+    let worker = ureq::post(&url)
+        .send_json(&object)
+        .map_err(|e| Error::rest_api(url.clone(), e.to_string()))?
+        .into_json::<RestReply<WorkerDesc>>()
+        .map_err(|e| Error::rest_api(url.clone(), e.to_string()))?
+        .into_result(url)?;
+
+    info!(
+        "{} worker {}",
+        if paused { "paused" } else { "resumed" },
+        worker.object.short_name()
+    );
+
+    Ok(worker)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{WorkerPauseCmd, WorkerResumeCmd};
+
+    use std::str::FromStr;
+
+    use abscissa_core::clap::Parser;
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+
+    #[test]
+    fn test_worker_pause() {
+        assert_eq!(
+            WorkerPauseCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                port_id: PortId::transfer(),
+                channel_id: ChannelId::from_str("channel-0").unwrap(),
+            },
+            WorkerPauseCmd::parse_from(&["test", "--chain", "chain_id", "--channel", "channel-0"])
+        )
+    }
+
+    #[test]
+    fn test_worker_pause_port() {
+        assert_eq!(
+            WorkerPauseCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                port_id: PortId::from_str("icahost").unwrap(),
+                channel_id: ChannelId::from_str("channel-0").unwrap(),
+            },
+            WorkerPauseCmd::parse_from(&[
+                "test",
+                "--chain",
+                "chain_id",
+                "--port",
+                "icahost",
+                "--chan",
+                "channel-0"
+            ])
+        )
+    }
+
+    #[test]
+    fn test_worker_pause_no_channel() {
+        assert!(WorkerPauseCmd::try_parse_from(&["test", "--chain", "chain_id"]).is_err())
+    }
+
+    #[test]
+    fn test_worker_resume() {
+        assert_eq!(
+            WorkerResumeCmd {
+                chain_id: ChainId::from_string("chain_id"),
+                port_id: PortId::transfer(),
+                channel_id: ChannelId::from_str("channel-0").unwrap(),
+            },
+            WorkerResumeCmd::parse_from(&["test", "--chain", "chain_id", "--channel", "channel-0"])
+        )
+    }
+
+    #[test]
+    fn test_worker_resume_invalid_channel() {
+        assert!(WorkerResumeCmd::try_parse_from(&[
+            "test",
+            "--chain",
+            "chain_id",
+            "--channel",
+            "Channel-0"
+        ])
+        .is_err())
+    }
+}
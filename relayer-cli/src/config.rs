@@ -4,12 +4,13 @@
 //! application's configuration file and/or command-line options
 //! for specifying it.
 
-use alloc::collections::BTreeSet;
+use alloc::collections::BTreeMap;
 use std::path::PathBuf;
 
 use flex_error::{define_error, TraceError};
 use ibc::core::ics24_host::identifier::ChainId;
 use ibc_relayer::config::{ChainConfig, Config, ModeConfig};
+use ibc_relayer::denom::validate_denom;
 use tendermint_light_client_verifier::types::TrustThreshold;
 use tracing_subscriber::filter::ParseError;
 
@@ -21,6 +22,12 @@ pub fn config_path() -> Option<PathBuf> {
     app.config_path().cloned()
 }
 
+/// Get the paths to the configuration files, in the order they are merged
+pub fn config_paths() -> Vec<PathBuf> {
+    let app = app_reader();
+    app.config_paths().to_vec()
+}
+
 // Specifies all the possible syntactic errors
 // that a Hermes configuration file could contain.
 define_error! {
@@ -43,10 +50,47 @@ define_error! {
             },
 
         DuplicateChains
-            { chain_id: ChainId }
+            {
+                chain_id: ChainId,
+                first: usize,
+                second: usize,
+            }
             |e| {
-                format!("config file has duplicate entry for the chain '{0}'",
-                    e.chain_id)
+                format!("config file has duplicate entries for the chain '{0}', in the `[[chains]]` blocks #{1} and #{2}",
+                    e.chain_id, e.first, e.second)
+            },
+
+        EmptyChainId
+            { position: usize }
+            |e| {
+                format!("config file specifies an empty chain id in the `[[chains]]` block #{0}",
+                    e.position)
+            },
+
+        AmbiguousNickname
+            {
+                nickname: String,
+                chain_id: ChainId,
+                other: ChainId,
+            }
+            |e| {
+                format!("config file specifies the nickname '{0}' for the chain '{1}', which is already the identifier or nickname of the chain '{2}'",
+                    e.nickname, e.chain_id, e.other)
+            },
+
+        SharedKey
+            {
+                key_name: String,
+                key_store_folder: PathBuf,
+                first: ChainId,
+                second: ChainId,
+            }
+            |e| {
+                format!(
+                    "the chains '{0}' and '{1}' both use the key '{2}' stored in '{3}'; \
+                    if they are forks of the same network, their transactions will contend for the same account sequence",
+                    e.first, e.second, e.key_name, e.key_store_folder.display()
+                )
             },
 
         InvalidTrustThreshold
@@ -73,6 +117,17 @@ define_error! {
                 )
             },
 
+        InvalidGasPriceDenom
+            {
+                denom: String,
+                chain_id: ChainId,
+                reason: String,
+            }
+            |e| {
+                format!("config file specifies an invalid `gas_price.denom` ('{0}') for the chain '{1}', caused by: {2}",
+                    e.denom, e.chain_id, e.reason)
+            },
+
         DeprecatedGasAdjustment
             {
                 gas_adjustment: f64,
@@ -97,12 +152,20 @@ pub enum Diagnostic<E> {
 
 /// Method for syntactic validation of the input configuration file.
 pub fn validate_config(config: &Config) -> Result<(), Diagnostic<Error>> {
-    // Check for duplicate chain configuration and invalid trust thresholds
-    let mut unique_chain_ids = BTreeSet::new();
-    for c in config.chains.iter() {
-        let already_present = !unique_chain_ids.insert(c.id.clone());
-        if already_present {
-            return Err(Diagnostic::Error(Error::duplicate_chains(c.id.clone())));
+    // Check for empty and duplicate chain ids, and invalid trust thresholds.
+    // The positions of the `[[chains]]` blocks are reported starting from 1.
+    let mut positions = BTreeMap::new();
+    for (position, c) in (1..).zip(config.chains.iter()) {
+        if c.id.as_str().trim().is_empty() {
+            return Err(Diagnostic::Error(Error::empty_chain_id(position)));
+        }
+
+        if let Some(first) = positions.insert(&c.id, position) {
+            return Err(Diagnostic::Error(Error::duplicate_chains(
+                c.id.clone(),
+                first,
+                position,
+            )));
         }
 
         validate_trust_threshold(&c.id, c.trust_threshold)?;
@@ -111,9 +174,61 @@ pub fn validate_config(config: &Config) -> Result<(), Diagnostic<Error>> {
         validate_gas_settings(&c.id, c)?;
     }
 
+    validate_nicknames(&config.chains)?;
+
     // Check for invalid mode config
     validate_mode(&config.mode)?;
 
+    validate_key_usage(&config.chains)?;
+
+    Ok(())
+}
+
+/// Check that the nickname of each chain refers to no other chain, neither
+/// as its chain id nor as its nickname, so that the chains given on the
+/// command line resolve unambiguously.
+fn validate_nicknames(chains: &[ChainConfig]) -> Result<(), Diagnostic<Error>> {
+    for c in chains {
+        let nickname = match &c.nickname {
+            Some(nickname) => nickname,
+            None => continue,
+        };
+
+        let other = chains.iter().find(|other| {
+            other.id != c.id
+                && (other.id.as_str() == nickname || other.nickname.as_ref() == Some(nickname))
+        });
+
+        if let Some(other) = other {
+            return Err(Diagnostic::Error(Error::ambiguous_nickname(
+                nickname.clone(),
+                c.id.clone(),
+                other.id.clone(),
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Warn when two chains sign with the same key, i.e. use the same `key_name`
+/// in the same key store folder. Chains without a `key_store_folder` keep their
+/// keys in a folder of their own, named after their chain id.
+fn validate_key_usage(chains: &[ChainConfig]) -> Result<(), Diagnostic<Error>> {
+    let mut keys = BTreeMap::new();
+    for c in chains {
+        if let Some(folder) = &c.key_store_folder {
+            if let Some(first) = keys.insert((folder, &c.key_name), &c.id) {
+                return Err(Diagnostic::Warning(Error::shared_key(
+                    c.key_name.clone(),
+                    folder.clone(),
+                    first.clone(),
+                    c.id.clone(),
+                )));
+            }
+        }
+    }
+
     Ok(())
 }
 
@@ -180,6 +295,15 @@ fn validate_gas_settings(id: &ChainId, config: &ChainConfig) -> Result<(), Diagn
         }
     }
 
+    // Check that the fee denomination is valid, including the denominations of IBC vouchers
+    if let Err(reason) = validate_denom(&config.gas_price.denom) {
+        return Err(Diagnostic::Error(Error::invalid_gas_price_denom(
+            config.gas_price.denom.clone(),
+            id.clone(),
+            reason,
+        )));
+    }
+
     // Check that the gas_adjustment option is not set
     if let Some(gas_adjustment) = config.gas_adjustment {
         let gas_multiplier = gas_adjustment + 1.0;
@@ -193,3 +317,138 @@ fn validate_gas_settings(id: &ChainId, config: &ChainConfig) -> Result<(), Diagn
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_config, Diagnostic};
+
+    use std::path::PathBuf;
+
+    use ibc::core::ics24_host::identifier::ChainId;
+    use ibc_relayer::config::{load, Config};
+
+    fn config() -> Config {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../relayer/tests/config/fixtures/relayer_conf_example.toml"
+        );
+
+        load(path).expect("could not parse config")
+    }
+
+    fn error(config: &Config) -> String {
+        match validate_config(config) {
+            Err(Diagnostic::Error(e)) => e.detail().to_string(),
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn valid_config() {
+        assert!(validate_config(&config()).is_ok());
+    }
+
+    #[test]
+    fn duplicate_chain_ids() {
+        let mut config = config();
+        let chain = config.chains[0].clone();
+        config.chains.push(chain);
+
+        assert_eq!(
+            error(&config),
+            "config file has duplicate entries for the chain 'chain_A', in the `[[chains]]` blocks #1 and #3"
+        );
+    }
+
+    #[test]
+    fn empty_chain_ids() {
+        for id in ["", " \t"] {
+            let mut config = config();
+            config.chains[1].id = ChainId::from_string(id);
+
+            assert_eq!(
+                error(&config),
+                "config file specifies an empty chain id in the `[[chains]]` block #2"
+            );
+        }
+    }
+
+    #[test]
+    fn ambiguous_nicknames() {
+        let mut config = config();
+
+        config.chains[0].nickname = Some("chain_A".to_string());
+        config.chains[1].nickname = Some("b".to_string());
+        assert!(validate_config(&config).is_ok());
+
+        config.chains[0].nickname = Some("chain_B".to_string());
+        assert_eq!(
+            error(&config),
+            "config file specifies the nickname 'chain_B' for the chain 'chain_A', \
+            which is already the identifier or nickname of the chain 'chain_B'"
+        );
+
+        config.chains[0].nickname = Some("b".to_string());
+        assert_eq!(
+            error(&config),
+            "config file specifies the nickname 'b' for the chain 'chain_A', \
+            which is already the identifier or nickname of the chain 'chain_B'"
+        );
+    }
+
+    #[test]
+    fn shared_keys() {
+        let mut config = config();
+        let folder = PathBuf::from("/keys");
+
+        // Same key name in different folders
+        config.chains[0].key_store_folder = Some(folder.clone());
+        config.chains[1].key_store_folder = Some(PathBuf::from("/other-keys"));
+        assert!(validate_config(&config).is_ok());
+
+        config.chains[1].key_store_folder = Some(folder);
+        match validate_config(&config) {
+            Err(Diagnostic::Warning(e)) => assert_eq!(
+                e.detail().to_string(),
+                "the chains 'chain_A' and 'chain_B' both use the key 'testkey' stored in '/keys'; \
+                if they are forks of the same network, their transactions will contend for the same account sequence"
+            ),
+            other => panic!("expected a validation warning, got {:?}", other),
+        }
+
+        // Different key names in the same folder
+        config.chains[1].key_name = "otherkey".to_string();
+        assert!(validate_config(&config).is_ok());
+    }
+
+    #[test]
+    fn gas_price_voucher_denom() {
+        let mut config = config();
+        let hash = "27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2";
+
+        config.chains[0].gas_price.denom = format!("ibc/{}", hash);
+        assert!(validate_config(&config).is_ok());
+
+        config.chains[0].gas_price.denom = format!("ibc/{}", hash.to_ascii_lowercase());
+        assert_eq!(
+            error(&config),
+            format!(
+                "config file specifies an invalid `gas_price.denom` ('ibc/{0}') for the chain 'chain_A', \
+                caused by: the hash of an IBC voucher denomination must be 64 upper case hex characters, \
+                e.g. 'ibc/{1}'",
+                hash.to_ascii_lowercase(),
+                hash
+            )
+        );
+    }
+
+    #[test]
+    fn invalid_gas_price_denom() {
+        let mut config = config();
+        config.chains[1].gas_price.denom = "1stake".to_string();
+
+        assert!(error(&config).starts_with(
+            "config file specifies an invalid `gas_price.denom` ('1stake') for the chain 'chain_B'"
+        ));
+    }
+}
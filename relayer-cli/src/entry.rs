@@ -14,9 +14,22 @@ use crate::commands::CliCmd;
 #[derive(Command, Debug, Parser)]
 #[clap(author, about, version)]
 pub struct EntryPoint {
-    /// Path to the configuration file
-    #[clap(long = "config", help = "Path to configuration file")]
-    pub config: Option<PathBuf>,
+    /// Paths to the configuration files, merged in order
+    #[clap(
+        long = "config",
+        multiple_occurrences = true,
+        help = "Path to configuration file, can be repeated to override the previous files"
+    )]
+    pub config: Vec<PathBuf>,
+
+    /// Path to the home folder, overriding the `HERMES_HOME` environment variable
+    #[clap(
+        long = "home",
+        value_name = "PATH",
+        help = "Path to the home folder, holding the default configuration file and the keys; \
+                defaults to the HERMES_HOME environment variable, or else to ~/.hermes"
+    )]
+    pub home: Option<PathBuf>,
 
     /// Toggle JSON output mode one verbosity setting
     #[clap(long = "json", help = "Enable JSON output")]
@@ -42,26 +55,37 @@ impl Runnable for EntryPoint {
     }
 }
 
-impl Configurable<Config> for EntryPoint {
-    /// Path to the command's configuration file
-    fn config_path(&self) -> Option<PathBuf> {
+impl EntryPoint {
+    /// Paths to the command's configuration files, in the order they are to be merged.
+    pub fn config_paths(&self) -> Vec<PathBuf> {
         // Skip config processing for `completions`
         // and the legacy `version` subcommand.
         match &self.command {
             Some(CliCmd::Completions(_)) | Some(CliCmd::Version(_)) => {
-                return None;
+                return Vec::new();
             }
             _ => {}
         }
 
-        match &self.config {
-            // Use explicit `--config` argument if passed
-            Some(cfg) => Some(cfg.clone()),
-
-            // Otherwise defer to the toplevel command's config path logic
-            None => self.command.as_ref().and_then(|cmd| cmd.config_path()),
+        if self.config.is_empty() {
+            // Defer to the toplevel command's config path logic
+            self.command
+                .as_ref()
+                .and_then(|cmd| cmd.config_path())
+                .into_iter()
+                .collect()
+        } else {
+            // Use explicit `--config` arguments if passed
+            self.config.clone()
         }
     }
+}
+
+impl Configurable<Config> for EntryPoint {
+    /// Path to the command's first configuration file
+    fn config_path(&self) -> Option<PathBuf> {
+        self.config_paths().into_iter().next()
+    }
 
     /// Process the configuration after it has been loaded, potentially
     /// modifying it or returning an error if options are incompatible
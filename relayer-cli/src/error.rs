@@ -1,15 +1,16 @@
 //! All errors which can be raised from a command.
 
-use flex_error::define_error;
+use flex_error::{define_error, TraceError};
 
 use tendermint::Error as TendermintError;
 
-use ibc::core::ics04_channel::channel::IdentifiedChannelEnd;
-use ibc::core::ics24_host::identifier::ChainId;
+use ibc::core::ics04_channel::channel::{IdentifiedChannelEnd, State};
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
 
 use ibc_relayer::channel::ChannelError;
 use ibc_relayer::connection::ConnectionError;
 use ibc_relayer::error::Error as RelayerError;
+use ibc_relayer::fee::estimate::FeeEstimateError;
 use ibc_relayer::foreign_client::ForeignClientError;
 use ibc_relayer::link::error::LinkError;
 use ibc_relayer::spawn::SpawnError;
@@ -60,6 +61,27 @@ define_error! {
                     e.chain_id)
             },
 
+        UnknownChain
+            { chain: ChainId, configured: String }
+            | e | {
+                format_args!("unknown chain '{}', which is neither the identifier nor the nickname of a chain in the configuration file; configured chains: {}",
+                    e.chain, e.configured)
+            },
+
+        MissingAdHocChainFlags
+            { chain_id: ChainId, flags: String }
+            | e | {
+                format_args!("missing {} to use chain '{}', which is not in the configuration file",
+                    e.flags, e.chain_id)
+            },
+
+        AdHocChainConfigured
+            { chain_id: ChainId }
+            | e | {
+                format_args!("chain '{}' is in the configuration file, its parameters cannot be given on the command line",
+                    e.chain_id)
+            },
+
         MissingCounterpartyChannelId
             { channel_end: IdentifiedChannelEnd }
             | e | {
@@ -67,6 +89,59 @@ define_error! {
                     e.channel_end)
             },
 
+        TransferFile
+            { path: String, reason: String }
+            | e | {
+                format_args!("failed to read the transfer parameters from '{}': {}",
+                    e.path, e.reason)
+            },
+
+        MissingTransferParameter
+            { flag: String, key: String }
+            | e | {
+                format_args!("missing `{}`, give it on the command line or as `{}` in the file given with `--from-file`",
+                    e.flag, e.key)
+            },
+
+        ChannelNotOpen
+            { chain_id: ChainId, port_id: PortId, channel_id: ChannelId, state: State }
+            | e | {
+                format_args!("channel '{}' on port '{}' of chain '{}' is in state {}, no packets can be pending until its handshake completes",
+                    e.channel_id, e.port_id, e.chain_id, e.state)
+            },
+
+        DenomTraceMismatch
+            { chain_id: ChainId, hash: String, expected: String, actual: String }
+            | e | {
+                format_args!("chain '{}' returned denomination trace '{}' for hash {}, expected '{}'",
+                    e.chain_id, e.actual, e.hash, e.expected)
+            },
+
+        ConfigFile
+            { path: String }
+            [ TraceError<std::io::Error> ]
+            | e | {
+                format_args!("failed to update the configuration file '{}'",
+                    e.path)
+            },
+
+        KeyNameNotInConfigFile
+            { chain_id: ChainId }
+            | e | {
+                format_args!("found no `key_name` for chain '{}' in the configuration files",
+                    e.chain_id)
+            },
+
+        RestApiDisabled
+            |_| { "the REST API is disabled, enable it in the `[rest]` section of the configuration file of the running Hermes instance" },
+
+        RestApi
+            { url: String, reason: String }
+            | e | {
+                format_args!("request to the REST API at {} failed: {}",
+                    e.url, e.reason)
+            },
+
         Relayer
             [ RelayerError ]
             |_| { "relayer error" },
@@ -102,5 +177,9 @@ define_error! {
         UpgradeChain
             [ UpgradeChainError ]
             |_| { "upgrade chain error" },
+
+        FeeEstimate
+            [ FeeEstimateError ]
+            |_| { "fee estimate error" },
     }
 }
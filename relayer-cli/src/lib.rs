@@ -32,6 +32,3 @@ pub mod config;
 pub mod entry;
 pub mod error;
 pub mod prelude;
-
-/// The path to the default configuration file, relative to the home directory.
-pub const DEFAULT_CONFIG_PATH: &str = ".hermes/config.toml";
@@ -1,15 +1,20 @@
 use core::fmt::Debug;
+use std::collections::BTreeMap;
 
 use tracing::error;
 
 use crossbeam_channel as channel;
 
 use ibc::core::ics24_host::identifier::ChainId;
-use ibc_relayer::supervisor::dump_state::SupervisorState;
+use ibc_relayer::object::{Object, ObjectType};
+use ibc_relayer::path::health::PathHealth;
+use ibc_relayer::supervisor::dump_state::{SupervisorState, WorkerDesc};
 use ibc_relayer::{
-    config::ChainConfig,
+    config::sanitize::SanitizedChainConfig,
     rest::{
-        request::{reply_channel, ReplySender, Request, VersionInfo},
+        request::{
+            reply_channel, HealthInfo, KeyRotation, ReplySender, Request, RotateKey, VersionInfo,
+        },
         RestApiError,
     },
 };
@@ -51,7 +56,7 @@ pub fn all_chain_ids(sender: &channel::Sender<Request>) -> Result<Vec<ChainId>,
 pub fn chain_config(
     sender: &channel::Sender<Request>,
     chain_id: &str,
-) -> Result<ChainConfig, RestApiError> {
+) -> Result<SanitizedChainConfig, RestApiError> {
     submit_request(sender, |reply_to| Request::GetChain {
         chain_id: ChainId::from_string(chain_id),
         reply_to,
@@ -64,6 +69,44 @@ pub fn supervisor_state(
     submit_request(sender, |reply_to| Request::State { reply_to })
 }
 
+pub fn workers(
+    sender: &channel::Sender<Request>,
+) -> Result<BTreeMap<ObjectType, Vec<WorkerDesc>>, RestApiError> {
+    supervisor_state(sender).map(|state| state.workers)
+}
+
+pub fn pause_worker(
+    sender: &channel::Sender<Request>,
+    object: Object,
+) -> Result<WorkerDesc, RestApiError> {
+    submit_request(sender, |reply_to| Request::PauseWorker { object, reply_to })
+}
+
+pub fn resume_worker(
+    sender: &channel::Sender<Request>,
+    object: Object,
+) -> Result<WorkerDesc, RestApiError> {
+    submit_request(sender, |reply_to| Request::ResumeWorker {
+        object,
+        reply_to,
+    })
+}
+
+pub fn rotate_key(
+    sender: &channel::Sender<Request>,
+    request: RotateKey,
+) -> Result<KeyRotation, RestApiError> {
+    submit_request(sender, |reply_to| Request::RotateKey { request, reply_to })
+}
+
+pub fn health(sender: &channel::Sender<Request>) -> Result<HealthInfo, RestApiError> {
+    submit_request(sender, |reply_to| Request::Health { reply_to })
+}
+
+pub fn paths(sender: &channel::Sender<Request>) -> Result<Vec<PathHealth>, RestApiError> {
+    submit_request(sender, |reply_to| Request::GetPaths { reply_to })
+}
+
 pub fn assemble_version_info(sender: &channel::Sender<Request>) -> Vec<VersionInfo> {
     // Fetch the relayer library version
     let lib_version = submit_request(sender, |reply_to| Request::Version { reply_to })
@@ -1,13 +1,22 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
 use std::thread;
 
 use crossbeam_channel as channel;
 use serde::{Deserialize, Serialize};
 use tracing::{info, trace};
 
-use ibc_relayer::rest::request::Request;
+use ibc_relayer::object::Object;
+use ibc_relayer::rest::{
+    request::{Request, RotateKey},
+    RestApiError,
+};
 
 use crate::{
-    handle::{all_chain_ids, assemble_version_info, chain_config, supervisor_state},
+    handle::{
+        all_chain_ids, assemble_version_info, chain_config, health, paths, pause_worker,
+        resume_worker, rotate_key, supervisor_state, workers,
+    },
     Config,
 };
 
@@ -52,6 +61,33 @@ impl<R, E> From<Result<R, E>> for JsonResult<R, E> {
     }
 }
 
+/// Parses the body of the request into the [`Object`] of a worker.
+fn worker_object(request: &rouille::Request) -> Result<Object, RestApiError> {
+    rouille::input::json_input(request)
+        .map_err(|e| RestApiError::InvalidWorkerObject(e.to_string()))
+}
+
+/// Replies with the given value as JSON, tagged with an `ETag` derived from
+/// its contents, or with an empty `304 Not Modified` response if the client
+/// already holds that version, as told by its `If-None-Match` header.
+fn json_with_etag<T: Serialize>(request: &rouille::Request, value: &T) -> rouille::Response {
+    let body = match serde_json::to_string(value) {
+        Ok(body) => body,
+        Err(_) => return rouille::Response::json(value),
+    };
+
+    let mut hasher = DefaultHasher::new();
+    hasher.write(body.as_bytes());
+    let etag = format!("\"{:016x}\"", hasher.finish());
+
+    rouille::Response::from_data("application/json", body).with_etag(request, etag)
+}
+
+/// Parses the body of the request into a [`RotateKey`] request.
+fn key_rotation(request: &rouille::Request) -> Result<RotateKey, RestApiError> {
+    rouille::input::json_input(request).map_err(|e| RestApiError::InvalidKeyRotation(e.to_string()))
+}
+
 #[allow(clippy::manual_strip)]
 fn run(config: Config, sender: channel::Sender<Request>) -> ServerHandle {
     let server = rouille::Server::new(config.address(), move |request| {
@@ -73,7 +109,7 @@ fn run(config: Config, sender: channel::Sender<Request>) -> ServerHandle {
             (GET) (/chain/{id: String}) => {
                 trace!("[rest] GET /chain/{}", id);
                 let result = chain_config(&sender, &id);
-                rouille::Response::json(&JsonResult::from(result))
+                json_with_etag(request, &JsonResult::from(result))
             },
 
             (GET) (/state) => {
@@ -82,6 +118,42 @@ fn run(config: Config, sender: channel::Sender<Request>) -> ServerHandle {
                 rouille::Response::json(&JsonResult::from(result))
             },
 
+            (GET) (/health) => {
+                trace!("[rest] GET /health");
+                let result = health(&sender);
+                rouille::Response::json(&JsonResult::from(result))
+            },
+
+            (GET) (/paths) => {
+                trace!("[rest] GET /paths");
+                let result = paths(&sender);
+                rouille::Response::json(&JsonResult::from(result))
+            },
+
+            (GET) (/workers) => {
+                trace!("[rest] GET /workers");
+                let result = workers(&sender);
+                rouille::Response::json(&JsonResult::from(result))
+            },
+
+            (POST) (/workers/pause) => {
+                trace!("[rest] POST /workers/pause");
+                let result = worker_object(request).and_then(|object| pause_worker(&sender, object));
+                rouille::Response::json(&JsonResult::from(result))
+            },
+
+            (POST) (/workers/resume) => {
+                trace!("[rest] POST /workers/resume");
+                let result = worker_object(request).and_then(|object| resume_worker(&sender, object));
+                rouille::Response::json(&JsonResult::from(result))
+            },
+
+            (POST) (/keys/rotate) => {
+                trace!("[rest] POST /keys/rotate");
+                let result = key_rotation(request).and_then(|request| rotate_key(&sender, request));
+                rouille::Response::json(&JsonResult::from(result))
+            },
+
             _ => rouille::Response::empty_404(),
         )
     })
@@ -2,11 +2,15 @@ use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
 
-use ibc::core::ics24_host::identifier::ChainId;
+use ibc::core::ics04_channel::channel::State as ChannelState;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
 use ibc_relayer::{
-    config::ChainConfig,
-    rest::request::{Request, VersionInfo},
-    supervisor::dump_state::SupervisorState,
+    config::{sanitize::SanitizedChainConfig, ChainConfig},
+    object::{Object, Packet},
+    path::health::{PathEnd, PathHealth, PathProblem, PendingCounts},
+    rest::request::{HealthInfo, KeyRotation, Request, RotateKey, VersionInfo},
+    supervisor::dump_state::{SupervisorState, WorkerDesc},
+    worker::WorkerId,
 };
 
 use ibc_relayer_rest::{server::spawn, Config};
@@ -28,6 +32,31 @@ fn run_test<R, F>(port: u16, path: &str, expected: R, handler: F)
 where
     R: Serialize,
     F: FnOnce(Request) -> TestResult + Send + 'static,
+{
+    run_request_test(port, |url| ureq::get(url).call(), path, expected, handler)
+}
+
+fn run_post_test<B, R, F>(port: u16, path: &str, body: B, expected: R, handler: F)
+where
+    B: Serialize,
+    R: Serialize,
+    F: FnOnce(Request) -> TestResult + Send + 'static,
+{
+    let body = serde_json::to_value(&body).unwrap();
+    run_request_test(
+        port,
+        |url| ureq::post(url).send_json(body),
+        path,
+        expected,
+        handler,
+    )
+}
+
+fn run_request_test<C, R, F>(port: u16, call: C, path: &str, expected: R, handler: F)
+where
+    C: FnOnce(&str) -> Result<ureq::Response, ureq::Error>,
+    R: Serialize,
+    F: FnOnce(Request) -> TestResult + Send + 'static,
 {
     let config = Config::new("127.0.0.1".to_string(), port);
 
@@ -41,8 +70,7 @@ where
         Err(e) => panic!("got an error: {}", e),
     });
 
-    let response = ureq::get(&format!("http://127.0.0.1:{}{}", port, path))
-        .call()
+    let response = call(&format!("http://127.0.0.1:{}{}", port, path))
         .unwrap()
         .into_string()
         .unwrap();
@@ -110,9 +138,14 @@ trusting_period = '14days'
 trust_threshold = { numerator = '1', denominator = '3' }
 "#;
 
+fn mock_chain_config() -> SanitizedChainConfig {
+    let config: ChainConfig = toml::de::from_str(MOCK_CHAIN_CONFIG).unwrap();
+    SanitizedChainConfig::from(&config)
+}
+
 #[test]
 fn get_chain() {
-    let config: ChainConfig = toml::de::from_str(MOCK_CHAIN_CONFIG).unwrap();
+    let config = mock_chain_config();
     let result: JsonResult<_, ()> = JsonResult::Success(config.clone());
 
     run_test(19103, "/chain/mock-0", result, |req| match req {
@@ -124,6 +157,38 @@ fn get_chain() {
     });
 }
 
+#[test]
+fn get_chain_not_modified() {
+    let config = Config::new("127.0.0.1".to_string(), 19109);
+    let (handle, rx) = spawn(config);
+
+    std::thread::spawn(move || {
+        for _ in 0..2 {
+            match rx.recv() {
+                Ok(Request::GetChain { reply_to, .. }) => {
+                    reply_to.send(Ok(mock_chain_config())).unwrap()
+                }
+                Ok(req) => panic!("got the wrong request: {:?}", req),
+                Err(e) => panic!("got an error: {}", e),
+            }
+        }
+    });
+
+    let url = "http://127.0.0.1:19109/chain/mock-0";
+
+    let response = ureq::get(url).call().unwrap();
+    let etag = response.header("ETag").unwrap().to_string();
+    let body = response.into_string().unwrap();
+    assert!(!body.contains("key_name"));
+    assert!(!body.contains("testkey"));
+
+    let response = ureq::get(url).set("If-None-Match", &etag).call().unwrap();
+    assert_eq!(response.status(), 304);
+
+    handle.stop();
+    handle.join().unwrap();
+}
+
 #[test]
 fn state() {
     let state = SupervisorState::new(vec!["mock-0".parse().unwrap()], std::iter::empty());
@@ -137,3 +202,135 @@ fn state() {
         req => TestResult::WrongRequest(req),
     });
 }
+
+#[test]
+fn health() {
+    let health = HealthInfo {
+        live: true,
+        ready: true,
+    };
+    let result: JsonResult<_, ()> = JsonResult::Success(health.clone());
+
+    run_test(19105, "/health", result, |req| match req {
+        Request::Health { reply_to } => {
+            reply_to.send(Ok(health)).unwrap();
+            TestResult::Success
+        }
+        req => TestResult::WrongRequest(req),
+    });
+}
+
+fn packet_worker(paused: bool) -> WorkerDesc {
+    let object = Object::Packet(Packet {
+        dst_chain_id: ChainId::from_str("mock-1").unwrap(),
+        src_chain_id: ChainId::from_str("mock-0").unwrap(),
+        src_channel_id: ChannelId::new(0),
+        src_port_id: PortId::transfer(),
+    });
+
+    WorkerDesc {
+        paused,
+        ..WorkerDesc::new(WorkerId::new(1), object, None)
+    }
+}
+
+#[test]
+fn workers() {
+    let worker = packet_worker(true);
+    let state = SupervisorState {
+        chains: vec!["mock-0".parse().unwrap()],
+        workers: [(worker.object.object_type(), vec![worker])].into(),
+    };
+    let result: JsonResult<_, ()> = JsonResult::Success(state.workers.clone());
+
+    run_test(19106, "/workers", result, |req| match req {
+        Request::State { reply_to } => {
+            reply_to.send(Ok(state)).unwrap();
+            TestResult::Success
+        }
+        req => TestResult::WrongRequest(req),
+    });
+}
+
+#[test]
+fn pause_worker() {
+    let worker = packet_worker(true);
+    let object = worker.object.clone();
+    let result: JsonResult<_, ()> = JsonResult::Success(worker.clone());
+
+    run_post_test(19107, "/workers/pause", &object, result, |req| match req {
+        Request::PauseWorker { object, reply_to } if object == worker.object => {
+            reply_to.send(Ok(worker)).unwrap();
+            TestResult::Success
+        }
+        req => TestResult::WrongRequest(req),
+    });
+}
+
+#[test]
+fn resume_worker() {
+    let worker = packet_worker(false);
+    let object = worker.object.clone();
+    let result: JsonResult<_, ()> = JsonResult::Success(worker.clone());
+
+    run_post_test(19108, "/workers/resume", &object, result, |req| match req {
+        Request::ResumeWorker { object, reply_to } if object == worker.object => {
+            reply_to.send(Ok(worker)).unwrap();
+            TestResult::Success
+        }
+        req => TestResult::WrongRequest(req),
+    });
+}
+
+#[test]
+fn paths() {
+    let worker = packet_worker(true);
+    let end = |chain: &str, channel: u64| PathEnd {
+        chain_id: ChainId::from_str(chain).unwrap(),
+        port_id: PortId::transfer(),
+        channel_id: Some(ChannelId::new(channel)),
+        channel_state: Some(ChannelState::Open),
+        client: None,
+        pending: Some(PendingCounts::default()),
+    };
+    let path = PathHealth {
+        src: end("mock-0", 0),
+        dst: end("mock-1", 1),
+        problems: vec![PathProblem::WorkerPaused {
+            worker: worker.object.short_name(),
+        }],
+        workers: Some(vec![worker]),
+    };
+    let result: JsonResult<_, ()> = JsonResult::Success(vec![path.clone()]);
+
+    run_test(19109, "/paths", result, |req| match req {
+        Request::GetPaths { reply_to } => {
+            reply_to.send(Ok(vec![path])).unwrap();
+            TestResult::Success
+        }
+        req => TestResult::WrongRequest(req),
+    });
+}
+
+#[test]
+fn rotate_key() {
+    let request = RotateKey {
+        chain_id: ChainId::from_str("mock-0").unwrap(),
+        key_name: "relayer-2".to_string(),
+    };
+    let rotation = KeyRotation {
+        chain_id: request.chain_id.clone(),
+        previous_key_name: "relayer".to_string(),
+        key_name: request.key_name.clone(),
+    };
+    let result: JsonResult<_, ()> = JsonResult::Success(rotation.clone());
+    let expected = request.clone();
+
+    run_post_test(19110, "/keys/rotate", &request, result, move |req| match req {
+        Request::RotateKey { request, reply_to } if request == expected => {
+            reply_to.send(Ok(rotation)).unwrap();
+            TestResult::Success
+        }
+        req => TestResult::WrongRequest(req),
+    });
+}
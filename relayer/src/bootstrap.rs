@@ -0,0 +1,615 @@
+//! Bootstrapping of a transfer path between two chains, as done by `hermes bootstrap path`.
+//!
+//! Every step reuses what is already on chain, so that bootstrapping the same path again,
+//! for instance after an interruption, picks up where the previous attempt stopped:
+//! healthy clients, and connections and channels in any state of their handshake,
+//! whose handshake is then resumed.
+
+use core::time::Duration;
+
+use flex_error::define_error;
+use ibc::applications::transfer::acknowledgement::Acknowledgement;
+use ibc::applications::transfer::{Amount, Coin};
+use ibc::core::ics02_client::client_state::ClientState;
+use ibc::core::ics03_connection::connection::{IdentifiedConnectionEnd, State as ConnectionState};
+use ibc::core::ics04_channel::channel::{IdentifiedChannelEnd, Order, State as ChannelState};
+use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId};
+use ibc::events::IbcEvent;
+use serde::Serialize;
+use tracing::info;
+
+use crate::chain::handle::ChainHandle;
+use crate::chain::requests::{
+    PageRequest, QueryClientStatesRequest, QueryConnectionChannelsRequest, QueryConnectionsRequest,
+};
+use crate::channel::{Channel, ChannelError};
+use crate::config::default::connection_delay;
+use crate::connection::{Connection, ConnectionError};
+use crate::denom::DenomTrace;
+use crate::error::Error;
+use crate::foreign_client::{ForeignClient, ForeignClientError};
+use crate::link::error::LinkError;
+use crate::link::{Link, LinkParameters};
+use crate::object::{Channel as WorkerChannelObject, Connection as WorkerConnectionObject};
+use crate::transfer::{build_and_send_transfer_messages, TransferError, TransferOptions};
+
+/// How long the probe packets remain valid, in the time of the receiving chain.
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+define_error! {
+    BootstrapError {
+        Relayer
+            [ Error ]
+            |_| { "relayer error" },
+
+        ForeignClient
+            [ ForeignClientError ]
+            |_| { "failed to create a client" },
+
+        Connection
+            [ ConnectionError ]
+            |_| { "failed to open the connection" },
+
+        Channel
+            [ ChannelError ]
+            |_| { "failed to open the transfer channel" },
+
+        Transfer
+            [ TransferError ]
+            |_| { "failed to send the probe transfer" },
+
+        Link
+            [ LinkError ]
+            |_| { "failed to relay the probe transfer" },
+
+        MissingChannelId
+            { chain_id: ChainId }
+            |e| {
+                format!("the transfer channel has no identifier on chain '{}'",
+                    e.chain_id)
+            },
+
+        ProbeNotSent
+            { chain_id: ChainId }
+            |e| {
+                format!("the probe transfer did not send any packet on chain '{}'",
+                    e.chain_id)
+            },
+
+        ProbeNotReceived
+            { chain_id: ChainId, sequence: Sequence }
+            |e| {
+                format!("the probe packet with sequence {} was not received on chain '{}'",
+                    e.sequence, e.chain_id)
+            },
+
+        ProbeNotAcknowledged
+            { chain_id: ChainId, sequence: Sequence }
+            |e| {
+                format!("the acknowledgement of the probe packet with sequence {} was not relayed to chain '{}'",
+                    e.sequence, e.chain_id)
+            },
+
+        ProbeFailed
+            { chain_id: ChainId, sequence: Sequence, ack: String }
+            |e| {
+                format!("the probe packet with sequence {} was acknowledged with an error on chain '{}': {}",
+                    e.sequence, e.chain_id, e.ack)
+            },
+    }
+}
+
+/// What bootstrapping a path did to obtain one of its objects.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepOutcome {
+    /// The object was already set up on chain.
+    Reused,
+    /// The handshake of the object had been started, and was completed.
+    Resumed,
+    /// The object was created.
+    Created,
+}
+
+/// A client hosted on `chain_id`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ClientReport {
+    pub chain_id: ChainId,
+    pub client_id: ClientId,
+    pub outcome: StepOutcome,
+}
+
+/// A connection or channel, with its identifiers on each side.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct EndsReport<Id> {
+    pub a: Id,
+    pub b: Id,
+    pub outcome: StepOutcome,
+}
+
+/// The objects making up a bootstrapped path, and the result of its probe, if any.
+#[derive(Clone, Debug, Serialize)]
+pub struct PathReport {
+    pub a_chain_id: ChainId,
+    pub b_chain_id: ChainId,
+    pub a_client: ClientReport,
+    pub b_client: ClientReport,
+    pub connection: EndsReport<ConnectionId>,
+    pub port_id: PortId,
+    pub channel: EndsReport<ChannelId>,
+    pub probe: Option<ProbeReport>,
+}
+
+impl PathReport {
+    /// Whether the path is set up and, unless skipped, the probe succeeded.
+    pub fn is_success(&self) -> bool {
+        self.probe
+            .as_ref()
+            .map_or(true, |probe| probe.error.is_none())
+    }
+}
+
+/// The probe transfers to send over a bootstrapped path.
+#[derive(Clone, Debug)]
+pub struct ProbeOptions {
+    /// The amount of coins to send from chain `a` to chain `b`.
+    pub amount: Amount,
+    /// The denomination of the coins on chain `a`.
+    pub denom: String,
+    /// Whether to send the vouchers received on chain `b` back to chain `a`.
+    pub send_back: bool,
+}
+
+/// A probe packet, relayed and acknowledged.
+#[derive(Clone, Debug, Serialize)]
+pub struct ProbeTransfer {
+    pub src_chain_id: ChainId,
+    pub dst_chain_id: ChainId,
+    pub amount: Amount,
+    pub denom: String,
+    pub sequence: Sequence,
+    /// The acknowledgement written by the destination chain.
+    pub ack: String,
+}
+
+/// The probe transfers which completed, and the error which stopped the probe, if any.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ProbeReport {
+    pub transfers: Vec<ProbeTransfer>,
+    pub error: Option<String>,
+}
+
+/// Sets up a channel between the `transfer` ports of `a_chain` and `b_chain`, along with
+/// its connection and clients, reusing or resuming those already on chain.
+pub fn bootstrap_channel<ChainA: ChainHandle, ChainB: ChainHandle>(
+    a_chain: &ChainA,
+    b_chain: &ChainB,
+) -> Result<(Channel<ChainA, ChainB>, PathReport), BootstrapError> {
+    let (connection, a_client, b_client, connection_outcome) =
+        bootstrap_connection(a_chain, b_chain)?;
+
+    let port_id = PortId::transfer();
+    let (channel, channel_outcome) = bootstrap_transfer_channel(connection.clone(), &port_id)?;
+
+    let report = PathReport {
+        a_chain_id: a_chain.id(),
+        b_chain_id: b_chain.id(),
+        a_client,
+        b_client,
+        connection: EndsReport {
+            a: connection_id(connection.a_connection_id())?,
+            b: connection_id(connection.b_connection_id())?,
+            outcome: connection_outcome,
+        },
+        port_id,
+        channel: EndsReport {
+            a: channel_id(channel.a_channel_id(), a_chain)?,
+            b: channel_id(channel.b_channel_id(), b_chain)?,
+            outcome: channel_outcome,
+        },
+        probe: None,
+    };
+
+    Ok((channel, report))
+}
+
+/// Sends `opts.amount` coins from chain `a` to chain `b` over the `channel`, then back
+/// if `opts.send_back` is set, relaying each packet and its acknowledgement.
+pub fn probe_channel<ChainA: ChainHandle, ChainB: ChainHandle>(
+    channel: &Channel<ChainA, ChainB>,
+    opts: &ProbeOptions,
+) -> ProbeReport {
+    let mut report = ProbeReport::default();
+
+    if let Err(e) = send_probes(channel, opts, &mut report.transfers) {
+        report.error = Some(e.to_string());
+    }
+
+    report
+}
+
+fn send_probes<ChainA: ChainHandle, ChainB: ChainHandle>(
+    channel: &Channel<ChainA, ChainB>,
+    opts: &ProbeOptions,
+    transfers: &mut Vec<ProbeTransfer>,
+) -> Result<(), BootstrapError> {
+    let (a_chain, b_chain) = (channel.a_chain(), channel.b_chain());
+    let a_channel_id = channel_id(channel.a_channel_id(), &a_chain)?;
+    let b_channel_id = channel_id(channel.b_channel_id(), &b_chain)?;
+    let (a_port_id, b_port_id) = (channel.src_port_id(), channel.dst_port_id());
+
+    transfers.push(send_probe(
+        &a_chain,
+        &b_chain,
+        a_port_id,
+        &a_channel_id,
+        opts.amount,
+        opts.denom.clone(),
+    )?);
+
+    if opts.send_back {
+        let voucher = DenomTrace {
+            path: String::new(),
+            base_denom: opts.denom.clone(),
+        }
+        .transferred(a_port_id, &a_channel_id, b_port_id, &b_channel_id);
+
+        transfers.push(send_probe(
+            &b_chain,
+            &a_chain,
+            b_port_id,
+            &b_channel_id,
+            opts.amount,
+            voucher.ibc_denom(),
+        )?);
+    }
+
+    Ok(())
+}
+
+/// Sends a probe packet from `src_chain` over the given channel end, relays it to
+/// `dst_chain`, and relays its acknowledgement back to `src_chain`.
+fn send_probe<SrcChain: ChainHandle, DstChain: ChainHandle>(
+    src_chain: &SrcChain,
+    dst_chain: &DstChain,
+    port_id: &PortId,
+    channel_id: &ChannelId,
+    amount: Amount,
+    denom: String,
+) -> Result<ProbeTransfer, BootstrapError> {
+    let opts = TransferOptions {
+        packet_src_port_id: port_id.clone(),
+        packet_src_channel_id: channel_id.clone(),
+        coins: vec![Coin {
+            denom: denom.clone(),
+            amount,
+        }],
+        receiver: None,
+        timeout_height_offset: 0,
+        timeout_duration: PROBE_TIMEOUT,
+        timeout_timestamp: None,
+        number_msgs: 1,
+        fee: None,
+        fee_granter: None,
+        memo: None,
+    };
+
+    let sequence = build_and_send_transfer_messages(src_chain, dst_chain, &opts)
+        .map_err(BootstrapError::transfer)?
+        .into_iter()
+        .find_map(|event| match event {
+            IbcEvent::SendPacket(ev) => Some(ev.packet.sequence),
+            _ => None,
+        })
+        .ok_or_else(|| BootstrapError::probe_not_sent(src_chain.id()))?;
+
+    info!(
+        "sent probe packet {} of {}{} from {} to {}",
+        sequence,
+        amount,
+        denom,
+        src_chain.id(),
+        dst_chain.id()
+    );
+
+    let link = Link::new_from_opts(
+        src_chain.clone(),
+        dst_chain.clone(),
+        LinkParameters {
+            src_port_id: port_id.clone(),
+            src_channel_id: channel_id.clone(),
+        },
+        false,
+    )
+    .map_err(BootstrapError::link)?;
+
+    let ack = link
+        .relay_recv_packet_and_timeout_messages()
+        .map_err(BootstrapError::link)?
+        .into_iter()
+        .find_map(|event| match event {
+            IbcEvent::WriteAcknowledgement(ev) if ev.packet.sequence == sequence => Some(ev.ack),
+            _ => None,
+        })
+        .ok_or_else(|| BootstrapError::probe_not_received(dst_chain.id(), sequence))?;
+
+    let acknowledged = link
+        .reverse(false)
+        .and_then(|reverse| reverse.relay_ack_packet_messages())
+        .map_err(BootstrapError::link)?
+        .iter()
+        .any(|event| matches!(event, IbcEvent::AcknowledgePacket(ev) if ev.packet.sequence == sequence));
+
+    if !acknowledged {
+        return Err(BootstrapError::probe_not_acknowledged(
+            src_chain.id(),
+            sequence,
+        ));
+    }
+
+    let ack_str = String::from_utf8_lossy(&ack).into_owned();
+
+    match serde_json::from_slice(&ack) {
+        Ok(Acknowledgement::Success(_)) => Ok(ProbeTransfer {
+            src_chain_id: src_chain.id(),
+            dst_chain_id: dst_chain.id(),
+            amount,
+            denom,
+            sequence,
+            ack: ack_str,
+        }),
+        _ => Err(BootstrapError::probe_failed(
+            src_chain.id(),
+            sequence,
+            ack_str,
+        )),
+    }
+}
+
+/// Finds a connection between `a_chain` and `b_chain` whose clients are healthy, preferring
+/// an open one, and completes its handshake if needed. Creates the connection otherwise,
+/// reusing the healthy clients on each chain if any.
+#[allow(clippy::type_complexity)]
+fn bootstrap_connection<ChainA: ChainHandle, ChainB: ChainHandle>(
+    a_chain: &ChainA,
+    b_chain: &ChainB,
+) -> Result<
+    (
+        Connection<ChainA, ChainB>,
+        ClientReport,
+        ClientReport,
+        StepOutcome,
+    ),
+    BootstrapError,
+> {
+    let a_clients = healthy_clients(a_chain, b_chain)?;
+    let b_clients = healthy_clients(b_chain, a_chain)?;
+
+    let mut connections: Vec<IdentifiedConnectionEnd> = a_chain
+        .query_connections(QueryConnectionsRequest {
+            pagination: Some(PageRequest::all()),
+        })
+        .map_err(BootstrapError::relayer)?
+        .into_iter()
+        .filter(|conn| {
+            let end = conn.end();
+            !end.state_matches(&ConnectionState::Uninitialized)
+                && a_clients.contains(end.client_id())
+                && b_clients.contains(end.counterparty().client_id())
+        })
+        .collect();
+
+    // Prefer the open connections over those whose handshake is ongoing
+    connections.sort_by_key(|conn| !conn.end().is_open());
+
+    if let Some(conn) = connections.into_iter().next() {
+        let a_client = client_report(a_chain, conn.end().client_id(), StepOutcome::Reused);
+        let b_client = client_report(
+            b_chain,
+            conn.end().counterparty().client_id(),
+            StepOutcome::Reused,
+        );
+
+        let (mut connection, state) = Connection::restore_from_state(
+            a_chain.clone(),
+            b_chain.clone(),
+            WorkerConnectionObject {
+                dst_chain_id: b_chain.id(),
+                src_chain_id: a_chain.id(),
+                src_connection_id: conn.id().clone(),
+            },
+            a_chain
+                .query_latest_height()
+                .map_err(BootstrapError::relayer)?,
+        )
+        .map_err(BootstrapError::connection)?;
+
+        let counterparty_state = connection
+            .counterparty_state()
+            .map_err(BootstrapError::connection)?;
+
+        if state == ConnectionState::Open && counterparty_state == ConnectionState::Open {
+            info!("reusing open connection {} on {}", conn.id(), a_chain.id());
+            return Ok((connection, a_client, b_client, StepOutcome::Reused));
+        }
+
+        info!(
+            "resuming the handshake of connection {} on {}",
+            conn.id(),
+            a_chain.id()
+        );
+        connection.handshake().map_err(BootstrapError::connection)?;
+
+        return Ok((connection, a_client, b_client, StepOutcome::Resumed));
+    }
+
+    let (a_client, a_report) = reuse_or_create_client(a_chain, b_chain, a_clients.first())?;
+    let (b_client, b_report) = reuse_or_create_client(b_chain, a_chain, b_clients.first())?;
+
+    let connection = Connection::new(a_client, b_client, connection_delay())
+        .map_err(BootstrapError::connection)?;
+
+    Ok((connection, a_report, b_report, StepOutcome::Created))
+}
+
+/// Finds a channel between the `port_id` ports over the `connection`, preferring an open one,
+/// and completes its handshake if needed. Creates an unordered channel otherwise.
+fn bootstrap_transfer_channel<ChainA: ChainHandle, ChainB: ChainHandle>(
+    connection: Connection<ChainA, ChainB>,
+    port_id: &PortId,
+) -> Result<(Channel<ChainA, ChainB>, StepOutcome), BootstrapError> {
+    let a_chain = connection.a_chain();
+    let b_chain = connection.b_chain();
+
+    let mut channels: Vec<IdentifiedChannelEnd> = a_chain
+        .query_connection_channels(QueryConnectionChannelsRequest {
+            connection_id: connection_id(connection.a_connection_id())?,
+            pagination: Some(PageRequest::all()),
+        })
+        .map_err(BootstrapError::relayer)?
+        .into_iter()
+        .filter(|chan| {
+            let end = &chan.channel_end;
+            &chan.port_id == port_id
+                && end.counterparty().port_id() == port_id
+                && end.ordering() == &Order::Unordered
+                && !end.state_matches(&ChannelState::Uninitialized)
+                && !end.state_matches(&ChannelState::Closed)
+        })
+        .collect();
+
+    // Prefer the open channels over those whose handshake is ongoing
+    channels.sort_by_key(|chan| !chan.channel_end.is_open());
+
+    if let Some(chan) = channels.into_iter().next() {
+        let (mut channel, state) = Channel::restore_from_state(
+            a_chain.clone(),
+            b_chain,
+            WorkerChannelObject {
+                dst_chain_id: connection.b_chain().id(),
+                src_chain_id: a_chain.id(),
+                src_channel_id: chan.channel_id.clone(),
+                src_port_id: chan.port_id.clone(),
+            },
+            a_chain
+                .query_latest_height()
+                .map_err(BootstrapError::relayer)?,
+        )
+        .map_err(BootstrapError::channel)?;
+
+        let counterparty_state = channel
+            .counterparty_state()
+            .map_err(BootstrapError::channel)?;
+
+        if state == ChannelState::Open && counterparty_state == ChannelState::Open {
+            info!(
+                "reusing open channel {} on {}",
+                chan.channel_id,
+                a_chain.id()
+            );
+            return Ok((channel, StepOutcome::Reused));
+        }
+
+        info!(
+            "resuming the handshake of channel {} on {}",
+            chan.channel_id,
+            a_chain.id()
+        );
+        channel.handshake().map_err(BootstrapError::channel)?;
+
+        return Ok((channel, StepOutcome::Resumed));
+    }
+
+    let channel = Channel::new(
+        connection,
+        Order::Unordered,
+        port_id.clone(),
+        port_id.clone(),
+        None,
+    )
+    .map_err(BootstrapError::channel)?;
+
+    Ok((channel, StepOutcome::Created))
+}
+
+/// The clients hosted on `host_chain` which track `target_chain` and are neither frozen
+/// nor expired.
+fn healthy_clients<HostChain: ChainHandle, TargetChain: ChainHandle>(
+    host_chain: &HostChain,
+    target_chain: &TargetChain,
+) -> Result<Vec<ClientId>, BootstrapError> {
+    let clients = host_chain
+        .query_clients(QueryClientStatesRequest {
+            pagination: Some(PageRequest::all()),
+        })
+        .map_err(BootstrapError::relayer)?;
+
+    Ok(clients
+        .into_iter()
+        .filter(|client| client.client_state.chain_id() == target_chain.id())
+        .filter(|client| {
+            ForeignClient::restore(
+                client.client_id.clone(),
+                host_chain.clone(),
+                target_chain.clone(),
+            )
+            .validated_client_state()
+            .is_ok()
+        })
+        .map(|client| client.client_id)
+        .collect())
+}
+
+fn reuse_or_create_client<HostChain: ChainHandle, TargetChain: ChainHandle>(
+    host_chain: &HostChain,
+    target_chain: &TargetChain,
+    healthy_client: Option<&ClientId>,
+) -> Result<(ForeignClient<HostChain, TargetChain>, ClientReport), BootstrapError> {
+    match healthy_client {
+        Some(client_id) => {
+            info!("reusing client {} on {}", client_id, host_chain.id());
+            let client =
+                ForeignClient::restore(client_id.clone(), host_chain.clone(), target_chain.clone());
+
+            Ok((
+                client,
+                client_report(host_chain, client_id, StepOutcome::Reused),
+            ))
+        }
+        None => {
+            let client = ForeignClient::new(host_chain.clone(), target_chain.clone())
+                .map_err(BootstrapError::foreign_client)?;
+            let report = client_report(host_chain, client.id(), StepOutcome::Created);
+
+            Ok((client, report))
+        }
+    }
+}
+
+fn client_report<Chain: ChainHandle>(
+    chain: &Chain,
+    client_id: &ClientId,
+    outcome: StepOutcome,
+) -> ClientReport {
+    ClientReport {
+        chain_id: chain.id(),
+        client_id: client_id.clone(),
+        outcome,
+    }
+}
+
+fn connection_id(connection_id: Option<&ConnectionId>) -> Result<ConnectionId, BootstrapError> {
+    connection_id
+        .cloned()
+        .ok_or_else(|| BootstrapError::connection(ConnectionError::missing_local_connection_id()))
+}
+
+fn channel_id<Chain: ChainHandle>(
+    channel_id: Option<&ChannelId>,
+    chain: &Chain,
+) -> Result<ChannelId, BootstrapError> {
+    channel_id
+        .cloned()
+        .ok_or_else(|| BootstrapError::missing_channel_id(chain.id()))
+}
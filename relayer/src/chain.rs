@@ -1,8 +1,10 @@
+pub mod block_time;
 pub mod client;
 pub mod cosmos;
 pub mod counterparty;
 pub mod endpoint;
 pub mod handle;
+pub mod observation;
 pub mod requests;
 pub mod runtime;
 pub mod tracking;
@@ -0,0 +1,85 @@
+use core::fmt;
+use core::time::Duration;
+
+use serde::Serialize;
+
+/// Where the maximum expected time per block of a chain comes from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaxBlockTimeSource {
+    /// The `max_expected_time_per_block` parameter of the IBC connection module of the chain.
+    Chain,
+    /// The `max_block_time` setting of the chain configuration.
+    Config,
+}
+
+impl fmt::Display for MaxBlockTimeSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Chain => write!(f, "chain"),
+            Self::Config => write!(f, "config"),
+        }
+    }
+}
+
+/// The maximum expected time per block of a chain, used to convert the connection delay
+/// into a number of blocks.
+///
+/// The on-chain value is preferred over the configured one, which may drift from the
+/// actual parameter of the chain after an upgrade.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct MaxBlockTime {
+    /// The `max_expected_time_per_block` queried from the chain, if available.
+    pub on_chain: Option<Duration>,
+    /// The `max_block_time` of the chain configuration.
+    pub config: Duration,
+}
+
+impl MaxBlockTime {
+    pub fn new(on_chain: Option<Duration>, config: Duration) -> Self {
+        Self {
+            // A zero value disables the block delay on chain, and cannot be relied upon
+            on_chain: on_chain.filter(|value| !value.is_zero()),
+            config,
+        }
+    }
+
+    /// The maximum expected time per block to use for the block delay computations.
+    pub fn value(&self) -> Duration {
+        self.on_chain.unwrap_or(self.config)
+    }
+
+    /// Where the value returned by [`MaxBlockTime::value`] comes from.
+    pub fn source(&self) -> MaxBlockTimeSource {
+        if self.on_chain.is_some() {
+            MaxBlockTimeSource::Chain
+        } else {
+            MaxBlockTimeSource::Config
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefers_on_chain_value() {
+        let max_block_time =
+            MaxBlockTime::new(Some(Duration::from_secs(20)), Duration::from_secs(30));
+
+        assert_eq!(max_block_time.value(), Duration::from_secs(20));
+        assert_eq!(max_block_time.source(), MaxBlockTimeSource::Chain);
+    }
+
+    #[test]
+    fn falls_back_to_config_value() {
+        for on_chain in [None, Some(Duration::ZERO)] {
+            let max_block_time = MaxBlockTime::new(on_chain, Duration::from_secs(30));
+
+            assert_eq!(max_block_time.on_chain, None);
+            assert_eq!(max_block_time.value(), Duration::from_secs(30));
+            assert_eq!(max_block_time.source(), MaxBlockTimeSource::Config);
+        }
+    }
+}
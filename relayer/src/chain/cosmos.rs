@@ -17,12 +17,10 @@ use tendermint::{
 };
 use tendermint_light_client_verifier::types::LightBlock as TmLightBlock;
 use tendermint_proto::Protobuf;
-use tendermint_rpc::{
-    endpoint::broadcast::tx_sync::Response, endpoint::status, Client, HttpClient, Order,
-};
+use tendermint_rpc::{endpoint::status, Client, HttpClient, Order};
 use tokio::runtime::Runtime as TokioRuntime;
-use tonic::{codegen::http::Uri, metadata::AsciiMetadataValue};
-use tracing::{error, span, warn, Level};
+use tonic::metadata::AsciiMetadataValue;
+use tracing::{error, info, span, warn, Level};
 
 use ibc::clients::ics07_tendermint::consensus_state::ConsensusState as TMConsensusState;
 use ibc::clients::ics07_tendermint::header::Header as TmHeader;
@@ -37,7 +35,7 @@ use ibc::core::ics23_commitment::commitment::CommitmentPrefix;
 use ibc::core::ics24_host::identifier::{ChainId, ClientId, ConnectionId};
 use ibc::core::ics24_host::path::{
     AcksPath, ChannelEndsPath, ClientConsensusStatePath, ClientStatePath, CommitmentsPath,
-    ConnectionsPath, ReceiptsPath, SeqRecvsPath,
+    ConnectionsPath, ReceiptsPath, SeqRecvsPath, SeqSendsPath,
 };
 use ibc::core::ics24_host::{ClientUpgradePath, Path, IBC_QUERY_PATH, SDK_UPGRADE_QUERY_PATH};
 use ibc::events::IbcEvent;
@@ -52,41 +50,61 @@ use ibc_proto::cosmos::staking::v1beta1::Params as StakingParams;
 use crate::account::Balance;
 use crate::chain::client::ClientSettings;
 use crate::chain::cosmos::batch::{
-    send_batched_messages_and_wait_check_tx, send_batched_messages_and_wait_commit,
+    estimate_batched_messages_gas, send_batched_messages_and_wait_check_tx,
+    send_batched_messages_and_wait_commit,
 };
+use crate::chain::cosmos::compatibility::Outcome;
 use crate::chain::cosmos::encode::encode_to_bech32;
 use crate::chain::cosmos::gas::{calculate_fee, mul_ceil};
+use crate::chain::cosmos::grpc::GrpcEndpoint;
 use crate::chain::cosmos::query::account::get_or_fetch_account;
 use crate::chain::cosmos::query::balance::query_balance;
-use crate::chain::cosmos::query::denom_trace::query_denom_trace;
+use crate::chain::cosmos::query::connection::query_max_expected_time_per_block;
+use crate::chain::cosmos::query::denom_metadata::query_denom_metadata;
+use crate::chain::cosmos::query::denom_trace::{query_denom_trace, query_denom_traces};
+use crate::chain::cosmos::query::fee::query_incentivized_packet;
+use crate::chain::cosmos::query::header::{query_block_header_grpc, query_block_header_rpc};
+use crate::chain::cosmos::query::services::query_services;
 use crate::chain::cosmos::query::status::query_status;
-use crate::chain::cosmos::query::tx::query_txs;
+use crate::chain::cosmos::query::tx::{
+    query_channel_open_tx, query_packet_relay_txs, query_packet_tx, query_send_packet_txs,
+    query_txs, query_written_acks,
+};
 use crate::chain::cosmos::query::{abci_query, fetch_version_specs, packet_query, QueryResponse};
 use crate::chain::cosmos::types::account::Account;
 use crate::chain::cosmos::types::config::TxConfig;
 use crate::chain::cosmos::types::events::channel as channel_events;
 use crate::chain::cosmos::types::gas::{default_gas_from_config, max_gas_from_config};
-use crate::chain::endpoint::{ChainEndpoint, ChainStatus, HealthCheck};
+use crate::chain::cosmos::types::tx::TxSyncResponse;
+use crate::chain::cosmos::version::NodeVersions;
+use crate::chain::endpoint::{
+    ChainEndpoint, ChainStatus, ChannelOpenTx, HealthCheck, PacketRelayTx, PacketTx, SendPacketTx,
+    SyncStatus, WrittenAck,
+};
 use crate::chain::tracking::TrackedMsgs;
 use crate::config::ChainConfig;
-use crate::denom::DenomTrace;
+use crate::denom::{ibc_denom_hash, DenomDisplay, DenomTrace};
 use crate::error::Error;
 use crate::event::monitor::{EventMonitor, EventReceiver, TxMonitorCmd};
+use crate::fee::PacketFee;
 use crate::keyring::{KeyEntry, KeyRing};
 use crate::light_client::tendermint::LightClient as TmLightClient;
 use crate::light_client::{LightClient, Verified};
 
 use super::requests::{
-    IncludeProof, QueryBlockRequest, QueryChannelClientStateRequest, QueryChannelRequest,
-    QueryChannelsRequest, QueryClientConnectionsRequest, QueryClientStateRequest,
-    QueryClientStatesRequest, QueryConnectionChannelsRequest, QueryConnectionRequest,
-    QueryConnectionsRequest, QueryConsensusStateRequest, QueryConsensusStatesRequest, QueryHeight,
-    QueryHostConsensusStateRequest, QueryNextSequenceReceiveRequest,
+    IncludeProof, QueryBlockRequest, QueryChannelClientStateRequest, QueryChannelOpenTxRequest,
+    QueryChannelRequest, QueryChannelsRequest, QueryClientConnectionsRequest,
+    QueryClientStateRequest, QueryClientStatesRequest, QueryConnectionChannelsRequest,
+    QueryConnectionRequest, QueryConnectionsRequest, QueryConsensusStateRequest,
+    QueryConsensusStatesRequest, QueryDenomTracesRequest, QueryHeight,
+    QueryHostConsensusStateRequest, QueryIncentivizedPacketRequest,
+    QueryNextSequenceReceiveRequest, QueryNextSequenceSendRequest,
     QueryPacketAcknowledgementRequest, QueryPacketAcknowledgementsRequest,
     QueryPacketCommitmentRequest, QueryPacketCommitmentsRequest, QueryPacketEventDataRequest,
-    QueryPacketReceiptRequest, QueryTxRequest, QueryUnreceivedAcksRequest,
+    QueryPacketReceiptRequest, QueryPacketRelayTxsRequest, QueryPacketTxRequest,
+    QuerySendPacketTxsRequest, QueryTxRequest, QueryUnreceivedAcksRequest,
     QueryUnreceivedPacketsRequest, QueryUpgradedClientStateRequest,
-    QueryUpgradedConsensusStateRequest,
+    QueryUpgradedConsensusStateRequest, QueryWrittenAcksRequest,
 };
 
 pub mod batch;
@@ -94,8 +112,11 @@ pub mod client;
 pub mod compatibility;
 pub mod encode;
 pub mod estimate;
+pub mod failed_tx;
 pub mod gas;
+pub mod grpc;
 pub mod query;
+pub mod rest;
 pub mod retry;
 pub mod simulate;
 pub mod tx;
@@ -111,7 +132,7 @@ pub struct CosmosSdkChain {
     config: ChainConfig,
     tx_config: TxConfig,
     rpc_client: HttpClient,
-    grpc_addr: Uri,
+    grpc_addr: GrpcEndpoint,
     rt: Arc<TokioRuntime>,
     keybase: KeyRing,
     /// A cached copy of the account information
@@ -132,6 +153,7 @@ impl CosmosSdkChain {
     ///     - the trusting period is greater than zero
     ///     - the trusting period is smaller than the unbonding period
     ///     - the default gas is smaller than the max gas
+    ///     - the fee denomination, if it is an IBC voucher denomination, is known to the chain
     ///
     /// Emits a log warning in case any error is encountered and
     /// exits early without doing subsequent validations.
@@ -172,6 +194,25 @@ impl CosmosSdkChain {
             ));
         }
 
+        // Resolve the fee denomination if it is the denomination of an IBC voucher,
+        // so that the operator can check which coin the fees are actually paid in.
+        if let Some(hash) = ibc_denom_hash(&self.config.gas_price.denom) {
+            let trace = self.query_denom_trace(hash.to_string()).map_err(|e| {
+                Error::config_validation_unknown_fee_denom(
+                    self.id().clone(),
+                    self.config.gas_price.denom.clone(),
+                    e.to_string(),
+                )
+            })?;
+
+            info!(
+                "fees on chain '{}' are paid in '{}', the voucher of '{}'",
+                self.id(),
+                self.config.gas_price.denom,
+                trace.full_path()
+            );
+        }
+
         // Get the latest height and convert to tendermint Height
         let latest_height = TmHeight::try_from(self.query_chain_latest_height()?.revision_height())
             .map_err(Error::invalid_height)?;
@@ -228,13 +269,9 @@ impl CosmosSdkChain {
         crate::time!("query_staking_params");
         crate::telemetry!(query, self.id(), "query_staking_params");
 
-        let mut client = self
-            .block_on(
-                ibc_proto::cosmos::staking::v1beta1::query_client::QueryClient::connect(
-                    self.grpc_addr.clone(),
-                ),
-            )
-            .map_err(Error::grpc_transport)?;
+        let mut client = ibc_proto::cosmos::staking::v1beta1::query_client::QueryClient::new(
+            self.block_on(self.grpc_addr.connect())?,
+        );
 
         let request =
             tonic::Request::new(ibc_proto::cosmos::staking::v1beta1::QueryParamsRequest {});
@@ -372,6 +409,47 @@ impl CosmosSdkChain {
     ///
     /// Returns an error if the node is still syncing and has not caught up,
     /// ie. if `sync_info.catching_up` is `true`.
+    /// Check the versions of the software run by the node against the versions supported
+    /// by the relayer, logging a warning for each version which is not supported.
+    ///
+    /// Fails if a version is not supported, or if the versions cannot be queried,
+    /// when `strict_version_check` is enabled in the configuration of the chain.
+    fn check_node_versions(&self) -> Result<(), Error> {
+        let strict = self.config.strict_version_check;
+
+        let versions = match self.query_node_versions() {
+            Ok(versions) => versions,
+            Err(e) if strict => {
+                return Err(Error::unsupported_node_version(
+                    self.id().clone(),
+                    format!("failed to query the versions of the node: {}", e),
+                ))
+            }
+            Err(e) => {
+                warn!(
+                    chain = %self.id(),
+                    "failed to query the versions of the node, skipping the version check: {}", e
+                );
+
+                return Ok(());
+            }
+        };
+
+        let checks = compatibility::check_versions(&versions);
+
+        for check in checks.iter().filter(|c| c.outcome != Outcome::Pass) {
+            warn!(chain = %self.id(), "{}", check);
+        }
+
+        match checks.into_iter().find(|c| c.outcome == Outcome::Fail) {
+            Some(failed) if strict => Err(Error::unsupported_node_version(
+                self.id().clone(),
+                failed.to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
     fn chain_status(&self) -> Result<status::Response, Error> {
         let status = self
             .block_on(self.rpc_client.status())
@@ -407,18 +485,34 @@ impl CosmosSdkChain {
     ) -> Result<Vec<IbcEvent>, Error> {
         crate::time!("send_messages_and_wait_commit");
 
-        let _span =
-            span!(Level::DEBUG, "send_tx_commit", id = %tracked_msgs.tracking_id()).entered();
+        let _span = span!(
+            Level::DEBUG,
+            "send_tx_commit",
+            id = %tracked_msgs.tracking_id(),
+            correlation_id = %tracked_msgs.correlation_id()
+        )
+        .entered();
 
         let proto_msgs = tracked_msgs.msgs;
 
+        let tx_config = match tracked_msgs.fee {
+            Some(fee) => self.tx_config.with_explicit_fee(fee),
+            None => self.tx_config.clone(),
+        }
+        .with_correlation_id(tracked_msgs.correlation_id);
+
+        let tx_config = match tracked_msgs.fee_granter {
+            Some(fee_granter) => tx_config.with_fee_granter(fee_granter),
+            None => tx_config,
+        };
+
         let key_entry = self.key()?;
 
         let account =
             get_or_fetch_account(&self.grpc_addr, &key_entry.account, &mut self.account).await?;
 
-        send_batched_messages_and_wait_commit(
-            &self.tx_config,
+        let msg_events = send_batched_messages_and_wait_commit(
+            &tx_config,
             self.config.max_msg_num,
             self.config.max_tx_size,
             &key_entry,
@@ -426,27 +520,48 @@ impl CosmosSdkChain {
             &self.config.memo_prefix,
             proto_msgs,
         )
-        .await
+        .await?;
+
+        Ok(msg_events
+            .into_iter()
+            .flat_map(|msg_events| msg_events.events)
+            .collect())
     }
 
     async fn do_send_messages_and_wait_check_tx(
         &mut self,
         tracked_msgs: TrackedMsgs,
-    ) -> Result<Vec<Response>, Error> {
+    ) -> Result<Vec<TxSyncResponse>, Error> {
         crate::time!("send_messages_and_wait_check_tx");
 
-        let span = span!(Level::DEBUG, "send_tx_check", id = %tracked_msgs.tracking_id());
+        let span = span!(
+            Level::DEBUG,
+            "send_tx_check",
+            id = %tracked_msgs.tracking_id(),
+            correlation_id = %tracked_msgs.correlation_id()
+        );
         let _enter = span.enter();
 
         let proto_msgs = tracked_msgs.msgs;
 
+        let tx_config = match tracked_msgs.fee {
+            Some(fee) => self.tx_config.with_explicit_fee(fee),
+            None => self.tx_config.clone(),
+        }
+        .with_correlation_id(tracked_msgs.correlation_id);
+
+        let tx_config = match tracked_msgs.fee_granter {
+            Some(fee_granter) => tx_config.with_fee_granter(fee_granter),
+            None => tx_config,
+        };
+
         let key_entry = self.key()?;
 
         let account =
             get_or_fetch_account(&self.grpc_addr, &key_entry.account, &mut self.account).await?;
 
         send_batched_messages_and_wait_check_tx(
-            &self.tx_config,
+            &tx_config,
             self.config.max_msg_num,
             self.config.max_tx_size,
             &key_entry,
@@ -456,6 +571,31 @@ impl CosmosSdkChain {
         )
         .await
     }
+
+    async fn do_estimate_messages_gas(&mut self, tracked_msgs: &TrackedMsgs) -> Result<u64, Error> {
+        crate::time!("estimate_messages_gas");
+
+        let key_entry = self.key()?;
+
+        let account =
+            get_or_fetch_account(&self.grpc_addr, &key_entry.account, &mut self.account).await?;
+
+        let tx_config = match &tracked_msgs.fee_granter {
+            Some(fee_granter) => self.tx_config.clone().with_fee_granter(fee_granter.clone()),
+            None => self.tx_config.clone(),
+        };
+
+        estimate_batched_messages_gas(
+            &tx_config,
+            self.config.max_msg_num,
+            self.config.max_tx_size,
+            &key_entry,
+            account,
+            &self.config.memo_prefix,
+            tracked_msgs.msgs.clone(),
+        )
+        .await
+    }
 }
 
 impl ChainEndpoint for CosmosSdkChain {
@@ -470,15 +610,19 @@ impl ChainEndpoint for CosmosSdkChain {
             .map_err(|e| Error::rpc(config.rpc_addr.clone(), e))?;
 
         // Initialize key store and load key
-        let keybase = KeyRing::new(config.key_store_type, &config.account_prefix, &config.id)
-            .map_err(Error::key_base)?;
+        let keybase = KeyRing::new(
+            config.key_store_type,
+            &config.account_prefix,
+            &config.id,
+            &config.key_store_folder,
+        )
+        .map_err(Error::key_base)?;
 
-        let grpc_addr = Uri::from_str(&config.grpc_addr.to_string())
-            .map_err(|e| Error::invalid_uri(config.grpc_addr.to_string(), e))?;
+        grpc::warn_tls_config(&config);
 
-        let tx_config = TxConfig::try_from(&config)?;
+        let grpc_addr = GrpcEndpoint::from_config(&config)?;
 
-        // Retrieve the version specification of this chain
+        let tx_config = TxConfig::try_from(&config)?;
 
         let chain = Self {
             config,
@@ -490,6 +634,9 @@ impl ChainEndpoint for CosmosSdkChain {
             tx_config,
         };
 
+        // Retrieve the version specification of this chain
+        chain.check_node_versions()?;
+
         Ok(chain)
     }
 
@@ -596,12 +743,18 @@ impl ChainEndpoint for CosmosSdkChain {
     fn send_messages_and_wait_check_tx(
         &mut self,
         tracked_msgs: TrackedMsgs,
-    ) -> Result<Vec<Response>, Error> {
+    ) -> Result<Vec<TxSyncResponse>, Error> {
         let runtime = self.rt.clone();
 
         runtime.block_on(self.do_send_messages_and_wait_check_tx(tracked_msgs))
     }
 
+    fn estimate_messages_gas(&mut self, tracked_msgs: &TrackedMsgs) -> Result<u64, Error> {
+        let runtime = self.rt.clone();
+
+        runtime.block_on(self.do_estimate_messages_gas(tracked_msgs))
+    }
+
     /// Get the account for the signer
     fn get_signer(&mut self) -> Result<Signer, Error> {
         crate::time!("get_signer");
@@ -644,12 +797,55 @@ impl ChainEndpoint for CosmosSdkChain {
         Ok(())
     }
 
+    /// The requests handled by the runtime before this one were already broadcast with
+    /// the previous key. The messages built with the previous key as their signer but
+    /// submitted afterwards fail the signature check, and are regenerated by the workers
+    /// retrying them.
+    fn rotate_key(&mut self, key_name: &str) -> Result<(), Error> {
+        let key = self
+            .keybase()
+            .get_key(key_name)
+            .map_err(|e| Error::key_not_found(key_name.to_string(), e))?;
+
+        let balance = self.block_on(query_balance(
+            &self.grpc_addr,
+            &key.account,
+            &self.config.gas_price.denom,
+        ))?;
+
+        if !balance.amount.parse::<u128>().map_or(false, |amount| amount > 0) {
+            return Err(Error::key_not_funded(
+                self.id().clone(),
+                key_name.to_string(),
+                balance.denom,
+            ));
+        }
+
+        info!(
+            chain = %self.id(),
+            previous = %self.config.key_name,
+            key = %key_name,
+            "rotating the key signing the transactions"
+        );
+
+        self.config.key_name = key_name.to_string();
+
+        // The account of the new key has its own sequence number, to fetch before its first tx
+        self.account = None;
+
+        Ok(())
+    }
+
     fn ibc_version(&self) -> Result<Option<semver::Version>, Error> {
         let version_specs = self.block_on(fetch_version_specs(self.id(), &self.grpc_addr))?;
         Ok(version_specs.ibc_go_version)
     }
 
-    fn query_balance(&self, key_name: Option<String>) -> Result<Balance, Error> {
+    fn query_balance(
+        &self,
+        key_name: Option<String>,
+        denom: Option<String>,
+    ) -> Result<Balance, Error> {
         // If a key_name is given, extract the account hash.
         // Else retrieve the account from the configuration file.
         let account = match key_name {
@@ -663,21 +859,175 @@ impl ChainEndpoint for CosmosSdkChain {
             }
         };
 
-        let balance = self.block_on(query_balance(
-            &self.grpc_addr,
-            &account,
-            &self.config.gas_price.denom,
-        ))?;
+        let denom = denom.unwrap_or_else(|| self.config.gas_price.denom.clone());
+        let balance = self.block_on(query_balance(&self.grpc_addr, &account, &denom))?;
 
         Ok(balance)
     }
 
     fn query_denom_trace(&self, hash: String) -> Result<DenomTrace, Error> {
-        let denom_trace = self.block_on(query_denom_trace(&self.grpc_addr, &hash))?;
+        // Accept the full voucher denomination, ie. `ibc/{hash}`, as well as the hash alone
+        let hash = ibc_denom_hash(&hash).unwrap_or(&hash);
+
+        let denom_trace = self.block_on(query_denom_trace(&self.grpc_addr, hash))?;
 
         Ok(denom_trace)
     }
 
+    fn query_denom_traces(
+        &self,
+        request: QueryDenomTracesRequest,
+    ) -> Result<Vec<DenomTrace>, Error> {
+        crate::time!("query_denom_traces");
+        crate::telemetry!(query, self.id(), "query_denom_traces");
+
+        self.block_on(query_denom_traces(&self.grpc_addr, request))
+    }
+
+    fn query_denom_metadata(&self, denom: String) -> Result<Option<DenomDisplay>, Error> {
+        crate::time!("query_denom_metadata");
+        crate::telemetry!(query, self.id(), "query_denom_metadata");
+
+        self.block_on(query_denom_metadata(&self.grpc_addr, &denom))
+    }
+
+    fn query_incentivized_packet(
+        &self,
+        request: QueryIncentivizedPacketRequest,
+    ) -> Result<Vec<PacketFee>, Error> {
+        crate::time!("query_incentivized_packet");
+        crate::telemetry!(query, self.id(), "query_incentivized_packet");
+
+        self.block_on(query_incentivized_packet(&self.grpc_addr, request))
+    }
+
+    fn query_channel_open_tx(
+        &self,
+        request: QueryChannelOpenTxRequest,
+    ) -> Result<Option<ChannelOpenTx>, Error> {
+        crate::time!("query_channel_open_tx");
+        crate::telemetry!(query, self.id(), "query_channel_open_tx");
+
+        self.block_on(query_channel_open_tx(
+            self.id(),
+            &self.rpc_client,
+            &self.config.rpc_addr,
+            request,
+        ))
+    }
+
+    fn query_packet_relay_txs(
+        &self,
+        request: QueryPacketRelayTxsRequest,
+    ) -> Result<Vec<PacketRelayTx>, Error> {
+        crate::time!("query_packet_relay_txs");
+        crate::telemetry!(query, self.id(), "query_packet_relay_txs");
+
+        self.block_on(query_packet_relay_txs(
+            self.id(),
+            &self.rpc_client,
+            &self.config.rpc_addr,
+            request,
+        ))
+    }
+
+    fn query_send_packet_txs(
+        &self,
+        request: QuerySendPacketTxsRequest,
+    ) -> Result<Vec<SendPacketTx>, Error> {
+        crate::time!("query_send_packet_txs");
+        crate::telemetry!(query, self.id(), "query_send_packet_txs");
+
+        self.block_on(query_send_packet_txs(
+            self.id(),
+            &self.rpc_client,
+            &self.config.rpc_addr,
+            request,
+        ))
+    }
+
+    fn query_packet_tx(&self, request: QueryPacketTxRequest) -> Result<Option<PacketTx>, Error> {
+        crate::time!("query_packet_tx");
+        crate::telemetry!(query, self.id(), "query_packet_tx");
+
+        self.block_on(query_packet_tx(
+            self.id(),
+            &self.rpc_client,
+            &self.config.rpc_addr,
+            request,
+        ))
+    }
+
+    fn query_written_acks(
+        &self,
+        request: QueryWrittenAcksRequest,
+    ) -> Result<Vec<WrittenAck>, Error> {
+        crate::time!("query_written_acks");
+        crate::telemetry!(query, self.id(), "query_written_acks");
+
+        self.block_on(query_written_acks(
+            self.id(),
+            &self.rpc_client,
+            &self.config.rpc_addr,
+            request,
+        ))
+    }
+
+    fn query_services(&self) -> Result<Vec<String>, Error> {
+        crate::time!("query_services");
+        crate::telemetry!(query, self.id(), "query_services");
+
+        self.block_on(query_services(&self.grpc_addr))
+    }
+
+    fn query_allowed_clients(&self) -> Result<Vec<String>, Error> {
+        crate::time!("query_allowed_clients");
+        crate::telemetry!(query, self.id(), "query_allowed_clients");
+
+        let mut client = ibc_proto::ibc::core::client::v1::query_client::QueryClient::new(
+            self.block_on(self.grpc_addr.connect())?,
+        );
+
+        let request =
+            tonic::Request::new(ibc_proto::ibc::core::client::v1::QueryClientParamsRequest {});
+        let response = self
+            .block_on(client.client_params(request))
+            .map_err(Error::grpc_status)?
+            .into_inner();
+
+        let params = response
+            .params
+            .ok_or_else(|| Error::grpc_response_param("no client params".to_string()))?;
+
+        Ok(params.allowed_clients)
+    }
+
+    fn query_node_versions(&self) -> Result<NodeVersions, Error> {
+        crate::time!("query_node_versions");
+        crate::telemetry!(query, self.id(), "query_node_versions");
+
+        let abci_info = self
+            .block_on(self.rpc_client.abci_info())
+            .map_err(|e| Error::rpc(self.config.rpc_addr.clone(), e))?;
+
+        let status = self
+            .block_on(self.rpc_client.status())
+            .map_err(|e| Error::rpc(self.config.rpc_addr.clone(), e))?;
+
+        let version_specs = self.block_on(fetch_version_specs(self.id(), &self.grpc_addr))?;
+
+        Ok(NodeVersions {
+            app: format!("{} {}", abci_info.data, abci_info.version),
+            tendermint_version: status.node_info.version.to_string(),
+            sdk_version: version_specs.sdk_version.to_string(),
+            ibc_go_version: version_specs.ibc_go_version.map(|v| v.to_string()),
+        })
+    }
+
+    fn query_unbonding_period(&self) -> Result<Duration, Error> {
+        self.unbonding_period()
+    }
+
     fn query_commitment_prefix(&self) -> Result<CommitmentPrefix, Error> {
         crate::time!("query_commitment_prefix");
         crate::telemetry!(query, self.id(), "query_commitment_prefix");
@@ -687,6 +1037,16 @@ impl ChainEndpoint for CosmosSdkChain {
             .map_err(|_| Error::ics02(ClientError::empty_prefix()))
     }
 
+    fn query_max_expected_time_per_block(&self) -> Result<Duration, Error> {
+        crate::time!("query_max_expected_time_per_block");
+        crate::telemetry!(query, self.id(), "query_max_expected_time_per_block");
+
+        self.block_on(query_max_expected_time_per_block(
+            &self.rpc_client,
+            &self.config.rpc_addr,
+        ))
+    }
+
     /// Query the application status
     fn query_application_status(&self) -> Result<ChainStatus, Error> {
         crate::time!("query_application_status");
@@ -728,6 +1088,33 @@ impl ChainEndpoint for CosmosSdkChain {
         };
     }
 
+    /// Query the sync status of the node via the `/status` RPC endpoint.
+    ///
+    /// Unlike [`CosmosSdkChain::chain_status`], this does not fail
+    /// if the node is still catching up.
+    fn query_sync_status(&self) -> Result<SyncStatus, Error> {
+        crate::time!("query_sync_status");
+        crate::telemetry!(query, self.id(), "query_sync_status");
+
+        let status = self
+            .block_on(self.rpc_client.status())
+            .map_err(|e| Error::rpc(self.config.rpc_addr.clone(), e))?;
+
+        let height = ICSHeight::new(
+            ChainId::chain_version(status.node_info.network.as_str()),
+            u64::from(status.sync_info.latest_block_height),
+        )
+        .map_err(|_| Error::invalid_height_no_source())?;
+
+        Ok(SyncStatus {
+            height,
+            timestamp: status.sync_info.latest_block_time.into(),
+            catching_up: status.sync_info.catching_up,
+            network: ChainId::from_string(status.node_info.network.as_str()),
+            node_version: status.node_info.version.to_string(),
+        })
+    }
+
     fn query_clients(
         &self,
         request: QueryClientStatesRequest,
@@ -735,13 +1122,9 @@ impl ChainEndpoint for CosmosSdkChain {
         crate::time!("query_clients");
         crate::telemetry!(query, self.id(), "query_clients");
 
-        let mut client = self
-            .block_on(
-                ibc_proto::ibc::core::client::v1::query_client::QueryClient::connect(
-                    self.grpc_addr.clone(),
-                ),
-            )
-            .map_err(Error::grpc_transport)?;
+        let mut client = ibc_proto::ibc::core::client::v1::query_client::QueryClient::new(
+            self.block_on(self.grpc_addr.connect())?,
+        );
 
         let request = tonic::Request::new(request.into());
         let response = self
@@ -842,13 +1225,9 @@ impl ChainEndpoint for CosmosSdkChain {
         crate::time!("query_consensus_states");
         crate::telemetry!(query, self.id(), "query_consensus_states");
 
-        let mut client = self
-            .block_on(
-                ibc_proto::ibc::core::client::v1::query_client::QueryClient::connect(
-                    self.grpc_addr.clone(),
-                ),
-            )
-            .map_err(Error::grpc_transport)?;
+        let mut client = ibc_proto::ibc::core::client::v1::query_client::QueryClient::new(
+            self.block_on(self.grpc_addr.connect())?,
+        );
 
         let request = tonic::Request::new(request.into());
         let response = self
@@ -909,13 +1288,9 @@ impl ChainEndpoint for CosmosSdkChain {
         crate::time!("query_client_connections");
         crate::telemetry!(query, self.id(), "query_client_connections");
 
-        let mut client = self
-            .block_on(
-                ibc_proto::ibc::core::connection::v1::query_client::QueryClient::connect(
-                    self.grpc_addr.clone(),
-                ),
-            )
-            .map_err(Error::grpc_transport)?;
+        let mut client = ibc_proto::ibc::core::connection::v1::query_client::QueryClient::new(
+            self.block_on(self.grpc_addr.connect())?,
+        );
 
         let request = tonic::Request::new(request.into());
 
@@ -944,13 +1319,9 @@ impl ChainEndpoint for CosmosSdkChain {
         crate::time!("query_connections");
         crate::telemetry!(query, self.id(), "query_connections");
 
-        let mut client = self
-            .block_on(
-                ibc_proto::ibc::core::connection::v1::query_client::QueryClient::connect(
-                    self.grpc_addr.clone(),
-                ),
-            )
-            .map_err(Error::grpc_transport)?;
+        let mut client = ibc_proto::ibc::core::connection::v1::query_client::QueryClient::new(
+            self.block_on(self.grpc_addr.connect())?,
+        );
 
         let request = tonic::Request::new(request.into());
 
@@ -988,9 +1359,7 @@ impl ChainEndpoint for CosmosSdkChain {
             use tonic::IntoRequest;
 
             let mut client =
-                connection::query_client::QueryClient::connect(chain.grpc_addr.clone())
-                    .await
-                    .map_err(Error::grpc_transport)?;
+                connection::query_client::QueryClient::new(chain.grpc_addr.connect().await?);
 
             let mut request = connection::QueryConnectionRequest {
                 connection_id: connection_id.to_string(),
@@ -1042,11 +1411,19 @@ impl ChainEndpoint for CosmosSdkChain {
                     Some(res.proof.ok_or_else(Error::empty_response_proof)?),
                 ))
             }
-            IncludeProof::No => self
-                .block_on(async {
-                    do_query_connection(self, &request.connection_id, request.height).await
-                })
-                .map(|conn_end| (conn_end, None)),
+            IncludeProof::No => {
+                let connection_id = &request.connection_id;
+                let height = request.height;
+
+                self.block_on(self.grpc_addr.query_with_rest_fallback(
+                    "connection",
+                    do_query_connection(self, connection_id, height),
+                    |lcd| async move {
+                        rest::connection::query_connection(&lcd, connection_id, height).await
+                    },
+                ))
+                .map(|conn_end| (conn_end, None))
+            }
         }
     }
 
@@ -1057,13 +1434,9 @@ impl ChainEndpoint for CosmosSdkChain {
         crate::time!("query_connection_channels");
         crate::telemetry!(query, self.id(), "query_connection_channels");
 
-        let mut client = self
-            .block_on(
-                ibc_proto::ibc::core::channel::v1::query_client::QueryClient::connect(
-                    self.grpc_addr.clone(),
-                ),
-            )
-            .map_err(Error::grpc_transport)?;
+        let mut client = ibc_proto::ibc::core::channel::v1::query_client::QueryClient::new(
+            self.block_on(self.grpc_addr.connect())?,
+        );
 
         let request = tonic::Request::new(request.into());
 
@@ -1090,13 +1463,9 @@ impl ChainEndpoint for CosmosSdkChain {
         crate::time!("query_channels");
         crate::telemetry!(query, self.id(), "query_channels");
 
-        let mut client = self
-            .block_on(
-                ibc_proto::ibc::core::channel::v1::query_client::QueryClient::connect(
-                    self.grpc_addr.clone(),
-                ),
-            )
-            .map_err(Error::grpc_transport)?;
+        let mut client = ibc_proto::ibc::core::channel::v1::query_client::QueryClient::new(
+            self.block_on(self.grpc_addr.connect())?,
+        );
 
         let request = tonic::Request::new(request.into());
 
@@ -1145,13 +1514,9 @@ impl ChainEndpoint for CosmosSdkChain {
         crate::time!("query_channel_client_state");
         crate::telemetry!(query, self.id(), "query_channel_client_state");
 
-        let mut client = self
-            .block_on(
-                ibc_proto::ibc::core::channel::v1::query_client::QueryClient::connect(
-                    self.grpc_addr.clone(),
-                ),
-            )
-            .map_err(Error::grpc_transport)?;
+        let mut client = ibc_proto::ibc::core::channel::v1::query_client::QueryClient::new(
+            self.block_on(self.grpc_addr.connect())?,
+        );
 
         let request = tonic::Request::new(request.into());
 
@@ -1200,34 +1565,39 @@ impl ChainEndpoint for CosmosSdkChain {
         crate::time!("query_packet_commitments");
         crate::telemetry!(query, self.id(), "query_packet_commitments");
 
-        let mut client = self
-            .block_on(
-                ibc_proto::ibc::core::channel::v1::query_client::QueryClient::connect(
-                    self.grpc_addr.clone(),
-                ),
-            )
-            .map_err(Error::grpc_transport)?;
-
-        let request = tonic::Request::new(request.into());
-
-        let response = self
-            .block_on(client.packet_commitments(request))
-            .map_err(Error::grpc_status)?
-            .into_inner();
+        let grpc_request = tonic::Request::new(request.clone().into());
 
-        let mut commitment_sequences: Vec<Sequence> = response
-            .commitments
-            .into_iter()
-            .map(|v| v.sequence.into())
-            .collect();
-        commitment_sequences.sort_unstable();
+        let grpc_query = async move {
+            let mut client = ibc_proto::ibc::core::channel::v1::query_client::QueryClient::new(
+                self.grpc_addr.connect().await?,
+            );
 
-        let height = response
-            .height
-            .and_then(|raw_height| raw_height.try_into().ok())
-            .ok_or_else(|| Error::grpc_response_param("height".to_string()))?;
+            let response = client
+                .packet_commitments(grpc_request)
+                .await
+                .map_err(Error::grpc_status)?
+                .into_inner();
+
+            let mut commitment_sequences: Vec<Sequence> = response
+                .commitments
+                .into_iter()
+                .map(|v| v.sequence.into())
+                .collect();
+            commitment_sequences.sort_unstable();
+
+            let height = response
+                .height
+                .and_then(|raw_height| raw_height.try_into().ok())
+                .ok_or_else(|| Error::grpc_response_param("height".to_string()))?;
+
+            Ok((commitment_sequences, height))
+        };
 
-        Ok((commitment_sequences, height))
+        self.block_on(self.grpc_addr.query_with_rest_fallback(
+            "packet_commitments",
+            grpc_query,
+            |lcd| async move { rest::channel::query_packet_commitments(&lcd, request).await },
+        ))
     }
 
     fn query_packet_receipt(
@@ -1263,27 +1633,32 @@ impl ChainEndpoint for CosmosSdkChain {
         crate::time!("query_unreceived_packets");
         crate::telemetry!(query, self.id(), "query_unreceived_packets");
 
-        let mut client = self
-            .block_on(
-                ibc_proto::ibc::core::channel::v1::query_client::QueryClient::connect(
-                    self.grpc_addr.clone(),
-                ),
-            )
-            .map_err(Error::grpc_transport)?;
+        let grpc_request = tonic::Request::new(request.clone().into());
 
-        let request = tonic::Request::new(request.into());
+        let grpc_query = async move {
+            let mut client = ibc_proto::ibc::core::channel::v1::query_client::QueryClient::new(
+                self.grpc_addr.connect().await?,
+            );
 
-        let mut response = self
-            .block_on(client.unreceived_packets(request))
-            .map_err(Error::grpc_status)?
-            .into_inner();
+            let mut response = client
+                .unreceived_packets(grpc_request)
+                .await
+                .map_err(Error::grpc_status)?
+                .into_inner();
+
+            response.sequences.sort_unstable();
+            Ok(response
+                .sequences
+                .into_iter()
+                .map(|seq| seq.into())
+                .collect())
+        };
 
-        response.sequences.sort_unstable();
-        Ok(response
-            .sequences
-            .into_iter()
-            .map(|seq| seq.into())
-            .collect())
+        self.block_on(self.grpc_addr.query_with_rest_fallback(
+            "unreceived_packets",
+            grpc_query,
+            |lcd| async move { rest::channel::query_unreceived_packets(&lcd, request).await },
+        ))
     }
 
     fn query_packet_acknowledgement(
@@ -1319,33 +1694,38 @@ impl ChainEndpoint for CosmosSdkChain {
         crate::time!("query_packet_acknowledgements");
         crate::telemetry!(query, self.id(), "query_packet_acknowledgements");
 
-        let mut client = self
-            .block_on(
-                ibc_proto::ibc::core::channel::v1::query_client::QueryClient::connect(
-                    self.grpc_addr.clone(),
-                ),
-            )
-            .map_err(Error::grpc_transport)?;
+        let grpc_request = tonic::Request::new(request.clone().into());
 
-        let request = tonic::Request::new(request.into());
+        let grpc_query = async move {
+            let mut client = ibc_proto::ibc::core::channel::v1::query_client::QueryClient::new(
+                self.grpc_addr.connect().await?,
+            );
 
-        let response = self
-            .block_on(client.packet_acknowledgements(request))
-            .map_err(Error::grpc_status)?
-            .into_inner();
+            let response = client
+                .packet_acknowledgements(grpc_request)
+                .await
+                .map_err(Error::grpc_status)?
+                .into_inner();
 
-        let acks_sequences = response
-            .acknowledgements
-            .into_iter()
-            .map(|v| v.sequence.into())
-            .collect();
+            let acks_sequences = response
+                .acknowledgements
+                .into_iter()
+                .map(|v| v.sequence.into())
+                .collect();
 
-        let height = response
-            .height
-            .and_then(|raw_height| raw_height.try_into().ok())
-            .ok_or_else(|| Error::grpc_response_param("height".to_string()))?;
+            let height = response
+                .height
+                .and_then(|raw_height| raw_height.try_into().ok())
+                .ok_or_else(|| Error::grpc_response_param("height".to_string()))?;
 
-        Ok((acks_sequences, height))
+            Ok((acks_sequences, height))
+        };
+
+        self.block_on(self.grpc_addr.query_with_rest_fallback(
+            "packet_acknowledgements",
+            grpc_query,
+            |lcd| async move { rest::channel::query_packet_acknowledgements(&lcd, request).await },
+        ))
     }
 
     /// Queries the unreceived acknowledgements sequences associated with a channel.
@@ -1356,27 +1736,36 @@ impl ChainEndpoint for CosmosSdkChain {
         crate::time!("query_unreceived_acknowledgements");
         crate::telemetry!(query, self.id(), "query_unreceived_acknowledgements");
 
-        let mut client = self
-            .block_on(
-                ibc_proto::ibc::core::channel::v1::query_client::QueryClient::connect(
-                    self.grpc_addr.clone(),
-                ),
-            )
-            .map_err(Error::grpc_transport)?;
+        let grpc_request = tonic::Request::new(request.clone().into());
 
-        let request = tonic::Request::new(request.into());
+        let grpc_query = async move {
+            let mut client = ibc_proto::ibc::core::channel::v1::query_client::QueryClient::new(
+                self.grpc_addr.connect().await?,
+            );
 
-        let mut response = self
-            .block_on(client.unreceived_acks(request))
-            .map_err(Error::grpc_status)?
-            .into_inner();
+            let mut response = client
+                .unreceived_acks(grpc_request)
+                .await
+                .map_err(Error::grpc_status)?
+                .into_inner();
+
+            response.sequences.sort_unstable();
+            Ok(response
+                .sequences
+                .into_iter()
+                .map(|seq| seq.into())
+                .collect())
+        };
 
-        response.sequences.sort_unstable();
-        Ok(response
-            .sequences
-            .into_iter()
-            .map(|seq| seq.into())
-            .collect())
+        self.block_on(
+            self.grpc_addr.query_with_rest_fallback(
+                "unreceived_acknowledgements",
+                grpc_query,
+                |lcd| async move {
+                    rest::channel::query_unreceived_acknowledgements(&lcd, request).await
+                },
+            ),
+        )
     }
 
     fn query_next_sequence_receive(
@@ -1407,13 +1796,9 @@ impl ChainEndpoint for CosmosSdkChain {
                 Ok((seq, Some(proof)))
             }
             IncludeProof::No => {
-                let mut client = self
-                    .block_on(
-                        ibc_proto::ibc::core::channel::v1::query_client::QueryClient::connect(
-                            self.grpc_addr.clone(),
-                        ),
-                    )
-                    .map_err(Error::grpc_transport)?;
+                let mut client = ibc_proto::ibc::core::channel::v1::query_client::QueryClient::new(
+                    self.block_on(self.grpc_addr.connect())?,
+                );
 
                 let request = tonic::Request::new(request.into());
 
@@ -1427,6 +1812,38 @@ impl ChainEndpoint for CosmosSdkChain {
         }
     }
 
+    fn query_next_sequence_send(
+        &self,
+        request: QueryNextSequenceSendRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(Sequence, Option<MerkleProof>), Error> {
+        crate::time!("query_next_sequence_send");
+        crate::telemetry!(query, self.id(), "query_next_sequence_send");
+
+        // There is no gRPC endpoint for `nextSequenceSend`, so query the store directly
+        let prove = matches!(include_proof, IncludeProof::Yes);
+        let res = self.query(
+            SeqSendsPath(request.port_id, request.channel_id),
+            request.height,
+            prove,
+        )?;
+
+        // Note: We expect the return to be a u64 encoded in big-endian, as for `nextSequenceRecv`
+        if res.value.len() != 8 {
+            return Err(Error::query("next_sequence_send".into()));
+        }
+        let seq: Sequence = Bytes::from(res.value).get_u64().into();
+
+        match include_proof {
+            IncludeProof::Yes => {
+                let proof = res.proof.ok_or_else(Error::empty_response_proof)?;
+
+                Ok((seq, Some(proof)))
+            }
+            IncludeProof::No => Ok((seq, None)),
+        }
+    }
+
     /// This function queries transactions for events matching certain criteria.
     /// 1. Client Update request - returns a vector with at most one update client event
     /// 2. Packet event request - returns at most one packet event for each sequence specified
@@ -1522,22 +1939,38 @@ impl ChainEndpoint for CosmosSdkChain {
         &self,
         request: QueryHostConsensusStateRequest,
     ) -> Result<Self::ConsensusState, Error> {
+        crate::time!("query_host_consensus_state");
+        crate::telemetry!(query, self.id(), "query_host_consensus_state");
+
         let height = match request.height {
-            QueryHeight::Latest => TmHeight::from(0u32),
-            QueryHeight::Specific(ibc_height) => {
-                TmHeight::try_from(ibc_height.revision_height()).map_err(Error::invalid_height)?
-            }
+            QueryHeight::Latest => None,
+            QueryHeight::Specific(ibc_height) => Some(
+                TmHeight::try_from(ibc_height.revision_height()).map_err(Error::invalid_height)?,
+            ),
         };
 
-        // TODO(hu55a1n1): use the `/header` RPC endpoint instead when we move to tendermint v0.35.x
-        let rpc_call = match height.value() {
-            0 => self.rpc_client.latest_block(),
-            _ => self.rpc_client.block(height),
+        // The consensus state is built from the header of the block, queried with gRPC,
+        // or from the RPC endpoint when the gRPC endpoint does not serve the query.
+        let header = match self.block_on(query_block_header_grpc(&self.grpc_addr, height)) {
+            Ok(header) => header,
+            Err(e) if e.detail().is_grpc_unavailable() => {
+                warn!(
+                    "failed to query the block header of chain '{}' with gRPC, \
+                    falling back to the RPC endpoint: {}",
+                    self.id(),
+                    e
+                );
+
+                self.block_on(query_block_header_rpc(
+                    &self.rpc_client,
+                    &self.config.rpc_addr,
+                    height,
+                ))?
+            }
+            Err(e) => return Err(e),
         };
-        let response = self
-            .block_on(rpc_call)
-            .map_err(|e| Error::rpc(self.config.rpc_addr.clone(), e))?;
-        Ok(response.block.header.into())
+
+        Ok(header.into())
     }
 
     fn build_client_state(
@@ -1,14 +1,23 @@
 use core::mem;
 
+use futures::future::LocalBoxFuture;
+use ibc::core::ics02_client::msgs::update_client;
+use ibc::core::ics04_channel::msgs::{acknowledgement, recv_packet, timeout, timeout_on_close};
+use ibc::core::ics24_host::identifier::ChainId;
 use ibc::events::IbcEvent;
 use ibc_proto::google::protobuf::Any;
+use ibc_proto::ibc::core::channel::v1::{
+    MsgAcknowledgement, MsgRecvPacket, MsgTimeout, MsgTimeoutOnClose,
+};
 use prost::Message;
-use tendermint_rpc::endpoint::broadcast::tx_sync::Response;
+use tracing::warn;
 
+use crate::chain::cosmos::encode::{tx_envelope_size, tx_msg_size};
+use crate::chain::cosmos::estimate::estimate_tx_fees;
 use crate::chain::cosmos::retry::send_tx_with_account_sequence_retry;
 use crate::chain::cosmos::types::account::Account;
 use crate::chain::cosmos::types::config::TxConfig;
-use crate::chain::cosmos::types::tx::{TxStatus, TxSyncResult};
+use crate::chain::cosmos::types::tx::{MsgEvents, TxStatus, TxSyncResponse, TxSyncResult};
 use crate::chain::cosmos::wait::wait_for_block_commits;
 use crate::config::types::{MaxMsgNum, MaxTxSize, Memo};
 use crate::error::Error;
@@ -22,7 +31,7 @@ pub async fn send_batched_messages_and_wait_commit(
     account: &mut Account,
     tx_memo: &Memo,
     messages: Vec<Any>,
-) -> Result<Vec<IbcEvent>, Error> {
+) -> Result<Vec<MsgEvents>, Error> {
     if messages.is_empty() {
         return Ok(Vec::new());
     }
@@ -43,18 +52,46 @@ pub async fn send_batched_messages_and_wait_commit(
         &config.rpc_client,
         &config.rpc_address,
         &config.rpc_timeout,
+        config.failed_tx_dir.as_deref(),
+        config.correlation_id,
         &mut tx_sync_results,
     )
     .await?;
 
-    let events = tx_sync_results
-        .into_iter()
-        .flat_map(|el| el.events)
-        .collect();
+    // Index the messages among all the messages sent, rather than within their transaction
+    let mut events = Vec::new();
+
+    for tx_sync_result in tx_sync_results {
+        let offset = events.len();
+
+        for msg_events in tx_sync_result.events {
+            events.push(MsgEvents {
+                msg_index: events.len(),
+                events: msg_events
+                    .events
+                    .into_iter()
+                    .map(|event| offset_failed_msg_index(event, offset))
+                    .collect(),
+            });
+        }
+    }
 
     Ok(events)
 }
 
+/// Index the message reported as failed by the given event, if any, among all the
+/// messages sent, given the number of messages sent before those of its transaction.
+fn offset_failed_msg_index(event: IbcEvent, offset: usize) -> IbcEvent {
+    match event {
+        IbcEvent::ChainError(error) => {
+            let msg_index = error.msg_index.map(|msg_index| msg_index + offset);
+
+            IbcEvent::ChainError(error.with_msg_index(msg_index))
+        }
+        event => event,
+    }
+}
+
 pub async fn send_batched_messages_and_wait_check_tx(
     config: &TxConfig,
     max_msg_num: MaxMsgNum,
@@ -63,25 +100,54 @@ pub async fn send_batched_messages_and_wait_check_tx(
     account: &mut Account,
     tx_memo: &Memo,
     messages: Vec<Any>,
-) -> Result<Vec<Response>, Error> {
+) -> Result<Vec<TxSyncResponse>, Error> {
     if messages.is_empty() {
         return Ok(Vec::new());
     }
 
-    let batches = batch_messages(max_msg_num, max_tx_size, messages)?;
+    let envelope_size = tx_envelope_size(config, tx_memo)?;
+    let batches = batch_messages(max_msg_num, max_tx_size, envelope_size, messages)?;
 
     let mut responses = Vec::new();
 
     for batch in batches {
-        let response =
-            send_tx_with_account_sequence_retry(config, key_entry, account, tx_memo, batch).await?;
+        let sent = send_batch_in_chunks(config, key_entry, account, tx_memo, batch).await?;
 
-        responses.push(response);
+        responses.extend(sent.into_iter().map(|(_, sent)| sent));
     }
 
     Ok(responses)
 }
 
+/// Estimate the total gas needed to submit the given messages, by simulating
+/// each of the transactions they would be batched into.
+pub async fn estimate_batched_messages_gas(
+    config: &TxConfig,
+    max_msg_num: MaxMsgNum,
+    max_tx_size: MaxTxSize,
+    key_entry: &KeyEntry,
+    account: &Account,
+    tx_memo: &Memo,
+    messages: Vec<Any>,
+) -> Result<u64, Error> {
+    if messages.is_empty() {
+        return Ok(0);
+    }
+
+    let envelope_size = tx_envelope_size(config, tx_memo)?;
+    let batches = batch_messages(max_msg_num, max_tx_size, envelope_size, messages)?;
+
+    let mut estimated_gas = 0;
+
+    for batch in batches {
+        let fee = estimate_tx_fees(config, key_entry, account, tx_memo, batch).await?;
+
+        estimated_gas += fee.gas_limit;
+    }
+
+    Ok(estimated_gas)
+}
+
 async fn send_messages_as_batches(
     config: &TxConfig,
     max_msg_num: MaxMsgNum,
@@ -95,24 +161,28 @@ async fn send_messages_as_batches(
         return Ok(Vec::new());
     }
 
-    let batches = batch_messages(max_msg_num, max_tx_size, messages)?;
+    let envelope_size = tx_envelope_size(config, tx_memo)?;
+    let batches = batch_messages(max_msg_num, max_tx_size, envelope_size, messages)?;
 
-    let mut tx_sync_results = Vec::new();
+    let mut responses = Vec::new();
 
     for batch in batches {
-        let message_count = batch.len();
+        responses.extend(send_batch_in_chunks(config, key_entry, account, tx_memo, batch).await?);
+    }
 
-        let response =
-            send_tx_with_account_sequence_retry(config, key_entry, account, tx_memo, batch).await?;
+    let mut tx_sync_results = Vec::new();
 
-        if response.code.is_err() {
-            let events_per_tx = vec![IbcEvent::ChainError(format!(
-                "check_tx (broadcast_tx_sync) on chain {} for Tx hash {} reports error: code={:?}, log={:?}",
-                config.chain_id, response.hash, response.code, response.log
-            )); message_count];
+    for (message_count, sent) in responses {
+        if let Some(error) = sent.check_tx_error(&config.chain_id) {
+            let events_per_tx = (0..message_count)
+                .map(|msg_index| MsgEvents {
+                    msg_index,
+                    events: vec![error.clone()],
+                })
+                .collect();
 
             let tx_sync_result = TxSyncResult {
-                response,
+                response: sent.response,
                 events: events_per_tx,
                 status: TxStatus::ReceivedResponse,
             };
@@ -120,7 +190,7 @@ async fn send_messages_as_batches(
             tx_sync_results.push(tx_sync_result);
         } else {
             let tx_sync_result = TxSyncResult {
-                response,
+                response: sent.response,
                 events: Vec::new(),
                 status: TxStatus::Pending { message_count },
             };
@@ -132,13 +202,177 @@ async fn send_messages_as_batches(
     Ok(tx_sync_results)
 }
 
+/// Send the given batch of messages in a single transaction or, if this transaction exceeds
+/// the gas or size limits, in several transactions each carrying a chunk of the batch.
+///
+/// Returns the responses of the transactions, along with the number of messages they carry.
+async fn send_batch_in_chunks(
+    config: &TxConfig,
+    key_entry: &KeyEntry,
+    account: &mut Account,
+    tx_memo: &Memo,
+    batch: Vec<Any>,
+) -> Result<Vec<(usize, TxSyncResponse)>, Error> {
+    let mut sender = TxSender {
+        config,
+        key_entry,
+        account,
+        tx_memo,
+    };
+
+    send_in_chunks(&config.chain_id, &mut sender, batch).await
+}
+
+/// Sends the transactions carrying the chunks of a batch of messages.
+trait ChunkSender {
+    type Response;
+
+    fn send(&mut self, chunk: Vec<Any>) -> LocalBoxFuture<'_, Result<Self::Response, Error>>;
+}
+
+/// Sends the chunks to the chain, retrying on account sequence mismatches.
+struct TxSender<'a> {
+    config: &'a TxConfig,
+    key_entry: &'a KeyEntry,
+    account: &'a mut Account,
+    tx_memo: &'a Memo,
+}
+
+impl<'a> ChunkSender for TxSender<'a> {
+    type Response = TxSyncResponse;
+
+    fn send(&mut self, chunk: Vec<Any>) -> LocalBoxFuture<'_, Result<TxSyncResponse, Error>> {
+        Box::pin(send_tx_with_account_sequence_retry(
+            self.config,
+            self.key_entry,
+            self.account,
+            self.tx_memo,
+            chunk,
+        ))
+    }
+}
+
+/// Send the given batch of messages with the given sender, in chunks which are split
+/// in halves for as long as their transactions exceed the gas or size limits.
+async fn send_in_chunks<S: ChunkSender>(
+    chain_id: &ChainId,
+    sender: &mut S,
+    batch: Vec<Any>,
+) -> Result<Vec<(usize, S::Response)>, Error> {
+    let mut chunks = Chunks::new(batch);
+    let mut responses = Vec::new();
+
+    while let Some(chunk) = chunks.pop() {
+        let message_count = chunk.len();
+
+        match sender.send(chunk.clone()).await {
+            Err(e) if exceeds_tx_limits(&e) => {
+                warn!(
+                    id = %chain_id,
+                    "transaction with {} messages exceeds the limits, splitting it: {}",
+                    message_count, e
+                );
+
+                chunks.split(chunk, e)?;
+            }
+            result => responses.push((message_count, result?)),
+        }
+    }
+
+    Ok(responses)
+}
+
+/// Whether the given error reports that a transaction exceeds the configured
+/// `max_gas` or `max_tx_size`, so that its messages should be sent in smaller chunks.
+fn exceeds_tx_limits(e: &Error) -> bool {
+    use crate::error::ErrorDetail::*;
+
+    matches!(
+        e.detail(),
+        TxSimulateGasEstimateExceeded(_) | TxSizeExceeded(_)
+    )
+}
+
+/// The chunks of a batch of messages which remain to be sent, in order.
+///
+/// A chunk whose transaction exceeds the limits is split in halves, which are sent
+/// in its place, until the chunks fit or a single packet message does not fit.
+struct Chunks {
+    /// The chunks to send, the next one last.
+    pending: Vec<Vec<Any>>,
+}
+
+impl Chunks {
+    fn new(batch: Vec<Any>) -> Self {
+        Self {
+            pending: vec![batch],
+        }
+    }
+
+    /// The next chunk to send, if any.
+    fn pop(&mut self) -> Option<Vec<Any>> {
+        self.pending.pop()
+    }
+
+    /// Replace the given chunk, whose transaction failed with the given error, by its halves.
+    ///
+    /// The client updates leading the chunk are kept with its first half, and the
+    /// messages of the second half rely on the consensus states they install.
+    fn split(&mut self, mut chunk: Vec<Any>, e: Error) -> Result<(), Error> {
+        let updates = chunk
+            .iter()
+            .take_while(|message| message.type_url == update_client::TYPE_URL)
+            .count();
+
+        // A chunk made only of client updates is split like any other
+        let updates = if updates == chunk.len() { 0 } else { updates };
+
+        let packet_messages = chunk.len() - updates;
+
+        if packet_messages < 2 {
+            let message = chunk.pop().expect("chunks are never empty");
+
+            return Err(Error::message_exceeds_tx_limits(
+                message.type_url.clone(),
+                packet_sequence(&message),
+                e.to_string(),
+            ));
+        }
+
+        let second = chunk.split_off(updates + packet_messages / 2);
+
+        self.pending.push(second);
+        self.pending.push(chunk);
+
+        Ok(())
+    }
+}
+
+/// The sequence of the packet carried by the given message, if it is a packet message.
+fn packet_sequence(message: &Any) -> Option<u64> {
+    let value = message.value.as_slice();
+
+    let packet = match message.type_url.as_str() {
+        recv_packet::TYPE_URL => MsgRecvPacket::decode(value).ok()?.packet,
+        acknowledgement::TYPE_URL => MsgAcknowledgement::decode(value).ok()?.packet,
+        timeout::TYPE_URL => MsgTimeout::decode(value).ok()?.packet,
+        timeout_on_close::TYPE_URL => MsgTimeoutOnClose::decode(value).ok()?.packet,
+        _ => None,
+    };
+
+    packet.map(|packet| packet.sequence)
+}
+
+/// Batch the given messages into transactions of at most `max_msg_num` messages and
+/// `max_tx_size` bytes, each of which takes up `envelope_size` bytes besides its messages.
 fn batch_messages(
     max_msg_num: MaxMsgNum,
     max_tx_size: MaxTxSize,
+    envelope_size: usize,
     messages: Vec<Any>,
 ) -> Result<Vec<Vec<Any>>, Error> {
     let max_message_count = max_msg_num.to_usize();
-    let max_tx_size = max_tx_size.into();
+    let max_tx_size = usize::from(max_tx_size).saturating_sub(envelope_size);
 
     let mut batches = vec![];
 
@@ -147,7 +381,7 @@ fn batch_messages(
     let mut current_batch = vec![];
 
     for message in messages {
-        let message_len = message.encoded_len();
+        let message_len = tx_msg_size(&message);
 
         if message_len > max_tx_size {
             return Err(Error::message_exceeds_max_tx_size(message_len));
@@ -180,9 +414,170 @@ fn batch_messages(
 
 #[cfg(test)]
 mod tests {
-    use super::batch_messages;
+    use super::{batch_messages, exceeds_tx_limits, send_in_chunks, ChunkSender, Chunks};
     use crate::config::types::{MaxMsgNum, MaxTxSize};
+    use crate::error::{Error, ErrorDetail};
+    use futures::executor::block_on;
+    use futures::future::{self, LocalBoxFuture};
+    use ibc::core::ics02_client::msgs::update_client;
+    use ibc::core::ics04_channel::msgs::recv_packet;
+    use ibc::core::ics24_host::identifier::ChainId;
     use ibc_proto::google::protobuf::Any;
+    use ibc_proto::ibc::core::channel::v1::{MsgRecvPacket, Packet};
+    use prost::Message;
+
+    fn update_client_msg() -> Any {
+        Any {
+            type_url: update_client::TYPE_URL.into(),
+            value: vec![0; 8],
+        }
+    }
+
+    fn recv_packet_msg(sequence: u64) -> Any {
+        let msg = MsgRecvPacket {
+            packet: Some(Packet {
+                sequence,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        Any {
+            type_url: recv_packet::TYPE_URL.into(),
+            value: msg.encode_to_vec(),
+        }
+    }
+
+    fn gas_exceeded() -> Error {
+        Error::tx_simulate_gas_estimate_exceeded(chain_id(), 200, 100)
+    }
+
+    /// A sender whose transactions exceed the limits when they carry more than
+    /// `max_messages` messages, and which responds with the chunks it sent.
+    struct LimitedSender {
+        max_messages: usize,
+    }
+
+    impl ChunkSender for LimitedSender {
+        type Response = Vec<Any>;
+
+        fn send(&mut self, chunk: Vec<Any>) -> LocalBoxFuture<'_, Result<Vec<Any>, Error>> {
+            let result = if chunk.len() > self.max_messages {
+                Err(gas_exceeded())
+            } else {
+                Ok(chunk)
+            };
+
+            Box::pin(future::ready(result))
+        }
+    }
+
+    /// Send the chunks of the given batch with a [`LimitedSender`].
+    fn send_chunks(batch: Vec<Any>, max_messages: usize) -> Result<Vec<Vec<Any>>, Error> {
+        let mut sender = LimitedSender { max_messages };
+
+        let sent = block_on(send_in_chunks(&chain_id(), &mut sender, batch))?;
+
+        // The number of messages of each chunk is reported along with its response
+        assert!(sent.iter().all(|(count, chunk)| *count == chunk.len()));
+
+        Ok(sent.into_iter().map(|(_, chunk)| chunk).collect())
+    }
+
+    fn chain_id() -> ChainId {
+        ChainId::new("ibc".to_string(), 0)
+    }
+
+    #[test]
+    fn chunks_are_split_in_halves_until_they_fit() {
+        let mut batch = vec![update_client_msg()];
+        batch.extend((1..=8).map(recv_packet_msg));
+
+        let sent = send_chunks(batch.clone(), 3).unwrap();
+
+        assert_eq!(
+            sent.iter().map(Vec::len).collect::<Vec<_>>(),
+            vec![3, 2, 2, 2]
+        );
+
+        // The client update is sent first, with the first packets
+        assert_eq!(sent[0][0].type_url, update_client::TYPE_URL);
+        assert!(sent[1..]
+            .iter()
+            .flatten()
+            .all(|msg| msg.type_url == recv_packet::TYPE_URL));
+
+        // The messages are sent in order
+        assert_eq!(sent.concat(), batch);
+    }
+
+    #[test]
+    fn chunk_which_fits_is_not_split() {
+        let batch = vec![update_client_msg(), recv_packet_msg(1), recv_packet_msg(2)];
+
+        let sent = send_chunks(batch.clone(), 3).unwrap();
+
+        assert_eq!(sent, vec![batch]);
+    }
+
+    #[test]
+    fn other_errors_do_not_split_chunks() {
+        struct FailingSender;
+
+        impl ChunkSender for FailingSender {
+            type Response = ();
+
+            fn send(&mut self, _: Vec<Any>) -> LocalBoxFuture<'_, Result<(), Error>> {
+                Box::pin(future::ready(Err(Error::message_exceeds_max_tx_size(200))))
+            }
+        }
+
+        let batch = (1..=4).map(recv_packet_msg).collect();
+        let e = block_on(send_in_chunks(&chain_id(), &mut FailingSender, batch)).unwrap_err();
+
+        assert!(matches!(
+            e.detail(),
+            ErrorDetail::MessageExceedsMaxTxSize(_)
+        ));
+    }
+
+    #[test]
+    fn chunk_of_client_updates_is_split() {
+        let mut chunks = Chunks::new((0..4).map(|_| update_client_msg()).collect());
+        let chunk = chunks.pop().unwrap();
+
+        chunks.split(chunk, gas_exceeded()).unwrap();
+
+        assert_eq!(chunks.pop().map(|chunk| chunk.len()), Some(2));
+        assert_eq!(chunks.pop().map(|chunk| chunk.len()), Some(2));
+        assert!(chunks.pop().is_none());
+    }
+
+    #[test]
+    fn error_on_single_oversized_packet_message() {
+        let batch = vec![update_client_msg(), recv_packet_msg(7)];
+
+        let e = send_chunks(batch, 1).unwrap_err();
+
+        match e.detail() {
+            ErrorDetail::MessageExceedsTxLimits(detail) => {
+                assert_eq!(detail.type_url, recv_packet::TYPE_URL);
+                assert_eq!(detail.sequence, Some(7));
+            }
+            _ => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn only_limit_errors_split_chunks() {
+        assert!(exceeds_tx_limits(&gas_exceeded()));
+        assert!(exceeds_tx_limits(&Error::tx_size_exceeded(
+            chain_id(),
+            200,
+            100
+        )));
+        assert!(!exceeds_tx_limits(&Error::message_exceeds_max_tx_size(200)));
+    }
 
     #[test]
     fn batch_does_not_exceed_max_tx_size() {
@@ -200,8 +595,13 @@ mod tests {
                 value: vec![0; 2],
             },
         ];
-        let batches =
-            batch_messages(MaxMsgNum::default(), MaxTxSize::new(42).unwrap(), messages).unwrap();
+        let batches = batch_messages(
+            MaxMsgNum::default(),
+            MaxTxSize::new(46).unwrap(),
+            0,
+            messages,
+        )
+        .unwrap();
 
         assert_eq!(batches.len(), 2);
         assert_eq!(batches[0].len(), 2);
@@ -224,7 +624,8 @@ mod tests {
 
         let batches = batch_messages(
             MaxMsgNum::default(),
-            MaxTxSize::new(22).unwrap(),
+            MaxTxSize::new(24).unwrap(),
+            0,
             messages.clone(),
         )
         .unwrap();
@@ -232,7 +633,55 @@ mod tests {
         assert_eq!(batches.len(), 1);
         assert_eq!(batches[0].len(), 1);
 
-        let res = batch_messages(MaxMsgNum::default(), MaxTxSize::new(21).unwrap(), messages);
+        let res = batch_messages(
+            MaxMsgNum::default(),
+            MaxTxSize::new(23).unwrap(),
+            0,
+            messages,
+        );
+
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn envelope_is_budgeted_for() {
+        let messages = vec![
+            Any {
+                type_url: "/example.Foo".into(),
+                value: vec![0; 6],
+            },
+            Any {
+                type_url: "/example.Bar".into(),
+                value: vec![0; 6],
+            },
+        ];
+
+        let batches = batch_messages(
+            MaxMsgNum::default(),
+            MaxTxSize::new(148).unwrap(),
+            100,
+            messages.clone(),
+        )
+        .unwrap();
+
+        assert_eq!(batches.len(), 1);
+
+        let batches = batch_messages(
+            MaxMsgNum::default(),
+            MaxTxSize::new(147).unwrap(),
+            100,
+            messages.clone(),
+        )
+        .unwrap();
+
+        assert_eq!(batches.len(), 2);
+
+        let res = batch_messages(
+            MaxMsgNum::default(),
+            MaxTxSize::new(123).unwrap(),
+            100,
+            messages,
+        );
 
         assert!(res.is_err());
     }
@@ -267,6 +716,7 @@ mod tests {
         let batches = batch_messages(
             MaxMsgNum::new(1).unwrap(),
             MaxTxSize::default(),
+            0,
             messages.clone(),
         )
         .unwrap();
@@ -279,8 +729,13 @@ mod tests {
 
         // Ensure that when MaxMsgNum > the number of messages, the resulting
         // batch consists of a single smaller batch with all of the messages
-        let batches =
-            batch_messages(MaxMsgNum::new(100).unwrap(), MaxTxSize::default(), messages).unwrap();
+        let batches = batch_messages(
+            MaxMsgNum::new(100).unwrap(),
+            MaxTxSize::default(),
+            0,
+            messages,
+        )
+        .unwrap();
 
         assert_eq!(batches.len(), 1);
         assert_eq!(batches[0].len(), 5);
@@ -288,7 +743,7 @@ mod tests {
 
     #[test]
     fn test_batches_are_structured_appropriately_per_max_tx_size() {
-        // Ensure that when MaxTxSize == the size of each message, the resulting batch
+        // Ensure that when MaxTxSize == the size each message takes up, the resulting batch
         // consists of 5 smaller batches, each with a single message
         let messages = vec![
             Any {
@@ -315,7 +770,8 @@ mod tests {
 
         let batches = batch_messages(
             MaxMsgNum::default(),
-            MaxTxSize::new(26).unwrap(),
+            MaxTxSize::new(28).unwrap(),
+            0,
             messages.clone(),
         )
         .unwrap();
@@ -329,7 +785,7 @@ mod tests {
         // Ensure that when MaxTxSize > the size of all the messages, the
         // resulting batch consists of a single smaller batch with all of
         // messages inside
-        let batches = batch_messages(MaxMsgNum::default(), MaxTxSize::max(), messages).unwrap();
+        let batches = batch_messages(MaxMsgNum::default(), MaxTxSize::max(), 0, messages).unwrap();
 
         assert_eq!(batches.len(), 1);
         assert_eq!(batches[0].len(), 5);
@@ -338,6 +794,6 @@ mod tests {
     #[test]
     #[should_panic(expected = "`max_msg_num` must be greater than or equal to 1, found 0")]
     fn test_max_msg_num_of_zero_panics() {
-        let _batches = batch_messages(MaxMsgNum::new(0).unwrap(), MaxTxSize::default(), vec![]);
+        let _batches = batch_messages(MaxMsgNum::new(0).unwrap(), MaxTxSize::default(), 0, vec![]);
     }
 }
@@ -1,9 +1,12 @@
 //! Cosmos-SDK compatibility constants and diagnostic methods.
 
+use core::fmt;
+
+use serde::Serialize;
 use thiserror::Error;
 use tracing::debug;
 
-use super::version;
+use super::version::{self, NodeVersions};
 
 /// Specifies the SDK module version requirement.
 ///
@@ -22,6 +25,129 @@ const SDK_MODULE_VERSION_REQ: &str = ">=0.41, <0.46";
 /// [features]: https://hermes.informal.systems/features.html
 const IBC_GO_MODULE_VERSION_REQ: &str = ">=1.1, <=3";
 
+/// Specifies the requirement on the Tendermint version run by the nodes,
+/// whose RPC endpoints the relayer must be able to query.
+///
+/// # Note: Should be consistent with [features] guide page.
+///
+/// [features]: https://hermes.informal.systems/features.html
+const TENDERMINT_VERSION_REQ: &str = ">=0.34, <0.35";
+
+/// The outcome of the check of the version of a component run by a node.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    /// The version is supported.
+    Pass,
+    /// The version could not be determined.
+    Warn,
+    /// The version is not supported.
+    Fail,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Pass => write!(f, "pass"),
+            Outcome::Warn => write!(f, "warn"),
+            Outcome::Fail => write!(f, "fail"),
+        }
+    }
+}
+
+/// The check of the version of a component run by a node against
+/// the versions of that component supported by the relayer.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct VersionCheck {
+    pub component: &'static str,
+    /// The version of the component reported by the node, if any.
+    pub detected: Option<String>,
+    /// The requirement on the version of the component.
+    pub supported: &'static str,
+    pub outcome: Outcome,
+}
+
+impl fmt::Display for VersionCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let detected = self.detected.as_deref().unwrap_or("none");
+
+        match self.outcome {
+            Outcome::Pass => write!(
+                f,
+                "{} version '{}' is supported ({})",
+                self.component, detected, self.supported
+            ),
+            Outcome::Warn => write!(
+                f,
+                "{} version '{}' could not be checked against the supported versions {}",
+                self.component, detected, self.supported
+            ),
+            Outcome::Fail => write!(
+                f,
+                "{} version '{}' does not meet compatibility requirements {}",
+                self.component, detected, self.supported
+            ),
+        }
+    }
+}
+
+/// Checks the versions of the Tendermint, SDK and IBC-go software run
+/// by a node against the versions supported by the relayer.
+///
+/// A node without the standalone IBC-go module passes its check,
+/// and a version which cannot be parsed only raises a warning.
+pub fn check_versions(versions: &NodeVersions) -> Vec<VersionCheck> {
+    vec![
+        check_version(
+            "Tendermint",
+            Some(&versions.tendermint_version),
+            TENDERMINT_VERSION_REQ,
+        ),
+        check_version(
+            "Cosmos SDK",
+            Some(&versions.sdk_version),
+            SDK_MODULE_VERSION_REQ,
+        ),
+        check_version(
+            "IBC-go",
+            versions.ibc_go_version.as_deref(),
+            IBC_GO_MODULE_VERSION_REQ,
+        ),
+    ]
+}
+
+fn check_version(
+    component: &'static str,
+    detected: Option<&str>,
+    supported: &'static str,
+) -> VersionCheck {
+    let requirements =
+        semver::VersionReq::parse(supported).expect("parsing the version requirements into semver");
+
+    let outcome = match detected.map(parse_version) {
+        None => Outcome::Pass,
+        Some(Ok(version)) if requirements.matches(&version) => Outcome::Pass,
+        Some(Ok(_)) => Outcome::Fail,
+        Some(Err(_)) => Outcome::Warn,
+    };
+
+    VersionCheck {
+        component,
+        detected: detected.map(ToString::to_string),
+        supported,
+        outcome,
+    }
+}
+
+/// Parses a version with an optional leading 'v', ignoring its pre-release
+/// identifier so that pre-releases are treated as their normal version.
+fn parse_version(raw_version: &str) -> Result<semver::Version, semver::Error> {
+    let mut version = semver::Version::parse(raw_version.trim_start_matches('v'))?;
+    version.pre = semver::Prerelease::EMPTY;
+
+    Ok(version)
+}
+
 #[derive(Error, Debug)]
 pub enum Diagnostic {
     #[error(
@@ -85,3 +211,57 @@ fn ibc_go_diagnostic(version_info: Option<semver::Version>) -> Result<(), Diagno
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{check_versions, Outcome};
+    use crate::chain::cosmos::version::NodeVersions;
+
+    fn versions(tendermint: &str, sdk: &str, ibc_go: Option<&str>) -> NodeVersions {
+        NodeVersions {
+            app: "gaiad v7.0.1".to_string(),
+            tendermint_version: tendermint.to_string(),
+            sdk_version: sdk.to_string(),
+            ibc_go_version: ibc_go.map(ToString::to_string),
+        }
+    }
+
+    fn outcomes(versions: &NodeVersions) -> Vec<Outcome> {
+        check_versions(versions)
+            .into_iter()
+            .map(|check| check.outcome)
+            .collect()
+    }
+
+    #[test]
+    fn supported_versions_pass() {
+        assert_eq!(
+            outcomes(&versions("0.34.19", "0.45.4", Some("3.0.0"))),
+            vec![Outcome::Pass, Outcome::Pass, Outcome::Pass]
+        );
+
+        // Pre-releases are treated as their normal version, and
+        // chains without the standalone IBC-go module are supported
+        assert_eq!(
+            outcomes(&versions("v0.34.21-rc1", "v0.42.0-rc2", None)),
+            vec![Outcome::Pass, Outcome::Pass, Outcome::Pass]
+        );
+    }
+
+    #[test]
+    fn unsupported_versions_fail() {
+        assert_eq!(
+            outcomes(&versions("0.37.0", "0.47.1", Some("7.0.0"))),
+            vec![Outcome::Fail, Outcome::Fail, Outcome::Fail]
+        );
+    }
+
+    #[test]
+    fn unparsable_versions_warn() {
+        let checks = check_versions(&versions("unknown", "0.45.4", Some("3.0.0")));
+
+        assert_eq!(checks[0].outcome, Outcome::Warn);
+        assert_eq!(checks[0].detected.as_deref(), Some("unknown"));
+        assert_eq!(checks[0].supported, ">=0.34, <0.35");
+    }
+}
@@ -6,11 +6,14 @@ use ibc_proto::cosmos::tx::v1beta1::{AuthInfo, Fee, ModeInfo, SignDoc, SignerInf
 use ibc_proto::google::protobuf::Any;
 use tendermint::account::Id as AccountId;
 
+use crate::chain::cosmos::gas::explicit_fee_to_fee;
 use crate::chain::cosmos::types::account::{Account, AccountNumber, AccountSequence};
 use crate::chain::cosmos::types::config::TxConfig;
+use crate::chain::cosmos::types::extension::extension_options;
+use crate::chain::cosmos::types::gas::GasConfig;
 use crate::chain::cosmos::types::tx::SignedTx;
 use crate::config::types::Memo;
-use crate::config::AddressType;
+use crate::config::{AddressType, ChainConfig};
 use crate::error::Error;
 use crate::keyring::{sign_message, KeyEntry};
 
@@ -45,7 +48,8 @@ pub fn sign_tx(
 
     let signer = encode_signer_info(&config.address_type, account.sequence, key_bytes)?;
 
-    let (body, body_bytes) = tx_body_and_bytes(messages, tx_memo)?;
+    let (body, body_bytes) =
+        tx_body_and_bytes(messages, tx_memo, config.extension_options.clone())?;
 
     let (auth_info, auth_info_bytes) = auth_info_and_bytes(signer, fee.clone())?;
 
@@ -67,6 +71,78 @@ pub fn sign_tx(
     })
 }
 
+/// The size a message takes up in the body of a transaction: its encoding, along with
+/// the tag and the length which prefix it.
+pub fn tx_msg_size(message: &Any) -> usize {
+    let len = prost::Message::encoded_len(message);
+
+    1 + prost::length_delimiter_len(len) + len
+}
+
+/// An upper bound of the size a transaction signed with the given configuration takes
+/// up besides its messages, i.e. the size of its memo, extension options, fee, signer
+/// info and signature.
+///
+/// The messages of a transaction of at most `config.max_tx_size` bytes must then fit
+/// in the remaining bytes, each taking up [`tx_msg_size`] bytes.
+pub fn tx_envelope_size(config: &TxConfig, tx_memo: &Memo) -> Result<usize, Error> {
+    let gas_config = &config.gas_config;
+
+    // The fee paid is at most the max fee, unless the fee is given explicitly
+    let fee = match &gas_config.explicit_fee {
+        Some(explicit_fee) => explicit_fee_to_fee(gas_config, explicit_fee),
+        None => gas_config.max_fee.clone(),
+    };
+
+    envelope_size(
+        &config.address_type,
+        config.extension_options.clone(),
+        fee,
+        tx_memo,
+        config.max_tx_size.into(),
+    )
+}
+
+/// The [`tx_envelope_size`] of the transactions sent to the chain with the given configuration.
+pub fn chain_tx_envelope_size(config: &ChainConfig) -> Result<usize, Error> {
+    envelope_size(
+        &config.address_type,
+        extension_options(config)?,
+        GasConfig::from(config).max_fee,
+        &config.memo_prefix,
+        config.max_tx_size.into(),
+    )
+}
+
+fn envelope_size(
+    address_type: &AddressType,
+    extension_options: Vec<Any>,
+    fee: Fee,
+    tx_memo: &Memo,
+    max_tx_size: usize,
+) -> Result<usize, Error> {
+    // A compressed secp256k1 public key, encoded as by `encode_key_bytes`,
+    // and the largest account sequence
+    let key_bytes = prost::Message::encode_to_vec(&vec![0_u8; 33]);
+    let signer = encode_signer_info(address_type, AccountSequence::new(u64::MAX), key_bytes)?;
+
+    let (_, body_bytes) = tx_body_and_bytes(vec![], tx_memo, extension_options)?;
+    let (_, auth_info_bytes) = auth_info_and_bytes(signer, fee)?;
+
+    let tx_raw = TxRaw {
+        // The body is accounted for below, as its length grows with the messages
+        body_bytes: vec![],
+        auth_info_bytes,
+        // A compact secp256k1 signature
+        signatures: vec![vec![0; 64]],
+    };
+
+    // The tag of the body, and its length, which is at most the size of the transaction
+    let body_prefix_size = 1 + prost::length_delimiter_len(max_tx_size);
+
+    Ok(encode_tx_raw(tx_raw)?.len() + body_prefix_size + body_bytes.len())
+}
+
 fn encode_key_bytes(key: &KeyEntry) -> Result<Vec<u8>, Error> {
     let mut pk_buf = Vec::new();
 
@@ -159,13 +235,17 @@ fn auth_info_and_bytes(signer_info: SignerInfo, fee: Fee) -> Result<(AuthInfo, V
     Ok((auth_info, auth_buf))
 }
 
-fn tx_body_and_bytes(proto_msgs: Vec<Any>, memo: &Memo) -> Result<(TxBody, Vec<u8>), Error> {
+fn tx_body_and_bytes(
+    proto_msgs: Vec<Any>,
+    memo: &Memo,
+    extension_options: Vec<Any>,
+) -> Result<(TxBody, Vec<u8>), Error> {
     // Create TxBody
     let body = TxBody {
         messages: proto_msgs.to_vec(),
         memo: memo.to_string(),
         timeout_height: 0_u64,
-        extension_options: Vec::<Any>::new(),
+        extension_options,
         non_critical_extension_options: Vec::<Any>::new(),
     };
 
@@ -177,3 +257,80 @@ fn tx_body_and_bytes(proto_msgs: Vec<Any>, memo: &Memo) -> Result<(TxBody, Vec<u
 
     Ok((body, body_buf))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        auth_info_and_bytes, chain_tx_envelope_size, encode_signer_info, encode_tx_raw,
+        tx_body_and_bytes, tx_msg_size,
+    };
+
+    use ibc_proto::cosmos::tx::v1beta1::TxRaw;
+    use ibc_proto::google::protobuf::Any;
+
+    use crate::chain::cosmos::types::account::AccountSequence;
+    use crate::chain::cosmos::types::extension::extension_options;
+    use crate::chain::cosmos::types::gas::GasConfig;
+    use crate::chain::mock::test_utils::get_basic_chain_config;
+    use crate::config::types::Memo;
+    use crate::config::TxExtension;
+
+    /// The body of an empty transaction with the memo `hermes` and an
+    /// `ExtensionOptionDynamicFeeTx` with a maximum priority price of 1 gwei.
+    const DYNAMIC_FEE_TX_BODY: &str = "12066865726d6573fa3f3f0a2f2f65746865726d696e742e74797065732e76312e457874656e73696f6e4f7074696f6e44796e616d69634665655478120c0a0a31303030303030303030";
+
+    #[test]
+    fn tx_body_with_dynamic_fee_extension() {
+        let mut config = get_basic_chain_config("evmos_9001-2");
+        config.tx_extension = Some(TxExtension::EthermintDynamicFee);
+        config.max_priority_price = 1_000_000_000;
+
+        let memo = Memo::new("hermes".to_string()).unwrap();
+
+        let (body, body_bytes) =
+            tx_body_and_bytes(vec![], &memo, extension_options(&config).unwrap()).unwrap();
+
+        assert_eq!(body.extension_options.len(), 1);
+        assert!(body.non_critical_extension_options.is_empty());
+        assert_eq!(hex::encode(body_bytes), DYNAMIC_FEE_TX_BODY);
+    }
+
+    #[test]
+    fn signed_tx_fits_in_envelope_and_messages() {
+        let mut config = get_basic_chain_config("ibc-0");
+        config.memo_prefix = Memo::new("hermes".to_string()).unwrap();
+
+        let messages = (1..=50)
+            .map(|i| Any {
+                type_url: "/example.Foo".into(),
+                value: vec![0; 10 * i],
+            })
+            .collect::<Vec<_>>();
+
+        let messages_size: usize = messages.iter().map(tx_msg_size).sum();
+
+        let key_bytes = prost::Message::encode_to_vec(&vec![1_u8; 33]);
+        let signer =
+            encode_signer_info(&config.address_type, AccountSequence::new(42), key_bytes).unwrap();
+
+        let (_, body_bytes) = tx_body_and_bytes(
+            messages,
+            &config.memo_prefix,
+            extension_options(&config).unwrap(),
+        )
+        .unwrap();
+        let (_, auth_info_bytes) =
+            auth_info_and_bytes(signer, GasConfig::from(&config).max_fee).unwrap();
+
+        let tx_bytes = encode_tx_raw(TxRaw {
+            body_bytes,
+            auth_info_bytes,
+            signatures: vec![vec![1; 64]],
+        })
+        .unwrap();
+
+        let envelope_size = chain_tx_envelope_size(&config).unwrap();
+
+        assert!(tx_bytes.len() <= envelope_size + messages_size);
+    }
+}
@@ -1,11 +1,11 @@
 use ibc::core::ics24_host::identifier::ChainId;
 use ibc_proto::cosmos::tx::v1beta1::{Fee, Tx};
 use ibc_proto::google::protobuf::Any;
-use tonic::codegen::http::Uri;
 use tracing::{debug, error, span, warn, Level};
 
 use crate::chain::cosmos::encode::sign_tx;
-use crate::chain::cosmos::gas::{gas_amount_to_fee, PrettyFee};
+use crate::chain::cosmos::gas::{explicit_fee_to_fee, gas_amount_to_fee, PrettyFee};
+use crate::chain::cosmos::grpc::GrpcEndpoint;
 use crate::chain::cosmos::simulate::send_tx_simulate;
 use crate::chain::cosmos::types::account::Account;
 use crate::chain::cosmos::types::config::TxConfig;
@@ -23,6 +23,18 @@ pub async fn estimate_tx_fees(
 ) -> Result<Fee, Error> {
     let gas_config = &config.gas_config;
 
+    if let Some(explicit_fee) = &gas_config.explicit_fee {
+        let fee = explicit_fee_to_fee(gas_config, explicit_fee);
+
+        debug!(
+            id = %config.chain_id,
+            "send_tx: using explicit fee {}, skipping tx simulation",
+            PrettyFee(&fee)
+        );
+
+        return Ok(fee);
+    }
+
     debug!(
         "max fee, for use in tx simulation: {}",
         PrettyFee(&gas_config.max_fee)
@@ -51,7 +63,7 @@ pub async fn estimate_tx_fees(
 
 async fn estimate_fee_with_tx(
     gas_config: &GasConfig,
-    grpc_address: &Uri,
+    grpc_address: &GrpcEndpoint,
     chain_id: &ChainId,
     tx: Tx,
 ) -> Result<Fee, Error> {
@@ -92,7 +104,7 @@ async fn estimate_fee_with_tx(
 /// In this case we use the `default_gas` param.
 async fn estimate_gas_with_tx(
     gas_config: &GasConfig,
-    grpc_address: &Uri,
+    grpc_address: &GrpcEndpoint,
     tx: Tx,
 ) -> Result<u64, Error> {
     let simulated_gas = send_tx_simulate(grpc_address, tx)
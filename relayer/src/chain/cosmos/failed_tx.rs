@@ -0,0 +1,439 @@
+//! Dumps of the transactions rejected by a chain, for post-mortem analysis.
+//!
+//! When `failed_tx_dir` is set in the configuration of a chain, each transaction
+//! which fails at the `check_tx` or `deliver_tx` step is written to that folder
+//! as a JSON file, together with the response of the node. Only the most recent
+//! [`MAX_FAILED_TX_DUMPS`] dumps are kept.
+
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use ibc::core::ics24_host::identifier::ChainId;
+use ibc_proto::cosmos::tx::v1beta1::{AuthInfo, TxBody, TxRaw};
+use prost::Message;
+use serde_derive::Serialize;
+use subtle_encoding::base64;
+use tendermint::abci::DeliverTx;
+use tracing::{error, warn};
+
+use crate::chain::cosmos::types::tx::TxSyncResponse;
+use crate::chain::tracking::CorrelationId;
+use crate::error::Error;
+
+/// The maximum number of dumps kept in a folder, the oldest ones being deleted first.
+pub const MAX_FAILED_TX_DUMPS: usize = 100;
+
+const DUMP_EXTENSION: &str = "json";
+
+/// The step at which a transaction was rejected.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailedTxStage {
+    CheckTx,
+    DeliverTx,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct FailedTxDump {
+    pub chain_id: String,
+    pub tx_hash: String,
+    /// The correlation id of the messages of the transaction, if any
+    pub correlation_id: Option<String>,
+    pub stage: FailedTxStage,
+    /// The time of the failure, in RFC 3339 format
+    pub timestamp: String,
+    /// The raw signed transaction, base64-encoded
+    pub tx: String,
+    pub messages: Vec<DumpedMessage>,
+    pub memo: String,
+    /// The fee paid by the transaction, whose gas limit is the result
+    /// of the simulation unless an explicit fee is configured
+    pub fee: Option<DumpedFee>,
+    pub response: DumpedResponse,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DumpedMessage {
+    pub type_url: String,
+    /// The protobuf encoding of the message, base64-encoded
+    pub value: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DumpedFee {
+    pub amount: Vec<String>,
+    pub gas_limit: u64,
+    pub granter: String,
+}
+
+/// The ABCI response of the node. The gas is only reported by `deliver_tx`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DumpedResponse {
+    pub code: u32,
+    pub codespace: String,
+    pub log: String,
+    pub gas_wanted: Option<u64>,
+    pub gas_used: Option<u64>,
+}
+
+impl DumpedResponse {
+    pub fn from_check_tx(response: &TxSyncResponse) -> Self {
+        Self {
+            code: response.response.code.value(),
+            codespace: response.codespace.clone(),
+            log: response.response.log.to_string(),
+            gas_wanted: None,
+            gas_used: None,
+        }
+    }
+
+    pub fn from_deliver_tx(result: &DeliverTx) -> Self {
+        Self {
+            code: result.code.value(),
+            codespace: result.codespace.to_string(),
+            log: result.log.to_string(),
+            gas_wanted: Some(result.gas_wanted.value()),
+            gas_used: Some(result.gas_used.value()),
+        }
+    }
+}
+
+impl FailedTxDump {
+    /// Builds the dump of a failed transaction, decoding its messages and fee
+    /// from the raw bytes. The parts which cannot be decoded are left empty,
+    /// the raw bytes being dumped in any case.
+    pub fn new(
+        chain_id: &ChainId,
+        tx_hash: String,
+        correlation_id: Option<CorrelationId>,
+        stage: FailedTxStage,
+        tx_bytes: &[u8],
+        response: DumpedResponse,
+        time: SystemTime,
+    ) -> Self {
+        let tx_raw = TxRaw::decode(tx_bytes).unwrap_or_default();
+        let body = TxBody::decode(tx_raw.body_bytes.as_slice()).unwrap_or_default();
+        let auth_info = AuthInfo::decode(tx_raw.auth_info_bytes.as_slice()).unwrap_or_default();
+
+        let messages = body
+            .messages
+            .into_iter()
+            .map(|msg| DumpedMessage {
+                type_url: msg.type_url,
+                value: encode_base64(&msg.value),
+            })
+            .collect();
+
+        let fee = auth_info.fee.map(|fee| DumpedFee {
+            amount: fee
+                .amount
+                .iter()
+                .map(|coin| format!("{}{}", coin.amount, coin.denom))
+                .collect(),
+            gas_limit: fee.gas_limit,
+            granter: fee.granter,
+        });
+
+        Self {
+            chain_id: chain_id.to_string(),
+            tx_hash,
+            correlation_id: correlation_id.map(|id| id.to_string()),
+            stage,
+            timestamp: humantime::format_rfc3339_millis(time).to_string(),
+            tx: encode_base64(tx_bytes),
+            messages,
+            memo: body.memo,
+            fee,
+            response,
+        }
+    }
+
+    /// The name of the file holding this dump, e.g. `20221017T093012.345Z_ibc-0_<hash>.json`,
+    /// or `20221017T093012.345Z_ibc-0_<correlation id>_<hash>.json` if the messages of the
+    /// transaction have a correlation id.
+    ///
+    /// The names start with the compact timestamp of the failure, so that
+    /// sorting them orders the dumps from the oldest to the most recent.
+    pub fn file_name(&self) -> String {
+        let timestamp: String = self
+            .timestamp
+            .chars()
+            .filter(|c| *c != '-' && *c != ':')
+            .collect();
+
+        match &self.correlation_id {
+            Some(correlation_id) => format!(
+                "{}_{}_{}_{}.{}",
+                timestamp, self.chain_id, correlation_id, self.tx_hash, DUMP_EXTENSION
+            ),
+            None => format!(
+                "{}_{}_{}.{}",
+                timestamp, self.chain_id, self.tx_hash, DUMP_EXTENSION
+            ),
+        }
+    }
+}
+
+/// Writes the given dump in the given folder, then deletes the oldest dumps
+/// of that folder beyond [`MAX_FAILED_TX_DUMPS`].
+///
+/// Returns the path of the dump, or logs the error and returns `None` if it
+/// could not be written, as failing to dump must not prevent relaying.
+pub fn try_dump_failed_tx(dir: &Path, dump: &FailedTxDump) -> Option<PathBuf> {
+    match dump_failed_tx(dir, dump, MAX_FAILED_TX_DUMPS) {
+        Ok(path) => {
+            error!(
+                id = %dump.chain_id,
+                "{:?} failed for tx {}, dumped to {}",
+                dump.stage,
+                dump.tx_hash,
+                path.display()
+            );
+
+            Some(path)
+        }
+        Err(e) => {
+            warn!(
+                id = %dump.chain_id,
+                "failed to dump tx {} to {}: {}",
+                dump.tx_hash,
+                dir.display(),
+                e
+            );
+
+            None
+        }
+    }
+}
+
+fn dump_failed_tx(dir: &Path, dump: &FailedTxDump, max_dumps: usize) -> Result<PathBuf, Error> {
+    fs::create_dir_all(dir).map_err(Error::io)?;
+
+    let path = dir.join(dump.file_name());
+    let file = File::create(&path).map_err(Error::io)?;
+    serde_json::to_writer_pretty(file, dump).map_err(|e| Error::io(e.into()))?;
+
+    rotate_dumps(dir, max_dumps)?;
+
+    Ok(path)
+}
+
+/// Deletes the oldest dumps of the given folder, so that at most `max_dumps` remain.
+/// Files which are not dumps are left untouched.
+fn rotate_dumps(dir: &Path, max_dumps: usize) -> Result<(), Error> {
+    let mut dumps = Vec::new();
+
+    for entry in fs::read_dir(dir).map_err(Error::io)? {
+        let path = entry.map_err(Error::io)?.path();
+
+        if path.is_file() && path.extension().map_or(false, |ext| ext == DUMP_EXTENSION) {
+            dumps.push(path);
+        }
+    }
+
+    if dumps.len() <= max_dumps {
+        return Ok(());
+    }
+
+    dumps.sort();
+
+    for path in &dumps[..dumps.len() - max_dumps] {
+        fs::remove_file(path).map_err(Error::io)?;
+    }
+
+    Ok(())
+}
+
+fn encode_base64(bytes: &[u8]) -> String {
+    String::from_utf8(base64::encode(bytes)).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::time::Duration;
+    use ibc_proto::cosmos::base::v1beta1::Coin;
+    use ibc_proto::cosmos::tx::v1beta1::Fee;
+    use ibc_proto::google::protobuf::Any;
+    use std::time::UNIX_EPOCH;
+
+    fn tx_bytes() -> Vec<u8> {
+        let body = TxBody {
+            messages: vec![Any {
+                type_url: "/ibc.core.channel.v1.MsgRecvPacket".to_string(),
+                value: vec![1, 2, 3],
+            }],
+            memo: "relayed by hermes".to_string(),
+            ..Default::default()
+        };
+
+        let auth_info = AuthInfo {
+            fee: Some(Fee {
+                amount: vec![Coin {
+                    denom: "stake".to_string(),
+                    amount: "2500".to_string(),
+                }],
+                gas_limit: 100_000,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        TxRaw {
+            body_bytes: body.encode_to_vec(),
+            auth_info_bytes: auth_info.encode_to_vec(),
+            signatures: vec![vec![0xab; 4]],
+        }
+        .encode_to_vec()
+    }
+
+    fn response() -> DumpedResponse {
+        DumpedResponse {
+            code: 11,
+            codespace: "sdk".to_string(),
+            log: "out of gas".to_string(),
+            gas_wanted: Some(100_000),
+            gas_used: Some(100_123),
+        }
+    }
+
+    fn dump_at(secs: u64, tx_hash: &str) -> FailedTxDump {
+        FailedTxDump::new(
+            &ChainId::from_string("ibc-0"),
+            tx_hash.to_string(),
+            None,
+            FailedTxStage::DeliverTx,
+            &tx_bytes(),
+            response(),
+            UNIX_EPOCH + Duration::from_millis(secs * 1000 + 345),
+        )
+    }
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("hermes-failed-tx-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn file_names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn dump_decodes_tx() {
+        let dump = dump_at(1_666_000_000, "A1B2");
+
+        assert_eq!(
+            dump.messages,
+            vec![DumpedMessage {
+                type_url: "/ibc.core.channel.v1.MsgRecvPacket".to_string(),
+                value: "AQID".to_string(),
+            }]
+        );
+        assert_eq!(dump.memo, "relayed by hermes");
+        assert_eq!(
+            dump.fee,
+            Some(DumpedFee {
+                amount: vec!["2500stake".to_string()],
+                gas_limit: 100_000,
+                granter: String::new(),
+            })
+        );
+        assert_eq!(base64::decode(&dump.tx).unwrap(), tx_bytes());
+    }
+
+    #[test]
+    fn dump_of_undecodable_tx() {
+        let dump = FailedTxDump::new(
+            &ChainId::from_string("ibc-0"),
+            "A1B2".to_string(),
+            None,
+            FailedTxStage::CheckTx,
+            &[0xff, 0xff],
+            response(),
+            UNIX_EPOCH,
+        );
+
+        assert!(dump.messages.is_empty());
+        assert_eq!(dump.fee, None);
+        assert_eq!(dump.tx, "//8=");
+    }
+
+    #[test]
+    fn dump_serialization() {
+        let json = serde_json::to_value(&dump_at(1_666_000_000, "A1B2")).unwrap();
+
+        assert_eq!(json["chain_id"], "ibc-0");
+        assert_eq!(json["tx_hash"], "A1B2");
+        assert_eq!(json["stage"], "deliver_tx");
+        assert_eq!(json["timestamp"], "2022-10-17T09:46:40.345Z");
+        assert_eq!(json["messages"][0]["value"], "AQID");
+        assert_eq!(json["fee"]["gas_limit"], 100_000);
+        assert_eq!(json["response"]["code"], 11);
+        assert_eq!(json["response"]["codespace"], "sdk");
+        assert_eq!(json["response"]["log"], "out of gas");
+        assert_eq!(json["response"]["gas_used"], 100_123);
+    }
+
+    #[test]
+    fn dump_file_name() {
+        assert_eq!(
+            dump_at(1_666_000_000, "A1B2").file_name(),
+            "20221017T094640.345Z_ibc-0_A1B2.json"
+        );
+    }
+
+    #[test]
+    fn dump_file_name_with_correlation_id() {
+        let correlation_id = CorrelationId::new();
+        let dump = FailedTxDump {
+            correlation_id: Some(correlation_id.to_string()),
+            ..dump_at(1_666_000_000, "A1B2")
+        };
+
+        assert_eq!(
+            dump.file_name(),
+            format!("20221017T094640.345Z_ibc-0_{}_A1B2.json", correlation_id)
+        );
+    }
+
+    #[test]
+    fn dumps_are_rotated() {
+        let dir = test_dir("rotation");
+
+        for (i, hash) in ["A", "B", "C", "D"].iter().enumerate() {
+            let path = dump_failed_tx(&dir, &dump_at(1_666_000_000 + i as u64, hash), 2).unwrap();
+            assert!(path.is_file());
+        }
+
+        fs::write(dir.join("notes.txt"), "kept").unwrap();
+        rotate_dumps(&dir, 1).unwrap();
+
+        assert_eq!(
+            file_names(&dir),
+            vec!["20221017T094643.345Z_ibc-0_D.json", "notes.txt"]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn dumps_below_the_cap_are_kept() {
+        let dir = test_dir("below-cap");
+
+        dump_failed_tx(&dir, &dump_at(1_666_000_000, "A"), 3).unwrap();
+        dump_failed_tx(&dir, &dump_at(1_666_000_001, "B"), 3).unwrap();
+
+        assert_eq!(file_names(&dir).len(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
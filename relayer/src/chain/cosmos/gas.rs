@@ -5,7 +5,7 @@ use ibc_proto::cosmos::tx::v1beta1::Fee;
 use num_bigint::BigInt;
 use num_rational::BigRational;
 
-use crate::chain::cosmos::types::gas::GasConfig;
+use crate::chain::cosmos::types::gas::{ExplicitFee, GasConfig};
 use crate::config::GasPrice;
 
 pub struct PrettyFee<'a>(pub &'a Fee);
@@ -28,6 +28,16 @@ pub fn gas_amount_to_fee(config: &GasConfig, gas_amount: u64) -> Fee {
     }
 }
 
+/// The fee to pay for a transaction whose fee is given explicitly, without simulating it.
+pub fn explicit_fee_to_fee(config: &GasConfig, fee: &ExplicitFee) -> Fee {
+    Fee {
+        amount: vec![fee.amount.clone()],
+        gas_limit: fee.gas_limit.unwrap_or(config.default_gas),
+        payer: "".to_string(),
+        granter: config.fee_granter.clone(),
+    }
+}
+
 pub fn calculate_fee(adjusted_gas_amount: u64, gas_price: &GasPrice) -> Coin {
     let fee_amount = mul_ceil(adjusted_gas_amount, gas_price.price);
 
@@ -117,7 +127,66 @@ impl fmt::Display for PrettyFee<'_> {
 
 #[cfg(test)]
 mod tests {
-    use super::{adjust_estimated_gas, AdjustGas};
+    use ibc_proto::cosmos::base::v1beta1::Coin;
+    use ibc_proto::cosmos::tx::v1beta1::Fee;
+
+    use super::{adjust_estimated_gas, explicit_fee_to_fee, AdjustGas};
+    use crate::chain::cosmos::types::gas::{ExplicitFee, GasConfig};
+    use crate::config::GasPrice;
+
+    fn gas_config() -> GasConfig {
+        GasConfig {
+            default_gas: 100_000,
+            max_gas: 1_000_000,
+            gas_multiplier: 1.1,
+            gas_price: GasPrice::new(0.001, "uatom".to_string()),
+            max_fee: Fee::default(),
+            fee_granter: "cosmos1granter".to_string(),
+            explicit_fee: None,
+        }
+    }
+
+    fn uatom(amount: &str) -> Coin {
+        Coin {
+            denom: "uatom".to_string(),
+            amount: amount.to_string(),
+        }
+    }
+
+    #[test]
+    fn explicit_fee_with_gas() {
+        let fee = explicit_fee_to_fee(
+            &gas_config(),
+            &ExplicitFee {
+                amount: uatom("5000"),
+                gas_limit: Some(250_000),
+            },
+        );
+
+        assert_eq!(
+            fee,
+            Fee {
+                amount: vec![uatom("5000")],
+                gas_limit: 250_000,
+                payer: "".to_string(),
+                granter: "cosmos1granter".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn explicit_fee_with_default_gas() {
+        let fee = explicit_fee_to_fee(
+            &gas_config(),
+            &ExplicitFee {
+                amount: uatom("5000"),
+                gas_limit: None,
+            },
+        );
+
+        assert_eq!(fee.amount, vec![uatom("5000")]);
+        assert_eq!(fee.gas_limit, 100_000);
+    }
 
     #[test]
     fn adjust_zero_gas() {
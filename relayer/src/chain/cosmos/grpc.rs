@@ -0,0 +1,522 @@
+//! Connections to the gRPC endpoint of a chain, using the TLS settings
+//! of the chain for the `https` endpoints, and the fallback of the queries
+//! to the LCD endpoint of the chain while the gRPC endpoint is unavailable.
+
+use core::fmt;
+use core::future::Future;
+use core::str::FromStr;
+use core::time::Duration;
+use std::error::Error as StdError;
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime};
+
+use http::uri::{Scheme, Uri};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::client::{ServerCertVerified, ServerCertVerifier};
+use tokio_rustls::rustls::{self, ClientConfig, ServerName};
+use tokio_rustls::TlsConnector;
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Error as TransportError};
+use tracing::warn;
+
+use crate::chain::cosmos::rest::RestEndpoint;
+use crate::config::{ChainConfig, TlsConfig};
+use crate::error::Error;
+use crate::home;
+
+/// How long the queries go straight to the LCD endpoint of a chain after its
+/// gRPC endpoint was found unavailable, before the gRPC endpoint is tried again.
+const GRPC_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The gRPC endpoint of a chain, along with the TLS settings to connect to it
+/// and the LCD endpoint to fall back to, if any.
+#[derive(Clone, Debug)]
+pub struct GrpcEndpoint {
+    uri: Uri,
+    ca_certificate: Option<Vec<u8>>,
+    skip_verify: bool,
+    server_name: Option<String>,
+    rest: Option<Arc<RestFallback>>,
+}
+
+/// The LCD endpoint which the queries fall back to, shared by the clones of
+/// a [`GrpcEndpoint`] for them to agree on whether the gRPC endpoint is down.
+#[derive(Debug)]
+struct RestFallback {
+    endpoint: RestEndpoint,
+    /// When the gRPC endpoint was last found unavailable, if it has not answered since.
+    grpc_unavailable_since: Mutex<Option<Instant>>,
+}
+
+impl GrpcEndpoint {
+    /// The endpoint at the given address, with the given TLS settings.
+    /// Reads the CA file of the settings, if any.
+    pub fn new(uri: Uri, tls: &TlsConfig) -> Result<Self, Error> {
+        let ca_certificate = match &tls.ca_file {
+            Some(ca_file) => {
+                let path = home::resolve_path(ca_file);
+                let pem = std::fs::read(&path).map_err(|e| {
+                    Error::grpc_tls_config(
+                        uri.to_string(),
+                        format!("failed to read the CA file {}: {}", path.display(), e),
+                    )
+                })?;
+
+                Some(pem)
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            uri,
+            ca_certificate,
+            skip_verify: tls.skip_verify,
+            server_name: tls.server_name.clone(),
+            rest: None,
+        })
+    }
+
+    /// The gRPC endpoint of the given chain, falling back to its LCD endpoint if one is configured.
+    pub fn from_config(config: &ChainConfig) -> Result<Self, Error> {
+        let uri = Uri::from_str(&config.grpc_addr.to_string())
+            .map_err(|e| Error::invalid_uri(config.grpc_addr.to_string(), e))?;
+
+        let endpoint = Self::new(uri, &config.tls)?;
+
+        match &config.rest_addr {
+            Some(rest_addr) => {
+                Ok(endpoint.with_rest_fallback(RestEndpoint::new(rest_addr, config.rpc_timeout)))
+            }
+            None => Ok(endpoint),
+        }
+    }
+
+    /// Falls back to the given LCD endpoint for the queries which support it,
+    /// see [`GrpcEndpoint::query_with_rest_fallback`].
+    pub fn with_rest_fallback(self, rest: RestEndpoint) -> Self {
+        Self {
+            rest: Some(Arc::new(RestFallback {
+                endpoint: rest,
+                grpc_unavailable_since: Mutex::new(None),
+            })),
+            ..self
+        }
+    }
+
+    /// The LCD endpoint which the queries fall back to, if any.
+    pub fn rest(&self) -> Option<&RestEndpoint> {
+        self.rest.as_ref().map(|rest| &rest.endpoint)
+    }
+
+    /// Runs the given gRPC query, unless the gRPC endpoint was found unavailable
+    /// less than [`GRPC_RETRY_INTERVAL`] ago, in which case `rest_query` is run
+    /// against the LCD endpoint instead.
+    ///
+    /// When the gRPC query fails because the endpoint is unavailable, as told by
+    /// [`ErrorDetail::is_grpc_unavailable`], the query is run again with `rest_query`.
+    /// Without an LCD endpoint, this is just the gRPC query.
+    ///
+    /// [`ErrorDetail::is_grpc_unavailable`]: crate::error::ErrorDetail::is_grpc_unavailable
+    pub async fn query_with_rest_fallback<T, Q, R, F>(
+        &self,
+        name: &str,
+        query: Q,
+        rest_query: R,
+    ) -> Result<T, Error>
+    where
+        Q: Future<Output = Result<T, Error>>,
+        R: FnOnce(RestEndpoint) -> F,
+        F: Future<Output = Result<T, Error>>,
+    {
+        let rest = match &self.rest {
+            Some(rest) => rest,
+            None => return query.await,
+        };
+
+        let grpc_down = rest
+            .grpc_unavailable_since
+            .lock()
+            .unwrap()
+            .map_or(false, |since| since.elapsed() < GRPC_RETRY_INTERVAL);
+
+        if grpc_down {
+            return rest_query(rest.endpoint.clone()).await;
+        }
+
+        match query.await {
+            Err(e) if e.detail().is_grpc_unavailable() => {
+                warn!(
+                    "gRPC endpoint {} is unavailable, falling back to the LCD endpoint {} \
+                    for the `{}` query and the next ones for {}: {}",
+                    self.uri,
+                    rest.endpoint,
+                    name,
+                    humantime::format_duration(GRPC_RETRY_INTERVAL),
+                    e
+                );
+
+                *rest.grpc_unavailable_since.lock().unwrap() = Some(Instant::now());
+
+                rest_query(rest.endpoint.clone()).await
+            }
+            result => {
+                *rest.grpc_unavailable_since.lock().unwrap() = None;
+
+                result
+            }
+        }
+    }
+
+    pub fn uri(&self) -> &Uri {
+        &self.uri
+    }
+
+    fn is_https(&self) -> bool {
+        self.uri.scheme() == Some(&Scheme::HTTPS)
+    }
+
+    /// Opens a channel to the endpoint, to be wrapped into a gRPC client.
+    ///
+    /// The errors raised when verifying the certificate of the endpoint
+    /// are reported as [`Error::grpc_certificate_verification`], apart
+    /// from the other connection errors.
+    pub async fn connect(&self) -> Result<Channel, Error> {
+        let address = self.uri.to_string();
+
+        let result = if !self.is_https() {
+            Endpoint::from(self.uri.clone()).connect().await
+        } else if self.skip_verify {
+            self.connect_without_verification().await?
+        } else {
+            let mut tls = ClientTlsConfig::new();
+
+            if let Some(pem) = &self.ca_certificate {
+                tls = tls.ca_certificate(Certificate::from_pem(pem));
+            }
+
+            if let Some(server_name) = &self.server_name {
+                tls = tls.domain_name(server_name);
+            }
+
+            Endpoint::from(self.uri.clone())
+                .tls_config(tls)
+                .map_err(|e| Error::grpc_tls_config(address.clone(), e.to_string()))?
+                .connect()
+                .await
+        };
+
+        result.map_err(|e| {
+            if is_certificate_error(&e) {
+                Error::grpc_certificate_verification(address, e)
+            } else {
+                Error::grpc_connection(address, e)
+            }
+        })
+    }
+
+    /// Performs the TLS handshake ourselves with a verifier accepting any certificate,
+    /// as tonic does not let us skip the verification. The channel itself is then
+    /// opened over an `http` address, so that tonic does not wrap the TLS stream
+    /// into another one.
+    async fn connect_without_verification(&self) -> Result<Result<Channel, TransportError>, Error> {
+        let address = self.uri.to_string();
+
+        let host = self
+            .uri
+            .host()
+            .map(|host| {
+                host.trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .to_string()
+            })
+            .ok_or_else(|| Error::grpc_tls_config(address.clone(), "missing host".to_string()))?;
+
+        let port = self.uri.port_u16().unwrap_or(443);
+
+        let server_name = ServerName::try_from(self.server_name.as_deref().unwrap_or(&host))
+            .map_err(|e| Error::grpc_tls_config(address.clone(), e.to_string()))?;
+
+        let mut config = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_custom_certificate_verifier(Arc::new(NoVerification))
+            .with_no_client_auth();
+
+        config.alpn_protocols = vec![b"h2".to_vec()];
+
+        let connector = TlsConnector::from(Arc::new(config));
+
+        let mut parts = self.uri.clone().into_parts();
+        parts.scheme = Some(Scheme::HTTP);
+
+        let uri = Uri::from_parts(parts)
+            .map_err(|e| Error::grpc_tls_config(address.clone(), e.to_string()))?;
+
+        let result = Endpoint::from(uri)
+            .connect_with_connector(tower::service_fn(move |_: Uri| {
+                let connector = connector.clone();
+                let server_name = server_name.clone();
+                let host = host.clone();
+
+                async move {
+                    let stream = TcpStream::connect((host.as_str(), port)).await?;
+                    connector.connect(server_name, stream).await
+                }
+            }))
+            .await;
+
+        Ok(result)
+    }
+}
+
+impl fmt::Display for GrpcEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.uri.fmt(f)
+    }
+}
+
+/// Warns about the TLS settings of the given chain which weaken or do not
+/// affect its connections.
+pub fn warn_tls_config(config: &ChainConfig) {
+    if config.tls.is_default() {
+        return;
+    }
+
+    if config.tls.skip_verify {
+        warn!(
+            chain = %config.id,
+            "the TLS certificate of the gRPC endpoint {} is NOT verified, as `tls.skip_verify` \
+            is enabled: anyone between Hermes and the endpoint can read and tamper with the \
+            queries and transactions, never use this setting in production",
+            config.grpc_addr
+        );
+    }
+
+    if !config.grpc_addr.to_string().starts_with("https:") {
+        warn!(
+            chain = %config.id,
+            "the `tls` settings are ignored, as the gRPC endpoint {} does not use TLS",
+            config.grpc_addr
+        );
+    }
+
+    if config.rpc_addr.to_string().starts_with("https:") {
+        warn!(
+            chain = %config.id,
+            "the `tls` settings only apply to the gRPC endpoint, the RPC endpoint {} \
+            is verified against the CAs trusted by the system, which can be extended \
+            with the `SSL_CERT_FILE` environment variable",
+            config.rpc_addr
+        );
+    }
+}
+
+/// Whether the given error, or one of its sources, is a failure of rustls
+/// to verify the certificate of the server.
+fn is_certificate_error(e: &TransportError) -> bool {
+    let mut source: Option<&(dyn StdError + 'static)> = Some(e);
+
+    while let Some(e) = source {
+        let tls_error = e
+            .downcast_ref::<std::io::Error>()
+            .and_then(|e| e.get_ref())
+            .and_then(|e| e.downcast_ref::<rustls::Error>())
+            .or_else(|| e.downcast_ref::<rustls::Error>());
+
+        if let Some(tls_error) = tls_error {
+            return matches!(
+                tls_error,
+                rustls::Error::InvalidCertificateData(_)
+                    | rustls::Error::InvalidCertificateEncoding
+                    | rustls::Error::InvalidCertificateSignature
+                    | rustls::Error::InvalidCertificateSignatureType
+            );
+        }
+
+        source = e.source();
+    }
+
+    false
+}
+
+/// Accepts any certificate, for the endpoints configured with `tls.skip_verify`.
+struct NoVerification;
+
+impl ServerCertVerifier for NoVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &rustls::Certificate,
+        _intermediates: &[rustls::Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GrpcEndpoint;
+
+    use std::fs::File;
+    use std::io::BufReader;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use http::Uri;
+    use tokio::net::TcpListener;
+    use tokio::runtime::Runtime;
+    use tokio_rustls::rustls::{Certificate, PrivateKey, ServerConfig};
+    use tokio_rustls::TlsAcceptor;
+
+    use crate::chain::cosmos::rest::RestEndpoint;
+    use crate::config::TlsConfig;
+    use crate::error::{Error, ErrorDetail};
+
+    const CA_FILE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/tls/ca.pem");
+    const CERT_FILE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/tls/server.pem");
+    const KEY_FILE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/tls/server.key");
+
+    fn runtime() -> Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+    }
+
+    /// Spawns a TLS server for `localhost`, with a certificate issued by the
+    /// test CA, which keeps the connections open without answering them.
+    async fn spawn_tls_server() -> Uri {
+        let certs = rustls_pemfile::certs(&mut BufReader::new(File::open(CERT_FILE).unwrap()))
+            .unwrap()
+            .into_iter()
+            .map(Certificate)
+            .collect();
+
+        let key =
+            rustls_pemfile::pkcs8_private_keys(&mut BufReader::new(File::open(KEY_FILE).unwrap()))
+                .unwrap()
+                .remove(0);
+
+        let mut config = ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(certs, PrivateKey(key))
+            .unwrap();
+
+        config.alpn_protocols = vec![b"h2".to_vec()];
+
+        let acceptor = TlsAcceptor::from(Arc::new(config));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        tokio::spawn(async move {
+            let mut streams = Vec::new();
+
+            while let Ok((stream, _)) = listener.accept().await {
+                if let Ok(stream) = acceptor.accept(stream).await {
+                    streams.push(stream);
+                }
+            }
+        });
+
+        format!("https://127.0.0.1:{}", port).parse().unwrap()
+    }
+
+    fn tls_config(ca_file: Option<&str>, skip_verify: bool) -> TlsConfig {
+        TlsConfig {
+            ca_file: ca_file.map(PathBuf::from),
+            skip_verify,
+            server_name: Some("localhost".to_string()),
+        }
+    }
+
+    #[test]
+    fn connect_with_custom_ca() {
+        runtime().block_on(async {
+            let uri = spawn_tls_server().await;
+            let endpoint = GrpcEndpoint::new(uri, &tls_config(Some(CA_FILE), false)).unwrap();
+
+            endpoint.connect().await.unwrap();
+        });
+    }
+
+    #[test]
+    fn reject_unknown_ca() {
+        runtime().block_on(async {
+            let uri = spawn_tls_server().await;
+            let endpoint = GrpcEndpoint::new(uri, &tls_config(None, false)).unwrap();
+
+            let error = endpoint.connect().await.unwrap_err();
+
+            assert!(
+                matches!(error.detail(), ErrorDetail::GrpcCertificateVerification(_)),
+                "unexpected error: {}",
+                error
+            );
+        });
+    }
+
+    #[test]
+    fn connect_without_verification() {
+        runtime().block_on(async {
+            let uri = spawn_tls_server().await;
+            let endpoint = GrpcEndpoint::new(uri, &tls_config(None, true)).unwrap();
+
+            endpoint.connect().await.unwrap();
+        });
+    }
+
+    #[test]
+    fn fall_back_to_rest_while_grpc_is_unavailable() {
+        runtime().block_on(async {
+            let uri = "http://127.0.0.1:9090".parse().unwrap();
+            let rest = RestEndpoint::new(
+                &"http://127.0.0.1:1317".parse().unwrap(),
+                Duration::from_secs(1),
+            );
+            let endpoint = GrpcEndpoint::new(uri, &TlsConfig::default())
+                .unwrap()
+                .with_rest_fallback(rest);
+
+            let unavailable = async { Err(Error::grpc_status(tonic::Status::unavailable("down"))) };
+            let result = endpoint
+                .query_with_rest_fallback("test", unavailable, |_| async { Ok(1) })
+                .await;
+            assert_eq!(result.unwrap(), 1);
+
+            // The gRPC endpoint is not queried again until the retry interval has elapsed
+            let result = endpoint
+                .clone()
+                .query_with_rest_fallback("test", async { Ok(2) }, |_| async { Ok(3) })
+                .await;
+            assert_eq!(result.unwrap(), 3);
+
+            // The errors of the gRPC endpoint which answered are returned as such
+            let endpoint = GrpcEndpoint::new(
+                "http://127.0.0.1:9090".parse().unwrap(),
+                &TlsConfig::default(),
+            )
+            .unwrap()
+            .with_rest_fallback(endpoint.rest().unwrap().clone());
+
+            let not_found = async { Err(Error::grpc_status(tonic::Status::not_found("channel"))) };
+            let result: Result<u64, _> = endpoint
+                .query_with_rest_fallback("test", not_found, |_| async { Ok(4) })
+                .await;
+            assert!(matches!(
+                result.unwrap_err().detail(),
+                ErrorDetail::GrpcStatus(_)
+            ));
+        });
+    }
+
+    #[test]
+    fn missing_ca_file() {
+        let uri = "https://127.0.0.1:9090".parse().unwrap();
+        let error = GrpcEndpoint::new(uri, &tls_config(Some("missing.pem"), false)).unwrap_err();
+
+        assert!(matches!(error.detail(), ErrorDetail::GrpcTlsConfig(_)));
+    }
+}
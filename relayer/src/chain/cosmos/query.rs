@@ -1,21 +1,33 @@
-use http::uri::Uri;
 use ibc::core::ics04_channel::packet::Sequence;
 use ibc::core::ics23_commitment::merkle::{convert_tm_to_ics_merkle_proof, MerkleProof};
 use ibc::core::ics24_host::identifier::ChainId;
 use ibc_proto::cosmos::base::tendermint::v1beta1::service_client::ServiceClient;
-use ibc_proto::cosmos::base::tendermint::v1beta1::GetNodeInfoRequest;
+use ibc_proto::cosmos::base::tendermint::v1beta1::{GetNodeInfoRequest, VersionInfo};
 use tendermint::abci::Path as TendermintABCIPath;
 use tendermint::block::Height;
 use tendermint_rpc::query::Query;
 use tendermint_rpc::{Client, HttpClient, Url};
 
+use crate::chain::cosmos::grpc::GrpcEndpoint;
+use crate::chain::cosmos::rest;
 use crate::chain::cosmos::version::Specs;
-use crate::chain::requests::{QueryClientEventRequest, QueryPacketEventDataRequest, QueryTxHash};
+use ibc::events::{IbcEventType, WithBlockDataType};
+
+use crate::chain::requests::{
+    QueryChannelOpenTxRequest, QueryClientEventRequest, QueryPacketEventDataRequest,
+    QueryPacketEventRangeRequest, QueryPacketRelayTxsRequest, QueryPacketTxRequest,
+    QuerySendPacketTxsRequest, QueryTxHash, QueryWrittenAcksRequest,
+};
 use crate::error::Error;
 
 pub mod account;
 pub mod balance;
+pub mod connection;
+pub mod denom_metadata;
 pub mod denom_trace;
+pub mod fee;
+pub mod header;
+pub mod services;
 pub mod status;
 pub mod tx;
 
@@ -50,6 +62,26 @@ pub fn packet_query(request: &QueryPacketEventDataRequest, seq: Sequence) -> Que
     )
 }
 
+pub fn packet_range_query(request: &QueryPacketEventRangeRequest) -> Query {
+    // `WriteAck` events are emitted on the destination channel end of the packets,
+    // all the other packet events on their source channel end.
+    let side = match request.event_id {
+        WithBlockDataType::WriteAck => "dst",
+        _ => "src",
+    };
+
+    Query::eq(
+        format!("{}.packet_{}_channel", request.event_id.as_str(), side),
+        request.channel_id.to_string(),
+    )
+    .and_eq(
+        format!("{}.packet_{}_port", request.event_id.as_str(), side),
+        request.port_id.to_string(),
+    )
+    .and_gte("tx.height", request.start_height.revision_height())
+    .and_lte("tx.height", request.end_height.revision_height())
+}
+
 pub fn header_query(request: &QueryClientEventRequest) -> Query {
     Query::eq(
         format!("{}.client_id", request.event_id.as_str()),
@@ -65,6 +97,88 @@ pub fn header_query(request: &QueryClientEventRequest) -> Query {
     )
 }
 
+pub fn channel_open_query(event_type: &IbcEventType, request: &QueryChannelOpenTxRequest) -> Query {
+    Query::eq(
+        format!("{}.port_id", event_type.as_str()),
+        request.port_id.to_string(),
+    )
+    .and_eq(
+        format!("{}.channel_id", event_type.as_str()),
+        request.channel_id.to_string(),
+    )
+}
+
+pub fn packet_relay_query(
+    event_type: &IbcEventType,
+    request: &QueryPacketRelayTxsRequest,
+) -> Query {
+    // Packets are received on their destination channel end, and acknowledgements
+    // on the source channel end of their packets. Note that ibc-go names the
+    // events of received packets `recv_packet`.
+    let (event, side) = match event_type {
+        IbcEventType::ReceivePacket => ("recv_packet", "dst"),
+        _ => (event_type.as_str(), "src"),
+    };
+
+    Query::eq(
+        format!("{}.packet_{}_channel", event, side),
+        request.channel_id.to_string(),
+    )
+    .and_eq(
+        format!("{}.packet_{}_port", event, side),
+        request.port_id.to_string(),
+    )
+    .and_gte("tx.height", request.start_height.revision_height())
+    .and_lte("tx.height", request.end_height.revision_height())
+}
+
+pub fn send_packet_query(request: &QuerySendPacketTxsRequest, seq: Sequence) -> Query {
+    Query::eq(
+        "send_packet.packet_src_channel",
+        request.channel_id.to_string(),
+    )
+    .and_eq("send_packet.packet_src_port", request.port_id.to_string())
+    .and_eq("send_packet.packet_sequence", seq.to_string())
+}
+
+pub fn packet_tx_query(request: &QueryPacketTxRequest) -> Query {
+    // Packets are received and their acknowledgement written on their destination
+    // channel end, the other events are emitted on their source channel end.
+    // Note that ibc-go names the events of received packets `recv_packet`, and
+    // those of the packets timed out on close `timeout_on_close_packet`.
+    let (event, side) = match request.event_type {
+        IbcEventType::ReceivePacket => ("recv_packet", "dst"),
+        IbcEventType::WriteAck => (request.event_type.as_str(), "dst"),
+        IbcEventType::TimeoutOnClose => ("timeout_on_close_packet", "src"),
+        _ => (request.event_type.as_str(), "src"),
+    };
+
+    Query::eq(
+        format!("{}.packet_{}_channel", event, side),
+        request.channel_id.to_string(),
+    )
+    .and_eq(
+        format!("{}.packet_{}_port", event, side),
+        request.port_id.to_string(),
+    )
+    .and_eq(
+        format!("{}.packet_sequence", event),
+        request.sequence.to_string(),
+    )
+}
+
+pub fn write_ack_query(request: &QueryWrittenAcksRequest, seq: Sequence) -> Query {
+    Query::eq(
+        "write_acknowledgement.packet_dst_channel",
+        request.channel_id.to_string(),
+    )
+    .and_eq(
+        "write_acknowledgement.packet_dst_port",
+        request.port_id.to_string(),
+    )
+    .and_eq("write_acknowledgement.packet_sequence", seq.to_string())
+}
+
 pub fn tx_hash_query(request: &QueryTxHash) -> Query {
     Query::eq("tx.hash", request.0.to_string())
 }
@@ -116,20 +230,42 @@ pub async fn abci_query(
 }
 
 /// Queries the chain to obtain the version information.
-pub async fn fetch_version_specs(chain_id: &ChainId, grpc_address: &Uri) -> Result<Specs, Error> {
+pub async fn fetch_version_specs(
+    chain_id: &ChainId,
+    grpc_address: &GrpcEndpoint,
+) -> Result<Specs, Error> {
+    let grpc_addr_string = grpc_address.to_string();
+
+    let version = grpc_address
+        .query_with_rest_fallback(
+            "node_info",
+            fetch_version_info_grpc(chain_id, grpc_address),
+            |lcd| async move {
+                rest::node::query_version_info(&lcd).await?.ok_or_else(|| {
+                    Error::fetch_version_invalid_version_response(
+                        chain_id.clone(),
+                        lcd.to_string(),
+                        "tendermint::GetNodeInfoRequest".to_string(),
+                    )
+                })
+            },
+        )
+        .await?;
+
+    // Parse the raw version info into a domain-type `version::Specs`
+    version
+        .try_into()
+        .map_err(|e| Error::fetch_version_parsing(chain_id.clone(), grpc_addr_string.clone(), e))
+}
+
+async fn fetch_version_info_grpc(
+    chain_id: &ChainId,
+    grpc_address: &GrpcEndpoint,
+) -> Result<VersionInfo, Error> {
     let grpc_addr_string = grpc_address.to_string();
 
     // Construct a gRPC client
-    let mut client = ServiceClient::connect(grpc_address.clone())
-        .await
-        .map_err(|e| {
-            Error::fetch_version_grpc_transport(
-                chain_id.clone(),
-                grpc_addr_string.clone(),
-                "tendermint::ServiceClient".to_string(),
-                e,
-            )
-        })?;
+    let mut client = ServiceClient::new(grpc_address.connect().await?);
 
     let request = tonic::Request::new(GetNodeInfoRequest {});
 
@@ -142,16 +278,11 @@ pub async fn fetch_version_specs(chain_id: &ChainId, grpc_address: &Uri) -> Resu
         )
     })?;
 
-    let version = response.into_inner().application_version.ok_or_else(|| {
+    response.into_inner().application_version.ok_or_else(|| {
         Error::fetch_version_invalid_version_response(
             chain_id.clone(),
             grpc_addr_string.clone(),
             "tendermint::GetNodeInfoRequest".to_string(),
         )
-    })?;
-
-    // Parse the raw version info into a domain-type `version::Specs`
-    version
-        .try_into()
-        .map_err(|e| Error::fetch_version_parsing(chain_id.clone(), grpc_addr_string.clone(), e))
+    })
 }
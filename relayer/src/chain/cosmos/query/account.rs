@@ -1,9 +1,10 @@
-use http::uri::Uri;
 use ibc_proto::cosmos::auth::v1beta1::query_client::QueryClient;
 use ibc_proto::cosmos::auth::v1beta1::{BaseAccount, EthAccount, QueryAccountRequest};
 use prost::Message;
 use tracing::info;
 
+use crate::chain::cosmos::grpc::GrpcEndpoint;
+use crate::chain::cosmos::rest;
 use crate::chain::cosmos::types::account::Account;
 use crate::error::Error;
 
@@ -11,7 +12,7 @@ use crate::error::Error;
 /// Otherwise query for the account information, update the `Option` to `Some`,
 /// and return the underlying `&mut` reference.
 pub async fn get_or_fetch_account<'a>(
-    grpc_address: &'a Uri,
+    grpc_address: &'a GrpcEndpoint,
     account_address: &'a str,
     m_account: &'a mut Option<Account>,
 ) -> Result<&'a mut Account, Error> {
@@ -31,7 +32,7 @@ pub async fn get_or_fetch_account<'a>(
 /// Refresh the account sequence behind the `&mut Account` by refetching the
 /// account and updating the `&mut` reference.
 pub async fn refresh_account<'a>(
-    grpc_address: &Uri,
+    grpc_address: &GrpcEndpoint,
     account_address: &str,
     m_account: &'a mut Account,
 ) -> Result<(), Error> {
@@ -48,14 +49,26 @@ pub async fn refresh_account<'a>(
     Ok(())
 }
 
-/// Uses the GRPC client to retrieve the account sequence
+/// Uses the GRPC client to retrieve the account sequence, or the LCD endpoint
+/// of the chain while its gRPC endpoint is unavailable
 pub async fn query_account(
-    grpc_address: &Uri,
+    grpc_address: &GrpcEndpoint,
     account_address: &str,
 ) -> Result<BaseAccount, Error> {
-    let mut client = QueryClient::connect(grpc_address.clone())
+    grpc_address
+        .query_with_rest_fallback(
+            "account",
+            query_account_grpc(grpc_address, account_address),
+            |lcd| async move { rest::account::query_account(&lcd, account_address).await },
+        )
         .await
-        .map_err(Error::grpc_transport)?;
+}
+
+async fn query_account_grpc(
+    grpc_address: &GrpcEndpoint,
+    account_address: &str,
+) -> Result<BaseAccount, Error> {
+    let mut client = QueryClient::new(grpc_address.connect().await?);
 
     let request = tonic::Request::new(QueryAccountRequest {
         address: account_address.to_string(),
@@ -1,18 +1,31 @@
-use http::uri::Uri;
-
 use ibc_proto::cosmos::bank::v1beta1::{query_client::QueryClient, QueryBalanceRequest};
 
+use crate::chain::cosmos::grpc::GrpcEndpoint;
+use crate::chain::cosmos::rest;
 use crate::{account::Balance, error::Error};
 
-/// Uses the GRPC client to retrieve the account balance for a specific denom
+/// Uses the GRPC client to retrieve the account balance for a specific denom,
+/// or the LCD endpoint of the chain while its gRPC endpoint is unavailable
 pub async fn query_balance(
-    grpc_address: &Uri,
+    grpc_address: &GrpcEndpoint,
     account_address: &str,
     denom: &str,
 ) -> Result<Balance, Error> {
-    let mut client = QueryClient::connect(grpc_address.clone())
+    grpc_address
+        .query_with_rest_fallback(
+            "balance",
+            query_balance_grpc(grpc_address, account_address, denom),
+            |lcd| async move { rest::bank::query_balance(&lcd, account_address, denom).await },
+        )
         .await
-        .map_err(Error::grpc_transport)?;
+}
+
+async fn query_balance_grpc(
+    grpc_address: &GrpcEndpoint,
+    account_address: &str,
+    denom: &str,
+) -> Result<Balance, Error> {
+    let mut client = QueryClient::new(grpc_address.connect().await?);
 
     let request = tonic::Request::new(QueryBalanceRequest {
         address: account_address.to_string(),
@@ -0,0 +1,53 @@
+use core::str::FromStr;
+use core::time::Duration;
+
+use ibc_proto::ibc::core::connection::v1::Params;
+use prost::Message;
+use tendermint::abci::Path as TendermintABCIPath;
+use tendermint::block::Height;
+use tendermint_rpc::{HttpClient, Url};
+
+use crate::chain::cosmos::query::abci_query;
+use crate::error::Error;
+
+/// The gRPC method of the IBC connection module which returns its parameters.
+const CONNECTION_PARAMS_QUERY_PATH: &str = "/ibc.core.connection.v1.Query/ConnectionParams";
+
+/// The response of the `ConnectionParams` query, which is not part of the
+/// protobuf definitions compiled into `ibc-proto`.
+#[derive(Clone, PartialEq, Message)]
+struct QueryConnectionParamsResponse {
+    #[prost(message, optional, tag = "1")]
+    params: Option<Params>,
+}
+
+/// Query the `max_expected_time_per_block` parameter of the IBC connection module.
+///
+/// Fails if the chain does not expose the `ConnectionParams` query, which was
+/// added in ibc-go v3.
+pub async fn query_max_expected_time_per_block(
+    rpc_client: &HttpClient,
+    rpc_address: &Url,
+) -> Result<Duration, Error> {
+    // SAFETY: Creating a Path from a constant; this should never fail
+    let path = TendermintABCIPath::from_str(CONNECTION_PARAMS_QUERY_PATH)
+        .expect("Turning connection params query path constant into a Tendermint ABCI path");
+
+    // The request has no fields, hence its encoding is empty
+    let response = abci_query(
+        rpc_client,
+        rpc_address,
+        path,
+        String::new(),
+        Height::from(0_u32),
+        false,
+    )
+    .await?;
+
+    let params = QueryConnectionParamsResponse::decode(response.value.as_slice())
+        .map_err(|e| Error::protobuf_decode("QueryConnectionParamsResponse".to_string(), e))?
+        .params
+        .ok_or_else(|| Error::grpc_response_param("params".to_string()))?;
+
+    Ok(Duration::from_nanos(params.max_expected_time_per_block))
+}
@@ -0,0 +1,48 @@
+use ibc_proto::cosmos::bank::v1beta1::{
+    query_client::QueryClient, Metadata, QueryDenomMetadataRequest,
+};
+
+use crate::chain::cosmos::grpc::GrpcEndpoint;
+use crate::{denom::DenomDisplay, error::Error};
+
+/// Uses the GRPC client to retrieve the display unit of a denomination from its bank metadata.
+/// Returns `None` if the denomination has no metadata, or if its display unit is not one
+/// of its units.
+pub async fn query_denom_metadata(
+    grpc_address: &GrpcEndpoint,
+    denom: &str,
+) -> Result<Option<DenomDisplay>, Error> {
+    let mut client = QueryClient::new(grpc_address.connect().await?);
+
+    let request = tonic::Request::new(QueryDenomMetadataRequest {
+        denom: denom.to_owned(),
+    });
+
+    let response = match client.denom_metadata(request).await {
+        Ok(response) => response.into_inner(),
+        // The bank module reports the denominations without metadata as not found
+        Err(status) if status.code() == tonic::Code::NotFound => return Ok(None),
+        Err(status) => return Err(Error::grpc_status(status)),
+    };
+
+    Ok(response.metadata.and_then(denom_display))
+}
+
+/// The display unit of the metadata, named after the symbol of the coins if any.
+fn denom_display(metadata: Metadata) -> Option<DenomDisplay> {
+    let unit = metadata
+        .denom_units
+        .iter()
+        .find(|unit| unit.denom == metadata.display)?;
+
+    let name = if metadata.symbol.is_empty() {
+        metadata.display.clone()
+    } else {
+        metadata.symbol.clone()
+    };
+
+    Some(DenomDisplay {
+        unit: name,
+        exponent: unit.exponent,
+    })
+}
@@ -1,16 +1,32 @@
-use http::uri::Uri;
-
 use ibc_proto::ibc::applications::transfer::v1::{
     query_client::QueryClient, QueryDenomTraceRequest,
 };
 
+use crate::chain::cosmos::grpc::GrpcEndpoint;
+use crate::chain::cosmos::rest;
+use crate::chain::requests::QueryDenomTracesRequest;
 use crate::{denom::DenomTrace, error::Error};
 
-// Uses the GRPC client to retrieve the denom trace for a specific hash
-pub async fn query_denom_trace(grpc_address: &Uri, hash: &str) -> Result<DenomTrace, Error> {
-    let mut client = QueryClient::connect(grpc_address.clone())
+// Uses the GRPC client to retrieve the denom trace for a specific hash,
+// or the LCD endpoint of the chain while its gRPC endpoint is unavailable
+pub async fn query_denom_trace(
+    grpc_address: &GrpcEndpoint,
+    hash: &str,
+) -> Result<DenomTrace, Error> {
+    grpc_address
+        .query_with_rest_fallback(
+            "denom_trace",
+            query_denom_trace_grpc(grpc_address, hash),
+            |lcd| async move { rest::transfer::query_denom_trace(&lcd, hash).await },
+        )
         .await
-        .map_err(Error::grpc_transport)?;
+}
+
+async fn query_denom_trace_grpc(
+    grpc_address: &GrpcEndpoint,
+    hash: &str,
+) -> Result<DenomTrace, Error> {
+    let mut client = QueryClient::new(grpc_address.connect().await?);
 
     let request = tonic::Request::new(QueryDenomTraceRequest {
         hash: hash.to_owned(),
@@ -20,7 +36,10 @@ pub async fn query_denom_trace(grpc_address: &Uri, hash: &str) -> Result<DenomTr
         .denom_trace(request)
         .await
         .map(|r| r.into_inner())
-        .map_err(Error::grpc_status)?;
+        .map_err(|e| match e.code() {
+            tonic::Code::NotFound => Error::denom_trace_not_found(hash.to_owned()),
+            _ => Error::grpc_status(e),
+        })?;
 
     let denom_trace = response
         .denom_trace
@@ -31,3 +50,42 @@ pub async fn query_denom_trace(grpc_address: &Uri, hash: &str) -> Result<DenomTr
         base_denom: denom_trace.base_denom,
     })
 }
+
+// Uses the GRPC client to retrieve the denom traces known to the chain, a page at a time,
+// or the LCD endpoint of the chain while its gRPC endpoint is unavailable
+pub async fn query_denom_traces(
+    grpc_address: &GrpcEndpoint,
+    request: QueryDenomTracesRequest,
+) -> Result<Vec<DenomTrace>, Error> {
+    grpc_address
+        .query_with_rest_fallback(
+            "denom_traces",
+            query_denom_traces_grpc(grpc_address, request.clone()),
+            |lcd| async move { rest::transfer::query_denom_traces(&lcd, request).await },
+        )
+        .await
+}
+
+async fn query_denom_traces_grpc(
+    grpc_address: &GrpcEndpoint,
+    request: QueryDenomTracesRequest,
+) -> Result<Vec<DenomTrace>, Error> {
+    let mut client = QueryClient::new(grpc_address.connect().await?);
+
+    let request = tonic::Request::new(request.into());
+
+    let response = client
+        .denom_traces(request)
+        .await
+        .map(|r| r.into_inner())
+        .map_err(Error::grpc_status)?;
+
+    Ok(response
+        .denom_traces
+        .into_iter()
+        .map(|denom_trace| DenomTrace {
+            path: denom_trace.path,
+            base_denom: denom_trace.base_denom,
+        })
+        .collect())
+}
@@ -0,0 +1,35 @@
+use ibc_proto::ibc::applications::fee::v1::query_client::QueryClient;
+
+use crate::chain::cosmos::grpc::GrpcEndpoint;
+use crate::chain::requests::QueryIncentivizedPacketRequest;
+use crate::error::Error;
+use crate::fee::PacketFee;
+
+/// Uses the GRPC client to retrieve the ICS-29 fees escrowed for a packet.
+///
+/// Returns no fee if the packet is not incentivized.
+pub async fn query_incentivized_packet(
+    grpc_address: &GrpcEndpoint,
+    request: QueryIncentivizedPacketRequest,
+) -> Result<Vec<PacketFee>, Error> {
+    let mut client = QueryClient::new(grpc_address.connect().await?);
+
+    let request = tonic::Request::new(request.into());
+
+    let response = match client.incentivized_packet(request).await {
+        Ok(response) => response.into_inner(),
+        Err(e) if e.code() == tonic::Code::NotFound => return Ok(vec![]),
+        Err(e) => return Err(Error::grpc_status(e)),
+    };
+
+    Ok(response
+        .incentivized_packet
+        .map(|packet| {
+            packet
+                .packet_fees
+                .into_iter()
+                .map(PacketFee::from)
+                .collect()
+        })
+        .unwrap_or_default())
+}
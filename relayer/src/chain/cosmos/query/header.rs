@@ -0,0 +1,64 @@
+use ibc_proto::cosmos::base::tendermint::v1beta1::service_client::ServiceClient;
+use ibc_proto::cosmos::base::tendermint::v1beta1::{
+    GetBlockByHeightRequest, GetLatestBlockRequest,
+};
+use tendermint::block::{Header, Height};
+use tendermint_rpc::{Client, HttpClient, Url};
+
+use crate::chain::cosmos::grpc::GrpcEndpoint;
+use crate::error::Error;
+
+/// Uses the `cosmos.base.tendermint.v1beta1.Service` gRPC service of the chain
+/// to query the header of the block at the given height, or of the latest block.
+pub async fn query_block_header_grpc(
+    grpc_address: &GrpcEndpoint,
+    height: Option<Height>,
+) -> Result<Header, Error> {
+    let mut client = ServiceClient::new(grpc_address.connect().await?);
+
+    let block = match height {
+        None => {
+            client
+                .get_latest_block(tonic::Request::new(GetLatestBlockRequest {}))
+                .await
+                .map_err(Error::grpc_status)?
+                .into_inner()
+                .block
+        }
+        Some(height) => {
+            let request = GetBlockByHeightRequest {
+                height: height.into(),
+            };
+
+            client
+                .get_block_by_height(tonic::Request::new(request))
+                .await
+                .map_err(Error::grpc_status)?
+                .into_inner()
+                .block
+        }
+    };
+
+    let header = block
+        .and_then(|block| block.header)
+        .ok_or_else(|| Error::grpc_response_param("no block header".to_string()))?;
+
+    Header::try_from(header).map_err(Error::invalid_block_header)
+}
+
+/// Uses the `/block` RPC endpoint of the chain to query the header
+/// of the block at the given height, or of the latest block.
+pub async fn query_block_header_rpc(
+    rpc_client: &HttpClient,
+    rpc_address: &Url,
+    height: Option<Height>,
+) -> Result<Header, Error> {
+    // TODO(hu55a1n1): use the `/header` RPC endpoint instead when we move to tendermint v0.35.x
+    let response = match height {
+        None => rpc_client.latest_block().await,
+        Some(height) => rpc_client.block(height).await,
+    }
+    .map_err(|e| Error::rpc(rpc_address.clone(), e))?;
+
+    Ok(response.block.header)
+}
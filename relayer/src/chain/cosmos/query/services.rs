@@ -0,0 +1,30 @@
+use ibc_proto::cosmos::base::reflection::v2alpha1::reflection_service_client::ReflectionServiceClient;
+use ibc_proto::cosmos::base::reflection::v2alpha1::GetQueryServicesDescriptorRequest;
+
+use crate::chain::cosmos::grpc::GrpcEndpoint;
+use crate::error::Error;
+
+/// Uses the GRPC reflection service of the chain to retrieve the fully-qualified
+/// names of the query services registered on it, e.g. `ibc.applications.transfer.v1.Query`.
+pub async fn query_services(grpc_address: &GrpcEndpoint) -> Result<Vec<String>, Error> {
+    let mut client = ReflectionServiceClient::new(grpc_address.connect().await?);
+
+    let request = tonic::Request::new(GetQueryServicesDescriptorRequest {});
+
+    let response = client
+        .get_query_services_descriptor(request)
+        .await
+        .map_err(Error::grpc_status)?
+        .into_inner();
+
+    Ok(response
+        .queries
+        .map(|queries| {
+            queries
+                .query_services
+                .into_iter()
+                .map(|service| service.fullname)
+                .collect()
+        })
+        .unwrap_or_default())
+}
@@ -1,18 +1,28 @@
 use ibc::core::ics04_channel::packet::{Packet, Sequence};
 use ibc::core::ics24_host::identifier::ChainId;
-use ibc::events::IbcEvent;
+use ibc::events::{ChainErrorEvent, IbcEvent, IbcEventType, WithBlockDataType};
 use ibc::Height as ICSHeight;
 use tendermint::abci::transaction::Hash as TxHash;
 use tendermint::abci::Event;
 use tendermint_rpc::endpoint::tx::Response as TxResponse;
 use tendermint_rpc::{Client, HttpClient, Order, Url};
 
-use crate::chain::cosmos::query::{header_query, packet_query, tx_hash_query};
+use crate::chain::cosmos::query::{
+    channel_open_query, header_query, packet_query, packet_range_query, packet_relay_query,
+    packet_tx_query, send_packet_query, tx_hash_query, write_ack_query,
+};
 use crate::chain::cosmos::types::events;
+use crate::chain::endpoint::{ChannelOpenTx, PacketRelayTx, PacketTx, SendPacketTx, WrittenAck};
 use crate::chain::requests::{
-    QueryClientEventRequest, QueryHeight, QueryPacketEventDataRequest, QueryTxHash, QueryTxRequest,
+    QueryChannelOpenTxRequest, QueryClientEventRequest, QueryHeight, QueryPacketEventDataRequest,
+    QueryPacketEventRangeRequest, QueryPacketRelayTxsRequest, QueryPacketTxRequest,
+    QuerySendPacketTxsRequest, QueryTxHash, QueryTxRequest, QueryWrittenAcksRequest,
 };
 use crate::error::Error;
+use crate::sdk_error::failed_msg_index;
+
+/// Number of transactions fetched per page when querying packet events in a range of heights.
+const PACKET_RANGE_PAGE_SIZE: u8 = 100;
 
 /// This function queries transactions for events matching certain criteria.
 /// 1. Client Update request - returns a vector with at most one update client event
@@ -25,6 +35,8 @@ use crate::error::Error;
 ///    Therefore, for packets we perform one tx_search for each sequence.
 ///    Alternatively, a single query for all packets could be performed but it would return all
 ///    packets ever sent.
+/// 3. Packet range request - returns all the packet events emitted on a channel end within
+///    a range of heights, in ascending order.
 pub async fn query_txs(
     chain_id: &ChainId,
     rpc_client: &HttpClient,
@@ -74,6 +86,43 @@ pub async fn query_txs(
             Ok(result)
         }
 
+        QueryTxRequest::PacketRange(request) => {
+            crate::time!("query_txs: query packet events in range");
+
+            let mut result: Vec<IbcEvent> = vec![];
+            let mut fetched = 0;
+            let mut page = 1;
+
+            loop {
+                let response = rpc_client
+                    .tx_search(
+                        packet_range_query(&request),
+                        false,
+                        page,
+                        PACKET_RANGE_PAGE_SIZE,
+                        Order::Ascending,
+                    )
+                    .await
+                    .map_err(|e| Error::rpc(rpc_address.clone(), e))?;
+
+                let total_count = response.total_count as usize;
+                let page_len = response.txs.len();
+
+                for tx in response.txs {
+                    result.extend(packets_from_tx_search_response(chain_id, &request, tx)?);
+                }
+
+                fetched += page_len;
+                if page_len == 0 || fetched >= total_count {
+                    break;
+                }
+
+                page += 1;
+            }
+
+            Ok(result)
+        }
+
         QueryTxRequest::Client(request) => {
             crate::time!("query_txs: single client update event");
 
@@ -203,6 +252,49 @@ fn packet_from_tx_search_response(
         .find_map(|ev| filter_matching_event(ev, request, seq)))
 }
 
+// Extract all the packet events matching the range query from the query_txs RPC response.
+// A Tx may contain events for several packets of the channel, and all of them are returned.
+fn packets_from_tx_search_response(
+    chain_id: &ChainId,
+    request: &QueryPacketEventRangeRequest,
+    response: TxResponse,
+) -> Result<Vec<IbcEvent>, Error> {
+    let height = ICSHeight::new(chain_id.version(), u64::from(response.height))
+        .map_err(|_| Error::invalid_height_no_source())?;
+
+    let matches_channel = |packet: &Packet| match request.event_id {
+        WithBlockDataType::WriteAck => {
+            packet.destination_port == request.port_id
+                && packet.destination_channel == request.channel_id
+        }
+        _ => packet.source_port == request.port_id && packet.source_channel == request.channel_id,
+    };
+
+    Ok(response
+        .tx_result
+        .events
+        .into_iter()
+        .filter(|event| event.type_str == request.event_id.as_str())
+        .filter_map(|event| events::channel::try_from_tx(&event))
+        .filter_map(|mut event| {
+            let packet = match &event {
+                IbcEvent::SendPacket(ev) => &ev.packet,
+                IbcEvent::WriteAcknowledgement(ev) => &ev.packet,
+                IbcEvent::AcknowledgePacket(ev) => &ev.packet,
+                IbcEvent::TimeoutPacket(ev) => &ev.packet,
+                _ => return None,
+            };
+
+            if matches_channel(packet) {
+                event.set_height(height);
+                Some(event)
+            } else {
+                None
+            }
+        })
+        .collect())
+}
+
 fn filter_matching_event(
     event: Event,
     request: &QueryPacketEventDataRequest,
@@ -238,6 +330,248 @@ fn filter_matching_event(
     }
 }
 
+/// Queries the transaction of the opening handshake which brought a channel end to
+/// the requested state, e.g. for the `Open` state the one which completed the handshake,
+/// ie. the one with its `channel_open_confirm` event or, if the channel end initiated
+/// the handshake, its `channel_open_ack` event.
+///
+/// Returns `None` if the channel end did not reach the state, or if the node pruned
+/// or did not index the transaction.
+pub async fn query_channel_open_tx(
+    chain_id: &ChainId,
+    rpc_client: &HttpClient,
+    rpc_address: &Url,
+    request: QueryChannelOpenTxRequest,
+) -> Result<Option<ChannelOpenTx>, Error> {
+    for event_type in request.event_types() {
+        let response = rpc_client
+            .tx_search(
+                channel_open_query(event_type, &request),
+                false,
+                1,
+                1, // get only the first Tx matching the query
+                Order::Ascending,
+            )
+            .await
+            .map_err(|e| Error::rpc(rpc_address.clone(), e))?;
+
+        if let Some(tx) = response.txs.into_iter().next() {
+            let height = ICSHeight::new(chain_id.version(), u64::from(tx.height))
+                .map_err(|_| Error::invalid_height_no_source())?;
+
+            return Ok(Some(ChannelOpenTx {
+                event_type: event_type.clone(),
+                height,
+                tx_hash: tx.hash.to_string(),
+                sender: tx_sender(&tx.tx_result.events),
+            }));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Queries the transactions which relayed packets to a channel end, or acknowledgements
+/// of the packets sent from it, within a range of heights, in ascending order.
+///
+/// Transactions relaying both packets and acknowledgements are returned once for each.
+pub async fn query_packet_relay_txs(
+    chain_id: &ChainId,
+    rpc_client: &HttpClient,
+    rpc_address: &Url,
+    request: QueryPacketRelayTxsRequest,
+) -> Result<Vec<PacketRelayTx>, Error> {
+    let mut result = vec![];
+
+    for event_type in [IbcEventType::ReceivePacket, IbcEventType::AckPacket] {
+        let mut fetched = 0;
+        let mut page = 1;
+
+        loop {
+            let response = rpc_client
+                .tx_search(
+                    packet_relay_query(&event_type, &request),
+                    false,
+                    page,
+                    PACKET_RANGE_PAGE_SIZE,
+                    Order::Ascending,
+                )
+                .await
+                .map_err(|e| Error::rpc(rpc_address.clone(), e))?;
+
+            let total_count = response.total_count as usize;
+            let page_len = response.txs.len();
+
+            for tx in response.txs {
+                let height = ICSHeight::new(chain_id.version(), u64::from(tx.height))
+                    .map_err(|_| Error::invalid_height_no_source())?;
+
+                result.push(PacketRelayTx {
+                    event_type: event_type.clone(),
+                    height,
+                    sender: tx_sender(&tx.tx_result.events),
+                });
+            }
+
+            fetched += page_len;
+            if page_len == 0 || fetched >= total_count {
+                break;
+            }
+
+            page += 1;
+        }
+    }
+
+    result.sort_by_key(|tx| tx.height);
+
+    Ok(result)
+}
+
+/// Queries the transactions which sent the packets with the requested sequences
+/// from a channel end, in the order of the requested sequences.
+///
+/// The packets whose transaction is not found, eg. because the node pruned it, are skipped.
+pub async fn query_send_packet_txs(
+    chain_id: &ChainId,
+    rpc_client: &HttpClient,
+    rpc_address: &Url,
+    request: QuerySendPacketTxsRequest,
+) -> Result<Vec<SendPacketTx>, Error> {
+    let mut result = vec![];
+
+    for seq in &request.sequences {
+        let response = rpc_client
+            .tx_search(
+                send_packet_query(&request, *seq),
+                false,
+                1,
+                1, // get only the first Tx matching the query
+                Order::Ascending,
+            )
+            .await
+            .map_err(|e| Error::rpc(rpc_address.clone(), e))?;
+
+        if let Some(tx) = response.txs.into_iter().next() {
+            let height = ICSHeight::new(chain_id.version(), u64::from(tx.height))
+                .map_err(|_| Error::invalid_height_no_source())?;
+
+            result.push(SendPacketTx {
+                sequence: *seq,
+                height,
+                tx_hash: tx.hash.to_string(),
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+/// Queries the first transaction which emitted the requested event of a packet.
+///
+/// Returns `None` if the event was not emitted, or if the node pruned or did not
+/// index the transaction.
+pub async fn query_packet_tx(
+    chain_id: &ChainId,
+    rpc_client: &HttpClient,
+    rpc_address: &Url,
+    request: QueryPacketTxRequest,
+) -> Result<Option<PacketTx>, Error> {
+    let response = rpc_client
+        .tx_search(
+            packet_tx_query(&request),
+            false,
+            1,
+            1, // get only the first Tx matching the query
+            Order::Ascending,
+        )
+        .await
+        .map_err(|e| Error::rpc(rpc_address.clone(), e))?;
+
+    match response.txs.into_iter().next() {
+        Some(tx) => {
+            let height = ICSHeight::new(chain_id.version(), u64::from(tx.height))
+                .map_err(|_| Error::invalid_height_no_source())?;
+
+            Ok(Some(PacketTx {
+                event_type: request.event_type,
+                height,
+                tx_hash: tx.hash.to_string(),
+                sender: tx_sender(&tx.tx_result.events),
+            }))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Queries the acknowledgements written for the packets with the requested sequences
+/// received on a channel end, in the order of the requested sequences.
+///
+/// The packets for which no acknowledgement is found, either because none was written
+/// yet or because the node pruned its transaction, are skipped.
+pub async fn query_written_acks(
+    chain_id: &ChainId,
+    rpc_client: &HttpClient,
+    rpc_address: &Url,
+    request: QueryWrittenAcksRequest,
+) -> Result<Vec<WrittenAck>, Error> {
+    let mut result = vec![];
+
+    for seq in &request.sequences {
+        let response = rpc_client
+            .tx_search(
+                write_ack_query(&request, *seq),
+                false,
+                1,
+                1, // get only the first Tx matching the query
+                Order::Ascending,
+            )
+            .await
+            .map_err(|e| Error::rpc(rpc_address.clone(), e))?;
+
+        if let Some(tx) = response.txs.into_iter().next() {
+            let height = ICSHeight::new(chain_id.version(), u64::from(tx.height))
+                .map_err(|_| Error::invalid_height_no_source())?;
+
+            let ack = tx
+                .tx_result
+                .events
+                .iter()
+                .filter(|event| event.type_str == WithBlockDataType::WriteAck.as_str())
+                .filter_map(events::channel::try_from_tx)
+                .find_map(|event| match event {
+                    IbcEvent::WriteAcknowledgement(ev)
+                        if ev.packet.sequence == *seq
+                            && ev.packet.destination_port == request.port_id
+                            && ev.packet.destination_channel == request.channel_id =>
+                    {
+                        Some(ev.ack)
+                    }
+                    _ => None,
+                });
+
+            if let Some(ack) = ack {
+                result.push(WrittenAck {
+                    sequence: *seq,
+                    height,
+                    ack,
+                });
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// The `sender` of the first message of a transaction, as reported by its `message` events.
+fn tx_sender(events: &[Event]) -> Option<String> {
+    events
+        .iter()
+        .filter(|event| event.type_str == "message")
+        .flat_map(|event| event.attributes.iter())
+        .find(|tag| tag.key.as_ref() == "sender")
+        .map(|tag| tag.value.as_ref().to_string())
+}
+
 pub async fn query_tx_response(
     rpc_client: &HttpClient,
     rpc_address: &Url,
@@ -267,10 +601,20 @@ fn all_ibc_events_from_tx_search_response(
     if deliver_tx_result.code.is_err() {
         // We can only return a single ChainError here because at this point
         // we have lost information about how many messages were in the transaction
-        vec![IbcEvent::ChainError(format!(
-            "deliver_tx for {} reports error: code={:?}, log={:?}",
-            response.hash, deliver_tx_result.code, deliver_tx_result.log
-        ))]
+        let error = ChainErrorEvent::new(
+            format!(
+                "deliver_tx for {} reports error: code={:?}, codespace={:?}, log={:?}",
+                response.hash,
+                deliver_tx_result.code,
+                deliver_tx_result.codespace.to_string(),
+                deliver_tx_result.log
+            ),
+            deliver_tx_result.code.value(),
+            deliver_tx_result.codespace.to_string(),
+        )
+        .with_msg_index(failed_msg_index(&deliver_tx_result.log.to_string()));
+
+        vec![IbcEvent::ChainError(error)]
     } else {
         let result = deliver_tx_result
             .events
@@ -0,0 +1,352 @@
+//! Queries to the LCD (REST) endpoint of a chain, i.e. the JSON gateway to its gRPC
+//! services, which are run in place of the gRPC queries when the gRPC endpoint of the
+//! chain is unavailable, see [`GrpcEndpoint::query_with_rest_fallback`].
+//!
+//! [`GrpcEndpoint::query_with_rest_fallback`]: crate::chain::cosmos::grpc::GrpcEndpoint::query_with_rest_fallback
+
+use core::fmt;
+use core::time::Duration;
+
+use http::header::{ACCEPT, CONTENT_TYPE};
+use http::{Method, Request, StatusCode};
+use hyper::client::HttpConnector;
+use hyper::{Body, Client};
+use hyper_rustls::HttpsConnector;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use tendermint_rpc::Url;
+
+use ibc::Height;
+
+use crate::chain::requests::PageRequest;
+use crate::error::Error;
+
+pub mod account;
+pub mod bank;
+pub mod channel;
+pub mod connection;
+pub mod node;
+pub mod transfer;
+pub mod tx;
+
+/// The header with which the gRPC gateway is asked for the state at a given height.
+const HEIGHT_HEADER: &str = "x-cosmos-block-height";
+
+/// The LCD endpoint of a chain.
+///
+/// The certificate of an `https` endpoint is verified against the Mozilla root CAs,
+/// the `tls` settings of the chain only apply to its gRPC endpoint.
+#[derive(Clone)]
+pub struct RestEndpoint {
+    /// The address of the endpoint, without a trailing slash.
+    url: String,
+    timeout: Duration,
+    client: Client<HttpsConnector<HttpConnector>>,
+}
+
+impl RestEndpoint {
+    /// The endpoint at the given address, whose queries fail after `timeout`.
+    pub fn new(url: &Url, timeout: Duration) -> Self {
+        Self {
+            url: url.to_string().trim_end_matches('/').to_string(),
+            timeout,
+            client: Client::builder().build(HttpsConnector::with_webpki_roots()),
+        }
+    }
+
+    /// Queries the given path, with the given query parameters, for the latest state.
+    pub async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        params: &[(&str, String)],
+    ) -> Result<T, Error> {
+        self.get_at_height(path, params, None).await
+    }
+
+    /// Queries the given path, with the given query parameters, for the state at the
+    /// given height, or for the latest state if none is given.
+    pub async fn get_at_height<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        params: &[(&str, String)],
+        height: Option<u64>,
+    ) -> Result<T, Error> {
+        let mut request = Request::builder().method(Method::GET).uri(format!(
+            "{}{}{}",
+            self.url,
+            path,
+            query_string(params)
+        ));
+
+        if let Some(height) = height {
+            request = request.header(HEIGHT_HEADER, height.to_string());
+        }
+
+        self.send(path, request, Body::empty()).await
+    }
+
+    /// Posts the given JSON body to the given path.
+    pub async fn post<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &serde_json::Value,
+    ) -> Result<T, Error> {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(format!("{}{}", self.url, path))
+            .header(CONTENT_TYPE, "application/json");
+
+        self.send(path, request, Body::from(body.to_string())).await
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        request: http::request::Builder,
+        body: Body,
+    ) -> Result<T, Error> {
+        let url = format!("{}{}", self.url, path);
+
+        let request = request
+            .header(ACCEPT, "application/json")
+            .body(body)
+            .map_err(|e| Error::rest_query(url.clone(), e.to_string()))?;
+
+        let (status, body) = tokio::time::timeout(self.timeout, async {
+            let response = self.client.request(request).await?;
+            let status = response.status();
+            let body = hyper::body::to_bytes(response.into_body()).await?;
+
+            Ok::<_, hyper::Error>((status, body))
+        })
+        .await
+        .map_err(|_| {
+            Error::rest_query(
+                url.clone(),
+                format!(
+                    "no response after {}",
+                    humantime::format_duration(self.timeout)
+                ),
+            )
+        })?
+        .map_err(|e| Error::rest_query(url.clone(), e.to_string()))?;
+
+        if !status.is_success() {
+            return Err(status_error(&url, status, &body));
+        }
+
+        serde_json::from_slice(&body)
+            .map_err(|e| Error::rest_decode(path.to_string(), e.to_string()))
+    }
+}
+
+impl fmt::Display for RestEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.url.fmt(f)
+    }
+}
+
+impl fmt::Debug for RestEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RestEndpoint")
+            .field("url", &self.url)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+/// The error of a query which the LCD endpoint answered with an error status.
+///
+/// The gRPC gateway reports the status of the gRPC call it forwarded the query to,
+/// which is returned as such, for the errors of the queries to be handled the same
+/// whichever endpoint they were sent to, e.g. an object which does not exist.
+fn status_error(url: &str, status: StatusCode, body: &[u8]) -> Error {
+    #[derive(Deserialize)]
+    struct GatewayError {
+        code: i32,
+        message: String,
+    }
+
+    match serde_json::from_slice::<GatewayError>(body) {
+        Ok(e) => Error::grpc_status(tonic::Status::new(tonic::Code::from_i32(e.code), e.message)),
+        Err(_) => Error::rest_query(
+            url.to_string(),
+            format!("{}: {}", status, String::from_utf8_lossy(body)),
+        ),
+    }
+}
+
+/// The query string of the given parameters, percent-encoded, or an empty string if there are none.
+fn query_string(params: &[(&str, String)]) -> String {
+    if params.is_empty() {
+        return String::new();
+    }
+
+    let params: Vec<String> = params
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, percent_encode(value)))
+        .collect();
+
+    format!("?{}", params.join("&"))
+}
+
+/// Percent-encodes all the characters of `value` but the unreserved ones.
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// The query parameters of the given pagination, if any, leaving out the default values.
+pub fn pagination_params(pagination: &Option<PageRequest>) -> Vec<(&'static str, String)> {
+    let pagination = match pagination {
+        Some(pagination) => pagination,
+        None => return Vec::new(),
+    };
+
+    let mut params = Vec::new();
+
+    if !pagination.key.is_empty() {
+        let key = subtle_encoding::base64::encode(&pagination.key);
+        params.push(("pagination.key", String::from_utf8_lossy(&key).into_owned()));
+    }
+
+    if pagination.offset != 0 {
+        params.push(("pagination.offset", pagination.offset.to_string()));
+    }
+
+    if pagination.limit != 0 {
+        params.push(("pagination.limit", pagination.limit.to_string()));
+    }
+
+    if pagination.count_total {
+        params.push(("pagination.count_total", "true".to_string()));
+    }
+
+    if pagination.reverse {
+        params.push(("pagination.reverse", "true".to_string()));
+    }
+
+    params
+}
+
+/// An unsigned 64-bit integer, which the JSON encoding of protobuf messages renders as a string.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct JsonU64(pub u64);
+
+impl<'de> Deserialize<'de> for JsonU64 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            String(String),
+            Number(u64),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::String(value) => value.parse().map(JsonU64).map_err(serde::de::Error::custom),
+            Raw::Number(value) => Ok(JsonU64(value)),
+        }
+    }
+}
+
+/// A height, in the JSON encoding of an `ibc.core.client.v1.Height`.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct JsonHeight {
+    pub revision_number: JsonU64,
+    pub revision_height: JsonU64,
+}
+
+impl JsonHeight {
+    /// The height, which the response to the query at `path` is at.
+    pub fn to_height(self, path: &str) -> Result<Height, Error> {
+        Height::new(self.revision_number.0, self.revision_height.0)
+            .map_err(|e| Error::rest_decode(path.to_string(), e.to_string()))
+    }
+}
+
+/// Decodes a bytes field, which the JSON encoding of protobuf messages renders in base64.
+pub fn decode_base64(path: &str, value: &str) -> Result<Vec<u8>, Error> {
+    subtle_encoding::base64::decode(value)
+        .map_err(|e| Error::rest_decode(path.to_string(), e.to_string()))
+}
+
+/// The response of an LCD endpoint recorded in the given file of `tests/lcd`.
+#[cfg(test)]
+pub(crate) fn recorded_response<T: DeserializeOwned>(name: &str) -> T {
+    let path = format!("{}/tests/lcd/{}", env!("CARGO_MANIFEST_DIR"), name);
+
+    serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pagination_params, query_string, status_error, JsonHeight, JsonU64};
+
+    use http::StatusCode;
+
+    use crate::chain::requests::PageRequest;
+    use crate::error::ErrorDetail;
+
+    #[test]
+    fn decode_json_u64() {
+        let values: Vec<JsonU64> = serde_json::from_str(r#"["18446744073709551615", 42]"#).unwrap();
+        assert_eq!(values, vec![JsonU64(u64::MAX), JsonU64(42)]);
+
+        assert!(serde_json::from_str::<JsonU64>(r#""-1""#).is_err());
+    }
+
+    #[test]
+    fn decode_json_height() {
+        let height: JsonHeight =
+            serde_json::from_str(r#"{"revision_number":"1","revision_height":"2547"}"#).unwrap();
+
+        assert_eq!(height.to_height("/path").unwrap().to_string(), "1-2547");
+
+        let zero: JsonHeight = serde_json::from_str("{}").unwrap();
+        assert!(zero.to_height("/path").is_err());
+    }
+
+    #[test]
+    fn encode_pagination() {
+        let pagination = PageRequest {
+            key: vec![0xfb, 0xff],
+            limit: 10,
+            reverse: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            query_string(&pagination_params(&Some(pagination))),
+            "?pagination.key=%2B%2F8%3D&pagination.limit=10&pagination.reverse=true"
+        );
+        assert_eq!(query_string(&pagination_params(&None)), "");
+    }
+
+    #[test]
+    fn gateway_error_as_grpc_status() {
+        let body = br#"{"code":5,"message":"denomination trace not found","details":[]}"#;
+        let error = status_error("http://127.0.0.1:1317/path", StatusCode::NOT_FOUND, body);
+
+        assert!(matches!(
+            error.detail(),
+            ErrorDetail::GrpcStatus(e) if e.status.code() == tonic::Code::NotFound
+        ));
+
+        let error = status_error(
+            "http://127.0.0.1:1317/path",
+            StatusCode::BAD_GATEWAY,
+            b"nginx",
+        );
+
+        assert!(matches!(error.detail(), ErrorDetail::RestQuery(_)));
+        assert!(error.detail().is_transient());
+    }
+}
@@ -0,0 +1,112 @@
+use ibc_proto::cosmos::auth::v1beta1::BaseAccount;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::chain::cosmos::rest::{JsonU64, RestEndpoint};
+use crate::error::Error;
+
+#[derive(Deserialize)]
+struct QueryAccountResponse {
+    account: Option<Value>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct JsonBaseAccount {
+    address: String,
+    account_number: JsonU64,
+    sequence: JsonU64,
+}
+
+/// Uses the LCD endpoint to retrieve the account sequence, see
+/// [`query_account`](crate::chain::cosmos::query::account::query_account).
+pub async fn query_account(
+    rest: &RestEndpoint,
+    account_address: &str,
+) -> Result<BaseAccount, Error> {
+    let path = format!("/cosmos/auth/v1beta1/accounts/{}", account_address);
+
+    let response: QueryAccountResponse = rest.get(&path, &[]).await?;
+
+    decode_account(&path, account_address, response)
+}
+
+/// The base account of the account of the response, which is a `BaseAccount`,
+/// or an `EthAccount` wrapping one. The public key of the account is left out.
+fn decode_account(
+    path: &str,
+    account_address: &str,
+    response: QueryAccountResponse,
+) -> Result<BaseAccount, Error> {
+    let account = response
+        .account
+        .ok_or_else(|| Error::empty_query_account(account_address.to_string()))?;
+
+    let type_url = account
+        .get("@type")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+
+    let base_account = if type_url == "/cosmos.auth.v1beta1.BaseAccount" {
+        account
+    } else if type_url.ends_with(".EthAccount") {
+        account
+            .get("base_account")
+            .cloned()
+            .ok_or_else(Error::empty_base_account)?
+    } else {
+        return Err(Error::unknown_account_type(type_url));
+    };
+
+    let base_account: JsonBaseAccount = serde_json::from_value(base_account)
+        .map_err(|e| Error::rest_decode(path.to_string(), e.to_string()))?;
+
+    Ok(BaseAccount {
+        address: base_account.address,
+        pub_key: None,
+        account_number: base_account.account_number.0,
+        sequence: base_account.sequence.0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode_account;
+
+    use crate::chain::cosmos::rest::recorded_response;
+    use crate::error::ErrorDetail;
+
+    #[test]
+    fn decode_base_account() {
+        let address = "cosmos1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5lzv7xu";
+        let account = decode_account("/path", address, recorded_response("account.json")).unwrap();
+
+        assert_eq!(account.address, address);
+        assert_eq!(account.account_number, 7);
+        assert_eq!(account.sequence, 1342);
+    }
+
+    #[test]
+    fn decode_eth_account() {
+        let address = "evmos1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5tvjq8x";
+        let account =
+            decode_account("/path", address, recorded_response("eth_account.json")).unwrap();
+
+        assert_eq!(account.address, address);
+        assert_eq!(account.account_number, 2291);
+        assert_eq!(account.sequence, 18);
+    }
+
+    #[test]
+    fn decode_unknown_account() {
+        let response = serde_json::from_str(
+            r#"{"account":{"@type":"/cosmos.auth.v1beta1.ModuleAccount","name":"transfer"}}"#,
+        )
+        .unwrap();
+
+        let error = decode_account("/path", "cosmos1", response).unwrap_err();
+
+        assert!(matches!(error.detail(), ErrorDetail::UnknownAccountType(_)));
+    }
+}
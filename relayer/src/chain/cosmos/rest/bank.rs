@@ -0,0 +1,57 @@
+use serde::Deserialize;
+
+use crate::account::Balance;
+use crate::chain::cosmos::rest::RestEndpoint;
+use crate::error::Error;
+
+#[derive(Deserialize)]
+struct QueryBalanceResponse {
+    balance: Option<JsonCoin>,
+}
+
+#[derive(Deserialize)]
+struct JsonCoin {
+    denom: String,
+    amount: String,
+}
+
+/// Uses the LCD endpoint to retrieve the account balance for a specific denom, see
+/// [`query_balance`](crate::chain::cosmos::query::balance::query_balance).
+pub async fn query_balance(
+    rest: &RestEndpoint,
+    account_address: &str,
+    denom: &str,
+) -> Result<Balance, Error> {
+    let path = format!("/cosmos/bank/v1beta1/balances/{}/by_denom", account_address);
+
+    let response: QueryBalanceResponse = rest.get(&path, &[("denom", denom.to_string())]).await?;
+
+    decode_balance(account_address, response)
+}
+
+fn decode_balance(account_address: &str, response: QueryBalanceResponse) -> Result<Balance, Error> {
+    // Querying for a balance might fail, i.e. if the account doesn't actually exist
+    let balance = response
+        .balance
+        .ok_or_else(|| Error::empty_query_account(account_address.to_string()))?;
+
+    Ok(Balance {
+        amount: balance.amount,
+        denom: balance.denom,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode_balance;
+
+    use crate::chain::cosmos::rest::recorded_response;
+
+    #[test]
+    fn decode_recorded_balance() {
+        let balance = decode_balance("cosmos1", recorded_response("balance.json")).unwrap();
+
+        assert_eq!(balance.denom, "uatom");
+        assert_eq!(balance.amount, "1234567");
+    }
+}
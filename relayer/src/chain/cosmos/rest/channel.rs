@@ -0,0 +1,244 @@
+use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics24_host::identifier::{ChannelId, PortId};
+use ibc::Height;
+use serde::Deserialize;
+
+use crate::chain::cosmos::rest::{pagination_params, JsonHeight, JsonU64, RestEndpoint};
+use crate::chain::requests::{
+    QueryPacketAcknowledgementsRequest, QueryPacketCommitmentsRequest, QueryUnreceivedAcksRequest,
+    QueryUnreceivedPacketsRequest,
+};
+use crate::error::Error;
+
+#[derive(Deserialize)]
+struct QueryPacketCommitmentsResponse {
+    #[serde(default)]
+    commitments: Vec<JsonPacketState>,
+    #[serde(default)]
+    height: JsonHeight,
+}
+
+#[derive(Deserialize)]
+struct QueryPacketAcknowledgementsResponse {
+    #[serde(default)]
+    acknowledgements: Vec<JsonPacketState>,
+    #[serde(default)]
+    height: JsonHeight,
+}
+
+/// A packet commitment or acknowledgement, of which only the sequence is needed.
+#[derive(Deserialize)]
+struct JsonPacketState {
+    sequence: JsonU64,
+}
+
+#[derive(Deserialize)]
+struct QueryUnreceivedResponse {
+    #[serde(default)]
+    sequences: Vec<JsonU64>,
+}
+
+/// The path of the queries about the packets of the given channel.
+fn channel_path(port_id: &PortId, channel_id: &ChannelId) -> String {
+    format!(
+        "/ibc/core/channel/v1/channels/{}/ports/{}",
+        channel_id, port_id
+    )
+}
+
+/// The given sequences, separated by commas, as the gRPC gateway takes them in a path.
+fn sequences_segment(sequences: &[Sequence]) -> String {
+    sequences
+        .iter()
+        .map(|sequence| sequence.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Uses the LCD endpoint to retrieve the sequences of the packet commitments
+/// of a channel, sorted, along with the height they were queried at.
+pub async fn query_packet_commitments(
+    rest: &RestEndpoint,
+    request: QueryPacketCommitmentsRequest,
+) -> Result<(Vec<Sequence>, Height), Error> {
+    let path = format!(
+        "{}/packet_commitments",
+        channel_path(&request.port_id, &request.channel_id)
+    );
+
+    let response: QueryPacketCommitmentsResponse = rest
+        .get(&path, &pagination_params(&request.pagination))
+        .await?;
+
+    decode_packet_commitments(&path, response)
+}
+
+fn decode_packet_commitments(
+    path: &str,
+    response: QueryPacketCommitmentsResponse,
+) -> Result<(Vec<Sequence>, Height), Error> {
+    let mut sequences: Vec<Sequence> = response
+        .commitments
+        .iter()
+        .map(|commitment| commitment.sequence.0.into())
+        .collect();
+    sequences.sort_unstable();
+
+    Ok((sequences, response.height.to_height(path)?))
+}
+
+/// Uses the LCD endpoint to retrieve the sequences of the packet acknowledgements
+/// of a channel, along with the height they were queried at.
+pub async fn query_packet_acknowledgements(
+    rest: &RestEndpoint,
+    request: QueryPacketAcknowledgementsRequest,
+) -> Result<(Vec<Sequence>, Height), Error> {
+    let path = format!(
+        "{}/packet_acknowledgements",
+        channel_path(&request.port_id, &request.channel_id)
+    );
+
+    let mut params = pagination_params(&request.pagination);
+    params.extend(
+        request
+            .packet_commitment_sequences
+            .iter()
+            .map(|sequence| ("packet_commitment_sequences", sequence.to_string())),
+    );
+
+    let response: QueryPacketAcknowledgementsResponse = rest.get(&path, &params).await?;
+
+    decode_packet_acknowledgements(&path, response)
+}
+
+fn decode_packet_acknowledgements(
+    path: &str,
+    response: QueryPacketAcknowledgementsResponse,
+) -> Result<(Vec<Sequence>, Height), Error> {
+    let sequences = response
+        .acknowledgements
+        .iter()
+        .map(|acknowledgement| acknowledgement.sequence.0.into())
+        .collect();
+
+    Ok((sequences, response.height.to_height(path)?))
+}
+
+/// Uses the LCD endpoint to retrieve the sequences, sorted, of the given packets
+/// which the counterparty chain has not received.
+pub async fn query_unreceived_packets(
+    rest: &RestEndpoint,
+    request: QueryUnreceivedPacketsRequest,
+) -> Result<Vec<Sequence>, Error> {
+    // The sequences are part of the path, which cannot end with an empty segment
+    if request.packet_commitment_sequences.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let path = format!(
+        "{}/packet_commitments/{}/unreceived_packets",
+        channel_path(&request.port_id, &request.channel_id),
+        sequences_segment(&request.packet_commitment_sequences)
+    );
+
+    let response: QueryUnreceivedResponse = rest.get(&path, &[]).await?;
+
+    Ok(decode_unreceived(response))
+}
+
+/// Uses the LCD endpoint to retrieve the sequences, sorted, of the given packets
+/// whose acknowledgement the counterparty chain has not received.
+pub async fn query_unreceived_acknowledgements(
+    rest: &RestEndpoint,
+    request: QueryUnreceivedAcksRequest,
+) -> Result<Vec<Sequence>, Error> {
+    if request.packet_ack_sequences.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let path = format!(
+        "{}/packet_commitments/{}/unreceived_acks",
+        channel_path(&request.port_id, &request.channel_id),
+        sequences_segment(&request.packet_ack_sequences)
+    );
+
+    let response: QueryUnreceivedResponse = rest.get(&path, &[]).await?;
+
+    Ok(decode_unreceived(response))
+}
+
+fn decode_unreceived(response: QueryUnreceivedResponse) -> Vec<Sequence> {
+    let mut sequences: Vec<Sequence> = response
+        .sequences
+        .iter()
+        .map(|sequence| sequence.0.into())
+        .collect();
+    sequences.sort_unstable();
+
+    sequences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        channel_path, decode_packet_acknowledgements, decode_packet_commitments, decode_unreceived,
+        sequences_segment,
+    };
+
+    use core::str::FromStr;
+
+    use ibc::core::ics04_channel::packet::Sequence;
+    use ibc::core::ics24_host::identifier::{ChannelId, PortId};
+
+    use crate::chain::cosmos::rest::recorded_response;
+
+    fn sequences(sequences: &[u64]) -> Vec<Sequence> {
+        sequences.iter().map(|&sequence| sequence.into()).collect()
+    }
+
+    #[test]
+    fn decode_recorded_packet_commitments() {
+        let (commitments, height) =
+            decode_packet_commitments("/path", recorded_response("packet_commitments.json"))
+                .unwrap();
+
+        assert_eq!(commitments, sequences(&[9, 12]));
+        assert_eq!(height.to_string(), "1-2547");
+    }
+
+    #[test]
+    fn decode_recorded_packet_acknowledgements() {
+        let (acknowledgements, height) = decode_packet_acknowledgements(
+            "/path",
+            recorded_response("packet_acknowledgements.json"),
+        )
+        .unwrap();
+
+        assert_eq!(acknowledgements, sequences(&[3]));
+        assert_eq!(height.to_string(), "1-2548");
+    }
+
+    #[test]
+    fn decode_recorded_unreceived_packets() {
+        let unreceived = decode_unreceived(recorded_response("unreceived_packets.json"));
+
+        assert_eq!(unreceived, sequences(&[9, 11]));
+    }
+
+    #[test]
+    fn unreceived_packets_path() {
+        let path = format!(
+            "{}/packet_commitments/{}/unreceived_packets",
+            channel_path(
+                &PortId::transfer(),
+                &ChannelId::from_str("channel-0").unwrap()
+            ),
+            sequences_segment(&sequences(&[1, 2, 5]))
+        );
+
+        assert_eq!(
+            path,
+            "/ibc/core/channel/v1/channels/channel-0/ports/transfer/packet_commitments/1,2,5/unreceived_packets"
+        );
+    }
+}
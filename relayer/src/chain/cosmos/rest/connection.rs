@@ -0,0 +1,130 @@
+use ibc::core::ics03_connection::connection::ConnectionEnd;
+use ibc::core::ics24_host::identifier::ConnectionId;
+use ibc_proto::ibc::core::connection::v1::{
+    ConnectionEnd as RawConnectionEnd, Counterparty as RawCounterparty, State as RawState,
+    Version as RawVersion,
+};
+use serde::Deserialize;
+
+use crate::chain::cosmos::rest::{JsonU64, RestEndpoint};
+use crate::chain::requests::QueryHeight;
+use crate::error::{Error, ErrorDetail};
+
+#[derive(Deserialize)]
+struct QueryConnectionResponse {
+    connection: Option<JsonConnectionEnd>,
+}
+
+/// A connection end, in the JSON encoding of an `ibc.core.connection.v1.ConnectionEnd`.
+#[derive(Deserialize)]
+struct JsonConnectionEnd {
+    client_id: String,
+    #[serde(default)]
+    versions: Vec<RawVersion>,
+    state: String,
+    counterparty: Option<RawCounterparty>,
+    #[serde(default)]
+    delay_period: JsonU64,
+}
+
+/// Uses the LCD endpoint to retrieve the end of the given connection at the given height.
+pub async fn query_connection(
+    rest: &RestEndpoint,
+    connection_id: &ConnectionId,
+    height_query: QueryHeight,
+) -> Result<ConnectionEnd, Error> {
+    let path = format!("/ibc/core/connection/v1/connections/{}", connection_id);
+
+    let height = match height_query {
+        QueryHeight::Latest => None,
+        QueryHeight::Specific(height) => Some(height.revision_height()),
+    };
+
+    let response: QueryConnectionResponse =
+        rest.get_at_height(&path, &[], height)
+            .await
+            .map_err(|e| match e.detail() {
+                ErrorDetail::GrpcStatus(detail)
+                    if detail.status.code() == tonic::Code::NotFound =>
+                {
+                    Error::connection_not_found(connection_id.clone())
+                }
+                _ => e,
+            })?;
+
+    decode_connection(&path, connection_id, response)
+}
+
+fn decode_connection(
+    path: &str,
+    connection_id: &ConnectionId,
+    response: QueryConnectionResponse,
+) -> Result<ConnectionEnd, Error> {
+    let connection = response
+        .connection
+        .ok_or_else(|| Error::connection_not_found(connection_id.clone()))?;
+
+    let state = match connection.state.as_str() {
+        "STATE_UNINITIALIZED_UNSPECIFIED" => RawState::UninitializedUnspecified,
+        "STATE_INIT" => RawState::Init,
+        "STATE_TRYOPEN" => RawState::Tryopen,
+        "STATE_OPEN" => RawState::Open,
+        state => {
+            return Err(Error::rest_decode(
+                path.to_string(),
+                format!("unknown connection state '{}'", state),
+            ))
+        }
+    };
+
+    let raw_connection = RawConnectionEnd {
+        client_id: connection.client_id,
+        versions: connection.versions,
+        state: state as i32,
+        counterparty: connection.counterparty,
+        delay_period: connection.delay_period.0,
+    };
+
+    raw_connection.try_into().map_err(Error::ics03)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode_connection;
+
+    use core::str::FromStr;
+    use core::time::Duration;
+
+    use ibc::core::ics03_connection::connection::State;
+    use ibc::core::ics24_host::identifier::{ClientId, ConnectionId};
+
+    use crate::chain::cosmos::rest::recorded_response;
+
+    #[test]
+    fn decode_recorded_connection() {
+        let connection_id = ConnectionId::from_str("connection-0").unwrap();
+
+        let connection_end = decode_connection(
+            "/path",
+            &connection_id,
+            recorded_response("connection.json"),
+        )
+        .unwrap();
+
+        assert_eq!(connection_end.state, State::Open);
+        assert_eq!(
+            connection_end.client_id(),
+            &ClientId::from_str("07-tendermint-0").unwrap()
+        );
+        assert_eq!(
+            connection_end.counterparty().connection_id(),
+            Some(&ConnectionId::from_str("connection-3").unwrap())
+        );
+        assert_eq!(
+            connection_end.counterparty().prefix().as_bytes(),
+            b"ibc".as_slice()
+        );
+        assert_eq!(connection_end.versions().len(), 1);
+        assert_eq!(connection_end.delay_period(), Duration::ZERO);
+    }
+}
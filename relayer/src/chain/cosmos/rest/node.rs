@@ -0,0 +1,81 @@
+use ibc_proto::cosmos::base::tendermint::v1beta1::{Module, VersionInfo};
+use serde::Deserialize;
+
+use crate::chain::cosmos::rest::RestEndpoint;
+use crate::error::Error;
+
+const NODE_INFO_PATH: &str = "/cosmos/base/tendermint/v1beta1/node_info";
+
+#[derive(Deserialize)]
+struct GetNodeInfoResponse {
+    application_version: Option<JsonVersionInfo>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct JsonVersionInfo {
+    name: String,
+    app_name: String,
+    version: String,
+    git_commit: String,
+    build_tags: String,
+    go_version: String,
+    build_deps: Vec<JsonModule>,
+    cosmos_sdk_version: String,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct JsonModule {
+    path: String,
+    version: String,
+    sum: String,
+}
+
+/// Uses the LCD endpoint to retrieve the version of the application run by the
+/// node, if it reports one.
+pub async fn query_version_info(rest: &RestEndpoint) -> Result<Option<VersionInfo>, Error> {
+    let response: GetNodeInfoResponse = rest.get(NODE_INFO_PATH, &[]).await?;
+
+    Ok(response.application_version.map(decode_version_info))
+}
+
+fn decode_version_info(version: JsonVersionInfo) -> VersionInfo {
+    VersionInfo {
+        name: version.name,
+        app_name: version.app_name,
+        version: version.version,
+        git_commit: version.git_commit,
+        build_tags: version.build_tags,
+        go_version: version.go_version,
+        build_deps: version
+            .build_deps
+            .into_iter()
+            .map(|module| Module {
+                path: module.path,
+                version: module.version,
+                sum: module.sum,
+            })
+            .collect(),
+        cosmos_sdk_version: version.cosmos_sdk_version,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_version_info, GetNodeInfoResponse};
+
+    use crate::chain::cosmos::rest::recorded_response;
+
+    #[test]
+    fn decode_recorded_node_info() {
+        let response: GetNodeInfoResponse = recorded_response("node_info.json");
+        let version = decode_version_info(response.application_version.unwrap());
+
+        assert_eq!(version.name, "gaia");
+        assert_eq!(version.cosmos_sdk_version, "v0.45.6");
+        assert_eq!(version.build_deps.len(), 2);
+        assert_eq!(version.build_deps[1].path, "github.com/cosmos/ibc-go/v3");
+        assert_eq!(version.build_deps[1].version, "v3.0.0");
+    }
+}
@@ -0,0 +1,90 @@
+use serde::Deserialize;
+
+use crate::chain::cosmos::rest::{pagination_params, RestEndpoint};
+use crate::chain::requests::QueryDenomTracesRequest;
+use crate::denom::DenomTrace;
+use crate::error::{Error, ErrorDetail};
+
+#[derive(Deserialize)]
+struct QueryDenomTraceResponse {
+    denom_trace: Option<DenomTrace>,
+}
+
+#[derive(Deserialize)]
+struct QueryDenomTracesResponse {
+    #[serde(default)]
+    denom_traces: Vec<DenomTrace>,
+}
+
+/// Uses the LCD endpoint to retrieve the denom trace for a specific hash, see
+/// [`query_denom_trace`](crate::chain::cosmos::query::denom_trace::query_denom_trace).
+pub async fn query_denom_trace(rest: &RestEndpoint, hash: &str) -> Result<DenomTrace, Error> {
+    let path = format!("/ibc/apps/transfer/v1/denom_traces/{}", hash);
+
+    let response: QueryDenomTraceResponse =
+        rest.get(&path, &[]).await.map_err(|e| match e.detail() {
+            ErrorDetail::GrpcStatus(detail) if detail.status.code() == tonic::Code::NotFound => {
+                Error::denom_trace_not_found(hash.to_owned())
+            }
+            _ => e,
+        })?;
+
+    response
+        .denom_trace
+        .ok_or_else(|| Error::empty_denom_trace(hash.to_owned()))
+}
+
+/// Uses the LCD endpoint to retrieve the denom traces known to the chain, a page at a time, see
+/// [`query_denom_traces`](crate::chain::cosmos::query::denom_trace::query_denom_traces).
+pub async fn query_denom_traces(
+    rest: &RestEndpoint,
+    request: QueryDenomTracesRequest,
+) -> Result<Vec<DenomTrace>, Error> {
+    let response: QueryDenomTracesResponse = rest
+        .get(
+            "/ibc/apps/transfer/v1/denom_traces",
+            &pagination_params(&request.pagination),
+        )
+        .await?;
+
+    Ok(response.denom_traces)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{QueryDenomTraceResponse, QueryDenomTracesResponse};
+
+    use crate::chain::cosmos::rest::recorded_response;
+
+    #[test]
+    fn decode_recorded_denom_trace() {
+        let response: QueryDenomTraceResponse = recorded_response("denom_trace.json");
+        let denom_trace = response.denom_trace.unwrap();
+
+        assert_eq!(denom_trace.path, "transfer/channel-0");
+        assert_eq!(denom_trace.base_denom, "samoleans");
+        assert_eq!(
+            denom_trace.ibc_denom(),
+            "ibc/27A6394C3F9FF9C9DCF5DFFADF9BB5FE9A37C7E92B006199894CF1824DF9AC7C"
+        );
+    }
+
+    #[test]
+    fn decode_recorded_denom_traces() {
+        let response: QueryDenomTracesResponse = recorded_response("denom_traces.json");
+
+        let full_paths: Vec<String> = response
+            .denom_traces
+            .iter()
+            .map(|denom_trace| denom_trace.full_path())
+            .collect();
+
+        assert_eq!(
+            full_paths,
+            vec![
+                "transfer/channel-0/samoleans",
+                "transfer/channel-1/transfer/channel-4/uosmo"
+            ]
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use ibc_proto::cosmos::base::abci::v1beta1::GasInfo;
+use ibc_proto::cosmos::tx::v1beta1::{SimulateResponse, Tx};
+use serde::Deserialize;
+
+use crate::chain::cosmos::rest::{JsonU64, RestEndpoint};
+use crate::error::Error;
+
+const SIMULATE_PATH: &str = "/cosmos/tx/v1beta1/simulate";
+
+#[derive(Deserialize)]
+struct JsonSimulateResponse {
+    #[serde(default)]
+    gas_info: JsonGasInfo,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct JsonGasInfo {
+    gas_wanted: JsonU64,
+    gas_used: JsonU64,
+}
+
+/// Uses the LCD endpoint to simulate the given transaction.
+///
+/// Only the gas information of the response is decoded, which is all the
+/// relayer uses a simulation for.
+pub async fn simulate(rest: &RestEndpoint, tx: Tx) -> Result<SimulateResponse, Error> {
+    let mut tx_bytes = vec![];
+    prost::Message::encode(&tx, &mut tx_bytes)
+        .map_err(|e| Error::protobuf_encode(String::from("Transaction"), e))?;
+
+    let body = serde_json::json!({
+        "tx_bytes": String::from_utf8_lossy(&subtle_encoding::base64::encode(&tx_bytes)),
+    });
+
+    let response: JsonSimulateResponse = rest.post(SIMULATE_PATH, &body).await?;
+
+    Ok(decode_simulate_response(response))
+}
+
+fn decode_simulate_response(response: JsonSimulateResponse) -> SimulateResponse {
+    SimulateResponse {
+        gas_info: Some(GasInfo {
+            gas_wanted: response.gas_info.gas_wanted.0,
+            gas_used: response.gas_info.gas_used.0,
+        }),
+        result: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode_simulate_response;
+
+    use crate::chain::cosmos::rest::recorded_response;
+
+    #[test]
+    fn decode_recorded_simulate_response() {
+        let response = decode_simulate_response(recorded_response("simulate.json"));
+        let gas_info = response.gas_info.unwrap();
+
+        assert_eq!(gas_info.gas_used, 81234);
+        assert_eq!(gas_info.gas_wanted, 0);
+    }
+}
@@ -2,13 +2,13 @@ use core::time::Duration;
 use ibc_proto::google::protobuf::Any;
 use std::thread;
 use tendermint::abci::Code;
-use tendermint_rpc::endpoint::broadcast::tx_sync::Response;
 use tracing::{debug, error, span, warn, Level};
 
 use crate::chain::cosmos::query::account::refresh_account;
 use crate::chain::cosmos::tx::estimate_fee_and_send_tx;
 use crate::chain::cosmos::types::account::Account;
 use crate::chain::cosmos::types::config::TxConfig;
+use crate::chain::cosmos::types::tx::TxSyncResponse;
 use crate::config::types::Memo;
 use crate::error::Error;
 use crate::keyring::KeyEntry;
@@ -39,7 +39,7 @@ pub async fn send_tx_with_account_sequence_retry(
     account: &mut Account,
     tx_memo: &Memo,
     messages: Vec<Any>,
-) -> Result<Response, Error> {
+) -> Result<TxSyncResponse, Error> {
     crate::time!("send_tx_with_account_sequence_retry");
 
     let _span =
@@ -56,7 +56,7 @@ async fn refresh_account_and_retry_send_tx_with_account_sequence(
     account: &mut Account,
     tx_memo: &Memo,
     messages: Vec<Any>,
-) -> Result<Response, Error> {
+) -> Result<TxSyncResponse, Error> {
     // Re-fetch the account s.n.
     refresh_account(&config.grpc_address, &key_entry.account, account).await?;
     // Retry after delay.
@@ -70,7 +70,7 @@ async fn do_send_tx_with_account_sequence_retry(
     account: &mut Account,
     tx_memo: &Memo,
     messages: Vec<Any>,
-) -> Result<Response, Error> {
+) -> Result<TxSyncResponse, Error> {
     match estimate_fee_and_send_tx(config, key_entry, account, tx_memo, messages.clone()).await {
         // Gas estimation failed with acct. s.n. mismatch at estimate gas step.
         // It indicates that the account sequence cached by hermes is stale (got < expected).
@@ -88,11 +88,11 @@ async fn do_send_tx_with_account_sequence_retry(
         }
 
         // Gas estimation succeeded but broadcast_tx_sync failed with a retry-able error.
-        Ok(ref response) if response.code == Code::Err(INCORRECT_ACCOUNT_SEQUENCE_ERR) => {
+        Ok(ref response) if response.response.code == Code::Err(INCORRECT_ACCOUNT_SEQUENCE_ERR) => {
             warn!(
                 "failed at broadcast_tx_sync step with incorrect account sequence {:?}.  \
                 refresh account sequence number and retry once",
-                response
+                response.response
             );
             refresh_account_and_retry_send_tx_with_account_sequence(
                 config, key_entry, account, tx_memo, messages,
@@ -104,10 +104,10 @@ async fn do_send_tx_with_account_sequence_retry(
         // an unrecoverable error.
         Ok(response) => {
             // Gas estimation and broadcast_tx_sync were successful.
-            match response.code {
+            match response.response.code {
                 Code::Ok => {
                     // Increase account s.n.
-                    debug!("broadcast_tx_sync: {:?}", response);
+                    debug!("broadcast_tx_sync: {:?}", response.response);
                     account.sequence.increment_mut();
                     Ok(response)
                 }
@@ -117,8 +117,9 @@ async fn do_send_tx_with_account_sequence_retry(
                     // Do not increase the account s.n. since CheckTx step of broadcast_tx_sync has failed.
                     // Log the error.
                     error!(
-                        "broadcast_tx_sync: {:?}: diagnostic: {:?}",
-                        response,
+                        "broadcast_tx_sync: {:?}: codespace: {}: diagnostic: {:?}",
+                        response.response,
+                        response.codespace,
                         sdk_error_from_tx_sync_error_code(code)
                     );
                     Ok(response)
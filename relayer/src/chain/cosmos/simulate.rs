@@ -1,12 +1,31 @@
 use ibc_proto::cosmos::tx::v1beta1::service_client::ServiceClient;
 use ibc_proto::cosmos::tx::v1beta1::{SimulateRequest, SimulateResponse, Tx};
-use tonic::codegen::http::Uri;
 
+use crate::chain::cosmos::grpc::GrpcEndpoint;
+use crate::chain::cosmos::rest;
 use crate::error::Error;
 
-pub async fn send_tx_simulate(grpc_address: &Uri, tx: Tx) -> Result<SimulateResponse, Error> {
+/// Simulates the given transaction through the gRPC endpoint of the chain,
+/// or its LCD endpoint while the gRPC endpoint is unavailable.
+pub async fn send_tx_simulate(
+    grpc_address: &GrpcEndpoint,
+    tx: Tx,
+) -> Result<SimulateResponse, Error> {
     crate::time!("send_tx_simulate");
 
+    grpc_address
+        .query_with_rest_fallback(
+            "simulate",
+            send_tx_simulate_grpc(grpc_address, tx.clone()),
+            |lcd| async move { rest::tx::simulate(&lcd, tx).await },
+        )
+        .await
+}
+
+async fn send_tx_simulate_grpc(
+    grpc_address: &GrpcEndpoint,
+    tx: Tx,
+) -> Result<SimulateResponse, Error> {
     // The `tx` field of `SimulateRequest` was deprecated in Cosmos SDK 0.43 in favor of `tx_bytes`.
     let mut tx_bytes = vec![];
     prost::Message::encode(&tx, &mut tx_bytes)
@@ -18,9 +37,7 @@ pub async fn send_tx_simulate(grpc_address: &Uri, tx: Tx) -> Result<SimulateResp
         tx_bytes,     // needed for simulation to go through with Cosmos SDk >= 0.43
     };
 
-    let mut client = ServiceClient::connect(grpc_address.clone())
-        .await
-        .map_err(Error::grpc_transport)?;
+    let mut client = ServiceClient::new(grpc_address.connect().await?);
 
     let request = tonic::Request::new(req);
     let response = client
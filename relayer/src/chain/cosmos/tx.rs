@@ -1,12 +1,18 @@
 use ibc_proto::cosmos::tx::v1beta1::Fee;
 use ibc_proto::google::protobuf::Any;
-use tendermint_rpc::endpoint::broadcast::tx_sync::Response;
-use tendermint_rpc::{Client, HttpClient, Url};
+use serde_derive::{Deserialize, Serialize};
+use std::time::SystemTime;
+use tendermint_rpc::endpoint::broadcast::tx_sync;
+use tendermint_rpc::{Client, HttpClient, Method, SimpleRequest, Url};
 
 use crate::chain::cosmos::encode::sign_and_encode_tx;
 use crate::chain::cosmos::estimate::estimate_tx_fees;
+use crate::chain::cosmos::failed_tx::{
+    try_dump_failed_tx, DumpedResponse, FailedTxDump, FailedTxStage,
+};
 use crate::chain::cosmos::types::account::Account;
 use crate::chain::cosmos::types::config::TxConfig;
+use crate::chain::cosmos::types::tx::TxSyncResponse;
 use crate::config::types::Memo;
 use crate::error::Error;
 use crate::keyring::KeyEntry;
@@ -17,7 +23,7 @@ pub async fn estimate_fee_and_send_tx(
     account: &Account,
     tx_memo: &Memo,
     messages: Vec<Any>,
-) -> Result<Response, Error> {
+) -> Result<TxSyncResponse, Error> {
     let fee = estimate_tx_fees(config, key_entry, account, tx_memo, messages.clone()).await?;
 
     send_tx_with_fee(config, key_entry, account, tx_memo, messages, &fee).await
@@ -30,10 +36,34 @@ async fn send_tx_with_fee(
     tx_memo: &Memo,
     messages: Vec<Any>,
     fee: &Fee,
-) -> Result<Response, Error> {
+) -> Result<TxSyncResponse, Error> {
     let tx_bytes = sign_and_encode_tx(config, key_entry, account, tx_memo, messages, fee)?;
 
-    let response = broadcast_tx_sync(&config.rpc_client, &config.rpc_address, tx_bytes).await?;
+    let max_tx_size = config.max_tx_size.into();
+    if tx_bytes.len() > max_tx_size {
+        return Err(Error::tx_size_exceeded(
+            config.chain_id.clone(),
+            tx_bytes.len(),
+            max_tx_size,
+        ));
+    }
+
+    let response =
+        broadcast_tx_sync(&config.rpc_client, &config.rpc_address, tx_bytes.clone()).await?;
+
+    if let (true, Some(dir)) = (response.response.code.is_err(), &config.failed_tx_dir) {
+        let dump = FailedTxDump::new(
+            &config.chain_id,
+            response.response.hash.to_string(),
+            config.correlation_id,
+            FailedTxStage::CheckTx,
+            &tx_bytes,
+            DumpedResponse::from_check_tx(&response),
+            SystemTime::now(),
+        );
+
+        try_dump_failed_tx(dir, &dump);
+    }
 
     Ok(response)
 }
@@ -43,11 +73,27 @@ async fn broadcast_tx_sync(
     rpc_client: &HttpClient,
     rpc_address: &Url,
     data: Vec<u8>,
-) -> Result<Response, Error> {
+) -> Result<TxSyncResponse, Error> {
     let response = rpc_client
-        .broadcast_tx_sync(data.into())
+        .perform(BroadcastTxSync(tx_sync::Request::new(data.into())))
         .await
         .map_err(|e| Error::rpc(rpc_address.clone(), e))?;
 
     Ok(response)
 }
+
+/// A `broadcast_tx_sync` request, whose response is deserialized along with
+/// the codespace of its error, see [`TxSyncResponse`].
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+struct BroadcastTxSync(tx_sync::Request);
+
+impl tendermint_rpc::Request for BroadcastTxSync {
+    type Response = TxSyncResponse;
+
+    fn method(&self) -> Method {
+        Method::BroadcastTxSync
+    }
+}
+
+impl SimpleRequest for BroadcastTxSync {}
@@ -1,12 +1,17 @@
-use core::str::FromStr;
 use core::time::Duration;
-use http::Uri;
 use ibc::core::ics24_host::identifier::ChainId;
+use ibc_proto::google::protobuf::Any;
+use std::path::PathBuf;
 use tendermint_rpc::{HttpClient, Url};
 
-use crate::chain::cosmos::types::gas::GasConfig;
+use crate::chain::cosmos::grpc::GrpcEndpoint;
+use crate::chain::cosmos::types::extension::extension_options;
+use crate::chain::cosmos::types::gas::{ExplicitFee, GasConfig};
+use crate::chain::tracking::CorrelationId;
+use crate::config::types::MaxTxSize;
 use crate::config::{AddressType, ChainConfig};
 use crate::error::Error;
+use crate::home;
 
 #[derive(Debug, Clone)]
 pub struct TxConfig {
@@ -14,9 +19,39 @@ pub struct TxConfig {
     pub gas_config: GasConfig,
     pub rpc_client: HttpClient,
     pub rpc_address: Url,
-    pub grpc_address: Uri,
+    pub grpc_address: GrpcEndpoint,
     pub rpc_timeout: Duration,
     pub address_type: AddressType,
+    pub extension_options: Vec<Any>,
+    pub failed_tx_dir: Option<PathBuf>,
+    pub max_tx_size: MaxTxSize,
+    /// The correlation id of the messages being sent, recorded in the file names
+    /// of the dumps of their failed transactions.
+    pub correlation_id: Option<CorrelationId>,
+}
+
+impl TxConfig {
+    /// This configuration, paying the given fee for each transaction instead of simulating it.
+    pub fn with_explicit_fee(&self, fee: ExplicitFee) -> Self {
+        let mut config = self.clone();
+        config.gas_config.explicit_fee = Some(fee);
+        config
+    }
+
+    /// This configuration, having the given account pay the fees through a fee grant.
+    pub fn with_fee_granter(mut self, fee_granter: String) -> Self {
+        self.gas_config.max_fee.granter = fee_granter.clone();
+        self.gas_config.fee_granter = fee_granter;
+        self
+    }
+
+    /// This configuration, sending messages tagged with the given correlation id.
+    pub fn with_correlation_id(self, correlation_id: CorrelationId) -> Self {
+        Self {
+            correlation_id: Some(correlation_id),
+            ..self
+        }
+    }
 }
 
 impl<'a> TryFrom<&'a ChainConfig> for TxConfig {
@@ -26,8 +61,7 @@ impl<'a> TryFrom<&'a ChainConfig> for TxConfig {
         let rpc_client = HttpClient::new(config.rpc_addr.clone())
             .map_err(|e| Error::rpc(config.rpc_addr.clone(), e))?;
 
-        let grpc_address = Uri::from_str(&config.grpc_addr.to_string())
-            .map_err(|e| Error::invalid_uri(config.grpc_addr.to_string(), e))?;
+        let grpc_address = GrpcEndpoint::from_config(config)?;
 
         let gas_config = GasConfig::from(config);
 
@@ -39,6 +73,10 @@ impl<'a> TryFrom<&'a ChainConfig> for TxConfig {
             grpc_address,
             rpc_timeout: config.rpc_timeout,
             address_type: config.address_type.clone(),
+            extension_options: extension_options(config)?,
+            failed_tx_dir: config.failed_tx_dir.as_deref().map(home::resolve_path),
+            max_tx_size: config.max_tx_size,
+            correlation_id: None,
         })
     }
 }
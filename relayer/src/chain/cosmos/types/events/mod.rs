@@ -2,10 +2,21 @@ use ibc::events::IbcEvent;
 use ibc::Height;
 use tendermint::abci::Event as AbciEvent;
 
+use crate::chain::cosmos::types::tx::MsgEvents;
+
 pub mod channel;
 pub mod client;
 pub mod connection;
 
+/// The attribute with which chains running Cosmos SDK 0.46+ tag each event
+/// emitted by a message with the index of the message in its transaction.
+const MSG_INDEX_ATTRIBUTE_KEY: &str = "msg_index";
+
+/// Events of this type with an `action` attribute are emitted by the Cosmos SDK
+/// before the events of each message of a transaction.
+const MESSAGE_EVENT_TYPE: &str = "message";
+const ACTION_ATTRIBUTE_KEY: &str = "action";
+
 pub fn from_tx_response_event(height: Height, event: &AbciEvent) -> Option<IbcEvent> {
     // Return the first hit we find
     if let Some(mut client_res) = client::try_from_tx(event) {
@@ -21,3 +32,255 @@ pub fn from_tx_response_event(height: Height, event: &AbciEvent) -> Option<IbcEv
         None
     }
 }
+
+/// Splits the events of a transaction comprising `message_count` messages
+/// into the IBC events emitted by each message, in the order of the messages.
+///
+/// The events are attributed to messages by their `msg_index` attribute. On chains
+/// which do not set it, the events of each message are assumed to follow the
+/// `message` event carrying the `action` of that message. On chains which emit
+/// neither, the messages cannot be told apart, and all the IBC events of the
+/// transaction are attributed to its first message, in the order they were emitted.
+pub fn split_events_by_messages(
+    height: Height,
+    events: &[AbciEvent],
+    message_count: usize,
+) -> Vec<MsgEvents> {
+    let mut msg_events = (0..message_count)
+        .map(|msg_index| MsgEvents {
+            msg_index,
+            events: vec![],
+        })
+        .collect::<Vec<_>>();
+
+    let has_msg_index = events.iter().any(|event| msg_index(event).is_some());
+    let has_message_actions = events.iter().any(is_message_action);
+
+    let mut current = if has_msg_index || has_message_actions {
+        None
+    } else {
+        Some(0)
+    };
+
+    for event in events {
+        if has_msg_index {
+            current = msg_index(event);
+        } else if is_message_action(event) {
+            current = Some(current.map_or(0, |index| index + 1));
+        }
+
+        // Events emitted outside of any message, eg. by the ante handler, carry no IBC event
+        let msg = match current.and_then(|index| msg_events.get_mut(index)) {
+            Some(msg) => msg,
+            None => continue,
+        };
+
+        if let Some(ibc_event) = from_tx_response_event(height, event) {
+            msg.events.push(ibc_event);
+        }
+    }
+
+    msg_events
+}
+
+fn msg_index(event: &AbciEvent) -> Option<usize> {
+    event
+        .attributes
+        .iter()
+        .find(|tag| tag.key.as_ref() == MSG_INDEX_ATTRIBUTE_KEY)
+        .and_then(|tag| tag.value.as_ref().parse().ok())
+}
+
+fn is_message_action(event: &AbciEvent) -> bool {
+    event.type_str == MESSAGE_EVENT_TYPE
+        && event
+            .attributes
+            .iter()
+            .any(|tag| tag.key.as_ref() == ACTION_ATTRIBUTE_KEY)
+}
+
+#[cfg(test)]
+mod tests {
+    use ibc::events::IbcEvent;
+    use ibc::Height;
+    use tendermint::abci::tag::Tag;
+    use tendermint::abci::Event as AbciEvent;
+
+    use super::split_events_by_messages;
+
+    fn event(type_str: &str, attributes: &[(&str, &str)]) -> AbciEvent {
+        AbciEvent {
+            type_str: type_str.to_string(),
+            attributes: attributes
+                .iter()
+                .map(|(key, value)| Tag {
+                    key: key.parse().unwrap(),
+                    value: value.parse().unwrap(),
+                })
+                .collect(),
+        }
+    }
+
+    fn with_msg_index(mut event: AbciEvent, msg_index: usize) -> AbciEvent {
+        event.attributes.push(Tag {
+            key: "msg_index".parse().unwrap(),
+            value: msg_index.to_string().parse().unwrap(),
+        });
+        event
+    }
+
+    fn packet_event(type_str: &str, sequence: &str) -> AbciEvent {
+        let mut attributes = vec![
+            ("packet_data", r#"{"amount":"100","denom":"stake"}"#),
+            ("packet_timeout_height", "0-1200"),
+            ("packet_timeout_timestamp", "0"),
+            ("packet_sequence", sequence),
+            ("packet_src_port", "transfer"),
+            ("packet_src_channel", "channel-0"),
+            ("packet_dst_port", "transfer"),
+            ("packet_dst_channel", "channel-1"),
+        ];
+
+        if type_str == "write_acknowledgement" {
+            attributes.push(("packet_ack", r#"{"result":"AQ=="}"#));
+        }
+
+        event(type_str, &attributes)
+    }
+
+    /// The events of a transaction comprising a `MsgUpdateClient`
+    /// followed by two `MsgRecvPacket`, as emitted by a chain which
+    /// does not tag them with the index of their message.
+    fn update_client_and_recv_packets() -> Vec<AbciEvent> {
+        vec![
+            event("tx", &[("fee", "1200stake")]),
+            event("tx", &[("acc_seq", "cosmos1relayer/42")]),
+            event(
+                "message",
+                &[("action", "/ibc.core.client.v1.MsgUpdateClient")],
+            ),
+            event(
+                "update_client",
+                &[
+                    ("client_id", "07-tendermint-0"),
+                    ("client_type", "07-tendermint"),
+                    ("consensus_height", "0-105"),
+                ],
+            ),
+            event("message", &[("module", "ibc_client")]),
+            event(
+                "message",
+                &[("action", "/ibc.core.channel.v1.MsgRecvPacket")],
+            ),
+            packet_event("recv_packet", "1"),
+            packet_event("write_acknowledgement", "1"),
+            event("message", &[("module", "ibc_channel")]),
+            event("fungible_token_packet", &[("success", "true")]),
+            event(
+                "message",
+                &[("action", "/ibc.core.channel.v1.MsgRecvPacket")],
+            ),
+            packet_event("recv_packet", "2"),
+            packet_event("write_acknowledgement", "2"),
+            event("message", &[("module", "ibc_channel")]),
+            event("fungible_token_packet", &[("success", "true")]),
+        ]
+    }
+
+    /// A summary of the IBC events attributed to each message.
+    fn summary(events: &[AbciEvent], message_count: usize) -> Vec<(usize, Vec<String>)> {
+        let height = Height::new(0, 110).unwrap();
+
+        split_events_by_messages(height, events, message_count)
+            .into_iter()
+            .map(|msg| {
+                let events = msg
+                    .events
+                    .iter()
+                    .map(|event| match event {
+                        IbcEvent::UpdateClient(_) => "update_client".to_string(),
+                        IbcEvent::WriteAcknowledgement(ev) => {
+                            format!("write_ack {}", ev.packet.sequence)
+                        }
+                        other => panic!("unexpected event {}", other),
+                    })
+                    .collect();
+
+                (msg.msg_index, events)
+            })
+            .collect()
+    }
+
+    /// The `recv_packet` events are not parsed from transactions, as only
+    /// their `write_acknowledgement` events are relayed.
+    fn expected() -> Vec<(usize, Vec<String>)> {
+        vec![
+            (0, vec!["update_client".to_string()]),
+            (1, vec!["write_ack 1".to_string()]),
+            (2, vec!["write_ack 2".to_string()]),
+        ]
+    }
+
+    #[test]
+    fn split_events_by_message_actions() {
+        assert_eq!(summary(&update_client_and_recv_packets(), 3), expected());
+    }
+
+    #[test]
+    fn split_interleaved_events_by_msg_index() {
+        let mut events = update_client_and_recv_packets();
+
+        // Tag the events of each message with its index, and leave the
+        // events of the ante handler untagged
+        let msg_indices = [0, 0, 0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2];
+        let mut tagged = events
+            .split_off(2)
+            .into_iter()
+            .zip(msg_indices)
+            .map(|(event, msg_index)| with_msg_index(event, msg_index))
+            .collect::<Vec<_>>();
+
+        // Interleave the events of both packets, so that the `write_acknowledgement`
+        // event of the first packet follows the `recv_packet` event of the second one
+        tagged.swap(5, 9);
+
+        events.extend(tagged);
+
+        assert_eq!(summary(&events, 3), expected());
+    }
+
+    #[test]
+    fn split_events_of_messages_without_events() {
+        let events = update_client_and_recv_packets()
+            .into_iter()
+            .take(5)
+            .collect::<Vec<_>>();
+
+        let summary = summary(&events, 2);
+        assert_eq!(summary[0], expected()[0]);
+        assert_eq!(summary[1], (1, vec![]));
+    }
+
+    #[test]
+    fn events_without_message_boundaries_are_all_kept() {
+        let events = update_client_and_recv_packets()
+            .into_iter()
+            .filter(|event| event.type_str != "message")
+            .collect::<Vec<_>>();
+
+        let summary = summary(&events, 3);
+        assert_eq!(
+            summary[0],
+            (
+                0,
+                vec![
+                    "update_client".to_string(),
+                    "write_ack 1".to_string(),
+                    "write_ack 2".to_string(),
+                ]
+            )
+        );
+        assert_eq!(summary[1], (1, vec![]));
+        assert_eq!(summary[2], (2, vec![]));
+    }
+}
@@ -0,0 +1,100 @@
+use ibc_proto::google::protobuf::Any;
+
+use crate::config::{AddressType, ChainConfig, TxExtension};
+use crate::error::Error;
+
+pub const ETHERMINT_DYNAMIC_FEE_TYPE_URL: &str = "/ethermint.types.v1.ExtensionOptionDynamicFeeTx";
+
+/// The `ExtensionOptionDynamicFeeTx` of Ethermint, which sets the maximum priority price
+/// of the transactions sent to chains whose fee market is enabled.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ExtensionOptionDynamicFeeTx {
+    /// The maximum priority price per unit of gas, as an `sdk.Int`.
+    #[prost(string, tag = "1")]
+    pub max_priority_price: String,
+}
+
+/// The extension option to include in the transactions sent to the chain with the given
+/// configuration, which is the `ethermint-dynamic-fee` one for Ethermint chains unless
+/// configured otherwise.
+pub fn tx_extension(config: &ChainConfig) -> TxExtension {
+    config.tx_extension.unwrap_or(match config.address_type {
+        AddressType::Ethermint { .. } => TxExtension::EthermintDynamicFee,
+        AddressType::Cosmos => TxExtension::None,
+    })
+}
+
+/// The encoded extension options to include in the body of the transactions sent to
+/// the chain with the given configuration.
+pub fn extension_options(config: &ChainConfig) -> Result<Vec<Any>, Error> {
+    match tx_extension(config) {
+        TxExtension::None => Ok(vec![]),
+        TxExtension::EthermintDynamicFee => {
+            let option = ExtensionOptionDynamicFeeTx {
+                max_priority_price: config.max_priority_price.to_string(),
+            };
+
+            let mut value = Vec::new();
+            prost::Message::encode(&option, &mut value).map_err(|e| {
+                Error::protobuf_encode("ExtensionOptionDynamicFeeTx".to_string(), e)
+            })?;
+
+            Ok(vec![Any {
+                type_url: ETHERMINT_DYNAMIC_FEE_TYPE_URL.to_string(),
+                value,
+            }])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extension_options, tx_extension};
+
+    use crate::chain::mock::test_utils::get_basic_chain_config;
+    use crate::config::{AddressType, TxExtension};
+
+    /// An `ExtensionOptionDynamicFeeTx` with a maximum priority price of 1 gwei, as `Any`.
+    const DYNAMIC_FEE_1_GWEI: &str = "0a2f2f65746865726d696e742e74797065732e76312e457874656e73696f6e4f7074696f6e44796e616d69634665655478120c0a0a31303030303030303030";
+
+    /// An `ExtensionOptionDynamicFeeTx` with a maximum priority price of zero, as `Any`.
+    const DYNAMIC_FEE_ZERO: &str = "0a2f2f65746865726d696e742e74797065732e76312e457874656e73696f6e4f7074696f6e44796e616d6963466565547812030a0130";
+
+    fn encode(option: &ibc_proto::google::protobuf::Any) -> String {
+        hex::encode(prost::Message::encode_to_vec(option))
+    }
+
+    fn ethermint() -> AddressType {
+        AddressType::Ethermint {
+            pk_type: "/ethermint.crypto.v1.ethsecp256k1.PubKey".to_string(),
+        }
+    }
+
+    #[test]
+    fn tx_extension_defaults_to_address_type() {
+        let mut config = get_basic_chain_config("chain_A");
+        assert_eq!(tx_extension(&config), TxExtension::None);
+
+        config.address_type = ethermint();
+        assert_eq!(tx_extension(&config), TxExtension::EthermintDynamicFee);
+
+        config.tx_extension = Some(TxExtension::None);
+        assert_eq!(tx_extension(&config), TxExtension::None);
+        assert!(extension_options(&config).unwrap().is_empty());
+    }
+
+    #[test]
+    fn ethermint_dynamic_fee_encoding() {
+        let mut config = get_basic_chain_config("evmos_9001-2");
+        config.tx_extension = Some(TxExtension::EthermintDynamicFee);
+
+        let options = extension_options(&config).unwrap();
+        assert_eq!(options.len(), 1);
+        assert_eq!(encode(&options[0]), DYNAMIC_FEE_ZERO);
+
+        config.max_priority_price = 1_000_000_000;
+
+        let options = extension_options(&config).unwrap();
+        assert_eq!(encode(&options[0]), DYNAMIC_FEE_1_GWEI);
+    }
+}
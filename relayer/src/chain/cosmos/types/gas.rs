@@ -1,3 +1,4 @@
+use ibc_proto::cosmos::base::v1beta1::Coin;
 use ibc_proto::cosmos::tx::v1beta1::Fee;
 
 use crate::chain::cosmos::calculate_fee;
@@ -19,6 +20,17 @@ pub struct GasConfig {
     pub gas_price: GasPrice,
     pub max_fee: Fee,
     pub fee_granter: String,
+    /// The fee to pay for each transaction instead of the fee
+    /// computed from the gas estimated by simulating it.
+    pub explicit_fee: Option<ExplicitFee>,
+}
+
+/// A fee given explicitly for a transaction, which is then not simulated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExplicitFee {
+    pub amount: Coin,
+    /// The gas limit of the transaction, the `default_gas` of the chain if unset.
+    pub gas_limit: Option<u64>,
 }
 
 impl<'a> From<&'a ChainConfig> for GasConfig {
@@ -30,6 +42,7 @@ impl<'a> From<&'a ChainConfig> for GasConfig {
             gas_price: config.gas_price.clone(),
             max_fee: max_fee_from_config(config),
             fee_granter: fee_granter_from_config(config),
+            explicit_fee: None,
         }
     }
 }
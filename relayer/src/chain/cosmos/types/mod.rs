@@ -1,5 +1,6 @@
 pub mod account;
 pub mod config;
 pub mod events;
+pub mod extension;
 pub mod gas;
 pub mod tx;
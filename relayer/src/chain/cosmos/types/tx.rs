@@ -1,5 +1,7 @@
-use ibc::events::IbcEvent;
+use ibc::core::ics24_host::identifier::ChainId;
+use ibc::events::{ChainErrorEvent, IbcEvent};
 use ibc_proto::cosmos::tx::v1beta1::{AuthInfo, TxBody};
+use serde_derive::{Deserialize, Serialize};
 use tendermint_rpc::endpoint::broadcast::tx_sync::Response;
 
 pub struct SignedTx {
@@ -15,10 +17,51 @@ pub enum TxStatus {
     ReceivedResponse,
 }
 
+/// The IBC events emitted by one of the messages of a transaction.
+#[derive(Clone, Debug)]
+pub struct MsgEvents {
+    // the index of the message among the messages sent together
+    pub msg_index: usize,
+    pub events: Vec<IbcEvent>,
+}
+
+/// The response to a `broadcast_tx_sync` request, along with the codespace of the
+/// error reported by `check_tx`, which the [`Response`] of `tendermint-rpc` leaves out.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TxSyncResponse {
+    #[serde(flatten)]
+    pub response: Response,
+    #[serde(default)]
+    pub codespace: String,
+}
+
+impl tendermint_rpc::Response for TxSyncResponse {}
+
+impl TxSyncResponse {
+    /// The `ChainError` event reporting the error of `check_tx`, if any, which is
+    /// that of the transaction as a whole.
+    pub fn check_tx_error(&self, chain_id: &ChainId) -> Option<IbcEvent> {
+        if self.response.code.is_ok() {
+            return None;
+        }
+
+        let error = ChainErrorEvent::new(
+            format!(
+                "check_tx (broadcast_tx_sync) on chain {} for Tx hash {} reports error: code={:?}, codespace={:?}, log={:?}",
+                chain_id, self.response.hash, self.response.code, self.codespace, self.response.log
+            ),
+            self.response.code.value(),
+            self.codespace.clone(),
+        );
+
+        Some(IbcEvent::ChainError(error))
+    }
+}
+
 pub struct TxSyncResult {
     // the broadcast_tx_sync response
     pub response: Response,
-    // the events generated by a Tx once executed
-    pub events: Vec<IbcEvent>,
+    // the events generated by a Tx once executed, grouped by message
+    pub events: Vec<MsgEvents>,
     pub status: TxStatus,
 }
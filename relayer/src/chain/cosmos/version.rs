@@ -3,6 +3,7 @@
 //! is captured in a domain-type semver format in [`Specs`].
 
 use flex_error::define_error;
+use serde::Serialize;
 use tracing::trace;
 
 use ibc_proto::cosmos::base::tendermint::v1beta1::VersionInfo;
@@ -37,6 +38,22 @@ pub struct Specs {
     pub tendermint_version: semver::Version,
 }
 
+/// The versions of the software run by a node, as reported by its
+/// RPC and gRPC endpoints.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct NodeVersions {
+    /// The name and version of the application, as reported by the `/abci_info` RPC endpoint.
+    pub app: String,
+    /// The version of Tendermint run by the node, as reported by the `/status` RPC endpoint.
+    pub tendermint_version: String,
+    /// The version of the SDK the application was built with,
+    /// as reported by the `GetNodeInfo` gRPC query.
+    pub sdk_version: String,
+    /// The version of the IBC-go module the application was built with, if any,
+    /// as reported by the `GetNodeInfo` gRPC query.
+    pub ibc_go_version: Option<String>,
+}
+
 define_error! {
     Error {
         SdkModuleNotFound
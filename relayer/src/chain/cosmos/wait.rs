@@ -1,28 +1,38 @@
 use core::time::Duration;
 use ibc::core::ics24_host::identifier::ChainId;
-use ibc::events::IbcEvent;
+use ibc::events::{ChainErrorEvent, IbcEvent};
 use ibc::Height;
 use itertools::Itertools;
+use std::path::Path;
 use std::thread;
-use std::time::Instant;
+use std::time::{Instant, SystemTime};
 use tendermint_rpc::{HttpClient, Url};
 use tracing::{info, trace};
 
+use crate::chain::cosmos::failed_tx::{
+    try_dump_failed_tx, DumpedResponse, FailedTxDump, FailedTxStage,
+};
 use crate::chain::cosmos::query::tx::query_tx_response;
-use crate::chain::cosmos::types::events::from_tx_response_event;
-use crate::chain::cosmos::types::tx::{TxStatus, TxSyncResult};
+use crate::chain::cosmos::types::events::split_events_by_messages;
+use crate::chain::cosmos::types::tx::{MsgEvents, TxStatus, TxSyncResult};
+use crate::chain::tracking::CorrelationId;
 use crate::error::Error;
+use crate::sdk_error::failed_msg_index;
 
 const WAIT_BACKOFF: Duration = Duration::from_millis(300);
 
 /// Given a vector of `TxSyncResult` elements,
 /// each including a transaction response hash for one or more messages, periodically queries the chain
 /// with the transaction hashes to get the list of IbcEvents included in those transactions.
+/// The transactions which failed are dumped to `failed_tx_dir`, if given, under
+/// a file name including the `correlation_id` of their messages, if any.
 pub async fn wait_for_block_commits(
     chain_id: &ChainId,
     rpc_client: &HttpClient,
     rpc_address: &Url,
     rpc_timeout: &Duration,
+    failed_tx_dir: Option<&Path>,
+    correlation_id: Option<CorrelationId>,
     tx_sync_results: &mut [TxSyncResult],
 ) -> Result<(), Error> {
     let start_time = Instant::now();
@@ -57,8 +67,15 @@ pub async fn wait_for_block_commits(
 
             for tx_sync_result in tx_sync_results.iter_mut() {
                 // ignore error
-                let _ =
-                    update_tx_sync_result(chain_id, rpc_client, rpc_address, tx_sync_result).await;
+                let _ = update_tx_sync_result(
+                    chain_id,
+                    rpc_client,
+                    rpc_address,
+                    failed_tx_dir,
+                    correlation_id,
+                    tx_sync_result,
+                )
+                .await;
             }
         }
     }
@@ -68,6 +85,8 @@ async fn update_tx_sync_result(
     chain_id: &ChainId,
     rpc_client: &HttpClient,
     rpc_address: &Url,
+    failed_tx_dir: Option<&Path>,
+    correlation_id: Option<CorrelationId>,
     tx_sync_result: &mut TxSyncResult,
 ) -> Result<(), Error> {
     if let TxStatus::Pending { message_count } = tx_sync_result.status {
@@ -78,22 +97,53 @@ async fn update_tx_sync_result(
             tx_sync_result.status = TxStatus::ReceivedResponse;
 
             if response.tx_result.code.is_err() {
-                tx_sync_result.events = vec![
-                    IbcEvent::ChainError(format!(
-                        "deliver_tx for {} reports error: code={:?}, log={:?}",
-                        response.hash, response.tx_result.code, response.tx_result.log
-                    ));
-                    message_count
-                ];
+                let dump_path = failed_tx_dir.and_then(|dir| {
+                    let dump = FailedTxDump::new(
+                        chain_id,
+                        response.hash.to_string(),
+                        correlation_id,
+                        FailedTxStage::DeliverTx,
+                        response.tx.as_bytes(),
+                        DumpedResponse::from_deliver_tx(&response.tx_result),
+                        SystemTime::now(),
+                    );
+
+                    try_dump_failed_tx(dir, &dump)
+                });
+
+                let dump_note = dump_path
+                    .map(|path| format!(", dumped to {}", path.display()))
+                    .unwrap_or_default();
+
+                let tx_result = &response.tx_result;
+
+                let error = ChainErrorEvent::new(
+                    format!(
+                        "deliver_tx for {} reports error: code={:?}, codespace={:?}, log={:?}{}",
+                        response.hash,
+                        tx_result.code,
+                        tx_result.codespace.to_string(),
+                        tx_result.log,
+                        dump_note
+                    ),
+                    tx_result.code.value(),
+                    tx_result.codespace.to_string(),
+                )
+                .with_msg_index(failed_msg_index(&tx_result.log.to_string()));
+
+                let error = IbcEvent::ChainError(error);
+
+                tx_sync_result.events = (0..message_count)
+                    .map(|msg_index| MsgEvents {
+                        msg_index,
+                        events: vec![error.clone()],
+                    })
+                    .collect();
             } else {
                 let height = Height::new(chain_id.version(), u64::from(response.height)).unwrap();
 
-                tx_sync_result.events = response
-                    .tx_result
-                    .events
-                    .iter()
-                    .flat_map(|event| from_tx_response_event(height, event).into_iter())
-                    .collect::<Vec<_>>();
+                tx_sync_result.events =
+                    split_events_by_messages(height, &response.tx_result.events, message_count);
             }
         }
     }
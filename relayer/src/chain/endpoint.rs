@@ -1,5 +1,6 @@
 use alloc::sync::Arc;
 use core::convert::TryFrom;
+use core::time::Duration;
 use ibc::core::ics23_commitment::merkle::MerkleProof;
 
 use tokio::runtime::Runtime as TokioRuntime;
@@ -17,30 +18,35 @@ use ibc::core::ics04_channel::channel::{ChannelEnd, IdentifiedChannelEnd};
 use ibc::core::ics04_channel::packet::{PacketMsgType, Sequence};
 use ibc::core::ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes};
 use ibc::core::ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId};
-use ibc::events::IbcEvent;
+use ibc::events::{IbcEvent, IbcEventType};
 use ibc::proofs::{ConsensusProof, Proofs};
 use ibc::signer::Signer;
 use ibc::timestamp::Timestamp;
 use ibc::Height as ICSHeight;
-use tendermint_rpc::endpoint::broadcast::tx_sync::Response as TxResponse;
 
 use crate::account::Balance;
 use crate::chain::client::ClientSettings;
+use crate::chain::cosmos::types::tx::TxSyncResponse;
+use crate::chain::cosmos::version::NodeVersions;
 use crate::chain::requests::{
-    QueryChannelClientStateRequest, QueryChannelRequest, QueryChannelsRequest,
-    QueryClientConnectionsRequest, QueryClientStateRequest, QueryClientStatesRequest,
-    QueryConnectionChannelsRequest, QueryConnectionRequest, QueryConnectionsRequest,
-    QueryConsensusStateRequest, QueryConsensusStatesRequest, QueryHostConsensusStateRequest,
-    QueryNextSequenceReceiveRequest, QueryPacketAcknowledgementsRequest,
-    QueryPacketCommitmentsRequest, QueryUnreceivedAcksRequest, QueryUnreceivedPacketsRequest,
-    QueryUpgradedClientStateRequest, QueryUpgradedConsensusStateRequest,
+    QueryChannelClientStateRequest, QueryChannelOpenTxRequest, QueryChannelRequest,
+    QueryChannelsRequest, QueryClientConnectionsRequest, QueryClientStateRequest,
+    QueryClientStatesRequest, QueryConnectionChannelsRequest, QueryConnectionRequest,
+    QueryConnectionsRequest, QueryConsensusStateRequest, QueryConsensusStatesRequest,
+    QueryDenomTracesRequest, QueryHostConsensusStateRequest, QueryIncentivizedPacketRequest,
+    QueryNextSequenceReceiveRequest, QueryNextSequenceSendRequest,
+    QueryPacketAcknowledgementsRequest, QueryPacketCommitmentsRequest, QueryPacketRelayTxsRequest,
+    QueryPacketTxRequest, QuerySendPacketTxsRequest, QueryUnreceivedAcksRequest,
+    QueryUnreceivedPacketsRequest, QueryUpgradedClientStateRequest,
+    QueryUpgradedConsensusStateRequest, QueryWrittenAcksRequest,
 };
 use crate::chain::tracking::TrackedMsgs;
 use crate::config::ChainConfig;
 use crate::connection::ConnectionMsgType;
-use crate::denom::DenomTrace;
+use crate::denom::{DenomDisplay, DenomTrace};
 use crate::error::{Error, QUERY_PROOF_EXPECT_MSG};
 use crate::event::monitor::{EventReceiver, TxMonitorCmd};
+use crate::fee::PacketFee;
 use crate::keyring::{KeyEntry, KeyRing};
 use crate::light_client::LightClient;
 
@@ -63,6 +69,84 @@ pub struct ChainStatus {
     pub timestamp: Timestamp,
 }
 
+/// The result of the sync status query, as reported by the node.
+#[derive(Clone, Debug)]
+pub struct SyncStatus {
+    pub height: ICSHeight,
+    pub timestamp: Timestamp,
+    pub catching_up: bool,
+    /// The chain identifier reported by the node.
+    pub network: ChainId,
+    pub node_version: String,
+}
+
+impl SyncStatus {
+    /// How far the latest block of the node lags behind `now`.
+    ///
+    /// Returns zero if the latest block is ahead of `now`,
+    /// eg. because of clock drift.
+    pub fn lag(&self, now: Timestamp) -> Duration {
+        now.duration_since(&self.timestamp).unwrap_or_default()
+    }
+
+    /// Whether the node has caught up and, if `max_lag` is given,
+    /// its latest block lags behind `now` by no more than `max_lag`.
+    pub fn is_synced(&self, now: Timestamp, max_lag: Option<Duration>) -> bool {
+        !self.catching_up && max_lag.map_or(true, |max_lag| self.lag(now) <= max_lag)
+    }
+}
+
+/// A transaction of the opening handshake of a channel end, e.g. the one which completed it.
+#[derive(Clone, Debug)]
+pub struct ChannelOpenTx {
+    /// `OpenInitChannel` or `OpenTryChannel` for the first step of the handshake on the
+    /// channel end, `OpenConfirmChannel`, or `OpenAckChannel` on the end which initiated
+    /// the handshake, for the last one.
+    pub event_type: IbcEventType,
+    pub height: ICSHeight,
+    pub tx_hash: String,
+    /// The account which submitted the transaction, if reported by the chain.
+    pub sender: Option<String>,
+}
+
+/// A transaction which relayed packets or acknowledgements to a channel end.
+#[derive(Clone, Debug)]
+pub struct PacketRelayTx {
+    /// `ReceivePacket` for packets, or `AckPacket` for acknowledgements.
+    pub event_type: IbcEventType,
+    pub height: ICSHeight,
+    /// The account which submitted the transaction, if reported by the chain.
+    pub sender: Option<String>,
+}
+
+/// A transaction which sent a packet from a channel end.
+#[derive(Clone, Debug)]
+pub struct SendPacketTx {
+    pub sequence: Sequence,
+    pub height: ICSHeight,
+    pub tx_hash: String,
+}
+
+/// A transaction of the lifecycle of a packet.
+#[derive(Clone, Debug)]
+pub struct PacketTx {
+    /// `SendPacket`, `AckPacket`, `Timeout` or `TimeoutOnClose` on the source channel end
+    /// of the packet, `ReceivePacket` or `WriteAck` on its destination channel end.
+    pub event_type: IbcEventType,
+    pub height: ICSHeight,
+    pub tx_hash: String,
+    /// The account which submitted the transaction, if reported by the chain.
+    pub sender: Option<String>,
+}
+
+/// An acknowledgement written for a packet received on a channel end.
+#[derive(Clone, Debug)]
+pub struct WrittenAck {
+    pub sequence: Sequence,
+    pub height: ICSHeight,
+    pub ack: Vec<u8>,
+}
+
 /// Defines a blockchain as understood by the relayer
 pub trait ChainEndpoint: Sized {
     /// Type of light blocks for this chain
@@ -119,7 +203,11 @@ pub trait ChainEndpoint: Sized {
     fn send_messages_and_wait_check_tx(
         &mut self,
         tracked_msgs: TrackedMsgs,
-    ) -> Result<Vec<TxResponse>, Error>;
+    ) -> Result<Vec<TxSyncResponse>, Error>;
+
+    /// Estimates the gas needed to submit the transactions with `msgs` to chain,
+    /// by simulating them, without broadcasting anything.
+    fn estimate_messages_gas(&mut self, tracked_msgs: &TrackedMsgs) -> Result<u64, Error>;
 
     fn get_signer(&mut self) -> Result<Signer, Error>;
 
@@ -129,20 +217,87 @@ pub trait ChainEndpoint: Sized {
 
     fn add_key(&mut self, key_name: &str, key: KeyEntry) -> Result<(), Error>;
 
+    /// Sign the transactions submitted to the chain from now on with the given key,
+    /// after checking that it is in the keyring and has a balance to pay the fees.
+    fn rotate_key(&mut self, key_name: &str) -> Result<(), Error>;
+
     /// Return the version of the IBC protocol that this chain is running, if known.
     fn ibc_version(&self) -> Result<Option<semver::Version>, Error>;
 
     // Queries
 
-    /// Query the balance of the given account for the denom used to pay tx fees.
+    /// Query the balance of the given account for the given denom, or else for the denom
+    /// used to pay tx fees.
     /// If no account is given, behavior must be specified, e.g. retrieve it from configuration file.
-    fn query_balance(&self, key_name: Option<String>) -> Result<Balance, Error>;
+    fn query_balance(
+        &self,
+        key_name: Option<String>,
+        denom: Option<String>,
+    ) -> Result<Balance, Error>;
 
     /// Query the denomination trace given a trace hash.
     fn query_denom_trace(&self, hash: String) -> Result<DenomTrace, Error>;
 
+    /// Query the denomination traces of the IBC vouchers known to the chain.
+    fn query_denom_traces(
+        &self,
+        request: QueryDenomTracesRequest,
+    ) -> Result<Vec<DenomTrace>, Error>;
+
+    /// Query the display unit of a denomination in its bank metadata,
+    /// returning `None` if the denomination has no metadata.
+    fn query_denom_metadata(&self, denom: String) -> Result<Option<DenomDisplay>, Error>;
+
+    /// Query the ICS-29 fees escrowed for a packet sent on the chain.
+    fn query_incentivized_packet(
+        &self,
+        request: QueryIncentivizedPacketRequest,
+    ) -> Result<Vec<PacketFee>, Error>;
+
+    /// Query the transaction of the opening handshake which brought a channel end to a state.
+    fn query_channel_open_tx(
+        &self,
+        request: QueryChannelOpenTxRequest,
+    ) -> Result<Option<ChannelOpenTx>, Error>;
+
+    /// Query the transactions which relayed packets or acknowledgements to a channel end.
+    fn query_packet_relay_txs(
+        &self,
+        request: QueryPacketRelayTxsRequest,
+    ) -> Result<Vec<PacketRelayTx>, Error>;
+
+    /// Query the transactions which sent the given packets from a channel end.
+    fn query_send_packet_txs(
+        &self,
+        request: QuerySendPacketTxsRequest,
+    ) -> Result<Vec<SendPacketTx>, Error>;
+
+    /// Query the transaction which emitted the given event of the lifecycle of a packet.
+    fn query_packet_tx(&self, request: QueryPacketTxRequest) -> Result<Option<PacketTx>, Error>;
+
+    /// Query the acknowledgements written for the given packets received on a channel end.
+    fn query_written_acks(
+        &self,
+        request: QueryWrittenAcksRequest,
+    ) -> Result<Vec<WrittenAck>, Error>;
+
+    /// Query the fully-qualified names of the gRPC query services registered on the chain.
+    fn query_services(&self) -> Result<Vec<String>, Error>;
+
+    /// Query the types of the light clients which may be created on the chain.
+    fn query_allowed_clients(&self) -> Result<Vec<String>, Error>;
+
+    /// Query the versions of the software run by the node of the chain.
+    fn query_node_versions(&self) -> Result<NodeVersions, Error>;
+
+    /// Query the current unbonding period of the chain.
+    fn query_unbonding_period(&self) -> Result<Duration, Error>;
+
     fn query_commitment_prefix(&self) -> Result<CommitmentPrefix, Error>;
 
+    /// Query the `max_expected_time_per_block` parameter of the IBC connection module.
+    fn query_max_expected_time_per_block(&self) -> Result<Duration, Error>;
+
     fn query_compatible_versions(&self) -> Result<Vec<Version>, Error> {
         // TODO - do a real chain query
         Ok(get_compatible_versions())
@@ -151,6 +306,9 @@ pub trait ChainEndpoint: Sized {
     /// Query the latest height and timestamp the application is at
     fn query_application_status(&self) -> Result<ChainStatus, Error>;
 
+    /// Query the latest block of the node and whether it is still catching up
+    fn query_sync_status(&self) -> Result<SyncStatus, Error>;
+
     /// Performs a query to retrieve the state of all clients that a chain hosts.
     fn query_clients(
         &self,
@@ -313,6 +471,15 @@ pub trait ChainEndpoint: Sized {
         include_proof: IncludeProof,
     ) -> Result<(Sequence, Option<MerkleProof>), Error>;
 
+    /// Performs a query to retrieve `nextSequenceSend` stored at path
+    /// `path::SeqSendsPath` as defined in ICS-4. A proof can optionally be
+    /// returned along with the result.
+    fn query_next_sequence_send(
+        &self,
+        request: QueryNextSequenceSendRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(Sequence, Option<MerkleProof>), Error>;
+
     fn query_txs(&self, request: QueryTxRequest) -> Result<Vec<IbcEvent>, Error>;
 
     fn query_blocks(
@@ -1,5 +1,6 @@
 use alloc::sync::Arc;
 use core::fmt::{self, Debug};
+use core::time::Duration;
 
 use crossbeam_channel as channel;
 use serde::Serialize;
@@ -34,25 +35,34 @@ use crate::{
     account::Balance,
     config::ChainConfig,
     connection::ConnectionMsgType,
-    denom::DenomTrace,
+    denom::{ibc_denom_hash, DenomDisplay, DenomTrace},
     error::Error,
     event::monitor::{EventBatch, Result as MonitorResult},
+    fee::PacketFee,
     keyring::KeyEntry,
 };
 
 use super::{
+    block_time::MaxBlockTime,
     client::ClientSettings,
-    endpoint::{ChainStatus, HealthCheck},
+    cosmos::version::NodeVersions,
+    endpoint::{
+        ChainStatus, ChannelOpenTx, HealthCheck, PacketRelayTx, PacketTx, SendPacketTx, SyncStatus,
+        WrittenAck,
+    },
     requests::{
-        IncludeProof, QueryBlockRequest, QueryChannelClientStateRequest, QueryChannelRequest,
-        QueryChannelsRequest, QueryClientConnectionsRequest, QueryClientStateRequest,
-        QueryClientStatesRequest, QueryConnectionChannelsRequest, QueryConnectionRequest,
-        QueryConnectionsRequest, QueryConsensusStateRequest, QueryConsensusStatesRequest,
-        QueryHostConsensusStateRequest, QueryNextSequenceReceiveRequest,
-        QueryPacketAcknowledgementRequest, QueryPacketAcknowledgementsRequest,
-        QueryPacketCommitmentRequest, QueryPacketCommitmentsRequest, QueryPacketReceiptRequest,
-        QueryTxRequest, QueryUnreceivedAcksRequest, QueryUnreceivedPacketsRequest,
-        QueryUpgradedClientStateRequest, QueryUpgradedConsensusStateRequest,
+        IncludeProof, QueryBlockRequest, QueryChannelClientStateRequest, QueryChannelOpenTxRequest,
+        QueryChannelRequest, QueryChannelsRequest, QueryClientConnectionsRequest,
+        QueryClientStateRequest, QueryClientStatesRequest, QueryConnectionChannelsRequest,
+        QueryConnectionRequest, QueryConnectionsRequest, QueryConsensusStateRequest,
+        QueryConsensusStatesRequest, QueryDenomTracesRequest, QueryHostConsensusStateRequest,
+        QueryIncentivizedPacketRequest, QueryNextSequenceReceiveRequest,
+        QueryNextSequenceSendRequest, QueryPacketAcknowledgementRequest,
+        QueryPacketAcknowledgementsRequest, QueryPacketCommitmentRequest,
+        QueryPacketCommitmentsRequest, QueryPacketReceiptRequest, QueryPacketRelayTxsRequest,
+        QueryPacketTxRequest, QuerySendPacketTxsRequest, QueryTxRequest,
+        QueryUnreceivedAcksRequest, QueryUnreceivedPacketsRequest, QueryUpgradedClientStateRequest,
+        QueryUpgradedConsensusStateRequest, QueryWrittenAcksRequest,
     },
     tracking::TrackedMsgs,
 };
@@ -61,6 +71,11 @@ mod base;
 mod cache;
 mod counting;
 
+#[cfg(any(test, feature = "mocks"))]
+pub mod mock;
+
+pub mod nonblocking;
+
 pub use base::BaseChainHandle;
 pub use counting::CountingChainHandle;
 
@@ -126,13 +141,22 @@ pub enum ChainRequest {
 
     SendMessagesAndWaitCheckTx {
         tracked_msgs: TrackedMsgs,
-        reply_to: ReplyTo<Vec<tendermint_rpc::endpoint::broadcast::tx_sync::Response>>,
+        reply_to: ReplyTo<Vec<crate::chain::cosmos::types::tx::TxSyncResponse>>,
+    },
+
+    EstimateMessagesGas {
+        tracked_msgs: TrackedMsgs,
+        reply_to: ReplyTo<u64>,
     },
 
     Config {
         reply_to: ReplyTo<ChainConfig>,
     },
 
+    MaxBlockTime {
+        reply_to: ReplyTo<MaxBlockTime>,
+    },
+
     Signer {
         reply_to: ReplyTo<Signer>,
     },
@@ -147,12 +171,18 @@ pub enum ChainRequest {
         reply_to: ReplyTo<()>,
     },
 
+    RotateKey {
+        key_name: String,
+        reply_to: ReplyTo<()>,
+    },
+
     IbcVersion {
         reply_to: ReplyTo<Option<semver::Version>>,
     },
 
     QueryBalance {
         key_name: Option<String>,
+        denom: Option<String>,
         reply_to: ReplyTo<Balance>,
     },
 
@@ -161,10 +191,70 @@ pub enum ChainRequest {
         reply_to: ReplyTo<DenomTrace>,
     },
 
+    QueryDenomTraces {
+        request: QueryDenomTracesRequest,
+        reply_to: ReplyTo<Vec<DenomTrace>>,
+    },
+
+    QueryDenomMetadata {
+        denom: String,
+        reply_to: ReplyTo<Option<DenomDisplay>>,
+    },
+
+    QueryIncentivizedPacket {
+        request: QueryIncentivizedPacketRequest,
+        reply_to: ReplyTo<Vec<PacketFee>>,
+    },
+
+    QueryChannelOpenTx {
+        request: QueryChannelOpenTxRequest,
+        reply_to: ReplyTo<Option<ChannelOpenTx>>,
+    },
+
+    QueryPacketRelayTxs {
+        request: QueryPacketRelayTxsRequest,
+        reply_to: ReplyTo<Vec<PacketRelayTx>>,
+    },
+
+    QuerySendPacketTxs {
+        request: QuerySendPacketTxsRequest,
+        reply_to: ReplyTo<Vec<SendPacketTx>>,
+    },
+
+    QueryPacketTx {
+        request: QueryPacketTxRequest,
+        reply_to: ReplyTo<Option<PacketTx>>,
+    },
+
+    QueryWrittenAcks {
+        request: QueryWrittenAcksRequest,
+        reply_to: ReplyTo<Vec<WrittenAck>>,
+    },
+
+    QueryServices {
+        reply_to: ReplyTo<Vec<String>>,
+    },
+
+    QueryAllowedClients {
+        reply_to: ReplyTo<Vec<String>>,
+    },
+
+    QueryNodeVersions {
+        reply_to: ReplyTo<NodeVersions>,
+    },
+
+    QueryUnbondingPeriod {
+        reply_to: ReplyTo<Duration>,
+    },
+
     QueryApplicationStatus {
         reply_to: ReplyTo<ChainStatus>,
     },
 
+    QuerySyncStatus {
+        reply_to: ReplyTo<SyncStatus>,
+    },
+
     QueryClients {
         request: QueryClientStatesRequest,
         reply_to: ReplyTo<Vec<IdentifiedAnyClientState>>,
@@ -282,6 +372,12 @@ pub enum ChainRequest {
         reply_to: ReplyTo<(Sequence, Option<MerkleProof>)>,
     },
 
+    QueryNextSequenceSend {
+        request: QueryNextSequenceSendRequest,
+        include_proof: IncludeProof,
+        reply_to: ReplyTo<(Sequence, Option<MerkleProof>)>,
+    },
+
     BuildChannelProofs {
         port_id: PortId,
         channel_id: ChannelId,
@@ -381,29 +477,115 @@ pub trait ChainHandle: Clone + Send + Sync + Serialize + Debug + 'static {
     fn send_messages_and_wait_check_tx(
         &self,
         tracked_msgs: TrackedMsgs,
-    ) -> Result<Vec<tendermint_rpc::endpoint::broadcast::tx_sync::Response>, Error>;
+    ) -> Result<Vec<crate::chain::cosmos::types::tx::TxSyncResponse>, Error>;
+
+    /// Estimate the gas needed to submit the given `msgs` to the chain, by simulating
+    /// the transactions they would be packaged in, without broadcasting anything.
+    fn estimate_messages_gas(&self, tracked_msgs: TrackedMsgs) -> Result<u64, Error>;
 
     fn get_signer(&self) -> Result<Signer, Error>;
 
     fn config(&self) -> Result<ChainConfig, Error>;
 
+    /// Return the maximum expected time per block of the chain, as resolved when
+    /// its runtime was spawned.
+    fn max_block_time(&self) -> Result<MaxBlockTime, Error>;
+
     fn get_key(&self) -> Result<KeyEntry, Error>;
 
     fn add_key(&self, key_name: String, key: KeyEntry) -> Result<(), Error>;
 
+    /// Sign the transactions submitted to the chain from now on with the given key,
+    /// which must be in the keyring and have a balance to pay the fees.
+    ///
+    /// The requests submitted to the runtime before this one complete with the previous key.
+    fn rotate_key(&self, key_name: String) -> Result<(), Error>;
+
     /// Return the version of the IBC protocol that this chain is running, if known.
     fn ibc_version(&self) -> Result<Option<semver::Version>, Error>;
 
-    /// Query the balance of the given account for the denom used to pay tx fees.
+    /// Query the balance of the given account for the given denom, or else for the denom
+    /// used to pay tx fees.
     /// If no account is given, behavior must be specified, e.g. retrieve it from configuration file.
-    fn query_balance(&self, key_name: Option<String>) -> Result<Balance, Error>;
+    fn query_balance(
+        &self,
+        key_name: Option<String>,
+        denom: Option<String>,
+    ) -> Result<Balance, Error>;
 
     /// Query the denomination trace given a trace hash.
     fn query_denom_trace(&self, hash: String) -> Result<DenomTrace, Error>;
 
+    /// Query the denomination traces of the IBC vouchers known to the chain.
+    fn query_denom_traces(
+        &self,
+        request: QueryDenomTracesRequest,
+    ) -> Result<Vec<DenomTrace>, Error>;
+
+    /// Query the trace of the given denomination if it is the denomination of an
+    /// IBC voucher, ie. `ibc/{hash}`, returning `None` for any other denomination.
+    fn query_ibc_denom_trace(&self, denom: &str) -> Result<Option<DenomTrace>, Error> {
+        match ibc_denom_hash(denom) {
+            Some(hash) => self.query_denom_trace(hash.to_string()).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Query the display unit of a denomination in its bank metadata,
+    /// returning `None` if the denomination has no metadata.
+    fn query_denom_metadata(&self, denom: String) -> Result<Option<DenomDisplay>, Error>;
+
+    /// Query the ICS-29 fees escrowed for a packet sent on the chain.
+    fn query_incentivized_packet(
+        &self,
+        request: QueryIncentivizedPacketRequest,
+    ) -> Result<Vec<PacketFee>, Error>;
+
+    /// Query the transaction of the opening handshake which brought a channel end to a state.
+    fn query_channel_open_tx(
+        &self,
+        request: QueryChannelOpenTxRequest,
+    ) -> Result<Option<ChannelOpenTx>, Error>;
+
+    /// Query the transactions which relayed packets or acknowledgements to a channel end.
+    fn query_packet_relay_txs(
+        &self,
+        request: QueryPacketRelayTxsRequest,
+    ) -> Result<Vec<PacketRelayTx>, Error>;
+
+    /// Query the transactions which sent the given packets from a channel end.
+    fn query_send_packet_txs(
+        &self,
+        request: QuerySendPacketTxsRequest,
+    ) -> Result<Vec<SendPacketTx>, Error>;
+
+    /// Query the transaction which emitted the given event of the lifecycle of a packet.
+    fn query_packet_tx(&self, request: QueryPacketTxRequest) -> Result<Option<PacketTx>, Error>;
+
+    /// Query the acknowledgements written for the given packets received on a channel end.
+    fn query_written_acks(
+        &self,
+        request: QueryWrittenAcksRequest,
+    ) -> Result<Vec<WrittenAck>, Error>;
+
+    /// Query the fully-qualified names of the gRPC query services registered on the chain.
+    fn query_services(&self) -> Result<Vec<String>, Error>;
+
+    /// Query the types of the light clients which may be created on the chain.
+    fn query_allowed_clients(&self) -> Result<Vec<String>, Error>;
+
+    /// Query the versions of the software run by the node of the chain.
+    fn query_node_versions(&self) -> Result<NodeVersions, Error>;
+
+    /// Query the current unbonding period of the chain.
+    fn query_unbonding_period(&self) -> Result<Duration, Error>;
+
     /// Query the latest height and timestamp the application is at
     fn query_application_status(&self) -> Result<ChainStatus, Error>;
 
+    /// Query the latest block of the node and whether it is still catching up
+    fn query_sync_status(&self) -> Result<SyncStatus, Error>;
+
     fn query_latest_height(&self) -> Result<Height, Error> {
         Ok(self.query_application_status()?.height)
     }
@@ -487,6 +669,15 @@ pub trait ChainHandle: Clone + Send + Sync + Serialize + Debug + 'static {
         include_proof: IncludeProof,
     ) -> Result<(Sequence, Option<MerkleProof>), Error>;
 
+    /// Performs a query to retrieve `nextSequenceSend` stored at path
+    /// `path::SeqSendsPath` as defined in ICS-4. A proof can optionally be
+    /// returned along with the result.
+    fn query_next_sequence_send(
+        &self,
+        request: QueryNextSequenceSendRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(Sequence, Option<MerkleProof>), Error>;
+
     /// Performs a query to retrieve all the channels of a chain.
     fn query_channels(
         &self,
@@ -1,4 +1,5 @@
 use core::fmt::Debug;
+use core::time::Duration;
 
 use crossbeam_channel as channel;
 use serde::{Serialize, Serializer};
@@ -28,25 +29,35 @@ use ibc::{
 use crate::{
     account::Balance,
     chain::{
+        block_time::MaxBlockTime,
         client::ClientSettings,
-        endpoint::ChainStatus,
+        cosmos::version::NodeVersions,
+        endpoint::{
+            ChainStatus, ChannelOpenTx, PacketRelayTx, PacketTx, SendPacketTx, SyncStatus,
+            WrittenAck,
+        },
         requests::{
-            IncludeProof, QueryBlockRequest, QueryChannelClientStateRequest, QueryChannelRequest,
-            QueryChannelsRequest, QueryClientConnectionsRequest, QueryClientStateRequest,
-            QueryClientStatesRequest, QueryConnectionChannelsRequest, QueryConnectionRequest,
-            QueryConnectionsRequest, QueryConsensusStateRequest, QueryConsensusStatesRequest,
-            QueryHostConsensusStateRequest, QueryNextSequenceReceiveRequest,
+            IncludeProof, QueryBlockRequest, QueryChannelClientStateRequest,
+            QueryChannelOpenTxRequest, QueryChannelRequest, QueryChannelsRequest,
+            QueryClientConnectionsRequest, QueryClientStateRequest, QueryClientStatesRequest,
+            QueryConnectionChannelsRequest, QueryConnectionRequest, QueryConnectionsRequest,
+            QueryConsensusStateRequest, QueryConsensusStatesRequest, QueryDenomTracesRequest,
+            QueryHostConsensusStateRequest, QueryIncentivizedPacketRequest,
+            QueryNextSequenceReceiveRequest, QueryNextSequenceSendRequest,
             QueryPacketAcknowledgementRequest, QueryPacketAcknowledgementsRequest,
             QueryPacketCommitmentRequest, QueryPacketCommitmentsRequest, QueryPacketReceiptRequest,
+            QueryPacketRelayTxsRequest, QueryPacketTxRequest, QuerySendPacketTxsRequest,
             QueryTxRequest, QueryUnreceivedAcksRequest, QueryUnreceivedPacketsRequest,
             QueryUpgradedClientStateRequest, QueryUpgradedConsensusStateRequest,
+            QueryWrittenAcksRequest,
         },
         tracking::TrackedMsgs,
     },
     config::ChainConfig,
     connection::ConnectionMsgType,
-    denom::DenomTrace,
+    denom::{DenomDisplay, DenomTrace},
     error::Error,
+    fee::PacketFee,
     keyring::KeyEntry,
 };
 
@@ -119,13 +130,20 @@ impl ChainHandle for BaseChainHandle {
     fn send_messages_and_wait_check_tx(
         &self,
         tracked_msgs: TrackedMsgs,
-    ) -> Result<Vec<tendermint_rpc::endpoint::broadcast::tx_sync::Response>, Error> {
+    ) -> Result<Vec<crate::chain::cosmos::types::tx::TxSyncResponse>, Error> {
         self.send(|reply_to| ChainRequest::SendMessagesAndWaitCheckTx {
             tracked_msgs,
             reply_to,
         })
     }
 
+    fn estimate_messages_gas(&self, tracked_msgs: TrackedMsgs) -> Result<u64, Error> {
+        self.send(|reply_to| ChainRequest::EstimateMessagesGas {
+            tracked_msgs,
+            reply_to,
+        })
+    }
+
     fn get_signer(&self) -> Result<Signer, Error> {
         self.send(|reply_to| ChainRequest::Signer { reply_to })
     }
@@ -134,6 +152,10 @@ impl ChainHandle for BaseChainHandle {
         self.send(|reply_to| ChainRequest::Config { reply_to })
     }
 
+    fn max_block_time(&self) -> Result<MaxBlockTime, Error> {
+        self.send(|reply_to| ChainRequest::MaxBlockTime { reply_to })
+    }
+
     fn get_key(&self) -> Result<KeyEntry, Error> {
         self.send(|reply_to| ChainRequest::GetKey { reply_to })
     }
@@ -146,22 +168,104 @@ impl ChainHandle for BaseChainHandle {
         })
     }
 
+    fn rotate_key(&self, key_name: String) -> Result<(), Error> {
+        self.send(|reply_to| ChainRequest::RotateKey { key_name, reply_to })
+    }
+
     fn ibc_version(&self) -> Result<Option<semver::Version>, Error> {
         self.send(|reply_to| ChainRequest::IbcVersion { reply_to })
     }
 
-    fn query_balance(&self, key_name: Option<String>) -> Result<Balance, Error> {
-        self.send(|reply_to| ChainRequest::QueryBalance { key_name, reply_to })
+    fn query_balance(
+        &self,
+        key_name: Option<String>,
+        denom: Option<String>,
+    ) -> Result<Balance, Error> {
+        self.send(|reply_to| ChainRequest::QueryBalance {
+            key_name,
+            denom,
+            reply_to,
+        })
     }
 
     fn query_denom_trace(&self, hash: String) -> Result<DenomTrace, Error> {
         self.send(|reply_to| ChainRequest::QueryDenomTrace { hash, reply_to })
     }
 
+    fn query_denom_traces(
+        &self,
+        request: QueryDenomTracesRequest,
+    ) -> Result<Vec<DenomTrace>, Error> {
+        self.send(|reply_to| ChainRequest::QueryDenomTraces { request, reply_to })
+    }
+
+    fn query_denom_metadata(&self, denom: String) -> Result<Option<DenomDisplay>, Error> {
+        self.send(|reply_to| ChainRequest::QueryDenomMetadata { denom, reply_to })
+    }
+
+    fn query_incentivized_packet(
+        &self,
+        request: QueryIncentivizedPacketRequest,
+    ) -> Result<Vec<PacketFee>, Error> {
+        self.send(|reply_to| ChainRequest::QueryIncentivizedPacket { request, reply_to })
+    }
+
+    fn query_channel_open_tx(
+        &self,
+        request: QueryChannelOpenTxRequest,
+    ) -> Result<Option<ChannelOpenTx>, Error> {
+        self.send(|reply_to| ChainRequest::QueryChannelOpenTx { request, reply_to })
+    }
+
+    fn query_packet_relay_txs(
+        &self,
+        request: QueryPacketRelayTxsRequest,
+    ) -> Result<Vec<PacketRelayTx>, Error> {
+        self.send(|reply_to| ChainRequest::QueryPacketRelayTxs { request, reply_to })
+    }
+
+    fn query_send_packet_txs(
+        &self,
+        request: QuerySendPacketTxsRequest,
+    ) -> Result<Vec<SendPacketTx>, Error> {
+        self.send(|reply_to| ChainRequest::QuerySendPacketTxs { request, reply_to })
+    }
+
+    fn query_packet_tx(&self, request: QueryPacketTxRequest) -> Result<Option<PacketTx>, Error> {
+        self.send(|reply_to| ChainRequest::QueryPacketTx { request, reply_to })
+    }
+
+    fn query_written_acks(
+        &self,
+        request: QueryWrittenAcksRequest,
+    ) -> Result<Vec<WrittenAck>, Error> {
+        self.send(|reply_to| ChainRequest::QueryWrittenAcks { request, reply_to })
+    }
+
+    fn query_services(&self) -> Result<Vec<String>, Error> {
+        self.send(|reply_to| ChainRequest::QueryServices { reply_to })
+    }
+
+    fn query_allowed_clients(&self) -> Result<Vec<String>, Error> {
+        self.send(|reply_to| ChainRequest::QueryAllowedClients { reply_to })
+    }
+
+    fn query_node_versions(&self) -> Result<NodeVersions, Error> {
+        self.send(|reply_to| ChainRequest::QueryNodeVersions { reply_to })
+    }
+
+    fn query_unbonding_period(&self) -> Result<Duration, Error> {
+        self.send(|reply_to| ChainRequest::QueryUnbondingPeriod { reply_to })
+    }
+
     fn query_application_status(&self) -> Result<ChainStatus, Error> {
         self.send(|reply_to| ChainRequest::QueryApplicationStatus { reply_to })
     }
 
+    fn query_sync_status(&self) -> Result<SyncStatus, Error> {
+        self.send(|reply_to| ChainRequest::QuerySyncStatus { reply_to })
+    }
+
     fn query_clients(
         &self,
         request: QueryClientStatesRequest,
@@ -267,6 +371,18 @@ impl ChainHandle for BaseChainHandle {
         })
     }
 
+    fn query_next_sequence_send(
+        &self,
+        request: QueryNextSequenceSendRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(Sequence, Option<MerkleProof>), Error> {
+        self.send(|reply_to| ChainRequest::QueryNextSequenceSend {
+            request,
+            include_proof,
+            reply_to,
+        })
+    }
+
     fn query_channels(
         &self,
         request: QueryChannelsRequest,
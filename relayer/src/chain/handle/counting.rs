@@ -1,3 +1,5 @@
+use core::time::Duration;
+
 use crossbeam_channel as channel;
 use ibc::core::ics02_client::client_consensus::{AnyConsensusState, AnyConsensusStateWithHeight};
 use ibc::core::ics02_client::client_state::{AnyClientState, IdentifiedAnyClientState};
@@ -25,24 +27,32 @@ use std::sync::{Arc, RwLock, RwLockReadGuard};
 use tracing::debug;
 
 use crate::account::Balance;
+use crate::chain::block_time::MaxBlockTime;
 use crate::chain::client::ClientSettings;
-use crate::chain::endpoint::{ChainStatus, HealthCheck};
+use crate::chain::cosmos::version::NodeVersions;
+use crate::chain::endpoint::{
+    ChainStatus, ChannelOpenTx, HealthCheck, PacketRelayTx, PacketTx, SendPacketTx, SyncStatus,
+    WrittenAck,
+};
 use crate::chain::handle::{ChainHandle, ChainRequest, Subscription};
 use crate::chain::requests::{
-    IncludeProof, QueryBlockRequest, QueryChannelClientStateRequest, QueryChannelRequest,
-    QueryChannelsRequest, QueryClientConnectionsRequest, QueryClientStateRequest,
-    QueryClientStatesRequest, QueryConnectionChannelsRequest, QueryConnectionRequest,
-    QueryConnectionsRequest, QueryConsensusStateRequest, QueryConsensusStatesRequest,
-    QueryHostConsensusStateRequest, QueryNextSequenceReceiveRequest,
+    IncludeProof, QueryBlockRequest, QueryChannelClientStateRequest, QueryChannelOpenTxRequest,
+    QueryChannelRequest, QueryChannelsRequest, QueryClientConnectionsRequest,
+    QueryClientStateRequest, QueryClientStatesRequest, QueryConnectionChannelsRequest,
+    QueryConnectionRequest, QueryConnectionsRequest, QueryConsensusStateRequest,
+    QueryConsensusStatesRequest, QueryDenomTracesRequest, QueryHostConsensusStateRequest,
+    QueryIncentivizedPacketRequest, QueryNextSequenceReceiveRequest, QueryNextSequenceSendRequest,
     QueryPacketAcknowledgementRequest, QueryPacketAcknowledgementsRequest,
     QueryPacketCommitmentRequest, QueryPacketCommitmentsRequest, QueryPacketReceiptRequest,
-    QueryTxRequest, QueryUnreceivedAcksRequest, QueryUnreceivedPacketsRequest,
-    QueryUpgradedClientStateRequest, QueryUpgradedConsensusStateRequest,
+    QueryPacketRelayTxsRequest, QueryPacketTxRequest, QuerySendPacketTxsRequest, QueryTxRequest,
+    QueryUnreceivedAcksRequest, QueryUnreceivedPacketsRequest, QueryUpgradedClientStateRequest,
+    QueryUpgradedConsensusStateRequest, QueryWrittenAcksRequest,
 };
 use crate::chain::tracking::TrackedMsgs;
 use crate::config::ChainConfig;
-use crate::denom::DenomTrace;
+use crate::denom::{DenomDisplay, DenomTrace};
 use crate::error::Error;
+use crate::fee::PacketFee;
 use crate::util::lock::LockExt;
 use crate::{connection::ConnectionMsgType, keyring::KeyEntry};
 
@@ -127,11 +137,16 @@ impl<Handle: ChainHandle> ChainHandle for CountingChainHandle<Handle> {
     fn send_messages_and_wait_check_tx(
         &self,
         tracked_msgs: TrackedMsgs,
-    ) -> Result<Vec<tendermint_rpc::endpoint::broadcast::tx_sync::Response>, Error> {
+    ) -> Result<Vec<crate::chain::cosmos::types::tx::TxSyncResponse>, Error> {
         self.inc_metric("send_messages_and_wait_check_tx");
         self.inner().send_messages_and_wait_check_tx(tracked_msgs)
     }
 
+    fn estimate_messages_gas(&self, tracked_msgs: TrackedMsgs) -> Result<u64, Error> {
+        self.inc_metric("estimate_messages_gas");
+        self.inner().estimate_messages_gas(tracked_msgs)
+    }
+
     fn get_signer(&self) -> Result<Signer, Error> {
         self.inc_metric("get_signer");
         self.inner().get_signer()
@@ -142,6 +157,11 @@ impl<Handle: ChainHandle> ChainHandle for CountingChainHandle<Handle> {
         self.inner().config()
     }
 
+    fn max_block_time(&self) -> Result<MaxBlockTime, Error> {
+        self.inc_metric("max_block_time");
+        self.inner().max_block_time()
+    }
+
     fn get_key(&self) -> Result<KeyEntry, Error> {
         self.inc_metric("get_key");
         self.inner().get_key()
@@ -152,14 +172,23 @@ impl<Handle: ChainHandle> ChainHandle for CountingChainHandle<Handle> {
         self.inner().add_key(key_name, key)
     }
 
+    fn rotate_key(&self, key_name: String) -> Result<(), Error> {
+        self.inc_metric("rotate_key");
+        self.inner().rotate_key(key_name)
+    }
+
     fn ibc_version(&self) -> Result<Option<semver::Version>, Error> {
         self.inc_metric("ibc_version");
         self.inner().ibc_version()
     }
 
-    fn query_balance(&self, key_name: Option<String>) -> Result<Balance, Error> {
+    fn query_balance(
+        &self,
+        key_name: Option<String>,
+        denom: Option<String>,
+    ) -> Result<Balance, Error> {
         self.inc_metric("query_balance");
-        self.inner().query_balance(key_name)
+        self.inner().query_balance(key_name, denom)
     }
 
     fn query_denom_trace(&self, hash: String) -> Result<DenomTrace, Error> {
@@ -167,11 +196,94 @@ impl<Handle: ChainHandle> ChainHandle for CountingChainHandle<Handle> {
         self.inner().query_denom_trace(hash)
     }
 
+    fn query_denom_traces(
+        &self,
+        request: QueryDenomTracesRequest,
+    ) -> Result<Vec<DenomTrace>, Error> {
+        self.inc_metric("query_denom_traces");
+        self.inner().query_denom_traces(request)
+    }
+
+    fn query_denom_metadata(&self, denom: String) -> Result<Option<DenomDisplay>, Error> {
+        self.inc_metric("query_denom_metadata");
+        self.inner().query_denom_metadata(denom)
+    }
+
+    fn query_incentivized_packet(
+        &self,
+        request: QueryIncentivizedPacketRequest,
+    ) -> Result<Vec<PacketFee>, Error> {
+        self.inc_metric("query_incentivized_packet");
+        self.inner().query_incentivized_packet(request)
+    }
+
+    fn query_channel_open_tx(
+        &self,
+        request: QueryChannelOpenTxRequest,
+    ) -> Result<Option<ChannelOpenTx>, Error> {
+        self.inc_metric("query_channel_open_tx");
+        self.inner().query_channel_open_tx(request)
+    }
+
+    fn query_packet_relay_txs(
+        &self,
+        request: QueryPacketRelayTxsRequest,
+    ) -> Result<Vec<PacketRelayTx>, Error> {
+        self.inc_metric("query_packet_relay_txs");
+        self.inner().query_packet_relay_txs(request)
+    }
+
+    fn query_send_packet_txs(
+        &self,
+        request: QuerySendPacketTxsRequest,
+    ) -> Result<Vec<SendPacketTx>, Error> {
+        self.inc_metric("query_send_packet_txs");
+        self.inner().query_send_packet_txs(request)
+    }
+
+    fn query_packet_tx(&self, request: QueryPacketTxRequest) -> Result<Option<PacketTx>, Error> {
+        self.inc_metric("query_packet_tx");
+        self.inner().query_packet_tx(request)
+    }
+
+    fn query_written_acks(
+        &self,
+        request: QueryWrittenAcksRequest,
+    ) -> Result<Vec<WrittenAck>, Error> {
+        self.inc_metric("query_written_acks");
+        self.inner().query_written_acks(request)
+    }
+
+    fn query_services(&self) -> Result<Vec<String>, Error> {
+        self.inc_metric("query_services");
+        self.inner().query_services()
+    }
+
+    fn query_allowed_clients(&self) -> Result<Vec<String>, Error> {
+        self.inc_metric("query_allowed_clients");
+        self.inner().query_allowed_clients()
+    }
+
+    fn query_node_versions(&self) -> Result<NodeVersions, Error> {
+        self.inc_metric("query_node_versions");
+        self.inner().query_node_versions()
+    }
+
+    fn query_unbonding_period(&self) -> Result<Duration, Error> {
+        self.inc_metric("query_unbonding_period");
+        self.inner().query_unbonding_period()
+    }
+
     fn query_application_status(&self) -> Result<ChainStatus, Error> {
         self.inc_metric("query_application_status");
         self.inner().query_application_status()
     }
 
+    fn query_sync_status(&self) -> Result<SyncStatus, Error> {
+        self.inc_metric("query_sync_status");
+        self.inner().query_sync_status()
+    }
+
     fn query_latest_height(&self) -> Result<Height, Error> {
         self.inc_metric("query_latest_height");
         self.inner().query_latest_height()
@@ -283,6 +395,16 @@ impl<Handle: ChainHandle> ChainHandle for CountingChainHandle<Handle> {
             .query_next_sequence_receive(request, include_proof)
     }
 
+    fn query_next_sequence_send(
+        &self,
+        request: QueryNextSequenceSendRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(Sequence, Option<MerkleProof>), Error> {
+        self.inc_metric("query_next_sequence_send");
+        self.inner()
+            .query_next_sequence_send(request, include_proof)
+    }
+
     fn query_channels(
         &self,
         request: QueryChannelsRequest,
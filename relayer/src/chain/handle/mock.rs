@@ -0,0 +1,1241 @@
+//! An in-memory [`ChainHandle`], whose state and behavior are programmed by
+//! the test using it, to test the code interacting with chains without
+//! running any chain.
+//!
+//! A [`MockChainHandle`] answers the queries from the clients, connections,
+//! channels and packets it was loaded with, records every message sent to
+//! the chain instead of broadcasting it, and fails the operations for which
+//! a failure was injected:
+//!
+//! ```rust
+//! use ibc::core::ics04_channel::channel::ChannelEnd;
+//! use ibc::core::ics04_channel::packet::Sequence;
+//! use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+//! use ibc_relayer::chain::handle::mock::MockChainHandle;
+//! use ibc_relayer::chain::handle::ChainHandle;
+//! use ibc_relayer::chain::requests::QueryUnreceivedPacketsRequest;
+//! use ibc_relayer::error::Error;
+//!
+//! let chain = MockChainHandle::new(ChainId::new("mock".to_string(), 0));
+//!
+//! chain.add_channel(PortId::transfer(), ChannelId::new(0), ChannelEnd::default());
+//! chain.receive_packet(PortId::transfer(), ChannelId::new(0), Sequence::from(1));
+//!
+//! let request = QueryUnreceivedPacketsRequest {
+//!     port_id: PortId::transfer(),
+//!     channel_id: ChannelId::new(0),
+//!     packet_commitment_sequences: vec![Sequence::from(1), Sequence::from(2)],
+//! };
+//!
+//! let unreceived = chain.query_unreceived_packets(request.clone()).unwrap();
+//! assert_eq!(unreceived, vec![Sequence::from(2)]);
+//!
+//! chain.inject_failure("query_unreceived_packets", Error::channel_send);
+//! assert!(chain.query_unreceived_packets(request).is_err());
+//! ```
+//!
+//! The queries ignore the height they are made at, and answer from the latest
+//! state of the chain. Proofs cannot be built from the state of a mock chain,
+//! so the queries and operations requiring a proof fail, except for building
+//! the proofs of a channel end, which are mock proofs that the other mock
+//! chains do not check.
+//!
+//! This module is available with the `mocks` feature.
+
+use alloc::collections::{BTreeMap, BTreeSet, VecDeque};
+use alloc::sync::Arc;
+use core::time::Duration;
+use std::sync::{RwLockReadGuard, RwLockWriteGuard};
+use std::thread;
+
+use crossbeam_channel as channel;
+use ibc_proto::google::protobuf::Any;
+use serde::{Serialize, Serializer};
+
+use ibc::{
+    core::{
+        ics02_client::client_consensus::{AnyConsensusState, AnyConsensusStateWithHeight},
+        ics02_client::client_state::{AnyClientState, IdentifiedAnyClientState},
+        ics02_client::client_type::ClientType,
+        ics02_client::events::UpdateClient,
+        ics02_client::header::AnyHeader,
+        ics02_client::misbehaviour::MisbehaviourEvidence,
+        ics03_connection::connection::{ConnectionEnd, IdentifiedConnectionEnd},
+        ics03_connection::version::{get_compatible_versions, Version},
+        ics04_channel::channel::{ChannelEnd, IdentifiedChannelEnd},
+        ics04_channel::packet::{PacketMsgType, Sequence},
+        ics23_commitment::{
+            commitment::{CommitmentPrefix, CommitmentProofBytes},
+            merkle::MerkleProof,
+        },
+        ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId},
+    },
+    events::IbcEvent,
+    mock::{client_state::MockConsensusState, header::MockHeader},
+    proofs::Proofs,
+    signer::Signer,
+    timestamp::Timestamp,
+    Height,
+};
+
+use crate::{
+    account::Balance,
+    chain::{
+        block_time::MaxBlockTime,
+        client::ClientSettings,
+        cosmos::{types::tx::TxSyncResponse, version::NodeVersions},
+        endpoint::{
+            ChainStatus, ChannelOpenTx, PacketRelayTx, PacketTx, SendPacketTx, SyncStatus,
+            WrittenAck,
+        },
+        requests::{
+            IncludeProof, QueryBlockRequest, QueryChannelClientStateRequest,
+            QueryChannelOpenTxRequest, QueryChannelRequest, QueryChannelsRequest,
+            QueryClientConnectionsRequest, QueryClientStateRequest, QueryClientStatesRequest,
+            QueryConnectionChannelsRequest, QueryConnectionRequest, QueryConnectionsRequest,
+            QueryConsensusStateRequest, QueryConsensusStatesRequest, QueryDenomTracesRequest,
+            QueryHeight, QueryHostConsensusStateRequest, QueryIncentivizedPacketRequest,
+            QueryNextSequenceReceiveRequest, QueryNextSequenceSendRequest,
+            QueryPacketAcknowledgementRequest, QueryPacketAcknowledgementsRequest,
+            QueryPacketCommitmentRequest, QueryPacketCommitmentsRequest, QueryPacketReceiptRequest,
+            QueryPacketRelayTxsRequest, QueryPacketTxRequest, QuerySendPacketTxsRequest,
+            QueryTxRequest, QueryUnreceivedAcksRequest, QueryUnreceivedPacketsRequest,
+            QueryUpgradedClientStateRequest, QueryUpgradedConsensusStateRequest,
+            QueryWrittenAcksRequest,
+        },
+        tracking::{CorrelationId, TrackedMsgs, TrackingId},
+    },
+    config::ChainConfig,
+    connection::ConnectionMsgType,
+    denom::{DenomDisplay, DenomTrace},
+    error::Error,
+    event::monitor::{EventBatch, Result as MonitorResult},
+    fee::PacketFee,
+    keyring::KeyEntry,
+    util::lock::{LockExt, RwArc},
+};
+
+use super::{ChainHandle, ChainRequest, HealthCheck, Subscription};
+
+/// Builds the error returned by an operation of a [`MockChainHandle`]
+/// for which a failure was injected.
+pub type MockFailure = fn() -> Error;
+
+type ChannelKey = (PortId, ChannelId);
+
+/// The state of a mock chain, shared by the clones of its handle.
+#[derive(Debug)]
+struct MockChainState {
+    height: Height,
+    timestamp: Timestamp,
+    config: Option<ChainConfig>,
+    signer: Option<Signer>,
+    /// The unbonding period returned by `query_unbonding_period`.
+    unbonding_period: Option<Duration>,
+
+    clients: BTreeMap<ClientId, AnyClientState>,
+    consensus_states: BTreeMap<ClientId, BTreeMap<Height, AnyConsensusState>>,
+    connections: BTreeMap<ConnectionId, ConnectionEnd>,
+    channels: BTreeMap<ChannelKey, ChannelEnd>,
+
+    commitments: BTreeMap<ChannelKey, BTreeMap<Sequence, Vec<u8>>>,
+    receipts: BTreeMap<ChannelKey, BTreeSet<Sequence>>,
+    acks: BTreeMap<ChannelKey, BTreeMap<Sequence, Vec<u8>>>,
+    next_sequence_send: BTreeMap<ChannelKey, Sequence>,
+    next_sequence_recv: BTreeMap<ChannelKey, Sequence>,
+
+    /// The query services returned by `query_services`.
+    services: Vec<String>,
+    /// The client types returned by `query_allowed_clients`.
+    allowed_clients: Vec<String>,
+    /// The events returned by the next transactions, in order.
+    tx_events: VecDeque<Vec<IbcEvent>>,
+    /// The `check_tx` responses returned by the next asynchronous transactions, in order.
+    tx_responses: VecDeque<Vec<TxSyncResponse>>,
+    /// The events returned by `query_txs`.
+    queried_tx_events: Vec<IbcEvent>,
+    /// Every message sent to the chain, in order.
+    sent_msgs: Vec<Any>,
+    /// The correlation id of every batch of messages sent to the chain, in order.
+    sent_correlation_ids: Vec<CorrelationId>,
+    /// The gas returned by `estimate_messages_gas`.
+    estimated_gas: u64,
+    /// How long sending a transaction takes.
+    tx_latency: Duration,
+    /// The display units returned by `query_denom_metadata`, by denomination.
+    denom_displays: BTreeMap<String, DenomDisplay>,
+    /// The traces returned by `query_denom_trace`, by hash.
+    denom_traces: BTreeMap<String, DenomTrace>,
+
+    subscribers: Vec<channel::Sender<Arc<MonitorResult<EventBatch>>>>,
+    failures: BTreeMap<&'static str, MockFailure>,
+}
+
+/// An in-memory chain handle, see the [module documentation](self).
+///
+/// All the clones of a handle share the state of the same mock chain.
+#[derive(Clone, Debug)]
+pub struct MockChainHandle {
+    chain_id: ChainId,
+    state: RwArc<MockChainState>,
+}
+
+impl MockChainHandle {
+    /// Creates the handle of an empty mock chain, at height 1 of its revision.
+    pub fn new(chain_id: ChainId) -> Self {
+        let height = Height::new(chain_id.version(), 1).expect("height 1 is valid");
+
+        let state = MockChainState {
+            height,
+            timestamp: Timestamp::now(),
+            config: None,
+            signer: None,
+            unbonding_period: None,
+            clients: BTreeMap::new(),
+            consensus_states: BTreeMap::new(),
+            connections: BTreeMap::new(),
+            channels: BTreeMap::new(),
+            commitments: BTreeMap::new(),
+            receipts: BTreeMap::new(),
+            acks: BTreeMap::new(),
+            next_sequence_send: BTreeMap::new(),
+            next_sequence_recv: BTreeMap::new(),
+            services: Vec::new(),
+            allowed_clients: vec![ClientType::Tendermint.as_str().to_string()],
+            tx_events: VecDeque::new(),
+            tx_responses: VecDeque::new(),
+            queried_tx_events: Vec::new(),
+            sent_msgs: Vec::new(),
+            sent_correlation_ids: Vec::new(),
+            estimated_gas: 0,
+            tx_latency: Duration::ZERO,
+            denom_displays: BTreeMap::new(),
+            denom_traces: BTreeMap::new(),
+            subscribers: Vec::new(),
+            failures: BTreeMap::new(),
+        };
+
+        Self {
+            chain_id,
+            state: <RwArc<_>>::new_lock(state),
+        }
+    }
+
+    /// Sets the configuration returned by [`ChainHandle::config`].
+    pub fn set_config(&self, config: ChainConfig) {
+        self.state.acquire_write().config = Some(config);
+    }
+
+    /// Sets the signer returned by [`ChainHandle::get_signer`].
+    pub fn set_signer(&self, signer: Signer) {
+        self.state.acquire_write().signer = Some(signer);
+    }
+
+    /// Sets the unbonding period returned by [`ChainHandle::query_unbonding_period`].
+    pub fn set_unbonding_period(&self, unbonding_period: Duration) {
+        self.state.acquire_write().unbonding_period = Some(unbonding_period);
+    }
+
+    /// Sets the gas returned by [`ChainHandle::estimate_messages_gas`].
+    pub fn set_estimated_gas(&self, estimated_gas: u64) {
+        self.state.acquire_write().estimated_gas = estimated_gas;
+    }
+
+    /// Sets how long sending a transaction takes, e.g. to wait for it to be committed.
+    /// The other operations on the chain are not delayed meanwhile.
+    pub fn set_tx_latency(&self, tx_latency: Duration) {
+        self.state.acquire_write().tx_latency = tx_latency;
+    }
+
+    /// Sets the latest height and timestamp of the chain.
+    pub fn set_latest(&self, height: Height, timestamp: Timestamp) {
+        let mut state = self.state.acquire_write();
+        state.height = height;
+        state.timestamp = timestamp;
+    }
+
+    /// Adds a client, or replaces its state.
+    pub fn add_client(&self, client_id: ClientId, client_state: AnyClientState) {
+        self.state
+            .acquire_write()
+            .clients
+            .insert(client_id, client_state);
+    }
+
+    /// Adds a consensus state of a client at the given height.
+    pub fn add_consensus_state(
+        &self,
+        client_id: ClientId,
+        height: Height,
+        consensus_state: AnyConsensusState,
+    ) {
+        self.state
+            .acquire_write()
+            .consensus_states
+            .entry(client_id)
+            .or_default()
+            .insert(height, consensus_state);
+    }
+
+    /// Adds a connection, or replaces its end.
+    pub fn add_connection(&self, connection_id: ConnectionId, connection_end: ConnectionEnd) {
+        self.state
+            .acquire_write()
+            .connections
+            .insert(connection_id, connection_end);
+    }
+
+    /// Adds a channel, or replaces its end.
+    pub fn add_channel(&self, port_id: PortId, channel_id: ChannelId, channel_end: ChannelEnd) {
+        self.state
+            .acquire_write()
+            .channels
+            .insert((port_id, channel_id), channel_end);
+    }
+
+    /// Stores the commitment of a packet sent on the channel, and advances
+    /// the next sequence to send past it.
+    pub fn commit_packet(
+        &self,
+        port_id: PortId,
+        channel_id: ChannelId,
+        sequence: Sequence,
+        commitment: Vec<u8>,
+    ) {
+        let mut state = self.state.acquire_write();
+        let key = (port_id, channel_id);
+
+        advance_sequence(&mut state.next_sequence_send, key.clone(), sequence);
+
+        state
+            .commitments
+            .entry(key)
+            .or_default()
+            .insert(sequence, commitment);
+    }
+
+    /// Deletes the commitment of a packet sent on the channel,
+    /// as when its acknowledgement or timeout is received.
+    pub fn delete_packet_commitment(
+        &self,
+        port_id: PortId,
+        channel_id: ChannelId,
+        sequence: Sequence,
+    ) {
+        if let Some(commitments) = self
+            .state
+            .acquire_write()
+            .commitments
+            .get_mut(&(port_id, channel_id))
+        {
+            commitments.remove(&sequence);
+        }
+    }
+
+    /// Stores the receipt of a packet received on the channel, and advances
+    /// the next sequence to receive past it.
+    pub fn receive_packet(&self, port_id: PortId, channel_id: ChannelId, sequence: Sequence) {
+        let mut state = self.state.acquire_write();
+        let key = (port_id, channel_id);
+
+        advance_sequence(&mut state.next_sequence_recv, key.clone(), sequence);
+
+        state.receipts.entry(key).or_default().insert(sequence);
+    }
+
+    /// Stores the acknowledgement written for a packet received on the channel.
+    pub fn write_acknowledgement(
+        &self,
+        port_id: PortId,
+        channel_id: ChannelId,
+        sequence: Sequence,
+        ack: Vec<u8>,
+    ) {
+        self.state
+            .acquire_write()
+            .acks
+            .entry((port_id, channel_id))
+            .or_default()
+            .insert(sequence, ack);
+    }
+
+    /// Sets the display unit of a denomination returned by [`ChainHandle::query_denom_metadata`].
+    pub fn set_denom_display(&self, denom: &str, display: DenomDisplay) {
+        self.state
+            .acquire_write()
+            .denom_displays
+            .insert(denom.to_string(), display);
+    }
+
+    /// Adds a denomination trace, returned by [`ChainHandle::query_denom_trace`] for its hash.
+    pub fn add_denom_trace(&self, trace: DenomTrace) {
+        self.state
+            .acquire_write()
+            .denom_traces
+            .insert(trace.hash(), trace);
+    }
+
+    /// Sets the query services returned by [`ChainHandle::query_services`].
+    pub fn set_services(&self, services: Vec<String>) {
+        self.state.acquire_write().services = services;
+    }
+
+    /// Sets the client types returned by [`ChainHandle::query_allowed_clients`],
+    /// which are only `07-tendermint` by default.
+    pub fn set_allowed_clients(&self, allowed_clients: Vec<String>) {
+        self.state.acquire_write().allowed_clients = allowed_clients;
+    }
+
+    /// Queues the events returned for the next transaction sent with
+    /// [`ChainHandle::send_messages_and_wait_commit`]. Once the queue is
+    /// empty, transactions return no event.
+    pub fn push_tx_events(&self, events: Vec<IbcEvent>) {
+        self.state.acquire_write().tx_events.push_back(events);
+    }
+
+    /// Queues the responses returned for the next transaction sent with
+    /// [`ChainHandle::send_messages_and_wait_check_tx`]. Once the queue is
+    /// empty, transactions return no response.
+    pub fn push_tx_responses(&self, responses: Vec<TxSyncResponse>) {
+        self.state.acquire_write().tx_responses.push_back(responses);
+    }
+
+    /// Sets the events returned by [`ChainHandle::query_txs`], whatever the query.
+    pub fn set_queried_tx_events(&self, events: Vec<IbcEvent>) {
+        self.state.acquire_write().queried_tx_events = events;
+    }
+
+    /// Returns every message sent to the chain so far, in order.
+    pub fn sent_msgs(&self) -> Vec<Any> {
+        self.state.acquire_read().sent_msgs.clone()
+    }
+
+    /// Returns the correlation id of every batch of messages sent to the chain so far, in order.
+    pub fn sent_correlation_ids(&self) -> Vec<CorrelationId> {
+        self.state.acquire_read().sent_correlation_ids.clone()
+    }
+
+    /// Sends a batch of `events`, at the latest height of the chain,
+    /// to the current subscribers of the chain.
+    pub fn publish(&self, events: Vec<IbcEvent>) {
+        let mut state = self.state.acquire_write();
+
+        let batch = Arc::new(Ok(EventBatch {
+            chain_id: self.chain_id.clone(),
+            tracking_id: TrackingId::new_uuid(),
+            height: state.height,
+            events,
+        }));
+
+        state
+            .subscribers
+            .retain(|subscriber| subscriber.send(batch.clone()).is_ok());
+    }
+
+    /// Makes every subsequent call to the [`ChainHandle`] method named `method`,
+    /// e.g. `"query_channel"`, fail with the error built by `failure`.
+    pub fn inject_failure(&self, method: &'static str, failure: MockFailure) {
+        self.state.acquire_write().failures.insert(method, failure);
+    }
+
+    /// Stops the failures injected into the method named `method`.
+    pub fn clear_failure(&self, method: &'static str) {
+        self.state.acquire_write().failures.remove(method);
+    }
+
+    /// Returns the error built by the failure injected into `method`, if any.
+    ///
+    /// The failure is called without holding the state of the chain,
+    /// for it to be able to block without blocking the other methods.
+    fn failure(&self, method: &'static str) -> Option<Error> {
+        let failure = self.state.acquire_read().failures.get(method).copied();
+        failure.map(|failure| failure())
+    }
+
+    /// Fails if a failure was injected into `method`.
+    fn check_failure(&self, method: &'static str) -> Result<(), Error> {
+        match self.failure(method) {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the state of the chain, unless a failure was injected into `method`.
+    fn state(&self, method: &'static str) -> Result<RwLockReadGuard<'_, MockChainState>, Error> {
+        self.check_failure(method)?;
+        Ok(self.state.acquire_read())
+    }
+
+    /// Same as [`Self::state`], for the methods modifying the state of the chain.
+    fn state_mut(
+        &self,
+        method: &'static str,
+    ) -> Result<RwLockWriteGuard<'_, MockChainState>, Error> {
+        self.check_failure(method)?;
+        Ok(self.state.acquire_write())
+    }
+
+    /// Waits for the latency of a transaction, without holding the state of the chain.
+    fn wait_tx_latency(&self) {
+        let tx_latency = self.state.acquire_read().tx_latency;
+
+        if !tx_latency.is_zero() {
+            thread::sleep(tx_latency);
+        }
+    }
+
+    fn not_found(&self, what: impl core::fmt::Display) -> Error {
+        Error::query(format!("{} on mock chain {}", what, self.chain_id))
+    }
+
+    fn unsupported<T>(&self, method: &'static str) -> Result<T, Error> {
+        self.check_failure(method)?;
+        Err(self.not_found(format_args!("{}, which is not supported", method)))
+    }
+}
+
+fn advance_sequence(next: &mut BTreeMap<ChannelKey, Sequence>, key: ChannelKey, sent: Sequence) {
+    let next = next.entry(key).or_insert_with(|| Sequence::from(1));
+
+    if *next <= sent {
+        *next = sent.increment();
+    }
+}
+
+/// Queries for proofs fail, as would those to a node which does not return proofs.
+fn no_proof<T>(value: T, include_proof: IncludeProof) -> Result<(T, Option<MerkleProof>), Error> {
+    match include_proof {
+        IncludeProof::Yes => Err(Error::empty_response_proof()),
+        IncludeProof::No => Ok((value, None)),
+    }
+}
+
+impl Serialize for MockChainHandle {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.chain_id.serialize(serializer)
+    }
+}
+
+impl ChainHandle for MockChainHandle {
+    /// Creates the handle of an empty mock chain, ignoring the `sender`
+    /// since there is no chain runtime to send requests to.
+    fn new(chain_id: ChainId, _sender: channel::Sender<ChainRequest>) -> Self {
+        MockChainHandle::new(chain_id)
+    }
+
+    fn id(&self) -> ChainId {
+        self.chain_id.clone()
+    }
+
+    fn shutdown(&self) -> Result<(), Error> {
+        self.state_mut("shutdown")?.subscribers.clear();
+        Ok(())
+    }
+
+    fn health_check(&self) -> Result<HealthCheck, Error> {
+        self.check_failure("health_check")?;
+        Ok(HealthCheck::Healthy)
+    }
+
+    fn subscribe(&self) -> Result<Subscription, Error> {
+        let (sender, receiver) = channel::unbounded();
+        self.state_mut("subscribe")?.subscribers.push(sender);
+        Ok(receiver)
+    }
+
+    fn send_messages_and_wait_commit(
+        &self,
+        tracked_msgs: TrackedMsgs,
+    ) -> Result<Vec<IbcEvent>, Error> {
+        self.wait_tx_latency();
+
+        let mut state = self.state_mut("send_messages_and_wait_commit")?;
+        state.sent_correlation_ids.push(tracked_msgs.correlation_id);
+        state.sent_msgs.extend(tracked_msgs.msgs);
+        Ok(state.tx_events.pop_front().unwrap_or_default())
+    }
+
+    /// Records the messages, and returns the queued responses, if any.
+    fn send_messages_and_wait_check_tx(
+        &self,
+        tracked_msgs: TrackedMsgs,
+    ) -> Result<Vec<TxSyncResponse>, Error> {
+        self.wait_tx_latency();
+
+        let mut state = self.state_mut("send_messages_and_wait_check_tx")?;
+        state.sent_correlation_ids.push(tracked_msgs.correlation_id);
+        state.sent_msgs.extend(tracked_msgs.msgs);
+        Ok(state.tx_responses.pop_front().unwrap_or_default())
+    }
+
+    fn estimate_messages_gas(&self, _tracked_msgs: TrackedMsgs) -> Result<u64, Error> {
+        Ok(self.state("estimate_messages_gas")?.estimated_gas)
+    }
+
+    fn get_signer(&self) -> Result<Signer, Error> {
+        self.state("get_signer")?
+            .signer
+            .clone()
+            .ok_or_else(|| self.not_found("signer"))
+    }
+
+    fn config(&self) -> Result<ChainConfig, Error> {
+        self.state("config")?
+            .config
+            .clone()
+            .ok_or_else(|| self.not_found("config"))
+    }
+
+    fn max_block_time(&self) -> Result<MaxBlockTime, Error> {
+        let max_block_time = self
+            .state("max_block_time")?
+            .config
+            .as_ref()
+            .map(|config| config.max_block_time)
+            .ok_or_else(|| self.not_found("config"))?;
+
+        Ok(MaxBlockTime::new(None, max_block_time))
+    }
+
+    fn get_key(&self) -> Result<KeyEntry, Error> {
+        self.unsupported("get_key")
+    }
+
+    fn add_key(&self, _key_name: String, _key: KeyEntry) -> Result<(), Error> {
+        self.unsupported("add_key")
+    }
+
+    fn rotate_key(&self, _key_name: String) -> Result<(), Error> {
+        self.unsupported("rotate_key")
+    }
+
+    fn ibc_version(&self) -> Result<Option<semver::Version>, Error> {
+        self.check_failure("ibc_version")?;
+        Ok(None)
+    }
+
+    fn query_balance(
+        &self,
+        _key_name: Option<String>,
+        _denom: Option<String>,
+    ) -> Result<Balance, Error> {
+        self.unsupported("query_balance")
+    }
+
+    fn query_denom_trace(&self, hash: String) -> Result<DenomTrace, Error> {
+        self.state("query_denom_trace")?
+            .denom_traces
+            .get(&hash)
+            .cloned()
+            .ok_or_else(|| self.not_found(format_args!("denom trace {}", hash)))
+    }
+
+    /// Returns all the denomination traces added to the chain, regardless of the pagination.
+    fn query_denom_traces(
+        &self,
+        _request: QueryDenomTracesRequest,
+    ) -> Result<Vec<DenomTrace>, Error> {
+        Ok(self
+            .state("query_denom_traces")?
+            .denom_traces
+            .values()
+            .cloned()
+            .collect())
+    }
+
+    fn query_denom_metadata(&self, denom: String) -> Result<Option<DenomDisplay>, Error> {
+        Ok(self
+            .state("query_denom_metadata")?
+            .denom_displays
+            .get(&denom)
+            .cloned())
+    }
+
+    fn query_incentivized_packet(
+        &self,
+        _request: QueryIncentivizedPacketRequest,
+    ) -> Result<Vec<PacketFee>, Error> {
+        self.check_failure("query_incentivized_packet")?;
+        Ok(Vec::new())
+    }
+
+    fn query_channel_open_tx(
+        &self,
+        _request: QueryChannelOpenTxRequest,
+    ) -> Result<Option<ChannelOpenTx>, Error> {
+        self.check_failure("query_channel_open_tx")?;
+        Ok(None)
+    }
+
+    fn query_packet_relay_txs(
+        &self,
+        _request: QueryPacketRelayTxsRequest,
+    ) -> Result<Vec<PacketRelayTx>, Error> {
+        self.check_failure("query_packet_relay_txs")?;
+        Ok(Vec::new())
+    }
+
+    fn query_send_packet_txs(
+        &self,
+        _request: QuerySendPacketTxsRequest,
+    ) -> Result<Vec<SendPacketTx>, Error> {
+        self.check_failure("query_send_packet_txs")?;
+        Ok(Vec::new())
+    }
+
+    fn query_packet_tx(&self, _request: QueryPacketTxRequest) -> Result<Option<PacketTx>, Error> {
+        self.check_failure("query_packet_tx")?;
+        Ok(None)
+    }
+
+    /// Returns the acknowledgements stored with [`MockChainHandle::write_acknowledgement`],
+    /// as written at the latest height.
+    fn query_written_acks(
+        &self,
+        request: QueryWrittenAcksRequest,
+    ) -> Result<Vec<WrittenAck>, Error> {
+        let state = self.state("query_written_acks")?;
+        let acks = state.acks.get(&(request.port_id, request.channel_id));
+
+        Ok(request
+            .sequences
+            .into_iter()
+            .filter_map(|sequence| {
+                let ack = acks?.get(&sequence)?;
+
+                Some(WrittenAck {
+                    sequence,
+                    height: state.height,
+                    ack: ack.clone(),
+                })
+            })
+            .collect())
+    }
+
+    fn query_services(&self) -> Result<Vec<String>, Error> {
+        Ok(self.state("query_services")?.services.clone())
+    }
+
+    fn query_allowed_clients(&self) -> Result<Vec<String>, Error> {
+        Ok(self.state("query_allowed_clients")?.allowed_clients.clone())
+    }
+
+    fn query_node_versions(&self) -> Result<NodeVersions, Error> {
+        self.unsupported("query_node_versions")
+    }
+
+    fn query_unbonding_period(&self) -> Result<Duration, Error> {
+        self.state("query_unbonding_period")?
+            .unbonding_period
+            .ok_or_else(|| self.not_found("unbonding period"))
+    }
+
+    fn query_application_status(&self) -> Result<ChainStatus, Error> {
+        let state = self.state("query_application_status")?;
+
+        Ok(ChainStatus {
+            height: state.height,
+            timestamp: state.timestamp,
+        })
+    }
+
+    fn query_sync_status(&self) -> Result<SyncStatus, Error> {
+        let state = self.state("query_sync_status")?;
+
+        Ok(SyncStatus {
+            height: state.height,
+            timestamp: state.timestamp,
+            catching_up: false,
+            network: self.chain_id.clone(),
+            node_version: "mock".to_string(),
+        })
+    }
+
+    fn query_clients(
+        &self,
+        _request: QueryClientStatesRequest,
+    ) -> Result<Vec<IdentifiedAnyClientState>, Error> {
+        let state = self.state("query_clients")?;
+
+        Ok(state
+            .clients
+            .iter()
+            .map(|(client_id, client_state)| {
+                IdentifiedAnyClientState::new(client_id.clone(), client_state.clone())
+            })
+            .collect())
+    }
+
+    fn query_client_state(
+        &self,
+        request: QueryClientStateRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(AnyClientState, Option<MerkleProof>), Error> {
+        let client_state = self
+            .state("query_client_state")?
+            .clients
+            .get(&request.client_id)
+            .cloned()
+            .ok_or_else(|| self.not_found(format_args!("client {}", request.client_id)))?;
+
+        no_proof(client_state, include_proof)
+    }
+
+    fn query_client_connections(
+        &self,
+        request: QueryClientConnectionsRequest,
+    ) -> Result<Vec<ConnectionId>, Error> {
+        let state = self.state("query_client_connections")?;
+
+        Ok(state
+            .connections
+            .iter()
+            .filter(|(_, connection_end)| connection_end.client_id() == &request.client_id)
+            .map(|(connection_id, _)| connection_id.clone())
+            .collect())
+    }
+
+    fn query_consensus_state(
+        &self,
+        request: QueryConsensusStateRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(AnyConsensusState, Option<MerkleProof>), Error> {
+        let consensus_state = self
+            .state("query_consensus_state")?
+            .consensus_states
+            .get(&request.client_id)
+            .and_then(|states| states.get(&request.consensus_height))
+            .cloned()
+            .ok_or_else(|| {
+                self.not_found(format_args!(
+                    "consensus state of client {} at height {}",
+                    request.client_id, request.consensus_height
+                ))
+            })?;
+
+        no_proof(consensus_state, include_proof)
+    }
+
+    fn query_consensus_states(
+        &self,
+        request: QueryConsensusStatesRequest,
+    ) -> Result<Vec<AnyConsensusStateWithHeight>, Error> {
+        let state = self.state("query_consensus_states")?;
+
+        // Sorted from the latest, as the consensus states returned by a chain
+        Ok(state
+            .consensus_states
+            .get(&request.client_id)
+            .into_iter()
+            .flat_map(|states| states.iter().rev())
+            .map(|(height, consensus_state)| AnyConsensusStateWithHeight {
+                height: *height,
+                consensus_state: consensus_state.clone(),
+            })
+            .collect())
+    }
+
+    fn query_upgraded_client_state(
+        &self,
+        _request: QueryUpgradedClientStateRequest,
+    ) -> Result<(AnyClientState, MerkleProof), Error> {
+        self.unsupported("query_upgraded_client_state")
+    }
+
+    fn query_upgraded_consensus_state(
+        &self,
+        _request: QueryUpgradedConsensusStateRequest,
+    ) -> Result<(AnyConsensusState, MerkleProof), Error> {
+        self.unsupported("query_upgraded_consensus_state")
+    }
+
+    fn query_commitment_prefix(&self) -> Result<CommitmentPrefix, Error> {
+        self.check_failure("query_commitment_prefix")?;
+        Ok(CommitmentPrefix::try_from(b"ibc".to_vec()).expect("prefix is not empty"))
+    }
+
+    fn query_compatible_versions(&self) -> Result<Vec<Version>, Error> {
+        self.check_failure("query_compatible_versions")?;
+        Ok(get_compatible_versions())
+    }
+
+    /// Returns an uninitialized connection end for an unknown connection, as a chain would.
+    fn query_connection(
+        &self,
+        request: QueryConnectionRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(ConnectionEnd, Option<MerkleProof>), Error> {
+        let connection_end = self
+            .state("query_connection")?
+            .connections
+            .get(&request.connection_id)
+            .cloned()
+            .unwrap_or_default();
+
+        no_proof(connection_end, include_proof)
+    }
+
+    fn query_connections(
+        &self,
+        _request: QueryConnectionsRequest,
+    ) -> Result<Vec<IdentifiedConnectionEnd>, Error> {
+        let state = self.state("query_connections")?;
+
+        Ok(state
+            .connections
+            .iter()
+            .map(|(connection_id, connection_end)| {
+                IdentifiedConnectionEnd::new(connection_id.clone(), connection_end.clone())
+            })
+            .collect())
+    }
+
+    fn query_connection_channels(
+        &self,
+        request: QueryConnectionChannelsRequest,
+    ) -> Result<Vec<IdentifiedChannelEnd>, Error> {
+        let state = self.state("query_connection_channels")?;
+
+        Ok(state
+            .channels
+            .iter()
+            .filter(|(_, channel_end)| {
+                channel_end.connection_hops().first() == Some(&request.connection_id)
+            })
+            .map(|((port_id, channel_id), channel_end)| {
+                IdentifiedChannelEnd::new(port_id.clone(), channel_id.clone(), channel_end.clone())
+            })
+            .collect())
+    }
+
+    fn query_next_sequence_receive(
+        &self,
+        request: QueryNextSequenceReceiveRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(Sequence, Option<MerkleProof>), Error> {
+        let sequence = self
+            .state("query_next_sequence_receive")?
+            .next_sequence_recv
+            .get(&(request.port_id, request.channel_id))
+            .copied()
+            .unwrap_or_else(|| Sequence::from(1));
+
+        no_proof(sequence, include_proof)
+    }
+
+    fn query_next_sequence_send(
+        &self,
+        request: QueryNextSequenceSendRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(Sequence, Option<MerkleProof>), Error> {
+        let sequence = self
+            .state("query_next_sequence_send")?
+            .next_sequence_send
+            .get(&(request.port_id, request.channel_id))
+            .copied()
+            .unwrap_or_else(|| Sequence::from(1));
+
+        no_proof(sequence, include_proof)
+    }
+
+    fn query_channels(
+        &self,
+        _request: QueryChannelsRequest,
+    ) -> Result<Vec<IdentifiedChannelEnd>, Error> {
+        let state = self.state("query_channels")?;
+
+        Ok(state
+            .channels
+            .iter()
+            .map(|((port_id, channel_id), channel_end)| {
+                IdentifiedChannelEnd::new(port_id.clone(), channel_id.clone(), channel_end.clone())
+            })
+            .collect())
+    }
+
+    /// Returns an uninitialized channel end for an unknown channel, as a chain would.
+    fn query_channel(
+        &self,
+        request: QueryChannelRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(ChannelEnd, Option<MerkleProof>), Error> {
+        let channel_end = self
+            .state("query_channel")?
+            .channels
+            .get(&(request.port_id, request.channel_id))
+            .cloned()
+            .unwrap_or_default();
+
+        no_proof(channel_end, include_proof)
+    }
+
+    fn query_channel_client_state(
+        &self,
+        request: QueryChannelClientStateRequest,
+    ) -> Result<Option<IdentifiedAnyClientState>, Error> {
+        let state = self.state("query_channel_client_state")?;
+
+        let client_state = state
+            .channels
+            .get(&(request.port_id, request.channel_id))
+            .and_then(|channel_end| channel_end.connection_hops().first())
+            .and_then(|connection_id| state.connections.get(connection_id))
+            .and_then(|connection_end| {
+                let client_id = connection_end.client_id();
+
+                state.clients.get(client_id).map(|client_state| {
+                    IdentifiedAnyClientState::new(client_id.clone(), client_state.clone())
+                })
+            });
+
+        Ok(client_state)
+    }
+
+    /// Returns a mock header at `target_height`, with the latest timestamp of the chain.
+    fn build_header(
+        &self,
+        _trusted_height: Height,
+        target_height: Height,
+        _client_state: AnyClientState,
+    ) -> Result<(AnyHeader, Vec<AnyHeader>), Error> {
+        let timestamp = self.state("build_header")?.timestamp;
+        let header = MockHeader::new(target_height).with_timestamp(timestamp);
+
+        Ok((header.into(), vec![]))
+    }
+
+    fn build_client_state(
+        &self,
+        _height: Height,
+        _settings: ClientSettings,
+    ) -> Result<AnyClientState, Error> {
+        self.unsupported("build_client_state")
+    }
+
+    fn build_consensus_state(
+        &self,
+        _trusted: Height,
+        _target: Height,
+        _client_state: AnyClientState,
+    ) -> Result<AnyConsensusState, Error> {
+        self.unsupported("build_consensus_state")
+    }
+
+    fn check_misbehaviour(
+        &self,
+        _update: UpdateClient,
+        _client_state: AnyClientState,
+    ) -> Result<Option<MisbehaviourEvidence>, Error> {
+        self.check_failure("check_misbehaviour")?;
+        Ok(None)
+    }
+
+    fn build_connection_proofs_and_client_state(
+        &self,
+        _message_type: ConnectionMsgType,
+        _connection_id: &ConnectionId,
+        _client_id: &ClientId,
+        _height: Height,
+    ) -> Result<(Option<AnyClientState>, Proofs), Error> {
+        self.unsupported("build_connection_proofs_and_client_state")
+    }
+
+    /// Returns mock proofs of a channel end at `height`, which prove nothing.
+    fn build_channel_proofs(
+        &self,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        height: Height,
+    ) -> Result<Proofs, Error> {
+        let key = (port_id.clone(), channel_id.clone());
+
+        if !self
+            .state("build_channel_proofs")?
+            .channels
+            .contains_key(&key)
+        {
+            return Err(self.not_found(format_args!("channel {}/{}", port_id, channel_id)));
+        }
+
+        let object_proof =
+            CommitmentProofBytes::try_from(vec![0]).map_err(Error::malformed_proof)?;
+        Proofs::new(object_proof, None, None, None, height).map_err(Error::malformed_proof)
+    }
+
+    fn build_packet_proofs(
+        &self,
+        _packet_type: PacketMsgType,
+        _port_id: &PortId,
+        _channel_id: &ChannelId,
+        _sequence: Sequence,
+        _height: Height,
+    ) -> Result<Proofs, Error> {
+        self.unsupported("build_packet_proofs")
+    }
+
+    /// Returns an empty commitment for a packet without commitment, as a chain would.
+    fn query_packet_commitment(
+        &self,
+        request: QueryPacketCommitmentRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error> {
+        let commitment = self
+            .state("query_packet_commitment")?
+            .commitments
+            .get(&(request.port_id, request.channel_id))
+            .and_then(|commitments| commitments.get(&request.sequence))
+            .cloned()
+            .unwrap_or_default();
+
+        no_proof(commitment, include_proof)
+    }
+
+    fn query_packet_commitments(
+        &self,
+        request: QueryPacketCommitmentsRequest,
+    ) -> Result<(Vec<Sequence>, Height), Error> {
+        let state = self.state("query_packet_commitments")?;
+
+        let sequences = state
+            .commitments
+            .get(&(request.port_id, request.channel_id))
+            .map(|commitments| commitments.keys().copied().collect())
+            .unwrap_or_default();
+
+        Ok((sequences, state.height))
+    }
+
+    /// Returns `[1]` for a received packet, and an empty receipt otherwise.
+    fn query_packet_receipt(
+        &self,
+        request: QueryPacketReceiptRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error> {
+        let received = self
+            .state("query_packet_receipt")?
+            .receipts
+            .get(&(request.port_id, request.channel_id))
+            .map_or(false, |receipts| receipts.contains(&request.sequence));
+
+        let receipt = if received { vec![1] } else { Vec::new() };
+
+        no_proof(receipt, include_proof)
+    }
+
+    fn query_unreceived_packets(
+        &self,
+        request: QueryUnreceivedPacketsRequest,
+    ) -> Result<Vec<Sequence>, Error> {
+        let state = self.state("query_unreceived_packets")?;
+        let receipts = state.receipts.get(&(request.port_id, request.channel_id));
+
+        Ok(request
+            .packet_commitment_sequences
+            .into_iter()
+            .filter(|sequence| receipts.map_or(true, |receipts| !receipts.contains(sequence)))
+            .collect())
+    }
+
+    /// Returns an empty acknowledgement for a packet without acknowledgement, as a chain would.
+    fn query_packet_acknowledgement(
+        &self,
+        request: QueryPacketAcknowledgementRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(Vec<u8>, Option<MerkleProof>), Error> {
+        let ack = self
+            .state("query_packet_acknowledgement")?
+            .acks
+            .get(&(request.port_id, request.channel_id))
+            .and_then(|acks| acks.get(&request.sequence))
+            .cloned()
+            .unwrap_or_default();
+
+        no_proof(ack, include_proof)
+    }
+
+    /// Returns the acknowledged packets among `packet_commitment_sequences`,
+    /// or all of them if none is given.
+    fn query_packet_acknowledgements(
+        &self,
+        request: QueryPacketAcknowledgementsRequest,
+    ) -> Result<(Vec<Sequence>, Height), Error> {
+        let state = self.state("query_packet_acknowledgements")?;
+
+        let sequences = state
+            .acks
+            .get(&(request.port_id, request.channel_id))
+            .map(|acks| {
+                acks.keys()
+                    .copied()
+                    .filter(|sequence| {
+                        request.packet_commitment_sequences.is_empty()
+                            || request.packet_commitment_sequences.contains(sequence)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok((sequences, state.height))
+    }
+
+    /// Returns the packets among `packet_ack_sequences` whose commitment is still stored.
+    fn query_unreceived_acknowledgements(
+        &self,
+        request: QueryUnreceivedAcksRequest,
+    ) -> Result<Vec<Sequence>, Error> {
+        let state = self.state("query_unreceived_acknowledgements")?;
+        let commitments = state
+            .commitments
+            .get(&(request.port_id, request.channel_id));
+
+        Ok(request
+            .packet_ack_sequences
+            .into_iter()
+            .filter(|sequence| {
+                commitments.map_or(false, |commitments| commitments.contains_key(sequence))
+            })
+            .collect())
+    }
+
+    fn query_txs(&self, _request: QueryTxRequest) -> Result<Vec<IbcEvent>, Error> {
+        Ok(self.state("query_txs")?.queried_tx_events.clone())
+    }
+
+    fn query_blocks(
+        &self,
+        _request: QueryBlockRequest,
+    ) -> Result<(Vec<IbcEvent>, Vec<IbcEvent>), Error> {
+        self.check_failure("query_blocks")?;
+        Ok((Vec::new(), Vec::new()))
+    }
+
+    fn query_host_consensus_state(
+        &self,
+        request: QueryHostConsensusStateRequest,
+    ) -> Result<AnyConsensusState, Error> {
+        let state = self.state("query_host_consensus_state")?;
+
+        // The chain has a consensus state at each of the heights of its current revision
+        let height = match request.height {
+            QueryHeight::Latest => state.height,
+            QueryHeight::Specific(height)
+                if height.revision_number() == state.height.revision_number()
+                    && height <= state.height =>
+            {
+                height
+            }
+            QueryHeight::Specific(height) => {
+                return Err(
+                    self.not_found(format_args!("host consensus state at height {}", height))
+                )
+            }
+        };
+
+        let header = MockHeader::new(height).with_timestamp(state.timestamp);
+
+        Ok(MockConsensusState::new(header).into())
+    }
+}
@@ -0,0 +1,33 @@
+//! Non-blocking variants of the methods of [`ChainHandle`] which submit transactions,
+//! to be called from an async context, e.g. when embedding the relayer in a tokio service.
+//!
+//! The methods of a [`ChainHandle`] send a request to the runtime of the chain, which runs
+//! on its own thread with its own tokio runtime, and block the calling thread until the
+//! runtime replies. The functions of this module instead make that blocking call from a
+//! dedicated thread, see [`unblock`], so that they do not block the calling executor.
+
+use ibc::events::IbcEvent;
+
+use crate::chain::cosmos::types::tx::TxSyncResponse;
+use crate::chain::handle::ChainHandle;
+use crate::chain::tracking::TrackedMsgs;
+use crate::error::Error;
+use crate::util::unblock::unblock;
+
+/// Non-blocking variant of [`ChainHandle::send_messages_and_wait_commit`].
+pub async fn send_messages_and_wait_commit<Handle: ChainHandle>(
+    handle: &Handle,
+    tracked_msgs: TrackedMsgs,
+) -> Result<Vec<IbcEvent>, Error> {
+    let handle = handle.clone();
+    unblock(move || handle.send_messages_and_wait_commit(tracked_msgs)).await
+}
+
+/// Non-blocking variant of [`ChainHandle::send_messages_and_wait_check_tx`].
+pub async fn send_messages_and_wait_check_tx<Handle: ChainHandle>(
+    handle: &Handle,
+    tracked_msgs: TrackedMsgs,
+) -> Result<Vec<TxSyncResponse>, Error> {
+    let handle = handle.clone();
+    unblock(move || handle.send_messages_and_wait_check_tx(tracked_msgs)).await
+}
@@ -30,14 +30,21 @@ use ibc::Height;
 
 use crate::account::Balance;
 use crate::chain::client::ClientSettings;
-use crate::chain::endpoint::{ChainEndpoint, ChainStatus, HealthCheck};
+use crate::chain::cosmos::version::NodeVersions;
+use crate::chain::endpoint::{
+    ChainEndpoint, ChainStatus, ChannelOpenTx, HealthCheck, PacketRelayTx, PacketTx, SendPacketTx,
+    SyncStatus, WrittenAck,
+};
 use crate::chain::requests::{
-    QueryChannelClientStateRequest, QueryChannelRequest, QueryClientStatesRequest,
+    QueryChannelClientStateRequest, QueryChannelOpenTxRequest, QueryChannelRequest,
+    QueryClientStatesRequest, QueryDenomTracesRequest, QueryPacketRelayTxsRequest,
+    QueryPacketTxRequest, QuerySendPacketTxsRequest, QueryWrittenAcksRequest,
 };
 use crate::config::ChainConfig;
-use crate::denom::DenomTrace;
+use crate::denom::{DenomDisplay, DenomTrace};
 use crate::error::Error;
 use crate::event::monitor::{EventReceiver, EventSender, TxMonitorCmd};
+use crate::fee::PacketFee;
 use crate::keyring::{KeyEntry, KeyRing};
 use crate::light_client::Verified;
 use crate::light_client::{mock::LightClient as MockLightClient, LightClient};
@@ -46,7 +53,8 @@ use super::requests::{
     IncludeProof, QueryBlockRequest, QueryChannelsRequest, QueryClientConnectionsRequest,
     QueryClientStateRequest, QueryConnectionChannelsRequest, QueryConnectionRequest,
     QueryConnectionsRequest, QueryConsensusStateRequest, QueryConsensusStatesRequest,
-    QueryHostConsensusStateRequest, QueryNextSequenceReceiveRequest,
+    QueryHostConsensusStateRequest, QueryIncentivizedPacketRequest,
+    QueryNextSequenceReceiveRequest, QueryNextSequenceSendRequest,
     QueryPacketAcknowledgementRequest, QueryPacketAcknowledgementsRequest,
     QueryPacketCommitmentRequest, QueryPacketCommitmentsRequest, QueryPacketReceiptRequest,
     QueryTxRequest, QueryUnreceivedAcksRequest, QueryUnreceivedPacketsRequest,
@@ -73,6 +81,10 @@ impl MockChain {
             .trusting_period
             .unwrap_or_else(|| Duration::from_secs(14 * 24 * 60 * 60)) // 14 days
     }
+
+    fn unbonding_period(&self) -> Duration {
+        self.trusting_period().add(Duration::from_secs(1000))
+    }
 }
 
 impl ChainEndpoint for MockChain {
@@ -142,10 +154,14 @@ impl ChainEndpoint for MockChain {
     fn send_messages_and_wait_check_tx(
         &mut self,
         _tracked_msgs: TrackedMsgs,
-    ) -> Result<Vec<tendermint_rpc::endpoint::broadcast::tx_sync::Response>, Error> {
+    ) -> Result<Vec<crate::chain::cosmos::types::tx::TxSyncResponse>, Error> {
         todo!()
     }
 
+    fn estimate_messages_gas(&mut self, _tracked_msgs: &TrackedMsgs) -> Result<u64, Error> {
+        Ok(0)
+    }
+
     fn get_signer(&mut self) -> Result<Signer, Error> {
         Ok(get_dummy_account_id())
     }
@@ -162,11 +178,20 @@ impl ChainEndpoint for MockChain {
         unimplemented!()
     }
 
+    fn rotate_key(&mut self, key_name: &str) -> Result<(), Error> {
+        self.config.key_name = key_name.to_string();
+        Ok(())
+    }
+
     fn ibc_version(&self) -> Result<Option<semver::Version>, Error> {
         Ok(Some(semver::Version::new(3, 0, 0)))
     }
 
-    fn query_balance(&self, _key_name: Option<String>) -> Result<Balance, Error> {
+    fn query_balance(
+        &self,
+        _key_name: Option<String>,
+        _denom: Option<String>,
+    ) -> Result<Balance, Error> {
         unimplemented!()
     }
 
@@ -174,10 +199,80 @@ impl ChainEndpoint for MockChain {
         unimplemented!()
     }
 
+    fn query_denom_traces(
+        &self,
+        _request: QueryDenomTracesRequest,
+    ) -> Result<Vec<DenomTrace>, Error> {
+        unimplemented!()
+    }
+
+    fn query_denom_metadata(&self, _denom: String) -> Result<Option<DenomDisplay>, Error> {
+        unimplemented!()
+    }
+
+    fn query_incentivized_packet(
+        &self,
+        _request: QueryIncentivizedPacketRequest,
+    ) -> Result<Vec<PacketFee>, Error> {
+        unimplemented!()
+    }
+
+    fn query_channel_open_tx(
+        &self,
+        _request: QueryChannelOpenTxRequest,
+    ) -> Result<Option<ChannelOpenTx>, Error> {
+        unimplemented!()
+    }
+
+    fn query_packet_relay_txs(
+        &self,
+        _request: QueryPacketRelayTxsRequest,
+    ) -> Result<Vec<PacketRelayTx>, Error> {
+        unimplemented!()
+    }
+
+    fn query_send_packet_txs(
+        &self,
+        _request: QuerySendPacketTxsRequest,
+    ) -> Result<Vec<SendPacketTx>, Error> {
+        unimplemented!()
+    }
+
+    fn query_packet_tx(&self, _request: QueryPacketTxRequest) -> Result<Option<PacketTx>, Error> {
+        unimplemented!()
+    }
+
+    fn query_written_acks(
+        &self,
+        _request: QueryWrittenAcksRequest,
+    ) -> Result<Vec<WrittenAck>, Error> {
+        unimplemented!()
+    }
+
+    fn query_services(&self) -> Result<Vec<String>, Error> {
+        unimplemented!()
+    }
+
+    fn query_allowed_clients(&self) -> Result<Vec<String>, Error> {
+        unimplemented!()
+    }
+
+    fn query_node_versions(&self) -> Result<NodeVersions, Error> {
+        unimplemented!()
+    }
+
+    fn query_unbonding_period(&self) -> Result<Duration, Error> {
+        Ok(self.unbonding_period())
+    }
+
     fn query_commitment_prefix(&self) -> Result<CommitmentPrefix, Error> {
         unimplemented!()
     }
 
+    fn query_max_expected_time_per_block(&self) -> Result<Duration, Error> {
+        Ok(self.context.max_expected_time_per_block())
+    }
+
     fn query_application_status(&self) -> Result<ChainStatus, Error> {
         Ok(ChainStatus {
             height: self.context.host_height(),
@@ -185,6 +280,16 @@ impl ChainEndpoint for MockChain {
         })
     }
 
+    fn query_sync_status(&self) -> Result<SyncStatus, Error> {
+        Ok(SyncStatus {
+            height: self.context.host_height(),
+            timestamp: self.context.host_timestamp(),
+            catching_up: false,
+            network: self.config.id.clone(),
+            node_version: "mock".to_string(),
+        })
+    }
+
     fn query_clients(
         &self,
         _request: QueryClientStatesRequest,
@@ -324,6 +429,14 @@ impl ChainEndpoint for MockChain {
         unimplemented!()
     }
 
+    fn query_next_sequence_send(
+        &self,
+        _request: QueryNextSequenceSendRequest,
+        _include_proof: IncludeProof,
+    ) -> Result<(Sequence, Option<MerkleProof>), Error> {
+        unimplemented!()
+    }
+
     fn query_txs(&self, _request: QueryTxRequest) -> Result<Vec<IbcEvent>, Error> {
         unimplemented!()
     }
@@ -356,7 +469,7 @@ impl ChainEndpoint for MockChain {
             self.id().clone(),
             settings.trust_threshold,
             trusting_period,
-            self.trusting_period().add(Duration::from_secs(1000)),
+            self.unbonding_period(),
             settings.max_clock_drift,
             height,
             ProofSpecs::default(),
@@ -462,6 +575,7 @@ pub mod test_utils {
             r#type: ChainType::Mock,
             rpc_addr: "http://127.0.0.1:26656".parse().unwrap(),
             grpc_addr: "http://127.0.0.1:9090".parse().unwrap(),
+            rest_addr: None,
             websocket_addr: "ws://127.0.0.1:26656/websocket".parse().unwrap(),
             rpc_timeout: crate::config::default::rpc_timeout(),
             account_prefix: "".to_string(),
@@ -469,6 +583,8 @@ pub mod test_utils {
             store_prefix: "".to_string(),
             default_gas: None,
             key_store_type: Default::default(),
+            key_store_folder: None,
+            nickname: None,
             max_gas: None,
             gas_price: GasPrice::new(0.001, "uatom".to_string()),
             gas_adjustment: None,
@@ -482,8 +598,19 @@ pub mod test_utils {
             trust_threshold: Default::default(),
             packet_filter: PacketFilter::default(),
             address_type: AddressType::default(),
+            channel_versions: Default::default(),
+            tx_errors: Default::default(),
+            tls: Default::default(),
             memo_prefix: Default::default(),
             proof_specs: Default::default(),
+            strict_version_check: false,
+            verify_proofs_locally: false,
+            halt_detection_window: None,
+            tx_extension: None,
+            max_priority_price: 0,
+            failed_tx_dir: None,
+            max_transfer_memo_len: crate::config::default::max_transfer_memo_len(),
+            resolve_denoms: crate::config::default::resolve_denoms(),
         }
     }
 }
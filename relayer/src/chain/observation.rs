@@ -0,0 +1,144 @@
+//! Recording of the messages that the relayer would have sent to a chain,
+//! when running in observation mode.
+
+use alloc::collections::btree_map::BTreeMap as HashMap;
+
+use prost::Message;
+use tracing::info;
+
+use ibc::core::ics24_host::identifier::ChainId;
+
+use crate::chain::tracking::TrackedMsgs;
+use crate::telemetry;
+use crate::util::lock::{LockExt, RwArc};
+
+/// Records the messages that would have been sent to a chain when the relayer
+/// runs in observation mode, instead of broadcasting them.
+///
+/// Clones of a recorder share the same counters, so that a recorder handed over
+/// to a chain runtime can still be inspected from the outside.
+#[derive(Clone, Debug, Default)]
+pub struct ObservationRecorder {
+    /// How many messages would have been sent, per message type
+    would_send: RwArc<HashMap<String, u64>>,
+}
+
+impl ObservationRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the given messages, which would have been sent to the given chain
+    /// in transactions needing `estimated_gas` gas, if the estimation succeeded.
+    pub fn record(
+        &self,
+        chain_id: &ChainId,
+        tracked_msgs: &TrackedMsgs,
+        estimated_gas: Option<u64>,
+    ) {
+        let mut would_send = self.would_send.acquire_write();
+
+        for msg in tracked_msgs.messages() {
+            info!(
+                chain = %chain_id,
+                tracking_id = %tracked_msgs.tracking_id(),
+                r#type = %msg.type_url,
+                size = msg.encoded_len(),
+                "[observation mode] would have sent message"
+            );
+
+            *would_send.entry(msg.type_url.clone()).or_default() += 1;
+
+            telemetry!(would_send_msg_num, chain_id, &msg.type_url, 1);
+        }
+
+        info!(
+            chain = %chain_id,
+            tracking_id = %tracked_msgs.tracking_id(),
+            count = tracked_msgs.messages().len(),
+            estimated_gas = ?estimated_gas,
+            "[observation mode] would have sent {} message(s)",
+            tracked_msgs.messages().len()
+        );
+    }
+
+    /// How many messages of the given type would have been sent.
+    pub fn would_send_count(&self, msg_type: &str) -> u64 {
+        self.would_send
+            .acquire_read()
+            .get(msg_type)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// How many messages would have been sent in total.
+    pub fn would_send_total(&self) -> u64 {
+        self.would_send.acquire_read().values().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::sync::Arc;
+
+    use test_log::test;
+    use tokio::runtime::Runtime as TokioRuntime;
+
+    use ibc::core::ics02_client::client_type::ClientType;
+    use ibc::core::ics02_client::msgs::create_client::TYPE_URL as CREATE_CLIENT_TYPE_URL;
+    use ibc::core::ics24_host::identifier::ClientId;
+
+    use super::ObservationRecorder;
+    use crate::chain::handle::{BaseChainHandle, ChainHandle};
+    use crate::chain::mock::test_utils::get_basic_chain_config;
+    use crate::chain::mock::MockChain;
+    use crate::chain::requests::{IncludeProof, QueryClientStateRequest, QueryHeight};
+    use crate::chain::runtime::ChainRuntime;
+    use crate::foreign_client::ForeignClient;
+
+    /// In observation mode, the messages sent to a chain are recorded but never broadcast.
+    #[test]
+    fn observation_mode_does_not_broadcast() {
+        let a_cfg = get_basic_chain_config("chain_a");
+        let b_cfg = get_basic_chain_config("chain_b");
+
+        let rt = Arc::new(TokioRuntime::new().unwrap());
+        let recorder = ObservationRecorder::new();
+
+        let a_chain = ChainRuntime::<MockChain>::spawn_with_observation::<BaseChainHandle>(
+            a_cfg,
+            Some(recorder.clone()),
+            rt.clone(),
+        )
+        .unwrap();
+        let b_chain = ChainRuntime::<MockChain>::spawn::<BaseChainHandle>(b_cfg, rt).unwrap();
+
+        let a_height = a_chain.query_latest_height().unwrap();
+
+        let a_client = ForeignClient::restore(ClientId::default(), a_chain.clone(), b_chain);
+
+        for _ in 0..2 {
+            let res = a_client.build_create_client_and_send(Default::default());
+            assert!(
+                res.is_err(),
+                "client creation unexpectedly emitted an event: {:?}",
+                res
+            );
+        }
+
+        assert_eq!(recorder.would_send_count(CREATE_CLIENT_TYPE_URL), 2);
+        assert_eq!(recorder.would_send_total(), 2);
+
+        // Nothing was delivered to chain A
+        assert_eq!(a_chain.query_latest_height().unwrap(), a_height);
+
+        let res = a_chain.query_client_state(
+            QueryClientStateRequest {
+                client_id: ClientId::new(ClientType::Tendermint, 0).unwrap(),
+                height: QueryHeight::Latest,
+            },
+            IncludeProof::No,
+        );
+        assert!(res.is_err(), "client was created on chain A: {:?}", res);
+    }
+}
@@ -2,11 +2,15 @@ use core::fmt::Display;
 
 use crate::error::Error;
 
+use ibc::core::ics04_channel::channel::State as ChannelState;
 use ibc::core::ics04_channel::packet::Sequence;
 use ibc::core::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
-use ibc::events::WithBlockDataType;
+use ibc::events::{IbcEventType, WithBlockDataType};
 use ibc::Height;
 use ibc_proto::cosmos::base::query::v1beta1::PageRequest as RawPageRequest;
+use ibc_proto::ibc::applications::fee::v1::QueryIncentivizedPacketRequest as RawQueryIncentivizedPacketRequest;
+use ibc_proto::ibc::applications::transfer::v1::QueryDenomTracesRequest as RawQueryDenomTracesRequest;
+use ibc_proto::ibc::core::channel::v1::PacketId as RawPacketId;
 use ibc_proto::ibc::core::channel::v1::{
     QueryChannelClientStateRequest as RawQueryChannelClientStateRequest,
     QueryChannelsRequest as RawQueryChannelsRequest,
@@ -145,6 +149,19 @@ impl From<QueryClientStatesRequest> for RawQueryClientStatesRequest {
     }
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QueryDenomTracesRequest {
+    pub pagination: Option<PageRequest>,
+}
+
+impl From<QueryDenomTracesRequest> for RawQueryDenomTracesRequest {
+    fn from(request: QueryDenomTracesRequest) -> Self {
+        RawQueryDenomTracesRequest {
+            pagination: request.pagination.map(|pagination| pagination.into()),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct QueryConsensusStateRequest {
     pub client_id: ClientId,
@@ -383,6 +400,109 @@ impl From<QueryNextSequenceReceiveRequest> for RawQueryNextSequenceReceiveReques
     }
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QueryNextSequenceSendRequest {
+    pub port_id: PortId,
+    pub channel_id: ChannelId,
+    pub height: QueryHeight,
+}
+
+/// Query request for the ICS-29 fees escrowed for the packet sent with
+/// `sequence` on the channel end `port_id`/`channel_id` of the queried chain.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QueryIncentivizedPacketRequest {
+    pub port_id: PortId,
+    pub channel_id: ChannelId,
+    pub sequence: Sequence,
+    pub height: QueryHeight,
+}
+
+impl From<QueryIncentivizedPacketRequest> for RawQueryIncentivizedPacketRequest {
+    fn from(request: QueryIncentivizedPacketRequest) -> Self {
+        RawQueryIncentivizedPacketRequest {
+            packet_id: Some(RawPacketId {
+                port_id: request.port_id.to_string(),
+                channel_id: request.channel_id.to_string(),
+                sequence: request.sequence.into(),
+            }),
+            query_height: match request.height {
+                QueryHeight::Latest => 0,
+                QueryHeight::Specific(height) => height.revision_height(),
+            },
+        }
+    }
+}
+
+/// Query request for the transaction of the opening handshake which brought the
+/// channel end `port_id`/`channel_id` of the queried chain to `state`, e.g. the one
+/// which completed the handshake for `ChannelState::Open`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QueryChannelOpenTxRequest {
+    pub port_id: PortId,
+    pub channel_id: ChannelId,
+    pub state: ChannelState,
+}
+
+impl QueryChannelOpenTxRequest {
+    /// The types of the events emitted by the transaction bringing the channel end
+    /// to `state`, in the order they are to be looked for.
+    ///
+    /// The states which are not reached by the opening handshake have none.
+    pub fn event_types(&self) -> &'static [IbcEventType] {
+        match self.state {
+            ChannelState::Init => &[IbcEventType::OpenInitChannel],
+            ChannelState::TryOpen => &[IbcEventType::OpenTryChannel],
+            ChannelState::Open => &[
+                IbcEventType::OpenConfirmChannel,
+                IbcEventType::OpenAckChannel,
+            ],
+            ChannelState::Uninitialized | ChannelState::Closed => &[],
+        }
+    }
+}
+
+/// Query request for the transactions which relayed packets to the channel end
+/// `port_id`/`channel_id` of the queried chain, or acknowledgements of the packets
+/// sent from it, between `start_height` and `end_height` (inclusive).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QueryPacketRelayTxsRequest {
+    pub port_id: PortId,
+    pub channel_id: ChannelId,
+    pub start_height: Height,
+    pub end_height: Height,
+}
+
+/// Query request for the transactions which sent the packets with the given `sequences`
+/// from the channel end `port_id`/`channel_id` of the queried chain.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QuerySendPacketTxsRequest {
+    pub port_id: PortId,
+    pub channel_id: ChannelId,
+    pub sequences: Vec<Sequence>,
+}
+
+/// Query request for the transaction which emitted the `event_type` event of the packet
+/// with the given `sequence`, on the channel end `port_id`/`channel_id` of the queried chain.
+///
+/// The channel end is the source one of the packet for `SendPacket`, `AckPacket`, `Timeout`
+/// and `TimeoutOnClose`, and its destination one for `ReceivePacket` and `WriteAck`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QueryPacketTxRequest {
+    pub event_type: IbcEventType,
+    pub port_id: PortId,
+    pub channel_id: ChannelId,
+    pub sequence: Sequence,
+}
+
+/// Query request for the acknowledgements written for the packets with the given `sequences`,
+/// received on the channel end `port_id`/`channel_id` of the queried chain.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QueryWrittenAcksRequest {
+    pub port_id: PortId,
+    pub channel_id: ChannelId,
+    pub sequences: Vec<Sequence>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct QueryHostConsensusStateRequest {
     pub height: QueryHeight,
@@ -392,6 +512,7 @@ pub struct QueryHostConsensusStateRequest {
 #[derive(Clone, Debug)]
 pub enum QueryTxRequest {
     Packet(QueryPacketEventDataRequest),
+    PacketRange(QueryPacketEventRangeRequest),
     Client(QueryClientEventRequest),
     Transaction(QueryTxHash),
 }
@@ -412,6 +533,21 @@ pub struct QueryPacketEventDataRequest {
     pub height: QueryHeight,
 }
 
+/// Used to query all the packet events, identified by `event_id`, emitted on the
+/// channel end `port_id`/`channel_id` of the queried chain between `start_height`
+/// and `end_height` (inclusive).
+///
+/// For `SendPacket`, `AckPacket` and `TimeoutPacket` events the channel end is the
+/// source of the packets, whereas for `WriteAck` events it is their destination.
+#[derive(Clone, Debug)]
+pub struct QueryPacketEventRangeRequest {
+    pub event_id: WithBlockDataType,
+    pub port_id: PortId,
+    pub channel_id: ChannelId,
+    pub start_height: Height,
+    pub end_height: Height,
+}
+
 /// Query request for a single client event, identified by `event_id`, for `client_id`.
 #[derive(Clone, Debug)]
 pub struct QueryClientEventRequest {
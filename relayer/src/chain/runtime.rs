@@ -1,9 +1,10 @@
 use alloc::sync::Arc;
+use core::time::Duration;
 use std::thread;
 
 use crossbeam_channel as channel;
 use tokio::runtime::Runtime as TokioRuntime;
-use tracing::error;
+use tracing::{error, info, warn};
 
 use ibc::{
     core::{
@@ -35,30 +36,40 @@ use crate::{
     account::Balance,
     config::ChainConfig,
     connection::ConnectionMsgType,
-    denom::DenomTrace,
+    denom::{DenomDisplay, DenomTrace},
     error::Error,
     event::{
         bus::EventBus,
         monitor::{EventBatch, EventReceiver, MonitorCmd, Result as MonitorResult, TxMonitorCmd},
     },
+    fee::PacketFee,
     keyring::KeyEntry,
     light_client::LightClient,
 };
 
 use super::{
+    block_time::MaxBlockTime,
     client::ClientSettings,
-    endpoint::{ChainEndpoint, ChainStatus, HealthCheck},
+    cosmos::version::NodeVersions,
+    endpoint::{
+        ChainEndpoint, ChainStatus, ChannelOpenTx, HealthCheck, PacketRelayTx, PacketTx,
+        SendPacketTx, SyncStatus, WrittenAck,
+    },
     handle::{ChainHandle, ChainRequest, ReplyTo, Subscription},
+    observation::ObservationRecorder,
     requests::{
-        IncludeProof, QueryBlockRequest, QueryChannelClientStateRequest, QueryChannelRequest,
-        QueryChannelsRequest, QueryClientConnectionsRequest, QueryClientStateRequest,
-        QueryClientStatesRequest, QueryConnectionChannelsRequest, QueryConnectionRequest,
-        QueryConnectionsRequest, QueryConsensusStateRequest, QueryConsensusStatesRequest,
-        QueryHostConsensusStateRequest, QueryNextSequenceReceiveRequest,
-        QueryPacketAcknowledgementRequest, QueryPacketAcknowledgementsRequest,
-        QueryPacketCommitmentRequest, QueryPacketCommitmentsRequest, QueryPacketReceiptRequest,
-        QueryTxRequest, QueryUnreceivedAcksRequest, QueryUnreceivedPacketsRequest,
-        QueryUpgradedClientStateRequest, QueryUpgradedConsensusStateRequest,
+        IncludeProof, QueryBlockRequest, QueryChannelClientStateRequest, QueryChannelOpenTxRequest,
+        QueryChannelRequest, QueryChannelsRequest, QueryClientConnectionsRequest,
+        QueryClientStateRequest, QueryClientStatesRequest, QueryConnectionChannelsRequest,
+        QueryConnectionRequest, QueryConnectionsRequest, QueryConsensusStateRequest,
+        QueryConsensusStatesRequest, QueryDenomTracesRequest, QueryHostConsensusStateRequest,
+        QueryIncentivizedPacketRequest, QueryNextSequenceReceiveRequest,
+        QueryNextSequenceSendRequest, QueryPacketAcknowledgementRequest,
+        QueryPacketAcknowledgementsRequest, QueryPacketCommitmentRequest,
+        QueryPacketCommitmentsRequest, QueryPacketReceiptRequest, QueryPacketRelayTxsRequest,
+        QueryPacketTxRequest, QuerySendPacketTxsRequest, QueryTxRequest,
+        QueryUnreceivedAcksRequest, QueryUnreceivedPacketsRequest, QueryUpgradedClientStateRequest,
+        QueryUpgradedConsensusStateRequest, QueryWrittenAcksRequest,
     },
     tracking::TrackedMsgs,
 };
@@ -147,6 +158,14 @@ pub struct ChainRuntime<Endpoint: ChainEndpoint> {
     /// A handle to the light client
     light_client: Endpoint::LightClient,
 
+    /// If the relayer runs in observation mode, the recorder for the messages
+    /// which would have been sent to the chain. No transaction is ever
+    /// broadcast when this is set.
+    observation: Option<ObservationRecorder>,
+
+    /// The maximum expected time per block of the chain, resolved when the runtime is spawned
+    max_block_time: MaxBlockTime,
+
     #[allow(dead_code)]
     rt: Arc<TokioRuntime>, // Making this future-proof, so we keep the runtime around.
 }
@@ -159,6 +178,17 @@ where
     pub fn spawn<Handle: ChainHandle>(
         config: ChainConfig,
         rt: Arc<TokioRuntime>,
+    ) -> Result<Handle, Error> {
+        Self::spawn_with_observation(config, None, rt)
+    }
+
+    /// Spawns a new runtime for a specific Chain implementation, which
+    /// records the messages it is asked to send with the given recorder
+    /// instead of broadcasting them, if any.
+    pub fn spawn_with_observation<Handle: ChainHandle>(
+        config: ChainConfig,
+        observation: Option<ObservationRecorder>,
+        rt: Arc<TokioRuntime>,
     ) -> Result<Handle, Error> {
         // Similar to `from_config`.
         let chain = Endpoint::bootstrap(config, rt.clone())?;
@@ -167,7 +197,7 @@ where
         let light_client = chain.init_light_client()?;
 
         // Instantiate & spawn the runtime
-        let (handle, _) = Self::init(chain, light_client, rt);
+        let (handle, _) = Self::init(chain, light_client, observation, rt);
 
         Ok(handle)
     }
@@ -176,9 +206,10 @@ where
     fn init<Handle: ChainHandle>(
         chain: Endpoint,
         light_client: Endpoint::LightClient,
+        observation: Option<ObservationRecorder>,
         rt: Arc<TokioRuntime>,
     ) -> (Handle, thread::JoinHandle<()>) {
-        let chain_runtime = Self::new(chain, light_client, rt);
+        let chain_runtime = Self::new(chain, light_client, observation, rt);
 
         // Get a handle to the runtime
         let handle: Handle = chain_runtime.handle();
@@ -195,9 +226,16 @@ where
     }
 
     /// Basic constructor
-    fn new(chain: Endpoint, light_client: Endpoint::LightClient, rt: Arc<TokioRuntime>) -> Self {
+    fn new(
+        chain: Endpoint,
+        light_client: Endpoint::LightClient,
+        observation: Option<ObservationRecorder>,
+        rt: Arc<TokioRuntime>,
+    ) -> Self {
         let (request_sender, request_receiver) = channel::unbounded::<ChainRequest>();
 
+        let max_block_time = Self::resolve_max_block_time(&chain);
+
         Self {
             rt,
             chain,
@@ -206,9 +244,40 @@ where
             event_bus: EventBus::new(),
             event_monitor_ctrl: EventMonitorCtrl::none(),
             light_client,
+            observation,
+            max_block_time,
         }
     }
 
+    /// Query the maximum expected time per block of the chain, falling back
+    /// to the configured `max_block_time` if it is not available.
+    fn resolve_max_block_time(chain: &Endpoint) -> MaxBlockTime {
+        let config = chain.config();
+
+        let on_chain = match chain.query_max_expected_time_per_block() {
+            Ok(on_chain) => Some(on_chain),
+            Err(e) => {
+                warn!(
+                    "failed to query the max expected time per block of chain '{}': {}",
+                    config.id, e
+                );
+                None
+            }
+        };
+
+        let max_block_time = MaxBlockTime::new(on_chain, config.max_block_time);
+
+        info!(
+            "using max block time of {:?} from {} for chain '{}' (configured: {:?})",
+            max_block_time.value(),
+            max_block_time.source(),
+            config.id,
+            max_block_time.config,
+        );
+
+        max_block_time
+    }
+
     pub fn handle<Handle: ChainHandle>(&self) -> Handle {
         let chain_id = ChainEndpoint::id(&self.chain).clone();
         let sender = self.request_sender.clone();
@@ -259,6 +328,10 @@ where
                             self.send_messages_and_wait_check_tx(tracked_msgs, reply_to)?
                         },
 
+                        Ok(ChainRequest::EstimateMessagesGas { tracked_msgs, reply_to }) => {
+                            self.estimate_messages_gas(tracked_msgs, reply_to)?
+                        },
+
                         Ok(ChainRequest::Signer { reply_to }) => {
                             self.get_signer(reply_to)?
                         },
@@ -267,6 +340,10 @@ where
                             self.get_config(reply_to)?
                         },
 
+                        Ok(ChainRequest::MaxBlockTime { reply_to }) => {
+                            self.get_max_block_time(reply_to)?
+                        },
+
                         Ok(ChainRequest::GetKey { reply_to }) => {
                             self.get_key(reply_to)?
                         },
@@ -275,6 +352,10 @@ where
                             self.add_key(key_name, key, reply_to)?
                         },
 
+                        Ok(ChainRequest::RotateKey { key_name, reply_to }) => {
+                            self.rotate_key(key_name, reply_to)?
+                        },
+
                         Ok(ChainRequest::IbcVersion { reply_to }) => {
                             self.ibc_version(reply_to)?
                         },
@@ -303,18 +384,70 @@ where
                             self.build_channel_proofs(port_id, channel_id, height, reply_to)?
                         },
 
-                        Ok(ChainRequest::QueryBalance { key_name, reply_to }) => {
-                            self.query_balance(key_name, reply_to)?
+                        Ok(ChainRequest::QueryBalance { key_name, denom, reply_to }) => {
+                            self.query_balance(key_name, denom, reply_to)?
                         },
 
                         Ok(ChainRequest::QueryDenomTrace { hash, reply_to }) => {
                             self.query_denom_trace(hash, reply_to)?
                         },
 
+                        Ok(ChainRequest::QueryDenomTraces { request, reply_to }) => {
+                            self.query_denom_traces(request, reply_to)?
+                        },
+
+                        Ok(ChainRequest::QueryDenomMetadata { denom, reply_to }) => {
+                            self.query_denom_metadata(denom, reply_to)?
+                        },
+
+                        Ok(ChainRequest::QueryIncentivizedPacket { request, reply_to }) => {
+                            self.query_incentivized_packet(request, reply_to)?
+                        },
+
+                        Ok(ChainRequest::QueryChannelOpenTx { request, reply_to }) => {
+                            self.query_channel_open_tx(request, reply_to)?
+                        },
+
+                        Ok(ChainRequest::QueryPacketRelayTxs { request, reply_to }) => {
+                            self.query_packet_relay_txs(request, reply_to)?
+                        },
+
+                        Ok(ChainRequest::QuerySendPacketTxs { request, reply_to }) => {
+                            self.query_send_packet_txs(request, reply_to)?
+                        },
+
+                        Ok(ChainRequest::QueryPacketTx { request, reply_to }) => {
+                            self.query_packet_tx(request, reply_to)?
+                        },
+
+                        Ok(ChainRequest::QueryWrittenAcks { request, reply_to }) => {
+                            self.query_written_acks(request, reply_to)?
+                        },
+
+                        Ok(ChainRequest::QueryServices { reply_to }) => {
+                            self.query_services(reply_to)?
+                        },
+
+                        Ok(ChainRequest::QueryAllowedClients { reply_to }) => {
+                            self.query_allowed_clients(reply_to)?
+                        },
+
+                        Ok(ChainRequest::QueryNodeVersions { reply_to }) => {
+                            self.query_node_versions(reply_to)?
+                        },
+
+                        Ok(ChainRequest::QueryUnbondingPeriod { reply_to }) => {
+                            self.query_unbonding_period(reply_to)?
+                        },
+
                         Ok(ChainRequest::QueryApplicationStatus { reply_to }) => {
                             self.query_application_status(reply_to)?
                         },
 
+                        Ok(ChainRequest::QuerySyncStatus { reply_to }) => {
+                            self.query_sync_status(reply_to)?
+                        },
+
                         Ok(ChainRequest::QueryClients { request, reply_to }) => {
                             self.query_clients(request, reply_to)?
                         },
@@ -411,6 +544,10 @@ where
                             self.query_next_sequence_receive(request, include_proof, reply_to)?
                         },
 
+                        Ok(ChainRequest::QueryNextSequenceSend { request, include_proof, reply_to }) => {
+                            self.query_next_sequence_send(request, include_proof, reply_to)?
+                        },
+
                         Ok(ChainRequest::QueryPacketEventDataFromTxs { request, reply_to }) => {
                             self.query_txs(request, reply_to)?
                         },
@@ -460,25 +597,74 @@ where
         tracked_msgs: TrackedMsgs,
         reply_to: ReplyTo<Vec<IbcEvent>>,
     ) -> Result<(), Error> {
-        let result = self.chain.send_messages_and_wait_commit(tracked_msgs);
+        let result = if self.observe_messages(&tracked_msgs) {
+            Ok(vec![])
+        } else {
+            self.chain.send_messages_and_wait_commit(tracked_msgs)
+        };
+
         reply_to.send(result).map_err(Error::send)
     }
 
     fn send_messages_and_wait_check_tx(
         &mut self,
         tracked_msgs: TrackedMsgs,
-        reply_to: ReplyTo<Vec<tendermint_rpc::endpoint::broadcast::tx_sync::Response>>,
+        reply_to: ReplyTo<Vec<crate::chain::cosmos::types::tx::TxSyncResponse>>,
+    ) -> Result<(), Error> {
+        let result = if self.observe_messages(&tracked_msgs) {
+            Ok(vec![])
+        } else {
+            self.chain.send_messages_and_wait_check_tx(tracked_msgs)
+        };
+
+        reply_to.send(result).map_err(Error::send)
+    }
+
+    fn estimate_messages_gas(
+        &mut self,
+        tracked_msgs: TrackedMsgs,
+        reply_to: ReplyTo<u64>,
     ) -> Result<(), Error> {
-        let result = self.chain.send_messages_and_wait_check_tx(tracked_msgs);
+        let result = self.chain.estimate_messages_gas(&tracked_msgs);
+
         reply_to.send(result).map_err(Error::send)
     }
 
+    /// In observation mode, record the given messages instead of sending them,
+    /// and return `true`. Otherwise, return `false`.
+    ///
+    /// This is the single point through which every transaction submission
+    /// goes, which guarantees that nothing is broadcast in observation mode.
+    fn observe_messages(&mut self, tracked_msgs: &TrackedMsgs) -> bool {
+        let observation = match &self.observation {
+            Some(observation) => observation.clone(),
+            None => return false,
+        };
+
+        let estimated_gas = self
+            .chain
+            .estimate_messages_gas(tracked_msgs)
+            .map_err(|e| {
+                warn!(
+                    "[observation mode] failed to estimate the gas for messages on chain {}: {}",
+                    self.chain.id(),
+                    e
+                )
+            })
+            .ok();
+
+        observation.record(self.chain.id(), tracked_msgs, estimated_gas);
+
+        true
+    }
+
     fn query_balance(
         &self,
         key_name: Option<String>,
+        denom: Option<String>,
         reply_to: ReplyTo<Balance>,
     ) -> Result<(), Error> {
-        let balance = self.chain.query_balance(key_name);
+        let balance = self.chain.query_balance(key_name, denom);
         reply_to.send(balance).map_err(Error::send)
     }
 
@@ -487,11 +673,108 @@ where
         reply_to.send(denom_trace).map_err(Error::send)
     }
 
+    fn query_denom_traces(
+        &self,
+        request: QueryDenomTracesRequest,
+        reply_to: ReplyTo<Vec<DenomTrace>>,
+    ) -> Result<(), Error> {
+        let denom_traces = self.chain.query_denom_traces(request);
+        reply_to.send(denom_traces).map_err(Error::send)
+    }
+
+    fn query_denom_metadata(
+        &self,
+        denom: String,
+        reply_to: ReplyTo<Option<DenomDisplay>>,
+    ) -> Result<(), Error> {
+        let display = self.chain.query_denom_metadata(denom);
+        reply_to.send(display).map_err(Error::send)
+    }
+
+    fn query_incentivized_packet(
+        &self,
+        request: QueryIncentivizedPacketRequest,
+        reply_to: ReplyTo<Vec<PacketFee>>,
+    ) -> Result<(), Error> {
+        let fees = self.chain.query_incentivized_packet(request);
+        reply_to.send(fees).map_err(Error::send)
+    }
+
+    fn query_channel_open_tx(
+        &self,
+        request: QueryChannelOpenTxRequest,
+        reply_to: ReplyTo<Option<ChannelOpenTx>>,
+    ) -> Result<(), Error> {
+        let tx = self.chain.query_channel_open_tx(request);
+        reply_to.send(tx).map_err(Error::send)
+    }
+
+    fn query_packet_relay_txs(
+        &self,
+        request: QueryPacketRelayTxsRequest,
+        reply_to: ReplyTo<Vec<PacketRelayTx>>,
+    ) -> Result<(), Error> {
+        let txs = self.chain.query_packet_relay_txs(request);
+        reply_to.send(txs).map_err(Error::send)
+    }
+
+    fn query_send_packet_txs(
+        &self,
+        request: QuerySendPacketTxsRequest,
+        reply_to: ReplyTo<Vec<SendPacketTx>>,
+    ) -> Result<(), Error> {
+        let txs = self.chain.query_send_packet_txs(request);
+        reply_to.send(txs).map_err(Error::send)
+    }
+
+    fn query_packet_tx(
+        &self,
+        request: QueryPacketTxRequest,
+        reply_to: ReplyTo<Option<PacketTx>>,
+    ) -> Result<(), Error> {
+        let tx = self.chain.query_packet_tx(request);
+        reply_to.send(tx).map_err(Error::send)
+    }
+
+    fn query_written_acks(
+        &self,
+        request: QueryWrittenAcksRequest,
+        reply_to: ReplyTo<Vec<WrittenAck>>,
+    ) -> Result<(), Error> {
+        let acks = self.chain.query_written_acks(request);
+        reply_to.send(acks).map_err(Error::send)
+    }
+
+    fn query_services(&self, reply_to: ReplyTo<Vec<String>>) -> Result<(), Error> {
+        let services = self.chain.query_services();
+        reply_to.send(services).map_err(Error::send)
+    }
+
+    fn query_allowed_clients(&self, reply_to: ReplyTo<Vec<String>>) -> Result<(), Error> {
+        let allowed_clients = self.chain.query_allowed_clients();
+        reply_to.send(allowed_clients).map_err(Error::send)
+    }
+
+    fn query_node_versions(&self, reply_to: ReplyTo<NodeVersions>) -> Result<(), Error> {
+        let versions = self.chain.query_node_versions();
+        reply_to.send(versions).map_err(Error::send)
+    }
+
+    fn query_unbonding_period(&self, reply_to: ReplyTo<Duration>) -> Result<(), Error> {
+        let unbonding_period = self.chain.query_unbonding_period();
+        reply_to.send(unbonding_period).map_err(Error::send)
+    }
+
     fn query_application_status(&self, reply_to: ReplyTo<ChainStatus>) -> Result<(), Error> {
         let latest_timestamp = self.chain.query_application_status();
         reply_to.send(latest_timestamp).map_err(Error::send)
     }
 
+    fn query_sync_status(&self, reply_to: ReplyTo<SyncStatus>) -> Result<(), Error> {
+        let sync_status = self.chain.query_sync_status();
+        reply_to.send(sync_status).map_err(Error::send)
+    }
+
     fn get_signer(&mut self, reply_to: ReplyTo<Signer>) -> Result<(), Error> {
         let result = self.chain.get_signer();
         reply_to.send(result).map_err(Error::send)
@@ -502,6 +785,10 @@ where
         reply_to.send(result).map_err(Error::send)
     }
 
+    fn get_max_block_time(&self, reply_to: ReplyTo<MaxBlockTime>) -> Result<(), Error> {
+        reply_to.send(Ok(self.max_block_time)).map_err(Error::send)
+    }
+
     fn get_key(&mut self, reply_to: ReplyTo<KeyEntry>) -> Result<(), Error> {
         let result = self.chain.get_key();
         reply_to.send(result).map_err(Error::send)
@@ -517,6 +804,11 @@ where
         reply_to.send(result).map_err(Error::send)
     }
 
+    fn rotate_key(&mut self, key_name: String, reply_to: ReplyTo<()>) -> Result<(), Error> {
+        let result = self.chain.rotate_key(&key_name);
+        reply_to.send(result).map_err(Error::send)
+    }
+
     fn ibc_version(&mut self, reply_to: ReplyTo<Option<semver::Version>>) -> Result<(), Error> {
         let result = self.chain.ibc_version();
         reply_to.send(result).map_err(Error::send)
@@ -868,6 +1160,16 @@ where
         reply_to.send(result).map_err(Error::send)
     }
 
+    fn query_next_sequence_send(
+        &self,
+        request: QueryNextSequenceSendRequest,
+        include_proof: IncludeProof,
+        reply_to: ReplyTo<(Sequence, Option<MerkleProof>)>,
+    ) -> Result<(), Error> {
+        let result = self.chain.query_next_sequence_send(request, include_proof);
+        reply_to.send(result).map_err(Error::send)
+    }
+
     fn query_txs(
         &self,
         request: QueryTxRequest,
@@ -3,6 +3,8 @@ use core::fmt;
 use ibc_proto::google::protobuf::Any;
 use uuid::Uuid;
 
+use crate::chain::cosmos::types::gas::ExplicitFee;
+
 /// Identifier used to track an `EventBatch` along
 /// the relaying pipeline until the corresponding
 /// transactions are submitted and/or confirmed.
@@ -32,6 +34,11 @@ impl TrackingId {
     pub fn new_cleared_uuid() -> Self {
         Self::ClearedUuid(Uuid::new_v4())
     }
+
+    /// Whether this identifier tracks the clearing of pending packets
+    pub fn is_cleared(&self) -> bool {
+        matches!(self, Self::ClearedUuid(_))
+    }
 }
 
 impl fmt::Display for TrackingId {
@@ -54,6 +61,33 @@ impl fmt::Display for TrackingId {
     }
 }
 
+/// Short random identifier assigned to each piece of operational data when it is
+/// created, and recorded along the spans of its scheduling, of the building of its
+/// client updates, and of the broadcast and confirmation of its transactions, to
+/// follow the relaying of a batch of messages through the logs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CorrelationId(Uuid);
+
+impl CorrelationId {
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl Default for CorrelationId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for CorrelationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut s = self.0.to_string();
+        s.truncate(8);
+        s.fmt(f)
+    }
+}
+
 /// A wrapper over a vector of proto-encoded messages
 /// (`Vec<Any>`), which has an associated tracking
 /// number.
@@ -65,17 +99,35 @@ impl fmt::Display for TrackingId {
 pub struct TrackedMsgs {
     pub msgs: Vec<Any>,
     pub tracking_id: TrackingId,
+    /// The fee to pay for each transaction sending the messages,
+    /// instead of the fee estimated by simulating them.
+    pub fee: Option<ExplicitFee>,
+    /// The account paying the fees of the transactions through a fee grant,
+    /// instead of the fee granter of the chain configuration if any.
+    pub fee_granter: Option<String>,
+    /// The correlation id of the operational data the messages were assembled from,
+    /// or else a fresh one.
+    pub correlation_id: CorrelationId,
 }
 
 impl TrackedMsgs {
     pub fn new(msgs: Vec<Any>, tracking_id: TrackingId) -> Self {
-        Self { msgs, tracking_id }
+        Self {
+            msgs,
+            tracking_id,
+            fee: None,
+            fee_granter: None,
+            correlation_id: CorrelationId::new(),
+        }
     }
 
     pub fn new_static(msgs: Vec<Any>, tracking_id: &'static str) -> Self {
         Self {
             msgs,
             tracking_id: TrackingId::Static(tracking_id),
+            fee: None,
+            fee_granter: None,
+            correlation_id: CorrelationId::new(),
         }
     }
 
@@ -83,6 +135,9 @@ impl TrackedMsgs {
         Self {
             msgs,
             tracking_id: TrackingId::Uuid(tracking_id),
+            fee: None,
+            fee_granter: None,
+            correlation_id: CorrelationId::new(),
         }
     }
 
@@ -90,6 +145,9 @@ impl TrackedMsgs {
         Self {
             msgs: vec![msg],
             tracking_id: TrackingId::Static(tracking_id),
+            fee: None,
+            fee_granter: None,
+            correlation_id: CorrelationId::new(),
         }
     }
 
@@ -97,6 +155,33 @@ impl TrackedMsgs {
         Self {
             msgs: vec![msg],
             tracking_id: TrackingId::Uuid(tracking_id),
+            fee: None,
+            fee_granter: None,
+            correlation_id: CorrelationId::new(),
+        }
+    }
+
+    /// Tag the messages with the correlation id of the operational data they were assembled from.
+    pub fn with_correlation_id(self, correlation_id: CorrelationId) -> Self {
+        Self {
+            correlation_id,
+            ..self
+        }
+    }
+
+    /// Pay the given fee for each transaction instead of simulating them.
+    pub fn with_fee(self, fee: ExplicitFee) -> Self {
+        Self {
+            fee: Some(fee),
+            ..self
+        }
+    }
+
+    /// Have the given account pay the fees of the transactions through a fee grant.
+    pub fn with_fee_granter(self, fee_granter: String) -> Self {
+        Self {
+            fee_granter: Some(fee_granter),
+            ..self
         }
     }
 
@@ -107,4 +192,8 @@ impl TrackedMsgs {
     pub fn tracking_id(&self) -> TrackingId {
         self.tracking_id
     }
+
+    pub fn correlation_id(&self) -> CorrelationId {
+        self.correlation_id
+    }
 }
@@ -27,14 +27,17 @@ use crate::chain::requests::{
     QueryConnectionRequest, QueryHeight,
 };
 use crate::chain::tracking::TrackedMsgs;
+use crate::config::ChannelVersionPolicies;
 use crate::connection::Connection;
-use crate::foreign_client::{ForeignClient, HasExpiredOrFrozenError};
+use crate::foreign_client::{ForeignClient, ForeignClientErrorDetail, HasExpiredOrFrozenError};
 use crate::object::Channel as WorkerChannelObject;
+use crate::progress::{self, HandshakeProgress};
 use crate::supervisor::error::Error as SupervisorError;
 use crate::util::retry::retry_with_index;
-use crate::util::retry::{retry_count, RetryResult};
+use crate::util::retry::{retry_after_client_update, retry_count, RetryResult};
 use crate::util::task::Next;
 
+pub mod age;
 pub mod error;
 pub mod version;
 
@@ -168,6 +171,19 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Channel<ChainA, ChainB> {
         a_port: PortId,
         b_port: PortId,
         version: Option<Version>,
+    ) -> Result<Self, ChannelError> {
+        Self::new_with_progress(connection, ordering, a_port, b_port, version, &mut |_| {})
+    }
+
+    /// Creates a new channel like [`Channel::new`], reporting each completed
+    /// step of the handshake to `progress`.
+    pub fn new_with_progress(
+        connection: Connection<ChainA, ChainB>,
+        ordering: Order,
+        a_port: PortId,
+        b_port: PortId,
+        version: Option<Version>,
+        progress: &mut dyn FnMut(HandshakeProgress),
     ) -> Result<Self, ChannelError> {
         let src_connection_id = connection
             .src_connection_id()
@@ -197,7 +213,7 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Channel<ChainA, ChainB> {
             connection_delay: connection.delay_period,
         };
 
-        channel.handshake()?;
+        channel.handshake_with_progress(progress)?;
 
         Ok(channel)
     }
@@ -578,7 +594,10 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Channel<ChainA, ChainB> {
 
     /// Sends a channel open handshake message.
     /// The message sent depends on the chain status of the channel ends.
-    fn do_chan_open_handshake(&mut self) -> Result<(), ChannelError> {
+    fn do_chan_open_handshake(
+        &mut self,
+        progress: &mut dyn FnMut(HandshakeProgress),
+    ) -> Result<(), ChannelError> {
         let (a_state, b_state) = self.update_channel_and_query_states()?;
         debug!(
             "do_chan_open_handshake with channel end states: {}, {}",
@@ -597,62 +616,84 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Channel<ChainA, ChainB> {
                     })?;
                 let channel_id = extract_channel_id(&event)?;
                 self.a_side.channel_id = Some(channel_id.clone());
+                progress::report(progress, self.a_chain().id(), &event);
             }
 
             // send the Try message to chain a (source)
             (State::Uninitialized, State::Init) | (State::Init, State::Init) => {
-                let event = self.flipped().build_chan_open_try_and_send().map_err(|e| {
-                    error!("failed ChanOpenTry {:?}: {:?}", self.a_side, e);
-                    e
-                })?;
+                let event = self
+                    .flipped()
+                    .with_dst_client_update(Channel::build_chan_open_try_and_send)
+                    .map_err(|e| {
+                        error!("failed ChanOpenTry {:?}: {:?}", self.a_side, e);
+                        e
+                    })?;
 
                 let channel_id = extract_channel_id(&event)?;
                 self.a_side.channel_id = Some(channel_id.clone());
+                progress::report(progress, self.a_chain().id(), &event);
             }
 
             // send the Try message to chain b (destination)
             (State::Init, State::Uninitialized) => {
-                let event = self.build_chan_open_try_and_send().map_err(|e| {
-                    error!("failed ChanOpenTry {:?}: {:?}", self.b_side, e);
-                    e
-                })?;
+                let event = self
+                    .with_dst_client_update(Self::build_chan_open_try_and_send)
+                    .map_err(|e| {
+                        error!("failed ChanOpenTry {:?}: {:?}", self.b_side, e);
+                        e
+                    })?;
 
                 let channel_id = extract_channel_id(&event)?;
                 self.b_side.channel_id = Some(channel_id.clone());
+                progress::report(progress, self.b_chain().id(), &event);
             }
 
             // send the Ack message to chain a (source)
             (State::Init, State::TryOpen) | (State::TryOpen, State::TryOpen) => {
-                self.flipped().build_chan_open_ack_and_send().map_err(|e| {
-                    error!("failed ChanOpenAck {:?}: {:?}", self.a_side, e);
-                    e
-                })?;
+                let event = self
+                    .flipped()
+                    .with_dst_client_update(Channel::build_chan_open_ack_and_send)
+                    .map_err(|e| {
+                        error!("failed ChanOpenAck {:?}: {:?}", self.a_side, e);
+                        e
+                    })?;
+
+                progress::report(progress, self.a_chain().id(), &event);
             }
 
             // send the Ack message to chain b (destination)
             (State::TryOpen, State::Init) => {
-                self.build_chan_open_ack_and_send().map_err(|e| {
-                    error!("failed ChanOpenAck {:?}: {:?}", self.b_side, e);
-                    e
-                })?;
+                let event = self
+                    .with_dst_client_update(Self::build_chan_open_ack_and_send)
+                    .map_err(|e| {
+                        error!("failed ChanOpenAck {:?}: {:?}", self.b_side, e);
+                        e
+                    })?;
+
+                progress::report(progress, self.b_chain().id(), &event);
             }
 
             // send the Confirm message to chain b (destination)
             (State::Open, State::TryOpen) => {
-                self.build_chan_open_confirm_and_send().map_err(|e| {
+                let event = self.build_chan_open_confirm_and_send().map_err(|e| {
                     error!("failed ChanOpenConfirm {:?}: {:?}", self.b_side, e);
                     e
                 })?;
+
+                progress::report(progress, self.b_chain().id(), &event);
             }
 
             // send the Confirm message to chain a (source)
             (State::TryOpen, State::Open) => {
-                self.flipped()
+                let event = self
+                    .flipped()
                     .build_chan_open_confirm_and_send()
                     .map_err(|e| {
                         error!("failed ChanOpenConfirm {:?}: {:?}", self.a_side, e);
                         e
                     })?;
+
+                progress::report(progress, self.a_chain().id(), &event);
             }
 
             (State::Open, State::Open) => {
@@ -674,12 +715,22 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Channel<ChainA, ChainB> {
         Err(ChannelError::handshake_finalize())
     }
 
-    /// Executes the channel handshake protocol (ICS004)
-    fn handshake(&mut self) -> Result<(), ChannelError> {
+    /// Executes the channel handshake protocol (ICS004), picking up from the
+    /// current state of the channel ends on both chains, if any.
+    pub fn handshake(&mut self) -> Result<(), ChannelError> {
+        self.handshake_with_progress(&mut |_| {})
+    }
+
+    /// Executes the channel handshake protocol like [`Channel::handshake`],
+    /// reporting each completed step to `progress`.
+    pub fn handshake_with_progress(
+        &mut self,
+        progress: &mut dyn FnMut(HandshakeProgress),
+    ) -> Result<(), ChannelError> {
         let max_block_times = self.max_block_times()?;
 
         retry_with_index(handshake_retry::default_strategy(max_block_times), |_| {
-            if let Err(e) = self.do_chan_open_handshake() {
+            if let Err(e) = self.do_chan_open_handshake(progress) {
                 if e.is_expired_or_frozen_error() {
                     RetryResult::Err(e)
                 } else {
@@ -723,10 +774,12 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Channel<ChainA, ChainB> {
         state: State,
     ) -> Result<(Option<IbcEvent>, Next), ChannelError> {
         let res = match (state, self.counterparty_state()?) {
-            (State::Init, State::Uninitialized) => Some(self.build_chan_open_try_and_send()?),
-            (State::Init, State::Init) => Some(self.build_chan_open_try_and_send()?),
-            (State::TryOpen, State::Init) => Some(self.build_chan_open_ack_and_send()?),
-            (State::TryOpen, State::TryOpen) => Some(self.build_chan_open_ack_and_send()?),
+            (State::Init, State::Uninitialized) | (State::Init, State::Init) => {
+                Some(self.with_dst_client_update(Self::build_chan_open_try_and_send)?)
+            }
+            (State::TryOpen, State::Init) | (State::TryOpen, State::TryOpen) => {
+                Some(self.with_dst_client_update(Self::build_chan_open_ack_and_send)?)
+            }
             (State::Open, State::TryOpen) => Some(self.build_chan_open_confirm_and_send()?),
             (State::Open, State::Open) => return Ok((None, Next::Abort)),
 
@@ -786,6 +839,92 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Channel<ChainA, ChainB> {
         })
     }
 
+    /// Updates the client hosted by the destination chain to the given height of the source chain,
+    /// or to its latest height if none is given.
+    fn update_dst_client(&self, target_height: Option<Height>) -> Result<(), ChannelError> {
+        let client = ForeignClient::restore(
+            self.dst_client_id().clone(),
+            self.dst_chain().clone(),
+            self.src_chain().clone(),
+        );
+
+        let target_height = target_height.map_or(QueryHeight::Latest, QueryHeight::Specific);
+
+        match client.build_update_client_and_send(target_height, None) {
+            Ok(_) => Ok(()),
+            // Another relayer updated the client in the meantime
+            Err(e)
+                if matches!(
+                    e.detail(),
+                    ForeignClientErrorDetail::ClientAlreadyUpToDate(_)
+                ) =>
+            {
+                Ok(())
+            }
+            Err(e) => Err(ChannelError::client_operation(
+                self.dst_client_id().clone(),
+                self.dst_chain().id(),
+                e,
+            )),
+        }
+    }
+
+    /// Runs a handshake step which sends messages with proofs from the source chain to the
+    /// destination chain. If the destination chain rejects them because its client has no
+    /// consensus state at the height of the proofs, updates the client and runs the step
+    /// once more, see [`retry_after_client_update`].
+    fn with_dst_client_update(
+        &self,
+        step: impl Fn(&Self) -> Result<IbcEvent, ChannelError>,
+    ) -> Result<IbcEvent, ChannelError> {
+        retry_after_client_update(
+            || step(self),
+            ChannelError::is_consensus_state_not_found_error,
+            |e| match e.consensus_state_not_found_height() {
+                Some(height) => {
+                    warn!(
+                        "client {} on chain {} has no consensus state at height {}, \
+                         updating it to that height and retrying",
+                        self.dst_client_id(),
+                        self.dst_chain().id(),
+                        height
+                    );
+
+                    self.update_dst_client(Some(height)).or_else(|e| {
+                        warn!(
+                            "failed to update client {} on chain {} to height {}, \
+                             updating it to the latest height instead: {}",
+                            self.dst_client_id(),
+                            self.dst_chain().id(),
+                            height,
+                            e
+                        );
+
+                        self.update_dst_client(None)
+                    })
+                }
+                None => {
+                    warn!(
+                        "client {} on chain {} has no consensus state at the height of the proofs, \
+                         updating it and retrying",
+                        self.dst_client_id(),
+                        self.dst_chain().id()
+                    );
+
+                    self.update_dst_client(None)
+                }
+            },
+        )
+    }
+
+    /// Returns the `channel_versions` policies configured for the destination chain.
+    fn dst_channel_version_policies(&self) -> Result<ChannelVersionPolicies, ChannelError> {
+        self.dst_chain()
+            .config()
+            .map(|config| config.channel_versions)
+            .map_err(|e| ChannelError::query(self.dst_chain().id(), e))
+    }
+
     pub fn build_chan_open_init(&self) -> Result<Vec<Any>, ChannelError> {
         let signer = self
             .dst_chain()
@@ -795,12 +934,14 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Channel<ChainA, ChainB> {
         let counterparty = Counterparty::new(self.src_port_id().clone(), None);
 
         // If the user supplied a version, use that.
-        // Otherwise, either use the version defined for the `transfer`
-        // or an empty version if the port is non-standard.
+        // Otherwise, either use the version configured for the port
+        // in `channel_versions`, the version defined for the `transfer`
+        // port, or an empty version if the port is non-standard.
+        let version_policies = self.dst_channel_version_policies()?;
         let version = self
             .dst_version()
             .cloned()
-            .or_else(|| version::default_by_port(self.dst_port_id()))
+            .or_else(|| version::proposed_by_port(&version_policies, self.dst_port_id()))
             .unwrap_or_else(|| {
                 warn!(
                     chain = %self.dst_chain().id(),
@@ -856,7 +997,7 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Channel<ChainA, ChainB> {
                 info!("🎊  {} => {:#?}\n", self.dst_chain().id(), result);
                 Ok(result)
             }
-            IbcEvent::ChainError(e) => Err(ChannelError::tx_response(e)),
+            IbcEvent::ChainError(e) => Err(ChannelError::tx_response(e.message)),
             _ => Err(ChannelError::invalid_event(result)),
         }
     }
@@ -979,8 +1120,14 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Channel<ChainA, ChainB> {
         let counterparty =
             Counterparty::new(self.src_port_id().clone(), self.src_channel_id().cloned());
 
-        // Re-use the version that was either set on ChanOpenInit or overwritten by the application.
-        let version = src_channel.version().clone();
+        // Re-use the version that was either set on ChanOpenInit or overwritten by the application,
+        // unless the `channel_versions` policy for the destination port selects another one.
+        let version = version::select_try_version(
+            &self.dst_channel_version_policies()?,
+            &self.dst_chain().id(),
+            self.dst_port_id(),
+            src_channel.version(),
+        )?;
 
         let channel = ChannelEnd::new(
             State::TryOpen,
@@ -1042,7 +1189,7 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Channel<ChainA, ChainB> {
                 info!("🎊  {} => {:#?}\n", self.dst_chain().id(), result);
                 Ok(result)
             }
-            IbcEvent::ChainError(e) => Err(ChannelError::tx_response(e)),
+            IbcEvent::ChainError(e) => Err(ChannelError::tx_response(e.message)),
             _ => Err(ChannelError::invalid_event(result)),
         }
     }
@@ -1083,6 +1230,14 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Channel<ChainA, ChainB> {
             )
             .map_err(|e| ChannelError::query(self.dst_chain().id(), e))?;
 
+        // The version returned by the counterparty must be acceptable for the destination port
+        version::validate_counterparty_version(
+            &self.dst_channel_version_policies()?,
+            &self.dst_chain().id(),
+            self.dst_port_id(),
+            src_channel.version(),
+        )?;
+
         let query_height = self
             .src_chain()
             .query_latest_height()
@@ -1145,7 +1300,7 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Channel<ChainA, ChainB> {
                     info!("🎊  {} => {:#?}\n", channel.dst_chain().id(), result);
                     Ok(result)
                 }
-                IbcEvent::ChainError(e) => Err(ChannelError::tx_response(e)),
+                IbcEvent::ChainError(e) => Err(ChannelError::tx_response(e.message)),
                 _ => Err(ChannelError::invalid_event(result)),
             }
         }
@@ -1252,7 +1407,7 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Channel<ChainA, ChainB> {
                     info!("🎊  {} => {:#?}\n", channel.dst_chain().id(), result);
                     Ok(result)
                 }
-                IbcEvent::ChainError(e) => Err(ChannelError::tx_response(e)),
+                IbcEvent::ChainError(e) => Err(ChannelError::tx_response(e.message)),
                 _ => Err(ChannelError::invalid_event(result)),
             }
         }
@@ -1322,7 +1477,7 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Channel<ChainA, ChainB> {
                 info!("👋 {} => {:#?}\n", self.dst_chain().id(), result);
                 Ok(result)
             }
-            IbcEvent::ChainError(e) => Err(ChannelError::tx_response(e)),
+            IbcEvent::ChainError(e) => Err(ChannelError::tx_response(e.message)),
             _ => Err(ChannelError::invalid_event(result)),
         }
     }
@@ -1419,7 +1574,7 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Channel<ChainA, ChainB> {
                 info!("👋 {} => {:#?}\n", self.dst_chain().id(), result);
                 Ok(result)
             }
-            IbcEvent::ChainError(e) => Err(ChannelError::tx_response(e)),
+            IbcEvent::ChainError(e) => Err(ChannelError::tx_response(e.message)),
             _ => Err(ChannelError::invalid_event(result)),
         }
     }
@@ -1482,3 +1637,171 @@ fn check_destination_channel_state(
         Err(ChannelError::channel_already_exist(channel_id.clone()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+    use core::time::Duration;
+
+    use ibc::clients::ics07_tendermint::client_state::{AllowUpdate, ClientState};
+    use ibc::core::ics02_client::client_state::AnyClientState;
+    use ibc::core::ics02_client::header::Header;
+    use ibc::core::ics02_client::msgs::update_client::{self, MsgUpdateAnyClient};
+    use ibc::core::ics02_client::trust_threshold::TrustThreshold;
+    use ibc::core::ics03_connection::connection::ConnectionEnd;
+    use ibc::core::ics04_channel::channel::{ChannelEnd, Counterparty, Order, State};
+    use ibc::core::ics04_channel::events::OpenTry;
+    use ibc::core::ics04_channel::msgs::chan_open_try;
+    use ibc::core::ics04_channel::Version;
+    use ibc::core::ics23_commitment::specs::ProofSpecs;
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId};
+    use ibc::events::{ChainErrorEvent, IbcEvent};
+    use ibc::mock::client_state::MockConsensusState;
+    use ibc::mock::header::MockHeader;
+    use ibc::signer::Signer;
+    use ibc::timestamp::Timestamp;
+    use ibc::Height;
+    use tendermint_proto::Protobuf;
+
+    use crate::chain::handle::mock::MockChainHandle;
+    use crate::chain::handle::ChainHandle;
+    use crate::chain::mock::test_utils::get_basic_chain_config;
+    use crate::util::retry::retry_after_client_update;
+
+    use super::{Channel, ChannelError, ChannelSide};
+
+    #[test]
+    fn chan_try_retried_after_client_update() {
+        let a = MockChainHandle::new(ChainId::from_string("chain_A"));
+        let b = MockChainHandle::new(ChainId::from_string("chain_B"));
+        let client_id = ClientId::from_str("07-tendermint-0").unwrap();
+        let connection_id = ConnectionId::new(0);
+
+        // The channel end initialized on `chain_A`, whose proofs are built at its latest height
+        let proof_height = Height::new(0, 50).unwrap();
+        a.set_latest(proof_height, Timestamp::now());
+        a.add_channel(
+            PortId::transfer(),
+            ChannelId::new(0),
+            ChannelEnd::new(
+                State::Init,
+                Order::Unordered,
+                Counterparty::new(PortId::transfer(), None),
+                vec![connection_id.clone()],
+                Version::ics20(),
+            ),
+        );
+
+        // The client of `chain_A` on `chain_B`, last updated below the proof height
+        let client_height = Height::new(0, 10).unwrap();
+        let client_state = ClientState::new(
+            a.id(),
+            TrustThreshold::ONE_THIRD,
+            Duration::from_secs(14 * 24 * 3600),
+            Duration::from_secs(21 * 24 * 3600),
+            Duration::from_secs(5),
+            client_height,
+            ProofSpecs::default(),
+            vec![],
+            AllowUpdate {
+                after_expiry: true,
+                after_misbehaviour: true,
+            },
+        )
+        .unwrap();
+
+        b.set_latest(Height::new(0, 20).unwrap(), Timestamp::now());
+        b.set_config(get_basic_chain_config("chain_B"));
+        b.set_signer(Signer::from_str("cosmos1relayer").unwrap());
+        b.add_connection(connection_id.clone(), ConnectionEnd::default());
+        b.add_client(client_id.clone(), AnyClientState::Tendermint(client_state));
+        b.add_consensus_state(
+            client_id.clone(),
+            client_height,
+            MockConsensusState::new(MockHeader::new(client_height)).into(),
+        );
+
+        // `chain_B` rejects the first `ChannelOpenTry`, as ibc-go does when the client has
+        // no consensus state at the proof height, and accepts it after the client update
+        b.push_tx_events(vec![IbcEvent::ChainError(ChainErrorEvent {
+            message: format!(
+                "failed to execute message; message index: 1: channel handshake open try \
+                 failed: consensus height {}: consensus state not found",
+                proof_height
+            ),
+            code: 7,
+            codespace: "client".to_string(),
+            msg_index: Some(1),
+        })]);
+        b.push_tx_events(vec![]);
+        b.push_tx_events(vec![OpenTry {
+            height: Height::new(0, 21).unwrap(),
+            port_id: PortId::transfer(),
+            channel_id: Some(ChannelId::new(0)),
+            connection_id: connection_id.clone(),
+            counterparty_port_id: PortId::transfer(),
+            counterparty_channel_id: Some(ChannelId::new(0)),
+        }
+        .into()]);
+
+        let channel = Channel {
+            ordering: Order::Unordered,
+            a_side: ChannelSide::new(
+                a,
+                ClientId::default(),
+                connection_id.clone(),
+                PortId::transfer(),
+                Some(ChannelId::new(0)),
+                None,
+            ),
+            b_side: ChannelSide::new(
+                b.clone(),
+                client_id,
+                connection_id,
+                PortId::transfer(),
+                None,
+                None,
+            ),
+            connection_delay: Duration::ZERO,
+        };
+
+        let result = channel.with_dst_client_update(Channel::build_chan_open_try_and_send);
+        assert!(matches!(result, Ok(IbcEvent::OpenTryChannel(_))));
+
+        // The client is updated on its own, before the step is run once more
+        let msgs = b.sent_msgs();
+        let type_urls = msgs
+            .iter()
+            .map(|msg| msg.type_url.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            type_urls,
+            [
+                update_client::TYPE_URL,
+                chan_open_try::TYPE_URL,
+                update_client::TYPE_URL,
+                update_client::TYPE_URL,
+                chan_open_try::TYPE_URL,
+            ]
+        );
+
+        // To the height reported by the error
+        let update = MsgUpdateAnyClient::decode_vec(&msgs[2].value).unwrap();
+        assert_eq!(update.header.height(), proof_height);
+    }
+
+    #[test]
+    fn chan_try_not_retried_on_other_errors() {
+        let result: Result<IbcEvent, _> = retry_after_client_update(
+            || {
+                Err(ChannelError::tx_response(
+                    "channel handshake open try failed: connection not found".to_string(),
+                ))
+            },
+            ChannelError::is_consensus_state_not_found_error,
+            |_| panic!("the client must not be updated"),
+        );
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,163 @@
+//! Estimation of how long the channel ends stuck in the opening handshake, i.e. in
+//! the `Init` or `TryOpen` state, have been in that state.
+//!
+//! The age is estimated from the time of the block of the transaction which brought the
+//! channel end to its state, which is only found if the node indexes the transactions
+//! and did not prune that one.
+
+use core::fmt::{Display, Error as FmtError, Formatter};
+use core::time::Duration;
+
+use serde::Serialize;
+
+use ibc::core::ics04_channel::channel::State;
+use ibc::core::ics24_host::identifier::{ChannelId, PortId};
+use ibc::timestamp::Timestamp;
+use ibc::Height;
+
+use crate::chain::handle::ChainHandle;
+use crate::chain::requests::{
+    QueryChannelOpenTxRequest, QueryHeight, QueryHostConsensusStateRequest,
+};
+use crate::error::Error;
+
+/// Whether a channel end in the given state is stuck in the opening handshake.
+///
+/// Note that the channel ends whose handshake is in progress are in these states as well.
+pub fn is_stuck(state: State) -> bool {
+    matches!(state, State::Init | State::TryOpen)
+}
+
+/// When a channel end reached its current state.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct StateAge {
+    pub state: State,
+    /// The height of the transaction which brought the channel end to `state`.
+    pub height: Height,
+    pub timestamp: Timestamp,
+    pub tx_hash: String,
+    pub age_secs: u64,
+}
+
+impl StateAge {
+    pub fn new(
+        state: State,
+        height: Height,
+        timestamp: Timestamp,
+        tx_hash: String,
+        now: Timestamp,
+    ) -> Self {
+        Self {
+            state,
+            height,
+            timestamp,
+            tx_hash,
+            age_secs: now.duration_since(&timestamp).unwrap_or_default().as_secs(),
+        }
+    }
+
+    /// Queries when the channel end `port_id`/`channel_id` reached the given `state`.
+    ///
+    /// Returns `None` if the transaction which brought the channel end to `state` is not
+    /// found, e.g. because the node does not index the transactions or pruned it.
+    pub fn query<Chain: ChainHandle>(
+        chain: &Chain,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        state: State,
+        now: Timestamp,
+    ) -> Result<Option<Self>, Error> {
+        let tx = chain.query_channel_open_tx(QueryChannelOpenTxRequest {
+            port_id: port_id.clone(),
+            channel_id: channel_id.clone(),
+            state,
+        })?;
+
+        let tx = match tx {
+            Some(tx) => tx,
+            None => return Ok(None),
+        };
+
+        let timestamp = chain
+            .query_host_consensus_state(QueryHostConsensusStateRequest {
+                height: QueryHeight::Specific(tx.height),
+            })?
+            .timestamp();
+
+        Ok(Some(Self::new(
+            state, tx.height, timestamp, tx.tx_hash, now,
+        )))
+    }
+
+    pub fn age(&self) -> Duration {
+        Duration::from_secs(self.age_secs)
+    }
+}
+
+impl Display for StateAge {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        write!(
+            f,
+            "{} for {} (since height {}, tx {})",
+            self.state,
+            humantime::format_duration(self.age()),
+            self.height,
+            self.tx_hash
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_stuck, StateAge};
+
+    use core::time::Duration;
+
+    use ibc::core::ics04_channel::channel::State;
+    use ibc::timestamp::Timestamp;
+    use ibc::Height;
+
+    fn at(secs: u64) -> Timestamp {
+        Timestamp::from_nanoseconds(secs * 1_000_000_000).unwrap()
+    }
+
+    #[test]
+    fn stuck_states() {
+        assert!(is_stuck(State::Init));
+        assert!(is_stuck(State::TryOpen));
+        assert!(!is_stuck(State::Open));
+        assert!(!is_stuck(State::Closed));
+        assert!(!is_stuck(State::Uninitialized));
+    }
+
+    #[test]
+    fn age_since_state_change() {
+        let height = Height::new(1, 42).unwrap();
+        let age = StateAge::new(
+            State::TryOpen,
+            height,
+            at(1_666_000_000),
+            "ABCD".to_string(),
+            at(1_666_090_061),
+        );
+
+        assert_eq!(age.age(), Duration::from_secs(90_061));
+        assert_eq!(
+            age.to_string(),
+            "TRYOPEN for 1day 1h 1m 1s (since height 1-42, tx ABCD)"
+        );
+    }
+
+    #[test]
+    fn no_age_in_the_future() {
+        let age = StateAge::new(
+            State::Init,
+            Height::new(1, 42).unwrap(),
+            at(1_666_000_010),
+            "ABCD".to_string(),
+            at(1_666_000_000),
+        );
+
+        assert_eq!(age.age(), Duration::ZERO);
+    }
+}
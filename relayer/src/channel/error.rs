@@ -2,10 +2,14 @@ use core::time::Duration;
 use flex_error::define_error;
 use ibc::core::ics02_client::error::Error as ClientError;
 use ibc::core::ics04_channel::channel::State;
+use ibc::core::ics04_channel::Version;
 use ibc::core::ics24_host::identifier::{ChainId, ChannelId, ClientId, PortChannelId, PortId};
 use ibc::events::IbcEvent;
+use ibc::Height;
 
-use crate::error::Error as RelayerError;
+use crate::error::{
+    consensus_state_not_found_height, is_consensus_state_not_found, Error as RelayerError,
+};
 use crate::foreign_client::{ForeignClientError, HasExpiredOrFrozenError};
 use crate::supervisor::Error as SupervisorError;
 
@@ -150,6 +154,17 @@ define_error! {
                     e.counterparty_channel_id)
             },
 
+        IncompatibleVersion
+            {
+                chain_id: ChainId,
+                port_id: PortId,
+                version: Version,
+            }
+            | e | {
+                format_args!("version '{}' proposed by the counterparty is not accepted on port '{}' of chain '{}' by the configured `channel_versions` policy",
+                    e.version, e.port_id, e.chain_id)
+            },
+
         MissingEvent
             { description: String }
             | e | {
@@ -190,6 +205,28 @@ define_error! {
     }
 }
 
+impl ChannelError {
+    /// Whether the chain rejected the messages sent to it because its client
+    /// of the counterparty chain has no consensus state at the height of their proofs.
+    pub fn is_consensus_state_not_found_error(&self) -> bool {
+        match self.detail() {
+            ChannelErrorDetail::Submit(e) => e.source.is_consensus_state_not_found_error(),
+            ChannelErrorDetail::TxResponse(e) => is_consensus_state_not_found(&e.reason),
+            _ => false,
+        }
+    }
+
+    /// The height of the proofs for which the chain found no consensus state,
+    /// if it could be told from the error.
+    pub fn consensus_state_not_found_height(&self) -> Option<Height> {
+        match self.detail() {
+            ChannelErrorDetail::Submit(e) => e.source.consensus_state_not_found_height(),
+            ChannelErrorDetail::TxResponse(e) => consensus_state_not_found_height(&e.reason),
+            _ => None,
+        }
+    }
+}
+
 impl HasExpiredOrFrozenError for ChannelErrorDetail {
     fn is_expired_or_frozen_error(&self) -> bool {
         match self {
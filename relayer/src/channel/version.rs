@@ -4,11 +4,19 @@
 //! channel version to be used in a channel open
 //! handshake.
 
+use alloc::collections::btree_map::BTreeMap;
+
 use ibc::{
     applications::transfer,
-    core::{ics04_channel::Version, ics24_host::identifier::PortId},
+    core::{
+        ics04_channel::Version,
+        ics24_host::identifier::{ChainId, ChannelId, PortId},
+    },
 };
 
+use crate::channel::ChannelError;
+use crate::config::ChannelVersionPolicies;
+
 /// Returns the default channel version, depending on the the given [`PortId`].
 pub fn default_by_port(port_id: &PortId) -> Option<Version> {
     if port_id.as_str() == transfer::PORT_ID_STR {
@@ -18,3 +26,338 @@ pub fn default_by_port(port_id: &PortId) -> Option<Version> {
         None
     }
 }
+
+/// Returns the version to propose in a `ChanOpenInit` on the given [`PortId`],
+/// as configured in the chain's `channel_versions` policies, falling back
+/// to the default version for the port.
+pub fn proposed_by_port(policies: &ChannelVersionPolicies, port_id: &PortId) -> Option<Version> {
+    policies
+        .for_port(port_id)
+        .map(|policy| policy.version.clone())
+        .or_else(|| default_by_port(port_id))
+}
+
+/// Selects the version to use in a `ChanOpenTry` on the given [`PortId`] of chain
+/// `chain_id`, given the version proposed by the counterparty in its `ChanOpenInit`.
+///
+/// - If no policy is configured for the port, the counterparty version is echoed.
+/// - If the counterparty did not propose any version, the configured version is proposed.
+/// - Otherwise, the counterparty version is used if the policy accepts it.
+pub fn select_try_version(
+    policies: &ChannelVersionPolicies,
+    chain_id: &ChainId,
+    port_id: &PortId,
+    counterparty_version: &Version,
+) -> Result<Version, ChannelError> {
+    let policy = match policies.for_port(port_id) {
+        Some(policy) => policy,
+        None => return Ok(counterparty_version.clone()),
+    };
+
+    if counterparty_version == &Version::empty() {
+        Ok(policy.version.clone())
+    } else if policy.accepts(counterparty_version) {
+        Ok(counterparty_version.clone())
+    } else {
+        Err(ChannelError::incompatible_version(
+            chain_id.clone(),
+            port_id.clone(),
+            counterparty_version.clone(),
+        ))
+    }
+}
+
+/// Checks that the version returned by the counterparty in its `ChanOpenTry`
+/// is accepted on the given [`PortId`] of chain `chain_id`.
+pub fn validate_counterparty_version(
+    policies: &ChannelVersionPolicies,
+    chain_id: &ChainId,
+    port_id: &PortId,
+    counterparty_version: &Version,
+) -> Result<(), ChannelError> {
+    match policies.for_port(port_id) {
+        Some(policy) if !policy.accepts(counterparty_version) => {
+            Err(ChannelError::incompatible_version(
+                chain_id.clone(),
+                port_id.clone(),
+                counterparty_version.clone(),
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Whether the given versions of the two ends of a channel are the same.
+///
+/// Versions which are JSON documents, e.g. the versions of the channels of
+/// middlewares wrapping an application, are compared as JSON values, so that
+/// they match regardless of the order of their fields or of their whitespace.
+pub fn versions_match(a: &Version, b: &Version) -> bool {
+    if a == b {
+        return true;
+    }
+
+    let parse = |version: &Version| serde_json::from_str::<serde_json::Value>(&version.to_string());
+
+    match (parse(a), parse(b)) {
+        (Ok(a), Ok(b)) => a.is_object() && a == b,
+        _ => false,
+    }
+}
+
+/// The versions of the two ends of a channel, as fetched
+/// before relaying the packets sent on the channel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChannelVersions {
+    /// The version of the channel end on the chain the packets are sent from
+    pub src: Version,
+    /// The version of the channel end on the chain the packets are sent to
+    pub dst: Version,
+}
+
+impl ChannelVersions {
+    pub fn is_mismatch(&self) -> bool {
+        !versions_match(&self.src, &self.dst)
+    }
+
+    fn reversed(&self) -> Self {
+        Self {
+            src: self.dst.clone(),
+            dst: self.src.clone(),
+        }
+    }
+}
+
+/// A channel end, identified by its chain, port and channel.
+pub type ChannelEndId = (ChainId, PortId, ChannelId);
+
+/// The [`ChannelVersions`] of the channels checked so far, stored for both
+/// of their ends, so that the workers relaying the packets of a channel in
+/// either direction check its versions once.
+#[derive(Debug, Default)]
+pub struct ChannelVersionCache(BTreeMap<ChannelEndId, (ChannelEndId, ChannelVersions)>);
+
+impl ChannelVersionCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The versions of the channel whose packets are sent from the given end.
+    pub fn get(&self, src: &ChannelEndId) -> Option<&ChannelVersions> {
+        self.0.get(src).map(|(_, versions)| versions)
+    }
+
+    pub fn insert(&mut self, src: ChannelEndId, dst: ChannelEndId, versions: ChannelVersions) {
+        self.0
+            .insert(dst.clone(), (src.clone(), versions.reversed()));
+        self.0.insert(src, (dst, versions));
+    }
+
+    /// Forgets the versions of the channel with the given end, so that they are
+    /// fetched again, e.g. because the channel went through a handshake.
+    pub fn invalidate(&mut self, end: &ChannelEndId) {
+        if let Some((counterparty, _)) = self.0.remove(end) {
+            self.0.remove(&counterparty);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::str::FromStr;
+
+    fn policies() -> ChannelVersionPolicies {
+        let toml_content = r#"
+            policies = [
+                { port = 'icacontroller-*', version = 'ics27-1', accepted = ['ics27-1*'] },
+                { port = 'oracle', version = 'oracle-2' },
+                { port = 'transfer', version = 'ics20-1', accepted = ['ics20-1', '*fee*'] },
+            ]
+            "#;
+
+        #[derive(serde::Deserialize)]
+        struct Wrapper {
+            policies: ChannelVersionPolicies,
+        }
+
+        toml::from_str::<Wrapper>(toml_content)
+            .expect("could not parse channel version policies")
+            .policies
+    }
+
+    fn chain_id() -> ChainId {
+        ChainId::from_string("chain-a")
+    }
+
+    fn port(port_id: &str) -> PortId {
+        PortId::from_str(port_id).unwrap()
+    }
+
+    fn version(v: &str) -> Version {
+        Version::new(v.to_string())
+    }
+
+    #[test]
+    fn proposed_version_by_port() {
+        let policies = policies();
+
+        assert_eq!(
+            proposed_by_port(&policies, &port("icacontroller-cosmos1abc")),
+            Some(version("ics27-1"))
+        );
+        assert_eq!(
+            proposed_by_port(&policies, &port("oracle")),
+            Some(version("oracle-2"))
+        );
+        assert_eq!(
+            proposed_by_port(&ChannelVersionPolicies::default(), &port("transfer")),
+            Some(Version::ics20())
+        );
+        assert_eq!(proposed_by_port(&policies, &port("unknown")), None);
+    }
+
+    #[test]
+    fn try_version_without_policy_echoes_counterparty() {
+        let policies = policies();
+
+        assert_eq!(
+            select_try_version(&policies, &chain_id(), &port("unknown"), &version("any")).unwrap(),
+            version("any")
+        );
+        assert_eq!(
+            select_try_version(&policies, &chain_id(), &port("unknown"), &Version::empty())
+                .unwrap(),
+            Version::empty()
+        );
+    }
+
+    #[test]
+    fn try_version_proposes_configured_version_when_counterparty_is_empty() {
+        let policies = policies();
+
+        assert_eq!(
+            select_try_version(&policies, &chain_id(), &port("oracle"), &Version::empty()).unwrap(),
+            version("oracle-2")
+        );
+    }
+
+    #[test]
+    fn try_version_accepts_matching_counterparty_version() {
+        let policies = policies();
+
+        assert_eq!(
+            select_try_version(
+                &policies,
+                &chain_id(),
+                &port("icacontroller-cosmos1abc"),
+                &version("ics27-1-ordered")
+            )
+            .unwrap(),
+            version("ics27-1-ordered")
+        );
+        assert_eq!(
+            select_try_version(
+                &policies,
+                &chain_id(),
+                &port("transfer"),
+                &version(r#"{"fee_version":"ics29-1","app_version":"ics20-1"}"#)
+            )
+            .unwrap(),
+            version(r#"{"fee_version":"ics29-1","app_version":"ics20-1"}"#)
+        );
+    }
+
+    #[test]
+    fn try_version_rejects_unaccepted_counterparty_version() {
+        let policies = policies();
+
+        assert!(select_try_version(
+            &policies,
+            &chain_id(),
+            &port("icacontroller-cosmos1abc"),
+            &version("ics20-1")
+        )
+        .is_err());
+        assert!(select_try_version(
+            &policies,
+            &chain_id(),
+            &port("oracle"),
+            &version("oracle-1")
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn counterparty_version_validation() {
+        let policies = policies();
+
+        assert!(validate_counterparty_version(
+            &policies,
+            &chain_id(),
+            &port("oracle"),
+            &version("oracle-2")
+        )
+        .is_ok());
+        assert!(validate_counterparty_version(
+            &policies,
+            &chain_id(),
+            &port("oracle"),
+            &version("oracle-3")
+        )
+        .is_err());
+        assert!(validate_counterparty_version(
+            &policies,
+            &chain_id(),
+            &port("unknown"),
+            &version("anything")
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn versions_match_json_aware() {
+        assert!(versions_match(&version("ics20-1"), &version("ics20-1")));
+        assert!(!versions_match(&version("ics20-1"), &version("ics20-2")));
+        assert!(versions_match(
+            &version(r#"{"fee_version":"ics29-1","app_version":"ics20-1"}"#),
+            &version(r#"{ "app_version": "ics20-1", "fee_version": "ics29-1" }"#)
+        ));
+        assert!(!versions_match(
+            &version(r#"{"fee_version":"ics29-1","app_version":"ics20-1"}"#),
+            &version("ics20-1")
+        ));
+        assert!(!versions_match(
+            &version(r#"{"fee_version":"ics29-1","app_version":"ics20-1"}"#),
+            &version(r#"{"fee_version":"ics29-1","app_version":"ics20-2"}"#)
+        ));
+    }
+
+    #[test]
+    fn version_cache_holds_both_ends() {
+        let a = (chain_id(), port("transfer"), ChannelId::new(0));
+        let b = (
+            ChainId::from_string("chain-b"),
+            port("transfer"),
+            ChannelId::new(3),
+        );
+
+        let mut cache = ChannelVersionCache::new();
+        cache.insert(
+            a.clone(),
+            b.clone(),
+            ChannelVersions {
+                src: version("ics20-1"),
+                dst: version("ics20-2"),
+            },
+        );
+
+        assert_eq!(cache.get(&b).unwrap().src, version("ics20-2"));
+        assert!(cache.get(&a).unwrap().is_mismatch());
+
+        cache.invalidate(&b);
+        assert!(cache.get(&a).is_none());
+        assert!(cache.get(&b).is_none());
+    }
+}
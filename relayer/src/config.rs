@@ -1,13 +1,23 @@
 //! Relayer configuration
 
+pub mod channel_version;
 pub mod error;
 pub mod filter;
+pub mod merge;
 pub mod proof_specs;
+pub mod sanitize;
+pub mod tls;
+pub mod tx_error;
 pub mod types;
 
 use alloc::collections::BTreeMap;
 use core::{fmt, time::Duration};
-use std::{fs, fs::File, io::Write, path::Path};
+use std::{
+    fs,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+};
 
 use serde_derive::{Deserialize, Serialize};
 use tendermint_light_client_verifier::types::TrustThreshold;
@@ -20,9 +30,12 @@ use crate::chain::ChainType;
 use crate::config::types::{MaxMsgNum, MaxTxSize, Memo};
 use crate::keyring::Store;
 
+pub use channel_version::{ChannelVersionPolicies, ChannelVersionPolicy};
 pub use error::Error;
 
-pub use filter::PacketFilter;
+pub use filter::{PacketFilter, RelayMode};
+pub use tls::TlsConfig;
+pub use tx_error::{TxErrorAction, TxErrorPolicies, TxErrorPolicy};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GasPrice {
@@ -58,6 +71,10 @@ pub mod default {
         100
     }
 
+    pub fn max_events_per_batch() -> usize {
+        1000
+    }
+
     pub fn rpc_timeout() -> Duration {
         Duration::from_secs(10)
     }
@@ -73,6 +90,14 @@ pub mod default {
     pub fn connection_delay() -> Duration {
         ZERO_DURATION
     }
+
+    pub fn max_transfer_memo_len() -> usize {
+        256
+    }
+
+    pub fn resolve_denoms() -> bool {
+        true
+    }
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -118,6 +143,26 @@ impl Config {
         }
     }
 
+    /// Returns the kinds of packet messages to relay on the channel [`PortId`] [`ChannelId`]
+    /// on [`ChainId`], as set in the packet filter of the chain.
+    pub fn packet_relay_mode(
+        &self,
+        chain_id: &ChainId,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> RelayMode {
+        self.find_chain(chain_id)
+            .map(|chain_config| chain_config.packet_filter.relay_mode(port_id, channel_id))
+            .unwrap_or_default()
+    }
+
+    /// Returns whether to verify locally the proofs queried from the chain [`ChainId`]
+    /// before submitting them, as set in the configuration of the chain.
+    pub fn verify_proofs_locally(&self, chain_id: &ChainId) -> bool {
+        self.find_chain(chain_id)
+            .map_or(false, |chain_config| chain_config.verify_proofs_locally)
+    }
+
     pub fn chains_map(&self) -> BTreeMap<&ChainId, &ChainConfig> {
         self.chains.iter().map(|c| (&c.id, c)).collect()
     }
@@ -126,6 +171,14 @@ impl Config {
 #[derive(Copy, Clone, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct ModeConfig {
+    /// Whether to run in observation mode, in which the workers run normally
+    /// but the transactions they would submit are only recorded, never broadcast.
+    #[serde(default)]
+    pub observation: bool,
+    /// Whether to pre-build, after the startup scan, a header for each client
+    /// which will be updated by the workers, so that the first client updates are fast.
+    #[serde(default)]
+    pub prewarm_clients: bool,
     pub clients: Clients,
     pub connections: Connections,
     pub channels: Channels,
@@ -144,10 +197,13 @@ impl ModeConfig {
 impl Default for ModeConfig {
     fn default() -> Self {
         Self {
+            observation: false,
+            prewarm_clients: false,
             clients: Clients {
                 enabled: true,
                 refresh: true,
                 misbehaviour: true,
+                halt_on_short_unbonding: false,
             },
             connections: Connections { enabled: false },
             channels: Channels { enabled: false },
@@ -156,6 +212,8 @@ impl Default for ModeConfig {
                 clear_interval: default::clear_packets_interval(),
                 clear_on_start: true,
                 tx_confirmation: false,
+                max_events_per_batch: default::max_events_per_batch(),
+                ignore_version_mismatch: false,
             },
         }
     }
@@ -169,6 +227,8 @@ pub struct Clients {
     pub refresh: bool,
     #[serde(default)]
     pub misbehaviour: bool,
+    #[serde(default)]
+    pub halt_on_short_unbonding: bool,
 }
 
 #[derive(Copy, Clone, Debug, Default, Deserialize, Serialize)]
@@ -193,6 +253,10 @@ pub struct Packets {
     pub clear_on_start: bool,
     #[serde(default = "default::tx_confirmation")]
     pub tx_confirmation: bool,
+    #[serde(default = "default::max_events_per_batch")]
+    pub max_events_per_batch: usize,
+    #[serde(default)]
+    pub ignore_version_mismatch: bool,
 }
 
 impl Default for Packets {
@@ -202,6 +266,8 @@ impl Default for Packets {
             clear_interval: default::clear_packets_interval(),
             clear_on_start: false,
             tx_confirmation: default::tx_confirmation(),
+            max_events_per_batch: default::max_events_per_batch(),
+            ignore_version_mismatch: false,
         }
     }
 }
@@ -249,6 +315,8 @@ pub struct TelemetryConfig {
     pub enabled: bool,
     pub host: String,
     pub port: u16,
+    #[serde(default)]
+    pub labels: TelemetryLabels,
 }
 
 impl Default for TelemetryConfig {
@@ -257,10 +325,55 @@ impl Default for TelemetryConfig {
             enabled: false,
             host: "127.0.0.1".to_string(),
             port: 3001,
+            labels: TelemetryLabels::default(),
         }
     }
 }
 
+/// The labels emitted with the telemetry metrics.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TelemetryLabels {
+    pub preset: TelemetryLabelsPreset,
+    /// The labels which are not emitted, on top of those dropped by the preset.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub drop: Vec<TelemetryLabel>,
+}
+
+/// A predefined selection of the labels emitted with the telemetry metrics.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TelemetryLabelsPreset {
+    /// All the labels.
+    Full,
+    /// The labels which do not depend on the channels or clients, e.g. `chain` and `counterparty`.
+    PerChain,
+    /// Only the `chain`, `relay_mode` and `type` labels.
+    Minimal,
+}
+
+impl Default for TelemetryLabelsPreset {
+    fn default() -> Self {
+        Self::Full
+    }
+}
+
+/// A dimension along which the telemetry metrics are labelled.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TelemetryLabel {
+    Chain,
+    Counterparty,
+    Channel,
+    Port,
+    Client,
+    Account,
+    Denom,
+    RelayMode,
+    QueryType,
+    Type,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct RestConfig {
@@ -310,21 +423,40 @@ impl fmt::Display for AddressType {
     }
 }
 
+/// The extension option included in the body of the transactions sent to a chain.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TxExtension {
+    None,
+    /// The `ExtensionOptionDynamicFeeTx` required by Ethermint chains
+    /// when their fee market is enabled.
+    EthermintDynamicFee,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct ChainConfig {
     pub id: ChainId,
+    /// A short name to refer to the chain on the command line, instead of its identifier.
+    pub nickname: Option<String>,
     #[serde(default = "default::chain_type")]
     pub r#type: ChainType,
     pub rpc_addr: tendermint_rpc::Url,
     pub websocket_addr: tendermint_rpc::Url,
     pub grpc_addr: tendermint_rpc::Url,
+    /// The LCD (REST) endpoint of the chain, queried in place of the gRPC endpoint
+    /// when it is unavailable, for the queries which have an LCD equivalent.
+    pub rest_addr: Option<tendermint_rpc::Url>,
     #[serde(default = "default::rpc_timeout", with = "humantime_serde")]
     pub rpc_timeout: Duration,
     pub account_prefix: String,
     pub key_name: String,
     #[serde(default)]
     pub key_store_type: Store,
+    /// The folder the keys of the chain are stored in, when they are stored on disk.
+    /// Defaults to `<home>/keys/<chain id>/keyring-test`, where `<home>` is the Hermes
+    /// home folder, against which a relative folder is resolved.
+    pub key_store_folder: Option<PathBuf>,
     pub store_prefix: String,
     pub default_gas: Option<u64>,
     pub max_gas: Option<u64>,
@@ -360,6 +492,39 @@ pub struct ChainConfig {
     pub memo_prefix: Memo,
     #[serde(default, with = "self::proof_specs")]
     pub proof_specs: ProofSpecs,
+    /// Refuse to start relaying on the chain when its node runs an unsupported
+    /// version of Tendermint, the SDK or IBC-go, instead of only logging a warning.
+    #[serde(default)]
+    pub strict_version_check: bool,
+    /// Verify the proofs queried from this chain against the app hash known to the
+    /// counterparty client before submitting them, to detect a node on a fork.
+    #[serde(default)]
+    pub verify_proofs_locally: bool,
+    /// Consider the chain halted, and stop relaying packets to it, when
+    /// the height of its node has not advanced for this long, or when its latest block
+    /// is older than this. Halt detection is disabled if not set.
+    #[serde(default, with = "humantime_serde")]
+    pub halt_detection_window: Option<Duration>,
+    /// The extension option to include in the transactions, which defaults to
+    /// `ethermint-dynamic-fee` for Ethermint chains and to `none` otherwise.
+    pub tx_extension: Option<TxExtension>,
+    /// The maximum priority price per unit of gas, i.e. the tip paid to the validators,
+    /// set in the `ethermint-dynamic-fee` extension option.
+    #[serde(default)]
+    pub max_priority_price: u64,
+    /// The folder in which to dump the transactions which fail at the `check_tx`
+    /// or `deliver_tx` step, together with the response of the node.
+    /// A relative folder is resolved against the Hermes home folder.
+    /// No dump is made when this is not set.
+    pub failed_tx_dir: Option<PathBuf>,
+    /// The length of the memo of the ICS-20 transfers sent with `tx ft-transfer` above
+    /// which a warning is logged, as some chains reject the transfers with a longer memo.
+    #[serde(default = "default::max_transfer_memo_len")]
+    pub max_transfer_memo_len: usize,
+    /// Whether to query the bank metadata of the denominations of the chain, to also
+    /// display the amounts of coins in the display unit of their denomination.
+    #[serde(default = "default::resolve_denoms")]
+    pub resolve_denoms: bool,
 
     // these two need to be last otherwise we run into `ValueAfterTable` error when serializing to TOML
     /// The trust threshold defines what fraction of the total voting power of a known
@@ -371,6 +536,15 @@ pub struct ChainConfig {
     pub packet_filter: PacketFilter,
     #[serde(default)]
     pub address_type: AddressType,
+    #[serde(default, skip_serializing_if = "ChannelVersionPolicies::is_empty")]
+    pub channel_versions: ChannelVersionPolicies,
+    /// Overrides of the built-in classification of the errors of the transactions
+    /// which relay packets, deciding whether their messages are resubmitted.
+    #[serde(default, skip_serializing_if = "TxErrorPolicies::is_empty")]
+    pub tx_errors: TxErrorPolicies,
+    /// The TLS settings of the connections to the gRPC endpoint of the chain.
+    #[serde(default, skip_serializing_if = "TlsConfig::is_default")]
+    pub tls: TlsConfig,
 }
 
 /// Attempt to load and parse the TOML config file as a `Config`.
@@ -405,7 +579,7 @@ pub(crate) fn store_writer(config: &Config, mut writer: impl Write) -> Result<()
 
 #[cfg(test)]
 mod tests {
-    use super::{load, store_writer};
+    use super::{load, store_writer, TelemetryConfig, TelemetryLabel, TelemetryLabelsPreset};
     use test_log::test;
 
     #[test]
@@ -432,4 +606,51 @@ mod tests {
         let mut buffer = Vec::new();
         store_writer(&config, &mut buffer).unwrap();
     }
+
+    #[test]
+    fn parse_telemetry_labels() {
+        let config: TelemetryConfig = toml::from_str(
+            r#"
+                enabled = true
+                host = '127.0.0.1'
+                port = 3001
+
+                [labels]
+                preset = 'per-chain'
+                drop = ['counterparty', 'relay_mode']
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.labels.preset, TelemetryLabelsPreset::PerChain);
+        assert_eq!(
+            config.labels.drop,
+            [TelemetryLabel::Counterparty, TelemetryLabel::RelayMode]
+        );
+
+        let config: TelemetryConfig = toml::from_str(
+            r#"
+                enabled = true
+                host = '127.0.0.1'
+                port = 3001
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.labels.preset, TelemetryLabelsPreset::Full);
+        assert!(config.labels.drop.is_empty());
+
+        let unknown = toml::from_str::<TelemetryConfig>(
+            r#"
+                enabled = true
+                host = '127.0.0.1'
+                port = 3001
+
+                [labels]
+                drop = ['src_channel']
+            "#,
+        );
+
+        assert!(unknown.is_err());
+    }
 }
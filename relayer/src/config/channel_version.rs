@@ -0,0 +1,70 @@
+//! Per-port channel version negotiation policies.
+//!
+//! Custom IBC applications often need the relayer to propose a specific
+//! version string during the channel open handshake, and to only accept
+//! counterparty versions that the application knows how to handle.
+//! These policies are configured per chain, keyed by a port identifier
+//! pattern, e.g.:
+//!
+//! ```toml
+//! [[chains.channel_versions]]
+//! port = 'icacontroller-*'
+//! version = 'ics27-1'
+//! accepted = ['ics27-1', 'ics27-1-*']
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use ibc::core::ics04_channel::Version;
+use ibc::core::ics24_host::identifier::PortId;
+
+use crate::config::filter::{PortFilterMatch, Wildcard};
+
+/// The version negotiation policy for the ports matching a given pattern.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChannelVersionPolicy {
+    /// The port identifier, or wildcard pattern, this policy applies to.
+    pub port: PortFilterMatch,
+
+    /// The version to propose when initiating a handshake on a matching port,
+    /// or when the counterparty did not propose any version.
+    pub version: Version,
+
+    /// The counterparty versions that are acceptable on a matching port.
+    /// If empty, only `version` itself is accepted.
+    #[serde(default)]
+    pub accepted: Vec<Wildcard>,
+}
+
+impl ChannelVersionPolicy {
+    /// Whether the given version is acceptable under this policy.
+    pub fn accepts(&self, version: &Version) -> bool {
+        if self.accepted.is_empty() {
+            return version == &self.version;
+        }
+
+        let version = version.to_string();
+        self.accepted.iter().any(|pattern| pattern.is_match(&version))
+    }
+}
+
+/// The list of [`ChannelVersionPolicy`] configured for a chain.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ChannelVersionPolicies(Vec<ChannelVersionPolicy>);
+
+impl ChannelVersionPolicies {
+    pub fn new(policies: Vec<ChannelVersionPolicy>) -> Self {
+        Self(policies)
+    }
+
+    /// Returns the first policy whose port pattern matches the given [`PortId`], if any.
+    pub fn for_port(&self, port_id: &PortId) -> Option<&ChannelVersionPolicy> {
+        self.0.iter().find(|policy| policy.port.matches(port_id))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
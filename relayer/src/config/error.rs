@@ -13,5 +13,8 @@ define_error! {
         Encode
             [ TraceError<toml::ser::Error> ]
             |_| { "invalid configuration" },
+
+        ChainWithoutId
+            |_| { "every chain of a configuration file must have an `id`" },
     }
 }
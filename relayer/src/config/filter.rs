@@ -41,16 +41,155 @@ impl PacketFilter {
             PacketFilter::AllowAll => true,
         }
     }
+
+    /// Returns the kinds of packet messages to relay on the channel with [`PortId`]
+    /// and [`ChannelId`], as set by the first entry of the allow list matching the channel.
+    /// All messages are relayed on channels which are not in an allow list.
+    pub fn relay_mode(&self, port_id: &PortId, channel_id: &ChannelId) -> RelayMode {
+        match self {
+            PacketFilter::Allow(filters) => filters
+                .relay_mode((port_id, channel_id))
+                .unwrap_or_default(),
+            PacketFilter::Deny(_) | PacketFilter::AllowAll => RelayMode::All,
+        }
+    }
+}
+
+/// The kinds of packet messages relayed on a channel.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RelayMode {
+    /// Relay the packets sent on the channel as well as their acknowledgements and timeouts.
+    All,
+    /// Only relay the acknowledgements and timeouts of the packets, leaving the
+    /// packets sent on the channel to be received by another relayer.
+    AcksOnly,
+    /// Only relay the packets sent on the channel and their timeouts, leaving
+    /// their acknowledgements to another relayer.
+    RecvOnly,
+}
+
+impl Default for RelayMode {
+    fn default() -> Self {
+        Self::All
+    }
+}
+
+impl RelayMode {
+    /// Whether the packets sent on the channel are relayed to the counterparty chain.
+    pub fn relays_recv(&self) -> bool {
+        !matches!(self, RelayMode::AcksOnly)
+    }
+
+    /// Whether the acknowledgements written on the channel are relayed to the counterparty chain.
+    pub fn relays_acks(&self) -> bool {
+        !matches!(self, RelayMode::RecvOnly)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RelayMode::All => "all",
+            RelayMode::AcksOnly => "acks-only",
+            RelayMode::RecvOnly => "recv-only",
+        }
+    }
+}
+
+impl fmt::Display for RelayMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// An entry of a [`ChannelFilters`] list, matching channels by their port and channel
+/// identifiers, along with the kinds of packet messages to relay on these channels.
+///
+/// It is written either as a `[port, channel]` pair, relaying all messages, or as a
+/// `{ port = .., channel = .., relay = .. }` table. When serializing a list in which
+/// some entries do not relay all messages, all the entries are written as tables,
+/// as TOML serializers do not support arrays mixing arrays and tables.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChannelFilter {
+    pub port: PortFilterMatch,
+    pub channel: ChannelFilterMatch,
+    pub relay: RelayMode,
+}
+
+impl ChannelFilter {
+    pub fn new(port: PortFilterMatch, channel: ChannelFilterMatch) -> Self {
+        Self {
+            port,
+            channel,
+            relay: RelayMode::All,
+        }
+    }
+
+    pub fn with_relay_mode(self, relay: RelayMode) -> Self {
+        Self { relay, ..self }
+    }
+
+    /// Indicates whether this filter matches the given [`PortId`]-[`ChannelId`] pair.
+    pub fn matches(&self, port_id: &PortId, channel_id: &ChannelId) -> bool {
+        self.port.matches(port_id) && self.channel.matches(channel_id)
+    }
+}
+
+impl fmt::Display for ChannelFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.port, self.channel)?;
+
+        if self.relay != RelayMode::All {
+            write!(f, " ({})", self.relay)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'de> Deserialize<'de> for ChannelFilter {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged, deny_unknown_fields)]
+        enum Entry {
+            Pair(PortFilterMatch, ChannelFilterMatch),
+            Table {
+                port: PortFilterMatch,
+                channel: ChannelFilterMatch,
+                #[serde(default)]
+                relay: RelayMode,
+            },
+        }
+
+        match Entry::deserialize(deserializer)? {
+            Entry::Pair(port, channel) => Ok(Self::new(port, channel)),
+            Entry::Table {
+                port,
+                channel,
+                relay,
+            } => Ok(Self::new(port, channel).with_relay_mode(relay)),
+        }
+    }
 }
 
 /// The internal representation of channel filter policies.
 #[derive(Clone, Debug, Default, Deserialize)]
 #[serde(deny_unknown_fields)]
-pub struct ChannelFilters(Vec<(PortFilterMatch, ChannelFilterMatch)>);
+pub struct ChannelFilters(Vec<ChannelFilter>);
 
 impl ChannelFilters {
-    /// Create a new filter from the given list of port/channel filters.
+    /// Create a new filter from the given list of port/channel filters,
+    /// relaying all the packet messages on the matching channels.
     pub fn new(filters: Vec<(PortFilterMatch, ChannelFilterMatch)>) -> Self {
+        Self(
+            filters
+                .into_iter()
+                .map(|(port, channel)| ChannelFilter::new(port, channel))
+                .collect(),
+        )
+    }
+
+    /// Create a new filter from the given list of channel filters.
+    pub fn from_filters(filters: Vec<ChannelFilter>) -> Self {
         Self(filters)
     }
 
@@ -58,24 +197,39 @@ impl ChannelFilters {
     /// exists in the filter policy.
     pub fn matches(&self, channel_port: (&PortId, &ChannelId)) -> bool {
         let (port_id, channel_id) = channel_port;
-        self.0.iter().any(|(port_filter, chan_filter)| {
-            port_filter.matches(port_id) && chan_filter.matches(channel_id)
-        })
+        self.0
+            .iter()
+            .any(|filter| filter.matches(port_id, channel_id))
+    }
+
+    /// Returns the relay mode of the first filter matching the given
+    /// [`PortId`]-[`ChannelId`] pair, if any.
+    pub fn relay_mode(&self, channel_port: (&PortId, &ChannelId)) -> Option<RelayMode> {
+        let (port_id, channel_id) = channel_port;
+        self.0
+            .iter()
+            .find(|filter| filter.matches(port_id, channel_id))
+            .map(|filter| filter.relay)
     }
 
     /// Indicates whether this filter policy contains only exact patterns.
     #[inline]
     pub fn is_exact(&self) -> bool {
-        self.0.iter().all(|(port_filter, channel_filter)| {
-            port_filter.is_exact() && channel_filter.is_exact()
-        })
+        self.0
+            .iter()
+            .all(|filter| filter.port.is_exact() && filter.channel.is_exact())
+    }
+
+    /// An iterator over the port/channel filters of this filter policy.
+    pub fn iter(&self) -> impl Iterator<Item = &ChannelFilter> {
+        self.0.iter()
     }
 
     /// An iterator over the [`PortId`]-[`ChannelId`] pairs that don't contain wildcards.
     pub fn iter_exact(&self) -> impl Iterator<Item = (&PortId, &ChannelId)> {
-        self.0.iter().filter_map(|port_chan_filter| {
-            if let &(FilterPattern::Exact(ref port_id), FilterPattern::Exact(ref chan_id)) =
-                port_chan_filter
+        self.0.iter().filter_map(|filter| {
+            if let (FilterPattern::Exact(ref port_id), FilterPattern::Exact(ref chan_id)) =
+                (&filter.port, &filter.channel)
             {
                 Some((port_id, chan_id))
             } else {
@@ -87,14 +241,7 @@ impl ChannelFilters {
 
 impl fmt::Display for ChannelFilters {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            self.0
-                .iter()
-                .map(|(pid, cid)| format!("{}/{}", pid, cid))
-                .join(", ")
-        )
+        write!(f, "{}", self.0.iter().join(", "))
     }
 }
 
@@ -103,12 +250,9 @@ impl Serialize for ChannelFilters {
     where
         S: Serializer,
     {
-        use serde::ser::SerializeSeq;
+        use serde::ser::{SerializeSeq, SerializeStruct};
 
-        struct Pair<'a> {
-            a: &'a FilterPattern<PortId>,
-            b: &'a FilterPattern<ChannelId>,
-        }
+        struct Pair<'a>(&'a ChannelFilter);
 
         impl<'a> Serialize for Pair<'a> {
             fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -116,19 +260,37 @@ impl Serialize for ChannelFilters {
                 S: Serializer,
             {
                 let mut seq = serializer.serialize_seq(Some(2))?;
-                seq.serialize_element(self.a)?;
-                seq.serialize_element(self.b)?;
+                seq.serialize_element(&self.0.port)?;
+                seq.serialize_element(&self.0.channel)?;
                 seq.end()
             }
         }
 
+        struct Table<'a>(&'a ChannelFilter);
+
+        impl<'a> Serialize for Table<'a> {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                let mut table = serializer.serialize_struct("ChannelFilter", 3)?;
+                table.serialize_field("port", &self.0.port)?;
+                table.serialize_field("channel", &self.0.channel)?;
+                table.serialize_field("relay", &self.0.relay)?;
+                table.end()
+            }
+        }
+
+        let as_tables = self.0.iter().any(|filter| filter.relay != RelayMode::All);
+
         let mut outer_seq = serializer.serialize_seq(Some(self.0.len()))?;
 
-        for (port, channel) in &self.0 {
-            outer_seq.serialize_element(&Pair {
-                a: port,
-                b: channel,
-            })?;
+        for filter in &self.0 {
+            if as_tables {
+                outer_seq.serialize_element(&Table(filter))?;
+            } else {
+                outer_seq.serialize_element(&Pair(filter))?;
+            }
         }
 
         outer_seq.end()
@@ -178,6 +340,13 @@ impl Serialize for Wildcard {
     }
 }
 
+impl<'de> Deserialize<'de> for Wildcard {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        Self::new(pattern).map_err(de::Error::custom)
+    }
+}
+
 impl PartialEq for Wildcard {
     fn eq(&self, other: &Self) -> bool {
         self.pattern == other.pattern
@@ -350,7 +519,7 @@ mod tests {
 
         use ibc::core::ics24_host::identifier::{ChannelId, PortId};
 
-        let filter_policy = ChannelFilters(vec![
+        let filter_policy = ChannelFilters::new(vec![
             (
                 FilterPattern::Exact(PortId::from_str("transfer").unwrap()),
                 FilterPattern::Exact(ChannelId::from_str("channel-0").unwrap()),
@@ -498,4 +667,90 @@ mod tests {
         let wildcard = "ica*".parse::<Wildcard>().unwrap();
         assert_eq!(wildcard.to_string(), "ica*".to_string());
     }
+
+    #[test]
+    fn packet_filter_relay_modes() {
+        let allow_policy = r#"
+            policy = 'allow'
+            list = [
+              ['transfer', 'channel-0'],
+              { port = 'transfer', channel = 'channel-1', relay = 'acks-only' },
+              { port = 'ica*', channel = '*', relay = 'recv-only' },
+              { port = 'ft-transfer', channel = 'channel-2' },
+            ]
+            "#;
+
+        let pf: PacketFilter = toml::from_str(allow_policy).expect("could not parse filter policy");
+
+        let mode = |port: &str, channel: &str| {
+            pf.relay_mode(
+                &PortId::from_str(port).unwrap(),
+                &ChannelId::from_str(channel).unwrap(),
+            )
+        };
+
+        assert_eq!(mode("transfer", "channel-0"), RelayMode::All);
+        assert_eq!(mode("transfer", "channel-1"), RelayMode::AcksOnly);
+        assert_eq!(mode("icahost", "channel-5"), RelayMode::RecvOnly);
+        assert_eq!(mode("ft-transfer", "channel-2"), RelayMode::All);
+        assert!(pf.is_allowed(
+            &PortId::from_str("transfer").unwrap(),
+            &ChannelId::from_str("channel-1").unwrap()
+        ));
+    }
+
+    #[test]
+    fn packet_filter_relay_mode_roundtrip() {
+        let filter = PacketFilter::Allow(ChannelFilters::from_filters(vec![
+            ChannelFilter::new(
+                FilterPattern::Exact(PortId::transfer()),
+                FilterPattern::Exact(ChannelId::new(0)),
+            ),
+            ChannelFilter::new(
+                FilterPattern::Exact(PortId::transfer()),
+                FilterPattern::Exact(ChannelId::new(1)),
+            )
+            .with_relay_mode(RelayMode::AcksOnly),
+        ]));
+
+        let toml_str = toml::to_string(&filter).expect("could not serialize packet filter");
+        let parsed: PacketFilter = toml::from_str(&toml_str).expect("could not parse filter");
+
+        match parsed {
+            PacketFilter::Allow(filters) => assert_eq!(
+                filters.iter().map(|f| f.to_string()).collect::<Vec<_>>(),
+                vec!["transfer/channel-0", "transfer/channel-1 (acks-only)"]
+            ),
+            _ => panic!("expected `PacketFilter::Allow` variant"),
+        }
+    }
+
+    #[test]
+    fn packet_filter_unknown_relay_mode() {
+        let allow_policy = r#"
+            policy = 'allow'
+            list = [
+              { port = 'transfer', channel = 'channel-1', relay = 'acks' },
+            ]
+            "#;
+
+        assert!(toml::from_str::<PacketFilter>(allow_policy).is_err());
+    }
+
+    #[test]
+    fn deny_list_relays_all() {
+        let deny_policy = r#"
+            policy = 'deny'
+            list = [
+              { port = 'transfer', channel = 'channel-1', relay = 'acks-only' },
+            ]
+            "#;
+
+        let pf: PacketFilter = toml::from_str(deny_policy).expect("could not parse filter policy");
+
+        assert_eq!(
+            pf.relay_mode(&PortId::transfer(), &ChannelId::new(2)),
+            RelayMode::All
+        );
+    }
 }
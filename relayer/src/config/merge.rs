@@ -0,0 +1,435 @@
+//! Merging of several configuration files into a single configuration,
+//! where the files given later override the ones given earlier.
+//!
+//! The files are merged as TOML documents, before being parsed as a [`Config`],
+//! so that a file does not need to be a valid configuration on its own, e.g. an
+//! override file may only set `mode.packets.clear_interval` or the `gas_price`
+//! of a single chain.
+//!
+//! The merge rules are the following:
+//! - Tables, e.g. `[global]` or `[mode.packets]`, are merged field by field.
+//! - The chains of the `chains` array are matched by their `id`: a chain which
+//!   is already configured is merged field by field, a new chain is appended.
+//! - The `packet_filter` and the `address_type` of a chain are replaced as a whole,
+//!   as their fields only make sense together.
+//! - Any other value, including arrays, replaces the previous one.
+
+use std::path::Path;
+
+use toml::value::Table;
+use toml::Value;
+
+use crate::config::{Config, Error};
+
+/// The fields of a chain which are replaced as a whole instead of being merged.
+const REPLACED_CHAIN_FIELDS: &[&str] = &["packet_filter", "address_type"];
+
+/// Load the configuration files at the given paths and merge them, in order, into a single `Config`.
+pub fn load_merged<P: AsRef<Path>>(paths: &[P]) -> Result<Config, Error> {
+    let mut merged = Value::Table(Table::new());
+
+    for path in paths {
+        let config_toml = std::fs::read_to_string(path).map_err(Error::io)?;
+        let overrides = toml::from_str::<Value>(&config_toml).map_err(Error::decode)?;
+
+        merge(&mut merged, overrides)?;
+    }
+
+    merged.try_into().map_err(Error::decode)
+}
+
+/// Merge the `overrides` configuration document into the `base` one.
+pub fn merge(base: &mut Value, overrides: Value) -> Result<(), Error> {
+    match (base, overrides) {
+        (Value::Table(base), Value::Table(overrides)) => {
+            for (key, value) in overrides {
+                match (base.get_mut(&key), value) {
+                    (Some(Value::Array(chains)), Value::Array(overrides)) if key == "chains" => {
+                        merge_chains(chains, overrides)?
+                    }
+                    (Some(base_value), value) => merge_fields(base_value, value),
+                    (None, value) => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+
+            Ok(())
+        }
+        (base, overrides) => {
+            *base = overrides;
+            Ok(())
+        }
+    }
+}
+
+fn merge_chains(base: &mut Vec<Value>, overrides: Vec<Value>) -> Result<(), Error> {
+    for chain in overrides {
+        let chain_id = chain
+            .get("id")
+            .and_then(Value::as_str)
+            .ok_or_else(Error::chain_without_id)?;
+
+        let existing = base
+            .iter_mut()
+            .find(|existing| existing.get("id").and_then(Value::as_str) == Some(chain_id));
+
+        match existing {
+            Some(existing) => merge_chain(existing, chain),
+            None => base.push(chain),
+        }
+    }
+
+    Ok(())
+}
+
+fn merge_chain(base: &mut Value, overrides: Value) {
+    match (base, overrides) {
+        (Value::Table(base), Value::Table(overrides)) => {
+            for (key, value) in overrides {
+                match base.get_mut(&key) {
+                    Some(base_value) if !REPLACED_CHAIN_FIELDS.contains(&key.as_str()) => {
+                        merge_fields(base_value, value)
+                    }
+                    _ => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overrides) => *base = overrides,
+    }
+}
+
+fn merge_fields(base: &mut Value, overrides: Value) {
+    match (base, overrides) {
+        (Value::Table(base), Value::Table(overrides)) => {
+            for (key, value) in overrides {
+                match base.get_mut(&key) {
+                    Some(base_value) => merge_fields(base_value, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overrides) => *base = overrides,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+    use std::path::PathBuf;
+
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+
+    use crate::config::filter::PacketFilter;
+
+    const BASE: &str = r#"
+        [global]
+        log_level = 'info'
+
+        [mode.clients]
+        enabled = true
+        refresh = true
+        misbehaviour = true
+
+        [mode.connections]
+        enabled = false
+
+        [mode.channels]
+        enabled = false
+
+        [mode.packets]
+        enabled = true
+        clear_interval = 100
+        clear_on_start = true
+
+        [[chains]]
+        id = 'ibc-0'
+        rpc_addr = 'http://127.0.0.1:26657'
+        grpc_addr = 'http://127.0.0.1:9090'
+        websocket_addr = 'ws://127.0.0.1:26657/websocket'
+        account_prefix = 'cosmos'
+        key_name = 'testkey'
+        store_prefix = 'ibc'
+        gas_price = { price = 0.001, denom = 'stake' }
+        trusting_period = '14days'
+
+        [chains.packet_filter]
+        policy = 'allow'
+        list = [
+          ['transfer', 'channel-0'],
+          ['transfer', 'channel-1'],
+        ]
+
+        [[chains]]
+        id = 'ibc-1'
+        rpc_addr = 'http://127.0.0.1:26557'
+        grpc_addr = 'http://127.0.0.1:9091'
+        websocket_addr = 'ws://127.0.0.1:26557/websocket'
+        account_prefix = 'cosmos'
+        key_name = 'testkey'
+        store_prefix = 'ibc'
+        gas_price = { price = 0.001, denom = 'stake' }
+        trusting_period = '14days'
+    "#;
+
+    fn parse(toml: &str) -> Value {
+        toml::from_str(toml).unwrap()
+    }
+
+    fn merged(documents: &[&str]) -> Value {
+        let mut merged = Value::Table(Table::new());
+
+        for document in documents {
+            merge(&mut merged, parse(document)).unwrap();
+        }
+
+        merged
+    }
+
+    fn write_file(name: &str, content: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "hermes-config-merge-{}-{}.toml",
+            name,
+            std::process::id()
+        ));
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn chain<'a>(config: &'a Value, id: &str) -> &'a Value {
+        config["chains"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|chain| chain["id"].as_str() == Some(id))
+            .unwrap()
+    }
+
+    #[test]
+    fn override_chain_fields() {
+        let config = merged(&[
+            BASE,
+            r#"
+                [[chains]]
+                id = 'ibc-1'
+                key_name = 'other-key'
+                gas_price = { price = 0.01 }
+            "#,
+        ]);
+
+        assert_eq!(config["chains"].as_array().unwrap().len(), 2);
+
+        let ibc_1 = chain(&config, "ibc-1");
+        assert_eq!(ibc_1["key_name"].as_str(), Some("other-key"));
+        assert_eq!(ibc_1["gas_price"]["price"].as_float(), Some(0.01));
+        assert_eq!(ibc_1["gas_price"]["denom"].as_str(), Some("stake"));
+        assert_eq!(ibc_1["rpc_addr"].as_str(), Some("http://127.0.0.1:26557"));
+
+        let ibc_0 = chain(&config, "ibc-0");
+        assert_eq!(ibc_0["key_name"].as_str(), Some("testkey"));
+    }
+
+    #[test]
+    fn append_new_chain() {
+        let config = merged(&[
+            BASE,
+            r#"
+                [[chains]]
+                id = 'ibc-2'
+                key_name = 'testkey'
+            "#,
+        ]);
+
+        let ids: Vec<_> = config["chains"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|chain| chain["id"].as_str().unwrap())
+            .collect();
+
+        assert_eq!(ids, ["ibc-0", "ibc-1", "ibc-2"]);
+    }
+
+    #[test]
+    fn merge_global_sections_field_wise() {
+        let config = merged(&[
+            BASE,
+            r#"
+                [global]
+                log_level = 'debug'
+
+                [mode.packets]
+                clear_interval = 0
+
+                [telemetry]
+                enabled = true
+            "#,
+        ]);
+
+        assert_eq!(config["global"]["log_level"].as_str(), Some("debug"));
+        assert_eq!(
+            config["mode"]["packets"]["clear_interval"].as_integer(),
+            Some(0)
+        );
+        assert_eq!(config["mode"]["packets"]["enabled"].as_bool(), Some(true));
+        assert_eq!(
+            config["mode"]["packets"]["clear_on_start"].as_bool(),
+            Some(true)
+        );
+        assert_eq!(config["telemetry"]["enabled"].as_bool(), Some(true));
+        assert_eq!(config["chains"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn replace_packet_filter() {
+        let config = merged(&[
+            BASE,
+            r#"
+                [[chains]]
+                id = 'ibc-0'
+
+                [chains.packet_filter]
+                policy = 'deny'
+                list = [
+                  ['ica*', '*'],
+                ]
+            "#,
+        ]);
+
+        let filter = &chain(&config, "ibc-0")["packet_filter"];
+        assert_eq!(filter["policy"].as_str(), Some("deny"));
+        assert_eq!(filter["list"].as_array().unwrap().len(), 1);
+        assert_eq!(filter["list"][0][0].as_str(), Some("ica*"));
+    }
+
+    #[test]
+    fn replace_arrays() {
+        let config = merged(&[
+            r#"
+                [[chains]]
+                id = 'ibc-0'
+                channel_versions = [
+                  { port = 'transfer', accepted = ['ics20-1'] },
+                  { port = 'icahost', accepted = ['*'] },
+                ]
+            "#,
+            r#"
+                [[chains]]
+                id = 'ibc-0'
+                channel_versions = [
+                  { port = 'transfer', accepted = ['ics20-2'] },
+                ]
+            "#,
+        ]);
+
+        let versions = chain(&config, "ibc-0")["channel_versions"]
+            .as_array()
+            .unwrap();
+
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0]["accepted"][0].as_str(), Some("ics20-2"));
+    }
+
+    #[test]
+    fn later_files_win() {
+        let config = merged(&[
+            BASE,
+            r#"
+                [global]
+                log_level = 'debug'
+
+                [[chains]]
+                id = 'ibc-0'
+                key_name = 'second'
+            "#,
+            r#"
+                [global]
+                log_level = 'trace'
+
+                [[chains]]
+                id = 'ibc-0'
+                key_name = 'third'
+            "#,
+        ]);
+
+        assert_eq!(config["global"]["log_level"].as_str(), Some("trace"));
+        assert_eq!(chain(&config, "ibc-0")["key_name"].as_str(), Some("third"));
+    }
+
+    #[test]
+    fn single_document_is_unchanged() {
+        assert_eq!(merged(&[BASE]), parse(BASE));
+    }
+
+    #[test]
+    fn chain_without_id() {
+        let mut config = parse(BASE);
+
+        let result = merge(
+            &mut config,
+            parse(
+                r#"
+                    [[chains]]
+                    key_name = 'other-key'
+                "#,
+            ),
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn load_merged_files() {
+        let base = write_file("base", BASE);
+        let overrides = write_file(
+            "overrides",
+            r#"
+                [mode.packets]
+                clear_interval = 0
+
+                [[chains]]
+                id = 'ibc-0'
+
+                [chains.packet_filter]
+                policy = 'allow'
+                list = [
+                  ['transfer', 'channel-7'],
+                ]
+            "#,
+        );
+
+        let config = load_merged(&[&base, &overrides]).unwrap();
+        let _ = fs::remove_file(base);
+        let _ = fs::remove_file(overrides);
+
+        assert_eq!(config.mode.packets.clear_interval, 0);
+        assert!(config.mode.packets.clear_on_start);
+        assert_eq!(config.chains.len(), 2);
+
+        let ibc_0 = config.find_chain(&ChainId::from_string("ibc-0")).unwrap();
+        assert_eq!(ibc_0.key_name, "testkey");
+        assert!(matches!(ibc_0.packet_filter, PacketFilter::Allow(_)));
+
+        let transfer = PortId::transfer();
+        assert!(ibc_0
+            .packet_filter
+            .is_allowed(&transfer, &ChannelId::new(7)));
+        assert!(!ibc_0
+            .packet_filter
+            .is_allowed(&transfer, &ChannelId::new(0)));
+    }
+
+    #[test]
+    fn load_merged_invalid_result() {
+        let path = write_file("invalid", "[[chains]]\nid = 'ibc-0'\n");
+
+        assert!(load_merged(&[&path]).is_err());
+        let _ = fs::remove_file(path);
+    }
+}
@@ -0,0 +1,38 @@
+//! Per-chain TLS settings of the connections to the gRPC endpoint of a chain,
+//! e.g. for an endpoint behind a proxy whose certificate is issued by an internal CA:
+//!
+//! ```toml
+//! [chains.tls]
+//! ca_file = 'certs/internal-ca.pem'
+//! server_name = 'grpc.internal'
+//! ```
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// The TLS settings of the connections to the gRPC endpoint of a chain,
+/// only used for the `https` gRPC addresses.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TlsConfig {
+    /// A PEM file with the certificates of the CAs to trust, in addition to the
+    /// CAs trusted by the system. A relative path is resolved against the Hermes
+    /// home folder.
+    pub ca_file: Option<PathBuf>,
+
+    /// Accept any certificate, without verifying it, which lets anyone between the
+    /// relayer and the endpoint tamper with the responses. Only meant for testing.
+    #[serde(default)]
+    pub skip_verify: bool,
+
+    /// The name of the server sent in the TLS handshake (SNI) and against which
+    /// its certificate is verified, instead of the host of the gRPC address.
+    pub server_name: Option<String>,
+}
+
+impl TlsConfig {
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
@@ -0,0 +1,210 @@
+//! Per-chain overrides of the classification of the errors returned by the
+//! transactions which relay packets.
+//!
+//! A failed transaction reports the codespace and code of its ABCI error, which
+//! determine whether relaying its messages again may succeed. The built-in
+//! classification can be overridden per chain, e.g.:
+//!
+//! ```toml
+//! [[chains.tx_errors]]
+//! codespace = 'sdk'
+//! code = 5
+//! action = 'retry'
+//! ```
+
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// What to do with the messages of a transaction which failed with a given error.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TxErrorAction {
+    /// Resubmit the messages, after regenerating them.
+    Retry,
+    /// Drop the message which failed, or the messages if the transaction failed as a whole,
+    /// as relaying them again would fail the same way, which is expected, e.g. because
+    /// another relayer already did it.
+    DropSilently,
+    /// Drop the message which failed, as relaying it again would fail the same way, and warn
+    /// about it, as it is unexpected. If the transaction failed as a whole, e.g. for lack of
+    /// funds, its messages are kept queued until the error is resolved.
+    DropAndAlert,
+}
+
+impl TxErrorAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Retry => "retry",
+            Self::DropSilently => "drop-silently",
+            Self::DropAndAlert => "drop-and-alert",
+        }
+    }
+}
+
+impl fmt::Display for TxErrorAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// The action to take for the transactions failing with a given ABCI error.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TxErrorPolicy {
+    /// The codespace of the error, e.g. `sdk` or `channel`.
+    pub codespace: String,
+
+    /// The code of the error within its codespace.
+    pub code: u32,
+
+    pub action: TxErrorAction,
+}
+
+/// The list of [`TxErrorPolicy`] configured for a chain.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TxErrorPolicies(Vec<TxErrorPolicy>);
+
+impl TxErrorPolicies {
+    pub fn new(policies: Vec<TxErrorPolicy>) -> Self {
+        Self(policies)
+    }
+
+    /// Returns the action of the last policy configured for the given error, if any.
+    pub fn action_for(&self, codespace: &str, code: u32) -> Option<TxErrorAction> {
+        self.0
+            .iter()
+            .rev()
+            .find(|policy| policy.codespace == codespace && policy.code == code)
+            .map(|policy| policy.action)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Chain {
+        #[serde(default)]
+        tx_errors: TxErrorPolicies,
+    }
+
+    fn parse(toml: &str) -> Result<TxErrorPolicies, toml::de::Error> {
+        toml::from_str::<Chain>(toml).map(|chain| chain.tx_errors)
+    }
+
+    #[test]
+    fn parse_tx_error_policies() {
+        let policies = parse(
+            r#"
+                [[tx_errors]]
+                codespace = 'channel'
+                code = 19
+                action = 'drop-and-alert'
+
+                [[tx_errors]]
+                codespace = 'sdk'
+                code = 5
+                action = 'retry'
+
+                [[tx_errors]]
+                codespace = 'wasm'
+                code = 42
+                action = 'drop-silently'
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            policies.action_for("channel", 19),
+            Some(TxErrorAction::DropAndAlert)
+        );
+        assert_eq!(policies.action_for("sdk", 5), Some(TxErrorAction::Retry));
+        assert_eq!(
+            policies.action_for("wasm", 42),
+            Some(TxErrorAction::DropSilently)
+        );
+        assert_eq!(policies.action_for("channel", 5), None);
+        assert_eq!(policies.action_for("sdk", 19), None);
+    }
+
+    #[test]
+    fn parse_empty_tx_error_policies() {
+        assert!(parse("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_invalid_tx_error_policies() {
+        let unknown_action = parse(
+            r#"
+                [[tx_errors]]
+                codespace = 'channel'
+                code = 19
+                action = 'drop'
+            "#,
+        );
+        assert!(unknown_action.is_err());
+
+        let missing_code = parse(
+            r#"
+                [[tx_errors]]
+                codespace = 'channel'
+                action = 'retry'
+            "#,
+        );
+        assert!(missing_code.is_err());
+
+        let unknown_field = parse(
+            r#"
+                [[tx_errors]]
+                codespace = 'channel'
+                code = 19
+                action = 'retry'
+                log = 'packet already received'
+            "#,
+        );
+        assert!(unknown_field.is_err());
+    }
+
+    #[test]
+    fn last_matching_policy_wins() {
+        let policy = |action| TxErrorPolicy {
+            codespace: "channel".to_string(),
+            code: 19,
+            action,
+        };
+
+        let policies = TxErrorPolicies::new(vec![
+            policy(TxErrorAction::Retry),
+            policy(TxErrorAction::DropSilently),
+        ]);
+
+        assert_eq!(
+            policies.action_for("channel", 19),
+            Some(TxErrorAction::DropSilently)
+        );
+    }
+
+    #[test]
+    fn display_matches_config_names() {
+        for action in [
+            TxErrorAction::Retry,
+            TxErrorAction::DropSilently,
+            TxErrorAction::DropAndAlert,
+        ] {
+            let parsed = parse(&format!(
+                "[[tx_errors]]\ncodespace = 'sdk'\ncode = 1\naction = '{}'",
+                action
+            ))
+            .unwrap();
+
+            assert_eq!(parsed.action_for("sdk", 1), Some(action));
+        }
+    }
+}
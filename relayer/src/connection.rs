@@ -5,6 +5,7 @@ use ibc_proto::google::protobuf::Any;
 use serde::Serialize;
 use tracing::{debug, error, info, warn};
 
+use ibc::core::ics02_client::client_state::{AnyClientState, ClientState};
 use ibc::core::ics02_client::height::Height;
 use ibc::core::ics03_connection::connection::{
     ConnectionEnd, Counterparty, IdentifiedConnectionEnd, State,
@@ -22,11 +23,13 @@ use crate::chain::counterparty::connection_state_on_destination;
 use crate::chain::handle::ChainHandle;
 use crate::chain::requests::{
     IncludeProof, PageRequest, QueryConnectionRequest, QueryConnectionsRequest, QueryHeight,
+    QueryHostConsensusStateRequest,
 };
 use crate::chain::tracking::TrackedMsgs;
-use crate::foreign_client::{ForeignClient, HasExpiredOrFrozenError};
+use crate::foreign_client::{ForeignClient, ForeignClientErrorDetail, HasExpiredOrFrozenError};
 use crate::object::Connection as WorkerConnectionObject;
-use crate::util::retry::{retry_count, retry_with_index, RetryResult};
+use crate::progress::{self, HandshakeProgress};
+use crate::util::retry::{retry_after_client_update, retry_count, retry_with_index, RetryResult};
 use crate::util::task::Next;
 
 mod error;
@@ -149,6 +152,17 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Connection<ChainA, ChainB> {
         b_to_a_client: ForeignClient<ChainA, ChainB>,
         a_to_b_client: ForeignClient<ChainB, ChainA>,
         delay_period: Duration,
+    ) -> Result<Self, ConnectionError> {
+        Self::new_with_progress(b_to_a_client, a_to_b_client, delay_period, &mut |_| {})
+    }
+
+    /// Create a new connection like [`Connection::new`], reporting each completed
+    /// step of the handshake to `progress`.
+    pub fn new_with_progress(
+        b_to_a_client: ForeignClient<ChainA, ChainB>,
+        a_to_b_client: ForeignClient<ChainB, ChainA>,
+        delay_period: Duration,
+        progress: &mut dyn FnMut(HandshakeProgress),
     ) -> Result<Self, ConnectionError> {
         Self::validate_clients(&b_to_a_client, &a_to_b_client)?;
 
@@ -174,7 +188,7 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Connection<ChainA, ChainB> {
             ),
         };
 
-        c.handshake()?;
+        c.handshake_with_progress(progress)?;
 
         Ok(c)
     }
@@ -556,7 +570,10 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Connection<ChainA, ChainB> {
 
     /// Sends a connection open handshake message.
     /// The message sent depends on the chain status of the connection ends.
-    fn do_conn_open_handshake(&mut self) -> Result<(), ConnectionError> {
+    fn do_conn_open_handshake(
+        &mut self,
+        progress: &mut dyn FnMut(HandshakeProgress),
+    ) -> Result<(), ConnectionError> {
         let (a_state, b_state) = self.update_connection_and_query_states()?;
         debug!(
             "do_conn_open_handshake with connection end states: {}, {}",
@@ -572,60 +589,81 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Connection<ChainA, ChainB> {
                 })?;
                 let connection_id = extract_connection_id(&event)?;
                 self.a_side.connection_id = Some(connection_id.clone());
+                progress::report(progress, self.a_chain().id(), &event);
             }
 
             // send the Try message to chain a (source)
             (State::Uninitialized, State::Init) | (State::Init, State::Init) => {
-                let event = self.flipped().build_conn_try_and_send().map_err(|e| {
-                    error!("failed ConnOpenTry {:?}: {:?}", self.a_side, e);
-                    e
-                })?;
+                let event = self
+                    .flipped()
+                    .with_dst_client_update(Connection::build_conn_try_and_send)
+                    .map_err(|e| {
+                        error!("failed ConnOpenTry {:?}: {:?}", self.a_side, e);
+                        e
+                    })?;
 
                 let connection_id = extract_connection_id(&event)?;
                 self.a_side.connection_id = Some(connection_id.clone());
+                progress::report(progress, self.a_chain().id(), &event);
             }
 
             // send the Try message to chain b (destination)
             (State::Init, State::Uninitialized) => {
-                let event = self.build_conn_try_and_send().map_err(|e| {
-                    error!("failed ConnOpenTry {:?}: {:?}", self.b_side, e);
-                    e
-                })?;
+                let event = self
+                    .with_dst_client_update(Self::build_conn_try_and_send)
+                    .map_err(|e| {
+                        error!("failed ConnOpenTry {:?}: {:?}", self.b_side, e);
+                        e
+                    })?;
 
                 let connection_id = extract_connection_id(&event)?;
                 self.b_side.connection_id = Some(connection_id.clone());
+                progress::report(progress, self.b_chain().id(), &event);
             }
 
             // send the Ack message to chain a (source)
             (State::Init, State::TryOpen) | (State::TryOpen, State::TryOpen) => {
-                self.flipped().build_conn_ack_and_send().map_err(|e| {
-                    error!("failed ConnOpenAck {:?}: {:?}", self.a_side, e);
-                    e
-                })?;
+                let event = self
+                    .flipped()
+                    .with_dst_client_update(Connection::build_conn_ack_and_send)
+                    .map_err(|e| {
+                        error!("failed ConnOpenAck {:?}: {:?}", self.a_side, e);
+                        e
+                    })?;
+
+                progress::report(progress, self.a_chain().id(), &event);
             }
 
             // send the Ack message to chain b (destination)
             (State::TryOpen, State::Init) => {
-                self.build_conn_ack_and_send().map_err(|e| {
-                    error!("failed ConnOpenAck {:?}: {:?}", self.b_side, e);
-                    e
-                })?;
+                let event = self
+                    .with_dst_client_update(Self::build_conn_ack_and_send)
+                    .map_err(|e| {
+                        error!("failed ConnOpenAck {:?}: {:?}", self.b_side, e);
+                        e
+                    })?;
+
+                progress::report(progress, self.b_chain().id(), &event);
             }
 
             // send the Confirm message to chain b (destination)
             (State::Open, State::TryOpen) => {
-                self.build_conn_confirm_and_send().map_err(|e| {
+                let event = self.build_conn_confirm_and_send().map_err(|e| {
                     error!("failed ConnOpenConfirm {:?}: {:?}", self.b_side, e);
                     e
                 })?;
+
+                progress::report(progress, self.b_chain().id(), &event);
             }
 
             // send the Confirm message to chain a (source)
             (State::TryOpen, State::Open) => {
-                self.flipped().build_conn_confirm_and_send().map_err(|e| {
+                let event = self.flipped().build_conn_confirm_and_send().map_err(|e| {
                     error!("failed ConnOpenConfirm {:?}: {:?}", self.a_side, e);
                     e
                 })?;
+
+                progress::report(progress, self.a_chain().id(), &event);
             }
 
             (State::Open, State::Open) => {
@@ -647,12 +685,22 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Connection<ChainA, ChainB> {
         Err(ConnectionError::handshake_finalize())
     }
 
-    /// Executes the connection handshake protocol (ICS003)
-    fn handshake(&mut self) -> Result<(), ConnectionError> {
+    /// Executes the connection handshake protocol (ICS003), picking up from the
+    /// current state of the connection ends on both chains, if any.
+    pub fn handshake(&mut self) -> Result<(), ConnectionError> {
+        self.handshake_with_progress(&mut |_| {})
+    }
+
+    /// Executes the connection handshake protocol like [`Connection::handshake`],
+    /// reporting each completed step to `progress`.
+    pub fn handshake_with_progress(
+        &mut self,
+        progress: &mut dyn FnMut(HandshakeProgress),
+    ) -> Result<(), ConnectionError> {
         let max_block_times = self.max_block_times()?;
 
         retry_with_index(handshake_retry::default_strategy(max_block_times), |_| {
-            if let Err(e) = self.do_conn_open_handshake() {
+            if let Err(e) = self.do_conn_open_handshake(progress) {
                 if e.is_expired_or_frozen_error() {
                     RetryResult::Err(e)
                 } else {
@@ -707,10 +755,12 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Connection<ChainA, ChainB> {
         state: State,
     ) -> Result<(Option<IbcEvent>, Next), ConnectionError> {
         let event = match (state, self.counterparty_state()?) {
-            (State::Init, State::Uninitialized) => Some(self.build_conn_try_and_send()?),
-            (State::Init, State::Init) => Some(self.build_conn_try_and_send()?),
-            (State::TryOpen, State::Init) => Some(self.build_conn_ack_and_send()?),
-            (State::TryOpen, State::TryOpen) => Some(self.build_conn_ack_and_send()?),
+            (State::Init, State::Uninitialized) | (State::Init, State::Init) => {
+                Some(self.with_dst_client_update(Self::build_conn_try_and_send)?)
+            }
+            (State::TryOpen, State::Init) | (State::TryOpen, State::TryOpen) => {
+                Some(self.with_dst_client_update(Self::build_conn_ack_and_send)?)
+            }
             (State::Open, State::TryOpen) => Some(self.build_conn_confirm_and_send()?),
             (State::Open, State::Open) => return Ok((None, Next::Abort)),
 
@@ -855,6 +905,81 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Connection<ChainA, ChainB> {
         })
     }
 
+    /// Updates the client hosted by the destination chain to the given height of the source chain,
+    /// or to its latest height if none is given.
+    fn update_dst_client(&self, target_height: Option<Height>) -> Result<(), ConnectionError> {
+        let target_height = target_height.map_or(QueryHeight::Latest, QueryHeight::Specific);
+
+        match self
+            .restore_dst_client()
+            .build_update_client_and_send(target_height, None)
+        {
+            Ok(_) => Ok(()),
+            // Another relayer updated the client in the meantime
+            Err(e)
+                if matches!(
+                    e.detail(),
+                    ForeignClientErrorDetail::ClientAlreadyUpToDate(_)
+                ) =>
+            {
+                Ok(())
+            }
+            Err(e) => Err(ConnectionError::client_operation(
+                self.dst_client_id().clone(),
+                self.dst_chain().id(),
+                e,
+            )),
+        }
+    }
+
+    /// Runs a handshake step which sends messages with proofs from the source chain to the
+    /// destination chain. If the destination chain rejects them because its client has no
+    /// consensus state at the height of the proofs, updates the client and runs the step
+    /// once more, see [`retry_after_client_update`].
+    fn with_dst_client_update(
+        &self,
+        step: impl Fn(&Self) -> Result<IbcEvent, ConnectionError>,
+    ) -> Result<IbcEvent, ConnectionError> {
+        retry_after_client_update(
+            || step(self),
+            ConnectionError::is_consensus_state_not_found_error,
+            |e| match e.consensus_state_not_found_height() {
+                Some(height) => {
+                    warn!(
+                        "client {} on chain {} has no consensus state at height {}, \
+                         updating it to that height and retrying",
+                        self.dst_client_id(),
+                        self.dst_chain().id(),
+                        height
+                    );
+
+                    self.update_dst_client(Some(height)).or_else(|e| {
+                        warn!(
+                            "failed to update client {} on chain {} to height {}, \
+                             updating it to the latest height instead: {}",
+                            self.dst_client_id(),
+                            self.dst_chain().id(),
+                            height,
+                            e
+                        );
+
+                        self.update_dst_client(None)
+                    })
+                }
+                None => {
+                    warn!(
+                        "client {} on chain {} has no consensus state at the height of the proofs, \
+                         updating it and retrying",
+                        self.dst_client_id(),
+                        self.dst_chain().id()
+                    );
+
+                    self.update_dst_client(None)
+                }
+            },
+        )
+    }
+
     pub fn build_conn_init(&self) -> Result<Vec<Any>, ConnectionError> {
         // Get signer
         let signer = self
@@ -912,7 +1037,7 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Connection<ChainA, ChainB> {
                 info!("🥂 {} => {:#?}\n", self.dst_chain().id(), result);
                 Ok(result)
             }
-            IbcEvent::ChainError(e) => Err(ConnectionError::tx_response(e)),
+            IbcEvent::ChainError(e) => Err(ConnectionError::tx_response(e.message)),
             _ => Err(ConnectionError::invalid_event(result)),
         }
     }
@@ -1006,6 +1131,10 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Connection<ChainA, ChainB> {
             )
             .map_err(ConnectionError::connection_proof)?;
 
+        if let Some(client_state) = &client_state {
+            validate_self_client_state(&self.dst_chain(), client_state)?;
+        }
+
         // Build message(s) for updating client on destination
         let mut msgs = self.build_update_client_on_dst(proofs.height())?;
 
@@ -1084,7 +1213,7 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Connection<ChainA, ChainB> {
                 info!("🥂 {} => {:#?}\n", self.dst_chain().id(), result);
                 Ok(result)
             }
-            IbcEvent::ChainError(e) => Err(ConnectionError::tx_response(e)),
+            IbcEvent::ChainError(e) => Err(ConnectionError::tx_response(e.message)),
             _ => Err(ConnectionError::invalid_event(result)),
         }
     }
@@ -1199,7 +1328,7 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Connection<ChainA, ChainB> {
                 info!("🥂 {} => {:#?}\n", self.dst_chain().id(), result);
                 Ok(result)
             }
-            IbcEvent::ChainError(e) => Err(ConnectionError::tx_response(e)),
+            IbcEvent::ChainError(e) => Err(ConnectionError::tx_response(e.message)),
             _ => Err(ConnectionError::invalid_event(result)),
         }
     }
@@ -1287,7 +1416,7 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Connection<ChainA, ChainB> {
                 info!("🥂 {} => {:#?}\n", self.dst_chain().id(), result);
                 Ok(result)
             }
-            IbcEvent::ChainError(e) => Err(ConnectionError::tx_response(e)),
+            IbcEvent::ChainError(e) => Err(ConnectionError::tx_response(e.message)),
             _ => Err(ConnectionError::invalid_event(result)),
         }
     }
@@ -1372,3 +1501,212 @@ fn check_destination_connection_state(
         Err(ConnectionError::connection_already_exists(connection_id))
     }
 }
+
+/// Checks that the client state of `chain`, as tracked by its counterparty,
+/// would pass the self-client validation that `chain` performs when handling
+/// a `MsgConnectionOpenTry`, so that a mismatch is reported locally with a
+/// clear error instead of as an opaque transaction failure.
+///
+/// The client state must track the chain itself, at its current revision,
+/// with its unbonding period as set in its staking parameters. The chain must
+/// also still have its own consensus state at the latest height of the client,
+/// against which it verifies the consensus state proof of the message.
+fn validate_self_client_state<Chain: ChainHandle>(
+    chain: &Chain,
+    client_state: &AnyClientState,
+) -> Result<(), ConnectionError> {
+    let chain_id = chain.id();
+
+    if client_state.chain_id() != chain_id {
+        return Err(ConnectionError::invalid_counterparty_client_state(
+            chain_id,
+            format!(
+                "client state tracks chain '{}' instead",
+                client_state.chain_id()
+            ),
+        ));
+    }
+
+    let host_height = chain
+        .query_latest_height()
+        .map_err(|e| ConnectionError::chain_query(chain_id.clone(), e))?;
+
+    let client_revision = client_state.latest_height().revision_number();
+    if client_revision != host_height.revision_number() {
+        return Err(ConnectionError::invalid_counterparty_client_state(
+            chain_id,
+            format!(
+                "client state is at revision {} but the chain is at revision {}",
+                client_revision,
+                host_height.revision_number()
+            ),
+        ));
+    }
+
+    let unbonding_period = chain
+        .query_unbonding_period()
+        .map_err(|e| ConnectionError::chain_query(chain_id.clone(), e))?;
+
+    if client_state.unbonding_period() != unbonding_period {
+        return Err(ConnectionError::invalid_counterparty_client_state(
+            chain_id,
+            format!(
+                "client state has an unbonding period of {:?} but the chain's unbonding period is {:?}",
+                client_state.unbonding_period(),
+                unbonding_period
+            ),
+        ));
+    }
+
+    chain
+        .query_host_consensus_state(QueryHostConsensusStateRequest {
+            height: QueryHeight::Specific(client_state.latest_height()),
+        })
+        .map_err(|e| ConnectionError::chain_query(chain_id, e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use ibc::clients::ics07_tendermint::client_state::{AllowUpdate, ClientState};
+    use ibc::core::ics02_client::client_state::AnyClientState;
+    use ibc::core::ics02_client::trust_threshold::TrustThreshold;
+    use ibc::core::ics23_commitment::specs::ProofSpecs;
+    use ibc::core::ics24_host::identifier::ChainId;
+    use ibc::events::IbcEvent;
+    use ibc::timestamp::Timestamp;
+    use ibc::Height;
+
+    use crate::chain::handle::mock::MockChainHandle;
+    use crate::chain::handle::ChainHandle;
+    use crate::util::retry::retry_after_client_update;
+
+    use super::error::ConnectionErrorDetail;
+    use super::{validate_self_client_state, ConnectionError};
+
+    const UNBONDING_PERIOD: Duration = Duration::from_secs(21 * 24 * 3600);
+
+    #[test]
+    fn conn_try_not_retried_on_other_errors() {
+        let result: Result<IbcEvent, _> = retry_after_client_update(
+            || {
+                Err(ConnectionError::tx_response(
+                    "connection handshake open try failed: height 0-9: self consensus state not found"
+                        .to_string(),
+                ))
+            },
+            ConnectionError::is_consensus_state_not_found_error,
+            |_| panic!("the client must not be updated"),
+        );
+
+        assert!(result.is_err());
+    }
+
+    /// A client state tracking the chain `chain_id` up to `latest_height`.
+    fn client_state(
+        chain_id: ChainId,
+        latest_height: Height,
+        unbonding_period: Duration,
+    ) -> AnyClientState {
+        let client_state = ClientState::new(
+            chain_id,
+            TrustThreshold::ONE_THIRD,
+            Duration::from_secs(14 * 24 * 3600),
+            unbonding_period,
+            Duration::from_secs(5),
+            latest_height,
+            ProofSpecs::default(),
+            vec![],
+            AllowUpdate {
+                after_expiry: true,
+                after_misbehaviour: true,
+            },
+        )
+        .unwrap();
+
+        AnyClientState::Tendermint(client_state)
+    }
+
+    /// A chain at height 1-100, with the default unbonding period.
+    fn self_chain() -> MockChainHandle {
+        let chain = MockChainHandle::new(ChainId::new("chain_b".to_string(), 1));
+        chain.set_latest(Height::new(1, 100).unwrap(), Timestamp::now());
+        chain.set_unbonding_period(UNBONDING_PERIOD);
+        chain
+    }
+
+    fn assert_invalid_self_client_state(result: Result<(), ConnectionError>, reason: &str) {
+        match result.unwrap_err().detail() {
+            ConnectionErrorDetail::InvalidCounterpartyClientState(e) => {
+                assert_eq!(e.chain_id, ChainId::new("chain_b".to_string(), 1));
+                assert!(e.reason.contains(reason), "unexpected reason: {}", e.reason);
+            }
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn self_client_state_validated() {
+        let chain = self_chain();
+        let client_state = client_state(chain.id(), Height::new(1, 90).unwrap(), UNBONDING_PERIOD);
+
+        assert!(validate_self_client_state(&chain, &client_state).is_ok());
+    }
+
+    #[test]
+    fn self_client_state_of_other_chain_rejected() {
+        let chain = self_chain();
+        let client_state = client_state(
+            ChainId::new("chain_c".to_string(), 1),
+            Height::new(1, 90).unwrap(),
+            UNBONDING_PERIOD,
+        );
+
+        assert_invalid_self_client_state(
+            validate_self_client_state(&chain, &client_state),
+            "tracks chain 'chain_c-1'",
+        );
+    }
+
+    #[test]
+    fn self_client_state_at_other_revision_rejected() {
+        let chain = self_chain();
+        let client_state = client_state(chain.id(), Height::new(0, 90).unwrap(), UNBONDING_PERIOD);
+
+        assert_invalid_self_client_state(
+            validate_self_client_state(&chain, &client_state),
+            "at revision 0 but the chain is at revision 1",
+        );
+    }
+
+    #[test]
+    fn self_client_state_with_other_unbonding_period_rejected() {
+        let chain = self_chain();
+        let client_state = client_state(
+            chain.id(),
+            Height::new(1, 90).unwrap(),
+            Duration::from_secs(14 * 24 * 3600 + 1),
+        );
+
+        assert_invalid_self_client_state(
+            validate_self_client_state(&chain, &client_state),
+            "unbonding period",
+        );
+    }
+
+    #[test]
+    fn self_client_state_ahead_of_chain_rejected() {
+        let chain = self_chain();
+        let client_state = client_state(chain.id(), Height::new(1, 110).unwrap(), UNBONDING_PERIOD);
+
+        let result = validate_self_client_state(&chain, &client_state);
+
+        assert!(matches!(
+            result.unwrap_err().detail(),
+            ConnectionErrorDetail::ChainQuery(_)
+        ));
+    }
+}
@@ -3,8 +3,11 @@ use flex_error::define_error;
 use ibc::core::ics03_connection::connection::{Counterparty, State};
 use ibc::core::ics24_host::identifier::{ChainId, ClientId, ConnectionId};
 use ibc::events::IbcEvent;
+use ibc::Height;
 
-use crate::error::Error as RelayerError;
+use crate::error::{
+    consensus_state_not_found_height, is_consensus_state_not_found, Error as RelayerError,
+};
 use crate::foreign_client::{ForeignClientError, HasExpiredOrFrozenError};
 use crate::supervisor::Error as SupervisorError;
 
@@ -169,6 +172,16 @@ define_error! {
                 format!("connection {} already exists in an incompatible state", e.connection_id)
             },
 
+        InvalidCounterpartyClientState
+            {
+                chain_id: ChainId,
+                reason: String,
+            }
+            |e| {
+                format!("the client state sent to chain '{}' will be rejected by its self-client validation: {}",
+                    e.chain_id, e.reason)
+            },
+
         MaxRetry
             {
                 description: String,
@@ -183,6 +196,28 @@ define_error! {
     }
 }
 
+impl ConnectionError {
+    /// Whether the chain rejected the messages sent to it because its client
+    /// of the counterparty chain has no consensus state at the height of their proofs.
+    pub fn is_consensus_state_not_found_error(&self) -> bool {
+        match self.detail() {
+            ConnectionErrorDetail::Submit(e) => e.source.is_consensus_state_not_found_error(),
+            ConnectionErrorDetail::TxResponse(e) => is_consensus_state_not_found(&e.event),
+            _ => false,
+        }
+    }
+
+    /// The height of the proofs for which the chain found no consensus state,
+    /// if it could be told from the error.
+    pub fn consensus_state_not_found_height(&self) -> Option<Height> {
+        match self.detail() {
+            ConnectionErrorDetail::Submit(e) => e.source.consensus_state_not_found_height(),
+            ConnectionErrorDetail::TxResponse(e) => consensus_state_not_found_height(&e.event),
+            _ => None,
+        }
+    }
+}
+
 impl HasExpiredOrFrozenError for ConnectionErrorDetail {
     fn is_expired_or_frozen_error(&self) -> bool {
         match self {
@@ -1,6 +1,59 @@
 //! Data structures related to the denomination of coins used by the relayer.
 
+use ibc::applications::transfer::PrefixedDenom;
+use ibc::core::ics24_host::identifier::{ChannelId, PortId};
+use ibc_proto::ibc::applications::transfer::v1::DenomTrace as RawDenomTrace;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+pub mod resolver;
+
+/// The prefix of the denomination of coins received over IBC.
+pub const IBC_DENOM_PREFIX: &str = "ibc";
+
+/// Returns the hash of the denomination trace of an IBC voucher, ie. `{hash}`
+/// for the denomination `ibc/{hash}`, or `None` for any other denomination.
+pub fn ibc_denom_hash(denom: &str) -> Option<&str> {
+    denom.strip_prefix(IBC_DENOM_PREFIX)?.strip_prefix('/')
+}
+
+/// Checks that `denom` is a valid Cosmos SDK coin denomination and, if it is the
+/// denomination of an IBC voucher, that its hash is spelled the way the chain records it,
+/// ie. as the upper case hex encoding of a SHA-256 hash, since balances and fees are
+/// matched against the exact denomination.
+pub fn validate_denom(denom: &str) -> Result<(), String> {
+    let valid_syntax = (3..=128).contains(&denom.len())
+        && denom.starts_with(|c: char| c.is_ascii_alphabetic())
+        && denom
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "/:._-".contains(c));
+
+    if !valid_syntax {
+        return Err(
+            "a denomination must be 3 to 128 characters long, start with a letter, \
+            and contain only letters, digits and the characters '/', ':', '.', '_' and '-'"
+                .to_string(),
+        );
+    }
+
+    if let Some(hash) = ibc_denom_hash(denom) {
+        let valid_hash = hash.len() == 64
+            && hash
+                .chars()
+                .all(|c| c.is_ascii_digit() || ('A'..='F').contains(&c));
+
+        if !valid_hash {
+            return Err(format!(
+                "the hash of an IBC voucher denomination must be 64 upper case hex characters, \
+                e.g. '{}/{}'",
+                IBC_DENOM_PREFIX,
+                hash.to_ascii_uppercase()
+            ));
+        }
+    }
+
+    Ok(())
+}
 
 /// The denom trace
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -10,3 +63,322 @@ pub struct DenomTrace {
     /// The base denomination for that coin
     pub base_denom: String,
 }
+
+impl DenomTrace {
+    /// Returns the full denomination trace, ie. `{path}/{base_denom}`,
+    /// or the base denomination alone if the path is empty.
+    pub fn full_path(&self) -> String {
+        if self.path.is_empty() {
+            self.base_denom.clone()
+        } else {
+            format!("{}/{}", self.path, self.base_denom)
+        }
+    }
+
+    /// Returns the hash of this denomination trace, ie. the upper case
+    /// hex encoding of the SHA-256 hash of the full denomination trace.
+    pub fn hash(&self) -> String {
+        hex::encode_upper(Sha256::digest(self.full_path().as_bytes()))
+    }
+
+    /// Returns the denomination under which the coin is known on the chain
+    /// holding it, ie. `ibc/{hash}`, or the base denomination if the coin
+    /// is native to that chain.
+    pub fn ibc_denom(&self) -> String {
+        if self.path.is_empty() {
+            self.base_denom.clone()
+        } else {
+            format!("{}/{}", IBC_DENOM_PREFIX, self.hash())
+        }
+    }
+
+    /// Whether the coin was last received over the channel whose end on the chain
+    /// holding it is `port_id/channel_id`, ie. the first hop of its path.
+    pub fn received_over(&self, port_id: &PortId, channel_id: &ChannelId) -> bool {
+        let prefix = format!("{}/{}", port_id, channel_id);
+
+        self.path == prefix
+            || self
+                .path
+                .strip_prefix(&prefix)
+                .map_or(false, |rest| rest.starts_with('/'))
+    }
+
+    /// Returns the denomination trace of the coin once transferred over the channel
+    /// whose end on the sending chain is `src_port_id/src_channel_id`, and whose end
+    /// on the receiving chain is `dst_port_id/dst_channel_id`, as per ICS-20.
+    ///
+    /// The coin returns to its previous chain if it was last received over that channel,
+    /// in which case the channel is removed from its path, otherwise it is prepended to it.
+    pub fn transferred(
+        &self,
+        src_port_id: &PortId,
+        src_channel_id: &ChannelId,
+        dst_port_id: &PortId,
+        dst_channel_id: &ChannelId,
+    ) -> DenomTrace {
+        let src_prefix = format!("{}/{}", src_port_id, src_channel_id);
+
+        let path = if self.path == src_prefix {
+            String::new()
+        } else if let Some(rest) = self
+            .path
+            .strip_prefix(&src_prefix)
+            .and_then(|rest| rest.strip_prefix('/'))
+        {
+            rest.to_string()
+        } else if self.path.is_empty() {
+            format!("{}/{}", dst_port_id, dst_channel_id)
+        } else {
+            format!("{}/{}/{}", dst_port_id, dst_channel_id, self.path)
+        };
+
+        DenomTrace {
+            path,
+            base_denom: self.base_denom.clone(),
+        }
+    }
+}
+
+/// The trace of the denomination of the tokens carried by a packet, as sent by their chain,
+/// e.g. `transfer/channel-7/uatom`, regardless of the version of the packet.
+impl From<&PrefixedDenom> for DenomTrace {
+    fn from(denom: &PrefixedDenom) -> Self {
+        let raw = RawDenomTrace::from(denom.clone());
+
+        Self {
+            path: raw.path,
+            base_denom: raw.base_denom,
+        }
+    }
+}
+
+/// The unit in which the amounts of coins of a denomination are displayed,
+/// as found in the bank metadata of the denomination, e.g. `ATOM` with 6 decimals
+/// for the denomination `uatom`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DenomDisplay {
+    /// The name of the display unit, e.g. `ATOM`.
+    pub unit: String,
+    /// The number of decimals of the display unit, ie. a display unit is worth
+    /// `10^exponent` coins of the denomination.
+    pub exponent: u32,
+}
+
+impl DenomDisplay {
+    /// Formats an amount of coins of the denomination in the display unit, e.g. `1.5 ATOM`
+    /// for `1500000`, without rounding, or returns `None` if `amount` is not a number.
+    pub fn format(&self, amount: &str) -> Option<String> {
+        if amount.is_empty() || !amount.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+
+        let exponent = self.exponent as usize;
+
+        let digits = amount.trim_start_matches('0');
+        let digits = format!("{:0>width$}", digits, width = exponent + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - exponent);
+        let fraction = fraction.trim_end_matches('0');
+
+        if fraction.is_empty() {
+            Some(format!("{} {}", integer, self.unit))
+        } else {
+            Some(format!("{}.{} {}", integer, fraction, self.unit))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ibc_denom_hash, validate_denom, DenomDisplay, DenomTrace};
+
+    use core::str::FromStr;
+    use ibc::applications::transfer::PrefixedDenom;
+    use ibc::core::ics24_host::identifier::{ChannelId, PortId};
+
+    fn trace(path: &str, base_denom: &str) -> DenomTrace {
+        DenomTrace {
+            path: path.to_string(),
+            base_denom: base_denom.to_string(),
+        }
+    }
+
+    #[test]
+    fn ibc_denom_single_hop() {
+        assert_eq!(
+            trace("transfer/channel-0", "uatom").ibc_denom(),
+            "ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"
+        );
+    }
+
+    #[test]
+    fn ibc_denom_native() {
+        let native = trace("", "uatom");
+
+        assert_eq!(native.full_path(), "uatom");
+        assert_eq!(native.ibc_denom(), "uatom");
+    }
+
+    #[test]
+    fn ibc_denom_multi_hop() {
+        let trace = trace("transfer/channel-1/transfer/channel-0", "uatom");
+
+        assert_eq!(
+            trace.full_path(),
+            "transfer/channel-1/transfer/channel-0/uatom"
+        );
+        assert_eq!(trace.ibc_denom(), format!("ibc/{}", trace.hash()));
+        assert_ne!(
+            trace.hash(),
+            "27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"
+        );
+    }
+
+    fn transferred(trace: &DenomTrace) -> DenomTrace {
+        let port = PortId::transfer();
+
+        trace.transferred(
+            &port,
+            &ChannelId::from_str("channel-0").unwrap(),
+            &port,
+            &ChannelId::from_str("channel-7").unwrap(),
+        )
+    }
+
+    #[test]
+    fn trace_of_packet_denom() {
+        let denom = PrefixedDenom::from_str("transfer/channel-1/transfer/channel-0/uatom").unwrap();
+        let trace = DenomTrace::from(&denom);
+
+        assert_eq!(trace.path, "transfer/channel-1/transfer/channel-0");
+        assert_eq!(trace.base_denom, "uatom");
+
+        let native = DenomTrace::from(&PrefixedDenom::from_str("uatom").unwrap());
+        assert_eq!(native.full_path(), "uatom");
+    }
+
+    #[test]
+    fn transferred_native() {
+        let received = transferred(&trace("", "uatom"));
+
+        assert_eq!(received.full_path(), "transfer/channel-7/uatom");
+    }
+
+    #[test]
+    fn transferred_back_to_source() {
+        let received = transferred(&trace("transfer/channel-0", "uatom"));
+
+        assert_eq!(received.full_path(), "uatom");
+        assert_eq!(received.ibc_denom(), "uatom");
+    }
+
+    #[test]
+    fn transferred_back_one_hop() {
+        let received = transferred(&trace("transfer/channel-0/transfer/channel-3", "uatom"));
+
+        assert_eq!(received.full_path(), "transfer/channel-3/uatom");
+    }
+
+    #[test]
+    fn transferred_further() {
+        let received = transferred(&trace("transfer/channel-00", "uatom"));
+
+        assert_eq!(
+            received.full_path(),
+            "transfer/channel-7/transfer/channel-00/uatom"
+        );
+    }
+
+    #[test]
+    fn received_over_first_hop() {
+        let (port_id, channel_id) = (PortId::transfer(), ChannelId::new(0));
+
+        assert!(trace("transfer/channel-0", "uatom").received_over(&port_id, &channel_id));
+        assert!(trace("transfer/channel-0/transfer/channel-3", "uatom")
+            .received_over(&port_id, &channel_id));
+
+        assert!(!trace("", "uatom").received_over(&port_id, &channel_id));
+        assert!(!trace("transfer/channel-00", "uatom").received_over(&port_id, &channel_id));
+        assert!(!trace("transfer/channel-3/transfer/channel-0", "uatom")
+            .received_over(&port_id, &channel_id));
+    }
+
+    const ATOM_HASH: &str = "27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2";
+
+    #[test]
+    fn ibc_denom_hash_of_voucher() {
+        let denom = trace("transfer/channel-0", "uatom").ibc_denom();
+
+        assert_eq!(ibc_denom_hash(&denom), Some(ATOM_HASH));
+        assert_eq!(ibc_denom_hash("uatom"), None);
+        assert_eq!(ibc_denom_hash("ibcx"), None);
+    }
+
+    #[test]
+    fn validate_native_denoms() {
+        for denom in [
+            "uatom",
+            "stake",
+            "gravity0x4f/usdc",
+            "factory/osmo1abc/foo",
+            "a.b_c-d:e",
+        ] {
+            assert!(validate_denom(denom).is_ok(), "denom '{}'", denom);
+        }
+
+        for denom in [
+            "",
+            "u",
+            "1uatom",
+            "u atom",
+            "uatom!",
+            "u".repeat(129).as_str(),
+        ] {
+            assert!(validate_denom(denom).is_err(), "denom '{}'", denom);
+        }
+    }
+
+    #[test]
+    fn format_in_display_unit() {
+        let atom = DenomDisplay {
+            unit: "ATOM".to_string(),
+            exponent: 6,
+        };
+
+        assert_eq!(atom.format("1500000").unwrap(), "1.5 ATOM");
+        assert_eq!(atom.format("1000000").unwrap(), "1 ATOM");
+        assert_eq!(atom.format("42").unwrap(), "0.000042 ATOM");
+        assert_eq!(atom.format("0").unwrap(), "0 ATOM");
+        assert_eq!(atom.format("000123").unwrap(), "0.000123 ATOM");
+
+        // Well beyond the precision of a `f64`
+        assert_eq!(
+            atom.format("123456789012345678901234567890").unwrap(),
+            "123456789012345678901234.56789 ATOM"
+        );
+
+        assert_eq!(atom.format(""), None);
+        assert_eq!(atom.format("1.5"), None);
+        assert_eq!(atom.format("-1"), None);
+
+        let no_decimals = DenomDisplay {
+            unit: "STAKE".to_string(),
+            exponent: 0,
+        };
+
+        assert_eq!(no_decimals.format("1500").unwrap(), "1500 STAKE");
+    }
+
+    #[test]
+    fn validate_voucher_denoms() {
+        assert!(validate_denom(&format!("ibc/{}", ATOM_HASH)).is_ok());
+
+        let lower_case = format!("ibc/{}", ATOM_HASH.to_ascii_lowercase());
+        assert!(validate_denom(&lower_case).is_err());
+
+        let truncated = format!("ibc/{}", &ATOM_HASH[..63]);
+        assert!(validate_denom(&truncated).is_err());
+
+        assert!(validate_denom("ibc/uatom").is_err());
+    }
+}
@@ -0,0 +1,187 @@
+//! Resolution of the units in which the amounts of coins are displayed.
+//!
+//! The relayer only ever handles amounts of coins in the base denomination of
+//! the coins, e.g. `1500000uatom`. A [`DenomResolver`] finds the display unit
+//! of a denomination, e.g. `ATOM` with 6 decimals, to also show the amounts in
+//! a human-readable form, e.g. `1.5 ATOM`. By default, the display units are
+//! found in the bank metadata of the denominations, see [`BankMetadataResolver`],
+//! but an application embedding the relayer can provide its own resolver, e.g.
+//! backed by a registry of denominations.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::Serialize;
+use tracing::debug;
+
+use crate::chain::handle::ChainHandle;
+use crate::config::ChainConfig;
+use crate::denom::DenomDisplay;
+use crate::util::lock::{LockExt, RwArc};
+
+/// Finds the display unit of the denominations of the coins of a chain.
+pub trait DenomResolver: Send + Sync {
+    /// The display unit of the coins of the given denomination, or `None` if unknown.
+    fn resolve(&self, denom: &str) -> Option<DenomDisplay>;
+}
+
+/// A resolver which does not know the display unit of any denomination,
+/// for the chains with `resolve_denoms` disabled.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoDenomResolver;
+
+impl DenomResolver for NoDenomResolver {
+    fn resolve(&self, _denom: &str) -> Option<DenomDisplay> {
+        None
+    }
+}
+
+/// Resolves the display unit of the denominations of a chain from their bank metadata.
+///
+/// The metadata of each denomination is queried once, and cached along with its
+/// absence, while the failed queries are retried on the next resolution.
+pub struct BankMetadataResolver<Chain> {
+    chain: Chain,
+    cache: RwArc<HashMap<String, Option<DenomDisplay>>>,
+}
+
+impl<Chain: ChainHandle> BankMetadataResolver<Chain> {
+    pub fn new(chain: Chain) -> Self {
+        Self {
+            chain,
+            cache: <RwArc<_>>::new_lock(HashMap::new()),
+        }
+    }
+}
+
+impl<Chain: ChainHandle> DenomResolver for BankMetadataResolver<Chain> {
+    fn resolve(&self, denom: &str) -> Option<DenomDisplay> {
+        if let Some(display) = self.cache.acquire_read().get(denom) {
+            return display.clone();
+        }
+
+        match self.chain.query_denom_metadata(denom.to_string()) {
+            Ok(display) => {
+                self.cache
+                    .acquire_write()
+                    .insert(denom.to_string(), display.clone());
+
+                display
+            }
+            Err(e) => {
+                debug!(
+                    chain = %self.chain.id(),
+                    "failed to query the metadata of denomination '{}': {}", denom, e
+                );
+
+                None
+            }
+        }
+    }
+}
+
+/// The resolver of the denominations of the given chain, from their bank metadata
+/// unless `resolve_denoms` is disabled in the configuration of the chain.
+pub fn default_resolver<Chain: ChainHandle>(
+    chain: &Chain,
+    config: &ChainConfig,
+) -> Box<dyn DenomResolver> {
+    if config.resolve_denoms {
+        Box::new(BankMetadataResolver::new(chain.clone()))
+    } else {
+        Box::new(NoDenomResolver)
+    }
+}
+
+/// An amount of coins in their base denomination, along with its formatting
+/// in the display unit of the denomination, if known.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DisplayAmount {
+    pub amount: String,
+    pub denom: String,
+    /// The amount in the display unit of the denomination, e.g. `1.5 ATOM`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+}
+
+impl DisplayAmount {
+    pub fn resolve(resolver: &dyn DenomResolver, amount: &str, denom: &str) -> Self {
+        let display = resolver
+            .resolve(denom)
+            .and_then(|display| display.format(amount));
+
+        Self {
+            amount: amount.to_string(),
+            denom: denom.to_string(),
+            display,
+        }
+    }
+}
+
+/// Displays the amount in the base denomination followed by the formatted amount,
+/// e.g. `1500000uatom (1.5 ATOM)`.
+impl fmt::Display for DisplayAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.amount, self.denom)?;
+
+        if let Some(display) = &self.display {
+            write!(f, " ({})", display)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BankMetadataResolver, DenomResolver, DisplayAmount, NoDenomResolver};
+
+    use ibc::core::ics24_host::identifier::ChainId;
+
+    use crate::chain::handle::mock::MockChainHandle;
+    use crate::denom::DenomDisplay;
+    use crate::error::Error;
+
+    fn atom() -> DenomDisplay {
+        DenomDisplay {
+            unit: "ATOM".to_string(),
+            exponent: 6,
+        }
+    }
+
+    #[test]
+    fn resolve_from_cached_metadata() {
+        let chain = MockChainHandle::new(ChainId::from_string("chain_A"));
+        chain.set_denom_display("uatom", atom());
+
+        let resolver = BankMetadataResolver::new(chain.clone());
+
+        // A failed query is not cached
+        chain.inject_failure("query_denom_metadata", Error::channel_send);
+        assert_eq!(resolver.resolve("uatom"), None);
+
+        chain.clear_failure("query_denom_metadata");
+        assert_eq!(resolver.resolve("uatom"), Some(atom()));
+        assert_eq!(resolver.resolve("stake"), None);
+
+        // Neither the metadata nor its absence are queried again
+        chain.inject_failure("query_denom_metadata", Error::channel_send);
+        chain.set_denom_display("stake", atom());
+        assert_eq!(resolver.resolve("uatom"), Some(atom()));
+        assert_eq!(resolver.resolve("stake"), None);
+    }
+
+    #[test]
+    fn display_amount() {
+        let chain = MockChainHandle::new(ChainId::from_string("chain_A"));
+        chain.set_denom_display("uatom", atom());
+
+        let resolver = BankMetadataResolver::new(chain);
+
+        let amount = DisplayAmount::resolve(&resolver, "1500000", "uatom");
+        assert_eq!(amount.to_string(), "1500000uatom (1.5 ATOM)");
+
+        let amount = DisplayAmount::resolve(&NoDenomResolver, "1500000", "uatom");
+        assert_eq!(amount.to_string(), "1500000uatom");
+    }
+}
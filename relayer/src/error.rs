@@ -15,6 +15,7 @@ use tendermint_light_client::errors::{
 use tendermint_proto::Error as TendermintProtoError;
 use tendermint_rpc::endpoint::abci_query::AbciQuery;
 use tendermint_rpc::endpoint::broadcast::tx_commit::TxResult;
+use tendermint_rpc::error::ErrorDetail as TendermintRpcErrorDetail;
 use tendermint_rpc::Error as TendermintRpcError;
 use tonic::{
     metadata::errors::InvalidMetadataValue, transport::Error as TransportError,
@@ -31,6 +32,7 @@ use ibc::{
     },
     proofs::ProofError,
     relayer::ics18_relayer::error as relayer_error,
+    Height,
 };
 
 use crate::chain::cosmos::version;
@@ -87,10 +89,39 @@ define_error! {
             [ TraceError<TransportError> ]
             |_| { "error in underlying transport when making gRPC call" },
 
+        GrpcConnection
+            { address: String }
+            [ TraceError<TransportError> ]
+            |e| { format!("failed to connect to the gRPC endpoint {0}", e.address) },
+
+        GrpcCertificateVerification
+            { address: String }
+            [ TraceError<TransportError> ]
+            |e| {
+                format!("failed to verify the TLS certificate of the gRPC endpoint {0}, \
+                    check the `tls` settings of the chain, e.g. its `ca_file`",
+                    e.address)
+            },
+
+        GrpcTlsConfig
+            { address: String, reason: String }
+            |e| {
+                format!("invalid TLS settings for the gRPC endpoint {0}: {1}",
+                    e.address, e.reason)
+            },
+
         GrpcResponseParam
             { param: String }
             |e| { format!("missing parameter in GRPC response: {}", e.param) },
 
+        RestQuery
+            { url: String, reason: String }
+            |e| { format!("failed to query the LCD endpoint {0}: {1}", e.url, e.reason) },
+
+        RestDecode
+            { path: String, reason: String }
+            |e| { format!("failed to decode the response of the LCD endpoint to {0}: {1}", e.path, e.reason) },
+
         Decode
             [ TraceError<TendermintProtoError> ]
             |_| { "error decoding protobuf" },
@@ -153,6 +184,10 @@ define_error! {
         InvalidHeightNoSource
             |_| { "invalid height" },
 
+        InvalidBlockHeader
+            [ TendermintError ]
+            |_| { "invalid block header" },
+
         InvalidMetadata
             [ TraceError<InvalidMetadataValue> ]
             |_| { "invalid metadata" },
@@ -246,6 +281,13 @@ define_error! {
             [ KeyringError ]
             |e| { format!("signature key not found: {}", e.key_name) },
 
+        KeyNotFunded
+            { chain_id: ChainId, key_name: String, denom: String }
+            |e| {
+                format!("key '{}' has no balance of '{}' to pay the fees of the transactions on chain '{}'",
+                    e.key_name, e.denom, e.chain_id)
+            },
+
         Ics02
             [ client_error::Error ]
             |e| { format!("ICS 02 error: {}", e.source) },
@@ -335,6 +377,17 @@ define_error! {
                     e.chain_id, e.estimated_gas, e.max_gas)
             },
 
+        TxSizeExceeded
+            {
+                chain_id: ChainId,
+                size: usize,
+                max_size: usize,
+            }
+            |e| {
+                format!("{} encoded Tx size {} exceeds the maximum configured {}",
+                    e.chain_id, e.size, e.max_size)
+            },
+
         HealthCheckJsonRpc
             {
                 chain_id: ChainId,
@@ -358,18 +411,6 @@ define_error! {
                     e.chain_id, e.address, e.source)
             },
 
-        FetchVersionGrpcTransport
-            {
-                chain_id: ChainId,
-                address: String,
-                endpoint: String,
-            }
-            [ DisplayOnly<tonic::transport::Error> ]
-            |e| {
-                format!("failed while fetching version info from endpoint {0} on the gRPC interface of chain {1}:{2}",
-                    e.endpoint, e.chain_id, e.address)
-            },
-
         FetchVersionGrpcStatus
             {
                 chain_id: ChainId,
@@ -459,6 +500,17 @@ define_error! {
                     e.chain_id, e.default_gas, e.max_gas)
             },
 
+        ConfigValidationUnknownFeeDenom
+            {
+                chain_id: ChainId,
+                denom: String,
+                cause: String,
+            }
+            |e| {
+                format!("semantic config validation failed for option `gas_price.denom` of chain '{}', reason: the denomination trace of the IBC voucher denomination '{}' could not be resolved: {}",
+                    e.chain_id, e.denom, e.cause)
+            },
+
         SdkModuleVersion
             {
                 chain_id: ChainId,
@@ -470,6 +522,16 @@ define_error! {
                     e.chain_id, e.address, e.cause)
             },
 
+        UnsupportedNodeVersion
+            {
+                chain_id: ChainId,
+                cause: String,
+            }
+            |e| {
+                format!("refusing to start chain {0}, whose node runs an unsupported version while `strict_version_check` is enabled: {1}",
+                    e.chain_id, e.cause)
+            },
+
         UnknownAccountType
             {
                 type_url: String
@@ -512,10 +574,32 @@ define_error! {
                     "Query/DenomTrace RPC returned an empty denom trace for trace hash: {}", e.hash)
             },
 
+        DenomTraceNotFound
+            { hash: String }
+            |e| {
+                format_args!(
+                    "no denom trace found for trace hash {}: the chain has received no voucher with this hash", e.hash)
+            },
+
         MessageExceedsMaxTxSize
             { len: usize }
             |e| {
                 format_args!("message length {} exceeds maximum transaction size", e.len)
+            },
+
+        MessageExceedsTxLimits
+            {
+                type_url: String,
+                sequence: Option<u64>,
+                reason: String,
+            }
+            |e| {
+                match e.sequence {
+                    Some(sequence) => format!("{} for packet sequence {} exceeds the transaction limits on its own: {}",
+                        e.type_url, sequence, e.reason),
+                    None => format!("{} exceeds the transaction limits on its own: {}",
+                        e.type_url, e.reason),
+                }
             }
     }
 }
@@ -536,6 +620,114 @@ impl Error {
     }
 }
 
+impl ErrorDetail {
+    /// Whether this is a transient failure to reach the chain, e.g. a timeout or a reset
+    /// connection, after which a read-only query may be retried. The errors returned by
+    /// the chain itself, e.g. for an object which does not exist, are not.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            ErrorDetail::Rpc(detail) => {
+                !matches!(detail.source.detail(), TendermintRpcErrorDetail::Server(_))
+            }
+            ErrorDetail::GrpcTransport(_)
+            | ErrorDetail::GrpcConnection(_)
+            | ErrorDetail::RestQuery(_) => true,
+            ErrorDetail::GrpcStatus(detail) => matches!(
+                detail.status.code(),
+                tonic::Code::Unavailable | tonic::Code::DeadlineExceeded | tonic::Code::Aborted
+            ),
+            _ => false,
+        }
+    }
+
+    /// Whether the gRPC endpoint of the chain could not serve a query at all, i.e. could
+    /// not be reached, or does not serve the queried service, e.g. because it is a proxy
+    /// which only forwards some services. The LCD endpoint of the chain, if any, is then
+    /// queried instead.
+    pub fn is_grpc_unavailable(&self) -> bool {
+        match self {
+            ErrorDetail::GrpcTransport(_) | ErrorDetail::GrpcConnection(_) => true,
+            ErrorDetail::GrpcStatus(detail) => matches!(
+                detail.status.code(),
+                tonic::Code::Unavailable | tonic::Code::Unimplemented
+            ),
+            ErrorDetail::FetchVersionGrpcStatus(detail) => matches!(
+                detail.status.code(),
+                tonic::Code::Unavailable | tonic::Code::Unimplemented
+            ),
+            _ => false,
+        }
+    }
+
+    /// Whether the chain rejected a simulated transaction because a client has
+    /// no consensus state at the height of the proofs of one of its messages,
+    /// see [`is_consensus_state_not_found`].
+    pub fn is_consensus_state_not_found_error(&self) -> bool {
+        match self {
+            ErrorDetail::GrpcStatus(detail) => detail.is_consensus_state_not_found(),
+            _ => false,
+        }
+    }
+
+    /// The height at which a client has no consensus state, if this error says so,
+    /// see [`consensus_state_not_found_height`].
+    pub fn consensus_state_not_found_height(&self) -> Option<Height> {
+        match self {
+            ErrorDetail::GrpcStatus(detail) => {
+                consensus_state_not_found_height(detail.status.message())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Whether an error message reported by a chain says that a client has no consensus
+/// state at the height of the proofs of a message, typically because the client was
+/// not updated to that height yet.
+///
+/// This is not the case of the `self consensus state not found` error, which the chain
+/// reports when it no longer has its own consensus state at the height of a proof.
+pub fn is_consensus_state_not_found(message: &str) -> bool {
+    message
+        .match_indices("consensus state not found")
+        .any(|(index, _)| !message[..index].ends_with("self "))
+}
+
+/// The height at which a client has no consensus state, according to an error message
+/// for which [`is_consensus_state_not_found`] holds.
+///
+/// The chains report the height either before the error, as in `consensus height 1-42:
+/// consensus state not found`, or after it, as in `consensus state not found for height 1-42`.
+/// The height closest to the error is taken, as the message may mention other heights.
+pub fn consensus_state_not_found_height(message: &str) -> Option<Height> {
+    let errors: Vec<usize> = message
+        .match_indices("consensus state not found")
+        .filter(|(index, _)| !message[..*index].ends_with("self "))
+        .map(|(index, _)| index)
+        .collect();
+
+    let re = Regex::new(r#"height:? (?P<height>\d+-\d+)"#).unwrap();
+
+    re.captures_iter(message)
+        .filter_map(|captures| {
+            let height = captures.name("height")?;
+            let distance = errors
+                .iter()
+                .map(|&index| {
+                    if height.start() < index {
+                        index - height.end()
+                    } else {
+                        height.start() - index
+                    }
+                })
+                .min()?;
+
+            Some((distance, height.as_str().parse().ok()?))
+        })
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, height)| height)
+}
+
 impl GrpcStatusSubdetail {
     /// Check whether this gRPC error matches
     /// - message: verification failed: ... failed packet acknowledgement verification for client: client state height < proof height ...
@@ -551,6 +743,12 @@ impl GrpcStatusSubdetail {
         msg.contains("verification failed") && msg.contains("client state height < proof height")
     }
 
+    /// Check whether this gRPC error says that a client has no consensus state
+    /// at the height of the proofs of a message.
+    pub fn is_consensus_state_not_found(&self) -> bool {
+        is_consensus_state_not_found(self.status.message())
+    }
+
     /// Check whether this gRPC error message starts with "account sequence mismatch".
     ///
     /// # Note:
@@ -681,4 +879,133 @@ mod tests {
             )
         }
     }
+
+    #[test]
+    fn consensus_state_not_found_message() {
+        assert!(is_consensus_state_not_found(
+            "deliver_tx for 5E3F reports error: code=Err(7), log=\"failed to execute message; \
+             message index: 1: connection handshake open try failed: consensus height 1-42: \
+             consensus state not found\""
+        ));
+        assert!(!is_consensus_state_not_found(
+            "failed to execute message; message index: 1: height 0-9: self consensus state not found"
+        ));
+        assert!(!is_consensus_state_not_found("client state not found"));
+    }
+
+    #[test]
+    fn consensus_state_not_found_height_in_message() {
+        struct Test<'a> {
+            name: &'a str,
+            message: &'a str,
+            result: Option<Height>,
+        }
+
+        let tests: Vec<Test<'_>> = vec![
+            Test {
+                name: "height before the error",
+                message: "deliver_tx for 5E3F reports error: code=Err(7), log=\"failed to execute \
+                          message; message index: 1: connection handshake open try failed: \
+                          consensus height 1-42: consensus state not found\"",
+                result: Some(Height::new(1, 42).unwrap()),
+            },
+            Test {
+                name: "height after the error",
+                message: "failed to execute message; message index: 0: please ensure the proof \
+                          was constructed against a height that exists on the client: \
+                          consensus state not found for height 0-1234",
+                result: Some(Height::new(0, 1234).unwrap()),
+            },
+            Test {
+                name: "height with a colon",
+                message: "consensus state not found: client: 07-tendermint-0, height: 2-7",
+                result: Some(Height::new(2, 7).unwrap()),
+            },
+            Test {
+                name: "closest of several heights",
+                message: "client state height 1-90 is valid; proof height 1-80: \
+                          consensus state not found; latest height 1-100",
+                result: Some(Height::new(1, 80).unwrap()),
+            },
+            Test {
+                name: "self consensus state",
+                message: "height 0-9: self consensus state not found",
+                result: None,
+            },
+            Test {
+                name: "no height",
+                message: "consensus state not found",
+                result: None,
+            },
+            Test {
+                name: "invalid height",
+                message: "consensus height 1-0: consensus state not found",
+                result: None,
+            },
+        ];
+
+        for test in tests {
+            assert_eq!(
+                test.result,
+                consensus_state_not_found_height(test.message),
+                "{}",
+                test.name
+            )
+        }
+    }
+
+    #[test]
+    fn transient_errors() {
+        let transient = |e: Error| e.detail().is_transient();
+
+        assert!(transient(Error::grpc_status(GrpcStatus::unavailable(
+            "connection reset by peer"
+        ))));
+        assert!(transient(Error::grpc_status(
+            GrpcStatus::deadline_exceeded("request timed out")
+        )));
+
+        // The chain answered, the query would fail again
+        assert!(!transient(Error::grpc_status(GrpcStatus::not_found(
+            "channel not found"
+        ))));
+        assert!(!transient(Error::rpc(
+            "http://127.0.0.1:26657".parse().unwrap(),
+            TendermintRpcError::server(
+                "height 100 must be less than or equal to the current blockchain height 90"
+                    .to_string()
+            )
+        )));
+        assert!(!transient(Error::channel_send()));
+    }
+
+    #[test]
+    fn grpc_unavailable_errors() {
+        let unavailable = |e: Error| e.detail().is_grpc_unavailable();
+
+        assert!(unavailable(Error::grpc_status(GrpcStatus::unavailable(
+            "connection refused"
+        ))));
+        assert!(unavailable(Error::grpc_status(GrpcStatus::unimplemented(
+            "unknown service ibc.core.channel.v1.Query"
+        ))));
+        assert!(unavailable(Error::fetch_version_grpc_status(
+            ChainId::from_string("ibc-0"),
+            "http://127.0.0.1:9090".to_string(),
+            "tendermint::ServiceClient".to_string(),
+            GrpcStatus::unimplemented("unknown service cosmos.base.tendermint.v1beta1.Service")
+        )));
+
+        // The endpoint answered the query
+        assert!(!unavailable(Error::grpc_status(GrpcStatus::not_found(
+            "channel not found"
+        ))));
+        assert!(!unavailable(Error::grpc_status(
+            GrpcStatus::deadline_exceeded("request timed out")
+        )));
+        assert!(!unavailable(Error::rest_query(
+            "http://127.0.0.1:1317".to_string(),
+            "connection refused".to_string()
+        )));
+    }
 }
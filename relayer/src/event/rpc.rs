@@ -125,13 +125,17 @@ pub fn get_all_events(
 
     match data {
         RpcEventData::NewBlock { block, .. } if query == queries::new_block().to_string() => {
+            let header = &block.as_ref().ok_or("tx.height")?.header;
+
             let height = Height::new(
                 ChainId::chain_version(chain_id.to_string().as_str()),
-                u64::from(block.as_ref().ok_or("tx.height")?.header.height),
+                u64::from(header.height),
             )
             .map_err(|_| String::from("tx.height: invalid header height of 0"))?;
 
-            vals.push((height, ClientEvents::NewBlock::new(height).into()));
+            let new_block = ClientEvents::NewBlock::new(height).with_time(header.time.into());
+
+            vals.push((height, new_block.into()));
             vals.append(&mut extract_block_events(height, &events));
         }
         RpcEventData::Tx { tx_result } => {
@@ -0,0 +1,54 @@
+//! Data structures related to the ICS-29 fees paid to the relayers of incentivized packets,
+//! and the [estimation](estimate) of the fees paid by the relayer for submitting messages.
+
+use ibc_proto::cosmos::base::v1beta1::Coin as RawCoin;
+use ibc_proto::ibc::applications::fee::v1::PacketFee as RawPacketFee;
+use serde::{Deserialize, Serialize};
+
+pub mod estimate;
+
+/// An amount of coins of a given denomination.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Coin {
+    /// The amount of coins, as a string to allow for large amounts
+    pub amount: String,
+    /// The denomination of the coins
+    pub denom: String,
+}
+
+impl From<RawCoin> for Coin {
+    fn from(coin: RawCoin) -> Self {
+        Self {
+            amount: coin.amount,
+            denom: coin.denom,
+        }
+    }
+}
+
+/// The fees escrowed on the source chain of a packet, and paid to the relayers
+/// which relay the packet, its acknowledgement or its timeout.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PacketFee {
+    /// Paid to the relayer of the packet to its destination chain
+    pub recv_fee: Vec<Coin>,
+    /// Paid to the relayer of the acknowledgement back to the source chain
+    pub ack_fee: Vec<Coin>,
+    /// Paid to the relayer of the timeout back to the source chain
+    pub timeout_fee: Vec<Coin>,
+    /// The address refunded with the unspent fees
+    pub refund_address: String,
+}
+
+impl From<RawPacketFee> for PacketFee {
+    fn from(packet_fee: RawPacketFee) -> Self {
+        let fee = packet_fee.fee.unwrap_or_default();
+        let coins = |coins: Vec<RawCoin>| coins.into_iter().map(Coin::from).collect();
+
+        Self {
+            recv_fee: coins(fee.recv_fee),
+            ack_fee: coins(fee.ack_fee),
+            timeout_fee: coins(fee.timeout_fee),
+            refund_address: packet_fee.refund_address,
+        }
+    }
+}
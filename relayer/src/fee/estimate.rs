@@ -0,0 +1,509 @@
+//! Estimation of the fees paid for submitting IBC messages to a chain, from the
+//! simulation of representative messages of each [`MessageType`].
+//!
+//! The messages are built from the objects of the chain where needed, e.g. its first open
+//! channel on a port, or the latest header of the counterparty chain of one of its clients.
+//! The packets of the `recv_packet` and `ack` messages are synthetic though, and their
+//! proofs cannot be verified, so a chain may fail their simulation. The fee is then
+//! estimated from the `default_gas` of the chain, which is what the relayer falls back on
+//! when it cannot simulate a transaction.
+
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+
+use flex_error::{define_error, DetailOnly};
+use ibc::applications::transfer::acknowledgement::Acknowledgement as TransferAcknowledgement;
+use ibc::applications::transfer::error::Error as Ics20Error;
+use ibc::applications::transfer::msgs::transfer::MsgTransfer;
+use ibc::applications::transfer::packet::PacketData;
+use ibc::applications::transfer::{Amount, PrefixedCoin};
+use ibc::core::ics04_channel::channel::IdentifiedChannelEnd;
+use ibc::core::ics04_channel::msgs::acknowledgement::MsgAcknowledgement;
+use ibc::core::ics04_channel::msgs::recv_packet::MsgRecvPacket;
+use ibc::core::ics04_channel::packet::{Packet, Sequence};
+use ibc::core::ics04_channel::timeout::TimeoutHeight;
+use ibc::core::ics23_commitment::commitment::CommitmentProofBytes;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, ClientId, PortId};
+use ibc::proofs::{ProofError, Proofs};
+use ibc::signer::Signer;
+use ibc::timestamp::{Timestamp, TimestampOverflowError};
+use ibc_proto::cosmos::base::v1beta1::Coin as RawCoin;
+use ibc_proto::google::protobuf::Any;
+use serde::Serialize;
+
+use crate::chain::cosmos::gas::{explicit_fee_to_fee, gas_amount_to_fee};
+use crate::chain::cosmos::types::gas::GasConfig;
+use crate::chain::endpoint::ChainStatus;
+use crate::chain::handle::ChainHandle;
+use crate::chain::requests::{PageRequest, QueryChannelsRequest};
+use crate::chain::tracking::TrackedMsgs;
+use crate::error::Error;
+use crate::foreign_client::{ForeignClient, ForeignClientError};
+
+/// How long after the latest timestamp of the chain the representative packets time out.
+const PACKET_TIMEOUT: Duration = Duration::from_secs(3600);
+
+define_error! {
+    FeeEstimateError {
+        Relayer
+            [ Error ]
+            |_| { "relayer error" },
+
+        ForeignClient
+            [ ForeignClientError ]
+            |_| { "failed to build the client update" },
+
+        InvalidDenom
+            { denom: String }
+            [ Ics20Error ]
+            |e| { format!("invalid denomination '{}'", e.denom) },
+
+        Proof
+            [ ProofError ]
+            |_| { "failed to build the synthetic proofs" },
+
+        TimestampOverflow
+            [ DetailOnly<TimestampOverflowError> ]
+            |_| { "timestamp overflow" },
+
+        UnknownMessageType
+            { message_type: String }
+            |e| {
+                format!("unknown message type '{}', expected one of: {}",
+                    e.message_type, MessageType::names())
+            },
+
+        NoOpenChannel
+            { chain_id: ChainId, port_id: PortId }
+            |e| {
+                format!("no open channel on port '{}' of chain '{}' to build the packet messages with",
+                    e.port_id, e.chain_id)
+            },
+
+        ClientUpToDate
+            { client_id: ClientId }
+            |e| {
+                format!("client '{}' is already up to date with the latest height of its counterparty chain",
+                    e.client_id)
+            },
+    }
+}
+
+/// The types of messages whose fee can be estimated.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageType {
+    /// A `MsgRecvPacket` of an ICS-20 packet
+    RecvPacket,
+    /// A `MsgAcknowledgement` of an ICS-20 packet
+    Ack,
+    /// A `MsgUpdateClient` to the latest header of the counterparty chain
+    UpdateClient,
+    /// A `MsgTransfer` of one unit of the gas price denomination
+    Transfer,
+}
+
+impl MessageType {
+    pub const ALL: [Self; 4] = [
+        Self::RecvPacket,
+        Self::Ack,
+        Self::UpdateClient,
+        Self::Transfer,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::RecvPacket => "recv_packet",
+            Self::Ack => "ack",
+            Self::UpdateClient => "update_client",
+            Self::Transfer => "transfer",
+        }
+    }
+
+    fn names() -> String {
+        Self::ALL
+            .iter()
+            .map(|message_type| message_type.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl fmt::Display for MessageType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for MessageType {
+    type Err = FeeEstimateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|message_type| message_type.as_str() == s)
+            .ok_or_else(|| FeeEstimateError::unknown_message_type(s.to_string()))
+    }
+}
+
+/// Builds the representative packet and transfer messages of a chain.
+#[derive(Clone, Debug)]
+pub struct RepresentativeMessages {
+    pub chain_id: ChainId,
+    /// The signer of the messages, which is also the sender and receiver of the packets
+    pub signer: Signer,
+    /// The denomination of the coins of the packets, i.e. the gas price denomination
+    pub denom: String,
+    /// The latest status of the chain, from which the packets time out
+    pub status: ChainStatus,
+    /// The port of the channel
+    pub port_id: PortId,
+    /// The open channel on the port along which the packets are sent, if any
+    pub channel: Option<IdentifiedChannelEnd>,
+}
+
+impl RepresentativeMessages {
+    /// Queries what the messages are built from, with the first open channel on the given port.
+    pub fn query(chain: &impl ChainHandle, port_id: &PortId) -> Result<Self, FeeEstimateError> {
+        let signer = chain.get_signer().map_err(FeeEstimateError::relayer)?;
+        let denom = chain
+            .config()
+            .map_err(FeeEstimateError::relayer)?
+            .gas_price
+            .denom;
+        let status = chain
+            .query_application_status()
+            .map_err(FeeEstimateError::relayer)?;
+
+        let channel = chain
+            .query_channels(QueryChannelsRequest {
+                pagination: Some(PageRequest::all()),
+            })
+            .map_err(FeeEstimateError::relayer)?
+            .into_iter()
+            .find(|channel| channel.port_id == *port_id && channel.channel_end.is_open());
+
+        Ok(Self {
+            chain_id: chain.id(),
+            signer,
+            denom,
+            status,
+            port_id: port_id.clone(),
+            channel,
+        })
+    }
+
+    /// A `MsgRecvPacket` of a packet received on the channel, with synthetic proofs.
+    pub fn recv_packet(&self) -> Result<MsgRecvPacket, FeeEstimateError> {
+        let channel = self.channel()?;
+        let counterparty = channel.channel_end.counterparty();
+
+        let packet = self.packet(
+            counterparty.port_id().clone(),
+            counterparty.channel_id().cloned().unwrap_or_default(),
+            channel.port_id.clone(),
+            channel.channel_id.clone(),
+        )?;
+
+        Ok(MsgRecvPacket::new(
+            packet,
+            self.proofs()?,
+            self.signer.clone(),
+        ))
+    }
+
+    /// A `MsgAcknowledgement` of a successful packet sent on the channel, with synthetic proofs.
+    pub fn ack(&self) -> Result<MsgAcknowledgement, FeeEstimateError> {
+        let channel = self.channel()?;
+        let counterparty = channel.channel_end.counterparty();
+
+        let packet = self.packet(
+            channel.port_id.clone(),
+            channel.channel_id.clone(),
+            counterparty.port_id().clone(),
+            counterparty.channel_id().cloned().unwrap_or_default(),
+        )?;
+
+        let ack = TransferAcknowledgement::success().as_ref().to_vec();
+
+        Ok(MsgAcknowledgement::new(
+            packet,
+            ack.into(),
+            self.proofs()?,
+            self.signer.clone(),
+        ))
+    }
+
+    /// A `MsgTransfer` of one unit of the denomination over the channel.
+    pub fn transfer(&self) -> Result<MsgTransfer, FeeEstimateError> {
+        let channel = self.channel()?;
+
+        Ok(MsgTransfer {
+            source_port: channel.port_id.clone(),
+            source_channel: channel.channel_id.clone(),
+            token: RawCoin {
+                denom: self.denom.clone(),
+                amount: "1".to_string(),
+            },
+            sender: self.signer.clone(),
+            receiver: self.signer.clone(),
+            timeout_height: TimeoutHeight::no_timeout(),
+            timeout_timestamp: self.timeout_timestamp()?,
+            memo: String::new(),
+        })
+    }
+
+    fn channel(&self) -> Result<&IdentifiedChannelEnd, FeeEstimateError> {
+        self.channel.as_ref().ok_or_else(|| {
+            FeeEstimateError::no_open_channel(self.chain_id.clone(), self.port_id.clone())
+        })
+    }
+
+    fn packet(
+        &self,
+        source_port: PortId,
+        source_channel: ChannelId,
+        destination_port: PortId,
+        destination_channel: ChannelId,
+    ) -> Result<Packet, FeeEstimateError> {
+        let data = PacketData {
+            token: PrefixedCoin {
+                denom: self
+                    .denom
+                    .parse()
+                    .map_err(|e| FeeEstimateError::invalid_denom(self.denom.clone(), e))?,
+                amount: Amount::from(1),
+            },
+            sender: self.signer.clone(),
+            receiver: self.signer.clone(),
+        };
+
+        Ok(Packet {
+            sequence: Sequence::from(1),
+            source_port,
+            source_channel,
+            destination_port,
+            destination_channel,
+            data: serde_json::to_vec(&data).unwrap_or_default(),
+            timeout_height: TimeoutHeight::no_timeout(),
+            timeout_timestamp: self.timeout_timestamp()?,
+        })
+    }
+
+    fn proofs(&self) -> Result<Proofs, FeeEstimateError> {
+        let proof = CommitmentProofBytes::try_from(vec![0]).map_err(FeeEstimateError::proof)?;
+
+        Proofs::new(proof, None, None, None, self.status.height).map_err(FeeEstimateError::proof)
+    }
+
+    fn timeout_timestamp(&self) -> Result<Timestamp, FeeEstimateError> {
+        (self.status.timestamp + PACKET_TIMEOUT).map_err(FeeEstimateError::timestamp_overflow)
+    }
+}
+
+/// The `MsgUpdateClient` updating the given client to the latest height of its counterparty chain.
+pub fn update_client_messages<DstChain: ChainHandle, SrcChain: ChainHandle>(
+    client: &ForeignClient<DstChain, SrcChain>,
+) -> Result<Vec<Any>, FeeEstimateError> {
+    let target_height = client
+        .src_chain()
+        .query_latest_height()
+        .map_err(FeeEstimateError::relayer)?;
+
+    let msgs = client
+        .build_update_client_with_trusted(target_height, None)
+        .map_err(FeeEstimateError::foreign_client)?;
+
+    if msgs.is_empty() {
+        return Err(FeeEstimateError::client_up_to_date(client.id().clone()));
+    }
+
+    Ok(msgs)
+}
+
+/// The estimated fee of a transaction with the representative messages of a type.
+#[derive(Clone, Debug, Serialize)]
+pub struct FeeEstimate {
+    pub message: MessageType,
+    /// The gas used by the simulated transaction, or the `default_gas` of the chain
+    pub gas: u64,
+    /// The gas limit the transaction would be submitted with
+    pub gas_limit: u64,
+    /// The fee at the gas price of the chain
+    pub fee: Vec<RawCoin>,
+    /// Why the transaction could not be simulated, if it was not
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub simulation_error: Option<String>,
+}
+
+/// Estimates the fee of a transaction with the given messages on the chain, either as
+/// the explicit fee of the chain if any, or from its simulation at the gas price of the chain.
+pub fn estimate_fee(
+    chain: &impl ChainHandle,
+    message: MessageType,
+    msgs: Vec<Any>,
+) -> Result<FeeEstimate, FeeEstimateError> {
+    let chain_config = chain.config().map_err(FeeEstimateError::relayer)?;
+    let gas_config = GasConfig::from(&chain_config);
+
+    if let Some(explicit_fee) = &gas_config.explicit_fee {
+        let fee = explicit_fee_to_fee(&gas_config, explicit_fee);
+
+        return Ok(FeeEstimate {
+            message,
+            gas: fee.gas_limit,
+            gas_limit: fee.gas_limit,
+            fee: fee.amount,
+            simulation_error: None,
+        });
+    }
+
+    let (gas, simulation_error) =
+        match chain.estimate_messages_gas(TrackedMsgs::new_static(msgs, "fees-estimate")) {
+            Ok(gas) => (gas, None),
+            Err(e) => (gas_config.default_gas, Some(e.to_string())),
+        };
+
+    let fee = gas_amount_to_fee(&gas_config, gas);
+
+    Ok(FeeEstimate {
+        message,
+        gas,
+        gas_limit: fee.gas_limit,
+        fee: fee.amount,
+        simulation_error,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{estimate_fee, FeeEstimateErrorDetail, MessageType, RepresentativeMessages};
+
+    use core::str::FromStr;
+    use core::time::Duration;
+
+    use ibc::applications::transfer::packet::PacketData;
+    use ibc::core::ics04_channel::channel::{
+        ChannelEnd, Counterparty as ChannelCounterparty, Order, State as ChannelState,
+    };
+    use ibc::core::ics04_channel::Version;
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, ConnectionId, PortId};
+    use ibc::signer::Signer;
+    use ibc::timestamp::Timestamp;
+    use ibc::Height;
+
+    use crate::chain::handle::mock::MockChainHandle;
+    use crate::chain::mock::test_utils::get_basic_chain_config;
+    use crate::error::Error;
+
+    fn chain(state: ChannelState) -> MockChainHandle {
+        let chain = MockChainHandle::new(ChainId::from_string("chain_A"));
+
+        let mut chain_config = get_basic_chain_config("chain_A");
+        chain_config.gas_multiplier = Some(1.0);
+        chain_config.default_gas = Some(300_000);
+        chain.set_config(chain_config);
+
+        chain.set_signer(Signer::from_str("cosmos1relayer").unwrap());
+        chain.set_latest(
+            Height::new(0, 10).unwrap(),
+            Timestamp::from_nanoseconds(1_000_000_000_000).unwrap(),
+        );
+
+        chain.add_channel(
+            PortId::transfer(),
+            ChannelId::new(0),
+            ChannelEnd::new(
+                state,
+                Order::Unordered,
+                ChannelCounterparty::new(PortId::transfer(), Some(ChannelId::new(5))),
+                vec![ConnectionId::new(0)],
+                Version::ics20(),
+            ),
+        );
+
+        chain
+    }
+
+    #[test]
+    fn parse_message_types() {
+        for message_type in MessageType::ALL {
+            assert_eq!(
+                MessageType::from_str(message_type.as_str()).unwrap(),
+                message_type
+            );
+        }
+
+        let err = MessageType::from_str("timeout").unwrap_err();
+        assert!(matches!(
+            err.detail(),
+            FeeEstimateErrorDetail::UnknownMessageType(e) if e.message_type == "timeout"
+        ));
+    }
+
+    #[test]
+    fn representative_packet_messages() {
+        let chain = chain(ChannelState::Open);
+        let messages = RepresentativeMessages::query(&chain, &PortId::transfer()).unwrap();
+
+        let timeout = (Timestamp::from_nanoseconds(1_000_000_000_000).unwrap()
+            + Duration::from_secs(3600))
+        .unwrap();
+
+        // The packet is received on the open channel, from its counterparty
+        let recv = messages.recv_packet().unwrap();
+        assert_eq!(recv.packet.source_channel, ChannelId::new(5));
+        assert_eq!(recv.packet.destination_channel, ChannelId::new(0));
+        assert_eq!(recv.packet.timeout_timestamp, timeout);
+
+        let data: PacketData = serde_json::from_slice(&recv.packet.data).unwrap();
+        assert_eq!(data.token.to_string(), "1-uatom");
+        assert_eq!(data.receiver.as_ref(), "cosmos1relayer");
+
+        // The acknowledged packet was sent on the open channel
+        let ack = messages.ack().unwrap();
+        assert_eq!(ack.packet.source_channel, ChannelId::new(0));
+        assert_eq!(ack.packet.destination_channel, ChannelId::new(5));
+
+        let transfer = messages.transfer().unwrap();
+        assert_eq!(transfer.source_channel, ChannelId::new(0));
+        assert_eq!(transfer.token.denom, "uatom");
+        assert_eq!(transfer.token.amount, "1");
+        assert_eq!(transfer.timeout_timestamp, timeout);
+    }
+
+    #[test]
+    fn no_open_channel() {
+        let chain = chain(ChannelState::Init);
+        let messages = RepresentativeMessages::query(&chain, &PortId::transfer()).unwrap();
+
+        assert!(messages.channel.is_none());
+        assert!(matches!(
+            messages.transfer().unwrap_err().detail(),
+            FeeEstimateErrorDetail::NoOpenChannel(_)
+        ));
+    }
+
+    #[test]
+    fn estimate_fee_at_the_gas_price() {
+        let chain = chain(ChannelState::Open);
+        chain.set_estimated_gas(100_000);
+
+        let estimate = estimate_fee(&chain, MessageType::Transfer, vec![]).unwrap();
+        assert_eq!(estimate.gas, 100_000);
+        assert_eq!(estimate.gas_limit, 100_000);
+        // The fee is rounded up, the gas price being slightly over 0.001 as a float
+        assert_eq!(estimate.fee[0].amount, "101");
+        assert_eq!(estimate.fee[0].denom, "uatom");
+        assert!(estimate.simulation_error.is_none());
+
+        // The default gas is used when the messages cannot be simulated
+        chain.inject_failure("estimate_messages_gas", Error::channel_send);
+
+        let estimate = estimate_fee(&chain, MessageType::RecvPacket, vec![]).unwrap();
+        assert_eq!(estimate.gas, 300_000);
+        assert_eq!(estimate.fee[0].amount, "301");
+        assert!(estimate.simulation_error.is_some());
+    }
+}
@@ -35,6 +35,7 @@ use ibc::tx_msg::Msg;
 use ibc::Height;
 
 use crate::chain::client::ClientSettings;
+use crate::chain::cosmos::encode::{chain_tx_envelope_size, tx_msg_size};
 use crate::chain::handle::ChainHandle;
 use crate::chain::requests::{
     IncludeProof, PageRequest, QueryClientEventRequest, QueryClientStateRequest,
@@ -274,6 +275,13 @@ define_error! {
                     e.event)
             },
 
+        MissingClientCreateEvent
+            { chain_id: ChainId }
+            |e| {
+                format_args!("no event was emitted by the client creation transaction on chain {}",
+                    e.chain_id)
+            },
+
         ChainErrorEvent
             {
                 chain_id: ChainId,
@@ -283,6 +291,29 @@ define_error! {
                 format_args!("failed to update client on destination {} because of error event: {}",
                     e.chain_id, e.event)
             },
+
+        UnbondingPeriodQuery
+            {
+                chain_id: ChainId,
+            }
+            [ RelayerError ]
+            |e| {
+                format_args!("failed while querying for the unbonding period of chain {0}",
+                    e.chain_id)
+            },
+
+        UpdateHeaderTooLarge
+            {
+                chain_id: ChainId,
+                trusted_height: Height,
+                target_height: Height,
+                size: usize,
+                max_tx_size: usize,
+            }
+            |e| {
+                format_args!("the client update message from trusted height {} to height {} is {} bytes, which exceeds the max_tx_size of {} bytes of chain {}, even between adjacent heights",
+                    e.trusted_height, e.target_height, e.size, e.max_tx_size, e.chain_id)
+            },
     }
 }
 
@@ -302,6 +333,99 @@ impl HasExpiredOrFrozenError for ForeignClientError {
     }
 }
 
+/// Estimates the size taken up by a message in a transaction.
+///
+/// This is the size of the encoded message along with its tag and length in the
+/// transaction body, which is the estimate used when batching messages into
+/// transactions of at most `max_tx_size` bytes, once the envelope is budgeted for.
+pub fn estimate_msg_size(msg: &Any) -> usize {
+    tx_msg_size(msg)
+}
+
+/// Returns the height halfway between the given trusted and target heights,
+/// or `None` if there is no height in between them.
+fn bisect_heights(trusted_height: Height, target_height: Height) -> Option<Height> {
+    if trusted_height.revision_number() != target_height.revision_number() {
+        return None;
+    }
+
+    let low = trusted_height.revision_height();
+    let high = target_height.revision_height();
+
+    if high <= low + 1 {
+        return None;
+    }
+
+    Height::new(target_height.revision_number(), low + (high - low) / 2).ok()
+}
+
+/// Builds the messages for updating a client from `trusted_height` to `target_height`,
+/// none of which exceeds `max_tx_size` bytes.
+///
+/// The messages for a single update, built by `build_msgs` for a pair of trusted and
+/// target heights, are too large when the validator set changed a lot between these
+/// heights. In that case, the update is bisected: the client is first updated to a
+/// height halfway between the trusted and the target heights, from which it is then
+/// updated to the target height. The messages for the intermediate updates come first.
+fn build_update_msgs_within_size<F>(
+    chain_id: &ChainId,
+    trusted_height: Height,
+    target_height: Height,
+    max_tx_size: usize,
+    mut build_msgs: F,
+) -> Result<Vec<Any>, ForeignClientError>
+where
+    F: FnMut(Height, Height) -> Result<Vec<Any>, ForeignClientError>,
+{
+    let mut msgs = vec![];
+    let mut trusted = trusted_height;
+
+    while trusted < target_height {
+        let mut target = target_height;
+
+        loop {
+            let update_msgs = build_msgs(trusted, target)?;
+
+            let oversized = update_msgs
+                .iter()
+                .map(estimate_msg_size)
+                .find(|size| *size > max_tx_size);
+
+            let size = match oversized {
+                Some(size) => size,
+                None => {
+                    msgs.extend(update_msgs);
+                    break;
+                }
+            };
+
+            match bisect_heights(trusted, target) {
+                Some(intermediate) => {
+                    warn!(
+                        "client update message from trusted height {} to height {} is {} bytes, exceeding max_tx_size of {} bytes on chain {}, updating to intermediate height {} first",
+                        trusted, target, size, max_tx_size, chain_id, intermediate
+                    );
+
+                    target = intermediate;
+                }
+                None => {
+                    return Err(ForeignClientError::update_header_too_large(
+                        chain_id.clone(),
+                        trusted,
+                        target,
+                        size,
+                        max_tx_size,
+                    ))
+                }
+            }
+        }
+
+        trusted = target;
+    }
+
+    Ok(msgs)
+}
+
 /// User-supplied options for the [`ForeignClient::build_create_client`] operation.
 ///
 /// Currently, the parameters are specific to the Tendermint-based chains.
@@ -313,6 +437,40 @@ pub struct CreateOptions {
     pub trust_threshold: Option<TrustThreshold>,
 }
 
+/// Captures the diagnostic of comparing the trusting period of a client
+/// with the current unbonding period of the chain it tracks.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TrustingPeriodCheck {
+    /// The trusting period is shorter than the unbonding period, as it should be.
+    Valid,
+    /// The trusting period is no longer shorter than the unbonding period,
+    /// e.g. because the unbonding period was reduced by governance on the
+    /// tracked chain, and the updates of the client will fail verification.
+    ExceedsUnbondingPeriod {
+        trusting_period: Duration,
+        unbonding_period: Duration,
+    },
+}
+
+impl TrustingPeriodCheck {
+    pub fn new(trusting_period: Duration, unbonding_period: Duration) -> Self {
+        if trusting_period < unbonding_period {
+            Self::Valid
+        } else {
+            Self::ExceedsUnbondingPeriod {
+                trusting_period,
+                unbonding_period,
+            }
+        }
+    }
+
+    /// Whether the refresh of the client should be halted,
+    /// if halting it on an invalid trusting period is enabled.
+    pub fn should_halt_refresh(&self, halt_enabled: bool) -> bool {
+        halt_enabled && matches!(self, Self::ExceedsUnbondingPeriod { .. })
+    }
+}
+
 /// Captures the diagnostic of verifying whether a certain
 /// consensus state is within the trusting period (i.e., trusted)
 /// or it's not within the trusting period (not trusted).
@@ -647,8 +805,9 @@ impl<DstChain: ChainHandle, SrcChain: ChainHandle> ForeignClient<DstChain, SrcCh
                 )
             })?;
 
-        assert!(!res.is_empty());
-        Ok(res[0].clone())
+        res.into_iter()
+            .next()
+            .ok_or_else(|| ForeignClientError::missing_client_create_event(self.dst_chain.id()))
     }
 
     /// Sends the client creation transaction & subsequently sets the id of this ForeignClient
@@ -770,6 +929,33 @@ impl<DstChain: ChainHandle, SrcChain: ChainHandle> ForeignClient<DstChain, SrcCh
         }
     }
 
+    /// Compares the trusting period of this client with the
+    /// current unbonding period of its source chain.
+    pub fn check_trusting_period(&self) -> Result<TrustingPeriodCheck, ForeignClientError> {
+        let (client_state, _) = self
+            .dst_chain
+            .query_client_state(
+                QueryClientStateRequest {
+                    client_id: self.id().clone(),
+                    height: QueryHeight::Latest,
+                },
+                IncludeProof::No,
+            )
+            .map_err(|e| {
+                ForeignClientError::client_query(self.id().clone(), self.dst_chain.id(), e)
+            })?;
+
+        let unbonding_period = self
+            .src_chain
+            .query_unbonding_period()
+            .map_err(|e| ForeignClientError::unbonding_period_query(self.src_chain.id(), e))?;
+
+        Ok(TrustingPeriodCheck::new(
+            client_state.trusting_period(),
+            unbonding_period,
+        ))
+    }
+
     pub fn is_expired_or_frozen(&self) -> bool {
         match self.validated_client_state() {
             Ok(_) => false,
@@ -780,6 +966,16 @@ impl<DstChain: ChainHandle, SrcChain: ChainHandle> ForeignClient<DstChain, SrcCh
     pub fn refresh(&mut self) -> Result<Option<Vec<IbcEvent>>, ForeignClientError> {
         let (client_state, elapsed) = self.validated_client_state()?;
 
+        if let Some(_elapsed) = elapsed {
+            crate::telemetry!(
+                client_age,
+                &self.dst_chain.id(),
+                &self.id,
+                _elapsed,
+                client_state.trusting_period().saturating_sub(_elapsed),
+            );
+        }
+
         // The refresh_window is the maximum duration
         // we can backoff between subsequent client updates.
         let refresh_window = client_state.refresh_period();
@@ -1031,17 +1227,6 @@ impl<DstChain: ChainHandle, SrcChain: ChainHandle> ForeignClient<DstChain, SrcCh
             return Ok(vec![]);
         }
 
-        let (header, support) = self
-            .src_chain()
-            .build_header(trusted_height, target_height, client_state.clone())
-            .map_err(|e| {
-                ForeignClientError::client_update(
-                    self.src_chain.id(),
-                    "failed building header with error".to_string(),
-                    e,
-                )
-            })?;
-
         let signer = self.dst_chain().get_signer().map_err(|e| {
             ForeignClientError::client_update(
                 self.dst_chain.id(),
@@ -1050,42 +1235,89 @@ impl<DstChain: ChainHandle, SrcChain: ChainHandle> ForeignClient<DstChain, SrcCh
             )
         })?;
 
-        self.wait_for_header_validation_delay(&client_state, &header)?;
+        let dst_config = self.dst_chain().config().map_err(|e| {
+            ForeignClientError::client_update(
+                self.dst_chain.id(),
+                "failed getting config of dst chain".to_string(),
+                e,
+            )
+        })?;
 
-        let mut msgs = vec![];
+        // The size left to the messages once the fee, signature and memo are budgeted for
+        let envelope_size = chain_tx_envelope_size(&dst_config).map_err(|e| {
+            ForeignClientError::client_update(
+                self.dst_chain.id(),
+                "failed estimating the transaction envelope size of dst chain".to_string(),
+                e,
+            )
+        })?;
 
-        for header in support {
-            debug!(
-                "[{}] MsgUpdateAnyClient for intermediate height {}",
-                self,
-                header.height(),
-            );
+        let max_tx_size = usize::from(dst_config.max_tx_size).saturating_sub(envelope_size);
 
-            msgs.push(
-                MsgUpdateAnyClient {
-                    header,
-                    client_id: self.id.clone(),
-                    signer: signer.clone(),
-                }
-                .to_any(),
-            );
-        }
+        // The header for the update to the target height, built last
+        let mut target_header = None;
 
-        debug!(
-            "[{}] MsgUpdateAnyClient from trusted height {} to target height {}",
-            self,
+        let msgs = build_update_msgs_within_size(
+            &self.dst_chain.id(),
             trusted_height,
-            header.height(),
-        );
+            target_height,
+            max_tx_size,
+            |trusted_height, target_height| {
+                let (header, support) = self
+                    .src_chain()
+                    .build_header(trusted_height, target_height, client_state.clone())
+                    .map_err(|e| {
+                        ForeignClientError::client_update(
+                            self.src_chain.id(),
+                            "failed building header with error".to_string(),
+                            e,
+                        )
+                    })?;
+
+                let mut msgs = vec![];
+
+                for header in support {
+                    debug!(
+                        "[{}] MsgUpdateAnyClient for intermediate height {}",
+                        self,
+                        header.height(),
+                    );
 
-        msgs.push(
-            MsgUpdateAnyClient {
-                header,
-                signer,
-                client_id: self.id.clone(),
-            }
-            .to_any(),
-        );
+                    msgs.push(
+                        MsgUpdateAnyClient {
+                            header,
+                            client_id: self.id.clone(),
+                            signer: signer.clone(),
+                        }
+                        .to_any(),
+                    );
+                }
+
+                debug!(
+                    "[{}] MsgUpdateAnyClient from trusted height {} to target height {}",
+                    self,
+                    trusted_height,
+                    header.height(),
+                );
+
+                msgs.push(
+                    MsgUpdateAnyClient {
+                        header: header.clone(),
+                        signer: signer.clone(),
+                        client_id: self.id.clone(),
+                    }
+                    .to_any(),
+                );
+
+                target_header = Some(header);
+
+                Ok(msgs)
+            },
+        )?;
+
+        if let Some(header) = &target_header {
+            self.wait_for_header_validation_delay(&client_state, header)?;
+        }
 
         Ok(msgs)
     }
@@ -1610,19 +1842,25 @@ pub fn extract_client_id(event: &IbcEvent) -> Result<&ClientId, ForeignClientErr
 mod test {
     use alloc::sync::Arc;
     use core::str::FromStr;
+    use core::time::Duration;
 
     use test_log::test;
     use tokio::runtime::Runtime as TokioRuntime;
 
-    use ibc::core::ics24_host::identifier::ClientId;
+    use ibc::core::ics24_host::identifier::{ChainId, ClientId};
     use ibc::events::IbcEvent;
+    use ibc::Height;
+    use ibc_proto::google::protobuf::Any;
 
     use crate::chain::handle::{BaseChainHandle, ChainHandle};
     use crate::chain::mock::test_utils::get_basic_chain_config;
     use crate::chain::mock::MockChain;
     use crate::chain::requests::{IncludeProof, QueryClientStateRequest, QueryHeight};
     use crate::chain::runtime::ChainRuntime;
-    use crate::foreign_client::ForeignClient;
+    use crate::foreign_client::{
+        bisect_heights, build_update_msgs_within_size, estimate_msg_size, ForeignClient,
+        ForeignClientErrorDetail, TrustingPeriodCheck,
+    };
 
     /// Basic test for the `build_create_client_and_send` method.
     #[test]
@@ -1888,4 +2126,175 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn trusting_period_check_boundary() {
+        let unbonding_period = Duration::from_secs(21 * 24 * 60 * 60);
+
+        assert_eq!(
+            TrustingPeriodCheck::new(unbonding_period - Duration::from_nanos(1), unbonding_period),
+            TrustingPeriodCheck::Valid
+        );
+
+        for trusting_period in [unbonding_period, unbonding_period + Duration::from_nanos(1)] {
+            assert_eq!(
+                TrustingPeriodCheck::new(trusting_period, unbonding_period),
+                TrustingPeriodCheck::ExceedsUnbondingPeriod {
+                    trusting_period,
+                    unbonding_period
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn trusting_period_check_halts_refresh() {
+        let unbonding_period = Duration::from_secs(100);
+
+        let valid = TrustingPeriodCheck::new(Duration::from_secs(99), unbonding_period);
+        assert!(!valid.should_halt_refresh(true));
+        assert!(!valid.should_halt_refresh(false));
+
+        let exceeded = TrustingPeriodCheck::new(Duration::from_secs(100), unbonding_period);
+        assert!(exceeded.should_halt_refresh(true));
+        assert!(!exceeded.should_halt_refresh(false));
+    }
+
+    /// The trusting period of a client created with the default settings
+    /// is shorter than the unbonding period of the chain it tracks.
+    #[test]
+    fn check_trusting_period_method() {
+        let a_cfg = get_basic_chain_config("chain_a");
+        let b_cfg = get_basic_chain_config("chain_b");
+
+        let rt = Arc::new(TokioRuntime::new().unwrap());
+        let a_chain =
+            ChainRuntime::<MockChain>::spawn::<BaseChainHandle>(a_cfg, rt.clone()).unwrap();
+        let b_chain = ChainRuntime::<MockChain>::spawn::<BaseChainHandle>(b_cfg, rt).unwrap();
+
+        let client_on_a = ForeignClient::new(a_chain, b_chain).unwrap();
+
+        assert_eq!(
+            client_on_a.check_trusting_period().unwrap(),
+            TrustingPeriodCheck::Valid
+        );
+    }
+
+    fn height(revision_height: u64) -> Height {
+        Height::new(0, revision_height).unwrap()
+    }
+
+    /// A fake update message, the size of which grows with
+    /// the distance between the trusted and target heights.
+    fn update_msg(trusted_height: Height, target_height: Height) -> Any {
+        let distance = target_height.revision_height() - trusted_height.revision_height();
+
+        Any {
+            type_url: "/test.MsgUpdate".to_string(),
+            value: vec![0; 100 * distance as usize],
+        }
+    }
+
+    #[test]
+    fn estimate_msg_size_is_encoded_len_in_body() {
+        let msg = update_msg(height(1), height(2));
+
+        // 2 bytes for the tag and length of the type url, 2 bytes for those of the value,
+        // 2 bytes for the tag and length of the message in the transaction body
+        assert_eq!(estimate_msg_size(&msg), 2 + 2 + 15 + 2 + 100);
+    }
+
+    #[test]
+    fn bisect_heights_bounds() {
+        assert_eq!(bisect_heights(height(10), height(20)), Some(height(15)));
+        assert_eq!(bisect_heights(height(10), height(13)), Some(height(11)));
+        assert_eq!(bisect_heights(height(10), height(12)), Some(height(11)));
+
+        // No height in between adjacent or out of order heights
+        assert_eq!(bisect_heights(height(10), height(11)), None);
+        assert_eq!(bisect_heights(height(10), height(10)), None);
+        assert_eq!(bisect_heights(height(11), height(10)), None);
+
+        // Nor across revisions
+        assert_eq!(
+            bisect_heights(height(10), Height::new(1, 20).unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn update_msgs_within_size_not_bisected() {
+        let mut segments = vec![];
+
+        let msgs = build_update_msgs_within_size(
+            &ChainId::from_string("chain_b"),
+            height(10),
+            height(20),
+            10_000,
+            |trusted, target| {
+                segments.push((trusted, target));
+                Ok(vec![update_msg(trusted, target)])
+            },
+        )
+        .unwrap();
+
+        assert_eq!(msgs.len(), 1);
+        assert_eq!(segments, vec![(height(10), height(20))]);
+    }
+
+    #[test]
+    fn update_msgs_within_size_bisected() {
+        let mut segments = vec![];
+
+        // Fits the update across up to 3 heights
+        let msgs = build_update_msgs_within_size(
+            &ChainId::from_string("chain_b"),
+            height(10),
+            height(20),
+            400,
+            |trusted, target| {
+                segments.push((trusted, target));
+                Ok(vec![update_msg(trusted, target)])
+            },
+        )
+        .unwrap();
+
+        // The client is updated through the intermediate heights, in order
+        let updates = segments
+            .into_iter()
+            .filter(|(trusted, target)| estimate_msg_size(&update_msg(*trusted, *target)) <= 400)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            updates,
+            vec![
+                (height(10), height(12)),
+                (height(12), height(14)),
+                (height(14), height(17)),
+                (height(17), height(20)),
+            ]
+        );
+        assert_eq!(msgs.len(), updates.len());
+    }
+
+    #[test]
+    fn update_msgs_within_size_too_large() {
+        let result = build_update_msgs_within_size(
+            &ChainId::from_string("chain_b"),
+            height(10),
+            height(20),
+            50,
+            |trusted, target| Ok(vec![update_msg(trusted, target)]),
+        );
+
+        match result.unwrap_err().detail() {
+            ForeignClientErrorDetail::UpdateHeaderTooLarge(e) => {
+                assert_eq!(e.trusted_height, height(10));
+                assert_eq!(e.target_height, height(11));
+                assert_eq!(e.size, 2 + 2 + 15 + 2 + 100);
+                assert_eq!(e.max_tx_size, 50);
+            }
+            e => panic!("unexpected error: {}", e),
+        }
+    }
 }
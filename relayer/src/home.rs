@@ -0,0 +1,225 @@
+//! Resolution of the Hermes home folder.
+//!
+//! The home folder holds the default configuration file and the keys stored on disk,
+//! and is the base folder against which the relative paths of the configuration,
+//! such as the `key_store_folder` and `failed_tx_dir` of the chains, are resolved.
+//!
+//! It is, in order of precedence:
+//! - the folder given with the `--home` flag of Hermes, set with [`init_home`],
+//! - the folder given in the `HERMES_HOME` environment variable,
+//! - the `.hermes` folder in the home directory of the user.
+//!
+//! A relative home folder is resolved against the current directory.
+
+use core::fmt::{Display, Error as FmtError, Formatter};
+use std::env;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+
+/// The environment variable giving the home folder, when no `--home` flag is given.
+pub const HOME_ENV_VAR: &str = "HERMES_HOME";
+
+/// The default home folder, relative to the home directory of the user.
+pub const DEFAULT_HOME_FOLDER: &str = ".hermes";
+
+/// The name of the default configuration file in the home folder.
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+
+static HOME: OnceCell<Home> = OnceCell::new();
+
+/// Where the home folder was taken from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HomeSource {
+    /// The `--home` flag.
+    Flag,
+    /// The `HERMES_HOME` environment variable.
+    Env,
+    /// The default folder in the home directory of the user.
+    Default,
+}
+
+impl Display for HomeSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            HomeSource::Flag => write!(f, "--home flag"),
+            HomeSource::Env => write!(f, "{} environment variable", HOME_ENV_VAR),
+            HomeSource::Default => write!(f, "default"),
+        }
+    }
+}
+
+/// The resolved home folder.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Home {
+    pub path: PathBuf,
+    pub source: HomeSource,
+}
+
+impl Home {
+    /// Resolves the home folder from the given `--home` flag, `HERMES_HOME` variable
+    /// and home directory of the user, relative paths being resolved against `cwd`.
+    ///
+    /// An empty flag or variable is ignored. Returns `None` if neither is set and the
+    /// home directory of the user is unknown.
+    pub fn resolve(
+        flag: Option<&Path>,
+        env: Option<&OsStr>,
+        user_home: Option<&Path>,
+        cwd: &Path,
+    ) -> Option<Self> {
+        let (path, source) = if let Some(flag) = flag.filter(|flag| !flag.as_os_str().is_empty()) {
+            (flag.to_path_buf(), HomeSource::Flag)
+        } else if let Some(env) = env.filter(|env| !env.is_empty()) {
+            (PathBuf::from(env), HomeSource::Env)
+        } else {
+            (user_home?.join(DEFAULT_HOME_FOLDER), HomeSource::Default)
+        };
+
+        Some(Self {
+            path: cwd.join(path),
+            source,
+        })
+    }
+
+    /// Resolves the home folder from the given `--home` flag and the environment of the process.
+    pub fn from_env(flag: Option<&Path>) -> Option<Self> {
+        Self::resolve(
+            flag,
+            env::var_os(HOME_ENV_VAR).as_deref(),
+            dirs_next::home_dir().as_deref(),
+            &env::current_dir().unwrap_or_default(),
+        )
+    }
+
+    /// The path to the default configuration file.
+    pub fn config_file(&self) -> PathBuf {
+        self.path.join(CONFIG_FILE_NAME)
+    }
+
+    /// Resolves the given path against the home folder, if it is relative.
+    pub fn resolve_path(&self, path: &Path) -> PathBuf {
+        self.path.join(path)
+    }
+}
+
+/// Resolves the home folder with the given `--home` flag, for the rest of the process.
+///
+/// Returns the home folder already in use if it was initialized before.
+pub fn init_home(flag: Option<&Path>) -> Option<&'static Home> {
+    match HOME.get() {
+        Some(home) => Some(home),
+        None => {
+            let home = Home::from_env(flag)?;
+            Some(HOME.get_or_init(|| home))
+        }
+    }
+}
+
+/// The home folder, as resolved by [`init_home`] or otherwise from the environment.
+pub fn home() -> Option<Home> {
+    HOME.get().cloned().or_else(|| Home::from_env(None))
+}
+
+/// Resolves the given path against the home folder, if it is relative and the home
+/// folder is known.
+pub fn resolve_path(path: &Path) -> PathBuf {
+    match home() {
+        Some(home) => home.resolve_path(path),
+        None => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Home, HomeSource};
+
+    use std::ffi::OsStr;
+    use std::path::{Path, PathBuf};
+
+    const CWD: &str = "/work";
+    const USER_HOME: &str = "/home/user";
+
+    fn resolve(flag: Option<&str>, env: Option<&str>, user_home: Option<&str>) -> Option<Home> {
+        Home::resolve(
+            flag.map(Path::new),
+            env.map(OsStr::new),
+            user_home.map(Path::new),
+            Path::new(CWD),
+        )
+    }
+
+    fn home(path: &str, source: HomeSource) -> Option<Home> {
+        Some(Home {
+            path: PathBuf::from(path),
+            source,
+        })
+    }
+
+    #[test]
+    fn flag_overrides_env_and_default() {
+        assert_eq!(
+            resolve(Some("/flag"), Some("/env"), Some(USER_HOME)),
+            home("/flag", HomeSource::Flag)
+        );
+        assert_eq!(
+            resolve(Some("/flag"), None, None),
+            home("/flag", HomeSource::Flag)
+        );
+    }
+
+    #[test]
+    fn env_overrides_default() {
+        assert_eq!(
+            resolve(None, Some("/env"), Some(USER_HOME)),
+            home("/env", HomeSource::Env)
+        );
+        assert_eq!(
+            resolve(Some(""), Some("/env"), None),
+            home("/env", HomeSource::Env)
+        );
+    }
+
+    #[test]
+    fn default_in_user_home() {
+        assert_eq!(
+            resolve(None, None, Some(USER_HOME)),
+            home("/home/user/.hermes", HomeSource::Default)
+        );
+        assert_eq!(
+            resolve(None, Some(""), Some(USER_HOME)),
+            home("/home/user/.hermes", HomeSource::Default)
+        );
+        assert_eq!(resolve(None, None, None), None);
+    }
+
+    #[test]
+    fn relative_home_resolved_against_cwd() {
+        assert_eq!(
+            resolve(Some("data/hermes"), None, None),
+            home("/work/data/hermes", HomeSource::Flag)
+        );
+        assert_eq!(
+            resolve(None, Some("./hermes"), None),
+            home("/work/./hermes", HomeSource::Env)
+        );
+    }
+
+    #[test]
+    fn paths_in_home() {
+        let home = resolve(Some("/flag"), None, None).unwrap();
+
+        assert_eq!(home.config_file(), PathBuf::from("/flag/config.toml"));
+        assert_eq!(
+            home.resolve_path(Path::new("keys/ibc-0")),
+            PathBuf::from("/flag/keys/ibc-0")
+        );
+        assert_eq!(
+            home.resolve_path(Path::new("/var/failed-txs")),
+            PathBuf::from("/var/failed-txs")
+        );
+    }
+}
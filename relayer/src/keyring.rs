@@ -4,7 +4,8 @@ use std::fs::{self, File};
 use std::path::{Path, PathBuf};
 
 use crate::config::AddressType;
-use bech32::{ToBase32, Variant};
+use crate::home;
+use bech32::{FromBase32, ToBase32, Variant};
 use bip39::{Language, Mnemonic, Seed};
 use bitcoin::{
     network::constants::Network,
@@ -27,7 +28,8 @@ mod pub_key;
 
 pub type HDPath = StandardHDPath;
 
-pub const KEYSTORE_DEFAULT_FOLDER: &str = ".hermes/keys/";
+/// The folder the keys are stored in by default, relative to the Hermes home folder.
+pub const KEYSTORE_DEFAULT_FOLDER: &str = "keys";
 pub const KEYSTORE_DISK_BACKEND: &str = "keyring-test";
 pub const KEYSTORE_FILE_EXTENSION: &str = "json";
 
@@ -49,6 +51,11 @@ pub struct KeyEntry {
 
     /// Address
     pub address: Vec<u8>,
+
+    /// Derivation path the private key was derived with,
+    /// unknown for the keys added before it was recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hd_path: Option<String>,
 }
 
 /// JSON key seed file
@@ -94,6 +101,7 @@ impl KeyEntry {
                 private_key,
                 account: key_file.address,
                 address: keyfile_address_bytes,
+                hd_path: Some(hd_path.to_string()),
             })
         }
     }
@@ -260,12 +268,17 @@ pub enum KeyRing {
 }
 
 impl KeyRing {
-    pub fn new(store: Store, account_prefix: &str, chain_id: &ChainId) -> Result<Self, Error> {
+    pub fn new(
+        store: Store,
+        account_prefix: &str,
+        chain_id: &ChainId,
+        key_store_folder: &Option<PathBuf>,
+    ) -> Result<Self, Error> {
         match store {
             Store::Memory => Ok(Self::Memory(Memory::new(account_prefix.to_string()))),
 
             Store::Test => {
-                let keys_folder = disk_store_path(chain_id.as_str())?;
+                let keys_folder = disk_store_folder(chain_id, key_store_folder.as_deref())?;
 
                 // Create keys folder if it does not exist
                 fs::create_dir_all(&keys_folder).map_err(|e| {
@@ -348,6 +361,7 @@ impl KeyRing {
             private_key,
             account,
             address,
+            hd_path: Some(hd_path.to_string()),
         })
     }
 
@@ -400,6 +414,92 @@ pub fn sign_message(
     }
 }
 
+/// Derive the Bech32 account of the given key, for the given account prefix and address type.
+pub fn derive_account(
+    key: &KeyEntry,
+    account_prefix: &str,
+    address_type: &AddressType,
+) -> Result<String, Error> {
+    let address = get_address(key.public_key, address_type);
+
+    bech32::encode(account_prefix, address.to_base32(), Variant::Bech32).map_err(Error::bech32)
+}
+
+/// Derive the address of the given key for the given address type.
+pub fn derive_address(key: &KeyEntry, address_type: &AddressType) -> Vec<u8> {
+    get_address(key.public_key, address_type)
+}
+
+/// Encode the given address in the hexadecimal form used by Ethereum,
+/// with the mixed-case checksum of EIP-55, e.g. `0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed`.
+pub fn encode_eth_address(address: &[u8]) -> String {
+    let lower = hex::encode(address);
+    let hash = keccak256_hash(lower.as_bytes());
+
+    let checksummed: String = lower
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect();
+
+    format!("0x{}", checksummed)
+}
+
+/// Check that the account derived from the given key, for the given account prefix
+/// and address type, matches the expected Bech32 address.
+///
+/// On success, returns the derived account. Otherwise, the error points at
+/// the most likely cause of the mismatch: a wrong account prefix, a wrong
+/// address type, or a wrong HD path or key material.
+pub fn check_address(
+    key: &KeyEntry,
+    expected: &str,
+    account_prefix: &str,
+    address_type: &AddressType,
+) -> Result<String, Error> {
+    let derived = derive_account(key, account_prefix, address_type)?;
+
+    if derived == expected {
+        return Ok(derived);
+    }
+
+    let (expected_prefix, expected_data, _) =
+        bech32::decode(expected).map_err(Error::bech32_account)?;
+
+    let expected_address = Vec::<u8>::from_base32(&expected_data).map_err(Error::bech32_account)?;
+
+    let other_address_type = match address_type {
+        AddressType::Cosmos => AddressType::Ethermint {
+            pk_type: "/ethermint.crypto.v1.ethsecp256k1.PubKey".to_string(),
+        },
+        AddressType::Ethermint { .. } => AddressType::Cosmos,
+    };
+
+    let hint = if expected_address == get_address(key.public_key, address_type) {
+        format!(
+            "wrong account prefix, the chain is configured with `account_prefix = '{}'` but the expected address uses '{}'",
+            account_prefix, expected_prefix
+        )
+    } else if expected_address == get_address(key.public_key, &other_address_type) {
+        format!(
+            "wrong address type, the chain is configured with the '{}' address type but the expected address uses the '{}' one",
+            address_type, other_address_type
+        )
+    } else {
+        "wrong HD path or key material, check the `--hd-path` option and the key file".to_string()
+    };
+
+    Err(Error::address_mismatch(expected.to_string(), derived, hint))
+}
+
 /// Decode an extended private key from a mnemonic
 fn private_key_from_mnemonic(
     mnemonic_words: &str,
@@ -455,8 +555,6 @@ fn get_address(pk: ExtendedPubKey, at: &AddressType) -> Vec<u8> {
 }
 
 fn decode_bech32(input: &str) -> Result<Vec<u8>, Error> {
-    use bech32::FromBase32;
-
     let bytes = bech32::decode(input)
         .and_then(|(_, data, _)| Vec::from_base32(&data))
         .map_err(Error::bech32_account)?;
@@ -464,12 +562,23 @@ fn decode_bech32(input: &str) -> Result<Vec<u8>, Error> {
     Ok(bytes)
 }
 
-fn disk_store_path(folder_name: &str) -> Result<PathBuf, Error> {
-    let home = dirs_next::home_dir().ok_or_else(Error::home_location_unavailable)?;
+/// The folder the keys of the chain are stored in on disk, i.e. the given
+/// `key_store_folder` resolved against the Hermes home folder, or by default
+/// `<home>/keys/<chain id>/keyring-test`.
+pub fn disk_store_folder(
+    chain_id: &ChainId,
+    key_store_folder: Option<&Path>,
+) -> Result<PathBuf, Error> {
+    if let Some(folder) = key_store_folder {
+        return Ok(home::resolve_path(folder));
+    }
+
+    let home = home::home().ok_or_else(Error::home_location_unavailable)?;
 
-    let folder = Path::new(home.as_path())
+    let folder = home
+        .path
         .join(KEYSTORE_DEFAULT_FOLDER)
-        .join(folder_name)
+        .join(chain_id.as_str())
         .join(KEYSTORE_DISK_BACKEND);
 
     Ok(folder)
@@ -497,3 +606,118 @@ fn standard_path_to_derivation_path(path: &StandardHDPath) -> DerivationPath {
 
     DerivationPath::from(child_numbers)
 }
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use bitcoin::secp256k1::Secp256k1;
+    use bitcoin::util::bip32::ExtendedPubKey;
+
+    use super::errors::{Error, ErrorDetail};
+    use super::{
+        check_address, derive_account, encode_eth_address, get_address, private_key_from_mnemonic,
+        AddressType, HDPath, KeyEntry,
+    };
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon \
+        abandon abandon abandon abandon abandon about";
+
+    /// The key derived from [`MNEMONIC`] with the given HD path.
+    fn key(hd_path: &str) -> KeyEntry {
+        let hd_path = HDPath::from_str(hd_path).unwrap();
+        let private_key = private_key_from_mnemonic(MNEMONIC, &hd_path).unwrap();
+        let public_key = ExtendedPubKey::from_priv(&Secp256k1::new(), &private_key);
+
+        KeyEntry {
+            public_key,
+            private_key,
+            account: String::new(),
+            address: get_address(public_key, &AddressType::Cosmos),
+            hd_path: Some(hd_path.to_string()),
+        }
+    }
+
+    fn ethermint() -> AddressType {
+        AddressType::Ethermint {
+            pk_type: "/ethermint.crypto.v1.ethsecp256k1.PubKey".to_string(),
+        }
+    }
+
+    fn mismatch_hint(result: Result<String, Error>) -> String {
+        match result.unwrap_err().detail() {
+            ErrorDetail::AddressMismatch(e) => e.hint.clone(),
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn address_matching_key() {
+        let key = key("m/44'/118'/0'/0/0");
+        let expected = derive_account(&key, "cosmos", &AddressType::Cosmos).unwrap();
+
+        let derived = check_address(&key, &expected, "cosmos", &AddressType::Cosmos).unwrap();
+        assert_eq!(derived, expected);
+    }
+
+    #[test]
+    fn address_with_other_prefix() {
+        let key = key("m/44'/118'/0'/0/0");
+        let expected = derive_account(&key, "osmo", &AddressType::Cosmos).unwrap();
+
+        let hint = mismatch_hint(check_address(
+            &key,
+            &expected,
+            "cosmos",
+            &AddressType::Cosmos,
+        ));
+        assert!(hint.starts_with("wrong account prefix"), "{}", hint);
+        assert!(hint.contains("'osmo'"), "{}", hint);
+    }
+
+    #[test]
+    fn address_of_other_address_type() {
+        let key = key("m/44'/118'/0'/0/0");
+        let expected = derive_account(&key, "evmos", &ethermint()).unwrap();
+
+        let hint = mismatch_hint(check_address(
+            &key,
+            &expected,
+            "evmos",
+            &AddressType::Cosmos,
+        ));
+        assert!(hint.starts_with("wrong address type"), "{}", hint);
+    }
+
+    #[test]
+    fn address_of_other_hd_path() {
+        let other = key("m/44'/60'/0'/0/0");
+        let expected = derive_account(&other, "cosmos", &AddressType::Cosmos).unwrap();
+
+        let hint = mismatch_hint(check_address(
+            &key("m/44'/118'/0'/0/0"),
+            &expected,
+            "cosmos",
+            &AddressType::Cosmos,
+        ));
+        assert!(
+            hint.starts_with("wrong HD path or key material"),
+            "{}",
+            hint
+        );
+    }
+
+    #[test]
+    fn eth_address_checksum() {
+        // Test vectors from EIP-55
+        for expected in [
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ] {
+            let address = hex::decode(expected[2..].to_lowercase()).unwrap();
+            assert_eq!(encode_eth_address(&address), expected);
+        }
+    }
+}
@@ -112,5 +112,16 @@ define_error! {
             |e| {
                 format!("invalid HD path: {0}", e.path)
             },
+
+        AddressMismatch
+            {
+                expected: String,
+                derived: String,
+                hint: String,
+            }
+            |e| {
+                format!("the address derived from the key '{}' does not match the expected address '{}': {}",
+                    e.derived, e.expected, e.hint)
+            },
     }
 }
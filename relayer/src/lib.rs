@@ -21,6 +21,7 @@
 extern crate alloc;
 
 pub mod account;
+pub mod bootstrap;
 pub mod cache;
 pub mod chain;
 pub mod channel;
@@ -29,13 +30,16 @@ pub mod connection;
 pub mod denom;
 pub mod error;
 pub mod event;
+pub mod fee;
 pub mod foreign_client;
+pub mod home;
 pub mod keyring;
 pub mod light_client;
 pub mod link;
 pub mod macros;
 pub mod object;
 pub mod path;
+pub mod progress;
 pub mod registry;
 pub mod rest;
 pub mod sdk_error;
@@ -8,25 +8,36 @@ use crate::chain::requests::{QueryChannelRequest, QueryHeight};
 use crate::chain::{counterparty::check_channel_counterparty, requests::QueryConnectionRequest};
 use crate::chain::{handle::ChainHandle, requests::IncludeProof};
 use crate::channel::{Channel, ChannelSide};
+use crate::config::RelayMode;
 use crate::link::error::LinkError;
 
 pub mod cli;
+pub mod deferred_timeout;
 pub mod error;
 pub mod operational_data;
+pub mod sent_sequences;
+pub mod submitted;
+pub mod tx_error;
 
 mod packet_events;
 mod pending;
+mod proof_check;
 mod relay_path;
-mod relay_sender;
+pub(crate) mod relay_sender;
 mod relay_summary;
 mod tx_hashes;
 
-use tx_hashes::TxHashes;
+pub use tx_hashes::TxHashes;
 
 // Re-export the telemetries summary
 pub use relay_summary::RelaySummary;
 
-pub use relay_path::{RelayPath, Resubmit};
+pub use deferred_timeout::DeferredTimeouts;
+pub use pending::PendingData;
+pub use proof_check::VerifyProofs;
+pub use relay_path::{ClearProgress, PacketClearing, PendingSequences, RelayPath, Resubmit};
+pub use sent_sequences::{SentSequences, SkippedSequences};
+pub use submitted::SubmittedTxs;
 
 #[derive(Clone, Debug)]
 pub struct LinkParameters {
@@ -48,6 +59,28 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> Link<ChainA, ChainB> {
         })
     }
 
+    /// This link, only relaying the kinds of packet messages of the given mode.
+    pub fn with_relay_mode(self, relay_mode: RelayMode) -> Self {
+        Self {
+            a_to_b: self.a_to_b.with_relay_mode(relay_mode),
+        }
+    }
+
+    /// This link, sharing with the previous and next workers of the path the given
+    /// record of the transactions submitted on its channel ends.
+    pub fn with_submitted_txs(self, submitted: SubmittedTxs) -> Self {
+        Self {
+            a_to_b: self.a_to_b.with_submitted_txs(submitted),
+        }
+    }
+
+    /// This link, verifying locally the proofs queried from the given chains.
+    pub fn with_proof_verification(self, verify_proofs: VerifyProofs) -> Self {
+        Self {
+            a_to_b: self.a_to_b.with_proof_verification(verify_proofs),
+        }
+    }
+
     pub fn new_from_opts(
         a_chain: ChainA,
         b_chain: ChainB,
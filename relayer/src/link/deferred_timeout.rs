@@ -0,0 +1,123 @@
+//! Timeouts of packets whose destination chain is unreachable.
+//!
+//! Relaying a timeout requires a proof that the packet was not received on the
+//! destination chain, which cannot be queried while that chain is down. When the
+//! last consensus state of the destination chain known to the client on the source
+//! chain already shows that the packet timed out, the timeout is deferred: it is kept
+//! here until the destination chain can be queried again, at which point the proof is
+//! built and the timeout relayed, no matter whether the packet is still scheduled.
+
+use alloc::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+use ibc::core::ics04_channel::events::SendPacket;
+use ibc::core::ics04_channel::packet::Sequence;
+use ibc::Height;
+
+use crate::util::lock::LockExt;
+
+/// A packet shown to be timed out by the client of its destination chain on
+/// its source chain, whose timeout awaits a proof from the destination chain.
+#[derive(Clone, Debug)]
+pub struct DeferredTimeout {
+    pub event: SendPacket,
+    /// The height of the consensus state of the destination chain
+    /// which shows that the packet timed out
+    pub client_height: Height,
+}
+
+/// The timeouts awaiting a proof on a relaying path, indexed by the sequence
+/// of their packet. All the clones share the same timeouts.
+#[derive(Clone, Debug, Default)]
+pub struct DeferredTimeouts(Arc<RwLock<BTreeMap<Sequence, DeferredTimeout>>>);
+
+impl DeferredTimeouts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How many timeouts await a proof.
+    pub fn len(&self) -> usize {
+        self.0.acquire_read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.acquire_read().is_empty()
+    }
+
+    pub fn contains(&self, sequence: Sequence) -> bool {
+        self.0.acquire_read().contains_key(&sequence)
+    }
+
+    /// Defers the timeout of a packet, returning `false` if it already was.
+    pub fn insert(&self, timeout: DeferredTimeout) -> bool {
+        self.0
+            .acquire_write()
+            .insert(timeout.event.packet.sequence, timeout)
+            .is_none()
+    }
+
+    /// Takes out all the deferred timeouts, by increasing sequence.
+    pub fn take_all(&self) -> Vec<DeferredTimeout> {
+        let timeouts = core::mem::take(&mut *self.0.acquire_write());
+        timeouts.into_values().collect()
+    }
+
+    /// Defers again the given timeouts, e.g. after failing to relay them.
+    pub fn restore(&self, timeouts: impl IntoIterator<Item = DeferredTimeout>) {
+        let mut deferred = self.0.acquire_write();
+
+        for timeout in timeouts {
+            deferred.insert(timeout.event.packet.sequence, timeout);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DeferredTimeout, DeferredTimeouts};
+
+    use ibc::core::ics04_channel::events::SendPacket;
+    use ibc::core::ics04_channel::packet::{Packet, Sequence};
+    use ibc::Height;
+
+    fn timeout(sequence: u64) -> DeferredTimeout {
+        let packet = Packet {
+            sequence: Sequence::from(sequence),
+            ..Packet::default()
+        };
+
+        DeferredTimeout {
+            event: SendPacket {
+                height: Height::new(0, 1).unwrap(),
+                packet,
+            },
+            client_height: Height::new(0, 10).unwrap(),
+        }
+    }
+
+    #[test]
+    fn shared_between_clones() {
+        let deferred = DeferredTimeouts::new();
+        let clone = deferred.clone();
+
+        assert!(deferred.insert(timeout(2)));
+        assert!(deferred.insert(timeout(1)));
+        assert!(!clone.insert(timeout(1)));
+
+        assert_eq!(clone.len(), 2);
+        assert!(clone.contains(Sequence::from(2)));
+
+        let sequences: Vec<_> = clone
+            .take_all()
+            .into_iter()
+            .map(|timeout| u64::from(timeout.event.packet.sequence))
+            .collect();
+
+        assert_eq!(sequences, vec![1, 2]);
+        assert!(deferred.is_empty());
+
+        deferred.restore(vec![timeout(3)]);
+        assert_eq!(clone.len(), 1);
+    }
+}
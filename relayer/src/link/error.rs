@@ -148,6 +148,28 @@ define_error! {
 
         UpdateClientFailed
              |_| { "failed to update client" },
+
+        MalformedMessage
+            {
+                type_url: String,
+                reason: String,
+            }
+            |e| {
+                format!("failed to decode message of type {}: {}",
+                    e.type_url, e.reason)
+            },
+
+        ForkedProof
+            {
+                chain_id: ChainId,
+                height: Height,
+                path: String,
+                reason: String,
+            }
+            |e| {
+                format!("proof of {} queried from chain {} at height {} does not match the app hash known to its counterparty, the node may be on a fork: {}",
+                    e.path, e.chain_id, e.height, e.reason)
+            },
    }
 }
 
@@ -3,7 +3,7 @@ use core::iter;
 use std::time::{Duration, Instant};
 
 use ibc_proto::google::protobuf::Any;
-use tracing::{debug, info};
+use tracing::{debug, info, span, Level};
 
 use ibc::core::ics02_client::client_state::ClientState;
 use ibc::core::ics04_channel::context::calculate_block_delay;
@@ -14,6 +14,7 @@ use crate::chain::handle::ChainHandle;
 use crate::chain::requests::IncludeProof;
 use crate::chain::requests::QueryClientStateRequest;
 use crate::chain::requests::QueryHeight;
+use crate::chain::tracking::CorrelationId;
 use crate::chain::tracking::TrackedMsgs;
 use crate::chain::tracking::TrackingId;
 use crate::link::error::LinkError;
@@ -97,6 +98,10 @@ pub struct OperationalData {
     /// A unique ID for tracking this batch of events starting from when they were received
     /// until the transactions corresponding to those events is submitted.
     pub tracking_id: TrackingId,
+    /// A short ID assigned to this piece of operational data when it is created, recorded
+    /// along its scheduling, client updates, and the broadcast and confirmation of its
+    /// transactions, and kept when its messages are regenerated to be retried.
+    pub correlation_id: CorrelationId,
     /// Stores `Some(ConnectionDelay)` if the delay is non-zero and `None` otherwise
     connection_delay: Option<ConnectionDelay>,
 }
@@ -120,6 +125,7 @@ impl OperationalData {
             target,
             connection_delay,
             tracking_id,
+            correlation_id: CorrelationId::new(),
         }
     }
 
@@ -131,6 +137,7 @@ impl OperationalData {
     pub fn info(&self) -> OperationalInfo {
         OperationalInfo {
             tracking_id: self.tracking_id,
+            correlation_id: self.correlation_id,
             target: self.target,
             proofs_height: self.proofs_height,
             batch_len: self.batch.len(),
@@ -154,6 +161,9 @@ impl OperationalData {
         &self,
         relay_path: &RelayPath<ChainA, ChainB>,
     ) -> Result<TrackedMsgs, LinkError> {
+        let _span =
+            span!(Level::DEBUG, "assemble", correlation_id = %self.correlation_id).entered();
+
         // For zero delay we prepend the client update msgs.
         let client_update_msg = if !self.conn_delay_needed() {
             let update_height = self.proofs_height.increment();
@@ -201,12 +211,16 @@ impl OperationalData {
             };
 
             if client_state.is_frozen() {
-                return Ok(TrackedMsgs::new(vec![], self.tracking_id));
+                return Ok(TrackedMsgs::new(vec![], self.tracking_id)
+                    .with_correlation_id(self.correlation_id));
             } else {
                 None
             }
         };
 
+        // Check the proofs before paying fees for messages which would fail on-chain
+        relay_path.verify_proofs(self, client_update_msg.as_ref())?;
+
         let msgs: Vec<Any> = match client_update_msg {
             Some(client_update) => iter::once(client_update)
                 .chain(self.batch.iter().map(|gm| gm.msg.clone()))
@@ -214,7 +228,7 @@ impl OperationalData {
             None => self.batch.iter().map(|gm| gm.msg.clone()).collect(),
         };
 
-        let tm = TrackedMsgs::new(msgs, self.tracking_id);
+        let tm = TrackedMsgs::new(msgs, self.tracking_id).with_correlation_id(self.correlation_id);
 
         info!("assembled batch of {} message(s)", tm.messages().len());
 
@@ -375,6 +389,7 @@ impl ConnectionDelay {
 /// out of [`OperationalData`] for e.g. logging purposes.
 pub struct OperationalInfo {
     tracking_id: TrackingId,
+    correlation_id: CorrelationId,
     target: OperationalDataTarget,
     proofs_height: Height,
     batch_len: usize,
@@ -385,6 +400,10 @@ impl OperationalInfo {
         self.target
     }
 
+    pub fn correlation_id(&self) -> CorrelationId {
+        self.correlation_id
+    }
+
     /// Returns the length of the assembled batch of in-transit messages.
     pub fn batch_len(&self) -> usize {
         self.batch_len
@@ -400,3 +419,55 @@ impl fmt::Display for OperationalInfo {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::chain::block_time::{MaxBlockTime, MaxBlockTimeSource};
+
+    fn delay_at(update_height: u64) -> ConnectionDelay {
+        let mut delay = ConnectionDelay::new(Duration::from_secs(60));
+        delay.update_height = Some(Height::new(0, update_height).unwrap());
+        delay
+    }
+
+    #[test]
+    fn conn_block_delay_with_on_chain_max_block_time() {
+        let max_block_time =
+            MaxBlockTime::new(Some(Duration::from_secs(20)), Duration::from_secs(30));
+        assert_eq!(max_block_time.source(), MaxBlockTimeSource::Chain);
+
+        let delay = delay_at(10);
+        let block_delay = delay.conn_block_delay(max_block_time.value());
+
+        assert_eq!(block_delay, 3);
+        assert_eq!(
+            delay.conn_block_delay_remaining(block_delay, Height::new(0, 11).unwrap()),
+            2
+        );
+        assert_eq!(
+            delay.conn_block_delay_remaining(block_delay, Height::new(0, 13).unwrap()),
+            0
+        );
+    }
+
+    #[test]
+    fn conn_block_delay_with_config_max_block_time() {
+        let max_block_time = MaxBlockTime::new(None, Duration::from_secs(30));
+        assert_eq!(max_block_time.source(), MaxBlockTimeSource::Config);
+
+        let delay = delay_at(10);
+        let block_delay = delay.conn_block_delay(max_block_time.value());
+
+        assert_eq!(block_delay, 2);
+        assert_eq!(
+            delay.conn_block_delay_remaining(block_delay, Height::new(0, 11).unwrap()),
+            1
+        );
+        assert_eq!(
+            delay.conn_block_delay_remaining(block_delay, Height::new(0, 12).unwrap()),
+            0
+        );
+    }
+}
@@ -2,15 +2,20 @@ use core::iter::Iterator;
 use core::time::Duration;
 use std::time::Instant;
 
-use tracing::{debug, error, trace, trace_span};
+use tracing::{debug, error, info, trace, trace_span, warn};
 
 use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
 use ibc::events::IbcEvent;
 
 use crate::chain::requests::{QueryTxHash, QueryTxRequest};
-use crate::chain::tracking::TrackingId;
+use crate::chain::tracking::{CorrelationId, TrackingId};
 use crate::error::Error as RelayerError;
-use crate::link::{error::LinkError, RelayPath};
+use crate::link::submitted::{ChannelEndKey, InFlightSequences, SubmittedTxs};
+use crate::link::tx_error::{skip_prepended_msgs, TxErrorOutcome};
+use crate::link::{
+    error::{LinkError, LinkErrorDetail},
+    RelayPath,
+};
 use crate::telemetry;
 use crate::util::queue::Queue;
 use crate::{
@@ -33,12 +38,41 @@ pub struct PendingData {
     pub tx_hashes: TxHashes,
     pub submit_time: Instant,
     pub error_events: Vec<IbcEvent>,
+    /// The number of messages submitted ahead of those of the operational data,
+    /// i.e. the client update, if any
+    pub prepended: usize,
 }
 
 impl PendingData {
     pub fn tracking_id(&self) -> TrackingId {
         self.original_od.tracking_id
     }
+
+    pub fn correlation_id(&self) -> CorrelationId {
+        self.original_od.correlation_id
+    }
+
+    /// The error of the first of these transactions which failed, if any, among the given
+    /// `events` of the transactions, indexing the message which failed, if reported, among
+    /// those of the operational data.
+    ///
+    /// The index is only known if the messages were submitted in a single transaction,
+    /// and is otherwise that of the message among those of its own transaction.
+    fn tx_error(&self, events: &[IbcEvent]) -> Option<IbcEvent> {
+        let error = events.iter().find_map(|event| match event {
+            IbcEvent::ChainError(error) => Some(error.clone()),
+            _ => None,
+        })?;
+
+        if self.tx_hashes.0.len() + self.error_events.len() == 1 {
+            Some(skip_prepended_msgs(
+                IbcEvent::ChainError(error),
+                self.prepended,
+            ))
+        } else {
+            Some(IbcEvent::ChainError(error.with_msg_index(None)))
+        }
+    }
 }
 
 /// Stores all pending data
@@ -49,6 +83,11 @@ pub struct PendingTxs<Chain> {
     pub port_id: PortId,
     pub counterparty_chain_id: ChainId,
     pub pending_queue: Queue<PendingData>,
+    /// The record of the transactions submitted on this channel end, shared
+    /// with the previous and next workers of the path
+    pub submitted: SubmittedTxs,
+    /// When this channel end was taken over from the previous workers of the path
+    pub taken_over_at: Instant,
 }
 
 impl<Chain> PendingTxs<Chain> {
@@ -64,6 +103,8 @@ impl<Chain> PendingTxs<Chain> {
             port_id,
             counterparty_chain_id,
             pending_queue: Queue::new(),
+            submitted: SubmittedTxs::new(),
+            taken_over_at: Instant::now(),
         }
     }
 }
@@ -73,20 +114,67 @@ impl<Chain: ChainHandle> PendingTxs<Chain> {
         self.chain.id()
     }
 
+    fn channel_end(&self) -> ChannelEndKey {
+        (
+            self.chain_id(),
+            self.port_id.clone(),
+            self.channel_id.clone(),
+        )
+    }
+
+    /// Shares the given record of the submitted transactions with the previous workers
+    /// of the path, and resumes the confirmation of the transactions they submitted on
+    /// this channel end, if any.
+    pub fn take_over(&mut self, submitted: SubmittedTxs) {
+        let taken_over_at = Instant::now();
+        let previous = submitted.submitted_before(&self.channel_end(), taken_over_at);
+
+        if !previous.is_empty() {
+            info!(
+                chain = %self.chain_id(),
+                counterparty_chain = %self.counterparty_chain_id,
+                port = %self.port_id,
+                channel = %self.channel_id,
+                "confirming {} transaction(s) submitted by the previous worker before clearing their packets",
+                previous.len()
+            );
+        }
+
+        for pending in previous {
+            self.pending_queue.push_back(pending);
+        }
+
+        self.submitted = submitted;
+        self.taken_over_at = taken_over_at;
+    }
+
+    /// The packets and acknowledgements relayed on this channel end by the transactions of
+    /// the previous workers of the path, which are neither confirmed nor timed out yet.
+    pub fn in_flight_from_previous_workers(&self) -> InFlightSequences {
+        let mut in_flight = InFlightSequences::default();
+
+        for pending in self
+            .submitted
+            .submitted_before(&self.channel_end(), self.taken_over_at)
+        {
+            in_flight.add(&pending);
+        }
+
+        in_flight
+    }
+
     /// Insert a new pending transaction to the back of the queue.
     pub fn insert_new_pending_tx(&self, r: AsyncReply, od: OperationalData) {
         let mut tx_hashes = Vec::new();
         let mut error_events = Vec::new();
 
         for response in r.responses.into_iter() {
-            if response.code.is_err() {
+            if let Some(error_event) = response.check_tx_error(&self.chain_id()) {
                 // If the response is an error, we do not want to check for the
                 // transaction confirmation status because it is never going to
                 // be committed. Instead we convert it into an error event and
-                // store it to be returned in the RelaySummary after all other
-                // transactions have been confirmed.
-                // This is not ideal, but is just to follow the previous synchronous
-                // behavior of handling the OperationalData.
+                // store it to be handled, and returned in the RelaySummary, after
+                // all other transactions have been confirmed.
 
                 let span = trace_span!(
                     "inserting new pending txs",
@@ -103,16 +191,9 @@ impl<Chain: ChainHandle> PendingTxs<Chain> {
                     response
                 );
 
-                let error_event = IbcEvent::ChainError(format!(
-                    "deliver_tx on chain {} for Tx hash {} reports error: code={:?}, log={:?}",
-                    self.chain_id(),
-                    response.hash,
-                    response.code,
-                    response.log
-                ));
                 error_events.push(error_event);
             } else {
-                tx_hashes.push(response.hash);
+                tx_hashes.push(response.response.hash);
             }
         }
 
@@ -121,8 +202,13 @@ impl<Chain: ChainHandle> PendingTxs<Chain> {
             tx_hashes: TxHashes(tx_hashes),
             submit_time: Instant::now(),
             error_events,
+            prepended: r.prepended,
         };
 
+        if !u.tx_hashes.0.is_empty() {
+            self.submitted.record(&self.channel_end(), u.clone());
+        }
+
         self.pending_queue.push_back(u);
     }
 
@@ -161,7 +247,10 @@ impl<Chain: ChainHandle> PendingTxs<Chain> {
             let submit_time = &pending.submit_time;
 
             if tx_hashes.0.is_empty() {
-                return Ok(Some(RelaySummary::from_events(pending.error_events)));
+                let events = pending.error_events.clone();
+                self.handle_failed_tx(pending, &events, relay_path, resubmit)?;
+
+                return Ok(Some(RelaySummary::from_events(events)));
             }
 
             let span = trace_span!(
@@ -170,6 +259,7 @@ impl<Chain: ChainHandle> PendingTxs<Chain> {
                 counterparty_chain = %self.counterparty_chain_id,
                 port = %self.port_id,
                 channel = %self.channel_id,
+                correlation_id = %pending.correlation_id(),
             );
 
             let _guard = span.enter();
@@ -203,6 +293,7 @@ impl<Chain: ChainHandle> PendingTxs<Chain> {
 
                                 match new_od.map(f) {
                                     Some(Ok(reply)) => {
+                                        self.submitted.forget(&self.channel_end(), tx_hashes);
                                         self.insert_new_pending_tx(reply, pending.original_od);
                                         Ok(None)
                                     }
@@ -212,12 +303,14 @@ impl<Chain: ChainHandle> PendingTxs<Chain> {
                                     }
                                     None => {
                                         // No operational data was regenerated; nothing to resubmit
+                                        self.submitted.forget(&self.channel_end(), tx_hashes);
                                         Ok(None)
                                     }
                                 }
                             }
                             None => {
                                 // `clear_interval != 0` such that resubmission has been disabled
+                                self.submitted.forget(&self.channel_end(), tx_hashes);
                                 Ok(None)
                             }
                         }
@@ -236,6 +329,7 @@ impl<Chain: ChainHandle> PendingTxs<Chain> {
 
                     debug!(
                         tracking_id = %pending.tracking_id(),
+                        correlation_id = %pending.correlation_id(),
                         elapsed = ?pending.submit_time.elapsed(),
                         tx_hashes = %tx_hashes,
                         "transactions confirmed",
@@ -251,8 +345,12 @@ impl<Chain: ChainHandle> PendingTxs<Chain> {
                         &self.counterparty_chain_id
                     );
 
+                    self.submitted.forget(&self.channel_end(), tx_hashes);
+
                     // Append the events corresponding to errors from the pending tx.
-                    events.extend(pending.error_events);
+                    events.extend(pending.error_events.iter().cloned());
+
+                    self.handle_failed_tx(pending, &events, relay_path, resubmit)?;
 
                     // Convert the events to RelaySummary and return them.
                     Ok(Some(RelaySummary::from_events(events)))
@@ -287,4 +385,82 @@ impl<Chain: ChainHandle> PendingTxs<Chain> {
             Ok(None)
         }
     }
+
+    /// Handles the messages of the given pending data, if one of its transactions failed
+    /// with an error reported among the given `events`, as classified by [`TxErrorOutcome`].
+    ///
+    /// As on timeouts, the messages to retry are only resubmitted if a `resubmit` closure
+    /// is provided, and are otherwise left for the packets to be cleared.
+    fn handle_failed_tx<ChainA: ChainHandle, ChainB: ChainHandle>(
+        &self,
+        pending: PendingData,
+        events: &[IbcEvent],
+        relay_path: &RelayPath<ChainA, ChainB>,
+        resubmit: Option<impl FnOnce(OperationalData) -> Result<AsyncReply, LinkError>>,
+    ) -> Result<(), LinkError> {
+        let error = match pending.tx_error(events) {
+            Some(error) => error,
+            None => return Ok(()),
+        };
+
+        let mut od = pending.original_od;
+        let action = relay_path.tx_error_action(od.target, &error);
+
+        match TxErrorOutcome::new(action, &error, od.batch.len()) {
+            TxErrorOutcome::Retry => {
+                error!("error {}", error);
+            }
+            TxErrorOutcome::DropMessage { msg_index, alert } => {
+                let dropped = od.batch.remove(msg_index);
+
+                if alert {
+                    warn!(
+                        "dropping message for {} which would fail again: {}",
+                        dropped.event, error
+                    );
+                } else {
+                    debug!(
+                        "dropping message for {} which would fail again: {}",
+                        dropped.event, error
+                    );
+                }
+
+                if od.batch.is_empty() {
+                    return Ok(());
+                }
+            }
+            TxErrorOutcome::DropAll => {
+                debug!("dropping messages which would fail again: {}", error);
+                return Ok(());
+            }
+            TxErrorOutcome::KeepQueued => {
+                warn!(
+                    "keeping messages queued until the error is resolved: {}",
+                    error
+                );
+                relay_path.requeue_operational_data(od);
+                return Ok(());
+            }
+        }
+
+        let f = match resubmit {
+            Some(f) => f,
+            None => return Ok(()),
+        };
+
+        match relay_path.regenerate_operational_data(od.clone()).map(f) {
+            Some(Ok(reply)) => {
+                self.insert_new_pending_tx(reply, od);
+                Ok(())
+            }
+            Some(Err(e)) => {
+                // Unless the messages are kept queued until the error is resolved
+                if let LinkErrorDetail::Send(_) = e.detail() {
+                    relay_path.requeue_operational_data(od);
+                }
+                Err(e)
+            }
+            None => Ok(()),
+        }
+    }
 }
@@ -0,0 +1,485 @@
+//! Local verification of the proofs carried by packet messages.
+//!
+//! When `verify_proofs_locally` is enabled in the configuration of a chain, the proofs
+//! queried from that chain are verified before the messages carrying them are submitted
+//! to the counterparty chain. They are checked against the commitment root, i.e. the app
+//! hash, which the counterparty client holds at the height following the proofs height,
+//! or which the client update prepended to the messages will install at that height.
+//!
+//! A proof which does not match was most likely queried from a node on a minority fork,
+//! and the transaction carrying it would fail on-chain, after paying the fees.
+
+use ibc::clients::ics07_tendermint::consensus_state::ConsensusState as TmConsensusState;
+use ibc::core::ics02_client::client_consensus::ConsensusState;
+use ibc::core::ics02_client::client_state::AnyClientState;
+use ibc::core::ics02_client::header::AnyHeader;
+use ibc::core::ics02_client::msgs::update_client::{self, MsgUpdateAnyClient};
+use ibc::core::ics04_channel::commitment::compute_ack_commitment;
+use ibc::core::ics04_channel::msgs::{
+    acknowledgement::{self, MsgAcknowledgement},
+    recv_packet::{self, MsgRecvPacket},
+    timeout::{self, MsgTimeout},
+    timeout_on_close::{self, MsgTimeoutOnClose},
+};
+use ibc::core::ics04_channel::packet::{Packet, Sequence};
+use ibc::core::ics23_commitment::commitment::{
+    CommitmentPrefix, CommitmentProofBytes, CommitmentRoot,
+};
+use ibc::core::ics23_commitment::merkle::{apply_prefix, MerkleProof};
+use ibc::core::ics23_commitment::specs::ProofSpecs;
+use ibc::core::ics24_host::identifier::ClientId;
+use ibc::core::ics24_host::path::{AcksPath, CommitmentsPath, Path, ReceiptsPath, SeqRecvsPath};
+use ibc::downcast;
+use ibc::Height;
+use ibc_proto::google::protobuf::Any;
+use ibc_proto::ibc::core::commitment::v1::MerkleProof as RawMerkleProof;
+use sha2::{Digest, Sha256};
+use tendermint_proto::Protobuf;
+use tracing::{debug, warn};
+
+use crate::chain::handle::ChainHandle;
+use crate::chain::requests::{
+    IncludeProof, QueryClientStateRequest, QueryConsensusStateRequest, QueryHeight,
+};
+use crate::link::error::LinkError;
+use crate::telemetry;
+
+/// The chains of a relaying path whose proofs are verified locally before
+/// submitting them to the counterparty chain.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct VerifyProofs {
+    /// Verify the proofs queried from the source chain of the path.
+    pub src: bool,
+    /// Verify the proofs queried from the destination chain of the path.
+    pub dst: bool,
+}
+
+/// The proof carried by a packet message, along with the path and the value it proves.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MessageProof {
+    pub proof: CommitmentProofBytes,
+    pub path: Path,
+    /// The value stored at `path`, or `None` for a proof of absence.
+    pub value: Option<Vec<u8>>,
+}
+
+impl MessageProof {
+    /// Extracts the proof carried by the given message, or `None` if it is not a packet message.
+    ///
+    /// What a timeout proves depends on whether the channel of the packet is `ordered`.
+    pub fn from_msg(msg: &Any, ordered: bool) -> Result<Option<Self>, LinkError> {
+        let decode_error = |e: tendermint_proto::Error| {
+            LinkError::malformed_message(msg.type_url.clone(), e.to_string())
+        };
+
+        let proof = match msg.type_url.as_str() {
+            recv_packet::TYPE_URL => {
+                let msg = MsgRecvPacket::decode_vec(&msg.value).map_err(decode_error)?;
+
+                Self {
+                    value: Some(packet_commitment(&msg.packet)),
+                    path: CommitmentsPath {
+                        port_id: msg.packet.source_port,
+                        channel_id: msg.packet.source_channel,
+                        sequence: msg.packet.sequence,
+                    }
+                    .into(),
+                    proof: msg.proofs.object_proof().clone(),
+                }
+            }
+            acknowledgement::TYPE_URL => {
+                let msg = MsgAcknowledgement::decode_vec(&msg.value).map_err(decode_error)?;
+
+                Self {
+                    value: Some(compute_ack_commitment(msg.acknowledgement.as_ref()).into_vec()),
+                    path: AcksPath {
+                        port_id: msg.packet.destination_port,
+                        channel_id: msg.packet.destination_channel,
+                        sequence: msg.packet.sequence,
+                    }
+                    .into(),
+                    proof: msg.proofs.object_proof().clone(),
+                }
+            }
+            timeout::TYPE_URL => {
+                let msg = MsgTimeout::decode_vec(&msg.value).map_err(decode_error)?;
+                let (path, value) = timeout_path(&msg.packet, msg.next_sequence_recv, ordered);
+
+                Self {
+                    path,
+                    value,
+                    proof: msg.proofs.object_proof().clone(),
+                }
+            }
+            timeout_on_close::TYPE_URL => {
+                let msg = MsgTimeoutOnClose::decode_vec(&msg.value).map_err(decode_error)?;
+                // The proofs of a timeout on close are built out of the packet receipt
+                let (path, value) = timeout_path(&msg.packet, msg.next_sequence_recv, false);
+
+                Self {
+                    path,
+                    value,
+                    proof: msg.proofs.object_proof().clone(),
+                }
+            }
+            _ => return Ok(None),
+        };
+
+        Ok(Some(proof))
+    }
+
+    /// Verifies the proof against the given commitment root, returning
+    /// the reason of the failure if it does not match.
+    pub fn verify(
+        &self,
+        prefix: &CommitmentPrefix,
+        specs: &ProofSpecs,
+        root: &CommitmentRoot,
+    ) -> Result<(), String> {
+        let merkle_path = apply_prefix(prefix, vec![self.path.to_string()]);
+        let merkle_proof: MerkleProof = RawMerkleProof::try_from(self.proof.clone())
+            .map_err(|e| e.to_string())?
+            .into();
+
+        match &self.value {
+            Some(value) => merkle_proof.verify_membership(
+                specs,
+                root.clone().into(),
+                merkle_path,
+                value.clone(),
+                0,
+            ),
+            None => merkle_proof.verify_non_membership(specs, root.clone().into(), merkle_path),
+        }
+        .map_err(|e| e.to_string())
+    }
+}
+
+/// The commitment to a packet stored by its source chain, as computed by
+/// [`ibc::core::ics04_channel::context::ChannelReader::packet_commitment`].
+fn packet_commitment(packet: &Packet) -> Vec<u8> {
+    let mut hash_input = packet
+        .timeout_timestamp
+        .nanoseconds()
+        .to_be_bytes()
+        .to_vec();
+    hash_input.extend(
+        packet
+            .timeout_height
+            .commitment_revision_number()
+            .to_be_bytes(),
+    );
+    hash_input.extend(
+        packet
+            .timeout_height
+            .commitment_revision_height()
+            .to_be_bytes(),
+    );
+    hash_input.extend(Sha256::digest(&packet.data));
+
+    Sha256::digest(&hash_input).to_vec()
+}
+
+/// The path and value proven by a timeout: the next sequence to receive on an
+/// ordered channel, or the absence of a receipt for the packet otherwise.
+fn timeout_path(
+    packet: &Packet,
+    next_sequence_recv: Sequence,
+    ordered: bool,
+) -> (Path, Option<Vec<u8>>) {
+    if ordered {
+        let mut seq_bytes = Vec::new();
+        prost::Message::encode(&u64::from(next_sequence_recv), &mut seq_bytes)
+            .expect("encoding a u64 into a vector cannot fail");
+
+        (
+            SeqRecvsPath(
+                packet.destination_port.clone(),
+                packet.destination_channel.clone(),
+            )
+            .into(),
+            Some(seq_bytes),
+        )
+    } else {
+        (
+            ReceiptsPath {
+                port_id: packet.destination_port.clone(),
+                channel_id: packet.destination_channel.clone(),
+                sequence: packet.sequence,
+            }
+            .into(),
+            None,
+        )
+    }
+}
+
+/// The commitment root installed by the given client update message,
+/// if it updates the client to the given height.
+fn root_from_update(msg: &Any, height: Height) -> Option<CommitmentRoot> {
+    if msg.type_url != update_client::TYPE_URL {
+        return None;
+    }
+
+    let msg = MsgUpdateAnyClient::decode_vec(&msg.value).ok()?;
+    let header = downcast!(msg.header => AnyHeader::Tendermint)?;
+
+    if header.height() != height {
+        return None;
+    }
+
+    Some(TmConsensusState::from(header).root)
+}
+
+/// Verifies the proofs carried by the given messages, which were queried from
+/// `proof_chain` at `proofs_height`, before submitting them to `target_chain`,
+/// whose client `client_id` tracks `proof_chain`.
+///
+/// The commitment root is taken from `client_update`, the client update message
+/// prepended to the messages, if any, or else from the consensus state of the client.
+///
+/// Proofs are only verified for Tendermint clients. Returns a `ForkedProof` error
+/// for the first proof which does not match the commitment root.
+pub fn verify_proofs<'a, ProofChain: ChainHandle, TargetChain: ChainHandle>(
+    proof_chain: &ProofChain,
+    target_chain: &TargetChain,
+    client_id: &ClientId,
+    proofs_height: Height,
+    msgs: impl Iterator<Item = &'a Any>,
+    client_update: Option<&Any>,
+    ordered: bool,
+) -> Result<(), LinkError> {
+    let mut proofs = Vec::new();
+    for msg in msgs {
+        proofs.extend(MessageProof::from_msg(msg, ordered)?);
+    }
+
+    if proofs.is_empty() {
+        return Ok(());
+    }
+
+    let (client_state, _) = target_chain
+        .query_client_state(
+            QueryClientStateRequest {
+                client_id: client_id.clone(),
+                height: QueryHeight::Latest,
+            },
+            IncludeProof::No,
+        )
+        .map_err(|e| LinkError::query(target_chain.id(), e))?;
+
+    let specs = match downcast!(client_state => AnyClientState::Tendermint) {
+        Some(client_state) => client_state.proof_specs,
+        None => {
+            debug!("skipping the local verification of the proofs for a non-Tendermint client");
+            return Ok(());
+        }
+    };
+
+    let root_height = proofs_height.increment();
+
+    let root = match client_update.and_then(|msg| root_from_update(msg, root_height)) {
+        Some(root) => root,
+        None => {
+            let (consensus_state, _) = target_chain
+                .query_consensus_state(
+                    QueryConsensusStateRequest {
+                        client_id: client_id.clone(),
+                        consensus_height: root_height,
+                        query_height: QueryHeight::Latest,
+                    },
+                    IncludeProof::No,
+                )
+                .map_err(|e| LinkError::query(target_chain.id(), e))?;
+
+            consensus_state.root().clone()
+        }
+    };
+
+    let prefix = proof_chain
+        .query_commitment_prefix()
+        .map_err(|e| LinkError::query(proof_chain.id(), e))?;
+
+    for proof in proofs {
+        if let Err(reason) = proof.verify(&prefix, &specs, &root) {
+            warn!(
+                "proof of {} queried from {} at height {} does not match the app hash at height {}: {}",
+                proof.path,
+                proof_chain.id(),
+                proofs_height,
+                root_height,
+                reason
+            );
+
+            telemetry!(forked_proof_detected, &proof_chain.id());
+
+            return Err(LinkError::forked_proof(
+                proof_chain.id(),
+                proofs_height,
+                proof.path.to_string(),
+                reason,
+            ));
+        }
+    }
+
+    debug!(
+        "verified locally the proofs queried from {} at height {}",
+        proof_chain.id(),
+        proofs_height
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ibc::core::ics04_channel::msgs::acknowledgement::Acknowledgement;
+    use ibc::core::ics04_channel::timeout::TimeoutHeight;
+    use ibc::core::ics24_host::identifier::{ChannelId, PortId};
+    use ibc::proofs::Proofs;
+    use ibc::timestamp::Timestamp;
+    use ibc::tx_msg::Msg;
+
+    fn packet() -> Packet {
+        Packet {
+            sequence: Sequence::from(7),
+            source_port: PortId::transfer(),
+            source_channel: ChannelId::new(0),
+            destination_port: PortId::transfer(),
+            destination_channel: ChannelId::new(1),
+            data: b"packet data".to_vec(),
+            timeout_height: TimeoutHeight::At(Height::new(1, 100).unwrap()),
+            timeout_timestamp: Timestamp::from_nanoseconds(1_000).unwrap(),
+        }
+    }
+
+    fn proofs() -> Proofs {
+        Proofs::new(
+            CommitmentProofBytes::try_from(vec![1, 2, 3]).unwrap(),
+            None,
+            None,
+            None,
+            Height::new(1, 10).unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn proof_of(msg: impl Msg, ordered: bool) -> MessageProof {
+        MessageProof::from_msg(&msg.to_any(), ordered)
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn recv_packet_proves_the_packet_commitment() {
+        let msg = MsgRecvPacket::new(packet(), proofs(), "signer".parse().unwrap());
+        let proof = proof_of(msg, false);
+
+        assert_eq!(
+            proof.path.to_string(),
+            "commitments/ports/transfer/channels/channel-0/sequences/7"
+        );
+        assert_eq!(proof.value, Some(packet_commitment(&packet())));
+        assert_eq!(&proof.proof, proofs().object_proof());
+    }
+
+    #[test]
+    fn packet_commitment_covers_the_timeouts_and_data() {
+        let mut other = packet();
+        other.timeout_timestamp = Timestamp::from_nanoseconds(2_000).unwrap();
+        assert_ne!(packet_commitment(&packet()), packet_commitment(&other));
+
+        let mut other = packet();
+        other.data = b"other data".to_vec();
+        assert_ne!(packet_commitment(&packet()), packet_commitment(&other));
+
+        assert_eq!(packet_commitment(&packet()).len(), 32);
+    }
+
+    #[test]
+    fn acknowledgement_proves_the_ack_commitment() {
+        let ack = Acknowledgement::from(b"ack".to_vec());
+        let msg = MsgAcknowledgement::new(packet(), ack, proofs(), "signer".parse().unwrap());
+        let proof = proof_of(msg, false);
+
+        assert_eq!(
+            proof.path.to_string(),
+            "acks/ports/transfer/channels/channel-1/sequences/7"
+        );
+        assert_eq!(proof.value, Some(Sha256::digest(b"ack").to_vec()));
+    }
+
+    #[test]
+    fn timeout_proves_the_receipt_absence_on_unordered_channels() {
+        let msg = MsgTimeout::new(
+            packet(),
+            Sequence::from(7),
+            proofs(),
+            "signer".parse().unwrap(),
+        );
+        let proof = proof_of(msg, false);
+
+        assert_eq!(
+            proof.path.to_string(),
+            "receipts/ports/transfer/channels/channel-1/sequences/7"
+        );
+        assert_eq!(proof.value, None);
+    }
+
+    #[test]
+    fn timeout_proves_the_next_sequence_recv_on_ordered_channels() {
+        let msg = MsgTimeout::new(
+            packet(),
+            Sequence::from(5),
+            proofs(),
+            "signer".parse().unwrap(),
+        );
+        let proof = proof_of(msg, true);
+
+        assert_eq!(
+            proof.path.to_string(),
+            "nextSequenceRecv/ports/transfer/channels/channel-1"
+        );
+        // The sequence is encoded as the first field of a protobuf message
+        assert_eq!(proof.value, Some(vec![0x08, 5]));
+    }
+
+    #[test]
+    fn timeout_on_close_proves_the_receipt_absence() {
+        let msg = MsgTimeoutOnClose::new(
+            packet(),
+            Sequence::from(7),
+            proofs(),
+            "signer".parse().unwrap(),
+        );
+        let proof = proof_of(msg, true);
+
+        assert_eq!(
+            proof.path.to_string(),
+            "receipts/ports/transfer/channels/channel-1/sequences/7"
+        );
+        assert_eq!(proof.value, None);
+    }
+
+    #[test]
+    fn other_messages_carry_no_packet_proof() {
+        let msg = Any {
+            type_url: update_client::TYPE_URL.to_string(),
+            value: vec![],
+        };
+
+        assert_eq!(MessageProof::from_msg(&msg, false).unwrap(), None);
+    }
+
+    #[test]
+    fn invalid_proof_does_not_verify() {
+        let msg = MsgRecvPacket::new(packet(), proofs(), "signer".parse().unwrap());
+        let proof = proof_of(msg, false);
+
+        let prefix = CommitmentPrefix::try_from(b"ibc".to_vec()).unwrap();
+        let root = CommitmentRoot::from_bytes(&[0xAB; 32]);
+
+        assert!(proof.verify(&prefix, &ProofSpecs::cosmos(), &root).is_err());
+    }
+}
@@ -1,6 +1,7 @@
 use alloc::collections::BTreeMap as HashMap;
 use alloc::collections::VecDeque;
 use std::ops::Sub;
+use std::sync::{Mutex, MutexGuard};
 use std::time::{Duration, Instant};
 
 use ibc_proto::google::protobuf::Any;
@@ -9,24 +10,32 @@ use tracing::{debug, error, info, span, trace, warn, Level};
 
 use crate::chain::counterparty::unreceived_acknowledgements;
 use crate::chain::counterparty::unreceived_packets;
+use crate::chain::counterparty::unreceived_packets_sequences;
 use crate::chain::endpoint::ChainStatus;
 use crate::chain::handle::ChainHandle;
 use crate::chain::requests::IncludeProof;
 use crate::chain::requests::QueryChannelRequest;
 use crate::chain::requests::QueryClientEventRequest;
+use crate::chain::requests::QueryClientStateRequest;
+use crate::chain::requests::QueryConsensusStateRequest;
 use crate::chain::requests::QueryHeight;
 use crate::chain::requests::QueryHostConsensusStateRequest;
 use crate::chain::requests::QueryNextSequenceReceiveRequest;
+use crate::chain::requests::QueryPacketAcknowledgementRequest;
 use crate::chain::requests::QueryPacketCommitmentRequest;
 use crate::chain::requests::QueryTxRequest;
 use crate::chain::requests::QueryUnreceivedAcksRequest;
 use crate::chain::requests::QueryUnreceivedPacketsRequest;
+use crate::chain::tracking::CorrelationId;
 use crate::chain::tracking::TrackedMsgs;
 use crate::chain::tracking::TrackingId;
 use crate::channel::error::ChannelError;
+use crate::channel::version::ChannelVersions;
 use crate::channel::Channel;
+use crate::config::{RelayMode, TxErrorAction};
 use crate::event::monitor::EventBatch;
 use crate::foreign_client::{ForeignClient, ForeignClientError};
+use crate::link::deferred_timeout::{DeferredTimeout, DeferredTimeouts};
 use crate::link::error::{self, LinkError};
 use crate::link::operational_data::{
     OperationalData, OperationalDataTarget, TrackedEvents, TransitMessage,
@@ -34,9 +43,14 @@ use crate::link::operational_data::{
 use crate::link::packet_events::query_packet_events_with;
 use crate::link::packet_events::query_send_packet_events;
 use crate::link::packet_events::query_write_ack_events;
+use crate::link::packet_events::QUERY_RESULT_LIMIT;
 use crate::link::pending::PendingTxs;
+use crate::link::proof_check::{self, VerifyProofs};
 use crate::link::relay_sender::{AsyncReply, SubmitReply};
 use crate::link::relay_summary::RelaySummary;
+use crate::link::sent_sequences::{unaccounted_sequences, SentSequences, SkippedSequences};
+use crate::link::submitted::SubmittedTxs;
+use crate::link::tx_error::{skip_prepended_msgs, TxErrorCode, TxErrorOutcome};
 use crate::link::{pending, relay_sender};
 use crate::path::PathIdentifiers;
 use crate::telemetry;
@@ -49,13 +63,14 @@ use ibc::{
         },
         ics04_channel::{
             channel::{ChannelEnd, Order, State as ChannelState},
+            commitment::compute_ack_commitment,
             events::{SendPacket, WriteAcknowledgement},
             msgs::{
                 acknowledgement::MsgAcknowledgement, chan_close_confirm::MsgChannelCloseConfirm,
                 recv_packet::MsgRecvPacket, timeout::MsgTimeout,
                 timeout_on_close::MsgTimeoutOnClose,
             },
-            packet::{Packet, PacketMsgType},
+            packet::{Packet, PacketMsgType, Sequence},
         },
         ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
     },
@@ -89,6 +104,72 @@ impl Resubmit {
     }
 }
 
+/// Progress of the clearing of the packets pending on a relaying path,
+/// reported each time a chunk of pending packets has been scheduled for relaying.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ClearProgress {
+    /// How many of the pending packets and acknowledgements were cleared so far
+    pub cleared: usize,
+    /// How many packets and acknowledgements were pending when the clearing started
+    pub pending: usize,
+}
+
+impl ClearProgress {
+    fn new(pending: usize) -> Self {
+        Self {
+            cleared: 0,
+            pending,
+        }
+    }
+
+    fn advance(&mut self, cleared: usize) {
+        self.cleared = (self.cleared + cleared).min(self.pending);
+    }
+}
+
+/// The packets and acknowledgements pending on a relaying path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PendingSequences {
+    /// Packets sent on the source chain and not yet received on the destination chain
+    pub packets: Vec<Sequence>,
+    /// The source chain height at which the packet commitments were queried
+    pub packets_height: Height,
+    /// Packets sent on the destination chain and received on the source chain,
+    /// whose acknowledgement is not yet received on the destination chain
+    pub acks: Vec<Sequence>,
+    /// The source chain height at which the acknowledgements were queried
+    pub acks_height: Height,
+}
+
+impl PendingSequences {
+    fn len(&self) -> usize {
+        self.packets.len() + self.acks.len()
+    }
+}
+
+/// The clearing of the packets and acknowledgements pending on a relaying path,
+/// scheduled one chunk at a time with [`RelayPath::clear_next_chunk`], so that
+/// it can be interrupted in between chunks.
+#[derive(Clone, Debug)]
+pub struct PacketClearing {
+    /// The packets and acknowledgements which are yet to be scheduled
+    remaining: PendingSequences,
+    height: Option<Height>,
+    tracking_id: TrackingId,
+    progress: ClearProgress,
+}
+
+impl PacketClearing {
+    pub fn progress(&self) -> ClearProgress {
+        self.progress
+    }
+
+    /// Whether all the pending packets and acknowledgements have been scheduled
+    pub fn is_done(&self) -> bool {
+        self.remaining.packets.is_empty() && self.remaining.acks.is_empty()
+    }
+}
+
 pub struct RelayPath<ChainA: ChainHandle, ChainB: ChainHandle> {
     channel: Channel<ChainA, ChainB>,
 
@@ -107,11 +188,30 @@ pub struct RelayPath<ChainA: ChainHandle, ChainB: ChainHandle> {
     // Toggle for the transaction confirmation mechanism.
     confirm_txes: bool,
 
+    // The kinds of packet messages relayed on this path, as set in the packet filter.
+    relay_mode: RelayMode,
+
+    // The chains whose proofs are verified locally before submitting them.
+    verify_proofs: VerifyProofs,
+
     // Stores pending (i.e., unconfirmed) operational data.
     // The relaying path periodically tries to confirm these pending
     // transactions if [`confirm_txes`] is true.
     pending_txs_src: PendingTxs<ChainA>,
     pending_txs_dst: PendingTxs<ChainB>,
+
+    // Serialize the relaying of the operational data targeting each chain.
+    // The pipelines targeting the source and the destination chain can thus
+    // run concurrently, while preserving the ordering within each of them.
+    src_pipeline: Mutex<()>,
+    dst_pipeline: Mutex<()>,
+
+    // The timeouts of packets shown to be timed out by the client on the source
+    // chain while the destination chain was unreachable, awaiting their proof.
+    deferred_timeouts: DeferredTimeouts,
+
+    // The sequences reported as skipped by the source chain, only reported once.
+    skipped_sequences: SkippedSequences,
 }
 
 impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
@@ -154,11 +254,50 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
             dst_operational_data: Queue::new(),
 
             confirm_txes: with_tx_confirmation,
+            relay_mode: RelayMode::All,
+            verify_proofs: VerifyProofs::default(),
             pending_txs_src: PendingTxs::new(src_chain, src_channel_id, src_port_id, dst_chain_id),
             pending_txs_dst: PendingTxs::new(dst_chain, dst_channel_id, dst_port_id, src_chain_id),
+
+            src_pipeline: Mutex::new(()),
+            dst_pipeline: Mutex::new(()),
+
+            deferred_timeouts: DeferredTimeouts::new(),
+
+            skipped_sequences: SkippedSequences::new(),
         })
     }
 
+    /// This relaying path, only relaying the kinds of packet messages of the given mode.
+    pub fn with_relay_mode(self, relay_mode: RelayMode) -> Self {
+        Self { relay_mode, ..self }
+    }
+
+    pub fn relay_mode(&self) -> RelayMode {
+        self.relay_mode
+    }
+
+    /// The timeouts awaiting a proof from the destination chain, while it is unreachable.
+    pub fn deferred_timeouts(&self) -> &DeferredTimeouts {
+        &self.deferred_timeouts
+    }
+
+    /// This relaying path, taking over from the previous workers of the path the given
+    /// record of the transactions submitted on its channel ends.
+    pub fn with_submitted_txs(mut self, submitted: SubmittedTxs) -> Self {
+        self.pending_txs_src.take_over(submitted.clone());
+        self.pending_txs_dst.take_over(submitted);
+        self
+    }
+
+    /// This relaying path, verifying locally the proofs queried from the given chains.
+    pub fn with_proof_verification(self, verify_proofs: VerifyProofs) -> Self {
+        Self {
+            verify_proofs,
+            ..self
+        }
+    }
+
     pub fn src_chain(&self) -> &ChainA {
         self.channel.src_chain()
     }
@@ -231,6 +370,14 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
             .map_err(|e| LinkError::channel(ChannelError::query(self.dst_chain().id(), e)))
     }
 
+    /// Fetches the versions of the two ends of the channel.
+    pub fn channel_versions(&self) -> Result<ChannelVersions, LinkError> {
+        Ok(ChannelVersions {
+            src: self.src_channel(QueryHeight::Latest)?.version().clone(),
+            dst: self.dst_channel(QueryHeight::Latest)?.version().clone(),
+        })
+    }
+
     fn src_signer(&self) -> Result<Signer, LinkError> {
         self.src_chain()
             .get_signer()
@@ -292,21 +439,19 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
     }
 
     pub(crate) fn src_max_block_time(&self) -> Result<Duration, LinkError> {
-        // TODO(hu55a1n1): Ideally, we should get the `max_expected_time_per_block` using the
-        // `/genesis` endpoint once it is working in tendermint-rs.
         Ok(self
             .src_chain()
-            .config()
+            .max_block_time()
             .map_err(LinkError::relayer)?
-            .max_block_time)
+            .value())
     }
 
     pub(crate) fn dst_max_block_time(&self) -> Result<Duration, LinkError> {
         Ok(self
             .dst_chain()
-            .config()
+            .max_block_time()
             .map_err(LinkError::relayer)?
-            .max_block_time)
+            .value())
     }
 
     fn unordered_channel(&self) -> bool {
@@ -331,6 +476,41 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
             .map_err(LinkError::client)
     }
 
+    /// Verifies locally the proofs carried by the messages of the given operational
+    /// data, if enabled for the chain they were queried from, against the app hash
+    /// installed by `client_update` or else known to the client of the target chain.
+    pub(crate) fn verify_proofs(
+        &self,
+        odata: &OperationalData,
+        client_update: Option<&Any>,
+    ) -> Result<(), LinkError> {
+        let msgs = odata.batch.iter().map(|gm| &gm.msg);
+
+        match odata.target {
+            OperationalDataTarget::Destination if self.verify_proofs.src => {
+                proof_check::verify_proofs(
+                    self.src_chain(),
+                    self.dst_chain(),
+                    self.dst_client_id(),
+                    odata.proofs_height,
+                    msgs,
+                    client_update,
+                    self.ordered_channel(),
+                )
+            }
+            OperationalDataTarget::Source if self.verify_proofs.dst => proof_check::verify_proofs(
+                self.dst_chain(),
+                self.src_chain(),
+                self.src_client_id(),
+                odata.proofs_height,
+                msgs,
+                client_update,
+                self.ordered_channel(),
+            ),
+            _ => Ok(()),
+        }
+    }
+
     fn build_chan_close_confirm_from_event(&self, event: &IbcEvent) -> Result<Any, LinkError> {
         let src_channel_id = self.src_channel_id();
         let proofs = self
@@ -350,7 +530,8 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
     }
 
     /// Determines if the events received are relevant and should be processed.
-    /// Only events for a port/channel matching one of the channel ends should be processed.
+    /// Only events for a port/channel matching one of the channel ends should be processed,
+    /// and `WriteAcknowledgement` events only if acknowledgements are relayed on this path.
     fn filter_relaying_events(
         &self,
         events: Vec<IbcEvent>,
@@ -370,7 +551,8 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
                     }
                 }
                 IbcEvent::WriteAcknowledgement(write_ack_ev) => {
-                    if src_channel_id == write_ack_ev.dst_channel_id()
+                    if self.relay_mode.relays_acks()
+                        && src_channel_id == write_ack_ev.dst_channel_id()
                         && self.src_port_id() == write_ack_ev.dst_port_id()
                     {
                         result.push(event);
@@ -398,16 +580,16 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         TrackedEvents::new(result, tracking_id)
     }
 
-    fn relay_pending_packets(&self, height: Option<Height>) -> Result<(), LinkError> {
+    fn relay_pending_packets(
+        &self,
+        height: Option<Height>,
+        on_progress: &mut dyn FnMut(ClearProgress),
+    ) -> Result<(), LinkError> {
         let tracking_id = TrackingId::new_cleared_uuid();
         telemetry!(received_event_batch, tracking_id);
 
         for i in 1..=MAX_RETRIES {
-            let cleared = self
-                .schedule_recv_packet_and_timeout_msgs(height, tracking_id)
-                .and_then(|_| self.schedule_packet_ack_msgs(height, tracking_id));
-
-            match cleared {
+            match self.clear_pending_packets(height, tracking_id, on_progress) {
                 Ok(()) => return Ok(()),
                 Err(e) => error!(
                     "failed to clear packets, retry {}/{}: {}",
@@ -419,9 +601,215 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         Err(LinkError::old_packet_clearing_failed())
     }
 
+    /// Schedules the relaying of all the packets and acknowledgements pending on this path,
+    /// reporting the progress to `on_progress` each time a chunk of them has been scheduled.
+    fn clear_pending_packets(
+        &self,
+        height: Option<Height>,
+        tracking_id: TrackingId,
+        on_progress: &mut dyn FnMut(ClearProgress),
+    ) -> Result<(), LinkError> {
+        let pending = self.pending_sequences()?;
+
+        let mut progress = ClearProgress::new(pending.len());
+        on_progress(progress);
+
+        self.schedule_recv_packet_and_timeout_msgs_for(
+            &pending.packets,
+            height.unwrap_or(pending.packets_height),
+            tracking_id,
+            &mut |cleared| {
+                progress.advance(cleared);
+                on_progress(progress);
+            },
+        )?;
+
+        self.schedule_packet_ack_msgs_for(
+            &pending.acks,
+            height.unwrap_or(pending.acks_height),
+            tracking_id,
+            &mut |cleared| {
+                progress.advance(cleared);
+                on_progress(progress);
+            },
+        )
+    }
+
+    /// Returns the sequences of the packets and acknowledgements pending on this path,
+    /// i.e. those which clearing the packets of the path would relay.
+    ///
+    /// The pending packets are cleared in every relay mode, as they may have timed out,
+    /// while no acknowledgement is pending when they are not relayed on this path.
+    ///
+    /// The pending packets are those whose commitment is present on the source chain,
+    /// with which the backlog of this path is reconciled along the way.
+    pub fn pending_sequences(&self) -> Result<PendingSequences, LinkError> {
+        let sent =
+            SentSequences::query(self.src_chain(), self.src_port_id(), self.src_channel_id())?;
+        self.reconcile_sent_sequences(&sent);
+
+        let mut packets = unreceived_packets_sequences(
+            self.dst_chain(),
+            self.dst_port_id(),
+            self.dst_channel_id(),
+            sent.commitments,
+        )
+        .map_err(LinkError::supervisor)?;
+
+        let packets_height = sent.height;
+
+        let (mut acks, acks_height) = if self.relay_mode.relays_acks() {
+            unreceived_acknowledgements(self.dst_chain(), self.src_chain(), &self.path_id)
+                .map_err(LinkError::supervisor)?
+        } else {
+            (Vec::new(), packets_height)
+        };
+
+        // Leave out the sequences relayed by the transactions of the previous workers of
+        // this path until these transactions are confirmed, to not relay them twice.
+        let mut in_flight = self.pending_txs_src.in_flight_from_previous_workers();
+        in_flight.extend(self.pending_txs_dst.in_flight_from_previous_workers());
+
+        if !in_flight.is_empty() {
+            debug!(
+                packets = ?in_flight.packets,
+                acks = ?in_flight.acks,
+                "leaving out the sequences in flight from the previous worker"
+            );
+
+            packets.retain(|seq| !in_flight.packets.contains(seq));
+            acks.retain(|seq| !in_flight.acks.contains(seq));
+        }
+
+        Ok(PendingSequences {
+            packets,
+            packets_height,
+            acks,
+            acks_height,
+        })
+    }
+
+    /// Reconciles the backlog of this path with the packet commitments on the source chain,
+    /// and reports the sequences which the source chain skipped, if any.
+    fn reconcile_sent_sequences(&self, sent: &SentSequences) {
+        telemetry!({
+            let commitments: Vec<u64> = sent.commitments.iter().map(|s| u64::from(*s)).collect();
+
+            ibc_telemetry::global().backlog_reconcile(
+                &commitments,
+                sent.next_sequence_send.into(),
+                &self.src_chain().id(),
+                self.src_channel_id(),
+                self.src_port_id(),
+                &self.dst_chain().id(),
+            );
+        });
+
+        // The check is only informative, its failure does not prevent the clearing
+        if let Err(e) = self.check_skipped_sequences(sent) {
+            debug!(
+                "failed to check for the sequences skipped by {}: {}",
+                self.src_chain().id(),
+                e
+            );
+        }
+    }
+
+    /// Checks that the last sequences sent after the highest commitment on the source chain
+    /// are accounted for, either received by the destination chain or found in a `send_packet`
+    /// event, e.g. of a packet which timed out. The others were skipped by the source chain,
+    /// which never committed any packet for them: they are reported once, as no packet will
+    /// ever be relayed for them.
+    fn check_skipped_sequences(&self, sent: &SentSequences) -> Result<(), LinkError> {
+        let candidates = sent.uncommitted_tail(QUERY_RESULT_LIMIT as u64);
+
+        if candidates.is_empty() {
+            return Ok(());
+        }
+
+        let unreceived = if self.ordered_channel() {
+            let (next_sequence_recv, _) = self
+                .dst_chain()
+                .query_next_sequence_receive(
+                    QueryNextSequenceReceiveRequest {
+                        port_id: self.dst_port_id().clone(),
+                        channel_id: self.dst_channel_id().clone(),
+                        height: QueryHeight::Latest,
+                    },
+                    IncludeProof::No,
+                )
+                .map_err(|e| LinkError::query(self.dst_chain().id(), e))?;
+
+            candidates
+                .into_iter()
+                .filter(|sequence| *sequence >= next_sequence_recv)
+                .collect()
+        } else {
+            unreceived_packets_sequences(
+                self.dst_chain(),
+                self.dst_port_id(),
+                self.dst_channel_id(),
+                candidates,
+            )
+            .map_err(LinkError::supervisor)?
+        };
+
+        if unreceived.is_empty() {
+            return Ok(());
+        }
+
+        let sent_events: Vec<Sequence> = query_send_packet_events(
+            self.src_chain(),
+            &self.path_id,
+            unreceived.clone(),
+            sent.height,
+        )?
+        .into_iter()
+        .filter_map(|event| match event {
+            IbcEvent::SendPacket(send_packet) => Some(send_packet.packet.sequence),
+            _ => None,
+        })
+        .collect();
+
+        let skipped = unaccounted_sequences(&unreceived, &[&sent_events]);
+        let newly_skipped = self.skipped_sequences.report(&skipped);
+
+        if !newly_skipped.is_empty() {
+            let highest_commitment = sent
+                .highest_commitment()
+                .map_or_else(|| "none".to_string(), |sequence| sequence.to_string());
+
+            warn!(
+                "chain {} skipped the sequence(s) {} on channel {}/{}: no packet was committed \
+                for them, none was received by {} and no `send_packet` event was found, so these \
+                sequences are not pending (highest commitment: {}, next sequence to send: {})",
+                self.src_chain().id(),
+                newly_skipped.iter().format(", "),
+                self.src_port_id(),
+                self.src_channel_id(),
+                self.dst_chain().id(),
+                highest_commitment,
+                sent.next_sequence_send,
+            );
+        }
+
+        Ok(())
+    }
+
     /// Clears any packets that were sent before `height`.
     /// If no height is passed in, then the latest height of the source chain is used.
     pub fn schedule_packet_clearing(&self, height: Option<Height>) -> Result<(), LinkError> {
+        self.schedule_packet_clearing_with_progress(height, |_| ())
+    }
+
+    /// Same as [`RelayPath::schedule_packet_clearing`], but reports the progress
+    /// of the clearing to `on_progress` each time a chunk of the pending packets
+    /// has been scheduled for relaying.
+    pub fn schedule_packet_clearing_with_progress(
+        &self,
+        height: Option<Height>,
+        mut on_progress: impl FnMut(ClearProgress),
+    ) -> Result<(), LinkError> {
         let span = span!(Level::DEBUG, "clear");
         let _enter = span.enter();
 
@@ -429,12 +817,71 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
             .map(|h| h.decrement().map_err(|e| LinkError::decrement_height(h, e)))
             .transpose()?;
 
-        self.relay_pending_packets(clear_height)?;
+        self.relay_pending_packets(clear_height, &mut on_progress)?;
 
         debug!(height = ?clear_height, "done scheduling");
         Ok(())
     }
 
+    /// Starts clearing the packets that were sent before `height`, or before the latest
+    /// height of the source chain if no height is passed in, by querying the packets and
+    /// acknowledgements pending on this path. Nothing is scheduled until
+    /// [`RelayPath::clear_next_chunk`] is called.
+    pub fn start_packet_clearing(
+        &self,
+        height: Option<Height>,
+    ) -> Result<PacketClearing, LinkError> {
+        let clear_height = height
+            .map(|h| h.decrement().map_err(|e| LinkError::decrement_height(h, e)))
+            .transpose()?;
+
+        let tracking_id = TrackingId::new_cleared_uuid();
+        telemetry!(received_event_batch, tracking_id);
+
+        let pending = self.pending_sequences()?;
+
+        Ok(PacketClearing {
+            progress: ClearProgress::new(pending.len()),
+            remaining: pending,
+            height: clear_height,
+            tracking_id,
+        })
+    }
+
+    /// Schedules the relaying of the next chunk of the packets, then of the acknowledgements,
+    /// pending in the given clearing. The chunk is left pending if scheduling it fails.
+    pub fn clear_next_chunk(&self, clearing: &mut PacketClearing) -> Result<(), LinkError> {
+        let remaining = &mut clearing.remaining;
+
+        let cleared = if !remaining.packets.is_empty() {
+            let len = remaining.packets.len().min(QUERY_RESULT_LIMIT);
+
+            self.schedule_recv_packet_and_timeout_msgs_for(
+                &remaining.packets[..len],
+                clearing.height.unwrap_or(remaining.packets_height),
+                clearing.tracking_id,
+                &mut |_| (),
+            )?;
+
+            remaining.packets.drain(..len).count()
+        } else {
+            let len = remaining.acks.len().min(QUERY_RESULT_LIMIT);
+
+            self.schedule_packet_ack_msgs_for(
+                &remaining.acks[..len],
+                clearing.height.unwrap_or(remaining.acks_height),
+                clearing.tracking_id,
+                &mut |_| (),
+            )?;
+
+            remaining.acks.drain(..len).count()
+        };
+
+        clearing.progress.advance(cleared);
+
+        Ok(())
+    }
+
     /// Generate & schedule operational data from the input `batch` of IBC events.
     pub fn update_schedule(&self, batch: EventBatch) -> Result<(), LinkError> {
         // Collect relevant events from the incoming batch & adjust their height.
@@ -491,10 +938,21 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
             Some(ev) => ev.height(),
         };
 
-        let dst_latest_info = self
-            .dst_chain()
-            .query_application_status()
-            .map_err(|e| LinkError::query(self.src_chain().id(), e))?;
+        let dst_latest_info = match self.dst_chain().query_application_status() {
+            Ok(status) => status,
+            Err(e) => {
+                // The events are dropped, but the timeouts which can be proven
+                // later on are kept until the destination chain is back.
+                let send_packets = input.iter().filter_map(|event| match event {
+                    IbcEvent::SendPacket(send_packet_ev) => Some(send_packet_ev),
+                    _ => None,
+                });
+
+                self.defer_timeouts(send_packets);
+
+                return Err(LinkError::query(self.src_chain().id(), e));
+            }
+        };
 
         let dst_latest_height = dst_latest_info.height;
 
@@ -548,9 +1006,10 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
                     }
                 }
                 IbcEvent::WriteAcknowledgement(ref write_ack_ev) => {
-                    if self
-                        .dst_channel(QueryHeight::Latest)?
-                        .state_matches(&ChannelState::Closed)
+                    if !self.relay_mode.relays_acks()
+                        || self
+                            .dst_channel(QueryHeight::Latest)?
+                            .state_matches(&ChannelState::Closed)
                     {
                         (None, None)
                     } else if self.write_ack_event_handled(write_ack_ev)? {
@@ -603,12 +1062,21 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
 
     /// Relays an [`OperationalData`] using a specific
     /// sender, which implements [`relay_sender::Submit`].
+    ///
+    /// The messages of a failed transaction are handled as classified by [`TxErrorOutcome`].
+    /// Those kept queued are not dropped: the error is returned to the caller instead.
     pub(crate) fn relay_from_operational_data<S: relay_sender::Submit>(
         &self,
         initial_od: OperationalData,
     ) -> Result<S::Reply, LinkError> {
         // We will operate on potentially different operational data if the initial one fails.
-        let _span = span!(Level::INFO, "relay", odata = %initial_od.info()).entered();
+        let _span = span!(
+            Level::INFO,
+            "relay",
+            odata = %initial_od.info(),
+            correlation_id = %initial_od.correlation_id
+        )
+        .entered();
 
         let mut odata = initial_od;
 
@@ -637,28 +1105,100 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
                     return Ok(reply);
                 }
                 Err(LinkError(error::LinkErrorDetail::Send(e), _)) => {
-                    // This error means we could retry
-                    error!("error {}", e.event);
-                    if i + 1 == MAX_RETRIES {
-                        error!("{}/{} retries exhausted. giving up", i + 1, MAX_RETRIES)
-                    } else {
-                        // If we haven't exhausted all retries, regenerate the op. data & retry
-                        match self.regenerate_operational_data(odata.clone()) {
-                            None => return Ok(S::Reply::empty()), // Nothing to retry
-                            Some(new_od) => odata = new_od,
+                    let action = self.tx_error_action(odata.target, &e.event);
+
+                    match TxErrorOutcome::new(action, &e.event, odata.batch.len()) {
+                        TxErrorOutcome::Retry => {
+                            // This error means we could retry
+                            error!("error {}", e.event);
+                        }
+                        TxErrorOutcome::DropMessage { msg_index, alert } => {
+                            let dropped = odata.batch.remove(msg_index);
+
+                            if alert {
+                                warn!(
+                                    "dropping message for {} which would fail again: {}",
+                                    dropped.event, e.event
+                                );
+                            } else {
+                                debug!(
+                                    "dropping message for {} which would fail again: {}",
+                                    dropped.event, e.event
+                                );
+                            }
+
+                            if odata.batch.is_empty() {
+                                return Ok(S::Reply::empty());
+                            }
+                        }
+                        TxErrorOutcome::DropAll => {
+                            debug!("dropping messages which would fail again: {}", e.event);
+                            return Ok(S::Reply::empty());
+                        }
+                        TxErrorOutcome::KeepQueued => {
+                            warn!(
+                                "keeping messages queued until the error is resolved: {}",
+                                e.event
+                            );
+                            return Err(LinkError::send(e.event));
                         }
                     }
                 }
+                Err(e @ LinkError(error::LinkErrorDetail::ForkedProof(_), _)) => {
+                    // The proofs do not match the chain known to the counterparty,
+                    // drop the messages and query the proofs again
+                    warn!("{}", e);
+                }
                 Err(e) => {
                     // Unrecoverable error, propagate up the stack
                     return Err(e);
                 }
             }
+
+            if i + 1 == MAX_RETRIES {
+                error!("{}/{} retries exhausted. giving up", i + 1, MAX_RETRIES)
+            } else {
+                // If we haven't exhausted all retries, regenerate the op. data & retry
+                match self.regenerate_operational_data(odata.clone()) {
+                    None => return Ok(S::Reply::empty()), // Nothing to retry
+                    Some(new_od) => odata = new_od,
+                }
+            }
         }
 
         Ok(S::Reply::empty())
     }
 
+    /// The action to take for the messages sent to the given target chain, whose
+    /// transaction failed with the error reported by the given `ChainError` event.
+    pub(crate) fn tx_error_action(
+        &self,
+        target: OperationalDataTarget,
+        event: &IbcEvent,
+    ) -> TxErrorAction {
+        let code = match TxErrorCode::from_event(event) {
+            Some(code) => code,
+            None => return TxErrorAction::Retry,
+        };
+
+        let config = match target {
+            OperationalDataTarget::Source => self.src_chain().config(),
+            OperationalDataTarget::Destination => self.dst_chain().config(),
+        };
+
+        // Fall back on the default classification if the config cannot be retrieved
+        let overrides = config.map(|config| config.tx_errors).unwrap_or_default();
+        let action = code.action(&overrides);
+
+        telemetry!({
+            let (chain, _, _, _) = self.target_info(target);
+
+            ibc_telemetry::global().tx_error(&chain, &code.codespace, code.code, action.as_str());
+        });
+
+        action
+    }
+
     /// Generates fresh operational data for a tx given the initial operational data
     /// that failed to send.
     ///
@@ -693,9 +1233,10 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
             } // Cannot retry, contain the error by reporting a None
         };
 
-        if let Some(src_od) = src_opt {
+        if let Some(mut src_od) = src_opt {
             if src_od.target == op_info.target() {
                 // Our target is the _source_ chain, retry these messages
+                src_od.correlation_id = op_info.correlation_id();
                 info!(odata = %src_od.info(), "will retry");
                 return Some(src_od);
             } else {
@@ -712,9 +1253,10 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
             }
         }
 
-        if let Some(dst_od) = dst_opt {
+        if let Some(mut dst_od) = dst_opt {
             if dst_od.target == op_info.target() {
                 // Our target is the _destination_ chain, retry these messages
+                dst_od.correlation_id = op_info.correlation_id();
                 info!(odata = %dst_od.info(), "will retry");
                 return Some(dst_od);
             } else {
@@ -756,10 +1298,23 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
 
         let msgs = odata.assemble_msgs(self)?;
 
-        match odata.target {
+        // The client update prepended to the messages of the operational data, if any
+        let prepended = msgs.msgs.len().saturating_sub(odata.batch.len());
+
+        let result = match odata.target {
             OperationalDataTarget::Source => S::submit(self.src_chain(), msgs),
             OperationalDataTarget::Destination => S::submit(self.dst_chain(), msgs),
-        }
+        };
+
+        // Index the message which failed, if any, among the messages of the operational data
+        result
+            .map(|reply| reply.with_prepended_msgs(prepended))
+            .map_err(|e| match e {
+                LinkError(error::LinkErrorDetail::Send(e), _) => {
+                    LinkError::send(skip_prepended_msgs(e.event, prepended))
+                }
+                e => e,
+            })
     }
 
     fn enqueue_pending_tx(&self, reply: AsyncReply, odata: OperationalData) {
@@ -916,8 +1471,9 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         &self,
         src_chain_height: Height,
         tracking_id: TrackingId,
+        correlation_id: CorrelationId,
     ) -> Result<Height, LinkError> {
-        self.do_update_client_dst(src_chain_height, tracking_id, MAX_RETRIES)
+        self.do_update_client_dst(src_chain_height, tracking_id, correlation_id, MAX_RETRIES)
     }
 
     /// Perform actual update_client_dst with retries.
@@ -930,12 +1486,13 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         &self,
         src_chain_height: Height,
         tracking_id: TrackingId,
+        correlation_id: CorrelationId,
         retries_left: usize,
     ) -> Result<Height, LinkError> {
         info!( "sending update_client to client hosted on source chain for height {} (retries left: {})", src_chain_height, retries_left );
 
         let dst_update = self.build_update_client_on_dst(src_chain_height)?;
-        let tm = TrackedMsgs::new(dst_update, tracking_id);
+        let tm = TrackedMsgs::new(dst_update, tracking_id).with_correlation_id(correlation_id);
         let dst_tx_events = self
             .dst_chain()
             .send_messages_and_wait_commit(tm)
@@ -955,7 +1512,12 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
                         chain_error,
                     )))
                 } else {
-                    self.do_update_client_dst(src_chain_height, tracking_id, retries_left - 1)
+                    self.do_update_client_dst(
+                        src_chain_height,
+                        tracking_id,
+                        correlation_id,
+                        retries_left - 1,
+                    )
                 }
             }
             (None, None, None) => {
@@ -966,9 +1528,12 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
                     src_chain_height,
                 ) {
                     Ok(update_height) => Ok(update_height),
-                    Err(_) if retries_left > 0 => {
-                        self.do_update_client_dst(src_chain_height, tracking_id, retries_left - 1)
-                    }
+                    Err(_) if retries_left > 0 => self.do_update_client_dst(
+                        src_chain_height,
+                        tracking_id,
+                        correlation_id,
+                        retries_left - 1,
+                    ),
                     _ => Err(LinkError::update_client_failed()),
                 }
             }
@@ -983,8 +1548,9 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         &self,
         dst_chain_height: Height,
         tracking_id: TrackingId,
+        correlation_id: CorrelationId,
     ) -> Result<Height, LinkError> {
-        self.do_update_client_src(dst_chain_height, tracking_id, MAX_RETRIES)
+        self.do_update_client_src(dst_chain_height, tracking_id, correlation_id, MAX_RETRIES)
     }
 
     /// Perform actual update_client_src with retries.
@@ -997,12 +1563,13 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         &self,
         dst_chain_height: Height,
         tracking_id: TrackingId,
+        correlation_id: CorrelationId,
         retries_left: usize,
     ) -> Result<Height, LinkError> {
         info!( "sending update_client to client hosted on source chain for height {} (retries left: {})", dst_chain_height, retries_left );
 
         let src_update = self.build_update_client_on_src(dst_chain_height)?;
-        let tm = TrackedMsgs::new(src_update, tracking_id);
+        let tm = TrackedMsgs::new(src_update, tracking_id).with_correlation_id(correlation_id);
         let src_tx_events = self
             .src_chain()
             .send_messages_and_wait_commit(tm)
@@ -1022,7 +1589,12 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
                         chain_error,
                     )))
                 } else {
-                    self.do_update_client_src(dst_chain_height, tracking_id, retries_left - 1)
+                    self.do_update_client_src(
+                        dst_chain_height,
+                        tracking_id,
+                        correlation_id,
+                        retries_left - 1,
+                    )
                 }
             }
             (None, None, None) => {
@@ -1033,9 +1605,12 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
                     dst_chain_height,
                 ) {
                     Ok(update_height) => Ok(update_height),
-                    Err(_) if retries_left > 0 => {
-                        self.do_update_client_src(dst_chain_height, tracking_id, retries_left - 1)
-                    }
+                    Err(_) if retries_left > 0 => self.do_update_client_src(
+                        dst_chain_height,
+                        tracking_id,
+                        correlation_id,
+                        retries_left - 1,
+                    ),
                     _ => Err(LinkError::update_client_failed()),
                 }
             }
@@ -1067,6 +1642,25 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
 
         let query_height = opt_query_height.unwrap_or(src_response_height);
 
+        self.schedule_recv_packet_and_timeout_msgs_for(
+            &sequences,
+            query_height,
+            tracking_id,
+            &mut |_| (),
+        )
+    }
+
+    /// Schedules the relaying of [`MsgRecvPacket`] and [`MsgTimeout`] messages for the
+    /// packets with the given sequence numbers, querying for packet data at `query_height`.
+    ///
+    /// Reports to `on_chunk` how many packets were scheduled, after each chunk of packets.
+    fn schedule_recv_packet_and_timeout_msgs_for(
+        &self,
+        sequences: &[Sequence],
+        query_height: Height,
+        tracking_id: TrackingId,
+        on_chunk: &mut dyn FnMut(usize),
+    ) -> Result<(), LinkError> {
         // Skip: no relevant events found.
         if sequences.is_empty() {
             return Ok(());
@@ -1082,7 +1676,7 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         // Chunk-up the list of sequence nrs. into smaller parts,
         // and schedule operational data incrementally across each chunk.
         for events_chunk in query_packet_events_with(
-            &sequences,
+            sequences,
             query_height,
             self.src_chain(),
             &self.path_id,
@@ -1094,7 +1688,10 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
                     self.record_cleared_send_packet(e);
                 }
             });
+
+            let chunk_len = events_chunk.len();
             self.events_to_operational_data(TrackedEvents::new(events_chunk, tracking_id))?;
+            on_chunk(chunk_len);
         }
 
         Ok(())
@@ -1112,12 +1709,34 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
     ) -> Result<(), LinkError> {
         let _span = span!(Level::DEBUG, "build_packet_ack_msgs", h = ?opt_query_height).entered();
 
+        if !self.relay_mode.relays_acks() {
+            debug!(
+                "skipping acknowledgements, not relayed in {} mode",
+                self.relay_mode
+            );
+            return Ok(());
+        }
+
         let (sequences, src_response_height) =
             unreceived_acknowledgements(self.dst_chain(), self.src_chain(), &self.path_id)
                 .map_err(LinkError::supervisor)?;
 
         let query_height = opt_query_height.unwrap_or(src_response_height);
 
+        self.schedule_packet_ack_msgs_for(&sequences, query_height, tracking_id, &mut |_| ())
+    }
+
+    /// Schedules the relaying of [`MsgAcknowledgement`] messages for the packets with
+    /// the given sequence numbers, querying for packet data at `query_height`.
+    ///
+    /// Reports to `on_chunk` how many acknowledgements were scheduled, after each chunk of them.
+    fn schedule_packet_ack_msgs_for(
+        &self,
+        sequences: &[Sequence],
+        query_height: Height,
+        tracking_id: TrackingId,
+        on_chunk: &mut dyn FnMut(usize),
+    ) -> Result<(), LinkError> {
         // Skip: no relevant events found.
         if sequences.is_empty() {
             return Ok(());
@@ -1132,14 +1751,17 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
 
         // Incrementally process all the available sequence numbers in chunks
         for events_chunk in query_packet_events_with(
-            &sequences,
+            sequences,
             query_height,
             self.src_chain(),
             &self.path_id,
             query_write_ack_events,
         ) {
             telemetry!(self.record_cleared_acknowledgments(events_chunk.clone()));
+
+            let chunk_len = events_chunk.len();
             self.events_to_operational_data(TrackedEvents::new(events_chunk, tracking_id))?;
+            on_chunk(chunk_len);
         }
 
         Ok(())
@@ -1168,10 +1790,88 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         Ok(Some(msg.to_any()))
     }
 
+    /// Returns the acknowledgement of the packet of the given event, checked against the
+    /// acknowledgement commitment stored on the source chain at the height of the event.
+    ///
+    /// The node may return acknowledgement bytes which do not match the committed ones, with
+    /// which the proof still verifies but the destination chain rejects the acknowledgement.
+    /// In that case, the `WriteAcknowledgement` event is queried again from the transactions
+    /// indexed by the node up to its latest height, rather than the height of the event, which
+    /// the bad acknowledgement was returned for. `None` is returned if its acknowledgement does
+    /// not match either, for the packet to be acknowledged once cleared.
+    fn verified_ack(&self, event: &WriteAcknowledgement) -> Result<Option<Vec<u8>>, LinkError> {
+        let packet = &event.packet;
+
+        let (commitment, _) = self
+            .src_chain()
+            .query_packet_acknowledgement(
+                QueryPacketAcknowledgementRequest {
+                    port_id: packet.destination_port.clone(),
+                    channel_id: packet.destination_channel.clone(),
+                    sequence: packet.sequence,
+                    height: QueryHeight::Specific(event.height),
+                },
+                IncludeProof::No,
+            )
+            .map_err(|e| LinkError::query(self.src_chain().id(), e))?;
+
+        let matches = |ack: &[u8]| compute_ack_commitment(ack).as_ref() == commitment.as_slice();
+
+        // Without commitment, building the proofs fails anyway
+        if commitment.is_empty() || matches(&event.ack) {
+            return Ok(Some(event.ack.clone()));
+        }
+
+        warn!(
+            "acknowledgement of packet {} from the event at height {} does not match its commitment on chain {}, querying it again",
+            packet,
+            event.height,
+            self.src_chain().id()
+        );
+
+        telemetry!(ack_commitment_mismatch, &self.src_chain().id());
+
+        let latest_height = self
+            .src_chain()
+            .query_latest_height()
+            .map_err(|e| LinkError::query(self.src_chain().id(), e))?;
+
+        let events = query_write_ack_events(
+            self.src_chain(),
+            &self.path_id,
+            vec![packet.sequence],
+            latest_height,
+        )?;
+
+        let ack = events.into_iter().find_map(|event| match event {
+            IbcEvent::WriteAcknowledgement(event)
+                if event.packet.sequence == packet.sequence && matches(&event.ack) =>
+            {
+                Some(event.ack)
+            }
+            _ => None,
+        });
+
+        if ack.is_none() {
+            warn!(
+                "found no acknowledgement of packet {} matching its commitment on chain {}, not relaying it",
+                packet,
+                self.src_chain().id()
+            );
+        }
+
+        Ok(ack)
+    }
+
     fn build_ack_from_recv_event(
         &self,
         event: &WriteAcknowledgement,
     ) -> Result<Option<Any>, LinkError> {
+        let ack = match self.verified_ack(event)? {
+            Some(ack) => ack,
+            None => return Ok(None),
+        };
+
         let packet = event.packet.clone();
 
         let proofs = self
@@ -1185,12 +1885,7 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
             )
             .map_err(|e| LinkError::packet_proofs_constructor(self.src_chain().id(), e))?;
 
-        let msg = MsgAcknowledgement::new(
-            packet,
-            event.ack.clone().into(),
-            proofs.clone(),
-            self.dst_signer()?,
-        );
+        let msg = MsgAcknowledgement::new(packet, ack.into(), proofs.clone(), self.dst_signer()?);
 
         trace!(
             "built acknowledgment msg {}, proofs at height {}",
@@ -1312,6 +2007,12 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         let timeout = self.build_timeout_from_send_packet_event(event, dst_info)?;
         if timeout.is_some() {
             Ok((None, timeout))
+        } else if !self.relay_mode.relays_recv() {
+            debug!(
+                "{} not timed out, not receiving it in {} mode",
+                event, self.relay_mode
+            );
+            Ok((None, None))
         } else {
             Ok((self.build_recv_packet(&event.packet, event.height)?, None))
         }
@@ -1337,12 +2038,13 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
     /// Should an error occur when attempting to relay a piece of operational
     /// data, this function returns all subsequent unprocessed pieces of
     /// operational data back to the caller so that they can be re-queued
-    /// for processing; the operational data that failed to send is dropped.
+    /// for processing; the operational data that failed to send is dropped,
+    /// unless its messages are kept queued, see [`TxErrorOutcome::KeepQueued`].
     ///
     /// Note that pieces of operational data that have not elapsed yet are
     /// also placed in the 'unprocessed' bucket.
     fn execute_schedule_for_target_chain<I: Iterator<Item = OperationalData>>(
-        &mut self,
+        &self,
         mut operations: I,
         target_chain: OperationalDataTarget,
     ) -> Result<VecDeque<OperationalData>, (VecDeque<OperationalData>, LinkError)> {
@@ -1375,6 +2077,11 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
                             // The relaying process failed; return all of the subsequent pieces of operational
                             // data along with the underlying error that occurred.
                             Err(e) => {
+                                // Unless its messages are kept queued until the error is resolved
+                                if let error::LinkErrorDetail::Send(_) = e.detail() {
+                                    unprocessed.push_back(od);
+                                }
+
                                 unprocessed.extend(operations);
 
                                 return Err((unprocessed, e));
@@ -1403,6 +2110,31 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         Ok(unprocessed)
     }
 
+    /// Acquires the lock on the pipeline relaying the operational data
+    /// targeting the given chain.
+    fn pipeline(&self, target: OperationalDataTarget) -> MutexGuard<'_, ()> {
+        match target {
+            OperationalDataTarget::Source => &self.src_pipeline,
+            OperationalDataTarget::Destination => &self.dst_pipeline,
+        }
+        .lock()
+        .expect("poisoned lock")
+    }
+
+    /// Queues the given operational data again, ahead of the data scheduled in the
+    /// meantime, for its messages to be relayed once the error of their transaction
+    /// is resolved, see [`TxErrorOutcome::KeepQueued`].
+    pub(crate) fn requeue_operational_data(&self, od: OperationalData) {
+        self.operational_data(od.target).push_front(od);
+    }
+
+    fn operational_data(&self, target: OperationalDataTarget) -> &Queue<OperationalData> {
+        match target {
+            OperationalDataTarget::Source => &self.src_operational_data,
+            OperationalDataTarget::Destination => &self.dst_operational_data,
+        }
+    }
+
     /// While there are pending operational data items, this function
     /// performs the relaying of packets corresponding to those
     /// operational data items to both the source and destination chains.
@@ -1410,55 +2142,77 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
     /// Any operational data items that do not get successfully relayed are
     /// dropped. Subsequent pending operational data items that went unprocessed
     /// are queued up again for re-submission.
-    pub fn execute_schedule(&mut self) -> Result<(), LinkError> {
-        let src_od_iter = self.src_operational_data.take().into_iter();
-
-        match self.execute_schedule_for_target_chain(src_od_iter, OperationalDataTarget::Source) {
-            Ok(unprocessed_src_data) => self.src_operational_data = unprocessed_src_data.into(),
-            Err((unprocessed_src_data, e)) => {
-                self.src_operational_data = unprocessed_src_data.into();
-                return Err(e);
-            }
-        }
-
-        let dst_od_iter = self.dst_operational_data.take().into_iter();
+    pub fn execute_schedule(&self) -> Result<(), LinkError> {
+        self.execute_schedule_for(OperationalDataTarget::Source)?;
+        self.execute_schedule_for(OperationalDataTarget::Destination)
+    }
 
-        match self
-            .execute_schedule_for_target_chain(dst_od_iter, OperationalDataTarget::Destination)
-        {
-            Ok(unprocessed_dst_data) => self.dst_operational_data = unprocessed_dst_data.into(),
-            Err((unprocessed_dst_data, e)) => {
-                self.dst_operational_data = unprocessed_dst_data.into();
-                return Err(e);
+    /// Performs the relaying of the pending operational data items
+    /// targeting the given chain.
+    ///
+    /// The schedules targeting the source and the destination chain may be executed
+    /// concurrently, but only one execution per target chain happens at a time.
+    /// The operational data items which went unprocessed are queued up again
+    /// ahead of the items scheduled in the meantime.
+    pub fn execute_schedule_for(&self, target: OperationalDataTarget) -> Result<(), LinkError> {
+        let _pipeline = self.pipeline(target);
+
+        let queue = self.operational_data(target);
+        let od_iter = queue.take().into_iter();
+
+        match self.execute_schedule_for_target_chain(od_iter, target) {
+            Ok(unprocessed) => {
+                queue.prepend(unprocessed);
+                Ok(())
+            }
+            Err((unprocessed, e)) => {
+                queue.prepend(unprocessed);
+                Err(e)
             }
         }
-
-        Ok(())
     }
 
     /// Kicks off the process of relaying pending txs to the source and destination chains.
     ///
     /// See [`Resubmit::from_clear_interval`] for more info about the `resubmit` parameter.
     pub fn process_pending_txs(&self, resubmit: Resubmit) -> RelaySummary {
+        let mut summary_src = self.process_pending_txs_for(OperationalDataTarget::Source, resubmit);
+        let summary_dst =
+            self.process_pending_txs_for(OperationalDataTarget::Destination, resubmit);
+
+        summary_src.extend(summary_dst);
+        summary_src
+    }
+
+    /// Kicks off the process of relaying pending txs to the given chain.
+    pub fn process_pending_txs_for(
+        &self,
+        target: OperationalDataTarget,
+        resubmit: Resubmit,
+    ) -> RelaySummary {
         if !self.confirm_txes {
             return RelaySummary::empty();
         }
 
-        let mut summary_src = self.process_pending_txs_src(resubmit).unwrap_or_else(|e| {
-            error!("error processing pending events in source chain: {}", e);
-            RelaySummary::empty()
-        });
+        let _pipeline = self.pipeline(target);
 
-        let summary_dst = self.process_pending_txs_dst(resubmit).unwrap_or_else(|e| {
-            error!(
-                "error processing pending events in destination chain: {}",
-                e
-            );
-            RelaySummary::empty()
-        });
-
-        summary_src.extend(summary_dst);
-        summary_src
+        match target {
+            OperationalDataTarget::Source => {
+                self.process_pending_txs_src(resubmit).unwrap_or_else(|e| {
+                    error!("error processing pending events in source chain: {}", e);
+                    RelaySummary::empty()
+                })
+            }
+            OperationalDataTarget::Destination => {
+                self.process_pending_txs_dst(resubmit).unwrap_or_else(|e| {
+                    error!(
+                        "error processing pending events in destination chain: {}",
+                        e
+                    );
+                    RelaySummary::empty()
+                })
+            }
+        }
     }
 
     fn process_pending_txs_src(&self, resubmit: Resubmit) -> Result<RelaySummary, LinkError> {
@@ -1496,25 +2250,38 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
     /// Refreshes the scheduled batches.
     /// Verifies if any sendPacket messages timed-out. If so, moves them from destination op. data
     /// to source operational data, and adjusts the events and messages accordingly.
+    ///
+    /// Also schedules the timeouts deferred while the destination chain was unreachable,
+    /// once it is reachable again, or defers the timeouts of the scheduled packets while
+    /// it is not.
     pub fn refresh_schedule(&self) -> Result<(), LinkError> {
+        let span = span!(Level::INFO, "refresh");
+        let _enter = span.enter();
+
+        self.schedule_deferred_timeouts()?;
+
         // Bail fast if no op. data to refresh
         if self.dst_operational_data.is_empty() {
             return Ok(());
         }
 
-        let span = span!(Level::INFO, "refresh");
-        let _enter = span.enter();
-
-        let dst_status = self
-            .dst_chain()
-            .query_application_status()
-            .map_err(|e| LinkError::query(self.src_chain().id(), e))?;
+        let dst_status = match self.dst_chain().query_application_status() {
+            Ok(status) => status,
+            Err(e) => {
+                self.defer_scheduled_timeouts();
+                return Err(LinkError::query(self.src_chain().id(), e));
+            }
+        };
 
         let dst_current_height = dst_status.height;
 
+        // Prevent the destination pipeline from relaying the operational data being refreshed
+        let dst_pipeline = self.pipeline(OperationalDataTarget::Destination);
+
         // Intermediary data struct to help better manage the transfer from dst. operational data
         // to source operational data.
         let mut all_dst_odata = self.dst_operational_data.clone_vec();
+        let refreshed_len = all_dst_odata.len();
 
         let mut timed_out: HashMap<usize, OperationalData> = HashMap::default();
 
@@ -1573,8 +2340,12 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         // Retain only the non-empty ones.
         all_dst_odata.retain(|o| !o.batch.is_empty());
 
-        // Replace the original operational data with the updated one
-        self.dst_operational_data.replace(all_dst_odata);
+        // Replace the original operational data with the updated one,
+        // keeping the operational data scheduled in the meantime
+        self.dst_operational_data
+            .replace_front(refreshed_len, all_dst_odata);
+
+        drop(dst_pipeline);
 
         // Handle timed-out events
         if timed_out.is_empty() {
@@ -1595,11 +2366,239 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         Ok(())
     }
 
+    /// Defers the timeouts of the given packets, sent while the destination chain is
+    /// unreachable, which are proven by the last consensus state of the destination
+    /// chain known to the client on the source chain.
+    /// Returns the sequences of the packets whose timeout was deferred.
+    fn defer_timeouts<'a>(
+        &self,
+        events: impl IntoIterator<Item = &'a SendPacket>,
+    ) -> Vec<Sequence> {
+        let mut events = events.into_iter().peekable();
+        if events.peek().is_none() {
+            return vec![];
+        }
+
+        match self.try_defer_timeouts(events) {
+            Ok(deferred) => {
+                if !deferred.is_empty() {
+                    self.record_deferred_timeouts();
+                }
+                deferred
+            }
+            Err(e) => {
+                warn!(
+                    "failed to check for packets timed out on unreachable chain {}: {}",
+                    self.dst_chain().id(),
+                    e
+                );
+                vec![]
+            }
+        }
+    }
+
+    fn try_defer_timeouts<'a>(
+        &self,
+        events: impl Iterator<Item = &'a SendPacket>,
+    ) -> Result<Vec<Sequence>, LinkError> {
+        let (client_state, _) = self
+            .src_chain()
+            .query_client_state(
+                QueryClientStateRequest {
+                    client_id: self.src_client_id().clone(),
+                    height: QueryHeight::Latest,
+                },
+                IncludeProof::No,
+            )
+            .map_err(|e| LinkError::query(self.src_chain().id(), e))?;
+
+        let client_height = client_state.latest_height();
+
+        let (consensus_state, _) = self
+            .src_chain()
+            .query_consensus_state(
+                QueryConsensusStateRequest {
+                    client_id: self.src_client_id().clone(),
+                    consensus_height: client_height,
+                    query_height: QueryHeight::Latest,
+                },
+                IncludeProof::No,
+            )
+            .map_err(|e| LinkError::query(self.src_chain().id(), e))?;
+
+        let client_timestamp = consensus_state.timestamp();
+
+        let mut deferred = vec![];
+
+        for event in events {
+            if !event.packet.timed_out(&client_timestamp, client_height)
+                || self.send_packet_commitment_cleared_on_src(&event.packet)?
+            {
+                continue;
+            }
+
+            let is_new = self.deferred_timeouts.insert(DeferredTimeout {
+                event: event.clone(),
+                client_height,
+            });
+
+            if is_new {
+                warn!(
+                    "{} timed out as of height {} of client {}, its timeout awaits a proof \
+                    from unreachable chain {}",
+                    event.packet,
+                    client_height,
+                    self.src_client_id(),
+                    self.dst_chain().id()
+                );
+            }
+
+            deferred.push(event.packet.sequence);
+        }
+
+        Ok(deferred)
+    }
+
+    /// Moves the timed out packets out of the operational data targeting
+    /// the unreachable destination chain, deferring their timeouts.
+    fn defer_scheduled_timeouts(&self) {
+        let _dst_pipeline = self.pipeline(OperationalDataTarget::Destination);
+
+        let mut all_dst_odata = self.dst_operational_data.clone_vec();
+        let refreshed_len = all_dst_odata.len();
+
+        let send_packets = all_dst_odata
+            .iter()
+            .flat_map(|odata| odata.batch.iter())
+            .filter_map(|gm| match &gm.event {
+                IbcEvent::SendPacket(e) => Some(e),
+                _ => None,
+            });
+
+        let deferred = self.defer_timeouts(send_packets);
+        if deferred.is_empty() {
+            return;
+        }
+
+        for odata in all_dst_odata.iter_mut() {
+            odata.batch.retain(|gm| match &gm.event {
+                IbcEvent::SendPacket(e) => !deferred.contains(&e.packet.sequence),
+                _ => true,
+            });
+        }
+
+        all_dst_odata.retain(|o| !o.batch.is_empty());
+
+        self.dst_operational_data
+            .replace_front(refreshed_len, all_dst_odata);
+    }
+
+    /// Schedules the timeouts deferred while the destination chain was unreachable,
+    /// now that their proofs can be queried from it. Does nothing while it is
+    /// still unreachable.
+    fn schedule_deferred_timeouts(&self) -> Result<(), LinkError> {
+        if self.deferred_timeouts.is_empty() {
+            return Ok(());
+        }
+
+        let dst_status = match self.dst_chain().query_application_status() {
+            Ok(status) => status,
+            Err(e) => {
+                debug!(
+                    "{} timeouts still await a proof from unreachable chain {}: {}",
+                    self.deferred_timeouts.len(),
+                    self.dst_chain().id(),
+                    e
+                );
+                return Ok(());
+            }
+        };
+
+        let mut od = OperationalData::new(
+            dst_status.height,
+            OperationalDataTarget::Source,
+            TrackingId::new_static("deferred-timeouts"),
+            self.channel.connection_delay,
+        );
+
+        let mut timeouts = self.deferred_timeouts.take_all().into_iter();
+        let mut remaining = vec![];
+
+        while let Some(timeout) = timeouts.next() {
+            let result = self
+                .send_packet_event_handled(&timeout.event)
+                .and_then(|handled| {
+                    if handled {
+                        Ok(None)
+                    } else {
+                        self.build_timeout_from_send_packet_event(&timeout.event, &dst_status)
+                    }
+                });
+
+            let msg = match result {
+                Ok(Some(msg)) => msg,
+                Ok(None) => {
+                    debug!("already handled deferred timeout of {}", timeout.event);
+                    continue;
+                }
+                Err(e) => {
+                    self.deferred_timeouts
+                        .restore(remaining.into_iter().chain(Some(timeout)).chain(timeouts));
+                    self.record_deferred_timeouts();
+                    return Err(e);
+                }
+            };
+
+            // For ordered channels, a single timeout is sent as it closes the channel,
+            // the other packets then time out on close.
+            if self.unordered_channel() || od.batch.is_empty() {
+                od.batch.push(TransitMessage {
+                    event: IbcEvent::SendPacket(timeout.event.clone()),
+                    msg,
+                });
+            } else {
+                remaining.push(timeout);
+            }
+        }
+
+        self.deferred_timeouts.restore(remaining);
+        self.record_deferred_timeouts();
+
+        if od.batch.is_empty() {
+            return Ok(());
+        }
+
+        info!(
+            "scheduling {} timeouts deferred while {} was unreachable",
+            od.batch.len(),
+            self.dst_chain().id()
+        );
+
+        self.schedule_operational_data(od)
+    }
+
+    fn record_deferred_timeouts(&self) {
+        telemetry!(
+            timeouts_awaiting_proof,
+            &self.src_chain().id(),
+            self.src_channel_id(),
+            self.src_port_id(),
+            &self.dst_chain().id(),
+            self.deferred_timeouts.len() as u64,
+        );
+    }
+
     /// Adds a new operational data item for this relaying path to process later.
     /// If the relaying path has non-zero packet delays, this method also updates the client on the
     /// target chain with the appropriate headers.
     fn schedule_operational_data(&self, mut od: OperationalData) -> Result<(), LinkError> {
-        let _span = span!(Level::INFO, "schedule", odata = %od.info()).entered();
+        let _span = span!(
+            Level::INFO,
+            "schedule",
+            odata = %od.info(),
+            correlation_id = %od.correlation_id
+        )
+        .entered();
 
         if od.batch.is_empty() {
             info!(
@@ -1617,12 +2616,14 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
             let target_height = od.proofs_height.increment();
             match od.target {
                 OperationalDataTarget::Source => {
-                    let update_height = self.update_client_src(target_height, od.tracking_id)?;
+                    let update_height =
+                        self.update_client_src(target_height, od.tracking_id, od.correlation_id)?;
                     od.set_update_height(update_height);
                     self.src_time_at_height(update_height)?
                 }
                 OperationalDataTarget::Destination => {
-                    let update_height = self.update_client_dst(target_height, od.tracking_id)?;
+                    let update_height =
+                        self.update_client_dst(target_height, od.tracking_id, od.correlation_id)?;
                     od.set_update_height(update_height);
                     self.dst_time_at_height(update_height)?
                 }
@@ -1637,11 +2638,20 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
 
         od.set_scheduled_time(scheduled_time);
 
-        match od.target {
-            OperationalDataTarget::Source => self.src_operational_data.push_back(od),
-            OperationalDataTarget::Destination => self.dst_operational_data.push_back(od),
+        let queue = match od.target {
+            OperationalDataTarget::Source => &self.src_operational_data,
+            OperationalDataTarget::Destination => &self.dst_operational_data,
         };
 
+        // The data relaying live events goes ahead of the data obtained by clearing packets,
+        // which may take a while to go through when a large backlog is being cleared, unless
+        // the channel is ordered, in which case the packets must be relayed in sequence.
+        if od.tracking_id.is_cleared() || self.ordered_channel() {
+            queue.push_back(od);
+        } else {
+            queue.insert_before(od, |queued| queued.tracking_id.is_cleared());
+        }
+
         Ok(())
     }
 
@@ -1808,3 +2818,118 @@ impl<ChainA: ChainHandle, ChainB: ChainHandle> RelayPath<ChainA, ChainB> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ibc::core::ics04_channel::timeout::TimeoutHeight;
+    use ibc::core::ics24_host::identifier::ChainId;
+
+    use crate::chain::handle::mock::MockChainHandle;
+    use crate::channel::ChannelSide;
+    use crate::error::Error;
+
+    /// The acknowledgement committed on `chain_A`.
+    const ACK: &[u8] = b"{\"result\":\"AQ==\"}";
+
+    /// The chain `chain_A`, on which the acknowledgement of the packet
+    /// sent over `channel-0` was written, and the path relaying it.
+    fn setup() -> (MockChainHandle, RelayPath<MockChainHandle, MockChainHandle>) {
+        let a = MockChainHandle::new(ChainId::from_string("chain_A"));
+        let b = MockChainHandle::new(ChainId::from_string("chain_B"));
+
+        a.set_latest(Height::new(0, 10).unwrap(), Timestamp::now());
+        a.write_acknowledgement(
+            PortId::transfer(),
+            ChannelId::new(0),
+            Sequence::from(1),
+            compute_ack_commitment(ACK).into_vec(),
+        );
+
+        let side = |chain: &MockChainHandle| {
+            ChannelSide::new(
+                chain.clone(),
+                ClientId::default(),
+                ConnectionId::new(0),
+                PortId::transfer(),
+                Some(ChannelId::new(0)),
+                None,
+            )
+        };
+
+        let channel = Channel {
+            ordering: Order::Unordered,
+            a_side: side(&a),
+            b_side: side(&b),
+            connection_delay: Duration::ZERO,
+        };
+
+        (a, RelayPath::new(channel, false).unwrap())
+    }
+
+    fn write_ack(ack: &[u8]) -> WriteAcknowledgement {
+        let packet = Packet {
+            sequence: Sequence::from(1),
+            source_port: PortId::transfer(),
+            source_channel: ChannelId::new(0),
+            destination_port: PortId::transfer(),
+            destination_channel: ChannelId::new(0),
+            data: vec![],
+            timeout_height: TimeoutHeight::Never,
+            timeout_timestamp: Timestamp::none(),
+        };
+
+        WriteAcknowledgement {
+            height: Height::new(0, 2).unwrap(),
+            packet,
+            ack: ack.to_vec(),
+        }
+    }
+
+    #[test]
+    fn ack_matching_its_commitment_is_relayed() {
+        let (a, relay_path) = setup();
+
+        // The event is not queried again
+        a.inject_failure("query_txs", Error::channel_send);
+
+        assert_eq!(
+            relay_path.verified_ack(&write_ack(ACK)).unwrap(),
+            Some(ACK.to_vec())
+        );
+    }
+
+    #[test]
+    fn ack_not_matching_its_commitment_is_queried_again() {
+        let (a, relay_path) = setup();
+
+        // The node indexed the acknowledgement written in the event since
+        a.set_queried_tx_events(vec![
+            IbcEvent::WriteAcknowledgement(write_ack(b"bad ack")),
+            IbcEvent::WriteAcknowledgement(write_ack(ACK)),
+        ]);
+
+        assert_eq!(
+            relay_path.verified_ack(&write_ack(b"bad ack")).unwrap(),
+            Some(ACK.to_vec())
+        );
+    }
+
+    #[test]
+    fn ack_not_matching_its_commitment_again_is_not_relayed() {
+        let (a, relay_path) = setup();
+
+        a.set_queried_tx_events(vec![IbcEvent::WriteAcknowledgement(write_ack(b"bad ack"))]);
+
+        assert_eq!(
+            relay_path.verified_ack(&write_ack(b"bad ack")).unwrap(),
+            None
+        );
+
+        // Nor is it when the latest height of the chain cannot be queried
+        a.inject_failure("query_application_status", Error::channel_send);
+
+        assert!(relay_path.verified_ack(&write_ack(b"bad ack")).is_err());
+    }
+}
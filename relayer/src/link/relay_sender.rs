@@ -1,10 +1,10 @@
 use core::fmt;
 
-use tendermint_rpc::endpoint::broadcast::tx_sync;
 use tracing::info;
 
 use ibc::events::{IbcEvent, PrettyEvents};
 
+use crate::chain::cosmos::types::tx::TxSyncResponse;
 use crate::chain::handle::ChainHandle;
 use crate::chain::tracking::TrackedMsgs;
 use crate::link::error::LinkError;
@@ -16,6 +16,10 @@ pub trait SubmitReply {
 
     /// Counts the number of replies that this instance contains.
     fn len(&self) -> usize;
+
+    /// Records the number of messages submitted ahead of those of the
+    /// operational data, i.e. the client update, if any.
+    fn with_prepended_msgs(self, prepended: usize) -> Self;
 }
 
 impl SubmitReply for RelaySummary {
@@ -26,6 +30,10 @@ impl SubmitReply for RelaySummary {
     fn len(&self) -> usize {
         self.events.len()
     }
+
+    fn with_prepended_msgs(self, _prepended: usize) -> Self {
+        self
+    }
 }
 
 /// Captures the ability to submit messages to a chain.
@@ -68,17 +76,26 @@ impl Submit for SyncSender {
 }
 
 pub struct AsyncReply {
-    pub responses: Vec<tx_sync::Response>,
+    pub responses: Vec<TxSyncResponse>,
+    /// The number of messages submitted ahead of those of the operational data
+    pub prepended: usize,
 }
 
 impl SubmitReply for AsyncReply {
     fn empty() -> Self {
-        Self { responses: vec![] }
+        Self {
+            responses: vec![],
+            prepended: 0,
+        }
     }
 
     fn len(&self) -> usize {
         self.responses.len()
     }
+
+    fn with_prepended_msgs(self, prepended: usize) -> Self {
+        Self { prepended, ..self }
+    }
 }
 
 // TODO(Adi): Consider removing the senders and keep only a generic
@@ -92,10 +109,23 @@ impl Submit for AsyncSender {
         let a = target
             .send_messages_and_wait_check_tx(msgs)
             .map_err(LinkError::relayer)?;
-        let reply = AsyncReply { responses: a };
+        let reply = AsyncReply {
+            responses: a,
+            prepended: 0,
+        };
         info!("[Async~>{}] {}\n", target.id(), reply);
 
-        Ok(reply)
+        // None of the transactions got into the mempool: fail as the synchronous sender
+        // does, for the messages to be handled as classified by their error right away
+        let mut errors = reply
+            .responses
+            .iter()
+            .map(|sent| sent.check_tx_error(&target.id()));
+
+        match errors.next() {
+            Some(Some(ev)) if errors.all(|error| error.is_some()) => Err(LinkError::send(ev)),
+            _ => Ok(reply),
+        }
     }
 }
 
@@ -104,6 +134,6 @@ impl fmt::Display for AsyncReply {
         write!(f, "response(s): {}", self.responses.len())?;
         self.responses
             .iter()
-            .try_for_each(|r| write!(f, "; {:?}:{}", r.code, r.hash))
+            .try_for_each(|r| write!(f, "; {:?}:{}", r.response.code, r.response.hash))
     }
 }
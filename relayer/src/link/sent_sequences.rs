@@ -0,0 +1,215 @@
+//! The sequences of the packets sent on the source end of a relaying path.
+//!
+//! The `next_sequence_send` of a channel end only bounds the sequences of the packets
+//! sent on it: a faulty application can burn sequences without committing any packet.
+//! The packets pending on a path are thus those whose commitment is present in the state
+//! of the source chain, never inferred from `next_sequence_send`. The sequences sent after
+//! the highest commitment must be accounted for, either by a packet received on the
+//! destination chain or by a `send_packet` event, and the others are reported once as
+//! skipped by the source chain.
+
+use alloc::collections::BTreeSet;
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+
+use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics24_host::identifier::{ChannelId, PortId};
+use ibc::Height;
+
+use crate::chain::counterparty::commitments_on_chain;
+use crate::chain::handle::ChainHandle;
+use crate::chain::requests::{IncludeProof, QueryHeight, QueryNextSequenceSendRequest};
+use crate::link::error::LinkError;
+use crate::util::lock::LockExt;
+
+/// The packet commitments of a channel end, along with its next sequence to send.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SentSequences {
+    /// The sequence of the next packet to send, at the height of the commitments
+    pub next_sequence_send: Sequence,
+    /// The sequences of the packet commitments, by increasing sequence
+    pub commitments: Vec<Sequence>,
+    /// The height at which the commitments were queried
+    pub height: Height,
+}
+
+impl SentSequences {
+    /// Queries the packet commitments of the given channel end, and its next
+    /// sequence to send at the same height.
+    pub fn query(
+        chain: &impl ChainHandle,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+    ) -> Result<Self, LinkError> {
+        let (commitments, height) =
+            commitments_on_chain(chain, port_id, channel_id).map_err(LinkError::supervisor)?;
+
+        let (next_sequence_send, _) = chain
+            .query_next_sequence_send(
+                QueryNextSequenceSendRequest {
+                    port_id: port_id.clone(),
+                    channel_id: channel_id.clone(),
+                    height: QueryHeight::Specific(height),
+                },
+                IncludeProof::No,
+            )
+            .map_err(|e| LinkError::query(chain.id(), e))?;
+
+        Ok(Self {
+            next_sequence_send,
+            commitments,
+            height,
+        })
+    }
+
+    /// The highest sequence with a packet commitment, if any.
+    pub fn highest_commitment(&self) -> Option<Sequence> {
+        self.commitments.last().copied()
+    }
+
+    /// The last sequences sent after the highest commitment, at most `window` of them,
+    /// whose packets are no longer pending if they were ever sent.
+    pub fn uncommitted_tail(&self, window: u64) -> Vec<Sequence> {
+        let end = u64::from(self.next_sequence_send);
+
+        let start = self
+            .highest_commitment()
+            .map_or(1, |highest| u64::from(highest) + 1)
+            .max(end.saturating_sub(window))
+            .max(1);
+
+        (start..end).map(Sequence::from).collect()
+    }
+}
+
+/// Returns the `candidates` which are in none of the `accounted` lists.
+pub fn unaccounted_sequences(candidates: &[Sequence], accounted: &[&[Sequence]]) -> Vec<Sequence> {
+    let accounted = accounted
+        .iter()
+        .flat_map(|sequences| sequences.iter())
+        .collect::<HashSet<_>>();
+
+    candidates
+        .iter()
+        .filter(|sequence| !accounted.contains(sequence))
+        .copied()
+        .collect()
+}
+
+/// The sequences reported as skipped by the source chain of a relaying path,
+/// so that each of them is only reported once. All the clones share them.
+#[derive(Clone, Debug, Default)]
+pub struct SkippedSequences(Arc<RwLock<BTreeSet<Sequence>>>);
+
+impl SkippedSequences {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the given skipped sequences, returning those which were not reported yet.
+    pub fn report(&self, skipped: &[Sequence]) -> Vec<Sequence> {
+        let mut reported = self.0.acquire_write();
+
+        skipped
+            .iter()
+            .copied()
+            .filter(|sequence| reported.insert(*sequence))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{unaccounted_sequences, SentSequences, SkippedSequences};
+
+    use ibc::core::ics04_channel::packet::Sequence;
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+    use ibc::Height;
+
+    use crate::chain::handle::mock::MockChainHandle;
+    use crate::error::Error;
+
+    fn seqs(sequences: &[u64]) -> Vec<Sequence> {
+        sequences.iter().copied().map(Sequence::from).collect()
+    }
+
+    fn sent(next_sequence_send: u64, commitments: &[u64]) -> SentSequences {
+        SentSequences {
+            next_sequence_send: Sequence::from(next_sequence_send),
+            commitments: seqs(commitments),
+            height: Height::new(0, 1).unwrap(),
+        }
+    }
+
+    #[test]
+    fn uncommitted_tail() {
+        // Nothing sent yet
+        assert!(sent(1, &[]).uncommitted_tail(50).is_empty());
+
+        // All the packets are pending
+        assert!(sent(4, &[1, 2, 3]).uncommitted_tail(50).is_empty());
+
+        // Sequences 4 and 5 are no longer pending, or were skipped
+        assert_eq!(sent(6, &[2, 3]).uncommitted_tail(50), seqs(&[4, 5]));
+
+        // Only the last sequences are checked
+        assert_eq!(sent(101, &[]).uncommitted_tail(3), seqs(&[98, 99, 100]));
+        assert_eq!(sent(101, &[99]).uncommitted_tail(3), seqs(&[100]));
+    }
+
+    #[test]
+    fn gap_above_the_highest_commitment() {
+        let candidates = sent(8, &[1, 3]).uncommitted_tail(50);
+        assert_eq!(candidates, seqs(&[4, 5, 6, 7]));
+
+        // None of them was received, and only the packets 4 and 6 have
+        // a `send_packet` event, i.e. they timed out
+        assert_eq!(
+            unaccounted_sequences(&candidates, &[&[], &seqs(&[4, 6])]),
+            seqs(&[5, 7])
+        );
+
+        // The packets 5 and 7 were received, without any trace of 4 and 6
+        assert_eq!(
+            unaccounted_sequences(&candidates, &[&seqs(&[5, 7]), &[]]),
+            seqs(&[4, 6])
+        );
+    }
+
+    #[test]
+    fn skipped_sequences_reported_once() {
+        let skipped = SkippedSequences::new();
+        let clone = skipped.clone();
+
+        assert_eq!(skipped.report(&seqs(&[4, 6])), seqs(&[4, 6]));
+        assert_eq!(clone.report(&seqs(&[4, 6, 9])), seqs(&[9]));
+        assert!(skipped.report(&seqs(&[9])).is_empty());
+    }
+
+    #[test]
+    fn query_sent_sequences() {
+        let chain = MockChainHandle::new(ChainId::from_string("chain_A"));
+        let (port_id, channel_id) = (PortId::transfer(), ChannelId::new(0));
+
+        // Packets 1 and 2 committed, then the chain skipped sequences 3 and 4
+        // before committing packet 5, whose acknowledgement was then received
+        for sequence in [1, 2, 5] {
+            chain.commit_packet(
+                port_id.clone(),
+                channel_id.clone(),
+                Sequence::from(sequence),
+                vec![1],
+            );
+        }
+        chain.delete_packet_commitment(port_id.clone(), channel_id.clone(), Sequence::from(5));
+
+        let sent = SentSequences::query(&chain, &port_id, &channel_id).unwrap();
+
+        assert_eq!(sent.next_sequence_send, Sequence::from(6));
+        assert_eq!(sent.commitments, seqs(&[1, 2]));
+        assert_eq!(sent.uncommitted_tail(50), seqs(&[3, 4, 5]));
+
+        chain.inject_failure("query_next_sequence_send", Error::channel_send);
+        assert!(SentSequences::query(&chain, &port_id, &channel_id).is_err());
+    }
+}
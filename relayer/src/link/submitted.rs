@@ -0,0 +1,276 @@
+//! Transactions submitted by the packet workers, recorded until they are confirmed.
+//!
+//! A packet worker keeps the transactions it submitted in memory until they are
+//! confirmed. When the worker is respawned, e.g. after one of its tasks panicked,
+//! that memory is lost while the transactions may still be committed, and the new
+//! worker would build and submit the same packets again, paying their fees twice.
+//! The supervisor thus keeps a record of the transactions submitted on each path,
+//! shared by the successive workers of the path: a respawned worker first confirms
+//! the transactions of its predecessors, and leaves their packets out of the packets
+//! it clears until these transactions are confirmed or time out.
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use core::fmt;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+
+use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc::events::IbcEvent;
+
+use crate::link::pending::{PendingData, TIMEOUT};
+use crate::link::TxHashes;
+use crate::util::lock::LockExt;
+
+/// The chain the transactions are submitted to, and the end of the channel on it.
+pub type ChannelEndKey = (ChainId, PortId, ChannelId);
+
+/// The transactions submitted on the channel ends relayed by the packet workers,
+/// until they are confirmed or time out. All the clones share the same record.
+#[derive(Clone, Default)]
+pub struct SubmittedTxs(Arc<RwLock<BTreeMap<ChannelEndKey, Vec<PendingData>>>>);
+
+impl SubmittedTxs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records transactions submitted on the given channel end, forgetting
+    /// along the way those of its transactions which timed out.
+    pub fn record(&self, channel_end: &ChannelEndKey, pending: PendingData) {
+        let mut submitted = self.0.acquire_write();
+        let txs = submitted.entry(channel_end.clone()).or_default();
+
+        txs.retain(|tx| tx.submit_time.elapsed() <= TIMEOUT);
+        txs.push(pending);
+    }
+
+    /// Forgets the given transactions submitted on the given channel end,
+    /// once confirmed or given up on.
+    pub fn forget(&self, channel_end: &ChannelEndKey, tx_hashes: &TxHashes) {
+        let mut submitted = self.0.acquire_write();
+
+        if let Some(txs) = submitted.get_mut(channel_end) {
+            txs.retain(|tx| tx.tx_hashes.0 != tx_hashes.0);
+
+            if txs.is_empty() {
+                submitted.remove(channel_end);
+            }
+        }
+    }
+
+    /// The transactions submitted on the given channel end before `since`,
+    /// which are neither confirmed nor timed out yet.
+    pub fn submitted_before(
+        &self,
+        channel_end: &ChannelEndKey,
+        since: Instant,
+    ) -> Vec<PendingData> {
+        self.0
+            .acquire_read()
+            .get(channel_end)
+            .map(|txs| {
+                txs.iter()
+                    .filter(|tx| tx.submit_time < since && tx.submit_time.elapsed() <= TIMEOUT)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// How many transactions are recorded, on all the channel ends.
+    pub fn len(&self) -> usize {
+        self.0.acquire_read().values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl fmt::Debug for SubmittedTxs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SubmittedTxs")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+/// The sequences of the packets, and of the acknowledgements, relayed by transactions
+/// which were submitted but are not confirmed yet.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InFlightSequences {
+    /// The packets received or timed out by the transactions
+    pub packets: BTreeSet<Sequence>,
+    /// The packets acknowledged by the transactions
+    pub acks: BTreeSet<Sequence>,
+}
+
+impl InFlightSequences {
+    /// Adds the sequences relayed by the messages of the given transactions.
+    pub fn add(&mut self, pending: &PendingData) {
+        for msg in &pending.original_od.batch {
+            match &msg.event {
+                IbcEvent::WriteAcknowledgement(event) => {
+                    self.acks.insert(event.packet.sequence);
+                }
+                event => {
+                    if let Some(packet) = event.packet() {
+                        self.packets.insert(packet.sequence);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn extend(&mut self, other: InFlightSequences) {
+        self.packets.extend(other.packets);
+        self.acks.extend(other.acks);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.packets.is_empty() && self.acks.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChannelEndKey, InFlightSequences, SubmittedTxs};
+
+    use core::time::Duration;
+    use std::time::Instant;
+
+    use ibc::core::ics04_channel::events::{SendPacket, WriteAcknowledgement};
+    use ibc::core::ics04_channel::packet::{Packet, Sequence};
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+    use ibc::events::IbcEvent;
+    use ibc::Height;
+    use ibc_proto::google::protobuf::Any;
+    use tendermint::abci::transaction::Hash;
+
+    use crate::chain::tracking::TrackingId;
+    use crate::link::operational_data::{OperationalData, OperationalDataTarget, TransitMessage};
+    use crate::link::pending::{PendingData, TIMEOUT};
+    use crate::link::TxHashes;
+
+    fn channel_end() -> ChannelEndKey {
+        (
+            ChainId::from_string("chain_B"),
+            PortId::transfer(),
+            ChannelId::new(0),
+        )
+    }
+
+    fn packet(sequence: u64) -> Packet {
+        Packet {
+            sequence: Sequence::from(sequence),
+            ..Packet::default()
+        }
+    }
+
+    /// A transaction with the given hash, relaying the given events.
+    fn pending(hash: u8, events: Vec<IbcEvent>, submit_time: Instant) -> PendingData {
+        let mut od = OperationalData::new(
+            Height::new(0, 1).unwrap(),
+            OperationalDataTarget::Destination,
+            TrackingId::new_static("test"),
+            Duration::ZERO,
+        );
+
+        for event in events {
+            od.push(TransitMessage {
+                event,
+                msg: Any::default(),
+            });
+        }
+
+        PendingData {
+            original_od: od,
+            tx_hashes: TxHashes(vec![Hash::new([hash; 32])]),
+            submit_time,
+            error_events: vec![],
+            prepended: 0,
+        }
+    }
+
+    fn recv(sequence: u64) -> IbcEvent {
+        IbcEvent::SendPacket(SendPacket {
+            height: Height::new(0, 1).unwrap(),
+            packet: packet(sequence),
+        })
+    }
+
+    fn ack(sequence: u64) -> IbcEvent {
+        IbcEvent::WriteAcknowledgement(WriteAcknowledgement {
+            height: Height::new(0, 1).unwrap(),
+            packet: packet(sequence),
+            ack: vec![1],
+        })
+    }
+
+    #[test]
+    fn record_until_confirmed() {
+        let submitted = SubmittedTxs::new();
+        let submit_time = Instant::now();
+
+        submitted.record(&channel_end(), pending(1, vec![recv(1)], submit_time));
+        submitted.record(&channel_end(), pending(2, vec![recv(2)], submit_time));
+        assert_eq!(submitted.len(), 2);
+
+        // Only the transactions submitted before the given time are returned
+        let since = submit_time + Duration::from_millis(1);
+        assert_eq!(submitted.submitted_before(&channel_end(), since).len(), 2);
+        assert!(submitted
+            .submitted_before(&channel_end(), submit_time)
+            .is_empty());
+
+        let other_end = (
+            ChainId::from_string("chain_A"),
+            PortId::transfer(),
+            ChannelId::new(0),
+        );
+        assert!(submitted.submitted_before(&other_end, since).is_empty());
+
+        submitted.forget(&channel_end(), &TxHashes(vec![Hash::new([1; 32])]));
+
+        let remaining = submitted.submitted_before(&channel_end(), since);
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].tx_hashes.0, vec![Hash::new([2; 32])]);
+
+        submitted.forget(&channel_end(), &TxHashes(vec![Hash::new([2; 32])]));
+        assert!(submitted.is_empty());
+    }
+
+    #[test]
+    fn timed_out_txs_are_forgotten() {
+        let submitted = SubmittedTxs::new();
+        let now = Instant::now();
+
+        let timed_out = match now.checked_sub(TIMEOUT + Duration::from_secs(1)) {
+            Some(timed_out) => timed_out,
+            // The clock started too recently to have timed out transactions
+            None => return,
+        };
+
+        submitted.record(&channel_end(), pending(1, vec![recv(1)], timed_out));
+        assert!(submitted.submitted_before(&channel_end(), now).is_empty());
+
+        // and dropped once another transaction is recorded
+        submitted.record(&channel_end(), pending(2, vec![recv(2)], now));
+        assert_eq!(submitted.len(), 1);
+    }
+
+    #[test]
+    fn in_flight_sequences() {
+        let mut in_flight = InFlightSequences::default();
+        assert!(in_flight.is_empty());
+
+        in_flight.add(&pending(1, vec![recv(1), recv(2), ack(7)], Instant::now()));
+
+        assert_eq!(
+            in_flight.packets,
+            [1, 2].into_iter().map(Sequence::from).collect()
+        );
+        assert_eq!(in_flight.acks, [Sequence::from(7)].into_iter().collect());
+    }
+}
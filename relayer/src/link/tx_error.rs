@@ -0,0 +1,374 @@
+//! Classification of the errors of the transactions relaying packets.
+//!
+//! Relaying the messages of a failed transaction again is only worth its fees if the
+//! error may not happen again. The codespace and code of the ABCI error reported by
+//! the transaction select whether its messages are resubmitted, dropped, or dropped
+//! with a warning, from the overrides configured for the chain, or else from the
+//! built-in classification of the errors of ibc-go and of the Cosmos SDK.
+//!
+//! Only the message which failed is dropped, when the error reports its index, and
+//! the other messages are resubmitted. See [`TxErrorOutcome`].
+
+use ibc::events::IbcEvent;
+
+use crate::config::{TxErrorAction, TxErrorPolicies};
+
+/// The codespace of the errors returned by the Cosmos SDK.
+pub const SDK_CODESPACE: &str = "sdk";
+
+/// The built-in action for each known error, by codespace and code.
+///
+/// The codes follow the `errors.go` files of ibc-go and of the Cosmos SDK.
+const DEFAULT_ACTIONS: &[(&str, u32, TxErrorAction)] = &[
+    // ibc-go/modules/core/04-channel/types/errors.go
+    ("channel", 13, TxErrorAction::DropAndAlert), // invalid packet
+    ("channel", 17, TxErrorAction::DropSilently), // acknowledgement for packet already exists
+    ("channel", 19, TxErrorAction::DropSilently), // packet already received
+    ("channel", 20, TxErrorAction::DropSilently), // packet commitment not found
+    ("channel", 22, TxErrorAction::DropSilently), // packet messages are redundant
+    ("channel", 23, TxErrorAction::DropSilently), // message is redundant, no-op will be performed
+    // ibc-go/modules/core/23-commitment/types/errors.go
+    ("commitment", 2, TxErrorAction::DropAndAlert), // invalid proof
+    ("commitment", 4, TxErrorAction::DropAndAlert), // invalid merkle proof
+    // ibc-go/modules/core/02-client/types/errors.go
+    ("client", 5, TxErrorAction::DropAndAlert), // light client is frozen due to misbehaviour
+    ("client", 29, TxErrorAction::DropAndAlert), // client is not active
+    // cosmos-sdk/types/errors/errors.go
+    (SDK_CODESPACE, 4, TxErrorAction::DropAndAlert), // unauthorized
+    (SDK_CODESPACE, 5, TxErrorAction::DropAndAlert), // insufficient funds
+    (SDK_CODESPACE, 19, TxErrorAction::DropSilently), // tx already in mempool
+    (SDK_CODESPACE, 21, TxErrorAction::DropAndAlert), // tx too large
+];
+
+/// The ABCI error of a failed transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TxErrorCode {
+    pub codespace: String,
+    pub code: u32,
+}
+
+impl TxErrorCode {
+    pub fn new(codespace: impl Into<String>, code: u32) -> Self {
+        Self {
+            codespace: codespace.into(),
+            code,
+        }
+    }
+
+    /// The ABCI error reported by the given `ChainError` event, if any.
+    pub fn from_event(event: &IbcEvent) -> Option<Self> {
+        match event {
+            IbcEvent::ChainError(error) => Some(Self::new(error.codespace.clone(), error.code)),
+            _ => None,
+        }
+    }
+
+    /// The action to take for the messages of a transaction which failed with this error,
+    /// as configured in the given overrides, or else as classified by default.
+    ///
+    /// The unknown errors are retried.
+    pub fn action(&self, overrides: &TxErrorPolicies) -> TxErrorAction {
+        overrides
+            .action_for(&self.codespace, self.code)
+            .or_else(|| self.default_action())
+            .unwrap_or(TxErrorAction::Retry)
+    }
+
+    /// The built-in action for this error, if it is known.
+    pub fn default_action(&self) -> Option<TxErrorAction> {
+        DEFAULT_ACTIONS
+            .iter()
+            .find(|(codespace, code, _)| *codespace == self.codespace && *code == self.code)
+            .map(|(_, _, action)| *action)
+    }
+}
+
+/// What happens to the messages of a failed transaction, given the action for its error.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TxErrorOutcome {
+    /// Resubmit all the messages.
+    Retry,
+    /// Drop the message which failed, at the given index among the messages,
+    /// warning about it if `alert` is set, and resubmit the others.
+    DropMessage { msg_index: usize, alert: bool },
+    /// Drop all the messages, as the transaction failed as a whole and would fail again.
+    DropAll,
+    /// Keep all the messages queued and warn about it, as the transaction failed as a whole,
+    /// e.g. for lack of funds, with an error which the operator is expected to resolve.
+    KeepQueued,
+}
+
+impl TxErrorOutcome {
+    /// The outcome of the given action for the `msg_count` messages of a transaction
+    /// which failed with the error reported by the given `ChainError` event.
+    pub fn new(action: TxErrorAction, event: &IbcEvent, msg_count: usize) -> Self {
+        let msg_index = match event {
+            IbcEvent::ChainError(error) => error.msg_index.filter(|index| *index < msg_count),
+            _ => None,
+        };
+
+        match (action, msg_index) {
+            (TxErrorAction::Retry, _) => Self::Retry,
+            (TxErrorAction::DropSilently, Some(msg_index)) => Self::DropMessage {
+                msg_index,
+                alert: false,
+            },
+            (TxErrorAction::DropAndAlert, Some(msg_index)) => Self::DropMessage {
+                msg_index,
+                alert: true,
+            },
+            (TxErrorAction::DropSilently, None) => Self::DropAll,
+            (TxErrorAction::DropAndAlert, None) => Self::KeepQueued,
+        }
+    }
+}
+
+/// Index the message reported as failed by the given `ChainError` event, if any, among
+/// the messages sent after the given number of prepended messages, i.e. the client update.
+///
+/// The failure of a prepended message is that of the transaction as a whole.
+pub fn skip_prepended_msgs(event: IbcEvent, prepended: usize) -> IbcEvent {
+    match event {
+        IbcEvent::ChainError(error) => {
+            let msg_index = error
+                .msg_index
+                .and_then(|msg_index| msg_index.checked_sub(prepended));
+
+            IbcEvent::ChainError(error.with_msg_index(msg_index))
+        }
+        event => event,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ibc::core::ics02_client::events::NewBlock;
+    use ibc::events::ChainErrorEvent;
+    use ibc::Height;
+
+    use crate::config::TxErrorPolicy;
+
+    // The errors reported by failed transactions: their codespace, code and log
+    type Captured = (&'static str, u32, &'static str);
+
+    const PACKET_ALREADY_RECEIVED: Captured = (
+        "channel",
+        19,
+        "failed to execute message; message index: 1: packet already received",
+    );
+    const INVALID_PROOF: Captured = (
+        "commitment",
+        2,
+        "failed to execute message; message index: 0: invalid proof",
+    );
+    const CONSENSUS_STATE_NOT_FOUND: Captured = (
+        "client",
+        7,
+        "failed to execute message; message index: 0: consensus state not found",
+    );
+    const OUT_OF_GAS: Captured = (
+        "sdk",
+        11,
+        "out of gas in location: ReadFlat; gasWanted: 100000, gasUsed: 100120: out of gas",
+    );
+    const INSUFFICIENT_FUNDS: Captured = (
+        "sdk",
+        5,
+        "0stake is smaller than 4000stake: insufficient funds: insufficient funds",
+    );
+    const REDUNDANT_MESSAGES: Captured =
+        ("channel", 23, "acknowledge packet messages are redundant");
+    const UNKNOWN_ERROR: Captured = (
+        "wasm",
+        42,
+        "failed to execute message; message index: 0: contract error",
+    );
+
+    fn chain_error((codespace, code, log): Captured) -> IbcEvent {
+        IbcEvent::ChainError(ChainErrorEvent::new(
+            format!(
+                "deliver_tx reports error: code=Err({}), log={:?}",
+                code, log
+            ),
+            code,
+            codespace.to_string(),
+        ))
+    }
+
+    fn code(error: Captured) -> TxErrorCode {
+        TxErrorCode::from_event(&chain_error(error)).unwrap()
+    }
+
+    fn policy(codespace: &str, code: u32, action: TxErrorAction) -> TxErrorPolicy {
+        TxErrorPolicy {
+            codespace: codespace.to_string(),
+            code,
+            action,
+        }
+    }
+
+    #[test]
+    fn codes_of_chain_errors() {
+        assert_eq!(
+            code(PACKET_ALREADY_RECEIVED),
+            TxErrorCode::new("channel", 19)
+        );
+        assert_eq!(code(INVALID_PROOF), TxErrorCode::new("commitment", 2));
+        assert_eq!(code(OUT_OF_GAS), TxErrorCode::new(SDK_CODESPACE, 11));
+        assert_eq!(code(REDUNDANT_MESSAGES), TxErrorCode::new("channel", 23));
+        assert_eq!(code(UNKNOWN_ERROR), TxErrorCode::new("wasm", 42));
+    }
+
+    #[test]
+    fn no_code_of_other_events() {
+        let event = IbcEvent::NewBlock(NewBlock::new(Height::new(0, 1).unwrap()));
+
+        assert_eq!(TxErrorCode::from_event(&event), None);
+    }
+
+    #[test]
+    fn unreported_codespace_is_not_assumed() {
+        // The code 5 of the SDK, but without its codespace
+        let error = code(("", 5, "insufficient funds"));
+
+        assert_eq!(error, TxErrorCode::new("", 5));
+        assert_eq!(
+            error.action(&TxErrorPolicies::default()),
+            TxErrorAction::Retry
+        );
+    }
+
+    #[test]
+    fn default_actions() {
+        let defaults = TxErrorPolicies::default();
+
+        let cases = [
+            (PACKET_ALREADY_RECEIVED, TxErrorAction::DropSilently),
+            (INVALID_PROOF, TxErrorAction::DropAndAlert),
+            (CONSENSUS_STATE_NOT_FOUND, TxErrorAction::Retry),
+            (OUT_OF_GAS, TxErrorAction::Retry),
+            (INSUFFICIENT_FUNDS, TxErrorAction::DropAndAlert),
+            (REDUNDANT_MESSAGES, TxErrorAction::DropSilently),
+            (UNKNOWN_ERROR, TxErrorAction::Retry),
+        ];
+
+        for (error, action) in cases {
+            assert_eq!(code(error).action(&defaults), action, "{}", error.2);
+        }
+    }
+
+    #[test]
+    fn default_actions_are_unique() {
+        for (i, (codespace, code, _)) in DEFAULT_ACTIONS.iter().enumerate() {
+            assert!(
+                DEFAULT_ACTIONS[i + 1..]
+                    .iter()
+                    .all(|(other_codespace, other_code, _)| (codespace, code)
+                        != (other_codespace, other_code)),
+                "duplicate default action for code {} of codespace {}",
+                code,
+                codespace
+            );
+        }
+    }
+
+    #[test]
+    fn overrides_take_precedence() {
+        let overrides = TxErrorPolicies::new(vec![
+            policy("channel", 19, TxErrorAction::DropAndAlert),
+            policy(SDK_CODESPACE, 5, TxErrorAction::Retry),
+            policy("wasm", 42, TxErrorAction::DropSilently),
+            policy("wasm", 42, TxErrorAction::DropAndAlert),
+        ]);
+
+        assert_eq!(
+            code(PACKET_ALREADY_RECEIVED).action(&overrides),
+            TxErrorAction::DropAndAlert
+        );
+        assert_eq!(
+            code(INSUFFICIENT_FUNDS).action(&overrides),
+            TxErrorAction::Retry
+        );
+
+        // The last matching override wins
+        assert_eq!(
+            code(UNKNOWN_ERROR).action(&overrides),
+            TxErrorAction::DropAndAlert
+        );
+
+        // The errors without override keep their default action
+        assert_eq!(
+            code(INVALID_PROOF).action(&overrides),
+            TxErrorAction::DropAndAlert
+        );
+    }
+
+    fn failed_msg(msg_index: Option<usize>) -> IbcEvent {
+        match chain_error(INVALID_PROOF) {
+            IbcEvent::ChainError(error) => IbcEvent::ChainError(error.with_msg_index(msg_index)),
+            event => event,
+        }
+    }
+
+    #[test]
+    fn only_the_failed_message_is_dropped() {
+        let event = failed_msg(Some(1));
+
+        assert_eq!(
+            TxErrorOutcome::new(TxErrorAction::DropSilently, &event, 3),
+            TxErrorOutcome::DropMessage {
+                msg_index: 1,
+                alert: false
+            }
+        );
+        assert_eq!(
+            TxErrorOutcome::new(TxErrorAction::DropAndAlert, &event, 3),
+            TxErrorOutcome::DropMessage {
+                msg_index: 1,
+                alert: true
+            }
+        );
+        assert_eq!(
+            TxErrorOutcome::new(TxErrorAction::Retry, &event, 3),
+            TxErrorOutcome::Retry
+        );
+    }
+
+    #[test]
+    fn messages_of_failed_tx_are_dropped_or_kept_queued() {
+        // The error of the transaction as a whole, or of a message out of those relayed
+        for event in [failed_msg(None), failed_msg(Some(3))] {
+            assert_eq!(
+                TxErrorOutcome::new(TxErrorAction::DropSilently, &event, 3),
+                TxErrorOutcome::DropAll
+            );
+            assert_eq!(
+                TxErrorOutcome::new(TxErrorAction::DropAndAlert, &event, 3),
+                TxErrorOutcome::KeepQueued
+            );
+        }
+    }
+
+    #[test]
+    fn failed_msg_is_indexed_after_prepended_msgs() {
+        let msg_index = |event: IbcEvent| match event {
+            IbcEvent::ChainError(ChainErrorEvent { msg_index, .. }) => msg_index,
+            _ => panic!("not a chain error"),
+        };
+
+        assert_eq!(
+            msg_index(skip_prepended_msgs(failed_msg(Some(2)), 1)),
+            Some(1)
+        );
+        assert_eq!(
+            msg_index(skip_prepended_msgs(failed_msg(Some(2)), 0)),
+            Some(2)
+        );
+        assert_eq!(msg_index(skip_prepended_msgs(failed_msg(None), 1)), None);
+
+        // The client update failed
+        assert_eq!(msg_index(skip_prepended_msgs(failed_msg(Some(0)), 1)), None);
+    }
+}
@@ -10,7 +10,7 @@ pub struct TxHashes(pub Vec<transaction::Hash>);
 
 impl From<AsyncReply> for TxHashes {
     fn from(r: AsyncReply) -> Self {
-        Self(r.responses.into_iter().map(|e| e.hash).collect())
+        Self(r.responses.into_iter().map(|e| e.response.hash).collect())
     }
 }
 
@@ -400,6 +400,41 @@ impl Object {
         .into())
     }
 
+    /// Build the packet objects of both ends of the channel whose handshake
+    /// or closing event has the given attributes, starting with the end on
+    /// the chain which emitted the event. The end on the counterparty chain
+    /// is omitted if the event does not carry its channel identifier.
+    pub fn packets_from_chan_events(
+        attributes: &Attributes,
+        src_chain: &impl ChainHandle,
+    ) -> Result<Vec<Packet>, ObjectError> {
+        let channel_id = attributes
+            .channel_id()
+            .ok_or_else(|| ObjectError::missing_channel_id(attributes.clone()))?;
+
+        let dst_chain_id =
+            counterparty_chain_from_channel(src_chain, channel_id, attributes.port_id())
+                .map_err(ObjectError::supervisor)?;
+
+        let mut packets = vec![Packet {
+            dst_chain_id: dst_chain_id.clone(),
+            src_chain_id: src_chain.id(),
+            src_channel_id: channel_id.clone(),
+            src_port_id: attributes.port_id().clone(),
+        }];
+
+        if let Some(counterparty_channel_id) = &attributes.counterparty_channel_id {
+            packets.push(Packet {
+                dst_chain_id: src_chain.id(),
+                src_chain_id: dst_chain_id,
+                src_channel_id: counterparty_channel_id.clone(),
+                src_port_id: attributes.counterparty_port_id.clone(),
+            });
+        }
+
+        Ok(packets)
+    }
+
     /// Build the object associated with the given [`SendPacket`] event.
     pub fn for_send_packet(
         e: &SendPacket,
@@ -1,6 +1,8 @@
 use ibc::core::ics04_channel::channel::IdentifiedChannelEnd;
 use ibc::core::ics24_host::identifier::{ChannelId, PortId};
 
+pub mod health;
+
 /// Defines the channel & port identifiers which comprise
 /// the two ends of a relayer path.
 pub struct PathIdentifiers {
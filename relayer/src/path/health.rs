@@ -0,0 +1,593 @@
+//! Health of the paths relayed between two chains, i.e. of the two ends of a channel,
+//! their clients, and the packets pending on them.
+
+use core::fmt;
+use core::time::Duration;
+
+use serde::Serialize;
+
+use ibc::core::ics02_client::client_state::{AnyClientState, ClientState};
+use ibc::core::ics04_channel::channel::State as ChannelState;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, ClientId, PortId};
+use ibc::timestamp::Timestamp;
+
+use crate::chain::counterparty::{
+    channel_connection_client, channel_on_destination, pending_packet_summary, PendingPackets,
+};
+use crate::chain::handle::ChainHandle;
+use crate::chain::requests::{
+    IncludeProof, QueryClientStateRequest, QueryConsensusStateRequest, QueryHeight,
+};
+use crate::error::Error as RelayerError;
+use crate::object::Object;
+use crate::supervisor::dump_state::WorkerDesc;
+use crate::supervisor::Error;
+
+/// The status of a client with respect to its trusting period.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientStatus {
+    /// Updated within its refresh period.
+    Active,
+    /// Not updated within its refresh period, which the client refresh should have done.
+    Stale,
+    /// Not updated within its trusting period, it cannot be updated anymore.
+    Expired,
+    /// Frozen after a misbehaviour was submitted.
+    Frozen,
+}
+
+impl fmt::Display for ClientStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Active => write!(f, "active"),
+            Self::Stale => write!(f, "stale"),
+            Self::Expired => write!(f, "expired"),
+            Self::Frozen => write!(f, "frozen"),
+        }
+    }
+}
+
+/// The client hosted at one end of a path, which tracks the chain at the other end.
+#[derive(Clone, Debug, Serialize)]
+pub struct ClientHealth {
+    pub client_id: ClientId,
+    /// The timestamp of the consensus state at the latest height of the client.
+    pub last_update: Timestamp,
+    /// Time remaining until the client expires if it is not updated.
+    pub remaining_secs: u64,
+    pub status: ClientStatus,
+}
+
+impl ClientHealth {
+    pub fn new(
+        client_id: ClientId,
+        client_state: &AnyClientState,
+        last_update: Timestamp,
+        now: Timestamp,
+    ) -> Self {
+        let age = now.duration_since(&last_update).unwrap_or_default();
+
+        let status = if client_state.is_frozen() {
+            ClientStatus::Frozen
+        } else if client_state.expired(age) {
+            ClientStatus::Expired
+        } else if matches!(client_state.refresh_period(), Some(refresh) if age > refresh) {
+            ClientStatus::Stale
+        } else {
+            ClientStatus::Active
+        };
+
+        Self {
+            client_id,
+            last_update,
+            remaining_secs: client_state.trusting_period().saturating_sub(age).as_secs(),
+            status,
+        }
+    }
+
+    /// Query the state of the given client hosted on `chain`.
+    pub fn query(
+        chain: &impl ChainHandle,
+        client_id: &ClientId,
+        now: Timestamp,
+    ) -> Result<Self, RelayerError> {
+        let (client_state, _) = chain.query_client_state(
+            QueryClientStateRequest {
+                client_id: client_id.clone(),
+                height: QueryHeight::Latest,
+            },
+            IncludeProof::No,
+        )?;
+
+        let (consensus_state, _) = chain.query_consensus_state(
+            QueryConsensusStateRequest {
+                client_id: client_id.clone(),
+                consensus_height: client_state.latest_height(),
+                query_height: QueryHeight::Latest,
+            },
+            IncludeProof::No,
+        )?;
+
+        Ok(Self::new(
+            client_id.clone(),
+            &client_state,
+            consensus_state.timestamp(),
+            now,
+        ))
+    }
+
+    pub fn remaining(&self) -> Duration {
+        Duration::from_secs(self.remaining_secs)
+    }
+}
+
+/// The number of packets sent from one end of a path which are not fully relayed yet.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct PendingCounts {
+    /// Not yet received on the other end.
+    pub unreceived_packets: usize,
+    /// Received on the other end, but whose acknowledgement is not yet received back.
+    pub unreceived_acks: usize,
+}
+
+/// One end of a path.
+#[derive(Clone, Debug, Serialize)]
+pub struct PathEnd {
+    pub chain_id: ChainId,
+    pub port_id: PortId,
+    /// Unknown on the counterparty end until the handshake reaches it.
+    pub channel_id: Option<ChannelId>,
+    /// The state of the channel end, if it exists.
+    pub channel_state: Option<ChannelState>,
+    /// The client hosted on this end, if it could be queried.
+    pub client: Option<ClientHealth>,
+    /// The packets sent from this end, if they could be queried.
+    pub pending: Option<PendingCounts>,
+}
+
+impl PathEnd {
+    fn new(chain_id: ChainId, port_id: PortId, channel_id: Option<ChannelId>) -> Self {
+        Self {
+            chain_id,
+            port_id,
+            channel_id,
+            channel_state: None,
+            client: None,
+            pending: None,
+        }
+    }
+
+    /// Whether this end is the given channel.
+    pub fn is(&self, chain_id: &ChainId, port_id: &PortId, channel_id: &ChannelId) -> bool {
+        &self.chain_id == chain_id
+            && &self.port_id == port_id
+            && self.channel_id.as_ref() == Some(channel_id)
+    }
+
+    fn is_open(&self) -> bool {
+        self.channel_state.map_or(false, ChannelState::is_open)
+    }
+}
+
+impl fmt::Display for PathEnd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.channel_id {
+            Some(channel_id) => write!(f, "{}/{}/{}", self.chain_id, self.port_id, channel_id),
+            None => write!(f, "{}/{}/<none>", self.chain_id, self.port_id),
+        }
+    }
+}
+
+/// Why a path is unhealthy.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "problem", rename_all = "snake_case")]
+pub enum PathProblem {
+    /// The channel end on the given chain is not open, or does not exist.
+    ChannelNotOpen {
+        chain_id: ChainId,
+        state: Option<ChannelState>,
+    },
+    /// The client hosted on the given chain is not active.
+    ClientNotActive {
+        chain_id: ChainId,
+        client_id: ClientId,
+        status: ClientStatus,
+    },
+    /// The running relayer has no packet worker for the path.
+    NoWorker,
+    /// The running relayer paused the packet worker of the path in the given direction.
+    WorkerPaused { worker: String },
+    /// Querying the given chain failed.
+    QueryFailed { chain_id: ChainId, reason: String },
+}
+
+impl fmt::Display for PathProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ChannelNotOpen {
+                chain_id,
+                state: Some(state),
+            } => write!(f, "channel on {} is {}", chain_id, state),
+            Self::ChannelNotOpen {
+                chain_id,
+                state: None,
+            } => write!(f, "channel on {} does not exist", chain_id),
+            Self::ClientNotActive {
+                chain_id,
+                client_id,
+                status,
+            } => write!(f, "client {} on {} is {}", client_id, chain_id, status),
+            Self::NoWorker => write!(f, "no packet worker is running"),
+            Self::WorkerPaused { worker } => write!(f, "packet worker {} is paused", worker),
+            Self::QueryFailed { chain_id, reason } => {
+                write!(f, "query to {} failed: {}", chain_id, reason)
+            }
+        }
+    }
+}
+
+/// The health of a path, i.e. of a channel and of the clients and packet workers relaying it.
+#[derive(Clone, Debug, Serialize)]
+pub struct PathHealth {
+    /// The end of the channel on the chain it was found on.
+    pub src: PathEnd,
+    /// The counterparty end of the channel.
+    pub dst: PathEnd,
+    /// The packet workers of the path in a running relayer, if it could be reached.
+    pub workers: Option<Vec<WorkerDesc>>,
+    /// Empty if the path is healthy.
+    pub problems: Vec<PathProblem>,
+}
+
+impl PathHealth {
+    /// Query the health of the path of the given channel on `chain`, whose counterparty is
+    /// on `counterparty_chain`.
+    ///
+    /// Only fails if the channel itself cannot be queried, the failures of the other
+    /// queries are reported as [`PathProblem::QueryFailed`].
+    pub fn query(
+        chain: &impl ChainHandle,
+        counterparty_chain: &impl ChainHandle,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        now: Timestamp,
+    ) -> Result<Self, Error> {
+        let chan_conn_cli = channel_connection_client(chain, port_id, channel_id)?;
+        let counterparty = chan_conn_cli.channel.channel_end.counterparty();
+
+        let mut src = PathEnd::new(chain.id(), port_id.clone(), Some(channel_id.clone()));
+        src.channel_state = Some(*chan_conn_cli.channel.channel_end.state());
+
+        let mut dst = PathEnd::new(
+            counterparty_chain.id(),
+            counterparty.port_id().clone(),
+            counterparty.channel_id().cloned(),
+        );
+
+        let mut problems = Vec::new();
+
+        let counterparty_channel = record(
+            &mut problems,
+            &dst.chain_id,
+            channel_on_destination(
+                &chan_conn_cli.channel,
+                &chan_conn_cli.connection,
+                counterparty_chain,
+            ),
+        )
+        .flatten();
+
+        dst.channel_state = counterparty_channel
+            .as_ref()
+            .map(|channel| *channel.channel_end.state());
+
+        src.client = record(
+            &mut problems,
+            &src.chain_id,
+            ClientHealth::query(chain, &chan_conn_cli.client.client_id, now),
+        );
+
+        dst.client = record(
+            &mut problems,
+            &dst.chain_id,
+            ClientHealth::query(
+                counterparty_chain,
+                chan_conn_cli
+                    .connection
+                    .connection_end
+                    .counterparty()
+                    .client_id(),
+                now,
+            ),
+        );
+
+        if let Some(counterparty_channel) = &counterparty_channel {
+            src.pending = record(
+                &mut problems,
+                &src.chain_id,
+                pending_packet_summary(chain, counterparty_chain, &chan_conn_cli.channel),
+            )
+            .map(pending_counts);
+
+            dst.pending = record(
+                &mut problems,
+                &dst.chain_id,
+                pending_packet_summary(counterparty_chain, chain, counterparty_channel),
+            )
+            .map(pending_counts);
+        }
+
+        Ok(Self::new(src, dst, problems))
+    }
+
+    fn new(src: PathEnd, dst: PathEnd, mut problems: Vec<PathProblem>) -> Self {
+        for end in [&src, &dst] {
+            if !end.is_open() {
+                problems.push(PathProblem::ChannelNotOpen {
+                    chain_id: end.chain_id.clone(),
+                    state: end.channel_state,
+                });
+            }
+
+            match &end.client {
+                Some(client) if client.status != ClientStatus::Active => {
+                    problems.push(PathProblem::ClientNotActive {
+                        chain_id: end.chain_id.clone(),
+                        client_id: client.client_id.clone(),
+                        status: client.status,
+                    })
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            src,
+            dst,
+            workers: None,
+            problems,
+        }
+    }
+
+    /// Attach the packet workers of this path among the given workers of a running relayer,
+    /// and report the problems with them.
+    pub fn with_workers(mut self, workers: &[WorkerDesc]) -> Self {
+        let workers = workers
+            .iter()
+            .filter(|desc| match &desc.object {
+                Object::Packet(packet) => self.has_end(
+                    &packet.src_chain_id,
+                    &packet.src_port_id,
+                    &packet.src_channel_id,
+                ),
+                _ => false,
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        // Packet workers are only spawned for the channels open on both ends
+        if workers.is_empty() && self.src.is_open() && self.dst.is_open() {
+            self.problems.push(PathProblem::NoWorker);
+        }
+
+        for desc in workers.iter().filter(|desc| desc.paused) {
+            self.problems.push(PathProblem::WorkerPaused {
+                worker: desc.object.short_name(),
+            });
+        }
+
+        self.workers = Some(workers);
+        self
+    }
+
+    /// Whether either end of this path is the given channel.
+    pub fn has_end(&self, chain_id: &ChainId, port_id: &PortId, channel_id: &ChannelId) -> bool {
+        self.src.is(chain_id, port_id, channel_id) || self.dst.is(chain_id, port_id, channel_id)
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+impl fmt::Display for PathHealth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} <-> {}: ", self.src, self.dst)?;
+
+        if self.is_healthy() {
+            write!(f, "healthy")?;
+        } else {
+            let problems = self.problems.iter().map(ToString::to_string);
+            write!(f, "{}", problems.collect::<Vec<_>>().join(", "))?;
+        }
+
+        for end in [&self.src, &self.dst] {
+            if let Some(client) = &end.client {
+                write!(
+                    f,
+                    "\n    | client {} on {}: {}, expires in {}",
+                    client.client_id,
+                    end.chain_id,
+                    client.status,
+                    humantime::format_duration(client.remaining())
+                )?;
+            }
+
+            if let Some(pending) = &end.pending {
+                write!(
+                    f,
+                    "\n    | sent from {}: {} unreceived packets, {} unreceived acks",
+                    end.chain_id, pending.unreceived_packets, pending.unreceived_acks
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Sorts the unhealthy paths first, keeping the order of the paths otherwise.
+pub fn unhealthy_first(paths: &mut [PathHealth]) {
+    paths.sort_by_key(PathHealth::is_healthy);
+}
+
+fn pending_counts(pending: PendingPackets) -> PendingCounts {
+    PendingCounts {
+        unreceived_packets: pending.unreceived_packets.len(),
+        unreceived_acks: pending.unreceived_acks.len(),
+    }
+}
+
+/// Returns the value of the given result, or records its error as a problem of the path.
+fn record<T, E: fmt::Display>(
+    problems: &mut Vec<PathProblem>,
+    chain_id: &ChainId,
+    result: Result<T, E>,
+) -> Option<T> {
+    match result {
+        Ok(value) => Some(value),
+        Err(e) => {
+            problems.push(PathProblem::QueryFailed {
+                chain_id: chain_id.clone(),
+                reason: e.to_string(),
+            });
+
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use core::str::FromStr;
+
+    use crate::object::Packet;
+    use crate::worker::WorkerId;
+
+    fn end(chain: &str, channel: &str, state: ChannelState, status: ClientStatus) -> PathEnd {
+        PathEnd {
+            channel_state: Some(state),
+            client: Some(ClientHealth {
+                client_id: ClientId::from_str("07-tendermint-0").unwrap(),
+                last_update: Timestamp::none(),
+                remaining_secs: 0,
+                status,
+            }),
+            ..PathEnd::new(
+                ChainId::from_string(chain),
+                PortId::transfer(),
+                Some(ChannelId::from_str(channel).unwrap()),
+            )
+        }
+    }
+
+    fn healthy_path() -> PathHealth {
+        PathHealth::new(
+            end(
+                "ibc-0",
+                "channel-0",
+                ChannelState::Open,
+                ClientStatus::Active,
+            ),
+            end(
+                "ibc-1",
+                "channel-1",
+                ChannelState::Open,
+                ClientStatus::Active,
+            ),
+            vec![],
+        )
+    }
+
+    fn packet_worker(src_chain: &str, dst_chain: &str, channel: &str, paused: bool) -> WorkerDesc {
+        let object = Object::Packet(Packet {
+            dst_chain_id: ChainId::from_string(dst_chain),
+            src_chain_id: ChainId::from_string(src_chain),
+            src_channel_id: ChannelId::from_str(channel).unwrap(),
+            src_port_id: PortId::transfer(),
+        });
+
+        WorkerDesc {
+            paused,
+            ..WorkerDesc::new(WorkerId::new(0), object, None)
+        }
+    }
+
+    #[test]
+    fn healthy_path_has_no_problems() {
+        let path = healthy_path();
+
+        assert!(path.is_healthy());
+        assert!(path.workers.is_none());
+    }
+
+    #[test]
+    fn channel_and_client_problems() {
+        let path = PathHealth::new(
+            end(
+                "ibc-0",
+                "channel-0",
+                ChannelState::TryOpen,
+                ClientStatus::Stale,
+            ),
+            PathEnd::new(ChainId::from_string("ibc-1"), PortId::transfer(), None),
+            vec![],
+        );
+
+        assert_eq!(
+            path.problems,
+            vec![
+                PathProblem::ChannelNotOpen {
+                    chain_id: ChainId::from_string("ibc-0"),
+                    state: Some(ChannelState::TryOpen),
+                },
+                PathProblem::ClientNotActive {
+                    chain_id: ChainId::from_string("ibc-0"),
+                    client_id: ClientId::from_str("07-tendermint-0").unwrap(),
+                    status: ClientStatus::Stale,
+                },
+                PathProblem::ChannelNotOpen {
+                    chain_id: ChainId::from_string("ibc-1"),
+                    state: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn worker_problems() {
+        let workers = [
+            packet_worker("ibc-0", "ibc-1", "channel-0", false),
+            packet_worker("ibc-1", "ibc-0", "channel-1", true),
+            packet_worker("ibc-0", "ibc-2", "channel-2", true),
+        ];
+
+        let path = healthy_path().with_workers(&workers);
+
+        assert_eq!(path.workers.as_ref().map(Vec::len), Some(2));
+        assert_eq!(
+            path.problems,
+            vec![PathProblem::WorkerPaused {
+                worker: workers[1].object.short_name()
+            }]
+        );
+
+        let path = healthy_path().with_workers(&workers[2..]);
+
+        assert_eq!(path.problems, vec![PathProblem::NoWorker]);
+    }
+
+    #[test]
+    fn sorts_unhealthy_paths_first() {
+        let unhealthy = healthy_path().with_workers(&[]);
+
+        let mut paths = vec![healthy_path(), unhealthy, healthy_path()];
+        unhealthy_first(&mut paths);
+
+        let healthy = paths.iter().map(PathHealth::is_healthy).collect::<Vec<_>>();
+        assert_eq!(healthy, vec![false, true, true]);
+    }
+}
@@ -0,0 +1,198 @@
+//! Progress of the opening handshakes of connections and channels, reported
+//! step by step to the users of the handshake drivers, e.g. to be streamed
+//! by `create connection --progress json`.
+
+use serde::Serialize;
+
+use ibc::core::ics04_channel::channel::State as ChannelState;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId};
+use ibc::events::IbcEvent;
+use ibc::Height;
+
+use crate::chain::handle::ChainHandle;
+use crate::chain::requests::QueryChannelOpenTxRequest;
+
+/// A step of an opening handshake, completed on one of the chains.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HandshakeStep {
+    /// The `OpenInit` message was committed, creating the end which initiates the handshake.
+    InitSubmitted,
+    /// The `OpenTry` message was committed, creating the counterparty end.
+    TryConfirmed,
+    /// The `OpenAck` message was committed, opening the end which initiated the handshake.
+    AckSubmitted,
+    /// The `OpenConfirm` message was committed, opening the counterparty end.
+    ConfirmObserved,
+}
+
+/// The identifiers of the end of a connection or a channel, and of its counterparty.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "object", rename_all = "snake_case")]
+pub enum HandshakeEnds {
+    Connection {
+        client_id: ClientId,
+        connection_id: Option<ConnectionId>,
+        counterparty_client_id: ClientId,
+        counterparty_connection_id: Option<ConnectionId>,
+    },
+    Channel {
+        connection_id: ConnectionId,
+        port_id: PortId,
+        channel_id: Option<ChannelId>,
+        counterparty_port_id: PortId,
+        counterparty_channel_id: Option<ChannelId>,
+    },
+}
+
+/// A completed step of an opening handshake.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct HandshakeProgress {
+    pub step: HandshakeStep,
+    /// The chain on which the step completed.
+    pub chain_id: ChainId,
+    /// The height at which the transaction of the step was committed.
+    pub height: Height,
+    /// The hash of the transaction of the step, if it could be found.
+    pub tx_hash: Option<String>,
+    #[serde(flatten)]
+    pub ends: HandshakeEnds,
+}
+
+impl HandshakeProgress {
+    /// The progress reported by the event of a handshake step committed on `chain_id`,
+    /// or `None` if the event is not the one of a handshake step.
+    pub fn from_event(chain_id: ChainId, event: &IbcEvent) -> Option<Self> {
+        let step = match event {
+            IbcEvent::OpenInitConnection(_) | IbcEvent::OpenInitChannel(_) => {
+                HandshakeStep::InitSubmitted
+            }
+            IbcEvent::OpenTryConnection(_) | IbcEvent::OpenTryChannel(_) => {
+                HandshakeStep::TryConfirmed
+            }
+            IbcEvent::OpenAckConnection(_) | IbcEvent::OpenAckChannel(_) => {
+                HandshakeStep::AckSubmitted
+            }
+            IbcEvent::OpenConfirmConnection(_) | IbcEvent::OpenConfirmChannel(_) => {
+                HandshakeStep::ConfirmObserved
+            }
+            _ => return None,
+        };
+
+        let ends = match event.connection_attributes() {
+            Some(attributes) => HandshakeEnds::Connection {
+                client_id: attributes.client_id.clone(),
+                connection_id: attributes.connection_id.clone(),
+                counterparty_client_id: attributes.counterparty_client_id.clone(),
+                counterparty_connection_id: attributes.counterparty_connection_id.clone(),
+            },
+            None => {
+                let attributes = event.clone().channel_attributes()?;
+
+                HandshakeEnds::Channel {
+                    connection_id: attributes.connection_id,
+                    port_id: attributes.port_id,
+                    channel_id: attributes.channel_id,
+                    counterparty_port_id: attributes.counterparty_port_id,
+                    counterparty_channel_id: attributes.counterparty_channel_id,
+                }
+            }
+        };
+
+        Some(Self {
+            step,
+            chain_id,
+            height: event.height(),
+            tx_hash: None,
+            ends,
+        })
+    }
+
+    /// Sets the hash of the transaction of the step.
+    pub fn with_tx_hash(self, tx_hash: Option<String>) -> Self {
+        Self { tx_hash, ..self }
+    }
+
+    /// Looks up on `chain`, the chain on which the step completed, the hash of the
+    /// transaction of a step of a channel handshake.
+    ///
+    /// The events of the handshake steps do not carry the hash of their transaction,
+    /// and it can only be found for the steps of channel handshakes; `None` is
+    /// returned for connection handshakes or if the lookup fails.
+    pub fn query_channel_tx_hash<Chain: ChainHandle>(&self, chain: &Chain) -> Option<String> {
+        let (port_id, channel_id) = match &self.ends {
+            HandshakeEnds::Channel {
+                port_id,
+                channel_id: Some(channel_id),
+                ..
+            } => (port_id.clone(), channel_id.clone()),
+            _ => return None,
+        };
+
+        let state = match self.step {
+            HandshakeStep::InitSubmitted => ChannelState::Init,
+            HandshakeStep::TryConfirmed => ChannelState::TryOpen,
+            HandshakeStep::AckSubmitted | HandshakeStep::ConfirmObserved => ChannelState::Open,
+        };
+
+        chain
+            .query_channel_open_tx(QueryChannelOpenTxRequest {
+                port_id,
+                channel_id,
+                state,
+            })
+            .ok()
+            .flatten()
+            .map(|tx| tx.tx_hash)
+    }
+}
+
+/// Reports to `progress` the handshake step of `event`, committed on `chain_id`, if any.
+pub(crate) fn report(
+    progress: &mut dyn FnMut(HandshakeProgress),
+    chain_id: ChainId,
+    event: &IbcEvent,
+) {
+    if let Some(step) = HandshakeProgress::from_event(chain_id, event) {
+        progress(step);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HandshakeProgress, HandshakeStep};
+
+    use ibc::core::ics02_client::events::NewBlock;
+    use ibc::core::ics03_connection::events::{Attributes, OpenTry};
+    use ibc::core::ics24_host::identifier::{ChainId, ConnectionId};
+    use ibc::events::IbcEvent;
+    use ibc::Height;
+
+    #[test]
+    fn progress_from_connection_event() {
+        let event = IbcEvent::OpenTryConnection(OpenTry::from(Attributes {
+            height: Height::new(1, 42).unwrap(),
+            connection_id: Some(ConnectionId::new(3)),
+            ..Attributes::default()
+        }));
+
+        let progress =
+            HandshakeProgress::from_event(ChainId::from_string("ibc-1"), &event).unwrap();
+
+        assert_eq!(progress.step, HandshakeStep::TryConfirmed);
+
+        let json = serde_json::to_value(&progress).unwrap();
+
+        assert_eq!(json["step"], "try_confirmed");
+        assert_eq!(json["object"], "connection");
+        assert_eq!(json["connection_id"], "connection-3");
+        assert_eq!(json["tx_hash"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn no_progress_from_other_events() {
+        let event = IbcEvent::NewBlock(NewBlock::new(Height::new(1, 42).unwrap()));
+
+        assert!(HandshakeProgress::from_event(ChainId::from_string("ibc-1"), &event).is_none());
+    }
+}
@@ -81,6 +81,14 @@ impl<Chain: ChainHandle> Registry<Chain> {
         }
     }
 
+    /// Set the key of the given chain in the configuration of the runtimes spawned from now on,
+    /// after the key of its running runtime was rotated.
+    pub fn set_key_name(&mut self, chain_id: &ChainId, key_name: String) {
+        if let Some(chain_config) = self.config.find_chain_mut(chain_id) {
+            chain_config.key_name = key_name;
+        }
+    }
+
     /// Shutdown the runtime associated with the given chain identifier.
     pub fn shutdown(&mut self, chain_id: &ChainId) {
         if let Some(handle) = self.handles.remove(chain_id) {
@@ -2,10 +2,12 @@ use crossbeam_channel::TryRecvError;
 use tracing::{error, trace};
 
 use crate::{
-    config::Config,
+    config::{sanitize::SanitizedChainConfig, Config},
+    object::Object,
+    path::health::PathHealth,
     rest::request::ReplySender,
-    rest::request::{Request, VersionInfo},
-    supervisor::dump_state::SupervisorState,
+    rest::request::{HealthInfo, KeyRotation, Request, RotateKey, VersionInfo},
+    supervisor::dump_state::{SupervisorState, WorkerDesc},
 };
 
 pub mod request;
@@ -31,6 +33,11 @@ pub type Receiver = crossbeam_channel::Receiver<Request>;
 //  e.g., adjusting chain config, removing chains, etc.
 pub enum Command {
     DumpState(ReplySender<SupervisorState>),
+    Health(ReplySender<HealthInfo>),
+    Paths(ReplySender<Vec<PathHealth>>),
+    PauseWorker(Object, ReplySender<WorkerDesc>),
+    ResumeWorker(Object, ReplySender<WorkerDesc>),
+    RotateKey(RotateKey, ReplySender<KeyRotation>),
 }
 
 /// Process incoming REST requests.
@@ -69,7 +76,7 @@ pub fn process_incoming_requests(config: &Config, channel: &Receiver) -> Option<
 
                 let result = config
                     .find_chain(&chain_id)
-                    .cloned()
+                    .map(SanitizedChainConfig::from)
                     .ok_or(RestApiError::ChainConfigNotFound(chain_id));
 
                 reply_to
@@ -82,6 +89,36 @@ pub fn process_incoming_requests(config: &Config, channel: &Receiver) -> Option<
 
                 return Some(Command::DumpState(reply_to));
             }
+
+            Request::Health { reply_to } => {
+                trace!("Health");
+
+                return Some(Command::Health(reply_to));
+            }
+
+            Request::GetPaths { reply_to } => {
+                trace!("GetPaths");
+
+                return Some(Command::Paths(reply_to));
+            }
+
+            Request::PauseWorker { object, reply_to } => {
+                trace!("PauseWorker {}", object.short_name());
+
+                return Some(Command::PauseWorker(object, reply_to));
+            }
+
+            Request::ResumeWorker { object, reply_to } => {
+                trace!("ResumeWorker {}", object.short_name());
+
+                return Some(Command::ResumeWorker(object, reply_to));
+            }
+
+            Request::RotateKey { request, reply_to } => {
+                trace!("RotateKey {} {}", request.chain_id, request.key_name);
+
+                return Some(Command::RotateKey(request, reply_to));
+            }
         },
         Err(e) => {
             if !matches!(e, TryRecvError::Empty) {
@@ -23,6 +23,18 @@ pub enum RestApiError {
     #[error("failed while parsing the request body into a chain configuration: {0}")]
     InvalidChainConfig(String),
 
+    #[error("could not find a packet worker for: {0}")]
+    WorkerNotFound(String),
+
+    #[error("failed while parsing the request body into a worker object: {0}")]
+    InvalidWorkerObject(String),
+
+    #[error("failed while parsing the request body into a key rotation: {0}")]
+    InvalidKeyRotation(String),
+
+    #[error("failed to rotate the key of chain {0}: {1}")]
+    KeyRotation(ChainId, String),
+
     #[error("not implemented")]
     Unimplemented,
 }
@@ -36,6 +48,10 @@ impl RestApiError {
             RestApiError::ChainConfigNotFound(_) => "ChainConfigNotFound",
             RestApiError::InvalidChainId(_, _) => "InvalidChainId",
             RestApiError::InvalidChainConfig(_) => "InvalidChainConfig",
+            RestApiError::WorkerNotFound(_) => "WorkerNotFound",
+            RestApiError::InvalidWorkerObject(_) => "InvalidWorkerObject",
+            RestApiError::InvalidKeyRotation(_) => "InvalidKeyRotation",
+            RestApiError::KeyRotation(_, _) => "KeyRotation",
             RestApiError::Unimplemented => "Unimplemented",
         }
     }
@@ -1,8 +1,14 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use ibc::core::ics24_host::identifier::ChainId;
 
-use crate::{config::ChainConfig, rest::RestApiError, supervisor::dump_state::SupervisorState};
+use crate::{
+    config::sanitize::SanitizedChainConfig,
+    object::Object,
+    path::health::PathHealth,
+    rest::RestApiError,
+    supervisor::dump_state::{SupervisorState, WorkerDesc},
+};
 
 pub type ReplySender<T> = crossbeam_channel::Sender<Result<T, RestApiError>>;
 pub type ReplyReceiver<T> = crossbeam_channel::Receiver<Result<T, RestApiError>>;
@@ -17,6 +23,30 @@ pub struct VersionInfo {
     pub version: String,
 }
 
+/// Whether the relayer is processing live events, and whether it has completed
+/// its startup, ie. whether the packets pending on start have all been cleared,
+/// or clearing them has timed out.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct HealthInfo {
+    pub live: bool,
+    pub ready: bool,
+}
+
+/// The body of a request to rotate the key signing the transactions submitted to a chain.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RotateKey {
+    pub chain_id: ChainId,
+    pub key_name: String,
+}
+
+/// The key signing the transactions submitted to a chain, before and after its rotation.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyRotation {
+    pub chain_id: ChainId,
+    pub previous_key_name: String,
+    pub key_name: String,
+}
+
 /// REST API request variants
 #[derive(Clone, Debug)]
 pub enum Request {
@@ -28,12 +58,35 @@ pub enum Request {
         reply_to: ReplySender<SupervisorState>,
     },
 
+    Health {
+        reply_to: ReplySender<HealthInfo>,
+    },
+
+    GetPaths {
+        reply_to: ReplySender<Vec<PathHealth>>,
+    },
+
     GetChains {
         reply_to: ReplySender<Vec<ChainId>>,
     },
 
     GetChain {
         chain_id: ChainId,
-        reply_to: ReplySender<ChainConfig>,
+        reply_to: ReplySender<SanitizedChainConfig>,
+    },
+
+    PauseWorker {
+        object: Object,
+        reply_to: ReplySender<WorkerDesc>,
+    },
+
+    ResumeWorker {
+        object: Object,
+        reply_to: ReplySender<WorkerDesc>,
+    },
+
+    RotateKey {
+        request: RotateKey,
+        reply_to: ReplySender<KeyRotation>,
     },
 }
@@ -1,4 +1,6 @@
 use flex_error::define_error;
+use once_cell::sync::Lazy;
+use regex::Regex;
 use tendermint::abci::Code;
 use tendermint_rpc::endpoint::broadcast::tx_commit::TxResult;
 
@@ -198,3 +200,43 @@ pub fn sdk_error_from_tx_sync_error_code(code: u32) -> SdkError {
         _ => SdkError::unknown_tx_sync(code),
     }
 }
+
+/// The index of the message which failed in a transaction, as reported by the log of
+/// its `deliver_tx` result, e.g. `failed to execute message; message index: 1: ...`.
+///
+/// The errors of the transaction as a whole, e.g. in the ante handler, report no index.
+/// Cf: <https://github.com/cosmos/cosmos-sdk/blob/v0.45.6/baseapp/baseapp.go#L734>
+pub fn failed_msg_index(log: &str) -> Option<usize> {
+    static MSG_INDEX: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"failed to execute message; message index: (\d+)").unwrap());
+
+    MSG_INDEX.captures(log)?[1].parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::failed_msg_index;
+
+    #[test]
+    fn msg_index_of_failed_message() {
+        assert_eq!(
+            failed_msg_index("failed to execute message; message index: 1: receive packet verification failed: packet already received"),
+            Some(1)
+        );
+        assert_eq!(
+            failed_msg_index("failed to execute message; message index: 12: invalid proof"),
+            Some(12)
+        );
+    }
+
+    #[test]
+    fn no_msg_index_of_failed_transaction() {
+        assert_eq!(
+            failed_msg_index(
+                "0stake is smaller than 4000stake: insufficient funds: insufficient funds"
+            ),
+            None
+        );
+        assert_eq!(failed_msg_index(""), None);
+    }
+}
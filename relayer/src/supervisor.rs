@@ -4,14 +4,19 @@ use core::convert::Infallible;
 use core::ops::Deref;
 use core::time::Duration;
 use std::sync::RwLock;
+use std::time::Instant;
 
 use crossbeam_channel::{unbounded, Receiver, Sender};
 use itertools::Itertools;
 use tracing::{debug, error, error_span, info, trace, warn};
 
 use ibc::{
-    core::ics24_host::identifier::{ChainId, ChannelId, PortId},
+    core::{
+        ics04_channel::events::Attributes as ChannelAttributes,
+        ics24_host::identifier::{ChainId, ChannelId, PortId},
+    },
     events::IbcEvent,
+    timestamp::Timestamp,
     Height,
 };
 
@@ -19,9 +24,14 @@ use crate::{
     chain::{endpoint::HealthCheck, handle::ChainHandle, tracking::TrackingId},
     config::Config,
     event::monitor::{self, Error as EventError, ErrorDetail as EventErrorDetail, EventBatch},
-    object::Object,
+    object::{Channel, Object, Packet},
+    path::health::{unhealthy_first, PathHealth},
     registry::{Registry, SharedRegistry},
-    rest,
+    rest::{
+        self,
+        request::{HealthInfo, KeyRotation, RotateKey},
+        RestApiError,
+    },
     supervisor::scan::ScanMode,
     telemetry,
     util::{
@@ -37,16 +47,32 @@ use client_state_filter::{FilterPolicy, Permission};
 pub mod error;
 pub use error::{Error, ErrorDetail};
 
+pub mod halt;
+use halt::{HaltDetector, HaltTransition, HALT_CHECK_INTERVAL};
+
 pub mod dump_state;
-use dump_state::SupervisorState;
+use dump_state::{SupervisorState, WorkerDesc};
 
+pub mod prewarm;
 pub mod scan;
 pub mod spawn;
+pub mod startup;
+use startup::{Readiness, StartupTracker, STARTUP_CLEAR_TIMEOUT};
 
 pub mod cmd;
 use cmd::SupervisorCmd;
 
-use self::{scan::ChainScanner, spawn::SpawnContext};
+use self::{
+    scan::{report_stuck_channels, ChainScanner},
+    spawn::SpawnContext,
+};
+
+/// How far the latest block of a node may lag behind the local time
+/// before the node is reported as out of sync on startup.
+const MAX_SYNC_LAG: Duration = Duration::from_secs(60);
+
+/// How often the supervisor checks for packet workers which panicked, to respawn them.
+const RESPAWN_CHECK_INTERVAL: Duration = Duration::from_secs(5);
 
 type ArcBatch = Arc<monitor::Result<EventBatch>>;
 type Subscription = Receiver<ArcBatch>;
@@ -72,6 +98,10 @@ pub struct SupervisorOptions {
     /// even when an allow list is configured for a chain and the full scan could
     /// be omitted.
     pub force_full_scan: bool,
+
+    /// Estimate and log how long each channel stuck in the opening handshake found
+    /// by the scan has been stuck for, which takes a query for each of them.
+    pub verbose_scan: bool,
 }
 
 /**
@@ -145,8 +175,32 @@ pub fn spawn_supervisor_tasks<Chain: ChainHandle>(
 
     info!("Scanned chains:");
     info!("{}", scan);
+    info!("discovered {} path(s)", scan.channel_count());
+
+    report_stuck_channels(&mut registry.write(), &scan, options.verbose_scan);
+
+    if config.mode.prewarm_clients {
+        prewarm::prewarm_clients(&mut registry.write(), &scan);
+    }
+
+    // Track the clearing of the packets pending on start by the workers spawned from
+    // the scan, so that the supervisor can be marked as ready once it is complete.
+    let readiness = Readiness::new();
+    let (progress_tx, progress_rx) = unbounded();
 
-    spawn_context(&config, &mut registry.write(), &mut workers.acquire_write()).spawn_workers(scan);
+    {
+        let mut workers = workers.acquire_write();
+
+        workers.report_startup_progress(Some(progress_tx));
+        spawn_context(&config, &mut registry.write(), &mut workers).spawn_workers(scan);
+        workers.report_startup_progress(None);
+    }
+
+    let startup_task = spawn_startup_worker(StartupTracker::new(
+        progress_rx,
+        readiness.clone(),
+        STARTUP_CLEAR_TIMEOUT,
+    ));
 
     let subscriptions = init_subscriptions(&config, &mut registry.write())?;
 
@@ -158,13 +212,24 @@ pub fn spawn_supervisor_tasks<Chain: ChainHandle>(
         subscriptions,
     );
 
+    // The packets pending on start are cleared in the background,
+    // while the live events are processed from now on.
+    readiness.set_live();
+    info!("processing live events");
+
     let cmd_task = spawn_cmd_worker(registry.clone(), workers.clone(), cmd_rx);
 
-    let mut tasks = vec![cmd_task];
+    let respawn_task = spawn_respawn_worker(config.clone(), registry.clone(), workers.clone());
+
+    let mut tasks = vec![cmd_task, startup_task, respawn_task];
     tasks.extend(batch_tasks);
 
+    if let Some(halt_task) = spawn_halt_worker(&config, registry.clone(), workers.clone()) {
+        tasks.push(halt_task);
+    }
+
     if let Some(rest_rx) = rest_rx {
-        let rest_task = spawn_rest_worker(config, registry, workers, rest_rx);
+        let rest_task = spawn_rest_worker(config, registry, workers, readiness, rest_rx);
         tasks.push(rest_task);
     }
 
@@ -225,6 +290,12 @@ pub fn spawn_cmd_worker<Chain: ChainHandle>(
                     SupervisorCmd::DumpState(reply_to) => {
                         dump_state(&registry.read(), &workers.acquire_read(), reply_to);
                     }
+                    SupervisorCmd::PauseWorker(object) => {
+                        set_worker_paused(&mut workers.acquire_write(), &object, true);
+                    }
+                    SupervisorCmd::ResumeWorker(object) => {
+                        set_worker_paused(&mut workers.acquire_write(), &object, false);
+                    }
                 }
             }
 
@@ -233,17 +304,164 @@ pub fn spawn_cmd_worker<Chain: ChainHandle>(
     )
 }
 
-pub fn spawn_rest_worker<Chain: ChainHandle>(
+/// Spawns a task which tracks the clearing of the packets pending on start,
+/// and terminates once the supervisor has been marked as ready.
+fn spawn_startup_worker(mut tracker: StartupTracker) -> TaskHandle {
+    spawn_background_task(
+        error_span!("startup"),
+        Some(Duration::from_millis(500)),
+        move || -> Result<Next, TaskError<Infallible>> {
+            if tracker.step() {
+                Ok(Next::Abort)
+            } else {
+                Ok(Next::Continue)
+            }
+        },
+    )
+}
+
+/// Spawns a task which polls the latest height of the chains with halt detection enabled,
+/// if any, and stops the packet workers from relaying to a chain while it is halted.
+fn spawn_halt_worker<Chain: ChainHandle>(
+    config: &Config,
+    registry: SharedRegistry<Chain>,
+    workers: Arc<RwLock<WorkerMap>>,
+) -> Option<TaskHandle> {
+    let mut detector = HaltDetector::new();
+    let now = Instant::now();
+
+    for chain_config in &config.chains {
+        if let Some(window) = chain_config.halt_detection_window {
+            detector.track(chain_config.id.clone(), window, now);
+        }
+    }
+
+    // Nothing to do if halt detection is not enabled for any chain
+    detector.chains().next()?;
+
+    let task = spawn_background_task(
+        error_span!("halt"),
+        Some(HALT_CHECK_INTERVAL),
+        move || -> Result<Next, TaskError<Infallible>> {
+            let chain_ids: Vec<ChainId> = detector.chains().cloned().collect();
+
+            for chain_id in chain_ids {
+                check_halted(&registry, &workers, &mut detector, &chain_id);
+            }
+
+            Ok(Next::Continue)
+        },
+    );
+
+    Some(task)
+}
+
+fn check_halted<Chain: ChainHandle>(
+    registry: &SharedRegistry<Chain>,
+    workers: &Arc<RwLock<WorkerMap>>,
+    detector: &mut HaltDetector,
+    chain_id: &ChainId,
+) {
+    // A chain which cannot be queried does not progress, and is eventually considered halted
+    match registry
+        .get_or_spawn(chain_id)
+        .map(|chain| chain.query_sync_status())
+    {
+        Ok(Ok(status)) => detector.observe(
+            chain_id,
+            status.height,
+            status.timestamp,
+            Instant::now(),
+            Timestamp::now(),
+        ),
+        Ok(Err(e)) => debug!(chain = %chain_id, "failed to query the latest height: {}", e),
+        Err(e) => debug!(chain = %chain_id, "failed to spawn the chain runtime: {}", e),
+    }
+
+    match detector.check(chain_id, Instant::now()) {
+        Some(HaltTransition::Halted) => {
+            warn!(
+                chain = %chain_id,
+                "chain has not produced a recent block, no longer relaying packets to it"
+            );
+
+            workers.acquire_write().set_chain_halted(chain_id, true);
+            telemetry!(chain_halted, chain_id, true);
+        }
+        Some(HaltTransition::Resumed) => {
+            info!(
+                chain = %chain_id,
+                "chain is producing blocks again, relaying packets to it again"
+            );
+
+            workers.acquire_write().set_chain_halted(chain_id, false);
+            telemetry!(chain_halted, chain_id, false);
+        }
+        None => {}
+    }
+}
+
+/// Spawns a task which respawns the packet workers aborted after one of their tasks panicked.
+fn spawn_respawn_worker<Chain: ChainHandle>(
     config: Config,
     registry: SharedRegistry<Chain>,
     workers: Arc<RwLock<WorkerMap>>,
+) -> TaskHandle {
+    spawn_background_task(
+        error_span!("respawn"),
+        Some(RESPAWN_CHECK_INTERVAL),
+        move || -> Result<Next, TaskError<Infallible>> {
+            respawn_panicked_workers(&config, &mut workers.acquire_write(), |chain_id| {
+                registry.get_or_spawn(chain_id).map_err(Error::spawn)
+            });
+
+            Ok(Next::Continue)
+        },
+    )
+}
+
+/// Respawns the packet workers aborted after one of their tasks panicked. A respawned
+/// worker first confirms the transactions submitted by its predecessor, so that the
+/// packets they relay are not relayed twice.
+fn respawn_panicked_workers<Chain: ChainHandle>(
+    config: &Config,
+    workers: &mut WorkerMap,
+    mut get_or_spawn_chain: impl FnMut(&ChainId) -> Result<Chain, Error>,
+) {
+    for object in workers.panicked_packet_workers() {
+        let chains = get_or_spawn_chain(object.src_chain_id())
+            .and_then(|src| Ok((src, get_or_spawn_chain(object.dst_chain_id())?)));
+
+        match chains {
+            Ok((src, dst)) => {
+                warn!(
+                    "respawning packet worker for {} after it panicked",
+                    object.short_name()
+                );
+
+                workers.respawn(src, dst, &object, config);
+            }
+            Err(e) => error!(
+                "failed to respawn packet worker for {} after it panicked, reason: {}",
+                object.short_name(),
+                e
+            ),
+        }
+    }
+}
+
+pub fn spawn_rest_worker<Chain: ChainHandle>(
+    mut config: Config,
+    registry: SharedRegistry<Chain>,
+    workers: Arc<RwLock<WorkerMap>>,
+    readiness: Readiness,
     rest_rx: rest::Receiver,
 ) -> TaskHandle {
     spawn_background_task(
         error_span!("rest"),
         Some(Duration::from_millis(500)),
         move || -> Result<Next, TaskError<Infallible>> {
-            handle_rest_requests(&config, &registry.read(), &workers.acquire_read(), &rest_rx);
+            handle_rest_requests(&mut config, &registry, &workers, &readiness, &rest_rx);
 
             Ok(Next::Continue)
         },
@@ -461,6 +679,199 @@ pub fn collect_events(
     collected
 }
 
+/// Split the `events` forwarded to a worker into chunks of at most `max_events` events,
+/// preserving their order, so that a block with a huge number of events does not hold
+/// up the worker, and the chain runtimes it shares with other paths, for minutes.
+///
+/// The events of a same packet are kept in the same chunk, for the packet not to be
+/// processed partially, hence a chunk may exceed `max_events` when these events are
+/// interleaved with the events of other packets. A `max_events` of `0` disables the splitting.
+pub fn split_events(events: Vec<IbcEvent>, max_events: usize) -> Vec<Vec<IbcEvent>> {
+    if max_events == 0 || events.len() <= max_events {
+        return vec![events];
+    }
+
+    let packet_key = |event: &IbcEvent| {
+        event.packet().map(|packet| {
+            (
+                packet.source_port.clone(),
+                packet.source_channel.clone(),
+                packet.sequence,
+            )
+        })
+    };
+
+    // The position of the last event of each packet in the batch
+    let last_positions: HashMap<_, usize> = events
+        .iter()
+        .enumerate()
+        .filter_map(|(position, event)| packet_key(event).map(|key| (key, position)))
+        .collect();
+
+    let mut chunks = vec![];
+    let mut chunk = Vec::with_capacity(max_events);
+
+    // The position of the last event of the packets which have events in the current chunk
+    let mut chunk_end = 0;
+
+    for (position, event) in events.into_iter().enumerate() {
+        if let Some(key) = packet_key(&event) {
+            chunk_end = chunk_end.max(last_positions[&key]);
+        }
+
+        chunk.push(event);
+
+        if chunk.len() >= max_events && chunk_end <= position {
+            chunks.push(core::mem::replace(
+                &mut chunk,
+                Vec::with_capacity(max_events),
+            ));
+        }
+    }
+
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+
+    chunks
+}
+
+/// A change in the state of a channel end, observed from the handshake and closing events
+/// of the channel, which requires spawning or shutting down the workers relaying on it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChannelChange {
+    /// The channel end is open, the packets sent on it can be relayed.
+    Opened(Packet),
+    /// The channel end is closed, no more packets are sent on it.
+    Closed(Packet),
+}
+
+/// Collect the changes of the channels which were opened or closed in the given batch,
+/// so that the packets sent on the channels created after the startup scan are relayed
+/// right away, even if channel workers are disabled.
+///
+/// A `chan_open_ack` event opens the channel end on the source chain, while
+/// `chan_open_confirm` and `chan_close_confirm` events open or close both ends
+/// of the channel. Only the channel ends allowed by the packet filter are opened.
+pub fn collect_channel_changes(
+    config: &Config,
+    src_chain: &impl ChainHandle,
+    batch: &EventBatch,
+) -> Vec<ChannelChange> {
+    if !config.mode.packets.enabled {
+        return vec![];
+    }
+
+    let packets = |event: &IbcEvent, attributes: ChannelAttributes| {
+        Object::packets_from_chan_events(&attributes, src_chain).unwrap_or_else(|e| {
+            warn!(
+                chain = %src_chain.id(),
+                "failed to build the packet objects for event {}: {}", event, e
+            );
+
+            vec![]
+        })
+    };
+
+    let mut changes = vec![];
+
+    for event in &batch.events {
+        match event {
+            IbcEvent::OpenAckChannel(open_ack) => {
+                // The channel end on the counterparty chain is still in TryOpen state
+                let opened = packets(event, open_ack.clone().into()).into_iter().take(1);
+                changes.extend(opened.map(ChannelChange::Opened));
+            }
+            IbcEvent::OpenConfirmChannel(open_confirm) => {
+                let opened = packets(event, open_confirm.clone().into());
+                changes.extend(opened.into_iter().map(ChannelChange::Opened));
+            }
+            IbcEvent::CloseConfirmChannel(close_confirm) => {
+                let closed = packets(event, close_confirm.clone().into());
+                changes.extend(closed.into_iter().map(ChannelChange::Closed));
+            }
+            _ => (),
+        }
+    }
+
+    changes.retain(|change| match change {
+        ChannelChange::Opened(path) => is_channel_allowed(
+            config,
+            &path.src_chain_id,
+            &path.src_port_id,
+            &path.src_channel_id,
+        ),
+        ChannelChange::Closed(_) => true,
+    });
+
+    changes
+}
+
+/// Spawn the packet workers of the opened channel ends, and shut down
+/// the packet and channel workers of the closed channel ends.
+fn update_channel_workers<Chain: ChainHandle>(
+    config: &Config,
+    workers: &mut WorkerMap,
+    changes: Vec<ChannelChange>,
+    mut get_or_spawn_chain: impl FnMut(&ChainId) -> Result<Chain, Error>,
+) {
+    for change in changes {
+        // The channel went through a handshake, which may have changed its version
+        match &change {
+            ChannelChange::Opened(path) | ChannelChange::Closed(path) => {
+                workers.invalidate_channel_versions(path)
+            }
+        }
+
+        match change {
+            ChannelChange::Opened(path) => {
+                let chains = get_or_spawn_chain(&path.src_chain_id)
+                    .and_then(|src| Ok((src, get_or_spawn_chain(&path.dst_chain_id)?)));
+
+                let (src, dst) = match chains {
+                    Ok(chains) => chains,
+                    Err(e) => {
+                        error!(
+                            "skipped packet worker for newly opened channel {}, reason: {}",
+                            path.short_name(),
+                            e
+                        );
+
+                        continue;
+                    }
+                };
+
+                let object = Object::Packet(path);
+
+                workers.spawn(src, dst, &object, config).then(|| {
+                    info!(
+                        "spawned packet worker for newly opened channel: {}",
+                        object.short_name()
+                    )
+                });
+            }
+            ChannelChange::Closed(path) => {
+                let channel_object = Object::Channel(Channel {
+                    dst_chain_id: path.dst_chain_id.clone(),
+                    src_chain_id: path.src_chain_id.clone(),
+                    src_channel_id: path.src_channel_id.clone(),
+                    src_port_id: path.src_port_id.clone(),
+                });
+
+                for object in [Object::Packet(path), channel_object] {
+                    if workers.contains(&object) {
+                        info!(
+                            "shutting down worker of closed channel: {}",
+                            object.short_name()
+                        );
+                        workers.shutdown_worker(&object);
+                    }
+                }
+            }
+        }
+    }
+}
+
 /// Create a new `SpawnContext` for spawning workers.
 fn spawn_context<'a, Chain: ChainHandle>(
     config: &'a Config,
@@ -490,11 +901,15 @@ fn health_check<Chain: ChainHandle>(config: &Config, registry: &mut Registry<Cha
         let chain = registry.get_or_spawn(id);
 
         match chain {
-            Ok(chain) => match chain.health_check() {
-                Ok(Healthy) => info!(chain = %id, "chain is healthy"),
-                Ok(Unhealthy(e)) => warn!(chain = %id, "chain is unhealthy: {}", e),
-                Err(e) => error!(chain = %id, "failed to perform health check: {}", e),
-            },
+            Ok(chain) => {
+                match chain.health_check() {
+                    Ok(Healthy) => info!(chain = %id, "chain is healthy"),
+                    Ok(Unhealthy(e)) => warn!(chain = %id, "chain is unhealthy: {}", e),
+                    Err(e) => error!(chain = %id, "failed to perform health check: {}", e),
+                }
+
+                sync_check(&chain);
+            }
             Err(e) => {
                 error!(
                     chain = %id,
@@ -506,6 +921,38 @@ fn health_check<Chain: ChainHandle>(config: &Config, registry: &mut Registry<Cha
     }
 }
 
+/// Check that the node of the given chain is fully synced, as relaying
+/// through a node which lags behind produces stale proofs.
+fn sync_check<Chain: ChainHandle>(chain: &Chain) {
+    let id = chain.id();
+
+    let status = match chain.query_sync_status() {
+        Ok(status) => status,
+        Err(e) => {
+            error!(chain = %id, "failed to query sync status: {}", e);
+            return;
+        }
+    };
+
+    let now = Timestamp::now();
+    let lag = status.lag(now);
+
+    telemetry!(chain_sync_lag, &id, lag);
+
+    if status.catching_up {
+        warn!(chain = %id, "node is still catching up, at height {}", status.height);
+    } else if !status.is_synced(now, Some(MAX_SYNC_LAG)) {
+        warn!(
+            chain = %id,
+            "latest block of the node lags behind by {:?}, more than {:?}",
+            lag,
+            MAX_SYNC_LAG
+        );
+    } else {
+        info!(chain = %id, "node is synced, lagging behind by {:?}", lag);
+    }
+}
+
 /// Subscribe to the events emitted by the chains the supervisor is connected to.
 fn init_subscriptions<Chain: ChainHandle>(
     config: &Config,
@@ -564,32 +1011,208 @@ fn state<Chain: ChainHandle>(registry: &Registry<Chain>, workers: &WorkerMap) ->
     SupervisorState::new(chains, workers.handles())
 }
 
+/// Pause or resume the packet worker for the given object, returning its description,
+/// or `None` if there is no such worker.
+fn set_worker_paused(workers: &mut WorkerMap, object: &Object, paused: bool) -> Option<WorkerDesc> {
+    let handle = if paused {
+        workers.pause(object)
+    } else {
+        workers.resume(object)
+    };
+
+    match handle {
+        Some(handle) => {
+            info!(
+                worker = %object.short_name(),
+                "{} packet worker",
+                if paused { "paused" } else { "resumed" }
+            );
+
+            Some(WorkerDesc::of(handle))
+        }
+        None => {
+            warn!(
+                worker = %object.short_name(),
+                "ignoring request to {} unknown packet worker",
+                if paused { "pause" } else { "resume" }
+            );
+
+            None
+        }
+    }
+}
+
 fn handle_rest_requests<Chain: ChainHandle>(
-    config: &Config,
-    registry: &Registry<Chain>,
-    workers: &WorkerMap,
+    config: &mut Config,
+    registry: &SharedRegistry<Chain>,
+    workers: &Arc<RwLock<WorkerMap>>,
+    readiness: &Readiness,
     rest_rx: &rest::Receiver,
 ) {
     if let Some(cmd) = rest::process_incoming_requests(config, rest_rx) {
-        handle_rest_cmd(registry, workers, cmd);
+        handle_rest_cmd(config, registry, workers, readiness, cmd);
     }
 }
 
 fn handle_rest_cmd<Chain: ChainHandle>(
-    registry: &Registry<Chain>,
-    workers: &WorkerMap,
+    config: &mut Config,
+    registry: &SharedRegistry<Chain>,
+    workers: &Arc<RwLock<WorkerMap>>,
+    readiness: &Readiness,
     m: rest::Command,
 ) {
     match m {
         rest::Command::DumpState(reply) => {
-            let state = state(registry, workers);
+            let state = state(&registry.read(), &workers.acquire_read());
             reply
                 .send(Ok(state))
                 .unwrap_or_else(|e| error!("error replying to a REST request {}", e));
         }
+        rest::Command::PauseWorker(object, reply) => {
+            let result = set_worker_paused(&mut workers.acquire_write(), &object, true)
+                .ok_or_else(|| RestApiError::WorkerNotFound(object.short_name()));
+            reply
+                .send(result)
+                .unwrap_or_else(|e| error!("error replying to a REST request {}", e));
+        }
+        rest::Command::ResumeWorker(object, reply) => {
+            let result = set_worker_paused(&mut workers.acquire_write(), &object, false)
+                .ok_or_else(|| RestApiError::WorkerNotFound(object.short_name()));
+            reply
+                .send(result)
+                .unwrap_or_else(|e| error!("error replying to a REST request {}", e));
+        }
+        rest::Command::Health(reply) => {
+            let health = HealthInfo {
+                live: readiness.is_live(),
+                ready: readiness.is_ready(),
+            };
+            reply
+                .send(Ok(health))
+                .unwrap_or_else(|e| error!("error replying to a REST request {}", e));
+        }
+        rest::Command::RotateKey(request, reply) => {
+            let result = rotate_key(config, registry, request);
+            reply
+                .send(result)
+                .unwrap_or_else(|e| error!("error replying to a REST request {}", e));
+        }
+        rest::Command::Paths(reply) => {
+            let paths = paths_health(registry, workers);
+            reply
+                .send(Ok(paths))
+                .unwrap_or_else(|e| error!("error replying to a REST request {}", e));
+        }
     }
 }
 
+/// Rotate the key signing the transactions submitted to a chain by its runtime, and record
+/// the new key in the configurations used to spawn its runtime again and to reply to the
+/// REST requests.
+///
+/// Only the configuration in memory is updated, not the configuration file.
+fn rotate_key<Chain: ChainHandle>(
+    config: &mut Config,
+    registry: &SharedRegistry<Chain>,
+    request: RotateKey,
+) -> Result<KeyRotation, RestApiError> {
+    let RotateKey { chain_id, key_name } = request;
+    let failed =
+        |e: &dyn core::fmt::Display| RestApiError::KeyRotation(chain_id.clone(), e.to_string());
+
+    let chain_config = config
+        .find_chain_mut(&chain_id)
+        .ok_or_else(|| RestApiError::ChainConfigNotFound(chain_id.clone()))?;
+
+    let chain = registry.get_or_spawn(&chain_id).map_err(|e| failed(&e))?;
+
+    // The registry is not locked while the runtime completes the requests submitted before
+    chain.rotate_key(key_name.clone()).map_err(|e| failed(&e))?;
+
+    registry.write().set_key_name(&chain_id, key_name.clone());
+
+    let previous_key_name = core::mem::replace(&mut chain_config.key_name, key_name.clone());
+
+    info!(
+        chain = %chain_id,
+        previous = %previous_key_name,
+        key = %key_name,
+        "rotated the key signing the transactions"
+    );
+
+    Ok(KeyRotation {
+        chain_id,
+        previous_key_name,
+        key_name,
+    })
+}
+
+/// Query the health of the paths relayed by the packet workers, the unhealthy ones first.
+///
+/// The workers lock is only held to describe them, not during the queries.
+fn paths_health<Chain: ChainHandle>(
+    registry: &SharedRegistry<Chain>,
+    workers: &Arc<RwLock<WorkerMap>>,
+) -> Vec<PathHealth> {
+    let descs = workers
+        .acquire_read()
+        .handles()
+        .map(WorkerDesc::of)
+        .collect_vec();
+
+    let now = Timestamp::now();
+    let mut paths: Vec<PathHealth> = Vec::new();
+
+    for desc in &descs {
+        let packet = match &desc.object {
+            Object::Packet(packet) => packet,
+            _ => continue,
+        };
+
+        // The packet workers of both directions of a path share it
+        if paths.iter().any(|path| {
+            path.has_end(
+                &packet.src_chain_id,
+                &packet.src_port_id,
+                &packet.src_channel_id,
+            )
+        }) {
+            continue;
+        }
+
+        let chains = registry
+            .get_or_spawn(&packet.src_chain_id)
+            .and_then(|chain| {
+                registry
+                    .get_or_spawn(&packet.dst_chain_id)
+                    .map(|counterparty_chain| (chain, counterparty_chain))
+            });
+
+        let result = chains
+            .map_err(Error::spawn)
+            .and_then(|(chain, counterparty_chain)| {
+                PathHealth::query(
+                    &chain,
+                    &counterparty_chain,
+                    &packet.src_port_id,
+                    &packet.src_channel_id,
+                    now,
+                )
+            });
+
+        match result {
+            Ok(path) => paths.push(path.with_workers(&descs)),
+            Err(e) => warn!(
+                worker = %packet.short_name(),
+                "failed to query the health of the path: {}", e
+            ),
+        }
+    }
+
+    unhealthy_first(&mut paths);
+    paths
+}
+
 fn clear_pending_packets(workers: &mut WorkerMap, chain_id: &ChainId) -> Result<(), Error> {
     for worker in workers.workers_for_chain(chain_id) {
         worker.clear_pending_packets();
@@ -613,6 +1236,25 @@ fn process_batch<Chain: ChainHandle>(
 
     let collected = collect_events(config, workers, &src_chain, batch);
 
+    // Spawn or shut down the workers of the channels opened or closed since the startup scan
+    let channel_changes = collect_channel_changes(config, &src_chain, batch)
+        .into_iter()
+        .filter(|change| match change {
+            ChannelChange::Opened(path) => relay_on_object(
+                config,
+                registry,
+                client_state_filter,
+                &path.src_chain_id,
+                &Object::Packet(path.clone()),
+            ),
+            ChannelChange::Closed(_) => true,
+        })
+        .collect();
+
+    update_channel_workers(config, workers, channel_changes, |chain_id| {
+        registry.get_or_spawn(chain_id).map_err(Error::spawn)
+    });
+
     // If there is a NewBlock event, forward this event first to any workers affected by it.
     if let Some(IbcEvent::NewBlock(new_block)) = collected.new_block {
         workers.notify_new_block(&src_chain.id(), batch.height, new_block);
@@ -681,12 +1323,26 @@ fn process_batch<Chain: ChainHandle>(
 
         let worker = workers.get_or_spawn(object, src, dst, config);
 
-        worker.send_events(
-            batch.height,
-            events,
-            batch.chain_id.clone(),
-            batch.tracking_id,
-        );
+        let chunks = split_events(events, config.mode.packets.max_events_per_batch);
+
+        if chunks.len() > 1 {
+            debug!(
+                chain = %batch.chain_id,
+                height = %batch.height,
+                "splitting the events for '{}' into {} chunks",
+                worker.object().short_name(),
+                chunks.len()
+            );
+        }
+
+        for chunk in chunks {
+            worker.send_events(
+                batch.height,
+                chunk,
+                batch.chain_id.clone(),
+                batch.tracking_id,
+            );
+        }
     }
 
     Ok(())
@@ -761,3 +1417,451 @@ impl CollectedEvents {
         self.new_block.is_some()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use ibc::clients::ics07_tendermint::client_state::{AllowUpdate, ClientState};
+    use ibc::core::ics02_client::client_state::AnyClientState;
+    use ibc::core::ics02_client::client_type::ClientType;
+    use ibc::core::ics02_client::trust_threshold::TrustThreshold;
+    use ibc::core::ics03_connection::connection::{
+        ConnectionEnd, Counterparty as ConnectionCounterparty, State as ConnectionState,
+    };
+    use ibc::core::ics03_connection::version::get_compatible_versions;
+    use ibc::core::ics04_channel::channel::{
+        ChannelEnd, Counterparty as ChannelCounterparty, Order, State as ChannelState,
+    };
+    use ibc::core::ics04_channel::events::{
+        CloseConfirm, OpenAck, OpenConfirm, SendPacket, TimeoutPacket,
+    };
+    use ibc::core::ics04_channel::packet::{Packet as IbcPacket, Sequence};
+    use ibc::core::ics04_channel::timeout::TimeoutHeight;
+    use ibc::core::ics04_channel::Version;
+    use ibc::core::ics23_commitment::commitment::CommitmentPrefix;
+    use ibc::core::ics23_commitment::specs::ProofSpecs;
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId};
+    use ibc::events::IbcEvent;
+    use ibc::timestamp::Timestamp;
+    use ibc::Height;
+
+    use super::{
+        collect_channel_changes, split_events, update_channel_workers, ChannelChange, Error,
+    };
+    use crate::chain::handle::mock::MockChainHandle;
+    use crate::chain::handle::ChainHandle;
+    use crate::chain::tracking::TrackingId;
+    use crate::config::{load, Config};
+    use crate::event::monitor::EventBatch;
+    use crate::object::{Object, Packet};
+    use crate::worker::WorkerMap;
+
+    fn config() -> Config {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/config/fixtures/relayer_conf_example.toml"
+        );
+
+        // Only the channels `channel-0` with port `transfer` and the `ica*` ports are allowed on `chain_A`
+        load(path).expect("could not parse config")
+    }
+
+    /// Loads `chain` with the channels `channel-0` and `channel-1` to `counterparty`,
+    /// over the connection `connection-0` and the client `07-tendermint-0`.
+    fn add_channels(chain: &MockChainHandle, counterparty: &MockChainHandle) {
+        let client_id = ClientId::new(ClientType::Tendermint, 0).unwrap();
+        let connection_id = ConnectionId::new(0);
+
+        let client_state = ClientState::new(
+            counterparty.id(),
+            TrustThreshold::ONE_THIRD,
+            Duration::from_secs(14 * 24 * 3600),
+            Duration::from_secs(21 * 24 * 3600),
+            Duration::from_secs(5),
+            Height::new(counterparty.id().version(), 10).unwrap(),
+            ProofSpecs::default(),
+            vec![],
+            AllowUpdate {
+                after_expiry: true,
+                after_misbehaviour: true,
+            },
+        )
+        .unwrap();
+
+        chain.add_client(client_id.clone(), AnyClientState::Tendermint(client_state));
+
+        chain.add_connection(
+            connection_id.clone(),
+            ConnectionEnd::new(
+                ConnectionState::Open,
+                client_id.clone(),
+                ConnectionCounterparty::new(
+                    client_id,
+                    Some(connection_id.clone()),
+                    CommitmentPrefix::try_from(b"ibc".to_vec()).unwrap(),
+                ),
+                get_compatible_versions(),
+                Duration::ZERO,
+            ),
+        );
+
+        for channel_id in [ChannelId::new(0), ChannelId::new(1)] {
+            chain.add_channel(
+                PortId::transfer(),
+                channel_id.clone(),
+                ChannelEnd::new(
+                    ChannelState::Open,
+                    Order::Unordered,
+                    ChannelCounterparty::new(PortId::transfer(), Some(channel_id)),
+                    vec![connection_id.clone()],
+                    Version::ics20(),
+                ),
+            );
+        }
+    }
+
+    fn chains() -> (MockChainHandle, MockChainHandle) {
+        let a = MockChainHandle::new(ChainId::from_string("chain_A"));
+        let b = MockChainHandle::new(ChainId::from_string("chain_B"));
+
+        add_channels(&a, &b);
+        add_channels(&b, &a);
+
+        (a, b)
+    }
+
+    fn path(src_chain: &MockChainHandle, dst_chain: &MockChainHandle, channel: u64) -> Packet {
+        Packet {
+            dst_chain_id: dst_chain.id(),
+            src_chain_id: src_chain.id(),
+            src_channel_id: ChannelId::new(channel),
+            src_port_id: PortId::transfer(),
+        }
+    }
+
+    fn batch(chain: &MockChainHandle, events: Vec<IbcEvent>) -> EventBatch {
+        EventBatch {
+            chain_id: chain.id(),
+            tracking_id: TrackingId::new_static("test"),
+            height: Height::new(chain.id().version(), 20).unwrap(),
+            events,
+        }
+    }
+
+    fn open_ack(channel: u64) -> IbcEvent {
+        OpenAck {
+            height: Height::new(0, 20).unwrap(),
+            port_id: PortId::transfer(),
+            channel_id: Some(ChannelId::new(channel)),
+            counterparty_channel_id: Some(ChannelId::new(channel)),
+            connection_id: ConnectionId::new(0),
+            counterparty_port_id: PortId::transfer(),
+        }
+        .into()
+    }
+
+    fn open_confirm(channel: u64) -> IbcEvent {
+        OpenConfirm {
+            height: Height::new(0, 20).unwrap(),
+            port_id: PortId::transfer(),
+            channel_id: Some(ChannelId::new(channel)),
+            connection_id: ConnectionId::new(0),
+            counterparty_port_id: PortId::transfer(),
+            counterparty_channel_id: Some(ChannelId::new(channel)),
+        }
+        .into()
+    }
+
+    fn close_confirm(channel: u64) -> IbcEvent {
+        CloseConfirm {
+            height: Height::new(0, 20).unwrap(),
+            channel_id: Some(ChannelId::new(channel)),
+            port_id: PortId::transfer(),
+            connection_id: ConnectionId::new(0),
+            counterparty_port_id: PortId::transfer(),
+            counterparty_channel_id: Some(ChannelId::new(channel)),
+        }
+        .into()
+    }
+
+    #[test]
+    fn open_ack_opens_channel_end_on_source_chain() {
+        let config = config();
+        let (a, b) = chains();
+
+        assert_eq!(
+            collect_channel_changes(&config, &a, &batch(&a, vec![open_ack(0)])),
+            vec![ChannelChange::Opened(path(&a, &b, 0))]
+        );
+    }
+
+    #[test]
+    fn open_confirm_opens_both_channel_ends() {
+        let config = config();
+        let (a, b) = chains();
+
+        assert_eq!(
+            collect_channel_changes(&config, &b, &batch(&b, vec![open_confirm(0)])),
+            vec![
+                ChannelChange::Opened(path(&b, &a, 0)),
+                ChannelChange::Opened(path(&a, &b, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn opened_channel_ends_are_filtered() {
+        let config = config();
+        let (a, b) = chains();
+
+        // `channel-1` is not allowed by the packet filter of `chain_A`
+        assert_eq!(
+            collect_channel_changes(&config, &a, &batch(&a, vec![open_ack(1)])),
+            vec![]
+        );
+
+        assert_eq!(
+            collect_channel_changes(&config, &b, &batch(&b, vec![open_confirm(1)])),
+            vec![ChannelChange::Opened(path(&b, &a, 1))]
+        );
+    }
+
+    #[test]
+    fn close_confirm_closes_both_channel_ends() {
+        let config = config();
+        let (a, b) = chains();
+
+        assert_eq!(
+            collect_channel_changes(&config, &b, &batch(&b, vec![close_confirm(1)])),
+            vec![
+                ChannelChange::Closed(path(&b, &a, 1)),
+                ChannelChange::Closed(path(&a, &b, 1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn no_channel_changes_without_packet_workers() {
+        let mut config = config();
+        config.mode.packets.enabled = false;
+
+        let (a, _) = chains();
+
+        assert_eq!(
+            collect_channel_changes(&config, &a, &batch(&a, vec![open_ack(0)])),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn channel_changes_update_workers() {
+        let config = config();
+        let (a, b) = chains();
+
+        let get_chain = |chain_id: &ChainId| -> Result<MockChainHandle, Error> {
+            Ok(if *chain_id == a.id() {
+                a.clone()
+            } else {
+                b.clone()
+            })
+        };
+
+        let mut workers = WorkerMap::new();
+
+        let changes = collect_channel_changes(&config, &b, &batch(&b, vec![open_confirm(0)]));
+        update_channel_workers(&config, &mut workers, changes, get_chain);
+
+        assert!(workers.contains(&Object::Packet(path(&a, &b, 0))));
+        assert!(workers.contains(&Object::Packet(path(&b, &a, 0))));
+        assert!(!workers.contains(&Object::Packet(path(&a, &b, 1))));
+
+        let changes = collect_channel_changes(&config, &a, &batch(&a, vec![close_confirm(0)]));
+        update_channel_workers(&config, &mut workers, changes, get_chain);
+
+        assert!(!workers.contains(&Object::Packet(path(&a, &b, 0))));
+        assert!(!workers.contains(&Object::Packet(path(&b, &a, 0))));
+    }
+
+    #[test]
+    fn paused_workers_stay_paused_when_respawned() {
+        let config = config();
+        let (a, b) = chains();
+        let a_to_b = Object::Packet(path(&a, &b, 0));
+
+        let get_chain = |chain_id: &ChainId| -> Result<MockChainHandle, Error> {
+            Ok(if *chain_id == a.id() {
+                a.clone()
+            } else {
+                b.clone()
+            })
+        };
+
+        let is_paused = |workers: &WorkerMap, object: &Object| {
+            workers
+                .handles()
+                .find(|handle| handle.object() == object)
+                .map(|handle| handle.is_paused())
+        };
+
+        let mut workers = WorkerMap::new();
+
+        let changes = collect_channel_changes(&config, &b, &batch(&b, vec![open_confirm(0)]));
+        update_channel_workers(&config, &mut workers, changes, get_chain);
+        assert_eq!(is_paused(&workers, &a_to_b), Some(false));
+
+        assert!(workers.pause(&a_to_b).unwrap().is_paused());
+        assert_eq!(
+            is_paused(&workers, &Object::Packet(path(&b, &a, 0))),
+            Some(false)
+        );
+
+        // Only existing packet workers can be paused
+        assert!(workers.pause(&Object::Packet(path(&a, &b, 1))).is_none());
+
+        let changes = collect_channel_changes(&config, &a, &batch(&a, vec![close_confirm(0)]));
+        update_channel_workers(&config, &mut workers, changes, get_chain);
+        assert_eq!(is_paused(&workers, &a_to_b), None);
+
+        let changes = collect_channel_changes(&config, &b, &batch(&b, vec![open_confirm(0)]));
+        update_channel_workers(&config, &mut workers, changes, get_chain);
+        assert_eq!(is_paused(&workers, &a_to_b), Some(true));
+
+        assert!(!workers.resume(&a_to_b).unwrap().is_paused());
+    }
+
+    #[test]
+    fn workers_of_halted_chain_keep_relaying_to_other_chain() {
+        let config = config();
+        let (a, b) = chains();
+        let a_to_b = Object::Packet(path(&a, &b, 0));
+        let b_to_a = Object::Packet(path(&b, &a, 0));
+
+        let get_chain = |chain_id: &ChainId| -> Result<MockChainHandle, Error> {
+            Ok(if *chain_id == a.id() {
+                a.clone()
+            } else {
+                b.clone()
+            })
+        };
+
+        let is_paused = |workers: &WorkerMap, object: &Object| {
+            workers
+                .handles()
+                .find(|handle| handle.object() == object)
+                .map(|handle| handle.is_paused())
+        };
+
+        let mut workers = WorkerMap::new();
+
+        let is_chain_halted = |workers: &WorkerMap, object: &Object, chain: &MockChainHandle| {
+            workers
+                .handles()
+                .find(|handle| handle.object() == object)
+                .map(|handle| handle.is_chain_halted(&chain.id()))
+        };
+
+        let changes = collect_channel_changes(&config, &b, &batch(&b, vec![open_confirm(0)]));
+        update_channel_workers(&config, &mut workers, changes, get_chain);
+        assert!(workers.pause(&b_to_a).unwrap().is_paused());
+
+        // The workers of both directions stop relaying to the halted chain only,
+        // eg. to keep relaying the timeouts of the packets sent to it
+        workers.set_chain_halted(&b.id(), true);
+        assert!(workers.is_chain_halted(&b.id()));
+        assert_eq!(is_paused(&workers, &a_to_b), Some(false));
+        assert_eq!(is_chain_halted(&workers, &a_to_b, &b), Some(true));
+        assert_eq!(is_chain_halted(&workers, &a_to_b, &a), Some(false));
+        assert_eq!(is_chain_halted(&workers, &b_to_a, &b), Some(true));
+
+        // The worker which was paused explicitly stays paused
+        workers.set_chain_halted(&b.id(), false);
+        assert!(!workers.is_chain_halted(&b.id()));
+        assert_eq!(is_chain_halted(&workers, &a_to_b, &b), Some(false));
+        assert_eq!(is_paused(&workers, &b_to_a), Some(true));
+
+        // The workers spawned while the chain is halted do not relay to it either,
+        // eg. the worker of `channel-1`, which is only allowed on `chain_B`
+        workers.set_chain_halted(&b.id(), true);
+
+        let changes = collect_channel_changes(&config, &b, &batch(&b, vec![open_confirm(1)]));
+        update_channel_workers(&config, &mut workers, changes, get_chain);
+
+        let b_to_a = Object::Packet(path(&b, &a, 1));
+        assert_eq!(is_paused(&workers, &b_to_a), Some(false));
+        assert_eq!(is_chain_halted(&workers, &b_to_a, &b), Some(true));
+        assert_eq!(is_chain_halted(&workers, &b_to_a, &a), Some(false));
+    }
+
+    fn packet(sequence: u64) -> IbcPacket {
+        IbcPacket {
+            sequence: Sequence::from(sequence),
+            source_port: PortId::transfer(),
+            source_channel: ChannelId::new(0),
+            destination_port: PortId::transfer(),
+            destination_channel: ChannelId::new(0),
+            data: vec![],
+            timeout_height: TimeoutHeight::Never,
+            timeout_timestamp: Timestamp::none(),
+        }
+    }
+
+    fn send_packet(sequence: u64) -> IbcEvent {
+        IbcEvent::SendPacket(SendPacket {
+            height: Height::new(0, 20).unwrap(),
+            packet: packet(sequence),
+        })
+    }
+
+    fn timeout_packet(sequence: u64) -> IbcEvent {
+        IbcEvent::TimeoutPacket(TimeoutPacket {
+            height: Height::new(0, 20).unwrap(),
+            packet: packet(sequence),
+        })
+    }
+
+    #[test]
+    fn giant_batch_is_split_in_order() {
+        let events: Vec<_> = (1..=2500).map(send_packet).collect();
+
+        let chunks = split_events(events.clone(), 1000);
+
+        assert_eq!(
+            chunks.iter().map(Vec::len).collect::<Vec<_>>(),
+            vec![1000, 1000, 500]
+        );
+        assert_eq!(chunks[1][0], send_packet(1001));
+        assert_eq!(chunks.concat(), events);
+    }
+
+    #[test]
+    fn events_of_a_packet_stay_in_the_same_chunk() {
+        let events = vec![
+            send_packet(1),
+            send_packet(2),
+            timeout_packet(1),
+            send_packet(3),
+            send_packet(4),
+            send_packet(5),
+        ];
+
+        let chunks = split_events(events, 2);
+
+        assert_eq!(
+            chunks,
+            vec![
+                vec![send_packet(1), send_packet(2), timeout_packet(1)],
+                vec![send_packet(3), send_packet(4)],
+                vec![send_packet(5)],
+            ]
+        );
+    }
+
+    #[test]
+    fn small_batches_are_not_split() {
+        let events: Vec<_> = (1..=10).map(send_packet).collect();
+
+        assert_eq!(split_events(events.clone(), 10), vec![events.clone()]);
+        assert_eq!(split_events(events.clone(), 0), vec![events]);
+    }
+}
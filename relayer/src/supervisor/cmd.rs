@@ -1,8 +1,14 @@
 use crossbeam_channel::Sender;
 
+use crate::object::Object;
+
 use super::dump_state::SupervisorState;
 
 #[derive(Clone, Debug)]
 pub enum SupervisorCmd {
     DumpState(Sender<SupervisorState>),
+    /// Pause the packet worker for the given object, see [`WorkerMap::pause`](crate::worker::WorkerMap::pause)
+    PauseWorker(Object),
+    /// Resume the packet worker for the given object, see [`WorkerMap::resume`](crate::worker::WorkerMap::resume)
+    ResumeWorker(Object),
 }
@@ -16,11 +16,31 @@ pub struct WorkerDesc {
     pub id: WorkerId,
     pub object: Object,
     pub data: Option<WorkerData>,
+    #[serde(default)]
+    pub paused: bool,
+    /// How many timeouts of packets await a proof from their unreachable destination chain
+    #[serde(default)]
+    pub timeouts_awaiting_proof: usize,
 }
 
 impl WorkerDesc {
     pub fn new(id: WorkerId, object: Object, data: Option<WorkerData>) -> Self {
-        Self { id, object, data }
+        Self {
+            id,
+            object,
+            data,
+            paused: false,
+            timeouts_awaiting_proof: 0,
+        }
+    }
+
+    /// Describes the worker of the given handle.
+    pub fn of(handle: &WorkerHandle) -> Self {
+        Self {
+            paused: handle.is_paused(),
+            timeouts_awaiting_proof: handle.timeouts_awaiting_proof(),
+            ..Self::new(handle.id(), handle.object().clone(), handle.data().cloned())
+        }
     }
 }
 
@@ -38,7 +58,7 @@ impl SupervisorState {
         chains.sort();
 
         let workers = workers
-            .map(|h| WorkerDesc::new(h.id(), h.object().clone(), h.data().cloned()))
+            .map(WorkerDesc::of)
             .into_group_map_by(|desc| desc.object.object_type())
             .into_iter()
             .update(|(_, os)| os.sort_by_key(|desc| desc.object.short_name()))
@@ -61,13 +81,36 @@ impl fmt::Display for SupervisorState {
         for (tpe, objects) in &self.workers {
             writeln!(f, "* {tpe:?} workers:")?;
             for desc in objects {
-                writeln!(f, "  - {} (id: {})", desc.object.short_name(), desc.id)?;
-                if let Some(WorkerData::Client {
-                    misbehaviour,
-                    refresh,
-                }) = desc.data
-                {
-                    writeln!(f, "    | misbehaviour: {misbehaviour}, refresh: {refresh}")?;
+                if desc.paused {
+                    writeln!(
+                        f,
+                        "  - {} (id: {}, paused)",
+                        desc.object.short_name(),
+                        desc.id
+                    )?;
+                } else {
+                    writeln!(f, "  - {} (id: {})", desc.object.short_name(), desc.id)?;
+                }
+                match desc.data {
+                    Some(WorkerData::Client {
+                        misbehaviour,
+                        refresh,
+                    }) => {
+                        writeln!(f, "    | misbehaviour: {misbehaviour}, refresh: {refresh}")?;
+                    }
+                    Some(WorkerData::Packet {
+                        version_mismatch: true,
+                    }) => {
+                        writeln!(f, "    | version mismatch")?;
+                    }
+                    _ => (),
+                }
+                if desc.timeouts_awaiting_proof > 0 {
+                    writeln!(
+                        f,
+                        "    | {} timeouts awaiting a proof from the destination chain",
+                        desc.timeouts_awaiting_proof
+                    )?;
                 }
             }
         }
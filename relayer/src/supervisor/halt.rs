@@ -0,0 +1,303 @@
+//! Detection of the chains which halted, or whose node lags far behind, so that the
+//! packet workers stop submitting messages to them, which fail and are retried at full
+//! speed. The workers keep scheduling the packets of the events they receive, and keep
+//! relaying to the other chain, eg. the timeouts of the packets sent to the halted chain.
+//! They clear the pending packets once the chain resumes.
+//!
+//! A chain is considered halted once the latest height of its node has not advanced
+//! for its `halt_detection_window`, or once the time of its latest block lags more than
+//! that window behind the local time. It is considered live again once
+//! [`RESUME_BLOCKS`] new recent blocks have been observed, so that a single slow
+//! block does not make the state flap.
+
+use alloc::collections::btree_map::BTreeMap;
+use core::time::Duration;
+use std::time::Instant;
+
+use ibc::core::ics24_host::identifier::ChainId;
+use ibc::timestamp::Timestamp;
+use ibc::Height;
+
+/// How often the latest heights of the chains with halt detection enabled are polled.
+pub const HALT_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How many new blocks must be observed on a halted chain before it is considered live again.
+pub const RESUME_BLOCKS: u64 = 2;
+
+/// A change in the state of a chain.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HaltTransition {
+    Halted,
+    Resumed,
+}
+
+#[derive(Clone, Debug)]
+struct ChainProgress {
+    window: Duration,
+    height: Option<Height>,
+    advanced_at: Instant,
+    halted: bool,
+    blocks_since_halt: u64,
+}
+
+/// Tracks the progress of the chains with halt detection enabled.
+#[derive(Clone, Debug, Default)]
+pub struct HaltDetector {
+    chains: BTreeMap<ChainId, ChainProgress>,
+}
+
+impl HaltDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Detect whether the given chain halts, considering it live as of `now`.
+    pub fn track(&mut self, chain_id: ChainId, window: Duration, now: Instant) {
+        self.chains.insert(
+            chain_id,
+            ChainProgress {
+                window,
+                height: None,
+                advanced_at: now,
+                halted: false,
+                blocks_since_halt: 0,
+            },
+        );
+    }
+
+    /// The chains whose progress is tracked.
+    pub fn chains(&self) -> impl Iterator<Item = &ChainId> {
+        self.chains.keys()
+    }
+
+    /// Whether the given chain is considered halted.
+    pub fn is_halted(&self, chain_id: &ChainId) -> bool {
+        self.chains
+            .get(chain_id)
+            .map_or(false, |progress| progress.halted)
+    }
+
+    /// Record the latest height of the given chain, and the time of its block.
+    ///
+    /// The chain progresses if the height advanced and the block is no older than
+    /// the window of the chain, as seen from `now_time`. A node which is far behind
+    /// therefore does not progress, even though its height advances while it syncs.
+    pub fn observe(
+        &mut self,
+        chain_id: &ChainId,
+        height: Height,
+        block_time: Timestamp,
+        now: Instant,
+        now_time: Timestamp,
+    ) {
+        let progress = match self.chains.get_mut(chain_id) {
+            Some(progress) => progress,
+            None => return,
+        };
+
+        if progress.height.map_or(false, |latest| height <= latest) {
+            return;
+        }
+
+        let is_first = progress.height.is_none();
+        progress.height = Some(height);
+
+        let is_recent = now_time
+            .duration_since(&block_time)
+            .map_or(true, |age| age <= progress.window);
+
+        // The first height only sets the reference to compare the next ones with
+        if is_recent && !is_first {
+            progress.advanced_at = now;
+
+            if progress.halted {
+                progress.blocks_since_halt += 1;
+            }
+        }
+    }
+
+    /// Update the state of the given chain as of `now`, returning the transition, if any.
+    pub fn check(&mut self, chain_id: &ChainId, now: Instant) -> Option<HaltTransition> {
+        let progress = self.chains.get_mut(chain_id)?;
+
+        if progress.halted {
+            if progress.blocks_since_halt >= RESUME_BLOCKS {
+                progress.halted = false;
+                progress.blocks_since_halt = 0;
+
+                return Some(HaltTransition::Resumed);
+            }
+        } else if now.saturating_duration_since(progress.advanced_at) >= progress.window {
+            progress.halted = true;
+            progress.blocks_since_halt = 0;
+
+            return Some(HaltTransition::Halted);
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_secs(60);
+
+    struct Clock {
+        start: Instant,
+        start_time: Timestamp,
+    }
+
+    impl Clock {
+        fn new() -> Self {
+            Self {
+                start: Instant::now(),
+                start_time: Timestamp::from_nanoseconds(1_666_000_000 * 1_000_000_000).unwrap(),
+            }
+        }
+
+        fn at(&self, secs: u64) -> (Instant, Timestamp) {
+            (
+                self.start + Duration::from_secs(secs),
+                (self.start_time + Duration::from_secs(secs)).unwrap(),
+            )
+        }
+    }
+
+    fn chain_id() -> ChainId {
+        ChainId::from_string("ibc-0")
+    }
+
+    fn height(height: u64) -> Height {
+        Height::new(0, height).unwrap()
+    }
+
+    fn detector(clock: &Clock) -> HaltDetector {
+        let mut detector = HaltDetector::new();
+        detector.track(chain_id(), WINDOW, clock.start);
+        detector
+    }
+
+    /// Observe a block produced at the given time, `lag` seconds later.
+    fn observe(detector: &mut HaltDetector, clock: &Clock, h: u64, secs: u64, lag: u64) {
+        let (_, block_time) = clock.at(secs);
+        let (now, now_time) = clock.at(secs + lag);
+        detector.observe(&chain_id(), height(h), block_time, now, now_time);
+    }
+
+    fn check(detector: &mut HaltDetector, clock: &Clock, secs: u64) -> Option<HaltTransition> {
+        detector.check(&chain_id(), clock.at(secs).0)
+    }
+
+    #[test]
+    fn progressing_chain_is_live() {
+        let clock = Clock::new();
+        let mut detector = detector(&clock);
+
+        for (i, secs) in (0..300).step_by(30).enumerate() {
+            observe(&mut detector, &clock, 10 + i as u64, secs, 1);
+            assert_eq!(check(&mut detector, &clock, secs + 1), None);
+        }
+
+        assert!(!detector.is_halted(&chain_id()));
+    }
+
+    #[test]
+    fn stalled_height_halts_after_window() {
+        let clock = Clock::new();
+        let mut detector = detector(&clock);
+
+        observe(&mut detector, &clock, 10, 0, 0);
+        observe(&mut detector, &clock, 11, 5, 0);
+
+        // The same height is observed again, which is no progress
+        observe(&mut detector, &clock, 11, 30, 0);
+        assert_eq!(check(&mut detector, &clock, 30), None);
+
+        assert_eq!(
+            check(&mut detector, &clock, 65),
+            Some(HaltTransition::Halted)
+        );
+        assert!(detector.is_halted(&chain_id()));
+        assert_eq!(check(&mut detector, &clock, 70), None);
+    }
+
+    #[test]
+    fn lagging_node_halts() {
+        let clock = Clock::new();
+        let mut detector = detector(&clock);
+
+        // The height advances, but the blocks are two minutes old
+        for i in 0..10 {
+            observe(&mut detector, &clock, 10 + i, i * 10, 120);
+        }
+
+        assert_eq!(
+            check(&mut detector, &clock, 100),
+            Some(HaltTransition::Halted)
+        );
+    }
+
+    #[test]
+    fn resume_needs_several_blocks() {
+        let clock = Clock::new();
+        let mut detector = detector(&clock);
+
+        observe(&mut detector, &clock, 10, 0, 0);
+        assert_eq!(
+            check(&mut detector, &clock, 60),
+            Some(HaltTransition::Halted)
+        );
+
+        // A single slow block does not resume the chain
+        observe(&mut detector, &clock, 11, 90, 0);
+        assert_eq!(check(&mut detector, &clock, 90), None);
+        assert!(detector.is_halted(&chain_id()));
+
+        observe(&mut detector, &clock, 12, 95, 0);
+        assert_eq!(
+            check(&mut detector, &clock, 95),
+            Some(HaltTransition::Resumed)
+        );
+        assert!(!detector.is_halted(&chain_id()));
+
+        // The window starts again from the latest block
+        assert_eq!(check(&mut detector, &clock, 150), None);
+        assert_eq!(
+            check(&mut detector, &clock, 155),
+            Some(HaltTransition::Halted)
+        );
+    }
+
+    #[test]
+    fn old_blocks_do_not_resume() {
+        let clock = Clock::new();
+        let mut detector = detector(&clock);
+
+        observe(&mut detector, &clock, 10, 0, 0);
+        assert_eq!(
+            check(&mut detector, &clock, 60),
+            Some(HaltTransition::Halted)
+        );
+
+        // The node syncs old blocks
+        observe(&mut detector, &clock, 11, 10, 90);
+        observe(&mut detector, &clock, 12, 20, 90);
+        assert_eq!(check(&mut detector, &clock, 110), None);
+        assert!(detector.is_halted(&chain_id()));
+    }
+
+    #[test]
+    fn untracked_chain_is_ignored() {
+        let clock = Clock::new();
+        let mut detector = detector(&clock);
+        let other = ChainId::from_string("ibc-1");
+
+        let (now, now_time) = clock.at(0);
+        detector.observe(&other, height(10), now_time, now, now_time);
+
+        assert_eq!(detector.check(&other, clock.at(600).0), None);
+        assert!(!detector.is_halted(&other));
+    }
+}
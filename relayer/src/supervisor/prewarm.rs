@@ -0,0 +1,147 @@
+use alloc::collections::btree_map::BTreeMap as HashMap;
+use std::thread;
+use std::time::Instant;
+
+use tracing::{debug, info, warn};
+
+use ibc::core::ics02_client::client_state::AnyClientState;
+use ibc::core::ics24_host::identifier::{ChainId, ClientId};
+
+use crate::chain::handle::ChainHandle;
+use crate::registry::Registry;
+
+use super::scan::ChainsScan;
+
+/// A client to pre-warm, hosted on `host_chain_id`.
+struct PrewarmTarget {
+    host_chain_id: ChainId,
+    client_id: ClientId,
+    client_state: AnyClientState,
+}
+
+/// Pre-builds, without submitting it, a header for updating each client found by the
+/// `scan` which underlies at least one channel, up to the latest height of the chain
+/// the client tracks.
+///
+/// Building the headers fetches and verifies the latest light blocks of the tracked
+/// chains, so that the first client updates submitted by the workers are not slowed
+/// down by bootstrapping the light clients.
+///
+/// Since a chain runtime processes its requests one at a time, the tracked chains
+/// are pre-warmed concurrently, and the clients of a given chain sequentially.
+/// Failures are logged per client, and are otherwise ignored.
+pub fn prewarm_clients<Chain: ChainHandle>(registry: &mut Registry<Chain>, scan: &ChainsScan) {
+    let start = Instant::now();
+
+    let targets = prewarm_targets(scan);
+    let total = targets.values().map(Vec::len).sum::<usize>();
+
+    info!("pre-warming {} client(s)", total);
+
+    // All the tasks are spawned before any is joined, for the chains to be pre-warmed concurrently
+    let mut tasks = Vec::with_capacity(targets.len());
+
+    for (chain_id, targets) in targets {
+        match registry.get_or_spawn(&chain_id) {
+            Ok(chain) => tasks.push(thread::spawn(move || prewarm_chain(&chain, targets))),
+            Err(e) => warn!(
+                "skipping pre-warm of {} client(s) tracking chain {}: {}",
+                targets.len(),
+                chain_id,
+                e
+            ),
+        }
+    }
+
+    let prewarmed = tasks
+        .into_iter()
+        .map(|task| task.join().unwrap_or(0))
+        .sum::<usize>();
+
+    info!(
+        "pre-warmed {}/{} client(s) in {}ms",
+        prewarmed,
+        total,
+        start.elapsed().as_millis()
+    );
+}
+
+/// The clients underlying at least one channel, grouped by the chain they track.
+fn prewarm_targets(scan: &ChainsScan) -> HashMap<ChainId, Vec<PrewarmTarget>> {
+    let mut targets: HashMap<ChainId, Vec<PrewarmTarget>> = HashMap::new();
+
+    for chain_scan in scan.chains.iter().flatten() {
+        for client in chain_scan.clients.values() {
+            let has_channels = client
+                .connections
+                .values()
+                .any(|conn| !conn.channels.is_empty());
+
+            if !has_channels {
+                continue;
+            }
+
+            targets
+                .entry(client.counterparty_chain_id())
+                .or_default()
+                .push(PrewarmTarget {
+                    host_chain_id: chain_scan.chain_id.clone(),
+                    client_id: client.id().clone(),
+                    client_state: client.client.client_state.clone(),
+                });
+        }
+    }
+
+    targets
+}
+
+/// Pre-warms the clients tracking the given `chain`, and returns how many succeeded.
+fn prewarm_chain<Chain: ChainHandle>(chain: &Chain, targets: Vec<PrewarmTarget>) -> usize {
+    let target_height = match chain.query_latest_height() {
+        Ok(height) => height,
+        Err(e) => {
+            warn!(
+                "failed to pre-warm {} client(s) tracking chain {}, could not query its latest height: {}",
+                targets.len(),
+                chain.id(),
+                e
+            );
+            return 0;
+        }
+    };
+
+    targets
+        .into_iter()
+        .filter(|target| {
+            let trusted_height = target.client_state.latest_height();
+
+            // The client is already up to date
+            if trusted_height >= target_height {
+                return true;
+            }
+
+            let start = Instant::now();
+
+            match chain.build_header(trusted_height, target_height, target.client_state.clone()) {
+                Ok(_) => {
+                    debug!(
+                        "pre-warmed client {} on chain {} from height {} to {} in {}ms",
+                        target.client_id,
+                        target.host_chain_id,
+                        trusted_height,
+                        target_height,
+                        start.elapsed().as_millis()
+                    );
+                    true
+                }
+                Err(e) => {
+                    warn!(
+                        "failed to pre-warm client {} on chain {}: {}",
+                        target.client_id, target.host_chain_id, e
+                    );
+                    false
+                }
+            }
+        })
+        .count()
+}
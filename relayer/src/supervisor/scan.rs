@@ -2,6 +2,7 @@ use core::fmt;
 use std::collections::BTreeMap;
 
 use itertools::Itertools;
+use serde::Serialize;
 use tracing::{debug, error, info, info_span, warn};
 
 use ibc::core::{
@@ -13,6 +14,7 @@ use ibc::core::{
     },
     ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId},
 };
+use ibc::timestamp::Timestamp;
 
 use crate::{
     chain::{
@@ -24,6 +26,7 @@ use crate::{
             QueryConnectionRequest, QueryHeight,
         },
     },
+    channel::age::{is_stuck, StateAge},
     config::{filter::ChannelFilters, ChainConfig, Config, PacketFilter},
     registry::Registry,
     supervisor::client_state_filter::{FilterPolicy, Permission},
@@ -90,10 +93,24 @@ pub struct ChainsScan {
     pub chains: Vec<Result<ChainScan, Error>>,
 }
 
+impl ChainsScan {
+    /// The number of channels found on all the chains which were successfully scanned.
+    pub fn channel_count(&self) -> usize {
+        self.chains
+            .iter()
+            .flatten()
+            .flat_map(|scan| scan.clients.values())
+            .flat_map(|client| client.connections.values())
+            .map(|conn| conn.channels.len())
+            .sum()
+    }
+}
+
 impl fmt::Display for ChainsScan {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for scan in self.chains.iter().flatten() {
             writeln!(f, "# Chain: {}", scan.chain_id)?;
+            writeln!(f, "  | Channels: {}", scan.channel_states())?;
 
             for client in scan.clients.values() {
                 writeln!(f, "  - Client: {}", client.client.client_id)?;
@@ -109,7 +126,12 @@ impl fmt::Display for ChainsScan {
                     writeln!(f, "      | State: {}", conn.state())?;
                     writeln!(f, "      | Counterparty state: {}", counterparty)?;
 
-                    for chan in conn.channels.values() {
+                    let (stuck, channels): (Vec<_>, Vec<_>) = conn
+                        .channels
+                        .values()
+                        .partition(|chan| is_stuck(chan.channel.channel_end.state));
+
+                    for chan in channels {
                         let counterparty = chan
                             .counterparty
                             .as_ref()
@@ -121,6 +143,16 @@ impl fmt::Display for ChainsScan {
                         writeln!(f, "        | State: {}", chan.channel.channel_end.state())?;
                         writeln!(f, "        | Counterparty: {}", counterparty)?;
                     }
+
+                    // Only count the channels stuck in the opening handshake, as there
+                    // may be thousands of them on hub chains
+                    if !stuck.is_empty() {
+                        writeln!(
+                            f,
+                            "      + {} channel(s) stuck in the opening handshake",
+                            stuck.len()
+                        )?;
+                    }
                 }
             }
         }
@@ -129,6 +161,76 @@ impl fmt::Display for ChainsScan {
     }
 }
 
+/// Warns about the channels stuck in the opening handshake found on each chain and,
+/// if `with_age`, logs how long each of them has been stuck for, which takes a query
+/// of the transaction which brought it to its state.
+pub fn report_stuck_channels<Chain: ChainHandle>(
+    registry: &mut Registry<Chain>,
+    scan: &ChainsScan,
+    with_age: bool,
+) {
+    let now = Timestamp::now();
+
+    for chain_scan in scan.chains.iter().flatten() {
+        let stuck = chain_scan.channel_states().stuck();
+
+        if stuck == 0 {
+            continue;
+        }
+
+        warn!(
+            chain = %chain_scan.chain_id,
+            "found {} channel(s) stuck in the opening handshake, no packet worker is spawned for them",
+            stuck
+        );
+
+        if !with_age {
+            continue;
+        }
+
+        let chain = match registry.get_or_spawn(&chain_scan.chain_id) {
+            Ok(chain) => chain,
+            Err(e) => {
+                error!(
+                    chain = %chain_scan.chain_id,
+                    "failed to estimate the age of the stuck channels: {}",
+                    e
+                );
+                continue;
+            }
+        };
+
+        for chan in chain_scan.stuck_channels() {
+            let (port_id, channel_id) = (&chan.channel.port_id, &chan.channel.channel_id);
+            let state = chan.channel.channel_end.state;
+
+            match StateAge::query(&chain, port_id, channel_id, state, now) {
+                Ok(Some(age)) => info!(
+                    chain = %chain_scan.chain_id,
+                    port = %port_id,
+                    channel = %channel_id,
+                    "channel stuck in {}",
+                    age
+                ),
+                Ok(None) => info!(
+                    chain = %chain_scan.chain_id,
+                    port = %port_id,
+                    channel = %channel_id,
+                    "channel stuck in {} for an unknown time, the transaction which brought it to this state was not found",
+                    state
+                ),
+                Err(e) => warn!(
+                    chain = %chain_scan.chain_id,
+                    port = %port_id,
+                    channel = %channel_id,
+                    "failed to estimate the age of the stuck channel: {}",
+                    e
+                ),
+            }
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ChainScan {
     pub chain_id: ChainId,
@@ -142,6 +244,68 @@ impl ChainScan {
             clients: BTreeMap::new(),
         }
     }
+
+    /// The channels found on the chain.
+    pub fn channels(&self) -> impl Iterator<Item = &ChannelScan> {
+        self.clients
+            .values()
+            .flat_map(|client| client.connections.values())
+            .flat_map(|conn| conn.channels.values())
+    }
+
+    /// The channels stuck in the opening handshake, i.e. in the `Init` or `TryOpen` state.
+    pub fn stuck_channels(&self) -> impl Iterator<Item = &ChannelScan> {
+        self.channels()
+            .filter(|chan| is_stuck(chan.channel.channel_end.state))
+    }
+
+    /// The number of channels found on the chain in each state.
+    pub fn channel_states(&self) -> ChannelStateCounts {
+        let mut counts = ChannelStateCounts::default();
+
+        for chan in self.channels() {
+            counts.add(chan.channel.channel_end.state);
+        }
+
+        counts
+    }
+}
+
+/// The number of channels in each state.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ChannelStateCounts {
+    pub init: usize,
+    pub try_open: usize,
+    pub open: usize,
+    pub closed: usize,
+}
+
+impl ChannelStateCounts {
+    /// Counts a channel in the given state. Uninitialized channels are not counted.
+    pub fn add(&mut self, state: ChannelState) {
+        match state {
+            ChannelState::Init => self.init += 1,
+            ChannelState::TryOpen => self.try_open += 1,
+            ChannelState::Open => self.open += 1,
+            ChannelState::Closed => self.closed += 1,
+            ChannelState::Uninitialized => {}
+        }
+    }
+
+    /// The number of channels stuck in the opening handshake.
+    pub fn stuck(&self) -> usize {
+        self.init + self.try_open
+    }
+}
+
+impl fmt::Display for ChannelStateCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} open, {} init, {} try_open, {} closed",
+            self.open, self.init, self.try_open, self.closed
+        )
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -476,13 +640,21 @@ impl<'a, Chain: ChainHandle> ChainScanner<'a, Chain> {
             .get_or_spawn(&client.client_state.chain_id())
             .map_err(Error::spawn)?;
 
+        let channel_workers_enabled = self.config.mode.channels.enabled;
+
         let channels = channels
             .into_iter()
             .filter(|channel| self.channel_allowed(chain, channel))
             .map(|channel| {
+                // The counterparty of the channels stuck in the opening handshake is
+                // only needed to spawn the workers completing their handshake
                 let counterparty =
-                    channel_on_destination(&channel, &scan.connection, &counterparty_chain)
-                        .unwrap_or_default();
+                    if is_stuck(channel.channel_end.state) && !channel_workers_enabled {
+                        None
+                    } else {
+                        channel_on_destination(&channel, &scan.connection, &counterparty_chain)
+                            .unwrap_or_default()
+                    };
 
                 let scan = ChannelScan {
                     channel,
@@ -815,3 +987,40 @@ fn query_connection_channels<Chain: ChainHandle>(
         })
         .map_err(Error::query)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ChannelStateCounts;
+
+    use ibc::core::ics04_channel::channel::State;
+
+    #[test]
+    fn count_channel_states() {
+        let mut counts = ChannelStateCounts::default();
+
+        for state in [
+            State::Open,
+            State::Init,
+            State::TryOpen,
+            State::Init,
+            State::Closed,
+            State::Uninitialized,
+            State::Open,
+            State::Open,
+        ] {
+            counts.add(state);
+        }
+
+        assert_eq!(
+            counts,
+            ChannelStateCounts {
+                init: 2,
+                try_open: 1,
+                open: 3,
+                closed: 1,
+            }
+        );
+        assert_eq!(counts.stuck(), 3);
+        assert_eq!(counts.to_string(), "3 open, 2 init, 1 try_open, 1 closed");
+    }
+}
@@ -0,0 +1,334 @@
+//! Tracking of the startup of the supervisor, which processes live events as soon
+//! as it has subscribed to them, and is complete once the packets pending on the
+//! paths discovered by the initial scan have been cleared in the background, or
+//! once clearing them has timed out.
+
+use alloc::collections::btree_set::BTreeSet;
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use core::time::Duration;
+use std::time::Instant;
+
+use crossbeam_channel::{Receiver, Sender, TryRecvError};
+use itertools::Itertools;
+use tracing::{info, warn};
+
+use crate::link::ClearProgress;
+use crate::object::Packet;
+
+/// How long to wait for the packets pending on start to be cleared
+/// before declaring the relayer ready anyway.
+pub const STARTUP_CLEAR_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// How many paths may clear the packets pending on start at the same time.
+pub const STARTUP_CLEAR_CONCURRENCY: usize = 4;
+
+/// Report sent by a packet worker about the clearing of the packets
+/// pending on its path when the relayer starts.
+#[derive(Clone, Debug)]
+pub enum StartupProgress {
+    /// The worker for the path will clear the pending packets on start
+    Pending { path: Packet },
+
+    /// Some of the pending packets on the path have been cleared
+    Clearing {
+        path: Packet,
+        progress: ClearProgress,
+    },
+
+    /// All the packets pending on start on the path have been cleared
+    Cleared { path: Packet },
+
+    /// Clearing the packets pending on start on the path failed
+    Failed { path: Packet, reason: String },
+}
+
+pub type ProgressSender = Sender<StartupProgress>;
+pub type ProgressReceiver = Receiver<StartupProgress>;
+
+/// Whether the supervisor is processing live events, and whether
+/// it has completed its startup, ie. cleared the pending packets.
+///
+/// Clones of a [`Readiness`] share the same state, so that it can
+/// be handed over to the REST API while being set by the supervisor.
+#[derive(Clone, Debug, Default)]
+pub struct Readiness {
+    live: Arc<AtomicBool>,
+    ready: Arc<AtomicBool>,
+}
+
+impl Readiness {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the supervisor is processing live events
+    pub fn is_live(&self) -> bool {
+        self.live.load(Ordering::SeqCst)
+    }
+
+    /// Whether the packets pending on start have been cleared,
+    /// or clearing them has timed out
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::SeqCst)
+    }
+
+    pub fn set_live(&self) {
+        self.live.store(true, Ordering::SeqCst)
+    }
+
+    fn set_ready(&self) {
+        self.ready.store(true, Ordering::SeqCst)
+    }
+}
+
+/// Bounds how many paths clear the packets pending on start at the same time,
+/// so that clearing a large number of paths does not starve the relaying of
+/// live events of node queries.
+///
+/// Clones of a [`ClearPermits`] share the same permits.
+#[derive(Clone, Debug)]
+pub struct ClearPermits(Arc<AtomicUsize>);
+
+impl ClearPermits {
+    pub fn new(permits: usize) -> Self {
+        Self(Arc::new(AtomicUsize::new(permits)))
+    }
+
+    /// Takes one of the permits, if any is left, which
+    /// is given back when the [`ClearPermit`] is dropped.
+    pub fn try_acquire(&self) -> Option<ClearPermit> {
+        self.0
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |permits| {
+                permits.checked_sub(1)
+            })
+            .ok()
+            .map(|_| ClearPermit(self.clone()))
+    }
+}
+
+impl Default for ClearPermits {
+    fn default() -> Self {
+        Self::new(STARTUP_CLEAR_CONCURRENCY)
+    }
+}
+
+/// A permit to clear the packets pending on a path, see [`ClearPermits`].
+#[derive(Debug)]
+pub struct ClearPermit(ClearPermits);
+
+impl Drop for ClearPermit {
+    fn drop(&mut self) {
+        (self.0).0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Tracks the progress of the clearing of the packets pending on start,
+/// as reported by the packet workers, and flips the [`Readiness`] of
+/// the supervisor once all of them are done or the timeout elapses.
+pub struct StartupTracker {
+    receiver: ProgressReceiver,
+    readiness: Readiness,
+    pending: BTreeSet<Packet>,
+    started_at: Instant,
+    timeout: Duration,
+}
+
+impl StartupTracker {
+    pub fn new(receiver: ProgressReceiver, readiness: Readiness, timeout: Duration) -> Self {
+        Self {
+            receiver,
+            readiness,
+            pending: BTreeSet::new(),
+            started_at: Instant::now(),
+            timeout,
+        }
+    }
+
+    /// Process the progress reports received so far.
+    ///
+    /// Returns `true` once the startup is complete and the
+    /// supervisor has been marked as ready.
+    pub fn step(&mut self) -> bool {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(progress) => self.handle_progress(progress),
+                Err(TryRecvError::Empty) => break,
+                // All the packet workers spawned on start are gone,
+                // none of them has anything more to report.
+                Err(TryRecvError::Disconnected) => {
+                    self.pending.clear();
+                    break;
+                }
+            }
+        }
+
+        if self.pending.is_empty() {
+            self.mark_ready();
+            return true;
+        }
+
+        if self.started_at.elapsed() >= self.timeout {
+            warn!(
+                "timed out after {:?} while clearing the packets pending on {} path(s): {}",
+                self.timeout,
+                self.pending.len(),
+                self.pending.iter().map(|path| path.short_name()).join(", ")
+            );
+
+            self.mark_ready();
+            return true;
+        }
+
+        false
+    }
+
+    fn handle_progress(&mut self, progress: StartupProgress) {
+        match progress {
+            StartupProgress::Pending { path } => {
+                self.pending.insert(path);
+            }
+            StartupProgress::Clearing { path, progress } => {
+                info!(
+                    path = %path.short_name(),
+                    "cleared {} of {} pending packet(s)",
+                    progress.cleared,
+                    progress.pending
+                );
+            }
+            StartupProgress::Cleared { path } => {
+                info!(path = %path.short_name(), "done clearing pending packets");
+
+                self.pending.remove(&path);
+            }
+            StartupProgress::Failed { path, reason } => {
+                warn!(
+                    path = %path.short_name(),
+                    "failed to clear pending packets: {}",
+                    reason
+                );
+
+                self.pending.remove(&path);
+            }
+        }
+    }
+
+    fn mark_ready(&self) {
+        self.readiness.set_ready();
+
+        info!(
+            "ready: startup completed in {:.2?}",
+            self.started_at.elapsed()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use crossbeam_channel::unbounded;
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+
+    use super::{ClearPermits, Readiness, StartupProgress, StartupTracker};
+    use crate::link::ClearProgress;
+    use crate::object::Packet;
+
+    fn path(channel: &str) -> Packet {
+        Packet {
+            dst_chain_id: ChainId::from_string("chain_b"),
+            src_chain_id: ChainId::from_string("chain_a"),
+            src_channel_id: channel.parse::<ChannelId>().unwrap(),
+            src_port_id: PortId::transfer(),
+        }
+    }
+
+    #[test]
+    fn ready_once_all_paths_are_cleared() {
+        let (tx, rx) = unbounded();
+        let readiness = Readiness::new();
+        let mut tracker = StartupTracker::new(rx, readiness.clone(), Duration::from_secs(60));
+
+        tx.send(StartupProgress::Pending {
+            path: path("channel-0"),
+        })
+        .unwrap();
+        tx.send(StartupProgress::Pending {
+            path: path("channel-1"),
+        })
+        .unwrap();
+        tx.send(StartupProgress::Clearing {
+            path: path("channel-0"),
+            progress: ClearProgress {
+                cleared: 1,
+                pending: 2,
+            },
+        })
+        .unwrap();
+        tx.send(StartupProgress::Cleared {
+            path: path("channel-0"),
+        })
+        .unwrap();
+
+        assert!(!tracker.step());
+        assert!(!readiness.is_ready());
+
+        tx.send(StartupProgress::Failed {
+            path: path("channel-1"),
+            reason: "failed".to_string(),
+        })
+        .unwrap();
+
+        assert!(tracker.step());
+        assert!(readiness.is_ready());
+    }
+
+    #[test]
+    fn ready_once_timed_out() {
+        let (tx, rx) = unbounded();
+        let readiness = Readiness::new();
+        let mut tracker = StartupTracker::new(rx, readiness.clone(), Duration::ZERO);
+
+        tx.send(StartupProgress::Pending {
+            path: path("channel-0"),
+        })
+        .unwrap();
+
+        assert!(tracker.step());
+        assert!(readiness.is_ready());
+    }
+
+    #[test]
+    fn live_before_ready() {
+        let (tx, rx) = unbounded();
+        let readiness = Readiness::new();
+        let mut tracker = StartupTracker::new(rx, readiness.clone(), Duration::from_secs(60));
+
+        tx.send(StartupProgress::Pending {
+            path: path("channel-0"),
+        })
+        .unwrap();
+
+        readiness.set_live();
+
+        assert!(!tracker.step());
+        assert!(readiness.is_live());
+        assert!(!readiness.is_ready());
+    }
+
+    #[test]
+    fn clear_permits_are_bounded() {
+        let permits = ClearPermits::new(2);
+
+        let first = permits.try_acquire().unwrap();
+        let second = permits.try_acquire().unwrap();
+        assert!(permits.try_acquire().is_none());
+
+        drop(first);
+        let third = permits.try_acquire().unwrap();
+        assert!(permits.try_acquire().is_none());
+
+        drop((second, third));
+        assert!(permits.try_acquire().is_some());
+    }
+}
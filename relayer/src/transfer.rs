@@ -1,25 +1,50 @@
 use ibc::signer::SignerError;
+use std::collections::BTreeMap;
 use std::str::FromStr;
+use std::thread;
+use std::time::Instant;
 
 use core::time::Duration;
 
-use flex_error::{define_error, DetailOnly};
+use flex_error::{define_error, DetailOnly, TraceError};
+use ibc::applications::transfer::acknowledgement::Acknowledgement;
 use ibc::applications::transfer::error::Error as Ics20Error;
 use ibc::applications::transfer::msgs::transfer::MsgTransfer;
-use ibc::applications::transfer::Amount;
+use ibc::applications::transfer::{Amount, Coin};
+use ibc::core::ics02_client::client_state::{AnyClientState, ClientState};
+use ibc::core::ics04_channel::channel::State;
+use ibc::core::ics04_channel::packet::{Packet, Sequence};
 use ibc::core::ics04_channel::timeout::TimeoutHeight;
-use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, ClientId, PortId};
 use ibc::events::IbcEvent;
 use ibc::signer::Signer;
 use ibc::timestamp::{Timestamp, TimestampOverflowError};
 use ibc::tx_msg::Msg;
-use ibc_proto::cosmos::base::v1beta1::Coin;
+use ibc_proto::cosmos::base::v1beta1::Coin as RawCoin;
 use ibc_proto::google::protobuf::Any;
+use serde::Serialize;
+use tracing::{debug, warn};
 
+use crate::chain::cosmos::gas::{explicit_fee_to_fee, gas_amount_to_fee};
+use crate::chain::cosmos::types::gas::{ExplicitFee, GasConfig};
 use crate::chain::endpoint::ChainStatus;
 use crate::chain::handle::ChainHandle;
-use crate::chain::tracking::TrackedMsgs;
+use crate::chain::requests::{
+    IncludeProof, QueryChannelRequest, QueryClientStateRequest, QueryConnectionRequest,
+    QueryConsensusStateRequest, QueryHeight, QueryWrittenAcksRequest,
+};
+use crate::chain::tracking::{CorrelationId, TrackedMsgs};
+use crate::config::ChainConfig;
+use crate::denom::DenomTrace;
 use crate::error::Error;
+use crate::util::unblock::unblock;
+
+/// The maximum length of the memo of a `MsgTransfer`, in bytes, as enforced by ibc-go.
+pub const MAX_MEMO_LEN: usize = 32768;
+
+/// How often the destination chain is queried while waiting for the acknowledgements
+/// of the packets of a transfer, see [`wait_for_transfer_acks`].
+const ACK_POLL_INTERVAL: Duration = Duration::from_secs(2);
 
 define_error! {
     TransferError {
@@ -42,6 +67,14 @@ define_error! {
                     e.chain_id)
             },
 
+        Simulate
+            { chain_id: ChainId }
+            [ Error ]
+            |e| {
+                format!("failed while simulating the Transfer message on chain {0}",
+                    e.chain_id)
+            },
+
         TimestampOverflow
             [ DetailOnly<TimestampOverflowError> ]
             |_| { "timestamp overflow" },
@@ -66,6 +99,103 @@ define_error! {
 
         ZeroTimeout
             | _ | { "packet timeout height and packet timeout timestamp cannot both be 0" },
+
+        InvalidReceiver
+            { receiver: String }
+            [ TraceError<bech32::Error> ]
+            |e| { format!("the receiver address '{}' is not a valid bech32 address", e.receiver) },
+
+        ReceiverPrefixMismatch
+            { receiver: String, prefix: String, chain_id: ChainId, account_prefix: String }
+            |e| {
+                format!("the receiver address '{}' has the prefix '{}', but the account prefix of chain '{}' is '{}'",
+                    e.receiver, e.prefix, e.chain_id, e.account_prefix)
+            },
+
+        InvalidFeeGranter
+            { fee_granter: String }
+            [ TraceError<bech32::Error> ]
+            |e| { format!("the fee granter address '{}' is not a valid bech32 address", e.fee_granter) },
+
+        FeeGranterPrefixMismatch
+            { fee_granter: String, prefix: String, chain_id: ChainId, account_prefix: String }
+            |e| {
+                format!("the fee granter address '{}' has the prefix '{}', but the account prefix of chain '{}' is '{}'",
+                    e.fee_granter, e.prefix, e.chain_id, e.account_prefix)
+            },
+
+        NoCoins
+            | _ | { "no coins to transfer" },
+
+        ConflictingCoins
+            { denom: String, amount: Amount, other_amount: Amount }
+            |e| {
+                format!("the coins of denomination '{}' are given twice, with conflicting amounts {} and {}",
+                    e.denom, e.amount, e.other_amount)
+            },
+
+        MemoTooLong
+            { length: usize }
+            |e| {
+                format!("the memo must be no longer than {} bytes, found length {}",
+                    MAX_MEMO_LEN, e.length)
+            },
+
+        ChannelNotOpen
+            { port_id: PortId, channel_id: ChannelId, chain_id: ChainId, state: State }
+            |e| {
+                format!("the requested port/channel ('{}'/'{}') on chain id '{}' is in state '{}'; expected 'open' state",
+                    e.port_id, e.channel_id, e.chain_id, e.state)
+            },
+
+        TimeoutTimestampElapsed
+            { timeout_timestamp: Timestamp, consensus_timestamp: Timestamp, client_id: ClientId, chain_id: ChainId }
+            |e| {
+                format!("the timeout timestamp {} is not after the timestamp {} of the latest consensus state of client '{}' on chain '{}', the packets would time out right away",
+                    e.timeout_timestamp, e.consensus_timestamp, e.client_id, e.chain_id)
+            },
+
+        MissingConnectionHop
+            { port_id: PortId, channel_id: ChannelId, chain_id: ChainId }
+            |e| {
+                format!("could not retrieve the connection hop underlying port/channel '{}'/'{}' on chain '{}'",
+                    e.port_id, e.channel_id, e.chain_id)
+            },
+
+        DestinationChainMismatch
+            {
+                port_id: PortId,
+                channel_id: ChannelId,
+                src_chain_id: ChainId,
+                counterparty_chain_id: ChainId,
+                dst_chain_id: ChainId,
+            }
+            |e| {
+                format!("the requested port/channel ('{}'/'{}') provides a path from chain '{}' to \
+                    chain '{}' (not to the destination chain '{}'). Bailing due to mismatching arguments.",
+                    e.port_id, e.channel_id, e.src_chain_id, e.counterparty_chain_id, e.dst_chain_id)
+            },
+
+        UnknownVoucherTrace
+            { denom: String, chain_id: ChainId }
+            [ Error ]
+            |e| {
+                format!("failed to resolve the trace of the IBC voucher denomination '{}' on chain '{}', \
+                    check that coins of this denomination were received on that chain",
+                    e.denom, e.chain_id)
+            },
+    }
+}
+
+impl TransferError {
+    /// Whether a query of the pre-flight checks of the transfer failed transiently,
+    /// see [`ErrorDetail::is_transient`](crate::error::ErrorDetail::is_transient).
+    pub fn is_transient(&self) -> bool {
+        match self.detail() {
+            TransferErrorDetail::Relayer(e) => e.source.is_transient(),
+            TransferErrorDetail::UnknownVoucherTrace(e) => e.source.is_transient(),
+            _ => false,
+        }
     }
 }
 
@@ -118,12 +248,285 @@ impl TransferTimeout {
 pub struct TransferOptions {
     pub packet_src_port_id: PortId,
     pub packet_src_channel_id: ChannelId,
-    pub amount: Amount,
-    pub denom: String,
+    /// The coins to transfer, each in its own `MsgTransfer`, in this order.
+    pub coins: Vec<Coin<String>>,
     pub receiver: Option<String>,
     pub timeout_height_offset: u64,
     pub timeout_duration: Duration,
+    /// The absolute timeout timestamp of the packets, used instead of `timeout_duration` if set.
+    pub timeout_timestamp: Option<Timestamp>,
     pub number_msgs: usize,
+    /// The fee to pay for each transaction instead of simulating it.
+    pub fee: Option<ExplicitFee>,
+    /// The account paying the fees of the transactions through a fee grant,
+    /// instead of the fee granter of the source chain configuration if any.
+    pub fee_granter: Option<String>,
+    /// The memo of the `MsgTransfer`s, left out of the messages when `None`.
+    pub memo: Option<String>,
+}
+
+impl TransferOptions {
+    /// Checks that there are coins to transfer, and that the coins of the same
+    /// denomination, if any, are of the same amount.
+    pub fn validate_coins(&self) -> Result<(), TransferError> {
+        if self.coins.is_empty() {
+            return Err(TransferError::no_coins());
+        }
+
+        for (i, coin) in self.coins.iter().enumerate() {
+            let conflicting = self.coins[..i]
+                .iter()
+                .find(|other| other.denom == coin.denom && other.amount != coin.amount);
+
+            if let Some(other) = conflicting {
+                return Err(TransferError::conflicting_coins(
+                    coin.denom.clone(),
+                    other.amount,
+                    coin.amount,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the receiver, if any, is a bech32 address with the account prefix
+    /// of the destination chain configured by `dst_chain_config`, for a mistyped
+    /// address not to receive coins that no one can ever spend.
+    pub fn validate_receiver(&self, dst_chain_config: &ChainConfig) -> Result<(), TransferError> {
+        let receiver = match &self.receiver {
+            Some(receiver) => receiver,
+            None => return Ok(()),
+        };
+
+        let (prefix, _, _) = bech32::decode(receiver)
+            .map_err(|e| TransferError::invalid_receiver(receiver.clone(), e))?;
+
+        if prefix != dst_chain_config.account_prefix {
+            return Err(TransferError::receiver_prefix_mismatch(
+                receiver.clone(),
+                prefix,
+                dst_chain_config.id.clone(),
+                dst_chain_config.account_prefix.clone(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the fee granter, if any, is a bech32 address with the account prefix
+    /// of the source chain configured by `src_chain_config`, which pays the fees.
+    pub fn validate_fee_granter(
+        &self,
+        src_chain_config: &ChainConfig,
+    ) -> Result<(), TransferError> {
+        let fee_granter = match &self.fee_granter {
+            Some(fee_granter) => fee_granter,
+            None => return Ok(()),
+        };
+
+        let (prefix, _, _) = bech32::decode(fee_granter)
+            .map_err(|e| TransferError::invalid_fee_granter(fee_granter.clone(), e))?;
+
+        if prefix != src_chain_config.account_prefix {
+            return Err(TransferError::fee_granter_prefix_mismatch(
+                fee_granter.clone(),
+                prefix,
+                src_chain_config.id.clone(),
+                src_chain_config.account_prefix.clone(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the memo, if any, is no longer than [`MAX_MEMO_LEN`].
+    pub fn validate_memo(&self) -> Result<(), TransferError> {
+        match &self.memo {
+            Some(memo) if memo.len() > MAX_MEMO_LEN => {
+                Err(TransferError::memo_too_long(memo.len()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Logs a warning if the memo is longer than the `max_transfer_memo_len` configured
+    /// for the given chain, which may reject the transfers.
+    ///
+    /// Returns whether the warning was logged.
+    pub fn warn_on_long_memo(&self, chain_config: &ChainConfig) -> bool {
+        match &self.memo {
+            Some(memo) if memo.len() > chain_config.max_transfer_memo_len => {
+                warn!(
+                    "the memo is {} bytes long, which is longer than the `max_transfer_memo_len` of {} bytes \
+                    configured for chain '{}', the transfers may be rejected",
+                    memo.len(),
+                    chain_config.max_transfer_memo_len,
+                    chain_config.id
+                );
+
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Checks that the given channel end on `src_chain` is open and provides a path
+/// to the chain `dst_chain_id`.
+///
+/// To do this, fetches from the source chain the channel end, then the associated
+/// connection end, and then the underlying client state; finally, checks that this
+/// client is verifying headers for the destination chain.
+///
+/// Returns the identifier and the state of this client.
+pub fn check_transfer_path<Chain: ChainHandle>(
+    src_chain: &Chain,
+    dst_chain_id: &ChainId,
+    port_id: &PortId,
+    channel_id: &ChannelId,
+) -> Result<(ClientId, AnyClientState), TransferError> {
+    let (channel_end, _) = src_chain
+        .query_channel(
+            QueryChannelRequest {
+                port_id: port_id.clone(),
+                channel_id: channel_id.clone(),
+                height: QueryHeight::Latest,
+            },
+            IncludeProof::No,
+        )
+        .map_err(TransferError::relayer)?;
+
+    if !channel_end.is_open() {
+        return Err(TransferError::channel_not_open(
+            port_id.clone(),
+            channel_id.clone(),
+            src_chain.id(),
+            channel_end.state,
+        ));
+    }
+
+    let connection_id = channel_end.connection_hops.first().ok_or_else(|| {
+        TransferError::missing_connection_hop(port_id.clone(), channel_id.clone(), src_chain.id())
+    })?;
+
+    let (connection_end, _) = src_chain
+        .query_connection(
+            QueryConnectionRequest {
+                connection_id: connection_id.clone(),
+                height: QueryHeight::Latest,
+            },
+            IncludeProof::No,
+        )
+        .map_err(TransferError::relayer)?;
+
+    debug!(
+        "connection hop underlying the channel: {:?}",
+        connection_end
+    );
+
+    let (client_state, _) = src_chain
+        .query_client_state(
+            QueryClientStateRequest {
+                client_id: connection_end.client_id().clone(),
+                height: QueryHeight::Latest,
+            },
+            IncludeProof::No,
+        )
+        .map_err(TransferError::relayer)?;
+
+    debug!("client state underlying the channel: {:?}", client_state);
+
+    if &client_state.chain_id() != dst_chain_id {
+        return Err(TransferError::destination_chain_mismatch(
+            port_id.clone(),
+            channel_id.clone(),
+            src_chain.id(),
+            client_state.chain_id(),
+            dst_chain_id.clone(),
+        ));
+    }
+
+    Ok((connection_end.client_id().clone(), client_state))
+}
+
+/// Checks that the absolute `timeout_timestamp` of the packets is after the timestamp of the
+/// latest consensus state of the client `client_id` of the destination chain on `src_chain`,
+/// as returned by [`check_transfer_path`], since the source chain rejects the packets which
+/// already timed out according to this consensus state.
+pub fn check_timeout_timestamp<Chain: ChainHandle>(
+    src_chain: &Chain,
+    client_id: &ClientId,
+    client_state: &AnyClientState,
+    timeout_timestamp: Timestamp,
+) -> Result<(), TransferError> {
+    let (consensus_state, _) = src_chain
+        .query_consensus_state(
+            QueryConsensusStateRequest {
+                client_id: client_id.clone(),
+                consensus_height: client_state.latest_height(),
+                query_height: QueryHeight::Latest,
+            },
+            IncludeProof::No,
+        )
+        .map_err(TransferError::relayer)?;
+
+    let consensus_timestamp = consensus_state.timestamp();
+
+    if !timeout_timestamp.after(&consensus_timestamp) {
+        return Err(TransferError::timeout_timestamp_elapsed(
+            timeout_timestamp,
+            consensus_timestamp,
+            client_id.clone(),
+            src_chain.id(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Resolves the trace of the coins of the given denomination on `src_chain` when they
+/// are IBC vouchers, ie. of denomination `ibc/{hash}`, so that sending coins without
+/// any trace on the source chain fails early. Returns `None` for any other denomination.
+pub fn resolve_voucher_trace<Chain: ChainHandle>(
+    src_chain: &Chain,
+    denom: &str,
+) -> Result<Option<DenomTrace>, TransferError> {
+    let trace = src_chain
+        .query_ibc_denom_trace(denom)
+        .map_err(|e| TransferError::unknown_voucher_trace(denom.to_string(), src_chain.id(), e))?;
+
+    if let Some(trace) = &trace {
+        debug!(
+            "denomination '{}' is the voucher of '{}'",
+            denom,
+            trace.full_path()
+        );
+    }
+
+    Ok(trace)
+}
+
+/// Warns when the voucher of the given denomination and `trace` is sent over another
+/// channel than the one it was last received over, in which case the destination chain
+/// receives a voucher of the voucher, with a longer trace, instead of unwinding it.
+pub fn check_voucher_channel(
+    denom: &str,
+    trace: &DenomTrace,
+    port_id: &PortId,
+    channel_id: &ChannelId,
+) {
+    if !trace.received_over(port_id, channel_id) {
+        warn!(
+            "the coins of denomination '{}' are the voucher of '{}', which is sent over '{}/{}' \
+            instead of the channel it was received over: the destination chain will receive \
+            a voucher of this voucher instead of unwinding it",
+            denom,
+            trace.full_path(),
+            port_id,
+            channel_id,
+        );
+    }
 }
 
 pub fn build_transfer_message(
@@ -139,7 +542,7 @@ pub fn build_transfer_message(
     let msg = MsgTransfer {
         source_port: packet_src_port_id,
         source_channel: packet_src_channel_id,
-        token: Coin {
+        token: RawCoin {
             denom,
             amount: amount.to_string(),
         },
@@ -147,16 +550,22 @@ pub fn build_transfer_message(
         receiver,
         timeout_height,
         timeout_timestamp,
+        memo: String::new(),
     };
 
     msg.to_any()
 }
 
-pub fn build_and_send_transfer_messages<SrcChain: ChainHandle, DstChain: ChainHandle>(
-    packet_src_chain: &SrcChain, // the chain whose account is debited
-    packet_dst_chain: &DstChain, // the chain whose account eventually gets credited
+/// Builds the `MsgTransfer`s of the transfer described by `opts`, in the order they
+/// would be sent by [`build_and_send_transfer_messages`], without sending them.
+pub fn build_transfer_messages<SrcChain: ChainHandle, DstChain: ChainHandle>(
+    packet_src_chain: &SrcChain,
+    packet_dst_chain: &DstChain,
     opts: &TransferOptions,
-) -> Result<Vec<IbcEvent>, TransferError> {
+) -> Result<Vec<MsgTransfer>, TransferError> {
+    opts.validate_coins()?;
+    opts.validate_memo()?;
+
     let receiver = match &opts.receiver {
         Some(receiver) => Signer::from_str(receiver).map_err(TransferError::receiver_address)?,
         None => packet_dst_chain.get_signer().map_err(TransferError::key)?,
@@ -168,30 +577,110 @@ pub fn build_and_send_transfer_messages<SrcChain: ChainHandle, DstChain: ChainHa
         .query_application_status()
         .map_err(TransferError::relayer)?;
 
-    let timeout = TransferTimeout::new(
+    let mut timeout = TransferTimeout::new(
         opts.timeout_height_offset,
         opts.timeout_duration,
         &destination_chain_status,
     )?;
 
-    let msg = MsgTransfer {
-        source_port: opts.packet_src_port_id.clone(),
-        source_channel: opts.packet_src_channel_id.clone(),
-        token: Coin {
-            denom: opts.denom.clone(),
-            amount: opts.amount.to_string(),
-        },
-        sender,
-        receiver,
-        timeout_height: timeout.timeout_height,
-        timeout_timestamp: timeout.timeout_timestamp,
+    // The absolute timeout timestamp is carried as is into the packets
+    if let Some(timeout_timestamp) = opts.timeout_timestamp {
+        timeout.timeout_timestamp = timeout_timestamp;
+    }
+
+    // The `number_msgs` messages of each coin follow those of the previous coin,
+    // for the events to be in the order of the coins
+    let msgs = opts
+        .coins
+        .iter()
+        .flat_map(|coin| {
+            let msg = MsgTransfer {
+                source_port: opts.packet_src_port_id.clone(),
+                source_channel: opts.packet_src_channel_id.clone(),
+                token: RawCoin::from(coin.clone()),
+                sender: sender.clone(),
+                receiver: receiver.clone(),
+                timeout_height: timeout.timeout_height,
+                timeout_timestamp: timeout.timeout_timestamp,
+                memo: opts.memo.clone().unwrap_or_default(),
+            };
+
+            vec![msg; opts.number_msgs]
+        })
+        .collect();
+
+    Ok(msgs)
+}
+
+/// Builds the `MsgTransfer`s of the transfer described by `opts` over each of the source
+/// channels `channel_ids` in turn, in place of the source channel of `opts`, e.g. to spread
+/// a transfer over several paths to the destination chain.
+pub fn build_multi_channel_transfer_messages<SrcChain: ChainHandle, DstChain: ChainHandle>(
+    packet_src_chain: &SrcChain,
+    packet_dst_chain: &DstChain,
+    opts: &TransferOptions,
+    channel_ids: &[ChannelId],
+) -> Result<Vec<MsgTransfer>, TransferError> {
+    let mut msgs = Vec::new();
+
+    for channel_id in channel_ids {
+        let opts = TransferOptions {
+            packet_src_channel_id: channel_id.clone(),
+            ..opts.clone()
+        };
+
+        msgs.extend(build_transfer_messages(
+            packet_src_chain,
+            packet_dst_chain,
+            &opts,
+        )?);
+    }
+
+    Ok(msgs)
+}
+
+/// The messages to submit the given `MsgTransfer`s, paying the explicit fee of `opts` if any,
+/// through the fee granter of `opts` if any.
+fn track_transfer_messages(msgs: &[MsgTransfer], opts: &TransferOptions) -> TrackedMsgs {
+    let any_msgs = msgs.iter().map(|msg| msg.clone().to_any()).collect();
+
+    let tracked_msgs = TrackedMsgs::new_static(any_msgs, "ft-transfer");
+    let tracked_msgs = match &opts.fee {
+        Some(fee) => tracked_msgs.with_fee(fee.clone()),
+        None => tracked_msgs,
     };
 
-    let raw_msg = msg.to_any();
-    let msgs = vec![raw_msg; opts.number_msgs];
+    match &opts.fee_granter {
+        Some(fee_granter) => tracked_msgs.with_fee_granter(fee_granter.clone()),
+        None => tracked_msgs,
+    }
+}
+
+pub fn build_and_send_transfer_messages<SrcChain: ChainHandle, DstChain: ChainHandle>(
+    packet_src_chain: &SrcChain, // the chain whose account is debited
+    packet_dst_chain: &DstChain, // the chain whose account eventually gets credited
+    opts: &TransferOptions,
+) -> Result<Vec<IbcEvent>, TransferError> {
+    let msgs = build_transfer_messages(packet_src_chain, packet_dst_chain, opts)?;
+
+    send_transfer_messages(packet_src_chain, &msgs, opts, CorrelationId::new())
+}
+
+/// Submits the given `MsgTransfer`s on the source chain, in as many transactions as
+/// the configuration of the chain requires, paying the explicit fee of `opts` if any.
+/// The messages are tagged with `correlation_id`, to find their transactions in the logs.
+///
+/// Returns the events of the transactions, or an error if the chain rejected one of them.
+pub fn send_transfer_messages<SrcChain: ChainHandle>(
+    packet_src_chain: &SrcChain,
+    msgs: &[MsgTransfer],
+    opts: &TransferOptions,
+    correlation_id: CorrelationId,
+) -> Result<Vec<IbcEvent>, TransferError> {
+    let tracked_msgs = track_transfer_messages(msgs, opts).with_correlation_id(correlation_id);
 
     let events = packet_src_chain
-        .send_messages_and_wait_commit(TrackedMsgs::new_static(msgs, "ft-transfer"))
+        .send_messages_and_wait_commit(tracked_msgs)
         .map_err(|e| TransferError::submit(packet_src_chain.id(), e))?;
 
     // Check if the chain rejected the transaction
@@ -203,7 +692,7 @@ pub fn build_and_send_transfer_messages<SrcChain: ChainHandle, DstChain: ChainHa
         None => Ok(events),
         Some(err) => {
             if let IbcEvent::ChainError(err) = err {
-                Err(TransferError::tx_response(err.clone()))
+                Err(TransferError::tx_response(err.message.clone()))
             } else {
                 panic!(
                     "internal error, expected IBCEvent::ChainError, got {:?}",
@@ -213,3 +702,718 @@ pub fn build_and_send_transfer_messages<SrcChain: ChainHandle, DstChain: ChainHa
         }
     }
 }
+
+/// The outcome of the simulation of a transfer, see [`simulate_transfer_messages`].
+#[derive(Clone, Debug, Serialize)]
+pub struct TransferSimulation {
+    /// The gas used by the simulated transactions.
+    pub estimated_gas: u64,
+    /// The gas limit the transactions would be submitted with.
+    pub gas_limit: u64,
+    /// The fee which would be paid for the transactions.
+    pub fee: Vec<RawCoin>,
+}
+
+/// Simulates the messages built by [`build_transfer_messages`] for the transfer
+/// described by `opts` on the source chain, to estimate their gas and fee,
+/// without broadcasting anything.
+///
+/// The fee is the explicit fee of `opts` if any, and otherwise the fee computed
+/// from the estimated gas with the gas settings of the source chain.
+pub fn simulate_transfer_messages<SrcChain: ChainHandle>(
+    packet_src_chain: &SrcChain,
+    msgs: &[MsgTransfer],
+    opts: &TransferOptions,
+) -> Result<TransferSimulation, TransferError> {
+    let estimated_gas = packet_src_chain
+        .estimate_messages_gas(track_transfer_messages(msgs, opts))
+        .map_err(|e| TransferError::simulate(packet_src_chain.id(), e))?;
+
+    let src_chain_config = packet_src_chain.config().map_err(TransferError::relayer)?;
+    let gas_config = GasConfig::from(&src_chain_config);
+
+    let fee = match &opts.fee {
+        Some(fee) => explicit_fee_to_fee(&gas_config, fee),
+        None => gas_amount_to_fee(&gas_config, estimated_gas),
+    };
+
+    Ok(TransferSimulation {
+        estimated_gas,
+        gas_limit: fee.gas_limit,
+        fee: fee.amount,
+    })
+}
+
+/// What became of a packet sent by a transfer, see [`wait_for_transfer_acks`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum PacketStatus {
+    /// The destination chain received the packet and credited the receiver.
+    Success,
+    /// The destination chain received the packet but failed the transfer, e.g. because
+    /// of an invalid receiver, so the tokens are refunded once the acknowledgement is relayed.
+    Error { error: String },
+    /// The packet timed out before being received, so the tokens are refunded
+    /// once the timeout is relayed.
+    TimedOut,
+    /// The packet was neither acknowledged nor timed out when the wait ended.
+    Pending,
+}
+
+/// The status of a packet sent by a transfer, identified by its sequence.
+#[derive(Clone, Debug, Serialize)]
+pub struct PacketOutcome {
+    pub sequence: Sequence,
+    #[serde(flatten)]
+    pub status: PacketStatus,
+}
+
+impl PacketStatus {
+    /// The status of a packet for which the destination chain wrote the given
+    /// acknowledgement, decoded as an ICS-20 acknowledgement.
+    fn from_ack(ack: &[u8]) -> Self {
+        match serde_json::from_slice::<Acknowledgement>(ack) {
+            Ok(Acknowledgement::Success(_)) => Self::Success,
+            Ok(Acknowledgement::Error(error)) => Self::Error { error },
+            Err(_) => Self::Error {
+                error: format!(
+                    "unexpected acknowledgement: {}",
+                    String::from_utf8_lossy(ack)
+                ),
+            },
+        }
+    }
+}
+
+/// Waits for the destination chain to acknowledge the packets sent by a transfer,
+/// found in the `events` returned by [`build_and_send_transfer_messages`], or for
+/// the packets to time out, for at most `timeout`.
+///
+/// Returns the status of each packet, in the order they were sent, where the
+/// packets still `Pending` were not acknowledged within `timeout`. The failures
+/// to query the destination chain are only logged, and the queries retried.
+pub fn wait_for_transfer_acks<DstChain: ChainHandle>(
+    packet_dst_chain: &DstChain,
+    events: &[IbcEvent],
+    timeout: Duration,
+) -> Vec<PacketOutcome> {
+    let mut packets: Vec<(Packet, PacketStatus)> = events
+        .iter()
+        .filter_map(|event| match event {
+            IbcEvent::SendPacket(ev) => Some((ev.packet.clone(), PacketStatus::Pending)),
+            _ => None,
+        })
+        .collect();
+
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        if let Err(e) = poll_transfer_packets(packet_dst_chain, &mut packets) {
+            warn!(
+                "failed to query the acknowledgements on chain {}, retrying: {}",
+                packet_dst_chain.id(),
+                e
+            );
+        }
+
+        let pending = packets
+            .iter()
+            .filter(|(_, status)| *status == PacketStatus::Pending)
+            .count();
+
+        let now = Instant::now();
+        if pending == 0 || now >= deadline {
+            break;
+        }
+
+        debug!(
+            "waiting for the acknowledgements of {} packets on chain {}",
+            pending,
+            packet_dst_chain.id()
+        );
+
+        thread::sleep(ACK_POLL_INTERVAL.min(deadline - now));
+    }
+
+    packets
+        .into_iter()
+        .map(|(packet, status)| PacketOutcome {
+            sequence: packet.sequence,
+            status,
+        })
+        .collect()
+}
+
+/// Updates the status of the pending packets with the acknowledgements written on the
+/// destination chain, and marks as timed out the ones which can no longer be received.
+fn poll_transfer_packets<DstChain: ChainHandle>(
+    packet_dst_chain: &DstChain,
+    packets: &mut [(Packet, PacketStatus)],
+) -> Result<(), Error> {
+    // Query the status first, so that a packet received before timing out
+    // has its acknowledgement written by the time the acknowledgements are queried
+    let dst_status = packet_dst_chain.query_application_status()?;
+
+    let mut pending: BTreeMap<(PortId, ChannelId), Vec<Sequence>> = BTreeMap::new();
+    for (packet, _) in packets.iter().filter(|(_, s)| *s == PacketStatus::Pending) {
+        pending
+            .entry((
+                packet.destination_port.clone(),
+                packet.destination_channel.clone(),
+            ))
+            .or_default()
+            .push(packet.sequence);
+    }
+
+    for ((port_id, channel_id), sequences) in pending {
+        let acks = packet_dst_chain.query_written_acks(QueryWrittenAcksRequest {
+            port_id: port_id.clone(),
+            channel_id: channel_id.clone(),
+            sequences,
+        })?;
+
+        for ack in acks {
+            let written = packets.iter_mut().find(|(packet, _)| {
+                packet.sequence == ack.sequence
+                    && packet.destination_port == port_id
+                    && packet.destination_channel == channel_id
+            });
+
+            if let Some((_, status)) = written {
+                *status = PacketStatus::from_ack(&ack.ack);
+            }
+        }
+    }
+
+    for (packet, status) in packets.iter_mut() {
+        if *status == PacketStatus::Pending
+            && packet.timed_out(&dst_status.timestamp, dst_status.height)
+        {
+            *status = PacketStatus::TimedOut;
+        }
+    }
+
+    Ok(())
+}
+
+/// Non-blocking variant of [`build_and_send_transfer_messages`], which can be called
+/// from an async context without blocking the executor.
+///
+/// The transfer is built and sent from a dedicated thread, which blocks on the replies
+/// of the runtimes of the chains, themselves running on their own threads,
+/// see [`unblock`](crate::util::unblock::unblock).
+pub async fn build_and_send_transfer_messages_async<SrcChain, DstChain>(
+    packet_src_chain: &SrcChain,
+    packet_dst_chain: &DstChain,
+    opts: &TransferOptions,
+) -> Result<Vec<IbcEvent>, TransferError>
+where
+    SrcChain: ChainHandle,
+    DstChain: ChainHandle,
+{
+    let packet_src_chain = packet_src_chain.clone();
+    let packet_dst_chain = packet_dst_chain.clone();
+    let opts = opts.clone();
+
+    unblock(move || build_and_send_transfer_messages(&packet_src_chain, &packet_dst_chain, &opts))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        build_and_send_transfer_messages, build_and_send_transfer_messages_async,
+        build_multi_channel_transfer_messages, build_transfer_messages, check_transfer_path,
+        resolve_voucher_trace, simulate_transfer_messages, wait_for_transfer_acks, PacketStatus,
+        TransferErrorDetail, TransferOptions, MAX_MEMO_LEN,
+    };
+
+    use core::str::FromStr;
+    use core::time::Duration;
+    use std::thread;
+
+    use futures::future::{select, Either};
+    use ibc::applications::transfer::msgs::transfer::MsgTransfer;
+    use ibc::applications::transfer::{Amount, Coin};
+    use ibc::core::ics04_channel::events::SendPacket;
+    use ibc::core::ics04_channel::packet::{Packet, Sequence};
+    use ibc::core::ics04_channel::timeout::TimeoutHeight;
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+    use ibc::events::IbcEvent;
+    use ibc::signer::Signer;
+    use ibc::timestamp::Timestamp;
+    use ibc::Height;
+    use tendermint_proto::Protobuf;
+
+    use crate::chain::handle::mock::MockChainHandle;
+    use crate::chain::mock::test_utils::get_basic_chain_config;
+    use crate::denom::DenomTrace;
+    use crate::error::Error;
+
+    fn chains() -> (MockChainHandle, MockChainHandle) {
+        let src_chain = MockChainHandle::new(ChainId::from_string("chain_A"));
+        let dst_chain = MockChainHandle::new(ChainId::from_string("chain_B"));
+
+        src_chain.set_signer(Signer::from_str("cosmos1sender").unwrap());
+        dst_chain.set_signer(Signer::from_str("cosmos1receiver").unwrap());
+
+        (src_chain, dst_chain)
+    }
+
+    fn coin(denom: &str, amount: u64) -> Coin<String> {
+        Coin {
+            denom: denom.to_string(),
+            amount: Amount::from(amount),
+        }
+    }
+
+    fn opts() -> TransferOptions {
+        TransferOptions {
+            packet_src_port_id: PortId::transfer(),
+            packet_src_channel_id: ChannelId::new(0),
+            coins: vec![coin("uatom", 1000)],
+            receiver: None,
+            timeout_height_offset: 100,
+            timeout_duration: Duration::from_secs(600),
+            timeout_timestamp: None,
+            number_msgs: 2,
+            fee: None,
+            fee_granter: None,
+            memo: None,
+        }
+    }
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn transfer_from_async_context() {
+        let (src_chain, dst_chain) = chains();
+
+        let events = runtime()
+            .block_on(build_and_send_transfer_messages_async(
+                &src_chain,
+                &dst_chain,
+                &opts(),
+            ))
+            .unwrap();
+
+        assert!(events.is_empty());
+        assert_eq!(src_chain.sent_msgs().len(), 2);
+        assert!(dst_chain.sent_msgs().is_empty());
+    }
+
+    #[test]
+    fn transfer_does_not_block_executor() {
+        let (src_chain, dst_chain) = chains();
+
+        // The chain takes a while to reject the transaction
+        src_chain.inject_failure("send_messages_and_wait_commit", || {
+            thread::sleep(Duration::from_millis(500));
+            Error::channel_send()
+        });
+
+        let opts = opts();
+
+        let result = runtime().block_on(async {
+            let transfer = build_and_send_transfer_messages_async(&src_chain, &dst_chain, &opts);
+            let other_task = tokio::time::sleep(Duration::from_millis(10));
+
+            // The single thread of the runtime must be free to run the other task
+            // while the transfer waits on the chain
+            match select(Box::pin(transfer), Box::pin(other_task)).await {
+                Either::Left(_) => panic!("the transfer blocked the executor"),
+                Either::Right(((), transfer)) => transfer.await,
+            }
+        });
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn simulate_transfer_without_sending() {
+        let (src_chain, dst_chain) = chains();
+
+        let mut chain_config = get_basic_chain_config("chain_A");
+        chain_config.gas_multiplier = Some(1.0);
+        src_chain.set_config(chain_config);
+        src_chain.set_estimated_gas(100_000);
+
+        let msgs = build_transfer_messages(&src_chain, &dst_chain, &opts()).unwrap();
+        let simulation = simulate_transfer_messages(&src_chain, &msgs, &opts()).unwrap();
+
+        assert_eq!(msgs.len(), 2);
+        assert_eq!(msgs[0].receiver.as_ref(), "cosmos1receiver");
+        assert_eq!(simulation.estimated_gas, 100_000);
+        assert_eq!(simulation.gas_limit, 100_000);
+        assert_eq!(simulation.fee.len(), 1);
+        assert_eq!(simulation.fee[0].denom, "uatom");
+        // The float nearest to the gas price of 0.001 exceeds it, hence one more uatom
+        assert_eq!(simulation.fee[0].amount, "101");
+        assert!(src_chain.sent_msgs().is_empty());
+    }
+
+    #[test]
+    fn transfer_over_several_channels() {
+        let (src_chain, dst_chain) = chains();
+
+        let channel_ids = [ChannelId::new(0), ChannelId::new(3)];
+        let msgs =
+            build_multi_channel_transfer_messages(&src_chain, &dst_chain, &opts(), &channel_ids)
+                .unwrap();
+
+        let channels = msgs
+            .iter()
+            .map(|msg| msg.source_channel.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            channels,
+            ["channel-0", "channel-0", "channel-3", "channel-3"]
+        );
+    }
+
+    #[test]
+    fn resolve_voucher_traces() {
+        let (src_chain, _) = chains();
+
+        let trace = DenomTrace {
+            path: "transfer/channel-0".to_string(),
+            base_denom: "uatom".to_string(),
+        };
+        src_chain.add_denom_trace(trace.clone());
+
+        let resolved = resolve_voucher_trace(&src_chain, &trace.ibc_denom()).unwrap();
+        assert_eq!(resolved.unwrap().full_path(), "transfer/channel-0/uatom");
+
+        // The native denominations have no trace
+        assert!(resolve_voucher_trace(&src_chain, "stake")
+            .unwrap()
+            .is_none());
+
+        // The voucher of another trace was never received by the source chain
+        let unknown = DenomTrace {
+            path: "transfer/channel-1".to_string(),
+            ..trace
+        };
+        let result = resolve_voucher_trace(&src_chain, &unknown.ibc_denom());
+
+        assert!(matches!(
+            result.unwrap_err().detail(),
+            TransferErrorDetail::UnknownVoucherTrace(e) if e.denom == unknown.ibc_denom()
+        ));
+    }
+
+    #[test]
+    fn transfer_with_memo() {
+        let (src_chain, dst_chain) = chains();
+
+        let opts = TransferOptions {
+            memo: Some("memo".to_string()),
+            ..opts()
+        };
+
+        build_and_send_transfer_messages(&src_chain, &dst_chain, &opts).unwrap();
+
+        for msg in src_chain.sent_msgs() {
+            assert_eq!(MsgTransfer::decode_vec(&msg.value).unwrap().memo, "memo");
+        }
+    }
+
+    #[test]
+    fn transfer_with_too_long_memo() {
+        let (src_chain, dst_chain) = chains();
+
+        let opts = TransferOptions {
+            memo: Some("m".repeat(MAX_MEMO_LEN + 1)),
+            ..opts()
+        };
+
+        let result = build_and_send_transfer_messages(&src_chain, &dst_chain, &opts);
+
+        assert!(matches!(
+            result.unwrap_err().detail(),
+            TransferErrorDetail::MemoTooLong(e) if e.length == MAX_MEMO_LEN + 1
+        ));
+        assert!(src_chain.sent_msgs().is_empty());
+
+        let opts = TransferOptions {
+            memo: Some("m".repeat(MAX_MEMO_LEN)),
+            ..opts
+        };
+
+        assert!(opts.validate_memo().is_ok());
+    }
+
+    #[test]
+    fn warn_on_long_memo() {
+        let mut chain_config = get_basic_chain_config("chain_A");
+        chain_config.max_transfer_memo_len = 256;
+
+        assert!(!opts().warn_on_long_memo(&chain_config));
+
+        let opts = TransferOptions {
+            memo: Some("m".repeat(256)),
+            ..opts()
+        };
+        assert!(!opts.warn_on_long_memo(&chain_config));
+
+        let opts = TransferOptions {
+            memo: Some("m".repeat(257)),
+            ..opts
+        };
+        assert!(opts.warn_on_long_memo(&chain_config));
+    }
+
+    #[test]
+    fn transfer_several_coins_in_order() {
+        let (src_chain, dst_chain) = chains();
+
+        let opts = TransferOptions {
+            coins: vec![coin("stake", 100), coin("samoleans", 25)],
+            ..opts()
+        };
+
+        build_and_send_transfer_messages(&src_chain, &dst_chain, &opts).unwrap();
+
+        let denoms = src_chain
+            .sent_msgs()
+            .iter()
+            .map(|msg| MsgTransfer::decode_vec(&msg.value).unwrap().token.denom)
+            .collect::<Vec<_>>();
+
+        assert_eq!(denoms, ["stake", "stake", "samoleans", "samoleans"]);
+    }
+
+    #[test]
+    fn validate_coins() {
+        assert!(opts().validate_coins().is_ok());
+
+        let opts_with = |coins| TransferOptions { coins, ..opts() };
+
+        assert!(matches!(
+            opts_with(vec![]).validate_coins().unwrap_err().detail(),
+            TransferErrorDetail::NoCoins(_)
+        ));
+
+        assert!(opts_with(vec![coin("stake", 100), coin("stake", 100)])
+            .validate_coins()
+            .is_ok());
+
+        assert!(matches!(
+            opts_with(vec![coin("stake", 100), coin("samoleans", 25), coin("stake", 50)])
+                .validate_coins()
+                .unwrap_err()
+                .detail(),
+            TransferErrorDetail::ConflictingCoins(e) if e.denom == "stake"
+        ));
+    }
+
+    #[test]
+    fn validate_receiver() {
+        let mut chain_config = get_basic_chain_config("chain_B");
+        chain_config.account_prefix = "cosmos".to_string();
+
+        let opts_with = |receiver: Option<&str>| TransferOptions {
+            receiver: receiver.map(ToString::to_string),
+            ..opts()
+        };
+
+        let receiver = "cosmos1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5lzv7xu";
+
+        assert!(opts_with(None).validate_receiver(&chain_config).is_ok());
+        assert!(opts_with(Some(receiver))
+            .validate_receiver(&chain_config)
+            .is_ok());
+
+        // A typo breaks the checksum
+        let mistyped = receiver.replace("xu", "xv");
+        assert!(matches!(
+            opts_with(Some(&mistyped))
+                .validate_receiver(&chain_config)
+                .unwrap_err()
+                .detail(),
+            TransferErrorDetail::InvalidReceiver(_)
+        ));
+
+        // Addresses of other schemes, e.g. the hex addresses of Ethermint chains
+        for other in ["0x8A0fD8D3a1B3F6C0e0B9B2cbD3E53dbD7a5bB4F2", "receiver"] {
+            assert!(matches!(
+                opts_with(Some(other))
+                    .validate_receiver(&chain_config)
+                    .unwrap_err()
+                    .detail(),
+                TransferErrorDetail::InvalidReceiver(e) if e.receiver == other
+            ));
+        }
+
+        chain_config.account_prefix = "osmo".to_string();
+        assert!(matches!(
+            opts_with(Some(receiver))
+                .validate_receiver(&chain_config)
+                .unwrap_err()
+                .detail(),
+            TransferErrorDetail::ReceiverPrefixMismatch(e) if e.prefix == "cosmos"
+        ));
+    }
+
+    #[test]
+    fn validate_fee_granter() {
+        let mut chain_config = get_basic_chain_config("chain_A");
+        chain_config.account_prefix = "cosmos".to_string();
+
+        let opts_with = |fee_granter: Option<&str>| TransferOptions {
+            fee_granter: fee_granter.map(ToString::to_string),
+            ..opts()
+        };
+
+        let fee_granter = "cosmos1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5lzv7xu";
+
+        assert!(opts_with(None).validate_fee_granter(&chain_config).is_ok());
+        assert!(opts_with(Some(fee_granter))
+            .validate_fee_granter(&chain_config)
+            .is_ok());
+
+        let mistyped = fee_granter.replace("xu", "xv");
+        assert!(matches!(
+            opts_with(Some(&mistyped))
+                .validate_fee_granter(&chain_config)
+                .unwrap_err()
+                .detail(),
+            TransferErrorDetail::InvalidFeeGranter(e) if e.fee_granter == mistyped
+        ));
+
+        chain_config.account_prefix = "osmo".to_string();
+        assert!(matches!(
+            opts_with(Some(fee_granter))
+                .validate_fee_granter(&chain_config)
+                .unwrap_err()
+                .detail(),
+            TransferErrorDetail::FeeGranterPrefixMismatch(e) if e.prefix == "cosmos"
+        ));
+    }
+
+    fn send_packet(sequence: u64, timeout_height: u64) -> IbcEvent {
+        IbcEvent::SendPacket(SendPacket {
+            height: Height::new(0, 1).unwrap(),
+            packet: Packet {
+                sequence: Sequence::from(sequence),
+                destination_port: PortId::transfer(),
+                destination_channel: ChannelId::new(1),
+                timeout_height: TimeoutHeight::At(Height::new(0, timeout_height).unwrap()),
+                ..Packet::default()
+            },
+        })
+    }
+
+    #[test]
+    fn wait_for_acks_of_transfer() {
+        let (_, dst_chain) = chains();
+        dst_chain.set_latest(Height::new(0, 10).unwrap(), Timestamp::none());
+
+        let ack = |sequence: u64, ack: &str| {
+            dst_chain.write_acknowledgement(
+                PortId::transfer(),
+                ChannelId::new(1),
+                Sequence::from(sequence),
+                ack.as_bytes().to_vec(),
+            )
+        };
+
+        ack(1, r#"{"result":"AQ=="}"#);
+        ack(2, r#"{"error":"invalid receiver"}"#);
+        ack(3, "not an ICS-20 acknowledgement");
+
+        // Packet 4 timed out on the destination chain, packet 5 can still be received
+        let events = (1..=5)
+            .map(|sequence| send_packet(sequence, if sequence == 4 { 5 } else { 100 }))
+            .collect::<Vec<_>>();
+
+        // A failure to query the destination chain leaves the packets pending
+        dst_chain.inject_failure("query_written_acks", Error::channel_send);
+        let outcomes = wait_for_transfer_acks(&dst_chain, &events, Duration::ZERO);
+        assert!(outcomes.iter().all(|o| o.status == PacketStatus::Pending));
+
+        dst_chain.clear_failure("query_written_acks");
+        let outcomes = wait_for_transfer_acks(&dst_chain, &events, Duration::ZERO);
+
+        let statuses = outcomes
+            .into_iter()
+            .map(|outcome| (u64::from(outcome.sequence), outcome.status))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            statuses,
+            vec![
+                (1, PacketStatus::Success),
+                (
+                    2,
+                    PacketStatus::Error {
+                        error: "invalid receiver".to_string()
+                    }
+                ),
+                (
+                    3,
+                    PacketStatus::Error {
+                        error: "unexpected acknowledgement: not an ICS-20 acknowledgement"
+                            .to_string()
+                    }
+                ),
+                (4, PacketStatus::TimedOut),
+                (5, PacketStatus::Pending),
+            ]
+        );
+    }
+
+    #[test]
+    fn transfer_with_absolute_timeout_timestamp() {
+        let (src_chain, dst_chain) = chains();
+
+        let timeout_timestamp = Timestamp::from_nanoseconds(1_725_192_000_000_000_000).unwrap();
+
+        let opts = TransferOptions {
+            timeout_duration: Duration::ZERO,
+            timeout_timestamp: Some(timeout_timestamp),
+            ..opts()
+        };
+
+        build_and_send_transfer_messages(&src_chain, &dst_chain, &opts).unwrap();
+
+        for msg in src_chain.sent_msgs() {
+            assert_eq!(
+                MsgTransfer::decode_vec(&msg.value)
+                    .unwrap()
+                    .timeout_timestamp,
+                timeout_timestamp
+            );
+        }
+    }
+
+    #[test]
+    fn transient_query_failures() {
+        let (src_chain, _) = chains();
+        let check = || {
+            check_transfer_path(
+                &src_chain,
+                &ChainId::from_string("chain_B"),
+                &PortId::transfer(),
+                &ChannelId::new(0),
+            )
+        };
+
+        src_chain.inject_failure("query_channel", || {
+            Error::grpc_status(tonic::Status::unavailable("connection reset by peer"))
+        });
+        assert!(check().unwrap_err().is_transient());
+
+        // A channel which does not exist is not retried
+        src_chain.inject_failure("query_channel", || {
+            Error::grpc_status(tonic::Status::not_found("channel not found"))
+        });
+        assert!(!check().unwrap_err().is_transient());
+    }
+}
@@ -47,7 +47,13 @@ define_error! {
             },
 
         TendermintOnly
-            |_| { "only Tendermint clients can be upgraded" }
+            |_| { "only Tendermint clients can be upgraded" },
+
+        MissingTxResponse
+            { chain_id: ChainId }
+            |e| {
+                format!("no response was received for the upgrade proposal submitted to chain {0}", e.chain_id)
+            },
     }
 }
 
@@ -151,7 +157,10 @@ pub fn build_and_send_ibc_upgrade_proposal(
         .send_messages_and_wait_check_tx(TrackedMsgs::new_single(any_msg, "upgrade"))
         .map_err(|e| UpgradeChainError::submit(dst_chain.id(), e))?;
 
-    Ok(responses[0].hash)
+    responses
+        .first()
+        .map(|sent| sent.response.hash)
+        .ok_or_else(|| UpgradeChainError::missing_tx_response(dst_chain.id()))
 }
 
 enum Proposal {
@@ -8,3 +8,4 @@ pub mod queue;
 pub mod retry;
 pub mod stream;
 pub mod task;
+pub mod unblock;
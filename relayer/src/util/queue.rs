@@ -31,6 +31,17 @@ impl<T> Queue<T> {
         self.0.acquire_write().push_front(val)
     }
 
+    /// Inserts the given element ahead of the first element of the queue for which
+    /// `pred` holds, or at the back of the queue if there is no such element.
+    pub fn insert_before(&self, val: T, pred: impl Fn(&T) -> bool) {
+        let mut queue = self.0.acquire_write();
+
+        match queue.iter().position(pred) {
+            Some(index) => queue.insert(index, val),
+            None => queue.push_back(val),
+        }
+    }
+
     pub fn len(&self) -> usize {
         self.0.acquire_read().len()
     }
@@ -50,6 +61,23 @@ impl<T> Queue<T> {
     pub fn take(&self) -> VecDeque<T> {
         self.0.acquire_write().drain(..).collect()
     }
+
+    /// Puts the given elements at the front of the queue,
+    /// ahead of the elements already in the queue.
+    pub fn prepend(&self, front: VecDeque<T>) {
+        self.replace_front(0, front)
+    }
+
+    /// Replaces the first `len` elements of the queue with the given ones,
+    /// keeping the elements pushed to the queue after them.
+    pub fn replace_front(&self, len: usize, front: VecDeque<T>) {
+        let mut queue = self.0.acquire_write();
+        let at = len.min(queue.len());
+        let back = queue.split_off(at);
+
+        *queue = front;
+        queue.extend(back);
+    }
 }
 
 impl<T: Clone> Queue<T> {
@@ -69,3 +97,39 @@ impl<T> From<VecDeque<T>> for Queue<T> {
         Queue(Arc::new(RwLock::new(deque)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::VecDeque;
+
+    use super::Queue;
+
+    #[test]
+    fn replace_front_keeps_pushed_elements() {
+        let queue = Queue::from(VecDeque::from(vec![1, 2, 3]));
+
+        // Elements pushed while the first three are being processed
+        queue.push_back(4);
+        queue.push_back(5);
+
+        queue.replace_front(3, VecDeque::from(vec![2]));
+        assert_eq!(queue.take(), VecDeque::from(vec![2, 4, 5]));
+    }
+
+    #[test]
+    fn insert_before_first_match() {
+        let queue = Queue::from(VecDeque::from(vec![1, 3, 10, 5, 20]));
+
+        queue.insert_before(4, |&n| n >= 10);
+        queue.insert_before(30, |&n| n >= 100);
+        assert_eq!(queue.take(), VecDeque::from(vec![1, 3, 4, 10, 5, 20, 30]));
+    }
+
+    #[test]
+    fn prepend_puts_elements_ahead() {
+        let queue = Queue::from(VecDeque::from(vec![3, 4]));
+
+        queue.prepend(VecDeque::from(vec![1, 2]));
+        assert_eq!(queue.take(), VecDeque::from(vec![1, 2, 3, 4]));
+    }
+}
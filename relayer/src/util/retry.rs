@@ -1,4 +1,8 @@
+use core::fmt::Display;
 use core::time::Duration;
+use std::thread;
+
+use tracing::warn;
 
 pub use retry::{
     delay::{Fibonacci, Fixed},
@@ -16,6 +20,61 @@ pub fn retry_count<E>(err: &RetryError<E>) -> u64 {
     }
 }
 
+/// Runs a handshake `step` which sends messages with proofs to a chain and, if the chain
+/// rejects them because its client of the counterparty chain is stale, ie. `is_stale_client`
+/// holds for the error, runs `update_client` with that error and then the step once more,
+/// with fresh proofs.
+///
+/// When the error tells the height of the proofs, the connection and channel handshakes
+/// update the client to exactly that height, from the highest consensus state below it,
+/// which must still be within the trusting period. This fills in the consensus states
+/// missing for proofs built at older heights, e.g. after restoring the relayer from an old
+/// snapshot. Otherwise, or if that update fails, they update the client to the latest
+/// height of the counterparty chain.
+pub fn retry_after_client_update<T, E>(
+    mut step: impl FnMut() -> Result<T, E>,
+    is_stale_client: impl Fn(&E) -> bool,
+    update_client: impl FnOnce(&E) -> Result<(), E>,
+) -> Result<T, E> {
+    match step() {
+        Err(e) if is_stale_client(&e) => {
+            update_client(&e)?;
+            step()
+        }
+        result => result,
+    }
+}
+
+/// Runs a read-only `query` and, as long as it fails with an error for which `is_transient`
+/// holds, retries it up to `max_retries` times, waiting `initial_delay` before the first
+/// retry and twice as long before each of the next ones. With no retries, the query is
+/// only run once.
+pub fn retry_transient<T, E: Display>(
+    max_retries: usize,
+    initial_delay: Duration,
+    mut query: impl FnMut() -> Result<T, E>,
+    is_transient: impl Fn(&E) -> bool,
+) -> Result<T, E> {
+    let mut delay = initial_delay;
+
+    for retry in 1..=max_retries {
+        match query() {
+            Err(e) if is_transient(&e) => {
+                warn!(
+                    "query failed, retrying in {:?} ({}/{}): {}",
+                    delay, retry, max_retries, e
+                );
+
+                thread::sleep(delay);
+                delay = delay.saturating_mul(2);
+            }
+            result => return result,
+        }
+    }
+
+    query()
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct ConstantGrowth {
     delay: Duration,
@@ -87,11 +146,144 @@ pub fn clamp_total(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use core::cell::Cell;
     use test_log::test;
 
     const CONST_STRATEGY: ConstantGrowth =
         ConstantGrowth::new(Duration::from_secs(1), Duration::from_millis(500));
 
+    #[test]
+    fn retry_transient_errors() {
+        let mut calls = 0;
+        let result = retry_transient(
+            3,
+            Duration::ZERO,
+            || {
+                calls += 1;
+                if calls < 3 {
+                    Err("timeout")
+                } else {
+                    Ok(calls)
+                }
+            },
+            |e| *e == "timeout",
+        );
+        assert_eq!(result, Ok(3));
+
+        // Retries are bounded
+        let mut calls = 0;
+        let result: Result<(), _> = retry_transient(
+            2,
+            Duration::ZERO,
+            || {
+                calls += 1;
+                Err("timeout")
+            },
+            |e| *e == "timeout",
+        );
+        assert_eq!(result, Err("timeout"));
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn no_retry_of_definitive_errors() {
+        let mut calls = 0;
+        let result: Result<(), _> = retry_transient(
+            3,
+            Duration::ZERO,
+            || {
+                calls += 1;
+                Err("channel not found")
+            },
+            |e| *e == "timeout",
+        );
+        assert_eq!(result, Err("channel not found"));
+        assert_eq!(calls, 1);
+
+        // Without retries, the query is run once
+        let mut calls = 0;
+        let result: Result<(), _> = retry_transient(
+            0,
+            Duration::ZERO,
+            || {
+                calls += 1;
+                Err("timeout")
+            },
+            |e| *e == "timeout",
+        );
+        assert_eq!(result, Err("timeout"));
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn retry_once_after_client_update() {
+        let updated = Cell::new(false);
+        let mut steps = 0;
+        let result = retry_after_client_update(
+            || {
+                steps += 1;
+                if updated.get() {
+                    Ok(steps)
+                } else {
+                    Err("consensus state not found")
+                }
+            },
+            |e| *e == "consensus state not found",
+            |e| {
+                assert_eq!(*e, "consensus state not found");
+                updated.set(true);
+                Ok(())
+            },
+        );
+        assert_eq!(result, Ok(2));
+
+        // The step is not retried a second time if the client is still stale
+        let mut steps = 0;
+        let result: Result<(), _> = retry_after_client_update(
+            || {
+                steps += 1;
+                Err("consensus state not found")
+            },
+            |e| *e == "consensus state not found",
+            |_| Ok(()),
+        );
+        assert_eq!(result, Err("consensus state not found"));
+        assert_eq!(steps, 2);
+    }
+
+    #[test]
+    fn no_client_update_on_other_errors() {
+        let updated = Cell::new(false);
+        let mut steps = 0;
+        let result: Result<(), _> = retry_after_client_update(
+            || {
+                steps += 1;
+                Err("connection not found")
+            },
+            |e| *e == "consensus state not found",
+            |_| {
+                updated.set(true);
+                Ok(())
+            },
+        );
+        assert_eq!(result, Err("connection not found"));
+        assert_eq!(steps, 1);
+        assert!(!updated.get());
+
+        // A failed client update is reported instead of retrying the step
+        let mut steps = 0;
+        let result: Result<(), _> = retry_after_client_update(
+            || {
+                steps += 1;
+                Err("consensus state not found")
+            },
+            |e| *e == "consensus state not found",
+            |_| Err("client update failed"),
+        );
+        assert_eq!(result, Err("client update failed"));
+        assert_eq!(steps, 1);
+    }
+
     #[test]
     fn const_growth_no_clamp() {
         let delays = CONST_STRATEGY.take(10).collect::<Vec<_>>();
@@ -2,6 +2,8 @@ use core::fmt::Display;
 use core::mem;
 use core::time::Duration;
 use crossbeam_channel::{bounded, Sender};
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::{Arc, RwLock};
 use std::thread;
 use tracing::{debug, error, warn};
@@ -22,6 +24,7 @@ use crate::util::lock::LockExt;
 pub struct TaskHandle {
     shutdown_sender: Sender<()>,
     stopped: Arc<RwLock<bool>>,
+    panicked: Arc<RwLock<bool>>,
     join_handle: DropJoinHandle,
 }
 
@@ -78,6 +81,10 @@ pub enum Next {
    instruction is sent, the task runner will stop calling the step runner
    and abort the background task.
 
+   If the step runner panics, the panic is caught and logged, and the
+   background task is aborted, which the [`TaskHandle`] reports through
+   [`has_panicked`](TaskHandle::has_panicked).
+
    If the step runner is receiving commands from other
    [channels](crossbeam_channel::Receiver), it should use the
    [`try_recv`](crossbeam_channel::Receiver::try_recv) function
@@ -95,6 +102,9 @@ pub fn spawn_background_task<E: Display>(
     let stopped = Arc::new(RwLock::new(false));
     let write_stopped = stopped.clone();
 
+    let panicked = Arc::new(RwLock::new(false));
+    let write_panicked = panicked.clone();
+
     let (shutdown_sender, receiver) = bounded(1);
 
     let join_handle = thread::spawn(move || {
@@ -104,19 +114,27 @@ pub fn spawn_background_task<E: Display>(
                 Ok(()) => {
                     break;
                 }
-                _ => match step_runner() {
-                    Ok(Next::Continue) => {}
-                    Ok(Next::Abort) => {
+                _ => match panic::catch_unwind(AssertUnwindSafe(&mut step_runner)) {
+                    Ok(Ok(Next::Continue)) => {}
+                    Ok(Ok(Next::Abort)) => {
                         debug!("aborting task");
                         break;
                     }
-                    Err(TaskError::Ignore(e)) => {
+                    Ok(Err(TaskError::Ignore(e))) => {
                         warn!("task encountered ignorable error: {}", e);
                     }
-                    Err(TaskError::Fatal(e)) => {
+                    Ok(Err(TaskError::Fatal(e))) => {
                         error!("task aborting after encountering fatal error: {}", e);
                         break;
                     }
+                    Err(payload) => {
+                        error!(
+                            "task aborting after panicking: {}",
+                            panic_message(&*payload)
+                        );
+                        *write_panicked.acquire_write() = true;
+                        break;
+                    }
                 },
             }
             if let Some(interval) = interval_pause {
@@ -132,10 +150,22 @@ pub fn spawn_background_task<E: Display>(
     TaskHandle {
         shutdown_sender,
         stopped,
+        panicked,
         join_handle: DropJoinHandle(Some(join_handle)),
     }
 }
 
+/// The message of a panic, if it was given one.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic"
+    }
+}
+
 impl TaskHandle {
     /**
        Wait for the background task to terminate.
@@ -179,6 +209,14 @@ impl TaskHandle {
     pub fn is_stopped(&self) -> bool {
         *self.stopped.acquire_read()
     }
+
+    /**
+       Check whether a background task has been stopped because its
+       step runner panicked.
+    */
+    pub fn has_panicked(&self) -> bool {
+        *self.panicked.acquire_read()
+    }
 }
 
 impl Drop for DropJoinHandle {
@@ -194,3 +232,56 @@ impl Drop for TaskHandle {
         let _ = self.shutdown_sender.send(());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{spawn_background_task, Next, TaskError, TaskHandle};
+
+    use core::convert::Infallible;
+    use core::time::Duration;
+    use std::thread;
+
+    fn wait_until_stopped(handle: &TaskHandle) {
+        for _ in 0..100 {
+            if handle.is_stopped() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        panic!("the task did not stop");
+    }
+
+    #[test]
+    fn panicking_task_is_stopped() {
+        let mut steps = 0;
+        let handle = spawn_background_task(
+            tracing::Span::none(),
+            None,
+            move || -> Result<Next, TaskError<Infallible>> {
+                steps += 1;
+                if steps == 3 {
+                    panic!("step {} failed", steps);
+                }
+                Ok(Next::Continue)
+            },
+        );
+
+        wait_until_stopped(&handle);
+        assert!(handle.has_panicked());
+
+        // The panic does not propagate to the holder of the handle
+        handle.join();
+    }
+
+    #[test]
+    fn aborted_task_has_not_panicked() {
+        let handle = spawn_background_task(
+            tracing::Span::none(),
+            None,
+            || -> Result<Next, TaskError<Infallible>> { Ok(Next::Abort) },
+        );
+
+        wait_until_stopped(&handle);
+        assert!(!handle.has_panicked());
+    }
+}
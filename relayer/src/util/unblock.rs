@@ -0,0 +1,29 @@
+//! Utility function to run blocking code from an async context
+
+use tokio::sync::oneshot;
+
+/// Runs the blocking function `f` on a dedicated thread, and waits for its result
+/// without blocking the executor polling the returned future.
+///
+/// Unlike `tokio::task::spawn_blocking`, this does not require the future to be polled
+/// from within a tokio runtime, nor does it exhaust the blocking thread pool of the
+/// runtime when `f` waits on a chain for a long time.
+///
+/// # Panics
+///
+/// The returned future panics if `f` panics.
+pub async fn unblock<T, F>(f: F) -> T
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let (sender, receiver) = oneshot::channel();
+
+    std::thread::spawn(move || {
+        let _ = sender.send(f());
+    });
+
+    receiver
+        .await
+        .expect("the blocking function run by `unblock` panicked")
+}
@@ -1,16 +1,21 @@
+use alloc::collections::BTreeSet;
 use alloc::sync::Arc;
 use core::fmt;
 use ibc::core::ics04_channel::channel::Order;
+use ibc::core::ics24_host::identifier::ChainId;
 use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
-use tracing::error;
+use tracing::{error, warn};
 
+use crate::channel::version::ChannelVersionCache;
 use crate::foreign_client::ForeignClient;
-use crate::link::{Link, LinkParameters, Resubmit};
+use crate::link::operational_data::OperationalDataTarget;
+use crate::link::{Link, LinkParameters, Resubmit, SubmittedTxs, VerifyProofs};
+use crate::supervisor::startup::{ClearPermits, ProgressSender, StartupProgress};
+use crate::worker::latency::PacketSendTimes;
 use crate::{
     chain::handle::{ChainHandle, ChainHandlePair},
     config::Config,
-    object::Object,
+    object::{Object, Packet},
 };
 
 pub mod retry_strategy;
@@ -30,6 +35,7 @@ pub use map::WorkerMap;
 pub mod channel;
 pub mod client;
 pub mod connection;
+pub mod latency;
 pub mod packet;
 pub mod wallet;
 
@@ -58,8 +64,17 @@ pub fn spawn_worker_tasks<ChainA: ChainHandle, ChainB: ChainHandle>(
     id: WorkerId,
     object: Object,
     config: &Config,
+    startup_progress: Option<&ProgressSender>,
+    clear_permits: &ClearPermits,
+    version_cache: &mut ChannelVersionCache,
+    send_times: &PacketSendTimes,
+    submitted_txs: &SubmittedTxs,
+    paused: bool,
+    halted_chains: &BTreeSet<ChainId>,
 ) -> WorkerHandle {
     let mut task_handles = Vec::new();
+    let mut deferred_timeouts = None;
+    let mut worker_halted_chains = BTreeSet::new();
 
     let (cmd_tx, data) = match &object {
         Object::Client(client) => {
@@ -67,7 +82,10 @@ pub fn spawn_worker_tasks<ChainA: ChainHandle, ChainB: ChainHandle>(
 
             let (mut refresh, mut misbehaviour) = (false, false);
 
-            let refresh_task = client::spawn_refresh_client(client.clone());
+            let refresh_task = client::spawn_refresh_client(
+                client.clone(),
+                config.mode.clients.halt_on_short_unbonding,
+            );
             if let Some(refresh_task) = refresh_task {
                 task_handles.push(refresh_task);
                 refresh = true;
@@ -110,6 +128,11 @@ pub fn spawn_worker_tasks<ChainA: ChainHandle, ChainB: ChainHandle>(
         }
         Object::Packet(path) => {
             let packets_config = config.mode.packets;
+            let relay_mode = config.packet_relay_mode(
+                &path.src_chain_id,
+                &path.src_port_id,
+                &path.src_channel_id,
+            );
             let link_res = Link::new_from_opts(
                 chains.a.clone(),
                 chains.b,
@@ -118,31 +141,111 @@ pub fn spawn_worker_tasks<ChainA: ChainHandle, ChainB: ChainHandle>(
                     src_channel_id: path.src_channel_id.clone(),
                 },
                 packets_config.tx_confirmation,
-            );
+            )
+            .map(|link| {
+                link.with_relay_mode(relay_mode)
+                    .with_proof_verification(VerifyProofs {
+                        src: config.verify_proofs_locally(&path.src_chain_id),
+                        dst: config.verify_proofs_locally(&path.dst_chain_id),
+                    })
+                    .with_submitted_txs(submitted_txs.clone())
+            });
 
             match link_res {
                 Ok(link) => {
-                    let channel_ordering = link.a_to_b.channel().ordering;
-                    let should_clear_on_start =
-                        packets_config.clear_on_start || channel_ordering == Order::Ordered;
-
-                    let (cmd_tx, cmd_rx) = crossbeam_channel::unbounded();
-                    let link = Arc::new(Mutex::new(link));
-                    let resubmit = Resubmit::from_clear_interval(packets_config.clear_interval);
-
-                    let packet_task = packet::spawn_packet_cmd_worker(
-                        cmd_rx,
-                        link.clone(),
-                        should_clear_on_start,
-                        packets_config.clear_interval,
-                        path.clone(),
-                    );
-                    task_handles.push(packet_task);
-
-                    let link_task = packet::spawn_packet_worker(path.clone(), link, resubmit);
-                    task_handles.push(link_task);
-
-                    (Some(cmd_tx), None)
+                    let version_mismatch = check_channel_versions(&link, path, version_cache);
+                    let data = Some(WorkerData::Packet { version_mismatch });
+
+                    if version_mismatch && !packets_config.ignore_version_mismatch {
+                        error!(
+                            "not relaying packets on {}, as the versions of the two ends of the \
+                            channel differ; set `ignore_version_mismatch = true` in the \
+                            `[mode.packets]` section of the configuration to relay them anyway",
+                            path.short_name()
+                        );
+
+                        (None, data)
+                    } else {
+                        let ordered = link.a_to_b.channel().ordering == Order::Ordered;
+                        let should_clear_on_start = packets_config.clear_on_start || ordered;
+
+                        let (cmd_tx, cmd_rx) = crossbeam_channel::unbounded();
+                        deferred_timeouts = Some(link.a_to_b.deferred_timeouts().clone());
+                        let link = Arc::new(link);
+                        let resubmit = Resubmit::from_clear_interval(packets_config.clear_interval);
+
+                        // Only report the progress of the clearing on start if it will happen
+                        let startup_progress =
+                            startup_progress.filter(|_| should_clear_on_start).cloned();
+
+                        if let Some(sender) = &startup_progress {
+                            let _ = sender.send(StartupProgress::Pending { path: path.clone() });
+                        }
+
+                        // Shared by the tasks below, so that pausing the worker pauses all of them
+                        let pause_flag = packet::PauseFlag::new(paused);
+                        let wakers = packet::ScheduleWakers::default();
+
+                        // Nothing is relayed to the chains which are halted until they resume
+                        let halted = packet::HaltedChains::new(
+                            halted_chains.contains(&path.src_chain_id),
+                            halted_chains.contains(&path.dst_chain_id),
+                        );
+                        worker_halted_chains = [&path.src_chain_id, &path.dst_chain_id]
+                            .into_iter()
+                            .filter(|chain_id| halted_chains.contains(*chain_id))
+                            .cloned()
+                            .collect();
+
+                        // The packets of an ordered channel must be relayed in sequence, so the
+                        // packets pending on start are cleared by the command worker ahead of the
+                        // incoming events. Otherwise, they are cleared in the background while
+                        // the incoming events are relayed.
+                        let packet_task = packet::spawn_packet_cmd_worker(
+                            cmd_rx,
+                            link.clone(),
+                            ordered,
+                            packets_config.clear_interval,
+                            path.clone(),
+                            startup_progress.clone().filter(|_| ordered),
+                            pause_flag.clone(),
+                            halted.clone(),
+                            // Only track the end-to-end latency of packets if it is exported
+                            config.telemetry.enabled.then(|| send_times.clone()),
+                            wakers.clone(),
+                        );
+                        task_handles.push(packet_task);
+
+                        if should_clear_on_start && !ordered {
+                            let clear_task = packet::spawn_clear_on_start_worker(
+                                link.clone(),
+                                path.clone(),
+                                clear_permits.clone(),
+                                startup_progress,
+                                pause_flag.clone(),
+                            );
+                            task_handles.push(clear_task);
+                        }
+
+                        // Relay to the source and destination chains on separate tasks
+                        for target in [
+                            OperationalDataTarget::Destination,
+                            OperationalDataTarget::Source,
+                        ] {
+                            let link_task = packet::spawn_packet_worker(
+                                path.clone(),
+                                link.clone(),
+                                target,
+                                resubmit,
+                                pause_flag.clone(),
+                                halted.clone(),
+                                wakers.clone(),
+                            );
+                            task_handles.push(link_task);
+                        }
+
+                        (Some(cmd_tx), data)
+                    }
                 }
                 Err(e) => {
                     error!("error initializing link object for packet worker: {}", e);
@@ -161,5 +264,65 @@ pub fn spawn_worker_tasks<ChainA: ChainHandle, ChainB: ChainHandle>(
         }
     };
 
-    WorkerHandle::new(id, object, data, cmd_tx, task_handles)
+    let handle = WorkerHandle::new(id, object, data, cmd_tx, task_handles, paused)
+        .with_halted_chains(worker_halted_chains);
+
+    match deferred_timeouts {
+        Some(deferred_timeouts) => handle.with_deferred_timeouts(deferred_timeouts),
+        None => handle,
+    }
+}
+
+/// Whether the versions of the two ends of the channel relayed by the given link differ,
+/// as fetched from the chains unless they are cached. If they cannot be fetched, the link
+/// is assumed to be fine, as they were just queried successfully to build it.
+fn check_channel_versions<ChainA: ChainHandle, ChainB: ChainHandle>(
+    link: &Link<ChainA, ChainB>,
+    path: &Packet,
+    version_cache: &mut ChannelVersionCache,
+) -> bool {
+    let src = (
+        path.src_chain_id.clone(),
+        path.src_port_id.clone(),
+        path.src_channel_id.clone(),
+    );
+
+    if let Some(versions) = version_cache.get(&src) {
+        return versions.is_mismatch();
+    }
+
+    let versions = match link.a_to_b.channel_versions() {
+        Ok(versions) => versions,
+        Err(e) => {
+            warn!(
+                "failed to fetch the versions of the channel {}: {}",
+                path.short_name(),
+                e
+            );
+
+            return false;
+        }
+    };
+
+    if versions.is_mismatch() {
+        error!(
+            "the two ends of the channel {} have different versions: '{}' on {} and '{}' on {}",
+            path.short_name(),
+            versions.src,
+            path.src_chain_id,
+            versions.dst,
+            path.dst_chain_id
+        );
+    }
+
+    let dst = (
+        path.dst_chain_id.clone(),
+        link.a_to_b.dst_port_id().clone(),
+        link.a_to_b.dst_channel_id().clone(),
+    );
+
+    let version_mismatch = versions.is_mismatch();
+    version_cache.insert(src, dst, versions);
+
+    version_mismatch
 }
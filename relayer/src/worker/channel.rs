@@ -72,7 +72,10 @@ pub fn spawn_channel_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
                     }
 
                     // nothing to do
-                    WorkerCmd::ClearPendingPackets => Ok(Next::Continue),
+                    WorkerCmd::ClearPendingPackets
+                    | WorkerCmd::Pause
+                    | WorkerCmd::Resume
+                    | WorkerCmd::ChainHalted { .. } => Ok(Next::Continue),
                 }
             } else {
                 Ok(Next::Continue)
@@ -1,21 +1,29 @@
 use core::convert::Infallible;
 use core::time::Duration;
 use crossbeam_channel::Receiver;
-use tracing::{debug, span, trace, warn};
+use std::time::Instant;
+use tracing::{debug, error, span, trace, warn};
 
 use ibc::events::IbcEvent;
 
 use crate::util::task::{spawn_background_task, Next, TaskError, TaskHandle};
 use crate::{
     chain::handle::ChainHandle,
-    foreign_client::{ForeignClient, HasExpiredOrFrozenError, MisbehaviourResults},
+    foreign_client::{
+        ForeignClient, HasExpiredOrFrozenError, MisbehaviourResults, TrustingPeriodCheck,
+    },
     telemetry,
 };
 
 use super::WorkerCmd;
 
+/// How often the trusting period of a client is checked against
+/// the unbonding period of the chain it tracks.
+const TRUSTING_PERIOD_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
 pub fn spawn_refresh_client<ChainA: ChainHandle, ChainB: ChainHandle>(
     mut client: ForeignClient<ChainA, ChainB>,
+    halt_on_short_unbonding: bool,
 ) -> Option<TaskHandle> {
     if client.is_expired_or_frozen() {
         warn!(
@@ -24,6 +32,8 @@ pub fn spawn_refresh_client<ChainA: ChainHandle, ChainB: ChainHandle>(
         );
         None
     } else {
+        let mut last_trusting_period_check: Option<Instant> = None;
+
         Some(spawn_background_task(
             span!(
                 tracing::Level::ERROR,
@@ -34,6 +44,17 @@ pub fn spawn_refresh_client<ChainA: ChainHandle, ChainB: ChainHandle>(
             ),
             Some(Duration::from_secs(1)),
             move || {
+                let check_due = last_trusting_period_check
+                    .map_or(true, |at| at.elapsed() >= TRUSTING_PERIOD_CHECK_INTERVAL);
+
+                if check_due {
+                    last_trusting_period_check = Some(Instant::now());
+
+                    if check_trusting_period(&client, halt_on_short_unbonding) {
+                        return Ok(Next::Abort);
+                    }
+                }
+
                 let res = client.refresh().map_err(|e| {
                     if e.is_expired_or_frozen_error() {
                         TaskError::Fatal(e)
@@ -52,6 +73,54 @@ pub fn spawn_refresh_client<ChainA: ChainHandle, ChainB: ChainHandle>(
     }
 }
 
+/// Checks that the trusting period of the client is still shorter than the
+/// unbonding period of the chain it tracks, and logs a critical warning if not.
+///
+/// Returns whether the refresh of the client should be halted.
+fn check_trusting_period<ChainA: ChainHandle, ChainB: ChainHandle>(
+    client: &ForeignClient<ChainA, ChainB>,
+    halt_on_short_unbonding: bool,
+) -> bool {
+    let check = match client.check_trusting_period() {
+        Ok(check) => check,
+        Err(e) => {
+            warn!(
+                "failed to check the trusting period of the client against the unbonding period of chain {}: {}",
+                client.src_chain.id(),
+                e
+            );
+
+            return false;
+        }
+    };
+
+    if let TrustingPeriodCheck::ExceedsUnbondingPeriod {
+        trusting_period,
+        unbonding_period,
+    } = check
+    {
+        error!(
+            trusting_period = ?trusting_period,
+            unbonding_period = ?unbonding_period,
+            "CRITICAL: the trusting period of client {} is no longer shorter than the unbonding period of chain {}, \
+             so its updates will fail verification; consider creating a replacement client with `hermes create client`",
+            client.id,
+            client.src_chain.id(),
+        );
+    }
+
+    let halt = check.should_halt_refresh(halt_on_short_unbonding);
+
+    if halt {
+        warn!(
+            "halting the refresh of client {} to avoid wasting fees",
+            client.id
+        );
+    }
+
+    halt
+}
+
 pub fn detect_misbehavior_task<ChainA: ChainHandle, ChainB: ChainHandle>(
     receiver: Receiver<WorkerCmd>,
     client: ForeignClient<ChainB, ChainA>,
@@ -123,7 +192,10 @@ pub fn detect_misbehavior_task<ChainA: ChainHandle, ChainB: ChainHandle>(
                     }
 
                     WorkerCmd::NewBlock { .. } => {}
-                    WorkerCmd::ClearPendingPackets => {}
+                    WorkerCmd::ClearPendingPackets
+                    | WorkerCmd::Pause
+                    | WorkerCmd::Resume
+                    | WorkerCmd::ChainHalted { .. } => {}
                 }
             }
 
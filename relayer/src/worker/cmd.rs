@@ -1,6 +1,9 @@
 use core::fmt;
 
-use ibc::{core::ics02_client::events::NewBlock, Height};
+use ibc::{
+    core::{ics02_client::events::NewBlock, ics24_host::identifier::ChainId},
+    Height,
+};
 
 use crate::event::monitor::EventBatch;
 
@@ -15,6 +18,18 @@ pub enum WorkerCmd {
 
     /// Trigger a pending packets clear
     ClearPendingPackets,
+
+    /// Stop submitting transactions, while still scheduling
+    /// the packets of the incoming events, until resumed
+    Pause,
+
+    /// Resume submitting transactions, starting with a pending packets clear
+    Resume,
+
+    /// The given chain has halted, or resumed: stop submitting transactions to it,
+    /// while still relaying to the other chain, until it resumes, at which point
+    /// the pending packets are cleared
+    ChainHalted { chain_id: ChainId, halted: bool },
 }
 
 impl fmt::Display for WorkerCmd {
@@ -31,6 +46,11 @@ impl fmt::Display for WorkerCmd {
                 write!(f, "NewBlock({}, {:?})", height, new_block)
             }
             WorkerCmd::ClearPendingPackets => write!(f, "CleaPendingPackets"),
+            WorkerCmd::Pause => write!(f, "Pause"),
+            WorkerCmd::Resume => write!(f, "Resume"),
+            WorkerCmd::ChainHalted { chain_id, halted } => {
+                write!(f, "ChainHalted({}, {})", chain_id, halted)
+            }
         }
     }
 }
@@ -75,7 +75,10 @@ pub fn spawn_connection_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
                     }
 
                     // nothing to do
-                    WorkerCmd::ClearPendingPackets => Ok(Next::Continue),
+                    WorkerCmd::ClearPendingPackets
+                    | WorkerCmd::Pause
+                    | WorkerCmd::Resume
+                    | WorkerCmd::ChainHalted { .. } => Ok(Next::Continue),
                 }
             } else {
                 Ok(Next::Continue)
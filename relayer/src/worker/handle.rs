@@ -1,3 +1,4 @@
+use alloc::collections::BTreeSet;
 use core::fmt;
 use core::mem;
 
@@ -13,6 +14,7 @@ use ibc::{
 };
 
 use crate::chain::tracking::TrackingId;
+use crate::link::DeferredTimeouts;
 use crate::util::lock::{LockExt, RwArc};
 use crate::util::task::TaskHandle;
 use crate::{event::monitor::EventBatch, object::Object};
@@ -23,6 +25,7 @@ use super::{WorkerCmd, WorkerId};
 #[serde(tag = "type")]
 pub enum WorkerData {
     Client { misbehaviour: bool, refresh: bool },
+    Packet { version_mismatch: bool },
 }
 
 pub struct WorkerHandle {
@@ -31,6 +34,9 @@ pub struct WorkerHandle {
     data: Option<WorkerData>,
     tx: RwArc<Option<Sender<WorkerCmd>>>,
     task_handles: Vec<TaskHandle>,
+    paused: bool,
+    halted_chains: BTreeSet<ChainId>,
+    deferred_timeouts: Option<DeferredTimeouts>,
 }
 
 impl WorkerHandle {
@@ -40,6 +46,7 @@ impl WorkerHandle {
         data: Option<WorkerData>,
         tx: Option<Sender<WorkerCmd>>,
         task_handles: Vec<TaskHandle>,
+        paused: bool,
     ) -> Self {
         Self {
             id,
@@ -47,9 +54,24 @@ impl WorkerHandle {
             data,
             tx: <RwArc<_>>::new_lock(tx),
             task_handles,
+            paused,
+            halted_chains: BTreeSet::new(),
+            deferred_timeouts: None,
         }
     }
 
+    /// This handle, for a packet worker spawned while the given chains are halted.
+    pub fn with_halted_chains(mut self, halted_chains: BTreeSet<ChainId>) -> Self {
+        self.halted_chains = halted_chains;
+        self
+    }
+
+    /// This handle, reporting the timeouts deferred by the packet worker.
+    pub fn with_deferred_timeouts(mut self, deferred_timeouts: DeferredTimeouts) -> Self {
+        self.deferred_timeouts = Some(deferred_timeouts);
+        self
+    }
+
     pub fn try_send_command(&self, cmd: WorkerCmd) {
         let res = if let Some(tx) = self.tx.acquire_read().as_ref() {
             tx.send(cmd)
@@ -91,6 +113,47 @@ impl WorkerHandle {
         self.try_send_command(WorkerCmd::ClearPendingPackets);
     }
 
+    /// Instruct the worker to pause, or to resume and clear pending packets.
+    /// Does nothing if the worker is already in the requested state.
+    pub fn set_paused(&mut self, paused: bool) {
+        if self.paused != paused {
+            self.paused = paused;
+            self.try_send_command(if paused {
+                WorkerCmd::Pause
+            } else {
+                WorkerCmd::Resume
+            });
+        }
+    }
+
+    /// Whether the worker has been paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Instruct the packet worker to stop relaying to the given chain, as it is halted,
+    /// or to resume relaying to it and clear pending packets. Does nothing if the chain
+    /// is already known to the worker to be in the requested state.
+    pub fn set_chain_halted(&mut self, chain_id: &ChainId, halted: bool) {
+        let changed = if halted {
+            self.halted_chains.insert(chain_id.clone())
+        } else {
+            self.halted_chains.remove(chain_id)
+        };
+
+        if changed {
+            self.try_send_command(WorkerCmd::ChainHalted {
+                chain_id: chain_id.clone(),
+                halted,
+            });
+        }
+    }
+
+    /// Whether the worker has stopped relaying to the given chain, as it is halted.
+    pub fn is_chain_halted(&self, chain_id: &ChainId) -> bool {
+        self.halted_chains.contains(chain_id)
+    }
+
     /// Shutdown all worker tasks without waiting for them to terminate.
     pub fn shutdown(&self) {
         for task in self.task_handles.iter() {
@@ -116,6 +179,11 @@ impl WorkerHandle {
         true
     }
 
+    /// Whether one of the worker tasks was aborted after panicking.
+    pub fn has_panicked(&self) -> bool {
+        self.task_handles.iter().any(TaskHandle::has_panicked)
+    }
+
     /// Wait for the worker thread to finish.
     pub fn join(mut self) {
         let task_handles = mem::take(&mut self.task_handles);
@@ -140,6 +208,14 @@ impl WorkerHandle {
     pub fn data(&self) -> Option<&WorkerData> {
         self.data.as_ref()
     }
+
+    /// How many timeouts of packets await a proof from their unreachable
+    /// destination chain, zero for the workers not relaying packets.
+    pub fn timeouts_awaiting_proof(&self) -> usize {
+        self.deferred_timeouts
+            .as_ref()
+            .map_or(0, DeferredTimeouts::len)
+    }
 }
 
 // Drop handle to send shutdown signals to background tasks in parallel
@@ -155,6 +231,8 @@ impl fmt::Debug for WorkerHandle {
         f.debug_struct("WorkerHandle")
             .field("id", &self.id)
             .field("object", &self.object)
+            .field("paused", &self.paused)
+            .field("halted_chains", &self.halted_chains)
             .finish_non_exhaustive()
     }
 }
@@ -0,0 +1,237 @@
+//! Tracking of the end-to-end latency of packets, i.e. the time elapsed between
+//! the block in which a packet is sent on its source chain and the block in which
+//! its acknowledgement is written on its destination chain.
+//!
+//! Both times are block times, rather than local times, so that the latency is
+//! not skewed by the clock of the relayer nor by the time it takes to relay.
+
+use alloc::collections::BTreeMap;
+use core::time::Duration;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use ibc::core::ics04_channel::packet::Sequence;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, PortId};
+use ibc::timestamp::Timestamp;
+
+/// The chain, port and channel packets are sent on.
+type PathKey = (ChainId, PortId, ChannelId);
+
+/// How many packets are tracked per path at most. Beyond that, the packets
+/// with the lowest sequences, e.g. those which timed out, are forgotten.
+const MAX_TRACKED_PACKETS_PER_PATH: usize = 1000;
+
+/// The times of the blocks in which the packets pending on each path were sent.
+///
+/// The send event of a packet and the acknowledgement write are seen by the packet
+/// workers of the two directions of a channel, which share this state.
+#[derive(Clone, Debug, Default)]
+pub struct PacketSendTimes(Arc<Mutex<HashMap<PathKey, BTreeMap<Sequence, Timestamp>>>>);
+
+impl PacketSendTimes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records that the packet with the given sequence was sent on
+    /// the given chain, port and channel in a block of the given time.
+    pub fn insert(
+        &self,
+        chain_id: &ChainId,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        sequence: Sequence,
+        sent_at: Timestamp,
+    ) {
+        let mut paths = self.0.lock().unwrap();
+
+        let path = paths
+            .entry((chain_id.clone(), port_id.clone(), channel_id.clone()))
+            .or_default();
+
+        path.insert(sequence, sent_at);
+
+        while path.len() > MAX_TRACKED_PACKETS_PER_PATH {
+            let oldest = *path.keys().next().unwrap();
+            path.remove(&oldest);
+        }
+    }
+
+    /// Returns and forgets the time of the block in which the packet with the
+    /// given sequence was sent on the given chain, port and channel, if known.
+    pub fn take(
+        &self,
+        chain_id: &ChainId,
+        port_id: &PortId,
+        channel_id: &ChannelId,
+        sequence: Sequence,
+    ) -> Option<Timestamp> {
+        let mut paths = self.0.lock().unwrap();
+
+        let key = (chain_id.clone(), port_id.clone(), channel_id.clone());
+        let path = paths.get_mut(&key)?;
+        let sent_at = path.remove(&sequence);
+
+        if path.is_empty() {
+            paths.remove(&key);
+        }
+
+        sent_at
+    }
+
+    /// How many packets are tracked on the given chain, port and channel.
+    pub fn len(&self, chain_id: &ChainId, port_id: &PortId, channel_id: &ChannelId) -> usize {
+        self.0
+            .lock()
+            .unwrap()
+            .get(&(chain_id.clone(), port_id.clone(), channel_id.clone()))
+            .map_or(0, BTreeMap::len)
+    }
+
+    /// Whether no packet is tracked, on any chain, port and channel.
+    pub fn is_empty(&self) -> bool {
+        // The paths are forgotten along with their last packet
+        self.0.lock().unwrap().is_empty()
+    }
+}
+
+/// The end-to-end latency of a packet.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct E2eLatency {
+    pub latency: Duration,
+    /// Whether the acknowledgement was written in a block older than the one in
+    /// which the packet was sent, due to the clocks of the chains being skewed,
+    /// in which case the latency is clamped to zero.
+    pub clamped: bool,
+}
+
+impl E2eLatency {
+    /// The latency between the given block times, or `None` if either is not set.
+    pub fn between(sent_at: Timestamp, ack_written_at: Timestamp) -> Option<Self> {
+        if sent_at == Timestamp::none() || ack_written_at == Timestamp::none() {
+            return None;
+        }
+
+        let latency = match ack_written_at.duration_since(&sent_at) {
+            Some(latency) => Self {
+                latency,
+                clamped: false,
+            },
+            None => Self {
+                latency: Duration::ZERO,
+                clamped: true,
+            },
+        };
+
+        Some(latency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::str::FromStr;
+
+    fn at(secs: u64) -> Timestamp {
+        Timestamp::from_nanoseconds(secs * 1_000_000_000).unwrap()
+    }
+
+    fn path() -> (ChainId, PortId, ChannelId) {
+        (
+            ChainId::from_string("ibc-0"),
+            PortId::transfer(),
+            ChannelId::from_str("channel-0").unwrap(),
+        )
+    }
+
+    #[test]
+    fn take_send_time_once() {
+        let times = PacketSendTimes::new();
+        let (chain, port, channel) = path();
+
+        times.insert(&chain, &port, &channel, Sequence::from(1), at(10));
+
+        assert_eq!(
+            times.take(&chain, &port, &channel, Sequence::from(1)),
+            Some(at(10))
+        );
+        assert_eq!(times.take(&chain, &port, &channel, Sequence::from(1)), None);
+        assert_eq!(times.len(&chain, &port, &channel), 0);
+        assert!(times.is_empty());
+    }
+
+    #[test]
+    fn send_times_are_per_path() {
+        let times = PacketSendTimes::new();
+        let (chain, port, channel) = path();
+        let other_channel = ChannelId::from_str("channel-1").unwrap();
+
+        times.insert(&chain, &port, &channel, Sequence::from(1), at(10));
+
+        assert_eq!(
+            times.take(&chain, &port, &other_channel, Sequence::from(1)),
+            None
+        );
+        assert_eq!(
+            times.take(
+                &ChainId::from_string("ibc-1"),
+                &port,
+                &channel,
+                Sequence::from(1)
+            ),
+            None
+        );
+        assert_eq!(times.len(&chain, &port, &channel), 1);
+    }
+
+    #[test]
+    fn forget_lowest_sequences_beyond_capacity() {
+        let times = PacketSendTimes::new();
+        let (chain, port, channel) = path();
+
+        for sequence in 1..=MAX_TRACKED_PACKETS_PER_PATH as u64 + 10 {
+            times.insert(&chain, &port, &channel, Sequence::from(sequence), at(10));
+        }
+
+        assert_eq!(
+            times.len(&chain, &port, &channel),
+            MAX_TRACKED_PACKETS_PER_PATH
+        );
+        assert_eq!(
+            times.take(&chain, &port, &channel, Sequence::from(10)),
+            None
+        );
+        assert!(times
+            .take(&chain, &port, &channel, Sequence::from(11))
+            .is_some());
+    }
+
+    #[test]
+    fn latency_between_block_times() {
+        assert_eq!(
+            E2eLatency::between(at(10), at(25)),
+            Some(E2eLatency {
+                latency: Duration::from_secs(15),
+                clamped: false,
+            })
+        );
+    }
+
+    #[test]
+    fn negative_latency_is_clamped() {
+        assert_eq!(
+            E2eLatency::between(at(25), at(10)),
+            Some(E2eLatency {
+                latency: Duration::ZERO,
+                clamped: true,
+            })
+        );
+    }
+
+    #[test]
+    fn latency_without_block_time() {
+        assert_eq!(E2eLatency::between(Timestamp::none(), at(10)), None);
+        assert_eq!(E2eLatency::between(at(10), Timestamp::none()), None);
+    }
+}
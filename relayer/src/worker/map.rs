@@ -1,4 +1,5 @@
 use alloc::collections::btree_map::BTreeMap as HashMap;
+use alloc::collections::btree_set::BTreeSet;
 use core::mem;
 
 use ibc::core::ics02_client::events::NewBlock;
@@ -8,18 +9,37 @@ use tracing::{debug, trace};
 
 use crate::{
     chain::handle::{ChainHandle, ChainHandlePair},
+    channel::version::ChannelVersionCache,
     config::Config,
-    object::Object,
+    link::SubmittedTxs,
+    object::{Object, Packet},
+    supervisor::startup::{ClearPermits, ProgressSender},
     telemetry,
 };
 
-use super::{spawn_worker_tasks, WorkerHandle, WorkerId};
+use super::latency::PacketSendTimes;
+use super::{spawn_worker_tasks, WorkerData, WorkerHandle, WorkerId};
 
 /// Manage the lifecycle of [`WorkerHandle`]s associated with [`Object`]s.
 #[derive(Debug)]
 pub struct WorkerMap {
     workers: HashMap<Object, WorkerHandle>,
     latest_worker_id: WorkerId,
+    startup_progress: Option<ProgressSender>,
+    /// Shared by the packet workers clearing the packets pending on start
+    clear_permits: ClearPermits,
+    /// The versions of the channels relayed by the packet workers
+    version_cache: ChannelVersionCache,
+    /// The times at which the packets relayed by the packet workers were sent
+    send_times: PacketSendTimes,
+    /// The transactions submitted by the packet workers, shared with their
+    /// successors so that a respawned worker does not relay them again.
+    submitted_txs: SubmittedTxs,
+    /// The objects whose packet workers have been paused, which stay
+    /// paused when they are respawned.
+    paused: BTreeSet<Object>,
+    /// The chains which are halted, to which the packet workers relay nothing until they resume.
+    halted_chains: BTreeSet<ChainId>,
 }
 
 impl Default for WorkerMap {
@@ -27,6 +47,13 @@ impl Default for WorkerMap {
         Self {
             workers: HashMap::new(),
             latest_worker_id: WorkerId::new(0),
+            startup_progress: None,
+            clear_permits: ClearPermits::default(),
+            version_cache: ChannelVersionCache::new(),
+            send_times: PacketSendTimes::new(),
+            submitted_txs: SubmittedTxs::new(),
+            paused: BTreeSet::new(),
+            halted_chains: BTreeSet::new(),
         }
     }
 }
@@ -39,6 +66,13 @@ impl WorkerMap {
         Self::default()
     }
 
+    /// Have the packet workers spawned from now on report the progress of
+    /// the clearing of pending packets on start through the given sender,
+    /// or stop doing so if `None` is given.
+    pub fn report_startup_progress(&mut self, sender: Option<ProgressSender>) {
+        self.startup_progress = sender;
+    }
+
     /// Returns `true` if there is a spawned [`WorkerHandle`] associated with the given [`Object`].
     pub fn contains(&self, object: &Object) -> bool {
         self.workers.contains_key(object)
@@ -166,9 +200,133 @@ impl WorkerMap {
             self.next_worker_id(),
             object.clone(),
             config,
+            self.startup_progress.as_ref(),
+            &self.clear_permits,
+            &mut self.version_cache,
+            &self.send_times,
+            &self.submitted_txs,
+            self.paused.contains(object),
+            &self.halted_chains,
         )
     }
 
+    /// List the [`Object`]s whose packet worker was aborted after one of its tasks panicked.
+    pub fn panicked_packet_workers(&self) -> Vec<Object> {
+        self.workers
+            .iter()
+            .filter(|(o, h)| matches!(o, Object::Packet(_)) && h.has_panicked())
+            .map(|(o, _)| o.clone())
+            .collect()
+    }
+
+    /// Shut down the worker associated with the given [`Object`] and spawn it again.
+    /// A respawned packet worker first confirms the transactions submitted by its
+    /// predecessor, before relaying their packets again.
+    ///
+    /// Returns whether or not the worker was actually spawned.
+    pub fn respawn<Chain: ChainHandle>(
+        &mut self,
+        src: Chain,
+        dst: Chain,
+        object: &Object,
+        config: &Config,
+    ) -> bool {
+        self.shutdown_worker(object);
+        self.spawn(src, dst, object, config)
+    }
+
+    /// Forget the versions of the channel relayed on the given path, so that they are
+    /// checked again, and shut down its packet worker if it is not relaying because the
+    /// versions differed, so that it is spawned again with the new versions.
+    pub fn invalidate_channel_versions(&mut self, path: &Packet) {
+        self.version_cache.invalidate(&(
+            path.src_chain_id.clone(),
+            path.src_port_id.clone(),
+            path.src_channel_id.clone(),
+        ));
+
+        let object = Object::Packet(path.clone());
+
+        let version_mismatch = matches!(
+            self.workers.get(&object).and_then(WorkerHandle::data),
+            Some(WorkerData::Packet {
+                version_mismatch: true
+            })
+        );
+
+        if version_mismatch {
+            self.shutdown_worker(&object);
+        }
+    }
+
+    /// Pause the packet worker associated with the given [`Object`]: it keeps scheduling
+    /// the packets of the events it receives, but submits nothing until resumed.
+    /// The worker stays paused when it is respawned, until the process exits.
+    ///
+    /// Returns the handle of the worker, or `None` if there is no such packet worker.
+    pub fn pause(&mut self, object: &Object) -> Option<&WorkerHandle> {
+        self.set_paused(object, true)
+    }
+
+    /// Resume the packet worker associated with the given [`Object`], which starts
+    /// by clearing the pending packets.
+    ///
+    /// Returns the handle of the worker, or `None` if there is no such packet worker.
+    pub fn resume(&mut self, object: &Object) -> Option<&WorkerHandle> {
+        self.set_paused(object, false)
+    }
+
+    fn set_paused(&mut self, object: &Object, paused: bool) -> Option<&WorkerHandle> {
+        if !matches!(object, Object::Packet(_)) {
+            return None;
+        }
+
+        if !self.workers.contains_key(object) {
+            return None;
+        }
+
+        if paused {
+            self.paused.insert(object.clone());
+        } else {
+            self.paused.remove(object);
+        }
+
+        let handle = self.workers.get_mut(object)?;
+        handle.set_paused(paused);
+
+        Some(handle)
+    }
+
+    /// Mark the given chain as halted or resumed. While it is halted, the packet workers
+    /// relaying to or from it submit nothing to it, but keep relaying to the other chain,
+    /// eg. the timeouts of the packets sent to the halted chain. On resume, the workers
+    /// start relaying to it again by clearing the pending packets.
+    pub fn set_chain_halted(&mut self, chain_id: &ChainId, halted: bool) {
+        if halted {
+            self.halted_chains.insert(chain_id.clone());
+        } else {
+            self.halted_chains.remove(chain_id);
+        }
+
+        let objects: Vec<Object> = self
+            .workers
+            .keys()
+            .filter(|object| matches!(object, Object::Packet(_)) && object.for_chain(chain_id))
+            .cloned()
+            .collect();
+
+        for object in objects {
+            if let Some(handle) = self.workers.get_mut(&object) {
+                handle.set_chain_halted(chain_id, halted);
+            }
+        }
+    }
+
+    /// Whether the given chain is marked as halted.
+    pub fn is_chain_halted(&self, chain_id: &ChainId) -> bool {
+        self.halted_chains.contains(chain_id)
+    }
+
     /// Compute the next worker id
     fn next_worker_id(&mut self) -> WorkerId {
         let id = self.latest_worker_id.next();
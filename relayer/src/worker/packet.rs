@@ -1,23 +1,138 @@
+use core::sync::atomic::{AtomicBool, Ordering};
 use core::time::Duration;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
-use crossbeam_channel::Receiver;
-use tracing::{error, error_span, trace};
+use crossbeam_channel::{bounded, Receiver, Sender};
+use tracing::{error, error_span, info, trace};
 
+use ibc::core::ics24_host::identifier::ChainId;
 use ibc::Height;
 
 use crate::chain::handle::ChainHandle;
+use crate::config::RelayMode;
 use crate::event::monitor::EventBatch;
 use crate::foreign_client::HasExpiredOrFrozenError;
+use crate::link::operational_data::OperationalDataTarget;
 use crate::link::Resubmit;
-use crate::link::{error::LinkError, Link};
+use crate::link::{error::LinkError, Link, PacketClearing, RelaySummary};
 use crate::object::Packet;
+use crate::supervisor::startup::{ClearPermit, ClearPermits, ProgressSender, StartupProgress};
 use crate::telemetry;
 use crate::util::task::{spawn_background_task, Next, TaskError, TaskHandle};
 
 use super::error::RunError;
+use super::latency::PacketSendTimes;
 use super::WorkerCmd;
 
+/// Whether a packet worker is paused, as shared between its tasks.
+///
+/// While paused, the worker keeps scheduling the packets of the events
+/// it receives, but submits no transaction until it is resumed.
+#[derive(Clone, Debug)]
+pub struct PauseFlag(Arc<AtomicBool>);
+
+impl PauseFlag {
+    pub fn new(paused: bool) -> Self {
+        Self(Arc::new(AtomicBool::new(paused)))
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    fn set(&self, paused: bool) {
+        self.0.store(paused, Ordering::SeqCst)
+    }
+}
+
+/// Whether the source and destination chains of a packet worker are halted,
+/// as shared between its tasks.
+///
+/// The worker submits no transaction to a halted chain, but keeps relaying to the
+/// other chain, eg. the timeouts of the packets sent to the halted chain.
+#[derive(Clone, Debug)]
+pub struct HaltedChains {
+    src: PauseFlag,
+    dst: PauseFlag,
+}
+
+impl HaltedChains {
+    pub fn new(src_halted: bool, dst_halted: bool) -> Self {
+        Self {
+            src: PauseFlag::new(src_halted),
+            dst: PauseFlag::new(dst_halted),
+        }
+    }
+
+    /// Whether the `target` chain is halted.
+    pub fn is_halted(&self, target: OperationalDataTarget) -> bool {
+        self.flag(target).is_paused()
+    }
+
+    /// Marks the given chain, if it is one of the chains of the `path`, as halted or resumed.
+    fn set(&self, path: &Packet, chain_id: &ChainId, halted: bool) {
+        if *chain_id == path.src_chain_id {
+            self.flag(OperationalDataTarget::Source).set(halted);
+        }
+
+        if *chain_id == path.dst_chain_id {
+            self.flag(OperationalDataTarget::Destination).set(halted);
+        }
+    }
+
+    fn flag(&self, target: OperationalDataTarget) -> &PauseFlag {
+        match target {
+            OperationalDataTarget::Source => &self.src,
+            OperationalDataTarget::Destination => &self.dst,
+        }
+    }
+}
+
+/// How long the task relaying the operational data scheduled for a chain
+/// waits to be woken up, before looking for operational data anyway.
+const EXECUTE_SCHEDULE_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// Wakes the tasks of a packet worker relaying the operational data scheduled
+/// for the source and destination chains, as shared between its tasks.
+///
+/// The task handling the commands only schedules the operational data, then wakes
+/// the tasks relaying it, so that the relaying to one chain does not wait on the
+/// relaying to the other chain.
+#[derive(Clone, Debug)]
+pub struct ScheduleWakers {
+    src: (Sender<()>, Receiver<()>),
+    dst: (Sender<()>, Receiver<()>),
+}
+
+impl Default for ScheduleWakers {
+    fn default() -> Self {
+        Self {
+            src: bounded(1),
+            dst: bounded(1),
+        }
+    }
+}
+
+impl ScheduleWakers {
+    /// Wakes the tasks relaying to both chains, unless they are already due to wake up.
+    fn wake(&self) {
+        for (sender, _) in [&self.src, &self.dst] {
+            let _ = sender.try_send(());
+        }
+    }
+
+    /// Waits until the task relaying to the `target` chain is woken up,
+    /// or until the given `timeout` elapses.
+    fn wait(&self, target: OperationalDataTarget, timeout: Duration) {
+        let (_, receiver) = match target {
+            OperationalDataTarget::Source => &self.src,
+            OperationalDataTarget::Destination => &self.dst,
+        };
+
+        let _ = receiver.recv_timeout(timeout);
+    }
+}
+
 fn handle_link_error_in_task(e: LinkError) -> TaskError<RunError> {
     if e.is_expired_or_frozen_error() {
         // If the client is expired or frozen, terminate the packet worker
@@ -29,40 +144,70 @@ fn handle_link_error_in_task(e: LinkError) -> TaskError<RunError> {
 }
 
 /// Spawns a packet worker task in the background that handles the work of
-/// processing pending txs between `ChainA` and `ChainB`.
+/// processing pending txs between `ChainA` and `ChainB`, targeting the given chain.
+///
+/// One such task is spawned for each of the source and destination chains, so
+/// that the messages relayed to each chain do not wait on the ones relayed to the
+/// other chain. The [`RelayPath`](crate::link::RelayPath) makes sure that the
+/// messages relayed to the same chain are processed in order.
+///
+/// The task relays the operational data as soon as it is woken up through the
+/// `wakers`, and at least every [`EXECUTE_SCHEDULE_INTERVAL`], unless the worker
+/// is paused or the target chain is halted.
 pub fn spawn_packet_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
     path: Packet,
-    // Mutex is used to prevent race condition between the packet workers
-    link: Arc<Mutex<Link<ChainA, ChainB>>>,
+    link: Arc<Link<ChainA, ChainB>>,
+    target: OperationalDataTarget,
     resubmit: Resubmit,
+    paused: PauseFlag,
+    halted: HaltedChains,
+    wakers: ScheduleWakers,
 ) -> TaskHandle {
     let span = {
-        let relay_path = &link.lock().unwrap().a_to_b;
+        let relay_path = &link.a_to_b;
         error_span!(
             "packet",
             src_chain = %relay_path.src_chain().id(),
             src_port = %relay_path.src_port_id(),
             src_channel = %relay_path.src_channel_id(),
             dst_chain = %relay_path.dst_chain().id(),
+            target = %target,
         )
     };
 
-    spawn_background_task(span, Some(Duration::from_millis(1000)), move || {
-        handle_execute_schedule(&mut link.lock().unwrap(), &path, resubmit)?;
+    spawn_background_task(span, None, move || {
+        wakers.wait(target, EXECUTE_SCHEDULE_INTERVAL);
+
+        if !paused.is_paused() {
+            handle_execute_schedule_for(&link, &path, target, resubmit, &halted)?;
+        }
         Ok(Next::Continue)
     })
 }
 
+/// Spawns the task handling the commands sent to a packet worker.
+///
+/// If `send_times` is given, the task records the end-to-end latency of the packets
+/// whose acknowledgements are written in the event batches it receives, and the time
+/// at which the packets sent in these batches were sent. Both are the time of the block
+/// of the batch, as carried by the `NewBlock` event of the source chain at its height.
+///
+/// The operational data scheduled by the task is relayed by the tasks spawned with
+/// [`spawn_packet_worker`], which the task wakes up through the `wakers`.
 pub fn spawn_packet_cmd_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
     cmd_rx: Receiver<WorkerCmd>,
-    // Mutex is used to prevent race condition between the packet workers
-    link: Arc<Mutex<Link<ChainA, ChainB>>>,
+    link: Arc<Link<ChainA, ChainB>>,
     mut should_clear_on_start: bool,
     clear_interval: u64,
     path: Packet,
+    mut startup_progress: Option<ProgressSender>,
+    paused: PauseFlag,
+    halted: HaltedChains,
+    send_times: Option<PacketSendTimes>,
+    wakers: ScheduleWakers,
 ) -> TaskHandle {
     let span = {
-        let relay_path = &link.lock().unwrap().a_to_b;
+        let relay_path = &link.a_to_b;
         error_span!(
             "packet_cmd",
             src_chain = %relay_path.src_chain().id(),
@@ -72,18 +217,35 @@ pub fn spawn_packet_cmd_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
         )
     };
 
+    // The last block of the source chain, whose time is the time of the events at its height
+    #[cfg(feature = "telemetry")]
+    let mut last_block = None;
+
     spawn_background_task(span, Some(Duration::from_millis(200)), move || {
         if let Ok(cmd) = cmd_rx.try_recv() {
+            if let Some(_send_times) = &send_times {
+                telemetry!(packet_latency_metrics(
+                    &path,
+                    _send_times,
+                    &mut last_block,
+                    &cmd
+                ));
+            }
+
             // Try to clear pending packets. At different levels down in `handle_packet_cmd` there
             // are retries mechanisms for MAX_RETRIES (current value hardcoded at 5).
             // If clearing fails after all these retries with ignorable error the task continues
             // (see `handle_link_error_in_task`) and clearing is retried with the next
             // (`NewBlock`) `cmd` that matches the clearing interval.
             handle_packet_cmd(
-                &mut link.lock().unwrap(),
+                &link,
                 &mut should_clear_on_start,
                 clear_interval,
                 &path,
+                &mut startup_progress,
+                &paused,
+                &halted,
+                &wakers,
                 cmd,
             )?;
         }
@@ -92,6 +254,150 @@ pub fn spawn_packet_cmd_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
     })
 }
 
+/// How many times in a row scheduling a chunk of the packets pending on start
+/// may fail before the clearing on start is given up.
+const MAX_CLEAR_ON_START_FAILURES: usize = 5;
+
+/// Spawns a task which clears the packets pending on the path when the worker starts,
+/// next to the packet command worker, so that the events received in the meantime
+/// are relayed without waiting for the clearing to complete.
+///
+/// The clearing waits for one of the `permits` before starting, which bounds how
+/// many paths are cleared at once, and schedules one chunk of pending packets per
+/// step, so that the task stops in between chunks when the worker is shut down.
+/// Its progress is reported through the `startup_progress` sender, if any.
+pub fn spawn_clear_on_start_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
+    link: Arc<Link<ChainA, ChainB>>,
+    path: Packet,
+    permits: ClearPermits,
+    startup_progress: Option<ProgressSender>,
+    paused: PauseFlag,
+) -> TaskHandle {
+    let span = {
+        let relay_path = &link.a_to_b;
+        error_span!(
+            "packet_clear_on_start",
+            src_chain = %relay_path.src_chain().id(),
+            src_port = %relay_path.src_port_id(),
+            src_channel = %relay_path.src_channel_id(),
+            dst_chain = %relay_path.dst_chain().id(),
+        )
+    };
+
+    let mut clear = ClearOnStart::new(path, permits, startup_progress, paused);
+
+    spawn_background_task(span, Some(Duration::from_millis(100)), move || {
+        clear.step(&link)
+    })
+}
+
+/// The state of the clearing of the packets pending on start on a path.
+struct ClearOnStart {
+    path: Packet,
+    permits: ClearPermits,
+    permit: Option<ClearPermit>,
+    clearing: Option<PacketClearing>,
+    failures: usize,
+    startup_progress: Option<ProgressSender>,
+    paused: PauseFlag,
+}
+
+impl ClearOnStart {
+    fn new(
+        path: Packet,
+        permits: ClearPermits,
+        startup_progress: Option<ProgressSender>,
+        paused: PauseFlag,
+    ) -> Self {
+        Self {
+            path,
+            permits,
+            permit: None,
+            clearing: None,
+            failures: 0,
+            startup_progress,
+            paused,
+        }
+    }
+
+    /// Waits for a permit, then queries the pending packets, then schedules
+    /// one chunk of them per step, until all of them have been scheduled.
+    fn step<ChainA: ChainHandle, ChainB: ChainHandle>(
+        &mut self,
+        link: &Link<ChainA, ChainB>,
+    ) -> Result<Next, TaskError<RunError>> {
+        if self.paused.is_paused() {
+            return Ok(Next::Continue);
+        }
+
+        if self.permit.is_none() {
+            match self.permits.try_acquire() {
+                Some(permit) => self.permit = Some(permit),
+                None => return Ok(Next::Continue),
+            }
+        }
+
+        let result = match &mut self.clearing {
+            None => link
+                .a_to_b
+                .start_packet_clearing(None)
+                .map(|clearing| self.clearing.insert(clearing).progress()),
+            Some(clearing) => link
+                .a_to_b
+                .clear_next_chunk(clearing)
+                .map(|()| clearing.progress()),
+        };
+
+        match result {
+            Ok(progress) => {
+                self.failures = 0;
+
+                self.report(StartupProgress::Clearing {
+                    path: self.path.clone(),
+                    progress,
+                });
+
+                if self
+                    .clearing
+                    .as_ref()
+                    .map_or(false, PacketClearing::is_done)
+                {
+                    info!("done clearing the packets pending on start");
+
+                    self.report(StartupProgress::Cleared {
+                        path: self.path.clone(),
+                    });
+
+                    Ok(Next::Abort)
+                } else {
+                    Ok(Next::Continue)
+                }
+            }
+            Err(e) => {
+                self.failures += 1;
+
+                if e.is_expired_or_frozen_error() || self.failures >= MAX_CLEAR_ON_START_FAILURES {
+                    self.report(StartupProgress::Failed {
+                        path: self.path.clone(),
+                        reason: e.to_string(),
+                    });
+
+                    Err(TaskError::Fatal(RunError::link(e)))
+                } else {
+                    Err(TaskError::Ignore(RunError::link(e)))
+                }
+            }
+        }
+    }
+
+    fn report(&self, progress: StartupProgress) {
+        // Sending errors are ignored, as the supervisor may have stopped tracking the startup
+        if let Some(sender) = &self.startup_progress {
+            let _ = sender.send(progress);
+        }
+    }
+}
+
 /// Receives worker commands and handles them accordingly.
 ///
 /// Given an `IbcEvent` command, updates the schedule and initiates
@@ -102,15 +408,60 @@ pub fn spawn_packet_cmd_worker<ChainA: ChainHandle, ChainB: ChainHandle>(
 ///
 /// Given a `ClearPendingPackets` command, clears pending packets.
 ///
-/// Regardless of the incoming command, this method also refreshes and
-/// and executes any scheduled operational data that is ready.
+/// Given a `Pause` command, pauses the worker: until the next `Resume` command,
+/// which clears pending packets, the packets of incoming events are scheduled
+/// but nothing is cleared nor submitted.
+///
+/// Given a `ChainHalted` command, marks the chain as halted, so that nothing is
+/// submitted to it, or as resumed, which clears pending packets.
+///
+/// The progress of the packet clearing done on start is reported through
+/// the `startup_progress` sender, if any, which is consumed in the process.
+///
+/// Once operational data is scheduled, this method wakes the tasks
+/// relaying it to the source and destination chains through the `wakers`.
 fn handle_packet_cmd<ChainA: ChainHandle, ChainB: ChainHandle>(
-    link: &mut Link<ChainA, ChainB>,
+    link: &Link<ChainA, ChainB>,
     should_clear_on_start: &mut bool,
     clear_interval: u64,
     path: &Packet,
+    startup_progress: &mut Option<ProgressSender>,
+    paused: &PauseFlag,
+    halted: &HaltedChains,
+    wakers: &ScheduleWakers,
     cmd: WorkerCmd,
 ) -> Result<(), TaskError<RunError>> {
+    match &cmd {
+        WorkerCmd::ChainHalted {
+            chain_id,
+            halted: true,
+        } => {
+            halted.set(path, chain_id, true);
+            return Ok(());
+        }
+        WorkerCmd::ChainHalted {
+            chain_id,
+            halted: false,
+        } => halted.set(path, chain_id, false),
+        _ => (),
+    }
+
+    match cmd {
+        WorkerCmd::Pause => {
+            paused.set(true);
+            return Ok(());
+        }
+        WorkerCmd::Resume => paused.set(false),
+        WorkerCmd::IbcEvents { batch } if paused.is_paused() => {
+            return link
+                .a_to_b
+                .update_schedule(batch)
+                .map_err(handle_link_error_in_task);
+        }
+        _ if paused.is_paused() => return Ok(()),
+        _ => (),
+    }
+
     // Handle packet clearing which is triggered from a command
     let (do_clear, maybe_height) = match &cmd {
         WorkerCmd::IbcEvents { batch } => {
@@ -131,21 +482,37 @@ fn handle_packet_cmd<ChainA: ChainHandle, ChainB: ChainHandle>(
             }
         }
 
-        WorkerCmd::ClearPendingPackets => (true, None),
+        WorkerCmd::ClearPendingPackets | WorkerCmd::Resume => (true, None),
+
+        // A chain which resumes is relayed to again, starting with the packets pending meanwhile
+        WorkerCmd::ChainHalted { .. } => (true, None),
+
+        WorkerCmd::Pause => (false, None),
     };
 
     if do_clear {
         // Reset the `clear_on_start` flag and attempt packet clearing once now.
         // More clearing will be done at clear interval.
-        if *should_clear_on_start {
+        let startup_progress = if *should_clear_on_start {
             *should_clear_on_start = false;
-        }
-        handle_clear_packet(link, clear_interval, path, maybe_height)?;
+            startup_progress.take()
+        } else {
+            None
+        };
+        handle_clear_packet(link, path, maybe_height, startup_progress, wakers)?;
     }
 
     // Handle command-specific task
     if let WorkerCmd::IbcEvents { batch } = cmd {
-        handle_update_schedule(link, clear_interval, path, batch)
+        telemetry!(
+            event_batch_size,
+            batch.events.len() as u64,
+            &path.src_chain_id,
+            &path.src_channel_id,
+            &path.src_port_id
+        );
+
+        handle_update_schedule(link, batch, wakers)
     } else {
         Ok(())
     }
@@ -162,72 +529,201 @@ fn should_clear_packets(clear_interval: u64, height: Height) -> bool {
 }
 
 fn handle_update_schedule<ChainA: ChainHandle, ChainB: ChainHandle>(
-    link: &mut Link<ChainA, ChainB>,
-    clear_interval: u64,
-    path: &Packet,
+    link: &Link<ChainA, ChainB>,
     batch: EventBatch,
+    wakers: &ScheduleWakers,
 ) -> Result<(), TaskError<RunError>> {
     link.a_to_b
         .update_schedule(batch)
         .map_err(handle_link_error_in_task)?;
 
-    handle_execute_schedule(link, path, Resubmit::from_clear_interval(clear_interval))
+    wakers.wake();
+
+    Ok(())
 }
 
 fn handle_clear_packet<ChainA: ChainHandle, ChainB: ChainHandle>(
-    link: &mut Link<ChainA, ChainB>,
-    clear_interval: u64,
+    link: &Link<ChainA, ChainB>,
     path: &Packet,
     height: Option<Height>,
+    startup_progress: Option<ProgressSender>,
+    wakers: &ScheduleWakers,
 ) -> Result<(), TaskError<RunError>> {
-    link.a_to_b
-        .schedule_packet_clearing(height)
-        .map_err(handle_link_error_in_task)?;
+    // Sending errors are ignored, as the supervisor may have stopped tracking the startup
+    let report = |progress: StartupProgress| {
+        if let Some(sender) = &startup_progress {
+            let _ = sender.send(progress);
+        }
+    };
 
-    handle_execute_schedule(link, path, Resubmit::from_clear_interval(clear_interval))
+    let result = link
+        .a_to_b
+        .schedule_packet_clearing_with_progress(height, |progress| {
+            report(StartupProgress::Clearing {
+                path: path.clone(),
+                progress,
+            })
+        })
+        .map_err(handle_link_error_in_task)
+        .map(|()| wakers.wake());
+
+    match &result {
+        Ok(()) => report(StartupProgress::Cleared { path: path.clone() }),
+        Err(TaskError::Ignore(e) | TaskError::Fatal(e)) => report(StartupProgress::Failed {
+            path: path.clone(),
+            reason: e.to_string(),
+        }),
+    }
+
+    result
 }
 
-fn handle_execute_schedule<ChainA: ChainHandle, ChainB: ChainHandle>(
-    link: &mut Link<ChainA, ChainB>,
+fn handle_execute_schedule_for<ChainA: ChainHandle, ChainB: ChainHandle>(
+    link: &Link<ChainA, ChainB>,
     _path: &Packet,
+    target: OperationalDataTarget,
     resubmit: Resubmit,
+    halted: &HaltedChains,
 ) -> Result<(), TaskError<RunError>> {
+    // Refreshing the schedule moves the packets which timed out
+    // from the destination to the source chain pipeline. It goes on
+    // while the destination chain is halted, for these timeouts to be
+    // relayed, or deferred while the destination chain is unreachable.
+    if target == OperationalDataTarget::Destination {
+        link.a_to_b
+            .refresh_schedule()
+            .map_err(handle_link_error_in_task)?;
+    }
+
+    if halted.is_halted(target) {
+        return Ok(());
+    }
+
     link.a_to_b
-        .refresh_schedule()
-        .map_err(handle_link_error_in_task)?;
+        .execute_schedule_for(target)
+        .map_err(handle_schedule_error_in_task)?;
 
-    link.a_to_b.execute_schedule().map_err(|e| {
-        if e.is_expired_or_frozen_error() {
-            TaskError::Fatal(RunError::link(e))
-        } else {
-            error!("will retry: schedule execution encountered error: {}", e,);
-            TaskError::Ignore(RunError::link(e))
-        }
-    })?;
+    let summary = link.a_to_b.process_pending_txs_for(target, resubmit);
 
-    let summary = link.a_to_b.process_pending_txs(resubmit);
+    report_summary(_path, link.a_to_b.relay_mode(), summary);
 
+    Ok(())
+}
+
+fn handle_schedule_error_in_task(e: LinkError) -> TaskError<RunError> {
+    if e.is_expired_or_frozen_error() {
+        TaskError::Fatal(RunError::link(e))
+    } else {
+        error!("will retry: schedule execution encountered error: {}", e,);
+        TaskError::Ignore(RunError::link(e))
+    }
+}
+
+fn report_summary(_path: &Packet, _relay_mode: RelayMode, summary: RelaySummary) {
     if !summary.is_empty() {
         trace!("produced relay summary: {:?}", summary);
     }
 
-    telemetry!(packet_metrics(_path, &summary));
-
-    Ok(())
+    telemetry!(packet_metrics(_path, _relay_mode, &summary));
 }
 
+/// Records the time of the block of the batch of the given command as the send time of
+/// the packets sent in it, and the end-to-end latency of the packets whose acknowledgements
+/// are written in it.
+///
+/// The time of the block is taken from the `NewBlock` event the worker receives before
+/// the batches at the same height, which `last_block` keeps track of, so that no query
+/// is made on the relaying path.
 #[cfg(feature = "telemetry")]
-use crate::link::RelaySummary;
+fn packet_latency_metrics(
+    path: &Packet,
+    send_times: &PacketSendTimes,
+    last_block: &mut Option<ibc::core::ics02_client::events::NewBlock>,
+    cmd: &WorkerCmd,
+) {
+    use ibc::events::IbcEvent::{SendPacket, WriteAcknowledgement};
+    use ibc::timestamp::Timestamp;
+    use tracing::debug;
+
+    use crate::worker::latency::E2eLatency;
+
+    let batch = match cmd {
+        WorkerCmd::NewBlock { new_block, .. } => {
+            *last_block = Some(*new_block);
+            return;
+        }
+        WorkerCmd::IbcEvents { batch } => batch,
+        _ => return,
+    };
+
+    if !batch
+        .events
+        .iter()
+        .any(|e| matches!(e, SendPacket(_) | WriteAcknowledgement(_)))
+    {
+        return;
+    }
+
+    let block_time = match last_block {
+        Some(block) if block.height == batch.height && block.time != Timestamp::none() => {
+            block.time
+        }
+        _ => {
+            debug!(
+                "the time of the block at height {} is unknown, \
+                not recording the end-to-end latency of its packets",
+                batch.height
+            );
+            return;
+        }
+    };
+
+    for event in &batch.events {
+        match event {
+            SendPacket(send) => send_times.insert(
+                &path.src_chain_id,
+                &send.packet.source_port,
+                &send.packet.source_channel,
+                send.packet.sequence,
+                block_time,
+            ),
+            WriteAcknowledgement(write_ack) => {
+                // The packet was sent on the counterparty chain
+                let sent_at = send_times.take(
+                    &path.dst_chain_id,
+                    &write_ack.packet.source_port,
+                    &write_ack.packet.source_channel,
+                    write_ack.packet.sequence,
+                );
+
+                if let Some(e2e) =
+                    sent_at.and_then(|sent_at| E2eLatency::between(sent_at, block_time))
+                {
+                    telemetry!(
+                        packet_e2e_latency,
+                        e2e.latency,
+                        e2e.clamped,
+                        &path.dst_chain_id,
+                        &write_ack.packet.source_channel,
+                        &write_ack.packet.source_port,
+                        &path.src_chain_id,
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+}
 
 #[cfg(feature = "telemetry")]
-fn packet_metrics(path: &Packet, summary: &RelaySummary) {
-    receive_packet_metrics(path, summary);
-    acknowledgment_metrics(path, summary);
-    timeout_metrics(path, summary);
+fn packet_metrics(path: &Packet, relay_mode: RelayMode, summary: &RelaySummary) {
+    receive_packet_metrics(path, relay_mode, summary);
+    acknowledgment_metrics(path, relay_mode, summary);
+    timeout_metrics(path, relay_mode, summary);
 }
 
 #[cfg(feature = "telemetry")]
-fn receive_packet_metrics(path: &Packet, summary: &RelaySummary) {
+fn receive_packet_metrics(path: &Packet, relay_mode: RelayMode, summary: &RelaySummary) {
     use ibc::events::IbcEvent::WriteAcknowledgement;
 
     let count = summary
@@ -241,12 +737,13 @@ fn receive_packet_metrics(path: &Packet, summary: &RelaySummary) {
         &path.src_chain_id,
         &path.src_channel_id,
         &path.src_port_id,
+        relay_mode.as_str(),
         count as u64,
     );
 }
 
 #[cfg(feature = "telemetry")]
-fn acknowledgment_metrics(path: &Packet, summary: &RelaySummary) {
+fn acknowledgment_metrics(path: &Packet, relay_mode: RelayMode, summary: &RelaySummary) {
     use ibc::events::IbcEvent::AcknowledgePacket;
 
     let count = summary
@@ -260,12 +757,13 @@ fn acknowledgment_metrics(path: &Packet, summary: &RelaySummary) {
         &path.src_chain_id,
         &path.src_channel_id,
         &path.src_port_id,
+        relay_mode.as_str(),
         count as u64,
     );
 }
 
 #[cfg(feature = "telemetry")]
-fn timeout_metrics(path: &Packet, summary: &RelaySummary) {
+fn timeout_metrics(path: &Packet, relay_mode: RelayMode, summary: &RelaySummary) {
     use ibc::events::IbcEvent::TimeoutPacket;
     let count = summary
         .events
@@ -278,6 +776,1194 @@ fn timeout_metrics(path: &Packet, summary: &RelaySummary) {
         &path.src_chain_id,
         &path.src_channel_id,
         &path.src_port_id,
+        relay_mode.as_str(),
         count as u64,
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::sync::Arc;
+    use core::ops::Range;
+    use core::time::Duration;
+    use std::io;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Mutex;
+    use std::thread;
+    use std::time::Instant;
+
+    use ibc::clients::ics07_tendermint::client_state::{AllowUpdate, ClientState};
+    use ibc::core::ics02_client::client_consensus::AnyConsensusState;
+    use ibc::core::ics02_client::client_state::AnyClientState;
+    use ibc::core::ics02_client::client_type::ClientType;
+    use ibc::core::ics02_client::events::NewBlock;
+    use ibc::core::ics02_client::trust_threshold::TrustThreshold;
+    use ibc::core::ics03_connection::connection::{
+        ConnectionEnd, Counterparty as ConnectionCounterparty, State as ConnectionState,
+    };
+    use ibc::core::ics03_connection::version::get_compatible_versions;
+    use ibc::core::ics04_channel::channel::{
+        ChannelEnd, Counterparty as ChannelCounterparty, Order, State as ChannelState,
+    };
+    use ibc::core::ics04_channel::events::{SendPacket, WriteAcknowledgement};
+    use ibc::core::ics04_channel::packet::{Packet as IbcPacket, Sequence};
+    use ibc::core::ics04_channel::timeout::TimeoutHeight;
+    use ibc::core::ics04_channel::Version;
+    use ibc::core::ics23_commitment::commitment::CommitmentPrefix;
+    use ibc::core::ics23_commitment::specs::ProofSpecs;
+    use ibc::core::ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId};
+    use ibc::events::{ChainErrorEvent, IbcEvent};
+    use ibc::mock::client_state::MockConsensusState;
+    use ibc::mock::header::MockHeader;
+    use ibc::timestamp::Timestamp;
+    use ibc::Height;
+    use ibc_proto::google::protobuf::Any;
+    use tendermint::abci::transaction::Hash;
+    use tendermint::abci::{Code, Data, Log};
+    use tendermint_rpc::endpoint::broadcast::tx_sync::Response;
+
+    use crossbeam_channel::{unbounded, Receiver, Sender};
+    use once_cell::sync::Lazy;
+
+    use super::{
+        handle_execute_schedule_for, handle_packet_cmd, spawn_packet_worker, ClearOnStart,
+        HaltedChains, PauseFlag, ScheduleWakers, MAX_CLEAR_ON_START_FAILURES,
+    };
+    use crate::chain::cosmos::types::tx::TxSyncResponse;
+    use crate::chain::handle::mock::MockChainHandle;
+    use crate::chain::handle::ChainHandle;
+    use crate::chain::mock::test_utils::get_basic_chain_config;
+    use crate::chain::tracking::TrackingId;
+    use crate::error::Error;
+    use crate::event::monitor::EventBatch;
+    use crate::link::error::LinkErrorDetail;
+    use crate::link::operational_data::{OperationalData, OperationalDataTarget, TransitMessage};
+    use crate::link::relay_sender::{AsyncSender, SyncSender};
+    use crate::link::ClearProgress;
+    use crate::link::{Link, LinkParameters, PendingData, Resubmit, SubmittedTxs, TxHashes};
+    use crate::object::Packet;
+    use crate::supervisor::startup::{ClearPermits, StartupProgress};
+    use crate::util::task::{Next, TaskError};
+    use crate::worker::WorkerCmd;
+
+    const CLEAR_INTERVAL: u64 = 100;
+
+    /// Loads `chain` with an open transfer channel `channel-0` to `counterparty`.
+    fn open_channel(chain: &MockChainHandle, counterparty: &MockChainHandle) {
+        let client_id = ClientId::new(ClientType::Tendermint, 0).unwrap();
+        let connection_id = ConnectionId::new(0);
+
+        let client_state = ClientState::new(
+            counterparty.id(),
+            TrustThreshold::ONE_THIRD,
+            Duration::from_secs(14 * 24 * 3600),
+            Duration::from_secs(21 * 24 * 3600),
+            Duration::from_secs(5),
+            Height::new(counterparty.id().version(), 10).unwrap(),
+            ProofSpecs::default(),
+            vec![],
+            AllowUpdate {
+                after_expiry: true,
+                after_misbehaviour: true,
+            },
+        )
+        .unwrap();
+
+        chain.add_client(client_id.clone(), AnyClientState::Tendermint(client_state));
+
+        chain.add_connection(
+            connection_id.clone(),
+            ConnectionEnd::new(
+                ConnectionState::Open,
+                client_id.clone(),
+                ConnectionCounterparty::new(
+                    client_id,
+                    Some(connection_id.clone()),
+                    CommitmentPrefix::try_from(b"ibc".to_vec()).unwrap(),
+                ),
+                get_compatible_versions(),
+                Duration::ZERO,
+            ),
+        );
+
+        chain.add_channel(
+            PortId::transfer(),
+            ChannelId::new(0),
+            ChannelEnd::new(
+                ChannelState::Open,
+                Order::Unordered,
+                ChannelCounterparty::new(PortId::transfer(), Some(ChannelId::new(0))),
+                vec![connection_id],
+                Version::ics20(),
+            ),
+        );
+    }
+
+    /// The chains `a` and `b`, the link relaying `channel-0` from `a` to `b`, and its path.
+    fn setup() -> (
+        MockChainHandle,
+        Link<MockChainHandle, MockChainHandle>,
+        Packet,
+    ) {
+        let a = MockChainHandle::new(ChainId::from_string("chain_A"));
+        let b = MockChainHandle::new(ChainId::from_string("chain_B"));
+
+        open_channel(&a, &b);
+        open_channel(&b, &a);
+
+        let link = Link::new_from_opts(
+            a.clone(),
+            b.clone(),
+            LinkParameters {
+                src_port_id: PortId::transfer(),
+                src_channel_id: ChannelId::new(0),
+            },
+            false,
+        )
+        .unwrap();
+
+        let path = Packet {
+            dst_chain_id: b.id(),
+            src_chain_id: a.id(),
+            src_channel_id: ChannelId::new(0),
+            src_port_id: PortId::transfer(),
+        };
+
+        (a, link, path)
+    }
+
+    fn new_block(height: u64) -> WorkerCmd {
+        let height = Height::new(0, height).unwrap();
+
+        WorkerCmd::NewBlock {
+            height,
+            new_block: NewBlock::new(height),
+        }
+    }
+
+    #[test]
+    fn paused_worker_defers_clearing_until_resumed() {
+        let (a, link, path) = setup();
+        let paused = PauseFlag::new(false);
+        let mut should_clear_on_start = true;
+
+        // Clearing the pending packets fails, as their commitments cannot be queried
+        a.inject_failure("query_packet_commitments", Error::channel_send);
+
+        let mut handle = |cmd| {
+            handle_packet_cmd(
+                &link,
+                &mut should_clear_on_start,
+                CLEAR_INTERVAL,
+                &path,
+                &mut None,
+                &paused,
+                &HaltedChains::new(false, false),
+                &ScheduleWakers::default(),
+                cmd,
+            )
+            .is_ok()
+        };
+
+        assert!(handle(WorkerCmd::Pause));
+        assert!(paused.is_paused());
+
+        // Neither the clear on start, nor the one at the clear interval, nor
+        // the requested one are attempted while the worker is paused
+        assert!(handle(new_block(CLEAR_INTERVAL - 1)));
+        assert!(handle(new_block(CLEAR_INTERVAL)));
+        assert!(handle(WorkerCmd::ClearPendingPackets));
+
+        // The incoming events are still scheduled
+        assert!(handle(WorkerCmd::IbcEvents {
+            batch: EventBatch {
+                chain_id: a.id(),
+                tracking_id: TrackingId::new_static("test"),
+                height: Height::new(0, CLEAR_INTERVAL).unwrap(),
+                events: vec![],
+            }
+        }));
+        assert!(paused.is_paused());
+
+        // Resuming clears the pending packets
+        assert!(!handle(WorkerCmd::Resume));
+        assert!(!paused.is_paused());
+
+        drop(handle);
+        assert!(!should_clear_on_start);
+    }
+
+    #[test]
+    fn running_worker_clears_on_start() {
+        let (a, link, path) = setup();
+        let paused = PauseFlag::new(false);
+        let mut should_clear_on_start = true;
+
+        a.inject_failure("query_packet_commitments", Error::channel_send);
+
+        assert!(handle_packet_cmd(
+            &link,
+            &mut should_clear_on_start,
+            CLEAR_INTERVAL,
+            &path,
+            &mut None,
+            &paused,
+            &HaltedChains::new(false, false),
+            &ScheduleWakers::default(),
+            new_block(CLEAR_INTERVAL - 1),
+        )
+        .is_err());
+        assert!(!should_clear_on_start);
+    }
+
+    #[test]
+    fn clear_on_start_in_chunks() {
+        let (a, link, path) = setup();
+        let (tx, rx) = unbounded();
+        let permits = ClearPermits::new(1);
+
+        for sequence in 1..=60 {
+            a.commit_packet(
+                PortId::transfer(),
+                ChannelId::new(0),
+                Sequence::from(sequence),
+                vec![1],
+            );
+        }
+
+        let mut clear = ClearOnStart::new(path, permits.clone(), Some(tx), PauseFlag::new(false));
+
+        let step = |clear: &mut ClearOnStart| match clear.step(&link) {
+            Ok(Next::Continue) => false,
+            Ok(Next::Abort) => true,
+            Err(_) => panic!("clearing failed"),
+        };
+
+        // The pending packets are queried, then cleared in two chunks,
+        // while holding the only permit
+        assert!(!step(&mut clear));
+        assert!(permits.try_acquire().is_none());
+        assert!(!step(&mut clear));
+        assert!(step(&mut clear));
+
+        let progress: Vec<_> = rx
+            .try_iter()
+            .filter_map(|progress| match progress {
+                StartupProgress::Clearing { progress, .. } => Some(progress),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(
+            progress,
+            [0, 50, 60].map(|cleared| ClearProgress {
+                cleared,
+                pending: 60
+            })
+        );
+
+        // The permit is given back once the clearing is over
+        drop(clear);
+        assert!(permits.try_acquire().is_some());
+    }
+
+    #[test]
+    fn clear_on_start_waits_for_permit() {
+        let (a, link, path) = setup();
+        let (tx, rx) = unbounded();
+        let permits = ClearPermits::new(1);
+        let permit = permits.try_acquire().unwrap();
+
+        a.inject_failure("query_packet_commitments", Error::channel_send);
+
+        let mut clear = ClearOnStart::new(path, permits, Some(tx), PauseFlag::new(false));
+
+        // Nothing is queried until the permit is given back
+        assert!(matches!(clear.step(&link), Ok(Next::Continue)));
+        assert!(rx.try_recv().is_err());
+
+        drop(permit);
+
+        for _ in 1..MAX_CLEAR_ON_START_FAILURES {
+            assert!(matches!(clear.step(&link), Err(TaskError::Ignore(_))));
+        }
+        assert!(matches!(clear.step(&link), Err(TaskError::Fatal(_))));
+
+        assert!(matches!(rx.try_recv(), Ok(StartupProgress::Failed { .. })));
+    }
+
+    /// A packet sent on `channel-0` of `chain_A`, timing out at the given height of `chain_B`.
+    fn send_packet(sequence: u64, timeout_height: u64) -> IbcEvent {
+        let packet = IbcPacket {
+            sequence: Sequence::from(sequence),
+            source_port: PortId::transfer(),
+            source_channel: ChannelId::new(0),
+            destination_port: PortId::transfer(),
+            destination_channel: ChannelId::new(0),
+            data: vec![],
+            timeout_height: TimeoutHeight::At(Height::new(0, timeout_height).unwrap()),
+            timeout_timestamp: Timestamp::none(),
+        };
+
+        IbcEvent::SendPacket(SendPacket {
+            height: Height::new(0, 2).unwrap(),
+            packet,
+        })
+    }
+
+    #[test]
+    fn respawned_worker_confirms_txs_of_previous_worker() {
+        let (a, link, _) = setup();
+        let b = link.a_to_b.dst_chain().clone();
+
+        for sequence in 1..=3 {
+            a.commit_packet(
+                PortId::transfer(),
+                ChannelId::new(0),
+                Sequence::from(sequence),
+                vec![1],
+            );
+        }
+
+        // The previous worker submitted the packet 2 to `chain_B` before panicking
+        let mut od = OperationalData::new(
+            Height::new(0, 2).unwrap(),
+            OperationalDataTarget::Destination,
+            TrackingId::new_static("test"),
+            Duration::ZERO,
+        );
+        od.push(TransitMessage {
+            event: send_packet(2, 20),
+            msg: Any::default(),
+        });
+
+        let submitted = SubmittedTxs::new();
+        submitted.record(
+            &(b.id(), PortId::transfer(), ChannelId::new(0)),
+            PendingData {
+                original_od: od,
+                tx_hashes: TxHashes(vec![Hash::new([2; 32])]),
+                submit_time: Instant::now() - Duration::from_secs(1),
+                error_events: vec![],
+                prepended: 0,
+            },
+        );
+
+        let link = Link::new_from_opts(
+            a,
+            b.clone(),
+            LinkParameters {
+                src_port_id: PortId::transfer(),
+                src_channel_id: ChannelId::new(0),
+            },
+            true,
+        )
+        .unwrap()
+        .with_submitted_txs(submitted.clone());
+
+        let pending_packets = || {
+            let packets = link.a_to_b.pending_sequences().unwrap().packets;
+            packets.into_iter().map(u64::from).collect::<Vec<_>>()
+        };
+
+        // The packet is not relayed again while its transaction is not confirmed
+        assert_eq!(pending_packets(), [1, 3]);
+        link.a_to_b.process_pending_txs(Resubmit::No);
+        assert_eq!(submitted.len(), 1);
+        assert_eq!(pending_packets(), [1, 3]);
+
+        // Once confirmed, the transaction is forgotten
+        b.set_queried_tx_events(vec![send_packet(2, 20)]);
+        link.a_to_b.process_pending_txs(Resubmit::No);
+
+        assert!(submitted.is_empty());
+        assert_eq!(pending_packets(), [1, 2, 3]);
+    }
+
+    /// The logs written at all levels while running `f`.
+    fn capture_logs(f: impl FnOnce()) -> String {
+        #[derive(Clone, Default)]
+        struct LogBuffer(Arc<Mutex<Vec<u8>>>);
+
+        impl io::Write for LogBuffer {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let buffer = LogBuffer::default();
+        let writer = buffer.clone();
+
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::TRACE)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+
+        tracing::subscriber::with_default(subscriber, f);
+
+        let logs = buffer.0.lock().unwrap().clone();
+        String::from_utf8(logs).unwrap()
+    }
+
+    #[test]
+    fn correlation_id_recorded_from_build_to_confirmation() {
+        let (a, link, _) = setup();
+        let b = link.a_to_b.dst_chain().clone();
+
+        // With a connection delay, the messages are sent without a client update
+        let mut od = OperationalData::new(
+            Height::new(0, 2).unwrap(),
+            OperationalDataTarget::Destination,
+            TrackingId::new_static("test"),
+            Duration::from_secs(1),
+        );
+        od.push(TransitMessage {
+            event: send_packet(2, 20),
+            msg: Any::default(),
+        });
+
+        let correlation_id = od.correlation_id;
+        let field = format!("correlation_id={}", correlation_id);
+
+        // Building and broadcasting the messages
+        let logs = capture_logs(|| {
+            link.a_to_b
+                .relay_from_operational_data::<SyncSender>(od.clone())
+                .unwrap();
+        });
+
+        assert!(logs.contains(&format!("{}}}:assemble{{{}}}", field, field)));
+        assert!(logs.lines().any(|line| line.contains("relay{")
+            && line.contains(&field)
+            && line.ends_with("success")));
+        assert_eq!(b.sent_correlation_ids(), vec![correlation_id]);
+
+        // Confirming the transaction
+        let submitted = SubmittedTxs::new();
+        submitted.record(
+            &(b.id(), PortId::transfer(), ChannelId::new(0)),
+            PendingData {
+                original_od: od,
+                tx_hashes: TxHashes(vec![Hash::new([2; 32])]),
+                submit_time: Instant::now(),
+                error_events: vec![],
+                prepended: 0,
+            },
+        );
+
+        let link = Link::new_from_opts(
+            a,
+            b.clone(),
+            LinkParameters {
+                src_port_id: PortId::transfer(),
+                src_channel_id: ChannelId::new(0),
+            },
+            true,
+        )
+        .unwrap()
+        .with_submitted_txs(submitted);
+
+        b.set_queried_tx_events(vec![send_packet(2, 20)]);
+
+        let logs = capture_logs(|| {
+            link.a_to_b.process_pending_txs(Resubmit::No);
+        });
+
+        assert!(logs
+            .lines()
+            .any(|line| line.contains("transactions confirmed") && line.contains(&field)));
+    }
+
+    /// Operational data, sent without client update, relaying the packets with the given sequences.
+    fn recv_packets_od(sequences: Range<u64>) -> OperationalData {
+        let mut od = OperationalData::new(
+            Height::new(0, 2).unwrap(),
+            OperationalDataTarget::Destination,
+            TrackingId::new_static("test"),
+            Duration::from_secs(1),
+        );
+
+        for sequence in sequences {
+            od.push(TransitMessage {
+                event: send_packet(sequence, 20),
+                msg: Any::default(),
+            });
+        }
+
+        od
+    }
+
+    fn chain_error(codespace: &str, code: u32, msg_index: Option<usize>) -> IbcEvent {
+        IbcEvent::ChainError(
+            ChainErrorEvent::new(
+                format!("deliver_tx reports error: code=Err({})", code),
+                code,
+                codespace.to_string(),
+            )
+            .with_msg_index(msg_index),
+        )
+    }
+
+    #[test]
+    fn message_which_would_fail_again_is_dropped() {
+        let (_, link, _) = setup();
+        let b = link.a_to_b.dst_chain().clone();
+
+        // The packet was already received
+        b.push_tx_events(vec![chain_error("channel", 19, Some(0))]);
+
+        link.a_to_b
+            .relay_from_operational_data::<SyncSender>(recv_packets_od(1..2))
+            .unwrap();
+
+        // The message is not sent again
+        assert_eq!(b.sent_msgs().len(), 1);
+    }
+
+    #[test]
+    fn messages_of_tx_failed_for_lack_of_funds_are_kept() {
+        let (_, link, _) = setup();
+        let b = link.a_to_b.dst_chain().clone();
+
+        // The relayer lacks funds
+        b.push_tx_events(vec![chain_error("sdk", 5, None)]);
+
+        let logs = capture_logs(|| {
+            let e = link
+                .a_to_b
+                .relay_from_operational_data::<SyncSender>(recv_packets_od(1..3))
+                .unwrap_err();
+
+            assert!(matches!(e.detail(), LinkErrorDetail::Send(_)));
+        });
+
+        // The messages are neither sent again nor dropped, but the error is returned
+        assert_eq!(b.sent_msgs().len(), 2);
+        assert!(logs.contains("keeping messages queued until the error is resolved"));
+    }
+
+    fn check_tx_error(codespace: &str, code: u32) -> TxSyncResponse {
+        TxSyncResponse {
+            response: Response {
+                code: Code::Err(code),
+                data: Data::default(),
+                log: Log::from("check_tx failed"),
+                hash: Hash::new([1; 32]),
+            },
+            codespace: codespace.to_string(),
+        }
+    }
+
+    #[test]
+    fn messages_of_tx_rejected_by_check_tx_for_lack_of_funds_are_kept() {
+        let (_, link, _) = setup();
+        let b = link.a_to_b.dst_chain().clone();
+
+        // The relayer lacks funds, which `check_tx` reports in its response
+        b.push_tx_responses(vec![check_tx_error("sdk", 5)]);
+
+        let e = match link
+            .a_to_b
+            .relay_from_operational_data::<AsyncSender>(recv_packets_od(1..3))
+        {
+            Err(e) => e,
+            Ok(_) => panic!("the rejected transaction must fail"),
+        };
+
+        // The error is classified by its codespace and code, as for synchronous transactions
+        match e.detail() {
+            LinkErrorDetail::Send(e) => assert!(matches!(
+                &e.event,
+                IbcEvent::ChainError(error) if error.codespace == "sdk" && error.code == 5
+            )),
+            e => panic!("unexpected error: {}", e),
+        }
+        assert_eq!(b.sent_msgs().len(), 2);
+    }
+
+    /// A link from `a` to `b` confirming its transactions, which takes over the confirmation
+    /// of the transaction relaying the packets 1 and 2 submitted to `b` by a previous worker.
+    fn link_confirming_tx(
+        a: MockChainHandle,
+        b: MockChainHandle,
+    ) -> Link<MockChainHandle, MockChainHandle> {
+        let submitted = SubmittedTxs::new();
+        submitted.record(
+            &(b.id(), PortId::transfer(), ChannelId::new(0)),
+            PendingData {
+                original_od: recv_packets_od(1..3),
+                tx_hashes: TxHashes(vec![Hash::new([2; 32])]),
+                submit_time: Instant::now(),
+                error_events: vec![],
+                prepended: 0,
+            },
+        );
+
+        Link::new_from_opts(
+            a,
+            b,
+            LinkParameters {
+                src_port_id: PortId::transfer(),
+                src_channel_id: ChannelId::new(0),
+            },
+            true,
+        )
+        .unwrap()
+        .with_submitted_txs(submitted)
+    }
+
+    #[test]
+    fn message_failed_in_deliver_tx_is_dropped() {
+        let (a, link, _) = setup();
+        let b = link.a_to_b.dst_chain().clone();
+        let link = link_confirming_tx(a, b.clone());
+
+        // The packet 2 was already received
+        b.set_queried_tx_events(vec![chain_error("channel", 19, Some(1))]);
+
+        let logs = capture_logs(|| {
+            link.a_to_b.process_pending_txs(Resubmit::No);
+        });
+
+        // Only the message which failed is dropped, the other is left for the packets to be cleared
+        assert!(logs
+            .lines()
+            .any(|line| line.contains("dropping message for") && line.contains("seq:2,")));
+        assert!(link.a_to_b.dst_operational_data.is_empty());
+    }
+
+    #[test]
+    fn messages_of_tx_failed_in_deliver_tx_for_lack_of_funds_are_kept() {
+        let (a, link, _) = setup();
+        let b = link.a_to_b.dst_chain().clone();
+        let link = link_confirming_tx(a, b.clone());
+
+        // The relayer lacks funds
+        b.set_queried_tx_events(vec![chain_error("sdk", 5, None)]);
+
+        link.a_to_b.process_pending_txs(Resubmit::No);
+
+        // The messages are queued again, to be relayed once the error is resolved
+        let queued = link.a_to_b.dst_operational_data.clone_vec();
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].batch.len(), 2);
+    }
+
+    /// Operational data relaying the message built from the given event, whose
+    /// connection delay has elapsed, so that it is sent without client update.
+    fn ready_od(target: OperationalDataTarget, event: IbcEvent) -> OperationalData {
+        let mut od = OperationalData::new(
+            Height::new(0, 2).unwrap(),
+            target,
+            TrackingId::new_static("test"),
+            Duration::from_secs(1),
+        );
+        od.set_scheduled_time(Instant::now() - Duration::from_secs(10));
+        od.set_update_height(Height::new(0, 2).unwrap());
+
+        od.push(TransitMessage {
+            event,
+            msg: Any::default(),
+        });
+
+        od
+    }
+
+    /// An acknowledgement written on `chain_A` for a packet sent on `channel-0` of `chain_B`.
+    fn write_ack(sequence: u64) -> IbcEvent {
+        let packet = IbcPacket {
+            sequence: Sequence::from(sequence),
+            source_port: PortId::transfer(),
+            source_channel: ChannelId::new(0),
+            destination_port: PortId::transfer(),
+            destination_channel: ChannelId::new(0),
+            data: vec![],
+            timeout_height: TimeoutHeight::Never,
+            timeout_timestamp: Timestamp::none(),
+        };
+
+        IbcEvent::WriteAcknowledgement(WriteAcknowledgement {
+            height: Height::new(0, 2).unwrap(),
+            packet,
+            ack: vec![1],
+        })
+    }
+
+    /// Signals that a transaction sent to `chain_A` started, in
+    /// [`relaying_to_destination_does_not_wait_on_source`].
+    static SOURCE_TX_STARTED: Lazy<(Sender<()>, Receiver<()>)> = Lazy::new(unbounded);
+
+    /// Releases the transaction sent to `chain_A`, in
+    /// [`relaying_to_destination_does_not_wait_on_source`].
+    static SOURCE_TX_RELEASED: Lazy<(Sender<()>, Receiver<()>)> = Lazy::new(unbounded);
+
+    /// Whether the transactions sent to `chain_A` are no longer stuck, in
+    /// [`relaying_to_destination_does_not_wait_on_source`].
+    static SOURCE_TX_UNSTUCK: AtomicBool = AtomicBool::new(false);
+
+    /// Waits until `chain` was sent `count` messages.
+    fn wait_sent_msgs(chain: &MockChainHandle, count: usize) {
+        let deadline = Instant::now() + Duration::from_secs(30);
+
+        while chain.sent_msgs().len() < count {
+            assert!(
+                Instant::now() < deadline,
+                "messages not sent to {}",
+                chain.id()
+            );
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn relaying_to_destination_does_not_wait_on_source() {
+        const ROUNDS: u64 = 3;
+
+        let (a, link, path) = setup();
+        let b = link.a_to_b.dst_chain().clone();
+        let paused = PauseFlag::new(false);
+        let wakers = ScheduleWakers::default();
+
+        for chain in [&a, &b] {
+            chain.set_config(get_basic_chain_config(chain.id().as_str()));
+            chain.set_latest(Height::new(0, 10).unwrap(), Timestamp::now());
+        }
+
+        // Every transaction sent to `chain_A` is stuck until the end of the test
+        a.inject_failure("send_messages_and_wait_check_tx", || {
+            if !SOURCE_TX_UNSTUCK.load(Ordering::SeqCst) {
+                let _ = SOURCE_TX_STARTED.0.send(());
+                let _ = SOURCE_TX_RELEASED.1.recv();
+            }
+            Error::channel_send()
+        });
+
+        let link = Arc::new(link);
+        link.a_to_b
+            .src_operational_data
+            .push_back(ready_od(OperationalDataTarget::Source, send_packet(1, 5)));
+
+        let _workers = [
+            OperationalDataTarget::Source,
+            OperationalDataTarget::Destination,
+        ]
+        .map(|target| {
+            spawn_packet_worker(
+                path.clone(),
+                link.clone(),
+                target,
+                Resubmit::No,
+                paused.clone(),
+                HaltedChains::new(false, false),
+                wakers.clone(),
+            )
+        });
+
+        SOURCE_TX_STARTED
+            .1
+            .recv_timeout(Duration::from_secs(30))
+            .expect("relaying to chain_A did not start");
+
+        for sequence in 1..=ROUNDS {
+            // The acknowledgement is still to be relayed to `chain_B`
+            b.commit_packet(
+                PortId::transfer(),
+                ChannelId::new(0),
+                Sequence::from(sequence),
+                vec![1],
+            );
+
+            link.a_to_b.dst_operational_data.push_back(ready_od(
+                OperationalDataTarget::Destination,
+                write_ack(sequence),
+            ));
+
+            // Scheduling the events wakes the tasks relaying them, without relaying them
+            assert!(handle_packet_cmd(
+                &link,
+                &mut false,
+                CLEAR_INTERVAL,
+                &path,
+                &mut None,
+                &paused,
+                &HaltedChains::new(false, false),
+                &wakers,
+                WorkerCmd::IbcEvents {
+                    batch: EventBatch {
+                        chain_id: a.id(),
+                        tracking_id: TrackingId::new_static("test"),
+                        height: Height::new(0, 2).unwrap(),
+                        events: vec![],
+                    },
+                },
+            )
+            .is_ok());
+
+            // The acknowledgement is relayed while relaying to `chain_A` is still stuck
+            wait_sent_msgs(&b, sequence as usize);
+        }
+
+        assert!(a.sent_msgs().is_empty());
+
+        SOURCE_TX_UNSTUCK.store(true, Ordering::SeqCst);
+        let _ = SOURCE_TX_RELEASED.0.send(());
+    }
+
+    /// Benchmarks the end-to-end latency of a ping-pong workload on mock chains, where each
+    /// round relays a message to each chain, e.g. a timeout to `chain_A` and an acknowledgement
+    /// to `chain_B`, with the tasks relaying to each chain against a single task relaying to
+    /// both chains in turn, as before they were split.
+    ///
+    /// Run with `cargo test -p ibc-relayer --lib ping_pong -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn ping_pong_latency() {
+        const ROUNDS: u64 = 20;
+        const TX_LATENCY: Duration = Duration::from_millis(50);
+
+        /// Loads the chains of a new link with the packets of all the rounds.
+        fn ping_pong_setup() -> (
+            MockChainHandle,
+            MockChainHandle,
+            Arc<Link<MockChainHandle, MockChainHandle>>,
+            Packet,
+        ) {
+            let (a, link, path) = setup();
+            let b = link.a_to_b.dst_chain().clone();
+
+            for chain in [&a, &b] {
+                chain.set_config(get_basic_chain_config(chain.id().as_str()));
+                chain.set_latest(Height::new(0, 10).unwrap(), Timestamp::now());
+                chain.set_tx_latency(TX_LATENCY);
+            }
+
+            // The client of `chain_B` on `chain_A` is at height 10, past the timeouts
+            let client_height = Height::new(0, 10).unwrap();
+            a.add_consensus_state(
+                ClientId::new(ClientType::Tendermint, 0).unwrap(),
+                client_height,
+                AnyConsensusState::from(MockConsensusState::new(MockHeader::new(client_height))),
+            );
+
+            for sequence in 1..=ROUNDS {
+                for chain in [&a, &b] {
+                    chain.commit_packet(
+                        PortId::transfer(),
+                        ChannelId::new(0),
+                        Sequence::from(sequence),
+                        vec![1],
+                    );
+                }
+            }
+
+            (a, b, Arc::new(link), path)
+        }
+
+        fn push_round(link: &Link<MockChainHandle, MockChainHandle>, sequence: u64) {
+            link.a_to_b.src_operational_data.push_back(ready_od(
+                OperationalDataTarget::Source,
+                send_packet(sequence, 5),
+            ));
+            link.a_to_b.dst_operational_data.push_back(ready_od(
+                OperationalDataTarget::Destination,
+                write_ack(sequence),
+            ));
+        }
+
+        fn mean(latencies: &[Duration]) -> Duration {
+            latencies.iter().sum::<Duration>() / latencies.len() as u32
+        }
+
+        // A single task relays to `chain_A`, then to `chain_B`
+        let (a, b, link, path) = ping_pong_setup();
+        let halted = HaltedChains::new(false, false);
+        let mut serial = vec![];
+
+        for sequence in 1..=ROUNDS {
+            let start = Instant::now();
+            push_round(&link, sequence);
+
+            for target in [
+                OperationalDataTarget::Source,
+                OperationalDataTarget::Destination,
+            ] {
+                assert!(
+                    handle_execute_schedule_for(&link, &path, target, Resubmit::No, &halted)
+                        .is_ok()
+                );
+            }
+
+            wait_sent_msgs(&a, sequence as usize);
+            wait_sent_msgs(&b, sequence as usize);
+            serial.push(start.elapsed());
+        }
+
+        // A task relays to each chain
+        let (a, b, link, path) = ping_pong_setup();
+        let wakers = ScheduleWakers::default();
+        let mut concurrent = vec![];
+
+        let _workers = [
+            OperationalDataTarget::Source,
+            OperationalDataTarget::Destination,
+        ]
+        .map(|target| {
+            spawn_packet_worker(
+                path.clone(),
+                link.clone(),
+                target,
+                Resubmit::No,
+                PauseFlag::new(false),
+                HaltedChains::new(false, false),
+                wakers.clone(),
+            )
+        });
+
+        for sequence in 1..=ROUNDS {
+            let start = Instant::now();
+            push_round(&link, sequence);
+            wakers.wake();
+
+            wait_sent_msgs(&a, sequence as usize);
+            wait_sent_msgs(&b, sequence as usize);
+            concurrent.push(start.elapsed());
+        }
+
+        println!(
+            "ping-pong latency over {} rounds, with {:?} per transaction: \
+            {:?} relaying to both chains in turn, {:?} relaying to each chain concurrently",
+            ROUNDS,
+            TX_LATENCY,
+            mean(&serial),
+            mean(&concurrent)
+        );
+
+        assert!(mean(&concurrent) < mean(&serial));
+    }
+
+    #[test]
+    fn timeouts_relayed_while_destination_halted() {
+        let (a, link, path) = setup();
+        let b = link.a_to_b.dst_chain().clone();
+        let paused = PauseFlag::new(false);
+        let halted = HaltedChains::new(false, false);
+        let deferred = link.a_to_b.deferred_timeouts();
+
+        for chain in [&a, &b] {
+            chain.set_config(get_basic_chain_config(chain.id().as_str()));
+            chain.set_latest(Height::new(0, 10).unwrap(), Timestamp::now());
+        }
+
+        // The client of `chain_B` on `chain_A` is at height 10
+        let client_height = Height::new(0, 10).unwrap();
+        a.add_consensus_state(
+            ClientId::new(ClientType::Tendermint, 0).unwrap(),
+            client_height,
+            AnyConsensusState::from(MockConsensusState::new(MockHeader::new(client_height))),
+        );
+
+        for sequence in 1..=2 {
+            a.commit_packet(
+                PortId::transfer(),
+                ChannelId::new(0),
+                Sequence::from(sequence),
+                vec![1],
+            );
+        }
+
+        let handle = |cmd| {
+            handle_packet_cmd(
+                &link,
+                &mut false,
+                CLEAR_INTERVAL,
+                &path,
+                &mut None,
+                &paused,
+                &halted,
+                &ScheduleWakers::default(),
+                cmd,
+            )
+        };
+
+        let execute =
+            |target| handle_execute_schedule_for(&link, &path, target, Resubmit::No, &halted);
+
+        assert!(handle(WorkerCmd::ChainHalted {
+            chain_id: b.id(),
+            halted: true,
+        })
+        .is_ok());
+
+        assert!(halted.is_halted(OperationalDataTarget::Destination));
+        assert!(!halted.is_halted(OperationalDataTarget::Source));
+        assert!(!paused.is_paused());
+
+        // The acknowledgement is still to be relayed to `chain_B`
+        b.commit_packet(
+            PortId::transfer(),
+            ChannelId::new(0),
+            Sequence::from(1),
+            vec![1],
+        );
+
+        link.a_to_b
+            .src_operational_data
+            .push_back(ready_od(OperationalDataTarget::Source, send_packet(1, 5)));
+        link.a_to_b
+            .dst_operational_data
+            .push_back(ready_od(OperationalDataTarget::Destination, write_ack(1)));
+
+        // The timeout is relayed to `chain_A`, but nothing is submitted to `chain_B`
+        assert!(execute(OperationalDataTarget::Source).is_ok());
+        assert!(execute(OperationalDataTarget::Destination).is_ok());
+
+        assert_eq!(a.sent_msgs().len(), 1);
+        assert!(b.sent_msgs().is_empty());
+        assert_eq!(link.a_to_b.dst_operational_data.len(), 1);
+
+        // Once `chain_B` is also unreachable, the timeouts of the packets scheduled
+        // to be received on it are deferred, until their proofs can be queried from it
+        link.a_to_b.dst_operational_data.push_back(ready_od(
+            OperationalDataTarget::Destination,
+            send_packet(2, 5),
+        ));
+
+        b.inject_failure("query_application_status", Error::channel_send);
+
+        assert!(execute(OperationalDataTarget::Destination).is_err());
+        assert_eq!(deferred.len(), 1);
+        assert!(deferred.contains(Sequence::from(2)));
+        assert!(b.sent_msgs().is_empty());
+
+        // Once `chain_B` resumes, it is relayed to again
+        b.clear_failure("query_application_status");
+
+        let _ = handle(WorkerCmd::ChainHalted {
+            chain_id: b.id(),
+            halted: false,
+        });
+
+        assert!(!halted.is_halted(OperationalDataTarget::Destination));
+    }
+
+    #[test]
+    fn timeouts_deferred_while_destination_unreachable() {
+        let (a, link, path) = setup();
+        let b = link.a_to_b.dst_chain().clone();
+        let paused = PauseFlag::new(false);
+        let deferred = link.a_to_b.deferred_timeouts();
+
+        // The client of `chain_B` on `chain_A` is at height 10
+        let client_height = Height::new(0, 10).unwrap();
+        a.add_consensus_state(
+            ClientId::new(ClientType::Tendermint, 0).unwrap(),
+            client_height,
+            AnyConsensusState::from(MockConsensusState::new(MockHeader::new(client_height))),
+        );
+
+        for sequence in 1..=2 {
+            a.commit_packet(
+                PortId::transfer(),
+                ChannelId::new(0),
+                Sequence::from(sequence),
+                vec![1],
+            );
+        }
+
+        b.inject_failure("query_application_status", Error::channel_send);
+
+        // Only the packet timing out before the height of the client is deferred
+        assert!(handle_packet_cmd(
+            &link,
+            &mut false,
+            CLEAR_INTERVAL,
+            &path,
+            &mut None,
+            &paused,
+            &HaltedChains::new(false, false),
+            &ScheduleWakers::default(),
+            WorkerCmd::IbcEvents {
+                batch: EventBatch {
+                    chain_id: a.id(),
+                    tracking_id: TrackingId::new_static("test"),
+                    height: Height::new(0, 2).unwrap(),
+                    events: vec![send_packet(1, 5), send_packet(2, 20)],
+                },
+            },
+        )
+        .is_err());
+
+        assert_eq!(deferred.len(), 1);
+        assert!(deferred.contains(Sequence::from(1)));
+
+        // The timeout is kept while `chain_B` is unreachable
+        link.a_to_b.refresh_schedule().unwrap();
+        assert_eq!(deferred.len(), 1);
+
+        // Once `chain_B` is back, the timeout is kept until its proof can be built
+        b.clear_failure("query_application_status");
+        b.set_latest(Height::new(0, 20).unwrap(), Timestamp::now());
+
+        assert!(link.a_to_b.refresh_schedule().is_err());
+        assert_eq!(deferred.len(), 1);
+
+        // The timeout is dropped once the packet timed out through another relayer
+        a.delete_packet_commitment(PortId::transfer(), ChannelId::new(0), Sequence::from(1));
+
+        link.a_to_b.refresh_schedule().unwrap();
+        assert!(deferred.is_empty());
+    }
+
+    #[cfg(feature = "telemetry")]
+    #[test]
+    fn send_times_are_taken_from_new_block_events() {
+        use super::packet_latency_metrics;
+        use crate::worker::latency::PacketSendTimes;
+
+        let (a, _, path) = setup();
+        let send_times = PacketSendTimes::new();
+        let mut last_block = None;
+
+        let batch = |height| WorkerCmd::IbcEvents {
+            batch: EventBatch {
+                chain_id: a.id(),
+                tracking_id: TrackingId::new_static("test"),
+                height: Height::new(0, height).unwrap(),
+                events: vec![send_packet(height, 100)],
+            },
+        };
+
+        // The time of the block of the batch is not known without its `NewBlock` event
+        packet_latency_metrics(&path, &send_times, &mut last_block, &batch(1));
+        assert_eq!(
+            send_times.len(&a.id(), &PortId::transfer(), &ChannelId::new(0)),
+            0
+        );
+
+        let height = Height::new(0, 2).unwrap();
+        let new_block = WorkerCmd::NewBlock {
+            height,
+            new_block: NewBlock::new(height).with_time(Timestamp::now()),
+        };
+
+        packet_latency_metrics(&path, &send_times, &mut last_block, &new_block);
+        packet_latency_metrics(&path, &send_times, &mut last_block, &batch(2));
+        assert_eq!(
+            send_times.len(&a.id(), &PortId::transfer(), &ChannelId::new(0)),
+            1
+        );
+
+        // Nor is the time of a later block
+        packet_latency_metrics(&path, &send_times, &mut last_block, &batch(3));
+        assert_eq!(
+            send_times.len(&a.id(), &PortId::transfer(), &ChannelId::new(0)),
+            1
+        );
+    }
+}
@@ -0,0 +1,423 @@
+//! Tests of the relayer logic against in-memory chains, as a downstream
+//! crate would write them with the `mocks` feature.
+
+use core::str::FromStr;
+use core::time::Duration;
+
+use ibc::clients::ics07_tendermint::client_state::{AllowUpdate, ClientState};
+use ibc::core::ics02_client::client_state::AnyClientState;
+use ibc::core::ics02_client::client_type::ClientType;
+use ibc::core::ics02_client::trust_threshold::TrustThreshold;
+use ibc::core::ics03_connection::connection::{
+    ConnectionEnd, Counterparty as ConnectionCounterparty, State as ConnectionState,
+};
+use ibc::core::ics03_connection::version::get_compatible_versions;
+use ibc::core::ics04_channel::channel::{
+    ChannelEnd, Counterparty as ChannelCounterparty, Order, State as ChannelState,
+};
+use ibc::core::ics04_channel::events::SendPacket;
+use ibc::core::ics04_channel::packet::{Packet, Sequence};
+use ibc::core::ics04_channel::Version;
+use ibc::core::ics23_commitment::commitment::CommitmentPrefix;
+use ibc::core::ics23_commitment::specs::ProofSpecs;
+use ibc::core::ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId};
+use ibc::events::IbcEvent;
+use ibc::mock::client_state::MockConsensusState;
+use ibc::mock::header::MockHeader;
+use ibc::timestamp::Timestamp;
+use ibc::Height;
+use ibc_proto::google::protobuf::Any;
+use ibc_relayer::bootstrap::{bootstrap_channel, ClientReport, EndsReport, StepOutcome};
+use ibc_relayer::chain::handle::mock::MockChainHandle;
+use ibc_relayer::chain::handle::ChainHandle;
+use ibc_relayer::chain::tracking::{TrackedMsgs, TrackingId};
+use ibc_relayer::config::RelayMode;
+use ibc_relayer::error::Error;
+use ibc_relayer::event::monitor::EventBatch;
+use ibc_relayer::link::{Link, LinkParameters, PendingSequences};
+use ibc_relayer::transfer::{check_timeout_timestamp, check_transfer_path, TransferErrorDetail};
+
+fn client_state(chain_id: &ChainId) -> AnyClientState {
+    let client_state = ClientState::new(
+        chain_id.clone(),
+        TrustThreshold::ONE_THIRD,
+        Duration::from_secs(14 * 24 * 3600),
+        Duration::from_secs(21 * 24 * 3600),
+        Duration::from_secs(5),
+        Height::new(chain_id.version(), 10).unwrap(),
+        ProofSpecs::default(),
+        vec![],
+        AllowUpdate {
+            after_expiry: true,
+            after_misbehaviour: true,
+        },
+    )
+    .unwrap();
+
+    AnyClientState::Tendermint(client_state)
+}
+
+/// Loads `chain` with an open transfer channel `channel-0`, over the connection
+/// `connection-0` and the client `07-tendermint-0` tracking `counterparty`.
+fn open_channel(chain: &MockChainHandle, counterparty: &MockChainHandle) {
+    let client_id = ClientId::new(ClientType::Tendermint, 0).unwrap();
+    let connection_id = ConnectionId::new(0);
+
+    chain.add_client(client_id.clone(), client_state(&counterparty.id()));
+
+    chain.add_connection(
+        connection_id.clone(),
+        ConnectionEnd::new(
+            ConnectionState::Open,
+            client_id.clone(),
+            ConnectionCounterparty::new(
+                client_id,
+                Some(connection_id.clone()),
+                CommitmentPrefix::try_from(b"ibc".to_vec()).unwrap(),
+            ),
+            get_compatible_versions(),
+            Duration::ZERO,
+        ),
+    );
+
+    chain.add_channel(
+        PortId::transfer(),
+        ChannelId::new(0),
+        ChannelEnd::new(
+            ChannelState::Open,
+            Order::Unordered,
+            ChannelCounterparty::new(PortId::transfer(), Some(ChannelId::new(0))),
+            vec![connection_id],
+            Version::ics20(),
+        ),
+    );
+}
+
+fn chains() -> (MockChainHandle, MockChainHandle) {
+    let a = MockChainHandle::new(ChainId::from_str("chain-a-1").unwrap());
+    let b = MockChainHandle::new(ChainId::from_str("chain-b-1").unwrap());
+
+    open_channel(&a, &b);
+    open_channel(&b, &a);
+
+    (a, b)
+}
+
+fn seqs(sequences: &[u64]) -> Vec<Sequence> {
+    sequences.iter().copied().map(Sequence::from).collect()
+}
+
+/// The link relaying the transfer channel `channel-0` from `a` to `b`, in the given mode.
+fn link(
+    a: &MockChainHandle,
+    b: &MockChainHandle,
+    relay_mode: RelayMode,
+) -> Link<MockChainHandle, MockChainHandle> {
+    Link::new_from_opts(
+        a.clone(),
+        b.clone(),
+        LinkParameters {
+            src_port_id: PortId::transfer(),
+            src_channel_id: ChannelId::new(0),
+        },
+        false,
+    )
+    .unwrap()
+    .with_relay_mode(relay_mode)
+}
+
+/// A `SendPacket` event from `a`, for a packet which never times out.
+fn send_packet(a: &MockChainHandle, sequence: u64) -> IbcEvent {
+    IbcEvent::SendPacket(SendPacket {
+        height: a.query_latest_height().unwrap(),
+        packet: Packet {
+            sequence: Sequence::from(sequence),
+            source_port: PortId::transfer(),
+            source_channel: ChannelId::new(0),
+            destination_port: PortId::transfer(),
+            destination_channel: ChannelId::new(0),
+            data: vec![1],
+            ..Packet::default()
+        },
+    })
+}
+
+#[test]
+fn transfer_path_validation() {
+    let (a, b) = chains();
+    let (port_id, channel_id) = (PortId::transfer(), ChannelId::new(0));
+
+    check_transfer_path(&a, &b.id(), &port_id, &channel_id).unwrap();
+
+    let other_chain_id = ChainId::from_str("chain-c-1").unwrap();
+    let err = check_transfer_path(&a, &other_chain_id, &port_id, &channel_id).unwrap_err();
+    assert!(matches!(
+        err.detail(),
+        TransferErrorDetail::DestinationChainMismatch(_)
+    ));
+
+    let err = check_transfer_path(&a, &b.id(), &port_id, &ChannelId::new(1)).unwrap_err();
+    assert!(matches!(
+        err.detail(),
+        TransferErrorDetail::ChannelNotOpen(_)
+    ));
+
+    a.inject_failure("query_client_state", Error::channel_send);
+    let err = check_transfer_path(&a, &b.id(), &port_id, &channel_id).unwrap_err();
+    assert!(matches!(err.detail(), TransferErrorDetail::Relayer(_)));
+}
+
+#[test]
+fn transfer_timeout_timestamp_validation() {
+    let (a, b) = chains();
+    let (port_id, channel_id) = (PortId::transfer(), ChannelId::new(0));
+
+    let (client_id, client_state) =
+        check_transfer_path(&a, &b.id(), &port_id, &channel_id).unwrap();
+
+    let height = client_state.latest_height();
+    let consensus_timestamp = Timestamp::from_nanoseconds(1_725_192_000_000_000_000).unwrap();
+    a.add_consensus_state(
+        client_id.clone(),
+        height,
+        MockConsensusState::new(MockHeader::new(height).with_timestamp(consensus_timestamp)).into(),
+    );
+
+    let later = (consensus_timestamp + Duration::from_secs(1)).unwrap();
+    check_timeout_timestamp(&a, &client_id, &client_state, later).unwrap();
+
+    // The packets would time out as soon as sent
+    let err =
+        check_timeout_timestamp(&a, &client_id, &client_state, consensus_timestamp).unwrap_err();
+    assert!(matches!(
+        err.detail(),
+        TransferErrorDetail::TimeoutTimestampElapsed(_)
+    ));
+}
+
+#[test]
+fn relay_path_pending_sequences() {
+    let (a, b) = chains();
+    let (port_id, channel_id) = (PortId::transfer(), ChannelId::new(0));
+
+    // Packets 1 to 3 sent from `a`, of which `b` received the first one
+    for sequence in 1..=3 {
+        a.commit_packet(
+            port_id.clone(),
+            channel_id.clone(),
+            Sequence::from(sequence),
+            vec![1],
+        );
+    }
+    b.receive_packet(port_id.clone(), channel_id.clone(), Sequence::from(1));
+
+    // Packet 1 sent from `b`, received and acknowledged by `a`
+    b.commit_packet(
+        port_id.clone(),
+        channel_id.clone(),
+        Sequence::from(1),
+        vec![1],
+    );
+    a.receive_packet(port_id.clone(), channel_id.clone(), Sequence::from(1));
+    a.write_acknowledgement(
+        port_id.clone(),
+        channel_id.clone(),
+        Sequence::from(1),
+        vec![1],
+    );
+
+    let link = Link::new_from_opts(
+        a.clone(),
+        b.clone(),
+        LinkParameters {
+            src_port_id: port_id.clone(),
+            src_channel_id: channel_id.clone(),
+        },
+        false,
+    )
+    .unwrap();
+
+    let pending = link.a_to_b.pending_sequences().unwrap();
+    let height = a.query_latest_height().unwrap();
+
+    assert_eq!(
+        pending,
+        PendingSequences {
+            packets: seqs(&[2, 3]),
+            packets_height: height,
+            acks: seqs(&[1]),
+            acks_height: height,
+        }
+    );
+
+    // Once `b` received the acknowledgement, it is no longer pending
+    b.delete_packet_commitment(port_id, channel_id, Sequence::from(1));
+    assert!(link.a_to_b.pending_sequences().unwrap().acks.is_empty());
+
+    a.inject_failure("query_packet_commitments", Error::channel_send);
+    assert!(link.a_to_b.pending_sequences().is_err());
+}
+
+#[test]
+fn skipped_sequences_are_not_pending() {
+    let (a, b) = chains();
+    let (port_id, channel_id) = (PortId::transfer(), ChannelId::new(0));
+
+    // Packets 1 and 2 sent from `a`, which then skipped the sequences 3 and 4
+    // before sending packet 5, which `b` received and acknowledged
+    for sequence in [1, 2, 5] {
+        a.commit_packet(
+            port_id.clone(),
+            channel_id.clone(),
+            Sequence::from(sequence),
+            vec![1],
+        );
+    }
+    b.receive_packet(port_id.clone(), channel_id.clone(), Sequence::from(5));
+    a.delete_packet_commitment(port_id, channel_id, Sequence::from(5));
+
+    let link = link(&a, &b, RelayMode::All);
+
+    // Checking for the skipped sequences does not fail the query,
+    // even though the mock cannot find any `send_packet` event
+    let pending = link.a_to_b.pending_sequences().unwrap();
+    assert_eq!(pending.packets, seqs(&[1, 2]));
+
+    a.inject_failure("query_txs", Error::channel_send);
+    assert_eq!(
+        link.a_to_b.pending_sequences().unwrap().packets,
+        seqs(&[1, 2])
+    );
+}
+
+#[test]
+fn acks_only_mode_never_builds_recv_packets() {
+    let (a, b) = chains();
+    let batch = || EventBatch {
+        chain_id: a.id(),
+        tracking_id: TrackingId::new_static("test"),
+        height: a.query_latest_height().unwrap(),
+        events: vec![send_packet(&a, 1)],
+    };
+
+    // Packet 1 sent from `a`, which `b` did not receive yet
+    a.commit_packet(
+        PortId::transfer(),
+        ChannelId::new(0),
+        Sequence::from(1),
+        vec![1],
+    );
+
+    // Building the `MsgRecvPacket` needs packet proofs, which the mock does not support
+    assert!(link(&a, &b, RelayMode::All)
+        .a_to_b
+        .update_schedule(batch())
+        .is_err());
+
+    let link = link(&a, &b, RelayMode::AcksOnly);
+    link.a_to_b.update_schedule(batch()).unwrap();
+    assert!(link.a_to_b.dst_operational_data.is_empty());
+
+    // Nor when clearing the packets sent from `a` that `b` did not receive yet
+    a.set_queried_tx_events(vec![send_packet(&a, 1)]);
+
+    link.a_to_b.schedule_packet_clearing(None).unwrap();
+    assert!(link.a_to_b.dst_operational_data.is_empty());
+}
+
+#[test]
+fn recv_only_mode_has_no_pending_acks() {
+    let (a, b) = chains();
+    let (port_id, channel_id) = (PortId::transfer(), ChannelId::new(0));
+
+    // Packet 1 sent from `b`, received and acknowledged by `a`
+    b.commit_packet(
+        port_id.clone(),
+        channel_id.clone(),
+        Sequence::from(1),
+        vec![1],
+    );
+    a.receive_packet(port_id.clone(), channel_id.clone(), Sequence::from(1));
+    a.write_acknowledgement(port_id, channel_id, Sequence::from(1), vec![1]);
+
+    let pending = link(&a, &b, RelayMode::All)
+        .a_to_b
+        .pending_sequences()
+        .unwrap();
+    assert_eq!(pending.acks, seqs(&[1]));
+
+    let pending = link(&a, &b, RelayMode::RecvOnly)
+        .a_to_b
+        .pending_sequences()
+        .unwrap();
+    assert!(pending.acks.is_empty());
+}
+
+#[test]
+fn bootstrap_reuses_open_channel() {
+    let (a, b) = chains();
+    let client_id = ClientId::new(ClientType::Tendermint, 0).unwrap();
+
+    // The clients are healthy, with a fresh consensus state at their latest height
+    for (chain, counterparty) in [(&a, &b), (&b, &a)] {
+        let height = Height::new(counterparty.id().version(), 10).unwrap();
+        chain.add_consensus_state(
+            client_id.clone(),
+            height,
+            MockConsensusState::new(MockHeader::new(height)).into(),
+        );
+    }
+
+    let (_, report) = bootstrap_channel(&a, &b).unwrap();
+
+    assert_eq!(
+        report.a_client,
+        ClientReport {
+            chain_id: a.id(),
+            client_id: client_id.clone(),
+            outcome: StepOutcome::Reused,
+        }
+    );
+    assert_eq!(
+        report.b_client,
+        ClientReport {
+            chain_id: b.id(),
+            client_id,
+            outcome: StepOutcome::Reused,
+        }
+    );
+    assert_eq!(
+        report.connection,
+        EndsReport {
+            a: ConnectionId::new(0),
+            b: ConnectionId::new(0),
+            outcome: StepOutcome::Reused,
+        }
+    );
+    assert_eq!(
+        report.channel,
+        EndsReport {
+            a: ChannelId::new(0),
+            b: ChannelId::new(0),
+            outcome: StepOutcome::Reused,
+        }
+    );
+    assert!(report.probe.is_none());
+}
+
+#[test]
+fn sent_messages_are_recorded() {
+    let (a, _) = chains();
+
+    let msg = Any {
+        type_url: "/test.Msg".to_string(),
+        value: vec![1, 2, 3],
+    };
+
+    let events = a
+        .send_messages_and_wait_commit(TrackedMsgs::new_static(vec![msg.clone()], "test"))
+        .unwrap();
+
+    assert!(events.is_empty());
+    assert_eq!(a.sent_msgs(), vec![msg]);
+}
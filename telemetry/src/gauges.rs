@@ -0,0 +1,135 @@
+use dashmap::DashMap;
+use opentelemetry::KeyValue;
+
+use crate::labels::LabelFilter;
+
+/// How the latest values of the series of a gauge are combined when these series
+/// are recorded in a single one, because the labels which tell them apart are not emitted.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Aggregation {
+    Sum,
+    Min,
+    Max,
+}
+
+impl Aggregation {
+    fn apply(self, a: f64, b: f64) -> f64 {
+        match self {
+            Self::Sum => a + b,
+            Self::Min => a.min(b),
+            Self::Max => a.max(b),
+        }
+    }
+}
+
+/// The labels of a series, as recorded, before the labels which are not emitted are removed.
+type Labels = Vec<(String, String)>;
+
+/// The latest value of each series of the gauges, used to aggregate the values of the
+/// series which are recorded in the same one when some of their labels are not emitted.
+#[derive(Debug, Default)]
+pub struct GaugeValues(DashMap<(&'static str, Labels), f64>);
+
+impl GaugeValues {
+    /// Records the given value as the latest one of the series of the given gauge with
+    /// the given labels, and returns the value to record in the series with the emitted
+    /// labels: the aggregate of the latest values of all the series it covers.
+    pub fn record(
+        &self,
+        gauge: &'static str,
+        labels: &[KeyValue],
+        value: f64,
+        filter: &LabelFilter,
+        aggregation: Aggregation,
+    ) -> f64 {
+        let labels: Labels = labels
+            .iter()
+            .map(|kv| (kv.key.as_str().to_string(), kv.value.as_str().into_owned()))
+            .collect();
+
+        let emitted = |labels: &Labels| {
+            labels
+                .iter()
+                .filter(|(key, _)| filter.emits(key))
+                .cloned()
+                .collect::<Labels>()
+        };
+
+        let series = emitted(&labels);
+
+        self.0.insert((gauge, labels), value);
+
+        if filter.is_full() {
+            return value;
+        }
+
+        self.0
+            .iter()
+            .filter(|v| v.key().0 == gauge && emitted(&v.key().1) == series)
+            .map(|v| *v.value())
+            .reduce(|a, b| aggregation.apply(a, b))
+            .unwrap_or(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::labels::LabelPreset;
+
+    fn labels(chain: &str, client: &str) -> [KeyValue; 2] {
+        [
+            KeyValue::new("chain", chain.to_string()),
+            KeyValue::new("client_id", client.to_string()),
+        ]
+    }
+
+    #[test]
+    fn values_of_merged_series_are_aggregated() {
+        let gauges = GaugeValues::default();
+        let filter = LabelFilter::from_preset(LabelPreset::PerChain);
+
+        let record = |chain, client, value, aggregation| {
+            gauges.record("age", &labels(chain, client), value, &filter, aggregation)
+        };
+
+        assert_eq!(record("ibc-0", "client-0", 10.0, Aggregation::Max), 10.0);
+        assert_eq!(record("ibc-0", "client-1", 5.0, Aggregation::Max), 10.0);
+        assert_eq!(record("ibc-0", "client-1", 20.0, Aggregation::Max), 20.0);
+
+        // The series of another chain are not merged with these
+        assert_eq!(record("ibc-1", "client-0", 1.0, Aggregation::Max), 1.0);
+
+        // Nor are the series of another gauge
+        assert_eq!(
+            gauges.record(
+                "remaining",
+                &labels("ibc-0", "client-0"),
+                3.0,
+                &filter,
+                Aggregation::Min
+            ),
+            3.0
+        );
+    }
+
+    #[test]
+    fn values_of_full_series_are_kept() {
+        let gauges = GaugeValues::default();
+        let filter = LabelFilter::default();
+
+        for (client, value) in [("client-0", 10.0), ("client-1", 5.0)] {
+            assert_eq!(
+                gauges.record(
+                    "age",
+                    &labels("ibc-0", client),
+                    value,
+                    &filter,
+                    Aggregation::Sum
+                ),
+                value
+            );
+        }
+    }
+}
@@ -0,0 +1,276 @@
+//! Selection of the labels emitted with the metrics, to bound the number of time
+//! series exported on relayers which serve many channels.
+//!
+//! The labels are grouped by [`Label`] dimension, e.g. the `channel` dimension covers
+//! the `channel`, `src_channel` and `path` labels. Dropping a dimension removes its labels
+//! from the metrics when they are recorded, so the values of the series which only differed
+//! by these labels are merged into a single series: the counters are summed, the
+//! histograms share their buckets and the gauges aggregate the latest values of the
+//! merged series.
+
+use alloc::collections::BTreeSet;
+use core::fmt;
+use core::str::FromStr;
+
+use opentelemetry::KeyValue;
+
+/// A dimension along which the metrics are labelled.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Label {
+    Chain,
+    Counterparty,
+    Channel,
+    Port,
+    Client,
+    Account,
+    Denom,
+    RelayMode,
+    QueryType,
+    Type,
+}
+
+impl Label {
+    pub const ALL: [Label; 10] = [
+        Self::Chain,
+        Self::Counterparty,
+        Self::Channel,
+        Self::Port,
+        Self::Client,
+        Self::Account,
+        Self::Denom,
+        Self::RelayMode,
+        Self::QueryType,
+        Self::Type,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Chain => "chain",
+            Self::Counterparty => "counterparty",
+            Self::Channel => "channel",
+            Self::Port => "port",
+            Self::Client => "client",
+            Self::Account => "account",
+            Self::Denom => "denom",
+            Self::RelayMode => "relay_mode",
+            Self::QueryType => "query_type",
+            Self::Type => "type",
+        }
+    }
+
+    /// The keys of the labels of this dimension, as emitted with the metrics.
+    pub fn keys(&self) -> &'static [&'static str] {
+        match self {
+            Self::Chain => &["chain", "src_chain"],
+            Self::Counterparty => &["counterparty"],
+            // The `path` label is the chain, port and channel a packet is sent on
+            Self::Channel => &["channel", "src_channel", "path"],
+            Self::Port => &["port", "src_port"],
+            Self::Client => &["client", "client_id"],
+            Self::Account => &["account"],
+            Self::Denom => &["denom"],
+            Self::RelayMode => &["relay_mode"],
+            Self::QueryType => &["query_type"],
+            Self::Type => &["type"],
+        }
+    }
+
+    fn of_key(key: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|label| label.keys().contains(&key))
+    }
+}
+
+impl fmt::Display for Label {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for Label {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|label| label.as_str() == s)
+            .ok_or_else(|| format!("unknown telemetry label '{}'", s))
+    }
+}
+
+/// A predefined selection of the labels emitted with the metrics.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LabelPreset {
+    /// All the labels.
+    Full,
+    /// The labels which do not depend on the channels or clients, e.g. `chain` and `counterparty`.
+    PerChain,
+    /// Only the `chain` label, and the `relay_mode` and `type` labels which take a handful of values.
+    Minimal,
+}
+
+impl Default for LabelPreset {
+    fn default() -> Self {
+        Self::Full
+    }
+}
+
+impl LabelPreset {
+    /// The dimensions which are not emitted with this preset.
+    pub fn dropped(&self) -> &'static [Label] {
+        match self {
+            Self::Full => &[],
+            Self::PerChain => &[Label::Channel, Label::Port, Label::Client],
+            Self::Minimal => &[
+                Label::Counterparty,
+                Label::Channel,
+                Label::Port,
+                Label::Client,
+                Label::Account,
+                Label::Denom,
+                Label::QueryType,
+            ],
+        }
+    }
+}
+
+impl fmt::Display for LabelPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full => write!(f, "full"),
+            Self::PerChain => write!(f, "per-chain"),
+            Self::Minimal => write!(f, "minimal"),
+        }
+    }
+}
+
+impl FromStr for LabelPreset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "full" => Ok(Self::Full),
+            "per-chain" => Ok(Self::PerChain),
+            "minimal" => Ok(Self::Minimal),
+            _ => Err(format!("unknown telemetry label preset '{}'", s)),
+        }
+    }
+}
+
+/// Removes the labels of the dropped dimensions from the labels of the metrics.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LabelFilter {
+    dropped: BTreeSet<Label>,
+}
+
+impl LabelFilter {
+    /// Emit the labels selected by the given preset.
+    pub fn from_preset(preset: LabelPreset) -> Self {
+        Self {
+            dropped: preset.dropped().iter().copied().collect(),
+        }
+    }
+
+    /// Do not emit the labels of the given dimension either.
+    pub fn without(mut self, label: Label) -> Self {
+        self.dropped.insert(label);
+        self
+    }
+
+    /// Whether the labels of the given dimension are emitted.
+    pub fn is_emitted(&self, label: Label) -> bool {
+        !self.dropped.contains(&label)
+    }
+
+    /// Whether all the labels are emitted.
+    pub fn is_full(&self) -> bool {
+        self.dropped.is_empty()
+    }
+
+    /// Whether the label with the given key is emitted. The labels which are not
+    /// of any dimension are always emitted.
+    pub fn emits(&self, key: &str) -> bool {
+        Label::of_key(key).map_or(true, |label| self.is_emitted(label))
+    }
+
+    /// The given labels, without those of the dropped dimensions.
+    pub fn apply(&self, labels: &[KeyValue]) -> Vec<KeyValue> {
+        labels
+            .iter()
+            .filter(|kv| self.emits(kv.key.as_str()))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(labels: &[KeyValue]) -> Vec<&str> {
+        labels.iter().map(|kv| kv.key.as_str()).collect()
+    }
+
+    fn labels() -> [KeyValue; 5] {
+        [
+            KeyValue::new("chain", "ibc-0"),
+            KeyValue::new("counterparty", "ibc-1"),
+            KeyValue::new("channel", "channel-0"),
+            KeyValue::new("port", "transfer"),
+            KeyValue::new("unknown", "value"),
+        ]
+    }
+
+    #[test]
+    fn full_keeps_all_labels() {
+        let filter = LabelFilter::default();
+
+        assert!(filter.is_full());
+        assert_eq!(
+            keys(&filter.apply(&labels())),
+            ["chain", "counterparty", "channel", "port", "unknown"]
+        );
+    }
+
+    #[test]
+    fn per_chain_drops_channel_labels() {
+        let filter = LabelFilter::from_preset(LabelPreset::PerChain);
+
+        assert_eq!(
+            keys(&filter.apply(&labels())),
+            ["chain", "counterparty", "unknown"]
+        );
+        assert!(filter
+            .apply(&[KeyValue::new("path", "ibc-0/transfer/channel-0")])
+            .is_empty());
+    }
+
+    #[test]
+    fn minimal_with_extra_dropped_label() {
+        let filter = LabelFilter::from_preset(LabelPreset::Minimal).without(Label::Type);
+
+        assert_eq!(keys(&filter.apply(&labels())), ["chain", "unknown"]);
+        assert!(filter.apply(&[KeyValue::new("type", "packet")]).is_empty());
+        assert!(!filter.is_emitted(Label::Type));
+        assert!(filter.is_emitted(Label::RelayMode));
+    }
+
+    #[test]
+    fn parse_labels_and_presets() {
+        for label in Label::ALL {
+            assert_eq!(label.to_string().parse::<Label>(), Ok(label));
+        }
+
+        for preset in [
+            LabelPreset::Full,
+            LabelPreset::PerChain,
+            LabelPreset::Minimal,
+        ] {
+            assert_eq!(preset.to_string().parse::<LabelPreset>(), Ok(preset));
+        }
+
+        assert!("src_channel".parse::<Label>().is_err());
+        assert!("per_chain".parse::<LabelPreset>().is_err());
+    }
+}
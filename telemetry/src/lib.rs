@@ -1,5 +1,7 @@
 extern crate alloc;
 
+mod gauges;
+pub mod labels;
 mod path_identifier;
 pub mod server;
 pub mod state;
@@ -1,19 +1,44 @@
+use crate::labels::{Label, LabelFilter};
+
 /// Structure used by the telemetry in order to define a UID
 /// to track the SendPacket and WriteAcknowledgement and Timeouts for a given
 /// chain, channel and port.
-#[derive(Debug, Hash, PartialEq, Eq)]
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct PathIdentifier {
     chain_id: String,
     channel_id: String,
     port_id: String,
+    counterparty_chain_id: String,
 }
 
 impl PathIdentifier {
-    pub fn new(chain_id: String, channel_id: String, port_id: String) -> Self {
+    pub fn new(
+        chain_id: String,
+        channel_id: String,
+        port_id: String,
+        counterparty_chain_id: String,
+    ) -> Self {
         Self {
             chain_id,
             channel_id,
             port_id,
+            counterparty_chain_id,
         }
     }
+
+    /// Whether the metrics of this path and of the given one are recorded in the
+    /// same series, i.e. whether they only differ by labels which are not emitted.
+    pub fn is_merged_with(&self, other: &Self, filter: &LabelFilter) -> bool {
+        let same =
+            |label, this: &String, other: &String| !filter.is_emitted(label) || this == other;
+
+        same(Label::Chain, &self.chain_id, &other.chain_id)
+            && same(Label::Channel, &self.channel_id, &other.channel_id)
+            && same(Label::Port, &self.port_id, &other.port_id)
+            && same(
+                Label::Counterparty,
+                &self.counterparty_chain_id,
+                &other.counterparty_chain_id,
+            )
+    }
 }
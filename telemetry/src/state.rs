@@ -1,4 +1,6 @@
 use core::fmt;
+use std::collections::HashSet;
+use std::sync::{RwLock, RwLockReadGuard};
 use std::time::{Duration, Instant};
 
 use dashmap::DashMap;
@@ -7,12 +9,14 @@ use opentelemetry::{
     metrics::{Counter, UpDownCounter, ValueRecorder},
     KeyValue,
 };
-use opentelemetry_prometheus::PrometheusExporter;
+use opentelemetry_prometheus::{ExporterBuilder, PrometheusExporter};
 use prometheus::proto::MetricFamily;
 
 use ibc::core::ics24_host::identifier::{ChainId, ChannelId, ClientId, PortId};
 use tendermint::Time;
 
+use crate::gauges::{Aggregation, GaugeValues};
+use crate::labels::LabelFilter;
 use crate::path_identifier::PathIdentifier;
 
 const EMPTY_BACKLOG_SYMBOL: u64 = 0;
@@ -52,13 +56,13 @@ pub struct TelemetryState {
     /// Number of client misbehaviours per client
     ibc_client_misbehaviours: Counter<u64>,
 
-    /// Number of receive packets relayed, per channel
+    /// Number of receive packets relayed, per channel and relay mode
     receive_packets: Counter<u64>,
 
-    /// Number of acknowledgment packets relayed, per channel
+    /// Number of acknowledgment packets relayed, per channel and relay mode
     acknowledgment_packets: Counter<u64>,
 
-    /// Number of timeout packets relayed, per channel
+    /// Number of timeout packets relayed, per channel and relay mode
     timeout_packets: Counter<u64>,
 
     /// Number of queries emitted by the relayer, per chain and query type
@@ -76,9 +80,34 @@ pub struct TelemetryState {
     /// How many messages Hermes submitted to the chain, per chain
     msg_num: Counter<u64>,
 
+    /// How many messages Hermes would have submitted to the chain in observation mode,
+    /// per chain and message type
+    would_send_msg_num: Counter<u64>,
+
+    /// How many times a proof queried from a chain did not match the commitment root
+    /// of the counterparty client when verified locally, per chain
+    forked_proof_detected: Counter<u64>,
+
+    /// How many times the acknowledgement of a packet queried from a chain did not
+    /// match the acknowledgement commitment stored on that chain, per chain
+    ack_commitment_mismatch: Counter<u64>,
+
+    /// How many transactions relaying packets failed, per chain, codespace and code
+    /// of their error, and action taken for their messages
+    tx_errors: Counter<u64>,
+
     /// The balance of each wallet Hermes uses per chain
     wallet_balance: ValueRecorder<f64>,
 
+    /// How far the latest block of the node lags behind the local time, per chain. Seconds.
+    chain_sync_lag: ValueRecorder<u64>,
+
+    /// Time elapsed since the latest update of each client, per chain and client. Seconds.
+    client_age: ValueRecorder<u64>,
+
+    /// Time remaining until each client expires if it is not updated, per chain and client. Seconds.
+    client_trust_period_remaining: ValueRecorder<u64>,
+
     /// Indicates the latency for all transactions submitted to a specific chain,
     /// i.e. the difference between the moment when Hermes received a batch of events
     /// until the corresponding transaction(s) were submitted. Milliseconds.
@@ -89,6 +118,22 @@ pub struct TelemetryState {
     /// until the corresponding transaction(s) were confirmed. Milliseconds.
     tx_latency_confirmed: ValueRecorder<u64>,
 
+    /// Indicates the end-to-end latency of packets on a specific path, i.e. the difference
+    /// between the time of the block in which a packet was sent on its source chain and
+    /// the time of the block in which its acknowledgement was written on its destination chain.
+    /// Seconds.
+    packet_e2e_latency: ValueRecorder<f64>,
+
+    /// Counts the packets, per path, whose acknowledgement was written in a block older than
+    /// the one in which they were sent, whose end-to-end latency is recorded as zero.
+    packet_e2e_clock_anomalies: Counter<u64>,
+
+    /// Indicates the size of the data of the packets sent on a specific channel. Bytes.
+    packet_data_size: ValueRecorder<u64>,
+
+    /// Indicates the number of events in the batches handled by the packet workers of a specific channel.
+    event_batch_size: ValueRecorder<u64>,
+
     /// Records the time at which we started processing an event batch.
     /// Used for computing the `tx_latency` metric.
     in_flight_events: moka::sync::Cache<String, Instant>,
@@ -118,6 +163,13 @@ pub struct TelemetryState {
     /// Records the length of the backlog, i.e., how many packets are pending.
     backlog_size: ValueRecorder<u64>,
 
+    /// Records whether a chain is considered halted (1) or not (0).
+    chain_halted: ValueRecorder<u64>,
+
+    /// Records how many packets are shown to be timed out by the client of their
+    /// destination chain, and await a proof from that unreachable chain.
+    timeouts_awaiting_proof: ValueRecorder<u64>,
+
     /// Stores the backlogs for all the paths the relayer is active on.
     /// This is a map of multiple inner backlogs, one inner backlog per path.
     ///
@@ -126,6 +178,17 @@ pub struct TelemetryState {
     /// that the relayer observed, and for which there was no associated Acknowledgement or
     /// Timeout event.
     backlogs: DashMap<PathIdentifier, DashMap<u64, u64>>,
+
+    /// The latest state of the backlog of each path, used to aggregate the backlogs
+    /// of the paths whose metrics are recorded in the same series.
+    backlog_summaries: DashMap<PathIdentifier, BacklogSummary>,
+
+    /// The latest value of each series of the other gauges, used to aggregate the values
+    /// of the series which are recorded in the same one.
+    gauges: GaugeValues,
+
+    /// Selects the labels emitted with the metrics.
+    label_filter: RwLock<LabelFilter>,
 }
 
 impl TelemetryState {
@@ -134,9 +197,42 @@ impl TelemetryState {
         self.exporter.registry().gather()
     }
 
+    /// Select the labels emitted with the metrics recorded from now on.
+    pub fn set_label_filter(&self, filter: LabelFilter) {
+        *self.label_filter.write().unwrap() = filter;
+    }
+
+    fn label_filter(&self) -> RwLockReadGuard<'_, LabelFilter> {
+        self.label_filter.read().unwrap()
+    }
+
+    /// The given labels, without those which are not emitted.
+    fn filter_labels(&self, labels: &[KeyValue]) -> Vec<KeyValue> {
+        self.label_filter().apply(labels)
+    }
+
+    /// The labels and the value to record in a gauge, given the labels and the value of
+    /// one of its series. If the labels which tell some series apart are not emitted,
+    /// these series are recorded in the same one, whose value aggregates their latest values.
+    fn gauge_value(
+        &self,
+        gauge: &'static str,
+        labels: &[KeyValue],
+        value: f64,
+        aggregation: Aggregation,
+    ) -> (Vec<KeyValue>, f64) {
+        let filter = self.label_filter();
+        let value = self
+            .gauges
+            .record(gauge, labels, value, &filter, aggregation);
+
+        (filter.apply(labels), value)
+    }
+
     /// Update the number of workers per object
     pub fn worker(&self, worker_type: WorkerType, count: i64) {
         let labels = &[KeyValue::new("type", worker_type.to_string())];
+        let labels = &self.filter_labels(labels);
         self.workers.add(count, labels);
     }
 
@@ -146,6 +242,7 @@ impl TelemetryState {
             KeyValue::new("chain", chain.to_string()),
             KeyValue::new("client", client.to_string()),
         ];
+        let labels = &self.filter_labels(labels);
 
         self.ibc_client_updates.add(count, labels);
     }
@@ -156,61 +253,71 @@ impl TelemetryState {
             KeyValue::new("chain", chain.to_string()),
             KeyValue::new("client", client.to_string()),
         ];
+        let labels = &self.filter_labels(labels);
 
         self.ibc_client_misbehaviours.add(count, labels);
     }
 
-    /// Number of receive packets relayed, per channel
+    /// Number of receive packets relayed, per channel and relay mode
     pub fn ibc_receive_packets(
         &self,
         src_chain: &ChainId,
         src_channel: &ChannelId,
         src_port: &PortId,
+        relay_mode: &str,
         count: u64,
     ) {
         let labels = &[
             KeyValue::new("src_chain", src_chain.to_string()),
             KeyValue::new("src_channel", src_channel.to_string()),
             KeyValue::new("src_port", src_port.to_string()),
+            KeyValue::new("relay_mode", relay_mode.to_string()),
         ];
+        let labels = &self.filter_labels(labels);
 
         if count > 0 {
             self.receive_packets.add(count, labels);
         }
     }
 
-    /// Number of acknowledgment packets relayed, per channel
+    /// Number of acknowledgment packets relayed, per channel and relay mode
     pub fn ibc_acknowledgment_packets(
         &self,
         src_chain: &ChainId,
         src_channel: &ChannelId,
         src_port: &PortId,
+        relay_mode: &str,
         count: u64,
     ) {
         let labels = &[
             KeyValue::new("src_chain", src_chain.to_string()),
             KeyValue::new("src_channel", src_channel.to_string()),
             KeyValue::new("src_port", src_port.to_string()),
+            KeyValue::new("relay_mode", relay_mode.to_string()),
         ];
+        let labels = &self.filter_labels(labels);
 
         if count > 0 {
             self.acknowledgment_packets.add(count, labels);
         }
     }
 
-    /// Number of timeout packets relayed, per channel
+    /// Number of timeout packets relayed, per channel and relay mode
     pub fn ibc_timeout_packets(
         &self,
         src_chain: &ChainId,
         src_channel: &ChannelId,
         src_port: &PortId,
+        relay_mode: &str,
         count: u64,
     ) {
         let labels = &[
             KeyValue::new("src_chain", src_chain.to_string()),
             KeyValue::new("src_channel", src_channel.to_string()),
             KeyValue::new("src_port", src_port.to_string()),
+            KeyValue::new("relay_mode", relay_mode.to_string()),
         ];
+        let labels = &self.filter_labels(labels);
 
         if count > 0 {
             self.timeout_packets.add(count, labels);
@@ -223,6 +330,7 @@ impl TelemetryState {
             KeyValue::new("chain", chain_id.to_string()),
             KeyValue::new("query_type", query_type),
         ];
+        let labels = &self.filter_labels(labels);
 
         self.queries.add(1, labels);
     }
@@ -233,6 +341,7 @@ impl TelemetryState {
             KeyValue::new("chain", chain_id.to_string()),
             KeyValue::new("query_type", query_type),
         ];
+        let labels = &self.filter_labels(labels);
 
         self.query_cache_hits.add(1, labels);
     }
@@ -240,6 +349,7 @@ impl TelemetryState {
     /// Number of time the relayer had to reconnect to the WebSocket endpoint, per chain
     pub fn ws_reconnect(&self, chain_id: &ChainId) {
         let labels = &[KeyValue::new("chain", chain_id.to_string())];
+        let labels = &self.filter_labels(labels);
 
         self.ws_reconnect.add(1, labels);
     }
@@ -247,6 +357,7 @@ impl TelemetryState {
     /// How many IBC events did Hermes receive via the WebSocket subscription, per chain
     pub fn ws_events(&self, chain_id: &ChainId, count: u64) {
         let labels = &[KeyValue::new("chain", chain_id.to_string())];
+        let labels = &self.filter_labels(labels);
 
         self.ws_events.add(count, labels);
     }
@@ -254,10 +365,55 @@ impl TelemetryState {
     /// How many messages Hermes submitted to the chain, per chain
     pub fn msg_num(&self, chain_id: &ChainId, count: u64) {
         let labels = &[KeyValue::new("chain", chain_id.to_string())];
+        let labels = &self.filter_labels(labels);
 
         self.msg_num.add(count, labels);
     }
 
+    /// How many messages Hermes would have submitted to the chain in observation mode,
+    /// per chain and message type
+    pub fn would_send_msg_num(&self, chain_id: &ChainId, msg_type: &str, count: u64) {
+        let labels = &[
+            KeyValue::new("chain", chain_id.to_string()),
+            KeyValue::new("type", msg_type.to_string()),
+        ];
+        let labels = &self.filter_labels(labels);
+
+        self.would_send_msg_num.add(count, labels);
+    }
+
+    /// How many times a proof queried from a chain did not match the commitment root
+    /// of the counterparty client when verified locally, per chain
+    pub fn forked_proof_detected(&self, chain_id: &ChainId) {
+        let labels = &[KeyValue::new("chain", chain_id.to_string())];
+        let labels = &self.filter_labels(labels);
+
+        self.forked_proof_detected.add(1, labels);
+    }
+
+    /// How many times the acknowledgement of a packet queried from a chain did not
+    /// match the acknowledgement commitment stored on that chain, per chain
+    pub fn ack_commitment_mismatch(&self, chain_id: &ChainId) {
+        let labels = &[KeyValue::new("chain", chain_id.to_string())];
+        let labels = &self.filter_labels(labels);
+
+        self.ack_commitment_mismatch.add(1, labels);
+    }
+
+    /// How many transactions relaying packets failed, per chain, codespace and code
+    /// of their error, and action taken for their messages
+    pub fn tx_error(&self, chain_id: &ChainId, codespace: &str, code: u32, action: &str) {
+        let labels = &[
+            KeyValue::new("chain", chain_id.to_string()),
+            KeyValue::new("codespace", codespace.to_string()),
+            KeyValue::new("code", code.to_string()),
+            KeyValue::new("action", action.to_string()),
+        ];
+        let labels = &self.filter_labels(labels);
+
+        self.tx_errors.add(1, labels);
+    }
+
     /// The balance in each wallet that Hermes is using, per account, denom and chain.
     /// The amount given is of unit: 10^6 * `denom`
     pub fn wallet_balance(&self, chain_id: &ChainId, account: &str, amount: f64, denom: &str) {
@@ -266,8 +422,51 @@ impl TelemetryState {
             KeyValue::new("account", account.to_string()),
             KeyValue::new("denom", denom.to_string()),
         ];
+        let (labels, amount) = self.gauge_value("wallet_balance", labels, amount, Aggregation::Sum);
+
+        self.wallet_balance.record(amount, &labels);
+    }
+
+    /// How far the latest block of the node of the given chain lags behind the local time.
+    pub fn chain_sync_lag(&self, chain_id: &ChainId, lag: Duration) {
+        let labels = &[KeyValue::new("chain", chain_id.to_string())];
+        let (labels, lag) = self.gauge_value(
+            "chain_sync_lag",
+            labels,
+            lag.as_secs() as f64,
+            Aggregation::Max,
+        );
+
+        self.chain_sync_lag.record(lag as u64, &labels);
+    }
+
+    /// Time elapsed since the latest update of the given client, hosted on the given chain,
+    /// and time remaining until the client expires if it is not updated.
+    pub fn client_age(
+        &self,
+        chain_id: &ChainId,
+        client_id: &ClientId,
+        age: Duration,
+        trust_period_remaining: Duration,
+    ) {
+        let labels = &[
+            KeyValue::new("chain", chain_id.to_string()),
+            KeyValue::new("client_id", client_id.to_string()),
+        ];
+
+        // The merged series report the stalest client, and the one closest to expiring
+        let (age_labels, age) =
+            self.gauge_value("client_age", labels, age.as_secs() as f64, Aggregation::Max);
+        let (remaining_labels, remaining) = self.gauge_value(
+            "client_trust_period_remaining",
+            labels,
+            trust_period_remaining.as_secs() as f64,
+            Aggregation::Min,
+        );
 
-        self.wallet_balance.record(amount, labels);
+        self.client_age.record(age as u64, &age_labels);
+        self.client_trust_period_remaining
+            .record(remaining as u64, &remaining_labels);
     }
 
     pub fn received_event_batch(&self, tracking_id: impl ToString) {
@@ -296,6 +495,7 @@ impl TelemetryState {
                 KeyValue::new("channel", channel_id.to_string()),
                 KeyValue::new("port", port_id.to_string()),
             ];
+            let labels = &self.filter_labels(labels);
 
             for _ in 0..tx_count {
                 self.tx_latency_submitted.record(latency, labels);
@@ -324,6 +524,7 @@ impl TelemetryState {
                 KeyValue::new("channel", channel_id.to_string()),
                 KeyValue::new("port", port_id.to_string()),
             ];
+            let labels = &self.filter_labels(labels);
 
             for _ in 0..tx_count {
                 self.tx_latency_confirmed.record(latency, labels);
@@ -331,6 +532,103 @@ impl TelemetryState {
         }
     }
 
+    /// Records the end-to-end latency of a packet sent on the given chain, channel and port.
+    /// If `clamped` is true, the latency was negative and has been clamped to zero.
+    pub fn packet_e2e_latency(
+        &self,
+        latency: Duration,
+        clamped: bool,
+        chain_id: &ChainId,
+        channel_id: &ChannelId,
+        port_id: &PortId,
+        counterparty_chain_id: &ChainId,
+    ) {
+        let labels = &[
+            KeyValue::new("path", format!("{}/{}/{}", chain_id, port_id, channel_id)),
+            KeyValue::new("counterparty", counterparty_chain_id.to_string()),
+        ];
+        let labels = &self.filter_labels(labels);
+
+        if clamped {
+            self.packet_e2e_clock_anomalies.add(1, labels);
+        }
+
+        self.packet_e2e_latency.record(latency.as_secs_f64(), labels);
+    }
+
+    /// Records whether the given chain is considered halted.
+    pub fn chain_halted(&self, chain_id: &ChainId, halted: bool) {
+        let labels = &[KeyValue::new("chain", chain_id.to_string())];
+        let (labels, halted) = self.gauge_value(
+            "chain_halted",
+            labels,
+            f64::from(u8::from(halted)),
+            Aggregation::Max,
+        );
+
+        self.chain_halted.record(halted as u64, &labels);
+    }
+
+    /// Records how many timeouts of packets sent on the given channel
+    /// await a proof from the unreachable counterparty chain.
+    pub fn timeouts_awaiting_proof(
+        &self,
+        chain_id: &ChainId,
+        channel_id: &ChannelId,
+        port_id: &PortId,
+        counterparty_chain_id: &ChainId,
+        count: u64,
+    ) {
+        let labels = &[
+            KeyValue::new("chain", chain_id.to_string()),
+            KeyValue::new("counterparty", counterparty_chain_id.to_string()),
+            KeyValue::new("channel", channel_id.to_string()),
+            KeyValue::new("port", port_id.to_string()),
+        ];
+        let (labels, count) = self.gauge_value(
+            "timeouts_awaiting_proof",
+            labels,
+            count as f64,
+            Aggregation::Sum,
+        );
+
+        self.timeouts_awaiting_proof.record(count as u64, &labels);
+    }
+
+    pub fn packet_data_size(
+        &self,
+        size: u64,
+        chain_id: &ChainId,
+        channel_id: &ChannelId,
+        port_id: &PortId,
+    ) {
+        let labels = &[
+            KeyValue::new("chain", chain_id.to_string()),
+            KeyValue::new("channel", channel_id.to_string()),
+            KeyValue::new("port", port_id.to_string()),
+        ];
+        let labels = &self.filter_labels(labels);
+
+        self.packet_data_size.record(size, labels);
+    }
+
+    pub fn event_batch_size(
+        &self,
+        size: u64,
+        chain_id: &ChainId,
+        channel_id: &ChannelId,
+        port_id: &PortId,
+    ) {
+        let labels = &[
+            KeyValue::new("chain", chain_id.to_string()),
+            KeyValue::new("channel", channel_id.to_string()),
+            KeyValue::new("port", port_id.to_string()),
+        ];
+        let labels = &self.filter_labels(labels);
+
+        self.event_batch_size.record(size, labels);
+    }
+
     pub fn send_packet_count(
         &self,
         _seq_nr: u64,
@@ -346,6 +644,7 @@ impl TelemetryState {
             KeyValue::new("channel", channel_id.to_string()),
             KeyValue::new("port", port_id.to_string()),
         ];
+        let labels = &self.filter_labels(labels);
 
         self.send_packet_count.add(1, labels);
     }
@@ -365,6 +664,7 @@ impl TelemetryState {
             KeyValue::new("channel", channel_id.to_string()),
             KeyValue::new("port", port_id.to_string()),
         ];
+        let labels = &self.filter_labels(labels);
 
         self.acknowledgement_count.add(1, labels);
     }
@@ -384,6 +684,7 @@ impl TelemetryState {
             KeyValue::new("channel", channel_id.to_string()),
             KeyValue::new("port", port_id.to_string()),
         ];
+        let labels = &self.filter_labels(labels);
 
         self.cleared_send_packet_count.add(1, labels);
     }
@@ -403,6 +704,7 @@ impl TelemetryState {
             KeyValue::new("channel", channel_id.to_string()),
             KeyValue::new("port", port_id.to_string()),
         ];
+        let labels = &self.filter_labels(labels);
 
         self.cleared_acknowledgment_count.add(1, labels);
     }
@@ -422,6 +724,7 @@ impl TelemetryState {
             chain_id.to_string(),
             channel_id.to_string(),
             port_id.to_string(),
+            counterparty_chain_id.to_string(),
         );
 
         let labels = &[
@@ -438,50 +741,23 @@ impl TelemetryState {
             Err(_) => 0,
         };
 
-        // Update the backlog with the incoming data and retrieve the oldest values
-        let (oldest_sn, oldest_ts, total) = if let Some(path_backlog) = self.backlogs.get(&path_uid)
-        {
-            // Avoid having the inner backlog map growing more than a given threshold, by removing
-            // the oldest sequence number entry.
-            if path_backlog.len() > BACKLOG_RESET_THRESHOLD {
-                if let Some(min) = path_backlog.iter().map(|v| *v.key()).min() {
-                    path_backlog.remove(&min);
-                }
-            }
-            path_backlog.insert(seq_nr, timestamp);
+        // If there is no inner backlog for this path, create a new map to store it.
+        let path_backlog = self
+            .backlogs
+            .entry(path_uid.clone())
+            .or_insert_with(|| DashMap::with_capacity(BACKLOG_CAPACITY));
 
-            // Return the oldest event information to be recorded in telemetry
+        // Avoid having the inner backlog map growing more than a given threshold, by removing
+        // the oldest sequence number entry.
+        if path_backlog.len() > BACKLOG_RESET_THRESHOLD {
             if let Some(min) = path_backlog.iter().map(|v| *v.key()).min() {
-                if let Some(oldest) = path_backlog.get(&min) {
-                    (min, *oldest.value(), path_backlog.len() as u64)
-                } else {
-                    // Timestamp was not found, this should not happen, record a 0 ts.
-                    (min, 0, path_backlog.len() as u64)
-                }
-            } else {
-                // We just inserted a new key/value, so this else branch is unlikely to activate,
-                // but it can happen in case of concurrent updates to the backlog.
-                (
-                    EMPTY_BACKLOG_SYMBOL,
-                    EMPTY_BACKLOG_SYMBOL,
-                    EMPTY_BACKLOG_SYMBOL,
-                )
+                path_backlog.remove(&min);
             }
-        } else {
-            // If there is no inner backlog for this path, create a new map to store it.
-            let new_path_backlog = DashMap::with_capacity(BACKLOG_CAPACITY);
-            new_path_backlog.insert(seq_nr, timestamp);
-            // Record it in the global backlog
-            self.backlogs.insert(path_uid, new_path_backlog);
-
-            // Return the current event information to be recorded in telemetry
-            (seq_nr, timestamp, 1)
-        };
+        }
+        path_backlog.insert(seq_nr, timestamp);
 
         // Update metrics to reflect the new state of the backlog
-        self.backlog_oldest_sequence.record(oldest_sn, labels);
-        self.backlog_oldest_timestamp.record(oldest_ts, labels);
-        self.backlog_size.record(total, labels);
+        self.record_backlog(path_uid, &path_backlog, labels);
     }
 
     /// Evicts from the backlog the event for the given sequence number.
@@ -501,6 +777,7 @@ impl TelemetryState {
             chain_id.to_string(),
             channel_id.to_string(),
             port_id.to_string(),
+            counterparty_chain_id.to_string(),
         );
 
         let labels = &[
@@ -511,32 +788,143 @@ impl TelemetryState {
         ];
 
         if let Some(path_backlog) = self.backlogs.get(&path_uid) {
-            match path_backlog.remove(&seq_nr) {
-                Some(_) => {
-                    // The oldest pending sequence number is the minimum key in the inner (path) backlog.
-                    if let Some(min_key) = path_backlog.iter().map(|v| *v.key()).min() {
-                        if let Some(oldest) = path_backlog.get(&min_key) {
-                            self.backlog_oldest_timestamp
-                                .record(*oldest.value(), labels);
-                        } else {
-                            self.backlog_oldest_timestamp.record(0, labels);
-                        }
-                        self.backlog_oldest_sequence.record(min_key, labels);
-                        self.backlog_size.record(path_backlog.len() as u64, labels);
-                    } else {
-                        // No mimimum found, update the metrics to reflect an empty backlog
-                        self.backlog_oldest_sequence
-                            .record(EMPTY_BACKLOG_SYMBOL, labels);
-                        self.backlog_oldest_timestamp
-                            .record(EMPTY_BACKLOG_SYMBOL, labels);
-                        self.backlog_size.record(EMPTY_BACKLOG_SYMBOL, labels);
-                    }
-                }
-                // No change performed to the backlog, no need to update the metrics.
-                None => {}
+            // No change performed to the backlog, no need to update the metrics.
+            if path_backlog.remove(&seq_nr).is_some() {
+                self.record_backlog(path_uid, &path_backlog, labels);
             }
         }
     }
+
+    /// Reconciles the backlog with the packet commitments present in the state of the chain,
+    /// queried at a height at which the next sequence to send was `next_sequence_send`.
+    ///
+    /// The sequences sent before `next_sequence_send` without a commitment are evicted, as
+    /// their packets were relayed, timed out or never committed, e.g. when the acknowledgment
+    /// was missed or the chain skipped sequences. The commitments which were not tracked yet,
+    /// e.g. of packets sent before the relayer started, are inserted with the current time.
+    pub fn backlog_reconcile(
+        &self,
+        commitments: &[u64],
+        next_sequence_send: u64,
+        chain_id: &ChainId,
+        channel_id: &ChannelId,
+        port_id: &PortId,
+        counterparty_chain_id: &ChainId,
+    ) {
+        // Unique identifier for a chain/channel/port path.
+        let path_uid: PathIdentifier = PathIdentifier::new(
+            chain_id.to_string(),
+            channel_id.to_string(),
+            port_id.to_string(),
+            counterparty_chain_id.to_string(),
+        );
+
+        let labels = &[
+            KeyValue::new("chain", chain_id.to_string()),
+            KeyValue::new("counterparty", counterparty_chain_id.to_string()),
+            KeyValue::new("channel", channel_id.to_string()),
+            KeyValue::new("port", port_id.to_string()),
+        ];
+
+        let timestamp = match Time::now().duration_since(Time::unix_epoch()) {
+            Ok(ts) => ts.as_secs(),
+            Err(_) => 0,
+        };
+
+        let path_backlog = self
+            .backlogs
+            .entry(path_uid.clone())
+            .or_insert_with(|| DashMap::with_capacity(BACKLOG_CAPACITY));
+
+        let committed: HashSet<u64> = commitments.iter().copied().collect();
+
+        // Sequences sent after the query are kept, as the commitments do not cover them yet.
+        path_backlog
+            .retain(|seq_nr, _| *seq_nr >= next_sequence_send || committed.contains(seq_nr));
+
+        for seq_nr in commitments.iter().rev().take(BACKLOG_RESET_THRESHOLD) {
+            path_backlog.entry(*seq_nr).or_insert(timestamp);
+        }
+
+        self.record_backlog(path_uid, &path_backlog, labels);
+    }
+
+    /// Records the backlog metrics of the given path, after its backlog changed.
+    ///
+    /// If the labels which tell some paths apart are not emitted, the backlogs of these
+    /// paths are recorded in the same series, so that series reports their total size
+    /// and the oldest of their pending packets.
+    fn record_backlog(
+        &self,
+        path_uid: PathIdentifier,
+        path_backlog: &DashMap<u64, u64>,
+        labels: &[KeyValue],
+    ) {
+        // The oldest pending sequence number is the minimum key in the inner (path) backlog.
+        let summary = match path_backlog.iter().map(|v| *v.key()).min() {
+            Some(min_key) => BacklogSummary {
+                // The timestamp may be missing in case of concurrent updates, record a 0 ts.
+                oldest_timestamp: path_backlog.get(&min_key).map_or(0, |v| *v.value()),
+                oldest_sequence: min_key,
+                size: path_backlog.len() as u64,
+            },
+            // No minimum found, update the metrics to reflect an empty backlog
+            None => BacklogSummary::EMPTY,
+        };
+
+        self.backlog_summaries.insert(path_uid.clone(), summary);
+
+        let filter = self.label_filter();
+
+        let summary = if filter.is_full() {
+            summary
+        } else {
+            self.backlog_summaries
+                .iter()
+                .filter(|v| v.key().is_merged_with(&path_uid, &filter))
+                .fold(BacklogSummary::EMPTY, |merged, v| merged.merge(*v.value()))
+        };
+
+        let labels = &filter.apply(labels);
+
+        self.backlog_oldest_sequence
+            .record(summary.oldest_sequence, labels);
+        self.backlog_oldest_timestamp
+            .record(summary.oldest_timestamp, labels);
+        self.backlog_size.record(summary.size, labels);
+    }
+}
+
+/// The state of the backlog of a path, or of several paths merged together.
+#[derive(Copy, Clone, Debug)]
+struct BacklogSummary {
+    oldest_sequence: u64,
+    oldest_timestamp: u64,
+    size: u64,
+}
+
+impl BacklogSummary {
+    const EMPTY: Self = Self {
+        oldest_sequence: EMPTY_BACKLOG_SYMBOL,
+        oldest_timestamp: EMPTY_BACKLOG_SYMBOL,
+        size: EMPTY_BACKLOG_SYMBOL,
+    };
+
+    fn merge(self, other: Self) -> Self {
+        let oldest = if self.size == 0
+            || (other.size > 0 && other.oldest_timestamp < self.oldest_timestamp)
+        {
+            other
+        } else {
+            self
+        };
+
+        Self {
+            oldest_sequence: oldest.oldest_sequence,
+            oldest_timestamp: oldest.oldest_timestamp,
+            size: self.size + other.size,
+        }
+    }
 }
 
 use std::sync::Arc;
@@ -552,9 +940,14 @@ impl AggregatorSelector for CustomAggregatorSelector {
     fn aggregator_for(&self, descriptor: &Descriptor) -> Option<Arc<dyn Aggregator + Send + Sync>> {
         match descriptor.name() {
             "wallet_balance" => Some(Arc::new(last_value())),
+            "ibc_chain_sync_lag_seconds" => Some(Arc::new(last_value())),
+            "ibc_client_age_seconds" => Some(Arc::new(last_value())),
+            "ibc_client_trust_period_remaining_seconds" => Some(Arc::new(last_value())),
             "backlog_oldest_sequence" => Some(Arc::new(last_value())),
             "backlog_oldest_timestamp" => Some(Arc::new(last_value())),
             "backlog_size" => Some(Arc::new(last_value())),
+            "chain_halted" => Some(Arc::new(last_value())),
+            "timeouts_awaiting_proof" => Some(Arc::new(last_value())),
             // Prometheus' supports only collector for histogram, sum, and last value aggregators.
             // https://docs.rs/opentelemetry-prometheus/0.10.0/src/opentelemetry_prometheus/lib.rs.html#411-418
             // TODO: Once quantile sketches are supported, replace histograms with that.
@@ -566,6 +959,21 @@ impl AggregatorSelector for CustomAggregatorSelector {
                 descriptor,
                 &[1000.0, 5000.0, 9000.0, 13000.0, 17000.0, 20000.0],
             ))),
+            "packet_e2e_latency_seconds" => Some(Arc::new(histogram(
+                descriptor,
+                &[1.0, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1800.0, 3600.0],
+            ))),
+            "packet_data_size" => Some(Arc::new(histogram(
+                descriptor,
+                &[
+                    100.0, 200.0, 300.0, 400.0, 500.0, 600.0, 700.0, 800.0, 900.0, 1000.0, 2000.0,
+                    5000.0, 10000.0, 100000.0,
+                ],
+            ))),
+            "event_batch_size" => Some(Arc::new(histogram(
+                descriptor,
+                &[1.0, 5.0, 10.0, 50.0, 100.0, 500.0, 1000.0, 2000.0, 5000.0],
+            ))),
             _ => Some(Arc::new(sum())),
         }
     }
@@ -573,7 +981,13 @@ impl AggregatorSelector for CustomAggregatorSelector {
 
 impl Default for TelemetryState {
     fn default() -> Self {
-        let exporter = opentelemetry_prometheus::ExporterBuilder::default()
+        Self::with_exporter(ExporterBuilder::default())
+    }
+}
+
+impl TelemetryState {
+    fn with_exporter(exporter: ExporterBuilder) -> Self {
+        let exporter = exporter
             .with_aggregator_selector(CustomAggregatorSelector)
             .init();
 
@@ -599,17 +1013,17 @@ impl Default for TelemetryState {
 
             receive_packets: meter
                 .u64_counter("ibc_receive_packets")
-                .with_description("Number of confirmed receive packets relayed per channel. Available if relayer runs with Tx confirmation enabled")
+                .with_description("Number of confirmed receive packets relayed per channel and relay mode. Available if relayer runs with Tx confirmation enabled")
                 .init(),
 
             acknowledgment_packets: meter
                 .u64_counter("ibc_acknowledgment_packets")
-                .with_description("Number of confirmed acknowledgment packets relayed per channel. Available if relayer runs with Tx confirmation enabled")
+                .with_description("Number of confirmed acknowledgment packets relayed per channel and relay mode. Available if relayer runs with Tx confirmation enabled")
                 .init(),
 
             timeout_packets: meter
                 .u64_counter("ibc_timeout_packets")
-                .with_description("Number of confirmed timeout packets relayed per channel. Available if relayer runs with Tx confirmation enabled")
+                .with_description("Number of confirmed timeout packets relayed per channel and relay mode. Available if relayer runs with Tx confirmation enabled")
                 .init(),
 
             queries: meter
@@ -639,11 +1053,49 @@ impl Default for TelemetryState {
                 .with_description("How many messages Hermes submitted to the chain, per chain")
                 .init(),
 
+            would_send_msg_num: meter
+                .u64_counter("would_send_msg_num")
+                .with_description("How many messages Hermes would have submitted to the chain in observation mode, per chain and message type")
+                .init(),
+
+            forked_proof_detected: meter
+                .u64_counter("forked_proof_detected")
+                .with_description("How many times a proof queried from the chain did not match the commitment root of the counterparty client when verified locally, per chain")
+                .init(),
+
+            ack_commitment_mismatch: meter
+                .u64_counter("ack_commitment_mismatch")
+                .with_description("How many times the acknowledgement of a packet queried from the chain did not match the acknowledgement commitment stored on the chain, per chain")
+                .init(),
+
+            tx_errors: meter
+                .u64_counter("tx_errors")
+                .with_description("How many transactions relaying packets failed, per chain, codespace and code of their error, and action taken for their messages")
+                .init(),
+
             wallet_balance: meter
                 .f64_value_recorder("wallet_balance")
                 .with_description("The balance of each wallet Hermes uses per chain. Please note that when converting the balance to f64 a loss in precision might be introduced in the displayed value")
                 .init(),
 
+            chain_sync_lag: meter
+                .u64_value_recorder("ibc_chain_sync_lag_seconds")
+                .with_unit(Unit::new("seconds"))
+                .with_description("How far the latest block of the node lags behind the local time, per chain")
+                .init(),
+
+            client_age: meter
+                .u64_value_recorder("ibc_client_age_seconds")
+                .with_unit(Unit::new("seconds"))
+                .with_description("Time elapsed since the latest update of each client, per chain and client")
+                .init(),
+
+            client_trust_period_remaining: meter
+                .u64_value_recorder("ibc_client_trust_period_remaining_seconds")
+                .with_unit(Unit::new("seconds"))
+                .with_description("Time remaining until each client expires if it is not updated, per chain and client")
+                .init(),
+
             send_packet_count: meter
                 .u64_counter("send_packet_count")
                 .with_description("Number of SendPacket events processed")
@@ -680,6 +1132,33 @@ impl Default for TelemetryState {
                     until the corresponding transaction(s) were confirmed. Milliseconds.")
                 .init(),
 
+            packet_e2e_latency: meter
+                .f64_value_recorder("packet_e2e_latency_seconds")
+                .with_unit(Unit::new("seconds"))
+                .with_description("The end-to-end latency of packets on a specific path, \
+                    i.e. the difference between the time of the block in which a packet was sent \
+                    on its source chain and the time of the block in which its acknowledgement \
+                    was written on its destination chain. Seconds.")
+                .init(),
+
+            packet_e2e_clock_anomalies: meter
+                .u64_counter("packet_e2e_clock_anomalies")
+                .with_description("Number of packets whose acknowledgement was written in a block \
+                    older than the one in which they were sent, per path. Their end-to-end latency \
+                    is recorded as zero.")
+                .init(),
+
+            packet_data_size: meter
+                .u64_value_recorder("packet_data_size")
+                .with_unit(Unit::new("bytes"))
+                .with_description("The size of the data of the packets sent on a specific channel. Bytes.")
+                .init(),
+
+            event_batch_size: meter
+                .u64_value_recorder("event_batch_size")
+                .with_description("The number of events in the batches handled by the packet workers of a specific channel")
+                .init(),
+
             in_flight_events: moka::sync::Cache::builder()
                 .time_to_live(Duration::from_secs(60 * 60)) // Remove entries after 1 hour
                 .time_to_idle(Duration::from_secs(30 * 60)) // Remove entries if they have been idle for 30 minutes
@@ -687,6 +1166,12 @@ impl Default for TelemetryState {
 
             backlogs: DashMap::new(),
 
+            backlog_summaries: DashMap::new(),
+
+            gauges: GaugeValues::default(),
+
+            label_filter: RwLock::new(LabelFilter::default()),
+
             backlog_oldest_sequence: meter
                 .u64_value_recorder("backlog_oldest_sequence")
                 .with_description("Sequence number of the oldest pending packet in the backlog, per channel")
@@ -702,6 +1187,290 @@ impl Default for TelemetryState {
                 .u64_value_recorder("backlog_size")
                 .with_description("Total number of pending packets, per channel")
                 .init(),
+
+            chain_halted: meter
+                .u64_value_recorder("chain_halted")
+                .with_description("Whether the chain is considered halted (1) or not (0), per chain")
+                .init(),
+
+            timeouts_awaiting_proof: meter
+                .u64_value_recorder("timeouts_awaiting_proof")
+                .with_description("Number of timed out packets awaiting a proof from their unreachable destination chain, per channel")
+                .init(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeSet;
+    use std::str::FromStr;
+    use std::sync::Mutex;
+
+    use once_cell::sync::Lazy;
+
+    use crate::labels::{Label, LabelPreset};
+
+    /// Creating a state sets the global meter provider, which the state then takes its
+    /// meter from, so the states of the tests must not be created concurrently.
+    static NEW_STATE: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+    fn new_state(preset: LabelPreset) -> TelemetryState {
+        let _guard = NEW_STATE.lock().unwrap();
+
+        // Do not cache the scrapes, so that they see the metrics recorded just before
+        let state = TelemetryState::with_exporter(
+            ExporterBuilder::default().with_cache_period(Duration::ZERO),
+        );
+        state.set_label_filter(LabelFilter::from_preset(preset));
+        state
+    }
+
+    /// The series of the given metric in a scrape, as the keys of their labels and their value.
+    fn scrape(state: &TelemetryState, name: &str) -> Vec<(BTreeSet<String>, f64)> {
+        let known_keys: BTreeSet<&str> = Label::ALL
+            .iter()
+            .flat_map(|label| label.keys().iter().copied())
+            .collect();
+
+        let families = state.gather();
+        let family = families
+            .iter()
+            .find(|family| {
+                family.get_name() == name || family.get_name() == format!("{}_total", name)
+            })
+            .unwrap_or_else(|| panic!("metric {} is not exported", name));
+
+        let mut series: Vec<_> = family
+            .get_metric()
+            .iter()
+            .map(|metric| {
+                // Ignore the labels of the exporter itself, e.g. `service_name`
+                let keys = metric
+                    .get_label()
+                    .iter()
+                    .map(|label| label.get_name())
+                    .filter(|key| known_keys.contains(key))
+                    .map(str::to_string)
+                    .collect();
+
+                let value = if metric.has_counter() {
+                    metric.get_counter().get_value()
+                } else {
+                    metric.get_gauge().get_value()
+                };
+
+                (keys, value)
+            })
+            .collect();
+
+        series.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        series
+    }
+
+    fn keys(keys: &[&str]) -> BTreeSet<String> {
+        keys.iter().map(|key| key.to_string()).collect()
+    }
+
+    /// Record the metrics of the packets sent on two channels of `ibc-0`,
+    /// and of the updates of two clients hosted on `ibc-0`.
+    fn record(state: &TelemetryState) {
+        let chain = ChainId::from_string("ibc-0");
+        let counterparty = ChainId::from_string("ibc-1");
+        let port = PortId::transfer();
+        let channel_0 = ChannelId::new(0);
+        let channel_1 = ChannelId::new(1);
+
+        state.send_packet_count(1, 10, &chain, &channel_0, &port, &counterparty);
+        state.send_packet_count(5, 10, &chain, &channel_1, &port, &counterparty);
+        state.send_packet_count(6, 11, &chain, &channel_1, &port, &counterparty);
+
+        state.backlog_insert(1, &chain, &channel_0, &port, &counterparty);
+        state.backlog_insert(5, &chain, &channel_1, &port, &counterparty);
+        state.backlog_insert(6, &chain, &channel_1, &port, &counterparty);
+        state.backlog_insert(7, &chain, &channel_1, &port, &counterparty);
+        state.backlog_remove(7, &chain, &channel_1, &port, &counterparty);
+
+        state.ibc_client_updates(&chain, &ClientId::from_str("07-tendermint-0").unwrap(), 1);
+        state.ibc_client_updates(&chain, &ClientId::from_str("07-tendermint-1").unwrap(), 1);
+    }
+
+    #[test]
+    fn full_labels() {
+        let state = new_state(LabelPreset::Full);
+        record(&state);
+
+        let packet_keys = keys(&["chain", "counterparty", "channel", "port"]);
+
+        assert_eq!(
+            scrape(&state, "send_packet_count"),
+            [(packet_keys.clone(), 1.0), (packet_keys.clone(), 2.0)]
+        );
+        assert_eq!(
+            scrape(&state, "backlog_size"),
+            [(packet_keys.clone(), 1.0), (packet_keys, 2.0)]
+        );
+
+        let client_keys = keys(&["chain", "client"]);
+
+        assert_eq!(
+            scrape(&state, "ibc_client_updates"),
+            [(client_keys.clone(), 1.0), (client_keys, 1.0)]
+        );
+    }
+
+    #[test]
+    fn per_chain_labels() {
+        let state = new_state(LabelPreset::PerChain);
+        record(&state);
+
+        // The values of the two channels are merged into a single series
+        let packet_keys = keys(&["chain", "counterparty"]);
+
+        assert_eq!(
+            scrape(&state, "send_packet_count"),
+            [(packet_keys.clone(), 3.0)]
+        );
+        assert_eq!(scrape(&state, "backlog_size"), [(packet_keys, 3.0)]);
+        assert_eq!(
+            scrape(&state, "ibc_client_updates"),
+            [(keys(&["chain"]), 2.0)]
+        );
+    }
+
+    #[test]
+    fn minimal_labels() {
+        let state = new_state(LabelPreset::Minimal);
+        record(&state);
+
+        assert_eq!(
+            scrape(&state, "send_packet_count"),
+            [(keys(&["chain"]), 3.0)]
+        );
+        assert_eq!(scrape(&state, "backlog_size"), [(keys(&["chain"]), 3.0)]);
+        assert_eq!(
+            scrape(&state, "ibc_client_updates"),
+            [(keys(&["chain"]), 2.0)]
+        );
+    }
+
+    #[test]
+    fn merged_backlog_is_emptied() {
+        let state = new_state(LabelPreset::PerChain);
+
+        let chain = ChainId::from_string("ibc-0");
+        let counterparty = ChainId::from_string("ibc-1");
+        let port = PortId::transfer();
+
+        state.backlog_insert(5, &chain, &ChannelId::new(1), &port, &counterparty);
+        state.backlog_insert(1, &chain, &ChannelId::new(0), &port, &counterparty);
+
+        assert_eq!(
+            scrape(&state, "backlog_size"),
+            [(keys(&["chain", "counterparty"]), 2.0)]
+        );
+
+        // The merged backlog is only empty once the packets of both channels are relayed
+        state.backlog_remove(5, &chain, &ChannelId::new(1), &port, &counterparty);
+
+        assert_eq!(
+            scrape(&state, "backlog_size"),
+            [(keys(&["chain", "counterparty"]), 1.0)]
+        );
+
+        state.backlog_remove(1, &chain, &ChannelId::new(0), &port, &counterparty);
+
+        assert_eq!(
+            scrape(&state, "backlog_size"),
+            [(keys(&["chain", "counterparty"]), 0.0)]
+        );
+        assert_eq!(
+            scrape(&state, "backlog_oldest_sequence"),
+            [(keys(&["chain", "counterparty"]), 0.0)]
+        );
+    }
+
+    #[test]
+    fn merged_gauges_are_aggregated() {
+        let state = new_state(LabelPreset::PerChain);
+
+        let chain = ChainId::from_string("ibc-0");
+        let counterparty = ChainId::from_string("ibc-1");
+        let port = PortId::transfer();
+        let client_0 = ClientId::from_str("07-tendermint-0").unwrap();
+        let client_1 = ClientId::from_str("07-tendermint-1").unwrap();
+
+        state.timeouts_awaiting_proof(&chain, &ChannelId::new(0), &port, &counterparty, 2);
+        state.timeouts_awaiting_proof(&chain, &ChannelId::new(1), &port, &counterparty, 3);
+
+        state.client_age(
+            &chain,
+            &client_0,
+            Duration::from_secs(60),
+            Duration::from_secs(100),
+        );
+        state.client_age(
+            &chain,
+            &client_1,
+            Duration::from_secs(30),
+            Duration::from_secs(200),
+        );
+
+        // The timeouts of both channels are counted, not only those recorded last
+        assert_eq!(
+            scrape(&state, "timeouts_awaiting_proof"),
+            [(keys(&["chain", "counterparty"]), 5.0)]
+        );
+        state.timeouts_awaiting_proof(&chain, &ChannelId::new(0), &port, &counterparty, 0);
+        assert_eq!(
+            scrape(&state, "timeouts_awaiting_proof"),
+            [(keys(&["chain", "counterparty"]), 3.0)]
+        );
+
+        // The stalest client and the one closest to expiring are reported
+        assert_eq!(
+            scrape(&state, "ibc_client_age_seconds"),
+            [(keys(&["chain"]), 60.0)]
+        );
+        assert_eq!(
+            scrape(&state, "ibc_client_trust_period_remaining_seconds"),
+            [(keys(&["chain"]), 100.0)]
+        );
+    }
+
+    #[test]
+    fn backlog_reconciled_with_commitments() {
+        let state = new_state(LabelPreset::Full);
+
+        let chain = ChainId::from_string("ibc-0");
+        let counterparty = ChainId::from_string("ibc-1");
+        let port = PortId::transfer();
+        let channel = ChannelId::new(0);
+        let packet_keys = keys(&["chain", "counterparty", "channel", "port"]);
+
+        // Packets 2 to 4 seen sent, of which the acknowledgment of 2 was missed
+        for seq_nr in 2..=4 {
+            state.backlog_insert(seq_nr, &chain, &channel, &port, &counterparty);
         }
+
+        // Packet 1 was sent before the relayer started, and packet 4 after the commitments
+        // were queried
+        state.backlog_reconcile(&[1, 3], 4, &chain, &channel, &port, &counterparty);
+
+        assert_eq!(scrape(&state, "backlog_size"), [(packet_keys.clone(), 3.0)]);
+        assert_eq!(
+            scrape(&state, "backlog_oldest_sequence"),
+            [(packet_keys.clone(), 1.0)]
+        );
+
+        // The chain then skipped sequence 5 before sending packet 6, and the
+        // skipped sequence is never pending
+        state.backlog_reconcile(&[1, 3, 4, 6], 7, &chain, &channel, &port, &counterparty);
+        assert_eq!(scrape(&state, "backlog_size"), [(packet_keys.clone(), 4.0)]);
+
+        state.backlog_reconcile(&[], 7, &chain, &channel, &port, &counterparty);
+        assert_eq!(scrape(&state, "backlog_size"), [(packet_keys, 0.0)]);
     }
 }
@@ -149,6 +149,7 @@ impl TestOverrides for IbcTransferMBT {
                 enabled: true,
                 refresh: true,
                 misbehaviour: true,
+                halt_on_short_unbonding: false,
             },
             connections: ConfigConnections { enabled: true },
             channels: ConfigChannels { enabled: true },
@@ -157,7 +158,11 @@ impl TestOverrides for IbcTransferMBT {
                 clear_interval: 10,
                 clear_on_start: true,
                 tx_confirmation: true,
+                max_events_per_batch: 1000,
+                ignore_version_mismatch: false,
             },
+            observation: false,
+            prewarm_clients: false,
         };
 
         for mut chain_config in config.chains.iter_mut() {
@@ -107,6 +107,7 @@ impl TestOverrides for ExpirationTestOverrides {
                 enabled: true,
                 refresh: true,
                 misbehaviour: true,
+                halt_on_short_unbonding: false,
             },
             connections: config::Connections { enabled: true },
             channels: config::Channels { enabled: true },
@@ -115,7 +116,11 @@ impl TestOverrides for ExpirationTestOverrides {
                 clear_interval: 10,
                 clear_on_start: true,
                 tx_confirmation: true,
+                max_events_per_batch: 1000,
+                ignore_version_mismatch: false,
             },
+            observation: false,
+            prewarm_clients: false,
         };
 
         for mut chain_config in config.chains.iter_mut() {
@@ -55,7 +55,7 @@ impl BinaryChannelTest for ExecuteScheduleTest {
             true,
         )?;
 
-        let mut relay_path_a_to_b = chain_a_link.a_to_b;
+        let relay_path_a_to_b = chain_a_link.a_to_b;
 
         // Construct `BATCH_SIZE` pieces of operational data and queue them up to be sent to chain B.
         for i in 0..BATCH_SIZE {
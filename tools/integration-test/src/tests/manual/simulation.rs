@@ -12,6 +12,7 @@
 */
 
 use core::time::Duration;
+use ibc::applications::transfer::Coin;
 use ibc::events::IbcEvent;
 use ibc_relayer::config::{types::MaxMsgNum, Config};
 use ibc_relayer::transfer::{build_and_send_transfer_messages, TransferOptions};
@@ -84,12 +85,18 @@ fn tx_raw_ft_transfer<SrcChain: ChainHandle, DstChain: ChainHandle>(
     let transfer_options = TransferOptions {
         packet_src_port_id: channel.port_a.value().clone(),
         packet_src_channel_id: channel.channel_id_a.value().clone(),
-        amount: amount.into(),
-        denom: denom.value().to_string(),
+        coins: vec![Coin {
+            denom: denom.value().to_string(),
+            amount: amount.into(),
+        }],
         receiver: Some(recipient.value().0.clone()),
         timeout_height_offset,
         timeout_duration,
+        timeout_timestamp: None,
         number_msgs: number_messages,
+        fee: None,
+        fee_granter: None,
+        memo: None,
     };
 
     let events = build_and_send_transfer_messages(src_handle, dst_handle, &transfer_options)?;
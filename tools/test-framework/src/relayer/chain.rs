@@ -20,6 +20,8 @@
    is still a [`ChainHandle`].
 */
 
+use core::time::Duration;
+
 use crossbeam_channel as channel;
 use ibc::core::ics02_client::client_consensus::{AnyConsensusState, AnyConsensusStateWithHeight};
 use ibc::core::ics02_client::client_state::{AnyClientState, IdentifiedAnyClientState};
@@ -44,25 +46,33 @@ use ibc::{
     Height,
 };
 use ibc_relayer::account::Balance;
+use ibc_relayer::chain::block_time::MaxBlockTime;
 use ibc_relayer::chain::client::ClientSettings;
-use ibc_relayer::chain::endpoint::{ChainStatus, HealthCheck};
+use ibc_relayer::chain::cosmos::version::NodeVersions;
+use ibc_relayer::chain::endpoint::{
+    ChainStatus, ChannelOpenTx, HealthCheck, PacketRelayTx, PacketTx, SendPacketTx, SyncStatus,
+    WrittenAck,
+};
 use ibc_relayer::chain::handle::{ChainHandle, ChainRequest, Subscription};
 use ibc_relayer::chain::requests::{
-    IncludeProof, QueryBlockRequest, QueryChannelClientStateRequest, QueryChannelRequest,
-    QueryChannelsRequest, QueryClientConnectionsRequest, QueryClientStateRequest,
-    QueryClientStatesRequest, QueryConnectionChannelsRequest, QueryConnectionRequest,
-    QueryConnectionsRequest, QueryConsensusStateRequest, QueryConsensusStatesRequest,
-    QueryHostConsensusStateRequest, QueryNextSequenceReceiveRequest,
+    IncludeProof, QueryBlockRequest, QueryChannelClientStateRequest, QueryChannelOpenTxRequest,
+    QueryChannelRequest, QueryChannelsRequest, QueryClientConnectionsRequest,
+    QueryClientStateRequest, QueryClientStatesRequest, QueryConnectionChannelsRequest,
+    QueryConnectionRequest, QueryConnectionsRequest, QueryConsensusStateRequest,
+    QueryConsensusStatesRequest, QueryDenomTracesRequest, QueryHostConsensusStateRequest,
+    QueryIncentivizedPacketRequest, QueryNextSequenceReceiveRequest, QueryNextSequenceSendRequest,
     QueryPacketAcknowledgementRequest, QueryPacketAcknowledgementsRequest,
     QueryPacketCommitmentRequest, QueryPacketCommitmentsRequest, QueryPacketReceiptRequest,
-    QueryTxRequest, QueryUnreceivedAcksRequest, QueryUnreceivedPacketsRequest,
-    QueryUpgradedClientStateRequest, QueryUpgradedConsensusStateRequest,
+    QueryPacketRelayTxsRequest, QueryPacketTxRequest, QuerySendPacketTxsRequest, QueryTxRequest,
+    QueryUnreceivedAcksRequest, QueryUnreceivedPacketsRequest, QueryUpgradedClientStateRequest,
+    QueryUpgradedConsensusStateRequest, QueryWrittenAcksRequest,
 };
 use ibc_relayer::chain::tracking::TrackedMsgs;
 use ibc_relayer::config::ChainConfig;
 use ibc_relayer::connection::ConnectionMsgType;
-use ibc_relayer::denom::DenomTrace;
+use ibc_relayer::denom::{DenomDisplay, DenomTrace};
 use ibc_relayer::error::Error;
+use ibc_relayer::fee::PacketFee;
 use ibc_relayer::keyring::KeyEntry;
 
 use crate::types::tagged::*;
@@ -107,10 +117,14 @@ where
     fn send_messages_and_wait_check_tx(
         &self,
         tracked_msgs: TrackedMsgs,
-    ) -> Result<Vec<tendermint_rpc::endpoint::broadcast::tx_sync::Response>, Error> {
+    ) -> Result<Vec<ibc_relayer::chain::cosmos::types::tx::TxSyncResponse>, Error> {
         self.value().send_messages_and_wait_check_tx(tracked_msgs)
     }
 
+    fn estimate_messages_gas(&self, tracked_msgs: TrackedMsgs) -> Result<u64, Error> {
+        self.value().estimate_messages_gas(tracked_msgs)
+    }
+
     fn get_signer(&self) -> Result<Signer, Error> {
         self.value().get_signer()
     }
@@ -119,6 +133,10 @@ where
         self.value().config()
     }
 
+    fn max_block_time(&self) -> Result<MaxBlockTime, Error> {
+        self.value().max_block_time()
+    }
+
     fn get_key(&self) -> Result<KeyEntry, Error> {
         self.value().get_key()
     }
@@ -127,6 +145,10 @@ where
         self.value().add_key(key_name, key)
     }
 
+    fn rotate_key(&self, key_name: String) -> Result<(), Error> {
+        self.value().rotate_key(key_name)
+    }
+
     fn ibc_version(&self) -> Result<Option<semver::Version>, Error> {
         self.value().ibc_version()
     }
@@ -135,6 +157,10 @@ where
         self.value().query_application_status()
     }
 
+    fn query_sync_status(&self) -> Result<SyncStatus, Error> {
+        self.value().query_sync_status()
+    }
+
     fn query_latest_height(&self) -> Result<Height, Error> {
         self.value().query_latest_height()
     }
@@ -229,6 +255,15 @@ where
             .query_next_sequence_receive(request, include_proof)
     }
 
+    fn query_next_sequence_send(
+        &self,
+        request: QueryNextSequenceSendRequest,
+        include_proof: IncludeProof,
+    ) -> Result<(Sequence, Option<MerkleProof>), Error> {
+        self.value()
+            .query_next_sequence_send(request, include_proof)
+    }
+
     fn query_channels(
         &self,
         request: QueryChannelsRequest,
@@ -397,11 +432,81 @@ where
         self.value().query_host_consensus_state(request)
     }
 
-    fn query_balance(&self, key_name: Option<String>) -> Result<Balance, Error> {
-        self.value().query_balance(key_name)
+    fn query_balance(
+        &self,
+        key_name: Option<String>,
+        denom: Option<String>,
+    ) -> Result<Balance, Error> {
+        self.value().query_balance(key_name, denom)
     }
 
     fn query_denom_trace(&self, hash: String) -> Result<DenomTrace, Error> {
         self.value().query_denom_trace(hash)
     }
+
+    fn query_denom_traces(
+        &self,
+        request: QueryDenomTracesRequest,
+    ) -> Result<Vec<DenomTrace>, Error> {
+        self.value().query_denom_traces(request)
+    }
+
+    fn query_denom_metadata(&self, denom: String) -> Result<Option<DenomDisplay>, Error> {
+        self.value().query_denom_metadata(denom)
+    }
+
+    fn query_incentivized_packet(
+        &self,
+        request: QueryIncentivizedPacketRequest,
+    ) -> Result<Vec<PacketFee>, Error> {
+        self.value().query_incentivized_packet(request)
+    }
+
+    fn query_channel_open_tx(
+        &self,
+        request: QueryChannelOpenTxRequest,
+    ) -> Result<Option<ChannelOpenTx>, Error> {
+        self.value().query_channel_open_tx(request)
+    }
+
+    fn query_packet_relay_txs(
+        &self,
+        request: QueryPacketRelayTxsRequest,
+    ) -> Result<Vec<PacketRelayTx>, Error> {
+        self.value().query_packet_relay_txs(request)
+    }
+
+    fn query_send_packet_txs(
+        &self,
+        request: QuerySendPacketTxsRequest,
+    ) -> Result<Vec<SendPacketTx>, Error> {
+        self.value().query_send_packet_txs(request)
+    }
+
+    fn query_packet_tx(&self, request: QueryPacketTxRequest) -> Result<Option<PacketTx>, Error> {
+        self.value().query_packet_tx(request)
+    }
+
+    fn query_written_acks(
+        &self,
+        request: QueryWrittenAcksRequest,
+    ) -> Result<Vec<WrittenAck>, Error> {
+        self.value().query_written_acks(request)
+    }
+
+    fn query_services(&self) -> Result<Vec<String>, Error> {
+        self.value().query_services()
+    }
+
+    fn query_allowed_clients(&self) -> Result<Vec<String>, Error> {
+        self.value().query_allowed_clients()
+    }
+
+    fn query_node_versions(&self) -> Result<NodeVersions, Error> {
+        self.value().query_node_versions()
+    }
+
+    fn query_unbonding_period(&self) -> Result<Duration, Error> {
+        self.value().query_unbonding_period()
+    }
 }
@@ -62,6 +62,7 @@ impl RelayerDriver {
             SupervisorOptions {
                 health_check: false,
                 force_full_scan: false,
+                verbose_scan: false,
             },
         )
         .map_err(Error::supervisor)
@@ -9,10 +9,10 @@ use crate::types::binary::foreign_client::ForeignClientPair;
 pub fn spawn_refresh_client_tasks<ChainA: ChainHandle, ChainB: ChainHandle>(
     foreign_clients: &ForeignClientPair<ChainA, ChainB>,
 ) -> Result<[TaskHandle; 2], Error> {
-    let refresh_task_a = spawn_refresh_client(foreign_clients.client_b_to_a.clone())
+    let refresh_task_a = spawn_refresh_client(foreign_clients.client_b_to_a.clone(), false)
         .ok_or_else(|| eyre!("expect refresh task spawned"))?;
 
-    let refresh_task_b = spawn_refresh_client(foreign_clients.client_a_to_b.clone())
+    let refresh_task_b = spawn_refresh_client(foreign_clients.client_a_to_b.clone(), false)
         .ok_or_else(|| eyre!("expect refresh task spawned"))?;
 
     Ok([refresh_task_a, refresh_task_b])
@@ -7,6 +7,7 @@ use ibc::events::IbcEvent;
 use ibc_proto::cosmos::tx::v1beta1::Fee;
 use ibc_proto::google::protobuf::Any;
 use ibc_relayer::chain::cosmos::gas::calculate_fee;
+use ibc_relayer::chain::cosmos::grpc::GrpcEndpoint;
 use ibc_relayer::chain::cosmos::query::account::query_account;
 use ibc_relayer::chain::cosmos::tx::estimate_fee_and_send_tx;
 use ibc_relayer::chain::cosmos::types::config::TxConfig;
@@ -41,6 +42,7 @@ pub fn gas_config_for_test() -> GasConfig {
         gas_price,
         max_fee,
         fee_granter,
+        explicit_fee: None,
     }
 }
 
@@ -53,7 +55,9 @@ pub fn new_tx_config_for_test(
 
     let rpc_client = HttpClient::new(rpc_address.clone()).map_err(handle_generic_error)?;
 
-    let grpc_address = Uri::from_str(&raw_grpc_address).map_err(handle_generic_error)?;
+    let grpc_uri = Uri::from_str(&raw_grpc_address).map_err(handle_generic_error)?;
+
+    let grpc_address = GrpcEndpoint::new(grpc_uri, &Default::default())?;
 
     let gas_config = gas_config_for_test();
 
@@ -69,6 +73,10 @@ pub fn new_tx_config_for_test(
         grpc_address,
         rpc_timeout,
         address_type,
+        extension_options: vec![],
+        failed_tx_dir: None,
+        max_tx_size: Default::default(),
+        correlation_id: None,
     })
 }
 
@@ -100,7 +108,7 @@ pub async fn simple_send_tx(
             .await?;
 
     let tx_sync_result = TxSyncResult {
-        response,
+        response: response.response,
         events: Vec::new(),
         status: TxStatus::Pending { message_count },
     };
@@ -112,12 +120,14 @@ pub async fn simple_send_tx(
         &config.rpc_client,
         &config.rpc_address,
         &config.rpc_timeout,
+        config.failed_tx_dir.as_deref(),
+        config.correlation_id,
         &mut tx_sync_results,
     )
     .await?;
 
     for result in tx_sync_results.iter() {
-        for event in result.events.iter() {
+        for event in result.events.iter().flat_map(|msg| &msg.events) {
             if let IbcEvent::ChainError(e) = event {
                 return Err(Error::generic(eyre!("send_tx result in error: {}", e)));
             }
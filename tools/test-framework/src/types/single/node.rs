@@ -121,10 +121,12 @@ impl FullNode {
     pub fn generate_chain_config(&self) -> Result<config::ChainConfig, Error> {
         Ok(config::ChainConfig {
             id: self.chain_driver.chain_id.clone(),
+            nickname: None,
             r#type: ChainType::CosmosSdk,
             rpc_addr: Url::from_str(&self.chain_driver.rpc_address())?,
             websocket_addr: Url::from_str(&self.chain_driver.websocket_address())?,
             grpc_addr: Url::from_str(&self.chain_driver.grpc_address())?,
+            rest_addr: None,
             rpc_timeout: Duration::from_secs(10),
             account_prefix: self.chain_driver.account_prefix.clone(),
             key_name: self.wallets.relayer.id.0.clone(),
@@ -133,6 +135,7 @@ impl FullNode {
             // ~/.hermes/keys. See
             // https://github.com/informalsystems/ibc-rs/issues/1541
             key_store_type: Store::Memory,
+            key_store_folder: None,
 
             store_prefix: "ibc".to_string(),
             default_gas: None,
@@ -149,8 +152,19 @@ impl FullNode {
             gas_price: config::GasPrice::new(0.001, "stake".to_string()),
             packet_filter: Default::default(),
             address_type: Default::default(),
+            channel_versions: Default::default(),
+            tx_errors: Default::default(),
+            tls: Default::default(),
             memo_prefix: Default::default(),
             proof_specs: Default::default(),
+            strict_version_check: false,
+            verify_proofs_locally: false,
+            halt_detection_window: None,
+            tx_extension: None,
+            max_priority_price: 0,
+            failed_tx_dir: None,
+            max_transfer_memo_len: config::default::max_transfer_memo_len(),
+            resolve_denoms: config::default::resolve_denoms(),
         })
     }
 